@@ -0,0 +1,116 @@
+#!/usr/bin/env python3
+"""
+Rust vs C++ Results Badge
+The latest comparison as a few lines of JSON (the geometric-mean speedup, each category's
+winner, the machine and compilers) and an SVG badge, so a README can show numbers that
+came from build.py rather than ones edited in by hand
+"""
+
+import json
+import sys
+from pathlib import Path
+from typing import Dict
+from xml.sax.saxutils import escape
+
+from summary import RESULTS_FILE, describe, summarize, winner
+
+BADGE_JSON = "badge.json"
+BADGE_SVG = "badge.svg"
+# GitHub's language colours, and grey for a suite that comes out even
+COLORS = {"Rust": "#dea584", "C++": "#f34b7d", "Tie": "#9f9f9f"}
+# Verdana at 11px averages about 7px a character; textLength squeezes the text to fit whatever it really is
+CHAR_WIDTH = 7
+PADDING = 6
+
+
+def headline(data: Dict, summary: Dict) -> Dict:
+    """The few numbers worth putting on the front page, and where they came from"""
+    speedup = summary["geomean_speedup"]
+    result = {
+        "benchmarks": summary["benchmarks"],
+        "geomean_speedup": speedup,
+        "winner": winner(speedup),
+        "headline": describe(speedup),
+        "rust_wins": summary["rust_wins"],
+        "cpp_wins": summary["cpp_wins"],
+        "ties": summary["ties"],
+        "categories": {category: {"winner": stats["winner"], "geomean_speedup": stats["geomean_speedup"]}
+                       for category, stats in summary["categories"].items()},
+        # Absent from results files written before build.py recorded them
+        "machine": data.get("machine"),
+        "target": data.get("target", {}).get("triple"),
+        "toolchain": data.get("toolchain"),
+    }
+    if data.get("measure", {}).get("name", "wall") != "wall":
+        result["measure"] = data["measure"]["name"]
+    # A run of one category, or one cut short, is not the whole suite
+    if "category" in data or "interrupted" in data:
+        result["partial"] = True
+    return result
+
+
+def message(entry: Dict) -> str:
+    """The badge's right half: "1.08x faster in Rust", with what was compared when it was not time"""
+    notes = [entry["measure"]] if "measure" in entry else []
+    if entry.get("partial"):
+        notes.append("partial")
+    return f"{entry['headline']} ({', '.join(notes)})" if notes else entry["headline"]
+
+
+def badge_svg(label: str, value: str, color: str) -> str:
+    """A flat two-part badge in the shields.io style"""
+    label_width = len(label) * CHAR_WIDTH + 2 * PADDING
+    value_width = len(value) * CHAR_WIDTH + 2 * PADDING
+    width = label_width + value_width
+    label, value = escape(label), escape(value)
+    return f"""<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {value}">
+  <title>{label}: {value}</title>
+  <linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+  <clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="#555"/>
+    <rect x="{label_width}" width="{value_width}" height="20" fill="{color}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+    <text x="{label_width / 2}" y="14" textLength="{label_width - 2 * PADDING}">{label}</text>
+    <text x="{label_width + value_width / 2}" y="14" textLength="{value_width - 2 * PADDING}">{value}</text>
+  </g>
+</svg>
+"""
+
+
+def write_badge(results_file: str = RESULTS_FILE) -> bool:
+    """Distill a results file into badge.json and badge.svg next to it"""
+    if not Path(results_file).exists():
+        print(f"❌ {results_file} not found; run build.py first")
+        return False
+
+    with open(results_file, "r") as f:
+        data = json.load(f)
+    summary = summarize(data)
+    if not summary:
+        print(f"❌ No benchmark in {results_file} has times for both Rust and C++")
+        return False
+
+    entry = headline(data, summary)
+    json_file = Path(results_file).with_name(BADGE_JSON)
+    with open(json_file, "w") as f:
+        json.dump(entry, f, indent=2)
+    svg_file = Path(results_file).with_name(BADGE_SVG)
+    svg_file.write_text(badge_svg(f"Rust vs C++, {entry['benchmarks']} benchmarks", message(entry),
+                                  COLORS[entry["winner"]]))
+
+    print(f"✅ Badge: {message(entry)}, saved to {svg_file} and {json_file}")
+    return True
+
+
+def main():
+    """Write the badge for the results file given on the command line, or build.py's"""
+    results_file = sys.argv[1] if len(sys.argv) > 1 else RESULTS_FILE
+    if not write_badge(results_file):
+        sys.exit(1)
+
+
+if __name__ == "__main__":
+    main()
@@ -4,59 +4,334 @@ Rust vs C++ Benchmark Runner
 Builds, runs, and plots performance comparison graphs
 """
 
+import argparse
 import subprocess
 import json
+import math
 import time
 import os
+import platform
+import random
+import re
+import shutil
+import signal
 import sys
+import tempfile
+import xml.etree.ElementTree as ET
 from pathlib import Path
-from typing import Dict, List, Tuple
+from typing import Dict, List, Optional, Tuple
 
 # Configuration
 NUM_RUNS = 5
+BOOTSTRAP_RESAMPLES = 10000
+# Above this run-to-run coefficient of variation (std / mean) a benchmark's numbers are reported as unreliable
+CV_THRESHOLD = 0.05
+CONFIDENCE = 0.95
+# What the benchmarks' number is (BENCH_MEASURE, see rust/bench_measure), and its unit
+MEASURES = {"wall": "s", "cpu": "s", "instructions": "instructions", "gpu": "s"}
+# How each benchmark's checksum is verified, between its runs and between the languages: exact text, or
+# every number in it within an absolute, relative or ULP distance (plus what printing rounded off). The
+# default is exact, so an integer checksum that is off by one is a failure, never within tolerance
+DEFAULT_CHECKSUM_POLICY = {"kind": "exact", "tolerance": 0}
+CHECKSUM_POLICIES = {
+    # f64 sums over an image or spectrum, accumulated in a different order by each language's kernels
+    "ray_tracer": {"kind": "rel", "tolerance": 1e-9},
+    "fft": {"kind": "rel", "tolerance": 1e-9},
+    "sdf_raymarch": {"kind": "rel", "tolerance": 1e-9},
+    "barnes_hut": {"kind": "rel", "tolerance": 1e-9},
+    # f32 arithmetic, where the languages differ in the seventh or eighth digit
+    "noise": {"kind": "rel", "tolerance": 1e-6},
+    "mlp_inference": {"kind": "rel", "tolerance": 1e-6},
+    "gpu_matmul": {"kind": "rel", "tolerance": 1e-6},
+    # f32 sums of a trig kernel, whose sin and cos are each GPU's own approximations, accumulated in f32
+    "gpu_compute": {"kind": "rel", "tolerance": 1e-4},
+    "metal_compute": {"kind": "rel", "tolerance": 1e-4},
+    # f32 sums that cancel to far less than their terms, so the error scales with the terms, not the total
+    "conv2d": {"kind": "abs", "tolerance": 1e-3},
+    "attention": {"kind": "abs", "tolerance": 1e-5},
+    # The same explicit FMAs in the same order in both languages: only libm may differ
+    "heat_stencil": {"kind": "ulp", "tolerance": 4},
+    "lattice_boltzmann": {"kind": "ulp", "tolerance": 4},
+}
+# Benchmarks whose checksum only rounds at some precisions, with a policy for each value of their
+# "Precision:" line (its first word); a run that printed none takes the benchmark's default precision
+PRECISION_POLICIES = {
+    # Small-integer inputs, so every product and partial sum is exact with f64 accumulation (f64 and
+    # mixed); f32 accumulation rounds, in each kernel's own order
+    "matrix_multiply": {
+        "f64": DEFAULT_CHECKSUM_POLICY,
+        "mixed": DEFAULT_CHECKSUM_POLICY,
+        "f32": {"kind": "rel", "tolerance": 1e-4},
+    },
+}
+CHECKSUM_NUMBER = re.compile(r"[-+]?(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?")
+# --target: the architecture of each cross-compilation target and the CPU it is built for unless
+# --target-cpu says otherwise, as target-cpu=native only describes the host. Rust and clang share
+# these CPU names; x86-64-v2 also runs under Rosetta on every macOS
+TARGETS = {
+    "aarch64-apple-darwin": {"arch": "aarch64", "cpu": "apple-m1"},
+    "x86_64-apple-darwin": {"arch": "x86_64", "cpu": "x86-64-v2"},
+    "aarch64-unknown-linux-gnu": {"arch": "aarch64", "cpu": "generic"},
+    "x86_64-unknown-linux-gnu": {"arch": "x86_64", "cpu": "x86-64-v2"},
+}
+# Set from --target and --target-cpu by configure_target; None builds for this machine with
+# target-cpu=native into rust/target/release and cpp/build
+TARGET: Optional[str] = None
+TARGET_CPU: Optional[str] = None
 BENCHMARKS = [
     "matrix_multiply",
     "parallel_quicksort", 
     "thread_pool",
+    "ring_buffer",
+    "lock_contention",
+    "thread_overhead",
+    "histogram",
+    "prefix_sum",
     "ray_tracer",
     "mandelbrot",
     "metal_compute",
+    "metal_scan",
+    "gpu_compute",
+    "gpu_matmul",
+    "gpu_mandelbrot",
+    "gpu_transfer",
+    "gpu_dispatch",
     "prime_sieve",
     "fft",
     "sha256",
-    "json_parse"
+    "json_parse",
+    "mlp_inference",
+    "conv2d",
+    "half_precision",
+    "attention",
+    "astar",
+    "levenshtein",
+    "smith_waterman",
+    "trie",
+    "bloom_filter",
+    "hash_join",
+    "word_count",
+    "recursion",
+    "dyn_dispatch",
+    "error_handling",
+    "string_format",
+    "rng",
+    "noise",
+    "sdf_raymarch",
+    "image_resize",
+    "convex_hull",
+    "delaunay",
+    "priority_queue",
+    "barnes_hut",
+    "heat_stencil",
+    "lattice_boltzmann",
+    "linear_solve",
+    "proof_of_work",
+    "password_hashing",
+    "ed25519",
+    "ffi_overhead",
+    "ffi_kernels",
+    "deflate",
+    "png_codec",
+    "qoi_codec",
+    "sqlite",
+    "kv_store",
+    "ordered_map",
+    "jsonl_stream",
+    "number_conversion",
+    "datetime",
+    "unicode_text",
+    "lexer",
+    "bytecode_vm",
+    "refcount_graph",
+    "arena_alloc",
+    "object_pool",
+    "small_buffers",
+    "small_strings",
+    "iter_pipeline",
+    "transpose",
+    "cache_sweep",
+    "mem_copy",
+    "branch_prediction",
+    "bit_ops",
+    "coremark",
+    "syscall_overhead",
+    "process_spawn",
+    "ray_triangle",
+    "bvh_build",
+    "rasterizer",
+    "json_to_parquet",
+    "arrow_compute",
+    "point_in_polygon",
+    "symbolic_diff",
+    "log_parse",
+    "startup_time",
+    "plugin_call",
+    "ipc",
+    "actors",
+    "fork_join",
+    "pipeline"
 ]
+# Benchmarks built with a Cargo profile other than release (rust/Cargo.toml), into rust/target/<profile>:
+# error_handling catches panics, so it needs panic = "unwind" where every other benchmark aborts
+RUST_PROFILES = {"error_handling": "release-unwind"}
 
 CATEGORIES = {
-    "Parallelization": ["matrix_multiply", "parallel_quicksort", "thread_pool"],
-    "Graphics": ["ray_tracer", "mandelbrot", "metal_compute"],
-    "Heavy Compute": ["prime_sieve", "fft", "sha256"],
-    "Other": ["json_parse"]
+    "Numerics": ["matrix_multiply", "prime_sieve", "fft", "rng", "mlp_inference", "conv2d", "half_precision", "attention", "astar", "levenshtein", "smith_waterman", "convex_hull", "delaunay", "point_in_polygon", "arrow_compute", "recursion", "dyn_dispatch", "error_handling", "iter_pipeline", "barnes_hut", "heat_stencil", "lattice_boltzmann", "linear_solve", "bytecode_vm", "branch_prediction", "bit_ops", "coremark"],
+    "Memory": ["trie", "bloom_filter", "priority_queue", "hash_join", "kv_store", "ordered_map", "refcount_graph", "arena_alloc", "object_pool", "small_buffers", "small_strings", "symbolic_diff", "transpose", "cache_sweep", "mem_copy"],
+    "Concurrency": ["parallel_quicksort", "thread_pool", "ring_buffer", "lock_contention", "thread_overhead", "histogram", "prefix_sum", "actors", "fork_join", "pipeline"],
+    "Serialization": ["json_parse", "deflate", "png_codec", "qoi_codec", "word_count", "jsonl_stream", "number_conversion", "datetime", "unicode_text", "json_to_parquet", "string_format", "lexer", "log_parse"],
+    "Crypto": ["sha256", "proof_of_work", "password_hashing", "ed25519"],
+    "Graphics": ["ray_tracer", "mandelbrot", "noise", "sdf_raymarch", "image_resize", "ray_triangle", "bvh_build", "rasterizer"],
+    "GPU": ["metal_compute", "metal_scan", "gpu_compute", "gpu_matmul", "gpu_transfer", "gpu_dispatch", "gpu_mandelbrot"],
+    "I/O": ["sqlite", "ffi_overhead", "ffi_kernels", "plugin_call", "syscall_overhead", "process_spawn", "startup_time", "ipc"]
 }
 
-def run_command(cmd: List[str], cwd: str = None) -> Tuple[bool, str, str]:
-    """Run a command and return success status, stdout, stderr"""
+def category_slug(category: str) -> str:
+    """A category's name for the command line: "Concurrency" -> concurrency, "I/O" -> io"""
+    return re.sub(r"[^a-z0-9]+", "", category.lower())
+
+def category_of(benchmark: str) -> Optional[str]:
+    return next((category for category, benches in CATEGORIES.items() if benchmark in benches), None)
+
+class Interrupted(Exception):
+    """SIGINT or SIGTERM, raised from the signal handler wherever the runner happens to be"""
+
+    def __init__(self, signum: int):
+        super().__init__(signal.Signals(signum).name)
+        self.signum = signum
+        self.benchmark: Optional[str] = None
+
+class Unmeasured(Exception):
+    """A benchmark that cannot take the requested measure, because it still times itself or has no GPU pass"""
+
+def raise_interrupted(signum, frame):
+    # A second signal takes the default action, in case cleanup itself hangs
+    signal.signal(signal.SIGINT, signal.SIG_DFL)
+    signal.signal(signal.SIGTERM, signal.SIG_DFL)
+    raise Interrupted(signum)
+
+def stop_process_group(process: subprocess.Popen):
+    """SIGTERM a command's process group, then SIGKILL whatever is left after a grace period"""
     try:
-        result = subprocess.run(
+        os.killpg(process.pid, signal.SIGTERM)
+        process.wait(timeout=5)
+    except (ProcessLookupError, subprocess.TimeoutExpired):
+        pass
+    try:
+        os.killpg(process.pid, signal.SIGKILL)
+    except ProcessLookupError:
+        pass
+    process.wait()
+
+def run_command(cmd: List[str], cwd: str = None, env: Dict[str, str] = None,
+                timeout: Optional[float] = 300) -> Tuple[bool, str, str]:
+    """Run a command and return success status, stdout, stderr; builds pass timeout=None"""
+    try:
+        # A session of its own, so the command and anything it spawns can be stopped as a group
+        process = subprocess.Popen(
             cmd,
             cwd=cwd,
-            capture_output=True,
+            env=env,
+            stdout=subprocess.PIPE,
+            stderr=subprocess.PIPE,
             text=True,
-            timeout=300
+            start_new_session=True
         )
-        return result.returncode == 0, result.stdout, result.stderr
-    except subprocess.TimeoutExpired:
-        return False, "", "Timeout"
     except Exception as e:
         return False, "", str(e)
+    try:
+        stdout, stderr = process.communicate(timeout=timeout)
+        return process.returncode == 0, stdout, stderr
+    except subprocess.TimeoutExpired:
+        stop_process_group(process)
+        return False, "", "Timeout"
+    except BaseException:
+        stop_process_group(process)
+        raise
+
+def hardware_arch() -> str:
+    """The CPU's architecture, also from a Python that Rosetta translates, which reports x86_64"""
+    if sys.platform == "darwin":
+        success, stdout, _ = run_command(["sysctl", "-n", "hw.optional.arm64"])
+        if success and stdout.strip() == "1":
+            return "aarch64"
+    # arm64 on macOS, aarch64 on Linux
+    return "aarch64" if platform.machine() == "arm64" else platform.machine()
+
+def host_triple() -> str:
+    success, stdout, _ = run_command(["rustc", "-vV"])
+    host = re.search(r"^host: (\S+)", stdout, re.M)
+    return host.group(1) if success and host else "unknown"
+
+def configure_target(target: Optional[str], cpu: Optional[str]):
+    """Build for target, or for this machine's own triple when only the CPU differs from native"""
+    global TARGET, TARGET_CPU
+    if cpu and not target:
+        target = host_triple()
+    if target and target not in TARGETS:
+        print(f"❌ No build settings for {target}; add it to TARGETS in build.py")
+        sys.exit(1)
+    if target and target.endswith("apple-darwin") != (sys.platform == "darwin"):
+        print(f"❌ {target} binaries are built on {'macOS' if target.endswith('apple-darwin') else 'Linux'}")
+        sys.exit(1)
+    TARGET, TARGET_CPU = target, cpu
+
+def target_cpu() -> str:
+    return TARGET_CPU or (TARGETS[TARGET]["cpu"] if TARGET else "native")
+
+def cpu_flag() -> str:
+    """The C++ compilers' spelling of target_cpu(): GCC has no -march for Arm CPU names, nor -mcpu on x86"""
+    if not TARGET:
+        return "-march=native"
+    return f"{'-mcpu' if TARGETS[TARGET]['arch'] == 'aarch64' else '-march'}={target_cpu()}"
+
+def rust_profile(benchmark: str) -> str:
+    return RUST_PROFILES.get(benchmark, "release")
+
+def cargo_build(*args: str, profile: str = "release") -> Tuple[bool, str, str]:
+    """cargo build with a release profile for TARGET, with its CPU and, from another Linux architecture, its cross linker"""
+    cmd = ["cargo", "build", "--profile", profile, *args]
+    # docker.py's image builds from the committed Cargo.lock and fails rather than resolve newer crates
+    if os.environ.get("BENCH_LOCKED"):
+        cmd.append("--locked")
+    # build.rs compiles the FFI benchmarks' C++ kernels with the same CPU flag as CMake
+    env = {**os.environ, "TARGET_CPU_FLAGS": cpu_flag()}
+    if TARGET:
+        cmd += ["--target", TARGET]
+        # Replaces .cargo/config.toml's target-cpu=native
+        env["RUSTFLAGS"] = f"-C target-cpu={target_cpu()}"
+        arch = TARGETS[TARGET]["arch"]
+        if TARGET.endswith("linux-gnu") and arch != hardware_arch():
+            env[f"CARGO_TARGET_{TARGET.upper().replace('-', '_')}_LINKER"] = f"{arch}-linux-gnu-gcc"
+    # A first build for a new target compiles every dependency, which can take longer than any run
+    return run_command(cmd, cwd="rust", env=env, timeout=None)
+
+def cpp_build_dir() -> Path:
+    return Path(f"cpp/build-{TARGET}") if TARGET else Path("cpp/build")
+
+def cmake_target_args() -> List[str]:
+    """CMake settings for TARGET: its CPU, and its architecture on macOS or cross compilers on Linux"""
+    args = [f"-DTARGET_CPU_FLAGS={cpu_flag()}"]
+    if not TARGET:
+        return args
+    arch = TARGETS[TARGET]["arch"]
+    if TARGET.endswith("apple-darwin"):
+        args.append(f"-DCMAKE_OSX_ARCHITECTURES={'arm64' if arch == 'aarch64' else 'x86_64'}")
+    elif arch != hardware_arch():
+        # Cross compilers from the distribution (apt install g++-aarch64-linux-gnu); the libraries
+        # CMake looks for must be installed for the target architecture too
+        args += ["-DCMAKE_SYSTEM_NAME=Linux", f"-DCMAKE_SYSTEM_PROCESSOR={arch}",
+                 f"-DCMAKE_C_COMPILER={arch}-linux-gnu-gcc", f"-DCMAKE_CXX_COMPILER={arch}-linux-gnu-g++"]
+    return args
 
 def build_rust() -> bool:
     """Build all Rust benchmarks"""
-    print("Building Rust benchmarks...")
-    success, stdout, stderr = run_command(
-        ["cargo", "build", "--release"],
-        cwd="rust"
-    )
+    print(f"Building Rust benchmarks{f' for {TARGET}' if TARGET else ''}...")
+    success, stdout, stderr = cargo_build()
+    for benchmark, profile in RUST_PROFILES.items():
+        if success:
+            success, stdout, stderr = cargo_build("--bin", benchmark, profile=profile)
     
     if not success:
         print(f"❌ Rust build failed:")
@@ -68,15 +343,15 @@ def build_rust() -> bool:
 
 def build_cpp() -> bool:
     """Build all C++ benchmarks"""
-    print("Building C++ benchmarks...")
+    print(f"Building C++ benchmarks{f' for {TARGET}' if TARGET else ''}...")
     
     # Create build directory
-    build_dir = Path("cpp/build")
+    build_dir = cpp_build_dir()
     build_dir.mkdir(exist_ok=True)
     
     # Run CMake
     success, stdout, stderr = run_command(
-        ["cmake", "..", "-DCMAKE_BUILD_TYPE=Release"],
+        ["cmake", "..", "-DCMAKE_BUILD_TYPE=Release", *cmake_target_args()],
         cwd=str(build_dir)
     )
     
@@ -88,7 +363,8 @@ def build_cpp() -> bool:
     # Build
     success, stdout, stderr = run_command(
         ["cmake", "--build", ".", "--config", "Release"],
-        cwd=str(build_dir)
+        cwd=str(build_dir),
+        timeout=None
     )
     
     if not success:
@@ -99,97 +375,681 @@ def build_cpp() -> bool:
     print("✅ C++ build successful")
     return True
 
-def run_benchmark(lang: str, benchmark: str) -> float:
-    """Run a single benchmark and return execution time"""
+def parse_dispatch(stderr: str) -> Optional[Dict]:
+    """The CPU features and code paths a benchmark reported, from its "CPU features:" and "Code path:" lines"""
+    dispatch = {}
+    for line in stderr.splitlines():
+        # "CPU features: avx2 fma bmi2 (x86_64; not available: avx512f sha)"
+        match = re.fullmatch(r"CPU features: (.*) \((\w+)(?:; not available: (.*))?\)", line)
+        if match:
+            available = [] if match.group(1) == "none" else match.group(1).split()
+            missing = match.group(3).split() if match.group(3) else []
+            dispatch["cpu"] = {"arch": match.group(2), "available": available, "not_available": missing}
+        # "Code path: compress: hw (SHA-NI)"
+        match = re.fullmatch(r"Code path: (.+?): (scalar|simd|hw) \((.*)\)", line)
+        if match:
+            kernel, path, detail = match.groups()
+            dispatch.setdefault("paths", []).append({"kernel": kernel, "path": path, "detail": detail})
+    return dispatch or None
+
+def parse_measure(stderr: str) -> Optional[str]:
+    """The measure a benchmark says its number is, from its "Measure: cpu (s)" line"""
+    match = re.search(r"^Measure: (\w+)", stderr, re.MULTILINE)
+    return match.group(1) if match else None
+
+def parse_checksum(stderr: str) -> Optional[str]:
+    """The value on a benchmark's last "Checksum:" line, without the bracketed extras ("(all variants
+    match: true)"), which differ between the languages"""
+    lines = re.findall(r"^Checksum: (.*)$", stderr, re.MULTILINE)
+    return lines[-1].split(" (")[0].strip() if lines else None
+
+def parse_precision(stderr: str) -> Optional[str]:
+    """The precision a benchmark says it ran at, from its "Precision: f32" line"""
+    match = re.search(r"^Precision: (\S+)", stderr, re.MULTILINE)
+    return match.group(1) if match else None
+
+def checksum_policy(benchmark: str, precision: Optional[str] = None) -> Dict:
+    """The policy a benchmark's checksums are verified under, at the precision it ran at"""
+    if benchmark in PRECISION_POLICIES:
+        policies = PRECISION_POLICIES[benchmark]
+        return policies.get(precision, next(iter(policies.values())))
+    return CHECKSUM_POLICIES.get(benchmark, DEFAULT_CHECKSUM_POLICY)
+
+def print_slack(number: str) -> float:
+    """Half a unit in the last place a number was printed to: C++ streams print 6 significant digits
+    by default, Rust prints f64 in full"""
+    mantissa, _, exponent = number.lower().partition("e")
+    decimals = len(mantissa.partition(".")[2])
+    return 0.5 * 10.0 ** (int(exponent or 0) - decimals)
+
+def numbers_agree(a: str, b: str, policy: Dict) -> bool:
+    x, y = float(a), float(b)
+    if x == y:
+        return True
+    kind, tolerance = policy["kind"], policy["tolerance"]
+    if kind == "abs":
+        allowed = tolerance
+    elif kind == "rel":
+        allowed = tolerance * max(abs(x), abs(y))
+    else:
+        allowed = tolerance * math.ulp(max(abs(x), abs(y)))
+    return abs(x - y) <= allowed + print_slack(a) + print_slack(b)
+
+def checksums_agree(a: str, b: str, policy: Dict) -> bool:
+    """Whether two checksums match under a benchmark's policy: exact compares the text, the others every
+    number in it, with the text between the numbers still exact"""
+    if policy["kind"] == "exact":
+        return a == b
+    if CHECKSUM_NUMBER.split(a) != CHECKSUM_NUMBER.split(b):
+        return False
+    return all(numbers_agree(x, y, policy) for x, y in zip(CHECKSUM_NUMBER.findall(a), CHECKSUM_NUMBER.findall(b)))
+
+def verify_checksums(results: Dict) -> Dict:
+    """Each benchmark's checksum verdicts: whether every run of a language printed the same checksum as its
+    first (determinism), and whether the languages' first runs agree, both under checksum_policy"""
+    verification = {}
+    for benchmark in BENCHMARKS:
+        checksums = {lang: [c for c in results.get("checksums", {}).get(lang, {}).get(benchmark, []) if c is not None]
+                     for lang in ("rust", "cpp")}
+        if not any(checksums.values()):
+            continue
+        precision = results.get("precision", {}).get("rust", {}).get(benchmark)
+        policy = checksum_policy(benchmark, precision)
+        verdict = {"policy": policy, "deterministic": {}}
+        if precision:
+            verdict["precision"] = precision
+        for lang, values in checksums.items():
+            if values:
+                verdict["deterministic"][lang] = all(checksums_agree(values[0], v, policy) for v in values[1:])
+        if checksums["rust"] and checksums["cpp"]:
+            verdict["languages_agree"] = checksums_agree(checksums["rust"][0], checksums["cpp"][0], policy)
+        verification[benchmark] = verdict
+    return verification
+
+def checksum_failures(results: Dict) -> List[str]:
+    """One line per failed check, with the checksums that failed it, for the summary"""
+    failures = []
+    for benchmark, verdict in verify_checksums(results).items():
+        first = {}
+        for lang, deterministic in verdict["deterministic"].items():
+            values = [c for c in results["checksums"][lang][benchmark] if c is not None]
+            first[lang] = values[0]
+            if not deterministic:
+                failures.append(f"{benchmark}: {'C++' if lang == 'cpp' else 'Rust'} runs disagree "
+                                f"({', '.join(dict.fromkeys(values))})")
+        if verdict.get("languages_agree") is False:
+            failures.append(f"{benchmark}: Rust {first['rust']} vs C++ {first['cpp']}")
+    return failures
+
+def format_amount(value: float, measure: str = "wall") -> str:
+    """A benchmark's number in its measure's unit: seconds, or billions of instructions"""
+    if MEASURES[measure] == "s":
+        return f"{value:.4f}s"
+    return f"{value / 1e9:.3f}G"
+
+def executable_path(lang: str, benchmark: str) -> str:
+    if lang == "rust":
+        profile = rust_profile(benchmark)
+        return f"rust/target/{TARGET}/{profile}/{benchmark}" if TARGET else f"rust/target/{profile}/{benchmark}"
+    return f"{cpp_build_dir()}/{benchmark}"
+
+# CPU type fields of Mach-O headers and machine fields of ELF headers
+MACHO_CPU_TYPES = {0x01000007: "x86_64", 0x0100000C: "aarch64"}
+ELF_MACHINES = {62: "x86_64", 183: "aarch64"}
+
+def binary_archs(path: str) -> List[str]:
+    """The architectures an executable holds, read from its Mach-O or ELF header; a universal binary holds several"""
+    with open(path, "rb") as f:
+        header = f.read(4096)
+    if header[:4] == b"\xcf\xfa\xed\xfe":  # 64-bit Mach-O, little-endian
+        return [MACHO_CPU_TYPES.get(int.from_bytes(header[4:8], "little"), "unknown")]
+    if header[:4] == b"\xca\xfe\xba\xbe":  # universal: a big-endian count, then 20 bytes per slice
+        count = int.from_bytes(header[4:8], "big")
+        return [MACHO_CPU_TYPES.get(int.from_bytes(header[8 + 20 * i:12 + 20 * i], "big"), "unknown")
+                for i in range(count)]
+    if header[:4] == b"\x7fELF":
+        order = "little" if header[5] == 1 else "big"
+        return [ELF_MACHINES.get(int.from_bytes(header[18:20], order), "unknown")]
+    return []
+
+def check_native(benchmarks: List[str], allow_translated: bool = False) -> bool:
+    """Whether every built benchmark is code for this CPU; anything else runs under Rosetta or an emulator
+
+    An x86_64 toolchain or cmake on Apple Silicon builds x86_64 binaries without a word, and their
+    times look like native ones. Unless allow_translated, such binaries stop the run
+    """
+    hardware = hardware_arch()
+    foreign = []
+    for benchmark in benchmarks:
+        for lang in ("rust", "cpp"):
+            executable = executable_path(lang, benchmark)
+            archs = binary_archs(executable) if os.path.exists(executable) else []
+            if archs and hardware not in archs:
+                foreign.append(f"{lang}/{benchmark} ({', '.join(archs)})")
+    if not foreign:
+        return True
+    translator = "Rosetta" if sys.platform == "darwin" else "an emulator"
+    listed = ", ".join(foreign[:4]) + (f" and {len(foreign) - 4} more" if len(foreign) > 4 else "")
+    if not allow_translated:
+        print(f"\n❌ Not {hardware} code, so these would run under {translator}: {listed}")
+        print("   Check the toolchain's host (rustc -vV, file $(which cmake)) or pass --target, or "
+              "--allow-translated to record them as translated")
+        sys.exit(1)
+    print(f"\n⚠️  Running under {translator} and recording the results as translated: {listed}")
+    return False
+
+def toolchain_info() -> Dict:
+    """The compilers that built the binaries, and the container image they ran in under docker.py"""
+    def version(cmd: List[str]) -> Optional[str]:
+        success, stdout, _ = run_command(cmd)
+        return stdout.splitlines()[0] if success and stdout else None
+    toolchain = {"rustc": version(["rustc", "-V"]), "cxx": version([os.environ.get("CXX", "c++"), "--version"])}
+    if os.environ.get("BENCH_CONTAINER"):
+        toolchain["container"] = os.environ["BENCH_CONTAINER"]
+    return toolchain
+
+def target_info(native: bool = True) -> Dict:
+    """What the binaries were built for: the target triple and CPU, and whether they ran translated"""
+    cpu = target_cpu()
+    if cpu == "native":
+        success, stdout, _ = run_command(["rustc", "--print", "target-cpus"])
+        current = re.search(r"currently (\S+?)\)?\.?$", stdout, re.M)
+        cpu = f"native ({current.group(1)})" if success and current else cpu
+    return {"triple": TARGET or host_triple(), "cpu": cpu, "translated": not native}
+
+def source_path(lang: str, benchmark: str) -> Optional[Path]:
+    """The benchmark's main source file: a binary of its own in Rust, its main translation unit in C++"""
     if lang == "rust":
-        executable = f"rust/target/release/{benchmark}"
+        candidates = [Path(f"rust/src/{benchmark}.rs")]
+    else:  # cpp; the Metal benchmarks are Objective-C++
+        candidates = [Path(f"cpp/src/{benchmark}.cpp"), Path(f"cpp/src/{benchmark}.mm")]
+    return next((path for path in candidates if path.exists()), None)
+
+def time_rebuild(lang: str, benchmark: str) -> Optional[float]:
+    """Touch a benchmark's main source and time rebuilding just its binary, or None if the rebuild fails"""
+    source = source_path(lang, benchmark)
+    if source is None:
+        return None
+    source.touch()
+    start = time.perf_counter()
+    if lang == "rust":
+        success, _, _ = cargo_build("--bin", benchmark, profile=rust_profile(benchmark))
     else:  # cpp
-        executable = f"cpp/build/{benchmark}"
+        success, _, _ = run_command(["cmake", "--build", ".", "--config", "Release", "--target", benchmark],
+                                    cwd=str(cpp_build_dir()))
+    return time.perf_counter() - start if success else None
+
+def measure_build_costs(results: Dict, rebuild: bool, benchmarks: List[str] = BENCHMARKS):
+    """Record each binary's size and, with rebuild, how long its own rebuild takes
+
+    Dependencies stay built, so the time is compiling and linking the benchmark itself: for Rust
+    that includes the LTO pass over every crate it uses, for C++ one translation unit and the link.
+    """
+    if rebuild:
+        print(f"\nTiming rebuilds of each benchmark binary ({len(benchmarks) * 2} builds)...")
+    for benchmark in benchmarks:
+        for lang in ["rust", "cpp"]:
+            cost = {}
+            if rebuild:
+                seconds = time_rebuild(lang, benchmark)
+                if seconds is None:
+                    print(f"  ⚠️  {lang}/{benchmark} rebuild failed")
+                else:
+                    cost["build_seconds"] = seconds
+            executable = executable_path(lang, benchmark)
+            if os.path.exists(executable):
+                cost["binary_bytes"] = os.path.getsize(executable)
+            if cost:
+                results["build"][lang][benchmark] = cost
+        if rebuild:
+            rust = results["build"]["rust"].get(benchmark, {})
+            cpp = results["build"]["cpp"].get(benchmark, {})
+            print(f"  {benchmark:<25} Rust {format_build_time(rust)}    C++ {format_build_time(cpp)}")
+
+def format_build_time(cost: Dict) -> str:
+    return f"{cost['build_seconds']:>9.2f}s" if "build_seconds" in cost else f"{'-':>10}"
+
+def format_binary_size(cost: Dict) -> str:
+    return f"{cost['binary_bytes'] / 1e6:>9.2f} MB" if "binary_bytes" in cost else f"{'-':>12}"
+
+def run_benchmark(lang: str, benchmark: str, scratch_dir: str = None, dispatch: Dict = None,
+                  measure: str = "wall", checksums: Dict = None, precision: Dict = None) -> float:
+    """Run a single benchmark and return its number (execution time unless measure says otherwise), recording
+    what it reported about code paths in dispatch, its checksum in checksums and its precision in precision"""
+    executable = executable_path(lang, benchmark)
     
-    success, stdout, stderr = run_command([executable])
+    # Temp files land in the scratch directory, which is removed even if a benchmark is killed
+    env = {**os.environ, "BENCH_MEASURE": measure}
+    if scratch_dir:
+        env["TMPDIR"] = scratch_dir
+    success, stdout, stderr = run_command([executable], env=env)
     
+    # The measurer's own refusal: no GPU pass in this benchmark, or no counters on this machine
+    refused = re.search(rf"BENCH_MEASURE={measure}: (.*)", stderr)
+    if refused:
+        raise Unmeasured(f"{lang}/{benchmark}: {refused.group(1)}")
+    # Benchmarks without a measurer print wall time whatever was asked, so only the stderr line counts
+    if measure != "wall" and success and parse_measure(stderr) != measure:
+        raise Unmeasured(f"{lang}/{benchmark} only reports wall-clock time")
+
     if not success:
         print(f"  ⚠️  {lang}/{benchmark} failed: {stderr}")
         return -1.0
     
+    reported = parse_dispatch(stderr)
+    if reported and dispatch is not None:
+        dispatch[benchmark] = reported
+    
     try:
-        return float(stdout.strip())
+        number = float(stdout.strip())
     except ValueError:
         print(f"  ⚠️  {lang}/{benchmark} returned invalid output: {stdout}")
         return -1.0
+    if checksums is not None:
+        checksums.setdefault(benchmark, []).append(parse_checksum(stderr))
+    if precision is not None and parse_precision(stderr):
+        precision[benchmark] = parse_precision(stderr)
+    return number
 
-def run_all_benchmarks() -> Dict:
-    """Run all benchmarks multiple times and collect results"""
-    results = {
-        "rust": {},
-        "cpp": {}
-    }
-    
-    total_tests = len(BENCHMARKS) * 2 * NUM_RUNS
+def run_all_benchmarks(results: Dict, scratch_dir: str = None, benchmarks: List[str] = BENCHMARKS,
+                       measure: str = "wall") -> Dict:
+    """Run the benchmarks multiple times, collecting into results as each run finishes; a benchmark that
+    cannot take the measure is skipped and listed under results["unmeasured"]"""
+    total_tests = len(benchmarks) * 2 * NUM_RUNS
     current_test = 0
     
-    for benchmark in BENCHMARKS:
-        print(f"\n{'='*60}")
-        print(f"Running: {benchmark}")
-        print(f"{'='*60}")
+    benchmark = None
+    try:
+        for benchmark in benchmarks:
+            print(f"\n{'='*60}")
+            print(f"Running: {benchmark}")
+            print(f"{'='*60}")
         
-        results["rust"][benchmark] = []
-        results["cpp"][benchmark] = []
+            results["rust"][benchmark] = []
+            results["cpp"][benchmark] = []
         
-        for run in range(NUM_RUNS):
-            current_test += 1
-            
-            # Run Rust
-            print(f"[{current_test}/{total_tests}] Rust {benchmark} (run {run+1}/{NUM_RUNS})...", end=" ", flush=True)
-            time_rust = run_benchmark("rust", benchmark)
-            if time_rust >= 0:
-                results["rust"][benchmark].append(time_rust)
-                print(f"✓ {time_rust:.4f}s")
-            else:
-                print("✗ Failed")
-            
-            current_test += 1
-            
-            # Run C++
-            print(f"[{current_test}/{total_tests}] C++  {benchmark} (run {run+1}/{NUM_RUNS})...", end=" ", flush=True)
-            time_cpp = run_benchmark("cpp", benchmark)
-            if time_cpp >= 0:
-                results["cpp"][benchmark].append(time_cpp)
-                print(f"✓ {time_cpp:.4f}s")
-            else:
-                print("✗ Failed")
+            try:
+                for run in range(NUM_RUNS):
+                    current_test += 1
+                
+                    # Run Rust
+                    print(f"[{current_test}/{total_tests}] Rust {benchmark} (run {run+1}/{NUM_RUNS})...", end=" ", flush=True)
+                    time_rust = run_benchmark("rust", benchmark, scratch_dir, results["dispatch"]["rust"], measure,
+                                              results["checksums"]["rust"], results["precision"]["rust"])
+                    if time_rust >= 0:
+                        results["rust"][benchmark].append(time_rust)
+                        print(f"✓ {format_amount(time_rust, measure)}")
+                    else:
+                        print("✗ Failed")
+                
+                    current_test += 1
+                
+                    # Run C++
+                    print(f"[{current_test}/{total_tests}] C++  {benchmark} (run {run+1}/{NUM_RUNS})...", end=" ", flush=True)
+                    time_cpp = run_benchmark("cpp", benchmark, scratch_dir, results["dispatch"]["cpp"], measure,
+                                             results["checksums"]["cpp"], results["precision"]["cpp"])
+                    if time_cpp >= 0:
+                        results["cpp"][benchmark].append(time_cpp)
+                        print(f"✓ {format_amount(time_cpp, measure)}")
+                    else:
+                        print("✗ Failed")
+            except Unmeasured as e:
+                # Comparing one language's instructions with the other's seconds would mean nothing
+                print(f"⏭️  {e}, skipping {benchmark}")
+                current_test = (benchmarks.index(benchmark) + 1) * 2 * NUM_RUNS
+                del results["rust"][benchmark], results["cpp"][benchmark]
+                for recorded in (*results["checksums"].values(), *results["precision"].values()):
+                    recorded.pop(benchmark, None)
+                results.setdefault("unmeasured", []).append(benchmark)
+    except Interrupted as e:
+        e.benchmark = benchmark
+        raise
     
     return results
 
+PROFILE_DIR = Path("results/profiles")
+
+def fold_xctrace(xml: str) -> str:
+    """Fold an exported Time Profiler table into one "outer;...;leaf count" line per distinct stack
+
+    xctrace writes each backtrace and frame in full once, with an id, and as a ref after that
+    """
+    elements = {}
+    def resolve(element):
+        if element.get("id") is not None:
+            elements[element.get("id")] = element
+        return elements.get(element.get("ref"), element)
+
+    stacks = {}
+    for row in ET.fromstring(xml).iter("row"):
+        for child in row:
+            resolve(child)
+        backtrace = row.find("backtrace")
+        if backtrace is None:
+            continue
+        frames = [resolve(frame).get("name") or "?" for frame in resolve(backtrace).iter("frame")]
+        stack = ";".join(reversed(frames))
+        stacks[stack] = stacks.get(stack, 0) + 1
+    return "".join(f"{stack} {count}\n" for stack, count in stacks.items())
+
+def profile_benchmark(benchmark: str, scratch_dir: str) -> bool:
+    """Run a benchmark once per language under the platform's sampling profiler and draw a flamegraph of each
+
+    perf on Linux and xctrace's Time Profiler on macOS sample both languages alike; the raw
+    recording stays next to the SVG for perf report or Instruments
+    """
+    profiler = "xctrace" if sys.platform == "darwin" else "perf"
+    missing = [tool for tool in (profiler, "inferno-flamegraph") if shutil.which(tool) is None]
+    if missing:
+        print(f"❌ {', '.join(missing)} not found (inferno: cargo install inferno)")
+        return False
+
+    PROFILE_DIR.mkdir(parents=True, exist_ok=True)
+    env = {**os.environ, "TMPDIR": scratch_dir}
+    for lang in ("rust", "cpp"):
+        executable = executable_path(lang, benchmark)
+        stem = PROFILE_DIR / f"{benchmark}_{lang}"
+        print(f"Profiling {lang}/{benchmark} with {profiler}...")
+
+        if profiler == "xctrace":
+            recording = stem.with_suffix(".trace")
+            shutil.rmtree(recording, ignore_errors=True)
+            record = ["xctrace", "record", "--template", "Time Profiler", "--output", str(recording),
+                      "--launch", "--", executable]
+            export = ["xctrace", "export", "--input", str(recording),
+                      "--xpath", '/trace-toc/run[@number="1"]/data/table[@schema="time-profile"]']
+        else:
+            recording = stem.with_suffix(".perf.data")
+            # DWARF unwinding, as the Rust release build omits frame pointers
+            record = ["perf", "record", "-F", "999", "--call-graph", "dwarf", "-o", str(recording), "--", executable]
+            export = ["perf", "script", "-i", str(recording)]
+
+        for step in (record, export):
+            success, stdout, stderr = run_command(step, env=env)
+            if not success:
+                print(f"  ⚠️  {' '.join(step[:2])} failed: {stderr}")
+                return False
+
+        folded = stem.with_suffix(".folded")
+        if profiler == "xctrace":
+            folded.write_text(fold_xctrace(stdout))
+        else:
+            script = stem.with_suffix(".perf.txt")
+            script.write_text(stdout)
+            success, stdout, stderr = run_command(["inferno-collapse-perf", str(script)])
+            script.unlink()
+            if not success:
+                print(f"  ⚠️  inferno-collapse-perf failed: {stderr}")
+                return False
+            folded.write_text(stdout)
+
+        success, stdout, stderr = run_command(["inferno-flamegraph", "--title", f"{benchmark} ({lang})", str(folded)])
+        if not success:
+            print(f"  ⚠️  inferno-flamegraph failed: {stderr}")
+            return False
+        stem.with_suffix(".svg").write_text(stdout)
+        print(f"✅ Saved: {stem.with_suffix('.svg')} (recording: {recording})")
+
+    return True
+
+def cpu_temperature() -> Optional[float]:
+    """The hottest thermal zone in °C, where Linux exposes them to unprivileged users"""
+    readings = []
+    for zone in Path("/sys/class/thermal").glob("thermal_zone*/temp"):
+        try:
+            readings.append(int(zone.read_text()) / 1000)
+        except (OSError, ValueError):
+            pass
+    return max(readings, default=None)
+
+def soak_run(lang: str, benchmark: str, env: Dict[str, str]) -> Optional[Dict]:
+    """One run of a benchmark with what it cost the machine: its time, peak RSS and involuntary context switches"""
+    with tempfile.TemporaryFile("w+") as out, tempfile.TemporaryFile("w+") as err:
+        process = subprocess.Popen([executable_path(lang, benchmark)], env=env, stdout=out, stderr=err,
+                                   text=True, start_new_session=True)
+        try:
+            # wait4 rather than wait, for the resource usage of this child alone
+            _, status, usage = os.wait4(process.pid, 0)
+        except BaseException:
+            stop_process_group(process)
+            raise
+        process.returncode = os.waitstatus_to_exitcode(status)
+        out.seek(0)
+        err.seek(0)
+        stdout, stderr = out.read(), err.read()
+
+    if process.returncode != 0:
+        print(f"  ⚠️  {lang}/{benchmark} failed: {stderr}")
+        return None
+    try:
+        seconds = float(stdout.strip())
+    except ValueError:
+        print(f"  ⚠️  {lang}/{benchmark} returned invalid output: {stdout}")
+        return None
+    return {
+        "time": seconds,
+        # ru_maxrss is in bytes on macOS and KiB on Linux
+        "max_rss_bytes": usage.ru_maxrss * (1 if sys.platform == "darwin" else 1024),
+        "involuntary_switches": usage.ru_nivcsw,
+        "temperature_c": cpu_temperature(),
+    }
+
+def soak_drift(samples: List[Dict]) -> Dict:
+    """How a language's runs changed over the soak: the last tenth against the first, and the trend over time"""
+    times = [sample["time"] for sample in samples]
+    tenth = max(1, len(samples) // 10)
+    first, last = mean(times[:tenth]), mean(times[-tenth:])
+    elapsed = [sample["elapsed"] / 60 for sample in samples]
+    # Least-squares slope of time against minutes elapsed
+    spread = sum((e - mean(elapsed)) ** 2 for e in elapsed)
+    slope = sum((e - mean(elapsed)) * (t - mean(times)) for e, t in zip(elapsed, times)) / spread if spread else 0
+    temperatures = [sample["temperature_c"] for sample in samples if sample["temperature_c"] is not None]
+    return {
+        "runs": len(samples),
+        "stats": calculate_statistics(times),
+        "first_tenth_mean": first,
+        "last_tenth_mean": last,
+        "drift_percent": 100 * (last - first) / first,
+        "trend_percent_per_10_min": 100 * slope * 10 / mean(times),
+        "first_max_rss_bytes": samples[0]["max_rss_bytes"],
+        "last_max_rss_bytes": samples[-1]["max_rss_bytes"],
+        "mean_involuntary_switches": mean([sample["involuntary_switches"] for sample in samples]),
+        "temperature_range_c": [min(temperatures), max(temperatures)] if temperatures else None,
+    }
+
+def soak(benchmark: str, minutes: float, scratch_dir: str):
+    """Build, then alternate Rust and C++ runs of one benchmark until minutes have passed, saving every run
+
+    Alternating puts both languages through the same thermal and background conditions. A
+    signal stops the soak early and keeps the runs so far, as it does for the suite
+    """
+    if not build_rust() or not build_cpp():
+        print("\n❌ Failed to build benchmarks")
+        sys.exit(1)
+    check_native([benchmark])
+
+    env = {**os.environ, "TMPDIR": scratch_dir}
+    samples = {"rust": [], "cpp": []}
+    start = time.monotonic()
+    print(f"\nSoaking {benchmark} for {minutes:g} minutes...")
+    interrupted = None
+    try:
+        while time.monotonic() - start < minutes * 60:
+            for lang in ("rust", "cpp"):
+                sample = soak_run(lang, benchmark, env)
+                if sample is None:
+                    continue
+                sample["elapsed"] = time.monotonic() - start
+                samples[lang].append(sample)
+                temperature = f", {sample['temperature_c']:.0f}°C" if sample["temperature_c"] is not None else ""
+                print(f"[{sample['elapsed'] / 60:6.1f} min] {lang:<4} {sample['time']:.4f}s, "
+                      f"{sample['max_rss_bytes'] / 1e6:.1f} MB RSS, {sample['involuntary_switches']} preempted{temperature}")
+    except Interrupted as e:
+        print(f"\n\n⚠️  {e} received, saving the runs finished so far")
+        interrupted = e
+
+    result = {"benchmark": benchmark, "minutes": minutes, "target": target_info(), "samples": samples,
+              "drift": {lang: soak_drift(runs) for lang, runs in samples.items() if runs}}
+    if interrupted:
+        result["interrupted"] = {"signal": str(interrupted), "after_seconds": time.monotonic() - start}
+    Path("results").mkdir(exist_ok=True)
+    filename = f"results/soak_{benchmark}.json"
+    with open(filename, "w") as f:
+        json.dump(result, f, indent=2)
+
+    print("\n" + "="*80)
+    print(f"SOAK: {benchmark}")
+    print("="*80)
+    print(f"{'':<32} {'Rust':>14} {'C++':>14}")
+    rows = [
+        ("Runs", lambda d: f"{d['runs']}"),
+        ("Mean time", lambda d: f"{d['stats']['mean']:.4f}s"),
+        ("First tenth -> last tenth", lambda d: f"{d['drift_percent']:+.1f}%"),
+        ("Trend per 10 minutes", lambda d: f"{d['trend_percent_per_10_min']:+.1f}%"),
+        ("Peak RSS, first -> last run", lambda d: f"{d['first_max_rss_bytes'] / 1e6:.0f}->{d['last_max_rss_bytes'] / 1e6:.0f} MB"),
+        ("Involuntary switches per run", lambda d: f"{d['mean_involuntary_switches']:.0f}"),
+        ("Temperature", lambda d: "{:.0f}-{:.0f}°C".format(*d["temperature_range_c"]) if d["temperature_range_c"] else "-"),
+    ]
+    for label, cell in rows:
+        cells = [cell(result["drift"][lang]) if lang in result["drift"] else "-" for lang in ("rust", "cpp")]
+        print(f"{label:<32} {cells[0]:>14} {cells[1]:>14}")
+    print("="*80)
+    print(f"\n✅ Time series saved to {filename}")
+    if interrupted:
+        sys.exit(128 + interrupted.signum)
+
 def calculate_statistics(times: List[float]) -> Dict:
     """Calculate mean, min, max, std from list of times"""
     if not times:
         return {"mean": 0, "min": 0, "max": 0, "std": 0}
     
     import statistics
-    return {
+    stats = {
         "mean": statistics.mean(times),
         "min": min(times),
         "max": max(times),
         "std": statistics.stdev(times) if len(times) > 1 else 0
     }
+    if len(times) > 1:
+        stats["mean_ci"] = list(bootstrap_interval([times], mean))
+        stats["cv"] = stats["std"] / stats["mean"] if stats["mean"] else 0
+        stats["unreliable"] = stats["cv"] > CV_THRESHOLD
+    return stats
+
+UNRELIABLE_ADVICE = ("More runs (NUM_RUNS in build.py), closing other programs, or pinning to idle cores "
+                     "(taskset -c on Linux) usually steady it")
+
+def mean(times: List[float]) -> float:
+    return sum(times) / len(times)
+
+def mean_ratio(rust_times: List[float], cpp_times: List[float]) -> float:
+    """C++ mean / Rust mean, >1 = Rust faster"""
+    return mean(cpp_times) / mean(rust_times)
+
+def bootstrap_interval(samples: List[List[float]], statistic) -> Tuple[float, float]:
+    """Percentile bootstrap CONFIDENCE interval of statistic(*samples), each sample resampled on its own
+
+    Seeded, so the same times always give the same interval. With a handful of runs the interval
+    is on the narrow side, so an interval that only just excludes a tie is still a weak result
+    """
+    rng = random.Random(0)
+    estimates = sorted(statistic(*[rng.choices(sample, k=len(sample)) for sample in samples])
+                       for _ in range(BOOTSTRAP_RESAMPLES))
+    tail = int(BOOTSTRAP_RESAMPLES * (1 - CONFIDENCE) / 2)
+    return estimates[tail], estimates[-tail - 1]
+
+def speedup_interval(rust_times: List[float], cpp_times: List[float]) -> Optional[Tuple[float, float]]:
+    """Bootstrap interval of C++ mean / Rust mean, or None with too few runs to resample"""
+    if len(rust_times) < 2 or len(cpp_times) < 2:
+        return None
+    return bootstrap_interval([rust_times, cpp_times], mean_ratio)
+
+def numa_topology() -> List[Dict]:
+    """NUMA nodes from sysfs with their CPU lists and memory, empty where the OS has none"""
+    nodes = []
+    for node in sorted(Path("/sys/devices/system/node").glob("node[0-9]*"), key=lambda p: int(p.name[4:])):
+        try:
+            cpus = (node / "cpulist").read_text().strip()
+            # "Node 0 MemTotal:       65831300 kB"
+            meminfo = (node / "meminfo").read_text()
+        except OSError:
+            continue
+        kilobytes = next((int(line.split()[-2]) for line in meminfo.splitlines() if "MemTotal:" in line), 0)
+        nodes.append({"node": int(node.name[4:]), "cpus": cpus, "memory_bytes": kilobytes * 1024})
+    return nodes
+
+def machine_info() -> Dict:
+    """Which machine a results file came from: its hostname as the id, OS, architecture, CPU model and core count"""
+    cpu = platform.processor()
+    if sys.platform == "darwin":
+        success, stdout, stderr = run_command(["sysctl", "-n", "machdep.cpu.brand_string"])
+        cpu = stdout.strip() if success else cpu
+    else:
+        try:
+            cpuinfo = Path("/proc/cpuinfo").read_text()
+            model = re.search(r"^(?:model name|Model)\s*:\s*(.+)$", cpuinfo, re.M)
+            cpu = model.group(1) if model else cpu
+        except OSError:
+            pass
+    return {
+        "id": platform.node(),
+        "system": platform.system(),
+        "arch": hardware_arch(),
+        "cpu": cpu,
+        "cores": os.cpu_count(),
+    }
 
-def save_results(results: Dict, filename: str = "results/benchmark_results.json"):
+def save_results(results: Dict, filename: str = "results/benchmark_results.json", interrupted: Interrupted = None):
     """Save results to JSON file"""
     Path("results").mkdir(exist_ok=True)
     
     # Calculate statistics
     processed = {
         "rust": {},
-        "cpp": {}
+        "cpp": {},
+        # Page placement dominates the memory-bound results on multi-socket machines
+        "numa": numa_topology(),
+        # So results from several machines can be merged (merge.py)
+        "machine": machine_info(),
+        # What the binaries were built for, and whether they ran translated
+        "target": results.get("target") or target_info(),
+        # So a run can be reproduced down to the compiler versions (docker.py)
+        "toolchain": results.get("toolchain") or toolchain_info(),
     }
+    if interrupted:
+        # Marks a partial run: benchmarks after this one never ran, and this one may be short of runs
+        processed["interrupted"] = {"signal": str(interrupted), "during": interrupted.benchmark}
+    if results.get("category"):
+        # A run of one category, so the benchmarks missing from it were skipped, not failed
+        processed["category"] = results["category"]
+    # Times, unless the run measured CPU time, instructions or GPU time instead
+    measure = results.get("measure", "wall")
+    processed["measure"] = {"name": measure, "unit": MEASURES[measure]}
+    if results.get("unmeasured"):
+        processed["unmeasured"] = results["unmeasured"]
     
     for lang in ["rust", "cpp"]:
         for benchmark, times in results[lang].items():
+            if not times and interrupted:
+                continue
             processed[lang][benchmark] = {
+                "category": category_of(benchmark),
                 "times": times,
                 "stats": calculate_statistics(times)
             }
+            # Which instructions ran, so results from different CPUs can be told apart
+            if benchmark in results.get("dispatch", {}).get(lang, {}):
+                processed[lang][benchmark]["dispatch"] = results["dispatch"][lang][benchmark]
+            # What the runtime cost to build and ship
+            if benchmark in results.get("build", {}).get(lang, {}):
+                processed[lang][benchmark]["build"] = results["build"][lang][benchmark]
+            # Each run's checksum, in run order like times
+            if benchmark in results.get("checksums", {}).get(lang, {}):
+                processed[lang][benchmark]["checksums"] = results["checksums"][lang][benchmark]
+    # Whether the checksums held across runs and languages, and under which policy
+    verification = verify_checksums(results)
+    if verification:
+        processed["verification"] = verification
     
     with open(filename, "w") as f:
         json.dump(processed, f, indent=2)
@@ -198,14 +1058,17 @@ def save_results(results: Dict, filename: str = "results/benchmark_results.json"
 
 def print_summary(results: Dict):
     """Print summary of results"""
-    print("\n" + "="*80)
+    print("\n" + "="*95)
     print("BENCHMARK SUMMARY")
-    print("="*80)
-    print(f"{'Benchmark':<25} {'Rust (avg)':<15} {'C++ (avg)':<15} {'Winner':<10} {'Speedup':<10}")
-    print("-"*80)
+    print("="*95)
+    measure = results.get("measure", "wall")
+    print(f"{'Benchmark':<25} {'Rust (avg)':<15} {'C++ (avg)':<15} {'Winner':<10} {'Speedup':<10} {f'{CONFIDENCE:.0%} CI':<15}")
+    print("-"*95)
     
     rust_wins = 0
     cpp_wins = 0
+    ties = 0
+    unreliable = 0
     
     for benchmark in BENCHMARKS:
         rust_times = results["rust"].get(benchmark, [])
@@ -217,19 +1080,61 @@ def print_summary(results: Dict):
         rust_avg = sum(rust_times) / len(rust_times)
         cpp_avg = sum(cpp_times) / len(cpp_times)
         
-        if rust_avg < cpp_avg:
+        # A win needs the whole interval of C++ / Rust on one side of 1; otherwise it is noise
+        measured = speedup_interval(rust_times, cpp_times)
+        interval = measured or (cpp_avg / rust_avg,) * 2
+        if interval[0] > 1:
             winner = "Rust"
-            speedup = cpp_avg / rust_avg
             rust_wins += 1
-        else:
+        elif interval[1] < 1:
             winner = "C++"
-            speedup = rust_avg / cpp_avg
             cpp_wins += 1
+        else:
+            winner = "Tie"
+            ties += 1
+        speedup = max(cpp_avg / rust_avg, rust_avg / cpp_avg)
+        ci = f"{interval[0]:.2f}-{interval[1]:.2f}" if measured else "-"
+        noisy = any(calculate_statistics(times).get("unreliable") for times in (rust_times, cpp_times))
+        unreliable += noisy
         
-        print(f"{benchmark:<25} {rust_avg:>10.4f}s    {cpp_avg:>10.4f}s    {winner:<10} {speedup:>6.2f}x")
+        print(f"{benchmark:<25} {format_amount(rust_avg, measure):>11}    {format_amount(cpp_avg, measure):>11}    {winner:<10} {speedup:>6.2f}x    {ci:<15}"
+              f"{'* unreliable' if noisy else ''}")
     
+    print("-"*95)
+    print(f"Total: Rust wins: {rust_wins}, C++ wins: {cpp_wins}, within noise: {ties}")
+    print(f"(CI: {CONFIDENCE:.0%} bootstrap interval of C++ time / Rust time)")
+    if measure != "wall":
+        amount = "billions of instructions" if MEASURES[measure] != "s" else f"{measure} seconds"
+        print(f"Measured: {measure}; averages are {amount}, and speedups compare those instead of time")
+        if results.get("unmeasured"):
+            print(f"Skipped, unable to take this measure: {', '.join(results['unmeasured'])}")
+    if unreliable:
+        print(f"* {unreliable} unreliable, run-to-run variation (CV) above {CV_THRESHOLD:.0%}. {UNRELIABLE_ADVICE}")
+    print("="*95)
+
+    failures = checksum_failures(results)
+    if failures:
+        print(f"\n⚠️  {len(failures)} checksum check{'s' if len(failures) > 1 else ''} failed "
+              "(CHECKSUM_POLICIES and PRECISION_POLICIES in build.py; every run's checksum is in the results file):")
+        for failure in failures:
+            print(f"  {failure}")
+
+    if any(results.get("build", {}).values()):
+        print_build_costs(results)
+
+def print_build_costs(results: Dict):
+    """Print each benchmark's rebuild time and binary size in both languages"""
+    print("\n" + "="*80)
+    print("BUILD COST")
+    print("="*80)
+    print(f"{'Benchmark':<25} {'Rust build':<12} {'C++ build':<12} {'Rust binary':<14} {'C++ binary':<14}")
     print("-"*80)
-    print(f"Total: Rust wins: {rust_wins}, C++ wins: {cpp_wins}")
+    for benchmark in BENCHMARKS:
+        rust = results["build"]["rust"].get(benchmark, {})
+        cpp = results["build"]["cpp"].get(benchmark, {})
+        if rust or cpp:
+            print(f"{benchmark:<25} {format_build_time(rust)}  {format_build_time(cpp)}  "
+                  f"{format_binary_size(rust)}  {format_binary_size(cpp)}")
     print("="*80)
 
 def plot_results(results_file: str = "results/benchmark_results.json"):
@@ -248,7 +1153,11 @@ def plot_results(results_file: str = "results/benchmark_results.json"):
     # Plot 1: Individual benchmark comparison
     fig, ax = plt.subplots(figsize=(14, 8))
     
-    benchmarks = BENCHMARKS
+    # Partial and single-category runs plot what ran
+    benchmarks = [b for b in BENCHMARKS if b in data["rust"] and b in data["cpp"]]
+    measure = data.get("measure", {"name": "wall", "unit": "s"})
+    amount = {"wall": "Time (seconds)", "cpu": "CPU Time (seconds)", "instructions": "Instructions",
+              "gpu": "GPU Time (seconds)"}[measure["name"]]
     rust_means = [data["rust"][b]["stats"]["mean"] for b in benchmarks if b in data["rust"]]
     cpp_means = [data["cpp"][b]["stats"]["mean"] for b in benchmarks if b in data["cpp"]]
     
@@ -259,7 +1168,7 @@ def plot_results(results_file: str = "results/benchmark_results.json"):
     bars2 = ax.bar(x + width/2, cpp_means, width, label='C++', color='#00599C')
     
     ax.set_xlabel('Benchmark', fontsize=12, fontweight='bold')
-    ax.set_ylabel('Time (seconds)', fontsize=12, fontweight='bold')
+    ax.set_ylabel(amount, fontsize=12, fontweight='bold')
     ax.set_title('Rust vs C++ Performance Comparison (Apple Silicon)', fontsize=14, fontweight='bold')
     ax.set_xticks(x)
     ax.set_xticklabels(benchmarks, rotation=45, ha='right')
@@ -273,11 +1182,12 @@ def plot_results(results_file: str = "results/benchmark_results.json"):
     # Plot 2: Category comparison
     fig, ax = plt.subplots(figsize=(12, 6))
     
-    categories = list(CATEGORIES.keys())
+    categories = [category for category, benches in CATEGORIES.items() if any(b in benchmarks for b in benches)]
     rust_category_times = []
     cpp_category_times = []
     
-    for category, benches in CATEGORIES.items():
+    for category in categories:
+        benches = CATEGORIES[category]
         rust_total = sum(data["rust"][b]["stats"]["mean"] for b in benches if b in data["rust"])
         cpp_total = sum(data["cpp"][b]["stats"]["mean"] for b in benches if b in data["cpp"])
         rust_category_times.append(rust_total)
@@ -290,7 +1200,7 @@ def plot_results(results_file: str = "results/benchmark_results.json"):
     bars2 = ax.bar(x + width/2, cpp_category_times, width, label='C++', color='#00599C')
     
     ax.set_xlabel('Category', fontsize=12, fontweight='bold')
-    ax.set_ylabel('Total Time (seconds)', fontsize=12, fontweight='bold')
+    ax.set_ylabel(f'Total {amount}', fontsize=12, fontweight='bold')
     ax.set_title('Performance by Category (Apple Silicon)', fontsize=14, fontweight='bold')
     ax.set_xticks(x)
     ax.set_xticklabels(categories)
@@ -337,10 +1247,68 @@ def plot_results(results_file: str = "results/benchmark_results.json"):
 
 def main():
     """Main execution"""
+    parser = argparse.ArgumentParser(description="Build, run and plot the Rust vs C++ benchmarks")
+    parser.add_argument("--build-times", action="store_true",
+                        help="also time a rebuild of every benchmark binary in both languages (slow: one LTO link per Rust binary)")
+    slugs = [category_slug(c) for c in CATEGORIES]
+    parser.add_argument("--category", choices=slugs, metavar="CATEGORY",
+                        help=f"run only one category's benchmarks: {', '.join(slugs)}")
+    parser.add_argument("--measure", choices=list(MEASURES), default="wall",
+                        help="what each run's number is: wall-clock time (default), CPU time (user + system), "
+                             "retired instructions (Linux) or GPU time from timestamp queries; benchmarks that only "
+                             "time themselves are skipped")
+    parser.add_argument("--target", choices=list(TARGETS), metavar="TRIPLE",
+                        help=f"cross-compile for another architecture: {', '.join(TARGETS)} (default: this machine, "
+                             "target-cpu=native)")
+    parser.add_argument("--target-cpu", metavar="CPU",
+                        help="the CPU to build for instead of the target's default, e.g. apple-m2 or x86-64-v3")
+    parser.add_argument("--allow-translated", action="store_true",
+                        help="run binaries built for another architecture under Rosetta or an emulator, recording "
+                             "the results as translated")
+    parser.add_argument("--soak", metavar="BENCHMARK", choices=BENCHMARKS,
+                        help="instead of the suite, alternate Rust and C++ runs of one benchmark for --minutes, "
+                             "saving the time series to results/soak_BENCHMARK.json")
+    parser.add_argument("--minutes", type=float, default=30, help="length of a --soak (default: 30)")
+    parser.add_argument("--profile", metavar="BENCHMARK", choices=BENCHMARKS,
+                        help="instead of the suite, run one benchmark under perf (Linux) or xctrace (macOS) and save flamegraphs to results/profiles")
+    args = parser.parse_args()
+    configure_target(args.target, args.target_cpu)
+
     print("="*80)
     print("Rust vs C++ Benchmark Suite (Apple Silicon Optimized)")
     print("="*80)
     
+    # Ctrl-C or a kill stops the current benchmark and keeps every run finished so far
+    signal.signal(signal.SIGINT, raise_interrupted)
+    signal.signal(signal.SIGTERM, raise_interrupted)
+    scratch_dir = tempfile.mkdtemp(prefix="rust_vs_cpp_")
+    try:
+        if args.profile:
+            profile(args.profile, scratch_dir)
+        elif args.soak:
+            soak(args.soak, args.minutes, scratch_dir)
+        else:
+            category = next((c for c in CATEGORIES if category_slug(c) == args.category), None)
+            run_suite(scratch_dir, args.build_times, category, args.measure, args.allow_translated)
+    except Interrupted as e:
+        print(f"\n\n⚠️  {e} received, stopping")
+        sys.exit(128 + e.signum)
+    finally:
+        shutil.rmtree(scratch_dir, ignore_errors=True)
+
+def profile(benchmark: str, scratch_dir: str):
+    """Build, then profile one benchmark in both languages"""
+    if not build_rust() or not build_cpp():
+        print("\n❌ Failed to build benchmarks")
+        sys.exit(1)
+    check_native([benchmark])
+
+    if not profile_benchmark(benchmark, scratch_dir):
+        sys.exit(1)
+
+def run_suite(scratch_dir: str, build_times: bool = False, category: str = None, measure: str = "wall",
+              allow_translated: bool = False):
+    """Build, run, save, summarize and plot"""
     # Build
     if not build_rust():
         print("\n❌ Failed to build Rust benchmarks")
@@ -352,15 +1320,38 @@ def main():
     
     print("\n✅ All builds successful!")
     
+    benchmarks = CATEGORIES[category] if category else BENCHMARKS
+    target = target_info(check_native(benchmarks, allow_translated))
+    toolchain = toolchain_info()
+    print(f"Target: {target['triple']}, CPU {target['cpu']}{' (translated)' if target['translated'] else ''}")
+    print(f"Toolchain: {toolchain['rustc']}; {toolchain['cxx']}" +
+          (f" in {toolchain['container']}" if "container" in toolchain else ""))
+    results = {"rust": {}, "cpp": {}, "dispatch": {"rust": {}, "cpp": {}}, "checksums": {"rust": {}, "cpp": {}},
+               "precision": {"rust": {}, "cpp": {}}, "build": {"rust": {}, "cpp": {}},
+               "category": category, "measure": measure, "target": target, "toolchain": toolchain}
+    measure_build_costs(results, build_times, benchmarks)
+    
     # Run benchmarks
-    print(f"\nRunning benchmarks ({NUM_RUNS} runs each)...")
-    results = run_all_benchmarks()
+    print(f"\nRunning {category or 'all'} benchmarks ({NUM_RUNS} runs each)...")
+    try:
+        run_all_benchmarks(results, scratch_dir, benchmarks, measure)
+    except Interrupted as e:
+        print(f"\n\n⚠️  {e} received during {e.benchmark}, saving the runs finished so far")
+        save_results(results, interrupted=e)
+        print_summary(results)
+        sys.exit(128 + e.signum)
     
     # Save results
     save_results(results)
     
     # Print summary
     print_summary(results)
+
+    # Headline numbers (imported here, as summary.py imports this module), and their badge
+    from summary import report
+    from badge import write_badge
+    if report():
+        write_badge()
     
     # Generate plots
     print("\nGenerating plots...")
@@ -371,6 +1362,9 @@ def main():
     print("="*80)
     print("\nResults saved in:")
     print("  - results/benchmark_results.json")
+    print("  - results/summary.json")
+    print("  - results/badge.json")
+    print("  - results/badge.svg")
     print("  - results/benchmark_comparison.png")
     print("  - results/category_comparison.png")
     print("  - results/speedup_comparison.png")
@@ -0,0 +1,474 @@
+#include <iostream>
+#include <memory>
+#include <vector>
+#include <deque>
+#include <functional>
+#include <mutex>
+#include <condition_variable>
+#include <thread>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#ifdef HAVE_CAF
+#include <caf/actor_system.hpp>
+#include <caf/actor_system_config.hpp>
+#include <caf/event_based_actor.hpp>
+#include <caf/init_global_meta_objects.hpp>
+#include <caf/scoped_actor.hpp>
+#endif
+
+const size_t RING_SIZE = 503;
+const int64_t HOPS = 2'000'000; // token passes around the ring
+const uint32_t MEETINGS = 200'000; // per chameneos game
+const size_t NUM_WORKERS = 8; // pool and CAF scheduler threads
+const size_t THROUGHPUT = 64; // messages a pooled actor handles before going back in the queue
+const int64_t WARMUP_HOPS = 10'000;
+const uint32_t WARMUP_MEETINGS = 1'000;
+const int64_t STOP = -1; // passed around the ring once the token is done
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+enum Color : int32_t { BLUE, RED, YELLOW };
+const int32_t FADED = -1; // the mall's answer once every meeting has happened
+
+// The Benchmarks Game's two chameneos games
+const std::vector<std::vector<Color>> GAMES = {
+    {BLUE, RED, YELLOW},
+    {BLUE, RED, YELLOW, RED, YELLOW, BLUE, RED, YELLOW, RED, BLUE},
+};
+
+// Two creatures that meet both take the color neither of them had, or keep theirs if they matched
+Color complement(Color a, Color b) {
+    if (a == b) return a;
+    return static_cast<Color>(3 - a - b);
+}
+
+// Blocking queue: what a thread-per-actor actor waits on, and how results reach main
+template <typename Msg>
+class Mailbox {
+    std::mutex mutex;
+    std::condition_variable ready;
+    std::deque<Msg> queue;
+
+public:
+    void push(Msg msg) {
+        {
+            std::lock_guard<std::mutex> lock(mutex);
+            queue.push_back(std::move(msg));
+        }
+        ready.notify_one();
+    }
+
+    Msg pop() {
+        std::unique_lock<std::mutex> lock(mutex);
+        ready.wait(lock, [&] { return !queue.empty(); });
+        Msg msg = std::move(queue.front());
+        queue.pop_front();
+        return msg;
+    }
+};
+
+// Both runtimes run an actor's handler on each message in order until it returns false, and
+// finish() waits for every actor spawned to stop before freeing them
+
+// One OS thread per actor, blocked on its mailbox
+class Threads {
+    struct Base {
+        virtual ~Base() = default;
+    };
+    std::vector<std::unique_ptr<Base>> actors;
+    std::vector<std::thread> threads;
+
+public:
+    template <typename Msg>
+    class Actor : public Base {
+        friend class Threads;
+        Mailbox<Msg> inbox;
+
+    public:
+        void send(Msg msg) { inbox.push(std::move(msg)); }
+    };
+
+    template <typename Msg>
+    Actor<Msg>* spawn(std::function<bool(Msg)> handler) {
+        auto actor = std::make_unique<Actor<Msg>>();
+        Actor<Msg>* raw = actor.get();
+        actors.push_back(std::move(actor));
+        threads.emplace_back([raw, handler] {
+            while (handler(raw->inbox.pop())) {}
+        });
+        return raw;
+    }
+
+    void finish() {
+        for (std::thread& t : threads) t.join();
+        threads.clear();
+        actors.clear();
+    }
+};
+
+// Actors as tasks on a fixed set of workers: a message to an idle actor puts it on the run queue,
+// and a worker drains up to THROUGHPUT messages before queueing it again behind the others
+class Pool {
+    struct Runnable {
+        virtual ~Runnable() = default;
+        virtual void run() = 0;
+    };
+
+    std::mutex mutex;
+    std::condition_variable ready;
+    std::deque<Runnable*> queue;
+    bool stopping = false;
+    size_t live = 0; // actors whose handler has not returned false
+    std::condition_variable all_stopped;
+    std::vector<std::unique_ptr<Runnable>> actors;
+    std::vector<std::thread> workers;
+
+    void schedule(Runnable* actor) {
+        {
+            std::lock_guard<std::mutex> lock(mutex);
+            queue.push_back(actor);
+        }
+        ready.notify_one();
+    }
+
+    void stopped() {
+        std::lock_guard<std::mutex> lock(mutex);
+        if (--live == 0) all_stopped.notify_all();
+    }
+
+public:
+    template <typename Msg>
+    class Actor : public Runnable {
+        friend class Pool;
+        Pool& pool;
+        std::function<bool(Msg)> handler;
+        std::mutex mutex;
+        std::deque<Msg> inbox;
+        bool scheduled = false;
+        bool done = false;
+
+        void run() override {
+            for (size_t i = 0; i < THROUGHPUT; i++) {
+                Msg msg;
+                {
+                    std::lock_guard<std::mutex> lock(mutex);
+                    if (inbox.empty()) {
+                        scheduled = false;
+                        return;
+                    }
+                    msg = std::move(inbox.front());
+                    inbox.pop_front();
+                }
+                if (!handler(std::move(msg))) {
+                    {
+                        // Still marked scheduled, so later messages are dropped without queueing it
+                        std::lock_guard<std::mutex> lock(mutex);
+                        done = true;
+                        inbox.clear();
+                    }
+                    pool.stopped();
+                    return;
+                }
+            }
+            pool.schedule(this);
+        }
+
+    public:
+        Actor(Pool& pool, std::function<bool(Msg)> handler) : pool(pool), handler(std::move(handler)) {}
+
+        void send(Msg msg) {
+            bool wake;
+            {
+                std::lock_guard<std::mutex> lock(mutex);
+                if (done) return;
+                inbox.push_back(std::move(msg));
+                wake = !scheduled;
+                scheduled = true;
+            }
+            if (wake) pool.schedule(this);
+        }
+    };
+
+    explicit Pool(size_t size) {
+        for (size_t i = 0; i < size; i++) {
+            workers.emplace_back([this] {
+                for (;;) {
+                    Runnable* actor;
+                    {
+                        std::unique_lock<std::mutex> lock(mutex);
+                        ready.wait(lock, [&] { return stopping || !queue.empty(); });
+                        if (stopping) return;
+                        actor = queue.front();
+                        queue.pop_front();
+                    }
+                    actor->run();
+                }
+            });
+        }
+    }
+
+    ~Pool() {
+        {
+            std::lock_guard<std::mutex> lock(mutex);
+            stopping = true;
+        }
+        ready.notify_all();
+        for (std::thread& t : workers) t.join();
+    }
+
+    template <typename Msg>
+    Actor<Msg>* spawn(std::function<bool(Msg)> handler) {
+        auto actor = std::make_unique<Actor<Msg>>(*this, std::move(handler));
+        Actor<Msg>* raw = actor.get();
+        std::lock_guard<std::mutex> lock(mutex);
+        actors.push_back(std::move(actor));
+        live++;
+        return raw;
+    }
+
+    void finish() {
+        std::unique_lock<std::mutex> lock(mutex);
+        all_stopped.wait(lock, [&] { return live == 0; });
+        actors.clear();
+    }
+};
+
+// Thread ring: the actor holding the token when it reaches zero reports its 1-based id, then a
+// STOP goes round so every actor returns
+template <typename Runtime>
+uint64_t ring(Runtime& runtime, int64_t hops) {
+    using Node = typename Runtime::template Actor<int64_t>;
+    Mailbox<uint64_t> done;
+    std::vector<Node*> nodes(RING_SIZE);
+    for (size_t i = 0; i < RING_SIZE; i++) {
+        nodes[i] = runtime.template spawn<int64_t>([&nodes, &done, i](int64_t token) {
+            Node* next = nodes[(i + 1) % RING_SIZE];
+            if (token == STOP) {
+                next->send(STOP);
+                return false;
+            }
+            if (token == 0) {
+                done.push(i + 1);
+                next->send(STOP);
+                return false;
+            }
+            next->send(token - 1);
+            return true;
+        });
+    }
+    nodes[0]->send(hops);
+    uint64_t holder = done.pop();
+    runtime.finish();
+    return holder;
+}
+
+// Chameneos-redux: every creature asks the mall for a partner until it is turned away, and the
+// meetings each one had add up to twice the total
+template <typename Runtime>
+uint64_t chameneos(Runtime& runtime, const std::vector<Color>& colors, uint32_t meetings) {
+    using Creature = typename Runtime::template Actor<int32_t>;
+    struct Request {
+        Color color;
+        Creature* creature;
+    };
+    using Mall = typename Runtime::template Actor<Request>;
+
+    Mailbox<uint64_t> done;
+    size_t faded = 0;
+    uint32_t meetings_left = meetings;
+    Request waiting{BLUE, nullptr};
+    Mall* mall = runtime.template spawn<Request>([&](Request request) {
+        if (meetings_left == 0) {
+            request.creature->send(FADED);
+            return ++faded < colors.size();
+        }
+        if (!waiting.creature) {
+            waiting = request;
+        } else {
+            meetings_left--;
+            waiting.creature->send(request.color);
+            request.creature->send(waiting.color);
+            waiting.creature = nullptr;
+        }
+        return true;
+    });
+
+    struct State {
+        Color color;
+        uint64_t met = 0;
+        Creature* self = nullptr;
+    };
+    std::vector<State> states(colors.size());
+    for (size_t i = 0; i < colors.size(); i++) {
+        State& state = states[i];
+        state.color = colors[i];
+        state.self = runtime.template spawn<int32_t>([&state, &done, &mall](int32_t partner) {
+            if (partner == FADED) {
+                done.push(state.met);
+                return false;
+            }
+            state.color = complement(state.color, static_cast<Color>(partner));
+            state.met++;
+            mall->send({state.color, state.self});
+            return true;
+        });
+    }
+    for (State& state : states) mall->send({state.color, state.self});
+
+    uint64_t total = 0;
+    for (size_t i = 0; i < colors.size(); i++) total += done.pop();
+    runtime.finish();
+    return total;
+}
+
+#ifdef HAVE_CAF
+// The same two problems on the C++ Actor Framework's work-stealing scheduler. Actors quit on their
+// own, as above, and the system waits for all of them when it goes out of scope
+uint64_t ring_caf(caf::actor_system& system, int64_t hops) {
+    caf::scoped_actor main_actor{system};
+    caf::actor done = caf::actor_cast<caf::actor>(main_actor);
+    std::vector<caf::actor> nodes;
+    for (size_t i = 0; i < RING_SIZE; i++) {
+        nodes.push_back(system.spawn([done, i](caf::event_based_actor* self) -> caf::behavior {
+            return {[self, done, i](caf::actor next) {
+                self->become([self, done, i, next](int64_t token) {
+                    if (token == STOP || token == 0) {
+                        if (token == 0) self->send(done, static_cast<uint64_t>(i + 1));
+                        self->send(next, STOP);
+                        self->quit();
+                        return;
+                    }
+                    self->send(next, token - 1);
+                });
+            }};
+        }));
+    }
+    for (size_t i = 0; i < RING_SIZE; i++) main_actor->send(nodes[i], nodes[(i + 1) % RING_SIZE]);
+    main_actor->send(nodes[0], hops);
+    uint64_t holder = 0;
+    main_actor->receive([&](uint64_t id) { holder = id; });
+    return holder;
+}
+
+uint64_t chameneos_caf(caf::actor_system& system, const std::vector<Color>& colors, uint32_t meetings) {
+    caf::scoped_actor main_actor{system};
+    caf::actor done = caf::actor_cast<caf::actor>(main_actor);
+    size_t creatures = colors.size();
+    caf::actor mall = system.spawn([meetings, creatures](caf::event_based_actor* self) -> caf::behavior {
+        auto meetings_left = std::make_shared<uint32_t>(meetings);
+        auto faded = std::make_shared<size_t>(0);
+        auto waiting = std::make_shared<std::pair<int32_t, caf::actor>>();
+        return {[=](int32_t color, caf::actor creature) {
+            if (*meetings_left == 0) {
+                self->send(creature, FADED);
+                if (++*faded == creatures) self->quit();
+            } else if (!waiting->second) {
+                *waiting = {color, creature};
+            } else {
+                --*meetings_left;
+                self->send(waiting->second, color);
+                self->send(creature, waiting->first);
+                waiting->second = nullptr;
+            }
+        }};
+    });
+    for (Color start : colors) {
+        system.spawn([mall, done, start](caf::event_based_actor* self) -> caf::behavior {
+            auto color = std::make_shared<Color>(start);
+            auto met = std::make_shared<uint64_t>(0);
+            caf::actor me = caf::actor_cast<caf::actor>(self);
+            self->send(mall, static_cast<int32_t>(start), me);
+            return {[=](int32_t partner) {
+                if (partner == FADED) {
+                    self->send(done, *met);
+                    self->quit();
+                    return;
+                }
+                *color = complement(*color, static_cast<Color>(partner));
+                ++*met;
+                self->send(mall, static_cast<int32_t>(*color), me);
+            }};
+        });
+    }
+    uint64_t total = 0;
+    for (size_t i = 0; i < creatures; i++) main_actor->receive([&](uint64_t met) { total += met; });
+    return total;
+}
+#endif
+
+struct Timing {
+    const char* name;
+    Duration ring;
+    Duration chameneos;
+    uint64_t holder;
+    uint64_t met;
+};
+
+template <typename Ring, typename Chameneos>
+Timing run(const char* name, Ring ring_fn, Chameneos chameneos_fn) {
+    ring_fn(WARMUP_HOPS);
+    chameneos_fn(GAMES[0], WARMUP_MEETINGS);
+    auto start = Clock::now();
+    uint64_t holder = ring_fn(HOPS);
+    Duration ring_time = Clock::now() - start;
+    start = Clock::now();
+    uint64_t met = 0;
+    for (const auto& game : GAMES) met += chameneos_fn(game, MEETINGS);
+    Duration chameneos_time = Clock::now() - start;
+    return {name, ring_time, chameneos_time, holder, met};
+}
+
+int main() {
+    std::vector<Timing> variants;
+    variants.push_back(run("threads + mailboxes",
+                           [](int64_t hops) { Threads t; return ring(t, hops); },
+                           [](const std::vector<Color>& game, uint32_t n) { Threads t; return chameneos(t, game, n); }));
+    {
+        Pool pool(NUM_WORKERS);
+        variants.push_back(run("pooled actors",
+                               [&](int64_t hops) { return ring(pool, hops); },
+                               [&](const std::vector<Color>& game, uint32_t n) { return chameneos(pool, game, n); }));
+    }
+#ifdef HAVE_CAF
+    caf::core::init_global_meta_objects();
+    {
+        caf::actor_system_config config;
+        config.set("caf.scheduler.max-threads", static_cast<int64_t>(NUM_WORKERS));
+        caf::actor_system system{config};
+        variants.push_back(run("CAF",
+                               [&](int64_t hops) { return ring_caf(system, hops); },
+                               [&](const std::vector<Color>& game, uint32_t n) { return chameneos_caf(system, game, n); }));
+    }
+#endif
+
+    // The Benchmarks Game answers: who holds the token at zero, and two meetings per meeting
+    uint64_t expected_holder = HOPS % RING_SIZE + 1;
+    uint64_t expected_met = 2ULL * MEETINGS * GAMES.size();
+    for (const Timing& t : variants) {
+        if (t.holder != expected_holder || t.met != expected_met) {
+            std::cerr << t.name << ": token ended at " << t.holder << " (expected " << expected_holder << "), " << t.met
+                      << " meetings (expected " << expected_met << ")" << std::endl;
+            return 1;
+        }
+    }
+
+    // The headline is the pooled runtime, the counterpart of Rust's async tasks
+    const Timing& headline = variants[1];
+    std::cout << std::fixed << std::setprecision(6) << (headline.ring + headline.chameneos).count() << std::endl;
+    std::cerr << "Thread ring: " << RING_SIZE << " actors, " << HOPS << " hops. Chameneos: " << MEETINGS
+              << " meetings each for " << GAMES[0].size() << " and " << GAMES[1].size() << " creatures" << std::endl;
+    for (const Timing& t : variants) {
+        char line[128];
+        std::snprintf(line, sizeof(line), "  %-22s ring %7.1f ns/hop   chameneos %7.1f ns/meeting", t.name,
+                      t.ring.count() * 1e9 / HOPS, t.chameneos.count() * 1e9 / (MEETINGS * GAMES.size()));
+        std::cerr << line << std::endl;
+    }
+#ifndef HAVE_CAF
+    std::cerr << "(CAF not found: built without the C++ Actor Framework)" << std::endl;
+#endif
+    std::cerr << "Checksum: " << expected_holder + expected_met << std::endl;
+    return 0;
+}
@@ -0,0 +1,263 @@
+#include <iostream>
+#include <memory>
+#include <memory_resource>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+const size_t NODES_PER_ROUND = 4'000'000;
+const size_t NUM_ROUNDS = 5;
+const uint32_t MAX_DEPTH = 16;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+enum class Kind : uint8_t { Num, Neg, Add, Sub, Mul };
+
+// Shape of the next node; every builder draws the same sequence, so all variants get identical trees
+struct Shape {
+    Kind kind;
+    int64_t value;
+};
+
+Shape next_shape(uint64_t& seed, uint32_t depth) {
+    uint64_t r = next_random(seed);
+    if (depth == 0 || r % 8 < 3) return {Kind::Num, static_cast<int64_t>((r >> 8) % 100)};
+    switch (r % 8) {
+        case 3: return {Kind::Neg, 0};
+        case 4:
+        case 5: return {Kind::Add, 0};
+        case 6: return {Kind::Sub, 0};
+        default: return {Kind::Mul, 0};
+    }
+}
+
+int64_t apply(Kind kind, int64_t a, int64_t b) {
+    // Unsigned arithmetic wraps like Rust's wrapping_* operations
+    uint64_t ua = a, ub = b;
+    switch (kind) {
+        case Kind::Add: return static_cast<int64_t>(ua + ub);
+        case Kind::Sub: return static_cast<int64_t>(ua - ub);
+        default: return static_cast<int64_t>(ua * ub);
+    }
+}
+
+// Variant 1: every node its own heap allocation
+struct BoxExpr {
+    Kind kind;
+    int64_t value;
+    std::unique_ptr<BoxExpr> lhs, rhs;
+};
+
+std::unique_ptr<BoxExpr> build_box(uint64_t& seed, uint32_t depth, size_t& count) {
+    count++;
+    Shape s = next_shape(seed, depth);
+    auto node = std::make_unique<BoxExpr>(BoxExpr{s.kind, s.value, nullptr, nullptr});
+    if (s.kind != Kind::Num) {
+        node->lhs = build_box(seed, depth - 1, count);
+        if (s.kind != Kind::Neg) node->rhs = build_box(seed, depth - 1, count);
+    }
+    return node;
+}
+
+int64_t eval_box(const BoxExpr& e) {
+    switch (e.kind) {
+        case Kind::Num: return e.value;
+        case Kind::Neg: return static_cast<int64_t>(0 - static_cast<uint64_t>(eval_box(*e.lhs)));
+        default: {
+            int64_t a = eval_box(*e.lhs);
+            int64_t b = eval_box(*e.rhs);
+            return apply(e.kind, a, b);
+        }
+    }
+}
+
+// Variant 2: pmr monotonic buffer, nodes are placement-constructed and freed all at once
+struct ArenaExpr {
+    Kind kind;
+    int64_t value;
+    const ArenaExpr* lhs;
+    const ArenaExpr* rhs;
+};
+
+const ArenaExpr* build_arena(std::pmr::memory_resource& mem, uint64_t& seed, uint32_t depth, size_t& count) {
+    count++;
+    Shape s = next_shape(seed, depth);
+    const ArenaExpr* lhs = nullptr;
+    const ArenaExpr* rhs = nullptr;
+    if (s.kind != Kind::Num) {
+        lhs = build_arena(mem, seed, depth - 1, count);
+        if (s.kind != Kind::Neg) rhs = build_arena(mem, seed, depth - 1, count);
+    }
+    void* slot = mem.allocate(sizeof(ArenaExpr), alignof(ArenaExpr));
+    return new (slot) ArenaExpr{s.kind, s.value, lhs, rhs};
+}
+
+int64_t eval_arena(const ArenaExpr& e) {
+    switch (e.kind) {
+        case Kind::Num: return e.value;
+        case Kind::Neg: return static_cast<int64_t>(0 - static_cast<uint64_t>(eval_arena(*e.lhs)));
+        default: {
+            int64_t a = eval_arena(*e.lhs);
+            int64_t b = eval_arena(*e.rhs);
+            return apply(e.kind, a, b);
+        }
+    }
+}
+
+// Variant 3: hand-rolled index arena, nodes live in one vector and refer to each other by uint32_t
+struct IndexExpr {
+    Kind kind;
+    int64_t value;
+    uint32_t lhs, rhs;
+};
+
+uint32_t build_index(std::vector<IndexExpr>& arena, uint64_t& seed, uint32_t depth) {
+    Shape s = next_shape(seed, depth);
+    uint32_t lhs = 0, rhs = 0;
+    if (s.kind != Kind::Num) {
+        lhs = build_index(arena, seed, depth - 1);
+        if (s.kind != Kind::Neg) rhs = build_index(arena, seed, depth - 1);
+    }
+    arena.push_back({s.kind, s.value, lhs, rhs});
+    return static_cast<uint32_t>(arena.size() - 1);
+}
+
+int64_t eval_index(const std::vector<IndexExpr>& arena, uint32_t id) {
+    const IndexExpr& e = arena[id];
+    switch (e.kind) {
+        case Kind::Num: return e.value;
+        case Kind::Neg: return static_cast<int64_t>(0 - static_cast<uint64_t>(eval_index(arena, e.lhs)));
+        default: {
+            int64_t a = eval_index(arena, e.lhs);
+            int64_t b = eval_index(arena, e.rhs);
+            return apply(e.kind, a, b);
+        }
+    }
+}
+
+struct Phases {
+    Duration build{}, traverse{}, teardown{};
+    size_t nodes = 0;
+    int64_t checksum = 0;
+
+    Duration total() const { return build + traverse + teardown; }
+};
+
+Phases run_box(size_t rounds, size_t nodes_per_round) {
+    Phases p;
+    for (size_t round = 0; round < rounds; round++) {
+        uint64_t seed = round;
+        auto start = Clock::now();
+        std::vector<std::unique_ptr<BoxExpr>> roots;
+        size_t count = 0;
+        while (count < nodes_per_round) {
+            roots.push_back(build_box(seed, MAX_DEPTH, count));
+        }
+        p.build += Clock::now() - start;
+        p.nodes += count;
+
+        start = Clock::now();
+        for (const auto& r : roots) p.checksum = apply(Kind::Add, p.checksum, eval_box(*r));
+        p.traverse += Clock::now() - start;
+
+        start = Clock::now();
+        roots.clear();
+        roots.shrink_to_fit();
+        p.teardown += Clock::now() - start;
+    }
+    return p;
+}
+
+// One resource reused across rounds: release() returns its buffers, the upstream allocator recycles them
+Phases run_arena(size_t rounds, size_t nodes_per_round) {
+    Phases p;
+    std::pmr::monotonic_buffer_resource mem;
+    for (size_t round = 0; round < rounds; round++) {
+        uint64_t seed = round;
+        auto start = Clock::now();
+        std::vector<const ArenaExpr*> roots;
+        size_t count = 0;
+        while (count < nodes_per_round) {
+            roots.push_back(build_arena(mem, seed, MAX_DEPTH, count));
+        }
+        p.build += Clock::now() - start;
+        p.nodes += count;
+
+        start = Clock::now();
+        for (const ArenaExpr* r : roots) p.checksum = apply(Kind::Add, p.checksum, eval_arena(*r));
+        p.traverse += Clock::now() - start;
+
+        start = Clock::now();
+        roots.clear();
+        roots.shrink_to_fit();
+        mem.release();
+        p.teardown += Clock::now() - start;
+    }
+    return p;
+}
+
+Phases run_index(size_t rounds, size_t nodes_per_round) {
+    Phases p;
+    std::vector<IndexExpr> arena;
+    for (size_t round = 0; round < rounds; round++) {
+        uint64_t seed = round;
+        auto start = Clock::now();
+        std::vector<uint32_t> roots;
+        while (arena.size() < nodes_per_round) {
+            roots.push_back(build_index(arena, seed, MAX_DEPTH));
+        }
+        p.build += Clock::now() - start;
+        p.nodes += arena.size();
+
+        start = Clock::now();
+        for (uint32_t r : roots) p.checksum = apply(Kind::Add, p.checksum, eval_index(arena, r));
+        p.traverse += Clock::now() - start;
+
+        start = Clock::now();
+        roots.clear();
+        roots.shrink_to_fit();
+        arena.clear();
+        p.teardown += Clock::now() - start;
+    }
+    return p;
+}
+
+void report(const char* name, const Phases& p) {
+    std::cerr << std::left << std::setw(12) << name << std::right << std::fixed << std::setprecision(3)
+              << " build " << p.build.count() << "s (" << std::setprecision(1)
+              << p.nodes / 1e6 / p.build.count() << "M nodes/s), traverse " << std::setprecision(3)
+              << p.traverse.count() << "s, teardown " << p.teardown.count() << "s" << std::endl;
+}
+
+int main() {
+    // Warm-up
+    run_box(1, NODES_PER_ROUND / 100);
+    run_arena(1, NODES_PER_ROUND / 100);
+    run_index(1, NODES_PER_ROUND / 100);
+
+    // Benchmark
+    Phases boxed = run_box(NUM_ROUNDS, NODES_PER_ROUND);
+    Phases arena = run_arena(NUM_ROUNDS, NODES_PER_ROUND);
+    Phases index = run_index(NUM_ROUNDS, NODES_PER_ROUND);
+
+    Duration total_duration = boxed.total() + arena.total() + index.total();
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    report("unique_ptr", boxed);
+    report("pmr arena", arena);
+    report("index arena", index);
+    std::cerr << "Nodes: " << boxed.nodes << " over " << NUM_ROUNDS << " rounds" << std::endl;
+    bool match = boxed.checksum == arena.checksum && boxed.checksum == index.checksum
+                 && boxed.nodes == arena.nodes && boxed.nodes == index.nodes;
+    std::cerr << "Checksum: " << boxed.checksum << " (all variants match: " << (match ? "true" : "false") << ")"
+              << std::endl;
+
+    return 0;
+}
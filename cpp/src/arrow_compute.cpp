@@ -0,0 +1,230 @@
+#include <arrow/api.h>
+#include <arrow/compute/api.h>
+#include <arrow/util/byte_size.h>
+#include <iostream>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <limits>
+#include <algorithm>
+#include <functional>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+
+namespace cp = arrow::compute;
+
+const size_t DEFAULT_ROWS = 100'000'000;
+const size_t WARMUP_ROWS = 1'000'000;
+const double MIN_PRICE = 500.0; // WHERE price >= 500 AND quantity <= 20 keeps about 10% of rows
+const int64_t MAX_QUANTITY = 20;
+const uint64_t SEED = 0x6172'726f'7763'6f6d;
+
+// SplitMix64; the top 53 bits become a double in [0, 1) exactly, in both languages
+struct Rng {
+    uint64_t state;
+
+    uint64_t next() {
+        state += 0x9e3779b97f4a7c15ULL;
+        uint64_t z = state;
+        z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+        z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+        return z ^ (z >> 31);
+    }
+};
+
+// Aborts with Arrow's message on any failed Result, like Rust's expect
+template <typename T>
+T expect(arrow::Result<T> result, const char* what) {
+    if (!result.ok()) {
+        std::cerr << what << ": " << result.status().ToString() << std::endl;
+        std::exit(1);
+    }
+    return std::move(result).ValueUnsafe();
+}
+
+// An orders table: id, price in [0, 1000) and quantity in 1..=100
+std::shared_ptr<arrow::RecordBatch> make_table(size_t rows) {
+    Rng rng{SEED};
+    arrow::UInt32Builder id;
+    arrow::DoubleBuilder price;
+    arrow::Int64Builder quantity;
+    if (!id.Reserve(rows).ok() || !price.Reserve(rows).ok() || !quantity.Reserve(rows).ok()) {
+        std::cerr << "Failed to allocate the table" << std::endl;
+        std::exit(1);
+    }
+    for (size_t i = 0; i < rows; i++) {
+        id.UnsafeAppend(static_cast<uint32_t>(i));
+        price.UnsafeAppend(static_cast<double>(rng.next() >> 11) * (1.0 / static_cast<double>(uint64_t(1) << 53)) * 1000.0);
+        quantity.UnsafeAppend(1 + static_cast<int64_t>(rng.next() % 100));
+    }
+    auto schema = arrow::schema({
+        arrow::field("id", arrow::uint32(), false),
+        arrow::field("price", arrow::float64(), false),
+        arrow::field("quantity", arrow::int64(), false),
+    });
+    return arrow::RecordBatch::Make(schema, static_cast<int64_t>(rows), {
+        expect(id.Finish(), "Failed to build the table"),
+        expect(price.Finish(), "Failed to build the table"),
+        expect(quantity.Finish(), "Failed to build the table"),
+    });
+}
+
+// WHERE price >= MIN_PRICE AND quantity <= MAX_QUANTITY: two comparison kernels, a boolean AND,
+// then one filter over every column
+std::shared_ptr<arrow::RecordBatch> filter(const std::shared_ptr<arrow::RecordBatch>& table) {
+    auto price = expect(cp::CallFunction("greater_equal", {table->GetColumnByName("price"), arrow::Datum(MIN_PRICE)}),
+                        "Comparison failed");
+    auto quantity = expect(cp::CallFunction("less_equal", {table->GetColumnByName("quantity"), arrow::Datum(MAX_QUANTITY)}),
+                           "Comparison failed");
+    auto mask = expect(cp::CallFunction("and", {price, quantity}), "AND failed");
+    return expect(cp::Filter(table, mask), "Filter failed").record_batch();
+}
+
+// Floating-point sums depend on each library's summation order, so they are reported but only the
+// exact results (integer sums, min and max) are checked
+struct Aggregates {
+    int64_t rows;
+    double price_sum;
+    double price_min;
+    double price_max;
+    int64_t quantity_sum;
+};
+
+Aggregates aggregate(const arrow::RecordBatch& batch) {
+    auto price = batch.GetColumnByName("price");
+    auto quantity = batch.GetColumnByName("quantity");
+    Aggregates out{batch.num_rows(), 0.0, std::numeric_limits<double>::quiet_NaN(),
+                   std::numeric_limits<double>::quiet_NaN(), 0};
+    auto price_sum = expect(cp::Sum(price), "Sum failed");
+    auto min_max = expect(cp::MinMax(price), "MinMax failed");
+    auto quantity_sum = expect(cp::Sum(quantity), "Sum failed");
+    if (price_sum.scalar()->is_valid) out.price_sum = price_sum.scalar_as<arrow::DoubleScalar>().value;
+    const auto& extremes = min_max.scalar_as<arrow::StructScalar>();
+    if (extremes.value[0]->is_valid) {
+        out.price_min = static_cast<const arrow::DoubleScalar&>(*extremes.value[0]).value;
+        out.price_max = static_cast<const arrow::DoubleScalar&>(*extremes.value[1]).value;
+    }
+    if (quantity_sum.scalar()->is_valid) out.quantity_sum = quantity_sum.scalar_as<arrow::Int64Scalar>().value;
+    return out;
+}
+
+// ORDER BY price DESC: sort the key to indices, then gather every column through them
+std::shared_ptr<arrow::RecordBatch> sort(const std::shared_ptr<arrow::RecordBatch>& batch) {
+    auto indices = expect(cp::SortIndices(*batch->GetColumnByName("price"), cp::SortOrder::Descending), "Sort failed");
+    return expect(cp::Take(batch, indices), "Take failed").record_batch();
+}
+
+const double* prices_of(const arrow::RecordBatch& batch) {
+    return static_cast<const arrow::DoubleArray&>(*batch.GetColumnByName("price")).raw_values();
+}
+
+// Position-weighted over the sorted prices, so the order matters but ties between equal prices do not
+uint64_t order_digest(const arrow::RecordBatch& sorted) {
+    const double* price = prices_of(sorted);
+    uint64_t sum = 0;
+    for (int64_t i = 0; i < sorted.num_rows(); i++) {
+        uint64_t bits;
+        std::memcpy(&bits, &price[i], sizeof(bits));
+        sum += (static_cast<uint64_t>(i) + 1) * (bits >> 32);
+    }
+    return sum;
+}
+
+// The same query as plain loops over the columns, to check the kernels against
+Aggregates reference(const arrow::RecordBatch& table) {
+    const double* price = prices_of(table);
+    const int64_t* quantity = static_cast<const arrow::Int64Array&>(*table.GetColumnByName("quantity")).raw_values();
+    Aggregates out{0, 0.0, std::numeric_limits<double>::infinity(), -std::numeric_limits<double>::infinity(), 0};
+    for (int64_t i = 0; i < table.num_rows(); i++) {
+        if (price[i] >= MIN_PRICE && quantity[i] <= MAX_QUANTITY) {
+            out.rows++;
+            out.quantity_sum += quantity[i];
+            out.price_min = std::min(out.price_min, price[i]);
+            out.price_max = std::max(out.price_max, price[i]);
+        }
+    }
+    return out;
+}
+
+size_t parse_rows(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--rows") == 0) {
+            long long n = i + 1 < argc ? std::atoll(argv[i + 1]) : 0;
+            if (n <= 0 || n > static_cast<long long>(UINT32_MAX)) {
+                std::cerr << "--rows expects a positive integer that fits in u32" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<size_t>(n);
+        }
+    }
+    return DEFAULT_ROWS;
+}
+
+int main(int argc, char** argv) {
+    // Since Arrow 21 the kernels live in libarrow_compute and register themselves here
+    if (!cp::Initialize().ok()) {
+        std::cerr << "Failed to initialize Arrow compute" << std::endl;
+        return 1;
+    }
+    size_t rows = parse_rows(argc, argv);
+    auto table = make_table(rows);
+
+    // Warm-up
+    auto warmup = make_table(WARMUP_ROWS);
+    sort(filter(warmup));
+    aggregate(*warmup);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    Aggregates totals = aggregate(*table);
+    std::chrono::duration<double> aggregate_time = std::chrono::high_resolution_clock::now() - start;
+    start = std::chrono::high_resolution_clock::now();
+    auto selected = filter(table);
+    std::chrono::duration<double> filter_time = std::chrono::high_resolution_clock::now() - start;
+    start = std::chrono::high_resolution_clock::now();
+    auto sorted = sort(selected);
+    std::chrono::duration<double> sort_time = std::chrono::high_resolution_clock::now() - start;
+    Aggregates selected_totals = aggregate(*sorted);
+
+    Aggregates expected = reference(*table);
+    const double* prices = prices_of(*sorted);
+    bool ordered = std::is_sorted(prices, prices + sorted->num_rows(), std::greater<double>());
+    if (selected_totals.rows != expected.rows || selected_totals.quantity_sum != expected.quantity_sum ||
+        std::memcmp(&selected_totals.price_min, &expected.price_min, sizeof(double)) != 0 ||
+        std::memcmp(&selected_totals.price_max, &expected.price_max, sizeof(double)) != 0 || !ordered) {
+        fprintf(stderr, "Kernel results differ from the reference loop: %lld rows, quantity %lld, price %f..%f, sorted %s; "
+                "expected %lld rows, quantity %lld, price %f..%f\n",
+                static_cast<long long>(selected_totals.rows), static_cast<long long>(selected_totals.quantity_sum),
+                selected_totals.price_min, selected_totals.price_max, ordered ? "true" : "false",
+                static_cast<long long>(expected.rows), static_cast<long long>(expected.quantity_sum),
+                expected.price_min, expected.price_max);
+        return 1;
+    }
+
+    double total = aggregate_time.count() + filter_time.count() + sort_time.count();
+    auto rate = [](double n, double seconds) { return n / seconds / 1e6; };
+    uint64_t checksum = static_cast<uint64_t>(totals.quantity_sum) + static_cast<uint64_t>(selected_totals.rows) +
+                        static_cast<uint64_t>(selected_totals.quantity_sum) + order_digest(*sorted);
+    std::cout << std::fixed << std::setprecision(6) << total << std::endl;
+    fprintf(stderr, "Table: %zu rows (id u32, price f64, quantity i64), %.0f MB\n", rows,
+            arrow::util::TotalBufferSize(*table) / 1e6);
+    fprintf(stderr, "Aggregate (sum, min, max of 2 columns): %.6fs, %.0fM rows/s\n", aggregate_time.count(),
+            rate(rows, aggregate_time.count()));
+    fprintf(stderr, "Filter (price >= %g, quantity <= %lld): %.6fs, %.0fM rows/s, %lld rows kept (%.1f%%)\n", MIN_PRICE,
+            static_cast<long long>(MAX_QUANTITY), filter_time.count(), rate(rows, filter_time.count()),
+            static_cast<long long>(selected_totals.rows), 100.0 * selected_totals.rows / rows);
+    fprintf(stderr, "Sort (price descending, 3 columns taken): %.6fs, %.1fM rows/s\n", sort_time.count(),
+            rate(selected_totals.rows, sort_time.count()));
+    fprintf(stderr, "Totals: price sum %.6e, min %.6f, max %.6f, quantity sum %lld\n", totals.price_sum, totals.price_min,
+            totals.price_max, static_cast<long long>(totals.quantity_sum));
+    if (selected_totals.rows > 0) {
+        const auto& ids = static_cast<const arrow::UInt32Array&>(*sorted->GetColumnByName("id"));
+        fprintf(stderr, "Top row: id %u, price %.6f; filtered quantity sum %lld, matching the reference loop\n",
+                ids.Value(0), prices[0], static_cast<long long>(selected_totals.quantity_sum));
+    }
+    fprintf(stderr, "Checksum: %llu\n", static_cast<unsigned long long>(checksum));
+
+    return 0;
+}
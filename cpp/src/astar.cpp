@@ -0,0 +1,186 @@
+#include <iostream>
+#include <vector>
+#include <queue>
+#include <unordered_map>
+#include <thread>
+#include <optional>
+#include <functional>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t GRID_SIZE = 512;
+const size_t NUM_QUERIES = 2000;
+const uint64_t OBSTACLE_PERCENT = 20;
+const uint64_t MAX_WEIGHT = 9;
+const uint8_t OBSTACLE = 0;
+const size_t NUM_THREADS = 8;
+
+struct PathResult {
+    uint64_t cost;
+    size_t length;
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// 0 = obstacle, otherwise the cost of entering the cell
+std::vector<uint8_t> generate_grid() {
+    uint64_t seed = 12345;
+    std::vector<uint8_t> cells(GRID_SIZE * GRID_SIZE);
+    for (auto& cell : cells) {
+        if (next_random(seed) % 100 < OBSTACLE_PERCENT) {
+            cell = OBSTACLE;
+        } else {
+            cell = static_cast<uint8_t>(1 + next_random(seed) % MAX_WEIGHT);
+        }
+    }
+    return cells;
+}
+
+std::vector<std::pair<size_t, size_t>> generate_queries(const std::vector<uint8_t>& grid) {
+    uint64_t seed = 67890;
+    auto random_open_cell = [&]() {
+        while (true) {
+            size_t cell = next_random(seed) % (GRID_SIZE * GRID_SIZE);
+            if (grid[cell] != OBSTACLE) {
+                return cell;
+            }
+        }
+    };
+
+    std::vector<std::pair<size_t, size_t>> queries;
+    for (size_t i = 0; i < NUM_QUERIES; i++) {
+        size_t start = random_open_cell();
+        size_t goal = random_open_cell();
+        queries.emplace_back(start, goal);
+    }
+    return queries;
+}
+
+// Manhattan distance; the cheapest cell costs 1, so it never overestimates
+uint32_t heuristic(size_t a, size_t b) {
+    size_t ax = a % GRID_SIZE, ay = a / GRID_SIZE;
+    size_t bx = b % GRID_SIZE, by = b / GRID_SIZE;
+    size_t dx = ax > bx ? ax - bx : bx - ax;
+    size_t dy = ay > by ? ay - by : by - ay;
+    return static_cast<uint32_t>(dx + dy);
+}
+
+// Returns (path cost, path length in steps), or nullopt if the goal is unreachable
+std::optional<PathResult> astar(const std::vector<uint8_t>& grid, size_t start, size_t goal) {
+    using Entry = std::pair<uint32_t, size_t>;
+    std::priority_queue<Entry, std::vector<Entry>, std::greater<Entry>> open;
+    std::unordered_map<size_t, uint32_t> g_score;
+    std::unordered_map<size_t, size_t> came_from;
+
+    g_score[start] = 0;
+    open.push({heuristic(start, goal), start});
+
+    while (!open.empty()) {
+        auto [f, current] = open.top();
+        open.pop();
+
+        uint32_t g = g_score[current];
+        if (f > g + heuristic(current, goal)) {
+            continue; // Stale heap entry
+        }
+
+        if (current == goal) {
+            size_t length = 0;
+            size_t node = goal;
+            while (node != start) {
+                node = came_from[node];
+                length++;
+            }
+            return PathResult{g, length};
+        }
+
+        size_t x = current % GRID_SIZE, y = current / GRID_SIZE;
+        size_t neighbors[4];
+        size_t count = 0;
+        if (x > 0) neighbors[count++] = current - 1;
+        if (x + 1 < GRID_SIZE) neighbors[count++] = current + 1;
+        if (y > 0) neighbors[count++] = current - GRID_SIZE;
+        if (y + 1 < GRID_SIZE) neighbors[count++] = current + GRID_SIZE;
+
+        for (size_t i = 0; i < count; i++) {
+            size_t next = neighbors[i];
+            uint8_t weight = grid[next];
+            if (weight == OBSTACLE) {
+                continue;
+            }
+            uint32_t tentative = g + weight;
+            auto it = g_score.find(next);
+            if (it == g_score.end() || tentative < it->second) {
+                g_score[next] = tentative;
+                came_from[next] = current;
+                open.push({tentative + heuristic(next, goal), next});
+            }
+        }
+    }
+
+    return std::nullopt;
+}
+
+std::vector<std::optional<PathResult>> solve_all(const std::vector<uint8_t>& grid,
+                                                 const std::vector<std::pair<size_t, size_t>>& queries,
+                                                 size_t count) {
+    std::vector<std::optional<PathResult>> results(count);
+    std::vector<std::thread> threads;
+    size_t per_thread = count / NUM_THREADS;
+
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        size_t begin = t * per_thread;
+        size_t end = (t == NUM_THREADS - 1) ? count : (t + 1) * per_thread;
+        threads.emplace_back([&, begin, end] {
+            for (size_t i = begin; i < end; i++) {
+                results[i] = astar(grid, queries[i].first, queries[i].second);
+            }
+        });
+    }
+
+    for (auto& thread : threads) {
+        thread.join();
+    }
+
+    return results;
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    auto grid = generate_grid();
+    auto queries = generate_queries(grid);
+
+    // Warm-up
+    auto warmup = solve_all(grid, queries, NUM_QUERIES / 20);
+
+    // Benchmark
+    measure->start();
+    auto results = solve_all(grid, queries, NUM_QUERIES);
+    measure->stop();
+
+    // Checksum
+    uint64_t total_cost = 0;
+    size_t total_length = 0;
+    size_t unreachable = 0;
+    for (const auto& r : results) {
+        if (r) {
+            total_cost += r->cost;
+            total_length += r->length;
+        } else {
+            unreachable++;
+        }
+    }
+
+    measure->report();
+    std::cerr << "Paths: " << NUM_QUERIES << ", unreachable: " << unreachable
+              << ", total steps: " << total_length << std::endl;
+    std::cerr << "Checksum: " << total_cost << std::endl;
+
+    return 0;
+}
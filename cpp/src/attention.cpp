@@ -0,0 +1,241 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <atomic>
+#include <cmath>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+#include <cstring>
+#include <cstdlib>
+#include <cstdint>
+#include <limits>
+
+#include "bench_measure.h"
+
+const size_t DEFAULT_SEQ_LEN = 2048;
+const size_t HEADS = 16;
+const size_t HEAD_DIM = 64;
+const size_t QUERY_BLOCK = 64;  // query rows per parallel task
+const size_t KEY_TILE = 256;    // key columns per tile; a 64x256 K^T tile stays cache-resident
+const size_t ITERATIONS = 3;
+const size_t CHECK_SEQ_LEN = 256;
+const size_t CHECK_HEADS = 2;
+const float TOLERANCE = 1e-4f;
+const size_t NUM_THREADS = 8;
+
+float next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return static_cast<float>(seed >> 40) / 8388608.0f - 1.0f;
+}
+
+// Q, K and V laid out [head][position][dim]
+struct Inputs {
+    size_t seq_len;
+    size_t heads;
+    std::vector<float> q, k, v;
+
+    Inputs(size_t seq_len, size_t heads) : seq_len(seq_len), heads(heads) {
+        uint64_t seed = 42;
+        size_t len = heads * seq_len * HEAD_DIM;
+        for (auto* tensor : {&q, &k, &v}) {
+            tensor->resize(len);
+            for (auto& x : *tensor) x = next_random(seed);
+        }
+    }
+};
+
+// Runs f(task) for task in [0, tasks) on NUM_THREADS threads pulling from a shared counter
+template <typename F>
+void parallel_for(size_t tasks, F f) {
+    std::atomic<size_t> next{0};
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&]() {
+            for (size_t task = next++; task < tasks; task = next++) {
+                f(task);
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+}
+
+// Per-head K^T ([dim][position]) so both GEMMs run as contiguous axpys
+std::vector<float> transpose_keys(const Inputs& inputs) {
+    size_t l = inputs.seq_len;
+    std::vector<float> kt(inputs.k.size());
+    parallel_for(inputs.heads, [&](size_t h) {
+        const float* k = &inputs.k[h * l * HEAD_DIM];
+        float* t = &kt[h * l * HEAD_DIM];
+        for (size_t pos = 0; pos < l; pos++) {
+            for (size_t d = 0; d < HEAD_DIM; d++) {
+                t[d * l + pos] = k[pos * HEAD_DIM + d];
+            }
+        }
+    });
+    return kt;
+}
+
+// One task: softmax(Q_blk K^T / sqrt(d)) V for QUERY_BLOCK rows of one head
+void attention_block(const float* q, const float* kt, const float* v, size_t seq_len, float* out, float* scores) {
+    const float scale = 1.0f / std::sqrt(static_cast<float>(HEAD_DIM));
+
+    // S = Q K^T, tiled over keys so the K^T tile is reused by every query row
+    std::fill(scores, scores + QUERY_BLOCK * seq_len, 0.0f);
+    for (size_t j0 = 0; j0 < seq_len; j0 += KEY_TILE) {
+        size_t j1 = std::min(j0 + KEY_TILE, seq_len);
+        for (size_t i = 0; i < QUERY_BLOCK; i++) {
+            float* s = &scores[i * seq_len];
+            for (size_t d = 0; d < HEAD_DIM; d++) {
+                float qd = q[i * HEAD_DIM + d] * scale;
+                const float* k = &kt[d * seq_len];
+                for (size_t j = j0; j < j1; j++) {
+                    s[j] = std::fma(qd, k[j], s[j]);
+                }
+            }
+        }
+    }
+
+    // Numerically stable row softmax
+    for (size_t i = 0; i < QUERY_BLOCK; i++) {
+        float* row = &scores[i * seq_len];
+        float max = -std::numeric_limits<float>::infinity();
+        for (size_t j = 0; j < seq_len; j++) max = std::max(max, row[j]);
+        float sum = 0.0f;
+        for (size_t j = 0; j < seq_len; j++) {
+            row[j] = std::exp(row[j] - max);
+            sum += row[j];
+        }
+        float inv = 1.0f / sum;
+        for (size_t j = 0; j < seq_len; j++) row[j] *= inv;
+    }
+
+    // O = P V, tiled over keys so the V tile is reused by every query row
+    std::fill(out, out + QUERY_BLOCK * HEAD_DIM, 0.0f);
+    for (size_t j0 = 0; j0 < seq_len; j0 += KEY_TILE) {
+        size_t j1 = std::min(j0 + KEY_TILE, seq_len);
+        for (size_t i = 0; i < QUERY_BLOCK; i++) {
+            float* o = &out[i * HEAD_DIM];
+            for (size_t j = j0; j < j1; j++) {
+                float p = scores[i * seq_len + j];
+                const float* vj = &v[j * HEAD_DIM];
+                for (size_t d = 0; d < HEAD_DIM; d++) {
+                    o[d] = std::fma(p, vj[d], o[d]);
+                }
+            }
+        }
+    }
+}
+
+std::vector<float> attention(const Inputs& inputs) {
+    size_t l = inputs.seq_len;
+    std::vector<float> kt = transpose_keys(inputs);
+    std::vector<float> out(inputs.q.size());
+    size_t blocks_per_head = l / QUERY_BLOCK;
+
+    // Blocks of the head-major output map one-to-one onto (head, query block) tasks
+    parallel_for(inputs.heads * blocks_per_head, [&](size_t task) {
+        thread_local std::vector<float> scores;
+        scores.resize(QUERY_BLOCK * l);
+        size_t head = task / blocks_per_head;
+        size_t row0 = head * l + (task % blocks_per_head) * QUERY_BLOCK;
+        size_t head_offset = head * l * HEAD_DIM;
+        attention_block(&inputs.q[row0 * HEAD_DIM], &kt[head_offset], &inputs.v[head_offset], l,
+                        &out[row0 * HEAD_DIM], scores.data());
+    });
+    return out;
+}
+
+// Textbook attention in double, one query at a time
+std::vector<float> reference_attention(const Inputs& inputs) {
+    size_t l = inputs.seq_len;
+    double scale = 1.0 / std::sqrt(static_cast<double>(HEAD_DIM));
+    std::vector<float> out(inputs.q.size());
+    std::vector<double> weights(l);
+    for (size_t h = 0; h < inputs.heads; h++) {
+        size_t base = h * l * HEAD_DIM;
+        for (size_t i = 0; i < l; i++) {
+            double max = -std::numeric_limits<double>::infinity();
+            for (size_t j = 0; j < l; j++) {
+                double s = 0.0;
+                for (size_t d = 0; d < HEAD_DIM; d++) {
+                    s += static_cast<double>(inputs.q[base + i * HEAD_DIM + d]) * inputs.k[base + j * HEAD_DIM + d];
+                }
+                weights[j] = s * scale;
+                max = std::max(max, weights[j]);
+            }
+            double sum = 0.0;
+            for (auto& w : weights) {
+                w = std::exp(w - max);
+                sum += w;
+            }
+            for (size_t d = 0; d < HEAD_DIM; d++) {
+                double o = 0.0;
+                for (size_t j = 0; j < l; j++) o += weights[j] * inputs.v[base + j * HEAD_DIM + d];
+                out[base + i * HEAD_DIM + d] = static_cast<float>(o / sum);
+            }
+        }
+    }
+    return out;
+}
+
+size_t parse_seq_len(int argc, char** argv) {
+    for (int i = 1; i + 1 < argc; i++) {
+        if (std::strcmp(argv[i], "--seq-len") == 0) {
+            long n = std::atol(argv[i + 1]);
+            if (n <= 0 || n % QUERY_BLOCK != 0) {
+                std::cerr << "--seq-len expects a positive multiple of 64" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<size_t>(n);
+        }
+    }
+    return DEFAULT_SEQ_LEN;
+}
+
+int main(int argc, char** argv) {
+    auto measure = bench_measure_from_env();
+    size_t seq_len = parse_seq_len(argc, argv);
+
+    // Cross-check against the double reference at a small size (doubles as warm-up)
+    Inputs check(CHECK_SEQ_LEN, CHECK_HEADS);
+    std::vector<float> fast = attention(check);
+    std::vector<float> reference = reference_attention(check);
+    float max_error = 0.0f;
+    for (size_t i = 0; i < fast.size(); i++) {
+        max_error = std::max(max_error, std::abs(fast[i] - reference[i]));
+    }
+    if (!(max_error < TOLERANCE)) {
+        std::cerr << "Attention differs from reference by " << max_error << std::endl;
+        return 1;
+    }
+
+    // Benchmark
+    Inputs inputs(seq_len, HEADS);
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    std::vector<float> out;
+    for (size_t iter = 0; iter < ITERATIONS; iter++) {
+        out = attention(inputs);
+    }
+    measure->stop();
+    auto end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> duration = end - start;
+
+    double flops = 4.0 * static_cast<double>(seq_len * seq_len * HEAD_DIM * HEADS) * ITERATIONS;
+    double checksum = 0.0;
+    for (size_t i = 0; i < out.size(); i += 97) {
+        checksum += out[i];
+    }
+
+    measure->report();
+    std::cerr << "Sequence " << seq_len << " x " << HEADS << " heads x " << HEAD_DIM << " dims, " << ITERATIONS
+              << " iterations: " << std::fixed << std::setprecision(2) << flops / duration.count() / 1e9 << " GFLOP/s" << std::endl;
+    std::cerr << "Cross-check (" << CHECK_SEQ_LEN << " x " << CHECK_HEADS << " heads): max abs error "
+              << std::scientific << std::setprecision(2) << max_error << std::endl;
+    std::cerr << "Checksum: " << std::fixed << std::setprecision(6) << checksum << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,432 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <algorithm>
+#include <cmath>
+#include <utility>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstring>
+#include <cstdlib>
+#include <limits>
+
+const size_t NUM_BODIES = 1'000'000;
+const size_t STEPS = 3;
+const double THETA = 0.5; // Opening angle: cells smaller than THETA x distance are treated as one mass
+const double SOFTENING2 = 1e-6;
+const double DT = 1e-3;
+const size_t LEAF_SIZE = 16;
+const uint32_t MORTON_BITS = 21; // Per axis; 63-bit codes
+const size_t ERROR_SAMPLES = 100;
+const size_t NUM_THREADS = 8;
+
+enum class Precision {
+    F64,
+    F32,
+};
+
+// Positions, velocities, tree moments and force arithmetic all use T; bodies are generated and
+// checked in double
+template <typename T>
+struct Vec3 {
+    T x, y, z;
+
+    Vec3(T x = 0, T y = 0, T z = 0) : x(x), y(y), z(z) {}
+
+    template <typename U>
+    Vec3<U> convert() const {
+        return Vec3<U>(static_cast<U>(x), static_cast<U>(y), static_cast<U>(z));
+    }
+
+    T dot(const Vec3& other) const {
+        return x * other.x + y * other.y + z * other.z;
+    }
+
+    Vec3 operator-(const Vec3& other) const {
+        return Vec3(x - other.x, y - other.y, z - other.z);
+    }
+
+    Vec3 operator+(const Vec3& other) const {
+        return Vec3(x + other.x, y + other.y, z + other.z);
+    }
+
+    Vec3 operator*(T scalar) const {
+        return Vec3(x * scalar, y * scalar, z * scalar);
+    }
+
+    Vec3 min(const Vec3& other) const {
+        return Vec3(std::min(x, other.x), std::min(y, other.y), std::min(z, other.z));
+    }
+
+    Vec3 max(const Vec3& other) const {
+        return Vec3(std::max(x, other.x), std::max(y, other.y), std::max(z, other.z));
+    }
+};
+
+template <typename T>
+struct Body {
+    Vec3<T> position;
+    Vec3<T> velocity;
+};
+
+// Octree cell: a leaf owns a run of the Morton-sorted bodies, an internal node owns up to 8 children
+template <typename T>
+struct Node {
+    Vec3<T> center_of_mass;
+    T mass = 0;
+    T size = 0;
+    size_t first = 0;
+    size_t count = 0;
+    std::vector<Node> children;
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+double next_f64(uint64_t& seed) {
+    return static_cast<double>(next_random(seed)) / static_cast<double>(1ULL << 31);
+}
+
+// Runs fn(start, end) over NUM_THREADS contiguous slices of [0, count)
+template <typename F>
+void parallel_for(size_t count, F fn) {
+    std::vector<std::thread> threads;
+    size_t per_thread = count / NUM_THREADS;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        size_t start = t * per_thread;
+        size_t end = (t == NUM_THREADS - 1) ? count : (t + 1) * per_thread;
+        threads.emplace_back(fn, start, end);
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+}
+
+// Uniform in the unit ball with a slow rigid rotation about the z axis
+std::vector<Body<double>> generate_bodies() {
+    uint64_t seed = 6174;
+    std::vector<Body<double>> bodies;
+    bodies.reserve(NUM_BODIES);
+    while (bodies.size() < NUM_BODIES) {
+        double x = next_f64(seed) * 2.0 - 1.0;
+        double y = next_f64(seed) * 2.0 - 1.0;
+        double z = next_f64(seed) * 2.0 - 1.0;
+        if (x * x + y * y + z * z <= 1.0) {
+            bodies.push_back({Vec3<double>(x, y, z), Vec3<double>(-0.5 * y, 0.5 * x, 0.0)});
+        }
+    }
+    return bodies;
+}
+
+// Spreads the low 21 bits of v two positions apart
+uint64_t spread_bits(uint64_t v) {
+    uint64_t x = v & 0x1FFFFF;
+    x = (x | (x << 32)) & 0x1F00000000FFFFULL;
+    x = (x | (x << 16)) & 0x1F0000FF0000FFULL;
+    x = (x | (x << 8)) & 0x100F00F00F00F00FULL;
+    x = (x | (x << 4)) & 0x10C30C30C30C30C3ULL;
+    x = (x | (x << 2)) & 0x1249249249249249ULL;
+    return x;
+}
+
+// Quantized in double whatever T is; the codes only order the bodies
+template <typename T>
+uint64_t morton_code(const Vec3<T>& p, const Vec3<T>& origin, double scale) {
+    const double max_cell = static_cast<double>((1ULL << MORTON_BITS) - 1);
+    auto q = [&](T v, T o) {
+        return static_cast<uint64_t>(std::clamp((static_cast<double>(v) - static_cast<double>(o)) * scale, 0.0, max_cell));
+    };
+    return (spread_bits(q(p.x, origin.x)) << 2) | (spread_bits(q(p.y, origin.y)) << 1) | spread_bits(q(p.z, origin.z));
+}
+
+template <typename T>
+Node<T> build_node(const std::vector<Body<T>>& bodies, const std::vector<uint64_t>& codes,
+                   size_t first, size_t count, uint32_t depth, T size) {
+    Node<T> node;
+    node.size = size;
+    node.first = first;
+    node.count = count;
+    if (count <= LEAF_SIZE || depth == MORTON_BITS) {
+        Vec3<T> sum(0, 0, 0);
+        for (size_t i = first; i < first + count; i++) {
+            sum = sum + bodies[i].position;
+        }
+        node.center_of_mass = sum * static_cast<T>(1.0 / count);
+        node.mass = static_cast<T>(static_cast<double>(count) / NUM_BODIES);
+        return node;
+    }
+
+    // Codes are sorted, so each octant at this depth is a contiguous run
+    uint32_t shift = 3 * (MORTON_BITS - 1 - depth);
+    size_t bounds[9];
+    bounds[0] = first;
+    for (uint64_t octant = 1; octant < 8; octant++) {
+        bounds[octant] = std::partition_point(codes.begin() + first, codes.begin() + first + count,
+                                              [&](uint64_t c) { return ((c >> shift) & 7) < octant; })
+                       - codes.begin();
+    }
+    bounds[8] = first + count;
+    for (size_t o = 0; o < 8; o++) {
+        if (bounds[o + 1] > bounds[o]) {
+            node.children.emplace_back();
+        }
+    }
+
+    // The root's octants are built on separate threads
+    std::vector<std::thread> threads;
+    size_t c = 0;
+    for (size_t o = 0; o < 8; o++) {
+        if (bounds[o + 1] == bounds[o]) continue;
+        Node<T>* child = &node.children[c++];
+        size_t lo = bounds[o], len = bounds[o + 1] - bounds[o];
+        if (depth == 0) {
+            threads.emplace_back([&, child, lo, len]() {
+                *child = build_node(bodies, codes, lo, len, depth + 1, size / T(2));
+            });
+        } else {
+            *child = build_node(bodies, codes, lo, len, depth + 1, size / T(2));
+        }
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+
+    Vec3<T> weighted(0, 0, 0);
+    for (const Node<T>& child : node.children) {
+        node.mass += child.mass;
+        weighted = weighted + child.center_of_mass * child.mass;
+    }
+    node.center_of_mass = weighted * (T(1) / node.mass);
+    return node;
+}
+
+// Sorts the bodies along a Morton curve and builds the octree over them
+template <typename T>
+Node<T> build_tree(std::vector<Body<T>>& bodies) {
+    const T max = std::numeric_limits<T>::max();
+    Vec3<T> lo(max, max, max), hi(-max, -max, -max);
+    for (const Body<T>& b : bodies) {
+        lo = lo.min(b.position);
+        hi = hi.max(b.position);
+    }
+    T extent = std::max(std::max(hi.x - lo.x, hi.y - lo.y), hi.z - lo.z) * static_cast<T>(1.0 + 1e-9);
+    double scale = static_cast<double>(1ULL << MORTON_BITS) / static_cast<double>(extent);
+
+    size_t n = bodies.size();
+    std::vector<std::pair<uint64_t, uint32_t>> keys(n);
+    parallel_for(n, [&](size_t start, size_t end) {
+        for (size_t i = start; i < end; i++) {
+            keys[i] = {morton_code(bodies[i].position, lo, scale), static_cast<uint32_t>(i)};
+        }
+    });
+
+    // Sort 8 slices in parallel, then merge them pairwise
+    std::vector<size_t> cuts(NUM_THREADS + 1);
+    for (size_t t = 0; t <= NUM_THREADS; t++) {
+        cuts[t] = (t == NUM_THREADS) ? n : t * (n / NUM_THREADS);
+    }
+    parallel_for(n, [&](size_t start, size_t end) {
+        std::sort(keys.begin() + start, keys.begin() + end);
+    });
+    for (size_t width = 1; width < NUM_THREADS; width *= 2) {
+        std::vector<std::thread> threads;
+        for (size_t t = 0; t + width < NUM_THREADS; t += 2 * width) {
+            size_t a = cuts[t], m = cuts[t + width], b = cuts[std::min(t + 2 * width, NUM_THREADS)];
+            threads.emplace_back([&keys, a, m, b]() {
+                std::inplace_merge(keys.begin() + a, keys.begin() + m, keys.begin() + b);
+            });
+        }
+        for (auto& thread : threads) {
+            thread.join();
+        }
+    }
+
+    std::vector<Body<T>> sorted(n);
+    std::vector<uint64_t> codes(n);
+    parallel_for(n, [&](size_t start, size_t end) {
+        for (size_t i = start; i < end; i++) {
+            sorted[i] = bodies[keys[i].second];
+            codes[i] = keys[i].first;
+        }
+    });
+    bodies = std::move(sorted);
+    return build_node(bodies, codes, 0, n, 0, extent);
+}
+
+template <typename T>
+void accumulate(const Node<T>& node, const Vec3<T>& p, const std::vector<Body<T>>& bodies, Vec3<T>& acc) {
+    const T softening2 = static_cast<T>(SOFTENING2);
+    Vec3<T> d = node.center_of_mass - p;
+    T dist2 = d.dot(d) + softening2;
+    if (node.children.empty()) {
+        // Leaves are summed directly; the body's own term vanishes because d = 0
+        T m = static_cast<T>(1.0 / NUM_BODIES);
+        for (size_t i = node.first; i < node.first + node.count; i++) {
+            Vec3<T> dj = bodies[i].position - p;
+            T r2 = dj.dot(dj) + softening2;
+            acc = acc + dj * (m / (r2 * std::sqrt(r2)));
+        }
+    } else if (node.size * node.size < static_cast<T>(THETA) * static_cast<T>(THETA) * dist2) {
+        acc = acc + d * (node.mass / (dist2 * std::sqrt(dist2)));
+    } else {
+        for (const Node<T>& child : node.children) {
+            accumulate(child, p, bodies, acc);
+        }
+    }
+}
+
+template <typename T>
+Vec3<T> acceleration(const Node<T>& root, const Vec3<T>& p, const std::vector<Body<T>>& bodies) {
+    Vec3<T> acc(0, 0, 0);
+    accumulate(root, p, bodies, acc);
+    return acc;
+}
+
+// Exact O(n²)-style sum for one body in double, used to measure the approximation error
+template <typename T>
+Vec3<double> direct_acceleration(const Vec3<double>& p, const std::vector<Body<T>>& bodies) {
+    double m = 1.0 / NUM_BODIES;
+    Vec3<double> acc(0.0, 0.0, 0.0);
+    for (const Body<T>& b : bodies) {
+        Vec3<double> d = b.position.template convert<double>() - p;
+        double r2 = d.dot(d) + SOFTENING2;
+        acc = acc + d * (m / (r2 * std::sqrt(r2)));
+    }
+    return acc;
+}
+
+template <typename T>
+std::pair<std::chrono::duration<double>, std::chrono::duration<double>> step(std::vector<Body<T>>& bodies) {
+    auto start = std::chrono::high_resolution_clock::now();
+    Node<T> root = build_tree(bodies);
+    auto build_end = std::chrono::high_resolution_clock::now();
+
+    auto force_start = std::chrono::high_resolution_clock::now();
+    std::vector<Vec3<T>> accelerations(bodies.size());
+    parallel_for(bodies.size(), [&](size_t begin, size_t end) {
+        for (size_t i = begin; i < end; i++) {
+            accelerations[i] = acceleration(root, bodies[i].position, bodies);
+        }
+    });
+    const T dt = static_cast<T>(DT);
+    parallel_for(bodies.size(), [&](size_t begin, size_t end) {
+        for (size_t i = begin; i < end; i++) {
+            bodies[i].velocity = bodies[i].velocity + accelerations[i] * dt;
+            bodies[i].position = bodies[i].position + bodies[i].velocity * dt;
+        }
+    });
+    auto force_end = std::chrono::high_resolution_clock::now();
+    return {build_end - start, force_end - force_start};
+}
+
+template <typename T>
+std::vector<Body<double>> widen(const std::vector<Body<T>>& bodies) {
+    std::vector<Body<double>> wide(bodies.size());
+    for (size_t i = 0; i < bodies.size(); i++) {
+        wide[i] = {bodies[i].position.template convert<double>(), bodies[i].velocity.template convert<double>()};
+    }
+    return wide;
+}
+
+// Mean relative error of the T tree walk against the double direct sum (theta and rounding together)
+// and against a double tree walk over the same bodies (rounding alone, zero in f64)
+template <typename T>
+std::pair<double, double> relative_error(std::vector<Body<T>> bodies) {
+    Node<T> root = build_tree(bodies);
+    std::vector<Body<double>> wide = widen(bodies);
+    Node<double> wide_root = build_tree(wide);
+    size_t stride = bodies.size() / ERROR_SAMPLES;
+    auto relative = [](const Vec3<double>& a, const Vec3<double>& b) {
+        Vec3<double> err = a - b;
+        return std::sqrt(err.dot(err) / b.dot(b));
+    };
+    double vs_direct = 0.0, vs_tree = 0.0;
+    for (size_t s = 0; s < ERROR_SAMPLES; s++) {
+        Vec3<T> p = bodies[s * stride].position;
+        Vec3<double> approx = acceleration(root, p, bodies).template convert<double>();
+        Vec3<double> exact = direct_acceleration(p.template convert<double>(), bodies);
+        Vec3<double> wide_approx = acceleration(wide_root, p.template convert<double>(), wide);
+        vs_direct += relative(approx, exact);
+        vs_tree += relative(approx, wide_approx);
+    }
+    return {vs_direct / ERROR_SAMPLES, vs_tree / ERROR_SAMPLES};
+}
+
+struct Run {
+    std::chrono::duration<double> build{0}, force{0};
+    std::vector<Body<double>> state;
+    std::pair<double, double> error;
+};
+
+// Converts the bodies to T outside the timed region; the final state comes back widened to double
+template <typename T>
+Run run(const std::vector<Body<double>>& initial) {
+    std::vector<Body<T>> bodies(initial.size());
+    for (size_t i = 0; i < initial.size(); i++) {
+        bodies[i] = {initial[i].position.convert<T>(), initial[i].velocity.convert<T>()};
+    }
+
+    // Warm-up
+    std::vector<Body<T>> warmup(bodies.begin(), bodies.begin() + NUM_BODIES / 100);
+    step(warmup);
+
+    // Benchmark
+    Run result;
+    std::vector<Body<T>> state = bodies;
+    for (size_t s = 0; s < STEPS; s++) {
+        auto [build, force] = step(state);
+        result.build += build;
+        result.force += force;
+    }
+
+    result.state = widen(state);
+    result.error = relative_error(bodies);
+    return result;
+}
+
+Precision parse_precision(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--precision") == 0) {
+            const char* value = i + 1 < argc ? argv[i + 1] : "";
+            if (std::strcmp(value, "f64") == 0) return Precision::F64;
+            if (std::strcmp(value, "f32") == 0) return Precision::F32;
+            std::cerr << "--precision expects f64 or f32" << std::endl;
+            std::exit(1);
+        }
+    }
+    return Precision::F64;
+}
+
+int main(int argc, char** argv) {
+    Precision precision = parse_precision(argc, argv);
+    std::vector<Body<double>> bodies = generate_bodies();
+    Run result = precision == Precision::F64 ? run<double>(bodies) : run<float>(bodies);
+    std::chrono::duration<double> build_total = result.build, force_total = result.force;
+    std::chrono::duration<double> total_duration = build_total + force_total;
+
+    // Checksum
+    Vec3<double> momentum(0.0, 0.0, 0.0);
+    double spread = 0.0;
+    for (const Body<double>& b : result.state) {
+        momentum = momentum + b.velocity;
+        spread += b.position.dot(b.position);
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << "Precision: " << (precision == Precision::F64 ? "f64" : "f32") << std::endl;
+    std::cerr << std::fixed << std::setprecision(3) << "Tree build: " << build_total.count() << "s, forces + integration: "
+              << force_total.count() << "s over " << STEPS << " steps" << std::endl;
+    std::cerr << std::defaultfloat << std::setprecision(3) << "Mean relative force error vs f64 direct sum (theta = "
+              << THETA << "): " << std::scientific << std::setprecision(2) << result.error.first << std::endl;
+    if (precision != Precision::F64) {
+        std::cerr << "Mean relative force error vs f64 tree walk: " << result.error.second << std::endl;
+    }
+    std::cerr << std::fixed << std::setprecision(9) << "Checksum: " << spread << std::scientific
+              << std::setprecision(3) << " (momentum: " << momentum.x / NUM_BODIES << ", "
+              << momentum.y / NUM_BODIES << ", " << momentum.z / NUM_BODIES << ")" << std::endl;
+
+    return 0;
+}
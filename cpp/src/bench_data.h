@@ -0,0 +1,126 @@
+// Generated benchmark inputs, cached across runs and shared with the Rust build; the same key
+// and layout as rust/bench_data:
+//
+//     <cache>/<name>/<bytes>-<seed>-v<version>/data
+//
+// so whichever language runs first writes a dataset and the other reads it byte for byte. The
+// cache is BENCH_DATA_DIR, or rust-vs-cpp-bench under the user's cache directory. The data is
+// written under a temporary name and renamed into place before the manifest (size and FNV-1a
+// digest) is, so a file without a matching manifest is generated again.
+#pragma once
+
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <filesystem>
+#include <fstream>
+#include <functional>
+#include <stdexcept>
+#include <string>
+#include <vector>
+#include <unistd.h>
+
+struct Dataset {
+    std::filesystem::path path;
+    uint64_t bytes = 0;
+    uint64_t digest = 0;
+    bool generated = false; // false when an earlier run in either language wrote it
+};
+
+inline std::filesystem::path bench_data_dir() {
+    if (const char* dir = std::getenv("BENCH_DATA_DIR")) {
+        return dir;
+    }
+    std::filesystem::path base;
+    if (const char* xdg = std::getenv("XDG_CACHE_HOME")) {
+        base = xdg;
+    } else if (const char* home = std::getenv("HOME")) {
+        base = std::filesystem::path(home) / ".cache";
+    } else {
+        base = std::filesystem::temp_directory_path();
+    }
+    return base / "rust-vs-cpp-bench";
+}
+
+inline std::string bench_data_key(const std::string& name, uint64_t target_bytes, uint64_t seed, uint32_t version) {
+    char suffix[64];
+    std::snprintf(suffix, sizeof(suffix), "%llu-%016llx-v%u", (unsigned long long)target_bytes,
+                  (unsigned long long)seed, version);
+    return name + "/" + suffix;
+}
+
+inline uint64_t bench_data_fnv1a(const std::filesystem::path& path) {
+    std::ifstream in(path, std::ios::binary);
+    std::vector<char> buffer(1 << 20);
+    uint64_t hash = 0xcbf29ce484222325ull;
+    while (in.read(buffer.data(), buffer.size()) || in.gcount() > 0) {
+        for (std::streamsize i = 0; i < in.gcount(); i++) {
+            hash = (hash ^ (uint8_t)buffer[i]) * 0x100000001b3ull;
+        }
+    }
+    return hash;
+}
+
+// "bytes 2147483700\nfnv1a64 0123456789abcdef\n"
+inline bool bench_data_read_manifest(const std::filesystem::path& path, uint64_t& bytes, uint64_t& digest) {
+    std::ifstream in(path);
+    std::string field, value;
+    bool have_bytes = false, have_digest = false;
+    while (in >> field >> value) {
+        if (field == "bytes") {
+            bytes = std::stoull(value);
+            have_bytes = true;
+        } else if (field == "fnv1a64") {
+            digest = std::stoull(value, nullptr, 16);
+            have_digest = true;
+        }
+    }
+    return have_bytes && have_digest;
+}
+
+// The dataset under its key in the cache, calling generate(path) to write it first if no
+// earlier run did
+inline Dataset bench_data_cached(const std::string& name, uint64_t target_bytes, uint64_t seed, uint32_t version,
+                                 const std::function<void(const std::filesystem::path&)>& generate) {
+    namespace fs = std::filesystem;
+    fs::path dir = bench_data_dir() / bench_data_key(name, target_bytes, seed, version);
+    Dataset dataset;
+    dataset.path = dir / "data";
+    fs::path manifest = dir / "manifest";
+    std::error_code error;
+    if (bench_data_read_manifest(manifest, dataset.bytes, dataset.digest) &&
+        fs::file_size(dataset.path, error) == dataset.bytes && !error) {
+        return dataset;
+    }
+
+    fs::create_directories(dir);
+    // Per-process names, so two runs generating at once each rename a complete file
+    std::string pid = std::to_string(getpid());
+    fs::path partial = dir / ("data.tmp-" + pid);
+    generate(partial);
+    dataset.digest = bench_data_fnv1a(partial);
+    dataset.bytes = fs::file_size(partial);
+    fs::rename(partial, dataset.path);
+    fs::path partial_manifest = dir / ("manifest.tmp-" + pid);
+    {
+        std::ofstream out(partial_manifest);
+        char text[64];
+        std::snprintf(text, sizeof(text), "bytes %llu\nfnv1a64 %016llx\n", (unsigned long long)dataset.bytes,
+                      (unsigned long long)dataset.digest);
+        out << text;
+        if (!out) {
+            throw std::runtime_error("cannot write " + partial_manifest.string());
+        }
+    }
+    fs::rename(partial_manifest, manifest);
+    dataset.generated = true;
+    return dataset;
+}
+
+// The stderr line, so runs can be checked to have read the same bytes
+inline std::string bench_data_describe(const Dataset& dataset) {
+    char text[64];
+    std::snprintf(text, sizeof(text), " (%.2f GB, FNV-1a %016llx)", dataset.bytes / 1e9,
+                  (unsigned long long)dataset.digest);
+    return std::string("Input: ") + (dataset.generated ? "generated " : "cached ") + dataset.path.string() + text;
+}
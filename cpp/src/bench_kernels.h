@@ -0,0 +1,31 @@
+// Single-threaded benchmark kernels with C linkage, written once in Rust (rust/src/kernels.rs,
+// built as the bench_kernels cdylib) and once in C++ (cpp/src/kernels.cpp). Each pair does the
+// same work and must return the same result, so a harness can time both in one process.
+#pragma once
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Escape iterations summed over a width x height grid of the zoom-1 mandelbrot view
+uint64_t rust_mandelbrot(uint32_t width, uint32_t height, uint32_t max_iter);
+uint64_t cpp_mandelbrot(uint32_t width, uint32_t height, uint32_t max_iter);
+
+// pi(limit), by prime_sieve's segmented odd-only sieve
+uint64_t rust_count_primes(uint64_t limit);
+uint64_t cpp_count_primes(uint64_t limit);
+
+// In-place ascending sort: parallel_quicksort's partitioning on one thread
+void rust_sort_i32(int32_t* data, size_t len);
+void cpp_sort_i32(int32_t* data, size_t len);
+
+// SHA-256 of len bytes into digest[0..32], with each language's sha256 implementation
+void rust_sha256(const uint8_t* data, size_t len, uint8_t* digest);
+void cpp_sha256(const uint8_t* data, size_t len, uint8_t* digest);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,196 @@
+// What the number on stdout measures, chosen per run through BENCH_MEASURE; the same measurers
+// and stderr line as rust/bench_measure:
+//
+//     BENCH_MEASURE=wall          elapsed time, in seconds
+//     BENCH_MEASURE=cpu           user + system time of every thread in the process, in seconds
+//     BENCH_MEASURE=instructions  retired user-space instructions (Linux perf_event)
+//     BENCH_MEASURE=gpu           time between the GPU's own timestamps around each pass, in seconds
+//
+// A benchmark brackets each timed region with start() and stop(), which add up over any number of
+// regions, and report() prints the total on stdout and "Measure: <name> (<unit>)" on stderr.
+// bench_measure_from_env() must run first thing in main, before any thread exists, so the
+// instruction counter follows them all; only benchmarks that write timestamp queries pass gpu.
+#pragma once
+
+#include <cerrno>
+#include <chrono>
+#include <cstdint>
+#include <cstdlib>
+#include <cstring>
+#include <iomanip>
+#include <iostream>
+#include <memory>
+#include <stdexcept>
+#include <string>
+#include <sys/resource.h>
+#include <unistd.h>
+#ifdef __linux__
+#include <linux/perf_event.h>
+#include <sys/syscall.h>
+#endif
+
+class Measurer {
+public:
+    virtual ~Measurer() = default;
+    virtual const char* name() const = 0;
+    virtual const char* unit() const = 0;
+    virtual void start() = 0;
+    virtual void stop() = 0; // adds the region since start() to the total
+    virtual double total() const = 0;
+
+    // Whether the benchmark should write GPU timestamps and pass what they span to record_gpu()
+    virtual bool wants_gpu() const { return false; }
+    virtual void record_gpu(double) {}
+
+    virtual void report() const {
+        std::cout << std::fixed << std::setprecision(6) << total() << std::endl;
+        std::cerr << "Measure: " << name() << " (" << unit() << ")" << std::endl;
+    }
+};
+
+class WallClock : public Measurer {
+    std::chrono::high_resolution_clock::time_point started;
+    bool running = false;
+    double sum = 0.0;
+
+public:
+    const char* name() const override { return "wall"; }
+    const char* unit() const override { return "s"; }
+    void start() override {
+        started = std::chrono::high_resolution_clock::now();
+        running = true;
+    }
+    void stop() override {
+        if (!running) return;
+        std::chrono::duration<double> span = std::chrono::high_resolution_clock::now() - started;
+        sum += span.count();
+        running = false;
+    }
+    double total() const override { return sum; }
+};
+
+// User plus system time of the whole process, so a parallel region counts every thread's share
+inline double bench_measure_cpu_seconds() {
+    rusage usage = {};
+    getrusage(RUSAGE_SELF, &usage);
+    auto seconds = [](timeval t) { return t.tv_sec + t.tv_usec / 1e6; };
+    return seconds(usage.ru_utime) + seconds(usage.ru_stime);
+}
+
+class CpuTime : public Measurer {
+    double started = 0.0;
+    bool running = false;
+    double sum = 0.0;
+
+public:
+    const char* name() const override { return "cpu"; }
+    const char* unit() const override { return "s"; }
+    void start() override {
+        started = bench_measure_cpu_seconds();
+        running = true;
+    }
+    void stop() override {
+        if (!running) return;
+        sum += bench_measure_cpu_seconds() - started;
+        running = false;
+    }
+    double total() const override { return sum; }
+};
+
+#ifdef __linux__
+class Instructions : public Measurer {
+    int fd = -1;
+    uint64_t started = 0;
+    bool running = false;
+    uint64_t sum = 0;
+
+    // The inherited counters of live threads are summed in by the kernel on every read
+    uint64_t read_counter() const {
+        uint64_t count = 0;
+        if (read(fd, &count, sizeof(count)) != sizeof(count)) {
+            throw std::runtime_error("reading the instruction counter failed");
+        }
+        return count;
+    }
+
+public:
+    // Counts from here on for this thread and every thread it creates afterwards (inherit), in
+    // user space only, which perf_event_paranoid up to 2 allows without privileges
+    Instructions() {
+        perf_event_attr attr = {};
+        attr.type = PERF_TYPE_HARDWARE;
+        attr.size = sizeof(attr);
+        attr.config = PERF_COUNT_HW_INSTRUCTIONS;
+        attr.inherit = 1;
+        attr.exclude_kernel = 1;
+        attr.exclude_hv = 1;
+        fd = static_cast<int>(syscall(SYS_perf_event_open, &attr, 0, -1, -1, PERF_FLAG_FD_CLOEXEC));
+        if (fd < 0) {
+            throw std::runtime_error(std::string("perf_event_open: ") + std::strerror(errno) +
+                                     " (no hardware counters, as in many VMs, or kernel.perf_event_paranoid above 2)");
+        }
+    }
+    ~Instructions() override { close(fd); }
+    Instructions(const Instructions&) = delete;
+    Instructions& operator=(const Instructions&) = delete;
+
+    const char* name() const override { return "instructions"; }
+    const char* unit() const override { return "instructions"; }
+    void start() override {
+        started = read_counter();
+        running = true;
+    }
+    void stop() override {
+        if (!running) return;
+        sum += read_counter() - started;
+        running = false;
+    }
+    double total() const override { return static_cast<double>(sum); }
+};
+#endif
+
+class GpuTimestamps : public Measurer {
+    double sum = 0.0;
+    size_t passes = 0;
+
+public:
+    const char* name() const override { return "gpu"; }
+    const char* unit() const override { return "s"; }
+    // The CPU side of the region does not count; only the passes inside it do
+    void start() override {}
+    void stop() override {}
+    double total() const override { return sum; }
+    bool wants_gpu() const override { return true; }
+    void record_gpu(double seconds) override {
+        sum += seconds;
+        passes++;
+    }
+    void report() const override {
+        if (passes == 0) {
+            throw std::runtime_error("BENCH_MEASURE=gpu, but no GPU pass was timed");
+        }
+        std::cout << std::fixed << std::setprecision(6) << sum << std::endl;
+        std::cerr << "Measure: gpu (s, " << passes << " passes)" << std::endl;
+    }
+};
+
+// The measurer BENCH_MEASURE names; "gpu" only for benchmarks that time their passes with timestamps
+inline std::unique_ptr<Measurer> bench_measure_from_env(bool gpu = false) {
+    const char* env = std::getenv("BENCH_MEASURE");
+    std::string name = env ? env : "wall";
+    if (name == "wall") return std::make_unique<WallClock>();
+    if (name == "cpu") return std::make_unique<CpuTime>();
+#ifdef __linux__
+    if (name == "instructions") {
+        try {
+            return std::make_unique<Instructions>();
+        } catch (const std::runtime_error& e) {
+            throw std::runtime_error("BENCH_MEASURE=instructions: " + std::string(e.what()));
+        }
+    }
+#endif
+    if (name == "gpu" && gpu) return std::make_unique<GpuTimestamps>();
+    if (name == "gpu") throw std::runtime_error("BENCH_MEASURE=gpu: this benchmark runs no GPU passes to time");
+    if (name == "instructions") throw std::runtime_error("BENCH_MEASURE=instructions is not available on this platform");
+    throw std::runtime_error("BENCH_MEASURE=" + name + ": unknown measure (one of wall, cpu, instructions, gpu)");
+}
@@ -0,0 +1,89 @@
+// Run-time values for the constants a scaling curve varies; the same BENCH_PARAMS variable and
+// stderr line as rust/bench_params:
+//
+//     BENCH_PARAMS=size=2048,threads=4    ->    Params: size=2048 threads=4
+//
+// A benchmark reads each parameter with its default, calls check() once it has read them all,
+// which rejects a name it never asked for, and prints report() with its other stderr lines.
+#pragma once
+
+#include <cstdlib>
+#include <stdexcept>
+#include <string>
+#include <utility>
+#include <vector>
+
+class BenchParams {
+    std::vector<std::pair<std::string, std::string>> given;
+    std::vector<std::pair<std::string, std::string>> taken;
+
+    static std::string trim(const std::string& text) {
+        size_t first = text.find_first_not_of(" \t");
+        size_t last = text.find_last_not_of(" \t");
+        return first == std::string::npos ? "" : text.substr(first, last - first + 1);
+    }
+
+public:
+    // "name=value,name=value"; an empty string overrides nothing
+    explicit BenchParams(const std::string& text) {
+        size_t begin = 0;
+        while (begin <= text.size()) {
+            size_t end = text.find(',', begin);
+            if (end == std::string::npos) end = text.size();
+            std::string item = trim(text.substr(begin, end - begin));
+            begin = end + 1;
+            if (item.empty()) continue;
+            size_t equals = item.find('=');
+            if (equals == std::string::npos) {
+                throw std::runtime_error("BENCH_PARAMS: expected name=value, got " + item);
+            }
+            given.emplace_back(trim(item.substr(0, equals)), trim(item.substr(equals + 1)));
+        }
+    }
+
+    static BenchParams from_env() {
+        const char* env = std::getenv("BENCH_PARAMS");
+        return BenchParams(env ? env : "");
+    }
+
+    // The value given for name, or fallback; the last one given wins
+    size_t size(const std::string& name, size_t fallback) {
+        size_t value = fallback;
+        for (auto it = given.rbegin(); it != given.rend(); ++it) {
+            if (it->first != name) continue;
+            char* end = nullptr;
+            unsigned long long parsed = std::strtoull(it->second.c_str(), &end, 10);
+            if (it->second.empty() || *end != '\0' || parsed == 0 || it->second[0] == '-') {
+                throw std::runtime_error("BENCH_PARAMS: " + name + "=" + it->second + " is not a positive whole number");
+            }
+            value = static_cast<size_t>(parsed);
+            break;
+        }
+        taken.emplace_back(name, std::to_string(value));
+        return value;
+    }
+
+    void check() const {
+        for (const auto& [name, value] : given) {
+            bool known = false;
+            std::string names;
+            for (const auto& [taken_name, taken_value] : taken) {
+                known = known || taken_name == name;
+                names += (names.empty() ? "" : ", ") + taken_name;
+            }
+            if (known) continue;
+            if (names.empty()) {
+                throw std::runtime_error("BENCH_PARAMS: this benchmark has no parameters, so not " + name);
+            }
+            throw std::runtime_error("BENCH_PARAMS: this benchmark has no parameter " + name + " (it takes " + names + ")");
+        }
+    }
+
+    std::string report() const {
+        std::string line = "Params:";
+        for (const auto& [name, value] : taken) {
+            line += " " + name + "=" + value;
+        }
+        return line;
+    }
+};
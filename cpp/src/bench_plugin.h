@@ -0,0 +1,33 @@
+// The plugin ABI of the plugin_call benchmark, written once in Rust (rust/src/plugin.rs, part of
+// the bench_kernels cdylib) and once in C++ (cpp/src/plugin.cpp, built as the bench_plugin shared
+// library). Both export the same symbols, so a host can load either; each host calls its own
+// language's plugin, linked in statically and loaded at runtime.
+#pragma once
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define BENCH_PLUGIN_ABI_VERSION 1
+
+// What plugin_entry hands a host: the ABI version to check, then the operations as function pointers
+typedef struct {
+    uint32_t abi_version;
+    uint64_t (*mix)(uint64_t x);
+    uint64_t (*mix_sum)(const uint64_t* values, size_t len);
+} BenchPlugin;
+
+// SplitMix64's output function: a few cycles of work, so the cost of reaching it shows
+uint64_t plugin_mix(uint64_t x);
+
+// plugin_mix summed over len values, wrapping: one call for a whole batch
+uint64_t plugin_mix_sum(const uint64_t* values, size_t len);
+
+const BenchPlugin* plugin_entry(void);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,220 @@
+#include <iostream>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <bit>
+#include <cstdint>
+#include <cstdio>
+#include <string>
+#include "cpu_features.h"
+#ifdef __BMI2__
+#include <immintrin.h>
+#endif
+
+const size_t NUM_WORDS = size_t(1) << 22; // 32MB per array
+const size_t PASSES = 10;
+const size_t WARMUP_WORDS = size_t(1) << 16;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t splitmix64(uint64_t& seed) {
+    seed += 0x9E3779B97F4A7C15ULL;
+    uint64_t z = seed;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+// Dense words to count and reverse, and sparse ones (about 16 of 64 bits set, a middlegame's
+// worth of pieces) used as PEXT/PDEP masks and as board occupancies
+void generate(size_t n, std::vector<uint64_t>& words, std::vector<uint64_t>& sparse) {
+    uint64_t seed = 2024;
+    words.resize(n);
+    sparse.resize(n);
+    for (auto& w : words) w = splitmix64(seed);
+    for (auto& s : sparse) {
+        uint64_t a = splitmix64(seed);
+        s = a & splitmix64(seed);
+    }
+}
+
+// The bit-by-bit loops behind PEXT and PDEP, used where BMI2 is not available
+[[maybe_unused]] uint64_t pext_soft(uint64_t x, uint64_t mask) {
+    uint64_t result = 0;
+    for (uint64_t bit = 1; mask != 0; bit <<= 1) {
+        if (x & mask & -mask) result |= bit;
+        mask &= mask - 1;
+    }
+    return result;
+}
+
+[[maybe_unused]] uint64_t pdep_soft(uint64_t x, uint64_t mask) {
+    uint64_t result = 0;
+    for (uint64_t bit = 1; mask != 0; bit <<= 1) {
+        if (x & bit) result |= mask & -mask;
+        mask &= mask - 1;
+    }
+    return result;
+}
+
+#ifdef __BMI2__
+const bool HARDWARE_PEXT = true;
+uint64_t pext(uint64_t x, uint64_t mask) { return _pext_u64(x, mask); }
+uint64_t pdep(uint64_t x, uint64_t mask) { return _pdep_u64(x, mask); }
+#else
+const bool HARDWARE_PEXT = false;
+uint64_t pext(uint64_t x, uint64_t mask) { return pext_soft(x, mask); }
+uint64_t pdep(uint64_t x, uint64_t mask) { return pdep_soft(x, mask); }
+#endif
+
+// What std::popcount compiles to: aarch64 has no scalar popcount, so it goes through the vector unit
+std::string popcount_path() {
+#if defined(__x86_64__) && defined(__POPCNT__)
+    return cpu_path("popcount", CpuPath::Hw, "popcnt");
+#elif defined(__aarch64__)
+    return cpu_path("popcount", CpuPath::Simd, "NEON cnt");
+#else
+    return cpu_path("popcount", CpuPath::Scalar, "shift-and-add bit counting");
+#endif
+}
+
+// C++20 has no bit reversal (Rust has u64::reverse_bits), so this is the portable swap ladder
+uint64_t bit_reverse(uint64_t x) {
+    x = ((x >> 1) & 0x5555555555555555ULL) | ((x & 0x5555555555555555ULL) << 1);
+    x = ((x >> 2) & 0x3333333333333333ULL) | ((x & 0x3333333333333333ULL) << 2);
+    x = ((x >> 4) & 0x0F0F0F0F0F0F0F0FULL) | ((x & 0x0F0F0F0F0F0F0F0FULL) << 4);
+    x = ((x >> 8) & 0x00FF00FF00FF00FFULL) | ((x & 0x00FF00FF00FF00FFULL) << 8);
+    x = ((x >> 16) & 0x0000FFFF0000FFFFULL) | ((x & 0x0000FFFF0000FFFFULL) << 16);
+    return (x >> 32) | (x << 32);
+}
+
+// Every square a knight on `from` (a single bit, a1 = bit 0) attacks, by shifting with the
+// files it would wrap across masked off
+uint64_t knight_attacks(uint64_t from) {
+    uint64_t l1 = (from >> 1) & 0x7f7f7f7f7f7f7f7fULL;
+    uint64_t l2 = (from >> 2) & 0x3f3f3f3f3f3f3f3fULL;
+    uint64_t r1 = (from << 1) & 0xfefefefefefefefeULL;
+    uint64_t r2 = (from << 2) & 0xfcfcfcfcfcfcfcfcULL;
+    uint64_t h1 = l1 | r1;
+    uint64_t h2 = l2 | r2;
+    return (h1 << 16) | (h1 >> 16) | (h2 << 8) | (h2 >> 8);
+}
+
+uint64_t popcount(const uint64_t* words, const uint64_t*, size_t n) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < n; i++) sum += std::popcount(words[i]);
+    return sum;
+}
+
+uint64_t leading_zeros(const uint64_t* words, const uint64_t*, size_t n) {
+    // Shifted right by a varying amount so the counts cover 0..=63 instead of nearly always 0
+    uint64_t sum = 0;
+    for (size_t i = 0; i < n; i++) sum += std::countl_zero(words[i] >> (words[i] & 63));
+    return sum;
+}
+
+uint64_t trailing_zeros(const uint64_t* words, const uint64_t*, size_t n) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < n; i++) sum += std::countr_zero(words[i] << (words[i] >> 58));
+    return sum;
+}
+
+uint64_t reverse_bits(const uint64_t* words, const uint64_t*, size_t n) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < n; i++) sum += bit_reverse(words[i]);
+    return sum;
+}
+
+uint64_t extract(const uint64_t* words, const uint64_t* masks, size_t n) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < n; i++) sum += pext(words[i], masks[i]);
+    return sum;
+}
+
+uint64_t deposit(const uint64_t* words, const uint64_t* masks, size_t n) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < n; i++) sum += pdep(words[i], masks[i]);
+    return sum;
+}
+
+// One knight on every occupied square: walk the set bits (trailing zeros, clear lowest) and count
+// the empty squares each attacks
+uint64_t knight_moves(const uint64_t*, const uint64_t* boards, size_t n) {
+    uint64_t moves = 0;
+    for (size_t i = 0; i < n; i++) {
+        uint64_t board = boards[i];
+        for (uint64_t pieces = board; pieces != 0; pieces &= pieces - 1) {
+            uint64_t from = uint64_t(1) << std::countr_zero(pieces);
+            moves += std::popcount(knight_attacks(from) & ~board);
+        }
+    }
+    return moves;
+}
+
+using Kernel = uint64_t (*)(const uint64_t*, const uint64_t*, size_t);
+
+struct NamedKernel {
+    const char* name;
+    Kernel kernel;
+};
+
+const NamedKernel KERNELS[] = {
+    {"popcount", popcount},
+    {"leading zeros", leading_zeros},
+    {"trailing zeros", trailing_zeros},
+    {"bit reverse", reverse_bits},
+    {"pext", extract},
+    {"pdep", deposit},
+    {"knight moves", knight_moves},
+};
+
+struct Run {
+    Duration duration;
+    uint64_t result = 0;
+};
+
+// The barrier on the inputs every pass stops the compiler computing one pass and multiplying
+Run run(Kernel kernel, const uint64_t* words, const uint64_t* sparse, size_t n, size_t passes) {
+    Run run;
+    auto start = Clock::now();
+    for (size_t p = 0; p < passes; p++) {
+        asm volatile("" : : "r"(words), "r"(sparse) : "memory");
+        run.result += kernel(words, sparse, n);
+    }
+    run.duration = Clock::now() - start;
+    return run;
+}
+
+int main() {
+    std::vector<uint64_t> words, sparse;
+    generate(NUM_WORDS, words, sparse);
+
+    // Warm-up
+    for (const auto& k : KERNELS) run(k.kernel, words.data(), sparse.data(), WARMUP_WORDS, 1);
+
+    // Benchmark
+    std::vector<Run> runs;
+    Duration total_duration(0);
+    uint64_t checksum = 0;
+    for (const auto& k : KERNELS) {
+        Run r = run(k.kernel, words.data(), sparse.data(), NUM_WORDS, PASSES);
+        total_duration += r.duration;
+        checksum += r.result;
+        runs.push_back(r);
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Words: %zu u64 x %zu passes per kernel\n", NUM_WORDS, PASSES);
+    for (size_t i = 0; i < runs.size(); i++) {
+        double ops = static_cast<double>(NUM_WORDS * PASSES) / runs[i].duration.count();
+        fprintf(stderr, "  %-15s %.3fs (%.0f Mops/s)\n", KERNELS[i].name, runs[i].duration.count(), ops / 1e6);
+    }
+    std::cerr << cpu_features_report() << std::endl;
+    std::cerr << popcount_path() << std::endl;
+    std::cerr << cpu_path("pext/pdep", HARDWARE_PEXT ? CpuPath::Hw : CpuPath::Scalar,
+                          HARDWARE_PEXT ? "BMI2 instructions" : "software loops") << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,191 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <atomic>
+#include <cmath>
+#include <cstring>
+#include <string>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const uint64_t NUM_KEYS = 100'000'000;
+const uint64_t NUM_QUERIES = 100'000'000;
+const double DEFAULT_FP_RATE = 0.01;
+const size_t NUM_THREADS = 8;
+
+// SplitMix64 finalizer: keys are sequential ids, so scramble them first
+inline uint64_t mix64(uint64_t x) {
+    x += 0x9E3779B97F4A7C15ULL;
+    x = (x ^ (x >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    x = (x ^ (x >> 27)) * 0x94D049BB133111EBULL;
+    return x ^ (x >> 31);
+}
+
+// Query i alternates between an inserted key and one that was never inserted
+inline uint64_t query_key(uint64_t i, bool& member) {
+    member = i % 2 == 0;
+    return member ? i / 2 % NUM_KEYS : NUM_KEYS + i;
+}
+
+struct BloomFilter {
+    std::vector<std::atomic<uint64_t>> words;
+    uint64_t num_bits;
+    uint32_t num_hashes;
+
+    // Optimal sizing: m = -n ln p / (ln 2)^2 bits and k = (m / n) ln 2 hashes
+    BloomFilter(uint64_t expected_keys, double fp_rate) {
+        const double ln2 = std::log(2.0);
+        num_bits = static_cast<uint64_t>(std::ceil(-static_cast<double>(expected_keys) * std::log(fp_rate) / (ln2 * ln2)));
+        num_hashes = static_cast<uint32_t>(std::max(1.0, std::round(static_cast<double>(num_bits) / expected_keys * ln2)));
+        words = std::vector<std::atomic<uint64_t>>((num_bits + 63) / 64);
+    }
+
+    // Kirsch-Mitzenmacher double hashing; multiply-shift maps a hash onto [0, num_bits)
+    inline uint64_t bit_index(uint64_t h1, uint64_t h2, uint64_t i) const {
+        uint64_t h = h1 + i * h2;
+        return static_cast<uint64_t>((static_cast<unsigned __int128>(h) * num_bits) >> 64);
+    }
+
+    void insert(uint64_t key) {
+        uint64_t h1 = mix64(key);
+        uint64_t h2 = mix64(h1) | 1;
+        for (uint64_t i = 0; i < num_hashes; i++) {
+            uint64_t bit = bit_index(h1, h2, i);
+            words[bit / 64].fetch_or(1ULL << (bit % 64), std::memory_order_relaxed);
+        }
+    }
+
+    bool contains(uint64_t key) const {
+        uint64_t h1 = mix64(key);
+        uint64_t h2 = mix64(h1) | 1;
+        for (uint64_t i = 0; i < num_hashes; i++) {
+            uint64_t bit = bit_index(h1, h2, i);
+            if (!(words[bit / 64].load(std::memory_order_relaxed) & (1ULL << (bit % 64)))) {
+                return false;
+            }
+        }
+        return true;
+    }
+};
+
+void insert_all(BloomFilter& filter, uint64_t count) {
+    std::vector<std::thread> threads;
+    uint64_t per_thread = count / NUM_THREADS;
+
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        uint64_t begin = t * per_thread;
+        uint64_t end = (t == NUM_THREADS - 1) ? count : (t + 1) * per_thread;
+        threads.emplace_back([&filter, begin, end] {
+            for (uint64_t key = begin; key < end; key++) {
+                filter.insert(key);
+            }
+        });
+    }
+
+    for (auto& thread : threads) {
+        thread.join();
+    }
+}
+
+// Returns (members found, false positives)
+std::pair<uint64_t, uint64_t> query_all(const BloomFilter& filter, uint64_t count) {
+    std::vector<std::thread> threads;
+    std::vector<std::pair<uint64_t, uint64_t>> partial(NUM_THREADS);
+    uint64_t per_thread = count / NUM_THREADS;
+
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        uint64_t begin = t * per_thread;
+        uint64_t end = (t == NUM_THREADS - 1) ? count : (t + 1) * per_thread;
+        threads.emplace_back([&filter, &partial, t, begin, end] {
+            uint64_t found = 0, false_positives = 0;
+            for (uint64_t i = begin; i < end; i++) {
+                bool member;
+                uint64_t key = query_key(i, member);
+                if (filter.contains(key)) {
+                    if (member) found++; else false_positives++;
+                }
+            }
+            partial[t] = {found, false_positives};
+        });
+    }
+
+    for (auto& thread : threads) {
+        thread.join();
+    }
+
+    std::pair<uint64_t, uint64_t> total = {0, 0};
+    for (const auto& p : partial) {
+        total.first += p.first;
+        total.second += p.second;
+    }
+    return total;
+}
+
+double parse_fp_rate(int argc, char** argv) {
+    for (int i = 1; i + 1 < argc; i++) {
+        if (std::strcmp(argv[i], "--fp-rate") == 0) {
+            double p = std::stod(argv[i + 1]);
+            if (!(p > 0.0 && p < 1.0)) {
+                std::cerr << "--fp-rate expects a probability between 0 and 1" << std::endl;
+                std::exit(1);
+            }
+            return p;
+        }
+    }
+    return DEFAULT_FP_RATE;
+}
+
+int main(int argc, char** argv) {
+    auto measure = bench_measure_from_env();
+    double fp_rate = parse_fp_rate(argc, argv);
+
+    // Warm-up
+    {
+        BloomFilter warmup(NUM_KEYS / 100, fp_rate);
+        insert_all(warmup, NUM_KEYS / 100);
+        volatile auto result = query_all(warmup, NUM_QUERIES / 100).first;
+        (void)result;
+    }
+
+    BloomFilter filter(NUM_KEYS, fp_rate);
+
+    // Benchmark insertion
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    insert_all(filter, NUM_KEYS);
+    measure->stop();
+    auto insert_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark queries
+    auto query_start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    auto [found, false_positives] = query_all(filter, NUM_QUERIES);
+    measure->stop();
+    auto query_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> insert_duration = insert_end - start;
+    std::chrono::duration<double> query_duration = query_end - query_start;
+
+    uint64_t non_members = NUM_QUERIES / 2;
+    uint64_t set_bits = 0;
+    for (const auto& w : filter.words) {
+        set_bits += __builtin_popcountll(w.load(std::memory_order_relaxed));
+    }
+
+    measure->report();
+    std::cerr << std::fixed << "Insert: " << insert_duration.count() << "s (" << std::setprecision(0)
+              << NUM_KEYS / insert_duration.count() << " ops/s), query: " << std::setprecision(6)
+              << query_duration.count() << "s (" << std::setprecision(0)
+              << NUM_QUERIES / query_duration.count() << " ops/s)" << std::endl;
+    std::cerr << std::defaultfloat << "Bits: " << filter.num_bits << " (" << filter.num_bits / 8 / 1'000'000
+              << " MB), hashes: " << filter.num_hashes << ", target FP rate: " << fp_rate
+              << ", measured FP rate: " << std::fixed << std::setprecision(6)
+              << static_cast<double>(false_positives) / non_members << std::endl;
+    std::cerr << "Checksum: " << found << " found, " << false_positives << " false positives, "
+              << set_bits << " bits set" << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,137 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+
+const size_t NUM_ELEMENTS = size_t(1) << 24;
+const int32_t THRESHOLD = 128; // values are uniform in 0..256, so half pass
+const size_t PASSES = 10;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+std::vector<int32_t> generate(size_t n) {
+    uint64_t seed = 42;
+    std::vector<int32_t> data(n);
+    for (auto& v : data) v = static_cast<int32_t>(next_random(seed) % 256);
+    return data;
+}
+
+// The plain `if`: the compiler is free to keep the branch, turn it into a cmov or vectorize it
+int64_t sum_natural(const int32_t* data, size_t n) {
+    int64_t sum = 0;
+    for (size_t i = 0; i < n; i++) {
+        if (data[i] >= THRESHOLD) sum += data[i];
+    }
+    return sum;
+}
+
+// The same `if` with an empty asm volatile inside it. A volatile asm cannot be executed
+// speculatively, so the compiler has to keep a real conditional branch
+int64_t sum_branchy(const int32_t* data, size_t n) {
+    int64_t sum = 0;
+    for (size_t i = 0; i < n; i++) {
+        int32_t v = data[i];
+        if (v >= THRESHOLD) {
+            asm volatile("" : "+r"(v));
+            sum += v;
+        }
+    }
+    return sum;
+}
+
+// No branch to predict: the comparison becomes an all-ones or all-zeros mask
+int64_t sum_branchless(const int32_t* data, size_t n) {
+    int64_t sum = 0;
+    for (size_t i = 0; i < n; i++) {
+        int32_t mask = -static_cast<int32_t>(data[i] >= THRESHOLD);
+        sum += data[i] & mask;
+    }
+    return sum;
+}
+
+using Kernel = int64_t (*)(const int32_t*, size_t);
+
+struct Variant {
+    const char* name;
+    Kernel kernel;
+};
+
+const Variant VARIANTS[] = {
+    {"natural", sum_natural},
+    {"branchy", sum_branchy},
+    {"branchless", sum_branchless},
+};
+
+struct Run {
+    Duration duration;
+    int64_t sum = 0;
+};
+
+// The barrier on the input every pass stops the compiler computing one pass and multiplying
+Run run(const Variant& variant, const std::vector<int32_t>& data, size_t passes) {
+    Run run;
+    auto start = Clock::now();
+    for (size_t p = 0; p < passes; p++) {
+        asm volatile("" : : "r"(data.data()) : "memory");
+        run.sum += variant.kernel(data.data(), data.size());
+    }
+    run.duration = Clock::now() - start;
+    return run;
+}
+
+double ns_per_element(const Run& run) {
+    return run.duration.count() * 1e9 / (NUM_ELEMENTS * PASSES);
+}
+
+int main() {
+    std::vector<int32_t> shuffled = generate(NUM_ELEMENTS);
+    std::vector<int32_t> sorted = shuffled;
+    std::sort(sorted.begin(), sorted.end());
+
+    // Warm-up
+    for (const auto& variant : VARIANTS) {
+        run(variant, sorted, 1);
+        run(variant, shuffled, 1);
+    }
+
+    // Benchmark
+    Duration total_duration(0);
+    std::vector<std::string> lines;
+    std::vector<int64_t> sums;
+    std::string ratios;
+    for (const auto& variant : VARIANTS) {
+        Run on_sorted = run(variant, sorted, PASSES);
+        Run on_shuffled = run(variant, shuffled, PASSES);
+        for (auto [data, r] : {std::pair<const char*, const Run*>{"sorted", &on_sorted}, {"shuffled", &on_shuffled}}) {
+            char line[128];
+            snprintf(line, sizeof(line), "  %-10s %-8s %.3fs (%.2f ns/element)", variant.name, data,
+                     r->duration.count(), ns_per_element(*r));
+            lines.push_back(line);
+            total_duration += r->duration;
+            sums.push_back(r->sum);
+        }
+        char ratio[64];
+        snprintf(ratio, sizeof(ratio), "%s%s %.2fx", ratios.empty() ? "" : ", ", variant.name,
+                 on_shuffled.duration.count() / on_sorted.duration.count());
+        ratios += ratio;
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Elements: %zu values in 0..256, summing those >= %d, %zu passes\n", NUM_ELEMENTS, THRESHOLD, PASSES);
+    for (const auto& line : lines) fprintf(stderr, "%s\n", line.c_str());
+    fprintf(stderr, "Shuffled/sorted time: %s\n", ratios.c_str());
+    bool match = std::all_of(sums.begin(), sums.end(), [&](int64_t s) { return s == sums[0]; });
+    std::cerr << "Checksum: " << sums[0] << " (all variants match: " << (match ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,447 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+#include <array>
+#include <limits>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+
+#include "bench_measure.h"
+
+const size_t DEFAULT_TRIANGLES = 2'000'000;
+const size_t WARMUP_TRIANGLES = 100'000;
+const size_t BINS = 16;
+const size_t MIN_LEAF = 2; // nodes this small are never split
+const size_t MAX_LEAF = 8; // nodes larger than this are always split, whatever SAH says
+const float TRAVERSAL_COST = 1.0f; // relative to one triangle test
+const size_t TASK_SIZE = 32 * 1024; // smaller nodes build their whole subtree on one thread
+const size_t PARALLEL_BIN_SIZE = 256 * 1024; // larger nodes bin their triangles in parallel
+const size_t NUM_THREADS = 8;
+const uint64_t SEED = 0x6276'685f'6275'696c;
+const float INF = std::numeric_limits<float>::infinity();
+
+struct Vec3 {
+    float x, y, z;
+
+    Vec3 operator+(const Vec3& o) const { return {x + o.x, y + o.y, z + o.z}; }
+    Vec3 operator-(const Vec3& o) const { return {x - o.x, y - o.y, z - o.z}; }
+    Vec3 operator*(float s) const { return {x * s, y * s, z * s}; }
+    bool operator==(const Vec3& o) const { return x == o.x && y == o.y && z == o.z; }
+
+    float axis(size_t axis) const { return axis == 0 ? x : axis == 1 ? y : z; }
+};
+
+// Component-wise, with Rust's f32::min/max results for the non-NaN values used here
+inline Vec3 vmin(const Vec3& a, const Vec3& b) {
+    return {std::min(a.x, b.x), std::min(a.y, b.y), std::min(a.z, b.z)};
+}
+
+inline Vec3 vmax(const Vec3& a, const Vec3& b) {
+    return {std::max(a.x, b.x), std::max(a.y, b.y), std::max(a.z, b.z)};
+}
+
+struct Triangle {
+    Vec3 v0, v1, v2;
+};
+
+struct Aabb {
+    Vec3 min = {INF, INF, INF};
+    Vec3 max = {-INF, -INF, -INF};
+
+    void grow(const Aabb& other) {
+        min = vmin(min, other.min);
+        max = vmax(max, other.max);
+    }
+
+    void grow(const Vec3& p) {
+        min = vmin(min, p);
+        max = vmax(max, p);
+    }
+
+    float area() const {
+        Vec3 d = max - min;
+        return 2.0f * (d.x * d.y + d.y * d.z + d.z * d.x);
+    }
+
+    bool operator==(const Aabb& o) const { return min == o.min && max == o.max; }
+};
+
+// What the builder reads per triangle: its box, and the box's center as the split key
+struct Primitive {
+    Aabb bounds;
+    Vec3 centroid;
+};
+
+// 32 bytes in depth-first order: an inner node's left child is the next node and offset is
+// the distance to its right child; a leaf owns order[offset..offset + count]
+struct Node {
+    Aabb bounds;
+    uint32_t offset;
+    uint32_t count;
+
+    bool operator==(const Node& o) const { return bounds == o.bounds && offset == o.offset && count == o.count; }
+};
+
+struct Bvh {
+    std::vector<Node> nodes;
+    std::vector<uint32_t> order;
+};
+
+struct Bin {
+    Aabb bounds;
+    uint32_t count = 0;
+};
+
+using Bins = std::array<std::array<Bin, BINS>, 3>;
+
+// Binned: triangles whose centroid falls in a bin below `bin` on `axis` go left.
+// Halves: every centroid in the same bin (coincident triangles), so the list is split in half
+struct Split {
+    enum Kind { None, Binned, Halves } kind = None;
+    size_t axis = 0;
+    size_t bin = 0;
+    float lo = 0.0f;
+    float scale = 0.0f;
+};
+
+// SplitMix64; the top 24 bits become a float in [0, 1) exactly, in both languages
+struct Rng {
+    uint64_t state;
+
+    uint64_t next() {
+        state += 0x9e3779b97f4a7c15ULL;
+        uint64_t z = state;
+        z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+        z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+        return z ^ (z >> 31);
+    }
+
+    float range(float lo, float hi) {
+        return lo + (hi - lo) * (static_cast<float>(next() >> 40) * (1.0f / 16777216.0f));
+    }
+
+    Vec3 point(float half) {
+        float x = range(-half, half);
+        float y = range(-half, half);
+        float z = range(-half, half);
+        return {x, y, z};
+    }
+};
+
+// Small triangles, each corner within 0.01 of its center, scattered through the [-1, 1] cube
+std::vector<Triangle> make_triangles(Rng rng, size_t count) {
+    std::vector<Triangle> triangles;
+    triangles.reserve(count);
+    for (size_t i = 0; i < count; i++) {
+        Vec3 center = rng.point(1.0f);
+        Vec3 v0 = center + rng.point(0.01f);
+        Vec3 v1 = center + rng.point(0.01f);
+        Vec3 v2 = center + rng.point(0.01f);
+        triangles.push_back({v0, v1, v2});
+    }
+    return triangles;
+}
+
+Primitive primitive(const Triangle& triangle) {
+    Aabb bounds;
+    bounds.grow(triangle.v0);
+    bounds.grow(triangle.v1);
+    bounds.grow(triangle.v2);
+    return {bounds, (bounds.min + bounds.max) * 0.5f};
+}
+
+inline size_t bin_index(float centroid, float lo, float scale) {
+    return std::min(static_cast<size_t>((centroid - lo) * scale), BINS - 1);
+}
+
+// Runs fn(thread, begin, end) over NUM_THREADS contiguous ranges of [0, count)
+template <typename Fn>
+void parallel_ranges(size_t count, Fn fn) {
+    std::vector<std::thread> threads;
+    size_t per_thread = (count + NUM_THREADS - 1) / NUM_THREADS;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        size_t begin = std::min(t * per_thread, count), end = std::min(begin + per_thread, count);
+        threads.emplace_back(fn, t, begin, end);
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+}
+
+// The node's box and the box of its triangles' centroids, which the bins divide
+void node_bounds(const std::vector<Primitive>& prims, const uint32_t* order, size_t count, Aabb& bounds, Aabb& centroids) {
+    for (size_t i = 0; i < count; i++) {
+        const Primitive& p = prims[order[i]];
+        bounds.grow(p.bounds);
+        centroids.grow(p.centroid);
+    }
+}
+
+void bin(const std::vector<Primitive>& prims, const uint32_t* order, size_t count, const Aabb& centroids,
+         const Vec3& scale, Bins& bins) {
+    for (size_t i = 0; i < count; i++) {
+        const Primitive& p = prims[order[i]];
+        for (size_t axis = 0; axis < 3; axis++) {
+            Bin& b = bins[axis][bin_index(p.centroid.axis(axis), centroids.min.axis(axis), scale.axis(axis))];
+            b.bounds.grow(p.bounds);
+            b.count++;
+        }
+    }
+}
+
+// Boxes only grow and counts only add, so merging chunks in any order gives the same bins
+void merge_bins(Bins& a, const Bins& b) {
+    for (size_t axis = 0; axis < 3; axis++) {
+        for (size_t i = 0; i < BINS; i++) {
+            a[axis][i].bounds.grow(b[axis][i].bounds);
+            a[axis][i].count += b[axis][i].count;
+        }
+    }
+}
+
+// Bins the node's triangles on all three axes and picks the cheapest of the BINS - 1 planes
+// per axis by the surface area heuristic; Split::None when a leaf is cheaper
+Split plan(const std::vector<Primitive>& prims, const uint32_t* order, size_t count, bool parallel, Aabb& bounds) {
+    Aabb centroids;
+    if (parallel) {
+        std::vector<Aabb> part_bounds(NUM_THREADS), part_centroids(NUM_THREADS);
+        parallel_ranges(count, [&](size_t t, size_t begin, size_t end) {
+            node_bounds(prims, order + begin, end - begin, part_bounds[t], part_centroids[t]);
+        });
+        for (size_t t = 0; t < NUM_THREADS; t++) {
+            bounds.grow(part_bounds[t]);
+            centroids.grow(part_centroids[t]);
+        }
+    } else {
+        node_bounds(prims, order, count, bounds, centroids);
+    }
+    if (count <= MIN_LEAF) return {};
+    Vec3 extent = centroids.max - centroids.min;
+    Vec3 scale = {extent.x > 0.0f ? BINS / extent.x : 0.0f, extent.y > 0.0f ? BINS / extent.y : 0.0f,
+                  extent.z > 0.0f ? BINS / extent.z : 0.0f};
+    Bins bins{};
+    if (parallel) {
+        std::vector<Bins> parts(NUM_THREADS);
+        parallel_ranges(count, [&](size_t t, size_t begin, size_t end) {
+            bin(prims, order + begin, end - begin, centroids, scale, parts[t]);
+        });
+        for (const Bins& part : parts) merge_bins(bins, part);
+    } else {
+        bin(prims, order, count, centroids, scale, bins);
+    }
+
+    bool found = false;
+    float best_cost = 0.0f;
+    size_t best_axis = 0, best_bin = 0;
+    for (size_t axis = 0; axis < 3; axis++) {
+        if (extent.axis(axis) <= 0.0f) continue;
+        // Right-to-left sweep first, so the left-to-right one can price each plane as it goes
+        float right_cost[BINS] = {};
+        Aabb right;
+        uint32_t right_count = 0;
+        for (size_t b = BINS - 1; b >= 1; b--) {
+            right.grow(bins[axis][b].bounds);
+            right_count += bins[axis][b].count;
+            right_cost[b] = right_count > 0 ? right.area() * static_cast<float>(right_count) : 0.0f;
+        }
+        Aabb left;
+        uint32_t left_count = 0;
+        for (size_t b = 1; b < BINS; b++) {
+            left.grow(bins[axis][b - 1].bounds);
+            left_count += bins[axis][b - 1].count;
+            if (left_count == 0 || left_count == count) continue;
+            float cost = left.area() * static_cast<float>(left_count) + right_cost[b];
+            if (!found || cost < best_cost) {
+                found = true;
+                best_cost = cost;
+                best_axis = axis;
+                best_bin = b;
+            }
+        }
+    }
+
+    float area = bounds.area();
+    if (!found) {
+        return count > MAX_LEAF ? Split{Split::Halves} : Split{};
+    }
+    if (count <= MAX_LEAF && TRAVERSAL_COST * area + best_cost >= area * static_cast<float>(count)) return {};
+    return {Split::Binned, best_axis, best_bin, centroids.min.axis(best_axis), scale.axis(best_axis)};
+}
+
+// In-place two-way partition; returns how many triangles went left
+size_t partition(const std::vector<Primitive>& prims, uint32_t* order, size_t count, const Split& split) {
+    if (split.kind == Split::Halves) return count / 2;
+    size_t i = 0, j = count;
+    while (i < j) {
+        if (bin_index(prims[order[i]].centroid.axis(split.axis), split.lo, split.scale) < split.bin) {
+            i++;
+        } else {
+            j--;
+            std::swap(order[i], order[j]);
+        }
+    }
+    return i;
+}
+
+void build_sequential(const std::vector<Primitive>& prims, uint32_t* order, size_t count, size_t first,
+                      std::vector<Node>& nodes) {
+    Aabb bounds;
+    Split split = plan(prims, order, count, false, bounds);
+    if (split.kind == Split::None) {
+        nodes.push_back({bounds, static_cast<uint32_t>(first), static_cast<uint32_t>(count)});
+        return;
+    }
+    size_t mid = partition(prims, order, count, split);
+    size_t at = nodes.size();
+    nodes.push_back({bounds, 0, 0});
+    build_sequential(prims, order, mid, first, nodes);
+    nodes[at].offset = static_cast<uint32_t>(nodes.size() - at);
+    build_sequential(prims, order + mid, count - mid, first + mid, nodes);
+}
+
+// Subtrees are built on their own threads into separate runs of nodes, returned in depth-first
+// order so one concatenation at the end lays them out exactly as build_sequential would
+std::vector<std::vector<Node>> build_parallel(const std::vector<Primitive>& prims, uint32_t* order, size_t count,
+                                              size_t first) {
+    if (count <= TASK_SIZE) {
+        std::vector<Node> nodes;
+        build_sequential(prims, order, count, first, nodes);
+        return {std::move(nodes)};
+    }
+    Aabb bounds;
+    Split split = plan(prims, order, count, count > PARALLEL_BIN_SIZE, bounds);
+    if (split.kind == Split::None) {
+        return {{Node{bounds, static_cast<uint32_t>(first), static_cast<uint32_t>(count)}}};
+    }
+    size_t mid = partition(prims, order, count, split);
+    std::vector<std::vector<Node>> left, right;
+    std::thread left_thread([&]() { left = build_parallel(prims, order, mid, first); });
+    right = build_parallel(prims, order + mid, count - mid, first + mid);
+    left_thread.join();
+    size_t left_nodes = 0;
+    for (const auto& run : left) left_nodes += run.size();
+    std::vector<std::vector<Node>> runs;
+    runs.push_back({Node{bounds, static_cast<uint32_t>(1 + left_nodes), 0}});
+    for (auto& run : left) runs.push_back(std::move(run));
+    for (auto& run : right) runs.push_back(std::move(run));
+    return runs;
+}
+
+Bvh build(const std::vector<Triangle>& triangles, bool parallel) {
+    Bvh bvh;
+    bvh.order.resize(triangles.size());
+    for (size_t i = 0; i < triangles.size(); i++) bvh.order[i] = static_cast<uint32_t>(i);
+    std::vector<Primitive> prims(triangles.size());
+    if (parallel) {
+        parallel_ranges(triangles.size(), [&](size_t, size_t begin, size_t end) {
+            for (size_t i = begin; i < end; i++) prims[i] = primitive(triangles[i]);
+        });
+        size_t total = 0;
+        auto runs = build_parallel(prims, bvh.order.data(), triangles.size(), 0);
+        for (const auto& run : runs) total += run.size();
+        bvh.nodes.reserve(total);
+        for (const auto& run : runs) bvh.nodes.insert(bvh.nodes.end(), run.begin(), run.end());
+    } else {
+        for (size_t i = 0; i < triangles.size(); i++) prims[i] = primitive(triangles[i]);
+        build_sequential(prims, bvh.order.data(), triangles.size(), 0, bvh.nodes);
+    }
+    return bvh;
+}
+
+struct Quality {
+    size_t leaves = 0;
+    uint32_t max_leaf = 0;
+    size_t max_depth = 0;
+    uint64_t depth_sum = 0; // over triangles, of the depth of the leaf holding them
+    double sah_cost = 0.0;  // expected cost of a random ray, in triangle tests
+};
+
+Quality quality(const Bvh& bvh) {
+    double root_area = bvh.nodes[0].bounds.area();
+    Quality q;
+    // Right child pushed first, so nodes are visited in index order
+    std::vector<std::pair<size_t, size_t>> stack = {{0, 0}};
+    while (!stack.empty()) {
+        auto [index, depth] = stack.back();
+        stack.pop_back();
+        const Node& node = bvh.nodes[index];
+        double weight = node.bounds.area() / root_area;
+        q.max_depth = std::max(q.max_depth, depth);
+        if (node.count > 0) {
+            q.leaves++;
+            q.max_leaf = std::max(q.max_leaf, node.count);
+            q.depth_sum += static_cast<uint64_t>(node.count) * depth;
+            q.sah_cost += weight * node.count;
+        } else {
+            q.sah_cost += weight * TRAVERSAL_COST;
+            stack.push_back({index + node.offset, depth + 1});
+            stack.push_back({index + 1, depth + 1});
+        }
+    }
+    return q;
+}
+
+size_t parse_triangles(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--triangles") == 0) {
+            long long n = i + 1 < argc ? std::atoll(argv[i + 1]) : 0;
+            if (n <= 0) {
+                std::cerr << "--triangles expects a positive integer" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<size_t>(n);
+        }
+    }
+    return DEFAULT_TRIANGLES;
+}
+
+int main(int argc, char** argv) {
+    auto measure = bench_measure_from_env();
+    size_t count = parse_triangles(argc, argv);
+    std::vector<Triangle> triangles = make_triangles(Rng{SEED}, count);
+
+    // Warm-up
+    std::vector<Triangle> warmup = make_triangles(Rng{SEED ^ 1}, WARMUP_TRIANGLES);
+    build(warmup, false);
+    build(warmup, true);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    Bvh sequential = build(triangles, false);
+    measure->stop();
+    std::chrono::duration<double> sequential_time = std::chrono::high_resolution_clock::now() - start;
+    start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    Bvh parallel = build(triangles, true);
+    measure->stop();
+    std::chrono::duration<double> parallel_time = std::chrono::high_resolution_clock::now() - start;
+
+    if (!(parallel.nodes == sequential.nodes) || parallel.order != sequential.order) {
+        std::cerr << "Parallel BVH differs from the single-threaded one" << std::endl;
+        return 1;
+    }
+    Quality q = quality(sequential);
+
+    measure->report();
+    fprintf(stderr, "Triangles: %zu, %zu-bin SAH on 3 axes, leaves of up to %zu triangles where SAH prefers them\n",
+            count, BINS, MAX_LEAF);
+    struct { const char* name; double seconds; } builds[] = {
+        {"single-threaded", sequential_time.count()}, {"parallel", parallel_time.count()}};
+    for (const auto& b : builds) {
+        fprintf(stderr, "%s: %.3fs, %.2fM triangles/s\n", b.name, b.seconds, count / b.seconds / 1e6);
+    }
+    fprintf(stderr, "Parallel speedup: %.2fx on %zu threads\n", sequential_time.count() / parallel_time.count(), NUM_THREADS);
+    fprintf(stderr, "Tree: %zu nodes, %zu leaves (mean %.2f, max %u triangles), depth max %zu mean %.2f, SAH cost %.3f\n",
+            sequential.nodes.size(), q.leaves, static_cast<double>(count) / q.leaves, q.max_leaf, q.max_depth,
+            static_cast<double>(q.depth_sum) / count, q.sah_cost);
+    fprintf(stderr, "Parallel and single-threaded trees match node for node\n");
+    fprintf(stderr, "Checksum: %llu\n", static_cast<unsigned long long>(q.depth_sum));
+
+    return 0;
+}
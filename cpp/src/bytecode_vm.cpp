@@ -0,0 +1,268 @@
+#include <iostream>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+#include <cstdint>
+
+const int32_t ITERATIONS = 50'000'000;
+const int32_t SEED = 12345;
+const size_t STACK_SIZE = 64;
+const size_t NUM_LOCALS = 4;
+
+enum Op : uint8_t { Push, Load, Store, Dup, Add, Sub, Mul, And, Xor, Shr, Lt, Jump, JumpIfZero, JumpIfNonZero, Halt };
+
+struct Instr {
+    Op op;
+    int32_t arg;
+};
+
+using Duration = std::chrono::duration<double>;
+
+// i = 0; acc = 0; x = SEED
+// do {
+//     x = (x * 1103515245 + 12345) & 0x7fffffff
+//     acc += (x >> 7) & 1023
+//     if (x & 1) acc ^= i else acc -= x & 15
+//     i += 1
+// } while (i < ITERATIONS)
+// return acc
+std::vector<Instr> build_program() {
+    const int32_t I = 0, ACC = 1, X = 2;
+    const int32_t LOOP = 6, ELSE = 31, NEXT = 37;
+    return {
+        {Push, 0}, {Store, I},
+        {Push, 0}, {Store, ACC},
+        {Push, SEED}, {Store, X},
+        // LOOP
+        {Load, X}, {Push, 1103515245}, {Mul, 0}, {Push, 12345}, {Add, 0},
+        {Push, 0x7fffffff}, {And, 0}, {Store, X},
+        {Load, ACC}, {Load, X}, {Push, 7}, {Shr, 0}, {Push, 1023}, {And, 0},
+        {Add, 0}, {Store, ACC},
+        {Load, X}, {Push, 1}, {And, 0}, {JumpIfZero, ELSE},
+        {Load, ACC}, {Load, I}, {Xor, 0}, {Store, ACC}, {Jump, NEXT},
+        // ELSE
+        {Load, ACC}, {Load, X}, {Push, 15}, {And, 0}, {Sub, 0}, {Store, ACC},
+        // NEXT
+        {Load, I}, {Push, 1}, {Add, 0}, {Dup, 0}, {Store, I},
+        {Push, ITERATIONS}, {Lt, 0}, {JumpIfNonZero, LOOP},
+        {Load, ACC}, {Halt, 0},
+    };
+}
+
+// The same computation in plain C++: the result every VM must reproduce, plus the dispatch count
+std::pair<int64_t, uint64_t> run_native(int32_t iterations) {
+    int64_t acc = 0, x = SEED;
+    uint64_t odd = 0;
+    for (int64_t i = 0; i < iterations; i++) {
+        x = static_cast<int64_t>(static_cast<uint64_t>(x) * 1103515245ULL + 12345ULL) & 0x7fffffff;
+        acc += (x >> 7) & 1023;
+        if (x & 1) {
+            acc ^= i;
+            odd++;
+        } else {
+            acc -= x & 15;
+        }
+    }
+    // 6 setup + 28 per iteration + 5 (then) or 6 (else) + 2 to return
+    uint64_t n = static_cast<uint64_t>(iterations);
+    return {acc, 6 + 28 * n + 5 * odd + 6 * (n - odd) + 2};
+}
+
+inline int64_t wrapping_mul(int64_t a, int64_t b) {
+    return static_cast<int64_t>(static_cast<uint64_t>(a) * static_cast<uint64_t>(b));
+}
+
+inline int64_t wrapping_add(int64_t a, int64_t b) {
+    return static_cast<int64_t>(static_cast<uint64_t>(a) + static_cast<uint64_t>(b));
+}
+
+inline int64_t wrapping_sub(int64_t a, int64_t b) {
+    return static_cast<int64_t>(static_cast<uint64_t>(a) - static_cast<uint64_t>(b));
+}
+
+// Variant 1: one `switch` over the opcode in a loop
+int64_t run_switch(const std::vector<Instr>& code) {
+    int64_t stack[STACK_SIZE] = {};
+    int64_t locals[NUM_LOCALS] = {};
+    size_t sp = 0;
+    size_t pc = 0;
+    while (true) {
+        const Instr ins = code[pc++];
+        switch (ins.op) {
+            case Push: stack[sp++] = ins.arg; break;
+            case Load: stack[sp++] = locals[ins.arg]; break;
+            case Store: locals[ins.arg] = stack[--sp]; break;
+            case Dup: stack[sp] = stack[sp - 1]; sp++; break;
+            case Add: sp--; stack[sp - 1] = wrapping_add(stack[sp - 1], stack[sp]); break;
+            case Sub: sp--; stack[sp - 1] = wrapping_sub(stack[sp - 1], stack[sp]); break;
+            case Mul: sp--; stack[sp - 1] = wrapping_mul(stack[sp - 1], stack[sp]); break;
+            case And: sp--; stack[sp - 1] &= stack[sp]; break;
+            case Xor: sp--; stack[sp - 1] ^= stack[sp]; break;
+            case Shr: sp--; stack[sp - 1] >>= stack[sp]; break;
+            case Lt: sp--; stack[sp - 1] = stack[sp - 1] < stack[sp]; break;
+            case Jump: pc = ins.arg; break;
+            case JumpIfZero: if (stack[--sp] == 0) pc = ins.arg; break;
+            case JumpIfNonZero: if (stack[--sp] != 0) pc = ins.arg; break;
+            case Halt: return stack[sp - 1];
+        }
+    }
+}
+
+// Variant 2: call threading. The program is pre-decoded into handler pointers, one indirect call per instruction
+struct Vm {
+    int64_t stack[STACK_SIZE] = {};
+    int64_t locals[NUM_LOCALS] = {};
+    size_t sp = 0;
+    size_t pc = 0;
+    bool running = true;
+
+    void push(int64_t v) { stack[sp++] = v; }
+    int64_t pop() { return stack[--sp]; }
+
+    template <typename F>
+    void binary(F f) {
+        int64_t b = pop();
+        int64_t a = pop();
+        push(f(a, b));
+    }
+};
+
+using Handler = void (*)(Vm&, int32_t);
+
+struct Threaded {
+    Handler handler;
+    int32_t arg;
+};
+
+void op_push(Vm& vm, int32_t arg) { vm.push(arg); }
+void op_load(Vm& vm, int32_t arg) { vm.push(vm.locals[arg]); }
+void op_store(Vm& vm, int32_t arg) { vm.locals[arg] = vm.pop(); }
+void op_dup(Vm& vm, int32_t) { vm.push(vm.stack[vm.sp - 1]); }
+void op_add(Vm& vm, int32_t) { vm.binary(wrapping_add); }
+void op_sub(Vm& vm, int32_t) { vm.binary(wrapping_sub); }
+void op_mul(Vm& vm, int32_t) { vm.binary(wrapping_mul); }
+void op_and(Vm& vm, int32_t) { vm.binary([](int64_t a, int64_t b) { return a & b; }); }
+void op_xor(Vm& vm, int32_t) { vm.binary([](int64_t a, int64_t b) { return a ^ b; }); }
+void op_shr(Vm& vm, int32_t) { vm.binary([](int64_t a, int64_t b) { return a >> b; }); }
+void op_lt(Vm& vm, int32_t) { vm.binary([](int64_t a, int64_t b) -> int64_t { return a < b; }); }
+void op_jump(Vm& vm, int32_t arg) { vm.pc = arg; }
+void op_jump_if_zero(Vm& vm, int32_t arg) {
+    if (vm.pop() == 0) vm.pc = arg;
+}
+void op_jump_if_nonzero(Vm& vm, int32_t arg) {
+    if (vm.pop() != 0) vm.pc = arg;
+}
+void op_halt(Vm& vm, int32_t) { vm.running = false; }
+
+// Indexed by opcode
+const Handler HANDLERS[15] = {
+    op_push, op_load, op_store, op_dup, op_add, op_sub, op_mul, op_and, op_xor, op_shr, op_lt,
+    op_jump, op_jump_if_zero, op_jump_if_nonzero, op_halt,
+};
+
+std::vector<Threaded> thread_code(const std::vector<Instr>& code) {
+    std::vector<Threaded> threaded;
+    for (const auto& ins : code) {
+        threaded.push_back({HANDLERS[ins.op], ins.arg});
+    }
+    return threaded;
+}
+
+int64_t run_threaded(const std::vector<Threaded>& code) {
+    Vm vm;
+    while (vm.running) {
+        const Threaded ins = code[vm.pc++];
+        ins.handler(vm, ins.arg);
+    }
+    return vm.stack[vm.sp - 1];
+}
+
+// Variant 3: computed goto (GCC/Clang labels-as-values). Each handler ends with its own indirect jump,
+// giving the branch predictor one history per opcode instead of a single shared dispatch branch
+int64_t run_computed_goto(const std::vector<Instr>& code) {
+    static const void* const LABELS[15] = {
+        &&op_push, &&op_load, &&op_store, &&op_dup, &&op_add, &&op_sub, &&op_mul, &&op_and, &&op_xor, &&op_shr,
+        &&op_lt, &&op_jump, &&op_jump_if_zero, &&op_jump_if_nonzero, &&op_halt,
+    };
+    int64_t stack[STACK_SIZE] = {};
+    int64_t locals[NUM_LOCALS] = {};
+    size_t sp = 0;
+    const Instr* pc = code.data();
+    const Instr* const base = code.data();
+    Instr ins;
+
+#define DISPATCH()            \
+    do {                      \
+        ins = *pc++;          \
+        goto* LABELS[ins.op]; \
+    } while (0)
+
+    DISPATCH();
+op_push: stack[sp++] = ins.arg; DISPATCH();
+op_load: stack[sp++] = locals[ins.arg]; DISPATCH();
+op_store: locals[ins.arg] = stack[--sp]; DISPATCH();
+op_dup: stack[sp] = stack[sp - 1]; sp++; DISPATCH();
+op_add: sp--; stack[sp - 1] = wrapping_add(stack[sp - 1], stack[sp]); DISPATCH();
+op_sub: sp--; stack[sp - 1] = wrapping_sub(stack[sp - 1], stack[sp]); DISPATCH();
+op_mul: sp--; stack[sp - 1] = wrapping_mul(stack[sp - 1], stack[sp]); DISPATCH();
+op_and: sp--; stack[sp - 1] &= stack[sp]; DISPATCH();
+op_xor: sp--; stack[sp - 1] ^= stack[sp]; DISPATCH();
+op_shr: sp--; stack[sp - 1] >>= stack[sp]; DISPATCH();
+op_lt: sp--; stack[sp - 1] = stack[sp - 1] < stack[sp]; DISPATCH();
+op_jump: pc = base + ins.arg; DISPATCH();
+op_jump_if_zero: if (stack[--sp] == 0) pc = base + ins.arg; DISPATCH();
+op_jump_if_nonzero: if (stack[--sp] != 0) pc = base + ins.arg; DISPATCH();
+op_halt: return stack[sp - 1];
+
+#undef DISPATCH
+}
+
+template <typename F>
+std::pair<Duration, int64_t> timed(F f) {
+    auto start = std::chrono::high_resolution_clock::now();
+    int64_t result = f();
+    return {std::chrono::high_resolution_clock::now() - start, result};
+}
+
+int main() {
+    std::vector<Instr> code = build_program();
+    std::vector<Threaded> threaded = thread_code(code);
+
+    // Warm-up on a short run of the same program
+    std::vector<Instr> warmup = code;
+    for (auto& ins : warmup) {
+        if (ins.op == Push && ins.arg == ITERATIONS) ins.arg = ITERATIONS / 100;
+    }
+    volatile int64_t sink = run_switch(warmup) + run_threaded(thread_code(warmup)) + run_computed_goto(warmup);
+    (void)sink;
+
+    // Benchmark
+    volatile int32_t iterations = ITERATIONS;
+    auto start = std::chrono::high_resolution_clock::now();
+    auto [native_result, dispatches] = run_native(iterations);
+    Duration native_duration = std::chrono::high_resolution_clock::now() - start;
+    auto [switch_duration, switch_result] = timed([&] { return run_switch(code); });
+    auto [threaded_duration, threaded_result] = timed([&] { return run_threaded(threaded); });
+    auto [goto_duration, goto_result] = timed([&] { return run_computed_goto(code); });
+
+    // Headline: the fastest dispatch strategy this language offers
+    Duration total_duration = std::min({switch_duration, threaded_duration, goto_duration});
+    auto ns_per_dispatch = [&](Duration d) { return d.count() * 1e9 / static_cast<double>(dispatches); };
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(3) << dispatches << " instructions dispatched per run, ns/dispatch:"
+              << std::endl;
+    std::cerr << "  switch loop       " << ns_per_dispatch(switch_duration) << " (" << switch_duration.count() << "s)"
+              << std::endl;
+    std::cerr << "  fn-pointer table  " << ns_per_dispatch(threaded_duration) << " (" << threaded_duration.count()
+              << "s)" << std::endl;
+    std::cerr << "  computed goto     " << ns_per_dispatch(goto_duration) << " (" << goto_duration.count() << "s)"
+              << std::endl;
+    std::cerr << "  native reference  " << native_duration.count() << "s" << std::endl;
+    std::cerr << "Checksum: " << switch_result << " (all variants match: " << std::boolalpha
+              << (switch_result == native_result && threaded_result == native_result && goto_result == native_result)
+              << ")" << std::endl;
+
+    return 0;
+}
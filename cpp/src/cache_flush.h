@@ -0,0 +1,126 @@
+// Cold caches for the benchmarks that repeat a kernel over data small enough to stay cached, the
+// same flag as rust/bench_cache:
+//
+//     --cold-cache  flush the measured data out of every cache level before each timed repetition
+//
+// cache_cold_enable() parses the flag. The benchmark then times each repetition on its own and
+// calls cache_flush() on its buffers in between: clflush on x86_64, dc civac on aarch64 Linux,
+// and elsewhere cache_evict(), a read-modify-write sweep over twice the last-level cache.
+#pragma once
+
+#include <cstdint>
+#include <cstring>
+#include <fstream>
+#include <string>
+#include <vector>
+#include <filesystem>
+#if defined(__x86_64__)
+#include <immintrin.h>
+#endif
+#ifdef __APPLE__
+#include <sys/sysctl.h>
+#endif
+
+const size_t CACHE_DEFAULT_LLC = size_t(32) << 20; // when the OS does not say
+const size_t CACHE_SWEEP_STEP = 64; // bytes; every current CPU's lines are 64 or 128
+// A cold repetition is timed alone after its own flush, so a few dozen give a steady mean and
+// keep a run where cache_flush() has to sweep from taking minutes
+const size_t CACHE_COLD_REPETITIONS = 32;
+
+inline bool& cache_cold_flag() {
+    static bool cold = false;
+    return cold;
+}
+
+// Parses --cold-cache
+inline bool cache_cold_enable(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--cold-cache") == 0) return cache_cold_flag() = true;
+    }
+    return false;
+}
+
+inline bool cache_cold() { return cache_cold_flag(); }
+
+// How many times to repeat a kernel the warm measurement would repeat `warm` times
+inline size_t cache_repetitions(size_t warm) { return cache_cold() && warm > CACHE_COLD_REPETITIONS ? CACHE_COLD_REPETITIONS : warm; }
+
+// The largest cache cpu0 sees, which is the last level; 0 when the OS does not say
+inline size_t cache_llc_bytes() {
+    size_t largest = 0;
+#ifdef __linux__
+    std::error_code ec;
+    for (const auto& entry : std::filesystem::directory_iterator("/sys/devices/system/cpu/cpu0/cache", ec)) {
+        // sysfs cache sizes: "48K", "2048K", "32M"
+        std::ifstream file(entry.path() / "size");
+        size_t n = 0;
+        std::string unit;
+        if (!(file >> n)) continue;
+        file >> unit;
+        int shift = unit == "K" ? 10 : unit == "M" ? 20 : unit == "G" ? 30 : 0;
+        if ((n << shift) > largest) largest = n << shift;
+    }
+#elif defined(__APPLE__)
+    // Apple Silicon has no L3; its performance cores' shared L2 is the last level
+    for (const char* name : {"hw.l3cachesize", "hw.perflevel0.l2cachesize", "hw.l2cachesize"}) {
+        uint64_t value = 0;
+        size_t len = sizeof(value);
+        if (sysctlbyname(name, &value, &len, nullptr, 0) == 0 && value > 0) return value;
+    }
+#endif
+    return largest;
+}
+
+inline size_t cache_sweep_bytes() {
+    size_t llc = cache_llc_bytes();
+    return 2 * (llc > 0 ? llc : CACHE_DEFAULT_LLC);
+}
+
+// Pushes everything out of the caches by writing to every line of a buffer twice the size of the
+// last-level cache. A no-op unless --cold-cache was given
+inline void cache_evict() {
+    if (!cache_cold()) return;
+    static std::vector<uint64_t> sweep(cache_sweep_bytes() / sizeof(uint64_t));
+    for (size_t i = 0; i < sweep.size(); i += CACHE_SWEEP_STEP / sizeof(uint64_t)) sweep[i]++;
+    asm volatile("" : : "r"(sweep.data()) : "memory");
+}
+
+#if defined(__x86_64__)
+inline const char* cache_flush_instruction() { return "clflush"; }
+#elif defined(__aarch64__) && defined(__linux__)
+inline const char* cache_flush_instruction() { return "dc civac"; }
+#else
+inline const char* cache_flush_instruction() { return nullptr; }
+#endif
+
+// Writes [data, data + bytes) back to memory and drops it from every cache level, so the next
+// access to it misses all the way to DRAM. A no-op unless --cold-cache was given
+inline void cache_flush(const void* data, size_t bytes) {
+    if (!cache_cold() || bytes == 0) return;
+    uintptr_t start = reinterpret_cast<uintptr_t>(data), end = start + bytes;
+#if defined(__x86_64__)
+    for (uintptr_t line = start / 64 * 64; line < end; line += 64) _mm_clflush(reinterpret_cast<const void*>(line));
+    // Orders the flushes before the next repetition's first access
+    _mm_mfence();
+#elif defined(__aarch64__) && defined(__linux__)
+    uint64_t ctr;
+    asm volatile("mrs %0, ctr_el0" : "=r"(ctr));
+    uintptr_t line = uintptr_t(4) << ((ctr >> 16) & 0xf); // DminLine: log2 of the smallest data line, in words
+    for (uintptr_t address = start / line * line; address < end; address += line) {
+        asm volatile("dc civac, %0" : : "r"(address) : "memory");
+    }
+    asm volatile("dsb ish" : : : "memory");
+#else
+    (void)end;
+    cache_evict();
+#endif
+}
+
+// The stderr line the cache-aware benchmarks print
+inline std::string cache_report() {
+    if (!cache_cold()) return "Cache: warm, each repetition finds the data the last one left in cache";
+    if (const char* instruction = cache_flush_instruction()) {
+        return std::string("Cache: cold, measured buffers flushed with ") + instruction + " before every timed repetition";
+    }
+    return "Cache: cold, " + std::to_string(cache_sweep_bytes() >> 20) + " MiB eviction sweep before every timed repetition";
+}
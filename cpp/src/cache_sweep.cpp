@@ -0,0 +1,141 @@
+#include <iostream>
+#include <fstream>
+#include <vector>
+#include <string>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+#include <cstdint>
+#include <cstdio>
+#include <cstring>
+#include <cstdlib>
+#include "cache_flush.h"
+#include "huge_pages.h"
+#include "numa_placement.h"
+
+const size_t MAX_WORKING_SET = 256 << 20; // 4 KiB .. 256 MiB in powers of two
+const size_t MIN_WORKING_SET = 4 << 10;
+const size_t STRIDES[8] = {8, 16, 32, 64, 128, 256, 1024, 4096}; // bytes
+const size_t NUM_STRIDES = sizeof(STRIDES) / sizeof(STRIDES[0]);
+const size_t ACCESSES_PER_POINT = 8 << 20;
+const size_t ELEMENT = sizeof(uint64_t);
+
+// One read-modify-write pass over the first `len` elements, touching every `step`th one
+void sweep(uint64_t* buf, size_t len, size_t step) {
+    for (size_t i = 0; i < len; i += step) {
+        buf[i]++;
+    }
+}
+
+struct Point {
+    size_t working_set;
+    size_t stride;
+    double ns_per_access;
+};
+
+std::pair<std::chrono::duration<double>, Point> measure(uint64_t* buf, size_t working_set, size_t stride) {
+    size_t len = working_set / ELEMENT;
+    size_t step = stride / ELEMENT;
+    size_t per_pass = (len + step - 1) / step;
+    size_t passes = cache_repetitions(std::max<size_t>(ACCESSES_PER_POINT / per_pass, 1));
+
+    // One untimed pass pulls the working set into whatever level it fits
+    sweep(buf, len, step);
+    std::chrono::duration<double> elapsed(0);
+    if (cache_cold()) {
+        // One pass at a time, each starting with the working set flushed out to DRAM
+        for (size_t pass = 0; pass < passes; pass++) {
+            cache_flush(buf, len * ELEMENT);
+            auto start = std::chrono::high_resolution_clock::now();
+            asm volatile("" : : "r"(buf) : "memory");
+            sweep(buf, len, step);
+            elapsed += std::chrono::high_resolution_clock::now() - start;
+        }
+    } else {
+        auto start = std::chrono::high_resolution_clock::now();
+        for (size_t pass = 0; pass < passes; pass++) {
+            // The asm barrier stops the compiler from fusing passes (e.g. one +passes when a pass is a single access)
+            asm volatile("" : : "r"(buf) : "memory");
+            sweep(buf, len, step);
+        }
+        elapsed = std::chrono::high_resolution_clock::now() - start;
+    }
+    double ns_per_access = elapsed.count() * 1e9 / static_cast<double>(passes * per_pass);
+    return {elapsed, {working_set, stride, ns_per_access}};
+}
+
+std::string size_label(size_t bytes) {
+    if (bytes >= 1 << 20) return std::to_string(bytes >> 20) + " MiB";
+    return std::to_string(bytes >> 10) + " KiB";
+}
+
+const char* parse_csv_path(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--csv") == 0) {
+            if (i + 1 >= argc) {
+                std::cerr << "--csv expects a file path" << std::endl;
+                std::exit(1);
+            }
+            return argv[i + 1];
+        }
+    }
+    return nullptr;
+}
+
+int main(int argc, char** argv) {
+    NumaPlacement placement = numa_place(argc, argv);
+    huge_pages_enable(argc, argv);
+    cache_cold_enable(argc, argv);
+    const char* csv_path = parse_csv_path(argc, argv);
+    std::vector<uint64_t> buf;
+    huge_reserve(buf, MAX_WORKING_SET / ELEMENT);
+    buf.resize(MAX_WORKING_SET / ELEMENT, 0);
+
+    std::chrono::duration<double> total(0);
+    std::vector<Point> points;
+    for (size_t working_set = MIN_WORKING_SET; working_set <= MAX_WORKING_SET; working_set *= 2) {
+        for (size_t stride : STRIDES) {
+            auto [elapsed, point] = measure(buf.data(), working_set, stride);
+            total += elapsed;
+            points.push_back(point);
+        }
+    }
+
+    fprintf(stderr, "Strided read-modify-write, rows = working set, columns = stride\n");
+    fprintf(stderr, "%11s", "ns/access");
+    for (size_t stride : STRIDES) {
+        fprintf(stderr, " %7s", (std::to_string(stride) + "B").c_str());
+    }
+    fprintf(stderr, "\n");
+    for (size_t row = 0; row < points.size(); row += NUM_STRIDES) {
+        fprintf(stderr, "%11s", size_label(points[row].working_set).c_str());
+        for (size_t k = row; k < row + NUM_STRIDES; k++) {
+            fprintf(stderr, " %7.3f", points[k].ns_per_access);
+        }
+        fprintf(stderr, "\n");
+    }
+
+    if (csv_path) {
+        std::ofstream csv(csv_path);
+        if (!csv) {
+            std::cerr << "Failed to write CSV" << std::endl;
+            return 1;
+        }
+        csv << "working_set_bytes,stride_bytes,ns_per_access\n" << std::fixed << std::setprecision(4);
+        for (const auto& p : points) {
+            csv << p.working_set << "," << p.stride << "," << p.ns_per_access << "\n";
+        }
+        std::cerr << "Wrote " << points.size() << " points to " << csv_path << std::endl;
+    }
+
+    // Every access adds one, so the sum is the exact access count
+    uint64_t checksum = 0;
+    for (uint64_t x : buf) checksum += x;
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    std::cerr << numa_report(placement) << std::endl;
+    std::cerr << huge_pages_report() << std::endl;
+    std::cerr << cache_report() << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
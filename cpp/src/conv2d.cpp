@@ -0,0 +1,192 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+
+#include "bench_measure.h"
+
+const size_t CHANNELS = 64; // Input and output channels
+const size_t HEIGHT = 224;
+const size_t WIDTH = 224;
+const size_t KERNEL = 3;
+const size_t BATCH = 8;
+const size_t NUM_THREADS = 8;
+
+const size_t PADDED_HEIGHT = HEIGHT + KERNEL - 1;
+const size_t PADDED_WIDTH = WIDTH + KERNEL - 1;
+const size_t PLANE = HEIGHT * WIDTH;
+const size_t PADDED_PLANE = PADDED_HEIGHT * PADDED_WIDTH;
+const size_t PATCH = CHANNELS * KERNEL * KERNEL; // Rows of the im2col matrix
+
+float next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return static_cast<float>(seed >> 40) / 16777216.0f - 0.5f;
+}
+
+// Inputs are stored pre-padded (zero border) in CHW layout
+std::vector<std::vector<float>> generate_images() {
+    uint64_t seed = 7;
+    std::vector<std::vector<float>> images(BATCH, std::vector<float>(CHANNELS * PADDED_PLANE, 0.0f));
+
+    for (auto& image : images) {
+        for (size_t c = 0; c < CHANNELS; c++) {
+            for (size_t y = 0; y < HEIGHT; y++) {
+                for (size_t x = 0; x < WIDTH; x++) {
+                    image[c * PADDED_PLANE + (y + 1) * PADDED_WIDTH + (x + 1)] = next_random(seed);
+                }
+            }
+        }
+    }
+
+    return images;
+}
+
+// Weights in [out_channel][in_channel][ky][kx] order
+std::vector<float> generate_weights() {
+    uint64_t seed = 42;
+    std::vector<float> weights(CHANNELS * PATCH);
+    for (auto& w : weights) {
+        w = next_random(seed) * 0.1f;
+    }
+    return weights;
+}
+
+// Runs fn(i) for i in [0, count) split into contiguous ranges across threads
+template<class F>
+void parallel_for(size_t count, F fn) {
+    std::vector<std::thread> threads;
+    size_t per_thread = count / NUM_THREADS;
+
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        size_t begin = t * per_thread;
+        size_t end = (t == NUM_THREADS - 1) ? count : (t + 1) * per_thread;
+        threads.emplace_back([begin, end, &fn] {
+            for (size_t i = begin; i < end; i++) {
+                fn(i);
+            }
+        });
+    }
+
+    for (auto& thread : threads) {
+        thread.join();
+    }
+}
+
+void conv_direct(const std::vector<float>& image, const std::vector<float>& weights, std::vector<float>& output) {
+    parallel_for(CHANNELS, [&](size_t oc) {
+        float* out = &output[oc * PLANE];
+        std::fill(out, out + PLANE, 0.0f);
+
+        for (size_t ic = 0; ic < CHANNELS; ic++) {
+            const float* plane = &image[ic * PADDED_PLANE];
+            for (size_t ky = 0; ky < KERNEL; ky++) {
+                for (size_t kx = 0; kx < KERNEL; kx++) {
+                    float w = weights[oc * PATCH + (ic * KERNEL + ky) * KERNEL + kx];
+                    for (size_t y = 0; y < HEIGHT; y++) {
+                        const float* src = &plane[(y + ky) * PADDED_WIDTH + kx];
+                        float* dst = &out[y * WIDTH];
+                        for (size_t x = 0; x < WIDTH; x++) {
+                            dst[x] += w * src[x];
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+void im2col(const std::vector<float>& image, std::vector<float>& columns) {
+    parallel_for(PATCH, [&](size_t row) {
+        size_t ic = row / (KERNEL * KERNEL);
+        size_t ky = (row / KERNEL) % KERNEL;
+        size_t kx = row % KERNEL;
+        const float* plane = &image[ic * PADDED_PLANE];
+        float* col = &columns[row * PLANE];
+
+        for (size_t y = 0; y < HEIGHT; y++) {
+            const float* src = &plane[(y + ky) * PADDED_WIDTH + kx];
+            std::copy(src, src + WIDTH, &col[y * WIDTH]);
+        }
+    });
+}
+
+// (CHANNELS x PATCH) * (PATCH x PLANE), i-k-j order
+void gemm(const std::vector<float>& weights, const std::vector<float>& columns, std::vector<float>& output) {
+    parallel_for(CHANNELS, [&](size_t oc) {
+        float* out = &output[oc * PLANE];
+        std::fill(out, out + PLANE, 0.0f);
+
+        for (size_t k = 0; k < PATCH; k++) {
+            float w = weights[oc * PATCH + k];
+            const float* col = &columns[k * PLANE];
+            for (size_t p = 0; p < PLANE; p++) {
+                out[p] += w * col[p];
+            }
+        }
+    });
+}
+
+void conv_im2col(const std::vector<float>& image, const std::vector<float>& weights,
+                 std::vector<float>& columns, std::vector<float>& output) {
+    im2col(image, columns);
+    gemm(weights, columns, output);
+}
+
+double checksum(const std::vector<float>& output) {
+    double sum = 0.0;
+    for (float v : output) {
+        sum += static_cast<double>(v);
+    }
+    return sum;
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    auto images = generate_images();
+    auto weights = generate_weights();
+    std::vector<float> output(CHANNELS * PLANE, 0.0f);
+    std::vector<float> columns(PATCH * PLANE, 0.0f);
+
+    double gflops = static_cast<double>(2 * BATCH * CHANNELS * PATCH * PLANE) / 1e9;
+
+    // Warm-up
+    conv_direct(images[0], weights, output);
+    conv_im2col(images[0], weights, columns, output);
+
+    // Benchmark direct convolution
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    double direct_checksum = 0.0;
+    for (const auto& image : images) {
+        conv_direct(image, weights, output);
+        direct_checksum += checksum(output);
+    }
+    measure->stop();
+    auto direct_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark im2col + GEMM
+    auto im2col_start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    double im2col_checksum = 0.0;
+    for (const auto& image : images) {
+        conv_im2col(image, weights, columns, output);
+        im2col_checksum += checksum(output);
+    }
+    measure->stop();
+    auto im2col_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> direct_duration = direct_end - start;
+    std::chrono::duration<double> im2col_duration = im2col_end - im2col_start;
+
+    measure->report();
+    std::cerr << std::fixed << "Direct: " << direct_duration.count() << "s (" << std::setprecision(2)
+              << gflops / direct_duration.count() << " GFLOP/s), im2col+GEMM: " << std::setprecision(6)
+              << im2col_duration.count() << "s (" << std::setprecision(2)
+              << gflops / im2col_duration.count() << " GFLOP/s)" << std::endl;
+    std::cerr << std::setprecision(6) << "Checksum: " << direct_checksum
+              << " (im2col: " << im2col_checksum << ")" << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,128 @@
+#include <iostream>
+#include <vector>
+#include <algorithm>
+#include <cmath>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t NUM_POINTS = 50'000'000;
+const int64_t RADIUS = 1LL << 29; // Keeps every cross product exact in int64_t
+const double PI = 3.14159265358979323846;
+
+struct Point {
+    int32_t x, y;
+
+    bool operator<(const Point& other) const {
+        return x < other.x || (x == other.x && y < other.y);
+    }
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// Uniform in a disk (rejection sampled), so the hull has many more vertices than a square's would
+std::vector<Point> generate_points() {
+    uint64_t seed = 31337;
+    std::vector<Point> points;
+    points.reserve(NUM_POINTS);
+    while (points.size() < NUM_POINTS) {
+        int64_t x = static_cast<int64_t>(next_random(seed) % (2 * RADIUS + 1)) - RADIUS;
+        int64_t y = static_cast<int64_t>(next_random(seed) % (2 * RADIUS + 1)) - RADIUS;
+        if (x * x + y * y <= RADIUS * RADIUS) {
+            points.push_back({static_cast<int32_t>(x), static_cast<int32_t>(y)});
+        }
+    }
+    return points;
+}
+
+// Positive when o -> a -> b turns counter-clockwise
+inline int64_t cross(const Point& o, const Point& a, const Point& b) {
+    return (static_cast<int64_t>(a.x) - o.x) * (static_cast<int64_t>(b.y) - o.y)
+         - (static_cast<int64_t>(a.y) - o.y) * (static_cast<int64_t>(b.x) - o.x);
+}
+
+// Andrew's monotone chain over points already sorted by (x, y); returns the hull counter-clockwise
+std::vector<Point> monotone_chain(const std::vector<Point>& points) {
+    if (points.size() < 3) {
+        return points;
+    }
+    std::vector<Point> hull;
+
+    // Lower hull
+    for (const Point& p : points) {
+        while (hull.size() >= 2 && cross(hull[hull.size() - 2], hull[hull.size() - 1], p) <= 0) {
+            hull.pop_back();
+        }
+        hull.push_back(p);
+    }
+
+    // Upper hull
+    size_t lower_len = hull.size() + 1;
+    for (size_t i = points.size() - 1; i-- > 0;) {
+        const Point& p = points[i];
+        while (hull.size() >= lower_len && cross(hull[hull.size() - 2], hull[hull.size() - 1], p) <= 0) {
+            hull.pop_back();
+        }
+        hull.push_back(p);
+    }
+
+    hull.pop_back(); // The last point repeats the first
+    return hull;
+}
+
+// Shoelace formula; exact because every vertex is an integer point
+int64_t twice_area(const std::vector<Point>& hull) {
+    int64_t sum = 0;
+    for (size_t i = 0; i < hull.size(); i++) {
+        const Point& a = hull[i];
+        const Point& b = hull[(i + 1) % hull.size()];
+        sum += static_cast<int64_t>(a.x) * b.y - static_cast<int64_t>(b.x) * a.y;
+    }
+    return sum;
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    std::vector<Point> points = generate_points();
+
+    // Warm-up
+    std::vector<Point> warmup(points.begin(), points.begin() + NUM_POINTS / 100);
+    std::sort(warmup.begin(), warmup.end());
+    volatile size_t warmup_size = monotone_chain(warmup).size();
+    (void)warmup_size;
+
+    // Benchmark: sort, then build the hull
+    std::vector<Point> sorted = points;
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    std::sort(sorted.begin(), sorted.end());
+    measure->stop();
+    auto sort_end = std::chrono::high_resolution_clock::now();
+
+    auto hull_start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    std::vector<Point> hull = monotone_chain(sorted);
+    measure->stop();
+    auto hull_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> sort_duration = sort_end - start;
+    std::chrono::duration<double> hull_duration = hull_end - hull_start;
+
+    // Checksum
+    int64_t area2 = twice_area(hull);
+    double disk_area = PI * static_cast<double>(RADIUS * RADIUS);
+
+    measure->report();
+    std::cerr << std::setprecision(3) << "Sort: " << sort_duration.count() << "s, hull: "
+              << hull_duration.count() << "s" << std::endl;
+    std::cerr << std::setprecision(9) << "Hull vertices: " << hull.size() << ", area / disk area: "
+              << area2 / 2.0 / disk_area << std::endl;
+    std::cerr << "Checksum: " << area2 << std::endl;
+
+    return 0;
+}
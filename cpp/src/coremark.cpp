@@ -0,0 +1,581 @@
+#include <iostream>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+#include <utility>
+
+#include "bench_measure.h"
+
+// CoreMark's performance run: a 2000-byte working set split three ways, seeds 0, 0 and 0x66
+const size_t TOTAL_DATA_SIZE = 2000;
+const size_t BLOCK_SIZE = TOTAL_DATA_SIZE / 3;
+const int16_t SEEDS[3] = {0, 0, 0x66};
+const size_t ITERATIONS = 40000;
+const size_t WARMUP_ITERATIONS = 2000;
+
+// The CRCs CoreMark's core_main.c checks the performance run against
+const uint16_t KNOWN_LIST_CRC = 0xe714;
+const uint16_t KNOWN_MATRIX_CRC = 0x1fd7;
+const uint16_t KNOWN_STATE_CRC = 0x8e3a;
+
+// Two's-complement adds, which CoreMark relies on and Rust spells wrapping_add
+int32_t wrapping_add(int32_t a, int32_t b) {
+    return static_cast<int32_t>(static_cast<uint32_t>(a) + static_cast<uint32_t>(b));
+}
+
+// ---- CRC-16 (reflected 0x8005), one bit at a time as in core_util.c ----
+
+uint16_t crcu8(uint8_t data, uint16_t crc) {
+    for (int i = 0; i < 8; i++) {
+        uint8_t x16 = (data & 1) ^ (crc & 1);
+        data >>= 1;
+        if (x16 == 1) {
+            crc ^= 0x4002;
+            crc = (crc >> 1) | 0x8000;
+        } else {
+            crc >>= 1;
+        }
+    }
+    return crc;
+}
+
+uint16_t crcu16(uint16_t value, uint16_t crc) {
+    return crcu8(static_cast<uint8_t>(value >> 8), crcu8(static_cast<uint8_t>(value), crc));
+}
+
+uint16_t crc16(int16_t value, uint16_t crc) { return crcu16(static_cast<uint16_t>(value), crc); }
+
+uint16_t crcu32(uint32_t value, uint16_t crc) {
+    return crc16(static_cast<int16_t>(value >> 16), crc16(static_cast<int16_t>(value), crc));
+}
+
+// ---- Matrix: N×N small ints, N chosen so A, B and C fit the block ----
+
+struct Matrix {
+    size_t n;
+    std::vector<int16_t> a, b;
+    std::vector<int32_t> c;
+
+    Matrix(size_t block_size, int32_t seed) {
+        size_t i = 0;
+        while (i * i * 2 * 4 < block_size) i++;
+        n = i - 1;
+        if (seed == 0) seed = 1;
+        a.assign(n * n, 0);
+        b.assign(n * n, 0);
+        c.assign(n * n, 0);
+        int32_t order = 1;
+        for (size_t k = 0; k < n * n; k++) {
+            seed = (order * seed) % 65536;
+            int32_t value = (seed + order) & 0xffff;
+            b[k] = static_cast<int16_t>(value);
+            a[k] = static_cast<int16_t>((value + order) & 0xff);
+            order++;
+        }
+    }
+
+    void add_const(int16_t value) {
+        for (auto& x : a) x = static_cast<int16_t>(x + value);
+    }
+
+    void mul_const(int16_t value) {
+        for (size_t k = 0; k < n * n; k++) c[k] = static_cast<int32_t>(a[k]) * value;
+    }
+
+    void mul_vect() {
+        for (size_t i = 0; i < n; i++) {
+            int32_t sum = 0;
+            for (size_t j = 0; j < n; j++) sum = wrapping_add(sum, static_cast<int32_t>(a[i * n + j]) * b[j]);
+            c[i] = sum;
+        }
+    }
+
+    void mul_matrix() {
+        for (size_t i = 0; i < n; i++) {
+            for (size_t j = 0; j < n; j++) {
+                int32_t sum = 0;
+                for (size_t k = 0; k < n; k++) sum = wrapping_add(sum, static_cast<int32_t>(a[i * n + k]) * b[k * n + j]);
+                c[i * n + j] = sum;
+            }
+        }
+    }
+
+    // Multiplies, then sums the product of two bit fields of each term instead of the term
+    void mul_matrix_bitextract() {
+        auto extract = [](int32_t x, int from, int bits) { return (x >> from) & ~(-1 << bits); };
+        for (size_t i = 0; i < n; i++) {
+            for (size_t j = 0; j < n; j++) {
+                int32_t sum = 0;
+                for (size_t k = 0; k < n; k++) {
+                    int32_t product = static_cast<int32_t>(a[i * n + k]) * b[k * n + j];
+                    sum = wrapping_add(sum, extract(product, 2, 4) * extract(product, 5, 7));
+                }
+                c[i * n + j] = sum;
+            }
+        }
+    }
+
+    // Scores C by how often a running sum overshoots `clip` and how often values rise
+    int16_t sum(int16_t clip) const {
+        int32_t total = 0, previous = 0;
+        int16_t score = 0;
+        for (int32_t current : c) {
+            total = wrapping_add(total, current);
+            if (total > clip) {
+                score = static_cast<int16_t>(score + 10);
+                total = 0;
+            } else if (current > previous) {
+                score = static_cast<int16_t>(score + 1);
+            }
+            previous = current;
+        }
+        return score;
+    }
+
+    uint16_t test(int16_t value) {
+        int16_t clip = static_cast<int16_t>(0xf000 | static_cast<uint16_t>(value));
+        uint16_t crc = 0;
+        add_const(value);
+        mul_const(value);
+        crc = crc16(sum(clip), crc);
+        mul_vect();
+        crc = crc16(sum(clip), crc);
+        mul_matrix();
+        crc = crc16(sum(clip), crc);
+        mul_matrix_bitextract();
+        crc = crc16(sum(clip), crc);
+        // Puts A back the way it was
+        add_const(static_cast<int16_t>(-value));
+        return crc;
+    }
+
+    uint16_t bench(int16_t seed, uint16_t crc) { return crc16(static_cast<int16_t>(test(seed)), crc); }
+};
+
+// ---- State machine: a scanner for comma-separated numbers ----
+
+enum State { START, INVALID, S1, S2, INT, FLOAT, EXPONENT, SCIENTIFIC, NUM_STATES };
+
+const char* INT_PATTERNS[4] = {"5012", "1234", "-874", "+122"};
+const char* FLOAT_PATTERNS[4] = {"35.54400", ".1234500", "-110.700", "+0.64400"};
+const char* SCIENTIFIC_PATTERNS[4] = {"5.500e+3", "-.123e-2", "-87e+832", "+0.6e-12"};
+const char* ERROR_PATTERNS[4] = {"T0.3e-1F", "-T.T++Tq", "1T3.4e4z", "34.0e-T^"};
+
+// Fills the block with comma-separated numbers, valid and not, chosen by the seed, then zeros
+std::vector<uint8_t> init_state(size_t size, int16_t seed) {
+    std::vector<uint8_t> block(size, 0);
+    size_t total = 0, next = 0;
+    const char* pattern = nullptr;
+    while (total + next + 1 < size - 1) {
+        if (next > 0) {
+            std::memcpy(&block[total], pattern, next);
+            block[total + next] = ',';
+            total += next + 1;
+        }
+        seed = static_cast<int16_t>(seed + 1);
+        size_t which = (seed >> 3) & 3;
+        switch (seed & 7) {
+            case 0: case 1: case 2: pattern = INT_PATTERNS[which]; next = 4; break;
+            case 3: case 4: pattern = FLOAT_PATTERNS[which]; next = 8; break;
+            case 5: case 6: pattern = SCIENTIFIC_PATTERNS[which]; next = 8; break;
+            default: pattern = ERROR_PATTERNS[which]; next = 8; break;
+        }
+    }
+    return block;
+}
+
+bool is_digit(uint8_t c) { return c >= '0' && c <= '9'; }
+
+// Scans one number from `pos`, leaving `pos` past its comma (or the character that made it
+// invalid), and counts the transitions out of each state
+State transition(const std::vector<uint8_t>& input, size_t& pos, uint32_t* counts) {
+    State state = START;
+    size_t i = pos;
+    while (i < input.size() && input[i] != 0 && state != INVALID) {
+        uint8_t symbol = input[i++];
+        if (symbol == ',') break;
+        bool digit = is_digit(symbol);
+        switch (state) {
+            case START:
+                if (digit) {
+                    state = INT;
+                } else if (symbol == '+' || symbol == '-') {
+                    state = S1;
+                } else if (symbol == '.') {
+                    state = FLOAT;
+                } else {
+                    state = INVALID;
+                    counts[INVALID]++;
+                }
+                counts[START]++;
+                break;
+            case S1:
+                state = digit ? INT : symbol == '.' ? FLOAT : INVALID;
+                counts[S1]++;
+                break;
+            case INT:
+                if (symbol == '.') {
+                    state = FLOAT;
+                    counts[INT]++;
+                } else if (!digit) {
+                    state = INVALID;
+                    counts[INT]++;
+                }
+                break;
+            case FLOAT:
+                if (symbol == 'E' || symbol == 'e') {
+                    state = S2;
+                    counts[FLOAT]++;
+                } else if (!digit) {
+                    state = INVALID;
+                    counts[FLOAT]++;
+                }
+                break;
+            case S2:
+                state = symbol == '+' || symbol == '-' ? EXPONENT : INVALID;
+                counts[S2]++;
+                break;
+            case EXPONENT:
+                state = digit ? SCIENTIFIC : INVALID;
+                counts[EXPONENT]++;
+                break;
+            case SCIENTIFIC:
+                if (!digit) {
+                    state = INVALID;
+                    counts[INVALID]++;
+                }
+                break;
+            default:
+                break;
+        }
+    }
+    pos = i;
+    return state;
+}
+
+void scan(const std::vector<uint8_t>& block, uint32_t* finals, uint32_t* transitions) {
+    size_t pos = 0;
+    while (pos < block.size() && block[pos] != 0) finals[transition(block, pos, transitions)]++;
+}
+
+// XORs every `step`th byte but the commas with `mask`, as CoreMark corrupts and restores its input
+void corrupt(std::vector<uint8_t>& block, size_t step, uint8_t mask) {
+    for (size_t i = 0; i < block.size(); i += step) {
+        if (block[i] != ',') block[i] ^= mask;
+    }
+}
+
+uint16_t bench_state(std::vector<uint8_t>& block, int16_t seed1, int16_t seed2, int16_t step, uint16_t crc) {
+    uint32_t finals[NUM_STATES] = {}, transitions[NUM_STATES] = {};
+    scan(block, finals, transitions);
+    corrupt(block, static_cast<size_t>(step), static_cast<uint8_t>(seed1));
+    scan(block, finals, transitions);
+    corrupt(block, static_cast<size_t>(step), static_cast<uint8_t>(seed2));
+    for (int i = 0; i < NUM_STATES; i++) {
+        crc = crcu32(finals[i], crc);
+        crc = crcu32(transitions[i], crc);
+    }
+    return crc;
+}
+
+// ---- Linked list: nodes in an arena, linked by index ----
+
+const uint32_t NIL = UINT32_MAX;
+
+struct Node {
+    uint32_t next;
+    int16_t data16;
+    int16_t idx;
+};
+
+struct Results {
+    int16_t seeds[3];
+    std::vector<Node> nodes;
+    uint32_t list;
+    Matrix matrix;
+    std::vector<uint8_t> state;
+    uint16_t crc = 0, crc_list = 0, crc_matrix = 0, crc_state = 0;
+};
+
+// The low 3 bits pick a kernel, bits 3-6 are its input, and bit 7 marks the low 7 bits as a
+// cached result. Running a kernel chains its CRC into the running one
+int16_t calc_func(int16_t& data, Results& res) {
+    if ((data >> 7) & 1) return data & 0x7f;
+    int16_t dtype = (data >> 3) & 0xf;
+    dtype |= dtype << 4;
+    int16_t result;
+    switch (data & 7) {
+        case 0:
+            result = static_cast<int16_t>(bench_state(res.state, res.seeds[0], res.seeds[1], std::max<int16_t>(dtype, 0x22), res.crc));
+            if (res.crc_state == 0) res.crc_state = static_cast<uint16_t>(result);
+            break;
+        case 1:
+            result = static_cast<int16_t>(res.matrix.bench(dtype, res.crc));
+            if (res.crc_matrix == 0) res.crc_matrix = static_cast<uint16_t>(result);
+            break;
+        default:
+            result = data;
+            break;
+    }
+    res.crc = crcu16(static_cast<uint16_t>(result), res.crc);
+    result &= 0x7f;
+    data = static_cast<int16_t>((data & 0xff00) | 0x80 | result);
+    return result;
+}
+
+enum class Order {
+    Index,   // by idx, first restoring each data16's low byte from its high byte
+    Complex, // by calc_func of data16, which runs the matrix and state kernels
+};
+
+int compare(std::vector<Node>& nodes, uint32_t a, uint32_t b, Order order, Results& res) {
+    if (order == Order::Index) {
+        for (uint32_t n : {a, b}) {
+            int16_t& d = nodes[n].data16;
+            d = static_cast<int16_t>((d & 0xff00) | ((d >> 8) & 0xff));
+        }
+        return nodes[a].idx - nodes[b].idx;
+    }
+    int x = calc_func(nodes[a].data16, res);
+    int y = calc_func(nodes[b].data16, res);
+    return x - y;
+}
+
+// Bottom-up merge sort of a singly linked list, as in core_list_mergesort
+uint32_t mergesort(std::vector<Node>& nodes, uint32_t list, Order order, Results& res) {
+    size_t insize = 1;
+    while (true) {
+        uint32_t p = list, tail = NIL;
+        list = NIL;
+        size_t merges = 0;
+        while (p != NIL) {
+            merges++;
+            uint32_t q = p;
+            size_t psize = 0;
+            for (size_t i = 0; i < insize; i++) {
+                psize++;
+                q = nodes[q].next;
+                if (q == NIL) break;
+            }
+            size_t qsize = insize;
+            while (psize > 0 || (qsize > 0 && q != NIL)) {
+                uint32_t e;
+                if (psize == 0) {
+                    e = q;
+                    q = nodes[q].next;
+                    qsize--;
+                } else if (qsize == 0 || q == NIL || compare(nodes, p, q, order, res) <= 0) {
+                    e = p;
+                    p = nodes[p].next;
+                    psize--;
+                } else {
+                    e = q;
+                    q = nodes[q].next;
+                    qsize--;
+                }
+                if (tail != NIL) {
+                    nodes[tail].next = e;
+                } else {
+                    list = e;
+                }
+                tail = e;
+            }
+            p = q;
+        }
+        nodes[tail].next = NIL;
+        if (merges <= 1) return list;
+        insize *= 2;
+    }
+}
+
+// By idx when it is non-negative, otherwise by the low byte of data16
+uint32_t find(const std::vector<Node>& nodes, uint32_t list, int16_t idx, int16_t data) {
+    while (list != NIL) {
+        const Node& node = nodes[list];
+        if ((idx >= 0 && node.idx == idx) || (idx < 0 && (node.data16 & 0xff) == data)) break;
+        list = node.next;
+    }
+    return list;
+}
+
+uint32_t reverse(std::vector<Node>& nodes, uint32_t list) {
+    uint32_t next = NIL;
+    while (list != NIL) {
+        uint32_t rest = nodes[list].next;
+        nodes[list].next = next;
+        next = list;
+        list = rest;
+    }
+    return next;
+}
+
+void swap_data(std::vector<Node>& nodes, uint32_t a, uint32_t b) {
+    std::swap(nodes[a].data16, nodes[b].data16);
+    std::swap(nodes[a].idx, nodes[b].idx);
+}
+
+// Unlinks the node after `item`, swapping their contents so `item` keeps its place in the list
+uint32_t remove(std::vector<Node>& nodes, uint32_t item) {
+    uint32_t removed = nodes[item].next;
+    swap_data(nodes, item, removed);
+    nodes[item].next = nodes[removed].next;
+    nodes[removed].next = NIL;
+    return removed;
+}
+
+void undo_remove(std::vector<Node>& nodes, uint32_t removed, uint32_t modified) {
+    swap_data(nodes, removed, modified);
+    nodes[removed].next = nodes[modified].next;
+    nodes[modified].next = removed;
+}
+
+// A head node, a sentinel with the largest idx, and as many items as fit the block, the first
+// fifth indexed in order and the rest pseudo-randomly; init() sorts them by idx
+std::vector<Node> init_list(size_t block_size, int16_t seed) {
+    // A 64-bit CoreMark's node: two pointers plus the 4-byte data it points to
+    size_t per_item = 16 + 4;
+    size_t size = block_size / per_item - 2;
+    std::vector<Node> nodes = {{NIL, static_cast<int16_t>(0x8080), 0}};
+    auto insert = [&](int16_t data16, int16_t idx) {
+        // core_list_insert_new stops one short of the block's capacity
+        if (nodes.size() + 1 < size) {
+            nodes.push_back({nodes[0].next, data16, idx});
+            nodes[0].next = static_cast<uint32_t>(nodes.size() - 1);
+        }
+    };
+    insert(static_cast<int16_t>(0xffff), 0x7fff);
+    for (uint16_t i = 0; i < size; i++) {
+        uint16_t pattern = (static_cast<uint16_t>(seed) ^ i) & 0xf;
+        uint16_t dat = static_cast<uint16_t>((pattern << 3) | (i & 7));
+        insert(static_cast<int16_t>((dat << 8) | dat), 0);
+    }
+    uint32_t finder = nodes[0].next;
+    uint16_t i = 1;
+    while (nodes[finder].next != NIL) {
+        if (i < size / 5) {
+            nodes[finder].idx = static_cast<int16_t>(i++);
+        } else {
+            uint16_t pattern = i++ ^ static_cast<uint16_t>(seed);
+            nodes[finder].idx = static_cast<int16_t>(0x3fff & (((i & 7) << 8) | pattern));
+        }
+        finder = nodes[finder].next;
+    }
+    return nodes;
+}
+
+uint16_t bench_list(Results& res, int16_t finder_idx) {
+    std::vector<Node> nodes = std::move(res.nodes);
+    uint32_t list = res.list;
+    uint16_t retval = 0, found = 0, missed = 0;
+    int16_t idx = finder_idx, data = 0;
+    // Finds seed3 values, reversing the list after each and moving the node after a hit to the front
+    for (int16_t i = 0; i < res.seeds[2]; i++) {
+        data = i & 0xff;
+        uint32_t hit = find(nodes, list, idx, data);
+        list = reverse(nodes, list);
+        if (hit == NIL) {
+            missed++;
+            retval += (nodes[nodes[list].next].data16 >> 8) & 1;
+        } else {
+            found++;
+            if (nodes[hit].data16 & 1) retval += (nodes[hit].data16 >> 9) & 1;
+            uint32_t after = nodes[hit].next;
+            if (after != NIL) {
+                nodes[hit].next = nodes[after].next;
+                nodes[after].next = nodes[list].next;
+                nodes[list].next = after;
+            }
+        }
+        if (idx >= 0) idx++;
+    }
+    retval = static_cast<uint16_t>(retval + found * 4 - missed);
+    if (finder_idx > 0) list = mergesort(nodes, list, Order::Complex, res);
+    uint32_t removed = remove(nodes, nodes[list].next);
+    // CoreMark CRCs the head's data once per node from here on, not each node's own
+    uint32_t finder = find(nodes, list, idx, data);
+    if (finder == NIL) finder = nodes[list].next;
+    while (finder != NIL) {
+        retval = crc16(nodes[list].data16, retval);
+        finder = nodes[finder].next;
+    }
+    undo_remove(nodes, removed, nodes[list].next);
+    list = mergesort(nodes, list, Order::Index, res);
+    finder = nodes[list].next;
+    while (finder != NIL) {
+        retval = crc16(nodes[list].data16, retval);
+        finder = nodes[finder].next;
+    }
+    res.nodes = std::move(nodes);
+    res.list = list;
+    return retval;
+}
+
+Results init(const int16_t* seeds) {
+    Results res{{seeds[0], seeds[1], seeds[2]}, {}, 0,
+                Matrix(BLOCK_SIZE, static_cast<int32_t>(seeds[0]) | (static_cast<int32_t>(seeds[1]) << 16)),
+                init_state(BLOCK_SIZE, seeds[0])};
+    std::vector<Node> nodes = init_list(BLOCK_SIZE, seeds[0]);
+    res.list = mergesort(nodes, 0, Order::Index, res);
+    res.nodes = std::move(nodes);
+    return res;
+}
+
+// Each iteration runs the list benchmark twice, once finding by index and once by value; its
+// sorts run the matrix and state kernels
+uint16_t iterate(Results& res, size_t iterations) {
+    res.crc = res.crc_list = res.crc_matrix = res.crc_state = 0;
+    for (size_t i = 0; i < iterations; i++) {
+        // bench_list updates res.crc itself, so it has to run before res.crc is read
+        uint16_t crc = bench_list(res, 1);
+        res.crc = crcu16(crc, res.crc);
+        crc = bench_list(res, -1);
+        res.crc = crcu16(crc, res.crc);
+        if (i == 0) res.crc_list = res.crc;
+    }
+    return res.crc;
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    // Read through a volatile, as CoreMark reads its seeds, so nothing is precomputed
+    volatile int16_t seed_source[3] = {SEEDS[0], SEEDS[1], SEEDS[2]};
+    int16_t seeds[3] = {seed_source[0], seed_source[1], seed_source[2]};
+
+    // Warm-up
+    Results warmup = init(seeds);
+    iterate(warmup, WARMUP_ITERATIONS);
+
+    // Benchmark
+    Results res = init(seeds);
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    uint16_t crc = iterate(res, ITERATIONS);
+    measure->stop();
+    std::chrono::duration<double> duration = std::chrono::high_resolution_clock::now() - start;
+
+    struct { const char* name; uint16_t crc, known; } crcs[] = {
+        {"list", res.crc_list, KNOWN_LIST_CRC}, {"matrix", res.crc_matrix, KNOWN_MATRIX_CRC},
+        {"state", res.crc_state, KNOWN_STATE_CRC}};
+    for (const auto& c : crcs) {
+        if (c.crc != c.known) {
+            fprintf(stderr, "%s CRC %#06x does not match CoreMark's %#06x\n", c.name, c.crc, c.known);
+            return 1;
+        }
+    }
+
+    measure->report();
+    fprintf(stderr, "Data: %zu bytes (%zu list nodes, %zux%zu matrices, %zu-byte state input), seeds %d, %d, %#x\n",
+            TOTAL_DATA_SIZE, res.nodes.size(), res.matrix.n, res.matrix.n, res.state.size(), seeds[0], seeds[1], seeds[2]);
+    fprintf(stderr, "Iterations: %zu in %.3fs (%.0f iterations/s)\n", ITERATIONS, duration.count(),
+            static_cast<double>(ITERATIONS) / duration.count());
+    fprintf(stderr, "CRCs: list %#06x, matrix %#06x, state %#06x (CoreMark's performance-run values)\n",
+            res.crc_list, res.crc_matrix, res.crc_state);
+    fprintf(stderr, "Checksum: %#06x\n", crc);
+
+    return 0;
+}
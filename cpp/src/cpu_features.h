@@ -0,0 +1,134 @@
+// Which instructions the CPU offers, and which of them each kernel actually ran, in the same
+// stderr lines as rust/bench_cpu:
+//
+//     CPU features: avx2 fma bmi2 sha aes crc32 (x86_64; not available: avx512f)
+//     Code path: compress: scalar (portable rounds)
+//
+// cpu_features_report() lists the features detected at runtime (__builtin_cpu_supports on x86,
+// getauxval on aarch64 Linux, sysctl on macOS), and cpu_path() formats one kernel's path.
+// build.py copies both lines into the benchmark's entry in the results file.
+#pragma once
+
+#include <string>
+#include <utility>
+#include <vector>
+#if defined(__aarch64__) && defined(__linux__)
+#include <asm/hwcap.h>
+#include <sys/auxv.h>
+#endif
+#ifdef __APPLE__
+#include <sys/sysctl.h>
+#endif
+
+enum class CpuPath { Scalar, Simd, Hw };
+
+inline const char* cpu_path_name(CpuPath path) {
+    switch (path) {
+        case CpuPath::Scalar: return "scalar";
+        case CpuPath::Simd: return "simd";
+        case CpuPath::Hw: return "hw";
+    }
+    return "";
+}
+
+#ifdef __APPLE__
+inline bool cpu_sysctl_flag(const char* name) {
+    int value = 0;
+    size_t len = sizeof(value);
+    return sysctlbyname(name, &value, &len, nullptr, 0) == 0 && value != 0;
+}
+#endif
+
+// Every feature this architecture's benchmarks might dispatch on, and whether the CPU has it
+inline std::vector<std::pair<const char*, bool>> cpu_detect() {
+#if defined(__x86_64__)
+    __builtin_cpu_init();
+    return {
+        {"avx2", __builtin_cpu_supports("avx2") != 0},
+        {"fma", __builtin_cpu_supports("fma") != 0},
+        {"avx512f", __builtin_cpu_supports("avx512f") != 0},
+        {"bmi2", __builtin_cpu_supports("bmi2") != 0},
+        {"sha", __builtin_cpu_supports("sha") != 0},
+        {"aes", __builtin_cpu_supports("aes") != 0},
+        // The crc32 instruction is part of SSE4.2
+        {"crc32", __builtin_cpu_supports("sse4.2") != 0},
+    };
+#elif defined(__aarch64__) && defined(__linux__)
+    unsigned long hwcap = getauxval(AT_HWCAP), hwcap2 = getauxval(AT_HWCAP2);
+    return {
+        {"neon", (hwcap & HWCAP_ASIMD) != 0},
+        {"sve", (hwcap & HWCAP_SVE) != 0},
+        {"sve2", (hwcap2 & HWCAP2_SVE2) != 0},
+        {"sha2", (hwcap & HWCAP_SHA2) != 0},
+        {"aes", (hwcap & HWCAP_AES) != 0},
+        {"crc", (hwcap & HWCAP_CRC32) != 0},
+    };
+#elif defined(__aarch64__) && defined(__APPLE__)
+    // Apple Silicon has no SVE
+    return {
+        {"neon", cpu_sysctl_flag("hw.optional.neon")},
+        {"sve", false},
+        {"sve2", false},
+        {"sha2", cpu_sysctl_flag("hw.optional.arm.FEAT_SHA256")},
+        {"aes", cpu_sysctl_flag("hw.optional.arm.FEAT_AES")},
+        {"crc", cpu_sysctl_flag("hw.optional.armv8_crc32")},
+    };
+#else
+    return {};
+#endif
+}
+
+inline bool cpu_has(const std::string& feature) {
+    for (const auto& [name, present] : cpu_detect()) {
+        if (present && feature == name) return true;
+    }
+    return false;
+}
+
+// The vector instructions the GCC/Clang vector extensions were compiled down to
+inline const char* cpu_simd_isa() {
+#if defined(__x86_64__) && defined(__AVX512F__)
+    return "AVX-512";
+#elif defined(__x86_64__) && defined(__AVX2__)
+    return "AVX2";
+#elif defined(__x86_64__)
+    return "SSE2";
+#elif defined(__aarch64__) && defined(__ARM_NEON)
+    return "NEON";
+#elif defined(__wasm_simd128__)
+    return "SIMD128";
+#else
+    return "no vector unit, emulated";
+#endif
+}
+
+inline const char* cpu_arch() {
+#if defined(__x86_64__)
+    return "x86_64";
+#elif defined(__aarch64__)
+    return "aarch64";
+#elif defined(__wasm32__)
+    return "wasm32";
+#else
+    return "unknown";
+#endif
+}
+
+// The stderr line listing the runtime-detected features
+inline std::string cpu_features_report() {
+    auto features = cpu_detect();
+    if (features.empty()) return std::string("CPU features: not detected on ") + cpu_arch();
+    std::string available, missing;
+    for (const auto& [name, present] : features) {
+        std::string& list = present ? available : missing;
+        list += (list.empty() ? "" : " ") + std::string(name);
+    }
+    std::string line = "CPU features: " + (available.empty() ? "none" : available) + " (" + cpu_arch();
+    if (!missing.empty()) line += "; not available: " + missing;
+    return line + ")";
+}
+
+// The stderr line for one kernel's code path, e.g. cpu_path("compress", CpuPath::Scalar, "portable rounds")
+inline std::string cpu_path(const std::string& kernel, CpuPath path, const std::string& detail) {
+    return "Code path: " + kernel + ": " + cpu_path_name(path) + " (" + detail + ")";
+}
@@ -0,0 +1,301 @@
+#include <iostream>
+#include <sstream>
+#include <vector>
+#include <string>
+#include <string_view>
+#include <charconv>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <ctime>
+
+const size_t NUM_TIMESTAMPS = 2'000'000;
+const size_t PASSES = 5; // so 10M timestamps per variant
+const int64_t FIRST_SECOND = 946'684'800; // 2000-01-01T00:00:00Z
+const int64_t LAST_SECOND = 2'208'988'800; // 2040-01-01T00:00:00Z
+// The zone every timestamp is converted to for the report, UTC+09:00. A fixed offset, as the
+// named-zone databases (the OS tzdata behind zoned_time, chrono-tz) differ by platform
+const int64_t REPORT_OFFSET = 9 * 3600;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t splitmix64(uint64_t& seed) {
+    seed += 0x9E3779B97F4A7C15ULL;
+    uint64_t z = seed;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+int64_t floor_div(int64_t a, int64_t b) {
+    return a / b - (a % b != 0 && (a < 0) != (b < 0));
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date, and back (Howard Hinnant's algorithms)
+int64_t days_from_civil(int64_t year, unsigned month, unsigned day) {
+    int64_t y = month <= 2 ? year - 1 : year;
+    int64_t era = floor_div(y, 400);
+    int64_t yoe = y - era * 400;
+    int64_t mp = (month + 9) % 12;
+    int64_t doy = (153 * mp + 2) / 5 + day - 1;
+    int64_t doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    return era * 146'097 + doe - 719'468;
+}
+
+void civil_from_days(int64_t days, int64_t& year, unsigned& month, unsigned& day) {
+    int64_t z = days + 719'468;
+    int64_t era = floor_div(z, 146'097);
+    int64_t doe = z - era * 146'097;
+    int64_t yoe = (doe - doe / 1460 + doe / 36'524 - doe / 146'096) / 365;
+    int64_t doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    int64_t mp = (5 * doy + 2) / 153;
+    day = static_cast<unsigned>(doy - (153 * mp + 2) / 5 + 1);
+    month = static_cast<unsigned>(mp < 10 ? mp + 3 : mp - 9);
+    year = yoe + era * 400 + (month <= 2);
+}
+
+// RFC3339 timestamps between 2000 and 2040 in local time at a random quarter-hour offset from
+// -12:00 to +14:00 (a quarter of them "Z"), half with milliseconds, one per line
+std::string generate(size_t n) {
+    uint64_t seed = 3339;
+    std::string text;
+    text.reserve(n * 30);
+    char buf[64];
+    for (size_t i = 0; i < n; i++) {
+        int64_t utc = FIRST_SECOND + static_cast<int64_t>(splitmix64(seed) % (LAST_SECOND - FIRST_SECOND));
+        bool has_millis = splitmix64(seed) % 2 == 0;
+        uint64_t millis = has_millis ? splitmix64(seed) % 1000 : 0;
+        bool has_offset = splitmix64(seed) % 4 != 0;
+        int64_t offset = has_offset ? static_cast<int64_t>(splitmix64(seed) % 105) * 15 * 60 - 12 * 3600 : 0;
+        int64_t local = utc + offset;
+        int64_t year;
+        unsigned month, day;
+        civil_from_days(floor_div(local, 86'400), year, month, day);
+        int64_t second_of_day = local - floor_div(local, 86'400) * 86'400;
+        snprintf(buf, sizeof(buf), "%04lld-%02u-%02uT%02lld:%02lld:%02lld", static_cast<long long>(year), month, day,
+                 static_cast<long long>(second_of_day / 3600), static_cast<long long>(second_of_day / 60 % 60),
+                 static_cast<long long>(second_of_day % 60));
+        text += buf;
+        if (has_millis) {
+            snprintf(buf, sizeof(buf), ".%03llu", static_cast<unsigned long long>(millis));
+            text += buf;
+        }
+        if (has_offset) {
+            snprintf(buf, sizeof(buf), "%c%02lld:%02lld", offset < 0 ? '-' : '+', static_cast<long long>(std::llabs(offset) / 3600),
+                     static_cast<long long>(std::llabs(offset) / 60 % 60));
+            text += buf;
+        } else {
+            text += 'Z';
+        }
+        text += '\n';
+    }
+    return text;
+}
+
+// What every variant reports: each timestamp as an instant, the durations between neighbours,
+// and the calendar fields after conversion to REPORT_OFFSET
+struct Totals {
+    uint64_t instants = 0; // wrapping sum of Unix milliseconds
+    uint64_t gaps = 0;     // sum of the absolute gaps to the previous timestamp, in milliseconds
+    uint64_t local = 0;    // sum of the local times as yyyymmddhh
+    uint64_t weekdays = 0; // sum of the local weekdays, Monday = 0
+
+    void add(int64_t unix_millis, uint64_t gap, int64_t year, unsigned month, unsigned day, unsigned hour, unsigned weekday) {
+        instants += static_cast<uint64_t>(unix_millis);
+        gaps += gap;
+        local += ((static_cast<uint64_t>(year) * 100 + month) * 100 + day) * 100 + hour;
+        weekdays += weekday;
+    }
+
+    uint64_t checksum() const { return instants + gaps + local + weekdays; }
+
+    bool operator==(const Totals&) const = default;
+};
+
+uint64_t abs_gap(int64_t t, int64_t previous) {
+    return static_cast<uint64_t>(t > previous ? t - previous : previous - t);
+}
+
+// Calls f on each line of newline-terminated text
+template <typename F>
+void for_each_line(std::string_view text, F f) {
+    size_t start = 0;
+    for (size_t end; (end = text.find('\n', start)) != std::string_view::npos; start = end + 1) {
+        f(text.substr(start, end - start));
+    }
+}
+
+unsigned digits(std::string_view s) {
+    unsigned value = 0;
+    for (char c : s) value = value * 10 + static_cast<unsigned>(c - '0');
+    return value;
+}
+
+// The fraction and offset after the seconds: milliseconds (truncating any digits beyond them)
+// and the offset in seconds
+void parse_tail(std::string_view tail, int64_t& millis, int64_t& offset) {
+    size_t i = 0;
+    millis = 0;
+    if (tail[i] == '.') {
+        i++;
+        for (int64_t scale = 100; i < tail.size() && tail[i] >= '0' && tail[i] <= '9'; i++, scale /= 10) {
+            millis += (tail[i] - '0') * scale;
+        }
+    }
+    if (tail[i] == 'Z' || tail[i] == 'z') {
+        offset = 0;
+    } else {
+        offset = digits(tail.substr(i + 1, 2)) * 3600 + digits(tail.substr(i + 4, 2)) * 60;
+        if (tail[i] == '-') offset = -offset;
+    }
+}
+
+// The iostream route: std::get_time for the fields and timegm/gmtime_r for the conversions
+Totals run_get_time(std::string_view text) {
+    Totals totals;
+    int64_t previous = 0;
+    bool first = true;
+    std::istringstream in;
+    for_each_line(text, [&](std::string_view line) {
+        std::tm tm = {};
+        in.str(std::string(line.substr(0, 19)));
+        in.clear();
+        in >> std::get_time(&tm, "%Y-%m-%dT%H:%M:%S");
+        int64_t millis, offset;
+        parse_tail(line.substr(19), millis, offset);
+        int64_t seconds = static_cast<int64_t>(timegm(&tm)) - offset;
+        int64_t t = seconds * 1000 + millis;
+        uint64_t gap = first ? 0 : abs_gap(t, previous);
+        previous = t, first = false;
+        time_t local_seconds = static_cast<time_t>(seconds + REPORT_OFFSET);
+        std::tm local;
+        gmtime_r(&local_seconds, &local);
+        totals.add(t, gap, local.tm_year + 1900, local.tm_mon + 1, local.tm_mday, local.tm_hour, (local.tm_wday + 6) % 7);
+    });
+    return totals;
+}
+
+// C++20 <chrono> calendar types. std::chrono::parse is not in libc++, so the fields are read
+// with from_chars
+Totals run_std_chrono(std::string_view text) {
+    using namespace std::chrono;
+    Totals totals;
+    sys_time<milliseconds> previous;
+    bool first = true;
+    for_each_line(text, [&](std::string_view line) {
+        int fields[6];
+        const size_t starts[6] = {0, 5, 8, 11, 14, 17};
+        for (size_t f = 0; f < 6; f++) {
+            std::from_chars(line.data() + starts[f], line.data() + starts[f] + (f == 0 ? 4 : 2), fields[f]);
+        }
+        int64_t millis, offset;
+        parse_tail(line.substr(19), millis, offset);
+        sys_days date = year{fields[0]} / fields[1] / fields[2];
+        sys_time<milliseconds> t = date + hours{fields[3]} + minutes{fields[4]} + seconds{fields[5]} +
+                                   milliseconds{millis} - seconds{offset};
+        uint64_t gap = first ? 0 : static_cast<uint64_t>(abs(t - previous).count());
+        previous = t, first = false;
+        sys_seconds local = floor<seconds>(t) + seconds{REPORT_OFFSET};
+        sys_days local_day = floor<days>(local);
+        year_month_day ymd{local_day};
+        hh_mm_ss time_of_day{local - local_day};
+        totals.add(t.time_since_epoch().count(), gap, static_cast<int>(ymd.year()), static_cast<unsigned>(ymd.month()),
+                   static_cast<unsigned>(ymd.day()), static_cast<unsigned>(time_of_day.hours().count()),
+                   weekday{local_day}.iso_encoding() - 1);
+    });
+    return totals;
+}
+
+// Fixed field positions and no validation
+int64_t parse_rfc3339(std::string_view line) {
+    int64_t days = days_from_civil(digits(line.substr(0, 4)), digits(line.substr(5, 2)), digits(line.substr(8, 2)));
+    int64_t seconds = days * 86'400 + digits(line.substr(11, 2)) * 3600 + digits(line.substr(14, 2)) * 60 +
+                      digits(line.substr(17, 2));
+    int64_t millis, offset;
+    parse_tail(line.substr(19), millis, offset);
+    return (seconds - offset) * 1000 + millis;
+}
+
+Totals run_hand(std::string_view text) {
+    Totals totals;
+    int64_t previous = 0;
+    bool first = true;
+    for_each_line(text, [&](std::string_view line) {
+        int64_t t = parse_rfc3339(line);
+        uint64_t gap = first ? 0 : abs_gap(t, previous);
+        previous = t, first = false;
+        int64_t local = floor_div(t, 1000) + REPORT_OFFSET;
+        int64_t days = floor_div(local, 86'400);
+        int64_t year;
+        unsigned month, day;
+        civil_from_days(days, year, month, day);
+        unsigned hour = static_cast<unsigned>((local - days * 86'400) / 3600);
+        unsigned weekday = static_cast<unsigned>(((days + 3) % 7 + 7) % 7); // 1970-01-01 was a Thursday
+        totals.add(t, gap, year, month, day, hour, weekday);
+    });
+    return totals;
+}
+
+using Variant = Totals (*)(std::string_view);
+
+struct NamedVariant {
+    const char* name;
+    Variant variant;
+};
+
+const NamedVariant VARIANTS[] = {
+    {"get_time", run_get_time},
+    {"std::chrono", run_std_chrono},
+    {"hand-rolled", run_hand},
+};
+
+struct Run {
+    Duration duration;
+    Totals totals;
+};
+
+// The barrier on the input every pass stops the compiler computing one pass and reusing it
+Run run(Variant variant, std::string_view text, size_t passes) {
+    Run run;
+    auto start = Clock::now();
+    for (size_t p = 0; p < passes; p++) {
+        asm volatile("" : : "r"(text.data()) : "memory");
+        run.totals = variant(text);
+    }
+    run.duration = Clock::now() - start;
+    return run;
+}
+
+int main() {
+    std::string text = generate(NUM_TIMESTAMPS);
+    size_t warmup_end = 0;
+    for (size_t i = 0; i <= NUM_TIMESTAMPS / 100; i++) warmup_end = text.find('\n', warmup_end) + 1;
+
+    // Warm-up
+    for (const auto& v : VARIANTS) v.variant(std::string_view(text).substr(0, warmup_end));
+
+    // Benchmark; the first variant's totals are the reference the others have to reproduce
+    std::vector<Run> runs;
+    Duration total_duration(0);
+    for (const auto& v : VARIANTS) {
+        runs.push_back(run(v.variant, text, PASSES));
+        total_duration += runs.back().duration;
+    }
+    bool all_match = true;
+    for (const Run& r : runs) all_match &= r.totals == runs[0].totals;
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Timestamps: %zu RFC3339 (%.0f MB) x %zu passes per variant, reported at UTC+%02lld:00\n",
+            NUM_TIMESTAMPS, text.size() / 1e6, PASSES, static_cast<long long>(REPORT_OFFSET / 3600));
+    for (size_t i = 0; i < runs.size(); i++) {
+        double rate = static_cast<double>(NUM_TIMESTAMPS * PASSES) / runs[i].duration.count();
+        fprintf(stderr, "  %-12s %.3fs (%.1f M timestamps/s)\n", VARIANTS[i].name, runs[i].duration.count(), rate / 1e6);
+    }
+    std::cerr << "Checksum: " << runs[0].totals.checksum() << " (all variants match: " << (all_match ? "true" : "false") << ")"
+              << std::endl;
+
+    return 0;
+}
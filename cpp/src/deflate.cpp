@@ -0,0 +1,158 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <stdexcept>
+#include <zlib.h>
+
+const size_t CORPUS_BYTES = 32 << 20; // 32MB of log lines
+const int COMPRESSION_LEVELS[2] = {1, 6}; // zlib's fastest and default levels
+const size_t VOCABULARY_SIZE = 50'000;
+const uint64_t NUM_REQUEST_IDS = 100'000;
+const std::string CONSONANTS = "bcdfghklmnprstvz";
+const std::string VOWELS = "aeiou";
+const char* LEVELS[4] = {"INFO", "DEBUG", "WARN", "ERROR"};
+const char* SERVICES[8] = {"auth", "billing", "gateway", "search", "storage", "queue", "mailer", "cache"};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+void push_two_digits(std::string& out, uint64_t n) {
+    out += static_cast<char>('0' + n / 10);
+    out += static_cast<char>('0' + n % 10);
+}
+
+// Same log-line generator as word_count: "12:34:56 WARN [billing] tokens... req=0x1a2b\n"
+std::string generate_corpus() {
+    uint64_t seed = 1234567;
+    std::vector<std::string> vocabulary(VOCABULARY_SIZE);
+    for (auto& word : vocabulary) {
+        uint64_t syllables = 2 + next_random(seed) % 3;
+        for (uint64_t s = 0; s < syllables; s++) {
+            word += CONSONANTS[next_random(seed) % CONSONANTS.size()];
+            word += VOWELS[next_random(seed) % VOWELS.size()];
+        }
+    }
+
+    std::vector<double> cdf;
+    cdf.reserve(VOCABULARY_SIZE);
+    double total = 0.0;
+    for (size_t rank = 0; rank < VOCABULARY_SIZE; rank++) {
+        total += 1.0 / static_cast<double>(rank + 1);
+        cdf.push_back(total);
+    }
+
+    std::string corpus;
+    corpus.reserve(CORPUS_BYTES + 256);
+    while (corpus.size() < CORPUS_BYTES) {
+        push_two_digits(corpus, next_random(seed) % 24);
+        corpus += ':';
+        push_two_digits(corpus, next_random(seed) % 60);
+        corpus += ':';
+        push_two_digits(corpus, next_random(seed) % 60);
+        corpus += ' ';
+
+        uint64_t level = next_random(seed) % 100;
+        corpus += LEVELS[level < 70 ? 0 : level < 85 ? 1 : level < 97 ? 2 : 3];
+        corpus += " [";
+        corpus += SERVICES[next_random(seed) % 8];
+        corpus += "]";
+
+        uint64_t words = 5 + next_random(seed) % 11;
+        for (uint64_t w = 0; w < words; w++) {
+            double u = static_cast<double>(next_random(seed)) / static_cast<double>(1ULL << 31) * total;
+            size_t rank = std::min<size_t>(std::lower_bound(cdf.begin(), cdf.end(), u) - cdf.begin(),
+                                           VOCABULARY_SIZE - 1);
+            corpus += ' ';
+            corpus += vocabulary[rank];
+        }
+
+        corpus += " req=0x";
+        uint64_t id = next_random(seed) % NUM_REQUEST_IDS;
+        for (int shift = 4; shift >= 0; shift--) {
+            corpus += "0123456789abcdef"[(id >> (shift * 4)) & 0xf];
+        }
+        corpus += '\n';
+    }
+
+    corpus.resize(CORPUS_BYTES);
+    return corpus;
+}
+
+std::vector<uint8_t> zlib_compress(const std::string& data, int level) {
+    uLongf len = compressBound(data.size());
+    std::vector<uint8_t> out(len);
+    int rc = compress2(out.data(), &len, reinterpret_cast<const Bytef*>(data.data()), data.size(), level);
+    if (rc != Z_OK) {
+        throw std::runtime_error("zlib compress2 failed");
+    }
+    out.resize(len);
+    return out;
+}
+
+std::string zlib_decompress(const std::vector<uint8_t>& data, size_t original_len) {
+    uLongf len = original_len;
+    std::string out(original_len, '\0');
+    int rc = uncompress(reinterpret_cast<Bytef*>(out.data()), &len, data.data(), data.size());
+    if (rc != Z_OK) {
+        throw std::runtime_error("zlib uncompress failed");
+    }
+    out.resize(len);
+    return out;
+}
+
+struct LevelResult {
+    size_t compressed_len;
+    std::chrono::duration<double> compress;
+    std::chrono::duration<double> decompress;
+    bool round_trip_ok;
+};
+
+LevelResult run_level(const std::string& corpus, int level) {
+    auto start = std::chrono::high_resolution_clock::now();
+    auto compressed = zlib_compress(corpus, level);
+    auto compress_end = std::chrono::high_resolution_clock::now();
+    std::string restored = zlib_decompress(compressed, corpus.size());
+    auto end = std::chrono::high_resolution_clock::now();
+    return {compressed.size(), compress_end - start, end - compress_end, restored == corpus};
+}
+
+int main() {
+    std::string corpus = generate_corpus();
+    double megabytes = corpus.size() / 1e6;
+
+    // Warm-up
+    run_level(corpus.substr(0, corpus.size() / 100), 6);
+
+    // Benchmark
+    std::vector<LevelResult> results;
+    for (int level : COMPRESSION_LEVELS) {
+        results.push_back(run_level(corpus, level));
+    }
+
+    std::chrono::duration<double> total_duration{0};
+    bool round_trips_ok = true;
+    for (const auto& r : results) {
+        total_duration += r.compress + r.decompress;
+        round_trips_ok = round_trips_ok && r.round_trip_ok;
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(1) << "Corpus: " << megabytes << " MB of log lines" << std::endl;
+    for (size_t i = 0; i < results.size(); i++) {
+        const auto& r = results[i];
+        std::cerr << std::setprecision(3) << "zlib         level " << COMPRESSION_LEVELS[i] << ": ratio "
+                  << r.compressed_len / (megabytes * 1e6) << std::setprecision(1) << ", compress "
+                  << megabytes / r.compress.count() << " MB/s, decompress " << megabytes / r.decompress.count()
+                  << " MB/s" << std::endl;
+    }
+    std::cerr << "Checksum: zlib sizes " << results[0].compressed_len << " / " << results[1].compressed_len
+              << " (round trips ok: " << std::boolalpha << round_trips_ok << ")" << std::endl;
+
+    return 0;
+}
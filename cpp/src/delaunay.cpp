@@ -0,0 +1,258 @@
+#include <iostream>
+#include <vector>
+#include <algorithm>
+#include <utility>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t NUM_POINTS = 2'000'000;
+const uint32_t COORD_BITS = 20; // Points on a 2^20 x 2^20 integer grid
+const int64_t SUPER_EXTENT = 1LL << 26; // Super-triangle corners, far outside the grid
+const uint32_t NONE = UINT32_MAX;
+
+struct Point {
+    int64_t x, y;
+};
+
+// Vertices counter-clockwise; neighbor[i] is across the edge opposite vertex i
+struct Triangle {
+    uint32_t v[3];
+    uint32_t neighbor[3];
+};
+
+// Cavity edge (a, b), counter-clockwise as seen from inside, facing the surviving `outer`
+struct BoundaryEdge {
+    uint32_t a, b, outer;
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+std::vector<Point> generate_points() {
+    uint64_t seed = 8128;
+    uint64_t mask = (1ULL << COORD_BITS) - 1;
+    std::vector<Point> points(NUM_POINTS);
+    for (auto& p : points) {
+        p.x = static_cast<int64_t>(next_random(seed) & mask);
+        p.y = static_cast<int64_t>(next_random(seed) & mask);
+    }
+    return points;
+}
+
+// Position along a Hilbert curve; inserting in this order keeps point-location walks short
+uint64_t hilbert_index(Point p) {
+    int64_t n = 1LL << COORD_BITS;
+    int64_t x = p.x, y = p.y;
+    uint64_t d = 0;
+    for (int64_t s = n / 2; s > 0; s /= 2) {
+        int64_t rx = (x & s) > 0;
+        int64_t ry = (y & s) > 0;
+        d += static_cast<uint64_t>(s * s * ((3 * rx) ^ ry));
+        if (ry == 0) {
+            if (rx == 1) {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            std::swap(x, y);
+        }
+    }
+    return d;
+}
+
+// Positive when a -> b -> c turns counter-clockwise; exact in int64_t for these coordinates
+inline int64_t orient(Point a, Point b, Point c) {
+    return (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+}
+
+// Positive when d lies strictly inside the circumcircle of counter-clockwise a, b, c; exact in __int128
+inline bool in_circle(Point a, Point b, Point c, Point d) {
+    int64_t adx = a.x - d.x, ady = a.y - d.y;
+    int64_t bdx = b.x - d.x, bdy = b.y - d.y;
+    int64_t cdx = c.x - d.x, cdy = c.y - d.y;
+    __int128 a_lift = adx * adx + ady * ady;
+    __int128 b_lift = bdx * bdx + bdy * bdy;
+    __int128 c_lift = cdx * cdx + cdy * cdy;
+    __int128 det = a_lift * (bdx * cdy - cdx * bdy)
+                 + b_lift * (cdx * ady - adx * cdy)
+                 + c_lift * (adx * bdy - bdx * ady);
+    return det > 0;
+}
+
+struct Triangulation {
+    std::vector<Point> points;
+    std::vector<Triangle> triangles;
+    // Insertion stamp per triangle marks membership of the current cavity
+    std::vector<uint32_t> stamp;
+    std::vector<uint32_t> stack;
+    std::vector<uint32_t> cavity;
+    std::vector<BoundaryEdge> boundary;
+    uint32_t last = 0;
+
+    // Starts from a single super-triangle whose corners are the last three points
+    explicit Triangulation(std::vector<Point> pts) : points(std::move(pts)) {
+        uint32_t n = static_cast<uint32_t>(points.size());
+        points.push_back({-SUPER_EXTENT, -SUPER_EXTENT});
+        points.push_back({SUPER_EXTENT, -SUPER_EXTENT});
+        points.push_back({0, SUPER_EXTENT});
+        triangles.push_back({{n, n + 1, n + 2}, {NONE, NONE, NONE}});
+        stamp.push_back(0);
+    }
+
+    bool in_circumcircle(uint32_t t, Point p) const {
+        const uint32_t* v = triangles[t].v;
+        return in_circle(points[v[0]], points[v[1]], points[v[2]], p);
+    }
+
+    // Visibility walk from the most recently created triangle
+    uint32_t locate(Point p) const {
+        uint32_t t = last;
+        while (true) {
+            const Triangle& tri = triangles[t];
+            bool moved = false;
+            for (int i = 0; i < 3; i++) {
+                Point a = points[tri.v[(i + 1) % 3]];
+                Point b = points[tri.v[(i + 2) % 3]];
+                if (orient(a, b, p) < 0) {
+                    t = tri.neighbor[i];
+                    moved = true;
+                    break;
+                }
+            }
+            if (!moved) {
+                return t;
+            }
+        }
+    }
+
+    // Bowyer-Watson step: carve out every triangle whose circumcircle holds the point, then fan it back in
+    void insert(uint32_t index, uint32_t current_stamp) {
+        Point p = points[index];
+        uint32_t start = locate(p);
+
+        cavity.clear();
+        boundary.clear();
+        stamp[start] = current_stamp;
+        stack.push_back(start);
+        while (!stack.empty()) {
+            uint32_t t = stack.back();
+            stack.pop_back();
+            cavity.push_back(t);
+            Triangle tri = triangles[t];
+            for (int i = 0; i < 3; i++) {
+                uint32_t nb = tri.neighbor[i];
+                if (nb != NONE && stamp[nb] == current_stamp) {
+                    continue;
+                }
+                if (nb != NONE && in_circumcircle(nb, p)) {
+                    stamp[nb] = current_stamp;
+                    stack.push_back(nb);
+                } else {
+                    boundary.push_back({tri.v[(i + 1) % 3], tri.v[(i + 2) % 3], nb});
+                }
+            }
+        }
+
+        // Reuse the cavity's slots first; a cavity of k triangles always refills with k + 2
+        size_t first_new = cavity.size();
+        for (size_t i = 0; i < boundary.size(); i++) {
+            uint32_t slot;
+            if (i < first_new) {
+                slot = cavity[i];
+            } else {
+                triangles.push_back({{0, 0, 0}, {NONE, NONE, NONE}});
+                stamp.push_back(0);
+                slot = static_cast<uint32_t>(triangles.size() - 1);
+                cavity.push_back(slot);
+            }
+            const BoundaryEdge& edge = boundary[i];
+            triangles[slot] = {{index, edge.a, edge.b}, {edge.outer, NONE, NONE}};
+            if (edge.outer != NONE) {
+                // Match by the vertex opposite the shared edge: slot ids may already be reused
+                Triangle& outer = triangles[edge.outer];
+                int j = 0;
+                while (outer.v[j] == edge.a || outer.v[j] == edge.b) j++;
+                outer.neighbor[j] = slot;
+            }
+        }
+
+        // Link the fan: triangle (p, a, b) meets the triangle that starts at b across edge (b, p)
+        for (size_t i = 0; i < boundary.size(); i++) {
+            uint32_t t = cavity[i];
+            uint32_t b = boundary[i].b;
+            size_t j = 0;
+            while (boundary[j].a != b) j++;
+            uint32_t next = cavity[j];
+            triangles[t].neighbor[1] = next;
+            triangles[next].neighbor[2] = t;
+        }
+        last = cavity[0];
+    }
+};
+
+Triangulation triangulate(const std::vector<Point>& points, size_t count) {
+    // Hilbert order, dropping duplicate points (they'd produce degenerate cavities)
+    std::vector<std::pair<uint64_t, Point>> order(count);
+    for (size_t i = 0; i < count; i++) {
+        order[i] = {hilbert_index(points[i]), points[i]};
+    }
+    std::sort(order.begin(), order.end(), [](const auto& a, const auto& b) { return a.first < b.first; });
+    order.erase(std::unique(order.begin(), order.end(), [](const auto& a, const auto& b) { return a.first == b.first; }),
+                order.end());
+
+    std::vector<Point> sorted(order.size());
+    for (size_t i = 0; i < order.size(); i++) {
+        sorted[i] = order[i].second;
+    }
+    Triangulation tri(std::move(sorted));
+    for (uint32_t i = 0; i < order.size(); i++) {
+        tri.insert(i, i + 1);
+    }
+    return tri;
+}
+
+// Triangles not touching the super-triangle, and twice their total area
+std::pair<size_t, int64_t> summarize(const Triangulation& tri) {
+    uint32_t first_super = static_cast<uint32_t>(tri.points.size() - 3);
+    size_t count = 0;
+    int64_t area2 = 0;
+    for (const Triangle& t : tri.triangles) {
+        if (t.v[0] < first_super && t.v[1] < first_super && t.v[2] < first_super) {
+            count++;
+            area2 += orient(tri.points[t.v[0]], tri.points[t.v[1]], tri.points[t.v[2]]);
+        }
+    }
+    return {count, area2};
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    std::vector<Point> points = generate_points();
+
+    // Warm-up
+    volatile size_t warmup = triangulate(points, NUM_POINTS / 100).triangles.size();
+    (void)warmup;
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    Triangulation tri = triangulate(points, NUM_POINTS);
+    measure->stop();
+    auto end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> duration = end - start;
+
+    // Checksum
+    auto [count, area2] = summarize(tri);
+    size_t unique = tri.points.size() - 3;
+
+    measure->report();
+    std::cerr << std::fixed << std::setprecision(0) << "Points: " << unique << " unique, triangles: " << count << ", "
+              << unique / duration.count() << " points/s" << std::endl;
+    std::cerr << "Checksum: " << area2 << " (triangles: " << count << ")" << std::endl;
+
+    return 0;
+}
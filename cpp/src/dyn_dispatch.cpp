@@ -0,0 +1,204 @@
+#include <iostream>
+#include <vector>
+#include <memory>
+#include <variant>
+#include <cmath>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t NUM_SHAPES = 10'000'000;
+const size_t NUM_PASSES = 10; // 100M shape evaluations per variant
+const double PI = 3.14159265358979323846;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+double random_dimension(uint64_t& seed) {
+    return static_cast<double>(next_random(seed) % 1000) / 100.0 + 1.0;
+}
+
+// Concrete shapes have non-virtual members so templates and std::visit can inline them
+struct Circle {
+    double radius;
+    double area() const { return PI * radius * radius; }
+    double perimeter() const { return 2.0 * PI * radius; }
+};
+
+struct Rectangle {
+    double width, height;
+    double area() const { return width * height; }
+    double perimeter() const { return 2.0 * (width + height); }
+};
+
+struct RightTriangle {
+    double a, b;
+    double area() const { return 0.5 * a * b; }
+    double perimeter() const { return a + b + std::sqrt(a * a + b * b); }
+};
+
+// Classic OOP hierarchy dispatched through a vtable
+struct Shape {
+    virtual ~Shape() = default;
+    virtual double area() const = 0;
+    virtual double perimeter() const = 0;
+};
+
+template<class T>
+struct VirtualShape final : Shape {
+    T shape;
+    explicit VirtualShape(T s) : shape(s) {}
+    double area() const override { return shape.area(); }
+    double perimeter() const override { return shape.perimeter(); }
+};
+
+// Closed set of shapes dispatched with std::visit instead of a vtable
+using ShapeVariant = std::variant<Circle, Rectangle, RightTriangle>;
+
+// Shape types are interleaved randomly so the virtual variant can't be devirtualized by position
+std::vector<ShapeVariant> generate_shapes() {
+    uint64_t seed = 2024;
+    std::vector<ShapeVariant> shapes;
+    shapes.reserve(NUM_SHAPES);
+    for (size_t i = 0; i < NUM_SHAPES; i++) {
+        switch (next_random(seed) % 3) {
+            case 0:
+                shapes.emplace_back(Circle{random_dimension(seed)});
+                break;
+            case 1: {
+                double width = random_dimension(seed);
+                shapes.emplace_back(Rectangle{width, random_dimension(seed)});
+                break;
+            }
+            default: {
+                double a = random_dimension(seed);
+                shapes.emplace_back(RightTriangle{a, random_dimension(seed)});
+                break;
+            }
+        }
+    }
+    return shapes;
+}
+
+std::vector<std::unique_ptr<Shape>> boxed(const std::vector<ShapeVariant>& shapes) {
+    std::vector<std::unique_ptr<Shape>> result;
+    result.reserve(shapes.size());
+    for (const auto& s : shapes) {
+        std::visit([&](const auto& shape) {
+            using T = std::decay_t<decltype(shape)>;
+            result.push_back(std::make_unique<VirtualShape<T>>(shape));
+        }, s);
+    }
+    return result;
+}
+
+// One homogeneous vector per concrete type, the layout templates push you towards
+struct ShapeGroups {
+    std::vector<Circle> circles;
+    std::vector<Rectangle> rectangles;
+    std::vector<RightTriangle> triangles;
+};
+
+ShapeGroups grouped(const std::vector<ShapeVariant>& shapes) {
+    ShapeGroups groups;
+    for (const auto& s : shapes) {
+        if (auto c = std::get_if<Circle>(&s)) groups.circles.push_back(*c);
+        else if (auto r = std::get_if<Rectangle>(&s)) groups.rectangles.push_back(*r);
+        else groups.triangles.push_back(std::get<RightTriangle>(s));
+    }
+    return groups;
+}
+
+double run_virtual(const std::vector<std::unique_ptr<Shape>>& shapes, size_t count) {
+    double total = 0.0;
+    for (size_t pass = 0; pass < NUM_PASSES; pass++) {
+        for (size_t i = 0; i < count; i++) {
+            total += shapes[i]->area() + shapes[i]->perimeter();
+        }
+    }
+    return total;
+}
+
+double run_variant(const std::vector<ShapeVariant>& shapes, size_t count) {
+    double total = 0.0;
+    for (size_t pass = 0; pass < NUM_PASSES; pass++) {
+        for (size_t i = 0; i < count; i++) {
+            total += std::visit([](const auto& s) { return s.area() + s.perimeter(); }, shapes[i]);
+        }
+    }
+    return total;
+}
+
+template<class S>
+double sum_template(const S* shapes, size_t count) {
+    double total = 0.0;
+    for (size_t i = 0; i < count; i++) {
+        total += shapes[i].area() + shapes[i].perimeter();
+    }
+    return total;
+}
+
+double run_template(const ShapeGroups& groups) {
+    double total = 0.0;
+    for (size_t pass = 0; pass < NUM_PASSES; pass++) {
+        total += sum_template(groups.circles.data(), groups.circles.size());
+        total += sum_template(groups.rectangles.data(), groups.rectangles.size());
+        total += sum_template(groups.triangles.data(), groups.triangles.size());
+    }
+    return total;
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    auto shapes = generate_shapes();
+    auto virtual_shapes = boxed(shapes);
+    auto groups = grouped(shapes);
+
+    // Warm-up
+    volatile double warmup = run_virtual(virtual_shapes, NUM_SHAPES / 100)
+                           + run_variant(shapes, NUM_SHAPES / 100)
+                           + sum_template(groups.circles.data(), NUM_SHAPES / 300);
+    (void)warmup;
+
+    // Benchmark virtual calls
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    double virtual_checksum = run_virtual(virtual_shapes, NUM_SHAPES);
+    measure->stop();
+    auto virtual_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark std::variant dispatch
+    auto variant_start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    double variant_checksum = run_variant(shapes, NUM_SHAPES);
+    measure->stop();
+    auto variant_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark templates
+    auto template_start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    double template_checksum = run_template(groups);
+    measure->stop();
+    auto template_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> virtual_duration = virtual_end - start;
+    std::chrono::duration<double> variant_duration = variant_end - variant_start;
+    std::chrono::duration<double> template_duration = template_end - template_start;
+    double evaluations = static_cast<double>(NUM_SHAPES * NUM_PASSES);
+
+    measure->report();
+    std::cerr << std::fixed << "virtual: " << virtual_duration.count() << "s (" << std::setprecision(0)
+              << evaluations / virtual_duration.count() << " shapes/s), variant: " << std::setprecision(6)
+              << variant_duration.count() << "s (" << std::setprecision(0)
+              << evaluations / variant_duration.count() << " shapes/s), template: " << std::setprecision(6)
+              << template_duration.count() << "s (" << std::setprecision(0)
+              << evaluations / template_duration.count() << " shapes/s)" << std::endl;
+    std::cerr << std::setprecision(6) << "Checksum: " << virtual_checksum << " (variant: " << variant_checksum
+              << ", template: " << template_checksum << ")" << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,140 @@
+#include <iostream>
+#include <vector>
+#include <array>
+#include <cstdint>
+#include <chrono>
+#include <iomanip>
+#include <stdexcept>
+#include <sodium.h>
+
+const size_t NUM_KEYS = 1'000;
+const size_t NUM_MESSAGES = 100'000;
+const size_t MESSAGE_LEN = 64;
+const size_t NUM_EXCHANGES = 100'000;
+
+using Message = std::array<uint8_t, MESSAGE_LEN>;
+using Signature = std::array<uint8_t, crypto_sign_BYTES>;
+using Key32 = std::array<uint8_t, 32>;
+
+struct KeyPair {
+    std::array<uint8_t, crypto_sign_PUBLICKEYBYTES> public_key;
+    std::array<uint8_t, crypto_sign_SECRETKEYBYTES> secret_key;
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+template <size_t N>
+std::array<uint8_t, N> random_bytes(uint64_t& seed) {
+    std::array<uint8_t, N> out;
+    for (auto& byte : out) {
+        byte = static_cast<uint8_t>(next_random(seed));
+    }
+    return out;
+}
+
+// FNV-1a over every output in order, so any single differing byte changes the checksum
+template <typename Container>
+uint64_t fold_bytes(const std::vector<Container>& outputs) {
+    uint64_t h = 0xcbf29ce484222325ULL;
+    for (const auto& out : outputs) {
+        for (uint8_t byte : out) {
+            h = (h ^ byte) * 0x100000001b3ULL;
+        }
+    }
+    return h;
+}
+
+std::vector<Signature> sign_all(const std::vector<KeyPair>& keys, const std::vector<Message>& messages, size_t count) {
+    std::vector<Signature> signatures(count);
+    for (size_t i = 0; i < count; i++) {
+        crypto_sign_detached(signatures[i].data(), nullptr, messages[i].data(), MESSAGE_LEN,
+                             keys[i % NUM_KEYS].secret_key.data());
+    }
+    return signatures;
+}
+
+size_t verify_all(const std::vector<KeyPair>& keys, const std::vector<Message>& messages,
+                  const std::vector<Signature>& signatures) {
+    size_t valid = 0;
+    for (size_t i = 0; i < signatures.size(); i++) {
+        if (crypto_sign_verify_detached(signatures[i].data(), messages[i].data(), MESSAGE_LEN,
+                                        keys[i % NUM_KEYS].public_key.data()) == 0) {
+            valid++;
+        }
+    }
+    return valid;
+}
+
+// Each exchange combines a fresh secret with one of the long-term peers' public keys
+std::vector<Key32> exchange_all(const std::vector<Key32>& secrets, const std::vector<Key32>& peers, size_t count) {
+    std::vector<Key32> shared(count);
+    for (size_t i = 0; i < count; i++) {
+        if (crypto_scalarmult(shared[i].data(), secrets[i].data(), peers[i % NUM_KEYS].data()) != 0) {
+            throw std::runtime_error("X25519 produced an all-zero shared secret");
+        }
+    }
+    return shared;
+}
+
+int main() {
+    if (sodium_init() < 0) {
+        throw std::runtime_error("libsodium failed to initialize");
+    }
+
+    uint64_t seed = 25519;
+    std::vector<KeyPair> keys(NUM_KEYS);
+    for (auto& k : keys) {
+        auto key_seed = random_bytes<crypto_sign_SEEDBYTES>(seed);
+        crypto_sign_seed_keypair(k.public_key.data(), k.secret_key.data(), key_seed.data());
+    }
+    std::vector<Message> messages(NUM_MESSAGES);
+    for (auto& m : messages) {
+        m = random_bytes<MESSAGE_LEN>(seed);
+    }
+    std::vector<Key32> peers(NUM_KEYS);
+    for (auto& p : peers) {
+        auto secret = random_bytes<32>(seed);
+        crypto_scalarmult_base(p.data(), secret.data());
+    }
+    std::vector<Key32> secrets(NUM_EXCHANGES);
+    for (auto& s : secrets) {
+        s = random_bytes<32>(seed);
+    }
+
+    // Warm-up
+    auto warmup = sign_all(keys, messages, NUM_KEYS);
+    verify_all(keys, messages, warmup);
+    exchange_all(secrets, peers, NUM_KEYS);
+
+    // Benchmark (single-threaded, to measure per-operation cost)
+    auto start = std::chrono::high_resolution_clock::now();
+    auto signatures = sign_all(keys, messages, NUM_MESSAGES);
+    auto end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> sign_duration = end - start;
+
+    start = std::chrono::high_resolution_clock::now();
+    size_t valid = verify_all(keys, messages, signatures);
+    end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> verify_duration = end - start;
+
+    start = std::chrono::high_resolution_clock::now();
+    auto shared = exchange_all(secrets, peers, NUM_EXCHANGES);
+    end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> exchange_duration = end - start;
+
+    auto total_duration = sign_duration + verify_duration + exchange_duration;
+
+    // Checksum: Ed25519 signatures and X25519 secrets are deterministic, so both languages must agree
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(0) << "Ed25519 sign: " << NUM_MESSAGES / sign_duration.count()
+              << " ops/s, verify: " << NUM_MESSAGES / verify_duration.count() << " ops/s (" << valid << " of "
+              << NUM_MESSAGES << " valid)" << std::endl;
+    std::cerr << "X25519 key exchange: " << NUM_EXCHANGES / exchange_duration.count() << " ops/s" << std::endl;
+    std::cerr << "Checksum: " << std::hex << std::setfill('0') << std::setw(16) << fold_bytes(signatures)
+              << " (X25519: " << std::setw(16) << fold_bytes(shared) << ")" << std::endl;
+
+    return 0;
+}
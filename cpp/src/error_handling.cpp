@@ -0,0 +1,146 @@
+#include <iostream>
+#include <exception>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+const uint64_t NUM_CALLS = 10'000'000;
+const uint32_t CALL_DEPTH = 16;
+const uint64_t ERROR_PERCENT = 1;
+
+// Error payload carried up the whole chain, like a parse error with context
+struct ParseError : std::exception {
+    uint64_t input;
+    uint32_t code;
+    ParseError(uint64_t input, uint32_t code) : input(input), code(code) {}
+    const char* what() const noexcept override { return "parse error"; }
+};
+
+inline uint64_t mix64(uint64_t x) {
+    x += 0x9E3779B97F4A7C15ULL;
+    x = (x ^ (x >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    x = (x ^ (x >> 27)) * 0x94D049BB133111EBULL;
+    return x ^ (x >> 31);
+}
+
+inline uint64_t rotate_left(uint64_t v, uint32_t n) {
+    n &= 63;
+    return n == 0 ? v : (v << n) | (v >> (64 - n));
+}
+
+// Error-code style: returns 0 on success, otherwise a nonzero code; fails for ~error_percent% of inputs
+uint32_t leaf_code(uint64_t x, uint64_t error_percent, uint64_t& out) {
+    uint64_t h = mix64(x);
+    if (h % 100 < error_percent) {
+        return static_cast<uint32_t>(h >> 32) | 1;
+    }
+    out = h;
+    return 0;
+}
+
+// Every level does a little work after its callee returns, so the chain can't become a loop
+uint32_t chain_code(uint32_t depth, uint64_t x, uint64_t error_percent, uint64_t& out) {
+    if (depth == 0) {
+        return leaf_code(x, error_percent, out);
+    }
+    uint64_t v;
+    if (uint32_t err = chain_code(depth - 1, x, error_percent, v)) {
+        return err;
+    }
+    out = rotate_left(v, depth) ^ depth;
+    return 0;
+}
+
+uint64_t leaf_throw(uint64_t x, uint64_t error_percent) {
+    uint64_t h = mix64(x);
+    if (h % 100 < error_percent) {
+        throw ParseError(x, static_cast<uint32_t>(h >> 32) | 1);
+    }
+    return h;
+}
+
+uint64_t chain_throw(uint32_t depth, uint64_t x, uint64_t error_percent) {
+    if (depth == 0) {
+        return leaf_throw(x, error_percent);
+    }
+    return rotate_left(chain_throw(depth - 1, x, error_percent), depth) ^ depth;
+}
+
+// Returns (checksum, errors seen)
+std::pair<uint64_t, uint64_t> run_code(uint64_t calls, uint64_t error_percent) {
+    volatile uint32_t depth_input = CALL_DEPTH;
+    uint32_t depth = depth_input;
+    uint64_t checksum = 0, errors = 0;
+    for (uint64_t i = 0; i < calls; i++) {
+        uint64_t v;
+        if (uint32_t err = chain_code(depth, i, error_percent, v)) {
+            errors++;
+            checksum += i ^ err;
+        } else {
+            checksum += v;
+        }
+    }
+    return {checksum, errors};
+}
+
+std::pair<uint64_t, uint64_t> run_exceptions(uint64_t calls, uint64_t error_percent) {
+    volatile uint32_t depth_input = CALL_DEPTH;
+    uint32_t depth = depth_input;
+    uint64_t checksum = 0, errors = 0;
+    for (uint64_t i = 0; i < calls; i++) {
+        try {
+            checksum += chain_throw(depth, i, error_percent);
+        } catch (const ParseError& e) {
+            errors++;
+            checksum += e.input ^ e.code;
+        }
+    }
+    return {checksum, errors};
+}
+
+int main() {
+    volatile uint64_t error_input = ERROR_PERCENT, no_errors_input = 0;
+    uint64_t error_percent = error_input, no_errors = no_errors_input;
+
+    // Warm-up
+    volatile uint64_t warmup = run_code(NUM_CALLS / 100, error_percent).first
+                             + run_exceptions(NUM_CALLS / 100, error_percent).first;
+    (void)warmup;
+
+    // Benchmark error codes
+    auto start = std::chrono::high_resolution_clock::now();
+    auto [code_happy, code_happy_errors] = run_code(NUM_CALLS, no_errors);
+    auto code_happy_end = std::chrono::high_resolution_clock::now();
+
+    auto code_error_start = std::chrono::high_resolution_clock::now();
+    auto [code_checksum, code_errors] = run_code(NUM_CALLS, error_percent);
+    auto code_error_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark exceptions
+    auto throw_happy_start = std::chrono::high_resolution_clock::now();
+    auto [throw_happy, throw_happy_errors] = run_exceptions(NUM_CALLS, no_errors);
+    auto throw_happy_end = std::chrono::high_resolution_clock::now();
+
+    auto throw_error_start = std::chrono::high_resolution_clock::now();
+    auto [throw_checksum, throw_errors] = run_exceptions(NUM_CALLS, error_percent);
+    auto throw_error_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> code_happy_duration = code_happy_end - start;
+    std::chrono::duration<double> code_error_duration = code_error_end - code_error_start;
+    std::chrono::duration<double> throw_happy_duration = throw_happy_end - throw_happy_start;
+    std::chrono::duration<double> throw_error_duration = throw_error_end - throw_error_start;
+    std::chrono::duration<double> total_duration = code_happy_duration + code_error_duration
+                                                 + throw_happy_duration + throw_error_duration;
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << std::fixed << "Error codes: " << code_happy_duration.count() << "s happy, "
+              << code_error_duration.count() << "s with " << ERROR_PERCENT << "% errors; exceptions: "
+              << throw_happy_duration.count() << "s happy, " << throw_error_duration.count() << "s with "
+              << ERROR_PERCENT << "% errors" << std::endl;
+    std::cerr << "Calls: " << NUM_CALLS << " x depth " << CALL_DEPTH << ", errors: " << code_errors
+              << " (exceptions: " << throw_errors << ")" << std::endl;
+    std::cerr << "Checksum: " << code_checksum << " (exceptions: " << throw_checksum << ", happy: "
+              << code_happy << " / " << throw_happy << ")" << std::endl;
+
+    return 0;
+}
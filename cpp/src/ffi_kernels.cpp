@@ -0,0 +1,146 @@
+#include "bench_kernels.h"
+
+#include <iostream>
+#include <vector>
+#include <array>
+#include <string>
+#include <chrono>
+#include <iomanip>
+#include <sstream>
+#include <cstdint>
+#include <cstdlib>
+
+const uint32_t MANDELBROT_SIZE = 1024;
+const uint32_t MAX_ITER = 1000;
+const uint64_t PRIME_LIMIT = 100'000'000;
+const size_t SORT_SIZE = 10'000'000;
+const size_t HASH_SIZE = 100'000'000;
+const int REPEATS = 3;
+
+using Clock = std::chrono::high_resolution_clock;
+
+struct Run {
+    double seconds;
+    uint64_t result;
+};
+
+// Fastest of REPEATS runs after an untimed one. `setup` builds each run's input and `check` reduces
+// its output to a comparable u64, both outside the timing
+template <typename Setup, typename Kernel, typename Check>
+Run best(Setup setup, Kernel kernel, Check check) {
+    {
+        auto input = setup();
+        kernel(input);
+    }
+    Run run{1e300, 0};
+    for (int r = 0; r < REPEATS; r++) {
+        auto input = setup();
+        auto start = Clock::now();
+        auto output = kernel(input);
+        run.seconds = std::min(run.seconds, std::chrono::duration<double>(Clock::now() - start).count());
+        run.result = check(output);
+    }
+    return run;
+}
+
+// Position-weighted, so any misplaced element changes it
+uint64_t sorted_checksum(const std::vector<int32_t>& v) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < v.size(); i++) {
+        if (i > 0 && v[i - 1] > v[i]) {
+            std::cerr << "output is not sorted" << std::endl;
+            exit(1);
+        }
+        sum += static_cast<uint64_t>(i) ^ static_cast<uint64_t>(static_cast<int64_t>(v[i]));
+    }
+    return sum;
+}
+
+uint64_t digest_prefix(const std::array<uint8_t, 32>& digest) {
+    uint64_t prefix = 0;
+    for (int i = 0; i < 8; i++) {
+        prefix = (prefix << 8) | digest[i];
+    }
+    return prefix;
+}
+
+std::string hex(const std::array<uint8_t, 32>& digest) {
+    std::ostringstream out;
+    for (uint8_t b : digest) {
+        out << std::hex << std::setw(2) << std::setfill('0') << static_cast<int>(b);
+    }
+    return out.str();
+}
+
+// The Rust kernels come from the bench_kernels cdylib (cargo build --release in rust/), the C++
+// ones from kernels.cpp linked into this binary
+int main() {
+    std::vector<int32_t> unsorted(SORT_SIZE);
+    for (size_t i = 0; i < SORT_SIZE; i++) {
+        unsorted[i] = static_cast<int32_t>((i * 1103515245 + 12345) % 2147483648);
+    }
+    std::vector<uint8_t> bytes(HASH_SIZE);
+    for (size_t i = 0; i < HASH_SIZE; i++) {
+        bytes[i] = static_cast<uint8_t>(i % 256);
+    }
+
+    auto none = [] { return 0; };
+    auto copy = [&] { return unsorted; };
+    auto id = [](uint64_t x) { return x; };
+    using Mandelbrot = uint64_t (*)(uint32_t, uint32_t, uint32_t);
+    using Primes = uint64_t (*)(uint64_t);
+    using Sort = void (*)(int32_t*, size_t);
+    using Hash = void (*)(const uint8_t*, size_t, uint8_t*);
+    auto runs = [&](Mandelbrot mandelbrot, Primes primes, Sort sort, Hash hash) {
+        return std::array<Run, 4>{
+            best(none, [&](int) { return mandelbrot(MANDELBROT_SIZE, MANDELBROT_SIZE, MAX_ITER); }, id),
+            best(none, [&](int) { return primes(PRIME_LIMIT); }, id),
+            best(copy, [&](std::vector<int32_t>& v) {
+                sort(v.data(), v.size());
+                return std::move(v);
+            }, sorted_checksum),
+            best(none, [&](int) {
+                std::array<uint8_t, 32> digest;
+                hash(bytes.data(), bytes.size(), digest.data());
+                return digest;
+            }, digest_prefix),
+        };
+    };
+    std::array<Run, 4> cpp_runs = runs(cpp_mandelbrot, cpp_count_primes, cpp_sort_i32, cpp_sha256);
+    std::array<Run, 4> rust_runs = runs(rust_mandelbrot, rust_count_primes, rust_sort_i32, rust_sha256);
+
+    std::array<std::string, 4> names = {
+        "mandelbrot (" + std::to_string(MANDELBROT_SIZE) + "x" + std::to_string(MANDELBROT_SIZE) + ", "
+            + std::to_string(MAX_ITER) + " iterations)",
+        "prime sieve (to " + std::to_string(PRIME_LIMIT) + ")",
+        "quicksort (" + std::to_string(SORT_SIZE / 1'000'000) + "M i32)",
+        "sha256 (" + std::to_string(HASH_SIZE / 1'000'000) + " MB)",
+    };
+    for (size_t k = 0; k < names.size(); k++) {
+        if (cpp_runs[k].result != rust_runs[k].result) {
+            std::cerr << names[k] << ": C++ gave " << cpp_runs[k].result << ", Rust gave " << rust_runs[k].result << std::endl;
+            exit(1);
+        }
+    }
+
+    std::array<uint8_t, 32> digest;
+    cpp_sha256(bytes.data(), bytes.size(), digest.data());
+
+    double total = 0;
+    for (const Run& run : cpp_runs) {
+        total += run.seconds;
+    }
+    std::cout << std::fixed << std::setprecision(6) << total << std::endl;
+    std::cerr << std::fixed << std::setprecision(6);
+    std::cerr << "In-process, single-threaded, best of " << REPEATS
+              << " (C++ kernels via C++, Rust kernels via the C ABI):" << std::endl;
+    for (size_t k = 0; k < names.size(); k++) {
+        std::cerr << "  " << std::left << std::setw(40) << names[k] << std::right
+                  << " C++ " << cpp_runs[k].seconds << "s, Rust " << rust_runs[k].seconds << "s (C++/Rust "
+                  << std::setprecision(2) << cpp_runs[k].seconds / rust_runs[k].seconds << ")"
+                  << std::setprecision(6) << std::endl;
+    }
+    std::cerr << "Checksum: mandelbrot " << cpp_runs[0].result << ", primes " << cpp_runs[1].result
+              << ", sort " << cpp_runs[2].result << ", sha256 " << hex(digest) << std::endl;
+    return 0;
+}
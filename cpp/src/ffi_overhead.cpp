@@ -0,0 +1,127 @@
+#include <iostream>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <utility>
+
+const uint64_t NUM_CALLS = 200'000'000;
+const uint64_t ADDEND = 0x9E3779B97F4A7C15ULL;
+
+struct Vec2 {
+    double x;
+    double y;
+};
+
+// Built from src/ffi_shim.c
+extern "C" {
+void ffi_noop(void);
+uint64_t ffi_add(uint64_t a, uint64_t b);
+double ffi_dot(Vec2 a, Vec2 b);
+}
+
+// Native equivalents kept out of line, so each call is a real call/return like the C ones
+__attribute__((noinline)) void native_noop() {
+    // Without a side effect the compiler would delete calls to an empty function
+    asm volatile("");
+}
+
+__attribute__((noinline)) uint64_t native_add(uint64_t a, uint64_t b) {
+    return a + b;
+}
+
+__attribute__((noinline)) double native_dot(Vec2 a, Vec2 b) {
+    return a.x * b.x + a.y * b.y;
+}
+
+__attribute__((always_inline)) inline uint64_t inlined_add(uint64_t a, uint64_t b) {
+    return a + b;
+}
+
+__attribute__((always_inline)) inline double inlined_dot(Vec2 a, Vec2 b) {
+    return a.x * b.x + a.y * b.y;
+}
+
+Vec2 vec2(uint64_t i) {
+    return {static_cast<double>(i & 1023), 1.0};
+}
+
+// Round trip through a volatile, like Rust's black_box, so loops can't be vectorized or folded into closed forms
+template <typename T>
+inline T opaque(T value) {
+    volatile T sink = value;
+    return sink;
+}
+
+template <typename F>
+std::chrono::duration<double> run_noop(uint64_t calls, F f) {
+    auto start = std::chrono::high_resolution_clock::now();
+    for (uint64_t i = 0; i < calls; i++) {
+        f();
+    }
+    return std::chrono::high_resolution_clock::now() - start;
+}
+
+template <typename F>
+std::pair<std::chrono::duration<double>, uint64_t> run_add(uint64_t calls, F f) {
+    auto start = std::chrono::high_resolution_clock::now();
+    uint64_t sum = 0;
+    for (uint64_t i = 0; i < calls; i++) {
+        sum += f(opaque(i), opaque(ADDEND));
+    }
+    return {std::chrono::high_resolution_clock::now() - start, sum};
+}
+
+template <typename F>
+std::pair<std::chrono::duration<double>, double> run_dot(uint64_t calls, F f) {
+    auto start = std::chrono::high_resolution_clock::now();
+    double sum = 0.0;
+    const Vec2 b = {opaque(0.5), opaque(0.25)};
+    for (uint64_t i = 0; i < calls; i++) {
+        sum += f(vec2(opaque(i)), b);
+    }
+    return {std::chrono::high_resolution_clock::now() - start, sum};
+}
+
+double ns_per_call(std::chrono::duration<double> d) {
+    return d.count() * 1e9 / static_cast<double>(NUM_CALLS);
+}
+
+int main() {
+    // Warm-up
+    run_noop(NUM_CALLS / 100, ffi_noop);
+    volatile uint64_t warmup = run_add(NUM_CALLS / 100, ffi_add).second;
+    volatile double warmup_dot = run_dot(NUM_CALLS / 100, ffi_dot).second;
+    (void)warmup;
+    (void)warmup_dot;
+
+    // Benchmark
+    auto ffi_noop_time = run_noop(NUM_CALLS, ffi_noop);
+    auto native_noop_time = run_noop(NUM_CALLS, native_noop);
+
+    auto [ffi_add_time, ffi_add_sum] = run_add(NUM_CALLS, ffi_add);
+    auto [native_add_time, native_add_sum] = run_add(NUM_CALLS, native_add);
+    auto [inlined_add_time, inlined_add_sum] = run_add(NUM_CALLS, [](uint64_t a, uint64_t b) { return inlined_add(a, b); });
+
+    auto [ffi_dot_time, ffi_dot_sum] = run_dot(NUM_CALLS, ffi_dot);
+    auto [native_dot_time, native_dot_sum] = run_dot(NUM_CALLS, native_dot);
+    auto [inlined_dot_time, inlined_dot_sum] = run_dot(NUM_CALLS, [](Vec2 a, Vec2 b) { return inlined_dot(a, b); });
+
+    auto total_duration = ffi_noop_time + native_noop_time + ffi_add_time + native_add_time + inlined_add_time
+                          + ffi_dot_time + native_dot_time + inlined_dot_time;
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(3) << NUM_CALLS << " calls each, ns/call:" << std::endl;
+    std::cerr << "  noop()            FFI " << ns_per_call(ffi_noop_time) << ", native "
+              << ns_per_call(native_noop_time) << std::endl;
+    std::cerr << "  add(u64, u64)     FFI " << ns_per_call(ffi_add_time) << ", native " << ns_per_call(native_add_time)
+              << ", inlined " << ns_per_call(inlined_add_time) << std::endl;
+    std::cerr << "  dot(Vec2, Vec2)   FFI " << ns_per_call(ffi_dot_time) << ", native " << ns_per_call(native_dot_time)
+              << ", inlined " << ns_per_call(inlined_dot_time) << std::endl;
+    std::cerr << std::setprecision(1) << "Checksum: " << ffi_add_sum << " (dot: " << ffi_dot_sum
+              << ", all paths match: " << std::boolalpha
+              << (ffi_add_sum == native_add_sum && ffi_add_sum == inlined_add_sum && ffi_dot_sum == native_dot_sum
+                  && ffi_dot_sum == inlined_dot_sum)
+              << ")" << std::endl;
+
+    return 0;
+}
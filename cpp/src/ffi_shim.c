@@ -0,0 +1,18 @@
+// Tiny C functions called from ffi_overhead.cpp through extern "C".
+// Compiled as a separate C translation unit, so the optimizer cannot inline them.
+#include <stdint.h>
+
+typedef struct {
+    double x;
+    double y;
+} Vec2;
+
+void ffi_noop(void) {}
+
+uint64_t ffi_add(uint64_t a, uint64_t b) {
+    return a + b;
+}
+
+double ffi_dot(Vec2 a, Vec2 b) {
+    return a.x * b.x + a.y * b.y;
+}
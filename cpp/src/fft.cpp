@@ -4,17 +4,49 @@
 #include <cmath>
 #include <chrono>
 #include <iomanip>
+#include <algorithm>
+#include <cstdio>
+#include <cstring>
+#include <cstdlib>
+#include <limits>
+#include <tuple>
+#include <thread>
+#include <atomic>
+#include "huge_pages.h"
+#ifdef __APPLE__
+#include <Accelerate/Accelerate.h>
+#endif
 
-using Complex = std::complex<double>;
 const double PI = 3.14159265358979323846;
+const size_t BATCH_COUNT = 8192;
+const size_t BATCH_LEN = 4096;
+const size_t BATCH_WARMUP_ROWS = 256;
+const size_t NUM_THREADS = 8;
 
-void fft(std::vector<Complex>& x) {
+enum class Precision {
+    F64,
+    F32,
+};
+
+enum class Variant {
+    Default, // the hand-written radix-2 FFT below, with the real transforms built on it
+    Vdsp,    // vDSP_DFT_* from Apple's Accelerate, the platform library both languages can call
+};
+
+enum class Mode {
+    Single,  // one 2^24-point signal: complex forward, real forward and real inverse
+    Batched, // BATCH_COUNT independent BATCH_LEN-point transforms spread across threads
+};
+
+// Twiddles are computed in double and rounded to T, as rustfft does
+template <typename T>
+void fft(std::vector<std::complex<T>>& x) {
     size_t N = x.size();
     if (N <= 1) return;
     
     // Divide
-    std::vector<Complex> even(N / 2);
-    std::vector<Complex> odd(N / 2);
+    std::vector<std::complex<T>> even(N / 2);
+    std::vector<std::complex<T>> odd(N / 2);
     for (size_t i = 0; i < N / 2; i++) {
         even[i] = x[i * 2];
         odd[i] = x[i * 2 + 1];
@@ -26,27 +58,92 @@ void fft(std::vector<Complex>& x) {
     
     // Combine
     for (size_t k = 0; k < N / 2; k++) {
-        Complex t = std::polar(1.0, -2 * PI * k / N) * odd[k];
+        std::complex<double> w = std::polar(1.0, -2 * PI * k / N);
+        std::complex<T> t = std::complex<T>(static_cast<T>(w.real()), static_cast<T>(w.imag())) * odd[k];
         x[k] = even[k] + t;
         x[k + N / 2] = even[k] - t;
     }
 }
 
-int main() {
-    const size_t SIZE = 16'777'216; // 2^24
-    
-    // Generate input signal
-    std::vector<Complex> buffer(SIZE);
-    for (size_t i = 0; i < SIZE; i++) {
-        double t = static_cast<double>(i) / SIZE;
-        double signal = std::sin(2.0 * PI * 50.0 * t) + std::sin(2.0 * PI * 120.0 * t);
-        buffer[i] = Complex(signal, 0.0);
+// Unnormalized inverse via the conjugation identity ifft(x) = conj(fft(conj(x)))
+template <typename T>
+void inverse_fft(std::vector<std::complex<T>>& x) {
+    for (auto& v : x) v = std::conj(v);
+    fft(x);
+    for (auto& v : x) v = std::conj(v);
+}
+
+// Real-to-complex transform of n samples through one n/2-point complex FFT: even samples go in
+// the real parts, odd samples in the imaginary parts, and a twiddle pass separates the two
+// half-length spectra. The inverse runs the same steps backwards
+template <typename T>
+struct RealFft {
+    std::vector<std::complex<T>> twiddles; // exp(-2πik/n) for k in 0..n/2, computed in double
+
+    explicit RealFft(size_t n) : twiddles(n / 2) {
+        for (size_t k = 0; k < n / 2; k++) {
+            std::complex<double> w = std::polar(1.0, -2 * PI * k / n);
+            twiddles[k] = std::complex<T>(static_cast<T>(w.real()), static_cast<T>(w.imag()));
+        }
+    }
+
+    // input: n samples; work: n/2 scratch; output: the n/2 + 1 non-redundant bins
+    void forward(const std::vector<T>& input, std::vector<std::complex<T>>& work,
+                 std::vector<std::complex<T>>& output) const {
+        size_t m = work.size();
+        for (size_t i = 0; i < m; i++) {
+            work[i] = std::complex<T>(input[2 * i], input[2 * i + 1]);
+        }
+        fft(work);
+
+        const T half = T(0.5);
+        for (size_t k = 0; k <= m; k++) {
+            std::complex<T> z = work[k % m];
+            std::complex<T> z_mirror = std::conj(work[(m - k) % m]);
+            std::complex<T> even = (z + z_mirror) * half;
+            std::complex<T> odd = (z - z_mirror) * std::complex<T>(0, -half);
+            std::complex<T> twiddle = k < m ? twiddles[k] : std::complex<T>(-1, 0);
+            output[k] = even + twiddle * odd;
+        }
+    }
+
+    // input: n/2 + 1 bins; work: n/2 scratch; output: n samples, normalized by 1/n
+    void inverse(const std::vector<std::complex<T>>& input, std::vector<std::complex<T>>& work,
+                 std::vector<T>& output) const {
+        size_t m = work.size();
+        const T half = T(0.5);
+        for (size_t k = 0; k < m; k++) {
+            std::complex<T> x = input[k];
+            std::complex<T> x_mirror = std::conj(input[m - k]);
+            std::complex<T> even = (x + x_mirror) * half;
+            std::complex<T> odd = (x - x_mirror) * std::conj(twiddles[k]) * half;
+            work[k] = even + odd * std::complex<T>(0, 1);
+        }
+        inverse_fft(work);
+
+        const T scale = static_cast<T>(1.0 / m);
+        for (size_t i = 0; i < m; i++) {
+            output[2 * i] = work[i].real() * scale;
+            output[2 * i + 1] = work[i].imag() * scale;
+        }
+    }
+};
+
+// Converts the double signal to T outside the timed region, then warms up and times one transform.
+// The spectrum comes back widened to double for the checksum and accuracy check
+template <typename T>
+std::pair<std::chrono::duration<double>, std::vector<std::complex<double>>> transform(const std::vector<double>& signal) {
+    std::vector<std::complex<T>> buffer;
+    huge_reserve(buffer, signal.size());
+    buffer.resize(signal.size());
+    for (size_t i = 0; i < signal.size(); i++) {
+        buffer[i] = std::complex<T>(static_cast<T>(signal[i]), T(0));
     }
     
     // Warm-up with smaller size
-    std::vector<Complex> warmup(1024);
+    std::vector<std::complex<T>> warmup(1024);
     for (size_t i = 0; i < 1024; i++) {
-        warmup[i] = Complex(static_cast<double>(i), 0.0);
+        warmup[i] = std::complex<T>(static_cast<T>(i), T(0));
     }
     fft(warmup);
     
@@ -55,17 +152,475 @@ int main() {
     fft(buffer);
     auto end = std::chrono::high_resolution_clock::now();
     
-    std::chrono::duration<double> duration = end - start;
+    std::vector<std::complex<double>> spectrum(buffer.size());
+    for (size_t i = 0; i < buffer.size(); i++) {
+        spectrum[i] = std::complex<double>(buffer[i].real(), buffer[i].imag());
+    }
+    return {end - start, spectrum};
+}
+
+struct Run {
+    std::chrono::duration<double> complex, real_forward, real_inverse;
+    std::vector<std::complex<double>> spectrum; // complex forward transform, widened to double
+    double real_spectrum_error;                 // max |X_real - X_complex| / max |X_complex| over the shared bins
+    double round_trip_error;                    // max |x' - x| / max |x| after forward and inverse real transforms
+    double tolerance;                           // 1000 ε of T
+};
+
+template <typename T>
+Run run(const std::vector<double>& signal) {
+    Run result;
+    std::tie(result.complex, result.spectrum) = transform<T>(signal);
+
+    const size_t n = signal.size();
+    RealFft<T> real_fft(n);
+    std::vector<T> input, output;
+    std::vector<std::complex<T>> work, half_spectrum;
+    huge_reserve(input, n);
+    huge_reserve(work, n / 2);
+    huge_reserve(half_spectrum, n / 2 + 1);
+    huge_reserve(output, n);
+    input.assign(signal.begin(), signal.end());
+    work.resize(n / 2);
+    half_spectrum.resize(n / 2 + 1);
+    output.resize(n);
+
+    // Warm-up with smaller size
+    RealFft<T> warmup_fft(1024);
+    std::vector<T> warmup_input(input.begin(), input.begin() + 1024);
+    std::vector<std::complex<T>> warmup_work(512), warmup_spectrum(513);
+    std::vector<T> warmup_output(1024);
+    warmup_fft.forward(warmup_input, warmup_work, warmup_spectrum);
+    warmup_fft.inverse(warmup_spectrum, warmup_work, warmup_output);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    real_fft.forward(input, work, half_spectrum);
+    auto middle = std::chrono::high_resolution_clock::now();
+    real_fft.inverse(half_spectrum, work, output);
+    auto end = std::chrono::high_resolution_clock::now();
+    result.real_forward = middle - start;
+    result.real_inverse = end - middle;
+
+    double max_spectrum = 0.0, max_spectrum_error = 0.0;
+    for (const auto& x : result.spectrum) {
+        max_spectrum = std::max(max_spectrum, std::abs(x));
+    }
+    for (size_t k = 0; k < half_spectrum.size(); k++) {
+        std::complex<double> x(half_spectrum[k].real(), half_spectrum[k].imag());
+        max_spectrum_error = std::max(max_spectrum_error, std::abs(x - result.spectrum[k]));
+    }
+    double max_signal = 0.0, max_round_trip_error = 0.0;
+    for (size_t i = 0; i < n; i++) {
+        max_signal = std::max(max_signal, std::abs(signal[i]));
+        max_round_trip_error = std::max(max_round_trip_error, std::abs(static_cast<double>(output[i]) - signal[i]));
+    }
+    result.real_spectrum_error = max_spectrum_error / max_spectrum;
+    result.round_trip_error = max_round_trip_error / max_signal;
+    result.tolerance = static_cast<double>(std::numeric_limits<T>::epsilon()) * 1e3;
+    return result;
+}
+
+Precision parse_precision(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--precision") == 0) {
+            const char* value = i + 1 < argc ? argv[i + 1] : "";
+            if (std::strcmp(value, "f64") == 0) return Precision::F64;
+            if (std::strcmp(value, "f32") == 0) return Precision::F32;
+            std::cerr << "--precision expects f64 or f32" << std::endl;
+            std::exit(1);
+        }
+    }
+    return Precision::F64;
+}
+
+Mode parse_mode(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--mode") == 0) {
+            const char* value = i + 1 < argc ? argv[i + 1] : "";
+            if (std::strcmp(value, "single") == 0) return Mode::Single;
+            if (std::strcmp(value, "batched") == 0) return Mode::Batched;
+            std::cerr << "--mode expects single or batched" << std::endl;
+            std::exit(1);
+        }
+    }
+    return Mode::Single;
+}
+
+Variant parse_variant(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--variant") == 0) {
+            const char* value = i + 1 < argc ? argv[i + 1] : "";
+            if (std::strcmp(value, "default") == 0) return Variant::Default;
+            if (std::strcmp(value, "vdsp") == 0) return Variant::Vdsp;
+            std::cerr << "--variant expects default or vdsp" << std::endl;
+            std::exit(1);
+        }
+    }
+    return Variant::Default;
+}
+
+template <typename T>
+using Batch = std::vector<std::vector<std::complex<T>>>;
+
+// Row r carries tones at 50 + r % 256 and 120 cycles per row, with a per-row phase
+std::vector<std::vector<double>> batch_signals() {
+    std::vector<std::vector<double>> signals(BATCH_COUNT, std::vector<double>(BATCH_LEN));
+    for (size_t r = 0; r < BATCH_COUNT; r++) {
+        double phase = static_cast<double>(r) / BATCH_COUNT;
+        for (size_t n = 0; n < BATCH_LEN; n++) {
+            double t = static_cast<double>(n) / BATCH_LEN;
+            signals[r][n] = std::sin(2.0 * PI * (static_cast<double>(50 + r % 256) * t + phase))
+                          + std::sin(2.0 * PI * 120.0 * t);
+        }
+    }
+    return signals;
+}
+
+// Runs f on every row index, NUM_THREADS threads pulling rows from a shared counter
+template <typename F>
+void for_each_row(size_t count, F f) {
+    std::atomic<size_t> next{0};
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&]() {
+            for (size_t r = next++; r < count; r = next++) {
+                f(r);
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+}
+
+template <typename T, typename F>
+void process_rows(Batch<T>& rows, F f) {
+    for_each_row(rows.size(), [&](size_t r) { f(rows[r]); });
+}
+
+// Converts outside the timed region, warms up on a few rows
+template <typename T>
+std::pair<std::chrono::duration<double>, Batch<T>> transform_batch(const std::vector<std::vector<double>>& signals) {
+    Batch<T> rows(signals.size(), std::vector<std::complex<T>>(BATCH_LEN));
+    for (size_t r = 0; r < signals.size(); r++) {
+        for (size_t n = 0; n < BATCH_LEN; n++) {
+            rows[r][n] = std::complex<T>(static_cast<T>(signals[r][n]), T(0));
+        }
+    }
+
+    // Warm-up
+    Batch<T> warmup(rows.begin(), rows.begin() + BATCH_WARMUP_ROWS);
+    process_rows(warmup, fft<T>);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    process_rows(rows, fft<T>);
+    auto end = std::chrono::high_resolution_clock::now();
+    return {end - start, rows};
+}
+
+// Inverts every row (untimed) and returns max |x' - x| / max |x|
+template <typename T>
+double batch_round_trip_error(Batch<T> spectra, const std::vector<std::vector<double>>& signals) {
+    process_rows(spectra, inverse_fft<T>);
+    const double scale = 1.0 / BATCH_LEN;
+    double max_error = 0.0, max_signal = 0.0;
+    for (size_t r = 0; r < signals.size(); r++) {
+        for (size_t n = 0; n < BATCH_LEN; n++) {
+            std::complex<double> x(spectra[r][n].real(), spectra[r][n].imag());
+            max_error = std::max(max_error, std::abs(x * scale - std::complex<double>(signals[r][n], 0.0)));
+            max_signal = std::max(max_signal, std::abs(signals[r][n]));
+        }
+    }
+    return max_error / max_signal;
+}
+
+struct BatchRun {
+    std::chrono::duration<double> duration;
+    Batch<double> spectra; // widened to double
+    double round_trip_error;
+    double tolerance; // 1000 ε of T
+};
+
+template <typename T>
+BatchRun run_batched(const std::vector<std::vector<double>>& signals) {
+    auto [duration, spectra] = transform_batch<T>(signals);
+    Batch<double> wide(spectra.size(), std::vector<std::complex<double>>(BATCH_LEN));
+    for (size_t r = 0; r < spectra.size(); r++) {
+        for (size_t k = 0; k < BATCH_LEN; k++) {
+            wide[r][k] = std::complex<double>(spectra[r][k].real(), spectra[r][k].imag());
+        }
+    }
+    double round_trip_error = batch_round_trip_error(std::move(spectra), signals);
+    return {duration, std::move(wide), round_trip_error, static_cast<double>(std::numeric_limits<T>::epsilon()) * 1e3};
+}
+
+// Accelerate's DFT works on split complex data: separate real and imaginary arrays. Data is laid
+// out that way outside the timed region, so only the vDSP_DFT_Execute calls are timed
+namespace vdsp {
+#ifdef __APPLE__
+// The float and double entry points behind one set of overloads
+inline vDSP_DFT_Setup create(float, bool real, size_t n, vDSP_DFT_Direction direction) {
+    return real ? vDSP_DFT_zrop_CreateSetup(nullptr, n, direction) : vDSP_DFT_zop_CreateSetup(nullptr, n, direction);
+}
+inline vDSP_DFT_SetupD create(double, bool real, size_t n, vDSP_DFT_Direction direction) {
+    return real ? vDSP_DFT_zrop_CreateSetupD(nullptr, n, direction) : vDSP_DFT_zop_CreateSetupD(nullptr, n, direction);
+}
+inline void execute(vDSP_DFT_Setup setup, const float* ir, const float* ii, float* out_r, float* out_i) {
+    vDSP_DFT_Execute(setup, ir, ii, out_r, out_i);
+}
+inline void execute(vDSP_DFT_SetupD setup, const double* ir, const double* ii, double* out_r, double* out_i) {
+    vDSP_DFT_ExecuteD(setup, ir, ii, out_r, out_i);
+}
+inline void destroy(vDSP_DFT_Setup setup) { vDSP_DFT_DestroySetup(setup); }
+inline void destroy(vDSP_DFT_SetupD setup) { vDSP_DFT_DestroySetupD(setup); }
+
+// A DFT setup, destroyed with the object. Executing only reads it, so threads can share one
+template <typename T>
+class Dft {
+public:
+    // real: a zrop setup for n real samples, packed as n/2 even/odd pairs
+    Dft(bool real, size_t n, vDSP_DFT_Direction direction) : setup_(create(T(), real, n, direction)) {
+        if (!setup_) {
+            std::cerr << "vDSP has no " << n << "-point DFT" << std::endl;
+            std::exit(1);
+        }
+    }
+    ~Dft() { destroy(setup_); }
+    Dft(const Dft&) = delete;
+    Dft& operator=(const Dft&) = delete;
+
+    void operator()(const T* ir, const T* ii, T* out_r, T* out_i) const { execute(setup_, ir, ii, out_r, out_i); }
+
+private:
+    decltype(create(T(), false, 0, vDSP_DFT_FORWARD)) setup_;
+};
+
+template <typename T>
+std::chrono::duration<double> timed(const Dft<T>& dft, const std::vector<T>& ir, const std::vector<T>& ii, std::vector<T>& out_r,
+               std::vector<T>& out_i) {
+    auto start = std::chrono::high_resolution_clock::now();
+    dft(ir.data(), ii.data(), out_r.data(), out_i.data());
+    return std::chrono::high_resolution_clock::now() - start;
+}
+
+template <typename T>
+std::vector<T> zeros(size_t n) {
+    std::vector<T> v;
+    huge_reserve(v, n);
+    v.resize(n);
+    return v;
+}
+
+template <typename T>
+Run run(const std::vector<double>& signal) {
+    const size_t n = signal.size(), m = n / 2;
+    Run result;
+
+    // Complex forward, the signal in the real parts
+    Dft<T> complex(false, n, vDSP_DFT_FORWARD);
+    std::vector<T> input_re = zeros<T>(n), input_im = zeros<T>(n), re = zeros<T>(n), im = zeros<T>(n);
+    for (size_t i = 0; i < n; i++) input_re[i] = static_cast<T>(signal[i]);
+    complex(input_re.data(), input_im.data(), re.data(), im.data()); // warm-up
+    result.complex = timed(complex, input_re, input_im, re, im);
+    result.spectrum.resize(n);
+    for (size_t k = 0; k < n; k++) result.spectrum[k] = std::complex<double>(re[k], im[k]);
+
+    // Real forward: even samples in, odd samples alongside. The result is packed as
+    // 2 X[k] for 0 < k < n/2, with 2 X[0] and 2 X[n/2], both real, sharing slot 0
+    Dft<T> real_forward(true, n, vDSP_DFT_FORWARD), real_inverse(true, n, vDSP_DFT_INVERSE);
+    std::vector<T> even = zeros<T>(m), odd = zeros<T>(m), packed_re = zeros<T>(m), packed_im = zeros<T>(m);
+    std::vector<T> out_even = zeros<T>(m), out_odd = zeros<T>(m);
+    for (size_t i = 0; i < m; i++) {
+        even[i] = static_cast<T>(signal[2 * i]);
+        odd[i] = static_cast<T>(signal[2 * i + 1]);
+    }
+    real_forward(even.data(), odd.data(), packed_re.data(), packed_im.data()); // warm-up
+    real_inverse(packed_re.data(), packed_im.data(), out_even.data(), out_odd.data());
+    result.real_forward = timed(real_forward, even, odd, packed_re, packed_im);
+    // The packed spectrum goes straight back in, and comes out as 2n times the signal
+    result.real_inverse = timed(real_inverse, packed_re, packed_im, out_even, out_odd);
+
+    double max_spectrum = 0.0, max_spectrum_error = 0.0;
+    for (const auto& x : result.spectrum) max_spectrum = std::max(max_spectrum, std::abs(x));
+    for (size_t k = 0; k <= m; k++) {
+        std::complex<double> bin = k == 0 ? std::complex<double>(packed_re[0], 0.0)
+                                 : k == m ? std::complex<double>(packed_im[0], 0.0)
+                                          : std::complex<double>(packed_re[k], packed_im[k]);
+        max_spectrum_error = std::max(max_spectrum_error, std::abs(bin * 0.5 - result.spectrum[k]));
+    }
+    const double scale = 1.0 / (2.0 * n);
+    double max_signal = 0.0, max_round_trip_error = 0.0;
+    for (size_t i = 0; i < m; i++) {
+        max_signal = std::max({max_signal, std::abs(signal[2 * i]), std::abs(signal[2 * i + 1])});
+        max_round_trip_error = std::max({max_round_trip_error, std::abs(out_even[i] * scale - signal[2 * i]),
+                                         std::abs(out_odd[i] * scale - signal[2 * i + 1])});
+    }
+    result.real_spectrum_error = max_spectrum_error / max_spectrum;
+    result.round_trip_error = max_round_trip_error / max_signal;
+    result.tolerance = static_cast<double>(std::numeric_limits<T>::epsilon()) * 1e3;
+    return result;
+}
+
+// Every BATCH_LEN row of the split arrays through one shared setup, rows spread over the threads
+template <typename T>
+void process_rows(const Dft<T>& dft, const std::vector<T>& ir, const std::vector<T>& ii, std::vector<T>& out_r,
+                  std::vector<T>& out_i, size_t rows) {
+    for_each_row(rows, [&](size_t r) {
+        size_t offset = r * BATCH_LEN;
+        dft(ir.data() + offset, ii.data() + offset, out_r.data() + offset, out_i.data() + offset);
+    });
+}
+
+template <typename T>
+BatchRun run_batched(const std::vector<std::vector<double>>& signals) {
+    const size_t len = BATCH_COUNT * BATCH_LEN;
+    Dft<T> forward(false, BATCH_LEN, vDSP_DFT_FORWARD);
+    std::vector<T> input_re(len), input_im(len), re(len), im(len);
+    for (size_t r = 0; r < BATCH_COUNT; r++) {
+        for (size_t n = 0; n < BATCH_LEN; n++) input_re[r * BATCH_LEN + n] = static_cast<T>(signals[r][n]);
+    }
+
+    // Warm-up
+    process_rows(forward, input_re, input_im, re, im, BATCH_WARMUP_ROWS);
+
+    auto start = std::chrono::high_resolution_clock::now();
+    process_rows(forward, input_re, input_im, re, im, BATCH_COUNT);
+    std::chrono::duration<double> duration = std::chrono::high_resolution_clock::now() - start;
+
+    // Untimed inverse of every row, unnormalized like the hand-written one
+    Dft<T> inverse(false, BATCH_LEN, vDSP_DFT_INVERSE);
+    std::vector<T> back_re(len), back_im(len);
+    process_rows(inverse, re, im, back_re, back_im, BATCH_COUNT);
+    const double scale = 1.0 / BATCH_LEN;
+    double max_error = 0.0, max_signal = 0.0;
+    Batch<double> spectra(BATCH_COUNT, std::vector<std::complex<double>>(BATCH_LEN));
+    for (size_t r = 0; r < BATCH_COUNT; r++) {
+        for (size_t n = 0; n < BATCH_LEN; n++) {
+            size_t i = r * BATCH_LEN + n;
+            spectra[r][n] = std::complex<double>(re[i], im[i]);
+            std::complex<double> x(back_re[i] * scale, back_im[i] * scale);
+            max_error = std::max(max_error, std::abs(x - std::complex<double>(signals[r][n], 0.0)));
+            max_signal = std::max(max_signal, std::abs(signals[r][n]));
+        }
+    }
+    return {duration, std::move(spectra), max_error / max_signal,
+            static_cast<double>(std::numeric_limits<T>::epsilon()) * 1e3};
+}
+#else
+template <typename T>
+[[noreturn]] Run run(const std::vector<double>&) {
+    std::cerr << "--variant vdsp needs macOS, where Accelerate provides vDSP" << std::endl;
+    std::exit(1);
+}
+
+template <typename T>
+[[noreturn]] BatchRun run_batched(const std::vector<std::vector<double>>&) {
+    std::cerr << "--variant vdsp needs macOS, where Accelerate provides vDSP" << std::endl;
+    std::exit(1);
+}
+#endif
+} // namespace vdsp
+
+const char* variant_name(Variant variant) {
+    return variant == Variant::Vdsp ? "vDSP (Accelerate)" : "hand-written radix-2";
+}
+
+void report_batched(Precision precision, Variant variant) {
+    std::vector<std::vector<double>> signals = batch_signals();
+    BatchRun result = variant == Variant::Vdsp
+                          ? (precision == Precision::F64 ? vdsp::run_batched<double>(signals) : vdsp::run_batched<float>(signals))
+                          : (precision == Precision::F64 ? run_batched<double>(signals) : run_batched<float>(signals));
+
+    // Checksum: every bin of every row
+    double checksum = 0.0;
+    for (const auto& row : result.spectra) {
+        for (const auto& x : row) {
+            checksum += std::abs(x);
+        }
+    }
+    double seconds = result.duration.count();
+    double flops = 5.0 * BATCH_LEN * std::log2(static_cast<double>(BATCH_LEN)) * BATCH_COUNT;
+
+    std::cout << std::fixed << std::setprecision(6) << seconds << std::endl;
+    std::cerr << "Variant: " << variant_name(variant) << std::endl;
+    std::cerr << "Precision: " << (precision == Precision::F64 ? "f64" : "f32") << std::endl;
+    fprintf(stderr, "Batched: %zu x %zu-point transforms on %zu threads, %.0f transforms/s, %.2f GFLOP/s (5 N log2 N)\n",
+            BATCH_COUNT, BATCH_LEN, NUM_THREADS, BATCH_COUNT / seconds, flops / seconds / 1e9);
+    if (precision == Precision::F32) {
+        // Normwise against the double batch (untimed), as in single mode
+        Batch<double> reference = transform_batch<double>(signals).second;
+        double max_error = 0.0, max_reference = 0.0;
+        for (size_t r = 0; r < BATCH_COUNT; r++) {
+            for (size_t k = 0; k < BATCH_LEN; k++) {
+                max_error = std::max(max_error, std::abs(result.spectra[r][k] - reference[r][k]));
+                max_reference = std::max(max_reference, std::abs(reference[r][k]));
+            }
+        }
+        fprintf(stderr, "Relative error vs f64: %.3e\n", max_error / max_reference);
+    }
+    fprintf(stderr, "Round-trip vs original signals: max error %.3e (tolerance %.1e: %s)\n", result.round_trip_error,
+            result.tolerance, result.round_trip_error <= result.tolerance ? "true" : "false");
+    std::cerr << huge_pages_report() << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+}
+
+void report_single(Precision precision, Variant variant) {
+    const size_t SIZE = 16'777'216; // 2^24
+    
+    // Generate input signal
+    std::vector<double> signal(SIZE);
+    for (size_t i = 0; i < SIZE; i++) {
+        double t = static_cast<double>(i) / SIZE;
+        signal[i] = std::sin(2.0 * PI * 50.0 * t) + std::sin(2.0 * PI * 120.0 * t);
+    }
+    
+    Run result = variant == Variant::Vdsp
+                     ? (precision == Precision::F64 ? vdsp::run<double>(signal) : vdsp::run<float>(signal))
+                     : (precision == Precision::F64 ? run<double>(signal) : run<float>(signal));
+    const auto& spectrum = result.spectrum;
     
     // Checksum
     double checksum = 0.0;
     for (size_t i = 0; i < 1000; i++) {
-        checksum += std::abs(buffer[i]);
+        checksum += std::abs(spectrum[i]);
     }
     
-    std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
+    std::chrono::duration<double> total_duration = result.complex + result.real_forward + result.real_inverse;
+    
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << "Variant: " << variant_name(variant) << std::endl;
+    std::cerr << "Precision: " << (precision == Precision::F64 ? "f64" : "f32") << std::endl;
+    fprintf(stderr, "Complex forward: %.6fs, real forward: %.6fs, real inverse: %.6fs\n", result.complex.count(),
+            result.real_forward.count(), result.real_inverse.count());
+    if (precision == Precision::F32) {
+        // Normwise against the double transform (untimed): max |X - X64| / max |X64|
+        auto reference = transform<double>(signal).second;
+        double max_error = 0.0, max_reference = 0.0;
+        for (size_t i = 0; i < SIZE; i++) {
+            max_error = std::max(max_error, std::abs(spectrum[i] - reference[i]));
+            max_reference = std::max(max_reference, std::abs(reference[i]));
+        }
+        fprintf(stderr, "Relative error vs f64: %.3e\n", max_error / max_reference);
+    }
+    fprintf(stderr, "Real vs complex spectrum: max error %.3e (tolerance %.1e: %s)\n", result.real_spectrum_error,
+            result.tolerance, result.real_spectrum_error <= result.tolerance ? "true" : "false");
+    fprintf(stderr, "Round-trip vs original signal: max error %.3e (tolerance %.1e: %s)\n", result.round_trip_error,
+            result.tolerance, result.round_trip_error <= result.tolerance ? "true" : "false");
+    std::cerr << huge_pages_report() << std::endl;
     std::cerr << "Checksum: " << checksum << std::endl;
+}
+
+int main(int argc, char** argv) {
+    huge_pages_enable(argc, argv);
+    Precision precision = parse_precision(argc, argv);
+    Variant variant = parse_variant(argc, argv);
+    if (parse_mode(argc, argv) == Mode::Batched) {
+        report_batched(precision, variant);
+    } else {
+        report_single(precision, variant);
+    }
     
     return 0;
 }
-
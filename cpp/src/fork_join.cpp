@@ -0,0 +1,216 @@
+#include <iostream>
+#include <memory>
+#include <vector>
+#include <string>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <omp.h>
+#ifdef HAVE_TBB
+#include <tbb/parallel_invoke.h>
+#endif
+
+const uint32_t FIB_N = 36;
+// Below these the recursion runs sequentially: coarse to fine, so forks grow down the list
+const uint32_t FIB_CUTOFFS[] = {30, 25, 20, 15, 10};
+const uint32_t TREE_HEIGHT = 22; // 4M nodes
+const uint32_t TREE_CUTOFFS[] = {18, 14, 10, 6, 2};
+const int ROUNDS = 3;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+uint64_t fib(uint32_t n) {
+    return n < 2 ? n : fib(n - 1) + fib(n - 2);
+}
+
+// OpenMP: the first call spawns a task, the second runs inline, then taskwait joins
+uint64_t fib_omp(uint32_t n, uint32_t cutoff) {
+    if (n < 2 || n <= cutoff) return fib(n);
+    uint64_t a, b;
+#pragma omp task shared(a)
+    a = fib_omp(n - 1, cutoff);
+    b = fib_omp(n - 2, cutoff);
+#pragma omp taskwait
+    return a + b;
+}
+
+#ifdef HAVE_TBB
+uint64_t fib_tbb(uint32_t n, uint32_t cutoff) {
+    if (n < 2 || n <= cutoff) return fib(n);
+    uint64_t a, b;
+    tbb::parallel_invoke([&] { a = fib_tbb(n - 1, cutoff); }, [&] { b = fib_tbb(n - 2, cutoff); });
+    return a + b;
+}
+#endif
+
+// Calls above the cutoff, each of which forks once
+uint64_t fib_forks(uint32_t n, uint32_t cutoff) {
+    return n < 2 || n <= cutoff ? 0 : 1 + fib_forks(n - 1, cutoff) + fib_forks(n - 2, cutoff);
+}
+
+struct Node {
+    uint64_t value;
+    std::unique_ptr<Node> left, right;
+};
+
+// A complete tree of the given height, values drawn and nodes allocated in pre-order, the order
+// the sums visit them
+std::unique_ptr<Node> build(uint32_t height, uint64_t& seed) {
+    if (height == 0) return nullptr;
+    auto node = std::make_unique<Node>();
+    node->value = next_random(seed);
+    node->left = build(height - 1, seed);
+    node->right = build(height - 1, seed);
+    return node;
+}
+
+uint64_t tree_sum(const Node* node) {
+    return node ? node->value + tree_sum(node->left.get()) + tree_sum(node->right.get()) : 0;
+}
+
+// height is that of the subtree under node, which forks while it stays above the cutoff
+uint64_t tree_sum_omp(const Node* node, uint32_t height, uint32_t cutoff) {
+    if (!node || height <= cutoff) return tree_sum(node);
+    uint64_t left, right;
+#pragma omp task shared(left)
+    left = tree_sum_omp(node->left.get(), height - 1, cutoff);
+    right = tree_sum_omp(node->right.get(), height - 1, cutoff);
+#pragma omp taskwait
+    return node->value + left + right;
+}
+
+#ifdef HAVE_TBB
+uint64_t tree_sum_tbb(const Node* node, uint32_t height, uint32_t cutoff) {
+    if (!node || height <= cutoff) return tree_sum(node);
+    uint64_t left, right;
+    tbb::parallel_invoke([&] { left = tree_sum_tbb(node->left.get(), height - 1, cutoff); },
+                         [&] { right = tree_sum_tbb(node->right.get(), height - 1, cutoff); });
+    return node->value + left + right;
+}
+#endif
+
+uint64_t tree_forks(uint32_t height, uint32_t cutoff) {
+    return height <= cutoff ? 0 : (1ULL << (height - cutoff)) - 1;
+}
+
+// The root of an OpenMP task tree: one thread of the team starts it, the rest steal its tasks
+template <typename F>
+uint64_t in_omp_team(F f) {
+    uint64_t value = 0;
+#pragma omp parallel
+#pragma omp single
+    value = f();
+    return value;
+}
+
+volatile uint32_t opaque_n = FIB_N; // keeps fib(FIB_N) from being folded at compile time
+
+// Total time over ROUNDS, checking every round returns the same value. The memory clobber stops the
+// compiler merging rounds of a pure sequential sum into one call
+template <typename F>
+std::pair<Duration, uint64_t> timed(F f) {
+    auto start = Clock::now();
+    uint64_t value = f();
+    for (int i = 1; i < ROUNDS; i++) {
+        asm volatile("" : : : "memory");
+        if (f() != value) {
+            std::cerr << "Rounds disagree" << std::endl;
+            std::exit(1);
+        }
+    }
+    return {Clock::now() - start, value};
+}
+
+struct Row {
+    uint32_t cutoff;
+    uint64_t forks;
+    Duration omp;
+    Duration tbb;
+};
+
+void report(const std::string& title, Duration sequential, const std::vector<Row>& rows) {
+    std::cerr << std::fixed << std::setprecision(3) << title << ": sequential " << sequential.count() << "s over "
+              << ROUNDS << " rounds" << std::endl;
+    for (const Row& r : rows) {
+        char line[160];
+        int n = std::snprintf(line, sizeof(line), "  cutoff %2u  %9llu forks  OpenMP tasks %.3fs (%.2fx)", r.cutoff,
+                              static_cast<unsigned long long>(r.forks), r.omp.count(), sequential.count() / r.omp.count());
+#ifdef HAVE_TBB
+        std::snprintf(line + n, sizeof(line) - n, "  TBB parallel_invoke %.3fs (%.2fx)", r.tbb.count(),
+                      sequential.count() / r.tbb.count());
+#else
+        (void)n;
+#endif
+        std::cerr << line << std::endl;
+    }
+}
+
+void check(uint64_t value, uint64_t expected, const char* what, uint32_t cutoff) {
+    if (value != expected) {
+        std::cerr << what << " with cutoff " << cutoff << " is wrong: " << value << ", expected " << expected << std::endl;
+        std::exit(1);
+    }
+}
+
+int main() {
+    uint64_t seed = 42;
+    std::unique_ptr<Node> tree = build(TREE_HEIGHT, seed);
+    const Node* root = tree.get();
+
+    // Warm-up: start the team's threads and touch the tree
+    in_omp_team([] { return fib_omp(25, 10); });
+    in_omp_team([&] { return tree_sum_omp(root, TREE_HEIGHT, TREE_HEIGHT - 4); });
+#ifdef HAVE_TBB
+    fib_tbb(25, 10);
+#endif
+
+    auto [fib_sequential, fib_value] = timed([] { return fib(opaque_n); });
+    auto [tree_sequential, tree_value] = timed([&] { return tree_sum(root); });
+    std::vector<Row> fib_rows, tree_rows;
+    for (uint32_t cutoff : FIB_CUTOFFS) {
+        Row row{cutoff, fib_forks(FIB_N, cutoff), {}, {}};
+        auto [omp_time, omp_value] = timed([&] { return in_omp_team([&] { return fib_omp(opaque_n, cutoff); }); });
+        check(omp_value, fib_value, "OpenMP fib", cutoff);
+        row.omp = omp_time;
+#ifdef HAVE_TBB
+        auto [tbb_time, tbb_value] = timed([&] { return fib_tbb(opaque_n, cutoff); });
+        check(tbb_value, fib_value, "TBB fib", cutoff);
+        row.tbb = tbb_time;
+#endif
+        fib_rows.push_back(row);
+    }
+    for (uint32_t cutoff : TREE_CUTOFFS) {
+        Row row{cutoff, tree_forks(TREE_HEIGHT, cutoff), {}, {}};
+        auto [omp_time, omp_value] = timed([&] { return in_omp_team([&] { return tree_sum_omp(root, TREE_HEIGHT, cutoff); }); });
+        check(omp_value, tree_value, "OpenMP tree sum", cutoff);
+        row.omp = omp_time;
+#ifdef HAVE_TBB
+        auto [tbb_time, tbb_value] = timed([&] { return tree_sum_tbb(root, TREE_HEIGHT, cutoff); });
+        check(tbb_value, tree_value, "TBB tree sum", cutoff);
+        row.tbb = tbb_time;
+#endif
+        tree_rows.push_back(row);
+    }
+
+    // The headline is OpenMP, which every build of this benchmark has
+    Duration total{0};
+    for (const Row& r : fib_rows) total += r.omp;
+    for (const Row& r : tree_rows) total += r.omp;
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    std::cerr << omp_get_max_threads() << " OpenMP threads" << std::endl;
+    report("fib(" + std::to_string(FIB_N) + ") = " + std::to_string(fib_value), fib_sequential, fib_rows);
+    report("Tree sum over " + std::to_string((1ULL << TREE_HEIGHT) - 1) + " nodes", tree_sequential, tree_rows);
+#ifndef HAVE_TBB
+    std::cerr << "(TBB not found: built without tbb::parallel_invoke)" << std::endl;
+#endif
+    std::cerr << "Checksum: " << fib_value + tree_value << std::endl;
+    return 0;
+}
@@ -0,0 +1,311 @@
+#include <webgpu/webgpu.h>
+#include <webgpu/wgpu.h>
+#include <iostream>
+#include <string>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <cmath>
+#include <stdexcept>
+
+#include "bench_measure.h"
+
+const size_t MATRIX_SIZE = 2048;  // 2048x2048 matrix
+const size_t TOTAL_ELEMENTS = MATRIX_SIZE * MATRIX_SIZE;
+const size_t ITERATIONS = 10;
+const size_t WORKGROUP_SIZE = 16;
+
+// The metal_compute kernel translated to WGSL, so it runs on Vulkan, DX12 and Metal alike
+const char* SHADER_SOURCE = R"(
+    @group(0) @binding(0) var<storage, read> a: array<f32>;
+    @group(0) @binding(1) var<storage, read> b: array<f32>;
+    @group(0) @binding(2) var<storage, read_write> result: array<f32>;
+    @group(0) @binding(3) var<uniform> matrix_size: u32;
+
+    // Heavy compute shader: matrix element-wise operations with trigonometric functions
+    @compute @workgroup_size(16, 16)
+    fn heavy_compute(@builtin(global_invocation_id) gid: vec3<u32>) {
+        if (gid.x >= matrix_size || gid.y >= matrix_size) {
+            return;
+        }
+        let idx = gid.y * matrix_size + gid.x;
+
+        // Perform multiple heavy computations
+        let val_a = a[idx];
+        let val_b = b[idx];
+
+        var sum = 0.0;
+        for (var i = 0; i < 50; i++) {
+            let t = val_a * f32(i) * 0.01 + val_b;
+            sum += sin(t) * cos(val_b * f32(i) * 0.01) +
+                   tan(val_a * 0.1) * exp(-val_b * 0.001) +
+                   sqrt(abs(t)) * log(abs(t) + 1.0);
+        }
+
+        // Additional matrix-style computation
+        var accumulator = 0.0;
+        let row = gid.y;
+        for (var col = 0u; col < matrix_size; col++) {
+            let idx2 = row * matrix_size + col;
+            accumulator += a[idx2] * b[idx2] * 0.0001;
+        }
+
+        result[idx] = sum + accumulator;
+    }
+)";
+
+WGPUStringView string_view(const char* s) {
+    return WGPUStringView{s, WGPU_STRLEN};
+}
+
+// wgpu-native completes adapter and device requests before the call returns
+WGPUAdapter request_adapter(WGPUInstance instance) {
+    WGPURequestAdapterOptions options = {};
+    options.powerPreference = WGPUPowerPreference_HighPerformance;
+
+    WGPURequestAdapterCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestAdapterStatus status, WGPUAdapter adapter, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestAdapterStatus_Success) *static_cast<WGPUAdapter*>(out) = adapter;
+    };
+    WGPUAdapter adapter = nullptr;
+    callback.userdata1 = &adapter;
+    wgpuInstanceRequestAdapter(instance, &options, callback);
+    return adapter;
+}
+
+WGPUDevice request_device(WGPUAdapter adapter, bool timestamps) {
+    WGPURequestDeviceCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestDeviceStatus status, WGPUDevice device, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestDeviceStatus_Success) *static_cast<WGPUDevice*>(out) = device;
+    };
+    WGPUDevice device = nullptr;
+    callback.userdata1 = &device;
+    WGPUFeatureName timestamp_query = WGPUFeatureName_TimestampQuery;
+    WGPUDeviceDescriptor desc = {};
+    desc.requiredFeatureCount = timestamps ? 1 : 0;
+    desc.requiredFeatures = &timestamp_query;
+    wgpuAdapterRequestDevice(adapter, &desc, callback);
+    return device;
+}
+
+WGPUBuffer create_buffer(WGPUDevice device, uint64_t size, WGPUBufferUsage usage) {
+    WGPUBufferDescriptor desc = {};
+    desc.size = size;
+    desc.usage = usage;
+    return wgpuDeviceCreateBuffer(device, &desc);
+}
+
+bool map_for_read(WGPUDevice device, WGPUBuffer buffer, uint64_t bytes) {
+    WGPUBufferMapCallbackInfo map_callback = {};
+    map_callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    map_callback.callback = [](WGPUMapAsyncStatus status, WGPUStringView, void* out, void*) {
+        *static_cast<bool*>(out) = status == WGPUMapAsyncStatus_Success;
+    };
+    bool mapped = false;
+    map_callback.userdata1 = &mapped;
+    wgpuBufferMapAsync(buffer, WGPUMapMode_Read, 0, bytes, map_callback);
+    wgpuDevicePoll(device, true, nullptr);
+    return mapped;
+}
+
+// Timestamps the GPU writes at the start and end of a pass, for BENCH_MEASURE=gpu
+struct PassTimer {
+    WGPUQuerySet queries;
+    WGPUBuffer resolved;
+    WGPUBuffer readback;
+};
+
+PassTimer create_pass_timer(WGPUDevice device) {
+    WGPUQuerySetDescriptor desc = {};
+    desc.type = WGPUQueryType_Timestamp;
+    desc.count = 2;
+    return PassTimer{wgpuDeviceCreateQuerySet(device, &desc),
+                     create_buffer(device, 2 * sizeof(uint64_t), WGPUBufferUsage_QueryResolve | WGPUBufferUsage_CopySrc),
+                     create_buffer(device, 2 * sizeof(uint64_t), WGPUBufferUsage_MapRead | WGPUBufferUsage_CopyDst)};
+}
+
+// webgpu.h resolves timestamps in nanoseconds, so unlike wgpu's Rust API there is no period to apply
+double pass_seconds(WGPUDevice device, const PassTimer& timer) {
+    if (!map_for_read(device, timer.readback, 2 * sizeof(uint64_t))) {
+        throw std::runtime_error("Failed to map timestamps");
+    }
+    const uint64_t* stamps = static_cast<const uint64_t*>(
+        wgpuBufferGetConstMappedRange(timer.readback, 0, 2 * sizeof(uint64_t)));
+    uint64_t ns = stamps[1] > stamps[0] ? stamps[1] - stamps[0] : 0;
+    wgpuBufferUnmap(timer.readback);
+    return ns / 1e9;
+}
+
+// Runs one pass; with a timer, also returns the GPU time between its timestamps (else -1)
+double dispatch(WGPUDevice device, WGPUQueue queue, WGPUComputePipeline pipeline, WGPUBindGroup bind_group,
+                const PassTimer* timer = nullptr) {
+    WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device, nullptr);
+    WGPUPassTimestampWrites timestamp_writes = {};
+    WGPUComputePassDescriptor pass_desc = {};
+    if (timer) {
+        timestamp_writes.querySet = timer->queries;
+        timestamp_writes.beginningOfPassWriteIndex = 0;
+        timestamp_writes.endOfPassWriteIndex = 1;
+        pass_desc.timestampWrites = &timestamp_writes;
+    }
+    WGPUComputePassEncoder pass = wgpuCommandEncoderBeginComputePass(encoder, &pass_desc);
+    wgpuComputePassEncoderSetPipeline(pass, pipeline);
+    wgpuComputePassEncoderSetBindGroup(pass, 0, bind_group, 0, nullptr);
+    uint32_t groups = static_cast<uint32_t>((MATRIX_SIZE + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE);
+    wgpuComputePassEncoderDispatchWorkgroups(pass, groups, groups, 1);
+    wgpuComputePassEncoderEnd(pass);
+    wgpuComputePassEncoderRelease(pass);
+    if (timer) {
+        wgpuCommandEncoderResolveQuerySet(encoder, timer->queries, 0, 2, timer->resolved, 0);
+        wgpuCommandEncoderCopyBufferToBuffer(encoder, timer->resolved, 0, timer->readback, 0, 2 * sizeof(uint64_t));
+    }
+
+    WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+    wgpuQueueSubmit(queue, 1, &commands);
+    wgpuCommandBufferRelease(commands);
+    wgpuCommandEncoderRelease(encoder);
+    wgpuDevicePoll(device, true, nullptr);
+    return timer ? pass_seconds(device, *timer) : -1.0;
+}
+
+int main() {
+    auto measure = bench_measure_from_env(true);
+    // Initialize WebGPU (wgpu-native) with whichever backend the platform offers
+    WGPUInstance instance = wgpuCreateInstance(nullptr);
+    WGPUAdapter adapter = instance ? request_adapter(instance) : nullptr;
+    if (!adapter) {
+        std::cerr << "No GPU adapter found" << std::endl;
+        return 1;
+    }
+    bool timestamps = measure->wants_gpu();
+    if (timestamps && !wgpuAdapterHasFeature(adapter, WGPUFeatureName_TimestampQuery)) {
+        std::cerr << "BENCH_MEASURE=gpu, but the adapter has no timestamp queries" << std::endl;
+        return 1;
+    }
+    WGPUDevice device = request_device(adapter, timestamps);
+    if (!device) {
+        std::cerr << "Failed to create device" << std::endl;
+        return 1;
+    }
+    WGPUQueue queue = wgpuDeviceGetQueue(device);
+
+    WGPUShaderSourceWGSL wgsl = {};
+    wgsl.chain.sType = WGPUSType_ShaderSourceWGSL;
+    wgsl.code = string_view(SHADER_SOURCE);
+    WGPUShaderModuleDescriptor shader_desc = {};
+    shader_desc.nextInChain = &wgsl.chain;
+    WGPUShaderModule shader = wgpuDeviceCreateShaderModule(device, &shader_desc);
+
+    WGPUComputePipelineDescriptor pipeline_desc = {};
+    pipeline_desc.compute.module = shader;
+    pipeline_desc.compute.entryPoint = string_view("heavy_compute");
+    WGPUComputePipeline pipeline = wgpuDeviceCreateComputePipeline(device, &pipeline_desc);
+    if (!pipeline) {
+        std::cerr << "Failed to create pipeline" << std::endl;
+        return 1;
+    }
+
+    // Create input data - large matrices
+    std::vector<float> input_a(TOTAL_ELEMENTS);
+    std::vector<float> input_b(TOTAL_ELEMENTS);
+
+    for (size_t i = 0; i < TOTAL_ELEMENTS; i++) {
+        input_a[i] = std::sin(static_cast<float>(i) * 0.001f);
+        input_b[i] = std::cos(static_cast<float>(i) * 0.002f);
+    }
+    const uint64_t result_bytes = TOTAL_ELEMENTS * sizeof(float);
+
+    WGPUBuffer buffer_a = create_buffer(device, result_bytes, WGPUBufferUsage_Storage | WGPUBufferUsage_CopyDst);
+    wgpuQueueWriteBuffer(queue, buffer_a, 0, input_a.data(), result_bytes);
+
+    WGPUBuffer buffer_b = create_buffer(device, result_bytes, WGPUBufferUsage_Storage | WGPUBufferUsage_CopyDst);
+    wgpuQueueWriteBuffer(queue, buffer_b, 0, input_b.data(), result_bytes);
+
+    WGPUBuffer buffer_result = create_buffer(device, result_bytes, WGPUBufferUsage_Storage | WGPUBufferUsage_CopySrc);
+
+    uint32_t matrix_size_val = static_cast<uint32_t>(MATRIX_SIZE);
+    WGPUBuffer buffer_size = create_buffer(device, sizeof(uint32_t), WGPUBufferUsage_Uniform | WGPUBufferUsage_CopyDst);
+    wgpuQueueWriteBuffer(queue, buffer_size, 0, &matrix_size_val, sizeof(uint32_t));
+
+    // Unlike Metal's shared storage mode, results come back through a mappable staging buffer
+    WGPUBuffer buffer_readback = create_buffer(device, result_bytes, WGPUBufferUsage_MapRead | WGPUBufferUsage_CopyDst);
+
+    WGPUBuffer bound[] = {buffer_a, buffer_b, buffer_result, buffer_size};
+    WGPUBindGroupEntry entries[4] = {};
+    for (uint32_t i = 0; i < 4; i++) {
+        entries[i].binding = i;
+        entries[i].buffer = bound[i];
+        entries[i].size = WGPU_WHOLE_SIZE;
+    }
+    WGPUBindGroupLayout layout = wgpuComputePipelineGetBindGroupLayout(pipeline, 0);
+    WGPUBindGroupDescriptor bind_group_desc = {};
+    bind_group_desc.layout = layout;
+    bind_group_desc.entryCount = 4;
+    bind_group_desc.entries = entries;
+    WGPUBindGroup bind_group = wgpuDeviceCreateBindGroup(device, &bind_group_desc);
+
+    PassTimer timer = {};
+    if (timestamps) timer = create_pass_timer(device);
+
+    // Warm-up
+    dispatch(device, queue, pipeline, bind_group);
+
+    // Benchmark - run multiple iterations
+    measure->start();
+
+    for (size_t iter = 0; iter < ITERATIONS; iter++) {
+        double seconds = dispatch(device, queue, pipeline, bind_group, timestamps ? &timer : nullptr);
+        if (timestamps) measure->record_gpu(seconds);
+    }
+
+    measure->stop();
+
+    // Get result and checksum
+    WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device, nullptr);
+    wgpuCommandEncoderCopyBufferToBuffer(encoder, buffer_result, 0, buffer_readback, 0, result_bytes);
+    WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+    wgpuQueueSubmit(queue, 1, &commands);
+
+    if (!map_for_read(device, buffer_readback, result_bytes)) {
+        std::cerr << "Failed to map readback buffer" << std::endl;
+        return 1;
+    }
+
+    const float* result_ptr = static_cast<const float*>(wgpuBufferGetConstMappedRange(buffer_readback, 0, result_bytes));
+    float checksum = 0.0f;
+    for (size_t i = 0; i < TOTAL_ELEMENTS; i += 1000) {
+        checksum += result_ptr[i];
+    }
+    wgpuBufferUnmap(buffer_readback);
+
+    WGPUAdapterInfo info = {};
+    wgpuAdapterGetInfo(adapter, &info);
+
+    measure->report();
+    std::cerr << "Adapter: " << std::string(info.device.data, info.device.length) << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    wgpuAdapterInfoFreeMembers(info);
+    wgpuCommandBufferRelease(commands);
+    wgpuCommandEncoderRelease(encoder);
+    for (WGPUBuffer buffer : {buffer_a, buffer_b, buffer_result, buffer_size, buffer_readback}) {
+        wgpuBufferRelease(buffer);
+    }
+    if (timestamps) {
+        wgpuBufferRelease(timer.resolved);
+        wgpuBufferRelease(timer.readback);
+        wgpuQuerySetRelease(timer.queries);
+    }
+    wgpuBindGroupRelease(bind_group);
+    wgpuBindGroupLayoutRelease(layout);
+    wgpuComputePipelineRelease(pipeline);
+    wgpuShaderModuleRelease(shader);
+    wgpuQueueRelease(queue);
+    wgpuDeviceRelease(device);
+    wgpuAdapterRelease(adapter);
+    wgpuInstanceRelease(instance);
+
+    return 0;
+}
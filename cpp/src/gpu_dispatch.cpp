@@ -0,0 +1,360 @@
+#include <webgpu/webgpu.h>
+#include <webgpu/wgpu.h>
+#include <iostream>
+#include <string>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <cstring>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+
+const size_t ELEMENTS = 1 << 23;     // 8M u32, 32 MiB
+const size_t WORKGROUP_SIZE = 256;
+const uint32_t ROUNDS = 64;          // mixing rounds per element, matched by the shader
+// The same work cut into this many dispatches: one launch of 32768 workgroups down to 32768 launches of one
+const size_t SPLITS[] = {1, 16, 256, 4096, 32768};
+const size_t ROUND_TRIPS = 20;       // single-chunk submit-and-wait launches per split
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+// Each thread mixes one element in place. The binding covers one chunk, placed by a dynamic offset
+const char* SHADER_SOURCE = R"(
+    @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+    const ROUNDS: u32 = 64u;
+
+    @compute @workgroup_size(256)
+    fn mix(@builtin(global_invocation_id) id: vec3<u32>) {
+        if (id.x >= arrayLength(&data)) {
+            return;
+        }
+        var x = data[id.x];
+        for (var i = 0u; i < ROUNDS; i++) {
+            x = x * 1664525u + 1013904223u;
+            x ^= x >> 16u;
+        }
+        data[id.x] = x;
+    }
+)";
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// CPU reference for one element
+uint32_t mix(uint32_t x) {
+    for (uint32_t i = 0; i < ROUNDS; i++) {
+        x = x * 1664525u + 1013904223u;
+        x ^= x >> 16;
+    }
+    return x;
+}
+
+struct Row {
+    size_t dispatches;
+    Duration one_pass;   // every dispatch recorded into one compute pass and submitted once
+    Duration per_submit; // one command buffer and submit per dispatch, waited on at the end
+    Duration round_trip; // a single chunk submitted and waited on, averaged over ROUND_TRIPS
+    Duration cpu;        // one CPU thread doing a chunk's worth of the same mixing
+
+    size_t chunk() const { return ELEMENTS / dispatches; }
+};
+
+// The smallest chunk whose round trip to the GPU beats doing it on one CPU thread, or 0 if none does
+size_t break_even(const std::vector<Row>& rows) {
+    size_t best = 0;
+    for (const Row& r : rows) {
+        if (r.round_trip < r.cpu && (best == 0 || r.chunk() < best)) best = r.chunk();
+    }
+    return best;
+}
+
+WGPUStringView string_view(const char* s) {
+    return WGPUStringView{s, WGPU_STRLEN};
+}
+
+// wgpu-native completes adapter and device requests before the call returns
+WGPUAdapter request_adapter(WGPUInstance instance) {
+    WGPURequestAdapterOptions options = {};
+    options.powerPreference = WGPUPowerPreference_HighPerformance;
+
+    WGPURequestAdapterCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestAdapterStatus status, WGPUAdapter adapter, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestAdapterStatus_Success) *static_cast<WGPUAdapter*>(out) = adapter;
+    };
+    WGPUAdapter adapter = nullptr;
+    callback.userdata1 = &adapter;
+    wgpuInstanceRequestAdapter(instance, &options, callback);
+    return adapter;
+}
+
+WGPUDevice request_device(WGPUAdapter adapter) {
+    WGPURequestDeviceCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestDeviceStatus status, WGPUDevice device, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestDeviceStatus_Success) *static_cast<WGPUDevice*>(out) = device;
+    };
+    WGPUDevice device = nullptr;
+    callback.userdata1 = &device;
+    wgpuAdapterRequestDevice(adapter, nullptr, callback);
+    return device;
+}
+
+WGPUBuffer create_buffer(WGPUDevice device, uint64_t size, WGPUBufferUsage usage) {
+    WGPUBufferDescriptor desc = {};
+    desc.size = size;
+    desc.usage = usage;
+    return wgpuDeviceCreateBuffer(device, &desc);
+}
+
+class Gpu {
+public:
+    bool init() {
+        instance_ = wgpuCreateInstance(nullptr);
+        adapter_ = instance_ ? request_adapter(instance_) : nullptr;
+        if (!adapter_) return false;
+        device_ = request_device(adapter_);
+        if (!device_) return false;
+        queue_ = wgpuDeviceGetQueue(device_);
+
+        // An explicit layout, since derived layouts have no dynamic offsets
+        WGPUBindGroupLayoutEntry entry = {};
+        entry.binding = 0;
+        entry.visibility = WGPUShaderStage_Compute;
+        entry.buffer.type = WGPUBufferBindingType_Storage;
+        entry.buffer.hasDynamicOffset = true;
+        WGPUBindGroupLayoutDescriptor layout_desc = {};
+        layout_desc.entryCount = 1;
+        layout_desc.entries = &entry;
+        layout_ = wgpuDeviceCreateBindGroupLayout(device_, &layout_desc);
+
+        WGPUPipelineLayoutDescriptor pipeline_layout_desc = {};
+        pipeline_layout_desc.bindGroupLayoutCount = 1;
+        pipeline_layout_desc.bindGroupLayouts = &layout_;
+        pipeline_layout_ = wgpuDeviceCreatePipelineLayout(device_, &pipeline_layout_desc);
+
+        WGPUShaderSourceWGSL wgsl = {};
+        wgsl.chain.sType = WGPUSType_ShaderSourceWGSL;
+        wgsl.code = string_view(SHADER_SOURCE);
+        WGPUShaderModuleDescriptor shader_desc = {};
+        shader_desc.nextInChain = &wgsl.chain;
+        shader_ = wgpuDeviceCreateShaderModule(device_, &shader_desc);
+
+        WGPUComputePipelineDescriptor pipeline_desc = {};
+        pipeline_desc.layout = pipeline_layout_;
+        pipeline_desc.compute.module = shader_;
+        pipeline_desc.compute.entryPoint = string_view("mix");
+        pipeline_ = wgpuDeviceCreateComputePipeline(device_, &pipeline_desc);
+
+        data_ = create_buffer(device_, ELEMENTS * 4, WGPUBufferUsage_Storage | WGPUBufferUsage_CopySrc | WGPUBufferUsage_CopyDst);
+        readback_ = create_buffer(device_, ELEMENTS * 4, WGPUBufferUsage_MapRead | WGPUBufferUsage_CopyDst);
+        return true;
+    }
+
+    ~Gpu() {
+        if (readback_) wgpuBufferRelease(readback_);
+        if (data_) wgpuBufferRelease(data_);
+        if (pipeline_) wgpuComputePipelineRelease(pipeline_);
+        if (shader_) wgpuShaderModuleRelease(shader_);
+        if (pipeline_layout_) wgpuPipelineLayoutRelease(pipeline_layout_);
+        if (layout_) wgpuBindGroupLayoutRelease(layout_);
+        if (queue_) wgpuQueueRelease(queue_);
+        if (device_) wgpuDeviceRelease(device_);
+        if (adapter_) wgpuAdapterRelease(adapter_);
+        if (instance_) wgpuInstanceRelease(instance_);
+    }
+
+    std::string adapter_name() const {
+        WGPUAdapterInfo info = {};
+        wgpuAdapterGetInfo(adapter_, &info);
+        std::string name(info.device.data, info.device.length);
+        wgpuAdapterInfoFreeMembers(info);
+        return name;
+    }
+
+    void wait() const {
+        wgpuDevicePoll(device_, true, nullptr);
+    }
+
+    // A bind group whose binding spans one chunk of the data buffer
+    WGPUBindGroup bind_group(size_t chunk) const {
+        WGPUBindGroupEntry entry = {};
+        entry.binding = 0;
+        entry.buffer = data_;
+        entry.offset = 0;
+        entry.size = chunk * 4;
+        WGPUBindGroupDescriptor desc = {};
+        desc.layout = layout_;
+        desc.entryCount = 1;
+        desc.entries = &entry;
+        return wgpuDeviceCreateBindGroup(device_, &desc);
+    }
+
+    // Records dispatches for chunks [first, last), each at its own offset
+    WGPUCommandBuffer encode(WGPUBindGroup bind_group, size_t chunk, size_t first, size_t last) const {
+        WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device_, nullptr);
+        WGPUComputePassEncoder pass = wgpuCommandEncoderBeginComputePass(encoder, nullptr);
+        wgpuComputePassEncoderSetPipeline(pass, pipeline_);
+        for (size_t i = first; i < last; i++) {
+            uint32_t offset = static_cast<uint32_t>(i * chunk * 4);
+            wgpuComputePassEncoderSetBindGroup(pass, 0, bind_group, 1, &offset);
+            wgpuComputePassEncoderDispatchWorkgroups(pass, static_cast<uint32_t>(chunk / WORKGROUP_SIZE), 1, 1);
+        }
+        wgpuComputePassEncoderEnd(pass);
+        wgpuComputePassEncoderRelease(pass);
+        WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+        wgpuCommandEncoderRelease(encoder);
+        return commands;
+    }
+
+    void submit(WGPUCommandBuffer commands) const {
+        wgpuQueueSubmit(queue_, 1, &commands);
+        wgpuCommandBufferRelease(commands);
+    }
+
+    void upload(const std::vector<uint32_t>& input) const {
+        // Queued writes land with the next submission, so flush them here rather than in a timed one
+        wgpuQueueWriteBuffer(queue_, data_, 0, input.data(), input.size() * 4);
+        wgpuQueueSubmit(queue_, 0, nullptr);
+        wait();
+    }
+
+    std::vector<uint32_t> download() const {
+        WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device_, nullptr);
+        wgpuCommandEncoderCopyBufferToBuffer(encoder, data_, 0, readback_, 0, ELEMENTS * 4);
+        WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+        wgpuCommandEncoderRelease(encoder);
+        submit(commands);
+
+        WGPUBufferMapCallbackInfo callback = {};
+        callback.mode = WGPUCallbackMode_AllowSpontaneous;
+        callback.callback = [](WGPUMapAsyncStatus status, WGPUStringView, void* out, void*) {
+            *static_cast<bool*>(out) = status == WGPUMapAsyncStatus_Success;
+        };
+        bool mapped = false;
+        callback.userdata1 = &mapped;
+        wgpuBufferMapAsync(readback_, WGPUMapMode_Read, 0, ELEMENTS * 4, callback);
+        wait();
+        if (!mapped) {
+            std::cerr << "Failed to map buffer" << std::endl;
+            std::exit(1);
+        }
+        std::vector<uint32_t> out(ELEMENTS);
+        std::memcpy(out.data(), wgpuBufferGetConstMappedRange(readback_, 0, ELEMENTS * 4), ELEMENTS * 4);
+        wgpuBufferUnmap(readback_);
+        return out;
+    }
+
+    // Times the GPU ways of running the work in this many dispatches, checking the full runs
+    void measure(Row& row, const std::vector<uint32_t>& input, const std::vector<uint32_t>& expected) const {
+        size_t dispatches = row.dispatches, chunk = row.chunk();
+        WGPUBindGroup group = bind_group(chunk);
+        auto check = [&](const char* how) {
+            if (download() != expected) {
+                std::cerr << dispatches << " dispatches " << how << ": GPU result differs from the CPU" << std::endl;
+                std::exit(1);
+            }
+        };
+
+        upload(input);
+        auto start = Clock::now();
+        submit(encode(group, chunk, 0, dispatches));
+        wait();
+        row.one_pass = Clock::now() - start;
+        check("in one pass");
+
+        upload(input);
+        start = Clock::now();
+        for (size_t i = 0; i < dispatches; i++) {
+            submit(encode(group, chunk, i, i + 1));
+        }
+        wait();
+        row.per_submit = Clock::now() - start;
+        check("submitted one by one");
+
+        start = Clock::now();
+        for (size_t i = 0; i < ROUND_TRIPS; i++) {
+            submit(encode(group, chunk, 0, 1));
+            wait();
+        }
+        row.round_trip = (Clock::now() - start) / ROUND_TRIPS;
+        wgpuBindGroupRelease(group);
+    }
+
+private:
+    WGPUInstance instance_ = nullptr;
+    WGPUAdapter adapter_ = nullptr;
+    WGPUDevice device_ = nullptr;
+    WGPUQueue queue_ = nullptr;
+    WGPUBindGroupLayout layout_ = nullptr;
+    WGPUPipelineLayout pipeline_layout_ = nullptr;
+    WGPUShaderModule shader_ = nullptr;
+    WGPUComputePipeline pipeline_ = nullptr;
+    WGPUBuffer data_ = nullptr;
+    WGPUBuffer readback_ = nullptr;
+};
+
+int main() {
+    Gpu gpu;
+    if (!gpu.init()) {
+        std::cerr << "Failed to initialize WebGPU" << std::endl;
+        return 1;
+    }
+    uint64_t seed = 42;
+    std::vector<uint32_t> input(ELEMENTS);
+    for (auto& x : input) x = static_cast<uint32_t>(next_random(seed));
+
+    auto start = Clock::now();
+    std::vector<uint32_t> expected(ELEMENTS);
+    for (size_t i = 0; i < ELEMENTS; i++) expected[i] = mix(input[i]);
+    Duration cpu = Clock::now() - start;
+
+    // Warm-up: compile the pipeline and touch the buffers
+    Row warm_up{SPLITS[1], {}, {}, {}, {}};
+    gpu.measure(warm_up, input, expected);
+
+    std::vector<Row> rows;
+    Duration total{0};
+    for (size_t dispatches : SPLITS) {
+        Row row{dispatches, {}, {}, {}, cpu / dispatches};
+        gpu.measure(row, input, expected);
+        total += row.one_pass + row.per_submit + row.round_trip * ROUND_TRIPS;
+        rows.push_back(row);
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    std::cerr << "Adapter: " << gpu.adapter_name() << std::endl;
+    fprintf(stderr, "%zu elements x %u rounds, workgroups of %zu; CPU (1 thread) %.3fs\n", ELEMENTS, ROUNDS,
+            WORKGROUP_SIZE, cpu.count());
+    fprintf(stderr, "%10s %9s %6s  %10s %9s %10s  %10s %9s  %11s %11s\n", "Dispatches", "Elements", "Groups",
+            "One pass", "Occupancy", "Overhead", "Per submit", "Overhead", "Round trip", "CPU chunk");
+    double base = rows[0].one_pass.count();
+    for (const Row& r : rows) {
+        // Extra time over the single dispatch, shared out over the extra dispatches
+        auto overhead = [&](Duration d) {
+            if (r.dispatches == 1) return std::string("-");
+            char text[32];
+            std::snprintf(text, sizeof(text), "%.2f us", (d.count() - base) * 1e6 / (r.dispatches - 1));
+            return std::string(text);
+        };
+        fprintf(stderr, "%10zu %9zu %6zu  %8.3fms %8.1f%% %10s  %8.3fms %9s  %8.1f us %8.1f us\n", r.dispatches,
+                r.chunk(), r.chunk() / WORKGROUP_SIZE, r.one_pass.count() * 1e3, base / r.one_pass.count() * 100.0,
+                overhead(r.one_pass).c_str(), r.per_submit.count() * 1e3, overhead(r.per_submit).c_str(),
+                r.round_trip.count() * 1e6, r.cpu.count() * 1e6);
+    }
+    if (size_t chunk = break_even(rows)) {
+        std::cerr << "A GPU round trip beats one CPU thread from " << chunk << " elements" << std::endl;
+    } else {
+        std::cerr << "A GPU round trip never beats one CPU thread at these sizes" << std::endl;
+    }
+    uint64_t checksum = 0;
+    for (uint32_t x : expected) checksum += x;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,407 @@
+#include <webgpu/webgpu.h>
+#include <webgpu/wgpu.h>
+#include <iostream>
+#include <string>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <cstring>
+#include <cstdint>
+#include <cstdlib>
+#include <algorithm>
+#include <atomic>
+#include <thread>
+#include <stdexcept>
+
+#include "bench_measure.h"
+
+const size_t WIDTH = 4096;  // the CPU mandelbrot's default image and region
+const size_t HEIGHT = 4096;
+const uint32_t MAX_ITER = 1000;
+const size_t ITERATIONS = 5;
+const size_t WORKGROUP_SIZE = 16;
+const int FRACTION_BITS = 27;  // Q4.27: z stays within 8 of the origin until it escapes
+
+// GPU compilers may fuse and reorder float math (wgpu's Metal shaders compile with fast math), so
+// an f32 kernel could disagree with the CPU on pixels near the boundary. Both sides iterate in
+// Q4.27 fixed point instead, where every step is exact and the counts match pixel for pixel.
+// WGSL has no 64-bit integers, so the shader builds each product from 16-bit halves
+const char* SHADER_SOURCE = R"(
+    struct View {
+        min_re: i32,
+        min_im: i32,
+        step_re: i32,
+        step_im: i32,
+        width: u32,
+        height: u32,
+        max_iter: u32,
+    }
+
+    @group(0) @binding(0) var<uniform> view: View;
+    @group(0) @binding(1) var<storage, read_write> counts: array<u32>;
+
+    const FRACTION_BITS: u32 = 27u;
+    const TWO: i32 = 1 << 28;
+    const FOUR: i32 = 1 << 29;
+
+    // (a * b) >> 27, rounded toward zero like the CPU's 64-bit division
+    fn mul_fixed(a: i32, b: i32) -> i32 {
+        let ua = u32(abs(a));
+        let ub = u32(abs(b));
+        let al = ua & 0xffffu;
+        let ah = ua >> 16u;
+        let bl = ub & 0xffffu;
+        let bh = ub >> 16u;
+        let mid = ah * bl + al * bh;
+        let low = al * bl;
+        let lo = low + (mid << 16u);
+        let hi = ah * bh + (mid >> 16u) + select(0u, 1u, lo < low);
+        let magnitude = i32((hi << (32u - FRACTION_BITS)) | (lo >> FRACTION_BITS));
+        return select(magnitude, -magnitude, (a < 0) != (b < 0));
+    }
+
+    @compute @workgroup_size(16, 16)
+    fn mandelbrot(@builtin(global_invocation_id) gid: vec3<u32>) {
+        if (gid.x >= view.width || gid.y >= view.height) {
+            return;
+        }
+        let cx = view.min_re + i32(gid.x) * view.step_re;
+        let cy = view.min_im + i32(gid.y) * view.step_im;
+        var x = 0;
+        var y = 0;
+        var iteration = 0u;
+        // A part beyond 2 has escaped already; checking it first keeps the squares in range
+        while (iteration < view.max_iter && abs(x) <= TWO && abs(y) <= TWO) {
+            let xx = mul_fixed(x, x);
+            let yy = mul_fixed(y, y);
+            if (xx + yy > FOUR) {
+                break;
+            }
+            let xy = mul_fixed(x, y);
+            x = xx - yy + cx;
+            y = 2 * xy + cy;
+            iteration++;
+        }
+        counts[gid.y * view.width + gid.x] = iteration;
+    }
+)";
+
+// The image and the rectangle it samples, in fixed point; laid out as the shader's uniform
+struct View {
+    int32_t min_re;
+    int32_t min_im;
+    int32_t step_re;
+    int32_t step_im;
+    uint32_t width;
+    uint32_t height;
+    uint32_t max_iter;
+    uint32_t padding;
+};
+
+// mandelbrot's default -2.5..1.0 x -1.0..1.0 region, whose pixel steps (3.5 / 4096 and
+// 2 / 4096) are whole numbers in Q4.27, so every pixel samples exactly the same point
+View default_region(size_t width, size_t height) {
+    auto fixed = [](double x) { return static_cast<int32_t>(x * static_cast<double>(1ULL << FRACTION_BITS)); };
+    return View{fixed(-2.5), fixed(-1.0), fixed(3.5 / width), fixed(2.0 / height),
+                static_cast<uint32_t>(width), static_cast<uint32_t>(height), MAX_ITER, 0};
+}
+
+int32_t mul_fixed(int32_t a, int32_t b) {
+    return static_cast<int32_t>(static_cast<int64_t>(a) * b / (int64_t(1) << FRACTION_BITS));
+}
+
+// The shader's loop, step for step
+uint32_t mandelbrot_point(int32_t cx, int32_t cy) {
+    const int32_t TWO = 1 << 28;
+    const int32_t FOUR = 1 << 29;
+    int32_t x = 0, y = 0;
+    uint32_t iteration = 0;
+    while (iteration < MAX_ITER && std::abs(x) <= TWO && std::abs(y) <= TWO) {
+        int32_t xx = mul_fixed(x, x);
+        int32_t yy = mul_fixed(y, y);
+        if (xx + yy > FOUR) break;
+        int32_t xy = mul_fixed(x, y);
+        x = xx - yy + cx;
+        y = 2 * xy + cy;
+        iteration++;
+    }
+    return iteration;
+}
+
+// The same kernel on every CPU core, both as the cross-check and as the CPU side of the
+// comparison; threads take rows from a shared counter, as rayon balances the Rust version
+std::vector<uint32_t> cpu_mandelbrot(const View& view, size_t num_threads) {
+    std::vector<uint32_t> counts(static_cast<size_t>(view.width) * view.height);
+    std::atomic<uint32_t> next_row{0};
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < num_threads; t++) {
+        threads.emplace_back([&] {
+            for (uint32_t y = next_row++; y < view.height; y = next_row++) {
+                int32_t cy = view.min_im + static_cast<int32_t>(y) * view.step_im;
+                for (uint32_t x = 0; x < view.width; x++) {
+                    counts[static_cast<size_t>(y) * view.width + x] =
+                        mandelbrot_point(view.min_re + static_cast<int32_t>(x) * view.step_re, cy);
+                }
+            }
+        });
+    }
+    for (auto& thread : threads) thread.join();
+    return counts;
+}
+
+WGPUStringView string_view(const char* s) {
+    return WGPUStringView{s, WGPU_STRLEN};
+}
+
+// wgpu-native completes adapter and device requests before the call returns
+WGPUAdapter request_adapter(WGPUInstance instance) {
+    WGPURequestAdapterOptions options = {};
+    options.powerPreference = WGPUPowerPreference_HighPerformance;
+
+    WGPURequestAdapterCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestAdapterStatus status, WGPUAdapter adapter, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestAdapterStatus_Success) *static_cast<WGPUAdapter*>(out) = adapter;
+    };
+    WGPUAdapter adapter = nullptr;
+    callback.userdata1 = &adapter;
+    wgpuInstanceRequestAdapter(instance, &options, callback);
+    return adapter;
+}
+
+WGPUDevice request_device(WGPUAdapter adapter, bool timestamps) {
+    WGPURequestDeviceCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestDeviceStatus status, WGPUDevice device, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestDeviceStatus_Success) *static_cast<WGPUDevice*>(out) = device;
+    };
+    WGPUDevice device = nullptr;
+    callback.userdata1 = &device;
+    WGPUFeatureName timestamp_query = WGPUFeatureName_TimestampQuery;
+    WGPUDeviceDescriptor desc = {};
+    desc.requiredFeatureCount = timestamps ? 1 : 0;
+    desc.requiredFeatures = &timestamp_query;
+    wgpuAdapterRequestDevice(adapter, &desc, callback);
+    return device;
+}
+
+WGPUBuffer create_buffer(WGPUDevice device, uint64_t size, WGPUBufferUsage usage) {
+    WGPUBufferDescriptor desc = {};
+    desc.size = size;
+    desc.usage = usage;
+    return wgpuDeviceCreateBuffer(device, &desc);
+}
+
+bool map_for_read(WGPUDevice device, WGPUBuffer buffer, uint64_t bytes) {
+    WGPUBufferMapCallbackInfo map_callback = {};
+    map_callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    map_callback.callback = [](WGPUMapAsyncStatus status, WGPUStringView, void* out, void*) {
+        *static_cast<bool*>(out) = status == WGPUMapAsyncStatus_Success;
+    };
+    bool mapped = false;
+    map_callback.userdata1 = &mapped;
+    wgpuBufferMapAsync(buffer, WGPUMapMode_Read, 0, bytes, map_callback);
+    wgpuDevicePoll(device, true, nullptr);
+    return mapped;
+}
+
+// Timestamps the GPU writes at the start and end of a pass, for BENCH_MEASURE=gpu
+struct PassTimer {
+    WGPUQuerySet queries;
+    WGPUBuffer resolved;
+    WGPUBuffer readback;
+};
+
+PassTimer create_pass_timer(WGPUDevice device) {
+    WGPUQuerySetDescriptor desc = {};
+    desc.type = WGPUQueryType_Timestamp;
+    desc.count = 2;
+    return PassTimer{wgpuDeviceCreateQuerySet(device, &desc),
+                     create_buffer(device, 2 * sizeof(uint64_t), WGPUBufferUsage_QueryResolve | WGPUBufferUsage_CopySrc),
+                     create_buffer(device, 2 * sizeof(uint64_t), WGPUBufferUsage_MapRead | WGPUBufferUsage_CopyDst)};
+}
+
+// webgpu.h resolves timestamps in nanoseconds, so unlike wgpu's Rust API there is no period to apply
+double pass_seconds(WGPUDevice device, const PassTimer& timer) {
+    if (!map_for_read(device, timer.readback, 2 * sizeof(uint64_t))) {
+        throw std::runtime_error("Failed to map timestamps");
+    }
+    const uint64_t* stamps = static_cast<const uint64_t*>(
+        wgpuBufferGetConstMappedRange(timer.readback, 0, 2 * sizeof(uint64_t)));
+    uint64_t ns = stamps[1] > stamps[0] ? stamps[1] - stamps[0] : 0;
+    wgpuBufferUnmap(timer.readback);
+    return ns / 1e9;
+}
+
+// Runs one pass over the image; with a timer, also returns the GPU time between its timestamps (else -1)
+double dispatch(WGPUDevice device, WGPUQueue queue, WGPUComputePipeline pipeline, WGPUBindGroup bind_group,
+                const View& view, const PassTimer* timer = nullptr) {
+    WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device, nullptr);
+    WGPUPassTimestampWrites timestamp_writes = {};
+    WGPUComputePassDescriptor pass_desc = {};
+    if (timer) {
+        timestamp_writes.querySet = timer->queries;
+        timestamp_writes.beginningOfPassWriteIndex = 0;
+        timestamp_writes.endOfPassWriteIndex = 1;
+        pass_desc.timestampWrites = &timestamp_writes;
+    }
+    WGPUComputePassEncoder pass = wgpuCommandEncoderBeginComputePass(encoder, &pass_desc);
+    wgpuComputePassEncoderSetPipeline(pass, pipeline);
+    wgpuComputePassEncoderSetBindGroup(pass, 0, bind_group, 0, nullptr);
+    wgpuComputePassEncoderDispatchWorkgroups(pass, static_cast<uint32_t>((view.width + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE),
+                                             static_cast<uint32_t>((view.height + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE), 1);
+    wgpuComputePassEncoderEnd(pass);
+    wgpuComputePassEncoderRelease(pass);
+    if (timer) {
+        wgpuCommandEncoderResolveQuerySet(encoder, timer->queries, 0, 2, timer->resolved, 0);
+        wgpuCommandEncoderCopyBufferToBuffer(encoder, timer->resolved, 0, timer->readback, 0, 2 * sizeof(uint64_t));
+    }
+
+    WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+    wgpuQueueSubmit(queue, 1, &commands);
+    wgpuCommandBufferRelease(commands);
+    wgpuCommandEncoderRelease(encoder);
+    wgpuDevicePoll(device, true, nullptr);
+    return timer ? pass_seconds(device, *timer) : -1.0;
+}
+
+int main() {
+    auto measure = bench_measure_from_env(true);
+    View view = default_region(WIDTH, HEIGHT);
+
+    // Initialize WebGPU (wgpu-native) with whichever backend the platform offers
+    WGPUInstance instance = wgpuCreateInstance(nullptr);
+    WGPUAdapter adapter = instance ? request_adapter(instance) : nullptr;
+    if (!adapter) {
+        std::cerr << "No GPU adapter found" << std::endl;
+        return 1;
+    }
+    bool timestamps = measure->wants_gpu();
+    if (timestamps && !wgpuAdapterHasFeature(adapter, WGPUFeatureName_TimestampQuery)) {
+        std::cerr << "BENCH_MEASURE=gpu, but the adapter has no timestamp queries" << std::endl;
+        return 1;
+    }
+    WGPUDevice device = request_device(adapter, timestamps);
+    if (!device) {
+        std::cerr << "Failed to create device" << std::endl;
+        return 1;
+    }
+    WGPUQueue queue = wgpuDeviceGetQueue(device);
+
+    WGPUShaderSourceWGSL wgsl = {};
+    wgsl.chain.sType = WGPUSType_ShaderSourceWGSL;
+    wgsl.code = string_view(SHADER_SOURCE);
+    WGPUShaderModuleDescriptor shader_desc = {};
+    shader_desc.nextInChain = &wgsl.chain;
+    WGPUShaderModule shader = wgpuDeviceCreateShaderModule(device, &shader_desc);
+
+    WGPUComputePipelineDescriptor pipeline_desc = {};
+    pipeline_desc.compute.module = shader;
+    pipeline_desc.compute.entryPoint = string_view("mandelbrot");
+    WGPUComputePipeline pipeline = wgpuDeviceCreateComputePipeline(device, &pipeline_desc);
+    if (!pipeline) {
+        std::cerr << "Failed to create pipeline" << std::endl;
+        return 1;
+    }
+
+    const uint64_t count_bytes = static_cast<uint64_t>(view.width) * view.height * sizeof(uint32_t);
+    WGPUBuffer buffer_view = create_buffer(device, sizeof(View), WGPUBufferUsage_Uniform | WGPUBufferUsage_CopyDst);
+    wgpuQueueWriteBuffer(queue, buffer_view, 0, &view, sizeof(View));
+    WGPUBuffer buffer_counts = create_buffer(device, count_bytes, WGPUBufferUsage_Storage | WGPUBufferUsage_CopySrc);
+    WGPUBuffer buffer_readback = create_buffer(device, count_bytes, WGPUBufferUsage_MapRead | WGPUBufferUsage_CopyDst);
+
+    WGPUBuffer bound[] = {buffer_view, buffer_counts};
+    WGPUBindGroupEntry entries[2] = {};
+    for (uint32_t i = 0; i < 2; i++) {
+        entries[i].binding = i;
+        entries[i].buffer = bound[i];
+        entries[i].size = WGPU_WHOLE_SIZE;
+    }
+    WGPUBindGroupLayout layout = wgpuComputePipelineGetBindGroupLayout(pipeline, 0);
+    WGPUBindGroupDescriptor bind_group_desc = {};
+    bind_group_desc.layout = layout;
+    bind_group_desc.entryCount = 2;
+    bind_group_desc.entries = entries;
+    WGPUBindGroup bind_group = wgpuDeviceCreateBindGroup(device, &bind_group_desc);
+
+    PassTimer timer = {};
+    if (timestamps) timer = create_pass_timer(device);
+
+    // Warm-up
+    dispatch(device, queue, pipeline, bind_group, view);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    for (size_t iter = 0; iter < ITERATIONS; iter++) {
+        double seconds = dispatch(device, queue, pipeline, bind_group, view, timestamps ? &timer : nullptr);
+        if (timestamps) measure->record_gpu(seconds);
+    }
+    measure->stop();
+    std::chrono::duration<double> gpu_duration = std::chrono::high_resolution_clock::now() - start;
+    double gpu_seconds = gpu_duration.count() / ITERATIONS;
+
+    WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device, nullptr);
+    wgpuCommandEncoderCopyBufferToBuffer(encoder, buffer_counts, 0, buffer_readback, 0, count_bytes);
+    WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+    wgpuQueueSubmit(queue, 1, &commands);
+    if (!map_for_read(device, buffer_readback, count_bytes)) {
+        std::cerr << "Failed to map readback buffer" << std::endl;
+        return 1;
+    }
+    std::vector<uint32_t> gpu_counts(static_cast<size_t>(view.width) * view.height);
+    std::memcpy(gpu_counts.data(), wgpuBufferGetConstMappedRange(buffer_readback, 0, count_bytes), count_bytes);
+    wgpuBufferUnmap(buffer_readback);
+
+    // The CPU renders the same image once, timed for the comparison
+    size_t num_threads = std::max(1u, std::thread::hardware_concurrency());
+    start = std::chrono::high_resolution_clock::now();
+    std::vector<uint32_t> cpu_counts = cpu_mandelbrot(view, num_threads);
+    std::chrono::duration<double> cpu_duration = std::chrono::high_resolution_clock::now() - start;
+    size_t mismatches = 0;
+    for (size_t i = 0; i < cpu_counts.size(); i++) {
+        mismatches += gpu_counts[i] != cpu_counts[i];
+    }
+    if (mismatches != 0) {
+        std::cerr << mismatches << " of " << cpu_counts.size() << " GPU iteration counts differ from the CPU's" << std::endl;
+        return 1;
+    }
+
+    uint64_t checksum = 0;
+    for (uint32_t count : gpu_counts) checksum += count;
+
+    WGPUAdapterInfo info = {};
+    wgpuAdapterGetInfo(adapter, &info);
+
+    measure->report();
+    std::cerr << "Adapter: " << std::string(info.device.data, info.device.length) << std::endl;
+    std::cerr << "Region: " << view.width << "x" << view.height << ", re -2.5 .. 1.0, im -1.0 .. 1.0, Q4.27 fixed point, "
+              << MAX_ITER << " iterations max" << std::endl;
+    std::cerr << std::fixed << std::setprecision(6) << "GPU: " << gpu_seconds << "s per image; CPU (" << num_threads
+              << " threads): " << cpu_duration.count() << "s; GPU speedup " << std::setprecision(1)
+              << cpu_duration.count() / gpu_seconds << "x" << std::endl;
+    std::cerr << "Cross-check: all " << cpu_counts.size() << " iteration counts match the CPU" << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    wgpuAdapterInfoFreeMembers(info);
+    wgpuCommandBufferRelease(commands);
+    wgpuCommandEncoderRelease(encoder);
+    wgpuBindGroupRelease(bind_group);
+    wgpuBindGroupLayoutRelease(layout);
+    for (WGPUBuffer buffer : {buffer_view, buffer_counts, buffer_readback}) {
+        wgpuBufferRelease(buffer);
+    }
+    if (timestamps) {
+        wgpuBufferRelease(timer.resolved);
+        wgpuBufferRelease(timer.readback);
+        wgpuQuerySetRelease(timer.queries);
+    }
+    wgpuComputePipelineRelease(pipeline);
+    wgpuShaderModuleRelease(shader);
+    wgpuQueueRelease(queue);
+    wgpuDeviceRelease(device);
+    wgpuAdapterRelease(adapter);
+    wgpuInstanceRelease(instance);
+
+    return 0;
+}
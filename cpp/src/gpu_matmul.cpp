@@ -0,0 +1,325 @@
+#include <webgpu/webgpu.h>
+#include <webgpu/wgpu.h>
+#include <iostream>
+#include <string>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <cmath>
+#include <cstring>
+#include <cstdlib>
+#include <cstdint>
+#include <algorithm>
+
+const size_t DEFAULT_SIZE = 4096;  // 4096x4096 matrices
+const size_t CHECK_SIZE = 256;     // cross-checked against the CPU
+const size_t ITERATIONS = 5;
+const size_t TILE = 64;            // output block computed by one workgroup
+const float TOLERANCE = 1e-3f;
+
+using Duration = std::chrono::duration<double>;
+
+// Tiled SGEMM: each 16x16 workgroup stages 64x16 slices of A and 16x64 slices of B in
+// workgroup memory and every thread accumulates a 4x4 block of C in registers
+const char* SHADER_SOURCE = R"(
+    @group(0) @binding(0) var<storage, read> a: array<f32>;
+    @group(0) @binding(1) var<storage, read> b: array<f32>;
+    @group(0) @binding(2) var<storage, read_write> c: array<f32>;
+    @group(0) @binding(3) var<uniform> n: u32;
+
+    const TILE: u32 = 64u;
+    const DEPTH: u32 = 16u;
+    const THREADS: u32 = 256u;
+
+    var<workgroup> tile_a: array<f32, 1024>; // TILE rows x DEPTH
+    var<workgroup> tile_b: array<f32, 1024>; // DEPTH rows x TILE
+
+    @compute @workgroup_size(16, 16)
+    fn matmul(@builtin(workgroup_id) wg: vec3<u32>, @builtin(local_invocation_id) lid: vec3<u32>) {
+        let row0 = wg.y * TILE;
+        let col0 = wg.x * TILE;
+        let local = lid.y * 16u + lid.x;
+        var acc: array<f32, 16>;
+
+        for (var k0 = 0u; k0 < n; k0 += DEPTH) {
+            for (var i = 0u; i < 4u; i++) {
+                let e = local + i * THREADS;
+                tile_a[e] = a[(row0 + e / DEPTH) * n + k0 + e % DEPTH];
+                tile_b[e] = b[(k0 + e / TILE) * n + col0 + e % TILE];
+            }
+            workgroupBarrier();
+
+            for (var k = 0u; k < DEPTH; k++) {
+                var bv: array<f32, 4>;
+                for (var j = 0u; j < 4u; j++) {
+                    bv[j] = tile_b[k * TILE + lid.x + j * 16u];
+                }
+                for (var i = 0u; i < 4u; i++) {
+                    let av = tile_a[(lid.y + i * 16u) * DEPTH + k];
+                    for (var j = 0u; j < 4u; j++) {
+                        acc[i * 4u + j] += av * bv[j];
+                    }
+                }
+            }
+            workgroupBarrier();
+        }
+
+        for (var i = 0u; i < 4u; i++) {
+            for (var j = 0u; j < 4u; j++) {
+                c[(row0 + lid.y + i * 16u) * n + col0 + lid.x + j * 16u] = acc[i * 4u + j];
+            }
+        }
+    }
+)";
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+std::vector<float> random_matrix(size_t n, uint64_t seed) {
+    std::vector<float> m(n * n);
+    for (auto& x : m) x = static_cast<float>(next_random(seed) % 2001) / 1000.0f - 1.0f;
+    return m;
+}
+
+// Reference i-k-j product with double accumulation
+std::vector<float> cpu_matmul(const std::vector<float>& a, const std::vector<float>& b, size_t n) {
+    std::vector<double> c(n * n, 0.0);
+    for (size_t i = 0; i < n; i++) {
+        for (size_t k = 0; k < n; k++) {
+            double aik = a[i * n + k];
+            for (size_t j = 0; j < n; j++) {
+                c[i * n + j] += aik * b[k * n + j];
+            }
+        }
+    }
+    return std::vector<float>(c.begin(), c.end());
+}
+
+WGPUStringView string_view(const char* s) {
+    return WGPUStringView{s, WGPU_STRLEN};
+}
+
+// wgpu-native completes adapter and device requests before the call returns
+WGPUAdapter request_adapter(WGPUInstance instance) {
+    WGPURequestAdapterOptions options = {};
+    options.powerPreference = WGPUPowerPreference_HighPerformance;
+
+    WGPURequestAdapterCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestAdapterStatus status, WGPUAdapter adapter, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestAdapterStatus_Success) *static_cast<WGPUAdapter*>(out) = adapter;
+    };
+    WGPUAdapter adapter = nullptr;
+    callback.userdata1 = &adapter;
+    wgpuInstanceRequestAdapter(instance, &options, callback);
+    return adapter;
+}
+
+WGPUDevice request_device(WGPUAdapter adapter) {
+    WGPURequestDeviceCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestDeviceStatus status, WGPUDevice device, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestDeviceStatus_Success) *static_cast<WGPUDevice*>(out) = device;
+    };
+    WGPUDevice device = nullptr;
+    callback.userdata1 = &device;
+    wgpuAdapterRequestDevice(adapter, nullptr, callback);
+    return device;
+}
+
+WGPUBuffer create_buffer(WGPUDevice device, uint64_t size, WGPUBufferUsage usage) {
+    WGPUBufferDescriptor desc = {};
+    desc.size = size;
+    desc.usage = usage;
+    return wgpuDeviceCreateBuffer(device, &desc);
+}
+
+class Gpu {
+public:
+    bool init() {
+        instance_ = wgpuCreateInstance(nullptr);
+        adapter_ = instance_ ? request_adapter(instance_) : nullptr;
+        if (!adapter_) return false;
+        device_ = request_device(adapter_);
+        if (!device_) return false;
+        queue_ = wgpuDeviceGetQueue(device_);
+
+        WGPUShaderSourceWGSL wgsl = {};
+        wgsl.chain.sType = WGPUSType_ShaderSourceWGSL;
+        wgsl.code = string_view(SHADER_SOURCE);
+        WGPUShaderModuleDescriptor shader_desc = {};
+        shader_desc.nextInChain = &wgsl.chain;
+        shader_ = wgpuDeviceCreateShaderModule(device_, &shader_desc);
+
+        WGPUComputePipelineDescriptor pipeline_desc = {};
+        pipeline_desc.compute.module = shader_;
+        pipeline_desc.compute.entryPoint = string_view("matmul");
+        pipeline_ = wgpuDeviceCreateComputePipeline(device_, &pipeline_desc);
+        return pipeline_ != nullptr;
+    }
+
+    ~Gpu() {
+        if (pipeline_) wgpuComputePipelineRelease(pipeline_);
+        if (shader_) wgpuShaderModuleRelease(shader_);
+        if (queue_) wgpuQueueRelease(queue_);
+        if (device_) wgpuDeviceRelease(device_);
+        if (adapter_) wgpuAdapterRelease(adapter_);
+        if (instance_) wgpuInstanceRelease(instance_);
+    }
+
+    std::string adapter_name() const {
+        WGPUAdapterInfo info = {};
+        wgpuAdapterGetInfo(adapter_, &info);
+        std::string name(info.device.data, info.device.length);
+        wgpuAdapterInfoFreeMembers(info);
+        return name;
+    }
+
+    // Uploads A and B, runs the kernel `iterations` times and reads C back; only the dispatches are timed
+    Duration multiply(const std::vector<float>& a, const std::vector<float>& b, size_t n, size_t iterations,
+                      std::vector<float>& c) {
+        const uint64_t bytes = n * n * sizeof(float);
+        WGPUBuffer buffer_a = create_buffer(device_, bytes, WGPUBufferUsage_Storage | WGPUBufferUsage_CopyDst);
+        wgpuQueueWriteBuffer(queue_, buffer_a, 0, a.data(), bytes);
+        WGPUBuffer buffer_b = create_buffer(device_, bytes, WGPUBufferUsage_Storage | WGPUBufferUsage_CopyDst);
+        wgpuQueueWriteBuffer(queue_, buffer_b, 0, b.data(), bytes);
+        WGPUBuffer buffer_c = create_buffer(device_, bytes, WGPUBufferUsage_Storage | WGPUBufferUsage_CopySrc);
+        uint32_t n_val = static_cast<uint32_t>(n);
+        WGPUBuffer buffer_n = create_buffer(device_, sizeof(uint32_t), WGPUBufferUsage_Uniform | WGPUBufferUsage_CopyDst);
+        wgpuQueueWriteBuffer(queue_, buffer_n, 0, &n_val, sizeof(uint32_t));
+        WGPUBuffer buffer_readback = create_buffer(device_, bytes, WGPUBufferUsage_MapRead | WGPUBufferUsage_CopyDst);
+
+        WGPUBuffer bound[] = {buffer_a, buffer_b, buffer_c, buffer_n};
+        WGPUBindGroupEntry entries[4] = {};
+        for (uint32_t i = 0; i < 4; i++) {
+            entries[i].binding = i;
+            entries[i].buffer = bound[i];
+            entries[i].size = WGPU_WHOLE_SIZE;
+        }
+        WGPUBindGroupLayout layout = wgpuComputePipelineGetBindGroupLayout(pipeline_, 0);
+        WGPUBindGroupDescriptor bind_group_desc = {};
+        bind_group_desc.layout = layout;
+        bind_group_desc.entryCount = 4;
+        bind_group_desc.entries = entries;
+        WGPUBindGroup bind_group = wgpuDeviceCreateBindGroup(device_, &bind_group_desc);
+        uint32_t groups = static_cast<uint32_t>(n / TILE);
+
+        auto start = std::chrono::high_resolution_clock::now();
+        for (size_t iter = 0; iter < iterations; iter++) {
+            WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device_, nullptr);
+            WGPUComputePassEncoder pass = wgpuCommandEncoderBeginComputePass(encoder, nullptr);
+            wgpuComputePassEncoderSetPipeline(pass, pipeline_);
+            wgpuComputePassEncoderSetBindGroup(pass, 0, bind_group, 0, nullptr);
+            wgpuComputePassEncoderDispatchWorkgroups(pass, groups, groups, 1);
+            wgpuComputePassEncoderEnd(pass);
+            wgpuComputePassEncoderRelease(pass);
+            WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+            wgpuQueueSubmit(queue_, 1, &commands);
+            wgpuCommandBufferRelease(commands);
+            wgpuCommandEncoderRelease(encoder);
+            wgpuDevicePoll(device_, true, nullptr);
+        }
+        Duration duration = std::chrono::high_resolution_clock::now() - start;
+
+        WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device_, nullptr);
+        wgpuCommandEncoderCopyBufferToBuffer(encoder, buffer_c, 0, buffer_readback, 0, bytes);
+        WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+        wgpuQueueSubmit(queue_, 1, &commands);
+
+        WGPUBufferMapCallbackInfo map_callback = {};
+        map_callback.mode = WGPUCallbackMode_AllowSpontaneous;
+        map_callback.callback = [](WGPUMapAsyncStatus status, WGPUStringView, void* out, void*) {
+            *static_cast<bool*>(out) = status == WGPUMapAsyncStatus_Success;
+        };
+        bool mapped = false;
+        map_callback.userdata1 = &mapped;
+        wgpuBufferMapAsync(buffer_readback, WGPUMapMode_Read, 0, bytes, map_callback);
+        wgpuDevicePoll(device_, true, nullptr);
+        c.assign(n * n, 0.0f);
+        if (mapped) {
+            std::memcpy(c.data(), wgpuBufferGetConstMappedRange(buffer_readback, 0, bytes), bytes);
+            wgpuBufferUnmap(buffer_readback);
+        } else {
+            std::cerr << "Failed to map readback buffer" << std::endl;
+        }
+
+        wgpuCommandBufferRelease(commands);
+        wgpuCommandEncoderRelease(encoder);
+        wgpuBindGroupRelease(bind_group);
+        wgpuBindGroupLayoutRelease(layout);
+        for (WGPUBuffer buffer : {buffer_a, buffer_b, buffer_c, buffer_n, buffer_readback}) {
+            wgpuBufferRelease(buffer);
+        }
+        return duration;
+    }
+
+private:
+    WGPUInstance instance_ = nullptr;
+    WGPUAdapter adapter_ = nullptr;
+    WGPUDevice device_ = nullptr;
+    WGPUQueue queue_ = nullptr;
+    WGPUShaderModule shader_ = nullptr;
+    WGPUComputePipeline pipeline_ = nullptr;
+};
+
+size_t parse_size(int argc, char** argv) {
+    for (int i = 1; i + 1 < argc; i++) {
+        if (std::strcmp(argv[i], "--size") == 0) {
+            long n = std::atol(argv[i + 1]);
+            if (n <= 0 || n % TILE != 0) {
+                std::cerr << "--size expects a positive multiple of 64" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<size_t>(n);
+        }
+    }
+    return DEFAULT_SIZE;
+}
+
+int main(int argc, char** argv) {
+    size_t n = parse_size(argc, argv);
+    Gpu gpu;
+    if (!gpu.init()) {
+        std::cerr << "Failed to initialize WebGPU" << std::endl;
+        return 1;
+    }
+
+    // Cross-check the kernel against the CPU at a small size (doubles as warm-up)
+    std::vector<float> a = random_matrix(CHECK_SIZE, 1);
+    std::vector<float> b = random_matrix(CHECK_SIZE, 2);
+    std::vector<float> gpu_c;
+    gpu.multiply(a, b, CHECK_SIZE, 1, gpu_c);
+    std::vector<float> cpu_c = cpu_matmul(a, b, CHECK_SIZE);
+    float max_error = 0.0f;
+    for (size_t i = 0; i < gpu_c.size(); i++) {
+        max_error = std::max(max_error, std::abs(gpu_c[i] - cpu_c[i]));
+    }
+    if (!(max_error < TOLERANCE)) {
+        std::cerr << "GPU result differs from CPU reference by " << max_error << std::endl;
+        return 1;
+    }
+
+    // Benchmark
+    a = random_matrix(n, 3);
+    b = random_matrix(n, 4);
+    std::vector<float> c;
+    Duration duration = gpu.multiply(a, b, n, ITERATIONS, c);
+
+    double gflops = 2.0 * std::pow(static_cast<double>(n), 3) * ITERATIONS / duration.count() / 1e9;
+    double checksum = 0.0;
+    for (size_t i = 0; i < c.size(); i += 1000) {
+        checksum += c[i];
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
+    std::cerr << "Adapter: " << gpu.adapter_name() << std::endl;
+    std::cerr << n << "x" << n << " SGEMM x" << ITERATIONS << ": " << std::setprecision(1) << gflops << " GFLOP/s"
+              << std::endl;
+    std::cerr << "Cross-check (" << CHECK_SIZE << "x" << CHECK_SIZE << "): max abs error " << std::scientific
+              << std::setprecision(2) << max_error << std::endl;
+    std::cerr << "Checksum: " << std::fixed << std::setprecision(6) << checksum << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,327 @@
+#include <webgpu/webgpu.h>
+#include <webgpu/wgpu.h>
+#include <iostream>
+#include <string>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <cstring>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <algorithm>
+
+const size_t SIZES[] = {4 << 10, 64 << 10, 1 << 20, 16 << 20, 128 << 20};  // 4 KiB .. 128 MiB
+const size_t BYTES_PER_SIZE = 256 << 20;  // repetitions scaled to move ~256 MiB per size
+const size_t MAX_REPS = 256;
+const size_t MIN_REPS = 4;
+const size_t LAUNCHES = 1000;             // submit-and-wait round trips
+const size_t BATCHED_DISPATCHES = 10000;  // dispatches recorded into a single submission
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+// Minimal kernel: one atomic increment, so launch cost is all that gets measured
+const char* SHADER_SOURCE = R"(
+    @group(0) @binding(0) var<storage, read_write> counter: atomic<u32>;
+
+    @compute @workgroup_size(1)
+    fn noop() {
+        atomicAdd(&counter, 1u);
+    }
+)";
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+struct Timings {
+    Duration upload_private{0};
+    Duration upload_shared{0};
+    Duration download_private{0};
+    Duration download_shared{0};
+
+    Duration total() const {
+        return upload_private + upload_shared + download_private + download_shared;
+    }
+};
+
+WGPUStringView string_view(const char* s) {
+    return WGPUStringView{s, WGPU_STRLEN};
+}
+
+// wgpu-native completes adapter and device requests before the call returns
+WGPUAdapter request_adapter(WGPUInstance instance) {
+    WGPURequestAdapterOptions options = {};
+    options.powerPreference = WGPUPowerPreference_HighPerformance;
+
+    WGPURequestAdapterCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestAdapterStatus status, WGPUAdapter adapter, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestAdapterStatus_Success) *static_cast<WGPUAdapter*>(out) = adapter;
+    };
+    WGPUAdapter adapter = nullptr;
+    callback.userdata1 = &adapter;
+    wgpuInstanceRequestAdapter(instance, &options, callback);
+    return adapter;
+}
+
+WGPUDevice request_device(WGPUAdapter adapter) {
+    WGPURequestDeviceCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestDeviceStatus status, WGPUDevice device, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestDeviceStatus_Success) *static_cast<WGPUDevice*>(out) = device;
+    };
+    WGPUDevice device = nullptr;
+    callback.userdata1 = &device;
+    wgpuAdapterRequestDevice(adapter, nullptr, callback);
+    return device;
+}
+
+WGPUBuffer create_buffer(WGPUDevice device, uint64_t size, WGPUBufferUsage usage) {
+    WGPUBufferDescriptor desc = {};
+    desc.size = size;
+    desc.usage = usage;
+    return wgpuDeviceCreateBuffer(device, &desc);
+}
+
+class Gpu {
+public:
+    bool init() {
+        instance_ = wgpuCreateInstance(nullptr);
+        adapter_ = instance_ ? request_adapter(instance_) : nullptr;
+        if (!adapter_) return false;
+        device_ = request_device(adapter_);
+        if (!device_) return false;
+        queue_ = wgpuDeviceGetQueue(device_);
+        return true;
+    }
+
+    ~Gpu() {
+        if (queue_) wgpuQueueRelease(queue_);
+        if (device_) wgpuDeviceRelease(device_);
+        if (adapter_) wgpuAdapterRelease(adapter_);
+        if (instance_) wgpuInstanceRelease(instance_);
+    }
+
+    std::string adapter_name() const {
+        WGPUAdapterInfo info = {};
+        wgpuAdapterGetInfo(adapter_, &info);
+        std::string name(info.device.data, info.device.length);
+        wgpuAdapterInfoFreeMembers(info);
+        return name;
+    }
+
+    WGPUBuffer buffer(size_t size, WGPUBufferUsage usage) const {
+        return create_buffer(device_, size, usage);
+    }
+
+    void wait() const {
+        wgpuDevicePoll(device_, true, nullptr);
+    }
+
+    void map(WGPUBuffer buffer, WGPUMapMode mode, size_t size) const {
+        WGPUBufferMapCallbackInfo callback = {};
+        callback.mode = WGPUCallbackMode_AllowSpontaneous;
+        callback.callback = [](WGPUMapAsyncStatus status, WGPUStringView, void* out, void*) {
+            *static_cast<bool*>(out) = status == WGPUMapAsyncStatus_Success;
+        };
+        bool mapped = false;
+        callback.userdata1 = &mapped;
+        wgpuBufferMapAsync(buffer, mode, 0, size, callback);
+        wait();
+        if (!mapped) {
+            std::cerr << "Failed to map buffer" << std::endl;
+            std::exit(1);
+        }
+    }
+
+    void copy(WGPUBuffer from, WGPUBuffer to, size_t size) const {
+        WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device_, nullptr);
+        wgpuCommandEncoderCopyBufferToBuffer(encoder, from, 0, to, 0, size);
+        submit(encoder);
+    }
+
+    // Shared: the CPU writes straight into host-visible memory
+    void upload_shared(WGPUBuffer upload, const std::vector<uint8_t>& data) const {
+        map(upload, WGPUMapMode_Write, data.size());
+        std::memcpy(wgpuBufferGetMappedRange(upload, 0, data.size()), data.data(), data.size());
+        wgpuBufferUnmap(upload);
+    }
+
+    // Private: the same write, followed by a GPU copy into device-local memory
+    void upload_private(WGPUBuffer upload, WGPUBuffer device_local, const std::vector<uint8_t>& data) const {
+        upload_shared(upload, data);
+        copy(upload, device_local, data.size());
+    }
+
+    void download_shared(WGPUBuffer readback, uint8_t* out, size_t size) const {
+        map(readback, WGPUMapMode_Read, size);
+        std::memcpy(out, wgpuBufferGetConstMappedRange(readback, 0, size), size);
+        wgpuBufferUnmap(readback);
+    }
+
+    void download_private(WGPUBuffer device_local, WGPUBuffer readback, uint8_t* out, size_t size) const {
+        copy(device_local, readback, size);
+        download_shared(readback, out, size);
+    }
+
+    Timings measure(size_t size, size_t reps, uint64_t& checksum) const {
+        uint64_t seed = size;
+        std::vector<uint8_t> data(size);
+        for (auto& x : data) x = static_cast<uint8_t>(next_random(seed));
+        std::vector<uint8_t> out(size);
+
+        WGPUBuffer upload = buffer(size, WGPUBufferUsage_MapWrite | WGPUBufferUsage_CopySrc);
+        WGPUBuffer device_local = buffer(size, WGPUBufferUsage_Storage | WGPUBufferUsage_CopySrc | WGPUBufferUsage_CopyDst);
+        WGPUBuffer readback = buffer(size, WGPUBufferUsage_MapRead | WGPUBufferUsage_CopyDst);
+
+        auto time = [reps](auto&& f) {
+            auto start = Clock::now();
+            for (size_t i = 0; i < reps; i++) {
+                f();
+            }
+            return Duration(Clock::now() - start);
+        };
+        Timings timings;
+        timings.upload_shared = time([&] { upload_shared(upload, data); });
+        timings.upload_private = time([&] { upload_private(upload, device_local, data); });
+        timings.download_private = time([&] { download_private(device_local, readback, out.data(), size); });
+        timings.download_shared = time([&] { download_shared(readback, out.data(), size); });
+
+        if (data != out) {
+            std::cerr << "Round trip corrupted the " << size << "-byte buffer" << std::endl;
+            std::exit(1);
+        }
+        for (size_t i = 0; i < size; i += 1024) {
+            checksum += out[i];
+        }
+        for (WGPUBuffer b : {upload, device_local, readback}) {
+            wgpuBufferRelease(b);
+        }
+        return timings;
+    }
+
+    // Returns the submit-and-wait round trip and batched dispatch durations; count receives the final counter value
+    std::pair<Duration, Duration> launch_latency(uint32_t& count) const {
+        WGPUShaderSourceWGSL wgsl = {};
+        wgsl.chain.sType = WGPUSType_ShaderSourceWGSL;
+        wgsl.code = string_view(SHADER_SOURCE);
+        WGPUShaderModuleDescriptor shader_desc = {};
+        shader_desc.nextInChain = &wgsl.chain;
+        WGPUShaderModule shader = wgpuDeviceCreateShaderModule(device_, &shader_desc);
+
+        WGPUComputePipelineDescriptor pipeline_desc = {};
+        pipeline_desc.compute.module = shader;
+        pipeline_desc.compute.entryPoint = string_view("noop");
+        WGPUComputePipeline pipeline = wgpuDeviceCreateComputePipeline(device_, &pipeline_desc);
+
+        WGPUBuffer counter = buffer(4, WGPUBufferUsage_Storage | WGPUBufferUsage_CopySrc | WGPUBufferUsage_CopyDst);
+        WGPUBuffer readback = buffer(4, WGPUBufferUsage_MapRead | WGPUBufferUsage_CopyDst);
+        WGPUBindGroupEntry entry = {};
+        entry.binding = 0;
+        entry.buffer = counter;
+        entry.size = WGPU_WHOLE_SIZE;
+        WGPUBindGroupLayout layout = wgpuComputePipelineGetBindGroupLayout(pipeline, 0);
+        WGPUBindGroupDescriptor bind_group_desc = {};
+        bind_group_desc.layout = layout;
+        bind_group_desc.entryCount = 1;
+        bind_group_desc.entries = &entry;
+        WGPUBindGroup bind_group = wgpuDeviceCreateBindGroup(device_, &bind_group_desc);
+
+        auto launch = [&](size_t dispatches) {
+            WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device_, nullptr);
+            WGPUComputePassEncoder pass = wgpuCommandEncoderBeginComputePass(encoder, nullptr);
+            wgpuComputePassEncoderSetPipeline(pass, pipeline);
+            wgpuComputePassEncoderSetBindGroup(pass, 0, bind_group, 0, nullptr);
+            for (size_t i = 0; i < dispatches; i++) {
+                wgpuComputePassEncoderDispatchWorkgroups(pass, 1, 1, 1);
+            }
+            wgpuComputePassEncoderEnd(pass);
+            wgpuComputePassEncoderRelease(pass);
+            submit(encoder);
+        };
+
+        // Warm-up, then reset the counter
+        launch(1);
+        uint32_t zero = 0;
+        wgpuQueueWriteBuffer(queue_, counter, 0, &zero, sizeof(zero));
+
+        auto start = Clock::now();
+        for (size_t i = 0; i < LAUNCHES; i++) {
+            launch(1);
+        }
+        Duration round_trip = Clock::now() - start;
+
+        start = Clock::now();
+        launch(BATCHED_DISPATCHES);
+        Duration batched = Clock::now() - start;
+
+        download_private(counter, readback, reinterpret_cast<uint8_t*>(&count), sizeof(count));
+
+        wgpuBindGroupRelease(bind_group);
+        wgpuBindGroupLayoutRelease(layout);
+        wgpuBufferRelease(counter);
+        wgpuBufferRelease(readback);
+        wgpuComputePipelineRelease(pipeline);
+        wgpuShaderModuleRelease(shader);
+        return {round_trip, batched};
+    }
+
+private:
+    void submit(WGPUCommandEncoder encoder) const {
+        WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+        wgpuQueueSubmit(queue_, 1, &commands);
+        wgpuCommandBufferRelease(commands);
+        wgpuCommandEncoderRelease(encoder);
+        wait();
+    }
+
+    WGPUInstance instance_ = nullptr;
+    WGPUAdapter adapter_ = nullptr;
+    WGPUDevice device_ = nullptr;
+    WGPUQueue queue_ = nullptr;
+};
+
+std::string label(size_t size) {
+    return size >= (1 << 20) ? std::to_string(size >> 20) + " MiB" : std::to_string(size >> 10) + " KiB";
+}
+
+int main() {
+    Gpu gpu;
+    if (!gpu.init()) {
+        std::cerr << "Failed to initialize WebGPU" << std::endl;
+        return 1;
+    }
+    uint64_t checksum = 0;
+    Duration total{0};
+
+    std::cerr << "Adapter: " << gpu.adapter_name() << std::endl;
+    fprintf(stderr, "%8s %6s  %12s %12s %12s %12s\n", "Size", "Reps", "H2D shared", "H2D private", "D2H shared", "D2H private");
+    for (size_t size : SIZES) {
+        size_t reps = std::clamp(BYTES_PER_SIZE / size, MIN_REPS, MAX_REPS);
+        Timings timings = gpu.measure(size, reps, checksum);
+        auto gbps = [&](Duration d) { return static_cast<double>(size * reps) / d.count() / 1e9; };
+        fprintf(stderr, "%8s %6zu  %7.2f GB/s %7.2f GB/s %7.2f GB/s %7.2f GB/s\n", label(size).c_str(), reps,
+                gbps(timings.upload_shared), gbps(timings.upload_private), gbps(timings.download_shared),
+                gbps(timings.download_private));
+        total += timings.total();
+    }
+
+    uint32_t count = 0;
+    auto [round_trip, batched] = gpu.launch_latency(count);
+    if (count != LAUNCHES + BATCHED_DISPATCHES) {
+        std::cerr << "Kernel launches were lost" << std::endl;
+        return 1;
+    }
+    total += round_trip + batched;
+    checksum += count;
+
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    fprintf(stderr, "Launch latency (submit + wait): %.1f us\n", round_trip.count() * 1e6 / LAUNCHES);
+    fprintf(stderr, "Batched dispatch cost: %.3f us\n", batched.count() * 1e6 / BATCHED_DISPATCHES);
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
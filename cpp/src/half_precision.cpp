@@ -0,0 +1,448 @@
+#ifdef HAVE_WGPU
+#include <webgpu/webgpu.h>
+#include <webgpu/wgpu.h>
+#endif
+#include <iostream>
+#include <string>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <cmath>
+#include <cstring>
+#include <cstdint>
+#include <algorithm>
+
+const size_t VECTOR_LEN = 16 * 1024 * 1024;  // 16M elements
+const size_t VECTOR_PASSES = 10;
+const size_t GEMM_SIZE = 1024;               // 1024x1024 matrices
+const size_t GPU_ITERATIONS = 10;
+const float ALPHA = 0.1f;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+// bfloat16 has no portable C++20 type: store the top 16 bits of a float, rounding to nearest even
+struct bf16 {
+    uint16_t bits = 0;
+};
+
+// Reduced-precision types are storage formats here: every kernel widens to float, computes with
+// explicit FMAs and rounds once on store, which is how CPUs without half ALUs run them
+template <typename T> struct Element;
+
+template <> struct Element<float> {
+    static constexpr const char* NAME = "f32";
+    static float from_f32(float x) { return x; }
+    static float to_f32(float x) { return x; }
+};
+
+template <> struct Element<_Float16> {
+    static constexpr const char* NAME = "f16";
+    static _Float16 from_f32(float x) { return static_cast<_Float16>(x); }
+    static float to_f32(_Float16 x) { return static_cast<float>(x); }
+};
+
+template <> struct Element<bf16> {
+    static constexpr const char* NAME = "bf16";
+    static bf16 from_f32(float x) {
+        uint32_t bits;
+        std::memcpy(&bits, &x, sizeof(bits));
+        if ((bits & 0x7FFFFFFFu) > 0x7F800000u) {
+            return bf16{static_cast<uint16_t>((bits >> 16) | 0x0040u)};  // keep NaNs quiet
+        }
+        const uint32_t round_bit = 0x00008000u;
+        uint32_t rounded = (bits >> 16) + ((bits & round_bit) != 0 && (bits & (3 * round_bit - 1)) != 0);
+        return bf16{static_cast<uint16_t>(rounded)};
+    }
+    static float to_f32(bf16 x) {
+        uint32_t bits = static_cast<uint32_t>(x.bits) << 16;
+        float f;
+        std::memcpy(&f, &bits, sizeof(f));
+        return f;
+    }
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+std::vector<float> random_vector(size_t len, uint64_t seed) {
+    std::vector<float> v(len);
+    for (auto& x : v) x = static_cast<float>(next_random(seed) % 2001) / 1000.0f - 1.0f;
+    return v;
+}
+
+template <typename T>
+void convert_from(std::vector<T>& dst, const std::vector<float>& src) {
+    for (size_t i = 0; i < dst.size(); i++) {
+        dst[i] = Element<T>::from_f32(src[i]);
+    }
+}
+
+template <typename T>
+void axpy(float alpha, const std::vector<T>& x, std::vector<T>& y) {
+    for (size_t i = 0; i < y.size(); i++) {
+        y[i] = Element<T>::from_f32(std::fma(alpha, Element<T>::to_f32(x[i]), Element<T>::to_f32(y[i])));
+    }
+}
+
+template <typename T>
+float dot(const std::vector<T>& x, const std::vector<T>& y) {
+    float acc = 0.0f;
+    for (size_t i = 0; i < x.size(); i++) {
+        acc = std::fma(Element<T>::to_f32(x[i]), Element<T>::to_f32(y[i]), acc);
+    }
+    return acc;
+}
+
+// i-k-j GEMM with a float accumulator row, rounded to T when the row is stored
+template <typename T>
+void gemm(const std::vector<T>& a, const std::vector<T>& b, std::vector<T>& c, size_t n) {
+    std::vector<float> row(n);
+    for (size_t i = 0; i < n; i++) {
+        std::fill(row.begin(), row.end(), 0.0f);
+        for (size_t k = 0; k < n; k++) {
+            float aik = Element<T>::to_f32(a[i * n + k]);
+            const T* b_row = &b[k * n];
+            for (size_t j = 0; j < n; j++) {
+                row[j] = std::fma(aik, Element<T>::to_f32(b_row[j]), row[j]);
+            }
+        }
+        for (size_t j = 0; j < n; j++) {
+            c[i * n + j] = Element<T>::from_f32(row[j]);
+        }
+    }
+}
+
+struct Inputs {
+    std::vector<float> x, y, a, b;
+};
+
+struct Timings {
+    Duration convert{0};
+    Duration axpy{0};
+    Duration dot{0};
+    Duration gemm{0};
+
+    Duration total() const { return convert + axpy + dot + gemm; }
+};
+
+struct Outcome {
+    Timings timings;
+    float dot = 0.0f;
+    std::vector<float> y, c;
+};
+
+template <typename T>
+Outcome run(const Inputs& inputs) {
+    Outcome outcome;
+    Timings& timings = outcome.timings;
+    std::vector<T> x(VECTOR_LEN), y(VECTOR_LEN);
+
+    for (size_t pass = 0; pass < VECTOR_PASSES; pass++) {
+        auto start = Clock::now();
+        convert_from(x, inputs.x);
+        convert_from(y, inputs.y);
+        timings.convert += Clock::now() - start;
+    }
+
+    volatile float dot_sink = 0.0f;
+    for (size_t pass = 0; pass < VECTOR_PASSES; pass++) {
+        auto start = Clock::now();
+        axpy(ALPHA, x, y);
+        timings.axpy += Clock::now() - start;
+
+        start = Clock::now();
+        dot_sink = dot(x, y);
+        timings.dot += Clock::now() - start;
+    }
+    outcome.dot = dot_sink;
+
+    std::vector<T> a(GEMM_SIZE * GEMM_SIZE), b(GEMM_SIZE * GEMM_SIZE), c(GEMM_SIZE * GEMM_SIZE);
+    convert_from(a, inputs.a);
+    convert_from(b, inputs.b);
+    auto start = Clock::now();
+    gemm(a, b, c, GEMM_SIZE);
+    timings.gemm = Clock::now() - start;
+
+    for (const T& v : y) outcome.y.push_back(Element<T>::to_f32(v));
+    for (const T& v : c) outcome.c.push_back(Element<T>::to_f32(v));
+    return outcome;
+}
+
+float max_error(const std::vector<float>& values, const std::vector<float>& reference) {
+    float error = 0.0f;
+    for (size_t i = 0; i < values.size(); i++) {
+        error = std::max(error, std::abs(values[i] - reference[i]));
+    }
+    return error;
+}
+
+#ifdef HAVE_WGPU
+// The same naive GEMM in WGSL, instantiated for f32 and f16 storage buffers (f32 accumulation)
+std::string gpu_shader(const std::string& element) {
+    std::string source = R"(
+    ENABLE
+    @group(0) @binding(0) var<storage, read> a: array<ELEMENT>;
+    @group(0) @binding(1) var<storage, read> b: array<ELEMENT>;
+    @group(0) @binding(2) var<storage, read_write> c: array<ELEMENT>;
+    @group(0) @binding(3) var<uniform> n: u32;
+
+    @compute @workgroup_size(16, 16)
+    fn gemm(@builtin(global_invocation_id) gid: vec3<u32>) {
+        if (gid.x >= n || gid.y >= n) {
+            return;
+        }
+        var acc = 0.0;
+        for (var k = 0u; k < n; k++) {
+            acc = fma(f32(a[gid.y * n + k]), f32(b[k * n + gid.x]), acc);
+        }
+        c[gid.y * n + gid.x] = ELEMENT(acc);
+    }
+)";
+    auto replace_all = [&](const std::string& from, const std::string& to) {
+        for (size_t pos = source.find(from); pos != std::string::npos; pos = source.find(from, pos + to.size())) {
+            source.replace(pos, from.size(), to);
+        }
+    };
+    replace_all("ENABLE", element == "f16" ? "enable f16;" : "");
+    replace_all("ELEMENT", element);
+    return source;
+}
+
+WGPUStringView string_view(const char* s) {
+    return WGPUStringView{s, WGPU_STRLEN};
+}
+
+// wgpu-native completes adapter and device requests before the call returns
+WGPUAdapter request_adapter(WGPUInstance instance) {
+    WGPURequestAdapterOptions options = {};
+    options.powerPreference = WGPUPowerPreference_HighPerformance;
+
+    WGPURequestAdapterCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestAdapterStatus status, WGPUAdapter adapter, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestAdapterStatus_Success) *static_cast<WGPUAdapter*>(out) = adapter;
+    };
+    WGPUAdapter adapter = nullptr;
+    callback.userdata1 = &adapter;
+    wgpuInstanceRequestAdapter(instance, &options, callback);
+    return adapter;
+}
+
+WGPUDevice request_device(WGPUAdapter adapter, const WGPUDeviceDescriptor* desc) {
+    WGPURequestDeviceCallbackInfo callback = {};
+    callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    callback.callback = [](WGPURequestDeviceStatus status, WGPUDevice device, WGPUStringView, void* out, void*) {
+        if (status == WGPURequestDeviceStatus_Success) *static_cast<WGPUDevice*>(out) = device;
+    };
+    WGPUDevice device = nullptr;
+    callback.userdata1 = &device;
+    wgpuAdapterRequestDevice(adapter, desc, callback);
+    return device;
+}
+
+WGPUBuffer create_buffer(WGPUDevice device, uint64_t size, WGPUBufferUsage usage) {
+    WGPUBufferDescriptor desc = {};
+    desc.size = size;
+    desc.usage = usage;
+    return wgpuDeviceCreateBuffer(device, &desc);
+}
+
+// Runs the GPU GEMM with `element` storage on raw input bytes; returns the time and fills the raw C bytes
+Duration gpu_gemm(WGPUDevice device, WGPUQueue queue, const std::string& element, const void* a, const void* b,
+                  size_t bytes, std::vector<uint8_t>& c) {
+    std::string source = gpu_shader(element);
+    WGPUShaderSourceWGSL wgsl = {};
+    wgsl.chain.sType = WGPUSType_ShaderSourceWGSL;
+    wgsl.code = string_view(source.c_str());
+    WGPUShaderModuleDescriptor shader_desc = {};
+    shader_desc.nextInChain = &wgsl.chain;
+    WGPUShaderModule shader = wgpuDeviceCreateShaderModule(device, &shader_desc);
+
+    WGPUComputePipelineDescriptor pipeline_desc = {};
+    pipeline_desc.compute.module = shader;
+    pipeline_desc.compute.entryPoint = string_view("gemm");
+    WGPUComputePipeline pipeline = wgpuDeviceCreateComputePipeline(device, &pipeline_desc);
+
+    WGPUBuffer buffer_a = create_buffer(device, bytes, WGPUBufferUsage_Storage | WGPUBufferUsage_CopyDst);
+    wgpuQueueWriteBuffer(queue, buffer_a, 0, a, bytes);
+    WGPUBuffer buffer_b = create_buffer(device, bytes, WGPUBufferUsage_Storage | WGPUBufferUsage_CopyDst);
+    wgpuQueueWriteBuffer(queue, buffer_b, 0, b, bytes);
+    WGPUBuffer buffer_c = create_buffer(device, bytes, WGPUBufferUsage_Storage | WGPUBufferUsage_CopySrc);
+    uint32_t n_val = static_cast<uint32_t>(GEMM_SIZE);
+    WGPUBuffer buffer_n = create_buffer(device, sizeof(uint32_t), WGPUBufferUsage_Uniform | WGPUBufferUsage_CopyDst);
+    wgpuQueueWriteBuffer(queue, buffer_n, 0, &n_val, sizeof(uint32_t));
+    WGPUBuffer buffer_readback = create_buffer(device, bytes, WGPUBufferUsage_MapRead | WGPUBufferUsage_CopyDst);
+
+    WGPUBuffer bound[] = {buffer_a, buffer_b, buffer_c, buffer_n};
+    WGPUBindGroupEntry entries[4] = {};
+    for (uint32_t i = 0; i < 4; i++) {
+        entries[i].binding = i;
+        entries[i].buffer = bound[i];
+        entries[i].size = WGPU_WHOLE_SIZE;
+    }
+    WGPUBindGroupLayout layout = wgpuComputePipelineGetBindGroupLayout(pipeline, 0);
+    WGPUBindGroupDescriptor bind_group_desc = {};
+    bind_group_desc.layout = layout;
+    bind_group_desc.entryCount = 4;
+    bind_group_desc.entries = entries;
+    WGPUBindGroup bind_group = wgpuDeviceCreateBindGroup(device, &bind_group_desc);
+
+    auto dispatch = [&]() {
+        WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device, nullptr);
+        WGPUComputePassEncoder pass = wgpuCommandEncoderBeginComputePass(encoder, nullptr);
+        wgpuComputePassEncoderSetPipeline(pass, pipeline);
+        wgpuComputePassEncoderSetBindGroup(pass, 0, bind_group, 0, nullptr);
+        uint32_t groups = static_cast<uint32_t>((GEMM_SIZE + 15) / 16);
+        wgpuComputePassEncoderDispatchWorkgroups(pass, groups, groups, 1);
+        wgpuComputePassEncoderEnd(pass);
+        wgpuComputePassEncoderRelease(pass);
+        WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+        wgpuQueueSubmit(queue, 1, &commands);
+        wgpuCommandBufferRelease(commands);
+        wgpuCommandEncoderRelease(encoder);
+        wgpuDevicePoll(device, true, nullptr);
+    };
+
+    // Warm-up
+    dispatch();
+    auto start = Clock::now();
+    for (size_t iter = 0; iter < GPU_ITERATIONS; iter++) {
+        dispatch();
+    }
+    Duration duration = Clock::now() - start;
+
+    WGPUCommandEncoder encoder = wgpuDeviceCreateCommandEncoder(device, nullptr);
+    wgpuCommandEncoderCopyBufferToBuffer(encoder, buffer_c, 0, buffer_readback, 0, bytes);
+    WGPUCommandBuffer commands = wgpuCommandEncoderFinish(encoder, nullptr);
+    wgpuQueueSubmit(queue, 1, &commands);
+
+    WGPUBufferMapCallbackInfo map_callback = {};
+    map_callback.mode = WGPUCallbackMode_AllowSpontaneous;
+    map_callback.callback = [](WGPUMapAsyncStatus status, WGPUStringView, void* out, void*) {
+        *static_cast<bool*>(out) = status == WGPUMapAsyncStatus_Success;
+    };
+    bool mapped = false;
+    map_callback.userdata1 = &mapped;
+    wgpuBufferMapAsync(buffer_readback, WGPUMapMode_Read, 0, bytes, map_callback);
+    wgpuDevicePoll(device, true, nullptr);
+    c.assign(bytes, 0);
+    if (mapped) {
+        std::memcpy(c.data(), wgpuBufferGetConstMappedRange(buffer_readback, 0, bytes), bytes);
+        wgpuBufferUnmap(buffer_readback);
+    } else {
+        std::cerr << "Failed to map readback buffer" << std::endl;
+    }
+
+    wgpuCommandBufferRelease(commands);
+    wgpuCommandEncoderRelease(encoder);
+    wgpuBindGroupRelease(bind_group);
+    wgpuBindGroupLayoutRelease(layout);
+    for (WGPUBuffer buffer : {buffer_a, buffer_b, buffer_c, buffer_n, buffer_readback}) {
+        wgpuBufferRelease(buffer);
+    }
+    wgpuComputePipelineRelease(pipeline);
+    wgpuShaderModuleRelease(shader);
+    return duration;
+}
+
+// GPU path: f32 vs f16 storage buffers, only on adapters exposing shader-f16
+void run_gpu(const Inputs& inputs, const std::vector<float>& reference) {
+    WGPUInstance instance = wgpuCreateInstance(nullptr);
+    WGPUAdapter adapter = instance ? request_adapter(instance) : nullptr;
+    if (!adapter) {
+        std::cerr << "GPU: no adapter found, skipping" << std::endl;
+        if (instance) wgpuInstanceRelease(instance);
+        return;
+    }
+    WGPUAdapterInfo info = {};
+    wgpuAdapterGetInfo(adapter, &info);
+    std::string name(info.device.data, info.device.length);
+    wgpuAdapterInfoFreeMembers(info);
+
+    WGPUDevice device = nullptr;
+    if (wgpuAdapterHasFeature(adapter, WGPUFeatureName_ShaderF16)) {
+        WGPUFeatureName features[] = {WGPUFeatureName_ShaderF16};
+        WGPUDeviceDescriptor desc = {};
+        desc.requiredFeatureCount = 1;
+        desc.requiredFeatures = features;
+        device = request_device(adapter, &desc);
+    }
+    if (!device) {
+        std::cerr << "GPU: " << name << " does not support shader-f16, skipping" << std::endl;
+        wgpuAdapterRelease(adapter);
+        wgpuInstanceRelease(instance);
+        return;
+    }
+    WGPUQueue queue = wgpuDeviceGetQueue(device);
+
+    std::vector<_Float16> a16(inputs.a.size()), b16(inputs.b.size());
+    convert_from(a16, inputs.a);
+    convert_from(b16, inputs.b);
+    std::vector<uint8_t> raw32, raw16;
+    Duration time32 = gpu_gemm(device, queue, "f32", inputs.a.data(), inputs.b.data(),
+                               inputs.a.size() * sizeof(float), raw32);
+    Duration time16 = gpu_gemm(device, queue, "f16", a16.data(), b16.data(), a16.size() * sizeof(_Float16), raw16);
+
+    std::vector<float> c32(reference.size()), c16(reference.size());
+    std::memcpy(c32.data(), raw32.data(), raw32.size());
+    const _Float16* half_values = reinterpret_cast<const _Float16*>(raw16.data());
+    for (size_t i = 0; i < c16.size(); i++) c16[i] = static_cast<float>(half_values[i]);
+
+    std::cerr << "GPU: " << name << ", " << GEMM_SIZE << "x" << GEMM_SIZE << " GEMM x" << GPU_ITERATIONS << std::endl;
+    std::cerr << std::fixed << std::setprecision(6) << "   f32: " << time32.count() << " s (max error vs CPU f32 "
+              << std::scientific << std::setprecision(2) << max_error(c32, reference) << ")" << std::endl;
+    std::cerr << std::fixed << std::setprecision(6) << "   f16: " << time16.count() << " s (max error vs CPU f32 "
+              << std::scientific << std::setprecision(2) << max_error(c16, reference) << ")" << std::endl;
+
+    wgpuQueueRelease(queue);
+    wgpuDeviceRelease(device);
+    wgpuAdapterRelease(adapter);
+    wgpuInstanceRelease(instance);
+}
+#else
+void run_gpu(const Inputs&, const std::vector<float>&) {
+    std::cerr << "GPU: built without wgpu-native, skipping" << std::endl;
+}
+#endif
+
+template <typename T>
+void report(const Outcome& outcome, const Outcome& reference, double& checksum) {
+    const Timings& t = outcome.timings;
+    std::cerr << std::setw(4) << Element<T>::NAME << std::fixed << std::setprecision(4) << ": convert "
+              << t.convert.count() << "s  axpy " << t.axpy.count() << "s  dot " << t.dot.count() << "s  gemm "
+              << t.gemm.count() << "s  (dot " << outcome.dot << ", max y error " << std::scientific
+              << std::setprecision(2) << max_error(outcome.y, reference.y) << ", max C error "
+              << max_error(outcome.c, reference.c) << ")" << std::endl;
+    checksum += outcome.dot;
+    for (size_t i = 0; i < outcome.y.size(); i += 1000) checksum += outcome.y[i];
+    for (size_t i = 0; i < outcome.c.size(); i += 100) checksum += outcome.c[i];
+}
+
+int main() {
+    Inputs inputs{
+        random_vector(VECTOR_LEN, 1),
+        random_vector(VECTOR_LEN, 2),
+        random_vector(GEMM_SIZE * GEMM_SIZE, 3),
+        random_vector(GEMM_SIZE * GEMM_SIZE, 4),
+    };
+
+    Outcome full = run<float>(inputs);
+    Outcome half = run<_Float16>(inputs);
+    Outcome brain = run<bf16>(inputs);
+    Duration total = full.timings.total() + half.timings.total() + brain.timings.total();
+
+    double checksum = 0.0;
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    std::cerr << "Vectors: " << (VECTOR_LEN >> 20) << "M elements x" << VECTOR_PASSES << " passes, GEMM: " << GEMM_SIZE
+              << "x" << GEMM_SIZE << std::endl;
+    report<float>(full, full, checksum);
+    report<_Float16>(half, full, checksum);
+    report<bf16>(brain, full, checksum);
+    run_gpu(inputs, full.c);
+    std::cerr << "Checksum: " << std::fixed << std::setprecision(6) << checksum << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,200 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <optional>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t FACT_ROWS = 100'000'000;
+const size_t DIM_ROWS = 10'000'000;
+const size_t NUM_GROUPS = 100'000;
+const uint64_t MATCH_PERCENT = 90; // Share of fact rows whose key exists in the dimension table
+const uint64_t MAX_AMOUNT = 10'000;
+const size_t NUM_THREADS = 8;
+const uint32_t EMPTY = UINT32_MAX;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// Column-oriented tables, the way analytical engines store them
+struct DimTable {
+    std::vector<uint32_t> keys;
+    std::vector<uint32_t> groups;
+};
+
+struct FactTable {
+    std::vector<uint32_t> keys;
+    std::vector<uint32_t> amounts;
+};
+
+void generate_tables(DimTable& dim, FactTable& fact) {
+    uint64_t seed = 8675309;
+
+    // Multiplying by an odd constant is a bijection on uint32_t, so dimension keys are unique
+    dim.keys.resize(DIM_ROWS);
+    dim.groups.resize(DIM_ROWS);
+    for (size_t i = 0; i < DIM_ROWS; i++) {
+        dim.keys[i] = static_cast<uint32_t>(i) * 2654435761u;
+    }
+    for (size_t i = 0; i < DIM_ROWS; i++) {
+        dim.groups[i] = static_cast<uint32_t>(next_random(seed) % NUM_GROUPS);
+    }
+
+    fact.keys.reserve(FACT_ROWS);
+    fact.amounts.reserve(FACT_ROWS);
+    for (size_t i = 0; i < FACT_ROWS; i++) {
+        uint32_t key;
+        if (next_random(seed) % 100 < MATCH_PERCENT) {
+            key = dim.keys[next_random(seed) % DIM_ROWS];
+        } else {
+            key = static_cast<uint32_t>(next_random(seed));
+        }
+        fact.keys.push_back(key);
+        fact.amounts.push_back(static_cast<uint32_t>(next_random(seed) % MAX_AMOUNT));
+    }
+}
+
+struct Slot {
+    uint32_t key;
+    uint32_t group; // EMPTY marks an unused slot
+};
+
+// Open addressing with linear probing and a power-of-two capacity at most half full
+struct JoinTable {
+    std::vector<Slot> slots;
+    uint32_t shift;
+
+    explicit JoinTable(const DimTable& dim) {
+        size_t capacity = 1;
+        while (capacity < dim.keys.size() * 2) capacity <<= 1;
+        slots.assign(capacity, Slot{0, EMPTY});
+        shift = 64 - __builtin_ctzll(capacity);
+        size_t mask = capacity - 1;
+        for (size_t r = 0; r < dim.keys.size(); r++) {
+            size_t i = slot_of(dim.keys[r]);
+            while (slots[i].group != EMPTY) {
+                i = (i + 1) & mask;
+            }
+            slots[i] = Slot{dim.keys[r], dim.groups[r]};
+        }
+    }
+
+    // Fibonacci hashing: the top bits of key * 2^64/phi
+    inline size_t slot_of(uint32_t key) const {
+        return static_cast<size_t>((static_cast<uint64_t>(key) * 0x9E3779B97F4A7C15ULL) >> shift);
+    }
+
+    inline std::optional<uint32_t> probe(uint32_t key) const {
+        size_t mask = slots.size() - 1;
+        size_t i = slot_of(key);
+        while (true) {
+            const Slot& slot = slots[i];
+            if (slot.group == EMPTY) {
+                return std::nullopt;
+            }
+            if (slot.key == key) {
+                return slot.group;
+            }
+            i = (i + 1) & mask;
+        }
+    }
+};
+
+// SELECT group, COUNT(*), SUM(amount) ... GROUP BY group, with dense group ids
+struct Aggregate {
+    std::vector<uint64_t> counts = std::vector<uint64_t>(NUM_GROUPS);
+    std::vector<uint64_t> sums = std::vector<uint64_t>(NUM_GROUPS);
+
+    void merge(const Aggregate& other) {
+        for (size_t g = 0; g < NUM_GROUPS; g++) {
+            counts[g] += other.counts[g];
+            sums[g] += other.sums[g];
+        }
+    }
+};
+
+Aggregate join_aggregate(const JoinTable& table, const FactTable& fact, size_t rows) {
+    std::vector<Aggregate> partial(NUM_THREADS);
+    std::vector<std::thread> threads;
+    size_t per_thread = rows / NUM_THREADS;
+
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        size_t begin = t * per_thread;
+        size_t end = (t == NUM_THREADS - 1) ? rows : (t + 1) * per_thread;
+        threads.emplace_back([&, t, begin, end] {
+            Aggregate& agg = partial[t];
+            for (size_t r = begin; r < end; r++) {
+                if (auto group = table.probe(fact.keys[r])) {
+                    agg.counts[*group]++;
+                    agg.sums[*group] += fact.amounts[r];
+                }
+            }
+        });
+    }
+
+    for (auto& thread : threads) {
+        thread.join();
+    }
+
+    Aggregate result;
+    for (const auto& agg : partial) {
+        result.merge(agg);
+    }
+    return result;
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    DimTable dim;
+    FactTable fact;
+    generate_tables(dim, fact);
+
+    // Warm-up
+    {
+        JoinTable table(dim);
+        auto warmup = join_aggregate(table, fact, FACT_ROWS / 20);
+    }
+
+    // Benchmark build
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    JoinTable table(dim);
+    measure->stop();
+    auto build_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark probe + aggregate
+    auto probe_start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    auto result = join_aggregate(table, fact, FACT_ROWS);
+    measure->stop();
+    auto probe_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> build_duration = build_end - start;
+    std::chrono::duration<double> probe_duration = probe_end - probe_start;
+
+    // Checksum
+    uint64_t matched = 0, total_amount = 0, checksum = 0;
+    size_t active_groups = 0;
+    for (size_t g = 0; g < NUM_GROUPS; g++) {
+        matched += result.counts[g];
+        total_amount += result.sums[g];
+        if (result.counts[g] > 0) active_groups++;
+        checksum += (g + 1) * result.sums[g];
+    }
+
+    measure->report();
+    std::cerr << std::fixed << "Build: " << build_duration.count() << "s (" << std::setprecision(0)
+              << DIM_ROWS / build_duration.count() << " rows/s), probe + aggregate: " << std::setprecision(6)
+              << probe_duration.count() << "s (" << std::setprecision(0)
+              << FACT_ROWS / probe_duration.count() << " rows/s)" << std::endl;
+    std::cerr << "Matched rows: " << matched << ", groups: " << active_groups
+              << ", total amount: " << total_amount << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,274 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <algorithm>
+#include <utility>
+#include <chrono>
+#include <iomanip>
+#include <array>
+#include <cmath>
+#include <cstdio>
+#include <cstring>
+#include <cstdlib>
+
+const size_t N_2D = 4096;
+const size_t STEPS_2D = 100;
+const double ALPHA_2D = 0.2; // Explicit scheme is stable below 1/4 in 2D
+const size_t BLOCK_ROWS_2D = 64;
+const size_t BLOCK_COLS_2D = 512;
+
+const size_t N_3D = 256;
+const size_t STEPS_3D = 100;
+const double ALPHA_3D = 0.1; // ... and below 1/6 in 3D
+const size_t BLOCK_PLANES_3D = 16;
+const size_t BLOCK_ROWS_3D = 16;
+const size_t BLOCK_COLS_3D = 64;
+
+const size_t NUM_THREADS = 8;
+
+enum class Precision {
+    F64,
+    F32,
+    Mixed, // float grids, double arithmetic: halves the memory traffic without float rounding in the update
+};
+
+const char* precision_name(Precision precision) {
+    switch (precision) {
+        case Precision::F64: return "f64";
+        case Precision::F32: return "f32";
+        case Precision::Mixed: return "mixed (f32 storage, f64 arithmetic)";
+    }
+    return "";
+}
+
+using Grid = std::vector<double>;
+template <typename S>
+using Stepper = void (*)(const std::vector<S>&, std::vector<S>&, size_t, size_t);
+
+// Zero on the boundary, 1.0 inside a centered hot square/cube a quarter of the domain wide
+Grid initial_2d() {
+    size_t lo = 3 * N_2D / 8, hi = 5 * N_2D / 8;
+    Grid grid(N_2D * N_2D, 0.0);
+    for (size_t y = lo; y < hi; y++) {
+        for (size_t x = lo; x < hi; x++) {
+            grid[y * N_2D + x] = 1.0;
+        }
+    }
+    return grid;
+}
+
+Grid initial_3d() {
+    size_t lo = 3 * N_3D / 8, hi = 5 * N_3D / 8;
+    Grid grid(N_3D * N_3D * N_3D, 0.0);
+    for (size_t z = lo; z < hi; z++) {
+        for (size_t y = lo; y < hi; y++) {
+            for (size_t x = lo; x < hi; x++) {
+                grid[(z * N_3D + y) * N_3D + x] = 1.0;
+            }
+        }
+    }
+    return grid;
+}
+
+// Updates read S cells and compute in A
+template <typename S, typename A>
+inline S update_2d(const S* cur, size_t i) {
+    auto at = [cur](size_t j) { return static_cast<A>(cur[j]); };
+    A c = at(i);
+    A sum = at(i - 1) + at(i + 1) + at(i - N_2D) + at(i + N_2D);
+    return static_cast<S>(c + static_cast<A>(ALPHA_2D) * (sum - A(4) * c));
+}
+
+template <typename S, typename A>
+inline S update_3d(const S* cur, size_t i) {
+    const size_t plane = N_3D * N_3D;
+    auto at = [cur](size_t j) { return static_cast<A>(cur[j]); };
+    A c = at(i);
+    A sum = at(i - 1) + at(i + 1) + at(i - N_3D) + at(i + N_3D) + at(i - plane) + at(i + plane);
+    return static_cast<S>(c + static_cast<A>(ALPHA_3D) * (sum - A(6) * c));
+}
+
+// Full-width row sweeps over rows [start_row, end_row)
+template <typename S, typename A>
+void step_2d_naive(const std::vector<S>& cur, std::vector<S>& next, size_t start_row, size_t end_row) {
+    for (size_t y = std::max<size_t>(start_row, 1); y < std::min(end_row, N_2D - 1); y++) {
+        for (size_t x = 1; x < N_2D - 1; x++) {
+            next[y * N_2D + x] = update_2d<S, A>(cur.data(), y * N_2D + x);
+        }
+    }
+}
+
+// Bands of rows swept in column tiles so the three input rows of a tile stay in L1
+template <typename S, typename A>
+void step_2d_blocked(const std::vector<S>& cur, std::vector<S>& next, size_t start_row, size_t end_row) {
+    size_t first = std::max<size_t>(start_row, 1), last = std::min(end_row, N_2D - 1);
+    for (size_t y0 = first; y0 < last; y0 += BLOCK_ROWS_2D) {
+        size_t y1 = std::min(y0 + BLOCK_ROWS_2D, last);
+        for (size_t x0 = 1; x0 < N_2D - 1; x0 += BLOCK_COLS_2D) {
+            size_t x1 = std::min(x0 + BLOCK_COLS_2D, N_2D - 1);
+            for (size_t y = y0; y < y1; y++) {
+                for (size_t x = x0; x < x1; x++) {
+                    next[y * N_2D + x] = update_2d<S, A>(cur.data(), y * N_2D + x);
+                }
+            }
+        }
+    }
+}
+
+// Full plane sweeps over planes [start_plane, end_plane)
+template <typename S, typename A>
+void step_3d_naive(const std::vector<S>& cur, std::vector<S>& next, size_t start_plane, size_t end_plane) {
+    for (size_t z = std::max<size_t>(start_plane, 1); z < std::min(end_plane, N_3D - 1); z++) {
+        for (size_t y = 1; y < N_3D - 1; y++) {
+            for (size_t x = 1; x < N_3D - 1; x++) {
+                size_t i = (z * N_3D + y) * N_3D + x;
+                next[i] = update_3d<S, A>(cur.data(), i);
+            }
+        }
+    }
+}
+
+// Slabs of planes; each (y, x) tile is marched through the slab so neighboring planes are reused
+template <typename S, typename A>
+void step_3d_blocked(const std::vector<S>& cur, std::vector<S>& next, size_t start_plane, size_t end_plane) {
+    size_t first = std::max<size_t>(start_plane, 1), last = std::min(end_plane, N_3D - 1);
+    for (size_t z0 = first; z0 < last; z0 += BLOCK_PLANES_3D) {
+        size_t z1 = std::min(z0 + BLOCK_PLANES_3D, last);
+        for (size_t y0 = 1; y0 < N_3D - 1; y0 += BLOCK_ROWS_3D) {
+            size_t y1 = std::min(y0 + BLOCK_ROWS_3D, N_3D - 1);
+            for (size_t x0 = 1; x0 < N_3D - 1; x0 += BLOCK_COLS_3D) {
+                size_t x1 = std::min(x0 + BLOCK_COLS_3D, N_3D - 1);
+                for (size_t z = z0; z < z1; z++) {
+                    for (size_t y = y0; y < y1; y++) {
+                        for (size_t x = x0; x < x1; x++) {
+                            size_t i = (z * N_3D + y) * N_3D + x;
+                            next[i] = update_3d<S, A>(cur.data(), i);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Runs the scheme with double buffering, splitting the outer dimension across threads each step;
+// returns the elapsed time and the final grid
+template <typename S>
+std::pair<std::chrono::duration<double>, std::vector<S>> simulate(const std::vector<S>& initial, size_t n, size_t steps,
+                                                                 Stepper<S> stepper) {
+    std::vector<S> cur = initial;
+    std::vector<S> next = initial;
+    auto start = std::chrono::high_resolution_clock::now();
+    for (size_t s = 0; s < steps; s++) {
+        std::vector<std::thread> threads;
+        size_t per_thread = n / NUM_THREADS;
+        for (size_t t = 0; t < NUM_THREADS; t++) {
+            size_t lo = t * per_thread;
+            size_t hi = (t == NUM_THREADS - 1) ? n : (t + 1) * per_thread;
+            threads.emplace_back(stepper, std::cref(cur), std::ref(next), lo, hi);
+        }
+        for (auto& thread : threads) {
+            thread.join();
+        }
+        std::swap(cur, next);
+    }
+    auto end = std::chrono::high_resolution_clock::now();
+    return {end - start, cur};
+}
+
+template <typename S>
+double total_heat(const std::vector<S>& field) {
+    double heat = 0.0;
+    for (S v : field) {
+        heat += static_cast<double>(v);
+    }
+    return heat;
+}
+
+struct Run {
+    std::array<std::chrono::duration<double>, 4> durations; // 2D naive, 2D blocked, 3D naive, 3D blocked
+    std::array<Grid, 2> fields;                             // final 2D and 3D grids from the naive runs, widened to double
+    bool blocked_matches;
+};
+
+// Stores the grids as S (converted outside the timed region) and runs all four schemes with A arithmetic
+template <typename S, typename A>
+Run run(const Grid& initial_2d_grid, const Grid& initial_3d_grid) {
+    std::vector<S> grid_2d(initial_2d_grid.begin(), initial_2d_grid.end());
+    std::vector<S> grid_3d(initial_3d_grid.begin(), initial_3d_grid.end());
+
+    // Warm-up
+    simulate<S>(grid_2d, N_2D, 2, step_2d_naive<S, A>);
+    simulate<S>(grid_3d, N_3D, 2, step_3d_blocked<S, A>);
+
+    // Benchmark
+    auto [naive_2d, field_2d] = simulate<S>(grid_2d, N_2D, STEPS_2D, step_2d_naive<S, A>);
+    auto [blocked_2d, blocked_field_2d] = simulate<S>(grid_2d, N_2D, STEPS_2D, step_2d_blocked<S, A>);
+    auto [naive_3d, field_3d] = simulate<S>(grid_3d, N_3D, STEPS_3D, step_3d_naive<S, A>);
+    auto [blocked_3d, blocked_field_3d] = simulate<S>(grid_3d, N_3D, STEPS_3D, step_3d_blocked<S, A>);
+
+    return Run{
+        {naive_2d, blocked_2d, naive_3d, blocked_3d},
+        {Grid(field_2d.begin(), field_2d.end()), Grid(field_3d.begin(), field_3d.end())},
+        total_heat(field_2d) == total_heat(blocked_field_2d) && total_heat(field_3d) == total_heat(blocked_field_3d),
+    };
+}
+
+Precision parse_precision(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--precision") == 0) {
+            const char* value = i + 1 < argc ? argv[i + 1] : "";
+            if (std::strcmp(value, "f64") == 0) return Precision::F64;
+            if (std::strcmp(value, "f32") == 0) return Precision::F32;
+            if (std::strcmp(value, "mixed") == 0) return Precision::Mixed;
+            std::cerr << "--precision expects f64, f32 or mixed" << std::endl;
+            std::exit(1);
+        }
+    }
+    return Precision::F64;
+}
+
+int main(int argc, char** argv) {
+    Precision precision = parse_precision(argc, argv);
+    Grid grid_2d = initial_2d();
+    Grid grid_3d = initial_3d();
+
+    Run result = precision == Precision::F64 ? run<double, double>(grid_2d, grid_3d)
+                 : precision == Precision::F32 ? run<float, float>(grid_2d, grid_3d)
+                                               : run<float, double>(grid_2d, grid_3d);
+    auto [naive_2d, blocked_2d, naive_3d, blocked_3d] = result.durations;
+    double heat_2d = total_heat(result.fields[0]);
+    double heat_3d = total_heat(result.fields[1]);
+
+    std::chrono::duration<double> total_duration = naive_2d + blocked_2d + naive_3d + blocked_3d;
+    auto mlups = [](size_t cells, size_t steps, std::chrono::duration<double> d) {
+        return static_cast<double>(cells * steps) / d.count() / 1e6;
+    };
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << "Precision: " << precision_name(precision) << std::endl;
+    std::cerr << std::fixed << std::setprecision(0) << "2D " << N_2D << "² x " << STEPS_2D << " steps: naive "
+              << mlups(N_2D * N_2D, STEPS_2D, naive_2d) << " MLUP/s, blocked "
+              << mlups(N_2D * N_2D, STEPS_2D, blocked_2d) << " MLUP/s" << std::endl;
+    std::cerr << "3D " << N_3D << "³ x " << STEPS_3D << " steps: naive "
+              << mlups(N_3D * N_3D * N_3D, STEPS_3D, naive_3d) << " MLUP/s, blocked "
+              << mlups(N_3D * N_3D * N_3D, STEPS_3D, blocked_3d) << " MLUP/s" << std::endl;
+    if (precision != Precision::F64) {
+        // Against an untimed f64 run: max pointwise difference (the peak temperature is 1) and total heat drift
+        Run reference = run<double, double>(grid_2d, grid_3d);
+        const char* labels[2] = {"2D", "3D"};
+        for (size_t d = 0; d < 2; d++) {
+            double max_error = 0.0;
+            for (size_t i = 0; i < result.fields[d].size(); i++) {
+                max_error = std::max(max_error, std::abs(result.fields[d][i] - reference.fields[d][i]));
+            }
+            double heat = total_heat(result.fields[d]), reference_heat = total_heat(reference.fields[d]);
+            fprintf(stderr, "%s error vs f64: max pointwise %.3e, total heat %.3e relative\n", labels[d], max_error,
+                    std::abs((heat - reference_heat) / reference_heat));
+        }
+    }
+    std::cerr << std::setprecision(9) << "Checksum: " << heat_2d << " (3D: " << heat_3d << ", blocked matches: "
+              << std::boolalpha << result.blocked_matches << ")" << std::endl;
+
+    return 0;
+}
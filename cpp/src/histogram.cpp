@@ -0,0 +1,240 @@
+#include <iostream>
+#include <vector>
+#include <array>
+#include <thread>
+#include <atomic>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cmath>
+#include <limits>
+
+const size_t NUM_BYTES = 1ULL << 30;  // 1 GiB of skewed bytes
+const size_t NUM_VALUES = 1ULL << 27; // 1 GiB of double
+const size_t PASSES = 3;
+const size_t ATOMIC_PASSES = 1; // one contended fetch_add per byte is an order of magnitude slower
+const size_t CHUNK_SIZE = 1 << 16; // elements per parallel work item
+const size_t BINS = 256;
+const size_t NUM_THREADS = 8;
+
+using Histogram = std::array<uint64_t, BINS>;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// Product of two uniform bytes: heavily skewed toward small values, so a few bins
+// are hot (the worst case for shared atomic counters)
+std::vector<uint8_t> generate_bytes() {
+    uint64_t seed = 42;
+    std::vector<uint8_t> bytes(NUM_BYTES);
+    for (auto& b : bytes) {
+        uint64_t r = next_random(seed);
+        b = static_cast<uint8_t>(((r & 0xff) * ((r >> 8) & 0xff)) >> 8);
+    }
+    return bytes;
+}
+
+// Uniform in [-1000, 1000) from the top 53 bits of the LCG state
+std::vector<double> generate_values() {
+    uint64_t seed = 7;
+    std::vector<double> values(NUM_VALUES);
+    for (auto& v : values) {
+        next_random(seed);
+        v = std::fma(static_cast<double>(seed >> 11) / static_cast<double>(1ULL << 53), 2000.0, -1000.0);
+    }
+    return values;
+}
+
+// Runs f(chunk_begin, chunk_end) over CHUNK_SIZE chunks on NUM_THREADS threads,
+// giving each thread its own state from make_state and returning all of them
+template <typename State, typename MakeState, typename F>
+std::vector<State> parallel_chunks(size_t len, MakeState make_state, F f) {
+    std::atomic<size_t> next{0};
+    std::vector<State> states;
+    for (size_t t = 0; t < NUM_THREADS; t++) states.push_back(make_state());
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&, t]() {
+            for (size_t begin = next.fetch_add(CHUNK_SIZE); begin < len; begin = next.fetch_add(CHUNK_SIZE)) {
+                f(states[t], begin, std::min(begin + CHUNK_SIZE, len));
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+    return states;
+}
+
+Histogram histogram_sequential(const std::vector<uint8_t>& data) {
+    Histogram bins{};
+    for (uint8_t b : data) {
+        bins[b]++;
+    }
+    return bins;
+}
+
+// Every byte is a relaxed fetch_add on one shared table
+Histogram histogram_atomic(const std::vector<uint8_t>& data) {
+    std::array<std::atomic<uint64_t>, BINS> bins{};
+    parallel_chunks<int>(data.size(), [] { return 0; }, [&](int&, size_t begin, size_t end) {
+        for (size_t i = begin; i < end; i++) {
+            bins[data[i]].fetch_add(1, std::memory_order_relaxed);
+        }
+    });
+    Histogram result;
+    for (size_t i = 0; i < BINS; i++) result[i] = bins[i].load(std::memory_order_relaxed);
+    return result;
+}
+
+// Private table per worker, merged once at the end
+Histogram histogram_per_thread(const std::vector<uint8_t>& data) {
+    auto partial = parallel_chunks<Histogram>(data.size(), [] { return Histogram{}; },
+                                              [&](Histogram& bins, size_t begin, size_t end) {
+        for (size_t i = begin; i < end; i++) {
+            bins[data[i]]++;
+        }
+    });
+    Histogram result{};
+    for (const auto& bins : partial) {
+        for (size_t i = 0; i < BINS; i++) result[i] += bins[i];
+    }
+    return result;
+}
+
+struct Stats {
+    double sum = 0.0;
+    double min = std::numeric_limits<double>::infinity();
+    double max = -std::numeric_limits<double>::infinity();
+
+    static Stats of(const double* values, size_t len) {
+        Stats s;
+        for (size_t i = 0; i < len; i++) {
+            s.sum += values[i];
+            s.min = std::min(s.min, values[i]);
+            s.max = std::max(s.max, values[i]);
+        }
+        return s;
+    }
+
+    Stats merge(const Stats& other) const {
+        return {sum + other.sum, std::min(min, other.min), std::max(max, other.max)};
+    }
+};
+
+// std::atomic<double> has no fetch_min/fetch_max, so each field is a compare-exchange loop
+template <typename F>
+void atomic_update(std::atomic<double>& cell, F f) {
+    double current = cell.load(std::memory_order_relaxed);
+    while (!cell.compare_exchange_weak(current, f(current), std::memory_order_relaxed)) {
+    }
+}
+
+// Each chunk is reduced locally, then folded into shared atomics
+Stats reduce_atomic(const std::vector<double>& values) {
+    std::atomic<double> sum{0.0};
+    std::atomic<double> min{std::numeric_limits<double>::infinity()};
+    std::atomic<double> max{-std::numeric_limits<double>::infinity()};
+    parallel_chunks<int>(values.size(), [] { return 0; }, [&](int&, size_t begin, size_t end) {
+        Stats s = Stats::of(&values[begin], end - begin);
+        atomic_update(sum, [&](double x) { return x + s.sum; });
+        atomic_update(min, [&](double x) { return std::min(x, s.min); });
+        atomic_update(max, [&](double x) { return std::max(x, s.max); });
+    });
+    return {sum.load(std::memory_order_relaxed), min.load(std::memory_order_relaxed), max.load(std::memory_order_relaxed)};
+}
+
+Stats reduce_per_thread(const std::vector<double>& values) {
+    auto partial = parallel_chunks<Stats>(values.size(), [] { return Stats{}; },
+                                          [&](Stats& s, size_t begin, size_t end) {
+        s = s.merge(Stats::of(&values[begin], end - begin));
+    });
+    Stats result;
+    for (const auto& s : partial) result = result.merge(s);
+    return result;
+}
+
+// The asm barrier keeps the compiler from collapsing repeated passes over unchanged input
+template <typename T, typename F>
+std::pair<std::chrono::duration<double>, T> timed(size_t passes, F f) {
+    auto start = std::chrono::high_resolution_clock::now();
+    T result;
+    for (size_t pass = 0; pass < passes; pass++) {
+        result = f();
+        asm volatile("" : : "r"(&result) : "memory");
+    }
+    return {std::chrono::high_resolution_clock::now() - start, result};
+}
+
+template <typename T>
+struct Run {
+    const char* name;
+    size_t passes;
+    std::pair<std::chrono::duration<double>, T> result;
+};
+
+int main() {
+    std::vector<uint8_t> bytes = generate_bytes();
+    std::vector<double> values = generate_values();
+
+    // Warm-up
+    histogram_per_thread(std::vector<uint8_t>(bytes.begin(), bytes.begin() + NUM_BYTES / 64));
+    reduce_per_thread(std::vector<double>(values.begin(), values.begin() + NUM_VALUES / 64));
+
+    std::vector<Run<Histogram>> hist_runs = {
+        {"sequential", PASSES, timed<Histogram>(PASSES, [&] { return histogram_sequential(bytes); })},
+        {"atomic", ATOMIC_PASSES, timed<Histogram>(ATOMIC_PASSES, [&] { return histogram_atomic(bytes); })},
+        {"per-thread", PASSES, timed<Histogram>(PASSES, [&] { return histogram_per_thread(bytes); })},
+    };
+    std::vector<Run<Stats>> reduce_runs = {
+        {"sequential", PASSES, timed<Stats>(PASSES, [&] { return Stats::of(values.data(), values.size()); })},
+        {"atomic", PASSES, timed<Stats>(PASSES, [&] { return reduce_atomic(values); })},
+        {"per-thread", PASSES, timed<Stats>(PASSES, [&] { return reduce_per_thread(values); })},
+    };
+
+    const Histogram& histogram = hist_runs[0].result.second;
+    const Stats& stats = reduce_runs[0].result.second;
+    for (const auto& run : hist_runs) {
+        if (run.result.second != histogram) {
+            std::cerr << run.name << " histogram differs from sequential" << std::endl;
+            return 1;
+        }
+    }
+    for (const auto& run : reduce_runs) {
+        const Stats& s = run.result.second;
+        if (s.min != stats.min || s.max != stats.max || !(std::abs((s.sum - stats.sum) / stats.sum) < 1e-9)) {
+            std::cerr << run.name << " reduction differs from sequential" << std::endl;
+            return 1;
+        }
+    }
+
+    std::chrono::duration<double> total(0);
+    for (const auto& run : hist_runs) total += run.result.first;
+    for (const auto& run : reduce_runs) total += run.result.first;
+
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    fprintf(stderr, "Histogram over %.2f GB of bytes\n", NUM_BYTES / 1e9);
+    for (const auto& run : hist_runs) {
+        double seconds = run.result.first.count();
+        fprintf(stderr, "  %-10s %zux %8.3fs (%.2f GB/s)\n", run.name, run.passes, seconds,
+                NUM_BYTES * run.passes / 1e9 / seconds);
+    }
+    fprintf(stderr, "Sum/min/max over %zuM double\n", NUM_VALUES >> 20);
+    for (const auto& run : reduce_runs) {
+        double seconds = run.result.first.count();
+        fprintf(stderr, "  %-10s %zux %8.3fs (%.2f GB/s)\n", run.name, run.passes, seconds,
+                NUM_VALUES * 8 * run.passes / 1e9 / seconds);
+    }
+
+    uint64_t weighted = 0;
+    for (size_t i = 0; i < BINS; i++) weighted += (i + 1) * histogram[i];
+    fprintf(stderr, "Checksum: histogram %llu (bin 0: %llu), sum %.6f, min %.6f, max %.6f\n",
+            static_cast<unsigned long long>(weighted), static_cast<unsigned long long>(histogram[0]), stats.sum,
+            stats.min, stats.max);
+
+    return 0;
+}
@@ -0,0 +1,109 @@
+// Huge pages for the benchmarks' largest buffers, the same flag as rust/bench_pages:
+//
+//     --huge-pages  back the big buffers with transparent huge pages (madvise(MADV_HUGEPAGE))
+//
+// huge_pages_enable() must run in main before the buffers exist. Each one is reserved through
+// huge_reserve() and only then filled, so the advice is in place before its pages are first
+// touched; that is also when the kernel decides whether it has a free 2 MiB page, so
+// huge_pages_report() counts the huge-page faults /proc/vmstat recorded since enabling.
+#pragma once
+
+#include <cstdint>
+#include <cstdio>
+#include <cstring>
+#include <fstream>
+#include <string>
+#include <vector>
+#ifdef __linux__
+#include <sys/mman.h>
+#include <unistd.h>
+#endif
+
+struct HugePageFaults {
+    bool available = false;
+    uint64_t huge = 0;     // thp_fault_alloc: faults that got a huge page
+    uint64_t fallback = 0; // thp_fault_fallback: faults in advised memory that fell back to 4 KiB pages
+};
+
+struct HugePageState {
+    bool requested = false;
+    uint64_t advised_bytes = 0;
+    HugePageFaults at_start;
+};
+
+inline HugePageState& huge_page_state() {
+    static HugePageState state;
+    return state;
+}
+
+// System-wide counters, so a run next to another process faulting in huge pages over-reports
+inline HugePageFaults huge_page_faults() {
+    HugePageFaults faults;
+    std::ifstream vmstat("/proc/vmstat");
+    std::string name;
+    uint64_t value;
+    int found = 0;
+    while (vmstat >> name >> value) {
+        if (name == "thp_fault_alloc") faults.huge = value, found++;
+        if (name == "thp_fault_fallback") faults.fallback = value, found++;
+    }
+    faults.available = found == 2;
+    return faults;
+}
+
+// Parses --huge-pages and, if present, starts advising every buffer reserved through huge_reserve()
+inline bool huge_pages_enable(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--huge-pages") == 0) {
+            huge_page_state().requested = true;
+            huge_page_state().at_start = huge_page_faults();
+            return true;
+        }
+    }
+    return false;
+}
+
+// v.reserve(n), advised for huge pages when they were requested. Fill it afterwards (resize,
+// push_back): the pages are only backed once written
+template <typename T>
+void huge_reserve(std::vector<T>& v, size_t n) {
+    v.reserve(n);
+    size_t bytes = v.capacity() * sizeof(T);
+    HugePageState& state = huge_page_state();
+    if (!state.requested || bytes < 2 * 1024 * 1024) return;
+#ifdef __linux__
+    // madvise wants page-aligned bounds; the kernel then uses huge pages for every 2 MiB-aligned
+    // extent inside them, so all but the ragged ends of a large buffer qualify
+    uintptr_t page = sysconf(_SC_PAGESIZE);
+    uintptr_t begin = (reinterpret_cast<uintptr_t>(v.data()) + page - 1) / page * page;
+    uintptr_t end = (reinterpret_cast<uintptr_t>(v.data()) + bytes) / page * page;
+    if (end > begin && madvise(reinterpret_cast<void*>(begin), end - begin, MADV_HUGEPAGE) == 0) {
+        state.advised_bytes += bytes;
+    }
+#endif
+}
+
+// The stderr line the huge-page-aware benchmarks print
+inline std::string huge_pages_report() {
+    // The bracketed choice in "always [madvise] never"; only "never" ignores the advice
+    std::string mode, word;
+    std::ifstream setting("/sys/kernel/mm/transparent_hugepage/enabled");
+    while (setting >> word) {
+        if (word.size() > 2 && word.front() == '[' && word.back() == ']') mode = word.substr(1, word.size() - 2);
+    }
+    std::string suffix = mode.empty() ? "" : " (THP " + mode + ")";
+    const HugePageState& state = huge_page_state();
+    if (!state.requested) return "Huge pages: off" + suffix;
+
+    HugePageFaults now = huge_page_faults();
+    if (mode.empty() || !state.at_start.available || !now.available) {
+        return "Huge pages: not honored, this OS has no transparent huge pages for heap memory";
+    }
+    uint64_t huge = now.huge - state.at_start.huge;
+    uint64_t fallback = now.fallback - state.at_start.fallback;
+    char line[160];
+    std::snprintf(line, sizeof(line), "Huge pages: %s, %.1f MB advised, %llu huge-page faults, %llu fallbacks to 4 KiB pages",
+                  huge > 0 ? "honored" : "not honored", state.advised_bytes / 1e6,
+                  static_cast<unsigned long long>(huge), static_cast<unsigned long long>(fallback));
+    return line + suffix;
+}
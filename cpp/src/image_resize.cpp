@@ -0,0 +1,158 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <chrono>
+#include <iomanip>
+#include <cmath>
+#include <cstdint>
+#include <cstdio>
+#include <algorithm>
+
+const size_t SRC_WIDTH = 6000;   // 24 MP source
+const size_t SRC_HEIGHT = 4000;
+const std::pair<size_t, size_t> TARGETS[] = {{3000, 2000}, {1280, 853}, {7200, 4800}};
+const size_t CHANNELS = 3;
+
+enum class Filter { Bilinear, Lanczos3 };
+
+const char* filter_name(Filter filter) {
+    return filter == Filter::Bilinear ? "bilinear" : "lanczos3";
+}
+
+double filter_support(Filter filter) {
+    return filter == Filter::Bilinear ? 1.0 : 3.0;
+}
+
+double sinc(double x) {
+    return x == 0.0 ? 1.0 : std::sin(M_PI * x) / (M_PI * x);
+}
+
+double filter_weight(Filter filter, double x) {
+    x = std::abs(x);
+    if (filter == Filter::Bilinear) return std::max(1.0 - x, 0.0);
+    return x < 3.0 ? sinc(x) * sinc(x / 3.0) : 0.0;
+}
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// Gradients, a checkerboard and per-pixel noise, so every filter tap matters
+std::vector<uint8_t> source_image() {
+    uint64_t seed = 42;
+    std::vector<uint8_t> pixels;
+    pixels.reserve(SRC_WIDTH * SRC_HEIGHT * CHANNELS);
+    for (size_t y = 0; y < SRC_HEIGHT; y++) {
+        for (size_t x = 0; x < SRC_WIDTH; x++) {
+            uint64_t noise = next_random(seed);
+            pixels.push_back(static_cast<uint8_t>(x * 223 / SRC_WIDTH + (noise & 31)));
+            pixels.push_back(static_cast<uint8_t>(y * 223 / SRC_HEIGHT + ((noise >> 5) & 31)));
+            pixels.push_back(static_cast<uint8_t>(((x / 48 + y / 48) % 2) * 192 + ((noise >> 10) & 63)));
+        }
+    }
+    return pixels;
+}
+
+// Per output coordinate: the first source index and normalized weights (Pillow-style,
+// with the kernel widened by the scale factor when downsampling)
+struct Coefficients {
+    std::vector<size_t> starts;
+    std::vector<std::vector<float>> weights;
+};
+
+Coefficients coefficients(Filter filter, size_t in_size, size_t out_size) {
+    double scale = static_cast<double>(in_size) / static_cast<double>(out_size);
+    double filter_scale = std::max(scale, 1.0);
+    double support = filter_support(filter) * filter_scale;
+    Coefficients result;
+    for (size_t i = 0; i < out_size; i++) {
+        double center = (static_cast<double>(i) + 0.5) * scale;
+        size_t start = static_cast<size_t>(std::max<long>(static_cast<long>(center - support + 0.5), 0));
+        size_t end = std::min(static_cast<size_t>(center + support + 0.5), in_size);
+        std::vector<double> raw;
+        double total = 0.0;
+        for (size_t x = start; x < end; x++) {
+            raw.push_back(filter_weight(filter, (static_cast<double>(x) - center + 0.5) / filter_scale));
+        }
+        for (double w : raw) total += w;
+        std::vector<float> weights;
+        for (double w : raw) weights.push_back(static_cast<float>(w / total));
+        result.starts.push_back(start);
+        result.weights.push_back(std::move(weights));
+    }
+    return result;
+}
+
+// Separable resample: horizontal pass into a float buffer, then vertical pass to u8
+std::vector<uint8_t> resize(const std::vector<uint8_t>& src, size_t width, size_t height, size_t out_width,
+                            size_t out_height, Filter filter) {
+    Coefficients horizontal = coefficients(filter, width, out_width);
+    Coefficients vertical = coefficients(filter, height, out_height);
+
+    size_t row_len = out_width * CHANNELS;
+    std::vector<float> tmp(row_len * height);
+    for (size_t y = 0; y < height; y++) {
+        const uint8_t* src_row = &src[y * width * CHANNELS];
+        float* tmp_row = &tmp[y * row_len];
+        for (size_t x = 0; x < out_width; x++) {
+            const uint8_t* p = &src_row[horizontal.starts[x] * CHANNELS];
+            const std::vector<float>& weights = horizontal.weights[x];
+            float acc[CHANNELS] = {};
+            for (size_t k = 0; k < weights.size(); k++) {
+                for (size_t c = 0; c < CHANNELS; c++) {
+                    acc[c] = std::fma(weights[k], static_cast<float>(p[k * CHANNELS + c]), acc[c]);
+                }
+            }
+            std::copy(acc, acc + CHANNELS, &tmp_row[x * CHANNELS]);
+        }
+    }
+
+    std::vector<uint8_t> dst(row_len * out_height);
+    std::vector<float> acc(row_len);
+    for (size_t y = 0; y < out_height; y++) {
+        std::fill(acc.begin(), acc.end(), 0.0f);
+        const std::vector<float>& weights = vertical.weights[y];
+        for (size_t k = 0; k < weights.size(); k++) {
+            const float* row = &tmp[(vertical.starts[y] + k) * row_len];
+            for (size_t i = 0; i < row_len; i++) {
+                acc[i] = std::fma(weights[k], row[i], acc[i]);
+            }
+        }
+        uint8_t* dst_row = &dst[y * row_len];
+        for (size_t i = 0; i < row_len; i++) {
+            dst_row[i] = static_cast<uint8_t>(std::clamp(std::round(acc[i]), 0.0f, 255.0f));
+        }
+    }
+    return dst;
+}
+
+int main() {
+    std::vector<uint8_t> src = source_image();
+
+    // Warm-up
+    resize(src, SRC_WIDTH, SRC_HEIGHT, 640, 427, Filter::Bilinear);
+
+    std::chrono::duration<double> total(0);
+    uint64_t checksum = 0;
+    std::cerr << "Source: " << SRC_WIDTH << "x" << SRC_HEIGHT << " RGB, output MPixels/s" << std::endl;
+    fprintf(stderr, "%8s %10s  %12s\n", "Filter", "Target", "hand-rolled");
+    for (Filter filter : {Filter::Bilinear, Filter::Lanczos3}) {
+        for (auto [w, h] : TARGETS) {
+            auto start = std::chrono::high_resolution_clock::now();
+            std::vector<uint8_t> out = resize(src, SRC_WIDTH, SRC_HEIGHT, w, h, filter);
+            std::chrono::duration<double> elapsed = std::chrono::high_resolution_clock::now() - start;
+
+            std::string target = std::to_string(w) + "x" + std::to_string(h);
+            fprintf(stderr, "%8s %10s  %12.1f\n", filter_name(filter), target.c_str(),
+                    static_cast<double>(w * h) / elapsed.count() / 1e6);
+            total += elapsed;
+            for (uint8_t b : out) checksum += b;
+        }
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
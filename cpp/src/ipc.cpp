@@ -0,0 +1,333 @@
+#include <iostream>
+#include <string>
+#include <memory>
+#include <vector>
+#include <atomic>
+#include <thread>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+#include <fcntl.h>
+#include <sys/mman.h>
+#include <sys/socket.h>
+#include <sys/stat.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+const size_t STREAM_BYTES = 1ULL << 30;
+const size_t CHUNK_BYTES = 64 << 10; // per send in the stream
+const size_t PING_BYTES = 64;
+const size_t WARMUP_ROUND_TRIPS = 1'000;
+const size_t ROUND_TRIPS = 50'000;
+const size_t RING_BYTES = 1 << 20; // data area of each shared-memory ring
+const size_t RING_HEADER = 256; // head and tail on cache lines of their own
+const uint32_t SPINS_BEFORE_YIELD = 100;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// A byte stream each way between the two processes
+struct Channel {
+    virtual ~Channel() = default;
+    virtual void send(const uint8_t* data, size_t len) = 0;
+    virtual void recv(uint8_t* buf, size_t len) = 0;
+};
+
+// A pipe or a socket: write and read until every byte has gone through the kernel
+struct FdChannel : Channel {
+    int read_fd, write_fd;
+
+    FdChannel(int read_fd, int write_fd) : read_fd(read_fd), write_fd(write_fd) {}
+    ~FdChannel() override {
+        close(read_fd);
+        if (write_fd != read_fd) close(write_fd);
+    }
+
+    void send(const uint8_t* data, size_t len) override {
+        while (len > 0) {
+            ssize_t n = write(write_fd, data, len);
+            if (n <= 0) {
+                perror("write");
+                _exit(1);
+            }
+            data += n;
+            len -= n;
+        }
+    }
+
+    void recv(uint8_t* buf, size_t len) override {
+        while (len > 0) {
+            ssize_t n = read(read_fd, buf, len);
+            if (n <= 0) {
+                perror("read");
+                _exit(1);
+            }
+            buf += n;
+            len -= n;
+        }
+    }
+};
+
+// Back off from a busy-wait: spin briefly, then give the CPU to the other process
+void wait_for_peer(uint32_t& spins) {
+    if (spins++ < SPINS_BEFORE_YIELD) {
+#if defined(__x86_64__)
+        __builtin_ia32_pause();
+#elif defined(__aarch64__)
+        asm volatile("yield");
+#endif
+    } else {
+        std::this_thread::yield();
+    }
+}
+
+// Single-producer single-consumer byte ring in memory shared by both processes. head counts bytes
+// written and tail bytes read; each side only stores its own and loads the other's
+struct Ring {
+    std::atomic<uint64_t>* head;
+    std::atomic<uint64_t>* tail;
+    uint8_t* data;
+
+    explicit Ring(uint8_t* base)
+        : head(new (base) std::atomic<uint64_t>(0)),
+          tail(new (base + RING_HEADER / 2) std::atomic<uint64_t>(0)),
+          data(base + RING_HEADER) {}
+
+    void write(const uint8_t* src, size_t len) {
+        uint64_t h = head->load(std::memory_order_relaxed);
+        uint32_t spins = 0;
+        while (len > 0) {
+            uint64_t free = RING_BYTES - (h - tail->load(std::memory_order_acquire));
+            if (free == 0) {
+                wait_for_peer(spins);
+                continue;
+            }
+            size_t at = h % RING_BYTES;
+            size_t n = std::min({len, static_cast<size_t>(free), RING_BYTES - at});
+            std::memcpy(data + at, src, n);
+            h += n;
+            src += n;
+            len -= n;
+            head->store(h, std::memory_order_release);
+            spins = 0;
+        }
+    }
+
+    void read(uint8_t* dst, size_t len) {
+        uint64_t t = tail->load(std::memory_order_relaxed);
+        uint32_t spins = 0;
+        while (len > 0) {
+            uint64_t available = head->load(std::memory_order_acquire) - t;
+            if (available == 0) {
+                wait_for_peer(spins);
+                continue;
+            }
+            size_t at = t % RING_BYTES;
+            size_t n = std::min({len, static_cast<size_t>(available), RING_BYTES - at});
+            std::memcpy(dst, data + at, n);
+            t += n;
+            dst += n;
+            len -= n;
+            tail->store(t, std::memory_order_release);
+            spins = 0;
+        }
+    }
+};
+
+struct ShmChannel : Channel {
+    Ring tx, rx;
+
+    ShmChannel(uint8_t* tx_base, uint8_t* rx_base) : tx(tx_base), rx(rx_base) {}
+
+    void send(const uint8_t* data, size_t len) override { tx.write(data, len); }
+    void recv(uint8_t* buf, size_t len) override { rx.read(buf, len); }
+};
+
+struct Endpoints {
+    std::unique_ptr<Channel> parent, child;
+};
+
+Endpoints make_pipes() {
+    int down[2], up[2];
+    if (pipe(down) != 0 || pipe(up) != 0) {
+        perror("pipe");
+        std::exit(1);
+    }
+    return {std::make_unique<FdChannel>(up[0], down[1]), std::make_unique<FdChannel>(down[0], up[1])};
+}
+
+Endpoints make_socket() {
+    int fds[2];
+    if (socketpair(AF_UNIX, SOCK_STREAM, 0, fds) != 0) {
+        perror("socketpair");
+        std::exit(1);
+    }
+    return {std::make_unique<FdChannel>(fds[0], fds[0]), std::make_unique<FdChannel>(fds[1], fds[1])};
+}
+
+// Both rings in one shared mapping of an unlinked file, on tmpfs where there is one. fork hands the
+// mapping to the child at the same address
+Endpoints make_shared_ring() {
+    size_t ring = RING_HEADER + RING_BYTES;
+    struct stat info;
+    std::string dir = stat("/dev/shm", &info) == 0 ? "/dev/shm" : (getenv("TMPDIR") ? getenv("TMPDIR") : "/tmp");
+    std::string path = dir + "/ipc_ring_" + std::to_string(getpid());
+    int fd = open(path.c_str(), O_RDWR | O_CREAT | O_EXCL, 0600);
+    if (fd == -1 || unlink(path.c_str()) != 0 || ftruncate(fd, 2 * ring) != 0) {
+        perror(path.c_str());
+        std::exit(1);
+    }
+    void* memory = mmap(nullptr, 2 * ring, PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+    close(fd);
+    if (memory == MAP_FAILED) {
+        perror("mmap");
+        std::exit(1);
+    }
+    uint8_t* down = static_cast<uint8_t*>(memory);
+    uint8_t* up = down + ring;
+    // The mapping outlives the run: the child exits without unmapping, and the parent keeps it
+    return {std::make_unique<ShmChannel>(down, up), std::make_unique<ShmChannel>(up, down)};
+}
+
+// The stream's message: random words, with the message index stamped over the first
+std::vector<uint8_t> stream_chunk() {
+    std::vector<uint8_t> chunk(CHUNK_BYTES);
+    uint64_t seed = 42;
+    for (size_t i = 0; i < CHUNK_BYTES; i += 8) {
+        uint64_t word = next_random(seed);
+        std::memcpy(&chunk[i], &word, 8);
+    }
+    return chunk;
+}
+
+uint64_t word_sum(const uint8_t* data, size_t len) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < len; i += 8) {
+        uint64_t word;
+        std::memcpy(&word, data + i, 8);
+        sum += word;
+    }
+    return sum;
+}
+
+// What the parent expects back: every message's words, indices included, summed with wraparound
+uint64_t expected_stream_sum() {
+    std::vector<uint8_t> chunk = stream_chunk();
+    uint64_t messages = STREAM_BYTES / CHUNK_BYTES;
+    uint64_t rest = word_sum(chunk.data() + 8, CHUNK_BYTES - 8);
+    return messages * rest + messages * (messages - 1) / 2;
+}
+
+// The child: sum the stream and report it, then echo every ping
+void serve(Channel& channel) {
+    std::vector<uint8_t> buf(CHUNK_BYTES);
+    uint64_t sum = 0;
+    for (size_t received = 0; received < STREAM_BYTES; received += CHUNK_BYTES) {
+        channel.recv(buf.data(), CHUNK_BYTES);
+        sum += word_sum(buf.data(), CHUNK_BYTES);
+    }
+    channel.send(reinterpret_cast<const uint8_t*>(&sum), sizeof(sum));
+
+    for (size_t i = 0; i < WARMUP_ROUND_TRIPS + ROUND_TRIPS; i++) {
+        channel.recv(buf.data(), PING_BYTES);
+        channel.send(buf.data(), PING_BYTES);
+    }
+}
+
+struct Result {
+    const char* name;
+    Duration stream;
+    Duration round_trips;
+    uint64_t sum;
+};
+
+void ping(Channel& channel, size_t round, std::vector<uint8_t>& message) {
+    uint64_t stamp = round;
+    std::memcpy(message.data(), &stamp, 8);
+    channel.send(message.data(), PING_BYTES);
+    channel.recv(message.data(), PING_BYTES);
+    std::memcpy(&stamp, message.data(), 8);
+    if (stamp != round) {
+        std::cerr << "Ping " << round << " came back as " << stamp << std::endl;
+        std::exit(1);
+    }
+}
+
+Result run(const char* name, Endpoints endpoints) {
+    pid_t pid = fork();
+    if (pid == -1) {
+        perror("fork");
+        std::exit(1);
+    }
+    if (pid == 0) {
+        endpoints.parent.reset();
+        serve(*endpoints.child);
+        _exit(0);
+    }
+    endpoints.child.reset();
+    Channel& channel = *endpoints.parent;
+
+    // Stream: time until the child has summed the last byte and said so
+    std::vector<uint8_t> chunk = stream_chunk();
+    auto start = Clock::now();
+    for (uint64_t message = 0; message < STREAM_BYTES / CHUNK_BYTES; message++) {
+        std::memcpy(chunk.data(), &message, 8);
+        channel.send(chunk.data(), CHUNK_BYTES);
+    }
+    uint64_t sum;
+    channel.recv(reinterpret_cast<uint8_t*>(&sum), sizeof(sum));
+    Duration stream = Clock::now() - start;
+
+    // Round trips, after a warm-up
+    std::vector<uint8_t> message(PING_BYTES, 0xA5);
+    for (size_t i = 0; i < WARMUP_ROUND_TRIPS; i++) ping(channel, i, message);
+    start = Clock::now();
+    for (size_t i = 0; i < ROUND_TRIPS; i++) ping(channel, WARMUP_ROUND_TRIPS + i, message);
+    Duration round_trips = Clock::now() - start;
+
+    int status = 0;
+    if (waitpid(pid, &status, 0) != pid || !WIFEXITED(status) || WEXITSTATUS(status) != 0) {
+        std::cerr << name << ": child did not exit cleanly" << std::endl;
+        std::exit(1);
+    }
+    return {name, stream, round_trips, sum};
+}
+
+int main() {
+    uint64_t expected = expected_stream_sum();
+
+    std::vector<Result> results;
+    results.push_back(run("pipe", make_pipes()));
+    results.push_back(run("unix socket", make_socket()));
+    results.push_back(run("shared-memory ring", make_shared_ring()));
+
+    Duration total{0};
+    for (const Result& r : results) {
+        if (r.sum != expected) {
+            std::cerr << r.name << " streamed a sum of " << r.sum << ", expected " << expected << std::endl;
+            return 1;
+        }
+        total += r.stream + r.round_trips;
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    std::cerr << (STREAM_BYTES >> 20) << " MiB streamed in " << (CHUNK_BYTES >> 10) << " KiB messages, "
+              << ROUND_TRIPS << " round trips of " << PING_BYTES << " bytes" << std::endl;
+    for (const Result& r : results) {
+        char line[128];
+        std::snprintf(line, sizeof(line), "  %-20s %7.2f GB/s   %7.2f us per round trip", r.name,
+                      STREAM_BYTES / r.stream.count() / 1e9, r.round_trips.count() * 1e6 / ROUND_TRIPS);
+        std::cerr << line << std::endl;
+    }
+    std::cerr << "Checksum: " << expected << std::endl;
+    return 0;
+}
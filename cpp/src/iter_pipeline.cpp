@@ -0,0 +1,109 @@
+#include <iostream>
+#include <vector>
+#include <ranges>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+const size_t NUM_ELEMENTS = 50'000'000;
+const size_t NUM_PASSES = 10; // 500M elements through each variant
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+std::vector<uint32_t> generate(uint64_t seed) {
+    std::vector<uint32_t> v(NUM_ELEMENTS);
+    for (auto& x : v) x = static_cast<uint32_t>(next_random(seed));
+    return v;
+}
+
+// The pipeline stages, shared by both variants so only the control flow differs
+inline bool keep(uint32_t x) {
+    return x % 3 != 0;
+}
+
+inline uint64_t transform(uint32_t x) {
+    return static_cast<uint64_t>((x * 0x9E3779B1u) >> 7);
+}
+
+inline uint64_t combine(uint64_t acc, uint64_t m, uint32_t y) {
+    return (acc + m * y) ^ (m >> 3);
+}
+
+// filter -> map -> zip -> fold with C++20 views. views::zip is C++23, so the
+// zip and fold are a range-for with a cursor into `b`
+uint64_t run_ranges(const std::vector<uint32_t>& a, const std::vector<uint32_t>& b) {
+    auto mapped = a | std::views::filter(keep) | std::views::transform(transform);
+    uint64_t acc = 0;
+    auto y = b.begin();
+    for (uint64_t m : mapped) {
+        if (y == b.end()) break;
+        acc = combine(acc, m, *y++);
+    }
+    return acc;
+}
+
+// The same pipeline as the loop you would write by hand; `j` is the zip cursor into `b`
+uint64_t run_loop(const std::vector<uint32_t>& a, const std::vector<uint32_t>& b) {
+    uint64_t acc = 0;
+    size_t j = 0;
+    for (size_t i = 0; i < a.size(); i++) {
+        uint32_t x = a[i];
+        if (!keep(x)) continue;
+        if (j == b.size()) break;
+        acc = combine(acc, transform(x), b[j]);
+        j++;
+    }
+    return acc;
+}
+
+uint64_t run_passes(const std::vector<uint32_t>& a, const std::vector<uint32_t>& b, size_t passes,
+                    uint64_t (*f)(const std::vector<uint32_t>&, const std::vector<uint32_t>&)) {
+    uint64_t checksum = 0;
+    for (size_t p = 0; p < passes; p++) {
+        // Volatile pointers keep the compiler from hoisting the pass out of the loop
+        const std::vector<uint32_t>* volatile pa = &a;
+        const std::vector<uint32_t>* volatile pb = &b;
+        checksum += f(*pa, *pb);
+    }
+    return checksum;
+}
+
+int main() {
+    std::vector<uint32_t> a = generate(2024);
+    std::vector<uint32_t> b = generate(4048);
+
+    // Warm-up
+    std::vector<uint32_t> warmup(a.begin(), a.begin() + NUM_ELEMENTS / 100);
+    run_passes(warmup, b, 1, run_ranges);
+    run_passes(warmup, b, 1, run_loop);
+
+    // Benchmark ranges pipeline
+    auto start = std::chrono::high_resolution_clock::now();
+    uint64_t ranges_checksum = run_passes(a, b, NUM_PASSES, run_ranges);
+    auto end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> ranges_duration = end - start;
+
+    // Benchmark hand-written loop
+    start = std::chrono::high_resolution_clock::now();
+    uint64_t loop_checksum = run_passes(a, b, NUM_PASSES, run_loop);
+    end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> loop_duration = end - start;
+
+    double total_duration = ranges_duration.count() + loop_duration.count();
+    double elements = static_cast<double>(NUM_ELEMENTS * NUM_PASSES);
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration << std::endl;
+    std::cerr << std::fixed << std::setprecision(3) << "Ranges pipeline: " << ranges_duration.count() << "s ("
+              << std::setprecision(0) << elements / 1e6 / ranges_duration.count() << "M elements/s)" << std::endl;
+    std::cerr << std::setprecision(3) << "Manual loop:     " << loop_duration.count() << "s ("
+              << std::setprecision(0) << elements / 1e6 / loop_duration.count() << "M elements/s)" << std::endl;
+    std::cerr << std::setprecision(3) << "Abstraction cost: " << ranges_duration.count() / loop_duration.count()
+              << "x" << std::endl;
+    std::cerr << "Checksum: " << ranges_checksum << " (variants match: "
+              << (ranges_checksum == loop_checksum ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
@@ -1,9 +1,23 @@
+#include <charconv>
 #include <iostream>
 #include <string>
 #include <vector>
 #include <sstream>
 #include <chrono>
 #include <iomanip>
+#include <optional>
+#include <string_view>
+#include <cstring>
+#include <cstdlib>
+#include <cstdint>
+#include <cmath>
+
+#include "bench_measure.h"
+
+const size_t RECORDS = 10'000;
+// --corpus floats: embeddings the size of a small sentence-embedding model's
+const size_t VECTORS = 2'000;
+const size_t DIMENSIONS = 384;
 
 // Simple JSON generator/parser (avoiding external dependencies)
 struct Metadata {
@@ -19,8 +33,38 @@ struct Record {
     uint32_t age;
     double balance;
     bool is_active;
+    // Optional fields, present in --optional-density of the records and omitted otherwise
+    std::optional<std::string> nickname;
+    std::optional<std::string> phone;
+    std::optional<double> score;
     std::vector<std::string> tags;
-    Metadata metadata;
+    // One entry per level of nesting: metadata, then metadata.parent, ...
+    std::vector<Metadata> metadata;
+};
+
+// A row of the float-heavy corpus: almost every byte of the document is a number, so its parse
+// time is number parsing, where the records above mostly copy strings
+struct Embedding {
+    uint32_t id;
+    std::vector<float> vector;
+};
+
+// The document shape; the defaults reproduce the original 10k flat records
+struct Shape {
+    size_t records = RECORDS;
+    size_t depth = 1;               // levels of metadata objects, 1 = a single nested object
+    std::optional<size_t> string_length; // pad or cut name and nickname to this many bytes
+    double optional_density = 0.0;  // fraction of records carrying each optional field
+    
+    // Deterministic per record and field, so both languages produce the same document
+    bool has_optional(size_t i, size_t field) const {
+        return (i * 37 + field * 11) % 100 < static_cast<size_t>(std::round(optional_density * 100.0));
+    }
+    
+    std::string sized(std::string base) const {
+        if (string_length) base.resize(*string_length, 'x');
+        return base;
+    }
 };
 
 std::string escape_json_string(const std::string& str) {
@@ -41,17 +85,23 @@ std::string serialize_record(const Record& r) {
     oss << "\"age\":" << r.age << ",";
     oss << std::fixed << std::setprecision(3) << "\"balance\":" << r.balance << ",";
     oss << "\"is_active\":" << (r.is_active ? "true" : "false") << ",";
+    if (r.nickname) oss << "\"nickname\":\"" << escape_json_string(*r.nickname) << "\",";
+    if (r.phone) oss << "\"phone\":\"" << escape_json_string(*r.phone) << "\",";
+    if (r.score) oss << "\"score\":" << *r.score << ",";
     oss << "\"tags\":[";
     for (size_t i = 0; i < r.tags.size(); i++) {
         if (i > 0) oss << ",";
         oss << "\"" << escape_json_string(r.tags[i]) << "\"";
     }
     oss << "],";
-    oss << "\"metadata\":{";
-    oss << "\"created_at\":\"" << escape_json_string(r.metadata.created_at) << "\",";
-    oss << "\"updated_at\":\"" << escape_json_string(r.metadata.updated_at) << "\",";
-    oss << "\"version\":" << r.metadata.version;
-    oss << "}}";
+    for (size_t level = 0; level < r.metadata.size(); level++) {
+        const Metadata& m = r.metadata[level];
+        oss << (level == 0 ? "\"metadata\":{" : ",\"parent\":{");
+        oss << "\"created_at\":\"" << escape_json_string(m.created_at) << "\",";
+        oss << "\"updated_at\":\"" << escape_json_string(m.updated_at) << "\",";
+        oss << "\"version\":" << m.version;
+    }
+    oss << std::string(r.metadata.size(), '}') << "}";
     return oss.str();
 }
 
@@ -66,33 +116,49 @@ std::string serialize_records(const std::vector<Record>& records) {
     return oss.str();
 }
 
-// Simple JSON parser
-size_t find_next_field(const std::string& json, const std::string& field, size_t start) {
-    std::string search = "\"" + field + "\":";
-    return json.find(search, start);
-}
-
-std::string extract_string_value(const std::string& json, size_t pos) {
-    size_t start = json.find('"', pos) + 1;
+// Simple JSON parser; values are looked up by key within one record's text
+std::string extract_string_value(std::string_view json, size_t pos) {
+    size_t start = json.find('"', json.find(':', pos)) + 1;
     size_t end = json.find('"', start);
-    return json.substr(start, end - start);
+    return std::string(json.substr(start, end - start));
 }
 
-int extract_int_value(const std::string& json, size_t pos) {
+int extract_int_value(std::string_view json, size_t pos) {
     size_t start = json.find(':', pos) + 1;
-    return std::stoi(json.substr(start));
+    return static_cast<int>(std::strtol(json.data() + start, nullptr, 10));
 }
 
-double extract_double_value(const std::string& json, size_t pos) {
+double extract_double_value(std::string_view json, size_t pos) {
     size_t start = json.find(':', pos) + 1;
-    return std::stod(json.substr(start));
+    return std::strtod(json.data() + start, nullptr);
 }
 
-bool extract_bool_value(const std::string& json, size_t pos) {
+bool extract_bool_value(std::string_view json, size_t pos) {
     size_t start = json.find(':', pos) + 1;
     return json.substr(start, 4) == "true";
 }
 
+// End of the object starting at `start`, matching braces outside strings so nested metadata
+// and optional fields do not confuse the record bounds
+size_t find_object_end(const std::string& json, size_t start) {
+    size_t depth = 0;
+    bool in_string = false;
+    for (size_t i = start; i < json.size(); i++) {
+        char c = json[i];
+        if (in_string) {
+            if (c == '\\') i++;
+            else if (c == '"') in_string = false;
+        } else if (c == '"') {
+            in_string = true;
+        } else if (c == '{') {
+            depth++;
+        } else if (c == '}' && --depth == 0) {
+            return i;
+        }
+    }
+    return std::string::npos;
+}
+
 std::vector<Record> parse_records(const std::string& json) {
     std::vector<Record> records;
     size_t pos = 1; // Start after '['
@@ -109,46 +175,56 @@ std::vector<Record> parse_records(const std::string& json) {
         if (json[pos] != '{') break;
         
         size_t record_start = pos;
-        size_t record_end = json.find('}', record_start);
+        size_t record_end = find_object_end(json, record_start);
         if (record_end == std::string::npos) break;
-        
-        // Find next nested object end if metadata exists
-        size_t nested_end = json.find('}', record_end + 1);
-        if (nested_end != std::string::npos && nested_end < record_end + 10) {
-            record_end = nested_end;
-        }
+        std::string_view record(json.data() + record_start, record_end - record_start + 1);
         
         Record r;
         
         // Parse fields within the record bounds
-        size_t id_pos = json.find("\"id\":", record_start);
-        if (id_pos != std::string::npos && id_pos < record_end) {
-            r.id = extract_int_value(json, id_pos);
+        size_t id_pos = record.find("\"id\":");
+        if (id_pos != std::string_view::npos) {
+            r.id = extract_int_value(record, id_pos);
         }
         
-        size_t name_pos = json.find("\"name\":", record_start);
-        if (name_pos != std::string::npos && name_pos < record_end) {
-            r.name = extract_string_value(json, name_pos);
+        size_t name_pos = record.find("\"name\":");
+        if (name_pos != std::string_view::npos) {
+            r.name = extract_string_value(record, name_pos);
         }
         
-        size_t email_pos = json.find("\"email\":", record_start);
-        if (email_pos != std::string::npos && email_pos < record_end) {
-            r.email = extract_string_value(json, email_pos);
+        size_t email_pos = record.find("\"email\":");
+        if (email_pos != std::string_view::npos) {
+            r.email = extract_string_value(record, email_pos);
         }
         
-        size_t age_pos = json.find("\"age\":", record_start);
-        if (age_pos != std::string::npos && age_pos < record_end) {
-            r.age = extract_int_value(json, age_pos);
+        size_t age_pos = record.find("\"age\":");
+        if (age_pos != std::string_view::npos) {
+            r.age = extract_int_value(record, age_pos);
         }
         
-        size_t balance_pos = json.find("\"balance\":", record_start);
-        if (balance_pos != std::string::npos && balance_pos < record_end) {
-            r.balance = extract_double_value(json, balance_pos);
+        size_t balance_pos = record.find("\"balance\":");
+        if (balance_pos != std::string_view::npos) {
+            r.balance = extract_double_value(record, balance_pos);
         }
         
-        size_t active_pos = json.find("\"is_active\":", record_start);
-        if (active_pos != std::string::npos && active_pos < record_end) {
-            r.is_active = extract_bool_value(json, active_pos);
+        size_t active_pos = record.find("\"is_active\":");
+        if (active_pos != std::string_view::npos) {
+            r.is_active = extract_bool_value(record, active_pos);
+        }
+        
+        size_t nickname_pos = record.find("\"nickname\":");
+        if (nickname_pos != std::string_view::npos) {
+            r.nickname = extract_string_value(record, nickname_pos);
+        }
+        
+        size_t phone_pos = record.find("\"phone\":");
+        if (phone_pos != std::string_view::npos) {
+            r.phone = extract_string_value(record, phone_pos);
+        }
+        
+        size_t score_pos = record.find("\"score\":");
+        if (score_pos != std::string_view::npos) {
+            r.score = extract_double_value(record, score_pos);
         }
         
         records.push_back(r);
@@ -158,26 +234,33 @@ std::vector<Record> parse_records(const std::string& json) {
     return records;
 }
 
-std::vector<Record> generate_records(size_t count) {
+std::vector<Record> generate_records(const Shape& shape) {
     std::vector<Record> records;
-    records.reserve(count);
+    records.reserve(shape.records);
     
-    for (size_t i = 0; i < count; i++) {
+    for (size_t i = 0; i < shape.records; i++) {
         Record r;
         r.id = static_cast<uint32_t>(i);
-        r.name = "User " + std::to_string(i);
+        r.name = shape.sized("User " + std::to_string(i));
         r.email = "user" + std::to_string(i) + "@example.com";
         r.age = 20 + (i % 50);
         r.balance = static_cast<double>(i) * 123.456;
         r.is_active = (i % 2 == 0);
+        if (shape.has_optional(i, 0)) r.nickname = shape.sized("nick" + std::to_string(i));
+        if (shape.has_optional(i, 1)) {
+            std::ostringstream phone;
+            phone << "+1-555-" << std::setw(4) << std::setfill('0') << i % 10'000;
+            r.phone = phone.str();
+        }
+        if (shape.has_optional(i, 2)) r.score = static_cast<double>(i % 1000) / 8.0;
         r.tags = {
             "tag" + std::to_string(i % 10),
             "category" + std::to_string(i % 5),
             "important"
         };
-        r.metadata.created_at = "2024-01-01T00:00:00Z";
-        r.metadata.updated_at = "2024-01-02T00:00:00Z";
-        r.metadata.version = i % 100;
+        for (size_t level = 0; level < shape.depth; level++) {
+            r.metadata.push_back({"2024-01-01T00:00:00Z", "2024-01-02T00:00:00Z", static_cast<uint32_t>((i + level) % 100)});
+        }
         
         records.push_back(r);
     }
@@ -185,8 +268,199 @@ std::vector<Record> generate_records(size_t count) {
     return records;
 }
 
-int main() {
-    auto records = generate_records(10'000);
+// Uniform in [-1, 1) and 24 bits deep, like a normalized feature; the same bits as the Rust version
+float embedding_value(uint64_t index) {
+    uint64_t h = (index + 1) * 0x9E3779B97F4A7C15ULL;
+    h = (h ^ (h >> 31)) * 0xBF58476D1CE4E5B9ULL;
+    return static_cast<float>(h >> 40) / static_cast<float>(1u << 23) - 1.0f;
+}
+
+std::vector<Embedding> generate_embeddings(size_t vectors, size_t dimensions) {
+    std::vector<Embedding> embeddings(vectors);
+    for (size_t i = 0; i < vectors; i++) {
+        embeddings[i].id = static_cast<uint32_t>(i);
+        embeddings[i].vector.resize(dimensions);
+        for (size_t j = 0; j < dimensions; j++) {
+            embeddings[i].vector[j] = embedding_value(i * dimensions + j);
+        }
+    }
+    return embeddings;
+}
+
+// Shortest round-trip form, as serde_json writes floats
+std::string serialize_embeddings(const std::vector<Embedding>& embeddings) {
+    std::string out = "[";
+    char buffer[32];
+    for (size_t i = 0; i < embeddings.size(); i++) {
+        if (i > 0) out += ',';
+        out += "{\"id\":" + std::to_string(embeddings[i].id) + ",\"vector\":[";
+        for (size_t j = 0; j < embeddings[i].vector.size(); j++) {
+            if (j > 0) out += ',';
+            out.append(buffer, std::to_chars(buffer, buffer + sizeof(buffer), embeddings[i].vector[j]).ptr);
+        }
+        out += "]}";
+    }
+    out += "]";
+    return out;
+}
+
+// Keys are found by search as in parse_records; the numbers are read with from_chars in one pass
+std::vector<Embedding> parse_embeddings(const std::string& json) {
+    std::vector<Embedding> embeddings;
+    const char* end = json.data() + json.size();
+    size_t pos = 0;
+    while ((pos = json.find("\"id\":", pos)) != std::string::npos) {
+        Embedding e{};
+        std::from_chars(json.data() + pos + 5, end, e.id);
+        pos = json.find("\"vector\":[", pos);
+        if (pos == std::string::npos) break;
+        const char* p = json.data() + pos + 10;
+        while (p < end && *p != ']') {
+            float value = 0.0f;
+            p = std::from_chars(p, end, value).ptr;
+            e.vector.push_back(value);
+            if (*p == ',') p++;
+        }
+        pos = p - json.data();
+        embeddings.push_back(std::move(e));
+    }
+    return embeddings;
+}
+
+// Folds every float's bits, so a parse that lands one ulp off shows up; matches the Rust checksum
+uint64_t embedding_digest(const std::vector<Embedding>& embeddings) {
+    uint64_t sum = 0;
+    for (const Embedding& e : embeddings) {
+        sum += e.id;
+        for (float x : e.vector) {
+            uint32_t bits;
+            std::memcpy(&bits, &x, sizeof(bits));
+            sum += bits;
+        }
+    }
+    return sum;
+}
+
+[[noreturn]] void fail(const char* message) {
+    std::cerr << message << std::endl;
+    std::exit(1);
+}
+
+Shape parse_shape(int argc, char** argv) {
+    Shape shape;
+    for (int i = 1; i < argc; i++) {
+        const char* value = i + 1 < argc ? argv[i + 1] : "";
+        char* end = nullptr;
+        if (std::strcmp(argv[i], "--records") == 0) {
+            shape.records = std::strtoull(value, &end, 10);
+            if (end == value || *end != '\0') fail("--records expects a record count");
+        } else if (std::strcmp(argv[i], "--depth") == 0) {
+            // serde_json stops at 128 levels of nesting; the same limit keeps the shapes comparable
+            shape.depth = std::strtoull(value, &end, 10);
+            if (end == value || *end != '\0' || shape.depth < 1 || shape.depth > 100) fail("--depth expects a nesting depth from 1 to 100");
+        } else if (std::strcmp(argv[i], "--string-length") == 0) {
+            shape.string_length = std::strtoull(value, &end, 10);
+            if (end == value || *end != '\0') fail("--string-length expects a length in bytes");
+        } else if (std::strcmp(argv[i], "--optional-density") == 0) {
+            shape.optional_density = std::strtod(value, &end);
+            if (end == value || *end != '\0' || !(shape.optional_density >= 0.0 && shape.optional_density <= 1.0)) {
+                fail("--optional-density expects a fraction from 0 to 1");
+            }
+        }
+    }
+    return shape;
+}
+
+struct Corpus {
+    bool floats = false;
+    size_t vectors = VECTORS;
+    size_t dimensions = DIMENSIONS;
+};
+
+Corpus parse_corpus(int argc, char** argv) {
+    Corpus corpus;
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--corpus") != 0) continue;
+        std::string value = i + 1 < argc ? argv[i + 1] : "";
+        if (value != "records" && value != "floats") fail("--corpus expects records or floats");
+        corpus.floats = value == "floats";
+    }
+    if (!corpus.floats) return corpus;
+    for (int i = 1; i < argc; i++) {
+        const char* value = i + 1 < argc ? argv[i + 1] : "";
+        char* end = nullptr;
+        if (std::strcmp(argv[i], "--records") == 0) {
+            corpus.vectors = std::strtoull(value, &end, 10);
+            if (end == value || *end != '\0') fail("--records expects a record count");
+        } else if (std::strcmp(argv[i], "--dimensions") == 0) {
+            corpus.dimensions = std::strtoull(value, &end, 10);
+            if (end == value || *end != '\0') fail("--dimensions expects a vector length");
+        } else if (std::strcmp(argv[i], "--depth") == 0 || std::strcmp(argv[i], "--string-length") == 0 ||
+                   std::strcmp(argv[i], "--optional-density") == 0) {
+            fail((std::string(argv[i]) + " only shapes --corpus records").c_str());
+        }
+    }
+    return corpus;
+}
+
+// The same parse and serialize round trip over embeddings
+int run_floats(const Corpus& corpus, bool json_output, Measurer& measure) {
+    auto embeddings = generate_embeddings(corpus.vectors, corpus.dimensions);
+    std::string json_string = serialize_embeddings(embeddings);
+
+    // Warm-up
+    auto warmup = parse_embeddings(json_string);
+
+    auto start = std::chrono::high_resolution_clock::now();
+    measure.start();
+    auto parsed = parse_embeddings(json_string);
+    measure.stop();
+    auto parse_end = std::chrono::high_resolution_clock::now();
+
+    auto serialize_start = std::chrono::high_resolution_clock::now();
+    measure.start();
+    auto serialized = serialize_embeddings(parsed);
+    measure.stop();
+    auto serialize_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> parse_duration = parse_end - start;
+    std::chrono::duration<double> serialize_duration = serialize_end - serialize_start;
+    double megabytes = json_string.length() / 1e6;
+    size_t numbers = corpus.vectors * corpus.dimensions;
+    uint64_t checksum = embedding_digest(parsed);
+    bool matches = checksum == embedding_digest(embeddings);
+
+    measure.report();
+    if (json_output) {
+        std::cerr << std::setprecision(9) << "{\"bytes\":" << serialized.length() << ",\"checksum\":" << checksum
+                  << ",\"corpus\":\"floats\""
+                  << ",\"parse\":{\"mb_per_s\":" << megabytes / parse_duration.count() << ",\"seconds\":" << parse_duration.count() << "}"
+                  << ",\"serialize\":{\"mb_per_s\":" << megabytes / serialize_duration.count() << ",\"seconds\":" << serialize_duration.count() << "}"
+                  << ",\"shape\":{\"dimensions\":" << corpus.dimensions << ",\"records\":" << corpus.vectors << "}"
+                  << ",\"variants_match\":" << (matches ? "true" : "false") << "}" << std::endl;
+        return 0;
+    }
+    std::cerr << std::fixed << std::setprecision(6);
+    std::cerr << "Corpus: floats, " << corpus.vectors << " vectors of " << corpus.dimensions << " dimensions" << std::endl;
+    std::cerr << "Parse: " << parse_duration.count() << "s, Serialize: " << serialize_duration.count() << "s" << std::endl;
+    std::cerr << std::setprecision(1) << "Parse: " << megabytes / parse_duration.count() << " MB/s, Serialize: "
+              << megabytes / serialize_duration.count() << " MB/s" << std::endl;
+    std::cerr << "Numbers: " << numbers << " (" << numbers / 1e6 / parse_duration.count() << " M/s parsed), JSON size: "
+              << serialized.length() << " bytes" << std::endl;
+    std::cerr << "Checksum: " << checksum << " (round trip matches: " << (matches ? "true" : "false") << ")" << std::endl;
+    return 0;
+}
+
+int main(int argc, char** argv) {
+    auto measure = bench_measure_from_env();
+    bool json_output = false;
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--json") == 0) json_output = true;
+    }
+    Corpus corpus = parse_corpus(argc, argv);
+    if (corpus.floats) return run_floats(corpus, json_output, *measure);
+    Shape shape = parse_shape(argc, argv);
+    auto records = generate_records(shape);
     
     // Serialize
     std::string json_string = serialize_records(records);
@@ -196,20 +470,40 @@ int main() {
     
     // Benchmark parse
     auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
     auto parsed = parse_records(json_string);
+    measure->stop();
     auto parse_end = std::chrono::high_resolution_clock::now();
     
     // Benchmark serialize
     auto serialize_start = std::chrono::high_resolution_clock::now();
+    measure->start();
     auto serialized = serialize_records(parsed);
+    measure->stop();
     auto serialize_end = std::chrono::high_resolution_clock::now();
     
     std::chrono::duration<double> parse_duration = parse_end - start;
     std::chrono::duration<double> serialize_duration = serialize_end - serialize_start;
-    std::chrono::duration<double> total_duration = parse_duration + serialize_duration;
     
-    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    double megabytes = json_string.length() / 1e6;
+    
+    measure->report();
+    if (json_output) {
+        // One machine-readable line instead of the text report
+        std::cerr << std::setprecision(9) << "{\"bytes\":" << serialized.length() << ",\"corpus\":\"records\""
+                  << ",\"parse\":{\"mb_per_s\":" << megabytes / parse_duration.count() << ",\"seconds\":" << parse_duration.count() << "}"
+                  << ",\"serialize\":{\"mb_per_s\":" << megabytes / serialize_duration.count() << ",\"seconds\":" << serialize_duration.count() << "}"
+                  << ",\"shape\":{\"depth\":" << shape.depth << ",\"optional_density\":" << shape.optional_density
+                  << ",\"records\":" << shape.records << ",\"string_length\":"
+                  << (shape.string_length ? std::to_string(*shape.string_length) : "null") << "}}" << std::endl;
+        return 0;
+    }
+    std::cerr << "Shape: " << shape.records << " records, depth " << shape.depth << ", string length "
+              << (shape.string_length ? std::to_string(*shape.string_length) : "default")
+              << ", optional density " << std::defaultfloat << shape.optional_density << std::fixed << std::endl;
     std::cerr << "Parse: " << parse_duration.count() << "s, Serialize: " << serialize_duration.count() << "s" << std::endl;
+    std::cerr << std::setprecision(1) << "Parse: " << megabytes / parse_duration.count() << " MB/s, Serialize: "
+              << megabytes / serialize_duration.count() << " MB/s" << std::endl;
     std::cerr << "Records: " << parsed.size() << ", JSON size: " << serialized.length() << " bytes" << std::endl;
     
     return 0;
@@ -0,0 +1,370 @@
+#include <arrow/api.h>
+#include <arrow/io/api.h>
+#include <arrow/json/api.h>
+#include <parquet/arrow/reader.h>
+#include <parquet/arrow/writer.h>
+#include <parquet/properties.h>
+#include <iostream>
+#include <string>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <optional>
+#include <charconv>
+#include <algorithm>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+
+const size_t DEFAULT_RECORDS = 1'000'000;
+const size_t WARMUP_RECORDS = 50'000;
+const int64_t BATCH_ROWS = 65'536; // rows per Parquet read batch
+const int64_t ROW_GROUP_ROWS = 131'072;
+const size_t OPTIONAL_PERCENT = 50; // share of records carrying each optional field
+
+// json_parse's record at depth 1, with half of the optional fields present
+struct Metadata {
+    std::string created_at;
+    std::string updated_at;
+    uint32_t version;
+};
+
+struct Record {
+    uint32_t id;
+    std::string name;
+    std::string email;
+    uint32_t age;
+    double balance;
+    bool is_active;
+    std::optional<std::string> nickname;
+    std::optional<std::string> phone;
+    std::optional<double> score;
+    std::vector<std::string> tags;
+    Metadata metadata;
+};
+
+enum class Codec { Uncompressed, Snappy, Zstd };
+
+std::optional<Codec> parse_codec(const char* name) {
+    if (std::strcmp(name, "none") == 0) return Codec::Uncompressed;
+    if (std::strcmp(name, "snappy") == 0) return Codec::Snappy;
+    if (std::strcmp(name, "zstd") == 0) return Codec::Zstd;
+    return std::nullopt;
+}
+
+const char* codec_name(Codec codec) {
+    switch (codec) {
+        case Codec::Uncompressed: return "none";
+        case Codec::Snappy: return "snappy";
+        case Codec::Zstd: return "zstd";
+    }
+    return "";
+}
+
+// Aborts with Arrow's message on any failed Status or Result, like Rust's expect
+template <typename T>
+T expect(arrow::Result<T> result, const char* what) {
+    if (!result.ok()) {
+        std::cerr << what << ": " << result.status().ToString() << std::endl;
+        std::exit(1);
+    }
+    return std::move(result).ValueUnsafe();
+}
+
+void expect(const arrow::Status& status, const char* what) {
+    if (!status.ok()) {
+        std::cerr << what << ": " << status.ToString() << std::endl;
+        std::exit(1);
+    }
+}
+
+// Deterministic per record and field, so both languages produce the same document
+bool has_optional(size_t i, size_t field) {
+    return (i * 37 + field * 11) % 100 < OPTIONAL_PERCENT;
+}
+
+std::vector<Record> generate_records(size_t count) {
+    std::vector<Record> records;
+    records.reserve(count);
+    char phone[32];
+    for (size_t i = 0; i < count; i++) {
+        Record r;
+        r.id = static_cast<uint32_t>(i);
+        r.name = "User " + std::to_string(i);
+        r.email = "user" + std::to_string(i) + "@example.com";
+        r.age = 20 + static_cast<uint32_t>(i % 50);
+        r.balance = static_cast<double>(i) * 123.456;
+        r.is_active = i % 2 == 0;
+        if (has_optional(i, 0)) r.nickname = "nick" + std::to_string(i);
+        if (has_optional(i, 1)) {
+            std::snprintf(phone, sizeof(phone), "+1-555-%04zu", i % 10'000);
+            r.phone = phone;
+        }
+        if (has_optional(i, 2)) r.score = static_cast<double>(i % 1000) / 8.0;
+        r.tags = {"tag" + std::to_string(i % 10), "category" + std::to_string(i % 5), "important"};
+        r.metadata = {"2024-01-01T00:00:00Z", "2024-01-02T00:00:00Z", static_cast<uint32_t>(i % 100)};
+        records.push_back(std::move(r));
+    }
+    return records;
+}
+
+// Shortest round-trip digits, with ".0" on integral values as serde_json writes them
+void append_real(std::string& out, double value) {
+    char buffer[32];
+    auto end = std::to_chars(buffer, buffer + sizeof(buffer), value).ptr;
+    out.append(buffer, end);
+    if (std::find_if(buffer, end, [](char c) { return c == '.' || c == 'e'; }) == end) out += ".0";
+}
+
+// One record per line, the form both Arrow JSON readers take; none of the strings need escaping
+std::string to_ndjson(const std::vector<Record>& records) {
+    std::string out;
+    auto field = [&](const char* name, const std::string& value) {
+        out += '"';
+        out += name;
+        out += "\":\"";
+        out += value;
+        out += "\",";
+    };
+    for (const Record& r : records) {
+        out += "{\"id\":" + std::to_string(r.id) + ",";
+        field("name", r.name);
+        field("email", r.email);
+        out += "\"age\":" + std::to_string(r.age) + ",\"balance\":";
+        append_real(out, r.balance);
+        out += r.is_active ? ",\"is_active\":true," : ",\"is_active\":false,";
+        if (r.nickname) field("nickname", *r.nickname);
+        if (r.phone) field("phone", *r.phone);
+        if (r.score) {
+            out += "\"score\":";
+            append_real(out, *r.score);
+            out += ',';
+        }
+        out += "\"tags\":[";
+        for (size_t i = 0; i < r.tags.size(); i++) {
+            out += (i > 0 ? ",\"" : "\"") + r.tags[i] + '"';
+        }
+        out += "],\"metadata\":{";
+        field("created_at", r.metadata.created_at);
+        field("updated_at", r.metadata.updated_at);
+        out += "\"version\":" + std::to_string(r.metadata.version) + "}}\n";
+    }
+    return out;
+}
+
+std::shared_ptr<arrow::Schema> schema() {
+    auto metadata = arrow::struct_({
+        arrow::field("created_at", arrow::utf8(), false),
+        arrow::field("updated_at", arrow::utf8(), false),
+        arrow::field("version", arrow::uint32(), false),
+    });
+    return arrow::schema({
+        arrow::field("id", arrow::uint32(), false),
+        arrow::field("name", arrow::utf8(), false),
+        arrow::field("email", arrow::utf8(), false),
+        arrow::field("age", arrow::uint32(), false),
+        arrow::field("balance", arrow::float64(), false),
+        arrow::field("is_active", arrow::boolean(), false),
+        arrow::field("nickname", arrow::utf8(), true),
+        arrow::field("phone", arrow::utf8(), true),
+        arrow::field("score", arrow::float64(), true),
+        arrow::field("tags", arrow::list(arrow::field("item", arrow::utf8(), true)), false),
+        arrow::field("metadata", metadata, false),
+    });
+}
+
+// JSON text to Arrow columns, against the explicit schema rather than an inferred one. The
+// reader runs on one thread, as arrow-json does
+std::shared_ptr<arrow::Table> decode(const std::string& ndjson, const std::shared_ptr<arrow::Schema>& schema) {
+    auto input = std::make_shared<arrow::io::BufferReader>(
+        std::make_shared<arrow::Buffer>(reinterpret_cast<const uint8_t*>(ndjson.data()), static_cast<int64_t>(ndjson.size())));
+    auto read_options = arrow::json::ReadOptions::Defaults();
+    read_options.use_threads = false;
+    auto parse_options = arrow::json::ParseOptions::Defaults();
+    parse_options.explicit_schema = schema;
+    parse_options.unexpected_field_behavior = arrow::json::UnexpectedFieldBehavior::Error;
+    auto reader = expect(arrow::json::TableReader::Make(arrow::default_memory_pool(), input, read_options, parse_options),
+                         "Failed to build the JSON reader");
+    return expect(reader->Read(), "Failed to decode JSON");
+}
+
+// Arrow columns to a Parquet file in memory, with dictionary encoding on (the default)
+std::shared_ptr<arrow::Buffer> encode(const arrow::Table& table, Codec codec) {
+    parquet::WriterProperties::Builder builder;
+    switch (codec) {
+        case Codec::Uncompressed: builder.compression(arrow::Compression::UNCOMPRESSED); break;
+        case Codec::Snappy: builder.compression(arrow::Compression::SNAPPY); break;
+        case Codec::Zstd: builder.compression(arrow::Compression::ZSTD)->compression_level(3); break;
+    }
+    auto sink = expect(arrow::io::BufferOutputStream::Create(), "Failed to create the output buffer");
+    expect(parquet::arrow::WriteTable(table, arrow::default_memory_pool(), sink, ROW_GROUP_ROWS, builder.build()),
+           "Failed to write the Parquet file");
+    return expect(sink->Finish(), "Failed to finish the file");
+}
+
+std::shared_ptr<arrow::Table> read_back(const std::shared_ptr<arrow::Buffer>& parquet) {
+    auto input = std::make_shared<arrow::io::BufferReader>(parquet);
+    parquet::ArrowReaderProperties properties;
+    properties.set_batch_size(BATCH_ROWS);
+    parquet::arrow::FileReaderBuilder builder;
+    expect(builder.Open(input), "Failed to open the Parquet file");
+    std::unique_ptr<parquet::arrow::FileReader> reader;
+    expect(builder.properties(properties)->Build(&reader), "Failed to build the Parquet reader");
+    std::shared_ptr<arrow::Table> table;
+    expect(reader->ReadTable(&table), "Failed to read the Parquet file");
+    return table;
+}
+
+// Folds every integer, flag, truncated real and string length, so the round trip can be checked
+// against the records it started from, and one language against the other
+struct Digest {
+    uint64_t rows = 0;
+    uint64_t sum = 0;
+
+    bool operator==(const Digest& o) const { return rows == o.rows && sum == o.sum; }
+};
+
+Digest digest_records(const std::vector<Record>& records) {
+    Digest digest;
+    for (const Record& r : records) {
+        digest.rows++;
+        digest.sum += uint64_t(r.id) + r.age + r.metadata.version + r.is_active;
+        digest.sum += static_cast<uint64_t>(r.balance) + (r.score ? static_cast<uint64_t>(*r.score) : 0);
+        uint64_t lengths = r.name.size() + r.email.size() + r.metadata.created_at.size() + r.metadata.updated_at.size();
+        if (r.nickname) lengths += r.nickname->size();
+        if (r.phone) lengths += r.phone->size();
+        for (const std::string& tag : r.tags) lengths += tag.size();
+        digest.sum += lengths;
+    }
+    return digest;
+}
+
+uint64_t sum_uint32(const arrow::Array& array) {
+    const auto& values = static_cast<const arrow::UInt32Array&>(array);
+    uint64_t sum = 0;
+    for (int64_t i = 0; i < values.length(); i++) sum += values.Value(i);
+    return sum;
+}
+
+uint64_t sum_truncated(const arrow::Array& array) {
+    const auto& values = static_cast<const arrow::DoubleArray&>(array);
+    uint64_t sum = 0;
+    for (int64_t i = 0; i < values.length(); i++) {
+        if (values.IsValid(i)) sum += static_cast<uint64_t>(values.Value(i));
+    }
+    return sum;
+}
+
+uint64_t sum_lengths(const arrow::Array& array) {
+    const auto& strings = static_cast<const arrow::StringArray&>(array);
+    uint64_t sum = 0;
+    for (int64_t i = 0; i < strings.length(); i++) {
+        if (strings.IsValid(i)) sum += strings.value_length(i);
+    }
+    return sum;
+}
+
+Digest digest_table(const arrow::Table& table) {
+    Digest digest;
+    digest.rows = table.num_rows();
+    auto chunks = [&](const char* name) { return table.GetColumnByName(name)->chunks(); };
+    auto metadata = chunks("metadata");
+    for (const auto& column : {chunks("id"), chunks("age")}) {
+        for (const auto& chunk : column) digest.sum += sum_uint32(*chunk);
+    }
+    for (const auto& chunk : chunks("is_active")) {
+        digest.sum += static_cast<const arrow::BooleanArray&>(*chunk).true_count();
+    }
+    for (const auto& column : {chunks("balance"), chunks("score")}) {
+        for (const auto& chunk : column) digest.sum += sum_truncated(*chunk);
+    }
+    for (const auto& column : {chunks("name"), chunks("email"), chunks("nickname"), chunks("phone")}) {
+        for (const auto& chunk : column) digest.sum += sum_lengths(*chunk);
+    }
+    for (const auto& chunk : chunks("tags")) {
+        digest.sum += sum_lengths(*static_cast<const arrow::ListArray&>(*chunk).values());
+    }
+    for (const auto& chunk : metadata) {
+        const auto& fields = static_cast<const arrow::StructArray&>(*chunk);
+        digest.sum += sum_uint32(*fields.GetFieldByName("version"));
+        digest.sum += sum_lengths(*fields.GetFieldByName("created_at"));
+        digest.sum += sum_lengths(*fields.GetFieldByName("updated_at"));
+    }
+    return digest;
+}
+
+const char* parse_flag(int argc, char** argv, const char* name) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], name) == 0) return i + 1 < argc ? argv[i + 1] : "";
+    }
+    return nullptr;
+}
+
+int main(int argc, char** argv) {
+    size_t count = DEFAULT_RECORDS;
+    if (const char* value = parse_flag(argc, argv, "--records")) {
+        long long n = std::atoll(value);
+        if (n <= 0) {
+            std::cerr << "--records expects a positive integer" << std::endl;
+            return 1;
+        }
+        count = static_cast<size_t>(n);
+    }
+    Codec codec = Codec::Snappy;
+    if (const char* value = parse_flag(argc, argv, "--compression")) {
+        auto parsed = parse_codec(value);
+        if (!parsed) {
+            std::cerr << "--compression expects none, snappy or zstd" << std::endl;
+            return 1;
+        }
+        codec = *parsed;
+    }
+    std::vector<Record> records = generate_records(count);
+    std::string ndjson = to_ndjson(records);
+    auto table_schema = schema();
+
+    // Warm-up
+    std::string warmup = to_ndjson(generate_records(WARMUP_RECORDS));
+    encode(*decode(warmup, table_schema), codec);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    auto table = decode(ndjson, table_schema);
+    std::chrono::duration<double> decode_time = std::chrono::high_resolution_clock::now() - start;
+    start = std::chrono::high_resolution_clock::now();
+    auto parquet = encode(*table, codec);
+    std::chrono::duration<double> encode_time = std::chrono::high_resolution_clock::now() - start;
+
+    // Read the file back and check it holds what was generated (not timed in the headline)
+    start = std::chrono::high_resolution_clock::now();
+    auto round_trip = read_back(parquet);
+    std::chrono::duration<double> read_time = std::chrono::high_resolution_clock::now() - start;
+    Digest expected = digest_records(records);
+    Digest actual = digest_table(*round_trip);
+    if (!(actual == expected)) {
+        fprintf(stderr, "Parquet round trip differs from the records: %llu rows, sum %llu, expected %llu rows, sum %llu\n",
+                static_cast<unsigned long long>(actual.rows), static_cast<unsigned long long>(actual.sum),
+                static_cast<unsigned long long>(expected.rows), static_cast<unsigned long long>(expected.sum));
+        return 1;
+    }
+
+    double total = decode_time.count() + encode_time.count();
+    double megabytes = ndjson.size() / 1e6;
+    auto rate = [&](double seconds) { return count / seconds / 1e6; };
+    std::cout << std::fixed << std::setprecision(6) << total << std::endl;
+    fprintf(stderr, "Input: %zu records, %.1f MB NDJSON\n", count, megabytes);
+    fprintf(stderr, "JSON decode (Arrow C++): %.6fs, %.2fM rows/s, %.1f MB/s\n", decode_time.count(),
+            rate(decode_time.count()), megabytes / decode_time.count());
+    fprintf(stderr, "Parquet encode (Arrow C++, %s): %.6fs, %.2fM rows/s\n", codec_name(codec), encode_time.count(),
+            rate(encode_time.count()));
+    fprintf(stderr, "Conversion: %.2fM rows/s end to end\n", rate(total));
+    fprintf(stderr, "Output: %lld bytes Parquet, %.1f%% of the JSON, %lld rows per row group\n",
+            static_cast<long long>(parquet->size()), 100.0 * parquet->size() / ndjson.size(),
+            static_cast<long long>(ROW_GROUP_ROWS));
+    fprintf(stderr, "Read back: %.6fs, %.2fM rows/s, %llu rows match the input\n", read_time.count(),
+            rate(read_time.count()), static_cast<unsigned long long>(actual.rows));
+    fprintf(stderr, "Checksum: %llu\n", static_cast<unsigned long long>(actual.sum));
+
+    return 0;
+}
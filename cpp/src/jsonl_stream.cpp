@@ -0,0 +1,282 @@
+#include <iostream>
+#include <fstream>
+#include <filesystem>
+#include <string>
+#include <string_view>
+#include <vector>
+#include <array>
+#include <charconv>
+#include <chrono>
+#include <iomanip>
+#include <cmath>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+#include <cstdint>
+
+#include "bench_data.h"
+#include "bench_measure.h"
+
+const uint64_t DEFAULT_MEGABYTES = 2048; // ~2GB stream
+const size_t IO_BUFFER_BYTES = 1 << 20;
+const size_t WARMUP_RECORDS = 100'000;
+const uint64_t SEED = 424242;
+// Bump with any change to generate_file's output, so cached streams are written again
+const uint32_t DATA_VERSION = 1;
+const uint64_t BASE_TIMESTAMP = 1'700'000'000'000ULL;
+const uint64_t NUM_USERS = 1'000'000;
+const uint64_t NUM_PAGES = 10'000;
+const char* EVENTS[5] = {"view", "click", "purchase", "signup", "logout"};
+const std::array<std::string_view, 16> COUNTRIES = {
+    "US", "DE", "FR", "GB", "JP", "BR", "IN", "CA", "AU", "ES", "IT", "NL", "SE", "KR", "MX", "PL",
+};
+
+// Only the fields the query needs; `user` and `page` are skipped by the parser
+struct Event {
+    uint64_t ts = 0;
+    std::string_view event;
+    double amount = 0.0;
+    std::string_view country;
+    bool ok = false;
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+uint64_t parse_megabytes(int argc, char** argv) {
+    for (int i = 1; i + 1 < argc; i++) {
+        if (std::strcmp(argv[i], "--megabytes") == 0) {
+            return std::stoull(argv[i + 1]);
+        }
+    }
+    return DEFAULT_MEGABYTES;
+}
+
+// Event log lines: {"ts":...,"user":"u123","event":"view","page":"/products/42","amount":12.34,"country":"DE","ok":true}
+uint64_t generate_file(const std::filesystem::path& path, uint64_t target_bytes) {
+    uint64_t seed = SEED;
+    std::FILE* out = std::fopen(path.c_str(), "wb");
+    if (!out) {
+        throw std::runtime_error("cannot create " + path.string());
+    }
+    std::vector<char> buffer(IO_BUFFER_BYTES);
+    std::setvbuf(out, buffer.data(), _IOFBF, buffer.size());
+    uint64_t written = 0;
+    char line[256];
+    for (uint64_t i = 0; written < target_bytes; i++) {
+        uint64_t ts = BASE_TIMESTAMP + i * 3 + next_random(seed) % 3;
+        uint64_t user = next_random(seed) % NUM_USERS;
+        uint64_t kind = next_random(seed) % 100;
+        const char* event = EVENTS[kind < 50 ? 0 : kind < 80 ? 1 : kind < 90 ? 2 : kind < 95 ? 3 : 4];
+        uint64_t page = next_random(seed) % NUM_PAGES;
+        uint64_t cents = next_random(seed) % 50'000;
+        std::string_view country = COUNTRIES[next_random(seed) % COUNTRIES.size()];
+        bool ok = next_random(seed) % 20 != 0;
+        int len = std::snprintf(line, sizeof(line),
+                                "{\"ts\":%llu,\"user\":\"u%llu\",\"event\":\"%s\",\"page\":\"/products/%llu\","
+                                "\"amount\":%llu.%02llu,\"country\":\"%.*s\",\"ok\":%s}\n",
+                                (unsigned long long)ts, (unsigned long long)user, event, (unsigned long long)page,
+                                (unsigned long long)(cents / 100), (unsigned long long)(cents % 100),
+                                (int)country.size(), country.data(), ok ? "true" : "false");
+        std::fwrite(line, 1, len, out);
+        written += len;
+    }
+    std::fclose(out);
+    return written;
+}
+
+// Minimal parser for one flat JSON object: string, number, and literal values; unknown keys are skipped.
+// Strings are returned as raw views into the line (no unescaping), like borrowed &str in serde.
+class LineParser {
+public:
+    explicit LineParser(const std::string& line) : p_(line.c_str()), end_(line.c_str() + line.size()) {}
+
+    bool parse(Event& e) {
+        unsigned seen = 0;
+        skip_ws();
+        if (!consume('{')) return false;
+        skip_ws();
+        if (consume('}')) return false;
+        while (true) {
+            std::string_view key;
+            if (!parse_string(key)) return false;
+            skip_ws();
+            if (!consume(':')) return false;
+            skip_ws();
+            if (key == "ts") {
+                auto [next, ec] = std::from_chars(p_, end_, e.ts);
+                if (ec != std::errc()) return false;
+                p_ = next;
+                seen |= 1;
+            } else if (key == "event") {
+                if (!parse_string(e.event)) return false;
+                seen |= 2;
+            } else if (key == "amount") {
+                if (!parse_number(e.amount)) return false;
+                seen |= 4;
+            } else if (key == "country") {
+                if (!parse_string(e.country)) return false;
+                seen |= 8;
+            } else if (key == "ok") {
+                if (!parse_bool(e.ok)) return false;
+                seen |= 16;
+            } else if (!skip_value()) {
+                return false;
+            }
+            skip_ws();
+            if (consume(',')) {
+                skip_ws();
+                continue;
+            }
+            return consume('}') && seen == 31;
+        }
+    }
+
+private:
+    void skip_ws() {
+        while (p_ < end_ && (*p_ == ' ' || *p_ == '\t' || *p_ == '\n' || *p_ == '\r')) p_++;
+    }
+
+    bool consume(char c) {
+        if (p_ < end_ && *p_ == c) {
+            p_++;
+            return true;
+        }
+        return false;
+    }
+
+    bool parse_string(std::string_view& out) {
+        if (!consume('"')) return false;
+        const char* start = p_;
+        while (p_ < end_ && *p_ != '"') {
+            p_ += (*p_ == '\\') ? 2 : 1;
+        }
+        if (p_ >= end_) return false;
+        out = std::string_view(start, p_ - start);
+        p_++;
+        return true;
+    }
+
+    bool parse_number(double& out) {
+        char* next;
+        out = std::strtod(p_, &next);
+        if (next == p_) return false;
+        p_ = next;
+        return true;
+    }
+
+    bool parse_bool(bool& out) {
+        if (end_ - p_ >= 4 && std::memcmp(p_, "true", 4) == 0) {
+            p_ += 4;
+            out = true;
+            return true;
+        }
+        if (end_ - p_ >= 5 && std::memcmp(p_, "false", 5) == 0) {
+            p_ += 5;
+            out = false;
+            return true;
+        }
+        return false;
+    }
+
+    bool skip_value() {
+        std::string_view s;
+        double d;
+        bool b;
+        if (p_ < end_ && *p_ == '"') return parse_string(s);
+        if (p_ < end_ && (*p_ == 't' || *p_ == 'f')) return parse_bool(b);
+        if (end_ - p_ >= 4 && std::memcmp(p_, "null", 4) == 0) {
+            p_ += 4;
+            return true;
+        }
+        return parse_number(d);
+    }
+
+    const char* p_;
+    const char* end_;
+};
+
+struct Aggregate {
+    uint64_t records = 0;
+    uint64_t bytes = 0;
+    uint64_t last_ts = 0;
+    std::array<uint64_t, 16> count_by_country{};
+    std::array<uint64_t, 16> cents_by_country{};
+};
+
+// SELECT country, COUNT(*), SUM(amount) WHERE event = 'purchase' AND ok GROUP BY country,
+// one line at a time through a reused buffer
+Aggregate process(const std::filesystem::path& path, size_t limit) {
+    std::vector<char> buffer(IO_BUFFER_BYTES);
+    std::ifstream in;
+    in.rdbuf()->pubsetbuf(buffer.data(), buffer.size());
+    in.open(path, std::ios::binary);
+    if (!in) {
+        throw std::runtime_error("cannot open " + path.string());
+    }
+    Aggregate agg;
+    std::string line;
+    Event event;
+    while (agg.records < limit && std::getline(in, line)) {
+        agg.bytes += line.size() + 1;
+        agg.records++;
+        if (!LineParser(line).parse(event)) {
+            throw std::runtime_error("malformed record");
+        }
+        agg.last_ts = std::max(agg.last_ts, event.ts);
+        if (event.event == "purchase" && event.ok) {
+            for (size_t c = 0; c < COUNTRIES.size(); c++) {
+                if (COUNTRIES[c] == event.country) {
+                    agg.count_by_country[c]++;
+                    agg.cents_by_country[c] += static_cast<uint64_t>(std::round(event.amount * 100.0));
+                    break;
+                }
+            }
+        }
+    }
+    return agg;
+}
+
+int main(int argc, char** argv) {
+    auto measure = bench_measure_from_env();
+    uint64_t megabytes = parse_megabytes(argc, argv);
+    // Generated by the first run in either language, then read by every run after it
+    Dataset dataset = bench_data_cached("jsonl_stream", megabytes << 20, SEED, DATA_VERSION,
+                                        [&](const std::filesystem::path& p) { generate_file(p, megabytes << 20); });
+    const std::filesystem::path& path = dataset.path;
+
+    // Warm-up
+    process(path, WARMUP_RECORDS);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    Aggregate agg = process(path, SIZE_MAX);
+    measure->stop();
+    auto end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> total_duration = end - start;
+
+    uint64_t purchases = 0, revenue = 0;
+    size_t top = 0;
+    uint64_t checksum = agg.last_ts;
+    for (size_t c = 0; c < COUNTRIES.size(); c++) {
+        purchases += agg.count_by_country[c];
+        revenue += agg.cents_by_country[c];
+        if (agg.cents_by_country[c] > agg.cents_by_country[top]) top = c;
+        checksum = checksum * 31 + agg.count_by_country[c] * 1'000'003 + agg.cents_by_country[c];
+    }
+
+    measure->report();
+    std::cerr << std::fixed << std::setprecision(2) << "Stream: " << dataset.bytes / 1e9 << " GB, " << agg.records
+              << " records, " << std::setprecision(1) << agg.bytes / 1e6 / total_duration.count()
+              << " MB/s sustained (" << std::setprecision(2) << agg.records / 1e6 / total_duration.count()
+              << "M records/s)" << std::endl;
+    std::cerr << "Purchases: " << purchases << ", revenue " << revenue / 100 << "." << std::setw(2)
+              << std::setfill('0') << revenue % 100 << ", top country " << COUNTRIES[top] << std::endl;
+    std::cerr << bench_data_describe(dataset) << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
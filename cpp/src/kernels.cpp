@@ -0,0 +1,263 @@
+// C++ halves of the kernels declared in bench_kernels.h. Linked into the ffi_kernels harness,
+// and compiled into the Rust crate by rust/build.rs so the Rust ffi_kernels binary can call them.
+#include "bench_kernels.h"
+
+#include <algorithm>
+#include <array>
+#include <bit>
+#include <cmath>
+#include <cstring>
+#include <utility>
+#include <vector>
+
+namespace {
+
+const double BASE_RE_SPAN = 3.5; // -2.5..1.0 on the real axis, as in mandelbrot at zoom 1
+const double CENTER_RE = -0.75;
+const size_t SEGMENT_SIZE = 32 * 1024; // odd numbers per sieve window, as in prime_sieve
+const size_t SORT_THRESHOLD = 10'000;  // subarrays this short go to std::sort
+
+uint32_t mandelbrot_point(double cx, double cy, uint32_t max_iter) {
+    double x = 0.0;
+    double y = 0.0;
+    uint32_t iteration = 0;
+
+    while (x * x + y * y <= 4.0 && iteration < max_iter) {
+        double xtemp = x * x - y * y + cx;
+        y = 2.0 * x * y + cy;
+        x = xtemp;
+        iteration++;
+    }
+
+    return iteration;
+}
+
+std::vector<uint64_t> small_odd_primes(uint64_t limit) {
+    std::vector<bool> is_prime(limit + 1, true);
+    std::vector<uint64_t> primes;
+    for (uint64_t i = 3; i <= limit; i += 2) {
+        if (is_prime[i]) {
+            primes.push_back(i);
+            for (uint64_t j = i * i; j <= limit; j += 2 * i) {
+                is_prime[j] = false;
+            }
+        }
+    }
+    return primes;
+}
+
+// Lomuto, last-element pivot, as in parallel_quicksort
+size_t partition(int32_t* arr, size_t len) {
+    size_t high = len - 1;
+    int32_t pivot = arr[high];
+    size_t i = 0;
+    for (size_t j = 0; j < high; j++) {
+        if (arr[j] <= pivot) {
+            std::swap(arr[i], arr[j]);
+            i++;
+        }
+    }
+    std::swap(arr[i], arr[high]);
+    return i;
+}
+
+void quicksort(int32_t* arr, size_t len, size_t depth) {
+    if (len <= SORT_THRESHOLD) {
+        std::sort(arr, arr + len);
+        return;
+    }
+    if (depth == 0) {
+        std::make_heap(arr, arr + len);
+        std::sort_heap(arr, arr + len);
+        return;
+    }
+    size_t pi = partition(arr, len);
+    quicksort(arr, pi, depth - 1);
+    quicksort(arr + pi + 1, len - pi - 1, depth - 1);
+}
+
+// The portable rounds from sha256.cpp
+class SHA256 {
+private:
+    uint32_t h[8];
+    uint8_t data[64];
+    uint32_t datalen;
+    uint64_t bitlen;
+    
+    static constexpr uint32_t k[64] = {
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
+    };
+    
+    static uint32_t rotr(uint32_t x, uint32_t n) {
+        return (x >> n) | (x << (32 - n));
+    }
+    
+    static uint32_t ch(uint32_t x, uint32_t y, uint32_t z) {
+        return (x & y) ^ (~x & z);
+    }
+    
+    static uint32_t maj(uint32_t x, uint32_t y, uint32_t z) {
+        return (x & y) ^ (x & z) ^ (y & z);
+    }
+    
+    static uint32_t sig0(uint32_t x) {
+        return rotr(x, 2) ^ rotr(x, 13) ^ rotr(x, 22);
+    }
+    
+    static uint32_t sig1(uint32_t x) {
+        return rotr(x, 6) ^ rotr(x, 11) ^ rotr(x, 25);
+    }
+    
+    void transform() {
+        uint32_t m[64];
+        uint32_t a, b, c, d, e, f, g, h_tmp, t1, t2;
+        
+        for (int i = 0, j = 0; i < 16; ++i, j += 4) {
+            m[i] = (data[j] << 24) | (data[j + 1] << 16) | (data[j + 2] << 8) | (data[j + 3]);
+        }
+        
+        for (int i = 16; i < 64; ++i) {
+            uint32_t s0 = rotr(m[i - 15], 7) ^ rotr(m[i - 15], 18) ^ (m[i - 15] >> 3);
+            uint32_t s1 = rotr(m[i - 2], 17) ^ rotr(m[i - 2], 19) ^ (m[i - 2] >> 10);
+            m[i] = m[i - 16] + s0 + m[i - 7] + s1;
+        }
+        
+        a = h[0]; b = h[1]; c = h[2]; d = h[3];
+        e = h[4]; f = h[5]; g = h[6]; h_tmp = h[7];
+        
+        for (int i = 0; i < 64; ++i) {
+            t1 = h_tmp + sig1(e) + ch(e, f, g) + k[i] + m[i];
+            t2 = sig0(a) + maj(a, b, c);
+            h_tmp = g; g = f; f = e; e = d + t1;
+            d = c; c = b; b = a; a = t1 + t2;
+        }
+        
+        h[0] += a; h[1] += b; h[2] += c; h[3] += d;
+        h[4] += e; h[5] += f; h[6] += g; h[7] += h_tmp;
+    }
+    
+public:
+    SHA256() {
+        h[0] = 0x6a09e667; h[1] = 0xbb67ae85; h[2] = 0x3c6ef372; h[3] = 0xa54ff53a;
+        h[4] = 0x510e527f; h[5] = 0x9b05688c; h[6] = 0x1f83d9ab; h[7] = 0x5be0cd19;
+        datalen = 0;
+        bitlen = 0;
+    }
+    
+    void update(const uint8_t* data_in, size_t len) {
+        for (size_t i = 0; i < len; ++i) {
+            data[datalen] = data_in[i];
+            datalen++;
+            if (datalen == 64) {
+                transform();
+                bitlen += 512;
+                datalen = 0;
+            }
+        }
+    }
+    
+    std::array<uint8_t, 32> finalize_bytes() {
+        uint32_t i = datalen;
+        
+        if (datalen < 56) {
+            data[i++] = 0x80;
+            while (i < 56) data[i++] = 0x00;
+        } else {
+            data[i++] = 0x80;
+            while (i < 64) data[i++] = 0x00;
+            transform();
+            std::memset(data, 0, 56);
+        }
+        
+        bitlen += datalen * 8;
+        data[63] = bitlen;
+        data[62] = bitlen >> 8;
+        data[61] = bitlen >> 16;
+        data[60] = bitlen >> 24;
+        data[59] = bitlen >> 32;
+        data[58] = bitlen >> 40;
+        data[57] = bitlen >> 48;
+        data[56] = bitlen >> 56;
+        transform();
+        
+        std::array<uint8_t, 32> digest;
+        for (int i = 0; i < 8; ++i) {
+            digest[4 * i] = h[i] >> 24;
+            digest[4 * i + 1] = h[i] >> 16;
+            digest[4 * i + 2] = h[i] >> 8;
+            digest[4 * i + 3] = h[i];
+        }
+        return digest;
+    }
+    
+};
+
+} // namespace
+
+extern "C" uint64_t cpp_mandelbrot(uint32_t width, uint32_t height, uint32_t max_iter) {
+    double scale = BASE_RE_SPAN / width;
+    double im_top = scale * height / 2.0;
+    uint64_t total = 0;
+    for (uint32_t py = 0; py < height; py++) {
+        double cy = im_top - py * scale;
+        for (uint32_t px = 0; px < width; px++) {
+            double cx = CENTER_RE - BASE_RE_SPAN / 2.0 + px * scale;
+            total += mandelbrot_point(cx, cy, max_iter);
+        }
+    }
+    return total;
+}
+
+extern "C" uint64_t cpp_count_primes(uint64_t limit) {
+    if (limit < 2) {
+        return 0;
+    }
+    uint64_t root = static_cast<uint64_t>(std::sqrt(static_cast<double>(limit)));
+    while (root * root > limit) root--;
+    while ((root + 1) * (root + 1) <= limit) root++;
+    std::vector<uint64_t> base_primes = small_odd_primes(root);
+    std::vector<uint64_t> next(base_primes.size());
+    for (size_t i = 0; i < base_primes.size(); i++) {
+        next[i] = base_primes[i] * base_primes[i];
+    }
+    std::vector<uint8_t> segment(SEGMENT_SIZE);
+
+    uint64_t count = 1; // 2
+    for (uint64_t seg_low = 3; seg_low <= limit;) {
+        uint64_t seg_high = std::min(seg_low + 2 * SEGMENT_SIZE, limit + 1);
+        size_t len = (seg_high - seg_low + 1) / 2;
+        std::fill(segment.begin(), segment.begin() + len, 0);
+        for (size_t i = 0; i < base_primes.size(); i++) {
+            uint64_t p = base_primes[i];
+            uint64_t j = next[i];
+            for (; j < seg_high; j += 2 * p) {
+                segment[(j - seg_low) / 2] = 1;
+            }
+            next[i] = j;
+        }
+        count += std::count(segment.begin(), segment.begin() + len, 0);
+        seg_low = seg_high;
+    }
+    return count;
+}
+
+extern "C" void cpp_sort_i32(int32_t* data, size_t len) {
+    if (len == 0) {
+        return;
+    }
+    quicksort(data, len, 2 * std::bit_width(len));
+}
+
+extern "C" void cpp_sha256(const uint8_t* data, size_t len, uint8_t* digest) {
+    SHA256 hasher;
+    hasher.update(data, len);
+    std::array<uint8_t, 32> result = hasher.finalize_bytes();
+    std::memcpy(digest, result.data(), result.size());
+}
@@ -0,0 +1,267 @@
+#include <iostream>
+#include <vector>
+#include <array>
+#include <unordered_map>
+#include <shared_mutex>
+#include <mutex>
+#include <thread>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cmath>
+#include <cstdint>
+
+const uint64_t RECORD_COUNT = 1 << 20;
+const size_t VALUE_WORDS = 16; // 128-byte records
+const unsigned SHARD_BITS = 6;
+const size_t NUM_SHARDS = 1 << SHARD_BITS;
+const size_t NUM_THREADS = 8;
+const double ZIPF_THETA = 0.99; // YCSB's default request skew
+const uint64_t MAX_SCAN_LENGTH = 100;
+
+using Value = std::array<uint64_t, VALUE_WORDS>;
+using Clock = std::chrono::high_resolution_clock;
+using Duration = std::chrono::duration<double>;
+
+struct Workload {
+    const char* name;
+    uint64_t read_percent;
+    uint64_t update_percent; // the remainder are scans
+    size_t ops_per_thread;
+};
+
+const Workload WORKLOADS[3] = {
+    {"A (update-heavy)", 50, 50, 250'000},
+    {"B (read-heavy)", 95, 5, 250'000},
+    {"E (scan)", 0, 5, 25'000},
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// Record contents depend only on the key and the phase that last wrote it, so the
+// final store is the same no matter how the client threads interleave
+Value make_value(uint64_t key, uint64_t version) {
+    uint64_t x = (key * 0x9E3779B97F4A7C15ULL) ^ version;
+    Value value;
+    for (auto& w : value) {
+        x = x * 6364136223846793005ULL + 1442695040888963407ULL;
+        w = x;
+    }
+    return value;
+}
+
+uint64_t fold_value(const Value& value) {
+    uint64_t acc = 0;
+    for (uint64_t w : value) {
+        acc = acc * 31 + w;
+    }
+    return acc;
+}
+
+// Padded so neighbouring shard locks don't share a cache line
+struct alignas(64) Shard {
+    mutable std::shared_mutex lock;
+    std::unordered_map<uint64_t, Value> map;
+};
+
+class Store {
+public:
+    Store() : shards_(NUM_SHARDS) {
+        for (auto& shard : shards_) {
+            shard.map.reserve(RECORD_COUNT / NUM_SHARDS);
+        }
+    }
+
+    bool read(uint64_t key, Value& out) const {
+        const Shard& shard = shard_for(key);
+        std::shared_lock guard(shard.lock);
+        auto it = shard.map.find(key);
+        if (it == shard.map.end()) {
+            return false;
+        }
+        out = it->second;
+        return true;
+    }
+
+    void write(uint64_t key, const Value& value) {
+        Shard& shard = shard_for(key);
+        std::unique_lock guard(shard.lock);
+        shard.map.insert_or_assign(key, value);
+    }
+
+    // Order-independent fold over every record
+    uint64_t checksum() const {
+        uint64_t acc = 0;
+        for (const auto& shard : shards_) {
+            std::shared_lock guard(shard.lock);
+            for (const auto& [key, value] : shard.map) {
+                acc += key ^ fold_value(value);
+            }
+        }
+        return acc;
+    }
+
+private:
+    Shard& shard_for(uint64_t key) {
+        return shards_[(key * 0x9E3779B97F4A7C15ULL) >> (64 - SHARD_BITS)];
+    }
+
+    const Shard& shard_for(uint64_t key) const {
+        return shards_[(key * 0x9E3779B97F4A7C15ULL) >> (64 - SHARD_BITS)];
+    }
+
+    std::vector<Shard> shards_;
+};
+
+// YCSB's ZipfianGenerator (Gray et al.), scrambled so the hottest keys land on different shards
+class Zipfian {
+public:
+    Zipfian(uint64_t n, double theta) {
+        zeta_n_ = 0.0;
+        for (uint64_t i = 1; i <= n; i++) {
+            zeta_n_ += 1.0 / std::pow(static_cast<double>(i), theta);
+        }
+        double zeta_2 = 1.0 + std::pow(0.5, theta);
+        alpha_ = 1.0 / (1.0 - theta);
+        eta_ = (1.0 - std::pow(2.0 / static_cast<double>(n), 1.0 - theta)) / (1.0 - zeta_2 / zeta_n_);
+        half_pow_theta_ = std::pow(0.5, theta);
+    }
+
+    uint64_t next_key(uint64_t& seed) const {
+        double u = static_cast<double>(next_random(seed)) / static_cast<double>(1ULL << 31);
+        double uz = u * zeta_n_;
+        uint64_t rank;
+        if (uz < 1.0) {
+            rank = 0;
+        } else if (uz < 1.0 + half_pow_theta_) {
+            rank = 1;
+        } else {
+            rank = std::min(static_cast<uint64_t>(static_cast<double>(RECORD_COUNT) * std::pow(eta_ * u - eta_ + 1.0, alpha_)),
+                            RECORD_COUNT - 1);
+        }
+        return (rank * 0xBF58476D1CE4E5B9ULL) & (RECORD_COUNT - 1);
+    }
+
+private:
+    double zeta_n_, alpha_, eta_, half_pow_theta_;
+};
+
+Duration load(Store& store) {
+    auto start = Clock::now();
+    std::vector<std::thread> threads;
+    uint64_t per_thread = RECORD_COUNT / NUM_THREADS;
+    for (uint64_t t = 0; t < NUM_THREADS; t++) {
+        uint64_t begin = t * per_thread;
+        uint64_t end = (t == NUM_THREADS - 1) ? RECORD_COUNT : (t + 1) * per_thread;
+        threads.emplace_back([&store, begin, end] {
+            for (uint64_t key = begin; key < end; key++) {
+                store.write(key, make_value(key, 0));
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+    return Clock::now() - start;
+}
+
+struct WorkloadResult {
+    Duration elapsed;
+    size_t ops;
+    std::vector<uint32_t> latencies_ns; // sorted
+};
+
+// Every client thread times each operation individually for the latency percentiles
+WorkloadResult run_workload(Store& store, const Zipfian& zipf, const Workload& workload, uint64_t version,
+                            size_t ops_per_thread) {
+    std::vector<std::vector<uint32_t>> per_thread(NUM_THREADS);
+    auto start = Clock::now();
+    std::vector<std::thread> threads;
+    for (uint64_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&, t] {
+            uint64_t seed = version * 1000 + t;
+            auto& latencies = per_thread[t];
+            latencies.reserve(ops_per_thread);
+            uint64_t read_sum = 0;
+            Value value;
+            for (size_t i = 0; i < ops_per_thread; i++) {
+                uint64_t op = next_random(seed) % 100;
+                uint64_t key = zipf.next_key(seed);
+                auto op_start = Clock::now();
+                if (op < workload.read_percent) {
+                    if (store.read(key, value)) {
+                        read_sum += fold_value(value);
+                    }
+                } else if (op < workload.read_percent + workload.update_percent) {
+                    store.write(key, make_value(key, version));
+                } else {
+                    // Hash-partitioned, so a scan is a multi-get over the next run of keys
+                    uint64_t length = 1 + next_random(seed) % MAX_SCAN_LENGTH;
+                    for (uint64_t k = key; k < key + length; k++) {
+                        if (store.read(k & (RECORD_COUNT - 1), value)) {
+                            read_sum += fold_value(value);
+                        }
+                    }
+                }
+                auto ns = std::chrono::duration_cast<std::chrono::nanoseconds>(Clock::now() - op_start).count();
+                latencies.push_back(static_cast<uint32_t>(std::min<int64_t>(ns, UINT32_MAX)));
+            }
+            volatile uint64_t sink = read_sum;
+            (void)sink;
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+    Duration elapsed = Clock::now() - start;
+
+    WorkloadResult result{elapsed, 0, {}};
+    for (auto& latencies : per_thread) {
+        result.latencies_ns.insert(result.latencies_ns.end(), latencies.begin(), latencies.end());
+    }
+    std::sort(result.latencies_ns.begin(), result.latencies_ns.end());
+    result.ops = result.latencies_ns.size();
+    return result;
+}
+
+double percentile_us(const std::vector<uint32_t>& sorted, size_t per_mille) {
+    return sorted[(sorted.size() - 1) * per_mille / 1000] / 1000.0;
+}
+
+int main() {
+    Zipfian zipf(RECORD_COUNT, ZIPF_THETA);
+    Store store;
+    Duration load_duration = load(store);
+
+    // Warm-up: rewrites load-phase values, so the store is unchanged
+    run_workload(store, zipf, WORKLOADS[1], 0, WORKLOADS[1].ops_per_thread / 100);
+
+    // Benchmark
+    std::vector<WorkloadResult> results;
+    for (size_t i = 0; i < 3; i++) {
+        results.push_back(run_workload(store, zipf, WORKLOADS[i], i + 1, WORKLOADS[i].ops_per_thread));
+    }
+
+    Duration total_duration = load_duration;
+    for (const auto& r : results) {
+        total_duration += r.elapsed;
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(0) << "Load: " << RECORD_COUNT << " records at "
+              << RECORD_COUNT / load_duration.count() << " inserts/s (" << NUM_SHARDS << " shards, " << NUM_THREADS
+              << " threads)" << std::endl;
+    for (size_t i = 0; i < 3; i++) {
+        const auto& r = results[i];
+        std::cerr << std::left << std::setw(18) << WORKLOADS[i].name << std::right << " " << std::setprecision(0)
+                  << r.ops / r.elapsed.count() << " ops/s, p50 " << std::setprecision(2)
+                  << percentile_us(r.latencies_ns, 500) << "us, p99 " << percentile_us(r.latencies_ns, 990)
+                  << "us, p99.9 " << percentile_us(r.latencies_ns, 999) << "us" << std::endl;
+    }
+    std::cerr << "Checksum: " << store.checksum() << std::endl;
+
+    return 0;
+}
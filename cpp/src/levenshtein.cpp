@@ -0,0 +1,163 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t NUM_PAIRS = 50'000;
+const size_t MIN_LEN = 16;
+const size_t MAX_LEN = 64; // Myers' algorithm keeps the whole pattern in one uint64_t
+const std::string ALPHABET = "abcdefgh";
+const uint64_t MAX_EDITS = 16;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// Pairs are a random string and a copy mutated by a handful of random edits
+std::vector<std::pair<std::string, std::string>> generate_pairs() {
+    uint64_t seed = 31337;
+    std::vector<std::pair<std::string, std::string>> pairs;
+    pairs.reserve(NUM_PAIRS);
+
+    for (size_t p = 0; p < NUM_PAIRS; p++) {
+        size_t len = MIN_LEN + next_random(seed) % (MAX_LEN - MIN_LEN + 1);
+        std::string a;
+        for (size_t i = 0; i < len; i++) {
+            a += ALPHABET[next_random(seed) % ALPHABET.size()];
+        }
+
+        std::string b = a;
+        uint64_t edits = next_random(seed) % (MAX_EDITS + 1);
+        for (uint64_t e = 0; e < edits; e++) {
+            size_t pos = next_random(seed) % b.size();
+            char c = ALPHABET[next_random(seed) % ALPHABET.size()];
+            uint64_t kind = next_random(seed) % 3;
+            if (kind == 1 && b.size() < MAX_LEN) {
+                b.insert(b.begin() + pos, c);
+            } else if (kind == 2 && b.size() > 1) {
+                b.erase(b.begin() + pos);
+            } else {
+                b[pos] = c;
+            }
+        }
+        pairs.emplace_back(std::move(a), std::move(b));
+    }
+
+    return pairs;
+}
+
+// Classic Wagner-Fischer dynamic programming with two rolling rows
+uint32_t levenshtein_dp(const std::string& a, const std::string& b,
+                        std::vector<uint32_t>& prev, std::vector<uint32_t>& curr) {
+    prev.resize(b.size() + 1);
+    curr.assign(b.size() + 1, 0);
+    for (size_t j = 0; j <= b.size(); j++) {
+        prev[j] = static_cast<uint32_t>(j);
+    }
+
+    for (size_t i = 0; i < a.size(); i++) {
+        curr[0] = static_cast<uint32_t>(i + 1);
+        for (size_t j = 0; j < b.size(); j++) {
+            uint32_t substitution = prev[j] + (a[i] != b[j]);
+            uint32_t deletion = prev[j + 1] + 1;
+            uint32_t insertion = curr[j] + 1;
+            curr[j + 1] = std::min({substitution, deletion, insertion});
+        }
+        std::swap(prev, curr);
+    }
+
+    return prev[b.size()];
+}
+
+// Myers/Hyyrö bit-parallel edit distance, pattern length <= 64
+uint32_t levenshtein_myers(const std::string& a, const std::string& b) {
+    if (a.empty()) {
+        return static_cast<uint32_t>(b.size());
+    }
+
+    uint64_t peq[256] = {0};
+    for (size_t i = 0; i < a.size(); i++) {
+        peq[static_cast<uint8_t>(a[i])] |= 1ULL << i;
+    }
+
+    uint64_t last = 1ULL << (a.size() - 1);
+    uint64_t pv = ~0ULL;
+    uint64_t mv = 0;
+    uint32_t score = static_cast<uint32_t>(a.size());
+
+    for (char c : b) {
+        uint64_t eq = peq[static_cast<uint8_t>(c)];
+        uint64_t xv = eq | mv;
+        uint64_t xh = (((eq & pv) + pv) ^ pv) | eq;
+        uint64_t ph = mv | ~(xh | pv);
+        uint64_t mh = pv & xh;
+        if (ph & last) score++;
+        if (mh & last) score--;
+        ph = (ph << 1) | 1;
+        mh <<= 1;
+        pv = mh | ~(xv | ph);
+        mv = ph & xv;
+    }
+
+    return score;
+}
+
+uint64_t run_dp(const std::vector<std::pair<std::string, std::string>>& pairs, size_t count) {
+    std::vector<uint32_t> prev, curr;
+    prev.reserve(MAX_LEN + 1);
+    curr.reserve(MAX_LEN + 1);
+    uint64_t sum = 0;
+    for (size_t i = 0; i < count; i++) {
+        sum += levenshtein_dp(pairs[i].first, pairs[i].second, prev, curr);
+    }
+    return sum;
+}
+
+uint64_t run_myers(const std::vector<std::pair<std::string, std::string>>& pairs, size_t count) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < count; i++) {
+        sum += levenshtein_myers(pairs[i].first, pairs[i].second);
+    }
+    return sum;
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    auto pairs = generate_pairs();
+
+    // Warm-up
+    volatile uint64_t warmup = run_dp(pairs, 1000) + run_myers(pairs, 1000);
+    (void)warmup;
+
+    // Benchmark dynamic programming
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    uint64_t dp_checksum = run_dp(pairs, NUM_PAIRS);
+    measure->stop();
+    auto dp_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark bit-parallel
+    auto myers_start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    uint64_t myers_checksum = run_myers(pairs, NUM_PAIRS);
+    measure->stop();
+    auto myers_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> dp_duration = dp_end - start;
+    std::chrono::duration<double> myers_duration = myers_end - myers_start;
+
+    measure->report();
+    std::cerr << std::fixed << "DP: " << dp_duration.count() << "s (" << std::setprecision(0)
+              << NUM_PAIRS / dp_duration.count() << " comparisons/s), Myers: " << std::setprecision(6)
+              << myers_duration.count() << "s (" << std::setprecision(0)
+              << NUM_PAIRS / myers_duration.count() << " comparisons/s)" << std::endl;
+    std::cerr << "Checksum: " << dp_checksum << " (Myers: " << myers_checksum << ")" << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,347 @@
+#include <iostream>
+#include <string>
+#include <string_view>
+#include <vector>
+#include <array>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdio>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t CORPUS_BYTES = 64 << 20; // 64MB of C-like source
+const size_t NUM_PASSES = 5;
+const uint64_t STATEMENTS_PER_FUNCTION = 12;
+const char* NAMES[10] = {"count", "buf", "node", "index", "value", "tmp", "ptr", "len", "key", "state"};
+const char* BINARY_OPS[10] = {"+", "-", "*", "/", "%", "<<", ">>", "&", "|", "^"};
+const char* COMPARISONS[6] = {"==", "!=", "<", "<=", ">", ">="};
+
+enum class TokenKind : uint8_t { Identifier, Keyword, Integer, Float, String, Char, Punct, Comment };
+
+const char* KIND_NAMES[8] = {"identifier", "keyword", "integer", "float", "string", "char", "punct", "comment"};
+
+struct Token {
+    TokenKind kind;
+    uint32_t start;
+    uint32_t len;
+};
+
+// Byte classes driving the lexer's start state
+enum : uint8_t { WS, IDENT, DIGIT, QUOTE, APOSTROPHE, SLASH, PUNCT, OTHER };
+
+constexpr std::array<uint8_t, 256> build_classes() {
+    std::array<uint8_t, 256> classes{};
+    for (int b = 0; b < 256; b++) {
+        char c = static_cast<char>(b);
+        uint8_t cls = OTHER;
+        if (c == ' ' || c == '\t' || c == '\n' || c == '\r') cls = WS;
+        else if ((c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c == '_') cls = IDENT;
+        else if (c >= '0' && c <= '9') cls = DIGIT;
+        else if (c == '"') cls = QUOTE;
+        else if (c == '\'') cls = APOSTROPHE;
+        else if (c == '/') cls = SLASH;
+        else if (std::string_view("+-*%=!<>&|^~?:;,.()[]{}#").find(c) != std::string_view::npos) cls = PUNCT;
+        classes[b] = cls;
+    }
+    return classes;
+}
+
+constexpr std::array<uint8_t, 256> CLASSES = build_classes();
+
+inline bool is_ident_continue(uint8_t b) {
+    return CLASSES[b] == IDENT || CLASSES[b] == DIGIT;
+}
+
+inline bool is_digit(uint8_t b) {
+    return b >= '0' && b <= '9';
+}
+
+inline bool is_hex_digit(uint8_t b) {
+    return is_digit(b) || ((b | 0x20) >= 'a' && (b | 0x20) <= 'f');
+}
+
+// Dispatch on length first, then compare, like a match over byte-string literals
+bool is_keyword(std::string_view s) {
+    switch (s.size()) {
+        case 2: return s == "if";
+        case 3: return s == "int" || s == "for";
+        case 4: return s == "char" || s == "else" || s == "long" || s == "void" || s == "case";
+        case 5: return s == "float" || s == "while" || s == "break" || s == "const";
+        case 6: return s == "double" || s == "return" || s == "struct" || s == "static" || s == "sizeof" || s == "switch";
+        case 7: return s == "default" || s == "typedef";
+        case 8: return s == "unsigned" || s == "continue";
+        default: return false;
+    }
+}
+
+// Longest punctuator starting at `i` (maximal munch)
+size_t punct_len(std::string_view src, size_t i) {
+    std::string_view rest = src.substr(i);
+    if (rest.size() >= 3) {
+        std::string_view three = rest.substr(0, 3);
+        if (three == "<<=" || three == ">>=" || three == "...") return 3;
+    }
+    if (rest.size() >= 2) {
+        static constexpr std::string_view TWO[] = {"==", "!=", "<=", ">=", "&&", "||", "++", "--", "+=", "-=",
+                                                   "*=", "/=", "%=", "&=", "|=", "^=", "<<", ">>", "->", "::"};
+        std::string_view two = rest.substr(0, 2);
+        for (auto p : TWO) {
+            if (two == p) return 2;
+        }
+    }
+    return 1;
+}
+
+size_t skip_quoted(std::string_view src, size_t i, char quote) {
+    i++;
+    while (i < src.size() && src[i] != quote) {
+        i += (src[i] == '\\') ? 2 : 1;
+    }
+    return std::min(i + 1, src.size());
+}
+
+void lex(std::string_view src, std::vector<Token>& tokens) {
+    tokens.clear();
+    const size_t n = src.size();
+    auto at = [&](size_t i) { return static_cast<uint8_t>(src[i]); };
+    size_t i = 0;
+    while (i < n) {
+        size_t start = i;
+        TokenKind kind;
+        switch (CLASSES[at(i)]) {
+            case WS:
+                i++;
+                while (i < n && CLASSES[at(i)] == WS) i++;
+                continue;
+            case IDENT:
+                i++;
+                while (i < n && is_ident_continue(at(i))) i++;
+                kind = is_keyword(src.substr(start, i - start)) ? TokenKind::Keyword : TokenKind::Identifier;
+                break;
+            case DIGIT:
+                if (src[i] == '0' && i + 1 < n && (at(i + 1) | 0x20) == 'x') {
+                    i += 2;
+                    while (i < n && is_hex_digit(at(i))) i++;
+                    kind = TokenKind::Integer;
+                } else {
+                    kind = TokenKind::Integer;
+                    while (i < n && is_digit(at(i))) i++;
+                    if (i + 1 < n && src[i] == '.' && is_digit(at(i + 1))) {
+                        kind = TokenKind::Float;
+                        i++;
+                        while (i < n && is_digit(at(i))) i++;
+                    }
+                    if (i < n && (at(i) | 0x20) == 'e') {
+                        size_t j = i + 1;
+                        if (j < n && (src[j] == '+' || src[j] == '-')) j++;
+                        if (j < n && is_digit(at(j))) {
+                            kind = TokenKind::Float;
+                            i = j;
+                            while (i < n && is_digit(at(i))) i++;
+                        }
+                    }
+                }
+                break;
+            case QUOTE:
+                i = skip_quoted(src, i, '"');
+                kind = TokenKind::String;
+                break;
+            case APOSTROPHE:
+                i = skip_quoted(src, i, '\'');
+                kind = TokenKind::Char;
+                break;
+            case SLASH:
+                if (i + 1 < n && src[i + 1] == '/') {
+                    while (i < n && src[i] != '\n') i++;
+                    kind = TokenKind::Comment;
+                    break;
+                }
+                if (i + 1 < n && src[i + 1] == '*') {
+                    i += 2;
+                    while (i + 1 < n && !(src[i] == '*' && src[i + 1] == '/')) i++;
+                    i = std::min(i + 2, n);
+                    kind = TokenKind::Comment;
+                    break;
+                }
+                [[fallthrough]];
+            case PUNCT:
+                i += punct_len(src, i);
+                kind = TokenKind::Punct;
+                break;
+            default:
+                // Stray bytes become single-byte punctuators rather than errors
+                i++;
+                kind = TokenKind::Punct;
+                break;
+        }
+        tokens.push_back({kind, static_cast<uint32_t>(start), static_cast<uint32_t>(i - start)});
+    }
+}
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+void push_name(std::string& out, uint64_t& seed) {
+    out += NAMES[next_random(seed) % 10];
+    out += '_';
+    out += std::to_string(next_random(seed) % 1000);
+}
+
+void push_number(std::string& out, uint64_t& seed) {
+    uint64_t value = next_random(seed) % 100'000;
+    if (next_random(seed) % 4 == 0) {
+        char hex[32];
+        std::snprintf(hex, sizeof(hex), "0x%llX", static_cast<unsigned long long>(value));
+        out += hex;
+    } else {
+        out += std::to_string(value);
+    }
+}
+
+template <size_t N>
+const char* pick(const char* (&items)[N], uint64_t& seed) {
+    return items[next_random(seed) % N];
+}
+
+// Functions full of declarations, arithmetic, branches, loops, calls, literals and comments
+std::string generate_corpus() {
+    uint64_t seed = 0xC0DE;
+    std::string out;
+    out.reserve(CORPUS_BYTES + 256);
+    uint64_t statement = 0;
+    while (out.size() < CORPUS_BYTES) {
+        if (statement % STATEMENTS_PER_FUNCTION == 0) {
+            if (statement > 0) {
+                out += "    return 0;\n}\n\n";
+            }
+            out += "static int ";
+            push_name(out, seed);
+            out += "(int ";
+            push_name(out, seed);
+            out += ", const char *";
+            push_name(out, seed);
+            out += ") {\n";
+        }
+        statement++;
+        out += "    ";
+        switch (next_random(seed) % 8) {
+            case 0:
+                out += "int ";
+                push_name(out, seed);
+                out += " = ";
+                push_number(out, seed);
+                out += ";\n";
+                break;
+            case 1:
+                push_name(out, seed);
+                out += " = ";
+                push_name(out, seed);
+                out += ' ';
+                out += pick(BINARY_OPS, seed);
+                out += ' ';
+                push_name(out, seed);
+                out += ";\n";
+                break;
+            case 2:
+                out += "if (";
+                push_name(out, seed);
+                out += ' ';
+                out += pick(COMPARISONS, seed);
+                out += ' ';
+                push_number(out, seed);
+                out += " && !";
+                push_name(out, seed);
+                out += ") { ";
+                push_name(out, seed);
+                out += " += 1; } else { ";
+                push_name(out, seed);
+                out += "--; }\n";
+                break;
+            case 3:
+                out += "// TODO: handle ";
+                push_name(out, seed);
+                out += " overflow when the buffer wraps\n";
+                break;
+            case 4:
+                out += "/* invariant: ";
+                push_name(out, seed);
+                out += " <= len * 2 */\n";
+                break;
+            case 5:
+                out += "printf(\"%s: \\\"%d\\\"\\n\", ";
+                push_name(out, seed);
+                out += ", ";
+                push_name(out, seed);
+                out += "->";
+                push_name(out, seed);
+                out += ");\n";
+                break;
+            case 6:
+                out += "double ";
+                push_name(out, seed);
+                out += " = ";
+                out += std::to_string(next_random(seed) % 1000);
+                out += '.';
+                out += std::to_string(next_random(seed) % 1000);
+                out += "e-";
+                out += std::to_string(next_random(seed) % 10);
+                out += ";\n";
+                break;
+            default:
+                out += "for (int i = 0; i < ";
+                push_number(out, seed);
+                out += "; i++) { ";
+                push_name(out, seed);
+                out += "[i] <<= '\\t'; }\n";
+                break;
+        }
+    }
+    out.resize(CORPUS_BYTES);
+    return out;
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    std::string corpus = generate_corpus();
+    std::vector<Token> tokens;
+    tokens.reserve(corpus.size() / 3);
+
+    // Warm-up
+    lex(std::string_view(corpus).substr(0, corpus.size() / 100), tokens);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    for (size_t pass = 0; pass < NUM_PASSES; pass++) {
+        lex(corpus, tokens);
+    }
+    measure->stop();
+    auto end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> total_duration = end - start;
+
+    size_t counts[8] = {};
+    uint64_t checksum = 0;
+    for (const auto& t : tokens) {
+        counts[static_cast<size_t>(t.kind)]++;
+        checksum = checksum * 31 + ((static_cast<uint64_t>(t.kind) << 32) | (static_cast<uint64_t>(t.start) ^ t.len));
+    }
+    double megabytes = static_cast<double>(corpus.size() * NUM_PASSES) / 1e6;
+
+    measure->report();
+    std::cerr << std::fixed << std::setprecision(1) << "Lexed " << megabytes << " MB in " << NUM_PASSES
+              << " passes: " << megabytes / total_duration.count() << " MB/s, "
+              << static_cast<double>(tokens.size() * NUM_PASSES) / 1e6 / total_duration.count() << "M tokens/s"
+              << std::endl;
+    std::cerr << "Tokens per pass: " << tokens.size() << " (";
+    for (size_t k = 0; k < 8; k++) {
+        std::cerr << (k ? ", " : "") << KIND_NAMES[k] << " " << counts[k];
+    }
+    std::cerr << ")" << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
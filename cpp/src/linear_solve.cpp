@@ -0,0 +1,339 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <atomic>
+#include <barrier>
+#include <chrono>
+#include <algorithm>
+#include <numeric>
+#include <cmath>
+#include <cstdio>
+#include <cstdint>
+#include <limits>
+
+#include "bench_measure.h"
+#include "bench_params.h"
+
+const size_t DEFAULT_SIZE = 2048; // BENCH_PARAMS size
+const size_t STRIP = 64; // columns of the inverse per task: a 64-wide row of it is one 512-byte stream
+const double RESIDUAL_LIMIT = 16.0; // HPL's pass threshold for the scaled residual
+// BENCH_PARAMS threads; set once in main, before the first parallel region
+size_t num_threads = 8;
+
+// Matrices are n x n, flat row-major: element (i, j) lives at i * n + j. Every multiply-add is an
+// explicit FMA and every sum runs in index order, so both languages pivot the same way and reach
+// the same solutions bit for bit
+using Matrix = std::vector<double>;
+
+// xorshift64, identical in the Rust version
+struct Rng {
+    uint64_t state;
+
+    // Uniform in [-1, 1) with 53 random bits
+    double next() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        return static_cast<double>(state >> 11) / static_cast<double>(uint64_t(1) << 52) - 1.0;
+    }
+};
+
+// Runs f(task) for task in [0, tasks) on num_threads threads pulling from a shared counter
+template <typename F>
+void parallel_for(size_t tasks, F f) {
+    std::atomic<size_t> next{0};
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < num_threads; t++) {
+        threads.emplace_back([&]() {
+            for (size_t task = next++; task < tasks; task = next++) {
+                f(task);
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+}
+
+// For k in [0, n): serial(k) on one thread, then row(k, i) for every trailing row i > k, dealt
+// round-robin. One team of num_threads lives through all n steps, meeting at a barrier around
+// each, rather than starting a new team per step as parallel_for would
+template <typename Serial, typename Row>
+void factor_steps(size_t n, Serial serial, Row row) {
+    std::barrier sync(static_cast<std::ptrdiff_t>(num_threads));
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < num_threads; t++) {
+        threads.emplace_back([&, t]() {
+            for (size_t k = 0; k < n; k++) {
+                if (t == 0) serial(k);
+                sync.arrive_and_wait();
+                for (size_t i = k + 1 + t; i < n; i += num_threads) {
+                    row(k, i);
+                }
+                sync.arrive_and_wait();
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+}
+
+// Uniform random entries; such matrices are nonsingular with overwhelming probability but need pivoting
+Matrix random_matrix(size_t n, uint64_t seed) {
+    Rng rng{seed};
+    Matrix a(n * n);
+    for (double& x : a) x = rng.next();
+    return a;
+}
+
+// Symmetric random off-diagonal entries with n on the diagonal: strictly diagonally dominant, so
+// positive definite
+Matrix spd_matrix(size_t n, uint64_t seed) {
+    Rng rng{seed};
+    Matrix a(n * n, 0.0);
+    for (size_t i = 0; i < n; i++) {
+        a[i * n + i] = static_cast<double>(n);
+        for (size_t j = i + 1; j < n; j++) {
+            double value = rng.next();
+            a[i * n + j] = value;
+            a[j * n + i] = value;
+        }
+    }
+    return a;
+}
+
+// The solution every system is built to have
+std::vector<double> known_solution(size_t n) {
+    std::vector<double> x(n);
+    for (size_t i = 0; i < n; i++) x[i] = 1.0 + static_cast<double>(i % 7);
+    return x;
+}
+
+std::vector<double> mat_vec(const Matrix& a, size_t n, const std::vector<double>& x) {
+    std::vector<double> y(n);
+    for (size_t i = 0; i < n; i++) {
+        double s = 0.0;
+        for (size_t j = 0; j < n; j++) s = std::fma(a[i * n + j], x[j], s);
+        y[i] = s;
+    }
+    return y;
+}
+
+double max_abs(const std::vector<double>& v) {
+    double m = 0.0;
+    for (double x : v) m = std::max(m, std::abs(x));
+    return m;
+}
+
+// HPL's ||Ax - b|| / (eps * (||A|| * ||x|| + ||b||) * n) in the infinity norm; a backward-stable
+// solve keeps it of order one whatever the matrix's condition
+double scaled_residual(const Matrix& a, size_t n, const std::vector<double>& x, const std::vector<double>& b) {
+    std::vector<double> residual = mat_vec(a, n, x);
+    for (size_t i = 0; i < n; i++) residual[i] -= b[i];
+    double norm_a = 0.0;
+    for (size_t i = 0; i < n; i++) {
+        double sum = 0.0;
+        for (size_t j = 0; j < n; j++) sum += std::abs(a[i * n + j]);
+        norm_a = std::max(norm_a, sum);
+    }
+    double eps = std::numeric_limits<double>::epsilon();
+    return max_abs(residual) / (eps * (norm_a * max_abs(x) + max_abs(b)) * static_cast<double>(n));
+}
+
+// PA = LU in place, right-looking: L's unit diagonal is implied below U. Returns the row each step
+// swapped in; the trailing rows update in parallel once the pivot row is fixed
+std::vector<size_t> lu_factor(Matrix& a, size_t n) {
+    std::vector<size_t> pivots(n);
+    factor_steps(
+        n,
+        [&](size_t k) {
+            // Partial pivoting: the first largest magnitude on or below the diagonal
+            size_t p = k;
+            for (size_t i = k + 1; i < n; i++) {
+                if (std::abs(a[i * n + k]) > std::abs(a[p * n + k])) p = i;
+            }
+            pivots[k] = p;
+            if (p != k) std::swap_ranges(&a[k * n], &a[(k + 1) * n], &a[p * n]);
+        },
+        [&](size_t k, size_t i) {
+            const double* row_k = &a[k * n];
+            double* row = &a[i * n];
+            double l = row[k] / row_k[k];
+            row[k] = l;
+            for (size_t j = k + 1; j < n; j++) row[j] = std::fma(-l, row_k[j], row[j]);
+        });
+    return pivots;
+}
+
+// x from U x = y, with U the upper triangle of u
+std::vector<double> back_substitute(const Matrix& u, size_t n, std::vector<double> y) {
+    for (size_t i = n; i-- > 0;) {
+        const double* row = &u[i * n];
+        double s = y[i];
+        for (size_t j = i + 1; j < n; j++) s = std::fma(-row[j], y[j], s);
+        y[i] = s / row[i];
+    }
+    return y;
+}
+
+std::vector<double> lu_solve(const Matrix& lu, size_t n, const std::vector<size_t>& pivots, const std::vector<double>& b) {
+    std::vector<double> y = b;
+    for (size_t k = 0; k < n; k++) std::swap(y[k], y[pivots[k]]);
+    for (size_t i = 0; i < n; i++) {
+        const double* row = &lu[i * n];
+        double s = y[i];
+        for (size_t j = 0; j < i; j++) s = std::fma(-row[j], y[j], s);
+        y[i] = s;
+    }
+    return back_substitute(lu, n, std::move(y));
+}
+
+// A = U^T U in place in the upper triangle (the lower one is left stale): each step finishes row k
+// of U, then subtracts its outer product from the trailing upper triangle, rows in parallel
+void cholesky_factor(Matrix& a, size_t n) {
+    factor_steps(
+        n,
+        [&](size_t k) {
+            double* row_k = &a[k * n];
+            double d = std::sqrt(row_k[k]);
+            row_k[k] = d;
+            for (size_t j = k + 1; j < n; j++) row_k[j] /= d;
+        },
+        [&](size_t k, size_t i) {
+            const double* row_k = &a[k * n];
+            double* row = &a[i * n];
+            double u_ki = row_k[i];
+            for (size_t j = i; j < n; j++) row[j] = std::fma(-u_ki, row_k[j], row[j]);
+        });
+}
+
+std::vector<double> cholesky_solve(const Matrix& u, size_t n, const std::vector<double>& b) {
+    // U^T y = b by columns of U^T, which are rows of U
+    std::vector<double> y = b;
+    for (size_t k = 0; k < n; k++) {
+        const double* row = &u[k * n];
+        y[k] /= row[k];
+        for (size_t i = k + 1; i < n; i++) y[i] = std::fma(-row[i], y[k], y[i]);
+    }
+    return back_substitute(u, n, std::move(y));
+}
+
+// A^-1 = U^-1 L^-1 P, solved for the columns of P in strips of STRIP in parallel: 2n^3 flops, as
+// the triangular solves do not skip the identity's zeros
+Matrix lu_inverse(const Matrix& lu, size_t n, const std::vector<size_t>& pivots) {
+    // Row i of P is the unit row e_perm[i], where perm replays the factorization's swaps on 0..n
+    std::vector<size_t> perm(n);
+    std::iota(perm.begin(), perm.end(), 0);
+    for (size_t k = 0; k < n; k++) std::swap(perm[k], perm[pivots[k]]);
+
+    Matrix inverse(n * n);
+    parallel_for((n + STRIP - 1) / STRIP, [&](size_t s) {
+        size_t j0 = s * STRIP, w = std::min(STRIP, n - j0);
+        // The strip's columns, stored n x w row-major
+        std::vector<double> x(n * w, 0.0);
+        for (size_t i = 0; i < n; i++) {
+            if (perm[i] >= j0 && perm[i] < j0 + w) x[i * w + perm[i] - j0] = 1.0;
+        }
+        // L Y = P, L unit lower triangular
+        for (size_t i = 0; i < n; i++) {
+            double* row = &x[i * w];
+            for (size_t k = 0; k < i; k++) {
+                double l = lu[i * n + k];
+                const double* y = &x[k * w];
+                for (size_t j = 0; j < w; j++) row[j] = std::fma(-l, y[j], row[j]);
+            }
+        }
+        // U X = Y
+        for (size_t i = n; i-- > 0;) {
+            double* row = &x[i * w];
+            for (size_t k = i + 1; k < n; k++) {
+                double u = lu[i * n + k];
+                const double* y = &x[k * w];
+                for (size_t j = 0; j < w; j++) row[j] = std::fma(-u, y[j], row[j]);
+            }
+            double d = lu[i * n + i];
+            for (size_t j = 0; j < w; j++) row[j] /= d;
+        }
+        for (size_t i = 0; i < n; i++) {
+            std::copy(&x[i * w], &x[i * w] + w, &inverse[i * n + j0]);
+        }
+    });
+    return inverse;
+}
+
+struct Phase {
+    const char* name;
+    double seconds;
+    double flops;
+    double residual;
+    double error;
+};
+
+int main() {
+    auto measure = bench_measure_from_env();
+    BenchParams params = BenchParams::from_env();
+    const size_t n = params.size("size", DEFAULT_SIZE);
+    num_threads = params.size("threads", std::max(1u, std::thread::hardware_concurrency()));
+    params.check();
+
+    Matrix a = random_matrix(n, 0x9E3779B97F4A7C15ull);
+    Matrix spd = spd_matrix(n, 0xD1B54A32D192ED03ull);
+    std::vector<double> x_true = known_solution(n);
+    std::vector<double> b = mat_vec(a, n, x_true);
+    std::vector<double> b_spd = mat_vec(spd, n, x_true);
+    double nf = static_cast<double>(n);
+
+    // Benchmark: each phase factors a fresh copy made outside the timed region
+    Matrix lu = a;
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    std::vector<size_t> pivots = lu_factor(lu, n);
+    std::vector<double> x_lu = lu_solve(lu, n, pivots, b);
+    measure->stop();
+    std::chrono::duration<double> lu_time = std::chrono::high_resolution_clock::now() - start;
+
+    Matrix u = spd;
+    start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    cholesky_factor(u, n);
+    std::vector<double> x_cholesky = cholesky_solve(u, n, b_spd);
+    measure->stop();
+    std::chrono::duration<double> cholesky_time = std::chrono::high_resolution_clock::now() - start;
+
+    start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    Matrix inverse = lu_inverse(lu, n, pivots);
+    measure->stop();
+    std::chrono::duration<double> inverse_time = std::chrono::high_resolution_clock::now() - start;
+    std::vector<double> x_inverse = mat_vec(inverse, n, b);
+
+    auto max_error = [&](const std::vector<double>& x) {
+        double m = 0.0;
+        for (size_t i = 0; i < n; i++) m = std::max(m, std::abs(x[i] - x_true[i]));
+        return m;
+    };
+    Phase phases[] = {
+        {"LU (partial pivoting)", lu_time.count(), 2.0 / 3.0 * nf * nf * nf, scaled_residual(a, n, x_lu, b), max_error(x_lu)},
+        {"Cholesky", cholesky_time.count(), nf * nf * nf / 3.0, scaled_residual(spd, n, x_cholesky, b_spd), max_error(x_cholesky)},
+        {"Inverse from LU, x = A^-1 b", inverse_time.count(), 2.0 * nf * nf * nf, scaled_residual(a, n, x_inverse, b), max_error(x_inverse)},
+    };
+
+    measure->report();
+    for (const Phase& phase : phases) {
+        fprintf(stderr, "%s: %.3fs, %.2f GFLOP/s, scaled residual %.4f, max error %.3e\n",
+                phase.name, phase.seconds, phase.flops / phase.seconds / 1e9, phase.residual, phase.error);
+        if (!(phase.residual < RESIDUAL_LIMIT)) {
+            fprintf(stderr, "%s: scaled residual %g is over %g\n", phase.name, phase.residual, RESIDUAL_LIMIT);
+            return 1;
+        }
+    }
+    std::cerr << params.report() << std::endl;
+    double checksum = 0.0;
+    for (const auto* x : {&x_lu, &x_cholesky, &x_inverse}) {
+        for (double value : *x) checksum += value;
+    }
+    fprintf(stderr, "Checksum: %.6f\n", checksum);
+
+    return 0;
+}
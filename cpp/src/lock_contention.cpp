@@ -0,0 +1,224 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <thread>
+#include <atomic>
+#include <mutex>
+#include <shared_mutex>
+#include <barrier>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#ifdef HAVE_ABSL
+#include <absl/synchronization/mutex.h>
+#endif
+
+const size_t THREAD_COUNTS[] = {1, 2, 4, 8};
+const size_t OPS = 5'000'000; // per configuration, split between the threads
+const size_t SLOTS = 16;
+const uint32_t SPINS_BEFORE_YIELD = 100;
+
+struct Mix {
+    const char* name;
+    uint64_t read_percent;
+};
+
+const Mix MIXES[] = {
+    {"read-heavy", 95},
+    {"write-heavy", 25},
+};
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t splitmix64(uint64_t& seed) {
+    seed += 0x9E3779B97F4A7C15ULL;
+    uint64_t z = seed;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+// Two cache lines of counters. A read sums them all; a write adds to two. Additions commute,
+// so the final state is the same under any interleaving
+struct State {
+    uint64_t slots[SLOTS] = {};
+
+    uint64_t sum() const {
+        uint64_t acc = 0;
+        for (uint64_t s : slots) acc += s;
+        return acc;
+    }
+
+    void update(uint64_t r) {
+        size_t k = (r >> 8) % SLOTS;
+        slots[k] += r >> 32;
+        slots[(k + 5) % SLOTS] += 1;
+    }
+
+    uint64_t digest() const {
+        uint64_t acc = 0;
+        for (uint64_t s : slots) acc = acc * 31 + s;
+        return acc;
+    }
+};
+
+// Exclusive locks take reads exclusively too
+struct StdMutex {
+    std::mutex mutex;
+    State state;
+    template <typename F> auto read(F f) { std::lock_guard guard(mutex); return f(state); }
+    template <typename F> void write(F f) { std::lock_guard guard(mutex); f(state); }
+};
+
+struct SharedMutex {
+    std::shared_mutex mutex;
+    State state;
+    template <typename F> auto read(F f) { std::shared_lock guard(mutex); return f(state); }
+    template <typename F> void write(F f) { std::unique_lock guard(mutex); f(state); }
+};
+
+#ifdef HAVE_ABSL
+// Abseil's reader-writer mutex, taken shared for reads
+struct AbslMutex {
+    absl::Mutex mutex;
+    State state;
+    template <typename F> auto read(F f) { absl::ReaderMutexLock guard(&mutex); return f(state); }
+    template <typename F> void write(F f) { absl::MutexLock guard(&mutex); f(state); }
+};
+#endif
+
+// Test-and-test-and-set: waiters spin on a plain load so the line stays shared until the holder
+// releases it, then yield so a preempted holder can run
+struct SpinLock {
+    std::atomic<bool> locked{false};
+    State state;
+
+    void lock() {
+        uint32_t spins = 0;
+        while (locked.exchange(true, std::memory_order_acquire)) {
+            while (locked.load(std::memory_order_relaxed)) {
+                if (spins < SPINS_BEFORE_YIELD) {
+                    spins++;
+#if defined(__aarch64__)
+                    asm volatile("isb");
+#elif defined(__x86_64__)
+                    __builtin_ia32_pause();
+#endif
+                } else {
+                    std::this_thread::yield();
+                }
+            }
+        }
+    }
+    void unlock() { locked.store(false, std::memory_order_release); }
+
+    template <typename F> auto read(F f) { std::lock_guard guard(*this); return f(state); }
+    template <typename F> void write(F f) { std::lock_guard guard(*this); f(state); }
+};
+
+struct Run {
+    Duration elapsed;
+    double fairness; // first thread to finish over last: 1.0 when every thread finishes together
+    uint64_t digest;
+};
+
+// Every thread does its share of the operations from its own seed; finish times after a common
+// start show whether the lock let some threads through ahead of others
+template <typename L>
+Run run(size_t threads, size_t ops, uint64_t read_percent) {
+    L lock;
+    std::barrier barrier(static_cast<std::ptrdiff_t>(threads));
+    std::vector<Duration> finishes(threads);
+    auto start = Clock::now();
+    std::vector<std::thread> workers;
+    for (uint64_t t = 0; t < threads; t++) {
+        workers.emplace_back([&, t] {
+            uint64_t seed = 0x10CC + t;
+            uint64_t reads = 0;
+            barrier.arrive_and_wait();
+            auto begin = Clock::now();
+            for (size_t i = 0; i < ops / threads; i++) {
+                uint64_t r = splitmix64(seed);
+                if (r % 100 < read_percent) {
+                    reads += lock.read([](const State& s) { return s.sum(); });
+                } else {
+                    lock.write([r](State& s) { s.update(r); });
+                }
+            }
+            asm volatile("" : : "r"(reads));
+            finishes[t] = Clock::now() - begin;
+        });
+    }
+    for (auto& w : workers) w.join();
+    Duration elapsed = Clock::now() - start;
+    auto [first, last] = std::minmax_element(finishes.begin(), finishes.end());
+    return {elapsed, first->count() / last->count(), lock.state.digest()};
+}
+
+struct NamedVariant {
+    const char* name;
+    Run (*run)(size_t, size_t, uint64_t);
+};
+
+const NamedVariant VARIANTS[] = {
+    {"std::mutex", run<StdMutex>},
+    {"spinlock", run<SpinLock>},
+    {"std::shared_mutex", run<SharedMutex>},
+#ifdef HAVE_ABSL
+    {"absl::Mutex", run<AbslMutex>},
+#endif
+};
+const size_t NUM_VARIANTS = sizeof(VARIANTS) / sizeof(VARIANTS[0]);
+const size_t NUM_COUNTS = sizeof(THREAD_COUNTS) / sizeof(THREAD_COUNTS[0]);
+
+int main() {
+    // Warm-up
+    for (const auto& v : VARIANTS) v.run(THREAD_COUNTS[NUM_COUNTS - 1], OPS / 100, MIXES[0].read_percent);
+
+    // Benchmark: runs[mix][variant][thread count]
+    std::vector<std::vector<std::vector<Run>>> runs;
+    Duration total_duration(0);
+    for (const Mix& mix : MIXES) {
+        auto& by_variant = runs.emplace_back();
+        for (const auto& v : VARIANTS) {
+            auto& by_threads = by_variant.emplace_back();
+            for (size_t t : THREAD_COUNTS) {
+                by_threads.push_back(v.run(t, OPS, mix.read_percent));
+                total_duration += by_threads.back().elapsed;
+            }
+        }
+    }
+    bool all_match = true;
+    uint64_t checksum = 0;
+    for (const auto& by_variant : runs) {
+        for (const auto& by_threads : by_variant) {
+            for (size_t c = 0; c < NUM_COUNTS; c++) all_match &= by_threads[c].digest == by_variant[0][c].digest;
+        }
+        for (const Run& r : by_variant[0]) checksum += r.digest;
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "%zu operations per configuration over %zu counters; Mops/s and fairness (first/last thread to finish)\n",
+            OPS, SLOTS);
+    for (size_t m = 0; m < runs.size(); m++) {
+        fprintf(stderr, "%s (%llu%% reads):\n", MIXES[m].name, static_cast<unsigned long long>(MIXES[m].read_percent));
+        for (size_t v = 0; v < NUM_VARIANTS; v++) {
+            fprintf(stderr, "  %-18s", VARIANTS[v].name);
+            for (size_t c = 0; c < NUM_COUNTS; c++) {
+                const Run& r = runs[m][v][c];
+                fprintf(stderr, "%s%zut %6.1f %.2f", c == 0 ? " " : "  ", THREAD_COUNTS[c], OPS / r.elapsed.count() / 1e6,
+                        r.fairness);
+            }
+            fprintf(stderr, "\n");
+        }
+    }
+#ifndef HAVE_ABSL
+    fprintf(stderr, "  (absl::Mutex not built: Abseil was not found)\n");
+#endif
+    std::cerr << "Checksum: " << checksum << " (all locks match: " << (all_match ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,409 @@
+#include <iostream>
+#include <string>
+#include <string_view>
+#include <optional>
+#include <algorithm>
+#include <array>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+
+const size_t DEFAULT_MEGABYTES = 256;
+const size_t WARMUP_BYTES = 4 << 20;
+const uint64_t BASE_MILLIS = 1'700'000'000'000ULL; // 2023-11-14T22:13:20Z
+const uint64_t MALFORMED_EVERY = 1000; // about one line in a thousand is broken in one of three ways
+const std::array<const char*, 8> SERVICES = {"auth", "billing", "gateway", "search", "storage", "queue", "mailer", "cache"};
+const std::array<const char*, 6> RESOURCES = {"users", "orders", "items", "carts", "sessions", "invoices"};
+const std::array<uint16_t, 8> STATUSES = {200, 200, 200, 201, 204, 404, 500, 503};
+const std::array<const char*, 16> WORDS = {
+    "request", "completed", "upstream", "timeout", "cache", "miss", "retry", "after", "token", "expired", "user",
+    "session", "queued", "payload", "rejected", "ok",
+};
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+int64_t floor_div(int64_t a, int64_t b) {
+    return a / b - (a % b != 0 && (a < 0) != (b < 0));
+}
+
+// Days since 1970-01-01 to (year, month, day) and back, from Howard Hinnant's algorithms
+void civil_from_days(int64_t days, int64_t& year, uint32_t& month, uint32_t& day) {
+    int64_t z = days + 719'468;
+    int64_t era = floor_div(z, 146'097);
+    int64_t doe = z - era * 146'097;
+    int64_t yoe = (doe - doe / 1460 + doe / 36'524 - doe / 146'096) / 365;
+    int64_t doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    int64_t mp = (5 * doy + 2) / 153;
+    day = static_cast<uint32_t>(doy - (153 * mp + 2) / 5 + 1);
+    month = static_cast<uint32_t>(mp < 10 ? mp + 3 : mp - 9);
+    year = yoe + era * 400 + (month <= 2);
+}
+
+int64_t days_from_civil(int64_t year, uint32_t month, uint32_t day) {
+    if (month <= 2) year -= 1;
+    int64_t era = floor_div(year, 400);
+    int64_t yoe = year - era * 400;
+    int64_t mp = month > 2 ? month - 3 : month + 9;
+    int64_t doy = (153 * mp + 2) / 5 + day - 1;
+    return era * 146'097 + yoe * 365 + yoe / 4 - yoe / 100 + doy - 719'468;
+}
+
+void append(std::string& out, const char* format, auto... args) {
+    char buf[128];
+    int n = std::snprintf(buf, sizeof(buf), format, args...);
+    out.append(buf, n);
+}
+
+// Lines look like
+//   2023-11-14T22:13:20.042Z WARN [billing] GET /api/v2/orders/1234 latency_us=18234 status=503 bytes=5120 req=1a2b3c4d5e6f7081 user=42 msg="upstream \"db\" timeout"
+// with the key=value fields rotated into a different order per line and user= on most lines
+std::string generate_log(size_t target_bytes) {
+    uint64_t seed = 20240601;
+    uint64_t millis = BASE_MILLIS;
+    std::string out;
+    out.reserve(target_bytes + 512);
+    uint64_t line = 0;
+    while (out.size() < target_bytes) {
+        line++;
+        millis += next_random(seed) % 50;
+        int64_t date = static_cast<int64_t>(millis / 86'400'000);
+        uint64_t time = millis % 86'400'000;
+        int64_t year;
+        uint32_t month, day;
+        civil_from_days(date, year, month, day);
+        uint64_t broken = line % MALFORMED_EVERY == 0 ? 1 + next_random(seed) % 3 : 0;
+        if (broken == 1) month = 13;
+        append(out, "%04lld-%02u-%02uT%02llu:%02llu:%02llu.%03lluZ ", static_cast<long long>(year), month, day,
+               static_cast<unsigned long long>(time / 3'600'000), static_cast<unsigned long long>(time / 60'000 % 60),
+               static_cast<unsigned long long>(time / 1000 % 60), static_cast<unsigned long long>(time % 1000));
+
+        uint64_t level = next_random(seed) % 100;
+        out += level < 10 ? "DEBUG" : level < 80 ? "INFO" : level < 95 ? "WARN" : "ERROR";
+        out += " [";
+        out += SERVICES[next_random(seed) % SERVICES.size()];
+        out += "] ";
+        uint64_t method = next_random(seed) % 20;
+        out += method < 12 ? "GET" : method < 17 ? "POST" : method < 19 ? "PUT" : "DELETE";
+        uint64_t version = 1 + next_random(seed) % 3;
+        const char* resource = RESOURCES[next_random(seed) % RESOURCES.size()];
+        uint64_t id = next_random(seed) % 100'000;
+        append(out, " /api/v%llu/%s/%llu", static_cast<unsigned long long>(version), resource,
+               static_cast<unsigned long long>(id));
+
+        uint16_t status = STATUSES[next_random(seed) % STATUSES.size()];
+        std::array<std::string, 4> fields;
+        fields[0] = broken == 2 ? "status=5x3" : "status=" + std::to_string(status);
+        fields[1] = "latency_us=" + std::to_string(next_random(seed) % 200'000);
+        fields[2] = "bytes=" + std::to_string(next_random(seed) % 65'536);
+        uint64_t high = next_random(seed) << 33;
+        uint64_t req = high ^ next_random(seed);
+        append(fields[3], "req=%016llx", static_cast<unsigned long long>(req));
+        std::rotate(fields.begin(), fields.begin() + next_random(seed) % 4, fields.end());
+        for (const auto& field : fields) {
+            out += ' ';
+            out += field;
+        }
+        if (next_random(seed) % 10 < 7) {
+            append(out, " user=%llu", static_cast<unsigned long long>(next_random(seed) % 1'000'000));
+        }
+
+        out += " msg=\"";
+        uint64_t words = 2 + next_random(seed) % 8;
+        for (uint64_t w = 0; w < words; w++) {
+            if (w > 0) out += ' ';
+            const char* word = WORDS[next_random(seed) % WORDS.size()];
+            switch (next_random(seed) % 20) {
+                case 0: append(out, "\\\"%s\\\"", word); break;
+                case 1: append(out, "C:\\\\%s", word); break;
+                default: out += word;
+            }
+        }
+        if (broken != 3) out += '"';
+        out += '\n';
+    }
+    return out;
+}
+
+enum Level { DEBUG, INFO, WARN, ERROR };
+enum Method { GET, POST, PUT, DELETE };
+
+// One parsed line; strings view into the log, and the message keeps its escapes
+struct Record {
+    uint64_t millis;
+    Level level;
+    std::string_view service;
+    Method method;
+    std::string_view path;
+    uint16_t status;
+    uint32_t latency_us;
+    uint64_t bytes;
+    uint64_t req;
+    std::optional<uint32_t> user;
+    std::string_view message;
+};
+
+std::optional<uint64_t> timestamp(uint64_t millis, uint64_t year, uint64_t month, uint64_t day, uint64_t hour,
+                                  uint64_t minute, uint64_t second) {
+    if (month < 1 || month > 12 || day < 1 || day > 31 || hour > 23 || minute > 59 || second > 59) {
+        return std::nullopt;
+    }
+    int64_t days = days_from_civil(static_cast<int64_t>(year), static_cast<uint32_t>(month), static_cast<uint32_t>(day));
+    return static_cast<uint64_t>(days) * 86'400'000 + ((hour * 60 + minute) * 60 + second) * 1000 + millis;
+}
+
+// A hand-written cursor over the line, the same code as the Rust hand-written variant
+struct Cursor {
+    std::string_view s;
+    size_t pos = 0;
+
+    bool at_end() const { return pos >= s.size(); }
+
+    bool byte(char b) {
+        if (at_end() || s[pos] != b) return false;
+        pos++;
+        return true;
+    }
+
+    bool literal(std::string_view lit) {
+        if (s.size() - pos < lit.size() || std::memcmp(s.data() + pos, lit.data(), lit.size()) != 0) return false;
+        pos += lit.size();
+        return true;
+    }
+
+    bool digits(size_t n, uint64_t& v) {
+        if (pos + n > s.size()) return false;
+        v = 0;
+        for (size_t i = pos; i < pos + n; i++) {
+            if (s[i] < '0' || s[i] > '9') return false;
+            v = v * 10 + static_cast<uint64_t>(s[i] - '0');
+        }
+        pos += n;
+        return true;
+    }
+
+    // One or more decimal digits, rejecting values above max
+    bool number(uint64_t max, uint64_t& v) {
+        size_t start = pos;
+        v = 0;
+        while (!at_end() && s[pos] >= '0' && s[pos] <= '9') {
+            if (__builtin_mul_overflow(v, 10, &v) || __builtin_add_overflow(v, static_cast<uint64_t>(s[pos] - '0'), &v)) {
+                return false;
+            }
+            pos++;
+        }
+        return pos > start && v <= max;
+    }
+
+    bool hex(uint64_t& v) {
+        size_t start = pos;
+        v = 0;
+        while (!at_end()) {
+            char c = s[pos];
+            uint64_t d;
+            if (c >= '0' && c <= '9') d = c - '0';
+            else if (c >= 'a' && c <= 'f') d = c - 'a' + 10;
+            else if (c >= 'A' && c <= 'F') d = c - 'A' + 10;
+            else break;
+            if (__builtin_mul_overflow(v, 16, &v)) return false;
+            v |= d;
+            pos++;
+        }
+        return pos > start;
+    }
+
+    template <typename Keep>
+    std::string_view take_while(Keep keep) {
+        size_t start = pos;
+        while (!at_end() && keep(s[pos])) pos++;
+        return s.substr(start, pos - start);
+    }
+};
+
+std::optional<Record> parse(std::string_view line) {
+    Cursor c{line};
+    Record r{};
+    uint64_t year, month, day, hour, minute, second, millis;
+    if (!(c.digits(4, year) && c.byte('-') && c.digits(2, month) && c.byte('-') && c.digits(2, day) && c.byte('T') &&
+          c.digits(2, hour) && c.byte(':') && c.digits(2, minute) && c.byte(':') && c.digits(2, second) &&
+          c.byte('.') && c.digits(3, millis) && c.byte('Z'))) {
+        return std::nullopt;
+    }
+    auto t = timestamp(millis, year, month, day, hour, minute, second);
+    if (!t) return std::nullopt;
+    r.millis = *t;
+
+    if (!c.byte(' ')) return std::nullopt;
+    if (c.literal("DEBUG")) r.level = DEBUG;
+    else if (c.literal("INFO")) r.level = INFO;
+    else if (c.literal("WARN")) r.level = WARN;
+    else if (c.literal("ERROR")) r.level = ERROR;
+    else return std::nullopt;
+    if (!c.literal(" [")) return std::nullopt;
+    r.service = c.take_while([](char b) { return b >= 'a' && b <= 'z'; });
+    if (r.service.empty() || !c.literal("] ")) return std::nullopt;
+    if (c.literal("GET")) r.method = GET;
+    else if (c.literal("POST")) r.method = POST;
+    else if (c.literal("PUT")) r.method = PUT;
+    else if (c.literal("DELETE")) r.method = DELETE;
+    else return std::nullopt;
+    if (!c.byte(' ')) return std::nullopt;
+    r.path = c.take_while([](char b) { return b != ' '; });
+    if (r.path.empty()) return std::nullopt;
+
+    // The key=value fields in any order: all but user= are required, and a repeated key keeps its last value
+    std::optional<uint64_t> status, latency_us, bytes, req, user;
+    size_t count = 0;
+    while (true) {
+        size_t at = c.pos;
+        if (!c.byte(' ')) break;
+        uint64_t v;
+        bool ok;
+        if (c.literal("status=")) { ok = c.number(UINT16_MAX, v); if (ok) status = v; }
+        else if (c.literal("latency_us=")) { ok = c.number(UINT32_MAX, v); if (ok) latency_us = v; }
+        else if (c.literal("bytes=")) { ok = c.number(UINT64_MAX, v); if (ok) bytes = v; }
+        else if (c.literal("req=")) { ok = c.hex(v); if (ok) req = v; }
+        else if (c.literal("user=")) { ok = c.number(UINT32_MAX, v); if (ok) user = v; }
+        else ok = false;
+        if (!ok) {
+            c.pos = at;
+            break;
+        }
+        count++;
+    }
+    if (count == 0 || !c.literal(" msg=\"")) return std::nullopt;
+    size_t start = c.pos;
+    while (true) {
+        if (c.at_end()) return std::nullopt;
+        char b = line[c.pos];
+        if (b == '"') break;
+        if (b == '\\') {
+            c.pos++;
+            if (c.at_end() || (line[c.pos] != '"' && line[c.pos] != '\\')) return std::nullopt;
+        }
+        c.pos++;
+    }
+    r.message = line.substr(start, c.pos - start);
+    if (c.pos + 1 != line.size()) return std::nullopt;
+    if (!status || !latency_us || !bytes || !req) return std::nullopt;
+    r.status = static_cast<uint16_t>(*status);
+    r.latency_us = static_cast<uint32_t>(*latency_us);
+    r.bytes = *bytes;
+    r.req = *req;
+    if (user) r.user = static_cast<uint32_t>(*user);
+    return r;
+}
+
+// Length of a message once each \" and \\ escape collapses to one character
+size_t unescaped_len(std::string_view message) {
+    size_t len = 0;
+    for (size_t i = 0; i < message.size(); i++) {
+        if (message[i] == '\\') i++;
+        len++;
+    }
+    return len;
+}
+
+// What the benchmark keeps from each line, folded into the checksum
+struct Stats {
+    uint64_t lines = 0;
+    uint64_t rejected = 0;
+    std::array<uint64_t, 4> levels{};
+    std::array<uint64_t, 4> methods{};
+    uint64_t server_errors = 0;
+    uint64_t latency_us = 0;
+    uint64_t bytes = 0;
+    uint64_t millis = 0;
+    uint64_t req = 0;
+    uint64_t users = 0;
+    uint64_t text = 0; // service, path and unescaped message characters
+
+    void add(const std::optional<Record>& record) {
+        lines++;
+        if (!record) {
+            rejected++;
+            return;
+        }
+        const Record& r = *record;
+        levels[r.level]++;
+        methods[r.method]++;
+        server_errors += r.status >= 500;
+        latency_us += r.latency_us;
+        bytes += r.bytes;
+        millis += r.millis;
+        req ^= r.req;
+        users += r.user ? *r.user + 1ULL : 0;
+        text += r.service.size() + r.path.size() + unescaped_len(r.message);
+    }
+
+    uint64_t checksum() const {
+        uint64_t h = 0;
+        for (uint64_t v : {lines, rejected, server_errors, latency_us, bytes, millis, req, users, text}) h = h * 31 + v;
+        for (uint64_t v : levels) h = h * 31 + v;
+        for (uint64_t v : methods) h = h * 31 + v;
+        return h;
+    }
+};
+
+Stats run(std::string_view log) {
+    Stats stats;
+    size_t start = 0;
+    while (start < log.size()) {
+        size_t end = log.find('\n', start);
+        if (end == std::string_view::npos) end = log.size();
+        stats.add(parse(log.substr(start, end - start)));
+        start = end + 1;
+    }
+    return stats;
+}
+
+size_t parse_megabytes(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--megabytes") == 0) {
+            long long n = i + 1 < argc ? std::atoll(argv[i + 1]) : 0;
+            if (n <= 0) {
+                std::cerr << "--megabytes expects a positive integer" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<size_t>(n);
+        }
+    }
+    return DEFAULT_MEGABYTES;
+}
+
+int main(int argc, char** argv) {
+    size_t megabytes = parse_megabytes(argc, argv);
+    std::string log = generate_log(megabytes << 20);
+
+    // Warm-up
+    std::string_view view = log;
+    size_t warmup_end = view.substr(0, std::min(WARMUP_BYTES, log.size())).rfind('\n');
+    Stats warmup = run(view.substr(0, warmup_end == std::string_view::npos ? 0 : warmup_end + 1));
+    (void)warmup;
+
+    // Benchmark
+    auto start = Clock::now();
+    Stats stats = run(view);
+    Duration duration = Clock::now() - start;
+
+    double mb = log.size() / 1e6;
+    std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(1) << "Log: " << mb << " MB, " << stats.lines << " lines, "
+              << stats.rejected << " malformed and rejected" << std::endl;
+    std::cerr << "Hand-written: " << std::setprecision(6) << duration.count() << "s, " << std::setprecision(0)
+              << mb / duration.count() << " MB/s, " << std::setprecision(2) << stats.lines / duration.count() / 1e6
+              << "M lines/s" << std::endl;
+    std::cerr << "Levels: DEBUG " << stats.levels[DEBUG] << ", INFO " << stats.levels[INFO] << ", WARN "
+              << stats.levels[WARN] << ", ERROR " << stats.levels[ERROR] << "; " << stats.server_errors
+              << " server errors, " << stats.bytes << " bytes served" << std::endl;
+    std::cerr << "Checksum: " << stats.checksum() << std::endl;
+    return 0;
+}
@@ -1,13 +1,68 @@
 #include <iostream>
 #include <vector>
+#include <string>
 #include <thread>
+#include <atomic>
 #include <chrono>
 #include <iomanip>
+#include <algorithm>
+#include <cmath>
+#include <cstdio>
+#include <cstring>
+#include <cstdlib>
+#include <cstdint>
+#include "bench_measure.h"
+#include "cpu_features.h"
+#ifdef HAVE_PNG
+#include <png.h>
+#endif
 
 const size_t WIDTH = 4096;
 const size_t HEIGHT = 4096;
 const uint32_t MAX_ITER = 1000;
 const size_t NUM_THREADS = 8;
+const size_t LANES = 4;
+const double DEFAULT_CENTER_RE = -0.75;
+const double DEFAULT_CENTER_IM = 0.0;
+const double BASE_RE_SPAN = 3.5; // zoom 1 spans -2.5..1.0 on the real axis
+const size_t BANDS = 8; // contiguous row bands for the load-imbalance estimate
+// Deep zoom: a point near the seahorse-valley spiral, deep enough that neighbouring pixels
+// are closer together than double can resolve around the center
+const char* DEEP_CENTER_RE = "-0.743643887037158704752191506114774";
+const char* DEEP_CENTER_IM = "0.131825904205311970493132056385139";
+const double DEEP_ZOOM = 1e20;
+const size_t DEEP_SIZE = 512;
+const uint32_t DEEP_MAX_ITER = 20'000;
+const size_t SPOT_CHECK_GRID = 8; // 8x8 pixels re-iterated directly in double-double
+
+// GCC/Clang vector extensions: portable explicit SIMD that lowers to NEON, SSE or AVX
+typedef double f64x4 __attribute__((vector_size(LANES * sizeof(double))));
+typedef int64_t i64x4 __attribute__((vector_size(LANES * sizeof(int64_t))));
+
+enum class Variant { Scalar, Simd, Deep };
+
+// Image size and the rectangle of the complex plane it samples
+struct View {
+    size_t width;
+    size_t height;
+    double min_re;
+    double max_re;
+    double min_im;
+    double max_im;
+
+    double re(size_t x) const {
+        return min_re + (static_cast<double>(x) / width) * (max_re - min_re);
+    }
+
+    double im(size_t y) const {
+        return min_im + (static_cast<double>(y) / height) * (max_im - min_im);
+    }
+};
+
+// Scalar operands of vector arithmetic are broadcast to every lane
+inline f64x4 splat(double v) {
+    return f64x4{} + v;
+}
 
 uint32_t mandelbrot_point(double cx, double cy) {
     double x = 0.0;
@@ -24,55 +79,512 @@ uint32_t mandelbrot_point(double cx, double cy) {
     return iteration;
 }
 
-void compute_section(std::vector<uint32_t>& result, size_t start_row, size_t end_row) {
-    const double min_re = -2.5;
-    const double max_re = 1.0;
-    const double min_im = -1.0;
-    const double max_im = 1.0;
-    
+// LANES adjacent pixels at once; lanes that escape are masked out of the count
+// while the rest keep iterating, and the loop ends once every lane has escaped
+void mandelbrot_lanes(f64x4 cx, double cy_scalar, uint32_t* out) {
+    f64x4 cy = splat(cy_scalar);
+    f64x4 four = splat(4.0);
+    f64x4 x = {};
+    f64x4 y = {};
+    i64x4 counts = {};
+    i64x4 active = x == x;
+
+    for (uint32_t i = 0; i < MAX_ITER; i++) {
+        active &= (x * x + y * y <= four);
+        if (!(active[0] | active[1] | active[2] | active[3])) break;
+        counts -= active; // true lanes are all ones, i.e. -1
+        f64x4 xtemp = x * x - y * y + cx;
+        y = 2.0 * x * y + cy;
+        x = xtemp;
+    }
+
+    for (size_t lane = 0; lane < LANES; lane++) {
+        out[lane] = static_cast<uint32_t>(counts[lane]);
+    }
+}
+
+void compute_section(std::vector<uint32_t>& result, const View& view, size_t start_row, size_t end_row, Variant variant) {
+    for (size_t y = start_row; y < end_row; y++) {
+        if (variant == Variant::Simd) {
+            double cy = view.im(y);
+            for (size_t x = 0; x < view.width; x += LANES) {
+                f64x4 cx;
+                for (size_t lane = 0; lane < LANES; lane++) {
+                    cx[lane] = view.re(x + lane);
+                }
+                mandelbrot_lanes(cx, cy, &result[y * view.width + x]);
+            }
+            continue;
+        }
+        for (size_t x = 0; x < view.width; x++) {
+            result[y * view.width + x] = mandelbrot_point(view.re(x), view.im(y));
+        }
+    }
+}
+
+// Unevaluated sum hi + lo with |lo| <= ulp(hi) / 2, about 106 bits of mantissa. Every
+// product goes through an explicit std::fma so both languages round identically
+struct DoubleDouble {
+    double hi;
+    double lo;
+};
+
+DoubleDouble two_sum(double a, double b) {
+    double s = a + b;
+    double bb = s - a;
+    return {s, (a - (s - bb)) + (b - bb)};
+}
+
+DoubleDouble quick_two_sum(double a, double b) {
+    double s = a + b;
+    return {s, b - (s - a)};
+}
+
+DoubleDouble two_prod(double a, double b) {
+    double p = a * b;
+    return {p, std::fma(a, b, -p)};
+}
+
+DoubleDouble dd_neg(DoubleDouble x) {
+    return {-x.hi, -x.lo};
+}
+
+DoubleDouble dd_add(DoubleDouble x, DoubleDouble y) {
+    DoubleDouble s = two_sum(x.hi, y.hi);
+    DoubleDouble t = two_sum(x.lo, y.lo);
+    DoubleDouble hi = quick_two_sum(s.hi, s.lo + t.hi);
+    return quick_two_sum(hi.hi, hi.lo + t.lo);
+}
+
+DoubleDouble dd_sub(DoubleDouble x, DoubleDouble y) {
+    return dd_add(x, dd_neg(y));
+}
+
+DoubleDouble dd_mul(DoubleDouble x, DoubleDouble y) {
+    DoubleDouble p = two_prod(x.hi, y.hi);
+    double lo = std::fma(x.hi, y.lo, std::fma(x.lo, y.hi, p.lo));
+    return quick_two_sum(p.hi, lo);
+}
+
+DoubleDouble dd_mul(DoubleDouble x, double b) {
+    DoubleDouble p = two_prod(x.hi, b);
+    return quick_two_sum(p.hi, std::fma(x.lo, b, p.lo));
+}
+
+DoubleDouble dd_div(DoubleDouble x, double b) {
+    double q1 = x.hi / b;
+    DoubleDouble r = dd_sub(x, two_prod(q1, b));
+    double q2 = r.hi / b;
+    DoubleDouble q = quick_two_sum(q1, q2);
+    r = dd_sub(r, two_prod(q2, b));
+    return quick_two_sum(q.hi, q.lo + r.hi / b);
+}
+
+// Digits are accumulated exactly enough in double-double, then scaled by the fraction length
+bool dd_parse(const char* text, DoubleDouble& out) {
+    bool negative = *text == '-';
+    if (negative) text++;
+    DoubleDouble value = {0.0, 0.0};
+    int fraction_digits = 0;
+    bool seen_point = false;
+    for (; *text; text++) {
+        if (*text == '.' && !seen_point) {
+            seen_point = true;
+        } else if (*text >= '0' && *text <= '9') {
+            value = dd_add(dd_mul(value, 10.0), DoubleDouble{static_cast<double>(*text - '0'), 0.0});
+            if (seen_point) fraction_digits++;
+        } else {
+            return false;
+        }
+    }
+    for (int i = 0; i < fraction_digits; i++) value = dd_div(value, 10.0);
+    out = negative ? dd_neg(value) : value;
+    return true;
+}
+
+// Deep-zoom view: the center needs double-double, but offsets from it fit in a double
+struct DeepView {
+    size_t width;
+    size_t height;
+    DoubleDouble center_re;
+    DoubleDouble center_im;
+    double zoom;
+    double re_span;
+    double im_span;
+
+    std::pair<double, double> delta(size_t x, size_t y) const {
+        return {(static_cast<double>(x) / width - 0.5) * re_span, (static_cast<double>(y) / height - 0.5) * im_span};
+    }
+};
+
+struct OrbitPoint {
+    double re;
+    double im;
+};
+
+// The center's orbit Z_n in double-double, rounded to double for the perturbation loop.
+// Stops at the first escaped value, which the per-pixel loop never steps from
+std::vector<OrbitPoint> reference_orbit(const DeepView& view) {
+    DoubleDouble zr = {0.0, 0.0}, zi = {0.0, 0.0};
+    std::vector<OrbitPoint> orbit;
+    orbit.reserve(DEEP_MAX_ITER + 1);
+    while (true) {
+        orbit.push_back({zr.hi, zi.hi});
+        if (std::fma(zr.hi, zr.hi, zi.hi * zi.hi) > 4.0 || orbit.size() > DEEP_MAX_ITER) return orbit;
+        DoubleDouble zr_next = dd_add(dd_sub(dd_mul(zr, zr), dd_mul(zi, zi)), view.center_re);
+        zi = dd_add(dd_mul(dd_mul(zr, zi), 2.0), view.center_im);
+        zr = zr_next;
+    }
+}
+
+// Perturbation: with z = Z + d and c = C + dc, d_{n+1} = (2 Z_n + d_n) d_n + dc, all in double.
+// When |z| drops below |d|, or the reference orbit runs out, the pixel rebases onto the
+// start of the orbit with d = z (Zhuoran's glitch avoidance), so one reference serves every pixel
+uint32_t perturbed_point(const std::vector<OrbitPoint>& orbit, double dcr, double dci, uint64_t& rebases) {
+    double dr = 0.0, di = 0.0;
+    size_t reference = 0;
+    uint32_t iteration = 0;
+    while (iteration < DEEP_MAX_ITER) {
+        double zr = orbit[reference].re, zi = orbit[reference].im;
+        double fr = zr + dr, fi = zi + di;
+        double magnitude = std::fma(fr, fr, fi * fi);
+        if (magnitude > 4.0) break;
+        if (magnitude < std::fma(dr, dr, di * di) || reference == orbit.size() - 1) {
+            dr = fr;
+            di = fi;
+            reference = 0;
+            rebases++;
+            zr = orbit[0].re;
+            zi = orbit[0].im;
+        }
+        double ar = zr + zr + dr, ai = zi + zi + di;
+        double dr_next = std::fma(ar, dr, std::fma(-ai, di, dcr));
+        di = std::fma(ar, di, std::fma(ai, dr, dci));
+        dr = dr_next;
+        reference++;
+        iteration++;
+    }
+    return iteration;
+}
+
+void compute_deep_section(std::vector<uint32_t>& result, const DeepView& view, const std::vector<OrbitPoint>& orbit,
+                          size_t start_row, size_t end_row, uint64_t& rebases) {
     for (size_t y = start_row; y < end_row; y++) {
-        for (size_t x = 0; x < WIDTH; x++) {
-            double cx = min_re + (static_cast<double>(x) / WIDTH) * (max_re - min_re);
-            double cy = min_im + (static_cast<double>(y) / HEIGHT) * (max_im - min_im);
-            result[y * WIDTH + x] = mandelbrot_point(cx, cy);
+        for (size_t x = 0; x < view.width; x++) {
+            auto [dcr, dci] = view.delta(x, y);
+            result[y * view.width + x] = perturbed_point(orbit, dcr, dci, rebases);
         }
     }
 }
 
-int main() {
-    std::vector<uint32_t> result(WIDTH * HEIGHT);
+// Rows are dealt out one at a time from a shared counter, since deep-zoom rows vary widely in cost
+uint64_t compute_deep(std::vector<uint32_t>& result, const DeepView& view, const std::vector<OrbitPoint>& orbit) {
+    std::atomic<size_t> next_row{0};
+    std::vector<uint64_t> rebases(NUM_THREADS, 0);
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&, t]() {
+            for (size_t y = next_row++; y < view.height; y = next_row++) {
+                compute_deep_section(result, view, orbit, y, y + 1, rebases[t]);
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+    uint64_t total = 0;
+    for (uint64_t r : rebases) total += r;
+    return total;
+}
+
+// Direct double-double iteration of one pixel, the slow reference the perturbation must match
+uint32_t direct_point(const DeepView& view, size_t x, size_t y) {
+    auto [dcr, dci] = view.delta(x, y);
+    DoubleDouble cr = dd_add(view.center_re, DoubleDouble{dcr, 0.0});
+    DoubleDouble ci = dd_add(view.center_im, DoubleDouble{dci, 0.0});
+    DoubleDouble zr = {0.0, 0.0}, zi = {0.0, 0.0};
+    uint32_t iteration = 0;
+    while (iteration < DEEP_MAX_ITER && std::fma(zr.hi, zr.hi, zi.hi * zi.hi) <= 4.0) {
+        DoubleDouble zr_next = dd_add(dd_sub(dd_mul(zr, zr), dd_mul(zi, zi)), cr);
+        zi = dd_add(dd_mul(dd_mul(zr, zi), 2.0), ci);
+        zr = zr_next;
+        iteration++;
+    }
+    return iteration;
+}
+
+// Black inside the set; outside, a smooth polynomial palette over sqrt-scaled iteration counts
+std::vector<uint8_t> colorize(const std::vector<uint32_t>& iterations, uint32_t max_iter) {
+    std::vector<uint8_t> rgb;
+    rgb.reserve(iterations.size() * 3);
+    for (uint32_t n : iterations) {
+        if (n >= max_iter) {
+            rgb.insert(rgb.end(), {0, 0, 0});
+            continue;
+        }
+        double t = std::sqrt(static_cast<double>(n) / max_iter);
+        double u = 1.0 - t;
+        rgb.push_back(static_cast<uint8_t>(9.0 * u * t * t * t * 255.0));
+        rgb.push_back(static_cast<uint8_t>(15.0 * u * u * t * t * 255.0));
+        rgb.push_back(static_cast<uint8_t>(8.5 * u * u * u * t * 255.0));
+    }
+    return rgb;
+}
+
+bool write_png(const char* path, size_t width, size_t height, const std::vector<uint32_t>& iterations,
+               uint32_t max_iter) {
+#ifdef HAVE_PNG
+    std::vector<uint8_t> rgb = colorize(iterations, max_iter);
+    png_image image{};
+    image.version = PNG_IMAGE_VERSION;
+    image.width = static_cast<png_uint_32>(width);
+    image.height = static_cast<png_uint_32>(height);
+    image.format = PNG_FORMAT_RGB;
+    return png_image_write_to_file(&image, path, 0, rgb.data(), 0, nullptr) != 0;
+#else
+    // Unreachable: main rejects --png in builds without libpng, such as the WASM one
+    (void)path, (void)width, (void)height, (void)iterations, (void)max_iter;
+    return false;
+#endif
+}
+
+Variant parse_variant(int argc, char** argv) {
+    for (int i = 1; i + 1 < argc; i++) {
+        if (std::strcmp(argv[i], "--variant") == 0) {
+            if (std::strcmp(argv[i + 1], "scalar") == 0) return Variant::Scalar;
+            if (std::strcmp(argv[i + 1], "simd") == 0) return Variant::Simd;
+            if (std::strcmp(argv[i + 1], "deep") == 0) return Variant::Deep;
+            std::cerr << "--variant expects scalar, simd or deep" << std::endl;
+            std::exit(1);
+        }
+    }
+    return Variant::Scalar;
+}
+
+const char* flag_value(int argc, char** argv, const char* name) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], name) == 0) {
+            if (i + 1 >= argc) {
+                std::cerr << name << " expects a value" << std::endl;
+                std::exit(1);
+            }
+            return argv[i + 1];
+        }
+    }
+    return nullptr;
+}
+
+// Without --center or --zoom the original stretched -2.5..1.0 x -1.0..1.0 region is kept,
+// so the default run is unchanged; either flag switches to square pixels around the center
+View parse_view(int argc, char** argv) {
+    size_t width = WIDTH, height = HEIGHT;
+    if (const char* size = flag_value(argc, argv, "--size")) {
+        if (std::sscanf(size, "%zux%zu", &width, &height) != 2) {
+            std::cerr << "--size expects WIDTHxHEIGHT" << std::endl;
+            std::exit(1);
+        }
+    }
+    if (width == 0 || height == 0 || width % LANES != 0) {
+        std::cerr << "--size width must be a positive multiple of " << LANES << std::endl;
+        std::exit(1);
+    }
+    const char* center = flag_value(argc, argv, "--center");
+    const char* zoom_arg = flag_value(argc, argv, "--zoom");
+    if (!center && !zoom_arg) {
+        return {width, height, -2.5, 1.0, -1.0, 1.0};
+    }
+    double center_re = DEFAULT_CENTER_RE, center_im = DEFAULT_CENTER_IM;
+    if (center && std::sscanf(center, "%lf,%lf", &center_re, &center_im) != 2) {
+        std::cerr << "--center expects RE,IM" << std::endl;
+        std::exit(1);
+    }
+    double zoom = zoom_arg ? std::atof(zoom_arg) : 1.0;
+    if (zoom <= 0.0) {
+        std::cerr << "--zoom expects a positive number" << std::endl;
+        std::exit(1);
+    }
+    double re_span = BASE_RE_SPAN / zoom;
+    double im_span = re_span * static_cast<double>(height) / static_cast<double>(width);
+    return {width, height, center_re - re_span / 2.0, center_re + re_span / 2.0,
+            center_im - im_span / 2.0, center_im + im_span / 2.0};
+}
+
+// The center stays a string until it is parsed into double-double, since a double would round
+// it to a point many pixels away at deep zoom
+DeepView parse_deep_view(int argc, char** argv) {
+    size_t width = DEEP_SIZE, height = DEEP_SIZE;
+    if (const char* size = flag_value(argc, argv, "--size")) {
+        if (std::sscanf(size, "%zux%zu", &width, &height) != 2 || width == 0 || height == 0) {
+            std::cerr << "--size expects WIDTHxHEIGHT" << std::endl;
+            std::exit(1);
+        }
+    }
+    std::string re = DEEP_CENTER_RE, im = DEEP_CENTER_IM;
+    if (const char* center = flag_value(argc, argv, "--center")) {
+        const char* comma = std::strchr(center, ',');
+        if (!comma) {
+            std::cerr << "--center expects RE,IM" << std::endl;
+            std::exit(1);
+        }
+        re = std::string(center, comma);
+        im = comma + 1;
+    }
+    DeepView view{};
+    if (!dd_parse(re.c_str(), view.center_re) || !dd_parse(im.c_str(), view.center_im)) {
+        std::cerr << "--center expects decimal RE,IM" << std::endl;
+        std::exit(1);
+    }
+    const char* zoom_arg = flag_value(argc, argv, "--zoom");
+    view.zoom = zoom_arg ? std::atof(zoom_arg) : DEEP_ZOOM;
+    if (view.zoom <= 0.0) {
+        std::cerr << "--zoom expects a positive number" << std::endl;
+        std::exit(1);
+    }
+    view.width = width;
+    view.height = height;
+    view.re_span = BASE_RE_SPAN / view.zoom;
+    view.im_span = view.re_span * static_cast<double>(height) / static_cast<double>(width);
+    return view;
+}
+
+// How unevenly the work is spread: the busiest of BANDS equal row bands against the mean,
+// which is what a static row split across threads would see
+double band_imbalance(const std::vector<uint32_t>& iterations, size_t width, size_t height) {
+    size_t band_len = (height + BANDS - 1) / BANDS * width;
+    std::vector<uint64_t> bands;
+    for (size_t start = 0; start < iterations.size(); start += band_len) {
+        uint64_t sum = 0;
+        for (size_t i = start; i < std::min(start + band_len, iterations.size()); i++) sum += iterations[i];
+        bands.push_back(sum);
+    }
+    uint64_t total = 0;
+    for (uint64_t b : bands) total += b;
+    double mean = static_cast<double>(total) / bands.size();
+    return *std::max_element(bands.begin(), bands.end()) / mean;
+}
+
+void print_iteration_stats(const std::vector<uint32_t>& result, size_t width, size_t height, uint32_t max_iter) {
+    uint64_t total_iterations = 0;
+    size_t in_set = 0;
+    for (uint32_t n : result) {
+        total_iterations += n;
+        in_set += n >= max_iter;
+    }
+    fprintf(stderr, "Iterations: mean %.2f, %.2f%% in set, %zu-band imbalance %.2fx\n",
+            static_cast<double>(total_iterations) / result.size(), 100.0 * in_set / result.size(), BANDS,
+            band_imbalance(result, width, height));
+}
+
+int run_deep(int argc, char** argv, const char* png_path) {
+    DeepView view = parse_deep_view(argc, argv);
+    std::vector<uint32_t> result(view.width * view.height);
+    compute_deep(result, view, reference_orbit(view)); // warm-up
+
+    // Benchmark: the reference orbit is part of the work, computed once per frame
+    auto start = std::chrono::high_resolution_clock::now();
+    std::vector<OrbitPoint> orbit = reference_orbit(view);
+    std::chrono::duration<double> orbit_time = std::chrono::high_resolution_clock::now() - start;
+    uint64_t rebases = compute_deep(result, view, orbit);
+    std::chrono::duration<double> duration = std::chrono::high_resolution_clock::now() - start;
+
+    uint64_t checksum = 0;
+    for (size_t i = 0; i < std::min<size_t>(1000, result.size()); i++) {
+        checksum += result[i];
+    }
+    size_t spot_mismatches = 0;
+    for (size_t gy = 0; gy < SPOT_CHECK_GRID; gy++) {
+        for (size_t gx = 0; gx < SPOT_CHECK_GRID; gx++) {
+            size_t x = (2 * gx + 1) * view.width / (2 * SPOT_CHECK_GRID);
+            size_t y = (2 * gy + 1) * view.height / (2 * SPOT_CHECK_GRID);
+            spot_mismatches += direct_point(view, x, y) != result[y * view.width + x];
+        }
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
+    std::cerr << cpu_features_report() << std::endl;
+    std::cerr << "Variant: deep (double-double reference orbit, double perturbation)" << std::endl;
+    std::cerr << cpu_path("escape", CpuPath::Scalar, "double perturbation") << std::endl;
+    fprintf(stderr, "Region: %zux%zu, zoom %g, pixel spacing %g\n", view.width, view.height, view.zoom,
+            view.re_span / view.width);
+    fprintf(stderr, "Reference orbit: %zu iterations in %.3fms, %llu rebases\n", orbit.size() - 1,
+            orbit_time.count() * 1e3, static_cast<unsigned long long>(rebases));
+    print_iteration_stats(result, view.width, view.height, DEEP_MAX_ITER);
+    fprintf(stderr, "Spot check: %zu/%zu pixels match direct double-double iteration\n",
+            SPOT_CHECK_GRID * SPOT_CHECK_GRID - spot_mismatches, SPOT_CHECK_GRID * SPOT_CHECK_GRID);
+    if (png_path) {
+        if (!write_png(png_path, view.width, view.height, result, DEEP_MAX_ITER)) {
+            std::cerr << "Failed to write PNG" << std::endl;
+            return 1;
+        }
+        std::cerr << "Wrote " << png_path << std::endl;
+    }
+    std::cerr << "Checksum: " << checksum << std::endl;
+    return 0;
+}
+
+int main(int argc, char** argv) {
+    auto measure = bench_measure_from_env();
+    Variant variant = parse_variant(argc, argv);
+    View view = parse_view(argc, argv);
+    const char* png_path = flag_value(argc, argv, "--png");
+#ifndef HAVE_PNG
+    if (png_path) {
+        std::cerr << "--png needs a build with libpng" << std::endl;
+        std::exit(1);
+    }
+#endif
+    if (variant == Variant::Deep) {
+        return run_deep(argc, argv, png_path);
+    }
+    std::vector<uint32_t> result(view.width * view.height);
     
     // Warm-up
-    compute_section(result, 0, 10);
+    compute_section(result, view, 0, std::min<size_t>(10, view.height), variant);
     
     // Benchmark
-    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
     
     std::vector<std::thread> threads;
-    size_t rows_per_thread = HEIGHT / NUM_THREADS;
+    size_t rows_per_thread = view.height / NUM_THREADS;
     
     for (size_t t = 0; t < NUM_THREADS; t++) {
         size_t start_row = t * rows_per_thread;
-        size_t end_row = (t == NUM_THREADS - 1) ? HEIGHT : (t + 1) * rows_per_thread;
-        threads.emplace_back(compute_section, std::ref(result), start_row, end_row);
+        size_t end_row = (t == NUM_THREADS - 1) ? view.height : (t + 1) * rows_per_thread;
+        threads.emplace_back(compute_section, std::ref(result), std::cref(view), start_row, end_row, variant);
     }
     
     for (auto& thread : threads) {
         thread.join();
     }
     
-    auto end = std::chrono::high_resolution_clock::now();
-    std::chrono::duration<double> duration = end - start;
+    measure->stop();
     
     // Checksum
     uint64_t checksum = 0;
-    for (size_t i = 0; i < 1000; i++) {
+    for (size_t i = 0; i < std::min<size_t>(1000, result.size()); i++) {
         checksum += result[i];
     }
     
-    std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
+    measure->report();
+    std::cerr << cpu_features_report() << std::endl;
+    if (variant == Variant::Simd) {
+        std::cerr << "Variant: simd (vector extensions, " << LANES << " pixels per vector)" << std::endl;
+        std::cerr << cpu_path("escape", CpuPath::Simd, std::string("4 x double on ") + cpu_simd_isa()) << std::endl;
+    } else {
+        std::cerr << "Variant: scalar" << std::endl;
+        std::cerr << cpu_path("escape", CpuPath::Scalar, "double") << std::endl;
+    }
+    fprintf(stderr, "Region: %zux%zu, re %.12f .. %.12f, im %.12f .. %.12f\n", view.width, view.height, view.min_re,
+            view.max_re, view.min_im, view.max_im);
+    print_iteration_stats(result, view.width, view.height, MAX_ITER);
+    if (png_path) {
+        if (!write_png(png_path, view.width, view.height, result, MAX_ITER)) {
+            std::cerr << "Failed to write PNG" << std::endl;
+            return 1;
+        }
+        std::cerr << "Wrote " << png_path << std::endl;
+    }
     std::cerr << "Checksum: " << checksum << std::endl;
     
     return 0;
 }
-
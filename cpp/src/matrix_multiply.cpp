@@ -1,76 +1,311 @@
 #include <iostream>
 #include <vector>
 #include <thread>
+#include <atomic>
 #include <chrono>
 #include <iomanip>
+#include <algorithm>
+#include <cmath>
+#include <cstdio>
+#include <cstring>
+#include <cstdlib>
+#include <type_traits>
+#include "bench_params.h"
+#include "numa_placement.h"
+#ifdef HAVE_CBLAS
+#ifdef __APPLE__
+#include <Accelerate/Accelerate.h>
+#else
+#include <cblas.h>
+#endif
+#endif
 
-const size_t SIZE = 1024;
-const size_t NUM_THREADS = 8;
-
-void matrix_multiply_row(const std::vector<std::vector<double>>& a,
-                         const std::vector<std::vector<double>>& b,
-                         std::vector<std::vector<double>>& result,
-                         size_t start_row, size_t end_row) {
-    for (size_t i = start_row; i < end_row; i++) {
-        for (size_t j = 0; j < SIZE; j++) {
-            double sum = 0.0;
-            for (size_t k = 0; k < SIZE; k++) {
-                sum += a[i][k] * b[k][j];
-            }
-            result[i][j] = sum;
-        }
+const size_t DEFAULT_SIZE = 1024; // BENCH_PARAMS size, a multiple of BLOCK
+const size_t BLOCK = 64; // a 64x64 double tile is 32 KiB, so a tile of B stays cache-resident while reused
+// BENCH_PARAMS threads; set once in main, before the first parallel_for
+size_t num_threads = 8;
+
+// Matrices are n x n, flat row-major: element (i, j) lives at i * n + j. Hand-written kernels come
+// first, then a library GEMM, so results separate "hand-written Rust vs hand-written C++" from
+// "ecosystem vs ecosystem"
+enum class Variant {
+    Naive,             // i-k-j: the inner loop streams a row of B into a row of C
+    Blocked,           // i-k-j over BLOCK x BLOCK tiles
+    BlockedTransposed, // B transposed first, then blocked row-by-row dot products
+    Blas,              // cblas_?gemm from the system BLAS (Accelerate or OpenBLAS), which threads itself
+};
+
+const char* variant_name(Variant variant) {
+    switch (variant) {
+        case Variant::Naive: return "naive";
+        case Variant::Blocked: return "blocked";
+        case Variant::BlockedTransposed: return "blocked-transposed";
+        case Variant::Blas: return "blas";
+    }
+    return "";
+}
+
+enum class Precision {
+    F64,
+    F32,
+    Mixed, // float inputs, double accumulation and result
+};
+
+const char* precision_name(Precision precision) {
+    switch (precision) {
+        case Precision::F64: return "f64";
+        case Precision::F32: return "f32";
+        case Precision::Mixed: return "mixed (f32 inputs, f64 accumulation)";
     }
+    return "";
 }
 
-void matrix_multiply_parallel(const std::vector<std::vector<double>>& a,
-                              const std::vector<std::vector<double>>& b,
-                              std::vector<std::vector<double>>& result) {
+// Runs f(task) for task in [0, tasks) on num_threads threads pulling from a shared counter
+template <typename F>
+void parallel_for(size_t tasks, F f) {
+    std::atomic<size_t> next{0};
     std::vector<std::thread> threads;
-    size_t rows_per_thread = SIZE / NUM_THREADS;
-    
-    for (size_t t = 0; t < NUM_THREADS; t++) {
-        size_t start_row = t * rows_per_thread;
-        size_t end_row = (t == NUM_THREADS - 1) ? SIZE : (t + 1) * rows_per_thread;
-        threads.emplace_back(matrix_multiply_row, std::ref(a), std::ref(b), std::ref(result), start_row, end_row);
+    for (size_t t = 0; t < num_threads; t++) {
+        threads.emplace_back([&]() {
+            for (size_t task = next++; task < tasks; task = next++) {
+                f(task);
+            }
+        });
     }
-    
     for (auto& thread : threads) {
         thread.join();
     }
 }
 
-int main() {
-    // Initialize matrices
-    std::vector<std::vector<double>> a(SIZE, std::vector<double>(SIZE));
-    std::vector<std::vector<double>> b(SIZE, std::vector<double>(SIZE));
-    std::vector<std::vector<double>> result(SIZE, std::vector<double>(SIZE, 0.0));
-    
-    for (size_t i = 0; i < SIZE; i++) {
-        for (size_t j = 0; j < SIZE; j++) {
-            a[i][j] = static_cast<double>(i + j);
-            b[i][j] = static_cast<double>(i * j);
+// Kernels take inputs of type S and accumulate in A. Every multiply-add is an explicit FMA so
+// both languages round identically in f32
+template <typename S, typename A>
+void matrix_multiply_naive(size_t n, const std::vector<S>& a, const std::vector<S>& b, std::vector<A>& result) {
+    parallel_for(n, [&](size_t i) {
+        A* row = &result[i * n];
+        for (size_t k = 0; k < n; k++) {
+            A a_ik = static_cast<A>(a[i * n + k]);
+            const S* b_row = &b[k * n];
+            for (size_t j = 0; j < n; j++) {
+                row[j] = std::fma(a_ik, static_cast<A>(b_row[j]), row[j]);
+            }
+        }
+    });
+}
+
+// Each task owns a band of BLOCK rows of C and walks the k and j tiles across it
+template <typename S, typename A>
+void matrix_multiply_blocked(size_t n, const std::vector<S>& a, const std::vector<S>& b, std::vector<A>& result) {
+    parallel_for(n / BLOCK, [&](size_t band) {
+        size_t i0 = band * BLOCK;
+        for (size_t kk = 0; kk < n; kk += BLOCK) {
+            for (size_t jj = 0; jj < n; jj += BLOCK) {
+                for (size_t i = i0; i < i0 + BLOCK; i++) {
+                    A* c = &result[i * n + jj];
+                    for (size_t k = kk; k < kk + BLOCK; k++) {
+                        A a_ik = static_cast<A>(a[i * n + k]);
+                        const S* b_row = &b[k * n + jj];
+                        for (size_t j = 0; j < BLOCK; j++) {
+                            c[j] = std::fma(a_ik, static_cast<A>(b_row[j]), c[j]);
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+template <typename S>
+std::vector<S> transpose(size_t n, const std::vector<S>& m) {
+    std::vector<S> t(n * n);
+    for (size_t i = 0; i < n; i++) {
+        for (size_t j = 0; j < n; j++) {
+            t[j * n + i] = m[i * n + j];
+        }
+    }
+    return t;
+}
+
+// Four independent partial sums so the reduction can vectorize (strict addition order
+// would otherwise serialize it); len is a multiple of 4
+template <typename S, typename A>
+A dot(const S* x, const S* y, size_t len) {
+    A lanes[4] = {0, 0, 0, 0};
+    for (size_t k = 0; k < len; k += 4) {
+        for (size_t l = 0; l < 4; l++) {
+            lanes[l] = std::fma(static_cast<A>(x[k + l]), static_cast<A>(y[k + l]), lanes[l]);
+        }
+    }
+    return (lanes[0] + lanes[1]) + (lanes[2] + lanes[3]);
+}
+
+// With B transposed both operands of each dot product are contiguous rows
+template <typename S, typename A>
+void matrix_multiply_blocked_transposed(size_t n, const std::vector<S>& a, const std::vector<S>& b, std::vector<A>& result) {
+    std::vector<S> bt = transpose(n, b);
+    parallel_for(n / BLOCK, [&](size_t band) {
+        size_t i0 = band * BLOCK;
+        for (size_t kk = 0; kk < n; kk += BLOCK) {
+            for (size_t jj = 0; jj < n; jj += BLOCK) {
+                for (size_t i = i0; i < i0 + BLOCK; i++) {
+                    const S* a_row = &a[i * n + kk];
+                    for (size_t j = jj; j < jj + BLOCK; j++) {
+                        result[i * n + j] += dot<S, A>(a_row, &bt[j * n + kk], BLOCK);
+                    }
+                }
+            }
         }
+    });
+}
+
+#ifdef HAVE_CBLAS
+void cblas_gemm(size_t size, const std::vector<double>& a, const std::vector<double>& b, std::vector<double>& result) {
+    const int n = static_cast<int>(size);
+    cblas_dgemm(CblasRowMajor, CblasNoTrans, CblasNoTrans, n, n, n, 1.0, a.data(), n, b.data(), n, 0.0, result.data(), n);
+}
+
+void cblas_gemm(size_t size, const std::vector<float>& a, const std::vector<float>& b, std::vector<float>& result) {
+    const int n = static_cast<int>(size);
+    cblas_sgemm(CblasRowMajor, CblasNoTrans, CblasNoTrans, n, n, n, 1.0f, a.data(), n, b.data(), n, 0.0f, result.data(), n);
+}
+#endif
+
+template <typename S, typename A>
+std::vector<A> matrix_multiply(Variant variant, size_t n, const std::vector<S>& a, const std::vector<S>& b) {
+    std::vector<A> result(n * n, A(0));
+    switch (variant) {
+        case Variant::Naive: matrix_multiply_naive(n, a, b, result); break;
+        case Variant::Blocked: matrix_multiply_blocked(n, a, b, result); break;
+        case Variant::BlockedTransposed: matrix_multiply_blocked_transposed(n, a, b, result); break;
+        case Variant::Blas:
+#ifdef HAVE_CBLAS
+            // Library GEMMs have one precision throughout; main rejects mixed for them
+            if constexpr (std::is_same_v<S, A>) cblas_gemm(n, a, b, result);
+#endif
+            break;
     }
-    
+    return result;
+}
+
+// Converts the double inputs to S outside the timed region, then warms up and times one multiply.
+// The result comes back widened to double for the checksum and accuracy check
+template <typename S, typename A>
+std::pair<std::chrono::duration<double>, std::vector<double>> benchmark(Variant variant, size_t n,
+                                                                        const std::vector<double>& a64,
+                                                                        const std::vector<double>& b64) {
+    std::vector<S> a(a64.begin(), a64.end());
+    std::vector<S> b(b64.begin(), b64.end());
+
     // Warm-up
-    matrix_multiply_parallel(a, b, result);
-    
+    matrix_multiply<S, A>(variant, n, a, b);
+
     // Benchmark
     auto start = std::chrono::high_resolution_clock::now();
-    matrix_multiply_parallel(a, b, result);
+    std::vector<A> result = matrix_multiply<S, A>(variant, n, a, b);
     auto end = std::chrono::high_resolution_clock::now();
-    
-    std::chrono::duration<double> duration = end - start;
-    
-    // Checksum
+
+    return {end - start, std::vector<double>(result.begin(), result.end())};
+}
+
+Variant parse_variant(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--variant") == 0) {
+            const char* value = i + 1 < argc ? argv[i + 1] : "";
+            if (std::strcmp(value, "naive") == 0) return Variant::Naive;
+            if (std::strcmp(value, "blocked") == 0) return Variant::Blocked;
+            if (std::strcmp(value, "blocked-transposed") == 0) return Variant::BlockedTransposed;
+            if (std::strcmp(value, "blas") == 0) {
+#ifndef HAVE_CBLAS
+                std::cerr << "--variant blas needs a build with a system BLAS (Accelerate or OpenBLAS)" << std::endl;
+                std::exit(1);
+#endif
+                return Variant::Blas;
+            }
+            if (std::strcmp(value, "matrixmultiply") == 0) {
+                std::cerr << "--variant matrixmultiply is the Rust crate; use blas for the C++ library GEMM" << std::endl;
+                std::exit(1);
+            }
+            std::cerr << "--variant expects naive, blocked, blocked-transposed or blas" << std::endl;
+            std::exit(1);
+        }
+    }
+    return Variant::Blocked;
+}
+
+Precision parse_precision(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--precision") == 0) {
+            const char* value = i + 1 < argc ? argv[i + 1] : "";
+            if (std::strcmp(value, "f64") == 0) return Precision::F64;
+            if (std::strcmp(value, "f32") == 0) return Precision::F32;
+            if (std::strcmp(value, "mixed") == 0) return Precision::Mixed;
+            std::cerr << "--precision expects f64, f32 or mixed" << std::endl;
+            std::exit(1);
+        }
+    }
+    return Precision::F64;
+}
+
+int main(int argc, char** argv) {
+    NumaPlacement placement = numa_place(argc, argv);
+    Variant variant = parse_variant(argc, argv);
+    Precision precision = parse_precision(argc, argv);
+    if (precision == Precision::Mixed && variant == Variant::Blas) {
+        std::cerr << "--precision mixed runs the hand-written kernels only" << std::endl;
+        return 1;
+    }
+    BenchParams params = BenchParams::from_env();
+    const size_t n = params.size("size", DEFAULT_SIZE);
+    num_threads = params.size("threads", num_threads);
+    params.check();
+    if (n % BLOCK != 0) {
+        std::cerr << "BENCH_PARAMS: size=" << n << " is not a multiple of the " << BLOCK << "-row block" << std::endl;
+        return 1;
+    }
+
+    // Initialize matrices
+    std::vector<double> a(n * n);
+    std::vector<double> b(n * n);
+    for (size_t i = 0; i < n; i++) {
+        for (size_t j = 0; j < n; j++) {
+            a[i * n + j] = static_cast<double>(i + j);
+            b[i * n + j] = static_cast<double>(i * j);
+        }
+    }
+
+    auto [duration, result] = precision == Precision::F64 ? benchmark<double, double>(variant, n, a, b)
+                              : precision == Precision::F32 ? benchmark<float, float>(variant, n, a, b)
+                                                            : benchmark<float, double>(variant, n, a, b);
+
+    // In f64 every product and partial sum is an integer below 2^53, so all variants agree
+    // exactly; the entries outgrow f32's 24-bit significand, so f32 results round
     double checksum = 0.0;
-    for (size_t j = 0; j < SIZE; j++) {
-        checksum += result[0][j];
+    for (size_t j = 0; j < n; j++) {
+        checksum += result[j];
     }
-    
+    double gflops = 2.0 * static_cast<double>(n * n * n) / duration.count() / 1e9;
+
     std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
-    std::cerr << "Checksum: " << checksum << std::endl;
-    
+    if (variant == Variant::Blocked || variant == Variant::BlockedTransposed) {
+        std::cerr << "Variant: " << variant_name(variant) << " (" << BLOCK << "x" << BLOCK << " blocks)" << std::endl;
+    } else {
+        std::cerr << "Variant: " << variant_name(variant) << std::endl;
+    }
+    std::cerr << "Precision: " << precision_name(precision) << std::endl;
+    std::cerr << params.report() << std::endl;
+    fprintf(stderr, "Throughput: %.2f GFLOP/s\n", gflops);
+    if (precision != Precision::F64) {
+        // Normwise against the exact f64 product (untimed): max |C - C64| / max |C64|
+        std::vector<double> reference = matrix_multiply<double, double>(Variant::Blocked, n, a, b);
+        double max_error = 0.0, max_reference = 0.0;
+        for (size_t i = 0; i < result.size(); i++) {
+            max_error = std::max(max_error, std::abs(result[i] - reference[i]));
+            max_reference = std::max(max_reference, std::abs(reference[i]));
+        }
+        fprintf(stderr, "Relative error vs f64: %.3e\n", max_error / max_reference);
+    }
+    std::cerr << numa_report(placement) << std::endl;
+    fprintf(stderr, "Checksum: %.0f\n", checksum);
+
     return 0;
 }
-
@@ -0,0 +1,162 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+#include <cstdint>
+#include <cstdio>
+#include <cstring>
+#include "cache_flush.h"
+#include "huge_pages.h"
+#include "numa_placement.h"
+
+const size_t MIN_SIZE = 64; // 64 B .. 1 GiB in powers of four
+const size_t MAX_SIZE = size_t(1) << 30;
+const size_t BYTES_PER_POINT = size_t(512) << 20;
+const size_t ALIGN = 64; // the cache line; "+1" destinations sit one byte past it
+const uint8_t FILL_BYTE = 0xA5;
+
+enum class Op {
+    Copy,   // std::copy
+    Loop,   // element-by-element assignment, left to the compiler's idiom recognition
+    Memcpy, // std::memcpy
+    Memset, // std::memset
+};
+
+const Op OPS[] = {Op::Copy, Op::Loop, Op::Memcpy, Op::Memset};
+
+// Column labels, shared with the Rust grid (copy_from_slice, clone_from_slice,
+// ptr::copy_nonoverlapping, fill)
+const char* op_label(Op op) {
+    switch (op) {
+        case Op::Copy: return "copy";
+        case Op::Loop: return "clone";
+        case Op::Memcpy: return "memcpy";
+        case Op::Memset: return "fill";
+    }
+    return "";
+}
+
+// Repeats body() `reps` times. The barrier on both pointers every time keeps the compiler from
+// hoisting a repeated copy or fill out of the loop, like black_box
+template <typename Body>
+void repeat(const uint8_t* src, uint8_t* dst, size_t reps, Body body) {
+    for (size_t r = 0; r < reps; r++) {
+        asm volatile("" : : "r"(src), "r"(dst) : "memory");
+        body();
+    }
+}
+
+void run(Op op, const uint8_t* src, uint8_t* dst, size_t n, size_t reps) {
+    switch (op) {
+        case Op::Copy: repeat(src, dst, reps, [&] { std::copy(src, src + n, dst); }); break;
+        case Op::Loop: repeat(src, dst, reps, [&] { for (size_t i = 0; i < n; i++) dst[i] = src[i]; }); break;
+        case Op::Memcpy: repeat(src, dst, reps, [&] { std::memcpy(dst, src, n); }); break;
+        case Op::Memset: repeat(src, dst, reps, [&] { std::memset(dst, FILL_BYTE, n); }); break;
+    }
+}
+
+struct Point {
+    size_t size;
+    double gb_per_s; // bytes written per second: one copy moves `size` bytes
+};
+
+struct Measurement {
+    std::chrono::duration<double> elapsed;
+    Point point;
+    uint64_t ends; // the first and last bytes written, for the checksum
+};
+
+// Times `op` on `size` bytes, with the destination `offset` bytes past its ALIGN boundary
+Measurement measure(Op op, const uint8_t* src, uint8_t* dst_base, size_t size, size_t offset) {
+    uint8_t* dst = dst_base + offset;
+    size_t reps = cache_repetitions(std::max<size_t>(BYTES_PER_POINT / size, 1));
+
+    // One untimed run pulls both buffers into whatever level they fit (and faults them in)
+    run(op, src, dst, size, 1);
+    std::chrono::duration<double> elapsed(0);
+    if (cache_cold()) {
+        // One repetition at a time, each starting with both buffers flushed out to DRAM
+        for (size_t r = 0; r < reps; r++) {
+            cache_flush(src, size);
+            cache_flush(dst, size);
+            auto start = std::chrono::high_resolution_clock::now();
+            run(op, src, dst, size, 1);
+            elapsed += std::chrono::high_resolution_clock::now() - start;
+        }
+    } else {
+        auto start = std::chrono::high_resolution_clock::now();
+        run(op, src, dst, size, reps);
+        elapsed = std::chrono::high_resolution_clock::now() - start;
+    }
+    double gb_per_s = static_cast<double>(size) * reps / elapsed.count() / 1e9;
+    return {elapsed, {size, gb_per_s}, static_cast<uint64_t>(dst[0]) + dst[size - 1]};
+}
+
+// The ALIGN-aligned part of `buf`
+uint8_t* aligned(std::vector<uint8_t>& buf) {
+    uintptr_t address = reinterpret_cast<uintptr_t>(buf.data());
+    return buf.data() + ((ALIGN - address % ALIGN) % ALIGN);
+}
+
+std::string size_label(size_t bytes) {
+    if (bytes >= (size_t(1) << 30)) return std::to_string(bytes >> 30) + " GiB";
+    if (bytes >= (size_t(1) << 20)) return std::to_string(bytes >> 20) + " MiB";
+    if (bytes >= (size_t(1) << 10)) return std::to_string(bytes >> 10) + " KiB";
+    return std::to_string(bytes) + " B";
+}
+
+int main(int argc, char** argv) {
+    NumaPlacement placement = numa_place(argc, argv);
+    huge_pages_enable(argc, argv);
+    cache_cold_enable(argc, argv);
+
+    std::vector<uint8_t> src_buf, dst_buf;
+    huge_reserve(src_buf, MAX_SIZE + ALIGN);
+    huge_reserve(dst_buf, MAX_SIZE + 2 * ALIGN);
+    src_buf.resize(MAX_SIZE + ALIGN, 0);
+    dst_buf.resize(MAX_SIZE + 2 * ALIGN, 0);
+    uint8_t* src = aligned(src_buf);
+    // Relative to the aligned start, so the bytes copied (and the checksum) do not depend on where
+    // the allocator put the buffer
+    for (size_t i = 0; i < MAX_SIZE; i++) src[i] = static_cast<uint8_t>(i % 251);
+    uint8_t* dst = aligned(dst_buf);
+
+    std::chrono::duration<double> total(0);
+    uint64_t checksum = 0;
+    std::vector<std::vector<Point>> rows;
+    for (size_t size = MIN_SIZE; size <= MAX_SIZE; size *= 4) {
+        std::vector<Point> row;
+        for (Op op : OPS) {
+            for (size_t offset : {0, 1}) {
+                Measurement m = measure(op, src, dst, size, offset);
+                total += m.elapsed;
+                checksum += m.ends;
+                row.push_back(m.point);
+            }
+        }
+        rows.push_back(row);
+    }
+
+    fprintf(stderr, "Bytes written per second, rows = size, columns = operation (+1: destination one byte past 64-byte alignment)\n");
+    fprintf(stderr, "copy = std::copy, clone = element loop, memcpy = std::memcpy, fill = std::memset\n");
+    fprintf(stderr, "%8s", "GB/s");
+    for (Op op : OPS) {
+        fprintf(stderr, " %8s %8s", op_label(op), (std::string(op_label(op)) + "+1").c_str());
+    }
+    fprintf(stderr, "\n");
+    for (const auto& row : rows) {
+        fprintf(stderr, "%8s", size_label(row[0].size).c_str());
+        for (const auto& p : row) fprintf(stderr, " %8.2f", p.gb_per_s);
+        fprintf(stderr, "\n");
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    std::cerr << numa_report(placement) << std::endl;
+    std::cerr << huge_pages_report() << std::endl;
+    std::cerr << cache_report() << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
@@ -5,12 +5,245 @@
 #include <chrono>
 #include <iomanip>
 #include <cmath>
+#include <cstring>
+#include <cstdlib>
+#include <string>
+#include <thread>
+#include <atomic>
+#include <algorithm>
+#include <optional>
 
 const size_t MATRIX_SIZE = 2048;  // 2048x2048 matrix
-const size_t TOTAL_ELEMENTS = MATRIX_SIZE * MATRIX_SIZE;
 const size_t ITERATIONS = 10;
+const size_t TUNE_ITERATIONS = 3; // timed dispatches per tuning candidate
+const size_t MIN_GROUP_SIDE = 8;  // tuning sweeps power-of-two sides from 8x8 up to the pipeline limit
+const size_t CHECK_SIZE = 256; // validated element-wise against the CPU
+const float TOLERANCE = 1e-3f; // relative; Metal compiles with fast math by default
+const size_t NUM_THREADS = 8;
 
-int main() {
+// dispatchThreads lets Metal trim the edge threadgroups; dispatchThreadgroups launches whole
+// groups only, so it is used only where the groups tile the grid (heavy_compute has no bounds check)
+enum class Strategy { Threads, Threadgroups };
+
+const char* strategy_name(Strategy strategy) {
+    return strategy == Strategy::Threads ? "dispatchThreads" : "dispatchThreadgroups";
+}
+
+struct Launch {
+    size_t width;
+    size_t height;
+    Strategy strategy;
+    
+    bool tiles(size_t n) const {
+        return strategy == Strategy::Threads || (n % width == 0 && n % height == 0);
+    }
+};
+
+// Everything a GPU experiment might change without recompiling. A replacement kernel must take
+// the same arguments as heavy_compute: a, b and result at buffers 0-2, the matrix size at 3
+struct Config {
+    const char* kernel_file = nullptr; // .metallib is loaded precompiled, anything else compiled as MSL
+    const char* kernel_name = "heavy_compute";
+    size_t matrix_size = MATRIX_SIZE;
+    size_t iterations = ITERATIONS;
+    size_t group_width = 0; // 0 tunes the shape
+    size_t group_height = 0;
+    std::optional<Strategy> strategy; // unset tunes the dispatch call
+};
+
+[[noreturn]] void fail(const std::string& message) {
+    std::cerr << message << std::endl;
+    std::exit(1);
+}
+
+size_t parse_count(const char* name, const char* value, const char* expected) {
+    char* end = nullptr;
+    unsigned long long n = std::strtoull(value, &end, 10);
+    if (end == value || *end != '\0' || n == 0) fail(std::string(name) + " expects " + expected);
+    return static_cast<size_t>(n);
+}
+
+Config parse_config(int argc, char** argv) {
+    Config config;
+    for (int i = 1; i + 1 < argc; i++) {
+        const char* value = argv[i + 1];
+        if (std::strcmp(argv[i], "--kernel-file") == 0) {
+            config.kernel_file = value;
+        } else if (std::strcmp(argv[i], "--kernel-name") == 0) {
+            config.kernel_name = value;
+        } else if (std::strcmp(argv[i], "--size") == 0) {
+            config.matrix_size = parse_count("--size", value, "a matrix size");
+        } else if (std::strcmp(argv[i], "--iterations") == 0) {
+            config.iterations = parse_count("--iterations", value, "an iteration count");
+        } else if (std::strcmp(argv[i], "--threadgroup") == 0) {
+            char* end = nullptr;
+            config.group_width = std::strtoull(value, &end, 10);
+            if (*end != 'x') fail("--threadgroup expects WIDTHxHEIGHT");
+            config.group_height = parse_count("--threadgroup", end + 1, "WIDTHxHEIGHT");
+            if (config.group_width == 0) fail("--threadgroup expects WIDTHxHEIGHT");
+        } else if (std::strcmp(argv[i], "--dispatch") == 0) {
+            if (std::strcmp(value, "threads") == 0) config.strategy = Strategy::Threads;
+            else if (std::strcmp(value, "threadgroups") == 0) config.strategy = Strategy::Threadgroups;
+            else fail("--dispatch expects threads or threadgroups");
+        }
+    }
+    return config;
+}
+
+void inputs(size_t n, std::vector<float>& a, std::vector<float>& b) {
+    a.resize(n * n);
+    b.resize(n * n);
+    for (size_t i = 0; i < n * n; i++) {
+        a[i] = std::sin(static_cast<float>(i) * 0.001f);
+        b[i] = std::cos(static_cast<float>(i) * 0.002f);
+    }
+}
+
+// heavy_compute on the CPU in float, rows pulled from a shared counter by NUM_THREADS threads.
+// The row reduction is the same for every element of a row, so it is done once per row
+std::vector<float> cpu_heavy_compute(const std::vector<float>& a, const std::vector<float>& b, size_t n) {
+    std::vector<float> result(n * n);
+    std::atomic<size_t> next{0};
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&]() {
+            for (size_t row = next++; row < n; row = next++) {
+                float accumulator = 0.0f;
+                for (size_t col = 0; col < n; col++) {
+                    accumulator += a[row * n + col] * b[row * n + col] * 0.0001f;
+                }
+                for (size_t col = 0; col < n; col++) {
+                    float val_a = a[row * n + col];
+                    float val_b = b[row * n + col];
+                    float sum = 0.0f;
+                    for (int i = 0; i < 50; i++) {
+                        float t = val_a * static_cast<float>(i) * 0.01f + val_b;
+                        sum += std::sin(t) * std::cos(val_b * static_cast<float>(i) * 0.01f) +
+                               std::tan(val_a * 0.1f) * std::exp(-val_b * 0.001f) +
+                               std::sqrt(std::abs(t)) * std::log(std::abs(t) + 1.0f);
+                    }
+                    result[row * n + col] = sum + accumulator;
+                }
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+    return result;
+}
+
+// Launches the tuning pass tries: every shape and strategy not fixed on the command line
+std::vector<Launch> candidates(const Config& config, size_t max_threads) {
+    std::vector<std::pair<size_t, size_t>> shapes;
+    if (config.group_width) {
+        shapes.push_back({config.group_width, config.group_height});
+    } else {
+        for (size_t w = MIN_GROUP_SIDE; w * MIN_GROUP_SIDE <= max_threads; w *= 2) {
+            for (size_t h = MIN_GROUP_SIDE; w * h <= max_threads; h *= 2) {
+                shapes.push_back({w, h});
+            }
+        }
+    }
+    std::vector<Strategy> strategies;
+    if (config.strategy) strategies.push_back(*config.strategy);
+    else strategies = {Strategy::Threads, Strategy::Threadgroups};
+    
+    std::vector<Launch> launches;
+    for (auto [w, h] : shapes) {
+        for (Strategy strategy : strategies) {
+            Launch launch{w, h, strategy};
+            if (config.strategy || launch.tiles(config.matrix_size)) launches.push_back(launch);
+        }
+    }
+    return launches;
+}
+
+struct Run {
+    std::vector<float> result;
+    double wall;
+    double encode;
+    double gpu;
+};
+
+// One untimed warm-up dispatch, then `iterations` timed ones over an n x n grid
+Run dispatch(id<MTLDevice> device, id<MTLComputePipelineState> pipeline, size_t n, const std::vector<float>& input_a,
+             const std::vector<float>& input_b, Launch launch, size_t iterations) {
+    const size_t total_elements = n * n;
+    
+    id<MTLBuffer> buffer_a = [device newBufferWithBytes:input_a.data()
+                                                 length:total_elements * sizeof(float)
+                                                options:MTLResourceStorageModeShared];
+    
+    id<MTLBuffer> buffer_b = [device newBufferWithBytes:input_b.data()
+                                                 length:total_elements * sizeof(float)
+                                                options:MTLResourceStorageModeShared];
+    
+    id<MTLBuffer> buffer_result = [device newBufferWithLength:total_elements * sizeof(float)
+                                                      options:MTLResourceStorageModeShared];
+    
+    uint32_t matrix_size_val = static_cast<uint32_t>(n);
+    id<MTLBuffer> buffer_size = [device newBufferWithBytes:&matrix_size_val
+                                                    length:sizeof(uint32_t)
+                                                   options:MTLResourceStorageModeShared];
+    
+    auto encode = [&](id<MTLCommandBuffer> commandBuffer) {
+        id<MTLComputeCommandEncoder> encoder = [commandBuffer computeCommandEncoder];
+        
+        [encoder setComputePipelineState:pipeline];
+        [encoder setBuffer:buffer_a offset:0 atIndex:0];
+        [encoder setBuffer:buffer_b offset:0 atIndex:1];
+        [encoder setBuffer:buffer_result offset:0 atIndex:2];
+        [encoder setBuffer:buffer_size offset:0 atIndex:3];
+        
+        MTLSize threadgroupSize = MTLSizeMake(launch.width, launch.height, 1);
+        if (launch.strategy == Strategy::Threads) {
+            [encoder dispatchThreads:MTLSizeMake(n, n, 1) threadsPerThreadgroup:threadgroupSize];
+        } else {
+            [encoder dispatchThreadgroups:MTLSizeMake(n / launch.width, n / launch.height, 1)
+                    threadsPerThreadgroup:threadgroupSize];
+        }
+        [encoder endEncoding];
+    };
+    
+    id<MTLCommandQueue> commandQueue = [device newCommandQueue];
+    
+    // Warm-up
+    {
+        id<MTLCommandBuffer> commandBuffer = [commandQueue commandBuffer];
+        encode(commandBuffer);
+        [commandBuffer commit];
+        [commandBuffer waitUntilCompleted];
+    }
+    
+    // Benchmark - run multiple iterations
+    auto start = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> encode_time(0);
+    double gpu_time = 0.0;
+    
+    for (size_t iter = 0; iter < iterations; iter++) {
+        auto encode_start = std::chrono::high_resolution_clock::now();
+        id<MTLCommandBuffer> commandBuffer = [commandQueue commandBuffer];
+        encode(commandBuffer);
+        [commandBuffer commit];
+        encode_time += std::chrono::high_resolution_clock::now() - encode_start;
+        [commandBuffer waitUntilCompleted];
+        
+        // GPUStartTime/GPUEndTime bracket the kernel on the GPU timeline
+        gpu_time += commandBuffer.GPUEndTime - commandBuffer.GPUStartTime;
+    }
+    
+    auto end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> duration = end - start;
+    
+    float* result_ptr = static_cast<float*>([buffer_result contents]);
+    return {std::vector<float>(result_ptr, result_ptr + total_elements), duration.count(), encode_time.count(), gpu_time};
+}
+
+int main(int argc, char** argv) {
+    Config config = parse_config(argc, argv);
+    const size_t matrix_size = config.matrix_size;
+    
     @autoreleasepool {
         id<MTLDevice> device = MTLCreateSystemDefaultDevice();
         if (!device) {
@@ -18,6 +251,7 @@ int main() {
             return 1;
         }
         
+        // The same kernel as shaders/heavy_compute.metal, embedded so the default run needs no files
         NSString* shaderSource = @R"(
             #include <metal_stdlib>
             using namespace metal;
@@ -56,99 +290,129 @@ int main() {
         )";
         
         NSError* error = nil;
-        id<MTLLibrary> library = [device newLibraryWithSource:shaderSource options:nil error:&error];
+        id<MTLLibrary> library = nil;
+        std::string origin;
+        std::string path = config.kernel_file ? config.kernel_file : "";
+        if (path.size() >= 9 && path.compare(path.size() - 9, 9, ".metallib") == 0) {
+            NSURL* url = [NSURL fileURLWithPath:[NSString stringWithUTF8String:path.c_str()]];
+            library = [device newLibraryWithURL:url error:&error];
+            origin = path + ", precompiled metallib";
+        } else {
+            if (config.kernel_file) {
+                shaderSource = [NSString stringWithContentsOfFile:[NSString stringWithUTF8String:path.c_str()]
+                                                         encoding:NSUTF8StringEncoding
+                                                            error:&error];
+                if (!shaderSource) fail("Failed to read " + path + ": " + [[error localizedDescription] UTF8String]);
+                origin = path + ", MSL source compiled at startup";
+            } else {
+                origin = "embedded MSL source";
+            }
+            library = [device newLibraryWithSource:shaderSource options:nil error:&error];
+        }
         if (!library) {
             std::cerr << "Failed to compile shader: " << [[error localizedDescription] UTF8String] << std::endl;
             return 1;
         }
         
-        id<MTLFunction> kernel = [library newFunctionWithName:@"heavy_compute"];
+        id<MTLFunction> kernel = [library newFunctionWithName:[NSString stringWithUTF8String:config.kernel_name]];
+        if (!kernel) fail(std::string("Failed to get kernel function ") + config.kernel_name);
         id<MTLComputePipelineState> pipeline = [device newComputePipelineStateWithFunction:kernel error:&error];
         if (!pipeline) {
             std::cerr << "Failed to create pipeline: " << [[error localizedDescription] UTF8String] << std::endl;
             return 1;
         }
-        
-        // Create input data - large matrices
-        std::vector<float> input_a(TOTAL_ELEMENTS);
-        std::vector<float> input_b(TOTAL_ELEMENTS);
-        
-        for (size_t i = 0; i < TOTAL_ELEMENTS; i++) {
-            input_a[i] = std::sin(static_cast<float>(i) * 0.001f);
-            input_b[i] = std::cos(static_cast<float>(i) * 0.002f);
+        const size_t max_threads = pipeline.maxTotalThreadsPerThreadgroup;
+        if (config.group_width * config.group_height > max_threads) {
+            fail("--threadgroup " + std::to_string(config.group_width) + "x" + std::to_string(config.group_height) +
+                 " exceeds this pipeline's limit of " + std::to_string(max_threads) + " threads");
         }
-        
-        id<MTLBuffer> buffer_a = [device newBufferWithBytes:input_a.data()
-                                                     length:TOTAL_ELEMENTS * sizeof(float)
-                                                    options:MTLResourceStorageModeShared];
-        
-        id<MTLBuffer> buffer_b = [device newBufferWithBytes:input_b.data()
-                                                     length:TOTAL_ELEMENTS * sizeof(float)
-                                                    options:MTLResourceStorageModeShared];
-        
-        id<MTLBuffer> buffer_result = [device newBufferWithLength:TOTAL_ELEMENTS * sizeof(float)
-                                                          options:MTLResourceStorageModeShared];
-        
-        uint32_t matrix_size_val = static_cast<uint32_t>(MATRIX_SIZE);
-        id<MTLBuffer> buffer_size = [device newBufferWithBytes:&matrix_size_val
-                                                        length:sizeof(uint32_t)
-                                                       options:MTLResourceStorageModeShared];
-        
-        id<MTLCommandQueue> commandQueue = [device newCommandQueue];
-        
-        // Warm-up
-        {
-            id<MTLCommandBuffer> commandBuffer = [commandQueue commandBuffer];
-            id<MTLComputeCommandEncoder> encoder = [commandBuffer computeCommandEncoder];
-            
-            [encoder setComputePipelineState:pipeline];
-            [encoder setBuffer:buffer_a offset:0 atIndex:0];
-            [encoder setBuffer:buffer_b offset:0 atIndex:1];
-            [encoder setBuffer:buffer_result offset:0 atIndex:2];
-            [encoder setBuffer:buffer_size offset:0 atIndex:3];
-            
-            MTLSize gridSize = MTLSizeMake(MATRIX_SIZE, MATRIX_SIZE, 1);
-            MTLSize threadgroupSize = MTLSizeMake(16, 16, 1);
-            [encoder dispatchThreads:gridSize threadsPerThreadgroup:threadgroupSize];
-            [encoder endEncoding];
-            
-            [commandBuffer commit];
-            [commandBuffer waitUntilCompleted];
+        std::vector<Launch> launches = candidates(config, max_threads);
+        for (const Launch& launch : launches) {
+            if (!launch.tiles(matrix_size)) {
+                fail("--dispatch threadgroups needs --size to be a multiple of the " + std::to_string(launch.width) + "x" +
+                     std::to_string(launch.height) + " threadgroup");
+            }
         }
+        std::vector<float> input_a, input_b;
+        inputs(matrix_size, input_a, input_b);
         
-        // Benchmark - run multiple iterations
-        auto start = std::chrono::high_resolution_clock::now();
+        // Tuning: neither binding's default shape is assumed, each candidate is timed on the GPU
+        // timeline and the fastest is used for the measurement below
+        std::vector<std::pair<Launch, double>> tuning;
+        if (launches.size() > 1) {
+            for (const Launch& candidate : launches) {
+                Run run = dispatch(device, pipeline, matrix_size, input_a, input_b, candidate, TUNE_ITERATIONS);
+                tuning.push_back({candidate, run.gpu / TUNE_ITERATIONS});
+            }
+        }
+        size_t best = 0;
+        for (size_t i = 1; i < tuning.size(); i++) {
+            if (tuning[i].second < tuning[best].second) best = i;
+        }
+        Launch launch = tuning.empty() ? launches[0] : tuning[best].first;
         
-        for (size_t iter = 0; iter < ITERATIONS; iter++) {
-            id<MTLCommandBuffer> commandBuffer = [commandQueue commandBuffer];
-            id<MTLComputeCommandEncoder> encoder = [commandBuffer computeCommandEncoder];
-            
-            [encoder setComputePipelineState:pipeline];
-            [encoder setBuffer:buffer_a offset:0 atIndex:0];
-            [encoder setBuffer:buffer_b offset:0 atIndex:1];
-            [encoder setBuffer:buffer_result offset:0 atIndex:2];
-            [encoder setBuffer:buffer_size offset:0 atIndex:3];
-            
-            MTLSize gridSize = MTLSizeMake(MATRIX_SIZE, MATRIX_SIZE, 1);
-            MTLSize threadgroupSize = MTLSizeMake(16, 16, 1);
-            [encoder dispatchThreads:gridSize threadsPerThreadgroup:threadgroupSize];
-            [encoder endEncoding];
-            
-            [commandBuffer commit];
-            [commandBuffer waitUntilCompleted];
+        // Validate element-wise against the CPU at a reduced size. Only the embedded kernel must
+        // pass; a replacement kernel's mismatch is reported, since it may compute something else
+        std::vector<float> check_a, check_b;
+        inputs(CHECK_SIZE, check_a, check_b);
+        Launch check_launch = launch;
+        if (!check_launch.tiles(CHECK_SIZE)) check_launch.strategy = Strategy::Threads;
+        Run check = dispatch(device, pipeline, CHECK_SIZE, check_a, check_b, check_launch, 1);
+        std::vector<float> reference = cpu_heavy_compute(check_a, check_b, CHECK_SIZE);
+        float max_error = 0.0f;
+        for (size_t i = 0; i < reference.size(); i++) {
+            max_error = std::max(max_error, std::abs(check.result[i] - reference[i]) / std::max(std::abs(reference[i]), 1.0f));
         }
+        if (!config.kernel_file && !(max_error < TOLERANCE)) {
+            fail("GPU result differs from CPU reference by " + std::to_string(max_error) + " (relative)");
+        }
+        
+        // Benchmark
+        Run run = dispatch(device, pipeline, matrix_size, input_a, input_b, launch, config.iterations);
         
-        auto end = std::chrono::high_resolution_clock::now();
-        std::chrono::duration<double> duration = end - start;
+        // The same work on the CPU once, as a same-binary comparison point (not part of the GPU time)
+        auto cpu_start = std::chrono::high_resolution_clock::now();
+        std::vector<float> cpu_result = cpu_heavy_compute(input_a, input_b, matrix_size);
+        double cpu_time = std::chrono::duration<double>(std::chrono::high_resolution_clock::now() - cpu_start).count();
         
         // Get result and checksum
-        float* result_ptr = static_cast<float*>([buffer_result contents]);
-        float checksum = 0.0f;
-        for (size_t i = 0; i < TOTAL_ELEMENTS; i += 1000) {
-            checksum += result_ptr[i];
+        float checksum = 0.0f, cpu_checksum = 0.0f;
+        for (size_t i = 0; i < run.result.size(); i += 1000) {
+            checksum += run.result[i];
+            cpu_checksum += cpu_result[i];
         }
         
-        std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
+        double gpu_time = run.gpu;
+        double wall_time = run.wall;
+        std::cout << std::fixed << std::setprecision(6) << wall_time << std::endl;
+        std::cerr << std::fixed << std::setprecision(6);
+        std::cerr << "Kernel: " << config.kernel_name << " (" << origin << "), " << matrix_size << "x" << matrix_size
+                  << " matrix, " << config.iterations << " iterations, " << launch.width << "x" << launch.height
+                  << " threadgroups via " << strategy_name(launch.strategy) << std::endl;
+        if (!tuning.empty()) {
+            std::cerr << "Threadgroup tuning (" << tuning.size() << " candidates, " << TUNE_ITERATIONS << " dispatches each, limit "
+                      << max_threads << " threads, SIMD width " << pipeline.threadExecutionWidth << "):" << std::endl;
+            for (size_t i = 0; i < tuning.size(); i++) {
+                const Launch& candidate = tuning[i].first;
+                std::string shape = std::to_string(candidate.width) + "x" + std::to_string(candidate.height);
+                std::cerr << "  " << std::right << std::setw(7) << shape << " " << std::left << std::setw(20)
+                          << strategy_name(candidate.strategy) << " " << std::right << tuning[i].second << " s per dispatch"
+                          << (i == best ? "  <- selected" : "") << std::endl;
+            }
+        }
+        std::cerr << "GPU execution time: " << gpu_time << " s" << std::endl;
+        std::cerr << "Encode + commit time: " << run.encode << " s" << std::endl;
+        std::cerr << "Driver + sync overhead: " << wall_time - gpu_time << " s (" << std::setprecision(1)
+                  << (wall_time - gpu_time) / wall_time * 100.0 << "% of wall time)" << std::endl;
+        std::cerr << std::scientific << std::setprecision(2) << "Validation (" << CHECK_SIZE << "x" << CHECK_SIZE
+                  << " vs CPU): max relative error " << max_error << " (tolerance " << std::setprecision(0) << TOLERANCE
+                  << ": " << (max_error < TOLERANCE ? "pass" : "FAIL") << ")" << std::endl;
+        double per_dispatch = gpu_time / config.iterations;
+        std::cerr << std::fixed << std::setprecision(6) << "CPU heavy_compute (" << NUM_THREADS << " threads): " << cpu_time
+                  << " s per pass vs GPU " << per_dispatch << " s per dispatch (" << std::setprecision(1)
+                  << cpu_time / per_dispatch << "x), CPU checksum " << std::defaultfloat << std::setprecision(6)
+                  << cpu_checksum << std::endl;
+        std::cerr << std::defaultfloat << std::setprecision(6);
         std::cerr << "Checksum: " << checksum << std::endl;
     }
     
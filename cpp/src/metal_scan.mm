@@ -0,0 +1,455 @@
+#import <Metal/Metal.h>
+#import <Foundation/Foundation.h>
+#include <iostream>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <cstring>
+#include <cstdlib>
+#include <cstdint>
+#include <string>
+#include <algorithm>
+
+const size_t DEFAULT_ELEMENTS = size_t(1) << 26; // 64M uint32_t, 256 MB
+const size_t ITERATIONS = 10;
+const size_t GROUP_SIZE = 256;   // threads per threadgroup, as in the shader
+const size_t BLOCK = 256 * 4;    // elements per scanned block: GROUP_SIZE x ELEMENTS_PER_THREAD
+const size_t REDUCE_GROUPS = 1024; // the reduction's grid-stride loop covers the input with this many groups
+const size_t MIN_SIMD_WIDTH = 16; // the _simd kernels scan the per-group totals within one SIMD group
+const size_t SAMPLE_STRIDE = 4099;
+
+// The same kernels as shaders/scan.metal, embedded so the benchmark needs no files
+const char* SHADER_SOURCE = R"(
+#include <metal_stdlib>
+using namespace metal;
+
+// Reduction and inclusive prefix scan over uint, wrapping on overflow like the CPU reference.
+// metal_scan dispatches every kernel in threadgroups of GROUP_SIZE threads. The _tree kernels
+// combine values through threadgroup memory and barriers alone; the _simd kernels combine
+// within each SIMD group with simdgroup intrinsics and use threadgroup memory only across groups
+
+constant uint GROUP_SIZE = 256;
+constant uint ELEMENTS_PER_THREAD = 4;
+constant uint BLOCK = GROUP_SIZE * ELEMENTS_PER_THREAD; // elements scanned per threadgroup
+// The SIMD kernels scan the per-group totals within one SIMD group, so they need at least as many
+// lanes as there are groups: a SIMD width of 16 or more, which metal_scan checks before dispatching
+constant uint MAX_SIMDGROUPS = GROUP_SIZE / 16;
+
+struct Lanes {
+    uint lid;         // thread in the threadgroup
+    uint lane;        // thread in its SIMD group
+    uint simd_id;     // SIMD group in the threadgroup
+    uint simd_count;  // SIMD groups in the threadgroup
+    uint simd_width;  // threads per SIMD group
+};
+
+// Exclusive scan of one value per thread across the threadgroup, also giving the group total.
+// `partial` holds GROUP_SIZE values for the tree scan and MAX_SIMDGROUPS + 1 for the SIMD one
+template <bool SIMD>
+uint exclusive_scan(uint value, Lanes t, threadgroup uint* partial, thread uint& total)
+{
+    if (SIMD) {
+        // Scan within each SIMD group, then scan the per-group totals in the first group
+        uint inclusive = simd_prefix_inclusive_sum(value);
+        if (t.lane == t.simd_width - 1) {
+            partial[t.simd_id] = inclusive;
+        }
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+        if (t.simd_id == 0) {
+            uint group = t.lane < t.simd_count ? partial[t.lane] : 0;
+            uint prefix = simd_prefix_exclusive_sum(group);
+            if (t.lane < t.simd_count) {
+                partial[t.lane] = prefix;
+            }
+            if (t.lane == t.simd_count - 1) {
+                partial[MAX_SIMDGROUPS] = prefix + group;
+            }
+        }
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+        total = partial[MAX_SIMDGROUPS];
+        return partial[t.simd_id] + inclusive - value;
+    }
+
+    // Hillis-Steele: log2(GROUP_SIZE) rounds, each read separated from its write by a barrier
+    partial[t.lid] = value;
+    threadgroup_barrier(mem_flags::mem_threadgroup);
+    for (uint offset = 1; offset < GROUP_SIZE; offset <<= 1) {
+        uint left = t.lid >= offset ? partial[t.lid - offset] : 0;
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+        partial[t.lid] += left;
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+    }
+    total = partial[GROUP_SIZE - 1];
+    return partial[t.lid] - value;
+}
+
+// Inclusive scan of data[base, base + BLOCK) plus `carry` into out, returning the block total.
+// Each thread scans ELEMENTS_PER_THREAD consecutive elements serially, so only one value per
+// thread crosses lanes; elements past `count` read as zero and are not written
+template <bool SIMD>
+uint scan_block(device const uint* data, device uint* out, uint base, uint count, uint carry,
+                Lanes t, threadgroup uint* partial)
+{
+    uint first = base + t.lid * ELEMENTS_PER_THREAD;
+    uint values[ELEMENTS_PER_THREAD];
+    uint sum = 0;
+    for (uint k = 0; k < ELEMENTS_PER_THREAD; k++) {
+        sum += first + k < count ? data[first + k] : 0;
+        values[k] = sum;
+    }
+    uint total;
+    uint prefix = carry + exclusive_scan<SIMD>(sum, t, partial, total);
+    for (uint k = 0; k < ELEMENTS_PER_THREAD; k++) {
+        if (first + k < count) {
+            out[first + k] = prefix + values[k];
+        }
+    }
+    return total;
+}
+
+#define LANE_ARGUMENTS                                              \
+    uint lid [[thread_position_in_threadgroup]],                    \
+    uint lane [[thread_index_in_simdgroup]],                        \
+    uint simd_id [[simdgroup_index_in_threadgroup]],                \
+    uint simd_count [[simdgroups_per_threadgroup]],                 \
+    uint simd_width [[threads_per_simdgroup]]
+
+// ---- Reduction: grid-stride partial sums, combined per threadgroup, one atomic per group ----
+
+kernel void reduce_tree(device const uint* input [[buffer(0)]],
+                        device atomic_uint* total [[buffer(1)]],
+                        constant uint& count [[buffer(2)]],
+                        uint gid [[thread_position_in_grid]],
+                        uint grid_size [[threads_per_grid]],
+                        uint lid [[thread_position_in_threadgroup]])
+{
+    threadgroup uint partial[GROUP_SIZE];
+    uint sum = 0;
+    for (uint i = gid; i < count; i += grid_size) {
+        sum += input[i];
+    }
+    partial[lid] = sum;
+    threadgroup_barrier(mem_flags::mem_threadgroup);
+    for (uint stride = GROUP_SIZE / 2; stride > 0; stride >>= 1) {
+        if (lid < stride) {
+            partial[lid] += partial[lid + stride];
+        }
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+    }
+    if (lid == 0) {
+        atomic_fetch_add_explicit(total, partial[0], memory_order_relaxed);
+    }
+}
+
+kernel void reduce_simd(device const uint* input [[buffer(0)]],
+                        device atomic_uint* total [[buffer(1)]],
+                        constant uint& count [[buffer(2)]],
+                        uint gid [[thread_position_in_grid]],
+                        uint grid_size [[threads_per_grid]],
+                        LANE_ARGUMENTS)
+{
+    threadgroup uint partial[MAX_SIMDGROUPS];
+    uint sum = 0;
+    for (uint i = gid; i < count; i += grid_size) {
+        sum += input[i];
+    }
+    sum = simd_sum(sum);
+    if (lane == 0) {
+        partial[simd_id] = sum;
+    }
+    threadgroup_barrier(mem_flags::mem_threadgroup);
+    if (simd_id == 0) {
+        sum = simd_sum(lane < simd_count ? partial[lane] : 0);
+        if (lane == 0) {
+            atomic_fetch_add_explicit(total, sum, memory_order_relaxed);
+        }
+    }
+}
+
+// ---- Scan: reduce-then-scan in three dispatches ----
+// 1. scan_blocks_*: every threadgroup scans one BLOCK and records its total
+// 2. scan_totals_*: one threadgroup scans the block totals in place, BLOCK at a time
+// 3. scan_add_offsets: every block after the first adds the inclusive total of the ones before it
+
+template <bool SIMD>
+void scan_blocks(device const uint* input, device uint* output, device uint* totals, uint count,
+                 uint group, Lanes t, threadgroup uint* partial)
+{
+    uint total = scan_block<SIMD>(input, output, group * BLOCK, count, 0, t, partial);
+    if (t.lid == 0) {
+        totals[group] = total;
+    }
+}
+
+template <bool SIMD>
+void scan_totals(device uint* totals, uint blocks, Lanes t, threadgroup uint* partial)
+{
+    uint carry = 0;
+    for (uint base = 0; base < blocks; base += BLOCK) {
+        carry += scan_block<SIMD>(totals, totals, base, blocks, carry, t, partial);
+        threadgroup_barrier(mem_flags::mem_threadgroup); // partial is reused by the next chunk
+    }
+}
+
+kernel void scan_blocks_tree(device const uint* input [[buffer(0)]],
+                             device uint* output [[buffer(1)]],
+                             device uint* totals [[buffer(2)]],
+                             constant uint& count [[buffer(3)]],
+                             uint group [[threadgroup_position_in_grid]],
+                             LANE_ARGUMENTS)
+{
+    threadgroup uint partial[GROUP_SIZE];
+    scan_blocks<false>(input, output, totals, count, group, {lid, lane, simd_id, simd_count, simd_width}, partial);
+}
+
+kernel void scan_blocks_simd(device const uint* input [[buffer(0)]],
+                             device uint* output [[buffer(1)]],
+                             device uint* totals [[buffer(2)]],
+                             constant uint& count [[buffer(3)]],
+                             uint group [[threadgroup_position_in_grid]],
+                             LANE_ARGUMENTS)
+{
+    threadgroup uint partial[MAX_SIMDGROUPS + 1];
+    scan_blocks<true>(input, output, totals, count, group, {lid, lane, simd_id, simd_count, simd_width}, partial);
+}
+
+kernel void scan_totals_tree(device uint* totals [[buffer(2)]],
+                             constant uint& blocks [[buffer(4)]],
+                             LANE_ARGUMENTS)
+{
+    threadgroup uint partial[GROUP_SIZE];
+    scan_totals<false>(totals, blocks, {lid, lane, simd_id, simd_count, simd_width}, partial);
+}
+
+kernel void scan_totals_simd(device uint* totals [[buffer(2)]],
+                             constant uint& blocks [[buffer(4)]],
+                             LANE_ARGUMENTS)
+{
+    threadgroup uint partial[MAX_SIMDGROUPS + 1];
+    scan_totals<true>(totals, blocks, {lid, lane, simd_id, simd_count, simd_width}, partial);
+}
+
+kernel void scan_add_offsets(device uint* output [[buffer(1)]],
+                             device const uint* totals [[buffer(2)]],
+                             constant uint& count [[buffer(3)]],
+                             uint group [[threadgroup_position_in_grid]],
+                             uint lid [[thread_position_in_threadgroup]])
+{
+    if (group == 0) {
+        return;
+    }
+    uint offset = totals[group - 1];
+    for (uint k = 0; k < ELEMENTS_PER_THREAD; k++) {
+        uint i = group * BLOCK + k * GROUP_SIZE + lid; // strided by GROUP_SIZE so loads coalesce
+        if (i < count) {
+            output[i] += offset;
+        }
+    }
+}
+)";
+
+[[noreturn]] void fail(const std::string& message) {
+    std::cerr << message << std::endl;
+    std::exit(1);
+}
+
+// The prefix_sum input, truncated to 32 bits: stateless per index, small values
+uint32_t input_value(size_t i) {
+    uint64_t x = static_cast<uint64_t>(i) * 0x9E3779B97F4A7C15ULL;
+    x ^= x >> 29;
+    return static_cast<uint32_t>(x & 1023);
+}
+
+enum class Combine {
+    Tree, // threadgroup memory and barriers only
+    Simd, // simd_sum / simd_prefix_*_sum within SIMD groups, threadgroup memory across them
+};
+
+const char* combine_name(Combine combine) {
+    return combine == Combine::Tree ? "threadgroup tree" : "simdgroup";
+}
+
+struct Timing {
+    double wall;
+    double gpu; // summed GPUEndTime - GPUStartTime over the timed command buffers
+};
+
+id<MTLComputePipelineState> make_pipeline(id<MTLDevice> device, id<MTLLibrary> library, const std::string& name) {
+    id<MTLFunction> function = [library newFunctionWithName:[NSString stringWithUTF8String:name.c_str()]];
+    if (!function) fail("Failed to get kernel function " + name);
+    NSError* error = nil;
+    id<MTLComputePipelineState> pipeline = [device newComputePipelineStateWithFunction:function error:&error];
+    if (!pipeline) fail("Failed to create pipeline " + name + ": " + [[error localizedDescription] UTF8String]);
+    if (pipeline.maxTotalThreadsPerThreadgroup < GROUP_SIZE) {
+        fail(name + " allows only " + std::to_string(pipeline.maxTotalThreadsPerThreadgroup) +
+             " threads per threadgroup, the shader needs " + std::to_string(GROUP_SIZE));
+    }
+    return pipeline;
+}
+
+// One untimed warm-up command buffer, then ITERATIONS timed ones. `reset` runs on the CPU
+// before each commit; `encode` records the dispatches
+template <typename Reset, typename Encode>
+Timing timed(id<MTLCommandQueue> queue, Reset reset, Encode encode) {
+    auto run = [&]() {
+        reset();
+        id<MTLCommandBuffer> commandBuffer = [queue commandBuffer];
+        id<MTLComputeCommandEncoder> encoder = [commandBuffer computeCommandEncoder];
+        encode(encoder);
+        [encoder endEncoding];
+        [commandBuffer commit];
+        [commandBuffer waitUntilCompleted];
+        return commandBuffer.GPUEndTime - commandBuffer.GPUStartTime;
+    };
+    run();
+    auto start = std::chrono::high_resolution_clock::now();
+    double gpu = 0.0;
+    for (size_t iter = 0; iter < ITERATIONS; iter++) {
+        gpu += run();
+    }
+    std::chrono::duration<double> wall = std::chrono::high_resolution_clock::now() - start;
+    return {wall.count(), gpu};
+}
+
+size_t parse_elements(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--elements") == 0) {
+            char* end = nullptr;
+            unsigned long long n = i + 1 < argc ? std::strtoull(argv[i + 1], &end, 10) : 0;
+            if (n == 0 || *end != '\0' || n > (1ULL << 31)) fail("--elements expects a count from 1 to 2^31");
+            return static_cast<size_t>(n);
+        }
+    }
+    return DEFAULT_ELEMENTS;
+}
+
+int main(int argc, char** argv) {
+    const size_t n = parse_elements(argc, argv);
+    const size_t blocks = (n + BLOCK - 1) / BLOCK;
+    
+    @autoreleasepool {
+        id<MTLDevice> device = MTLCreateSystemDefaultDevice();
+        if (!device) fail("Metal is not supported on this device");
+        NSError* error = nil;
+        id<MTLLibrary> library = [device newLibraryWithSource:[NSString stringWithUTF8String:SHADER_SOURCE]
+                                                      options:nil
+                                                        error:&error];
+        if (!library) fail(std::string("Failed to compile shader: ") + [[error localizedDescription] UTF8String]);
+        id<MTLCommandQueue> queue = [device newCommandQueue];
+        
+        std::vector<uint32_t> input(n);
+        for (size_t i = 0; i < n; i++) {
+            input[i] = input_value(i);
+        }
+        id<MTLBuffer> buffer_input = [device newBufferWithBytes:input.data()
+                                                         length:n * sizeof(uint32_t)
+                                                        options:MTLResourceStorageModeShared];
+        id<MTLBuffer> buffer_output = [device newBufferWithLength:n * sizeof(uint32_t) options:MTLResourceStorageModeShared];
+        id<MTLBuffer> buffer_totals = [device newBufferWithLength:blocks * sizeof(uint32_t) options:MTLResourceStorageModeShared];
+        id<MTLBuffer> buffer_sum = [device newBufferWithLength:sizeof(uint32_t) options:MTLResourceStorageModeShared];
+        const uint32_t count = static_cast<uint32_t>(n);
+        const uint32_t block_count = static_cast<uint32_t>(blocks);
+        
+        // CPU reference: wrapping, like the GPU's uint arithmetic
+        auto cpu_start = std::chrono::high_resolution_clock::now();
+        std::vector<uint32_t> expected_scan(n);
+        uint32_t acc = 0;
+        for (size_t i = 0; i < n; i++) {
+            acc += input[i];
+            expected_scan[i] = acc;
+        }
+        std::chrono::duration<double> cpu_time = std::chrono::high_resolution_clock::now() - cpu_start;
+        const uint32_t expected_sum = expected_scan[n - 1];
+        
+        uint32_t* sum_ptr = static_cast<uint32_t*>([buffer_sum contents]);
+        // Only read between command buffers, once the GPU is done writing
+        const uint32_t* output = static_cast<const uint32_t*>([buffer_output contents]);
+        MTLSize group = MTLSizeMake(GROUP_SIZE, 1, 1);
+        
+        size_t simd_width = SIZE_MAX;
+        std::vector<std::pair<Combine, Timing>> reduce_runs, scan_runs;
+        for (Combine combine : {Combine::Tree, Combine::Simd}) {
+            std::string suffix = combine == Combine::Tree ? "tree" : "simd";
+            id<MTLComputePipelineState> reduce = make_pipeline(device, library, "reduce_" + suffix);
+            id<MTLComputePipelineState> scan_blocks = make_pipeline(device, library, "scan_blocks_" + suffix);
+            id<MTLComputePipelineState> scan_totals = make_pipeline(device, library, "scan_totals_" + suffix);
+            id<MTLComputePipelineState> add_offsets = make_pipeline(device, library, "scan_add_offsets");
+            simd_width = std::min({simd_width, static_cast<size_t>(reduce.threadExecutionWidth),
+                                   static_cast<size_t>(scan_blocks.threadExecutionWidth)});
+            if (combine == Combine::Simd && simd_width < MIN_SIMD_WIDTH) {
+                fail("The simdgroup kernels need a SIMD width of at least " + std::to_string(MIN_SIMD_WIDTH) +
+                     ", this GPU has " + std::to_string(simd_width));
+            }
+            
+            Timing reduction = timed(queue, [&]() { *sum_ptr = 0; }, [&](id<MTLComputeCommandEncoder> encoder) {
+                [encoder setComputePipelineState:reduce];
+                [encoder setBuffer:buffer_input offset:0 atIndex:0];
+                [encoder setBuffer:buffer_sum offset:0 atIndex:1];
+                [encoder setBytes:&count length:sizeof(count) atIndex:2];
+                [encoder dispatchThreadgroups:MTLSizeMake(REDUCE_GROUPS, 1, 1) threadsPerThreadgroup:group];
+            });
+            if (*sum_ptr != expected_sum) {
+                fail(std::string(combine_name(combine)) + " reduction gave " + std::to_string(*sum_ptr) + ", expected " +
+                     std::to_string(expected_sum));
+            }
+            reduce_runs.push_back({combine, reduction});
+            
+            // The three dispatches share one encoder, whose serial dispatch order makes each see the
+            // previous one's writes
+            Timing scan = timed(queue, []() {}, [&](id<MTLComputeCommandEncoder> encoder) {
+                [encoder setBuffer:buffer_input offset:0 atIndex:0];
+                [encoder setBuffer:buffer_output offset:0 atIndex:1];
+                [encoder setBuffer:buffer_totals offset:0 atIndex:2];
+                [encoder setBytes:&count length:sizeof(count) atIndex:3];
+                [encoder setBytes:&block_count length:sizeof(block_count) atIndex:4];
+                [encoder setComputePipelineState:scan_blocks];
+                [encoder dispatchThreadgroups:MTLSizeMake(blocks, 1, 1) threadsPerThreadgroup:group];
+                [encoder setComputePipelineState:scan_totals];
+                [encoder dispatchThreadgroups:MTLSizeMake(1, 1, 1) threadsPerThreadgroup:group];
+                [encoder setComputePipelineState:add_offsets];
+                [encoder dispatchThreadgroups:MTLSizeMake(blocks, 1, 1) threadsPerThreadgroup:group];
+            });
+            for (size_t i = 0; i < n; i++) {
+                if (output[i] != expected_scan[i]) {
+                    fail(std::string(combine_name(combine)) + " scan differs from the CPU at element " + std::to_string(i) +
+                         ": " + std::to_string(output[i]) + " vs " + std::to_string(expected_scan[i]));
+                }
+            }
+            scan_runs.push_back({combine, scan});
+        }
+        
+        double total = 0.0;
+        for (const auto& [combine, t] : reduce_runs) total += t.wall;
+        for (const auto& [combine, t] : scan_runs) total += t.wall;
+        uint32_t checksum = 0;
+        for (size_t i = 0; i < n; i += SAMPLE_STRIDE) {
+            checksum += output[i];
+        }
+        checksum += output[n - 1];
+        
+        auto rate = [&](double seconds) { return n / seconds / 1e9; };
+        std::cout << std::fixed << std::setprecision(6) << total << std::endl;
+        std::cerr << std::fixed;
+        std::cerr << "Device: " << [[device name] UTF8String] << " (SIMD width " << simd_width << ")" << std::endl;
+        std::cerr << "Reduction over " << (n >> 20) << "M u32 (" << REDUCE_GROUPS << " groups of " << GROUP_SIZE
+                  << ", grid-stride), per dispatch:" << std::endl;
+        for (const auto& [combine, t] : reduce_runs) {
+            double per = t.gpu / ITERATIONS;
+            std::cerr << "  " << std::left << std::setw(16) << combine_name(combine) << " " << std::setprecision(6) << per
+                      << "s GPU (" << std::setprecision(2) << rate(per) << " G elements/s, " << std::setprecision(1)
+                      << rate(per) * 4.0 << " GB/s read)" << std::endl;
+        }
+        std::cerr << "Inclusive scan over " << (n >> 20) << "M u32 (" << blocks << " blocks of " << BLOCK
+                  << ", reduce-then-scan in 3 dispatches), per dispatch:" << std::endl;
+        for (const auto& [combine, t] : scan_runs) {
+            double per = t.gpu / ITERATIONS;
+            std::cerr << "  " << std::left << std::setw(16) << combine_name(combine) << " " << std::setprecision(6) << per
+                      << "s GPU (" << std::setprecision(2) << rate(per) << " G elements/s)" << std::endl;
+        }
+        std::cerr << "CPU sequential scan: " << std::setprecision(6) << cpu_time.count() << "s (" << std::setprecision(2)
+                  << rate(cpu_time.count()) << " G elements/s)" << std::endl;
+        std::cerr << "Checksum: sum " << expected_sum << ", scan " << checksum << std::endl;
+    }
+    
+    return 0;
+}
@@ -0,0 +1,168 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <cmath>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+
+#include "bench_measure.h"
+
+const size_t LAYER_SIZES[] = {784, 512, 512, 10};
+const size_t NUM_LAYERS = 3;
+const size_t BATCH_SIZE = 256;
+const size_t NUM_BATCHES = 1000;
+const size_t INPUT_POOL = 64; // Distinct input batches, reused round-robin
+const size_t NUM_THREADS = 8;
+
+struct Layer {
+    size_t inputs;
+    size_t outputs;
+    std::vector<float> weights; // inputs x outputs, row-major
+    std::vector<float> bias;
+};
+
+float next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return static_cast<float>(seed >> 40) / 16777216.0f - 0.5f;
+}
+
+std::vector<Layer> build_network() {
+    uint64_t seed = 42;
+    std::vector<Layer> network;
+
+    for (size_t l = 0; l < NUM_LAYERS; l++) {
+        Layer layer;
+        layer.inputs = LAYER_SIZES[l];
+        layer.outputs = LAYER_SIZES[l + 1];
+        float scale = 2.0f / std::sqrt(static_cast<float>(layer.inputs));
+
+        layer.weights.resize(layer.inputs * layer.outputs);
+        for (auto& w : layer.weights) {
+            w = next_random(seed) * scale;
+        }
+        layer.bias.resize(layer.outputs);
+        for (auto& b : layer.bias) {
+            b = next_random(seed) * 0.1f;
+        }
+        network.push_back(std::move(layer));
+    }
+
+    return network;
+}
+
+std::vector<std::vector<float>> build_inputs() {
+    uint64_t seed = 7;
+    std::vector<std::vector<float>> inputs(INPUT_POOL, std::vector<float>(BATCH_SIZE * LAYER_SIZES[0]));
+
+    for (auto& batch : inputs) {
+        for (auto& v : batch) {
+            v = next_random(seed) + 0.5f;
+        }
+    }
+
+    return inputs;
+}
+
+// i-k-j order so the innermost loop is a contiguous axpy both compilers vectorize
+void dense_plain(const Layer& layer, const std::vector<float>& input, std::vector<float>& output) {
+    for (size_t i = 0; i < BATCH_SIZE; i++) {
+        const float* x = &input[i * layer.inputs];
+        float* y = &output[i * layer.outputs];
+        std::copy(layer.bias.begin(), layer.bias.end(), y);
+
+        for (size_t k = 0; k < layer.inputs; k++) {
+            const float xk = x[k];
+            const float* w = &layer.weights[k * layer.outputs];
+            for (size_t j = 0; j < layer.outputs; j++) {
+                y[j] += xk * w[j];
+            }
+        }
+    }
+}
+
+double infer_batch(const std::vector<Layer>& network, const std::vector<float>& input) {
+    std::vector<float> activations = input;
+
+    for (size_t l = 0; l < network.size(); l++) {
+        const Layer& layer = network[l];
+        std::vector<float> output(BATCH_SIZE * layer.outputs, 0.0f);
+        dense_plain(layer, activations, output);
+
+        // ReLU on every hidden layer, raw logits out of the last one
+        if (l + 1 < network.size()) {
+            for (auto& v : output) {
+                v = std::max(v, 0.0f);
+            }
+        }
+        activations = std::move(output);
+    }
+
+    double sum = 0.0;
+    for (float v : activations) {
+        sum += static_cast<double>(v);
+    }
+    return sum;
+}
+
+void infer_section(const std::vector<Layer>& network, const std::vector<std::vector<float>>& inputs,
+                   std::vector<double>& per_batch, size_t start_batch, size_t end_batch) {
+    for (size_t b = start_batch; b < end_batch; b++) {
+        per_batch[b] = infer_batch(network, inputs[b % INPUT_POOL]);
+    }
+}
+
+double run_inference(const std::vector<Layer>& network, const std::vector<std::vector<float>>& inputs) {
+    std::vector<double> per_batch(NUM_BATCHES, 0.0);
+    std::vector<std::thread> threads;
+    size_t batches_per_thread = NUM_BATCHES / NUM_THREADS;
+
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        size_t start_batch = t * batches_per_thread;
+        size_t end_batch = (t == NUM_THREADS - 1) ? NUM_BATCHES : (t + 1) * batches_per_thread;
+        threads.emplace_back(infer_section, std::ref(network), std::ref(inputs), std::ref(per_batch),
+                             start_batch, end_batch);
+    }
+
+    for (auto& thread : threads) {
+        thread.join();
+    }
+
+    // Summed in batch order so the checksum doesn't depend on scheduling
+    double checksum = 0.0;
+    for (double v : per_batch) {
+        checksum += v;
+    }
+    return checksum;
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    auto network = build_network();
+    auto inputs = build_inputs();
+
+    size_t flops_per_batch = 0;
+    for (const auto& layer : network) {
+        flops_per_batch += 2 * BATCH_SIZE * layer.inputs * layer.outputs;
+    }
+    double total_gflops = static_cast<double>(flops_per_batch * NUM_BATCHES) / 1e9;
+
+    // Warm-up
+    infer_batch(network, inputs[0]);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    double checksum = run_inference(network, inputs);
+    measure->stop();
+    auto end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> duration = end - start;
+
+    measure->report();
+    std::cerr << std::fixed << "Plain loops: " << duration.count() << "s (" << std::setprecision(2)
+              << total_gflops / duration.count() << " GFLOP/s)" << std::endl;
+    std::cerr << "Checksum: " << std::setprecision(6) << checksum << std::endl;
+
+    return 0;
+}
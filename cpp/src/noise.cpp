@@ -0,0 +1,146 @@
+#include <iostream>
+#include <vector>
+#include <array>
+#include <thread>
+#include <cmath>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t SIZE = 8192;
+const uint32_t OCTAVES = 6;
+const float BASE_FREQUENCY = 1.0f / 512.0f;
+const float LACUNARITY = 2.0f;
+const float GAIN = 0.5f;
+const size_t NUM_THREADS = 8;
+
+// The 8 gradient directions of 2D improved Perlin noise
+const float GRADIENTS[8][2] = {
+    {1.0f, 1.0f}, {-1.0f, 1.0f}, {1.0f, -1.0f}, {-1.0f, -1.0f},
+    {1.0f, 0.0f}, {-1.0f, 0.0f}, {0.0f, 1.0f}, {0.0f, -1.0f},
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+using Permutation = std::array<uint8_t, 512>;
+
+// Shuffled 0..256, duplicated so lookups never need a wrap
+Permutation build_permutation() {
+    uint64_t seed = 2024;
+    std::array<uint8_t, 256> p;
+    for (size_t i = 0; i < 256; i++) p[i] = static_cast<uint8_t>(i);
+    for (size_t i = 255; i >= 1; i--) {
+        size_t j = next_random(seed) % (i + 1);
+        std::swap(p[i], p[j]);
+    }
+    Permutation perm;
+    for (size_t i = 0; i < 512; i++) perm[i] = p[i & 255];
+    return perm;
+}
+
+inline float fade(float t) {
+    return t * t * t * (t * (t * 6.0f - 15.0f) + 10.0f);
+}
+
+inline float lerp(float a, float b, float t) {
+    return a + t * (b - a);
+}
+
+inline float grad(uint8_t hash, float x, float y) {
+    const float* g = GRADIENTS[hash & 7];
+    return g[0] * x + g[1] * y;
+}
+
+float perlin(const Permutation& perm, float x, float y) {
+    float x0 = std::floor(x);
+    float y0 = std::floor(y);
+    size_t xi = static_cast<size_t>(static_cast<int32_t>(x0) & 255);
+    size_t yi = static_cast<size_t>(static_cast<int32_t>(y0) & 255);
+    float xf = x - x0;
+    float yf = y - y0;
+    float u = fade(xf);
+    float v = fade(yf);
+
+    size_t a = perm[xi] + yi;
+    size_t b = perm[xi + 1] + yi;
+    float n00 = grad(perm[a], xf, yf);
+    float n01 = grad(perm[a + 1], xf, yf - 1.0f);
+    float n10 = grad(perm[b], xf - 1.0f, yf);
+    float n11 = grad(perm[b + 1], xf - 1.0f, yf - 1.0f);
+
+    return lerp(lerp(n00, n10, u), lerp(n01, n11, u), v);
+}
+
+// Fractal Brownian motion: each octave doubles the frequency and halves the amplitude
+float fbm(const Permutation& perm, float x, float y) {
+    float sum = 0.0f;
+    float amplitude = 1.0f;
+    float frequency = BASE_FREQUENCY;
+    for (uint32_t o = 0; o < OCTAVES; o++) {
+        sum += amplitude * perlin(perm, x * frequency, y * frequency);
+        frequency *= LACUNARITY;
+        amplitude *= GAIN;
+    }
+    return sum;
+}
+
+void fill_rows(const Permutation& perm, std::vector<float>& heightmap, size_t start_row, size_t end_row) {
+    for (size_t y = start_row; y < end_row; y++) {
+        for (size_t x = 0; x < SIZE; x++) {
+            heightmap[y * SIZE + x] = fbm(perm, static_cast<float>(x), static_cast<float>(y));
+        }
+    }
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    Permutation perm = build_permutation();
+    std::vector<float> heightmap(SIZE * SIZE);
+
+    // Warm-up
+    fill_rows(perm, heightmap, 0, 10);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+
+    std::vector<std::thread> threads;
+    size_t rows_per_thread = SIZE / NUM_THREADS;
+
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        size_t start_row = t * rows_per_thread;
+        size_t end_row = (t == NUM_THREADS - 1) ? SIZE : (t + 1) * rows_per_thread;
+        threads.emplace_back(fill_rows, std::cref(perm), std::ref(heightmap), start_row, end_row);
+    }
+
+    for (auto& thread : threads) {
+        thread.join();
+    }
+
+    measure->stop();
+    auto end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> duration = end - start;
+
+    // Checksum
+    double sum = 0.0;
+    float min = heightmap[0], max = heightmap[0];
+    for (float h : heightmap) {
+        sum += h;
+        min = std::min(min, h);
+        max = std::max(max, h);
+    }
+
+    measure->report();
+    std::cerr << std::fixed << std::setprecision(0) << "Samples: " << (SIZE * SIZE) / duration.count() / 1e6
+              << " M/s (" << OCTAVES << " octaves), height range [" << std::setprecision(4) << min
+              << ", " << max << "]" << std::endl;
+    std::cerr << std::setprecision(6) << "Checksum: " << sum << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,156 @@
+// NUMA placement for the memory-bound benchmarks, the same flags as rust/bench_numa:
+//
+//     --numa-node N         run every thread on node N's CPUs and allocate from its memory
+//     --numa-memory-node M  allocate from node M instead (with --numa-node, measures remote access)
+//     --numa-interleave     spread pages round-robin over every node
+//
+// numa_place() must run first thing in main, before any thread or large allocation exists: the
+// CPU mask and memory policy it sets are inherited by every thread created afterwards. The
+// placement goes through libnuma, compiled in as HAVE_NUMA when CMake finds it; the topology is
+// read from sysfs and reported by every build.
+#pragma once
+
+#include <algorithm>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+#include <filesystem>
+#include <fstream>
+#include <iostream>
+#include <sstream>
+#include <string>
+#include <vector>
+#ifdef HAVE_NUMA
+#include <numa.h>
+#endif
+
+enum class NumaMemory { FirstTouch, Node, Interleave };
+
+struct NumaPlacement {
+    int cpu_node = -1; // -1 = unpinned
+    NumaMemory memory = NumaMemory::FirstTouch;
+    int memory_node = -1;
+
+    std::string describe() const {
+        std::string cpus = cpu_node >= 0 ? "CPUs on node " + std::to_string(cpu_node) : "CPUs unpinned";
+        switch (memory) {
+            case NumaMemory::FirstTouch: return cpus + ", memory first-touch";
+            case NumaMemory::Node: return cpus + ", memory on node " + std::to_string(memory_node);
+            case NumaMemory::Interleave: return cpus + ", memory interleaved";
+        }
+        return cpus;
+    }
+};
+
+inline int numa_node_flag(int argc, char** argv, const char* name) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], name) == 0) {
+            char* end = nullptr;
+            long node = i + 1 < argc ? std::strtol(argv[i + 1], &end, 10) : -1;
+            if (i + 1 >= argc || *end != '\0' || end == argv[i + 1] || node < 0) {
+                std::cerr << name << " expects a node number" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<int>(node);
+        }
+    }
+    return -1;
+}
+
+inline NumaPlacement numa_parse(int argc, char** argv) {
+    NumaPlacement placement;
+    placement.cpu_node = numa_node_flag(argc, argv, "--numa-node");
+    int memory_node = numa_node_flag(argc, argv, "--numa-memory-node");
+    bool interleave = std::any_of(argv + 1, argv + argc, [](const char* a) { return std::strcmp(a, "--numa-interleave") == 0; });
+    if (interleave && memory_node >= 0) {
+        std::cerr << "--numa-memory-node and --numa-interleave are exclusive" << std::endl;
+        std::exit(1);
+    }
+    if (memory_node < 0) memory_node = placement.cpu_node;
+    if (interleave) {
+        placement.memory = NumaMemory::Interleave;
+    } else if (memory_node >= 0) {
+        placement.memory = NumaMemory::Node;
+        placement.memory_node = memory_node;
+    }
+    return placement;
+}
+
+// Parses the NUMA flags and applies them to the calling thread, which every thread it starts
+// later inherits
+inline NumaPlacement numa_place(int argc, char** argv) {
+    NumaPlacement placement = numa_parse(argc, argv);
+    if (placement.cpu_node < 0 && placement.memory == NumaMemory::FirstTouch) return placement;
+#ifdef HAVE_NUMA
+    if (numa_available() < 0) {
+        std::cerr << "libnuma reports no NUMA support on this system" << std::endl;
+        std::exit(1);
+    }
+    int max_node = numa_max_node();
+    for (int node : {placement.cpu_node, placement.memory_node}) {
+        if (node > max_node) {
+            std::cerr << "NUMA node " << node << " does not exist (nodes 0-" << max_node << ")" << std::endl;
+            std::exit(1);
+        }
+    }
+    if (placement.cpu_node >= 0 && numa_run_on_node(placement.cpu_node) != 0) {
+        std::cerr << "numa_run_on_node(" << placement.cpu_node << ") failed" << std::endl;
+        std::exit(1);
+    }
+    if (placement.memory == NumaMemory::Node) {
+        // Strict: allocations fail rather than spill onto another node
+        struct bitmask* mask = numa_allocate_nodemask();
+        numa_bitmask_setbit(mask, placement.memory_node);
+        numa_set_membind(mask);
+        numa_bitmask_free(mask);
+    } else if (placement.memory == NumaMemory::Interleave) {
+        numa_set_interleave_mask(numa_all_nodes_ptr);
+    }
+#else
+    std::cerr << "--numa-node, --numa-memory-node and --numa-interleave need a build with libnuma (apt install libnuma-dev)" << std::endl;
+    std::exit(1);
+#endif
+    return placement;
+}
+
+// "2 nodes (0: CPUs 0-15, 64.0 GB; 1: CPUs 16-31, 64.0 GB)" from sysfs, as bench_numa reports it
+inline std::string numa_topology() {
+    namespace fs = std::filesystem;
+    std::error_code error;
+    std::vector<std::pair<int, std::string>> nodes;
+    for (const auto& entry : fs::directory_iterator("/sys/devices/system/node", error)) {
+        std::string name = entry.path().filename().string();
+        if (name.rfind("node", 0) != 0 || name.size() == 4 || name.find_first_not_of("0123456789", 4) != std::string::npos) continue;
+        std::string cpus;
+        std::ifstream(entry.path() / "cpulist") >> cpus;
+        // "Node 0 MemTotal:       65831300 kB"
+        std::ifstream meminfo(entry.path() / "meminfo");
+        std::string line;
+        unsigned long long kilobytes = 0;
+        while (std::getline(meminfo, line)) {
+            size_t at = line.find("MemTotal:");
+            if (at != std::string::npos) {
+                kilobytes = std::strtoull(line.c_str() + at + 9, nullptr, 10);
+                break;
+            }
+        }
+        char memory[32];
+        std::snprintf(memory, sizeof(memory), "%.1f GB", kilobytes * 1024.0 / 1e9);
+        int id = std::stoi(name.substr(4));
+        nodes.emplace_back(id, std::to_string(id) + ": CPUs " + cpus + ", " + memory);
+    }
+    if (nodes.empty()) return "topology not reported by this OS";
+    std::sort(nodes.begin(), nodes.end());
+    std::ostringstream out;
+    out << nodes.size() << (nodes.size() == 1 ? " node (" : " nodes (");
+    for (size_t i = 0; i < nodes.size(); i++) {
+        out << (i ? "; " : "") << nodes[i].second;
+    }
+    out << ")";
+    return out.str();
+}
+
+// The stderr line the NUMA-aware benchmarks print
+inline std::string numa_report(const NumaPlacement& placement) {
+    return "NUMA: " + numa_topology() + "; " + placement.describe();
+}
@@ -0,0 +1,290 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <string_view>
+#include <format>
+#include <charconv>
+#include <iterator>
+#include <chrono>
+#include <iomanip>
+#include <cmath>
+#include <cstdint>
+#include <cstdio>
+#include <cstring>
+
+const size_t NUM_VALUES = 5'000'000; // of each type
+const size_t PASSES = 10;            // so 50M integers and 50M floats per variant and direction
+const size_t MAX_DECIMALS = 6;
+const double POW10[MAX_DECIMALS + 1] = {1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6};
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t splitmix64(uint64_t& seed) {
+    seed += 0x9E3779B97F4A7C15ULL;
+    uint64_t z = seed;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+// Integers of 1 to 20 digits. Floats are m / 10^k with 1 <= k <= 6, m odd and under 10^10 with
+// at least one integer digit: the shortest round-trip text is then exactly that decimal in
+// fixed notation, in both languages, and the hand-rolled conversions below are exact
+void generate(size_t n, std::vector<uint64_t>& ints, std::vector<double>& floats) {
+    uint64_t seed = 7;
+    ints.resize(n);
+    floats.resize(n);
+    for (auto& v : ints) {
+        uint64_t x = splitmix64(seed);
+        v = x >> (splitmix64(seed) % 64);
+    }
+    for (auto& v : floats) {
+        size_t k = 1 + splitmix64(seed) % MAX_DECIMALS;
+        uint64_t low = 1;
+        for (size_t i = 0; i < k; i++) low *= 10;
+        uint64_t m = (low + splitmix64(seed) % (10'000'000'000ULL - low)) | 1;
+        v = static_cast<double>(m) / POW10[k];
+    }
+}
+
+uint64_t bits_of(double v) {
+    uint64_t bits;
+    std::memcpy(&bits, &v, sizeof(bits));
+    return bits;
+}
+
+// Parsers take newline-terminated text and return the wrapping sum of the values (of the bit
+// patterns, for floats)
+
+uint64_t parse_ints_std(std::string_view text) {
+    uint64_t sum = 0;
+    const char* end = text.data() + text.size();
+    for (const char* p = text.data(); p < end;) {
+        uint64_t v = 0;
+        p = std::from_chars(p, end, v).ptr + 1;
+        sum += v;
+    }
+    return sum;
+}
+
+uint64_t parse_floats_std(std::string_view text) {
+    uint64_t sum = 0;
+    const char* end = text.data() + text.size();
+    for (const char* p = text.data(); p < end;) {
+        double v = 0;
+        p = std::from_chars(p, end, v).ptr + 1;
+        sum += bits_of(v);
+    }
+    return sum;
+}
+
+// No validation: one pass over the bytes
+uint64_t parse_ints_hand(std::string_view text) {
+    uint64_t sum = 0, value = 0;
+    for (char c : text) {
+        if (c == '\n') {
+            sum += value;
+            value = 0;
+        } else {
+            value = value * 10 + static_cast<uint64_t>(c - '0');
+        }
+    }
+    return sum;
+}
+
+// The mantissa stays under 2^53 and the divisor is an exact power of ten, so one division
+// rounds correctly (the fast path of every real decimal-to-float parser)
+uint64_t parse_floats_hand(std::string_view text) {
+    uint64_t sum = 0, mantissa = 0;
+    size_t decimals = 0;
+    bool seen_point = false;
+    for (char c : text) {
+        if (c == '\n') {
+            sum += bits_of(static_cast<double>(mantissa) / POW10[decimals]);
+            mantissa = 0, decimals = 0, seen_point = false;
+        } else if (c == '.') {
+            seen_point = true;
+        } else {
+            mantissa = mantissa * 10 + static_cast<uint64_t>(c - '0');
+            decimals += seen_point;
+        }
+    }
+    return sum;
+}
+
+// Formatters append each value and a newline to `out`
+
+template <typename T>
+void format_std(const std::vector<T>& values, std::string& out) {
+    auto it = std::back_inserter(out);
+    for (T v : values) it = std::format_to(it, "{}\n", v);
+}
+
+template <typename T>
+void format_to_chars(const std::vector<T>& values, std::string& out) {
+    char buf[32];
+    for (T v : values) {
+        char* end = std::to_chars(buf, buf + sizeof(buf), v).ptr;
+        *end++ = '\n';
+        out.append(buf, end);
+    }
+}
+
+void push_digits(uint64_t v, std::string& out) {
+    char digits[20];
+    size_t i = sizeof(digits);
+    do {
+        digits[--i] = static_cast<char>('0' + v % 10);
+        v /= 10;
+    } while (v != 0);
+    out.append(digits + i, digits + sizeof(digits));
+}
+
+void format_ints_hand(const std::vector<uint64_t>& values, std::string& out) {
+    for (uint64_t v : values) {
+        push_digits(v, out);
+        out.push_back('\n');
+    }
+}
+
+// Fixed point at MAX_DECIMALS with trailing zeros dropped: only the shortest form for values
+// like these, the shortcut a pipeline takes when it knows its data
+void format_floats_hand(const std::vector<double>& values, std::string& out) {
+    const uint64_t scale = static_cast<uint64_t>(POW10[MAX_DECIMALS]);
+    for (double v : values) {
+        uint64_t fixed = static_cast<uint64_t>(std::round(v * POW10[MAX_DECIMALS]));
+        push_digits(fixed / scale, out);
+        uint64_t fraction = fixed % scale;
+        if (fraction != 0) {
+            size_t width = MAX_DECIMALS;
+            while (fraction % 10 == 0) {
+                fraction /= 10;
+                width--;
+            }
+            char digits[MAX_DECIMALS];
+            for (size_t i = width; i-- > 0;) {
+                digits[i] = static_cast<char>('0' + fraction % 10);
+                fraction /= 10;
+            }
+            out.push_back('.');
+            out.append(digits, digits + width);
+        }
+        out.push_back('\n');
+    }
+}
+
+using ParseFn = uint64_t (*)(std::string_view);
+template <typename T>
+using FormatFn = void (*)(const std::vector<T>&, std::string&);
+
+struct Parser {
+    const char* name;
+    ParseFn parse;
+};
+
+template <typename T>
+struct Formatter {
+    const char* name;
+    FormatFn<T> format;
+};
+
+const Parser INT_PARSERS[] = {{"from_chars", parse_ints_std}, {"hand-rolled", parse_ints_hand}};
+const Parser FLOAT_PARSERS[] = {{"from_chars", parse_floats_std}, {"hand-rolled", parse_floats_hand}};
+const Formatter<uint64_t> INT_FORMATTERS[] = {
+    {"std::format", format_std<uint64_t>}, {"to_chars", format_to_chars<uint64_t>}, {"hand-rolled", format_ints_hand}};
+const Formatter<double> FLOAT_FORMATTERS[] = {
+    {"std::format", format_std<double>}, {"to_chars", format_to_chars<double>}, {"hand-rolled", format_floats_hand}};
+
+// The barrier on the input every pass stops the compiler computing one pass and multiplying
+uint64_t run_parse(ParseFn parse, const std::string& text, size_t passes, Duration& duration) {
+    uint64_t sum = 0;
+    auto start = Clock::now();
+    for (size_t p = 0; p < passes; p++) {
+        asm volatile("" : : "r"(text.data()) : "memory");
+        sum += parse(text);
+    }
+    duration = Clock::now() - start;
+    return sum;
+}
+
+// The output buffer is reused, so only the first pass grows it
+template <typename T>
+Duration run_format(FormatFn<T> format, const std::vector<T>& values, std::string& out, size_t passes) {
+    auto start = Clock::now();
+    for (size_t p = 0; p < passes; p++) {
+        out.clear();
+        asm volatile("" : : "r"(values.data()) : "memory");
+        format(values, out);
+    }
+    return Clock::now() - start;
+}
+
+struct Line {
+    const char* what;
+    const char* variant;
+    Duration duration;
+};
+
+int main() {
+    std::vector<uint64_t> ints;
+    std::vector<double> floats;
+    generate(NUM_VALUES, ints, floats);
+    std::string int_text, float_text, out;
+    format_std(ints, int_text);
+    format_std(floats, float_text);
+    out.reserve(NUM_VALUES * 21);
+
+    Duration total_duration(0);
+    std::vector<Line> lines;
+    bool all_match = true;
+    uint64_t checksum = 0;
+
+    // Benchmark parsing (each variant gets one untimed warm-up pass)
+    struct ParseCase {
+        const char* what;
+        const Parser* parsers;
+        const std::string* text;
+    };
+    for (const ParseCase& c : {ParseCase{"parse u64", INT_PARSERS, &int_text}, ParseCase{"parse f64", FLOAT_PARSERS, &float_text}}) {
+        uint64_t expected = c.parsers[0].parse(*c.text);
+        checksum += expected;
+        for (size_t i = 0; i < 2; i++) {
+            c.parsers[i].parse(*c.text);
+            Duration duration;
+            uint64_t sum = run_parse(c.parsers[i].parse, *c.text, PASSES, duration);
+            all_match &= sum == expected * PASSES;
+            total_duration += duration;
+            lines.push_back({c.what, c.parsers[i].name, duration});
+        }
+    }
+
+    // Benchmark formatting; every variant has to reproduce the parsed text byte for byte
+    std::vector<uint64_t> int_warmup(ints.begin(), ints.begin() + NUM_VALUES / 100);
+    for (const auto& f : INT_FORMATTERS) {
+        f.format(int_warmup, out);
+        Duration duration = run_format(f.format, ints, out, PASSES);
+        all_match &= out == int_text;
+        total_duration += duration;
+        lines.push_back({"format u64", f.name, duration});
+    }
+    std::vector<double> float_warmup(floats.begin(), floats.begin() + NUM_VALUES / 100);
+    for (const auto& f : FLOAT_FORMATTERS) {
+        f.format(float_warmup, out);
+        Duration duration = run_format(f.format, floats, out, PASSES);
+        all_match &= out == float_text;
+        total_duration += duration;
+        lines.push_back({"format f64", f.name, duration});
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Values: %zu u64 (1-20 digits) and %zu f64 (1-%zu decimals) x %zu passes per variant (%.0f MB of text)\n",
+            NUM_VALUES, NUM_VALUES, MAX_DECIMALS, PASSES, (int_text.size() + float_text.size()) / 1e6);
+    for (const Line& l : lines) {
+        double rate = static_cast<double>(NUM_VALUES * PASSES) / l.duration.count();
+        fprintf(stderr, "  %-14s %-12s %.3fs (%.1f M/s)\n", l.what, l.variant, l.duration.count(), rate / 1e6);
+    }
+    std::cerr << "Checksum: " << checksum << " (all variants match: " << (all_match ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,193 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <memory>
+#include <memory_resource>
+#include <new>
+#include <utility>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+
+const size_t NUM_THREADS = 8;
+const size_t LIVE = 10'000; // long-lived entities per thread
+const size_t FRAMES = 2'000;
+const size_t CHURN = 500;   // long-lived entities replaced per frame
+const size_t BURST = 2'000; // short-lived entities spawned and released per frame
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t splitmix64(uint64_t& seed) {
+    seed += 0x9E3779B97F4A7C15ULL;
+    uint64_t z = seed;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+// One cache line, like a game server's entity or a connection's session state
+struct Entity {
+    uint64_t id;
+    uint32_t pos[4];
+    uint32_t vel[4];
+    uint64_t payload[3];
+};
+
+Entity spawn(uint64_t r) {
+    uint32_t lo = static_cast<uint32_t>(r), hi = static_cast<uint32_t>(r >> 32);
+    return Entity{r, {lo, hi, lo ^ hi, lo + hi}, {hi >> 24, lo >> 24, 1, 2}, {r, ~r, (r << 17) | (r >> 47)}};
+}
+
+void step(Entity& e) {
+    for (size_t i = 0; i < 4; i++) e.pos[i] += e.vel[i];
+}
+
+uint64_t digest(const Entity& e) {
+    uint64_t acc = e.id ^ e.payload[2];
+    for (uint32_t p : e.pos) acc = acc * 31 + p;
+    return acc;
+}
+
+// Each thread owns its pool, as a game server's per-worker pools do; only the heap variant
+// shares one allocator between the threads
+
+// A heap allocation per entity, through the global allocator
+struct Heap {
+    using Handle = std::unique_ptr<Entity>;
+    Handle acquire(const Entity& e) { return std::make_unique<Entity>(e); }
+    void release(Handle h) { h.reset(); }
+    Entity& get(Handle& h) { return *h; }
+};
+
+// The standard library's pool: fixed-size blocks carved from chunks, one free list per block
+// size, no locking
+struct PmrPool {
+    using Handle = Entity*;
+    std::pmr::unsynchronized_pool_resource resource;
+    Handle acquire(const Entity& e) { return new (resource.allocate(sizeof(Entity), alignof(Entity))) Entity(e); }
+    void release(Handle h) { resource.deallocate(h, sizeof(Entity), alignof(Entity)); }
+    Entity& get(Handle& h) { return *h; }
+};
+
+// Hand-rolled: the entities in one vector and a stack of free indices beside it. A released
+// slot keeps its stale entity until the next acquire overwrites it
+struct FreeList {
+    using Handle = uint32_t;
+    std::vector<Entity> objects;
+    std::vector<uint32_t> free;
+    Handle acquire(const Entity& e) {
+        if (!free.empty()) {
+            uint32_t i = free.back();
+            free.pop_back();
+            objects[i] = e;
+            return i;
+        }
+        objects.push_back(e);
+        return static_cast<uint32_t>(objects.size() - 1);
+    }
+    void release(Handle h) { free.push_back(h); }
+    Entity& get(Handle& h) { return objects[h]; }
+};
+
+// One thread's frames: replace CHURN random long-lived entities, spawn a burst of short-lived
+// ones and release them in spawn order, then step every long-lived entity. Released entities
+// are digested on the way out
+template <typename P>
+uint64_t churn(uint64_t thread, size_t frames) {
+    uint64_t seed = 0x600D + thread;
+    asm volatile("" : "+r"(seed));
+    P pool;
+    std::vector<typename P::Handle> live;
+    live.reserve(LIVE);
+    for (size_t i = 0; i < LIVE; i++) live.push_back(pool.acquire(spawn(splitmix64(seed))));
+    std::vector<typename P::Handle> burst;
+    burst.reserve(BURST);
+    uint64_t checksum = 0;
+    for (size_t frame = 0; frame < frames; frame++) {
+        for (size_t i = 0; i < CHURN; i++) {
+            uint64_t r = splitmix64(seed);
+            typename P::Handle fresh = pool.acquire(spawn(r));
+            typename P::Handle old = std::exchange(live[r % LIVE], std::move(fresh));
+            checksum += digest(pool.get(old));
+            pool.release(std::move(old));
+        }
+        for (size_t i = 0; i < BURST; i++) {
+            typename P::Handle h = pool.acquire(spawn(splitmix64(seed)));
+            step(pool.get(h));
+            burst.push_back(std::move(h));
+        }
+        for (auto& h : burst) {
+            checksum += digest(pool.get(h));
+            pool.release(std::move(h));
+        }
+        burst.clear();
+        for (auto& h : live) step(pool.get(h));
+    }
+    for (auto& h : live) {
+        checksum += digest(pool.get(h));
+        pool.release(std::move(h));
+    }
+    return checksum;
+}
+
+struct Run {
+    Duration duration;
+    uint64_t checksum;
+};
+
+template <typename P>
+Run run(size_t frames) {
+    auto start = Clock::now();
+    std::vector<uint64_t> checksums(NUM_THREADS);
+    std::vector<std::thread> threads;
+    for (uint64_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&checksums, t, frames] { checksums[t] = churn<P>(t, frames); });
+    }
+    uint64_t checksum = 0;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads[t].join();
+        checksum += checksums[t];
+    }
+    return {Clock::now() - start, checksum};
+}
+
+struct NamedVariant {
+    const char* name;
+    Run (*run)(size_t);
+};
+
+const NamedVariant VARIANTS[] = {
+    {"unique_ptr", run<Heap>},
+    {"pmr pool", run<PmrPool>},
+    {"free list", run<FreeList>},
+};
+
+int main() {
+    // Warm-up
+    for (const auto& v : VARIANTS) v.run(FRAMES / 100);
+
+    // Benchmark
+    std::vector<Run> runs;
+    Duration total_duration(0);
+    for (const auto& v : VARIANTS) {
+        runs.push_back(v.run(FRAMES));
+        total_duration += runs.back().duration;
+    }
+    bool all_match = true;
+    for (const Run& r : runs) all_match &= r.checksum == runs[0].checksum;
+    size_t pairs = NUM_THREADS * (LIVE + FRAMES * (CHURN + BURST));
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Threads: %zu, each %zu live entities of %zu bytes; per frame %zu replaced and %zu short-lived, %zu frames\n",
+            NUM_THREADS, LIVE, sizeof(Entity), CHURN, BURST, FRAMES);
+    for (size_t i = 0; i < runs.size(); i++) {
+        fprintf(stderr, "  %-10s %.3fs (%.1fM acquire/release pairs/s)\n", VARIANTS[i].name, runs[i].duration.count(),
+                pairs / runs[i].duration.count() / 1e6);
+    }
+    std::cerr << "Checksum: " << runs[0].checksum << " (all variants match: " << (all_match ? "true" : "false") << ")"
+              << std::endl;
+
+    return 0;
+}
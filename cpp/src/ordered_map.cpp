@@ -0,0 +1,177 @@
+#include <iostream>
+#include <vector>
+#include <map>
+#include <unordered_map>
+#include <algorithm>
+#include <string>
+#include <utility>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#ifdef HAVE_ABSL
+#include <absl/container/btree_map.h>
+#endif
+
+const size_t ENTRIES = 5'000'000;                 // inserts, so slightly fewer distinct keys
+const uint64_t KEY_SPACE = 4 * uint64_t(ENTRIES); // about a fifth of it ends up present
+const size_t LOOKUPS = 2'000'000;
+const size_t SCANS = 100'000;
+const uint64_t SCAN_WIDTH = 100; // keys per range, about 20 of them present
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t splitmix64(uint64_t& seed) {
+    seed += 0x9E3779B97F4A7C15ULL;
+    uint64_t z = seed;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+// Order-sensitive, so iteration out of key order fails the checksum
+uint64_t mix(uint64_t acc, uint64_t key, uint64_t value) {
+    return acc * 0x100000001b3ULL + (key ^ value);
+}
+
+struct Workload {
+    std::vector<uint64_t> inserts; // keys in insertion order; a repeated key overwrites its value
+    std::vector<uint64_t> lookups;
+    std::vector<uint64_t> scans; // first key of each range
+};
+
+Workload generate(size_t entries, uint64_t key_space, size_t lookups, size_t scans) {
+    uint64_t seed = 1972; // Bayer and McCreight's B-tree paper
+    Workload w;
+    for (size_t i = 0; i < entries; i++) w.inserts.push_back(splitmix64(seed) % key_space);
+    for (size_t i = 0; i < lookups; i++) w.lookups.push_back(splitmix64(seed) % key_space);
+    for (size_t i = 0; i < scans; i++) w.scans.push_back(splitmix64(seed) % (key_space - SCAN_WIDTH));
+    return w;
+}
+
+// The ordered maps walk a range from lower_bound and iterate in place. A hash map has no order
+// to use: its range scans probe every key in the range, and its ordered iteration sorts a copy
+// of the entries
+template <typename Map>
+constexpr bool ORDERED = requires(const Map& m, uint64_t k) { m.lower_bound(k); };
+
+template <typename Map>
+uint64_t scan(const Map& map, uint64_t first) {
+    uint64_t acc = 0;
+    if constexpr (ORDERED<Map>) {
+        for (auto it = map.lower_bound(first); it != map.end() && it->first < first + SCAN_WIDTH; ++it) {
+            acc = mix(acc, it->first, it->second);
+        }
+    } else {
+        for (uint64_t k = first; k < first + SCAN_WIDTH; k++) {
+            auto it = map.find(k);
+            if (it != map.end()) acc = mix(acc, k, it->second);
+        }
+    }
+    return acc;
+}
+
+template <typename Map>
+uint64_t iterate_in_order(const Map& map) {
+    uint64_t acc = 0;
+    if constexpr (ORDERED<Map>) {
+        for (const auto& [k, v] : map) acc = mix(acc, k, v);
+    } else {
+        std::vector<std::pair<uint64_t, uint64_t>> entries(map.begin(), map.end());
+        std::sort(entries.begin(), entries.end(), [](const auto& a, const auto& b) { return a.first < b.first; });
+        for (const auto& [k, v] : entries) acc = mix(acc, k, v);
+    }
+    return acc;
+}
+
+const char* const PHASES[] = {"insert", "lookup", "range scan", "in order"};
+
+struct Run {
+    const char* name;
+    Duration durations[4];
+    uint64_t results[4];
+};
+
+template <typename Map>
+Run run(const char* name, const Workload& w) {
+    Run r{name, {}, {}};
+    auto start = Clock::now();
+    Map map;
+    asm volatile("" : : "r"(w.inserts.data()) : "memory");
+    for (size_t i = 0; i < w.inserts.size(); i++) map.insert_or_assign(w.inserts[i], static_cast<uint64_t>(i));
+    auto insert_end = Clock::now();
+
+    uint64_t found = 0;
+    asm volatile("" : : "r"(w.lookups.data()) : "memory");
+    for (uint64_t key : w.lookups) {
+        auto it = map.find(key);
+        if (it != map.end()) found += it->second + 1;
+    }
+    auto lookup_end = Clock::now();
+
+    uint64_t scanned = 0;
+    asm volatile("" : : "r"(w.scans.data()) : "memory");
+    for (uint64_t first : w.scans) scanned += scan(map, first);
+    auto scan_end = Clock::now();
+
+    uint64_t iterated = iterate_in_order(map);
+    auto iterate_end = Clock::now();
+
+    r.durations[0] = insert_end - start;
+    r.durations[1] = lookup_end - insert_end;
+    r.durations[2] = scan_end - lookup_end;
+    r.durations[3] = iterate_end - scan_end;
+    r.results[0] = map.size();
+    r.results[1] = found;
+    r.results[2] = scanned;
+    r.results[3] = iterated;
+    return r;
+}
+
+std::vector<Run> run_all(const Workload& w) {
+    std::vector<Run> runs;
+    runs.push_back(run<std::map<uint64_t, uint64_t>>("std::map", w));
+#ifdef HAVE_ABSL
+    runs.push_back(run<absl::btree_map<uint64_t, uint64_t>>("absl::btree", w));
+#endif
+    runs.push_back(run<std::unordered_map<uint64_t, uint64_t>>("unordered", w));
+    return runs;
+}
+
+int main() {
+    Workload workload = generate(ENTRIES, KEY_SPACE, LOOKUPS, SCANS);
+
+    // Warm-up
+    run_all(generate(ENTRIES / 100, KEY_SPACE / 100, LOOKUPS / 100, SCANS / 100));
+
+    // Benchmark
+    std::vector<Run> runs = run_all(workload);
+    Duration total_duration(0);
+    bool all_match = true;
+    for (const Run& r : runs) {
+        for (size_t phase = 0; phase < 4; phase++) {
+            total_duration += r.durations[phase];
+            all_match &= r.results[phase] == runs[0].results[phase];
+        }
+    }
+    uint64_t checksum = 0;
+    for (uint64_t result : runs[0].results) checksum += result;
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Inserts: %zu over %llu keys; %zu lookups; %zu range scans of %llu keys\n", ENTRIES,
+            static_cast<unsigned long long>(KEY_SPACE), LOOKUPS, SCANS, static_cast<unsigned long long>(SCAN_WIDTH));
+    for (const Run& r : runs) {
+        fprintf(stderr, "  %-11s", r.name);
+        for (size_t phase = 0; phase < 4; phase++) {
+            fprintf(stderr, "%s%s %.3fs", phase == 0 ? " " : "  ", PHASES[phase], r.durations[phase].count());
+        }
+        fprintf(stderr, "\n");
+    }
+#ifndef HAVE_ABSL
+    fprintf(stderr, "  (absl::btree_map not built: Abseil was not found)\n");
+#endif
+    std::cerr << "Checksum: " << checksum << " (all maps match: " << (all_match ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
@@ -4,10 +4,74 @@
 #include <thread>
 #include <chrono>
 #include <iomanip>
+#include <atomic>
+#include <cstring>
+#include <cstdint>
+#include <cstdlib>
+#include <bit>
+#include "huge_pages.h"
+#include "numa_placement.h"
+#include "bench_measure.h"
 
 const size_t ARRAY_SIZE = 10'000'000;
 const size_t THRESHOLD = 10'000;
 
+enum class Distribution {
+    Lcg,        // the original (i * 1103515245 + 12345) mod 2^31 sequence
+    Uniform,    // splitmix64 draws in 0..2^31
+    Sorted,     // 0, 1, 2, ...
+    Reverse,    // n - 1, n - 2, ..., 0
+    Duplicates, // splitmix64 draws in 0..100
+    OrganPipe,  // ascending to the middle, then descending
+    Killer,     // Musser's median-of-3 killer sequence
+};
+
+const char* DISTRIBUTION_NAMES[] = {"lcg", "uniform", "sorted", "reverse", "duplicates", "organ-pipe", "killer"};
+
+uint64_t splitmix64(uint64_t& state) {
+    state += 0x9e3779b97f4a7c15ULL;
+    uint64_t z = state;
+    z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+    z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+    return z ^ (z >> 31);
+}
+
+std::vector<int> generate(Distribution distribution, size_t n) {
+    std::vector<int> data;
+    huge_reserve(data, n);
+    data.resize(n);
+    uint64_t state = 42;
+    for (size_t i = 0; i < n; i++) {
+        switch (distribution) {
+            case Distribution::Lcg: data[i] = static_cast<int>((i * 1103515245 + 12345) % 2147483648); break;
+            case Distribution::Uniform: data[i] = static_cast<int>(splitmix64(state) >> 33); break;
+            case Distribution::Sorted: data[i] = static_cast<int>(i); break;
+            case Distribution::Reverse: data[i] = static_cast<int>(n - 1 - i); break;
+            case Distribution::Duplicates: data[i] = static_cast<int>(splitmix64(state) % 100); break;
+            case Distribution::OrganPipe: data[i] = static_cast<int>(std::min(i, n - 1 - i)); break;
+            case Distribution::Killer: data[i] = static_cast<int>(n); break;
+        }
+    }
+    if (distribution == Distribution::Killer) {
+        // 1-based in Musser's paper: a[i] = i for odd i, k + i - 1 for even i, a[k + i] = 2i
+        size_t k = n / 2;
+        for (size_t i = 1; i <= k; i++) {
+            data[i - 1] = static_cast<int>(i % 2 == 1 ? i : k + i - 1);
+            data[k + i - 1] = static_cast<int>(2 * i);
+        }
+    }
+    return data;
+}
+
+// A last-element pivot goes quadratic on sorted, reversed, organ-pipe, killer and duplicate-heavy
+// input, and the recursion would exhaust threads and stack long before finishing. Past the depth
+// limit a subarray is heapsorted instead, as introsort does, and counted
+struct Stats {
+    size_t depth_limit;
+    std::atomic<size_t> max_depth{0};
+    std::atomic<size_t> heapsorted{0};
+};
+
 size_t partition(std::vector<int>& arr, size_t low, size_t high) {
     int pivot = arr[high];
     size_t i = low;
@@ -23,7 +87,7 @@ size_t partition(std::vector<int>& arr, size_t low, size_t high) {
     return i;
 }
 
-void parallel_quicksort(std::vector<int>& arr, size_t low, size_t high) {
+void parallel_quicksort(std::vector<int>& arr, size_t low, size_t high, size_t depth, Stats& stats) {
     if (low < high) {
         size_t len = high - low + 1;
         
@@ -32,46 +96,94 @@ void parallel_quicksort(std::vector<int>& arr, size_t low, size_t high) {
             return;
         }
         
+        size_t seen = stats.max_depth.load();
+        while (seen < depth && !stats.max_depth.compare_exchange_weak(seen, depth)) {}
+        if (depth >= stats.depth_limit) {
+            stats.heapsorted += len;
+            std::make_heap(arr.begin() + low, arr.begin() + high + 1);
+            std::sort_heap(arr.begin() + low, arr.begin() + high + 1);
+            return;
+        }
+        
         size_t pi = partition(arr, low, high);
         
         if (pi > 0) {
-            std::thread left_thread(parallel_quicksort, std::ref(arr), low, pi - 1);
+            std::thread left_thread(parallel_quicksort, std::ref(arr), low, pi - 1, depth + 1, std::ref(stats));
             if (pi + 1 <= high) {
-                parallel_quicksort(arr, pi + 1, high);
+                parallel_quicksort(arr, pi + 1, high, depth + 1, stats);
             }
             left_thread.join();
         } else {
             if (pi + 1 <= high) {
-                parallel_quicksort(arr, pi + 1, high);
+                parallel_quicksort(arr, pi + 1, high, depth + 1, stats);
             }
         }
     }
 }
 
-int main() {
-    // Generate data
-    std::vector<int> data(ARRAY_SIZE);
-    for (size_t i = 0; i < ARRAY_SIZE; i++) {
-        data[i] = static_cast<int>((i * 1103515245 + 12345) % 2147483648);
+Distribution parse_distribution(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--distribution") == 0) {
+            const char* value = i + 1 < argc ? argv[i + 1] : "";
+            for (size_t d = 0; d < std::size(DISTRIBUTION_NAMES); d++) {
+                if (std::strcmp(value, DISTRIBUTION_NAMES[d]) == 0) return static_cast<Distribution>(d);
+            }
+            std::cerr << "--distribution expects lcg, uniform, sorted, reverse, duplicates, organ-pipe or killer" << std::endl;
+            std::exit(1);
+        }
     }
+    return Distribution::Lcg;
+}
+
+int main(int argc, char** argv) {
+    auto measure = bench_measure_from_env();
+    NumaPlacement placement = numa_place(argc, argv);
+    huge_pages_enable(argc, argv);
+    Distribution distribution = parse_distribution(argc, argv);
+    
+    // Generate data
+    std::vector<int> data = generate(distribution, ARRAY_SIZE);
+    // Twice the depth of a perfectly balanced recursion, as introsort uses
+    size_t depth_limit = 2 * (std::bit_width(ARRAY_SIZE) - 1);
     
     // Warm-up
-    std::vector<int> warmup = data;
-    parallel_quicksort(warmup, 0, warmup.size() - 1);
+    {
+        std::vector<int> warmup = data;
+        Stats warmup_stats{depth_limit};
+        parallel_quicksort(warmup, 0, warmup.size() - 1, 0, warmup_stats);
+    }
     
-    // Benchmark
-    auto start = std::chrono::high_resolution_clock::now();
-    parallel_quicksort(data, 0, data.size() - 1);
-    auto end = std::chrono::high_resolution_clock::now();
+    // The production sort on the same input, for a comparison row
+    double baseline;
+    bool baseline_sorted;
+    {
+        std::vector<int> copy = data;
+        auto start = std::chrono::high_resolution_clock::now();
+        std::sort(copy.begin(), copy.end());
+        baseline = std::chrono::duration<double>(std::chrono::high_resolution_clock::now() - start).count();
+        baseline_sorted = std::is_sorted(copy.begin(), copy.end());
+    }
     
-    std::chrono::duration<double> duration = end - start;
+    // Benchmark
+    Stats stats{depth_limit};
+    measure->start();
+    parallel_quicksort(data, 0, data.size() - 1, 0, stats);
+    measure->stop();
     
     // Verify sort
     bool is_sorted = std::is_sorted(data.begin(), data.end());
     
-    std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
+    measure->report();
+    std::cerr << "Distribution: " << DISTRIBUTION_NAMES[static_cast<size_t>(distribution)] << std::endl;
+    std::cerr << "Max recursion depth: " << stats.max_depth.load() << " (limit " << depth_limit << ")" << std::endl;
+    if (stats.heapsorted.load() > 0) {
+        std::cerr << "Introsort fallback: " << stats.heapsorted.load()
+                  << " elements heapsorted at the depth limit (quadratic partitioning)" << std::endl;
+    }
+    std::cerr << std::fixed << std::setprecision(6) << "std::sort (introsort): " << baseline << "s (sorted: " << std::boolalpha << baseline_sorted << ")" << std::endl;
+    std::cerr << numa_report(placement) << std::endl;
+    std::cerr << huge_pages_report() << std::endl;
     std::cerr << "Sorted: " << std::boolalpha << is_sorted << std::endl;
     
     return 0;
 }
-
@@ -0,0 +1,286 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <deque>
+#include <optional>
+#include <mutex>
+#include <condition_variable>
+#include <thread>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <stdexcept>
+#include <zlib.h>
+
+const size_t BLOCK_BYTES = 64 << 10;
+const uint64_t BLOCKS = 1'024; // 64 MiB through the pipeline per run
+const size_t QUEUE_DEPTHS[] = {1, 4, 16, 64}; // blocks each queue holds before its producer blocks
+const int COMPRESSION_LEVEL = 1;
+const size_t VOCABULARY_SIZE = 512;
+const char* STAGES[] = {"generate", "transform", "compress", "hash"};
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+using Bytes = std::vector<uint8_t>;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+std::vector<std::string> vocabulary() {
+    uint64_t seed = 7;
+    std::vector<std::string> words;
+    for (size_t i = 0; i < VOCABULARY_SIZE; i++) {
+        uint64_t len = 3 + next_random(seed) % 7;
+        std::string word;
+        for (uint64_t j = 0; j < len; j++) word.push_back(static_cast<char>('a' + next_random(seed) % 26));
+        words.push_back(word);
+    }
+    return words;
+}
+
+void push_decimal(Bytes& out, uint64_t n) {
+    uint8_t digits[20];
+    size_t i = sizeof(digits);
+    do {
+        digits[--i] = static_cast<uint8_t>('0' + n % 10);
+        n /= 10;
+    } while (n != 0);
+    out.insert(out.end(), digits + i, digits + sizeof(digits));
+}
+
+// Stage 1: a block of log-like text, words with the odd number, seeded by its index
+Bytes generate(const std::vector<std::string>& vocabulary, uint64_t index) {
+    uint64_t seed = (index * 0x9E3779B97F4A7C15ULL) ^ 0xDEADBEEF;
+    Bytes out;
+    out.reserve(BLOCK_BYTES + 32);
+    while (out.size() < BLOCK_BYTES) {
+        uint64_t r = next_random(seed);
+        if (r % 8 == 0) {
+            push_decimal(out, next_random(seed) % 1'000'000);
+        } else {
+            const std::string& word = vocabulary[(r >> 3) % VOCABULARY_SIZE];
+            out.insert(out.end(), word.begin(), word.end());
+        }
+        out.push_back(r % 13 == 0 ? '\n' : ' ');
+    }
+    out.resize(BLOCK_BYTES);
+    return out;
+}
+
+// Stage 2: normalize in place, upper-casing letters and masking digits as a redaction pass would
+void transform(Bytes& data) {
+    for (uint8_t& b : data) {
+        b = b >= 'a' && b <= 'z' ? b - 32 : b >= '0' && b <= '9' ? '#' : b;
+    }
+}
+
+// Stage 3: zlib, as in deflate
+Bytes compress(const Bytes& data) {
+    uLongf len = compressBound(data.size());
+    Bytes out(len);
+    if (compress2(out.data(), &len, data.data(), data.size(), COMPRESSION_LEVEL) != Z_OK) {
+        throw std::runtime_error("zlib compress2 failed");
+    }
+    out.resize(len);
+    return out;
+}
+
+// Stage 4: FNV-1a 64 of the compressed block
+uint64_t hash(const Bytes& data) {
+    uint64_t h = 0xcbf29ce484222325ULL;
+    for (uint8_t b : data) h = (h ^ b) * 0x100000001b3ULL;
+    return h;
+}
+
+// What the last stage folds every block into, in order
+struct Digest {
+    uint64_t checksum = 0;
+    uint64_t compressed_bytes = 0;
+
+    void add(const Bytes& block) {
+        checksum = checksum * 31 + hash(block);
+        compressed_bytes += block.size();
+    }
+
+    bool operator==(const Digest& other) const {
+        return checksum == other.checksum && compressed_bytes == other.compressed_bytes;
+    }
+};
+
+// All four stages one block at a time on this thread
+Digest run_sequential(const std::vector<std::string>& vocabulary, uint64_t blocks) {
+    Digest digest;
+    for (uint64_t index = 0; index < blocks; index++) {
+        Bytes block = generate(vocabulary, index);
+        transform(block);
+        digest.add(compress(block));
+    }
+    return digest;
+}
+
+// Bounded blocking queue: push waits while it holds capacity items, pop returns nothing once it is
+// closed and drained
+template <typename T>
+class BoundedQueue {
+    std::mutex mutex;
+    std::condition_variable not_empty, not_full;
+    std::deque<T> items;
+    size_t capacity;
+    bool closed = false;
+
+public:
+    explicit BoundedQueue(size_t capacity) : capacity(capacity) {}
+
+    void push(T item) {
+        {
+            std::unique_lock<std::mutex> lock(mutex);
+            not_full.wait(lock, [&] { return items.size() < capacity; });
+            items.push_back(std::move(item));
+        }
+        not_empty.notify_one();
+    }
+
+    std::optional<T> pop() {
+        std::optional<T> item;
+        {
+            std::unique_lock<std::mutex> lock(mutex);
+            not_empty.wait(lock, [&] { return closed || !items.empty(); });
+            if (items.empty()) return std::nullopt;
+            item = std::move(items.front());
+            items.pop_front();
+        }
+        not_full.notify_one();
+        return item;
+    }
+
+    void close() {
+        {
+            std::lock_guard<std::mutex> lock(mutex);
+            closed = true;
+        }
+        not_empty.notify_all();
+    }
+};
+
+// Time spent inside a stage's work, as opposed to waiting on its queues
+template <typename F>
+auto busy(Duration& total, F f) {
+    auto start = Clock::now();
+    auto value = f();
+    total += Clock::now() - start;
+    return value;
+}
+
+struct Block {
+    uint64_t index;
+    Bytes data;
+};
+
+// A middle stage: pop, work, push on, until the stage before closes its queue
+template <typename F>
+void stage(BoundedQueue<Block>& inbox, BoundedQueue<Block>& outbox, Duration& total, F work) {
+    while (std::optional<Block> block = inbox.pop()) {
+        Bytes data = busy(total, [&] { return work(std::move(block->data)); });
+        outbox.push({block->index, std::move(data)});
+    }
+    outbox.close();
+}
+
+struct PipelineRun {
+    Digest digest;
+    Duration busy[4];
+};
+
+// One thread per stage, each pair joined by a queue holding up to depth blocks
+PipelineRun run_pipeline(const std::vector<std::string>& vocabulary, uint64_t blocks, size_t depth) {
+    BoundedQueue<Block> generated(depth), transformed(depth), compressed(depth);
+    PipelineRun run{};
+    std::thread generator([&] {
+        for (uint64_t index = 0; index < blocks; index++) {
+            Bytes data = busy(run.busy[0], [&] { return generate(vocabulary, index); });
+            generated.push({index, std::move(data)});
+        }
+        generated.close();
+    });
+    std::thread transformer([&] {
+        stage(generated, transformed, run.busy[1], [](Bytes data) {
+            transform(data);
+            return data;
+        });
+    });
+    std::thread compressor([&] { stage(transformed, compressed, run.busy[2], [](Bytes data) { return compress(data); }); });
+    std::thread hasher([&] {
+        uint64_t expected = 0;
+        while (std::optional<Block> block = compressed.pop()) {
+            if (block->index != expected++) {
+                std::cerr << "Blocks arrived out of order" << std::endl;
+                std::exit(1);
+            }
+            busy(run.busy[3], [&] {
+                run.digest.add(block->data);
+                return 0;
+            });
+        }
+    });
+    generator.join();
+    transformer.join();
+    compressor.join();
+    hasher.join();
+    return run;
+}
+
+int main() {
+    std::vector<std::string> words = vocabulary();
+
+    // Warm-up
+    run_pipeline(words, 32, 4);
+
+    auto start = Clock::now();
+    Digest expected = run_sequential(words, BLOCKS);
+    Duration sequential = Clock::now() - start;
+
+    struct Result {
+        size_t depth;
+        Duration wall;
+        PipelineRun run;
+    };
+    std::vector<Result> results;
+    for (size_t depth : QUEUE_DEPTHS) {
+        start = Clock::now();
+        PipelineRun run = run_pipeline(words, BLOCKS, depth);
+        Duration wall = Clock::now() - start;
+        if (!(run.digest == expected)) {
+            std::cerr << "Depth " << depth << ": pipeline checksum " << run.digest.checksum << " differs from sequential "
+                      << expected.checksum << std::endl;
+            return 1;
+        }
+        results.push_back({depth, wall, run});
+    }
+
+    double megabytes = BLOCKS * BLOCK_BYTES / 1e6;
+    Duration total{0};
+    for (const Result& r : results) total += r.wall;
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    char line[256];
+    std::snprintf(line, sizeof(line), "%llu blocks of %zu KiB, zlib level %d to %.1f MB (%.3f ratio)",
+                  static_cast<unsigned long long>(BLOCKS), BLOCK_BYTES >> 10, COMPRESSION_LEVEL,
+                  expected.compressed_bytes / 1e6, expected.compressed_bytes / 1e6 / megabytes);
+    std::cerr << line << std::endl;
+    std::snprintf(line, sizeof(line), "  sequential  %.3fs %7.1f MB/s", sequential.count(), megabytes / sequential.count());
+    std::cerr << line << std::endl;
+    for (const Result& r : results) {
+        int n = std::snprintf(line, sizeof(line), "  depth %3zu   %.3fs %7.1f MB/s (%.2fx)  busy:", r.depth, r.wall.count(),
+                              megabytes / r.wall.count(), sequential.count() / r.wall.count());
+        for (int s = 0; s < 4; s++) {
+            n += std::snprintf(line + n, sizeof(line) - n, "%s %s %.0f%%", s ? "," : "", STAGES[s],
+                               r.run.busy[s].count() / r.wall.count() * 100.0);
+        }
+        std::cerr << line << std::endl;
+    }
+    std::cerr << "Checksum: " << expected.checksum << std::endl;
+    return 0;
+}
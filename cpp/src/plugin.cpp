@@ -0,0 +1,22 @@
+// The C++ side of the plugin ABI in bench_plugin.h: built as the bench_plugin shared library that
+// plugin_call loads at runtime, and linked straight into plugin_call for its static variants
+#include "bench_plugin.h"
+
+uint64_t plugin_mix(uint64_t x) {
+    uint64_t z = x + 0x9E3779B97F4A7C15ULL;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+uint64_t plugin_mix_sum(const uint64_t* values, size_t len) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < len; i++) sum += plugin_mix(values[i]);
+    return sum;
+}
+
+static const BenchPlugin PLUGIN = {BENCH_PLUGIN_ABI_VERSION, plugin_mix, plugin_mix_sum};
+
+const BenchPlugin* plugin_entry(void) {
+    return &PLUGIN;
+}
@@ -0,0 +1,143 @@
+#include <iostream>
+#include <string>
+#include <vector>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <dlfcn.h>
+#include "bench_plugin.h"
+
+const uint64_t CALLS = 200'000'000; // per variant
+const size_t BATCH = 1024; // values per plugin_mix_sum call
+const size_t LOAD_CYCLES = 1'000;
+const uint64_t WARMUP_CALLS = 1'000'000;
+
+#ifdef __APPLE__
+const char* PLUGIN_FILE = "libbench_plugin.dylib";
+#else
+const char* PLUGIN_FILE = "libbench_plugin.so";
+#endif
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+using MixFn = uint64_t (*)(uint64_t);
+
+// One call per value: the sum of mix(i) for i in 0..calls
+template <typename Mix>
+uint64_t per_call(uint64_t calls, Mix mix) {
+    uint64_t sum = 0;
+    for (uint64_t i = 0; i < calls; i++) sum += mix(i);
+    return sum;
+}
+
+// The same sum, handing the plugin BATCH values per call
+uint64_t batched(uint64_t calls, const BenchPlugin* plugin) {
+    std::vector<uint64_t> values(BATCH);
+    uint64_t sum = 0;
+    for (uint64_t first = 0; first < calls; first += BATCH) {
+        size_t len = static_cast<size_t>(std::min<uint64_t>(BATCH, calls - first));
+        for (size_t j = 0; j < len; j++) values[j] = first + j;
+        sum += plugin->mix_sum(values.data(), len);
+    }
+    return sum;
+}
+
+void* open_plugin(const std::string& path) {
+    void* handle = dlopen(path.c_str(), RTLD_NOW | RTLD_LOCAL);
+    if (!handle) {
+        std::cerr << "Failed to load " << path << ": " << dlerror() << std::endl;
+        std::exit(1);
+    }
+    return handle;
+}
+
+template <typename T>
+T symbol(void* handle, const char* name) {
+    void* address = dlsym(handle, name);
+    if (!address) {
+        std::cerr << "Plugin has no " << name << std::endl;
+        std::exit(1);
+    }
+    return reinterpret_cast<T>(address);
+}
+
+const BenchPlugin* checked_entry(void* handle) {
+    const BenchPlugin* plugin = symbol<const BenchPlugin* (*)()>(handle, "plugin_entry")();
+    if (plugin->abi_version != BENCH_PLUGIN_ABI_VERSION) {
+        std::cerr << "Plugin ABI version " << plugin->abi_version << ", expected " << BENCH_PLUGIN_ABI_VERSION << std::endl;
+        std::exit(1);
+    }
+    return plugin;
+}
+
+struct Variant {
+    const char* name;
+    Duration time;
+    uint64_t sum;
+};
+
+template <typename F>
+Variant timed(const char* name, F run) {
+    auto start = Clock::now();
+    uint64_t sum = run();
+    return {name, Clock::now() - start, sum};
+}
+
+int main(int argc, char** argv) {
+    (void)argc;
+    std::string self = argv[0];
+    std::string path = (self.find('/') == std::string::npos ? "./" : self.substr(0, self.rfind('/') + 1)) + PLUGIN_FILE;
+
+    // Load, resolve and unload before anything else holds the library open, so each cycle maps it afresh
+    auto start = Clock::now();
+    for (size_t i = 0; i < LOAD_CYCLES; i++) {
+        void* handle = open_plugin(path);
+        checked_entry(handle);
+        dlclose(handle);
+    }
+    Duration load_time = Clock::now() - start;
+
+    void* handle = open_plugin(path);
+    MixFn dynamic_mix = symbol<MixFn>(handle, "plugin_mix");
+    const BenchPlugin* plugin = checked_entry(handle);
+    // Opaque to the optimizer, so the static pointer variant cannot become a direct call
+    MixFn volatile static_pointer = plugin_mix;
+    MixFn static_mix = static_pointer;
+
+    // Warm-up
+    per_call(WARMUP_CALLS, plugin_mix);
+    per_call(WARMUP_CALLS, dynamic_mix);
+    batched(WARMUP_CALLS, plugin);
+
+    // Benchmark
+    std::vector<Variant> variants;
+    variants.push_back(timed("static, direct call", [] { return per_call(CALLS, plugin_mix); }));
+    variants.push_back(timed("static, fn pointer", [&] { return per_call(CALLS, static_mix); }));
+    variants.push_back(timed("dlopen, symbol", [&] { return per_call(CALLS, dynamic_mix); }));
+    variants.push_back(timed("dlopen, vtable", [&] { return per_call(CALLS, [&](uint64_t x) { return plugin->mix(x); }); }));
+    variants.push_back(timed("dlopen, batched", [&] { return batched(CALLS, plugin); }));
+    dlclose(handle);
+
+    for (const Variant& v : variants) {
+        if (v.sum != variants[0].sum) {
+            std::cerr << v.name << " summed to " << v.sum << ", expected " << variants[0].sum << std::endl;
+            return 1;
+        }
+    }
+
+    // The headline is the loaded symbol, the usual way a host calls into a plugin
+    std::cout << std::fixed << std::setprecision(6) << variants[2].time.count() << std::endl;
+    std::cerr << CALLS << " calls per variant into " << PLUGIN_FILE << ", ns per value" << std::endl;
+    for (const Variant& v : variants) {
+        char line[96];
+        std::snprintf(line, sizeof(line), "  %-24s %7.3f", v.name, v.time.count() * 1e9 / CALLS);
+        std::cerr << line << std::endl;
+    }
+    std::cerr << std::fixed << std::setprecision(1) << "Load + resolve + unload: " << load_time.count() * 1e6 / LOAD_CYCLES
+              << " us per cycle over " << LOAD_CYCLES << " cycles" << std::endl;
+    std::cerr << "Checksum: " << variants[0].sum << std::endl;
+    return 0;
+}
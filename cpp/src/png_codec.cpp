@@ -0,0 +1,156 @@
+#include <iostream>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstring>
+#include <stdexcept>
+#include <png.h>
+
+const uint32_t WIDTH = 4096;  // 32 MB of RGBA8
+const uint32_t HEIGHT = 2048;
+const size_t CHANNELS = 4;
+const size_t ROUNDS = 2;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// Smooth gradients and soft circles with low-amplitude noise, so filters and
+// deflate both have real work to do (pure noise would be incompressible)
+std::vector<uint8_t> generate_image() {
+    size_t width = WIDTH, height = HEIGHT;
+    uint64_t seed = 42;
+    std::vector<uint8_t> pixels;
+    pixels.reserve(width * height * CHANNELS);
+    for (size_t y = 0; y < height; y++) {
+        for (size_t x = 0; x < width; x++) {
+            uint64_t noise = next_random(seed);
+            int64_t dx = static_cast<int64_t>(x % 512) - 256;
+            int64_t dy = static_cast<int64_t>(y % 512) - 256;
+            size_t ring = dx * dx + dy * dy < 160 * 160 ? 64 : 0;
+            pixels.push_back(static_cast<uint8_t>(x * 191 / width + (noise & 7)));
+            pixels.push_back(static_cast<uint8_t>(y * 191 / height + ring + ((noise >> 3) & 7)));
+            pixels.push_back(static_cast<uint8_t>((x + y) * 127 / (width + height) + ring * 2));
+            pixels.push_back((x / 64 + y / 64) % 7 == 0 ? 128 : 255);
+        }
+    }
+    return pixels;
+}
+
+void write_to_vector(png_structp png, png_bytep data, png_size_t len) {
+    auto* out = static_cast<std::vector<uint8_t>*>(png_get_io_ptr(png));
+    out->insert(out->end(), data, data + len);
+}
+
+void flush_nothing(png_structp) {}
+
+struct ReadCursor {
+    const std::vector<uint8_t>* bytes;
+    size_t pos;
+};
+
+void read_from_vector(png_structp png, png_bytep data, png_size_t len) {
+    auto* cursor = static_cast<ReadCursor*>(png_get_io_ptr(png));
+    if (cursor->pos + len > cursor->bytes->size()) {
+        png_error(png, "read past end of PNG data");
+    }
+    std::memcpy(data, cursor->bytes->data() + cursor->pos, len);
+    cursor->pos += len;
+}
+
+// Rows of the packed RGBA buffer, as libpng wants them
+std::vector<png_bytep> row_pointers(uint8_t* pixels, uint32_t height) {
+    std::vector<png_bytep> rows(height);
+    for (uint32_t y = 0; y < height; y++) {
+        rows[y] = pixels + static_cast<size_t>(y) * WIDTH * CHANNELS;
+    }
+    return rows;
+}
+
+// libpng's defaults: deflate level 6 with adaptive per-row filtering
+std::vector<uint8_t> encode(const std::vector<uint8_t>& pixels, uint32_t height) {
+    png_structp png = png_create_write_struct(PNG_LIBPNG_VER_STRING, nullptr, nullptr, nullptr);
+    png_infop info = png_create_info_struct(png);
+    if (!png || !info || setjmp(png_jmpbuf(png))) {
+        png_destroy_write_struct(&png, &info);
+        throw std::runtime_error("PNG encode failed");
+    }
+
+    std::vector<uint8_t> out;
+    png_set_write_fn(png, &out, write_to_vector, flush_nothing);
+    png_set_IHDR(png, info, WIDTH, height, 8, PNG_COLOR_TYPE_RGBA, PNG_INTERLACE_NONE, PNG_COMPRESSION_TYPE_DEFAULT,
+                 PNG_FILTER_TYPE_DEFAULT);
+    png_set_compression_level(png, 6);
+    png_write_info(png, info);
+    std::vector<png_bytep> rows = row_pointers(const_cast<uint8_t*>(pixels.data()), height);
+    png_write_image(png, rows.data());
+    png_write_end(png, nullptr);
+    png_destroy_write_struct(&png, &info);
+    return out;
+}
+
+std::vector<uint8_t> decode(const std::vector<uint8_t>& bytes) {
+    png_structp png = png_create_read_struct(PNG_LIBPNG_VER_STRING, nullptr, nullptr, nullptr);
+    png_infop info = png_create_info_struct(png);
+    if (!png || !info || setjmp(png_jmpbuf(png))) {
+        png_destroy_read_struct(&png, &info, nullptr);
+        throw std::runtime_error("PNG decode failed");
+    }
+
+    ReadCursor cursor{&bytes, 0};
+    png_set_read_fn(png, &cursor, read_from_vector);
+    png_read_info(png, info);
+    if (png_get_image_width(png, info) != WIDTH || png_get_color_type(png, info) != PNG_COLOR_TYPE_RGBA ||
+        png_get_bit_depth(png, info) != 8) {
+        png_error(png, "unexpected PNG format");
+    }
+    uint32_t height = png_get_image_height(png, info);
+    std::vector<uint8_t> pixels(static_cast<size_t>(WIDTH) * height * CHANNELS);
+    std::vector<png_bytep> rows = row_pointers(pixels.data(), height);
+    png_read_image(png, rows.data());
+    png_read_end(png, nullptr);
+    png_destroy_read_struct(&png, &info, nullptr);
+    return pixels;
+}
+
+int main() {
+    std::vector<uint8_t> pixels = generate_image();
+    double megabytes = pixels.size() / 1e6;
+
+    // Warm-up on the top sixteenth of the image
+    decode(encode(std::vector<uint8_t>(pixels.begin(), pixels.begin() + pixels.size() / 16), HEIGHT / 16));
+
+    std::chrono::duration<double> encode_time(0);
+    std::chrono::duration<double> decode_time(0);
+    std::vector<uint8_t> encoded;
+    std::vector<uint8_t> decoded;
+    for (size_t round = 0; round < ROUNDS; round++) {
+        auto start = std::chrono::high_resolution_clock::now();
+        encoded = encode(pixels, HEIGHT);
+        encode_time += std::chrono::high_resolution_clock::now() - start;
+
+        start = std::chrono::high_resolution_clock::now();
+        decoded = decode(encoded);
+        decode_time += std::chrono::high_resolution_clock::now() - start;
+    }
+    if (decoded != pixels) {
+        std::cerr << "Decoded pixels differ from the original" << std::endl;
+        return 1;
+    }
+
+    uint64_t checksum = 0;
+    for (uint8_t b : decoded) checksum += b;
+
+    std::cout << std::fixed << std::setprecision(6) << (encode_time + decode_time).count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(1) << "Image: " << WIDTH << "x" << HEIGHT << " RGBA8 ("
+              << megabytes << " MB raw), " << ROUNDS << " rounds" << std::endl;
+    std::cerr << std::setprecision(2) << "Encoded size: " << encoded.size() / 1e6 << " MB (ratio "
+              << std::setprecision(3) << static_cast<double>(encoded.size()) / pixels.size() << ")" << std::endl;
+    std::cerr << std::setprecision(1) << "Encode: " << megabytes * ROUNDS / encode_time.count()
+              << " MB/s, decode: " << megabytes * ROUNDS / decode_time.count() << " MB/s" << std::endl;
+    std::cerr << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
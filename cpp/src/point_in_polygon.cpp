@@ -0,0 +1,459 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <atomic>
+#include <algorithm>
+#include <numeric>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+#include <cmath>
+#include <limits>
+
+#include "bench_measure.h"
+
+#ifdef HAVE_BOOST_GEOMETRY
+#include <boost/geometry.hpp>
+#include <boost/geometry/geometries/point_xy.hpp>
+#include <boost/geometry/index/rtree.hpp>
+#endif
+
+const double WORLD = 1000.0; // points and polygons lie in [0, WORLD)^2
+const size_t POLYGONS = 1000;
+const size_t MIN_VERTICES = 16;
+const size_t MAX_VERTICES = 128;
+const double MIN_RADIUS = 3.0;
+const double MAX_RADIUS = 15.0;
+const size_t NODE_CAPACITY = 16; // children per R-tree node
+const uint64_t DEFAULT_POINTS = 100'000'000;
+const uint64_t WARMUP_POINTS = 1'000'000;
+const uint64_t VERIFY_POINTS = 100'000; // checked against a scan of every polygon, without the index
+const uint64_t CHUNK = 1 << 20;
+const size_t NUM_THREADS = 8;
+const uint64_t SEED = 0x706f'6c79'676f'6e73;
+const uint64_t GOLDEN = 0x9e37'79b9'7f4a'7c15;
+const double TAU = 6.283185307179586;
+
+// The SplitMix64 output function: a stream from a counter, or a point from its index
+uint64_t mix(uint64_t z) {
+    z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+    z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+    return z ^ (z >> 31);
+}
+
+struct Rng {
+    uint64_t state;
+
+    uint64_t next() {
+        state += GOLDEN;
+        return mix(state);
+    }
+
+    double range(double lo, double hi) {
+        return lo + (hi - lo) * (static_cast<double>(next() >> 11) * (1.0 / static_cast<double>(uint64_t(1) << 53)));
+    }
+};
+
+// Point i of the stream, 32 bits per coordinate, so no point set needs to be stored
+inline void point(uint64_t i, double& x, double& y) {
+    uint64_t bits = mix(SEED + (i + 1) * GOLDEN);
+    const double scale = WORLD / 4294967296.0;
+    x = static_cast<double>(bits >> 32) * scale;
+    y = static_cast<double>(bits & 0xffffffffULL) * scale;
+}
+
+struct Rect {
+    double min_x = std::numeric_limits<double>::infinity();
+    double min_y = std::numeric_limits<double>::infinity();
+    double max_x = -std::numeric_limits<double>::infinity();
+    double max_y = -std::numeric_limits<double>::infinity();
+
+    bool contains(double x, double y) const {
+        return x >= min_x && x <= max_x && y >= min_y && y <= max_y;
+    }
+
+    Rect merged(const Rect& o) const {
+        return {std::min(min_x, o.min_x), std::min(min_y, o.min_y), std::max(max_x, o.max_x), std::max(max_y, o.max_y)};
+    }
+
+    double center_x() const { return (min_x + max_x) * 0.5; }
+    double center_y() const { return (min_y + max_y) * 0.5; }
+};
+
+// Every polygon's ring in one pair of coordinate arrays; ring p is offsets[p]..offsets[p + 1]
+struct Polygons {
+    std::vector<double> xs;
+    std::vector<double> ys;
+    std::vector<size_t> offsets{0};
+    std::vector<Rect> bounds;
+
+    size_t size() const { return bounds.size(); }
+
+    // Even-odd ray casting: count the edges a ray from (x, y) toward +x crosses. Each edge is
+    // half-open in y, so a ray through a vertex counts it once
+    bool contains(size_t p, double x, double y) const {
+        const double* rx = xs.data() + offsets[p];
+        const double* ry = ys.data() + offsets[p];
+        size_t n = offsets[p + 1] - offsets[p];
+        bool inside = false;
+        for (size_t i = 0, j = n - 1; i < n; j = i++) {
+            if ((ry[i] > y) != (ry[j] > y) && x < (rx[j] - rx[i]) * (y - ry[i]) / (ry[j] - ry[i]) + rx[i]) {
+                inside = !inside;
+            }
+        }
+        return inside;
+    }
+};
+
+// Star-shaped, mostly concave rings: vertices at increasing angles around a center, each at a
+// random fraction of the radius, so no ring crosses itself. Polygons overlap one another freely
+Polygons make_polygons(Rng& rng) {
+    Polygons polygons;
+    for (size_t p = 0; p < POLYGONS; p++) {
+        double radius = rng.range(MIN_RADIUS, MAX_RADIUS);
+        double cx = rng.range(radius, WORLD - radius);
+        double cy = rng.range(radius, WORLD - radius);
+        size_t vertices = MIN_VERTICES + static_cast<size_t>(rng.next() % (MAX_VERTICES - MIN_VERTICES + 1));
+        Rect bounds;
+        for (size_t k = 0; k < vertices; k++) {
+            double angle = (static_cast<double>(k) + rng.range(0.0, 0.9)) * TAU / static_cast<double>(vertices);
+            double r = radius * rng.range(0.4, 1.0);
+            double x = cx + r * std::cos(angle);
+            double y = cy + r * std::sin(angle);
+            bounds = bounds.merged(Rect{x, y, x, y});
+            polygons.xs.push_back(x);
+            polygons.ys.push_back(y);
+        }
+        polygons.offsets.push_back(polygons.xs.size());
+        polygons.bounds.push_back(bounds);
+    }
+    return polygons;
+}
+
+struct Node {
+    Rect bounds;
+    uint32_t first; // first child node, or first entry of items for a leaf
+    uint32_t count;
+    bool leaf;
+};
+
+struct Entry {
+    uint32_t index;
+    Rect bounds;
+};
+
+struct Group {
+    size_t begin;
+    size_t end;
+};
+
+// Reorders entries into STR order and returns the groups of NODE_CAPACITY
+std::vector<Group> str_groups(std::vector<Entry>& entries) {
+    auto by_x = [](const Entry& a, const Entry& b) {
+        double ka = a.bounds.center_x(), kb = b.bounds.center_x();
+        return ka < kb || (ka == kb && a.index < b.index);
+    };
+    auto by_y = [](const Entry& a, const Entry& b) {
+        double ka = a.bounds.center_y(), kb = b.bounds.center_y();
+        return ka < kb || (ka == kb && a.index < b.index);
+    };
+    size_t leaves = (entries.size() + NODE_CAPACITY - 1) / NODE_CAPACITY;
+    size_t slices = static_cast<size_t>(std::ceil(std::sqrt(static_cast<double>(leaves))));
+    size_t slice_len = slices * NODE_CAPACITY;
+    std::sort(entries.begin(), entries.end(), by_x);
+    std::vector<Group> groups;
+    for (size_t start = 0; start < entries.size(); start += slice_len) {
+        size_t end = std::min(start + slice_len, entries.size());
+        std::sort(entries.begin() + start, entries.begin() + end, by_y);
+        for (size_t group = start; group < end; group += NODE_CAPACITY) {
+            groups.push_back({group, std::min(group + NODE_CAPACITY, end)});
+        }
+    }
+    return groups;
+}
+
+Rect bounds_of(const std::vector<Entry>& entries, Group group) {
+    Rect bounds;
+    for (size_t i = group.begin; i < group.end; i++) bounds = bounds.merged(entries[i].bounds);
+    return bounds;
+}
+
+// A static R-tree over the polygons' bounding boxes, bulk-loaded with Sort-Tile-Recursive:
+// sort by center x, cut into vertical slices, sort each slice by center y and pack runs of
+// NODE_CAPACITY into nodes, then repeat over the nodes until one root remains
+struct RTreeIndex {
+    std::vector<Node> nodes;
+    std::vector<uint32_t> items; // polygon indices in leaf order
+    size_t root = 0;
+
+    explicit RTreeIndex(const Polygons& polygons) {
+        std::vector<Entry> entries;
+        for (size_t i = 0; i < polygons.size(); i++) entries.push_back({static_cast<uint32_t>(i), polygons.bounds[i]});
+        std::vector<Node> pending;
+        for (Group group : str_groups(entries)) {
+            pending.push_back({bounds_of(entries, group), static_cast<uint32_t>(group.begin),
+                               static_cast<uint32_t>(group.end - group.begin), true});
+        }
+        for (const Entry& e : entries) items.push_back(e.index);
+        // Each pass packs the pending level into parents; a parent's children must be contiguous,
+        // so the level is stored in its packed order
+        while (pending.size() > 1) {
+            std::vector<Entry> level;
+            for (size_t i = 0; i < pending.size(); i++) level.push_back({static_cast<uint32_t>(i), pending[i].bounds});
+            std::vector<Group> groups = str_groups(level);
+            size_t base = nodes.size();
+            for (const Entry& e : level) nodes.push_back(pending[e.index]);
+            pending.clear();
+            for (Group group : groups) {
+                pending.push_back({bounds_of(level, group), static_cast<uint32_t>(base + group.begin),
+                                   static_cast<uint32_t>(group.end - group.begin), false});
+            }
+        }
+        root = nodes.size();
+        nodes.push_back(pending[0]);
+    }
+
+    // Calls visit with every polygon whose bounding box holds the point
+    template <typename F>
+    void query(double x, double y, F visit) const {
+        uint32_t stack[64];
+        stack[0] = static_cast<uint32_t>(root);
+        size_t depth = 1;
+        while (depth > 0) {
+            const Node& node = nodes[stack[--depth]];
+            if (!node.bounds.contains(x, y)) continue;
+            if (node.leaf) {
+                for (uint32_t i = node.first; i < node.first + node.count; i++) visit(items[i]);
+            } else {
+                for (uint32_t child = node.first; child < node.first + node.count; child++) stack[depth++] = child;
+            }
+        }
+    }
+
+    size_t height() const {
+        const Node* node = &nodes[root];
+        size_t height = 1;
+        while (!node->leaf) {
+            node = &nodes[node->first];
+            height++;
+        }
+        return height;
+    }
+};
+
+// Containment counts per polygon, and how many points fell in at least one
+struct Hits {
+    std::vector<uint64_t> per_polygon;
+    uint64_t covered = 0;
+
+    explicit Hits(size_t polygons) : per_polygon(polygons, 0) {}
+
+    void merge(const Hits& other) {
+        for (size_t p = 0; p < per_polygon.size(); p++) per_polygon[p] += other.per_polygon[p];
+        covered += other.covered;
+    }
+
+    uint64_t total() const { return std::accumulate(per_polygon.begin(), per_polygon.end(), uint64_t(0)); }
+
+    uint64_t checksum() const {
+        uint64_t sum = covered;
+        for (size_t p = 0; p < per_polygon.size(); p++) sum += per_polygon[p] * (p + 1);
+        return sum;
+    }
+
+    bool operator==(const Hits& other) const {
+        return covered == other.covered && per_polygon == other.per_polygon;
+    }
+};
+
+// Runs count(hits, start, end) over CHUNK-sized ranges of the point stream on NUM_THREADS threads
+template <typename F>
+Hits parallel_hits(size_t polygons, uint64_t points, F count) {
+    std::atomic<uint64_t> next{0};
+    std::vector<Hits> partial(NUM_THREADS, Hits(polygons));
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&, t]() {
+            for (uint64_t start = next.fetch_add(CHUNK); start < points; start = next.fetch_add(CHUNK)) {
+                count(partial[t], start, std::min(start + CHUNK, points));
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+    Hits hits(polygons);
+    for (const Hits& h : partial) hits.merge(h);
+    return hits;
+}
+
+Hits count_indexed(const Polygons& polygons, const RTreeIndex& index, uint64_t points) {
+    return parallel_hits(polygons.size(), points, [&](Hits& hits, uint64_t start, uint64_t end) {
+        for (uint64_t i = start; i < end; i++) {
+            double x, y;
+            point(i, x, y);
+            bool inside = false;
+            index.query(x, y, [&](uint32_t p) {
+                if (polygons.bounds[p].contains(x, y) && polygons.contains(p, x, y)) {
+                    hits.per_polygon[p]++;
+                    inside = true;
+                }
+            });
+            hits.covered += inside;
+        }
+    });
+}
+
+// Every polygon's box for every point: the cost the index saves, on a small prefix of the stream
+Hits count_scan(const Polygons& polygons, uint64_t points) {
+    Hits hits(polygons.size());
+    for (uint64_t i = 0; i < points; i++) {
+        double x, y;
+        point(i, x, y);
+        bool inside = false;
+        for (size_t p = 0; p < polygons.size(); p++) {
+            if (polygons.bounds[p].contains(x, y) && polygons.contains(p, x, y)) {
+                hits.per_polygon[p]++;
+                inside = true;
+            }
+        }
+        hits.covered += inside;
+    }
+    return hits;
+}
+
+#ifdef HAVE_BOOST_GEOMETRY
+namespace bg = boost::geometry;
+namespace bgi = boost::geometry::index;
+
+// Boost.Geometry's polygon and within, indexed by its R*-tree of bounding boxes (bulk-loaded by
+// the range constructor)
+struct BoostIndex {
+    using BPoint = bg::model::d2::point_xy<double>;
+    using BPolygon = bg::model::polygon<BPoint, false, false>; // counter-clockwise, open rings
+    using BBox = bg::model::box<BPoint>;
+    using Value = std::pair<BBox, size_t>;
+
+    std::vector<BPolygon> polygons;
+    bgi::rtree<Value, bgi::rstar<NODE_CAPACITY>> tree;
+
+    static bgi::rtree<Value, bgi::rstar<NODE_CAPACITY>> boxes(const Polygons& polygons) {
+        std::vector<Value> values;
+        for (size_t p = 0; p < polygons.size(); p++) {
+            const Rect& b = polygons.bounds[p];
+            values.emplace_back(BBox(BPoint(b.min_x, b.min_y), BPoint(b.max_x, b.max_y)), p);
+        }
+        return bgi::rtree<Value, bgi::rstar<NODE_CAPACITY>>(values);
+    }
+
+    explicit BoostIndex(const Polygons& source) : tree(boxes(source)) {
+        for (size_t p = 0; p < source.size(); p++) {
+            BPolygon polygon;
+            for (size_t v = source.offsets[p]; v < source.offsets[p + 1]; v++) {
+                bg::append(polygon.outer(), BPoint(source.xs[v], source.ys[v]));
+            }
+            polygons.push_back(std::move(polygon));
+        }
+    }
+
+    Hits count(uint64_t points) const {
+        return parallel_hits(polygons.size(), points, [&](Hits& hits, uint64_t start, uint64_t end) {
+            std::vector<Value> candidates;
+            for (uint64_t i = start; i < end; i++) {
+                double x, y;
+                point(i, x, y);
+                BPoint pt(x, y);
+                bool inside = false;
+                candidates.clear();
+                tree.query(bgi::intersects(pt), std::back_inserter(candidates));
+                for (const Value& candidate : candidates) {
+                    if (bg::within(pt, polygons[candidate.second])) {
+                        hits.per_polygon[candidate.second]++;
+                        inside = true;
+                    }
+                }
+                hits.covered += inside;
+            }
+        });
+    }
+};
+#endif
+
+uint64_t parse_points(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--points") == 0) {
+            long long n = i + 1 < argc ? std::atoll(argv[i + 1]) : 0;
+            if (n <= 0) {
+                std::cerr << "--points expects a positive integer" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<uint64_t>(n);
+        }
+    }
+    return DEFAULT_POINTS;
+}
+
+int main(int argc, char** argv) {
+    auto measure = bench_measure_from_env();
+    uint64_t points = parse_points(argc, argv);
+    Rng rng{SEED};
+    Polygons polygons = make_polygons(rng);
+    RTreeIndex index(polygons);
+#ifdef HAVE_BOOST_GEOMETRY
+    BoostIndex boost_index(polygons);
+#endif
+
+    // The index must find exactly what a scan of every polygon finds
+    uint64_t verify = std::min(VERIFY_POINTS, points);
+    if (!(count_indexed(polygons, index, verify) == count_scan(polygons, verify))) {
+        fprintf(stderr, "R-tree results differ from a full scan over the first %llu points\n",
+                static_cast<unsigned long long>(verify));
+        return 1;
+    }
+
+    // Warm-up
+    count_indexed(polygons, index, std::min(WARMUP_POINTS, points));
+#ifdef HAVE_BOOST_GEOMETRY
+    boost_index.count(std::min(WARMUP_POINTS, points));
+#endif
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    Hits hits = count_indexed(polygons, index, points);
+    measure->stop();
+    std::chrono::duration<double> indexed_time = std::chrono::high_resolution_clock::now() - start;
+#ifdef HAVE_BOOST_GEOMETRY
+    start = std::chrono::high_resolution_clock::now();
+    Hits boost_hits = boost_index.count(points);
+    std::chrono::duration<double> boost_time = std::chrono::high_resolution_clock::now() - start;
+    if (!(boost_hits == hits)) {
+        fprintf(stderr, "Boost.Geometry results differ from ray casting: %llu hits, expected %llu\n",
+                static_cast<unsigned long long>(boost_hits.total()), static_cast<unsigned long long>(hits.total()));
+        return 1;
+    }
+#endif
+
+    auto rate = [&](double seconds) { return static_cast<double>(points) / seconds / 1e6; };
+    measure->report();
+    fprintf(stderr, "Polygons: %zu star-shaped rings, %zu vertices, R-tree of %zu nodes, height %zu\n", polygons.size(),
+            polygons.xs.size(), index.nodes.size(), index.height());
+    fprintf(stderr, "Points: %llu in a %gx%g square, %llu checked against a full scan\n",
+            static_cast<unsigned long long>(points), WORLD, WORLD, static_cast<unsigned long long>(verify));
+    fprintf(stderr, "R-tree + ray casting: %.6fs, %.1fM points/s\n", indexed_time.count(), rate(indexed_time.count()));
+#ifdef HAVE_BOOST_GEOMETRY
+    fprintf(stderr, "Boost.Geometry + rtree: %.6fs, %.1fM points/s (%.2fx)\n", boost_time.count(),
+            rate(boost_time.count()), boost_time.count() / indexed_time.count());
+#else
+    fprintf(stderr, "  (Boost.Geometry variant not built: Boost was not found)\n");
+#endif
+    fprintf(stderr, "Hits: %llu point-polygon containments, %llu points (%.2f%%) inside at least one polygon\n",
+            static_cast<unsigned long long>(hits.total()), static_cast<unsigned long long>(hits.covered),
+            100.0 * static_cast<double>(hits.covered) / static_cast<double>(points));
+    fprintf(stderr, "Checksum: %llu\n", static_cast<unsigned long long>(hits.checksum()));
+
+    return 0;
+}
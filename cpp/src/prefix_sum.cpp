@@ -0,0 +1,172 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <atomic>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstring>
+#include <cstdlib>
+
+const size_t DEFAULT_ELEMENTS = 1'000'000'000; // 8 GB of u64, scanned in place
+const size_t NUM_BLOCKS = 64; // parallel blocks per pass
+const size_t ROUNDS = 3;
+const size_t SAMPLE_STRIDE = 4099;
+const size_t NUM_THREADS = 8;
+
+// Stateless per-index mix, so the input can be regenerated in parallel between rounds
+uint64_t input_value(size_t i) {
+    uint64_t x = static_cast<uint64_t>(i) * 0x9E3779B97F4A7C15ULL;
+    x ^= x >> 29;
+    return x & 1023;
+}
+
+// Runs f(task) for task in [0, tasks) on NUM_THREADS threads pulling from a shared counter
+template <typename F>
+void parallel_for(size_t tasks, F f) {
+    std::atomic<size_t> next{0};
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&]() {
+            for (size_t task = next++; task < tasks; task = next++) {
+                f(task);
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+}
+
+size_t block_size(size_t len) {
+    return (len + NUM_BLOCKS - 1) / NUM_BLOCKS;
+}
+
+void fill(uint64_t* data, size_t len) {
+    size_t block = block_size(len);
+    parallel_for(NUM_BLOCKS, [&](size_t b) {
+        for (size_t i = b * block; i < std::min((b + 1) * block, len); i++) {
+            data[i] = input_value(i);
+        }
+    });
+}
+
+void scan_sequential(uint64_t* data, size_t len) {
+    uint64_t acc = 0;
+    for (size_t i = 0; i < len; i++) {
+        acc += data[i];
+        data[i] = acc;
+    }
+}
+
+// Reduce-then-scan: block totals in parallel, a tiny sequential exclusive scan over
+// them, then every block rescans itself from its offset in parallel
+void scan_parallel(uint64_t* data, size_t len) {
+    size_t block = block_size(len);
+    std::vector<uint64_t> totals(NUM_BLOCKS, 0);
+    parallel_for(NUM_BLOCKS, [&](size_t b) {
+        uint64_t acc = 0;
+        for (size_t i = b * block; i < std::min((b + 1) * block, len); i++) {
+            acc += data[i];
+        }
+        totals[b] = acc;
+    });
+
+    std::vector<uint64_t> offsets(NUM_BLOCKS);
+    uint64_t running = 0;
+    for (size_t b = 0; b < NUM_BLOCKS; b++) {
+        offsets[b] = running;
+        running += totals[b];
+    }
+
+    parallel_for(NUM_BLOCKS, [&](size_t b) {
+        uint64_t acc = offsets[b];
+        for (size_t i = b * block; i < std::min((b + 1) * block, len); i++) {
+            acc += data[i];
+            data[i] = acc;
+        }
+    });
+}
+
+// Sampled elements plus the final one (which must equal the input total)
+uint64_t checksum(const uint64_t* data, size_t len) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < len; i += SAMPLE_STRIDE) {
+        sum += data[i];
+    }
+    return sum + data[len - 1];
+}
+
+std::pair<std::chrono::duration<double>, uint64_t> run(std::vector<uint64_t>& data, void (*scan)(uint64_t*, size_t)) {
+    std::chrono::duration<double> elapsed(0);
+    uint64_t sum = 0;
+    for (size_t round = 0; round < ROUNDS; round++) {
+        fill(data.data(), data.size());
+        auto start = std::chrono::high_resolution_clock::now();
+        scan(data.data(), data.size());
+        elapsed += std::chrono::high_resolution_clock::now() - start;
+        sum = checksum(data.data(), data.size());
+    }
+    return {elapsed, sum};
+}
+
+size_t parse_elements(int argc, char** argv) {
+    for (int i = 1; i + 1 < argc; i++) {
+        if (std::strcmp(argv[i], "--elements") == 0) {
+            long n = std::atol(argv[i + 1]);
+            if (n <= 0) {
+                std::cerr << "--elements expects a positive integer" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<size_t>(n);
+        }
+    }
+    return DEFAULT_ELEMENTS;
+}
+
+int main(int argc, char** argv) {
+    size_t elements = parse_elements(argc, argv);
+    std::vector<uint64_t> data(elements);
+
+    // Warm-up
+    size_t warmup = (elements + 99) / 100;
+    fill(data.data(), warmup);
+    scan_parallel(data.data(), warmup);
+
+    std::vector<uint64_t> block_totals(NUM_BLOCKS, 0);
+    size_t block = block_size(elements);
+    parallel_for(NUM_BLOCKS, [&](size_t b) {
+        for (size_t i = b * block; i < std::min((b + 1) * block, elements); i++) {
+            block_totals[b] += input_value(i);
+        }
+    });
+    uint64_t expected_total = 0;
+    for (uint64_t t : block_totals) expected_total += t;
+
+    auto [sequential_time, sequential_sum] = run(data, scan_sequential);
+    uint64_t sequential_last = data[elements - 1];
+    auto [parallel_time, parallel_sum] = run(data, scan_parallel);
+    if (sequential_last != expected_total || data[elements - 1] != expected_total) {
+        std::cerr << "Scan total is wrong" << std::endl;
+        return 1;
+    }
+    if (parallel_sum != sequential_sum) {
+        std::cerr << "Parallel scan differs from sequential" << std::endl;
+        return 1;
+    }
+
+    auto rate = [&](std::chrono::duration<double> d) { return elements * ROUNDS / d.count() / 1e9; };
+
+    std::cout << std::fixed << std::setprecision(6) << (sequential_time + parallel_time).count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(1) << "Inclusive scan of " << elements / 1'000'000 << "M u64 ("
+              << elements * 8 / 1e9 << " GB), " << ROUNDS << " rounds" << std::endl;
+    std::cerr << std::setprecision(3) << "Sequential: " << sequential_time.count() << "s (" << std::setprecision(2)
+              << rate(sequential_time) << " G elements/s)" << std::endl;
+    std::cerr << "Parallel (" << NUM_BLOCKS << " blocks, two-pass): " << std::setprecision(3) << parallel_time.count()
+              << "s (" << std::setprecision(2) << rate(parallel_time) << " G elements/s, "
+              << sequential_time.count() / parallel_time.count() << "x)" << std::endl;
+    std::cerr << "Checksum: " << sequential_sum << " (total " << expected_total << ")" << std::endl;
+
+    return 0;
+}
@@ -1,18 +1,84 @@
 #include <iostream>
 #include <vector>
+#include <thread>
+#include <atomic>
 #include <cmath>
 #include <chrono>
 #include <iomanip>
+#include <algorithm>
+#include <cstdint>
+#include <cstdio>
+#include <cstring>
+#include <cstdlib>
+#include "huge_pages.h"
 
 const size_t LIMIT = 100'000'000;
+const size_t SEGMENT_SIZE = 32 * 1024; // odd numbers per segment, one byte each: an L1-sized window
+const size_t MIN_CHUNK_SEGMENTS = 16;   // segments per parallel task, grown so there are at most MAX_CHUNKS tasks
+const size_t MAX_CHUNKS = 1024;
+const size_t NUM_THREADS = 8;
+const size_t WHEEL = 2 * 3 * 5 * 7;
+const size_t SPOKES = 48; // residues mod 210 coprime to it, the only candidates the wheel keeps
+const size_t WARMUP_LIMIT = 1'000'000;
+
+// π(n), the number of primes up to n, for checking runs whose limit is in the table
+const std::pair<size_t, size_t> PI_REFERENCE[] = {
+    {10, 4},
+    {100, 25},
+    {1'000, 168},
+    {10'000, 1'229},
+    {100'000, 9'592},
+    {1'000'000, 78'498},
+    {10'000'000, 664'579},
+    {100'000'000, 5'761'455},
+    {1'000'000'000, 50'847'534},
+    {4'294'967'296, 203'280'221},
+    {10'000'000'000, 455'052'511},
+};
+
+enum class Mode {
+    Bool,      // std::vector<bool> (the original benchmark; already bit-packed by the standard library)
+    Bits,      // one bit per odd number
+    Segmented, // one byte per odd number, sieved a cache-sized window at a time
+    Parallel,  // segmented, with contiguous runs of segments spread over worker threads
+    Wheel,     // one byte per number coprime to 2, 3, 5 and 7
+};
+
+const char* mode_name(Mode mode) {
+    switch (mode) {
+        case Mode::Bool: return "bool";
+        case Mode::Bits: return "bits";
+        case Mode::Segmented: return "segmented";
+        case Mode::Parallel: return "parallel";
+        case Mode::Wheel: return "wheel";
+    }
+    return "";
+}
+
+// Runs f(thread, task) for task in [0, tasks) on NUM_THREADS threads pulling from a shared counter
+template <typename F>
+void parallel_for(size_t tasks, F f) {
+    std::atomic<size_t> next{0};
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&, t]() {
+            for (size_t task = next++; task < tasks; task = next++) {
+                f(t, task);
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+}
 
 std::vector<size_t> sieve_of_eratosthenes(size_t limit) {
     std::vector<bool> is_prime(limit + 1, true);
     is_prime[0] = false;
     is_prime[1] = false;
-    
+
     size_t sqrt_limit = static_cast<size_t>(std::sqrt(limit));
-    
+
     for (size_t i = 2; i <= sqrt_limit; i++) {
         if (is_prime[i]) {
             for (size_t j = i * i; j <= limit; j += i) {
@@ -20,31 +86,257 @@ std::vector<size_t> sieve_of_eratosthenes(size_t limit) {
             }
         }
     }
-    
+
     std::vector<size_t> primes;
     for (size_t i = 0; i <= limit; i++) {
         if (is_prime[i]) {
             primes.push_back(i);
         }
     }
-    
+
     return primes;
 }
 
-int main() {
-    // Warm-up with smaller limit
-    auto warmup = sieve_of_eratosthenes(1'000'000);
-    
-    // Benchmark
+// Bit k stands for 2k + 1 and is set once that number is known composite
+size_t count_primes_bits(size_t limit) {
+    if (limit < 2) return 0;
+    size_t odd_count = (limit - 1) / 2 + 1;
+    std::vector<uint64_t> composite;
+    huge_reserve(composite, (odd_count + 63) / 64);
+    composite.resize((odd_count + 63) / 64, 0);
+    composite[0] = 1; // 1 is not prime
+
+    for (size_t i = 3; i * i <= limit; i += 2) {
+        if ((composite[i / 2 / 64] >> (i / 2 % 64) & 1) == 0) {
+            for (size_t j = i * i; j <= limit; j += 2 * i) {
+                composite[j / 2 / 64] |= uint64_t(1) << (j / 2 % 64);
+            }
+        }
+    }
+
+    size_t composites = 0;
+    for (uint64_t w : composite) composites += __builtin_popcountll(w);
+    return 1 + odd_count - composites; // 2, plus every odd number left unmarked
+}
+
+// Odd primes up to `limit`, for seeding the segmented sieve
+std::vector<size_t> small_odd_primes(size_t limit) {
+    std::vector<bool> is_prime(limit + 1, true);
+    std::vector<size_t> primes;
+    for (size_t i = 3; i <= limit; i += 2) {
+        if (is_prime[i]) {
+            primes.push_back(i);
+            for (size_t j = i * i; j <= limit; j += 2 * i) {
+                is_prime[j] = false;
+            }
+        }
+    }
+    return primes;
+}
+
+size_t isqrt(size_t n) {
+    size_t r = static_cast<size_t>(std::sqrt(static_cast<double>(n)));
+    while (r * r > n) r--;
+    while ((r + 1) * (r + 1) <= n) r++;
+    return r;
+}
+
+// Counts primes among the odd numbers in [low, high), low odd and > 1. Each base prime
+// carries its next odd multiple from one segment to the next
+size_t count_primes_range(size_t low, size_t high, const std::vector<size_t>& base_primes, std::vector<uint8_t>& segment) {
+    std::vector<size_t> next(base_primes.size());
+    for (size_t k = 0; k < base_primes.size(); k++) {
+        size_t p = base_primes[k];
+        size_t start = std::max(p * p, (low + p - 1) / p * p);
+        next[k] = start % 2 == 0 ? start + p : start;
+    }
+
+    size_t count = 0;
+    for (size_t seg_low = low; seg_low < high;) {
+        size_t seg_high = std::min(seg_low + 2 * segment.size(), high);
+        size_t len = (seg_high - seg_low + 1) / 2;
+        uint8_t* window = segment.data();
+        std::fill(window, window + len, 0);
+        for (size_t k = 0; k < base_primes.size(); k++) {
+            size_t step = 2 * base_primes[k];
+            size_t j = next[k];
+            for (; j < seg_high; j += step) {
+                window[(j - seg_low) / 2] = 1;
+            }
+            next[k] = j;
+        }
+        for (size_t i = 0; i < len; i++) count += window[i] == 0;
+        seg_low = seg_high; // an odd start plus an even width stays odd
+    }
+    return count;
+}
+
+size_t count_primes_segmented(size_t limit) {
+    if (limit < 2) return 0;
+    std::vector<size_t> base_primes = small_odd_primes(isqrt(limit));
+    std::vector<uint8_t> segment(SEGMENT_SIZE);
+    return 1 + count_primes_range(3, limit + 1, base_primes, segment);
+}
+
+// Each task sieves its own chunk with a per-thread window and fresh starting multiples,
+// so the only shared state is the read-only base primes
+size_t count_primes_parallel(size_t limit) {
+    if (limit < 2) return 0;
+    std::vector<size_t> base_primes = small_odd_primes(isqrt(limit));
+    size_t segment_span = 2 * SEGMENT_SIZE;
+    size_t chunk_segments = std::max((limit / segment_span + MAX_CHUNKS - 1) / MAX_CHUNKS, MIN_CHUNK_SEGMENTS);
+    size_t chunk_span = chunk_segments * segment_span; // even, so every chunk starts on an odd number
+    size_t chunks = (limit + 1 - 3 + chunk_span - 1) / chunk_span;
+
+    std::vector<std::vector<uint8_t>> segments(NUM_THREADS, std::vector<uint8_t>(SEGMENT_SIZE));
+    std::vector<size_t> counts(chunks, 0);
+    parallel_for(chunks, [&](size_t thread, size_t c) {
+        size_t low = 3 + c * chunk_span;
+        size_t high = std::min(low + chunk_span, limit + 1);
+        counts[c] = count_primes_range(low, high, base_primes, segments[thread]);
+    });
+
+    size_t odd_primes = 0;
+    for (size_t n : counts) odd_primes += n;
+    return 1 + odd_primes;
+}
+
+// 2-3-5-7 wheel: candidate k stands for WHEEL * (k / SPOKES) + residues[k % SPOKES], 22.9% of
+// the numbers. A prime p strikes p * q for every candidate q >= p; the q of one residue class are
+// WHEEL apart, so their multiples all share a residue and sit exactly SPOKES * p candidates apart
+size_t count_primes_wheel(size_t limit) {
+    if (limit < 11) {
+        size_t count = 0;
+        for (size_t p : {2, 3, 5, 7}) count += p <= limit;
+        return count;
+    }
+    std::vector<size_t> residues;
+    size_t index[WHEEL];
+    std::fill(std::begin(index), std::end(index), SIZE_MAX);
+    for (size_t r = 1; r < WHEEL; r++) {
+        if (r % 2 != 0 && r % 3 != 0 && r % 5 != 0 && r % 7 != 0) {
+            index[r] = residues.size();
+            residues.push_back(r);
+        }
+    }
+    auto value = [&](size_t k) { return WHEEL * (k / SPOKES) + residues[k % SPOKES]; };
+    auto position = [&](size_t n) { return SPOKES * (n / WHEEL) + index[n % WHEEL]; };
+
+    size_t candidates = SPOKES * (limit / WHEEL);
+    for (size_t r : residues) candidates += r <= limit % WHEEL;
+    std::vector<uint8_t> composite;
+    huge_reserve(composite, candidates);
+    composite.resize(candidates, 0);
+    composite[0] = 1; // 1 is not prime
+
+    for (size_t k = 1; value(k) * value(k) <= limit; k++) {
+        if (composite[k]) continue;
+        size_t p = value(k);
+        for (size_t i = k; i < k + SPOKES; i++) {
+            size_t q = value(i);
+            if (p * q > limit) break;
+            for (size_t j = position(p * q); j < candidates; j += SPOKES * p) {
+                composite[j] = 1;
+            }
+        }
+    }
+
+    return 4 + std::count(composite.begin(), composite.end(), 0); // 2, 3, 5 and 7, plus every candidate left unmarked
+}
+
+size_t count_primes(Mode mode, size_t limit) {
+    switch (mode) {
+        case Mode::Bool: return sieve_of_eratosthenes(limit).size();
+        case Mode::Bits: return count_primes_bits(limit);
+        case Mode::Segmented: return count_primes_segmented(limit);
+        case Mode::Parallel: return count_primes_parallel(limit);
+        case Mode::Wheel: return count_primes_wheel(limit);
+    }
+    return 0;
+}
+
+Mode parse_mode(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--mode") == 0) {
+            const char* value = i + 1 < argc ? argv[i + 1] : "";
+            if (std::strcmp(value, "bool") == 0) return Mode::Bool;
+            if (std::strcmp(value, "bits") == 0) return Mode::Bits;
+            if (std::strcmp(value, "segmented") == 0) return Mode::Segmented;
+            if (std::strcmp(value, "parallel") == 0) return Mode::Parallel;
+            if (std::strcmp(value, "wheel") == 0) return Mode::Wheel;
+            std::cerr << "--mode expects bool, bits, segmented, parallel or wheel" << std::endl;
+            std::exit(1);
+        }
+    }
+    return Mode::Bool;
+}
+
+size_t parse_limit(int argc, char** argv) {
+    for (int i = 1; i + 1 < argc; i++) {
+        if (std::strcmp(argv[i], "--limit") == 0) {
+            long n = std::atol(argv[i + 1]);
+            if (n <= 0) {
+                std::cerr << "--limit expects a positive integer" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<size_t>(n);
+        }
+    }
+    return LIMIT;
+}
+
+// 0 when the table has no value for `limit`
+size_t reference_count(size_t limit) {
+    for (auto [n, count] : PI_REFERENCE) {
+        if (n == limit) return count;
+    }
+    return 0;
+}
+
+std::pair<std::chrono::duration<double>, size_t> time_count(Mode mode, size_t limit) {
     auto start = std::chrono::high_resolution_clock::now();
-    auto primes = sieve_of_eratosthenes(LIMIT);
-    auto end = std::chrono::high_resolution_clock::now();
-    
-    std::chrono::duration<double> duration = end - start;
-    
+    size_t count = count_primes(mode, limit);
+    return {std::chrono::high_resolution_clock::now() - start, count};
+}
+
+int main(int argc, char** argv) {
+    huge_pages_enable(argc, argv);
+    Mode mode = parse_mode(argc, argv);
+    size_t limit = parse_limit(argc, argv);
+
+    // Warm-up with smaller limit, which doubles as a check of the mode against π(1e6)
+    if (count_primes(mode, WARMUP_LIMIT) != reference_count(WARMUP_LIMIT)) {
+        std::cerr << mode_name(mode) << " sieve miscounts primes up to " << WARMUP_LIMIT << std::endl;
+        return 1;
+    }
+
+    // Benchmark
+    auto [duration, count] = time_count(mode, limit);
+
     std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
-    std::cerr << "Number of primes: " << primes.size() << std::endl;
-    
+    std::cerr << "Sieve: " << mode_name(mode) << ", limit: " << limit << std::endl;
+    if (mode == Mode::Parallel) {
+        // The serial segmented sieve does identical work on one thread, so it is the baseline
+        auto [serial, serial_count] = time_count(Mode::Segmented, limit);
+        if (serial_count != count) {
+            std::cerr << "Parallel sieve differs from serial" << std::endl;
+            return 1;
+        }
+        fprintf(stderr, "Serial (segmented): %.3fs, parallel (%zu threads): %.3fs (%.2fx)\n", serial.count(), NUM_THREADS,
+                duration.count(), serial.count() / duration.count());
+    }
+    if (size_t expected = reference_count(limit)) {
+        if (count != expected) {
+            std::cerr << mode_name(mode) << " sieve counts " << count << " primes up to " << limit << ", π(" << limit
+                      << ") = " << expected << std::endl;
+            return 1;
+        }
+        std::cerr << "π(" << limit << ") check: pass" << std::endl;
+    } else {
+        std::cerr << "π(" << limit << ") check: no reference value (limits 10^1 to 10^10 and 2^32 have one)" << std::endl;
+    }
+    std::cerr << huge_pages_report() << std::endl;
+    std::cerr << "Number of primes: " << count << std::endl;
+
     return 0;
 }
-
@@ -0,0 +1,383 @@
+#include <iostream>
+#include <vector>
+#include <queue>
+#include <functional>
+#include <utility>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+
+const size_t BULK_KEYS = 2'000'000;
+const size_t HOLD_SIZE = 100'000;
+const size_t HOLD_OPS = 10'000'000;
+const uint64_t MAX_INCREMENT = uint64_t(1) << 20;
+const size_t GRAPH_NODES = 1'000'000;
+const size_t GRAPH_DEGREE = 8;
+const uint64_t MAX_WEIGHT = 1000;
+const uint32_t NONE = UINT32_MAX;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+using Entry = std::pair<uint64_t, uint32_t>; // key, item
+
+uint64_t splitmix64(uint64_t& seed) {
+    seed += 0x9E3779B97F4A7C15ULL;
+    uint64_t z = seed;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+// Order-sensitive, so a heap popping out of order fails the checksum
+uint64_t mix(uint64_t acc, uint64_t key) {
+    return acc * 0x100000001b3ULL + key;
+}
+
+// Min-heaps over uint64_t keys, with uint32_t items riding along for Dijkstra, which also needs
+// decrease_key: queue `item` at `key`, lowering its key if it is already queued
+
+// std::priority_queue, a binary heap. It has no decrease-key: a lowered key is pushed again and
+// the stale entry skipped when it surfaces (lazy deletion)
+class StdHeap {
+public:
+    explicit StdHeap(size_t) {}
+
+    void push(uint64_t key, uint32_t item) { heap_.push({key, item}); }
+
+    bool pop(Entry& top) {
+        if (heap_.empty()) return false;
+        top = heap_.top();
+        heap_.pop();
+        return true;
+    }
+
+    void decrease_key(uint32_t item, uint64_t key) { push(key, item); }
+
+private:
+    std::priority_queue<Entry, std::vector<Entry>, std::greater<Entry>> heap_;
+};
+
+// Implicit 4-ary heap: half the depth of a binary heap, and the four children share a cache
+// line. position_ maps items to their slots for decrease-key; it is only kept up to date for
+// items below its size, so the bulk and hold phases (items 0) leave it empty
+class QuadHeap {
+public:
+    explicit QuadHeap(size_t items) : position_(items, NONE) {}
+
+    void push(uint64_t key, uint32_t item) {
+        entries_.push_back({key, item});
+        sift_up(entries_.size() - 1, {key, item});
+    }
+
+    bool pop(Entry& top) {
+        if (entries_.empty()) return false;
+        Entry last = entries_.back();
+        entries_.pop_back();
+        if (entries_.empty()) {
+            top = last;
+            return true;
+        }
+        top = entries_[0];
+        sift_down(0, last);
+        if (top.second < position_.size()) position_[top.second] = NONE;
+        return true;
+    }
+
+    void decrease_key(uint32_t item, uint64_t key) {
+        if (position_[item] == NONE) {
+            push(key, item);
+        } else {
+            sift_up(position_[item], {key, item});
+        }
+    }
+
+private:
+    void place(size_t slot, Entry entry) {
+        entries_[slot] = entry;
+        if (entry.second < position_.size()) position_[entry.second] = static_cast<uint32_t>(slot);
+    }
+
+    void sift_up(size_t slot, Entry entry) {
+        while (slot > 0) {
+            size_t parent = (slot - 1) / 4;
+            if (entries_[parent].first <= entry.first) break;
+            place(slot, entries_[parent]);
+            slot = parent;
+        }
+        place(slot, entry);
+    }
+
+    void sift_down(size_t slot, Entry entry) {
+        size_t len = entries_.size();
+        for (;;) {
+            size_t first = slot * 4 + 1;
+            if (first >= len) break;
+            size_t best = first;
+            for (size_t child = first + 1; child < std::min(first + 4, len); child++) {
+                if (entries_[child].first < entries_[best].first) best = child;
+            }
+            if (entries_[best].first >= entry.first) break;
+            place(slot, entries_[best]);
+            slot = best;
+        }
+        place(slot, entry);
+    }
+
+    std::vector<Entry> entries_;
+    std::vector<uint32_t> position_;
+};
+
+// Pairing heap with its nodes in one arena, linked by index, and freed nodes recycled. Each
+// node keeps its first child, next sibling and previous node (parent for a first child), so
+// decrease-key can cut a subtree out in O(1)
+class PairingHeap {
+public:
+    explicit PairingHeap(size_t items) : handle_(items, NONE) {}
+
+    void push(uint64_t key, uint32_t item) {
+        Node node{key, item, NONE, NONE, NONE};
+        uint32_t index;
+        if (!free_.empty()) {
+            index = free_.back();
+            free_.pop_back();
+            nodes_[index] = node;
+        } else {
+            nodes_.push_back(node);
+            index = static_cast<uint32_t>(nodes_.size() - 1);
+        }
+        if (item < handle_.size()) handle_[item] = index;
+        root_ = meld(root_, index);
+    }
+
+    bool pop(Entry& top) {
+        if (root_ == NONE) return false;
+        uint32_t old_root = root_;
+        top = {nodes_[old_root].key, nodes_[old_root].item};
+        root_ = merge_children(nodes_[old_root].child);
+        free_.push_back(old_root);
+        if (top.second < handle_.size()) handle_[top.second] = NONE;
+        return true;
+    }
+
+    void decrease_key(uint32_t item, uint64_t key) {
+        uint32_t node = handle_[item];
+        if (node == NONE) return push(key, item);
+        nodes_[node].key = key;
+        if (node == root_) return;
+        // Cut the subtree out of its sibling list and meld it back in at the root
+        uint32_t prev = nodes_[node].prev, sibling = nodes_[node].sibling;
+        if (nodes_[prev].child == node) {
+            nodes_[prev].child = sibling;
+        } else {
+            nodes_[prev].sibling = sibling;
+        }
+        if (sibling != NONE) nodes_[sibling].prev = prev;
+        nodes_[node].sibling = NONE;
+        nodes_[node].prev = NONE;
+        root_ = meld(root_, node);
+    }
+
+private:
+    struct Node {
+        uint64_t key;
+        uint32_t item;
+        uint32_t child;
+        uint32_t sibling;
+        uint32_t prev;
+    };
+
+    // Melds two roots, the larger becoming the first child of the smaller
+    uint32_t meld(uint32_t a, uint32_t b) {
+        if (a == NONE) return b;
+        if (b == NONE) return a;
+        uint32_t parent = nodes_[b].key < nodes_[a].key ? b : a;
+        uint32_t child = parent == a ? b : a;
+        uint32_t first = nodes_[parent].child;
+        nodes_[child].sibling = first;
+        nodes_[child].prev = parent;
+        if (first != NONE) nodes_[first].prev = child;
+        nodes_[parent].child = child;
+        return parent;
+    }
+
+    // The standard two-pass merge: meld siblings pairwise left to right, then the pairs right
+    // to left
+    uint32_t merge_children(uint32_t first) {
+        uint32_t next = first;
+        while (next != NONE) {
+            uint32_t a = next;
+            uint32_t b = nodes_[a].sibling;
+            next = b == NONE ? NONE : nodes_[b].sibling;
+            for (uint32_t n : {a, b}) {
+                if (n != NONE) nodes_[n].sibling = nodes_[n].prev = NONE;
+            }
+            pairs_.push_back(meld(a, b));
+        }
+        uint32_t root = NONE;
+        while (!pairs_.empty()) {
+            root = meld(pairs_.back(), root);
+            pairs_.pop_back();
+        }
+        return root;
+    }
+
+    std::vector<Node> nodes_;
+    std::vector<uint32_t> free_;
+    uint32_t root_ = NONE;
+    std::vector<uint32_t> handle_; // node of each queued item, for decrease-key, as QuadHeap's position_
+    std::vector<uint32_t> pairs_;  // scratch for the two-pass merge
+};
+
+// Push every key, then pop them all: a heap sort
+template <typename Q>
+uint64_t bulk(Q& queue, const std::vector<uint64_t>& keys) {
+    for (uint64_t key : keys) queue.push(key, 0);
+    uint64_t acc = 0;
+    Entry top;
+    while (queue.pop(top)) acc = mix(acc, top.first);
+    return acc;
+}
+
+// The classic hold model of discrete-event simulation: the queue stays at its size while the
+// earliest event is popped and a later one pushed in its place
+template <typename Q>
+uint64_t hold(Q& queue, const std::vector<uint64_t>& keys, const std::vector<uint64_t>& increments) {
+    for (uint64_t key : keys) queue.push(key, 0);
+    uint64_t acc = 0;
+    Entry top;
+    for (uint64_t increment : increments) {
+        queue.pop(top);
+        acc = mix(acc, top.first);
+        queue.push(top.first + increment, 0);
+    }
+    while (queue.pop(top)) {}
+    return acc;
+}
+
+// Random directed graph in CSR form: GRAPH_DEGREE edges out of each node
+struct Graph {
+    std::vector<uint32_t> offsets, targets, weights;
+};
+
+Graph generate_graph(size_t nodes, size_t degree) {
+    uint64_t seed = 1959; // Dijkstra's paper
+    Graph graph;
+    for (size_t n = 0; n <= nodes; n++) graph.offsets.push_back(static_cast<uint32_t>(n * degree));
+    for (size_t e = 0; e < nodes * degree; e++) graph.targets.push_back(static_cast<uint32_t>(splitmix64(seed) % nodes));
+    for (size_t e = 0; e < nodes * degree; e++) graph.weights.push_back(static_cast<uint32_t>(1 + splitmix64(seed) % MAX_WEIGHT));
+    return graph;
+}
+
+// Single-source shortest paths from node 0: the sum of the distances to the reachable nodes
+template <typename Q>
+uint64_t dijkstra(Q& queue, const Graph& graph) {
+    std::vector<uint64_t> dist(graph.offsets.size() - 1, UINT64_MAX);
+    dist[0] = 0;
+    queue.decrease_key(0, 0);
+    Entry top;
+    while (queue.pop(top)) {
+        auto [d, u] = top;
+        if (d > dist[u]) continue; // a stale duplicate left by lazy deletion
+        for (uint32_t e = graph.offsets[u]; e < graph.offsets[u + 1]; e++) {
+            uint32_t v = graph.targets[e];
+            uint64_t candidate = d + graph.weights[e];
+            if (candidate < dist[v]) {
+                dist[v] = candidate;
+                queue.decrease_key(v, candidate);
+            }
+        }
+    }
+    uint64_t sum = 0;
+    for (uint64_t d : dist) {
+        if (d != UINT64_MAX) sum += d;
+    }
+    return sum;
+}
+
+struct Workloads {
+    std::vector<uint64_t> bulk_keys, hold_keys, increments;
+    Graph graph;
+};
+
+Workloads generate(size_t bulk_keys, size_t hold_size, size_t hold_ops, size_t nodes) {
+    uint64_t seed = 42;
+    Workloads w;
+    for (size_t i = 0; i < bulk_keys; i++) w.bulk_keys.push_back(splitmix64(seed) >> 1);
+    for (size_t i = 0; i < hold_size; i++) w.hold_keys.push_back(splitmix64(seed) % (MAX_INCREMENT * 4));
+    for (size_t i = 0; i < hold_ops; i++) w.increments.push_back(splitmix64(seed) % MAX_INCREMENT);
+    w.graph = generate_graph(nodes, GRAPH_DEGREE);
+    return w;
+}
+
+struct Run {
+    Duration durations[3]; // bulk, hold, dijkstra
+    uint64_t results[3];
+};
+
+// A fresh queue per phase, sized for the given number of Dijkstra items
+template <typename Q>
+Run run(const Workloads& w) {
+    Run r;
+    size_t nodes = w.graph.offsets.size() - 1;
+    auto start = Clock::now();
+    {
+        Q queue(0);
+        asm volatile("" : : "r"(w.bulk_keys.data()) : "memory");
+        r.results[0] = bulk(queue, w.bulk_keys);
+    }
+    auto bulk_end = Clock::now();
+    {
+        Q queue(0);
+        asm volatile("" : : "r"(w.hold_keys.data()) : "memory");
+        r.results[1] = hold(queue, w.hold_keys, w.increments);
+    }
+    auto hold_end = Clock::now();
+    {
+        Q queue(nodes);
+        asm volatile("" : : "r"(w.graph.targets.data()) : "memory");
+        r.results[2] = dijkstra(queue, w.graph);
+    }
+    auto dijkstra_end = Clock::now();
+    r.durations[0] = bulk_end - start;
+    r.durations[1] = hold_end - bulk_end;
+    r.durations[2] = dijkstra_end - hold_end;
+    return r;
+}
+
+const char* const HEAPS[] = {"priority_queue", "4-ary", "pairing"};
+
+std::vector<Run> run_all(const Workloads& w) {
+    return {run<StdHeap>(w), run<QuadHeap>(w), run<PairingHeap>(w)};
+}
+
+int main() {
+    Workloads workloads = generate(BULK_KEYS, HOLD_SIZE, HOLD_OPS, GRAPH_NODES);
+
+    // Warm-up
+    run_all(generate(BULK_KEYS / 100, HOLD_SIZE / 100, HOLD_OPS / 100, GRAPH_NODES / 100));
+
+    // Benchmark
+    std::vector<Run> runs = run_all(workloads);
+    Duration total_duration(0);
+    bool all_match = true;
+    for (const Run& r : runs) {
+        for (size_t phase = 0; phase < 3; phase++) {
+            total_duration += r.durations[phase];
+            all_match &= r.results[phase] == runs[0].results[phase];
+        }
+    }
+    uint64_t checksum = runs[0].results[0] + runs[0].results[1] + runs[0].results[2];
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Bulk: %zu keys; hold: %zu ops at size %zu; Dijkstra: %zu nodes, %zu edges\n", BULK_KEYS, HOLD_OPS,
+            HOLD_SIZE, GRAPH_NODES, GRAPH_NODES * GRAPH_DEGREE);
+    for (size_t i = 0; i < runs.size(); i++) {
+        double bulk_s = runs[i].durations[0].count(), hold_s = runs[i].durations[1].count();
+        fprintf(stderr, "  %-14s bulk %.3fs (%.1f M/s)  hold %.3fs (%.1f M/s)  dijkstra %.3fs\n", HEAPS[i], bulk_s,
+                BULK_KEYS / bulk_s / 1e6, hold_s, HOLD_OPS / hold_s / 1e6, runs[i].durations[2].count());
+    }
+    std::cerr << "Checksum: " << checksum << " (all heaps match: " << (all_match ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
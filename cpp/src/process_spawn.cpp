@@ -0,0 +1,124 @@
+#include <iostream>
+#include <vector>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <spawn.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+extern char** environ;
+
+const size_t SPAWNS = 1'000; // per spawner and wave size
+const size_t WAVES[] = {1, 16}; // children started before the first is reaped
+const char* PROGRAM = "/usr/bin/true";
+// A build tool's heap: fork has to copy its page tables, posix_spawn does not
+const size_t BALLAST_BYTES = 64 << 20;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+bool wait_pid(pid_t pid) {
+    int status = 0;
+    return waitpid(pid, &status, 0) == pid && WIFEXITED(status) && WEXITSTATUS(status) == 0;
+}
+
+char* const ARGV[] = {const_cast<char*>(PROGRAM), nullptr};
+
+pid_t spawn_posix_spawn() {
+    pid_t pid = 0;
+    if (posix_spawn(&pid, PROGRAM, nullptr, nullptr, ARGV, environ) != 0) {
+        perror("posix_spawn");
+        std::exit(1);
+    }
+    return pid;
+}
+
+// The child only calls execve and _exit, both async-signal-safe
+pid_t spawn_fork_exec() {
+    pid_t pid = fork();
+    if (pid == 0) {
+        execve(PROGRAM, ARGV, environ);
+        _exit(127);
+    }
+    if (pid == -1) {
+        perror("fork");
+        std::exit(1);
+    }
+    return pid;
+}
+
+struct Run {
+    Duration elapsed;
+    size_t succeeded = 0;
+};
+
+// Start a wave of children, reap them all, repeat
+template <pid_t (*Spawn)()>
+Run run(size_t spawns, size_t wave) {
+    std::vector<pid_t> children;
+    children.reserve(wave);
+    Run r;
+    auto start = Clock::now();
+    for (size_t first = 0; first < spawns; first += wave) {
+        for (size_t i = first; i < std::min(spawns, first + wave); i++) children.push_back(Spawn());
+        for (pid_t pid : children) r.succeeded += wait_pid(pid);
+        children.clear();
+    }
+    r.elapsed = Clock::now() - start;
+    return r;
+}
+
+struct NamedVariant {
+    const char* name;
+    Run (*run)(size_t, size_t);
+};
+
+const NamedVariant VARIANTS[] = {
+    {"posix_spawn", run<spawn_posix_spawn>},
+    {"fork + execve", run<spawn_fork_exec>},
+};
+
+int main() {
+    std::vector<uint8_t> ballast(BALLAST_BYTES, 1);
+    asm volatile("" : : "r"(ballast.data()) : "memory");
+
+    // Warm-up
+    for (const auto& v : VARIANTS) v.run(SPAWNS / 100, WAVES[0]);
+
+    // Benchmark: runs[variant][wave]
+    std::vector<std::vector<Run>> runs;
+    Duration total_duration(0);
+    for (const auto& v : VARIANTS) {
+        auto& by_wave = runs.emplace_back();
+        for (size_t wave : WAVES) {
+            by_wave.push_back(v.run(SPAWNS, wave));
+            total_duration += by_wave.back().elapsed;
+        }
+    }
+    bool all_match = true;
+    for (const auto& by_wave : runs) {
+        for (const Run& r : by_wave) all_match &= r.succeeded == SPAWNS;
+    }
+    uint64_t checksum = 0;
+    for (const Run& r : runs[0]) checksum += r.succeeded;
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "%zu runs of %s per spawner and wave size, with a %zu MiB parent heap; us per process\n", SPAWNS,
+            PROGRAM, BALLAST_BYTES >> 20);
+    for (size_t v = 0; v < runs.size(); v++) {
+        fprintf(stderr, "  %-20s", VARIANTS[v].name);
+        for (size_t w = 0; w < runs[v].size(); w++) {
+            fprintf(stderr, "%swave %2zu %7.1f", w == 0 ? " " : "  ", WAVES[w],
+                    runs[v][w].elapsed.count() * 1e6 / SPAWNS);
+        }
+        fprintf(stderr, "\n");
+    }
+    std::cerr << "Checksum: " << checksum << " (all children exited cleanly: " << (all_match ? "true" : "false") << ")"
+              << std::endl;
+
+    return 0;
+}
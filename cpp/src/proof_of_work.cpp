@@ -0,0 +1,239 @@
+#include <iostream>
+#include <vector>
+#include <array>
+#include <string>
+#include <cstring>
+#include <cstdint>
+#include <thread>
+#include <chrono>
+#include <iomanip>
+#include <sstream>
+#include <algorithm>
+#include <utility>
+#include <limits>
+
+#include "bench_measure.h"
+
+using Digest = std::array<uint8_t, 32>;
+
+// Copyable streaming SHA-256, so a hasher that has absorbed the fixed header prefix can be reused as a midstate
+class SHA256 {
+private:
+    uint32_t h[8];
+    uint8_t data[64];
+    uint32_t datalen;
+    uint64_t bitlen;
+
+    static constexpr uint32_t k[64] = {
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
+    };
+
+    static uint32_t rotr(uint32_t x, uint32_t n) {
+        return (x >> n) | (x << (32 - n));
+    }
+
+    void transform() {
+        uint32_t m[64];
+        for (int i = 0, j = 0; i < 16; ++i, j += 4) {
+            m[i] = (uint32_t(data[j]) << 24) | (uint32_t(data[j + 1]) << 16) | (uint32_t(data[j + 2]) << 8) | data[j + 3];
+        }
+        for (int i = 16; i < 64; ++i) {
+            uint32_t s0 = rotr(m[i - 15], 7) ^ rotr(m[i - 15], 18) ^ (m[i - 15] >> 3);
+            uint32_t s1 = rotr(m[i - 2], 17) ^ rotr(m[i - 2], 19) ^ (m[i - 2] >> 10);
+            m[i] = m[i - 16] + s0 + m[i - 7] + s1;
+        }
+
+        uint32_t a = h[0], b = h[1], c = h[2], d = h[3], e = h[4], f = h[5], g = h[6], hh = h[7];
+        for (int i = 0; i < 64; ++i) {
+            uint32_t t1 = hh + (rotr(e, 6) ^ rotr(e, 11) ^ rotr(e, 25)) + ((e & f) ^ (~e & g)) + k[i] + m[i];
+            uint32_t t2 = (rotr(a, 2) ^ rotr(a, 13) ^ rotr(a, 22)) + ((a & b) ^ (a & c) ^ (b & c));
+            hh = g; g = f; f = e; e = d + t1;
+            d = c; c = b; b = a; a = t1 + t2;
+        }
+        h[0] += a; h[1] += b; h[2] += c; h[3] += d;
+        h[4] += e; h[5] += f; h[6] += g; h[7] += hh;
+    }
+
+public:
+    SHA256() {
+        h[0] = 0x6a09e667; h[1] = 0xbb67ae85; h[2] = 0x3c6ef372; h[3] = 0xa54ff53a;
+        h[4] = 0x510e527f; h[5] = 0x9b05688c; h[6] = 0x1f83d9ab; h[7] = 0x5be0cd19;
+        datalen = 0;
+        bitlen = 0;
+    }
+
+    void update(const uint8_t* data_in, size_t len) {
+        while (len > 0) {
+            size_t take = std::min<size_t>(64 - datalen, len);
+            std::memcpy(data + datalen, data_in, take);
+            datalen += take;
+            data_in += take;
+            len -= take;
+            if (datalen == 64) {
+                transform();
+                bitlen += 512;
+                datalen = 0;
+            }
+        }
+    }
+
+    Digest finalize() {
+        uint32_t i = datalen;
+        data[i++] = 0x80;
+        if (datalen >= 56) {
+            std::memset(data + i, 0, 64 - i);
+            transform();
+            i = 0;
+        }
+        std::memset(data + i, 0, 56 - i);
+
+        bitlen += datalen * 8;
+        for (int b = 0; b < 8; b++) {
+            data[63 - b] = static_cast<uint8_t>(bitlen >> (8 * b));
+        }
+        transform();
+
+        Digest out;
+        for (int w = 0; w < 8; w++) {
+            for (int b = 0; b < 4; b++) {
+                out[4 * w + b] = static_cast<uint8_t>(h[w] >> (24 - 8 * b));
+            }
+        }
+        return out;
+    }
+};
+
+const uint32_t NUM_NONCES = 1u << 27;
+const uint32_t ZERO_BITS = 20; // Difficulty: a hash meets the target if its top 20 bits are zero
+const size_t NUM_THREADS = 8;
+
+using Header = std::array<uint8_t, 80>;
+using Best = std::pair<uint64_t, uint32_t>;
+
+struct SearchResult {
+    uint64_t found;
+    Best best;
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+void put_le32(uint8_t* out, uint32_t v) {
+    for (int b = 0; b < 4; b++) {
+        out[b] = static_cast<uint8_t>(v >> (8 * b));
+    }
+}
+
+// Bitcoin-style 80-byte header: version, previous block hash, merkle root, time, bits, nonce
+Header build_header() {
+    uint64_t seed = 21'000'000;
+    Header header{};
+    put_le32(&header[0], 0x20000000);
+    for (size_t i = 4; i < 68; i++) {
+        header[i] = static_cast<uint8_t>(next_random(seed));
+    }
+    put_le32(&header[68], 1'700'000'000);
+    put_le32(&header[72], 0x1d00ffff);
+    return header;
+}
+
+Digest sha256(const uint8_t* data, size_t len) {
+    SHA256 hasher;
+    hasher.update(data, len);
+    return hasher.finalize();
+}
+
+// Top 64 bits of the hash read as a 256-bit little-endian number, as Bitcoin compares against the target
+uint64_t hash_key(const Digest& hash) {
+    uint64_t key = 0;
+    for (int b = 7; b >= 0; b--) {
+        key = (key << 8) | hash[24 + b];
+    }
+    return key;
+}
+
+// Hashes every nonce in [start, end); the first 64 header bytes are absorbed once as a midstate
+SearchResult search(const Header& header, uint32_t start, uint32_t end) {
+    SHA256 midstate;
+    midstate.update(header.data(), 64);
+    uint8_t tail[16];
+    std::memcpy(tail, &header[64], 12);
+
+    SearchResult result{0, {std::numeric_limits<uint64_t>::max(), 0}};
+    for (uint32_t nonce = start; nonce < end; nonce++) {
+        put_le32(tail + 12, nonce);
+        SHA256 hasher = midstate;
+        hasher.update(tail, 16);
+        Digest first = hasher.finalize();
+        uint64_t key = hash_key(sha256(first.data(), first.size()));
+        if ((key >> (64 - ZERO_BITS)) == 0) {
+            result.found++;
+        }
+        result.best = std::min(result.best, Best{key, nonce});
+    }
+    return result;
+}
+
+SearchResult parallel_search(const Header& header, uint32_t nonces) {
+    std::vector<std::thread> threads;
+    std::vector<SearchResult> results(NUM_THREADS);
+    uint32_t per_thread = nonces / NUM_THREADS;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        uint32_t lo = t * per_thread;
+        uint32_t hi = (t == NUM_THREADS - 1) ? nonces : (t + 1) * per_thread;
+        threads.emplace_back([&, t, lo, hi]() { results[t] = search(header, lo, hi); });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+
+    SearchResult total{0, {std::numeric_limits<uint64_t>::max(), 0}};
+    for (const auto& r : results) {
+        total.found += r.found;
+        total.best = std::min(total.best, r.best);
+    }
+    return total;
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    Header header = build_header();
+
+    // Warm-up
+    parallel_search(header, NUM_NONCES / 64);
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    SearchResult result = parallel_search(header, NUM_NONCES);
+    measure->stop();
+    auto end = std::chrono::high_resolution_clock::now();
+    std::chrono::duration<double> duration = end - start;
+
+    // Checksum: rehash the best nonce and print it in Bitcoin's byte-reversed display order
+    Header block = header;
+    put_le32(&block[76], result.best.second);
+    Digest first = sha256(block.data(), block.size());
+    Digest best_hash = sha256(first.data(), first.size());
+    std::stringstream display;
+    for (int b = 31; b >= 0; b--) {
+        display << std::hex << std::setfill('0') << std::setw(2) << static_cast<int>(best_hash[b]);
+    }
+
+    measure->report();
+    std::cerr << std::fixed << std::setprecision(2) << static_cast<double>(NUM_NONCES) / duration.count() / 1e6
+              << " MH/s (double SHA-256), " << result.found << " of " << NUM_NONCES << " nonces below the "
+              << ZERO_BITS << "-bit target" << std::endl;
+    std::cerr << "Checksum: nonce " << result.best.second << " -> " << display.str() << std::endl;
+
+    return 0;
+}
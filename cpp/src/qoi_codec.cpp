@@ -0,0 +1,249 @@
+#include <iostream>
+#include <vector>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstring>
+#include <stdexcept>
+
+const uint32_t WIDTH = 4096;  // 64 MB of RGBA8
+const uint32_t HEIGHT = 4096;
+const size_t CHANNELS = 4;
+const size_t ROUNDS = 5;
+
+const uint8_t QOI_OP_INDEX = 0x00; // 00xxxxxx
+const uint8_t QOI_OP_DIFF = 0x40;  // 01xxxxxx
+const uint8_t QOI_OP_LUMA = 0x80;  // 10xxxxxx
+const uint8_t QOI_OP_RUN = 0xc0;   // 11xxxxxx
+const uint8_t QOI_OP_RGB = 0xfe;
+const uint8_t QOI_OP_RGBA = 0xff;
+const uint8_t QOI_MASK_2 = 0xc0;
+const uint8_t QOI_MAGIC[4] = {'q', 'o', 'i', 'f'};
+const size_t QOI_HEADER_SIZE = 14;
+const uint8_t QOI_PADDING[8] = {0, 0, 0, 0, 0, 0, 0, 1};
+
+struct Pixel {
+    uint8_t r, g, b, a;
+
+    bool operator==(const Pixel& other) const = default;
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// 256x256 tiles cycling through flat fills, gradients, a small palette and noise,
+// so every QOI op shows up in realistic proportions
+std::vector<uint8_t> generate_image() {
+    size_t width = WIDTH, height = HEIGHT;
+    uint64_t seed = 42;
+    std::vector<Pixel> palette;
+    for (int i = 0; i < 16; i++) {
+        uint64_t c = next_random(seed);
+        palette.push_back({static_cast<uint8_t>(c), static_cast<uint8_t>(c >> 8), static_cast<uint8_t>(c >> 16), 255});
+    }
+    std::vector<uint8_t> pixels;
+    pixels.reserve(width * height * CHANNELS);
+    for (size_t y = 0; y < height; y++) {
+        for (size_t x = 0; x < width; x++) {
+            uint64_t noise = next_random(seed);
+            size_t tile = x / 256 + y / 256;
+            Pixel pixel;
+            switch (tile % 4) {
+                case 0:
+                    pixel = {static_cast<uint8_t>(tile * 37), static_cast<uint8_t>(tile * 91),
+                             static_cast<uint8_t>(tile * 53), 255};
+                    break;
+                case 1:
+                    pixel = {static_cast<uint8_t>(x / 2), static_cast<uint8_t>(y / 3), static_cast<uint8_t>((x + y) / 4),
+                             255};
+                    break;
+                case 2:
+                    pixel = palette[noise % 16];
+                    break;
+                default:
+                    pixel = {static_cast<uint8_t>(x + (noise & 63)), static_cast<uint8_t>(y + ((noise >> 6) & 15)),
+                             static_cast<uint8_t>(noise >> 10), 255};
+                    break;
+            }
+            uint8_t alpha = (x / 128) % 9 == 4 ? static_cast<uint8_t>(y * 255 / height) : pixel.a;
+            pixels.insert(pixels.end(), {pixel.r, pixel.g, pixel.b, alpha});
+        }
+    }
+    return pixels;
+}
+
+size_t color_hash(Pixel p) {
+    return (p.r * 3 + p.g * 5 + p.b * 7 + p.a * 11) % 64;
+}
+
+void push_be32(std::vector<uint8_t>& out, uint32_t v) {
+    out.insert(out.end(), {static_cast<uint8_t>(v >> 24), static_cast<uint8_t>(v >> 16), static_cast<uint8_t>(v >> 8),
+                           static_cast<uint8_t>(v)});
+}
+
+uint32_t read_be32(const uint8_t* p) {
+    return static_cast<uint32_t>(p[0]) << 24 | static_cast<uint32_t>(p[1]) << 16 | static_cast<uint32_t>(p[2]) << 8 | p[3];
+}
+
+// Straight from the QOI specification, one op per pixel or run
+std::vector<uint8_t> encode(const std::vector<uint8_t>& pixels, uint32_t width, uint32_t height) {
+    size_t pixel_count = static_cast<size_t>(width) * height;
+    std::vector<uint8_t> out;
+    out.reserve(QOI_HEADER_SIZE + pixel_count * (CHANNELS + 1) + sizeof(QOI_PADDING));
+    for (uint8_t c : QOI_MAGIC) out.push_back(c);
+    push_be32(out, width);
+    push_be32(out, height);
+    out.push_back(CHANNELS);
+    out.push_back(0); // sRGB with linear alpha
+
+    Pixel index[64] = {};
+    Pixel prev = {0, 0, 0, 255};
+    uint8_t run = 0;
+    for (size_t i = 0; i < pixel_count; i++) {
+        const uint8_t* chunk = &pixels[i * CHANNELS];
+        Pixel px = {chunk[0], chunk[1], chunk[2], chunk[3]};
+        if (px == prev) {
+            run++;
+            if (run == 62 || i == pixel_count - 1) {
+                out.push_back(QOI_OP_RUN | (run - 1));
+                run = 0;
+            }
+            continue;
+        }
+        if (run > 0) {
+            out.push_back(QOI_OP_RUN | (run - 1));
+            run = 0;
+        }
+
+        size_t hash = color_hash(px);
+        if (index[hash] == px) {
+            out.push_back(QOI_OP_INDEX | hash);
+        } else {
+            index[hash] = px;
+            if (px.a == prev.a) {
+                int8_t vr = static_cast<int8_t>(px.r - prev.r);
+                int8_t vg = static_cast<int8_t>(px.g - prev.g);
+                int8_t vb = static_cast<int8_t>(px.b - prev.b);
+                int8_t vg_r = static_cast<int8_t>(vr - vg);
+                int8_t vg_b = static_cast<int8_t>(vb - vg);
+                if (vr >= -2 && vr <= 1 && vg >= -2 && vg <= 1 && vb >= -2 && vb <= 1) {
+                    out.push_back(QOI_OP_DIFF | (vr + 2) << 4 | (vg + 2) << 2 | (vb + 2));
+                } else if (vg_r >= -8 && vg_r <= 7 && vg >= -32 && vg <= 31 && vg_b >= -8 && vg_b <= 7) {
+                    out.push_back(QOI_OP_LUMA | (vg + 32));
+                    out.push_back((vg_r + 8) << 4 | (vg_b + 8));
+                } else {
+                    out.insert(out.end(), {QOI_OP_RGB, px.r, px.g, px.b});
+                }
+            } else {
+                out.insert(out.end(), {QOI_OP_RGBA, px.r, px.g, px.b, px.a});
+            }
+        }
+        prev = px;
+    }
+    out.insert(out.end(), QOI_PADDING, QOI_PADDING + sizeof(QOI_PADDING));
+    return out;
+}
+
+struct Image {
+    uint32_t width, height;
+    std::vector<uint8_t> pixels;
+};
+
+Image decode(const std::vector<uint8_t>& data) {
+    if (data.size() < QOI_HEADER_SIZE + sizeof(QOI_PADDING) || std::memcmp(data.data(), QOI_MAGIC, 4) != 0) {
+        throw std::runtime_error("Not a QOI image");
+    }
+    uint32_t width = read_be32(&data[4]);
+    uint32_t height = read_be32(&data[8]);
+    if (data[12] != CHANNELS) {
+        throw std::runtime_error("Only RGBA QOI images are supported");
+    }
+
+    std::vector<uint8_t> pixels(static_cast<size_t>(width) * height * CHANNELS);
+    Pixel index[64] = {};
+    Pixel px = {0, 0, 0, 255};
+    uint8_t run = 0;
+    size_t p = QOI_HEADER_SIZE;
+    size_t chunks_end = data.size() - sizeof(QOI_PADDING);
+    for (size_t pos = 0; pos < pixels.size(); pos += CHANNELS) {
+        if (run > 0) {
+            run--;
+        } else if (p < chunks_end) {
+            uint8_t b1 = data[p++];
+            if (b1 == QOI_OP_RGB) {
+                px.r = data[p++];
+                px.g = data[p++];
+                px.b = data[p++];
+            } else if (b1 == QOI_OP_RGBA) {
+                px.r = data[p++];
+                px.g = data[p++];
+                px.b = data[p++];
+                px.a = data[p++];
+            } else if ((b1 & QOI_MASK_2) == QOI_OP_INDEX) {
+                px = index[b1];
+            } else if ((b1 & QOI_MASK_2) == QOI_OP_DIFF) {
+                px.r += ((b1 >> 4) & 0x03) - 2;
+                px.g += ((b1 >> 2) & 0x03) - 2;
+                px.b += (b1 & 0x03) - 2;
+            } else if ((b1 & QOI_MASK_2) == QOI_OP_LUMA) {
+                uint8_t b2 = data[p++];
+                int vg = (b1 & 0x3f) - 32;
+                px.r += vg - 8 + ((b2 >> 4) & 0x0f);
+                px.g += vg;
+                px.b += vg - 8 + (b2 & 0x0f);
+            } else {
+                run = b1 & 0x3f;
+            }
+            index[color_hash(px)] = px;
+        }
+        std::memcpy(&pixels[pos], &px, CHANNELS);
+    }
+    return {width, height, std::move(pixels)};
+}
+
+int main() {
+    std::vector<uint8_t> pixels = generate_image();
+    double megabytes = pixels.size() / 1e6;
+
+    // Warm-up on the top sixteenth of the image
+    decode(encode(std::vector<uint8_t>(pixels.begin(), pixels.begin() + pixels.size() / 16), WIDTH, HEIGHT / 16));
+
+    std::chrono::duration<double> encode_time(0);
+    std::chrono::duration<double> decode_time(0);
+    std::vector<uint8_t> encoded;
+    Image decoded;
+    for (size_t round = 0; round < ROUNDS; round++) {
+        auto start = std::chrono::high_resolution_clock::now();
+        encoded = encode(pixels, WIDTH, HEIGHT);
+        encode_time += std::chrono::high_resolution_clock::now() - start;
+
+        start = std::chrono::high_resolution_clock::now();
+        decoded = decode(encoded);
+        decode_time += std::chrono::high_resolution_clock::now() - start;
+        if (decoded.width != WIDTH || decoded.height != HEIGHT) {
+            std::cerr << "Decoded dimensions differ from the original" << std::endl;
+            return 1;
+        }
+    }
+    if (decoded.pixels != pixels) {
+        std::cerr << "Decoded pixels differ from the original" << std::endl;
+        return 1;
+    }
+
+    // The format is fully specified, so the encoded bytes must match Rust exactly
+    uint64_t byte_sum = 0;
+    for (uint8_t b : encoded) byte_sum += b;
+
+    std::cout << std::fixed << std::setprecision(6) << (encode_time + decode_time).count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(1) << "Image: " << WIDTH << "x" << HEIGHT << " RGBA8 ("
+              << megabytes << " MB raw), " << ROUNDS << " rounds" << std::endl;
+    std::cerr << std::setprecision(2) << "Encoded size: " << encoded.size() / 1e6 << " MB (ratio "
+              << std::setprecision(3) << static_cast<double>(encoded.size()) / pixels.size() << ")" << std::endl;
+    std::cerr << std::setprecision(1) << "Encode: " << megabytes * ROUNDS / encode_time.count()
+              << " MB/s, decode: " << megabytes * ROUNDS / decode_time.count() << " MB/s" << std::endl;
+    std::cerr << "Checksum: " << encoded.size() << " encoded bytes, byte sum " << byte_sum << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,338 @@
+#include <iostream>
+#include <vector>
+#include <array>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+#include <utility>
+#include <cmath>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+
+const size_t WIDTH = 1920;
+const size_t HEIGHT = 1080;
+const size_t DEFAULT_LAYERS = 32; // 32 x 64,800 = 2.07M triangles
+const size_t WARMUP_LAYERS = 2;
+const size_t CELL = 8; // mesh cell size in pixels, two triangles per cell
+const float JITTER = 1.5f; // interior vertices move up to this far each way; under CELL / 4 keeps cells convex
+const float NEAR = 2.0f;
+const float FAR = 40.0f;
+const int32_t SUBPIXEL_BITS = 4; // vertices snap to 1/16 pixel
+const int32_t SUBPIXEL = 1 << SUBPIXEL_BITS;
+const uint32_t TEXTURE_BITS = 8;
+const size_t TEXTURE_SIZE = size_t(1) << TEXTURE_BITS; // 256x256 RGBA8, wrapped
+const uint64_t SEED = 0x7261'7374'6572'697a;
+
+// Screen-space corner: position in pixels, w (view depth) and texture coordinates in texels/size
+struct Vertex {
+    float x, y, w, u, v;
+};
+
+typedef std::array<Vertex, 3> Triangle;
+
+struct Texture {
+    std::vector<uint32_t> texels; // RGBA8, red in the low byte
+};
+
+struct Framebuffer {
+    std::vector<uint32_t> color;
+    std::vector<float> depth; // 1/w of the nearest surface so far, 0 for none
+};
+
+struct Stats {
+    uint64_t triangles = 0; // set up and scanned, after dropping zero-area ones
+    uint64_t tested = 0;    // pixels inside bounding boxes
+    uint64_t covered = 0;   // pixels inside triangles
+    uint64_t shaded = 0;    // pixels that passed the depth test and were textured
+};
+
+// SplitMix64; the top 24 bits become a float in [0, 1) exactly, in both languages
+struct Rng {
+    uint64_t state;
+
+    uint64_t next() {
+        state += 0x9e3779b97f4a7c15ULL;
+        uint64_t z = state;
+        z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+        z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+        return z ^ (z >> 31);
+    }
+
+    float range(float lo, float hi) {
+        return lo + (hi - lo) * (static_cast<float>(next() >> 40) * (1.0f / 16777216.0f));
+    }
+};
+
+// Full-screen meshes of jittered cells, one per layer, from FAR to NEAR in painter's order.
+// Border vertices stay on the screen edge, so every layer covers each pixel exactly once.
+// Each vertex's w varies up to 50% past its layer's depth, so perspective-correct and affine
+// interpolation differ and neighbouring layers interpenetrate
+std::vector<Triangle> make_scene(Rng& rng, size_t layers) {
+    size_t columns = WIDTH / CELL, rows = HEIGHT / CELL;
+    std::vector<Triangle> triangles;
+    triangles.reserve(layers * columns * rows * 2);
+    for (size_t layer = 0; layer < layers; layer++) {
+        float t = static_cast<float>(layer) / static_cast<float>(std::max<size_t>(layers, 2) - 1);
+        float depth = FAR + (NEAR - FAR) * t;
+        float offset = static_cast<float>(layer) * 0.37f; // so layers show different parts of the texture
+        std::vector<Vertex> grid;
+        grid.reserve((columns + 1) * (rows + 1));
+        for (size_t gy = 0; gy <= rows; gy++) {
+            for (size_t gx = 0; gx <= columns; gx++) {
+                float jitter_x = gx == 0 || gx == columns ? 0.0f : rng.range(-JITTER, JITTER);
+                float jitter_y = gy == 0 || gy == rows ? 0.0f : rng.range(-JITTER, JITTER);
+                float x = static_cast<float>(gx * CELL) + jitter_x;
+                float y = static_cast<float>(gy * CELL) + jitter_y;
+                float w = depth * rng.range(1.0f, 1.5f);
+                grid.push_back({x, y, w, x / 64.0f + offset, y / 64.0f + offset});
+            }
+        }
+        for (size_t gy = 0; gy < rows; gy++) {
+            for (size_t gx = 0; gx < columns; gx++) {
+                auto corner = [&](size_t dx, size_t dy) { return grid[(gy + dy) * (columns + 1) + gx + dx]; };
+                Vertex a = corner(0, 0), b = corner(1, 0), c = corner(1, 1), d = corner(0, 1);
+                // Alternate the diagonal, and the winding, from cell to cell
+                if ((gx + gy) % 2 == 0) {
+                    triangles.push_back({a, b, c});
+                    triangles.push_back({a, d, c});
+                } else {
+                    triangles.push_back({a, b, d});
+                    triangles.push_back({b, c, d});
+                }
+            }
+        }
+    }
+    return triangles;
+}
+
+// An XOR pattern over an 8x8 checkerboard, so both minification and blending show up in the checksum
+Texture make_texture() {
+    Texture texture;
+    texture.texels.reserve(TEXTURE_SIZE * TEXTURE_SIZE);
+    for (uint32_t y = 0; y < TEXTURE_SIZE; y++) {
+        for (uint32_t x = 0; x < TEXTURE_SIZE; x++) {
+            uint32_t checker = ((x >> 5) ^ (y >> 5)) & 1 ? 0x20 : 0xe0;
+            uint32_t r = (x ^ y) & 0xff;
+            uint32_t g = checker;
+            uint32_t b = ((x * y) >> 4) & 0xff;
+            texture.texels.push_back(r | (g << 8) | (b << 16) | 0xff000000u);
+        }
+    }
+    return texture;
+}
+
+// Bilinear filtering in 8.8 fixed point: u and v are in texels scaled by 256, the four weights
+// are 8-bit products summing to 65536, and each channel is blended in integers
+inline uint32_t sample_bilinear(const Texture& texture, int32_t u, int32_t v) {
+    const int32_t mask = static_cast<int32_t>(TEXTURE_SIZE) - 1;
+    u -= 128; // texel centers sit at +0.5
+    v -= 128;
+    int32_t x0 = (u >> 8) & mask, y0 = (v >> 8) & mask;
+    int32_t x1 = (x0 + 1) & mask, y1 = (y0 + 1) & mask;
+    uint32_t fx = static_cast<uint32_t>(u & 0xff), fy = static_cast<uint32_t>(v & 0xff);
+    auto texel = [&](int32_t x, int32_t y) { return texture.texels[static_cast<size_t>(y) * TEXTURE_SIZE + x]; };
+    uint32_t t00 = texel(x0, y0), t10 = texel(x1, y0), t01 = texel(x0, y1), t11 = texel(x1, y1);
+    uint32_t w00 = (256 - fx) * (256 - fy), w10 = fx * (256 - fy);
+    uint32_t w01 = (256 - fx) * fy, w11 = fx * fy;
+    uint32_t out = 0;
+    for (uint32_t shift : {0u, 8u, 16u, 24u}) {
+        auto channel = [shift](uint32_t t) { return (t >> shift) & 0xff; };
+        uint32_t blended = channel(t00) * w00 + channel(t10) * w10 + channel(t01) * w01 + channel(t11) * w11;
+        out |= (blended >> 16) << shift;
+    }
+    return out;
+}
+
+// One edge in fixed point: value at the first pixel center of the bounding box and its steps.
+// The fill test is value + bias >= 0, where bias is -1 off top and left edges, so a pixel center
+// exactly on an edge shared by two triangles is drawn by exactly one of them
+struct Edge {
+    int32_t step_x;
+    int32_t step_y;
+    int32_t bias;
+
+    // From a to b in 28.4 fixed point, for a triangle wound so its area is positive
+    Edge(int32_t ax, int32_t ay, int32_t bx, int32_t by) {
+        int32_t dx = bx - ax, dy = by - ay;
+        bool top_left = (dy == 0 && dx > 0) || dy < 0;
+        step_x = -dy * SUBPIXEL;
+        step_y = dx * SUBPIXEL;
+        bias = top_left ? 0 : -1;
+    }
+};
+
+inline int32_t edge_function(int32_t ax, int32_t ay, int32_t bx, int32_t by, int32_t px, int32_t py) {
+    return (bx - ax) * (py - ay) - (by - ay) * (px - ax);
+}
+
+// Scans the triangle's bounding box with incremental integer edge functions and calls
+// pixel(x, y, b0, b1, b2) with barycentric weights at each covered pixel center. Vertices must
+// lie on screen, which keeps every edge value within int32_t
+template <typename Pixel>
+void rasterize(const Triangle& tri, Stats& stats, Pixel&& pixel) {
+    auto snap = [](float c) { return static_cast<int32_t>(std::round(c * static_cast<float>(SUBPIXEL))); };
+    int32_t x[3] = {snap(tri[0].x), snap(tri[1].x), snap(tri[2].x)};
+    int32_t y[3] = {snap(tri[0].y), snap(tri[1].y), snap(tri[2].y)};
+    int order[3] = {0, 1, 2};
+    int32_t area = edge_function(x[0], y[0], x[1], y[1], x[2], y[2]);
+    if (area == 0) return;
+    if (area < 0) {
+        std::swap(x[1], x[2]);
+        std::swap(y[1], y[2]);
+        std::swap(order[1], order[2]);
+        area = -area;
+    }
+    stats.triangles++;
+
+    // Pixel centers whose box overlaps the triangle's, clamped to the screen
+    int32_t min_x = std::max((std::min({x[0], x[1], x[2]}) + SUBPIXEL / 2 - 1) >> SUBPIXEL_BITS, 0);
+    int32_t min_y = std::max((std::min({y[0], y[1], y[2]}) + SUBPIXEL / 2 - 1) >> SUBPIXEL_BITS, 0);
+    int32_t max_x = std::min((std::max({x[0], x[1], x[2]}) - SUBPIXEL / 2) >> SUBPIXEL_BITS, static_cast<int32_t>(WIDTH) - 1);
+    int32_t max_y = std::min((std::max({y[0], y[1], y[2]}) - SUBPIXEL / 2) >> SUBPIXEL_BITS, static_cast<int32_t>(HEIGHT) - 1);
+    if (min_x > max_x || min_y > max_y) return;
+
+    // Edge i is opposite vertex i, so its value is vertex i's barycentric weight times the area
+    const Edge edges[3] = {Edge(x[1], y[1], x[2], y[2]), Edge(x[2], y[2], x[0], y[0]), Edge(x[0], y[0], x[1], y[1])};
+    int32_t px0 = min_x * SUBPIXEL + SUBPIXEL / 2, py0 = min_y * SUBPIXEL + SUBPIXEL / 2;
+    int32_t row[3] = {
+        edge_function(x[1], y[1], x[2], y[2], px0, py0),
+        edge_function(x[2], y[2], x[0], y[0], px0, py0),
+        edge_function(x[0], y[0], x[1], y[1], px0, py0),
+    };
+    float inv_area = 1.0f / static_cast<float>(area);
+    stats.tested += static_cast<uint64_t>((max_x - min_x + 1) * (max_y - min_y + 1));
+
+    for (int32_t py = min_y; py <= max_y; py++) {
+        int32_t e[3] = {row[0], row[1], row[2]};
+        for (int32_t px = min_x; px <= max_x; px++) {
+            if (((e[0] + edges[0].bias) | (e[1] + edges[1].bias) | (e[2] + edges[2].bias)) >= 0) {
+                stats.covered++;
+                float b[3];
+                for (int i = 0; i < 3; i++) {
+                    b[order[i]] = static_cast<float>(e[i]) * inv_area;
+                }
+                pixel(static_cast<size_t>(px), static_cast<size_t>(py), b[0], b[1], b[2]);
+            }
+            for (int i = 0; i < 3; i++) {
+                e[i] += edges[i].step_x;
+            }
+        }
+        for (int i = 0; i < 3; i++) {
+            row[i] += edges[i].step_y;
+        }
+    }
+}
+
+// 1/w, u/w and v/w are linear in screen space, so they are interpolated with the barycentric
+// weights and u and v recovered with one divide per pixel: perspective-correct texture mapping
+struct Interpolants {
+    float iw[3], uw[3], vw[3];
+
+    explicit Interpolants(const Triangle& tri) {
+        for (int i = 0; i < 3; i++) {
+            iw[i] = 1.0f / tri[i].w;
+            uw[i] = tri[i].u * iw[i];
+            vw[i] = tri[i].v * iw[i];
+        }
+    }
+
+    float depth(float b0, float b1, float b2) const {
+        return b0 * iw[0] + b1 * iw[1] + b2 * iw[2];
+    }
+
+    void uv(float b0, float b1, float b2, float depth, float& u, float& v) const {
+        float w = 1.0f / depth;
+        u = (b0 * uw[0] + b1 * uw[1] + b2 * uw[2]) * w;
+        v = (b0 * vw[0] + b1 * vw[1] + b2 * vw[2]) * w;
+    }
+};
+
+// Depth-tests on 1/w (larger is nearer), then samples the texture and writes the pixel
+void draw(Framebuffer& fb, const Texture& texture, const Triangle& tri, Stats& stats) {
+    Interpolants interpolants(tri);
+    const float scale = static_cast<float>(TEXTURE_SIZE * 256);
+    uint64_t shaded = 0;
+    rasterize(tri, stats, [&](size_t x, size_t y, float b0, float b1, float b2) {
+        size_t index = y * WIDTH + x;
+        float z = interpolants.depth(b0, b1, b2);
+        if (z <= fb.depth[index]) return;
+        fb.depth[index] = z;
+        float u, v;
+        interpolants.uv(b0, b1, b2, z, u, v);
+        fb.color[index] = sample_bilinear(texture, static_cast<int32_t>(u * scale), static_cast<int32_t>(v * scale));
+        shaded++;
+    });
+    stats.shaded += shaded;
+}
+
+Stats render(Framebuffer& fb, const Texture& texture, const std::vector<Triangle>& triangles) {
+    std::fill(fb.color.begin(), fb.color.end(), 0u);
+    std::fill(fb.depth.begin(), fb.depth.end(), 0.0f);
+    Stats stats;
+    for (const Triangle& tri : triangles) {
+        draw(fb, texture, tri, stats);
+    }
+    return stats;
+}
+
+size_t parse_layers(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--layers") == 0) {
+            long long n = i + 1 < argc ? std::atoll(argv[i + 1]) : 0;
+            if (n <= 0) {
+                std::cerr << "--layers expects a positive integer" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<size_t>(n);
+        }
+    }
+    return DEFAULT_LAYERS;
+}
+
+int main(int argc, char** argv) {
+    size_t layers = parse_layers(argc, argv);
+    Rng rng{SEED};
+    std::vector<Triangle> triangles = make_scene(rng, layers);
+    Texture texture = make_texture();
+    Framebuffer fb{std::vector<uint32_t>(WIDTH * HEIGHT), std::vector<float>(WIDTH * HEIGHT)};
+
+    // Warm-up
+    Rng warmup_rng{SEED ^ 1};
+    render(fb, texture, make_scene(warmup_rng, WARMUP_LAYERS));
+
+    // Benchmark
+    auto start = std::chrono::high_resolution_clock::now();
+    Stats stats = render(fb, texture, triangles);
+    std::chrono::duration<double> duration = std::chrono::high_resolution_clock::now() - start;
+
+    // The meshes are watertight, so each layer covers every pixel once: no cracks, no double hits
+    uint64_t expected = layers * WIDTH * HEIGHT;
+    if (stats.covered != expected) {
+        fprintf(stderr, "Covered %llu pixels, expected %llu for %zu watertight layers\n",
+                static_cast<unsigned long long>(stats.covered), static_cast<unsigned long long>(expected), layers);
+        return 1;
+    }
+
+    uint64_t checksum = 0;
+    for (uint32_t c : fb.color) {
+        checksum += c;
+    }
+    double seconds = duration.count();
+
+    std::cout << std::fixed << std::setprecision(6) << seconds << std::endl;
+    fprintf(stderr, "Framebuffer: %zux%zu, %zux%zu RGBA8 texture, bilinear, perspective-correct\n", WIDTH, HEIGHT, TEXTURE_SIZE, TEXTURE_SIZE);
+    fprintf(stderr, "Triangles: %llu drawn, %.2fM triangles/s\n",
+            static_cast<unsigned long long>(stats.triangles), stats.triangles / seconds / 1e6);
+    fprintf(stderr, "Pixels: %llu tested, %llu covered (%.1f per triangle), %llu shaded, %.1fM shaded pixels/s\n",
+            static_cast<unsigned long long>(stats.tested), static_cast<unsigned long long>(stats.covered),
+            static_cast<double>(stats.covered) / std::max<uint64_t>(stats.triangles, 1),
+            static_cast<unsigned long long>(stats.shaded), stats.shaded / seconds / 1e6);
+    fprintf(stderr, "Depth complexity: %.2f covered per screen pixel, %.1f%% of covered pixels passed the depth test\n",
+            static_cast<double>(stats.covered) / (WIDTH * HEIGHT),
+            100.0 * stats.shaded / std::max<uint64_t>(stats.covered, 1));
+    fprintf(stderr, "Checksum: %llu\n", static_cast<unsigned long long>(checksum));
+
+    return 0;
+}
@@ -2,149 +2,1097 @@
 #include <vector>
 #include <cmath>
 #include <thread>
+#include <atomic>
 #include <chrono>
 #include <iomanip>
 #include <algorithm>
+#include <limits>
+#include <numeric>
+#include <string>
+#include <fstream>
+#include <sstream>
+#include <map>
+#include <array>
+#include <memory>
+#include <optional>
+#include <stdexcept>
+#include <cstring>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <png.h>
+
+#include "bench_measure.h"
 
 const size_t WIDTH = 1920;
 const size_t HEIGHT = 1080;
 const size_t SAMPLES = 4;
+const size_t DEFAULT_MAX_DEPTH = 8;
+const size_t LEAF_SIZE = 4;
+const size_t TILE_SIZE = 32; // square tiles; the last column and row are cut to the frame
 const size_t NUM_THREADS = 8;
+const double T_MIN = 1e-4;
+const size_t AO_RAYS = 4; // occlusion rays per camera sample in --mode ao
+const double AO_DISTANCE = 0.5; // how far an occluder may be and still shade the hit
+const char* GOLDEN_PATH = "golden/ray_tracer.png"; // relative to the repository root, where build.py runs
+const char* GOLDEN_AO_PATH = "golden/ray_tracer_ao.png";
+const uint8_t PIXEL_TOLERANCE = 8;           // per-channel difference, out of 255
+const double MAX_MISMATCH_FRACTION = 0.001;  // pixels beyond tolerance before --verify fails
+const double PI = 3.14159265358979323846;
 
 struct Vec3 {
     double x, y, z;
-    
+
     Vec3(double x = 0, double y = 0, double z = 0) : x(x), y(y), z(z) {}
-    
+
     double dot(const Vec3& other) const {
         return x * other.x + y * other.y + z * other.z;
     }
-    
+
     double length() const {
         return std::sqrt(x * x + y * y + z * z);
     }
-    
+
     Vec3 normalize() const {
         double len = length();
         return Vec3(x / len, y / len, z / len);
     }
-    
+
     Vec3 operator-(const Vec3& other) const {
         return Vec3(x - other.x, y - other.y, z - other.z);
     }
-    
+
     Vec3 operator+(const Vec3& other) const {
         return Vec3(x + other.x, y + other.y, z + other.z);
     }
-    
+
     Vec3 operator*(double scalar) const {
         return Vec3(x * scalar, y * scalar, z * scalar);
     }
+
+    Vec3 operator*(const Vec3& other) const {
+        return Vec3(x * other.x, y * other.y, z * other.z);
+    }
+
+    Vec3 min(const Vec3& other) const {
+        return Vec3(std::min(x, other.x), std::min(y, other.y), std::min(z, other.z));
+    }
+
+    Vec3 max(const Vec3& other) const {
+        return Vec3(std::max(x, other.x), std::max(y, other.y), std::max(z, other.z));
+    }
+
+    double axis(size_t a) const {
+        return a == 0 ? x : (a == 1 ? y : z);
+    }
+
+    Vec3 cross(const Vec3& other) const {
+        return Vec3(y * other.z - z * other.y, z * other.x - x * other.z, x * other.y - y * other.x);
+    }
+
+    Vec3 reflect(const Vec3& normal) const {
+        return *this - normal * (2.0 * dot(normal));
+    }
+};
+
+// Two unit vectors completing unit n to an orthonormal basis, without a branch on its direction
+// (Duff et al., "Building an Orthonormal Basis, Revisited")
+void orthonormal_basis(const Vec3& n, Vec3& tangent, Vec3& bitangent) {
+    double sign = std::copysign(1.0, n.z);
+    double a = -1.0 / (sign + n.z);
+    double b = n.x * n.y * a;
+    tangent = Vec3(1.0 + sign * n.x * n.x * a, sign * b, -sign * n.x);
+    bitangent = Vec3(b, sign + n.y * n.y * a, -n.y);
+}
+
+// Small PCG-style generator, seeded per pixel so renders are deterministic
+struct Rng {
+    uint64_t state;
+
+    explicit Rng(uint64_t seed) : state(seed * 0x9E3779B97F4A7C15ULL + 1) {
+        next_u64();
+    }
+
+    uint64_t next_u64() {
+        state = state * 6364136223846793005ULL + 1442695040888963407ULL;
+        uint64_t x = state;
+        return ((x ^ (x >> 33)) * 0xff51afd7ed558ccdULL) ^ (x >> 29);
+    }
+
+    double next_f64() {
+        return static_cast<double>(next_u64() >> 11) * (1.0 / static_cast<double>(1ULL << 53));
+    }
+
+    Vec3 unit_vector() {
+        double z = 1.0 - 2.0 * next_f64();
+        double r = std::sqrt(std::max(1.0 - z * z, 0.0));
+        double phi = 2.0 * PI * next_f64();
+        return Vec3(r * std::cos(phi), r * std::sin(phi), z);
+    }
+
+    // Cosine-weighted over the hemisphere around unit normal: uniform on the unit disk, lifted
+    // onto the hemisphere
+    Vec3 cosine_direction(const Vec3& normal) {
+        double r2 = next_f64();
+        double r = std::sqrt(r2);
+        double phi = 2.0 * PI * next_f64();
+        Vec3 tangent, bitangent;
+        orthonormal_basis(normal, tangent, bitangent);
+        return tangent * (r * std::cos(phi)) + bitangent * (r * std::sin(phi)) + normal * std::sqrt(std::max(1.0 - r2, 0.0));
+    }
+};
+
+enum class Material {
+    Diffuse,
+    Reflective,
+    Emissive // a light: ends the path, contributing its color
+};
+
+// What the BVH builder needs to know about one primitive
+struct PrimitiveBounds {
+    Vec3 min;
+    Vec3 max;
+    Vec3 centroid;
 };
 
 struct Sphere {
     Vec3 center;
     double radius;
     Vec3 color;
-    
-    bool intersect(const Vec3& origin, const Vec3& direction, double& t) const {
+    Material material;
+
+    bool intersect(const Vec3& origin, const Vec3& direction, double t_max, double& t) const {
         Vec3 oc = origin - center;
         double a = direction.dot(direction);
-        double b = 2.0 * oc.dot(direction);
+        double half_b = oc.dot(direction);
         double c = oc.dot(oc) - radius * radius;
-        double discriminant = b * b - 4.0 * a * c;
-        
+        double discriminant = half_b * half_b - a * c;
+
         if (discriminant < 0.0) {
             return false;
         }
-        
-        t = (-b - std::sqrt(discriminant)) / (2.0 * a);
-        return t > 0.0;
+
+        double sqrt_d = std::sqrt(discriminant);
+        double near = (-half_b - sqrt_d) / a;
+        if (near > T_MIN && near < t_max) {
+            t = near;
+            return true;
+        }
+        double far = (-half_b + sqrt_d) / a;
+        if (far > T_MIN && far < t_max) {
+            t = far;
+            return true;
+        }
+        return false;
+    }
+
+    PrimitiveBounds bounds() const {
+        Vec3 r(radius, radius, radius);
+        return {center - r, center + r, center};
     }
 };
 
-Vec3 trace_ray(const Vec3& origin, const Vec3& direction, const std::vector<Sphere>& spheres) {
-    double closest_t = std::numeric_limits<double>::infinity();
-    const Sphere* hit_sphere = nullptr;
-    
-    for (const auto& sphere : spheres) {
-        double t;
-        if (sphere.intersect(origin, direction, t) && t < closest_t) {
-            closest_t = t;
-            hit_sphere = &sphere;
-        }
-    }
-    
-    if (hit_sphere) {
-        Vec3 hit_point = origin + direction * closest_t;
-        Vec3 normal = (hit_point - hit_sphere->center).normalize();
-        Vec3 light_dir = Vec3(1.0, 1.0, 1.0).normalize();
-        double diffuse = std::max(0.0, normal.dot(light_dir));
-        return hit_sphere->color * diffuse;
-    }
-    
-    return Vec3(0.2, 0.3, 0.4); // Background color
-}
-
-void render_section(const std::vector<Sphere>& spheres, std::vector<Vec3>& image,
-                   size_t start_row, size_t end_row) {
-    for (size_t y = start_row; y < end_row; y++) {
-        for (size_t x = 0; x < WIDTH; x++) {
-            Vec3 color(0, 0, 0);
-            for (size_t s = 0; s < SAMPLES; s++) {
-                double u = static_cast<double>(x) / WIDTH - 0.5;
-                double v = 0.5 - static_cast<double>(y) / HEIGHT;
-                Vec3 origin(0, 0, 0);
-                Vec3 direction = Vec3(u * 2.0, v * 2.0, -1.0).normalize();
-                Vec3 sample_color = trace_ray(origin, direction, spheres);
-                color = color + sample_color;
+// Stored as one corner plus two edges, the form the Moller-Trumbore test wants
+struct Triangle {
+    Vec3 v0;
+    Vec3 edge1;
+    Vec3 edge2;
+    Vec3 normal;
+    Vec3 color;
+    Material material;
+
+    // False for a degenerate (zero-area) triangle, which no ray can hit
+    static bool make(const Vec3& v0, const Vec3& v1, const Vec3& v2, const Vec3& color, Material material,
+                     Triangle& out) {
+        Vec3 edge1 = v1 - v0;
+        Vec3 edge2 = v2 - v0;
+        Vec3 normal = edge1.cross(edge2);
+        if (normal.dot(normal) == 0.0) return false;
+        out = {v0, edge1, edge2, normal.normalize(), color, material};
+        return true;
+    }
+
+    bool intersect(const Vec3& origin, const Vec3& direction, double t_max, double& t) const {
+        Vec3 p = direction.cross(edge2);
+        double det = edge1.dot(p);
+        if (det == 0.0) {
+            return false; // ray parallel to the plane
+        }
+        double inv_det = 1.0 / det;
+        Vec3 s = origin - v0;
+        double u = s.dot(p) * inv_det;
+        if (u < 0.0 || u > 1.0) {
+            return false;
+        }
+        Vec3 q = s.cross(edge1);
+        double v = direction.dot(q) * inv_det;
+        if (v < 0.0 || u + v > 1.0) {
+            return false;
+        }
+        double hit = edge2.dot(q) * inv_det;
+        if (hit > T_MIN && hit < t_max) {
+            t = hit;
+            return true;
+        }
+        return false;
+    }
+
+    PrimitiveBounds bounds() const {
+        Vec3 v1 = v0 + edge1;
+        Vec3 v2 = v0 + edge2;
+        return {v0.min(v1).min(v2), v0.max(v1).max(v2), (v0 + v1 + v2) * (1.0 / 3.0)};
+    }
+};
+
+// Flattened BVH node: leaves (count > 0) store a range into `indices`,
+// interior nodes store their right child in `first` (left child is next)
+struct BvhNode {
+    Vec3 min;
+    Vec3 max;
+    size_t first;
+    size_t count;
+
+    bool hit(const Vec3& origin, const Vec3& inv_dir, double t_max) const {
+        double t_near = T_MIN;
+        double t_far = t_max;
+        for (size_t a = 0; a < 3; a++) {
+            double inv = inv_dir.axis(a);
+            double o = origin.axis(a);
+            double t0 = (min.axis(a) - o) * inv;
+            double t1 = (max.axis(a) - o) * inv;
+            if (inv < 0.0) {
+                std::swap(t0, t1);
+            }
+            t_near = std::max(t_near, t0);
+            t_far = std::min(t_far, t1);
+            if (t_far < t_near) {
+                return false;
+            }
+        }
+        return true;
+    }
+};
+
+// Pinhole camera whose image plane is kept vertical: `right` is horizontal and `up` is
+// world up, so the frame spans 2 * aspect by 2 units at the tip of `direction`
+struct Camera {
+    Vec3 origin;
+    Vec3 direction;
+    Vec3 right;
+    Vec3 up;
+
+    Camera(const Vec3& origin, const Vec3& direction) : origin(origin), direction(direction), up(0.0, 1.0, 0.0) {
+        right = direction.cross(up);
+        if (right.dot(right) <= 0.0) throw std::runtime_error("Camera direction must not be vertical");
+        right = right.normalize();
+    }
+};
+
+// Background gradient from the horizon color straight ahead to the zenith color overhead
+struct Sky {
+    Vec3 horizon;
+    Vec3 zenith;
+};
+
+// BVH over one kind of primitive; `indices` orders the primitives so every leaf is a range
+class Bvh {
+public:
+    std::vector<size_t> indices;
+    std::vector<BvhNode> nodes;
+
+    explicit Bvh(const std::vector<PrimitiveBounds>& bounds) : indices(bounds.size()) {
+        std::iota(indices.begin(), indices.end(), 0);
+        if (!bounds.empty()) build(bounds, 0, bounds.size());
+    }
+
+    // Index of the nearest primitive closer than `closest_t`, which shrinks to its distance,
+    // or SIZE_MAX. `hit(i, t_max, t)` tests primitive i against the current bound
+    template <typename Hit>
+    size_t traverse(const Vec3& origin, const Vec3& inv_dir, double& closest_t, Hit hit) const {
+        size_t nearest = SIZE_MAX;
+        if (nodes.empty()) return nearest;
+        size_t stack[64];
+        size_t stack_len = 1;
+        stack[0] = 0;
+
+        while (stack_len > 0) {
+            size_t node_index = stack[--stack_len];
+            const BvhNode& node = nodes[node_index];
+            if (!node.hit(origin, inv_dir, closest_t)) {
+                continue;
+            }
+
+            if (node.count > 0) {
+                for (size_t i = node.first; i < node.first + node.count; i++) {
+                    double t;
+                    if (hit(indices[i], closest_t, t)) {
+                        closest_t = t;
+                        nearest = indices[i];
+                    }
+                }
+            } else {
+                stack[stack_len] = node.first;
+                stack[stack_len + 1] = node_index + 1;
+                stack_len += 2;
+            }
+        }
+
+        return nearest;
+    }
+
+    // Whether any primitive is closer than t_max, stopping at the first one found: an
+    // occlusion ray needs no nearest hit
+    template <typename Hit>
+    bool any_hit(const Vec3& origin, const Vec3& inv_dir, double t_max, Hit hit) const {
+        if (nodes.empty()) return false;
+        size_t stack[64];
+        size_t stack_len = 1;
+        stack[0] = 0;
+
+        while (stack_len > 0) {
+            size_t node_index = stack[--stack_len];
+            const BvhNode& node = nodes[node_index];
+            if (!node.hit(origin, inv_dir, t_max)) {
+                continue;
+            }
+
+            if (node.count > 0) {
+                for (size_t i = node.first; i < node.first + node.count; i++) {
+                    double t;
+                    if (hit(indices[i], t_max, t)) return true;
+                }
+            } else {
+                stack[stack_len] = node.first;
+                stack[stack_len + 1] = node_index + 1;
+                stack_len += 2;
             }
-            image[y * WIDTH + x] = color * (1.0 / SAMPLES);
         }
+
+        return false;
     }
+
+private:
+    // Median split along the longest axis of the centroid bounds
+    size_t build(const std::vector<PrimitiveBounds>& bounds, size_t first, size_t count) {
+        size_t node_index = nodes.size();
+        const double inf = std::numeric_limits<double>::infinity();
+        Vec3 min(inf, inf, inf), max(-inf, -inf, -inf);
+        Vec3 centroid_min = min, centroid_max = max;
+        for (size_t i = first; i < first + count; i++) {
+            const PrimitiveBounds& b = bounds[indices[i]];
+            min = min.min(b.min);
+            max = max.max(b.max);
+            centroid_min = centroid_min.min(b.centroid);
+            centroid_max = centroid_max.max(b.centroid);
+        }
+        nodes.push_back({min, max, first, count});
+
+        if (count <= LEAF_SIZE) {
+            return node_index;
+        }
+
+        Vec3 extent = centroid_max - centroid_min;
+        size_t axis = (extent.x > extent.y && extent.x > extent.z) ? 0 : (extent.y > extent.z ? 1 : 2);
+
+        std::stable_sort(indices.begin() + first, indices.begin() + first + count, [&](size_t a, size_t b) {
+            return bounds[a].centroid.axis(axis) < bounds[b].centroid.axis(axis);
+        });
+
+        size_t half = count / 2;
+        build(bounds, first, half);
+        size_t right = build(bounds, first + half, count - half);
+
+        nodes[node_index].first = right;
+        nodes[node_index].count = 0;
+        return node_index;
+    }
+};
+
+template <typename T>
+std::vector<PrimitiveBounds> primitive_bounds(const std::vector<T>& primitives) {
+    std::vector<PrimitiveBounds> bounds;
+    bounds.reserve(primitives.size());
+    for (const auto& p : primitives) bounds.push_back(p.bounds());
+    return bounds;
 }
 
-int main() {
-    std::vector<Sphere> spheres = {
-        {Vec3(0, 0, -5), 1.0, Vec3(1, 0, 0)},
-        {Vec3(2, 0, -6), 1.0, Vec3(0, 1, 0)},
-        {Vec3(-2, 0, -6), 1.0, Vec3(0, 0, 1)},
-        {Vec3(0, -1001, -5), 1000.0, Vec3(0.8, 0.8, 0.8)}
+struct Surface {
+    const Sphere* sphere = nullptr;     // exactly one of these is set on a hit
+    const Triangle* triangle = nullptr;
+
+    Vec3 normal(const Vec3& hit_point, const Vec3& direction) const {
+        if (sphere) return (hit_point - sphere->center) * (1.0 / sphere->radius);
+        // Triangles are two-sided: shade the face the ray arrived at
+        return triangle->normal.dot(direction) > 0.0 ? triangle->normal * -1.0 : triangle->normal;
+    }
+};
+
+// Spheres and triangles keep separate BVHs: a ray walks the sphere tree, then the triangle
+// tree clipped to the nearest sphere hit
+class Scene {
+public:
+    std::vector<Sphere> spheres;
+    Bvh sphere_bvh;
+    std::vector<Triangle> triangles;
+    Bvh triangle_bvh;
+    Camera camera;
+    Sky sky;
+
+    Scene(std::vector<Sphere> s, std::vector<Triangle> tris, const Camera& camera, const Sky& sky)
+        : spheres(std::move(s)), sphere_bvh(primitive_bounds(spheres)), triangles(std::move(tris)),
+          triangle_bvh(primitive_bounds(triangles)), camera(camera), sky(sky) {}
+
+    size_t bvh_nodes() const {
+        return sphere_bvh.nodes.size() + triangle_bvh.nodes.size();
+    }
+
+    bool intersect(const Vec3& origin, const Vec3& direction, double& closest_t, Surface& surface) const {
+        Vec3 inv_dir(1.0 / direction.x, 1.0 / direction.y, 1.0 / direction.z);
+        closest_t = std::numeric_limits<double>::infinity();
+        size_t sphere = sphere_bvh.traverse(origin, inv_dir, closest_t, [&](size_t i, double t_max, double& t) {
+            return spheres[i].intersect(origin, direction, t_max, t);
+        });
+        size_t triangle = triangle_bvh.traverse(origin, inv_dir, closest_t, [&](size_t i, double t_max, double& t) {
+            return triangles[i].intersect(origin, direction, t_max, t);
+        });
+        if (triangle != SIZE_MAX) {
+            surface = {nullptr, &triangles[triangle]};
+        } else if (sphere != SIZE_MAX) {
+            surface = {&spheres[sphere], nullptr};
+        } else {
+            return false;
+        }
+        return true;
+    }
+
+    bool occluded(const Vec3& origin, const Vec3& direction, double t_max) const {
+        Vec3 inv_dir(1.0 / direction.x, 1.0 / direction.y, 1.0 / direction.z);
+        return sphere_bvh.any_hit(origin, inv_dir, t_max, [&](size_t i, double t_max, double& t) {
+                   return spheres[i].intersect(origin, direction, t_max, t);
+               }) ||
+               triangle_bvh.any_hit(origin, inv_dir, t_max, [&](size_t i, double t_max, double& t) {
+                   return triangles[i].intersect(origin, direction, t_max, t);
+               });
+    }
+};
+
+// Just enough JSON for scene files: objects, arrays, numbers, strings (no escapes) and literals
+struct Json {
+    enum class Type { Null, Bool, Number, String, Array, Object } type = Type::Null;
+    bool boolean = false;
+    double number = 0.0;
+    std::string string;
+    std::vector<Json> array;
+    std::map<std::string, Json> object;
+
+    const Json* find(const std::string& key) const {
+        auto it = object.find(key);
+        return it == object.end() ? nullptr : &it->second;
+    }
+
+    const Json& at(const std::string& key) const {
+        const Json* value = find(key);
+        if (!value) throw std::runtime_error("missing field `" + key + "`");
+        return *value;
+    }
+
+    double as_number() const {
+        if (type != Type::Number) throw std::runtime_error("expected a number");
+        return number;
+    }
+
+    const std::string& as_string() const {
+        if (type != Type::String) throw std::runtime_error("expected a string");
+        return string;
+    }
+
+    const std::vector<Json>& as_array() const {
+        if (type != Type::Array) throw std::runtime_error("expected an array");
+        return array;
+    }
+};
+
+class JsonParser {
+public:
+    explicit JsonParser(const std::string& text) : text(text) {}
+
+    Json parse_document() {
+        Json value = parse_value();
+        skip_whitespace();
+        if (pos != text.size()) fail("trailing characters");
+        return value;
+    }
+
+private:
+    const std::string& text;
+    size_t pos = 0;
+
+    [[noreturn]] void fail(const std::string& what) const {
+        throw std::runtime_error(what + " at byte " + std::to_string(pos));
+    }
+
+    void skip_whitespace() {
+        while (pos < text.size() && std::strchr(" \t\r\n", text[pos])) pos++;
+    }
+
+    void expect(char c) {
+        skip_whitespace();
+        if (pos >= text.size() || text[pos] != c) fail(std::string("expected '") + c + "'");
+        pos++;
+    }
+
+    bool consume(char c) {
+        skip_whitespace();
+        if (pos < text.size() && text[pos] == c) {
+            pos++;
+            return true;
+        }
+        return false;
+    }
+
+    std::string parse_string() {
+        expect('"');
+        size_t end = text.find('"', pos);
+        if (end == std::string::npos) fail("unterminated string");
+        std::string value = text.substr(pos, end - pos);
+        pos = end + 1;
+        return value;
+    }
+
+    Json parse_value() {
+        skip_whitespace();
+        if (pos >= text.size()) fail("unexpected end of input");
+        Json value;
+        char c = text[pos];
+        if (c == '{') {
+            pos++;
+            value.type = Json::Type::Object;
+            if (consume('}')) return value;
+            do {
+                std::string key = parse_string();
+                expect(':');
+                value.object[key] = parse_value();
+            } while (consume(','));
+            expect('}');
+        } else if (c == '[') {
+            pos++;
+            value.type = Json::Type::Array;
+            if (consume(']')) return value;
+            do {
+                value.array.push_back(parse_value());
+            } while (consume(','));
+            expect(']');
+        } else if (c == '"') {
+            value.type = Json::Type::String;
+            value.string = parse_string();
+        } else if (text.compare(pos, 4, "true") == 0 || text.compare(pos, 5, "false") == 0) {
+            value.type = Json::Type::Bool;
+            value.boolean = c == 't';
+            pos += value.boolean ? 4 : 5;
+        } else if (text.compare(pos, 4, "null") == 0) {
+            pos += 4;
+        } else {
+            char* end = nullptr;
+            value.type = Json::Type::Number;
+            value.number = std::strtod(text.c_str() + pos, &end);
+            if (end == text.c_str() + pos) fail("unexpected character");
+            pos = end - text.c_str();
+        }
+        return value;
+    }
+};
+
+Vec3 json_vec3(const Json& value) {
+    const auto& items = value.as_array();
+    if (items.size() != 3) throw std::runtime_error("expected a 3-element array");
+    return Vec3(items[0].as_number(), items[1].as_number(), items[2].as_number());
+}
+
+Material json_material_type(const Json& value) {
+    const std::string& name = value.as_string();
+    if (name == "diffuse") return Material::Diffuse;
+    if (name == "mirror") return Material::Reflective;
+    if (name == "emissive") return Material::Emissive;
+    throw std::runtime_error("unknown material type `" + name + "`");
+}
+
+// The benchmark's own scene, identical to scenes/default.json so no file is needed to run it
+const char* DEFAULT_SCENE = R"({
+  "camera": { "origin": [0, 0.5, 1], "direction": [0, -0.3, -1.5] },
+  "sky": { "horizon": [1, 1, 1], "zenith": [0.5, 0.7, 1] },
+  "materials": [
+    { "name": "ground", "type": "diffuse", "color": [0.8, 0.8, 0.8] },
+    { "name": "chrome", "type": "mirror", "color": [0.9, 0.9, 0.9] },
+    { "name": "green", "type": "diffuse", "color": [0.1, 0.8, 0.1] },
+    { "name": "blue", "type": "diffuse", "color": [0.1, 0.1, 0.8] }
+  ],
+  "spheres": [
+    { "center": [0, -1001, -5], "radius": 1000, "material": "ground" },
+    { "center": [0, 0, -5], "radius": 1, "material": "chrome" },
+    { "center": [2, 0, -6], "radius": 1, "material": "green" },
+    { "center": [-2, 0, -6], "radius": 1, "material": "blue" }
+  ],
+  "sphere_grid": {
+    "columns": 50, "rows": 40, "seed": 2024, "origin": [-12.5, -1, -2], "spacing": 0.5,
+    "jitter": 0.3, "radius_min": 0.1, "radius_jitter": 0.1, "mirror_fraction": 0.3
+  }
+})";
+
+// 1-based vertex index of one face corner ("7", "7/3", "7//2" or "7/3/2"); negative
+// indices count back from the most recent vertex
+bool obj_vertex_index(const std::string& corner, size_t vertex_count, size_t& index) {
+    char* end = nullptr;
+    long value = std::strtol(corner.c_str(), &end, 10);
+    if (end == corner.c_str() || (*end != '\0' && *end != '/')) return false;
+    long resolved = value < 0 ? static_cast<long>(vertex_count) + value : value - 1;
+    if (resolved < 0 || resolved >= static_cast<long>(vertex_count)) return false;
+    index = static_cast<size_t>(resolved);
+    return true;
+}
+
+// Positions and faces only: polygons are fanned into triangles, and normals, texture
+// coordinates, groups and materials are ignored
+void load_obj(const std::string& path, std::vector<Vec3>& vertices, std::vector<std::array<size_t, 3>>& faces) {
+    std::ifstream in(path);
+    if (!in) throw std::runtime_error("failed to read " + path);
+    std::string line;
+    for (size_t line_number = 1; std::getline(in, line); line_number++) {
+        auto fail = [&](const std::string& what) {
+            throw std::runtime_error(path + ":" + std::to_string(line_number) + ": " + what);
+        };
+        std::istringstream fields(line);
+        std::string keyword;
+        fields >> keyword;
+        if (keyword == "v") {
+            double x, y, z;
+            if (!(fields >> x >> y >> z)) fail("vertex needs three coordinates");
+            vertices.emplace_back(x, y, z);
+        } else if (keyword == "f") {
+            std::vector<size_t> corners;
+            for (std::string corner; fields >> corner;) {
+                size_t index;
+                if (!obj_vertex_index(corner, vertices.size(), index)) fail("bad face index");
+                corners.push_back(index);
+            }
+            if (corners.size() < 3) fail("face needs at least three vertices");
+            for (size_t k = 1; k + 1 < corners.size(); k++) {
+                faces.push_back({corners[0], corners[k], corners[k + 1]});
+            }
+        }
+    }
+}
+
+// Scene file layout, shared with the Rust version (see scenes/default.json). The optional
+// sphere_grid lays rows of small randomly sized, colored and jittered spheres on y = origin[1],
+// starting at origin and marching +x by column and -z by row. Each mesh places the triangles
+// of an OBJ file (path relative to the scene file), scaled about the model origin then translated
+Scene build_scene(const Json& file, const std::string& base_dir) {
+    struct MaterialDef {
+        Material kind;
+        Vec3 color;
     };
-    
-    std::vector<Vec3> image(WIDTH * HEIGHT);
-    
-    // Warm-up
-    render_section(spheres, image, 0, 10);
-    
-    // Benchmark
+    std::map<std::string, MaterialDef> materials;
+    for (const auto& m : file.at("materials").as_array()) {
+        materials[m.at("name").as_string()] = {json_material_type(m.at("type")), json_vec3(m.at("color"))};
+    }
+
+    auto lookup = [&](const Json& name) -> const MaterialDef& {
+        auto it = materials.find(name.as_string());
+        if (it == materials.end()) throw std::runtime_error("unknown material `" + name.as_string() + "`");
+        return it->second;
+    };
+
+    std::vector<Sphere> spheres;
+    if (const Json* list = file.find("spheres")) {
+        for (const auto& s : list->as_array()) {
+            const MaterialDef& material = lookup(s.at("material"));
+            spheres.push_back({json_vec3(s.at("center")), s.at("radius").as_number(), material.color, material.kind});
+        }
+    }
+
+    if (const Json* grid = file.find("sphere_grid")) {
+        size_t columns = static_cast<size_t>(grid->at("columns").as_number());
+        size_t rows = static_cast<size_t>(grid->at("rows").as_number());
+        Vec3 origin = json_vec3(grid->at("origin"));
+        double spacing = grid->at("spacing").as_number();
+        double jitter = grid->at("jitter").as_number();
+        double radius_min = grid->at("radius_min").as_number();
+        double radius_jitter = grid->at("radius_jitter").as_number();
+        double mirror_fraction = grid->at("mirror_fraction").as_number();
+        Rng rng(static_cast<uint64_t>(grid->at("seed").as_number()));
+        for (size_t gz = 0; gz < rows; gz++) {
+            for (size_t gx = 0; gx < columns; gx++) {
+                double radius = radius_min + radius_jitter * rng.next_f64();
+                double x = origin.x + static_cast<double>(gx) * spacing + jitter * rng.next_f64();
+                double z = origin.z - static_cast<double>(gz) * spacing - jitter * rng.next_f64();
+                double r = rng.next_f64();
+                double g = rng.next_f64();
+                double b = rng.next_f64();
+                Material material = rng.next_f64() < mirror_fraction ? Material::Reflective : Material::Diffuse;
+                spheres.push_back({Vec3(x, origin.y + radius, z), radius, Vec3(r, g, b), material});
+            }
+        }
+    }
+
+    std::vector<Triangle> triangles;
+    if (const Json* list = file.find("meshes")) {
+        for (const auto& mesh : list->as_array()) {
+            const MaterialDef& material = lookup(mesh.at("material"));
+            const Json* scale = mesh.find("scale");
+            const Json* translate = mesh.find("translate");
+            double s = scale ? scale->as_number() : 1.0;
+            Vec3 offset = translate ? json_vec3(*translate) : Vec3(0, 0, 0);
+            std::vector<Vec3> vertices;
+            std::vector<std::array<size_t, 3>> faces;
+            load_obj(base_dir + mesh.at("path").as_string(), vertices, faces);
+            for (auto& v : vertices) v = v * s + offset;
+            for (const auto& f : faces) {
+                Triangle tri;
+                if (Triangle::make(vertices[f[0]], vertices[f[1]], vertices[f[2]], material.color, material.kind, tri)) {
+                    triangles.push_back(tri);
+                }
+            }
+        }
+    }
+    if (spheres.empty() && triangles.empty()) throw std::runtime_error("scene is empty");
+
+    const Json& camera = file.at("camera");
+    const Json& sky = file.at("sky");
+    return Scene(std::move(spheres), std::move(triangles), Camera(json_vec3(camera.at("origin")), json_vec3(camera.at("direction"))),
+                 Sky{json_vec3(sky.at("horizon")), json_vec3(sky.at("zenith"))});
+}
+
+Scene load_scene(const char* path) {
+    std::string text = DEFAULT_SCENE;
+    std::string base_dir;
+    if (path) {
+        const char* slash = std::strrchr(path, '/');
+        if (slash) base_dir.assign(path, slash + 1);
+        std::ifstream in(path);
+        if (!in) throw std::runtime_error(std::string("failed to read ") + path);
+        std::stringstream buffer;
+        buffer << in.rdbuf();
+        text = buffer.str();
+    }
+    return build_scene(JsonParser(text).parse_document(), base_dir);
+}
+
+Vec3 sky(const Vec3& direction, const Sky& sky) {
+    double t = 0.5 * (direction.normalize().y + 1.0);
+    return sky.horizon * (1.0 - t) + sky.zenith * t;
+}
+
+Vec3 trace_path(Vec3 origin, Vec3 direction, const Scene& scene, Rng& rng, size_t max_depth) {
+    Vec3 throughput(1.0, 1.0, 1.0);
+
+    for (size_t depth = 0; depth < max_depth; depth++) {
+        double t;
+        Surface surface;
+        if (!scene.intersect(origin, direction, t, surface)) {
+            return throughput * sky(direction, scene.sky);
+        }
+
+        Vec3 hit_point = origin + direction * t;
+        Vec3 normal = surface.normal(hit_point, direction);
+        Vec3 color = surface.sphere ? surface.sphere->color : surface.triangle->color;
+        Material material = surface.sphere ? surface.sphere->material : surface.triangle->material;
+        if (material == Material::Emissive) {
+            return throughput * color;
+        }
+        if (material == Material::Diffuse) {
+            Vec3 scattered = normal + rng.unit_vector();
+            direction = scattered.dot(scattered) < 1e-12 ? normal : scattered;
+        } else {
+            direction = direction.reflect(normal);
+        }
+        throughput = throughput * color;
+        origin = hit_point;
+    }
+
+    return Vec3(0, 0, 0); // Path exceeded the bounce budget
+}
+
+// The fraction of AO_RAYS cosine-weighted rays from the first hit that travel AO_DISTANCE
+// unblocked, in gray; a miss is fully open. Materials and the sky play no part, so the cost is
+// the generator and the intersection tests
+Vec3 ambient_occlusion(const Vec3& origin, const Vec3& direction, const Scene& scene, Rng& rng) {
+    double t;
+    Surface surface;
+    if (!scene.intersect(origin, direction, t, surface)) {
+        return Vec3(1.0, 1.0, 1.0);
+    }
+    Vec3 hit_point = origin + direction * t;
+    Vec3 normal = surface.normal(hit_point, direction);
+    size_t open = 0;
+    for (size_t i = 0; i < AO_RAYS; i++) {
+        if (!scene.occluded(hit_point, rng.cosine_direction(normal), AO_DISTANCE)) open++;
+    }
+    double fraction = static_cast<double>(open) / AO_RAYS;
+    return Vec3(fraction, fraction, fraction);
+}
+
+// What each camera sample computes; max_depth applies to path tracing only
+struct Mode {
+    bool ambient_occlusion = false;
+    size_t max_depth = DEFAULT_MAX_DEPTH;
+};
+
+Vec3 render_pixel(size_t x, size_t y, const Scene& scene, Mode mode) {
+    Rng rng(y * WIDTH + x);
+    double aspect = static_cast<double>(WIDTH) / HEIGHT;
+    const Camera& camera = scene.camera;
+    Vec3 color(0, 0, 0);
+    for (size_t s = 0; s < SAMPLES; s++) {
+        double u = (static_cast<double>(x) + rng.next_f64()) / WIDTH - 0.5;
+        double v = 0.5 - (static_cast<double>(y) + rng.next_f64()) / HEIGHT;
+        Vec3 direction = (camera.direction + camera.right * (u * 2.0 * aspect) + camera.up * (v * 2.0)).normalize();
+        Vec3 sample_color = mode.ambient_occlusion ? ambient_occlusion(camera.origin, direction, scene, rng)
+                                                   : trace_path(camera.origin, direction, scene, rng, mode.max_depth);
+        color = color + sample_color;
+    }
+    return color * (1.0 / SAMPLES);
+}
+
+const size_t TILES_X = (WIDTH + TILE_SIZE - 1) / TILE_SIZE;
+const size_t TILE_COUNT = TILES_X * ((HEIGHT + TILE_SIZE - 1) / TILE_SIZE);
+
+// Renders tile `index` in place (tiles never overlap) and returns its time in seconds
+double render_tile(size_t index, const Scene& scene, std::vector<Vec3>& image, Mode mode) {
     auto start = std::chrono::high_resolution_clock::now();
-    
+    size_t x0 = index % TILES_X * TILE_SIZE;
+    size_t y0 = index / TILES_X * TILE_SIZE;
+    size_t x1 = std::min(x0 + TILE_SIZE, WIDTH);
+    size_t y1 = std::min(y0 + TILE_SIZE, HEIGHT);
+    for (size_t y = y0; y < y1; y++) {
+        for (size_t x = x0; x < x1; x++) {
+            image[y * WIDTH + x] = render_pixel(x, y, scene, mode);
+        }
+    }
+    return std::chrono::duration<double>(std::chrono::high_resolution_clock::now() - start).count();
+}
+
+// Threads pull tiles from a shared counter, so a thread stuck on expensive tiles takes fewer
+std::vector<double> render(const Scene& scene, std::vector<Vec3>& image, Mode mode) {
+    std::vector<double> tile_seconds(TILE_COUNT);
+    std::atomic<size_t> next{0};
     std::vector<std::thread> threads;
-    size_t rows_per_thread = HEIGHT / NUM_THREADS;
-    
     for (size_t t = 0; t < NUM_THREADS; t++) {
-        size_t start_row = t * rows_per_thread;
-        size_t end_row = (t == NUM_THREADS - 1) ? HEIGHT : (t + 1) * rows_per_thread;
-        threads.emplace_back(render_section, std::ref(spheres), std::ref(image), start_row, end_row);
+        threads.emplace_back([&]() {
+            for (size_t tile = next++; tile < TILE_COUNT; tile = next++) {
+                tile_seconds[tile] = render_tile(tile, scene, image, mode);
+            }
+        });
     }
-    
     for (auto& thread : threads) {
         thread.join();
     }
-    
-    auto end = std::chrono::high_resolution_clock::now();
-    std::chrono::duration<double> duration = end - start;
-    
+    return tile_seconds;
+}
+
+// Spread of tile costs: a max far above the mean means a few tiles (mirrors, dense geometry)
+// dominate and finer tiles would balance better
+void print_tile_stats(std::vector<double> tile_seconds) {
+    std::sort(tile_seconds.begin(), tile_seconds.end());
+    double mean = std::accumulate(tile_seconds.begin(), tile_seconds.end(), 0.0) / tile_seconds.size();
+    double max = tile_seconds.back();
+    fprintf(stderr, "Tiles: %zu of %zux%zu, ms per tile: min %.3f, median %.3f, mean %.3f, max %.3f (max/mean %.2f)\n",
+            tile_seconds.size(), TILE_SIZE, TILE_SIZE, 1e3 * tile_seconds[0], 1e3 * tile_seconds[tile_seconds.size() / 2],
+            1e3 * mean, 1e3 * max, max / mean);
+}
+
+// Gamma 2, clamped and rounded to 8 bits per channel
+std::vector<uint8_t> to_rgb8(const std::vector<Vec3>& image) {
+    std::vector<uint8_t> rgb;
+    rgb.reserve(image.size() * 3);
+    for (const auto& c : image) {
+        for (double v : {c.x, c.y, c.z}) {
+            rgb.push_back(static_cast<uint8_t>(std::min(std::sqrt(std::max(v, 0.0)), 1.0) * 255.0 + 0.5));
+        }
+    }
+    return rgb;
+}
+
+bool write_image(const char* path, const std::vector<uint8_t>& rgb) {
+    size_t len = std::strlen(path);
+    if (len >= 4 && std::strcmp(path + len - 4, ".ppm") == 0) {
+        FILE* out = std::fopen(path, "wb");
+        if (!out) return false;
+        std::fprintf(out, "P6\n%zu %zu\n255\n", WIDTH, HEIGHT);
+        bool ok = std::fwrite(rgb.data(), 1, rgb.size(), out) == rgb.size();
+        return std::fclose(out) == 0 && ok;
+    }
+    png_image image{};
+    image.version = PNG_IMAGE_VERSION;
+    image.width = WIDTH;
+    image.height = HEIGHT;
+    image.format = PNG_FORMAT_RGB;
+    return png_image_write_to_file(&image, path, 0, rgb.data(), 0, nullptr) != 0;
+}
+
+bool read_png(const char* path, std::vector<uint8_t>& pixels) {
+    png_image image{};
+    image.version = PNG_IMAGE_VERSION;
+    if (!png_image_begin_read_from_file(&image, path)) {
+        std::cerr << "Failed to open " << path << ": " << image.message << std::endl;
+        return false;
+    }
+    if (image.width != WIDTH || image.height != HEIGHT) {
+        std::cerr << path << " is not a " << WIDTH << "x" << HEIGHT << " image" << std::endl;
+        png_image_free(&image);
+        return false;
+    }
+    image.format = PNG_FORMAT_RGB;
+    pixels.resize(PNG_IMAGE_SIZE(image));
+    return png_image_finish_read(&image, nullptr, pixels.data(), 0, nullptr) != 0;
+}
+
+// Path tracing is chaotic: a last-bit difference can send one bounce elsewhere, so a few
+// pixels may legitimately differ a lot. The frame passes when nearly all pixels are close
+bool verify(const std::vector<uint8_t>& rgb, const char* path) {
+    std::vector<uint8_t> golden;
+    if (!read_png(path, golden)) return false;
+    size_t mismatches = 0;
+    int max_difference = 0;
+    for (size_t i = 0; i < rgb.size(); i += 3) {
+        int difference = 0;
+        for (size_t k = i; k < i + 3; k++) {
+            difference = std::max(difference, std::abs(static_cast<int>(rgb[k]) - static_cast<int>(golden[k])));
+        }
+        max_difference = std::max(max_difference, difference);
+        if (difference > PIXEL_TOLERANCE) mismatches++;
+    }
+    double fraction = static_cast<double>(mismatches) / (WIDTH * HEIGHT);
+    bool passed = fraction <= MAX_MISMATCH_FRACTION;
+    fprintf(stderr, "Verify: %zu pixels (%.4f%%) differ from %s by more than %d/255 (max %d), %s\n", mismatches,
+            100.0 * fraction, path, PIXEL_TOLERANCE, max_difference, passed ? "PASS" : "FAIL");
+    return passed;
+}
+
+const char* parse_output_path(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--output") == 0) {
+            if (i + 1 >= argc) {
+                std::cerr << "--output expects a file path" << std::endl;
+                std::exit(1);
+            }
+            return argv[i + 1];
+        }
+    }
+    return nullptr;
+}
+
+// A value is taken only when the next argument is not another flag
+const char* parse_verify_path(int argc, char** argv, const char* golden_path) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--verify") == 0) {
+            if (i + 1 < argc && std::strncmp(argv[i + 1], "--", 2) != 0) return argv[i + 1];
+            return golden_path;
+        }
+    }
+    return nullptr;
+}
+
+const char* parse_scene_path(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--scene") == 0) {
+            if (i + 1 >= argc) {
+                std::cerr << "--scene expects a file path" << std::endl;
+                std::exit(1);
+            }
+            return argv[i + 1];
+        }
+    }
+    return nullptr;
+}
+
+size_t parse_max_depth(int argc, char** argv) {
+    for (int i = 1; i + 1 < argc; i++) {
+        if (std::strcmp(argv[i], "--depth") == 0) {
+            return std::stoul(argv[i + 1]);
+        }
+    }
+    return DEFAULT_MAX_DEPTH;
+}
+
+Mode parse_mode(int argc, char** argv) {
+    Mode mode;
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--mode") == 0) {
+            const char* value = i + 1 < argc ? argv[i + 1] : "";
+            if (std::strcmp(value, "ao") == 0) {
+                mode.ambient_occlusion = true;
+            } else if (std::strcmp(value, "path") != 0) {
+                std::cerr << "--mode expects path or ao" << std::endl;
+                std::exit(1);
+            }
+        }
+    }
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--depth") == 0 && mode.ambient_occlusion) {
+            std::cerr << "--depth applies to --mode path only" << std::endl;
+            std::exit(1);
+        }
+    }
+    mode.max_depth = parse_max_depth(argc, argv);
+    return mode;
+}
+
+int main(int argc, char** argv) {
+    auto measure = bench_measure_from_env();
+    Mode mode = parse_mode(argc, argv);
+    const char* output_path = parse_output_path(argc, argv);
+    const char* verify_path = parse_verify_path(argc, argv, mode.ambient_occlusion ? GOLDEN_AO_PATH : GOLDEN_PATH);
+    const char* scene_path = parse_scene_path(argc, argv);
+    std::optional<Scene> loaded;
+    try {
+        loaded.emplace(load_scene(scene_path));
+    } catch (const std::exception& e) {
+        std::cerr << "Invalid scene file " << (scene_path ? scene_path : "(built-in)") << ": " << e.what() << std::endl;
+        return 1;
+    }
+    const Scene& scene = *loaded;
+
+    std::vector<Vec3> image(WIDTH * HEIGHT);
+
+    // Warm-up: the row of tiles across the middle of the frame
+    size_t middle = HEIGHT / 2 / TILE_SIZE * TILES_X;
+    for (size_t tile = middle; tile < middle + TILES_X; tile++) {
+        render_tile(tile, scene, image, mode);
+    }
+
+    // Benchmark
+    measure->start();
+    std::vector<double> tile_seconds = render(scene, image, mode);
+    measure->stop();
+
     // Checksum
     double checksum = 0.0;
-    for (size_t i = 0; i < 100; i++) {
-        checksum += image[i].x + image[i].y + image[i].z;
+    for (const auto& c : image) {
+        checksum += c.x + c.y + c.z;
+    }
+
+    measure->report();
+    std::cerr << "Scene: " << (scene_path ? scene_path : "built-in") << std::endl;
+    std::cerr << "Spheres: " << scene.spheres.size() << ", triangles: " << scene.triangles.size()
+              << ", BVH nodes: " << scene.bvh_nodes() << std::endl;
+    if (mode.ambient_occlusion) {
+        std::cerr << "Mode: ambient occlusion, " << AO_RAYS << " rays per sample within " << AO_DISTANCE << std::endl;
+    } else {
+        std::cerr << "Mode: path tracing, max depth: " << mode.max_depth << std::endl;
     }
-    
-    std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
+    print_tile_stats(tile_seconds);
     std::cerr << "Checksum: " << checksum << std::endl;
-    
+
+    if (!output_path && !verify_path) return 0;
+    std::vector<uint8_t> rgb = to_rgb8(image);
+    if (output_path) {
+        if (!write_image(output_path, rgb)) {
+            std::cerr << "Failed to write " << output_path << std::endl;
+            return 1;
+        }
+        std::cerr << "Wrote " << output_path << std::endl;
+    }
+    if (verify_path && !verify(rgb, verify_path)) return 1;
+
     return 0;
 }
-
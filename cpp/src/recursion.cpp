@@ -0,0 +1,61 @@
+#include <iostream>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const uint64_t FIB_N = 42;
+const uint64_t ACKERMANN_M = 3;
+const uint64_t ACKERMANN_N = 11;
+
+// Naive doubly-recursive Fibonacci: ~866M calls for n = 42
+uint64_t fib(uint64_t n) {
+    return n < 2 ? n : fib(n - 1) + fib(n - 2);
+}
+
+// Ackermann's function is not primitive recursive, so it can't be turned into loops;
+// A(3, n) recurses 2^(n+3) frames deep
+uint64_t ackermann(uint64_t m, uint64_t n) {
+    if (m == 0) return n + 1;
+    if (n == 0) return ackermann(m - 1, 1);
+    return ackermann(m - 1, ackermann(m, n - 1));
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    // Arguments are read through volatiles so neither call can be evaluated at compile time
+    volatile uint64_t fib_n = FIB_N;
+    volatile uint64_t ackermann_m = ACKERMANN_M;
+    volatile uint64_t ackermann_n = ACKERMANN_N;
+    volatile uint64_t warmup_fib_n = 30, warmup_m = 2, warmup_n = 1000;
+
+    // Warm-up
+    volatile uint64_t warmup = fib(warmup_fib_n) + ackermann(warmup_m, warmup_n);
+    (void)warmup;
+
+    // Benchmark Fibonacci
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    uint64_t fib_result = fib(fib_n);
+    measure->stop();
+    auto fib_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark Ackermann
+    auto ackermann_start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    uint64_t ackermann_result = ackermann(ackermann_m, ackermann_n);
+    measure->stop();
+    auto ackermann_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> fib_duration = fib_end - start;
+    std::chrono::duration<double> ackermann_duration = ackermann_end - ackermann_start;
+
+    measure->report();
+    std::cerr << std::fixed << "fib(" << FIB_N << "): " << fib_duration.count() << "s, ackermann("
+              << ACKERMANN_M << ", " << ACKERMANN_N << "): " << ackermann_duration.count() << "s" << std::endl;
+    std::cerr << "Checksum: " << fib_result + ackermann_result << " (fib: " << fib_result
+              << ", ackermann: " << ackermann_result << ")" << std::endl;
+
+    return 0;
+}
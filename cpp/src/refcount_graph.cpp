@@ -0,0 +1,210 @@
+#include <iostream>
+#include <memory>
+#include <vector>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+const size_t NUM_NODES = 1'000'000;
+const size_t NUM_OPS = 2'000'000;
+const uint64_t INSERT_PERCENT = 50;
+const uint64_t RELINK_PERCENT = 35; // the remainder drop a whole subtree
+const size_t INSERT_FANOUT = 3; // each insert adds a node with this many leaf children
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+struct Node {
+    uint64_t value;
+    std::weak_ptr<Node> parent;
+    std::vector<std::shared_ptr<Node>> children;
+};
+
+using NodePtr = std::shared_ptr<Node>;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+uint64_t rotate_left(uint64_t v, unsigned r) {
+    return (v << r) | (v >> (64 - r));
+}
+
+NodePtr attach(const NodePtr& parent, uint64_t value) {
+    auto child = std::make_shared<Node>(Node{value, parent, {}});
+    parent->children.push_back(child);
+    return child;
+}
+
+// Removes `node` from its parent's child list, returning that strong reference
+NodePtr detach(const NodePtr& node) {
+    NodePtr parent = node->parent.lock();
+    if (!parent) return nullptr;
+    auto& siblings = parent->children;
+    auto it = std::find(siblings.begin(), siblings.end(), node);
+    if (it == siblings.end()) return nullptr;
+    NodePtr removed = std::move(*it);
+    *it = std::move(siblings.back());
+    siblings.pop_back();
+    return removed;
+}
+
+class Graph {
+public:
+    explicit Graph(uint64_t seed) : seed_(seed) {
+        root_ = std::make_shared<Node>(Node{0, {}, {}});
+        handles_.push_back(root_);
+        for (size_t i = 1; i < NUM_NODES; i++) {
+            size_t slot = next_random(seed_) % i;
+            NodePtr parent = handles_[slot].lock();
+            uint64_t value = next_random(seed_);
+            NodePtr child = attach(parent, value);
+            handles_.push_back(child);
+        }
+    }
+
+    uint64_t next() {
+        return next_random(seed_);
+    }
+
+    void insert() {
+        NodePtr parent = pick_live();
+        uint64_t value = next_random(seed_);
+        NodePtr node = attach(parent, value);
+        handles_[1 + next_random(seed_) % (NUM_NODES - 1)] = node;
+        for (size_t i = 0; i < INSERT_FANOUT; i++) {
+            NodePtr leaf = attach(node, rotate_left(value, 7));
+            handles_[1 + next_random(seed_) % (NUM_NODES - 1)] = leaf;
+        }
+    }
+
+    // Moves a subtree under a new parent, unless that would create a cycle
+    bool relink() {
+        NodePtr node = pick_non_root();
+        NodePtr new_parent = pick_live();
+        if (is_ancestor_or_self(node, new_parent)) {
+            return false;
+        }
+        if (NodePtr strong = detach(node)) {
+            strong->parent = new_parent;
+            new_parent->children.push_back(std::move(strong));
+        }
+        return true;
+    }
+
+    // The last strong reference goes away here, freeing the whole subtree
+    void drop_subtree() {
+        NodePtr node = pick_non_root();
+        detach(node);
+    }
+
+    // Iterative walk holding strong references, the way a visitor would
+    std::pair<size_t, uint64_t> traverse() const {
+        size_t count = 0;
+        uint64_t sum = 0;
+        std::vector<NodePtr> stack;
+        if (root_) stack.push_back(root_);
+        while (!stack.empty()) {
+            NodePtr node = std::move(stack.back());
+            stack.pop_back();
+            count++;
+            sum += node->value;
+            stack.insert(stack.end(), node->children.begin(), node->children.end());
+        }
+        return {count, sum};
+    }
+
+private:
+    // Slot 0 is the root and is never overwritten, so this always terminates
+    NodePtr pick_live() {
+        while (true) {
+            size_t slot = next_random(seed_) % NUM_NODES;
+            if (NodePtr node = handles_[slot].lock()) {
+                return node;
+            }
+        }
+    }
+
+    NodePtr pick_non_root() {
+        while (true) {
+            NodePtr node = pick_live();
+            if (node->parent.lock()) {
+                return node;
+            }
+        }
+    }
+
+    static bool is_ancestor_or_self(const NodePtr& ancestor, const NodePtr& node) {
+        NodePtr current = node;
+        while (true) {
+            if (current == ancestor) return true;
+            NodePtr parent = current->parent.lock();
+            if (!parent) return false;
+            current = std::move(parent);
+        }
+    }
+
+    NodePtr root_;
+    // Weak handles to random nodes; slots go dead when their subtree is dropped
+    std::vector<std::weak_ptr<Node>> handles_;
+    uint64_t seed_;
+};
+
+struct Stats {
+    Duration build, churn, traverse, teardown;
+    size_t live_nodes;
+    uint64_t value_sum;
+    size_t rejected_relinks;
+
+    Duration total() const { return build + churn + traverse + teardown; }
+};
+
+Stats run(size_t ops) {
+    Stats s{};
+    auto start = Clock::now();
+    auto graph = std::make_unique<Graph>(2024);
+    s.build = Clock::now() - start;
+
+    start = Clock::now();
+    for (size_t i = 0; i < ops; i++) {
+        uint64_t op = graph->next() % 100;
+        if (op < INSERT_PERCENT) {
+            graph->insert();
+        } else if (op < INSERT_PERCENT + RELINK_PERCENT) {
+            s.rejected_relinks += !graph->relink();
+        } else {
+            graph->drop_subtree();
+        }
+    }
+    s.churn = Clock::now() - start;
+
+    start = Clock::now();
+    auto [live_nodes, value_sum] = graph->traverse();
+    s.traverse = Clock::now() - start;
+    s.live_nodes = live_nodes;
+    s.value_sum = value_sum;
+
+    start = Clock::now();
+    graph.reset();
+    s.teardown = Clock::now() - start;
+    return s;
+}
+
+int main() {
+    // Warm-up
+    run(NUM_OPS / 100);
+
+    // Benchmark
+    Stats s = run(NUM_OPS);
+
+    std::cout << std::fixed << std::setprecision(6) << s.total().count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(3) << "shared_ptr build " << s.build.count() << "s, churn "
+              << s.churn.count() << "s (" << std::setprecision(0) << NUM_OPS / s.churn.count() << " ops/s), traverse "
+              << std::setprecision(3) << s.traverse.count() << "s, teardown " << s.teardown.count() << "s" << std::endl;
+    std::cerr << "Live nodes: " << s.live_nodes << ", rejected relinks: " << s.rejected_relinks << std::endl;
+    std::cerr << "Checksum: " << s.value_sum << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,231 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <atomic>
+#include <memory>
+#include <utility>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+
+const size_t CAPACITY = 1024; // slots per ring, a power of two
+const size_t ROUND_TRIPS = 500'000;
+const uint64_t MESSAGES = 100'000'000;
+const uint32_t SPINS_BEFORE_YIELD = 100; // longer than a handoff between cores takes
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+// Apple Silicon and recent x86 parts move lines in pairs, so 128 bytes apart is what keeps two
+// indices from sharing
+struct alignas(128) CacheLine {
+    std::atomic<size_t> value{0};
+};
+
+// Each index on its own line, so each side writes only its own
+struct Padded {
+    CacheLine head, tail;
+    std::atomic<size_t>& head_index() { return head.value; }
+    std::atomic<size_t>& tail_index() { return tail.value; }
+};
+
+// Both indices on one line: every push and pop invalidates the other core's copy
+struct Adjacent {
+    std::atomic<size_t> head{0}, tail{0};
+    std::atomic<size_t>& head_index() { return head; }
+    std::atomic<size_t>& tail_index() { return tail; }
+};
+
+// Indices count up forever and are masked into the slots; the release store of an index
+// publishes them
+template <typename Indices>
+struct Ring {
+    Indices indices;
+    std::vector<uint64_t> slots = std::vector<uint64_t>(CAPACITY);
+};
+
+// Each side keeps its own index and a cached copy of the other's, and rereads the shared one
+// only when the cache says the ring is full or empty
+template <typename Indices>
+struct Producer {
+    std::shared_ptr<Ring<Indices>> ring;
+    size_t tail = 0, cached_head = 0;
+
+    bool try_send(uint64_t value) {
+        if (tail - cached_head == CAPACITY) {
+            cached_head = ring->indices.head_index().load(std::memory_order_acquire);
+            if (tail - cached_head == CAPACITY) return false;
+        }
+        ring->slots[tail & (CAPACITY - 1)] = value;
+        tail++;
+        ring->indices.tail_index().store(tail, std::memory_order_release);
+        return true;
+    }
+};
+
+template <typename Indices>
+struct Consumer {
+    std::shared_ptr<Ring<Indices>> ring;
+    size_t head = 0, cached_tail = 0;
+
+    bool try_recv(uint64_t& value) {
+        if (head == cached_tail) {
+            cached_tail = ring->indices.tail_index().load(std::memory_order_acquire);
+            if (head == cached_tail) return false;
+        }
+        value = ring->slots[head & (CAPACITY - 1)];
+        head++;
+        ring->indices.head_index().store(head, std::memory_order_release);
+        return true;
+    }
+};
+
+template <typename Indices>
+std::pair<Producer<Indices>, Consumer<Indices>> channel() {
+    auto ring = std::make_shared<Ring<Indices>>();
+    return {Producer<Indices>{ring}, Consumer<Indices>{ring}};
+}
+
+void cpu_relax() {
+#if defined(__aarch64__)
+    asm volatile("isb");
+#elif defined(__x86_64__)
+    __builtin_ia32_pause();
+#endif
+}
+
+// Spin, then give the core away, so the benchmark still finishes when both threads share one
+void backoff(uint32_t& spins) {
+    if (spins < SPINS_BEFORE_YIELD) {
+        spins++;
+        cpu_relax();
+    } else {
+        std::this_thread::yield();
+    }
+}
+
+template <typename Tx>
+void send(Tx& tx, uint64_t value) {
+    uint32_t spins = 0;
+    while (!tx.try_send(value)) backoff(spins);
+}
+
+template <typename Rx>
+uint64_t recv(Rx& rx) {
+    uint32_t spins = 0;
+    uint64_t value;
+    while (!rx.try_recv(value)) backoff(spins);
+    return value;
+}
+
+struct Latency {
+    Duration elapsed;
+    std::vector<uint32_t> round_trips_ns; // sorted
+    uint64_t checksum = 0;
+};
+
+// A message to an echo thread and back through a second ring, one in flight at a time. Each
+// round trip is two handoffs plus the two clock reads around it
+template <typename Indices>
+Latency ping_pong(size_t round_trips) {
+    auto [ping_tx, ping_rx] = channel<Indices>();
+    auto [pong_tx, pong_rx] = channel<Indices>();
+    Latency latency;
+    latency.round_trips_ns.reserve(round_trips);
+    auto start = Clock::now();
+    std::thread echo([&ping_rx = ping_rx, &pong_tx = pong_tx, round_trips] {
+        for (size_t i = 0; i < round_trips; i++) send(pong_tx, recv(ping_rx) + 1);
+    });
+    for (uint64_t i = 0; i < round_trips; i++) {
+        auto sent = Clock::now();
+        send(ping_tx, i);
+        latency.checksum += recv(pong_rx);
+        latency.round_trips_ns.push_back(
+            static_cast<uint32_t>(std::chrono::duration_cast<std::chrono::nanoseconds>(Clock::now() - sent).count()));
+    }
+    echo.join();
+    latency.elapsed = Clock::now() - start;
+    std::sort(latency.round_trips_ns.begin(), latency.round_trips_ns.end());
+    return latency;
+}
+
+struct Stream {
+    Duration elapsed;
+    uint64_t checksum = 0;
+};
+
+// The producer sends 0..messages flat out; the consumer folds them in arrival order
+template <typename Indices>
+Stream stream(uint64_t messages) {
+    auto [tx, rx] = channel<Indices>();
+    Stream s;
+    auto start = Clock::now();
+    std::thread producer([&tx = tx, messages] {
+        for (uint64_t i = 0; i < messages; i++) send(tx, i);
+    });
+    for (uint64_t i = 0; i < messages; i++) s.checksum = s.checksum * 31 + recv(rx);
+    producer.join();
+    s.elapsed = Clock::now() - start;
+    return s;
+}
+
+struct Run {
+    Latency latency;
+    Stream stream;
+};
+
+template <typename Indices>
+Run run(size_t round_trips, uint64_t messages) {
+    Run r;
+    r.latency = ping_pong<Indices>(round_trips);
+    r.stream = stream<Indices>(messages);
+    return r;
+}
+
+double handoff_ns(const std::vector<uint32_t>& sorted_round_trips, size_t per_mille) {
+    return sorted_round_trips[(sorted_round_trips.size() - 1) * per_mille / 1000] / 2.0;
+}
+
+struct NamedVariant {
+    const char* name;
+    Run (*run)(size_t, uint64_t);
+};
+
+const NamedVariant VARIANTS[] = {
+    {"padded", run<Padded>},
+    {"adjacent", run<Adjacent>},
+};
+
+int main() {
+    // Warm-up
+    for (const auto& v : VARIANTS) v.run(ROUND_TRIPS / 100, MESSAGES / 100);
+
+    // Benchmark
+    std::vector<Run> runs;
+    Duration total_duration(0);
+    for (const auto& v : VARIANTS) {
+        runs.push_back(v.run(ROUND_TRIPS, MESSAGES));
+        total_duration += runs.back().latency.elapsed + runs.back().stream.elapsed;
+    }
+    bool all_match = true;
+    for (const Run& r : runs) {
+        all_match &= r.latency.checksum == runs[0].latency.checksum && r.stream.checksum == runs[0].stream.checksum;
+    }
+    uint64_t checksum = runs[0].latency.checksum + runs[0].stream.checksum;
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Rings of %zu u64 slots: %zu round trips, then %llu messages streamed\n", CAPACITY, ROUND_TRIPS,
+            static_cast<unsigned long long>(MESSAGES));
+    for (size_t i = 0; i < runs.size(); i++) {
+        const auto& trips = runs[i].latency.round_trips_ns;
+        double stream_seconds = runs[i].stream.elapsed.count();
+        fprintf(stderr, "  %-8s handoff p50 %.0fns, p99 %.0fns, p99.9 %.0fns; stream %.3fs (%.1fM msgs/s)\n",
+                VARIANTS[i].name, handoff_ns(trips, 500), handoff_ns(trips, 990), handoff_ns(trips, 999), stream_seconds,
+                MESSAGES / stream_seconds / 1e6);
+    }
+    std::cerr << "Checksum: " << checksum << " (all variants match: " << (all_match ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,284 @@
+#include <iostream>
+#include <vector>
+#include <array>
+#include <thread>
+#include <random>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstring>
+
+const size_t NUM_VALUES = 200'000'000; // Per generator, per output type
+const size_t NUM_STREAMS = 8; // Independent generators in the multi-threaded run
+const uint64_t BASE_SEED = 0x5EED;
+
+using Seed = std::array<uint64_t, 4>;
+
+// SplitMix64 expands one u64 into the 32-byte seeds every generator here takes
+Seed seed_words(uint64_t stream) {
+    uint64_t x = BASE_SEED + stream * 0x9E3779B97F4A7C15ULL;
+    Seed seed;
+    for (auto& word : seed) {
+        x += 0x9E3779B97F4A7C15ULL;
+        uint64_t z = x;
+        z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+        z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+        word = z ^ (z >> 31);
+    }
+    return seed;
+}
+
+inline uint64_t rotl(uint64_t x, int k) {
+    return (x << k) | (x >> (64 - k));
+}
+
+// Bit-exact with rand_xoshiro::Xoshiro256PlusPlus
+struct Xoshiro256PlusPlus {
+    uint64_t s[4];
+
+    explicit Xoshiro256PlusPlus(const Seed& seed) {
+        for (int i = 0; i < 4; i++) s[i] = seed[i];
+    }
+
+    inline uint64_t next_u64() {
+        uint64_t result = rotl(s[0] + s[3], 23) + s[0];
+        uint64_t t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = rotl(s[3], 45);
+        return result;
+    }
+};
+
+// Bit-exact with rand_pcg::Pcg64 (128-bit LCG, XSL-RR output)
+struct Pcg64 {
+    static constexpr unsigned __int128 MULTIPLIER =
+        (static_cast<unsigned __int128>(0x2360ED051FC65DA4ULL) << 64) | 0x4385DF649FCCF645ULL;
+    unsigned __int128 state;
+    unsigned __int128 increment;
+
+    explicit Pcg64(const Seed& seed) {
+        state = (static_cast<unsigned __int128>(seed[1]) << 64) | seed[0];
+        increment = ((static_cast<unsigned __int128>(seed[3]) << 64) | seed[2]) | 1;
+        state += increment;
+        step();
+    }
+
+    inline void step() {
+        state = state * MULTIPLIER + increment;
+    }
+
+    inline uint64_t next_u64() {
+        step();
+        uint32_t rot = static_cast<uint32_t>(state >> 122);
+        uint64_t xsl = static_cast<uint64_t>(state >> 64) ^ static_cast<uint64_t>(state);
+        return (xsl >> rot) | (xsl << ((64 - rot) & 63));
+    }
+};
+
+// Bit-exact with rand_chacha::ChaCha8Rng: 64-bit block counter, stream 0, buffered 4 blocks at a time
+struct ChaCha8 {
+    static constexpr size_t BLOCKS = 4;
+    uint32_t input[16];
+    uint32_t buffer[16 * BLOCKS];
+    size_t index = 16 * BLOCKS;
+
+    explicit ChaCha8(const Seed& seed) {
+        input[0] = 0x61707865; input[1] = 0x3320646e; input[2] = 0x79622d32; input[3] = 0x6b206574;
+        for (int i = 0; i < 4; i++) {
+            input[4 + 2 * i] = static_cast<uint32_t>(seed[i]);
+            input[5 + 2 * i] = static_cast<uint32_t>(seed[i] >> 32);
+        }
+        for (int i = 12; i < 16; i++) input[i] = 0;
+    }
+
+    static inline uint32_t rotl32(uint32_t x, int k) {
+        return (x << k) | (x >> (32 - k));
+    }
+
+    static inline void quarter_round(uint32_t* x, int a, int b, int c, int d) {
+        x[a] += x[b]; x[d] = rotl32(x[d] ^ x[a], 16);
+        x[c] += x[d]; x[b] = rotl32(x[b] ^ x[c], 12);
+        x[a] += x[b]; x[d] = rotl32(x[d] ^ x[a], 8);
+        x[c] += x[d]; x[b] = rotl32(x[b] ^ x[c], 7);
+    }
+
+    void refill() {
+        for (size_t block = 0; block < BLOCKS; block++) {
+            uint32_t* x = buffer + 16 * block;
+            std::memcpy(x, input, sizeof(input));
+            for (int round = 0; round < 4; round++) {
+                quarter_round(x, 0, 4, 8, 12);
+                quarter_round(x, 1, 5, 9, 13);
+                quarter_round(x, 2, 6, 10, 14);
+                quarter_round(x, 3, 7, 11, 15);
+                quarter_round(x, 0, 5, 10, 15);
+                quarter_round(x, 1, 6, 11, 12);
+                quarter_round(x, 2, 7, 8, 13);
+                quarter_round(x, 3, 4, 9, 14);
+            }
+            for (int i = 0; i < 16; i++) x[i] += input[i];
+            uint64_t counter = ((static_cast<uint64_t>(input[13]) << 32) | input[12]) + 1;
+            input[12] = static_cast<uint32_t>(counter);
+            input[13] = static_cast<uint32_t>(counter >> 32);
+        }
+        index = 0;
+    }
+
+    inline uint64_t next_u64() {
+        if (index >= 16 * BLOCKS) refill();
+        uint64_t lo = buffer[index];
+        uint64_t hi = buffer[index + 1];
+        index += 2;
+        return (hi << 32) | lo;
+    }
+};
+
+// The standard library's general-purpose engine stands in for StdRng; its output differs from Rust's
+struct Mt19937_64 {
+    std::mt19937_64 engine;
+
+    explicit Mt19937_64(const Seed& seed) {
+        std::seed_seq seq{seed[0], seed[1], seed[2], seed[3]};
+        engine.seed(seq);
+    }
+
+    inline uint64_t next_u64() {
+        return engine();
+    }
+};
+
+template <typename R>
+uint64_t sum_u64(R& rng, size_t count) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < count; i++) {
+        sum += rng.next_u64();
+    }
+    return sum;
+}
+
+// Same conversion as rand's Standard f64: the top 53 bits scaled into [0, 1)
+template <typename R>
+double sum_f64(R& rng, size_t count) {
+    const double scale = 1.0 / static_cast<double>(1ULL << 53);
+    double sum = 0.0;
+    for (size_t i = 0; i < count; i++) {
+        sum += static_cast<double>(rng.next_u64() >> 11) * scale;
+    }
+    return sum;
+}
+
+struct Run {
+    std::chrono::duration<double> u64_duration;
+    std::chrono::duration<double> f64_duration;
+    uint64_t u64_sum;
+    double f64_sum;
+};
+
+template <typename R>
+Run run_single(size_t count) {
+    Run run;
+    R rng(seed_words(0));
+    auto start = std::chrono::high_resolution_clock::now();
+    run.u64_sum = sum_u64(rng, count);
+    run.u64_duration = std::chrono::high_resolution_clock::now() - start;
+
+    R fresh(seed_words(0));
+    start = std::chrono::high_resolution_clock::now();
+    run.f64_sum = sum_f64(fresh, count);
+    run.f64_duration = std::chrono::high_resolution_clock::now() - start;
+    return run;
+}
+
+// Each stream gets its own thread, generator and seed, mirroring the Rust version's 8 streams
+template <typename R>
+Run run_multi(size_t count) {
+    Run run;
+    size_t per_stream = count / NUM_STREAMS;
+
+    std::vector<uint64_t> u64_partial(NUM_STREAMS);
+    auto start = std::chrono::high_resolution_clock::now();
+    {
+        std::vector<std::thread> threads;
+        for (size_t s = 0; s < NUM_STREAMS; s++) {
+            threads.emplace_back([&, s]() {
+                R rng(seed_words(s));
+                u64_partial[s] = sum_u64(rng, per_stream);
+            });
+        }
+        for (auto& t : threads) t.join();
+    }
+    run.u64_sum = 0;
+    for (uint64_t partial : u64_partial) run.u64_sum += partial;
+    run.u64_duration = std::chrono::high_resolution_clock::now() - start;
+
+    std::vector<double> f64_partial(NUM_STREAMS);
+    start = std::chrono::high_resolution_clock::now();
+    {
+        std::vector<std::thread> threads;
+        for (size_t s = 0; s < NUM_STREAMS; s++) {
+            threads.emplace_back([&, s]() {
+                R rng(seed_words(s));
+                f64_partial[s] = sum_f64(rng, per_stream);
+            });
+        }
+        for (auto& t : threads) t.join();
+    }
+    run.f64_sum = 0.0;
+    for (double partial : f64_partial) run.f64_sum += partial;
+    run.f64_duration = std::chrono::high_resolution_clock::now() - start;
+    return run;
+}
+
+void report(const char* name, const Run& single, const Run& multi) {
+    auto rate = [](std::chrono::duration<double> d) { return NUM_VALUES / d.count() / 1e6; };
+    std::cerr << std::fixed << std::setprecision(0) << std::left << std::setw(12) << name << std::right
+              << " single: " << std::setw(7) << rate(single.u64_duration) << " M u64/s, "
+              << std::setw(7) << rate(single.f64_duration) << " M f64/s | multi: "
+              << std::setw(7) << rate(multi.u64_duration) << " M u64/s, "
+              << std::setw(7) << rate(multi.f64_duration) << " M f64/s" << std::endl;
+}
+
+std::chrono::duration<double> elapsed(const Run& run) {
+    return run.u64_duration + run.f64_duration;
+}
+
+int main() {
+    // Warm-up
+    volatile uint64_t warmup = run_single<Xoshiro256PlusPlus>(NUM_VALUES / 100).u64_sum
+                             + run_multi<ChaCha8>(NUM_VALUES / 100).u64_sum;
+    (void)warmup;
+
+    // Benchmark each generator single- and multi-threaded
+    Run xoshiro_single = run_single<Xoshiro256PlusPlus>(NUM_VALUES);
+    Run xoshiro_multi = run_multi<Xoshiro256PlusPlus>(NUM_VALUES);
+    Run pcg_single = run_single<Pcg64>(NUM_VALUES);
+    Run pcg_multi = run_multi<Pcg64>(NUM_VALUES);
+    Run chacha_single = run_single<ChaCha8>(NUM_VALUES);
+    Run chacha_multi = run_multi<ChaCha8>(NUM_VALUES);
+    Run mt_single = run_single<Mt19937_64>(NUM_VALUES);
+    Run mt_multi = run_multi<Mt19937_64>(NUM_VALUES);
+
+    std::chrono::duration<double> total_duration = elapsed(xoshiro_single) + elapsed(xoshiro_multi)
+                                                 + elapsed(pcg_single) + elapsed(pcg_multi)
+                                                 + elapsed(chacha_single) + elapsed(chacha_multi)
+                                                 + elapsed(mt_single) + elapsed(mt_multi);
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    report("Xoshiro256++", xoshiro_single, xoshiro_multi);
+    report("PCG64", pcg_single, pcg_multi);
+    report("ChaCha8", chacha_single, chacha_multi);
+    report("mt19937_64", mt_single, mt_multi);
+    // mt19937_64 has no bit-exact Rust counterpart, so it stays out of the checksum
+    uint64_t checksum = xoshiro_single.u64_sum + xoshiro_multi.u64_sum + pcg_single.u64_sum
+                      + pcg_multi.u64_sum + chacha_single.u64_sum + chacha_multi.u64_sum;
+    double float_checksum = (xoshiro_single.f64_sum + xoshiro_multi.f64_sum) + (pcg_single.f64_sum + pcg_multi.f64_sum)
+                          + (chacha_single.f64_sum + chacha_multi.f64_sum);
+    std::cerr << std::fixed << std::setprecision(6) << "Checksum: " << checksum
+              << " (f64 sum: " << float_checksum << ")" << std::endl;
+
+    return 0;
+}
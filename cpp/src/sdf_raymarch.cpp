@@ -0,0 +1,270 @@
+#include <iostream>
+#include <vector>
+#include <cmath>
+#include <thread>
+#include <chrono>
+#include <iomanip>
+#include <algorithm>
+#include <optional>
+#include <utility>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t WIDTH = 1920;
+const size_t HEIGHT = 1080;
+const uint32_t MAX_STEPS = 256;
+const double MAX_DISTANCE = 60.0;
+const double HIT_EPSILON = 1e-4;
+const uint32_t SHADOW_STEPS = 64;
+const double SHADOW_SOFTNESS = 16.0;
+const double NORMAL_EPSILON = 1e-4;
+const size_t NUM_THREADS = 8;
+
+struct Vec3 {
+    double x, y, z;
+
+    Vec3(double x = 0, double y = 0, double z = 0) : x(x), y(y), z(z) {}
+
+    double dot(const Vec3& other) const {
+        return x * other.x + y * other.y + z * other.z;
+    }
+
+    Vec3 cross(const Vec3& other) const {
+        return Vec3(y * other.z - z * other.y, z * other.x - x * other.z, x * other.y - y * other.x);
+    }
+
+    double length() const {
+        return std::sqrt(x * x + y * y + z * z);
+    }
+
+    Vec3 normalize() const {
+        double len = length();
+        return Vec3(x / len, y / len, z / len);
+    }
+
+    Vec3 operator-(const Vec3& other) const {
+        return Vec3(x - other.x, y - other.y, z - other.z);
+    }
+
+    Vec3 operator+(const Vec3& other) const {
+        return Vec3(x + other.x, y + other.y, z + other.z);
+    }
+
+    Vec3 operator*(double scalar) const {
+        return Vec3(x * scalar, y * scalar, z * scalar);
+    }
+
+    Vec3 abs() const {
+        return Vec3(std::abs(x), std::abs(y), std::abs(z));
+    }
+
+    Vec3 max_scalar(double v) const {
+        return Vec3(std::max(x, v), std::max(y, v), std::max(z, v));
+    }
+
+    Vec3 pow(double e) const {
+        return Vec3(std::pow(x, e), std::pow(y, e), std::pow(z, e));
+    }
+};
+
+double sd_sphere(const Vec3& p, double radius) {
+    return p.length() - radius;
+}
+
+double sd_box(const Vec3& p, const Vec3& half_extent) {
+    Vec3 q = p.abs() - half_extent;
+    return q.max_scalar(0.0).length() + std::min(std::max(q.x, std::max(q.y, q.z)), 0.0);
+}
+
+double sd_torus(const Vec3& p, double major, double minor) {
+    double qx = std::sqrt(p.x * p.x + p.z * p.z) - major;
+    return std::sqrt(qx * qx + p.y * p.y) - minor;
+}
+
+// Polynomial smooth minimum: blends surfaces within `k` of each other
+double smooth_min(double a, double b, double k) {
+    double h = std::clamp(0.5 + 0.5 * (b - a) / k, 0.0, 1.0);
+    return b + (a - b) * h - k * h * (1.0 - h);
+}
+
+// Everything above the ground plane
+double sd_objects(const Vec3& p) {
+    // Sphere melted into a rounded box
+    double blob = smooth_min(
+        sd_sphere(p - Vec3(-1.6, 0.7, -4.0), 0.6),
+        sd_box(p - Vec3(-0.9, 0.45, -4.0), Vec3(0.4, 0.4, 0.4)) - 0.05,
+        0.35);
+
+    // Sphere with a sinusoidal displacement
+    Vec3 c = p - Vec3(1.5, 0.8, -4.5);
+    double wobble = sd_sphere(c, 0.75) - 0.04 * std::sin(8.0 * c.x) * std::sin(8.0 * c.y) * std::sin(8.0 * c.z);
+
+    double ring = sd_torus(p - Vec3(0.2, 0.2, -2.8), 0.5, 0.15);
+
+    // 9×5 grid of small spheres via limited domain repetition
+    double spacing = 0.7;
+    double cell_x = std::clamp(std::round(p.x / spacing), -4.0, 4.0);
+    double cell_z = std::clamp(std::round((p.z + 7.0) / spacing), -2.0, 2.0);
+    Vec3 local(p.x - spacing * cell_x, p.y - 0.2, p.z + 7.0 - spacing * cell_z);
+    double grid = sd_sphere(local, 0.2);
+
+    return std::min(std::min(std::min(blob, wobble), ring), grid);
+}
+
+double sd_scene(const Vec3& p) {
+    return std::min(p.y, sd_objects(p));
+}
+
+// Returns the hit distance (if any) and the number of steps taken
+std::pair<std::optional<double>, uint32_t> march(const Vec3& origin, const Vec3& direction) {
+    double t = 0.0;
+    for (uint32_t step = 0; step < MAX_STEPS; step++) {
+        double d = sd_scene(origin + direction * t);
+        if (d < HIT_EPSILON * std::max(t, 1.0)) {
+            return {t, step + 1};
+        }
+        t += d;
+        if (t > MAX_DISTANCE) {
+            return {std::nullopt, step + 1};
+        }
+    }
+    return {std::nullopt, MAX_STEPS};
+}
+
+// Tetrahedral gradient estimate: 4 scene evaluations
+Vec3 normal(const Vec3& p) {
+    const Vec3 k[4] = {Vec3(1.0, -1.0, -1.0), Vec3(-1.0, -1.0, 1.0), Vec3(-1.0, 1.0, -1.0), Vec3(1.0, 1.0, 1.0)};
+    Vec3 n(0.0, 0.0, 0.0);
+    for (const Vec3& v : k) {
+        n = n + v * sd_scene(p + v * NORMAL_EPSILON);
+    }
+    return n.normalize();
+}
+
+// Penumbra from how closely the shadow ray grazes other surfaces
+double soft_shadow(const Vec3& origin, const Vec3& direction, double min_t, double max_t) {
+    double result = 1.0;
+    double t = min_t;
+    for (uint32_t i = 0; i < SHADOW_STEPS; i++) {
+        double h = sd_scene(origin + direction * t);
+        if (h < HIT_EPSILON) {
+            return 0.0;
+        }
+        result = std::min(result, SHADOW_SOFTNESS * h / t);
+        t += std::clamp(h, 0.01, 0.5);
+        if (t > max_t) {
+            break;
+        }
+    }
+    return std::clamp(result, 0.0, 1.0);
+}
+
+Vec3 sky(const Vec3& direction) {
+    double t = 0.5 * (direction.y + 1.0);
+    return Vec3(0.9, 0.9, 0.95) * (1.0 - t) + Vec3(0.4, 0.6, 1.0) * t;
+}
+
+Vec3 albedo(const Vec3& p) {
+    if (p.y < sd_objects(p)) {
+        // Checkerboard ground
+        if (static_cast<int64_t>(std::floor(p.x) + std::floor(p.z)) % 2 == 0) {
+            return Vec3(0.8, 0.8, 0.8);
+        }
+        return Vec3(0.3, 0.3, 0.3);
+    }
+    return Vec3(0.9, 0.55, 0.3);
+}
+
+Vec3 shade(const Vec3& p, const Vec3& direction, double t, const Vec3& light) {
+    Vec3 n = normal(p);
+    double diffuse = std::max(n.dot(light), 0.0);
+    double shadow = diffuse > 0.0 ? soft_shadow(p + n * 0.002, light, 0.01, 20.0) : 0.0;
+    Vec3 half = (light - direction).normalize();
+    double specular = std::pow(std::max(n.dot(half), 0.0), 32.0) * shadow;
+    double ambient = 0.5 + 0.5 * n.y;
+
+    Vec3 color = albedo(p) * (diffuse * shadow + 0.15 * ambient) + Vec3(specular, specular, specular) * 0.3;
+    double fog = std::exp(-0.002 * t * t);
+    return color * fog + sky(direction) * (1.0 - fog);
+}
+
+struct Camera {
+    Vec3 origin, forward, right, up;
+};
+
+Camera camera() {
+    Vec3 origin(0.0, 1.4, 1.0);
+    Vec3 forward = (Vec3(0.0, 0.5, -4.0) - origin).normalize();
+    Vec3 right = forward.cross(Vec3(0.0, 1.0, 0.0)).normalize();
+    Vec3 up = right.cross(forward);
+    return {origin, forward, right, up};
+}
+
+struct Pixel {
+    Vec3 color;
+    uint32_t steps;
+};
+
+Pixel render_pixel(size_t x, size_t y, const Camera& cam, const Vec3& light) {
+    double aspect = static_cast<double>(WIDTH) / HEIGHT;
+    double u = ((x + 0.5) / WIDTH * 2.0 - 1.0) * aspect;
+    double v = 1.0 - (y + 0.5) / HEIGHT * 2.0;
+    Vec3 direction = (cam.forward * 1.5 + cam.right * u + cam.up * v).normalize();
+    auto [hit, steps] = march(cam.origin, direction);
+    Vec3 color = hit ? shade(cam.origin + direction * *hit, direction, *hit, light) : sky(direction);
+    // Gamma correction
+    return {color.pow(1.0 / 2.2), steps};
+}
+
+void render_section(const Camera& cam, const Vec3& light, std::vector<Pixel>& image, size_t start_row, size_t end_row) {
+    for (size_t y = start_row; y < end_row; y++) {
+        for (size_t x = 0; x < WIDTH; x++) {
+            image[y * WIDTH + x] = render_pixel(x, y, cam, light);
+        }
+    }
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    Camera cam = camera();
+    Vec3 light = Vec3(0.6, 0.7, 0.3).normalize();
+
+    std::vector<Pixel> image(WIDTH * HEIGHT);
+
+    // Warm-up
+    render_section(cam, light, image, HEIGHT / 2, HEIGHT / 2 + 1);
+
+    // Benchmark
+    measure->start();
+
+    std::vector<std::thread> threads;
+    size_t rows_per_thread = HEIGHT / NUM_THREADS;
+
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        size_t start_row = t * rows_per_thread;
+        size_t end_row = (t == NUM_THREADS - 1) ? HEIGHT : (t + 1) * rows_per_thread;
+        threads.emplace_back(render_section, std::cref(cam), std::cref(light), std::ref(image), start_row, end_row);
+    }
+
+    for (auto& thread : threads) {
+        thread.join();
+    }
+
+    measure->stop();
+
+    // Checksum
+    double checksum = 0.0;
+    uint64_t total_steps = 0;
+    for (const auto& p : image) {
+        checksum += p.color.x + p.color.y + p.color.z;
+        total_steps += p.steps;
+    }
+
+    measure->report();
+    std::cerr << std::fixed << std::setprecision(1) << "Primary march steps: " << total_steps << " ("
+              << static_cast<double>(total_steps) / (WIDTH * HEIGHT) << " per pixel)" << std::endl;
+    std::cerr << std::setprecision(6) << "Checksum: " << checksum << std::endl;
+
+    return 0;
+}
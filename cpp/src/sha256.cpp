@@ -5,6 +5,17 @@
 #include <chrono>
 #include <iomanip>
 #include <sstream>
+#include <array>
+#include <thread>
+#include <atomic>
+#include <cstdlib>
+#include <cerrno>
+#include <fcntl.h>
+#include <sys/mman.h>
+#include <sys/stat.h>
+#include <unistd.h>
+#include "cpu_features.h"
+#include "bench_measure.h"
 
 class SHA256 {
 private:
@@ -92,7 +103,7 @@ public:
         }
     }
     
-    std::string finalize() {
+    std::array<uint8_t, 32> finalize_bytes() {
         uint32_t i = datalen;
         
         if (datalen < 56) {
@@ -116,24 +127,184 @@ public:
         data[56] = bitlen >> 56;
         transform();
         
-        std::stringstream ss;
+        std::array<uint8_t, 32> digest;
         for (int i = 0; i < 8; ++i) {
-            ss << std::hex << std::setfill('0') << std::setw(8) << h[i];
+            digest[4 * i] = h[i] >> 24;
+            digest[4 * i + 1] = h[i] >> 16;
+            digest[4 * i + 2] = h[i] >> 8;
+            digest[4 * i + 3] = h[i];
+        }
+        return digest;
+    }
+    
+    std::string finalize() {
+        return to_hex(finalize_bytes());
+    }
+    
+    static std::string to_hex(const std::array<uint8_t, 32>& digest) {
+        std::stringstream ss;
+        for (uint8_t byte : digest) {
+            ss << std::hex << std::setfill('0') << std::setw(2) << static_cast<int>(byte);
         }
-        
         return ss.str();
     }
 };
 
 const size_t DATA_SIZE = 100'000'000;
 const size_t CHUNK_SIZE = 1024;
+const size_t LEAF_SIZE = 1 << 20; // Merkle leaves are 1 MiB
+const size_t DEFAULT_READ_BUFFER = 64 * 1024;
+const size_t NUM_THREADS = 8;
+
+enum class Mode {
+    Stream, // one hasher fed CHUNK_SIZE slices in order
+    Merkle, // LEAF_SIZE leaves hashed in parallel, then combined pairwise into a root
+};
 
-int main() {
+// Where the bytes come from; the hashing work is the same for all three
+enum class Source {
+    Generated, // DATA_SIZE bytes built in memory
+    Read,      // read(2) into one reused buffer, each fill hashed as one update
+    Mmap,      // the whole file mapped read-only
+};
+
+struct Options {
+    Mode mode = Mode::Stream;
+    Source source = Source::Generated;
+    const char* path = nullptr;
+    size_t buffer_size = DEFAULT_READ_BUFFER;
+};
+
+using Hash = std::array<uint8_t, 32>;
+
+// Domain-separated as in RFC 6962: 0x00 prefixes leaves, 0x01 prefixes interior nodes, so a
+// leaf can never be mistaken for a parent
+Hash leaf_hash(const uint8_t* chunk, size_t len) {
+    const uint8_t prefix = 0x00;
+    SHA256 hasher;
+    hasher.update(&prefix, 1);
+    hasher.update(chunk, len);
+    return hasher.finalize_bytes();
+}
+
+Hash parent_hash(const Hash& left, const Hash& right) {
+    const uint8_t prefix = 0x01;
+    SHA256 hasher;
+    hasher.update(&prefix, 1);
+    hasher.update(left.data(), left.size());
+    hasher.update(right.data(), right.size());
+    return hasher.finalize_bytes();
+}
+
+// Leaves are the parallel part, NUM_THREADS threads pulling them from a shared counter; the
+// ~100 interior hashes are folded level by level on one thread, carrying an odd last node up unchanged
+Hash merkle_root(const uint8_t* data, size_t len) {
+    size_t leaves = (len + LEAF_SIZE - 1) / LEAF_SIZE;
+    std::vector<Hash> level(leaves);
+    std::atomic<size_t> next{0};
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&]() {
+            for (size_t i = next++; i < leaves; i = next++) {
+                size_t start = i * LEAF_SIZE;
+                level[i] = leaf_hash(data + start, std::min(LEAF_SIZE, len - start));
+            }
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+    while (level.size() > 1) {
+        std::vector<Hash> parents;
+        for (size_t i = 0; i < level.size(); i += 2) {
+            parents.push_back(i + 1 < level.size() ? parent_hash(level[i], level[i + 1]) : level[i]);
+        }
+        level = std::move(parents);
+    }
+    return level[0];
+}
+
+// These rounds are portable C++, so SHA instructions are never used, whether or not the CPU has them
+std::string compress_path() {
+    const char* instructions = cpu_has("sha") ? "SHA-NI" : cpu_has("sha2") ? "SHA2 instructions" : nullptr;
+    if (instructions) return cpu_path("compress", CpuPath::Scalar, std::string("portable rounds; the CPU has ") + instructions);
+    return cpu_path("compress", CpuPath::Scalar, "portable rounds");
+}
+
+[[noreturn]] void fail(const std::string& message) {
+    std::cerr << message << std::endl;
+    std::exit(1);
+}
+
+// Bytes with an optional binary K, M or G suffix: 4096, 64K, 1M
+size_t parse_size(const char* value) {
+    char* end = nullptr;
+    unsigned long long n = std::strtoull(value, &end, 10);
+    size_t scale = 1;
+    if (end != value && (*end == 'K' || *end == 'k')) { scale = size_t(1) << 10; end++; }
+    else if (end != value && (*end == 'M' || *end == 'm')) { scale = size_t(1) << 20; end++; }
+    else if (end != value && (*end == 'G' || *end == 'g')) { scale = size_t(1) << 30; end++; }
+    if (end == value || *end != '\0' || n == 0 || value[0] == '-') {
+        fail("--buffer-size expects a byte count such as 4096, 64K or 1M");
+    }
+    return static_cast<size_t>(n) * scale;
+}
+
+Options parse_options(int argc, char** argv) {
+    Options options;
+    bool has_buffer_size = false, use_mmap = false;
+    for (int i = 1; i < argc; i++) {
+        const char* value = i + 1 < argc ? argv[i + 1] : "";
+        if (std::strcmp(argv[i], "--mode") == 0) {
+            if (std::strcmp(value, "stream") == 0) options.mode = Mode::Stream;
+            else if (std::strcmp(value, "merkle") == 0) options.mode = Mode::Merkle;
+            else fail("--mode expects stream or merkle");
+        } else if (std::strcmp(argv[i], "--file") == 0) {
+            if (i + 1 >= argc) fail("--file expects a path");
+            options.path = value;
+        } else if (std::strcmp(argv[i], "--buffer-size") == 0) {
+            options.buffer_size = parse_size(value);
+            has_buffer_size = true;
+        } else if (std::strcmp(argv[i], "--mmap") == 0) {
+            use_mmap = true;
+        }
+    }
+    if (!options.path) {
+        if (has_buffer_size || use_mmap) fail("--buffer-size and --mmap need --file");
+    } else if (use_mmap) {
+        if (has_buffer_size) fail("--buffer-size applies to read() input, not --mmap");
+        options.source = Source::Mmap;
+    } else {
+        if (options.mode == Mode::Merkle) fail("--mode merkle hashes files through --mmap");
+        options.source = Source::Read;
+    }
+    return options;
+}
+
+std::string hash_stream(const uint8_t* data, size_t len) {
+    SHA256 hasher;
+    for (size_t i = 0; i < len; i += CHUNK_SIZE) {
+        hasher.update(data + i, std::min(CHUNK_SIZE, len - i));
+    }
+    return hasher.finalize();
+}
+
+int main(int argc, char** argv) {
+    auto measure = bench_measure_from_env();
+    Options options = parse_options(argc, argv);
+    Mode mode = options.mode;
+    
     // Generate data
-    std::vector<uint8_t> data(DATA_SIZE);
-    for (size_t i = 0; i < DATA_SIZE; i++) {
+    size_t data_size = options.source == Source::Generated ? DATA_SIZE : LEAF_SIZE * 8; // warm-up only for files
+    std::vector<uint8_t> data(data_size);
+    for (size_t i = 0; i < data_size; i++) {
         data[i] = static_cast<uint8_t>(i % 256);
     }
+    int fd = -1;
+    if (options.path) {
+        fd = open(options.path, O_RDONLY);
+        if (fd < 0) fail(std::string("Failed to open ") + options.path + ": " + std::strerror(errno));
+    }
     
     // Warm-up
     {
@@ -141,23 +312,81 @@ int main() {
         hasher.update(data.data(), 1'000'000);
         hasher.finalize();
     }
+    if (mode == Mode::Merkle) {
+        merkle_root(data.data(), LEAF_SIZE * 8);
+    }
     
     // Benchmark
     auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
     
-    SHA256 hasher;
-    for (size_t i = 0; i < DATA_SIZE; i += CHUNK_SIZE) {
-        size_t chunk_size = std::min(CHUNK_SIZE, DATA_SIZE - i);
-        hasher.update(data.data() + i, chunk_size);
+    std::string result;
+    size_t len = 0;
+    double read_time = 0, hash_time = 0;
+    if (options.source == Source::Generated) {
+        len = data.size();
+        result = mode == Mode::Stream ? hash_stream(data.data(), len) : SHA256::to_hex(merkle_root(data.data(), len));
+    } else if (options.source == Source::Read) {
+        // Times read() and the hasher separately to split I/O from hashing
+        std::vector<uint8_t> buffer(options.buffer_size);
+        SHA256 hasher;
+        while (true) {
+            auto read_start = std::chrono::high_resolution_clock::now();
+            ssize_t n = read(fd, buffer.data(), buffer.size());
+            auto read_end = std::chrono::high_resolution_clock::now();
+            read_time += std::chrono::duration<double>(read_end - read_start).count();
+            if (n < 0) fail(std::string("Failed to read ") + options.path + ": " + std::strerror(errno));
+            if (n == 0) break;
+            hasher.update(buffer.data(), static_cast<size_t>(n));
+            hash_time += std::chrono::duration<double>(std::chrono::high_resolution_clock::now() - read_end).count();
+            len += static_cast<size_t>(n);
+        }
+        result = hasher.finalize();
+    } else {
+        // Page faults land inside the hashing, so this measures I/O and hashing together
+        struct stat info;
+        if (fstat(fd, &info) != 0) fail(std::string("Failed to stat ") + options.path + ": " + std::strerror(errno));
+        len = static_cast<size_t>(info.st_size);
+        const uint8_t* bytes = nullptr;
+        void* mapping = nullptr;
+        if (len > 0) { // mmap rejects empty lengths
+            mapping = mmap(nullptr, len, PROT_READ, MAP_PRIVATE, fd, 0);
+            if (mapping == MAP_FAILED) fail(std::string("Failed to map ") + options.path + ": " + std::strerror(errno));
+            bytes = static_cast<const uint8_t*>(mapping);
+        }
+        result = mode == Mode::Stream ? hash_stream(bytes, len) : SHA256::to_hex(merkle_root(bytes, len));
+        if (mapping) munmap(mapping, len);
     }
-    std::string result = hasher.finalize();
     
+    measure->stop();
     auto end = std::chrono::high_resolution_clock::now();
     std::chrono::duration<double> duration = end - start;
+    if (fd >= 0) close(fd);
     
-    std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
+    measure->report();
+    if (options.source == Source::Read) {
+        std::cerr << "Input: " << options.path << " (" << len << " bytes), read() into a " << options.buffer_size << " B buffer" << std::endl;
+    } else if (options.source == Source::Mmap) {
+        std::cerr << "Input: " << options.path << " (" << len << " bytes), mmap" << std::endl;
+    } else {
+        std::cerr << "Input: generated (" << len << " bytes in memory)" << std::endl;
+    }
+    if (mode == Mode::Stream) {
+        size_t update = options.source == Source::Read ? options.buffer_size : CHUNK_SIZE;
+        std::cerr << "Mode: stream (" << update << " B updates, 1 thread)" << std::endl;
+    } else {
+        std::cerr << "Mode: merkle (" << (len + LEAF_SIZE - 1) / LEAF_SIZE << " leaves of " << LEAF_SIZE / 1024
+                  << " KiB on " << NUM_THREADS << " threads)" << std::endl;
+    }
+    std::cerr << std::fixed << std::setprecision(3) << "Throughput: " << len / duration.count() / 1e9 << " GB/s" << std::endl;
+    if (options.source == Source::Read) {
+        // I/O-bound vs CPU-bound: a warm page cache makes read() a memcpy
+        std::cerr << "Read: " << read_time << "s (" << len / read_time / 1e9 << " GB/s), hash: " << hash_time
+                  << "s (" << len / hash_time / 1e9 << " GB/s)" << std::endl;
+    }
+    std::cerr << cpu_features_report() << std::endl;
+    std::cerr << compress_path() << std::endl;
     std::cerr << "Hash: " << result << std::endl;
     
     return 0;
 }
-
@@ -0,0 +1,182 @@
+#include <iostream>
+#include <vector>
+#include <array>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <string>
+#include <cstdint>
+#include <cstdio>
+#include <cstring>
+#include <cstdlib>
+#include <type_traits>
+
+const size_t NUM_BUFFERS = 20'000'000;
+const size_t MAX_LEN = 24;      // lengths drawn uniformly from 1..=MAX_LEN, so a third outgrow INLINE
+const size_t INLINE = 16;       // SmallVector's inline slots
+const size_t MAX_CAPACITY = 64; // the fixed array's size, and the largest --max-len
+const size_t WARMUP_BUFFERS = 200'000;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+size_t next_len(uint64_t& seed, size_t max_len) {
+    return 1 + next_random(seed) % max_len;
+}
+
+// Inline storage for the first N elements, one heap block once it outgrows them: the design of
+// LLVM's SmallVector and boost::container::small_vector, neither of which is in the standard
+// library. Trivially copyable elements only, which is all this benchmark needs
+template <typename T, size_t N>
+class SmallVector {
+    static_assert(std::is_trivially_copyable_v<T>);
+    T inline_[N];
+    T* data_ = inline_;
+    size_t size_ = 0;
+    size_t capacity_ = N;
+
+    void grow(size_t capacity) {
+        T* heap = new T[capacity];
+        std::copy(data_, data_ + size_, heap);
+        if (spilled()) delete[] data_;
+        data_ = heap;
+        capacity_ = capacity;
+    }
+
+public:
+    SmallVector() = default;
+    SmallVector(const SmallVector&) = delete;
+    SmallVector& operator=(const SmallVector&) = delete;
+    ~SmallVector() {
+        if (spilled()) delete[] data_;
+    }
+
+    void reserve(size_t capacity) {
+        if (capacity > capacity_) grow(capacity);
+    }
+    void push_back(T value) {
+        if (size_ == capacity_) grow(2 * capacity_);
+        data_[size_++] = value;
+    }
+    bool spilled() const { return data_ != inline_; }
+    const T* data() const { return data_; }
+    size_t size() const { return size_; }
+};
+
+// The work done on every buffer: one position-weighted pass, light enough that allocation is
+// a visible share of the loop. The barrier makes each buffer exist in memory, like black_box,
+// so no variant's storage can be optimized into registers
+uint64_t digest(const uint32_t* buf, size_t len) {
+    asm volatile("" : : "r"(buf) : "memory");
+    uint64_t acc = 0;
+    for (size_t i = 0; i < len; i++) acc = acc * 31 + buf[i];
+    return acc;
+}
+
+// Every variant starts from the same seed, so they fill identical buffers in the same order
+struct Run {
+    Duration duration;
+    uint64_t checksum = 0;
+    size_t heap_allocations = 0;
+};
+
+// A fixed-size array, always on the stack, left uninitialized as C++ allows (Rust zeroes it)
+Run run_array(size_t buffers, size_t max_len) {
+    Run run;
+    uint64_t seed = 1;
+    auto start = Clock::now();
+    for (size_t b = 0; b < buffers; b++) {
+        size_t len = next_len(seed, max_len);
+        std::array<uint32_t, MAX_CAPACITY> buf;
+        for (size_t i = 0; i < len; i++) buf[i] = static_cast<uint32_t>(next_random(seed));
+        run.checksum += digest(buf.data(), len);
+    }
+    run.duration = Clock::now() - start;
+    return run;
+}
+
+// Inline up to INLINE elements, one heap allocation past that
+Run run_small_vector(size_t buffers, size_t max_len) {
+    Run run;
+    uint64_t seed = 1;
+    auto start = Clock::now();
+    for (size_t b = 0; b < buffers; b++) {
+        size_t len = next_len(seed, max_len);
+        SmallVector<uint32_t, INLINE> buf;
+        buf.reserve(len);
+        for (size_t i = 0; i < len; i++) buf.push_back(static_cast<uint32_t>(next_random(seed)));
+        run.heap_allocations += buf.spilled();
+        run.checksum += digest(buf.data(), buf.size());
+    }
+    run.duration = Clock::now() - start;
+    return run;
+}
+
+// One heap allocation per buffer
+Run run_vector(size_t buffers, size_t max_len) {
+    Run run;
+    uint64_t seed = 1;
+    auto start = Clock::now();
+    for (size_t b = 0; b < buffers; b++) {
+        size_t len = next_len(seed, max_len);
+        std::vector<uint32_t> buf;
+        buf.reserve(len);
+        for (size_t i = 0; i < len; i++) buf.push_back(static_cast<uint32_t>(next_random(seed)));
+        run.checksum += digest(buf.data(), buf.size());
+    }
+    run.duration = Clock::now() - start;
+    run.heap_allocations = buffers;
+    return run;
+}
+
+size_t parse_max_len(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--max-len") == 0) {
+            long n = i + 1 < argc ? std::atol(argv[i + 1]) : 0;
+            if (n < 1 || n > static_cast<long>(MAX_CAPACITY)) {
+                std::cerr << "--max-len expects a length from 1 to " << MAX_CAPACITY << std::endl;
+                std::exit(1);
+            }
+            return static_cast<size_t>(n);
+        }
+    }
+    return MAX_LEN;
+}
+
+void report(const std::string& name, const Run& run) {
+    fprintf(stderr, "  %-24s %.3fs (%.1f ns/buffer, %zu heap allocations)\n", name.c_str(), run.duration.count(),
+            run.duration.count() * 1e9 / NUM_BUFFERS, run.heap_allocations);
+}
+
+int main(int argc, char** argv) {
+    size_t max_len = parse_max_len(argc, argv);
+
+    // Warm-up
+    run_array(WARMUP_BUFFERS, max_len);
+    run_small_vector(WARMUP_BUFFERS, max_len);
+    run_vector(WARMUP_BUFFERS, max_len);
+
+    // Benchmark
+    Run array = run_array(NUM_BUFFERS, max_len);
+    Run small = run_small_vector(NUM_BUFFERS, max_len);
+    Run heap = run_vector(NUM_BUFFERS, max_len);
+
+    Duration total_duration = array.duration + small.duration + heap.duration;
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Buffers: %zu of 1-%zu uint32_t (%.1f%% longer than the %zu inline slots)\n", NUM_BUFFERS, max_len,
+            small.heap_allocations * 100.0 / NUM_BUFFERS, INLINE);
+    report("std::array<uint32_t, " + std::to_string(MAX_CAPACITY) + ">", array);
+    report("SmallVector<" + std::to_string(INLINE) + ">", small);
+    report("std::vector<uint32_t>", heap);
+    bool match = array.checksum == small.checksum && array.checksum == heap.checksum;
+    std::cerr << "Checksum: " << array.checksum << " (all variants match: " << (match ? "true" : "false") << ")"
+              << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,202 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <string_view>
+#include <algorithm>
+#include <memory>
+#include <cstring>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+
+const size_t NUM_STRINGS = 2'000'000;
+const size_t MAX_LEN = 22; // lengths uniform over 1..=MAX_LEN, all within libc++'s 22-byte SSO
+const size_t ROUNDS = 4;
+const char ALPHABET[] = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+const size_t ALPHABET_LEN = sizeof(ALPHABET) - 1;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t splitmix64(uint64_t& seed) {
+    seed += 0x9E3779B97F4A7C15ULL;
+    uint64_t z = seed;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+// The strings back to back in one buffer, with their lengths. Short lengths repeat often, so
+// the sorted set has runs of equal strings
+struct Corpus {
+    std::string text;
+    std::vector<size_t> lengths;
+};
+
+Corpus generate(size_t count) {
+    uint64_t seed = 23;
+    Corpus c;
+    c.lengths.reserve(count);
+    for (size_t i = 0; i < count; i++) {
+        size_t len = 1 + splitmix64(seed) % MAX_LEN;
+        for (size_t j = 0; j < len; j++) c.text += ALPHABET[splitmix64(seed) % ALPHABET_LEN];
+        c.lengths.push_back(len);
+    }
+    return c;
+}
+
+std::vector<std::string_view> slices(const Corpus& c) {
+    std::vector<std::string_view> out;
+    size_t start = 0;
+    for (size_t len : c.lengths) {
+        out.emplace_back(c.text.data() + start, len);
+        start += len;
+    }
+    return out;
+}
+
+// FNV-1a, the same in both languages, so the hash phase measures reaching the bytes rather than
+// each standard library's hasher
+uint64_t fnv1a(std::string_view bytes) {
+    uint64_t hash = 0xcbf29ce484222325ULL;
+    for (unsigned char b : bytes) hash = (hash ^ b) * 0x100000001b3ULL;
+    return hash;
+}
+
+// Always on the heap, pointer and length only, like Rust's Box<str>: what std::string would
+// cost without its small-string optimization
+class BoxedString {
+public:
+    explicit BoxedString(std::string_view s) : data_(new char[s.size()]), len_(s.size()) {
+        std::memcpy(data_.get(), s.data(), s.size());
+    }
+    BoxedString(const BoxedString& other) : BoxedString(other.view()) {}
+    BoxedString(BoxedString&&) noexcept = default;
+    BoxedString& operator=(const BoxedString& other) {
+        if (this != &other) *this = BoxedString(other);
+        return *this;
+    }
+    BoxedString& operator=(BoxedString&&) noexcept = default;
+
+    std::string_view view() const { return {data_.get(), len_}; }
+    bool operator==(const BoxedString& other) const { return view() == other.view(); }
+    bool operator<(const BoxedString& other) const { return view() < other.view(); }
+
+private:
+    std::unique_ptr<char[]> data_;
+    size_t len_;
+};
+
+std::string_view view(const std::string& s) { return s; }
+std::string_view view(const BoxedString& s) { return s.view(); }
+
+// Inline when the characters live inside the object itself
+bool on_heap(const std::string& s) {
+    auto object = reinterpret_cast<const char*>(&s);
+    return s.data() < object || s.data() >= object + sizeof(s);
+}
+bool on_heap(const BoxedString&) { return true; }
+
+const char* const PHASES[] = {"create", "clone", "hash", "compare", "drop"};
+
+struct Run {
+    Duration durations[5] = {};
+    uint64_t hashed = 0;   // sum of the strings' hashes
+    uint64_t distinct = 0; // strings unequal to their predecessor once sorted
+    uint64_t ordered = 0;  // order-sensitive fold of the sorted hashes
+    size_t on_heap = 0;
+    size_t handle_bytes = 0;
+};
+
+// Per round: build the strings from slices, clone the set, hash every clone, sort the clones and
+// count distinct neighbours, then drop both sets
+template <typename S>
+Run run(const std::vector<std::string_view>& sources, size_t rounds) {
+    Run r;
+    r.handle_bytes = sizeof(S);
+    for (size_t round = 0; round < rounds; round++) {
+        auto start = Clock::now();
+        asm volatile("" : : "r"(sources.data()) : "memory");
+        std::vector<S> strings;
+        strings.reserve(sources.size());
+        for (std::string_view s : sources) strings.emplace_back(s);
+        auto created = Clock::now();
+        std::vector<S> copies = strings;
+        auto cloned = Clock::now();
+        r.hashed = 0;
+        for (const S& s : copies) r.hashed += fnv1a(view(s));
+        auto hashed = Clock::now();
+        std::sort(copies.begin(), copies.end());
+        r.distinct = 1;
+        for (size_t i = 1; i < copies.size(); i++) r.distinct += !(copies[i] == copies[i - 1]);
+        auto compared = Clock::now();
+
+        r.ordered = 0;
+        for (const S& s : copies) r.ordered = r.ordered * 31 + fnv1a(view(s));
+        r.on_heap = 0;
+        for (const S& s : strings) r.on_heap += on_heap(s);
+
+        auto drop_start = Clock::now();
+        asm volatile("" : : "r"(copies.data()), "r"(strings.data()) : "memory");
+        std::vector<S>().swap(copies);
+        std::vector<S>().swap(strings);
+        auto dropped = Clock::now();
+
+        r.durations[0] += created - start;
+        r.durations[1] += cloned - created;
+        r.durations[2] += hashed - cloned;
+        r.durations[3] += compared - hashed;
+        r.durations[4] += dropped - drop_start;
+    }
+    return r;
+}
+
+using RunFn = Run (*)(const std::vector<std::string_view>&, size_t);
+
+struct NamedVariant {
+    const char* name;
+    RunFn run;
+};
+
+const NamedVariant VARIANTS[] = {
+    {"std::string", run<std::string>},
+    {"BoxedString", run<BoxedString>},
+};
+
+int main() {
+    Corpus corpus = generate(NUM_STRINGS);
+    std::vector<std::string_view> sources = slices(corpus);
+
+    // Warm-up
+    std::vector<std::string_view> warmup(sources.begin(), sources.begin() + NUM_STRINGS / 100);
+    for (const auto& v : VARIANTS) v.run(warmup, 1);
+
+    // Benchmark
+    std::vector<Run> runs;
+    Duration total_duration(0);
+    for (const auto& v : VARIANTS) {
+        runs.push_back(v.run(sources, ROUNDS));
+        for (Duration d : runs.back().durations) total_duration += d;
+    }
+    bool all_match = true;
+    for (const Run& r : runs) {
+        all_match &= r.hashed == runs[0].hashed && r.distinct == runs[0].distinct && r.ordered == runs[0].ordered;
+    }
+    uint64_t checksum = runs[0].hashed + runs[0].distinct + runs[0].ordered;
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Strings: %zu of 1-%zu bytes (%llu distinct) x %zu rounds\n", NUM_STRINGS, MAX_LEN,
+            static_cast<unsigned long long>(runs[0].distinct), ROUNDS);
+    for (size_t i = 0; i < runs.size(); i++) {
+        fprintf(stderr, "  %-14s", VARIANTS[i].name);
+        for (size_t phase = 0; phase < 5; phase++) {
+            fprintf(stderr, "%s%s %.3fs", phase == 0 ? " " : "  ", PHASES[phase], runs[i].durations[phase].count());
+        }
+        fprintf(stderr, "  (%zu-byte handle, %.1f%% on the heap)\n", runs[i].handle_bytes,
+                runs[i].on_heap * 100.0 / NUM_STRINGS);
+    }
+    std::cerr << "Checksum: " << checksum << " (all variants match: " << (all_match ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
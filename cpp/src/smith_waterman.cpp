@@ -0,0 +1,304 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t QUERY_LEN = 256;
+const size_t NUM_SEQUENCES = 5000;
+const size_t MIN_SEQ_LEN = 256;
+const size_t MAX_SEQ_LEN = 768;
+const int16_t MATCH = 2;
+const int16_t MISMATCH = -3;
+const int16_t GAP_OPEN = 5; // Cost of the first gap position
+const int16_t GAP_EXTEND = 2; // Cost of each further gap position
+const int32_t HIT_THRESHOLD = 50;
+const size_t NUM_THREADS = 8;
+
+const size_t LANES = 8; // 8 x int16_t = one 128-bit vector
+const int16_t NEG = -16384;
+
+struct Lanes {
+    int16_t v[LANES];
+};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+int16_t score(uint8_t a, uint8_t b) {
+    return a == b ? MATCH : MISMATCH;
+}
+
+// DNA encoded as 0..4; half of the database sequences embed a mutated slice of the query
+void generate_data(std::vector<uint8_t>& query, std::vector<std::vector<uint8_t>>& database) {
+    uint64_t seed = 2718;
+    query.resize(QUERY_LEN);
+    for (auto& q : query) {
+        q = static_cast<uint8_t>(next_random(seed) % 4);
+    }
+
+    database.resize(NUM_SEQUENCES);
+    for (auto& seq : database) {
+        size_t len = MIN_SEQ_LEN + next_random(seed) % (MAX_SEQ_LEN - MIN_SEQ_LEN + 1);
+        seq.resize(len);
+        for (auto& c : seq) {
+            c = static_cast<uint8_t>(next_random(seed) % 4);
+        }
+
+        if (next_random(seed) % 2 == 0) {
+            size_t slice_len = 64 + next_random(seed) % 128;
+            size_t from = next_random(seed) % (QUERY_LEN - slice_len + 1);
+            size_t to = next_random(seed) % (len - slice_len + 1);
+            for (size_t k = 0; k < slice_len; k++) {
+                if (next_random(seed) % 10 == 0) {
+                    seq[to + k] = static_cast<uint8_t>(next_random(seed) % 4);
+                } else {
+                    seq[to + k] = query[from + k];
+                }
+            }
+        }
+    }
+}
+
+// Gotoh's affine-gap recurrence, one database column at a time
+int32_t sw_scalar(const std::vector<uint8_t>& query, const std::vector<uint8_t>& db,
+                  std::vector<int32_t>& h, std::vector<int32_t>& e) {
+    const int32_t open = GAP_OPEN, extend = GAP_EXTEND;
+    std::fill(h.begin(), h.end(), 0);
+    std::fill(e.begin(), e.end(), static_cast<int32_t>(NEG));
+    int32_t best = 0;
+
+    for (uint8_t d : db) {
+        int32_t h_diag = 0;
+        int32_t h_up = 0;
+        int32_t f = NEG;
+        for (size_t i = 0; i < query.size(); i++) {
+            int32_t h_left = h[i];
+            int32_t e_i = std::max(e[i] - extend, h_left - open);
+            f = std::max(f - extend, h_up - open);
+            int32_t h_new = std::max({h_diag + score(query[i], d), e_i, f, 0});
+            e[i] = e_i;
+            h_diag = h_left;
+            h[i] = h_new;
+            h_up = h_new;
+            best = std::max(best, h_new);
+        }
+    }
+
+    return best;
+}
+
+inline Lanes splat(int16_t x) {
+    Lanes r;
+    for (size_t k = 0; k < LANES; k++) r.v[k] = x;
+    return r;
+}
+
+inline Lanes add(Lanes a, const Lanes& b) {
+    for (size_t k = 0; k < LANES; k++) a.v[k] += b.v[k];
+    return a;
+}
+
+inline Lanes sub(Lanes a, int16_t b) {
+    for (size_t k = 0; k < LANES; k++) a.v[k] -= b;
+    return a;
+}
+
+inline Lanes max(Lanes a, const Lanes& b) {
+    for (size_t k = 0; k < LANES; k++) a.v[k] = std::max(a.v[k], b.v[k]);
+    return a;
+}
+
+inline Lanes shift_in(const Lanes& a, int16_t fill) {
+    Lanes r;
+    r.v[0] = fill;
+    for (size_t k = 1; k < LANES; k++) r.v[k] = a.v[k - 1];
+    return r;
+}
+
+inline bool any_greater(const Lanes& a, const Lanes& b) {
+    bool any = false;
+    for (size_t k = 0; k < LANES; k++) any |= a.v[k] > b.v[k];
+    return any;
+}
+
+// Farrar's striped layout: lane k of segment j holds query position k * seg_len + j
+struct StripedProfile {
+    size_t seg_len;
+    std::vector<Lanes> profile; // [residue][segment]
+
+    explicit StripedProfile(const std::vector<uint8_t>& query) {
+        seg_len = (query.size() + LANES - 1) / LANES;
+        profile.assign(4 * seg_len, splat(0));
+        for (uint8_t residue = 0; residue < 4; residue++) {
+            for (size_t j = 0; j < seg_len; j++) {
+                for (size_t k = 0; k < LANES; k++) {
+                    size_t pos = k * seg_len + j;
+                    // Padding positions always mismatch, so they can never raise the best score
+                    profile[residue * seg_len + j].v[k] = pos < query.size() ? score(query[pos], residue) : MISMATCH;
+                }
+            }
+        }
+    }
+};
+
+struct StripedBuffers {
+    std::vector<Lanes> h_load;
+    std::vector<Lanes> h_store;
+    std::vector<Lanes> e;
+};
+
+int32_t sw_striped(const StripedProfile& profile, const std::vector<uint8_t>& db, StripedBuffers& buffers) {
+    const size_t seg_len = profile.seg_len;
+    auto& h_load = buffers.h_load;
+    auto& h_store = buffers.h_store;
+    auto& e = buffers.e;
+    h_store.assign(seg_len, splat(0));
+    h_load.assign(seg_len, splat(0));
+    e.assign(seg_len, splat(NEG));
+    Lanes v_max = splat(0);
+    const Lanes zero = splat(0);
+
+    for (uint8_t d : db) {
+        const Lanes* scores = &profile.profile[d * seg_len];
+        Lanes v_f = splat(NEG);
+        Lanes v_h = shift_in(h_store[seg_len - 1], 0);
+        std::swap(h_load, h_store);
+
+        for (size_t j = 0; j < seg_len; j++) {
+            v_h = add(v_h, scores[j]);
+            v_h = max(v_h, e[j]);
+            v_h = max(v_h, v_f);
+            v_h = max(v_h, zero);
+            h_store[j] = v_h;
+            v_max = max(v_max, v_h);
+
+            v_h = sub(v_h, GAP_OPEN);
+            e[j] = max(sub(e[j], GAP_EXTEND), v_h);
+            v_f = max(sub(v_f, GAP_EXTEND), v_h);
+            v_h = h_load[j];
+        }
+
+        // Lazy-F: propagate vertical gaps that crossed a segment boundary
+        for (size_t k = 0; k < LANES; k++) {
+            v_f = shift_in(v_f, NEG);
+            bool done = false;
+            for (size_t j = 0; j < seg_len; j++) {
+                Lanes h = max(h_store[j], v_f);
+                h_store[j] = h;
+                v_max = max(v_max, h);
+                Lanes h_open = sub(h, GAP_OPEN);
+                e[j] = max(e[j], h_open);
+                v_f = sub(v_f, GAP_EXTEND);
+                if (!any_greater(v_f, h_open)) {
+                    done = true;
+                    break;
+                }
+            }
+            if (done) break;
+        }
+    }
+
+    return *std::max_element(v_max.v, v_max.v + LANES);
+}
+
+template<class F>
+std::vector<int32_t> run_parallel(size_t count, F make_worker) {
+    std::vector<int32_t> scores(count);
+    std::vector<std::thread> threads;
+    size_t per_thread = count / NUM_THREADS;
+
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        size_t begin = t * per_thread;
+        size_t end = (t == NUM_THREADS - 1) ? count : (t + 1) * per_thread;
+        threads.emplace_back([&, begin, end] {
+            auto worker = make_worker();
+            for (size_t i = begin; i < end; i++) {
+                scores[i] = worker(i);
+            }
+        });
+    }
+
+    for (auto& thread : threads) {
+        thread.join();
+    }
+
+    return scores;
+}
+
+std::vector<int32_t> run_scalar(const std::vector<uint8_t>& query,
+                                const std::vector<std::vector<uint8_t>>& database, size_t count) {
+    return run_parallel(count, [&] {
+        return [&, h = std::vector<int32_t>(query.size()), e = std::vector<int32_t>(query.size())](size_t i) mutable {
+            return sw_scalar(query, database[i], h, e);
+        };
+    });
+}
+
+std::vector<int32_t> run_striped(const StripedProfile& profile,
+                                 const std::vector<std::vector<uint8_t>>& database, size_t count) {
+    return run_parallel(count, [&] {
+        return [&, buffers = StripedBuffers{}](size_t i) mutable {
+            return sw_striped(profile, database[i], buffers);
+        };
+    });
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    std::vector<uint8_t> query;
+    std::vector<std::vector<uint8_t>> database;
+    generate_data(query, database);
+    StripedProfile profile(query);
+
+    size_t cells = 0;
+    for (const auto& seq : database) {
+        cells += seq.size() * QUERY_LEN;
+    }
+
+    // Warm-up
+    auto warmup_scalar = run_scalar(query, database, 100);
+    auto warmup_striped = run_striped(profile, database, 100);
+
+    // Benchmark scalar
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    auto scalar_scores = run_scalar(query, database, NUM_SEQUENCES);
+    measure->stop();
+    auto scalar_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark striped
+    auto striped_start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    auto striped_scores = run_striped(profile, database, NUM_SEQUENCES);
+    measure->stop();
+    auto striped_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> scalar_duration = scalar_end - start;
+    std::chrono::duration<double> striped_duration = striped_end - striped_start;
+
+    // Checksum
+    int64_t checksum = 0, striped_checksum = 0;
+    size_t hits = 0;
+    for (size_t i = 0; i < NUM_SEQUENCES; i++) {
+        checksum += scalar_scores[i];
+        striped_checksum += striped_scores[i];
+        if (scalar_scores[i] >= HIT_THRESHOLD) hits++;
+    }
+
+    measure->report();
+    std::cerr << std::fixed << "Scalar: " << scalar_duration.count() << "s (" << std::setprecision(2)
+              << cells / scalar_duration.count() / 1e9 << " GCUPS), striped: " << std::setprecision(6)
+              << striped_duration.count() << "s (" << std::setprecision(2)
+              << cells / striped_duration.count() / 1e9 << " GCUPS)" << std::endl;
+    std::cerr << "Sequences: " << NUM_SEQUENCES << ", hits (score >= " << HIT_THRESHOLD << "): " << hits << std::endl;
+    std::cerr << "Checksum: " << checksum << " (striped: " << striped_checksum << ")" << std::endl;
+
+    return 0;
+}
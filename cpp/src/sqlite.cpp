@@ -0,0 +1,227 @@
+#include <iostream>
+#include <string>
+#include <stdexcept>
+#include <cstdint>
+#include <chrono>
+#include <iomanip>
+#include <tuple>
+#include <sqlite3.h>
+
+const int64_t NUM_ROWS = 1'000'000;
+const uint64_t NUM_CUSTOMERS = 100'000;
+const uint64_t NUM_PRODUCTS = 10'000;
+const size_t POINT_READS = 200'000;
+const size_t CUSTOMER_LOOKUPS = 50'000;
+const size_t POINT_UPDATES = 100'000;
+const size_t ANALYTICAL_ROUNDS = 5;
+const char* REGIONS[8] = {"north", "south", "east", "west", "central", "coastal", "mountain", "island"};
+
+const char* ANALYTICAL_QUERIES[3] = {
+    "SELECT region, COUNT(*), SUM(quantity * price_cents) FROM orders GROUP BY region ORDER BY region",
+    "SELECT product, SUM(quantity) AS units FROM orders GROUP BY product ORDER BY units DESC, product LIMIT 10",
+    "SELECT customer, COUNT(*) AS n FROM orders WHERE region = 'island' GROUP BY customer HAVING n > 2 ORDER BY n DESC, customer LIMIT 10",
+};
+
+using Duration = std::chrono::duration<double>;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+void check(sqlite3* db, int rc, int expected = SQLITE_OK) {
+    if (rc != expected) {
+        throw std::runtime_error(std::string("sqlite: ") + sqlite3_errmsg(db));
+    }
+}
+
+void exec(sqlite3* db, const char* sql) {
+    check(db, sqlite3_exec(db, sql, nullptr, nullptr, nullptr));
+}
+
+// Owns a prepared statement for the lifetime of one phase
+class Statement {
+public:
+    Statement(sqlite3* db, const char* sql) : db_(db) {
+        check(db_, sqlite3_prepare_v2(db_, sql, -1, &stmt_, nullptr));
+    }
+
+    ~Statement() {
+        sqlite3_finalize(stmt_);
+    }
+
+    Statement(const Statement&) = delete;
+    Statement& operator=(const Statement&) = delete;
+
+    sqlite3_stmt* get() {
+        return stmt_;
+    }
+
+    // Steps a statement that returns no rows, then resets it for the next bind
+    void execute() {
+        check(db_, sqlite3_step(stmt_), SQLITE_DONE);
+        sqlite3_reset(stmt_);
+    }
+
+private:
+    sqlite3* db_;
+    sqlite3_stmt* stmt_ = nullptr;
+};
+
+void create_schema(sqlite3* db) {
+    exec(db,
+         "CREATE TABLE orders ("
+         "    id INTEGER PRIMARY KEY,"
+         "    customer INTEGER NOT NULL,"
+         "    product INTEGER NOT NULL,"
+         "    quantity INTEGER NOT NULL,"
+         "    price_cents INTEGER NOT NULL,"
+         "    region TEXT NOT NULL"
+         ");");
+}
+
+// One transaction, one prepared statement, a bind + step + reset per row
+void bulk_insert(sqlite3* db, int64_t rows) {
+    uint64_t seed = 8086;
+    exec(db, "BEGIN");
+    {
+        Statement stmt(db, "INSERT INTO orders VALUES (?1, ?2, ?3, ?4, ?5, ?6)");
+        for (int64_t id = 0; id < rows; id++) {
+            uint64_t customer = next_random(seed) % NUM_CUSTOMERS;
+            uint64_t product = next_random(seed) % NUM_PRODUCTS;
+            uint64_t quantity = 1 + next_random(seed) % 10;
+            uint64_t price_cents = 100 + next_random(seed) % 100'000;
+            const char* region = REGIONS[next_random(seed) % 8];
+            sqlite3_bind_int64(stmt.get(), 1, id);
+            sqlite3_bind_int64(stmt.get(), 2, static_cast<int64_t>(customer));
+            sqlite3_bind_int64(stmt.get(), 3, static_cast<int64_t>(product));
+            sqlite3_bind_int64(stmt.get(), 4, static_cast<int64_t>(quantity));
+            sqlite3_bind_int64(stmt.get(), 5, static_cast<int64_t>(price_cents));
+            sqlite3_bind_text(stmt.get(), 6, region, -1, SQLITE_STATIC);
+            stmt.execute();
+        }
+    }
+    exec(db, "COMMIT");
+}
+
+void build_indexes(sqlite3* db) {
+    exec(db,
+         "CREATE INDEX idx_orders_customer ON orders(customer);"
+         "CREATE INDEX idx_orders_region_product ON orders(region, product);");
+}
+
+// Primary-key lookups and secondary-index aggregates; returns a checksum of everything read
+int64_t point_reads(sqlite3* db, int64_t rows, size_t reads, size_t lookups) {
+    uint64_t seed = 4004;
+    uint64_t checksum = 0;
+    Statement by_id(db, "SELECT quantity, price_cents FROM orders WHERE id = ?1");
+    for (size_t i = 0; i < reads; i++) {
+        auto id = static_cast<int64_t>(next_random(seed) % static_cast<uint64_t>(rows));
+        sqlite3_bind_int64(by_id.get(), 1, id);
+        check(db, sqlite3_step(by_id.get()), SQLITE_ROW);
+        int64_t quantity = sqlite3_column_int64(by_id.get(), 0);
+        int64_t price = sqlite3_column_int64(by_id.get(), 1);
+        sqlite3_reset(by_id.get());
+        checksum += static_cast<uint64_t>(quantity * price);
+    }
+    Statement by_customer(db, "SELECT COUNT(*), COALESCE(SUM(quantity), 0) FROM orders WHERE customer = ?1");
+    for (size_t i = 0; i < lookups; i++) {
+        auto customer = static_cast<int64_t>(next_random(seed) % NUM_CUSTOMERS);
+        sqlite3_bind_int64(by_customer.get(), 1, customer);
+        check(db, sqlite3_step(by_customer.get()), SQLITE_ROW);
+        int64_t count = sqlite3_column_int64(by_customer.get(), 0);
+        int64_t units = sqlite3_column_int64(by_customer.get(), 1);
+        sqlite3_reset(by_customer.get());
+        checksum += static_cast<uint64_t>(count * 1'000 + units);
+    }
+    return static_cast<int64_t>(checksum);
+}
+
+void point_updates(sqlite3* db, int64_t rows, size_t updates) {
+    uint64_t seed = 6502;
+    exec(db, "BEGIN");
+    {
+        Statement stmt(db, "UPDATE orders SET quantity = quantity + 1 WHERE id = ?1");
+        for (size_t i = 0; i < updates; i++) {
+            auto id = static_cast<int64_t>(next_random(seed) % static_cast<uint64_t>(rows));
+            sqlite3_bind_int64(stmt.get(), 1, id);
+            stmt.execute();
+        }
+    }
+    exec(db, "COMMIT");
+}
+
+// Full-scan group-bys; every column after the group key is folded into the checksum
+int64_t analytics(sqlite3* db) {
+    uint64_t checksum = 0;
+    for (const char* query : ANALYTICAL_QUERIES) {
+        Statement stmt(db, query);
+        int columns = sqlite3_column_count(stmt.get());
+        int rc;
+        while ((rc = sqlite3_step(stmt.get())) == SQLITE_ROW) {
+            for (int col = 1; col < columns; col++) {
+                checksum = checksum * 31 + static_cast<uint64_t>(sqlite3_column_int64(stmt.get(), col));
+            }
+        }
+        check(db, rc, SQLITE_DONE);
+    }
+    return static_cast<int64_t>(checksum);
+}
+
+struct Timings {
+    Duration insert, index, reads, updates, analytics;
+};
+
+template <typename F>
+Duration timed(F f) {
+    auto start = std::chrono::high_resolution_clock::now();
+    f();
+    return std::chrono::high_resolution_clock::now() - start;
+}
+
+// Every phase is divided by `scale`, so the warm-up is a miniature of the full run
+std::tuple<Timings, int64_t, int64_t> run(size_t scale) {
+    int64_t rows = NUM_ROWS / static_cast<int64_t>(scale);
+    sqlite3* db = nullptr;
+    if (sqlite3_open(":memory:", &db) != SQLITE_OK) {
+        throw std::runtime_error("sqlite: failed to open in-memory database");
+    }
+    create_schema(db);
+
+    Timings t;
+    int64_t read_checksum = 0, analytics_checksum = 0;
+    t.insert = timed([&] { bulk_insert(db, rows); });
+    t.index = timed([&] { build_indexes(db); });
+    t.reads = timed([&] { read_checksum = point_reads(db, rows, POINT_READS / scale, CUSTOMER_LOOKUPS / scale); });
+    t.updates = timed([&] { point_updates(db, rows, POINT_UPDATES / scale); });
+    t.analytics = timed([&] {
+        for (size_t r = 0; r < (ANALYTICAL_ROUNDS + scale - 1) / scale; r++) {
+            analytics_checksum = analytics(db);
+        }
+    });
+
+    sqlite3_close(db);
+    return {t, read_checksum, analytics_checksum};
+}
+
+int main() {
+    // Warm-up
+    run(100);
+
+    // Benchmark
+    auto [t, read_checksum, analytics_checksum] = run(1);
+    Duration total_duration = t.insert + t.index + t.reads + t.updates + t.analytics;
+    auto rate = [](size_t n, Duration d) { return static_cast<double>(n) / d.count(); };
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(0) << "Bulk insert: " << rate(NUM_ROWS, t.insert)
+              << " rows/s, index build: " << std::setprecision(3) << t.index.count() << "s" << std::endl;
+    std::cerr << std::setprecision(0) << "Point reads: " << rate(POINT_READS + CUSTOMER_LOOKUPS, t.reads)
+              << " TPS, point updates: " << rate(POINT_UPDATES, t.updates) << " TPS" << std::endl;
+    std::cerr << std::setprecision(2) << "Analytical queries: " << rate(ANALYTICAL_ROUNDS * 3, t.analytics)
+              << " queries/s" << std::endl;
+    std::cerr << "SQLite " << sqlite3_libversion() << std::endl;
+    std::cerr << "Checksum: " << read_checksum << " (analytics: " << analytics_checksum << ")" << std::endl;
+
+    return 0;
+}
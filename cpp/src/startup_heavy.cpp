@@ -0,0 +1,46 @@
+// Spawned by startup_time: reports when main was reached, then does what a small async HTTP tool
+// does before its first request: a Boost.Asio io_context run by worker threads, a resolver, one
+// Boost.Beast request built and handed to a worker. Nothing is sent. Both libraries are header-only
+#include <boost/asio.hpp>
+#include <boost/beast/http.hpp>
+#include <cstdio>
+#include <ctime>
+#include <future>
+#include <thread>
+#include <vector>
+
+namespace asio = boost::asio;
+namespace http = boost::beast::http;
+
+const int WORKERS = 2;
+const char* HOST = "127.0.0.1:8080";
+const char* TARGET = "/health";
+
+int main() {
+    timespec now;
+    clock_gettime(CLOCK_MONOTONIC, &now);
+    std::printf("%llu\n", static_cast<unsigned long long>(now.tv_sec) * 1'000'000'000ULL + now.tv_nsec);
+    std::fflush(stdout);
+
+    asio::io_context io;
+    auto work = asio::make_work_guard(io);
+    std::vector<std::thread> workers;
+    for (int i = 0; i < WORKERS; i++) workers.emplace_back([&io] { io.run(); });
+    asio::ip::tcp::resolver resolver(io);
+
+    http::request<http::string_body> request{http::verb::get, TARGET, 11};
+    request.set(http::field::host, HOST);
+    request.set(http::field::user_agent, "startup_heavy");
+    request.set(http::field::accept, "application/json");
+    request.prepare_payload();
+
+    // The request's target, echoed from a worker so the parent can check the setup really ran
+    std::promise<size_t> target;
+    asio::post(io, [&] { target.set_value(request.target().size()); });
+    size_t length = target.get_future().get();
+    work.reset();
+    io.stop();
+    for (auto& worker : workers) worker.join();
+    std::printf("%zu\n", length);
+    return 0;
+}
@@ -0,0 +1,177 @@
+#include <iostream>
+#include <string>
+#include <vector>
+#include <algorithm>
+#include <numeric>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <ctime>
+#include <spawn.h>
+#include <sys/stat.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+extern char** environ;
+
+const size_t RUNS = 500; // per helper
+const size_t WARMUP_RUNS = 20;
+
+// The helpers sit next to this binary. The trivial one links only the C++ runtime; the heavy one
+// includes Boost.Asio and Boost.Beast, and after reporting main it prints the request target's length
+// from an io_context worker. It is only built where CMake found Boost
+struct Helper {
+    const char* name;
+    const char* expect;
+};
+const Helper HELPERS[] = {{"startup_trivial", nullptr}, {"startup_heavy", "7"}};
+
+// CLOCK_MONOTONIC is shared by every process, so a child's reading can be compared with ours
+uint64_t monotonic_ns() {
+    timespec now;
+    clock_gettime(CLOCK_MONOTONIC, &now);
+    return static_cast<uint64_t>(now.tv_sec) * 1'000'000'000ULL + now.tv_nsec;
+}
+
+struct Spawned {
+    uint64_t start;
+    std::string output;
+    uint64_t exited;
+    bool status_ok;
+};
+
+// posix_spawn a child with its stdout on a pipe, read everything it prints and reap it
+Spawned spawn_once(const std::string& path) {
+    int fds[2];
+    if (pipe(fds) != 0) {
+        perror("pipe");
+        std::exit(1);
+    }
+    int read_end = fds[0], write_end = fds[1];
+    char* const argv[] = {const_cast<char*>(path.c_str()), nullptr};
+
+    posix_spawn_file_actions_t actions;
+    posix_spawn_file_actions_init(&actions);
+    posix_spawn_file_actions_adddup2(&actions, write_end, 1);
+    posix_spawn_file_actions_addclose(&actions, read_end);
+    posix_spawn_file_actions_addclose(&actions, write_end);
+    pid_t pid = 0;
+    Spawned spawned;
+    spawned.start = monotonic_ns();
+    int rc = posix_spawn(&pid, path.c_str(), &actions, nullptr, argv, environ);
+    posix_spawn_file_actions_destroy(&actions);
+    if (rc != 0) {
+        std::cerr << "posix_spawn of " << path << " failed" << std::endl;
+        std::exit(1);
+    }
+    close(write_end);
+
+    char buf[256];
+    ssize_t n;
+    while ((n = read(read_end, buf, sizeof(buf))) > 0) spawned.output.append(buf, n);
+    close(read_end);
+    int status = 0;
+    pid_t reaped = waitpid(pid, &status, 0);
+    spawned.exited = monotonic_ns();
+    spawned.status_ok = reaped == pid && WIFEXITED(status) && WEXITSTATUS(status) == 0;
+    return spawned;
+}
+
+struct Sample {
+    uint64_t to_main; // spawn call to the first line of main, ns
+    uint64_t to_exit; // spawn call to the child reaped, ns
+};
+
+// The child's report: its main timestamp, then the line it was expected to print if any
+bool sample(const Spawned& spawned, const char* expect, Sample& out) {
+    size_t newline = spawned.output.find('\n');
+    if (newline == std::string::npos || newline == 0) return false;
+    std::string first = spawned.output.substr(0, newline);
+    if (first.find_first_not_of("0123456789") != std::string::npos) return false;
+    uint64_t entered = std::strtoull(first.c_str(), nullptr, 10);
+    std::string rest = spawned.output.substr(newline + 1);
+    std::string expected = expect ? std::string(expect) + "\n" : "";
+    if (!spawned.status_ok || rest != expected || entered < spawned.start) return false;
+    out = {entered - spawned.start, spawned.exited - spawned.start};
+    return true;
+}
+
+struct Summary {
+    double median, mean, min; // us
+};
+
+Summary summarize(std::vector<uint64_t> values) {
+    std::sort(values.begin(), values.end());
+    double mean = std::accumulate(values.begin(), values.end(), 0.0) / values.size() / 1e3;
+    return {values[values.size() / 2] / 1e3, mean, values[0] / 1e3};
+}
+
+std::vector<Sample> run(const std::string& path, const char* expect, size_t runs) {
+    std::vector<Sample> samples;
+    for (size_t i = 0; i < runs; i++) {
+        Spawned spawned = spawn_once(path);
+        Sample s;
+        if (!sample(spawned, expect, s)) {
+            std::cerr << path << " did not report cleanly: \"" << spawned.output << "\"" << std::endl;
+            std::exit(1);
+        }
+        samples.push_back(s);
+    }
+    return samples;
+}
+
+struct Result {
+    const char* name;
+    off_t size;
+    std::vector<Sample> samples;
+};
+
+int main(int argc, char** argv) {
+    (void)argc;
+    std::string self = argv[0];
+    std::string dir = self.find('/') == std::string::npos ? "./" : self.substr(0, self.rfind('/') + 1);
+    std::vector<Result> results;
+    for (const Helper& helper : HELPERS) {
+        std::string path = dir + helper.name;
+        struct stat info;
+        if (stat(path.c_str(), &info) != 0) {
+            if (helper.expect) {
+                std::cerr << "(" << helper.name << " not built: Boost was not found)" << std::endl;
+                continue;
+            }
+            std::cerr << path << " not found next to startup_time; build every binary" << std::endl;
+            return 1;
+        }
+
+        // Warm-up: the first spawns fault the binary into the page cache
+        run(path, helper.expect, WARMUP_RUNS);
+        results.push_back({helper.name, info.st_size, run(path, helper.expect, RUNS)});
+    }
+
+    // The headline is the trivial helper, the floor every program in the language pays
+    uint64_t total_ns = 0;
+    for (const Sample& s : results[0].samples) total_ns += s.to_exit;
+    std::cout << std::fixed << std::setprecision(6) << total_ns / 1e9 << std::endl;
+    std::cerr << RUNS << " spawns per helper, us as median / mean / min" << std::endl;
+    std::vector<std::pair<double, double>> medians;
+    for (const Result& r : results) {
+        std::vector<uint64_t> to_main, to_exit;
+        for (const Sample& s : r.samples) {
+            to_main.push_back(s.to_main);
+            to_exit.push_back(s.to_exit);
+        }
+        Summary m = summarize(to_main), e = summarize(to_exit);
+        char line[160];
+        std::snprintf(line, sizeof(line), "  %-16s %6.2f MB   to main %7.1f / %7.1f / %7.1f   to exit %7.1f / %7.1f / %7.1f",
+                      r.name, r.size / 1e6, m.median, m.mean, m.min, e.median, e.mean, e.min);
+        std::cerr << line << std::endl;
+        medians.push_back({m.median, e.median});
+    }
+    if (medians.size() == 2) {
+        std::cerr << std::setprecision(1) << "Heavy over trivial: +" << medians[1].first - medians[0].first
+                  << " us to main, +" << medians[1].second - medians[0].second << " us to exit (medians)" << std::endl;
+    }
+    std::cerr << "Checksum: " << results[0].samples.size() << std::endl;
+    return 0;
+}
@@ -0,0 +1,10 @@
+// Spawned by startup_time: reports when main was reached and exits, linking nothing but the C++ runtime
+#include <cstdio>
+#include <ctime>
+
+int main() {
+    timespec now;
+    clock_gettime(CLOCK_MONOTONIC, &now);
+    std::printf("%llu\n", static_cast<unsigned long long>(now.tv_sec) * 1'000'000'000ULL + now.tv_nsec);
+    return 0;
+}
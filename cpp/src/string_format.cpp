@@ -0,0 +1,196 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <string_view>
+#include <format>
+#include <charconv>
+#include <iterator>
+#include <cstdio>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+const size_t NUM_RECORDS = 5'000'000;
+const std::string_view NAMES[8] = {"alice", "bob", "carol", "dave", "erin", "frank", "grace", "heidi"};
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+struct Record {
+    uint64_t id;
+    std::string_view name;
+    double score;
+    uint32_t count;
+    bool active;
+};
+
+// Scores are odd multiples of 0.001, so the shortest round-trip form always has exactly
+// three decimals and every formatting strategy produces byte-identical strings
+std::vector<Record> generate_records() {
+    uint64_t seed = 1618;
+    std::vector<Record> records;
+    records.reserve(NUM_RECORDS);
+    for (size_t i = 0; i < NUM_RECORDS; i++) {
+        uint64_t id = next_random(seed) * 1000;
+        id += next_random(seed) % 1000;
+        Record r{id, {}, 0.0, 0, false};
+        r.name = NAMES[next_random(seed) % 8];
+        r.score = static_cast<double>((next_random(seed) % 1'000'000) | 1) / 1000.0;
+        r.count = static_cast<uint32_t>(next_random(seed) % 100'000);
+        r.active = next_random(seed) % 2 == 0;
+        records.push_back(r);
+    }
+    return records;
+}
+
+inline uint64_t fingerprint(std::string_view s) {
+    return s.size() + static_cast<uint8_t>(s[s.size() / 2]);
+}
+
+// std::format allocates a fresh string per record
+uint64_t run_format(const std::vector<Record>& records, size_t count) {
+    uint64_t checksum = 0;
+    for (size_t i = 0; i < count; i++) {
+        const Record& r = records[i];
+        std::string s = std::format("id={} name={} score={} count={} active={}", r.id, r.name, r.score, r.count, r.active);
+        checksum += fingerprint(s);
+    }
+    return checksum;
+}
+
+inline void append_chars(std::string& s, double value) {
+    char buf[32];
+    auto [end, ec] = std::to_chars(buf, buf + sizeof(buf), value);
+    s.append(buf, end);
+}
+
+// Piecewise operator+= with std::to_string for integers (to_chars for the double,
+// since std::to_string(double) is fixed six-decimal)
+uint64_t run_append(const std::vector<Record>& records, size_t count) {
+    uint64_t checksum = 0;
+    for (size_t i = 0; i < count; i++) {
+        const Record& r = records[i];
+        std::string s;
+        s += "id=";
+        s += std::to_string(r.id);
+        s += " name=";
+        s += r.name;
+        s += " score=";
+        append_chars(s, r.score);
+        s += " count=";
+        s += std::to_string(r.count);
+        s += " active=";
+        s += r.active ? "true" : "false";
+        checksum += fingerprint(s);
+    }
+    return checksum;
+}
+
+// std::format_to into one preallocated buffer that is cleared between records
+uint64_t run_format_to(const std::vector<Record>& records, size_t count) {
+    std::string buf;
+    buf.reserve(128);
+    uint64_t checksum = 0;
+    for (size_t i = 0; i < count; i++) {
+        const Record& r = records[i];
+        buf.clear();
+        std::format_to(std::back_inserter(buf), "id={} name={} score={} count={} active={}",
+                       r.id, r.name, r.score, r.count, r.active);
+        checksum += fingerprint(buf);
+    }
+    return checksum;
+}
+
+// std::to_chars straight into a fixed char buffer
+uint64_t run_to_chars(const std::vector<Record>& records, size_t count) {
+    char buf[128];
+    uint64_t checksum = 0;
+    auto put = [](char* p, std::string_view text) {
+        for (char c : text) *p++ = c;
+        return p;
+    };
+    for (size_t i = 0; i < count; i++) {
+        const Record& r = records[i];
+        char* p = put(buf, "id=");
+        p = std::to_chars(p, buf + sizeof(buf), r.id).ptr;
+        p = put(p, " name=");
+        p = put(p, r.name);
+        p = put(p, " score=");
+        p = std::to_chars(p, buf + sizeof(buf), r.score).ptr;
+        p = put(p, " count=");
+        p = std::to_chars(p, buf + sizeof(buf), r.count).ptr;
+        p = put(p, " active=");
+        p = put(p, r.active ? "true" : "false");
+        checksum += fingerprint(std::string_view(buf, p - buf));
+    }
+    return checksum;
+}
+
+// snprintf into a fixed char buffer; %.3f matches the shortest form for these scores
+uint64_t run_snprintf(const std::vector<Record>& records, size_t count) {
+    char buf[128];
+    uint64_t checksum = 0;
+    for (size_t i = 0; i < count; i++) {
+        const Record& r = records[i];
+        int len = std::snprintf(buf, sizeof(buf), "id=%llu name=%.*s score=%.3f count=%u active=%s",
+                                static_cast<unsigned long long>(r.id), static_cast<int>(r.name.size()),
+                                r.name.data(), r.score, r.count, r.active ? "true" : "false");
+        checksum += fingerprint(std::string_view(buf, len));
+    }
+    return checksum;
+}
+
+int main() {
+    auto records = generate_records();
+    size_t warmup_count = NUM_RECORDS / 100;
+
+    // Warm-up
+    volatile uint64_t warmup = run_format(records, warmup_count) + run_append(records, warmup_count)
+                             + run_format_to(records, warmup_count) + run_to_chars(records, warmup_count)
+                             + run_snprintf(records, warmup_count);
+    (void)warmup;
+
+    // Benchmark each strategy
+    auto start = std::chrono::high_resolution_clock::now();
+    uint64_t format_checksum = run_format(records, NUM_RECORDS);
+    auto format_end = std::chrono::high_resolution_clock::now();
+
+    auto append_start = std::chrono::high_resolution_clock::now();
+    uint64_t append_checksum = run_append(records, NUM_RECORDS);
+    auto append_end = std::chrono::high_resolution_clock::now();
+
+    auto format_to_start = std::chrono::high_resolution_clock::now();
+    uint64_t format_to_checksum = run_format_to(records, NUM_RECORDS);
+    auto format_to_end = std::chrono::high_resolution_clock::now();
+
+    auto to_chars_start = std::chrono::high_resolution_clock::now();
+    uint64_t to_chars_checksum = run_to_chars(records, NUM_RECORDS);
+    auto to_chars_end = std::chrono::high_resolution_clock::now();
+
+    // Reported separately: Rust has no printf-style counterpart
+    auto snprintf_start = std::chrono::high_resolution_clock::now();
+    uint64_t snprintf_checksum = run_snprintf(records, NUM_RECORDS);
+    auto snprintf_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> format_duration = format_end - start;
+    std::chrono::duration<double> append_duration = append_end - append_start;
+    std::chrono::duration<double> format_to_duration = format_to_end - format_to_start;
+    std::chrono::duration<double> to_chars_duration = to_chars_end - to_chars_start;
+    std::chrono::duration<double> snprintf_duration = snprintf_end - snprintf_start;
+    std::chrono::duration<double> total_duration = format_duration + append_duration
+                                                 + format_to_duration + to_chars_duration;
+    auto rate = [](std::chrono::duration<double> d) { return NUM_RECORDS / d.count(); };
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << std::fixed << std::setprecision(0) << "std::format: " << rate(format_duration)
+              << " strings/s, append: " << rate(append_duration) << " strings/s, format_to: "
+              << rate(format_to_duration) << " strings/s, to_chars: " << rate(to_chars_duration)
+              << " strings/s, snprintf: " << rate(snprintf_duration) << " strings/s" << std::endl;
+    std::cerr << "Checksum: " << format_checksum << " (append: " << append_checksum << ", format_to: "
+              << format_to_checksum << ", to_chars: " << to_chars_checksum << ", snprintf: "
+              << snprintf_checksum << ")" << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,385 @@
+#include <iostream>
+#include <memory>
+#include <variant>
+#include <optional>
+#include <utility>
+#include <vector>
+#include <array>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+#include <cmath>
+
+const size_t DEFAULT_TREES = 5'000;
+const uint32_t MAX_DEPTH = 10;
+const size_t ORDER = 3; // d/dx taken three times, simplifying after each
+const double EVAL_AT = 0.3;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// Expressions in one variable x, with integer constants
+struct Expr;
+using Ptr = std::unique_ptr<Expr>;
+
+struct Num { int64_t value; };
+struct X {};
+struct Neg { Ptr a; };
+struct Add { Ptr a, b; };
+struct Sub { Ptr a, b; };
+struct Mul { Ptr a, b; };
+struct Pow { Ptr a; uint32_t n; };
+struct Sin { Ptr a; };
+struct Cos { Ptr a; };
+struct Exp { Ptr a; };
+
+using Node = std::variant<Num, X, Neg, Add, Sub, Mul, Pow, Sin, Cos, Exp>;
+
+struct Expr {
+    Node node;
+};
+
+template <class... Ts>
+struct overloaded : Ts... { using Ts::operator()...; };
+template <class... Ts>
+overloaded(Ts...) -> overloaded<Ts...>;
+
+Ptr make(Node node) {
+    return std::make_unique<Expr>(Expr{std::move(node)});
+}
+
+Ptr num(int64_t v) {
+    return make(Num{v});
+}
+
+Node leaf(uint64_t r) {
+    if ((r >> 8) % 3 == 0) return Num{1 + static_cast<int64_t>((r >> 10) % 9)};
+    return X{};
+}
+
+// A random tree: leaves are x or a small constant, inner nodes mostly arithmetic. Children are
+// built into locals first, so the draws happen in the same order as in Rust
+Ptr build(uint64_t& seed, uint32_t depth) {
+    uint64_t r = next_random(seed);
+    if (depth == 0 || r % 16 <= 3) return make(leaf(r));
+    Ptr a = build(seed, depth - 1);
+    switch (r % 16) {
+        case 4: case 5: case 6: { Ptr b = build(seed, depth - 1); return make(Add{std::move(a), std::move(b)}); }
+        case 7: { Ptr b = build(seed, depth - 1); return make(Sub{std::move(a), std::move(b)}); }
+        case 8: case 9: case 10: { Ptr b = build(seed, depth - 1); return make(Mul{std::move(a), std::move(b)}); }
+        case 11: return make(Neg{std::move(a)});
+        case 12: return make(Pow{std::move(a), 2 + static_cast<uint32_t>((r >> 8) % 3)});
+        case 13: return make(Sin{std::move(a)});
+        case 14: return make(Cos{std::move(a)});
+        default: return make(Exp{std::move(a)});
+    }
+}
+
+Ptr clone(const Expr& e) {
+    return std::visit(overloaded{
+        [](const Num& n) { return num(n.value); },
+        [](const X&) { return make(X{}); },
+        [](const Neg& n) { return make(Neg{clone(*n.a)}); },
+        [](const Add& n) { return make(Add{clone(*n.a), clone(*n.b)}); },
+        [](const Sub& n) { return make(Sub{clone(*n.a), clone(*n.b)}); },
+        [](const Mul& n) { return make(Mul{clone(*n.a), clone(*n.b)}); },
+        [](const Pow& n) { return make(Pow{clone(*n.a), n.n}); },
+        [](const Sin& n) { return make(Sin{clone(*n.a)}); },
+        [](const Cos& n) { return make(Cos{clone(*n.a)}); },
+        [](const Exp& n) { return make(Exp{clone(*n.a)}); },
+    }, e.node);
+}
+
+// d/dx by the textbook rules; every subtree a rule reuses is cloned, as a naive CAS would
+Ptr derive(const Expr& e) {
+    return std::visit(overloaded{
+        [](const Num&) { return num(0); },
+        [](const X&) { return num(1); },
+        [](const Neg& n) { return make(Neg{derive(*n.a)}); },
+        [](const Add& n) { return make(Add{derive(*n.a), derive(*n.b)}); },
+        [](const Sub& n) { return make(Sub{derive(*n.a), derive(*n.b)}); },
+        [](const Mul& n) {
+            return make(Add{make(Mul{derive(*n.a), clone(*n.b)}), make(Mul{clone(*n.a), derive(*n.b)})});
+        },
+        [](const Pow& n) {
+            return make(Mul{make(Mul{num(n.n), make(Pow{clone(*n.a), n.n - 1})}), derive(*n.a)});
+        },
+        [](const Sin& n) { return make(Mul{make(Cos{clone(*n.a)}), derive(*n.a)}); },
+        [](const Cos& n) { return make(Neg{make(Mul{make(Sin{clone(*n.a)}), derive(*n.a)})}); },
+        [](const Exp& n) { return make(Mul{make(Exp{clone(*n.a)}), derive(*n.a)}); },
+    }, e.node);
+}
+
+std::optional<int64_t> constant(const Ptr& e) {
+    if (const Num* n = std::get_if<Num>(&e->node)) return n->value;
+    return std::nullopt;
+}
+
+// Constants fold only while the result is exact as a double, so folding never changes a value
+bool exact(int64_t v) {
+    return (v < 0 ? 0 - static_cast<uint64_t>(v) : static_cast<uint64_t>(v)) <= (uint64_t(1) << 53);
+}
+
+bool checked_pow(int64_t x, uint32_t n, int64_t& out) {
+    out = 1;
+    for (uint32_t i = 0; i < n; i++) {
+        if (__builtin_mul_overflow(out, x, &out)) return true;
+    }
+    return false;
+}
+
+Ptr set(Ptr e, Node node) {
+    e->node = std::move(node);
+    return e;
+}
+
+// -a of an already simplified a, written into e's node when a stays
+Ptr negate(Ptr e, Ptr a) {
+    if (Num* n = std::get_if<Num>(&a->node)) {
+        n->value = -n->value;
+        return a;
+    }
+    if (Neg* n = std::get_if<Neg>(&a->node)) return std::move(n->a);
+    return set(std::move(e), Neg{std::move(a)});
+}
+
+Ptr simplify(Ptr e);
+
+// Bottom-up rewriting: fold constants, drop identities and zeros, cancel double negation and
+// move constant factors to the left. No rule reassociates, so values stay the same to the bit.
+// Takes the tree by value and rewrites nodes in place, so only discarded subtrees are freed
+Ptr simplify(Ptr e) {
+    Node node = std::exchange(e->node, X{});
+    return std::visit(overloaded{
+        [&](Neg& n) { return negate(std::move(e), simplify(std::move(n.a))); },
+        [&](Add& n) {
+            Ptr a = simplify(std::move(n.a));
+            Ptr b = simplify(std::move(n.b));
+            auto x = constant(a), y = constant(b);
+            int64_t v;
+            if (x && y && !__builtin_add_overflow(*x, *y, &v) && exact(v)) return set(std::move(e), Num{v});
+            if (x == 0) return b;
+            if (y == 0) return a;
+            return set(std::move(e), Add{std::move(a), std::move(b)});
+        },
+        [&](Sub& n) {
+            Ptr a = simplify(std::move(n.a));
+            Ptr b = simplify(std::move(n.b));
+            auto x = constant(a), y = constant(b);
+            int64_t v;
+            if (x && y && !__builtin_sub_overflow(*x, *y, &v) && exact(v)) return set(std::move(e), Num{v});
+            if (y == 0) return a;
+            if (x == 0) return negate(std::move(e), std::move(b));
+            return set(std::move(e), Sub{std::move(a), std::move(b)});
+        },
+        [&](Mul& n) {
+            Ptr a = simplify(std::move(n.a));
+            Ptr b = simplify(std::move(n.b));
+            auto x = constant(a), y = constant(b);
+            int64_t v;
+            if (x && y && !__builtin_mul_overflow(*x, *y, &v) && exact(v)) return set(std::move(e), Num{v});
+            if (x == 0 || y == 0) return set(std::move(e), Num{0});
+            if (x == 1) return b;
+            if (y == 1) return a;
+            if (!x && y) return set(std::move(e), Mul{std::move(b), std::move(a)});
+            return set(std::move(e), Mul{std::move(a), std::move(b)});
+        },
+        [&](Pow& n) {
+            Ptr a = simplify(std::move(n.a));
+            auto x = constant(a);
+            int64_t v;
+            if (n.n == 0) return set(std::move(e), Num{1});
+            if (n.n == 1) return a;
+            if (x && !checked_pow(*x, n.n, v) && exact(v)) return set(std::move(e), Num{v});
+            return set(std::move(e), Pow{std::move(a), n.n});
+        },
+        [&](Sin& n) {
+            Ptr a = simplify(std::move(n.a));
+            if (constant(a) == 0) return a;
+            return set(std::move(e), Sin{std::move(a)});
+        },
+        [&](Cos& n) {
+            Ptr a = simplify(std::move(n.a));
+            if (constant(a) == 0) return set(std::move(e), Num{1});
+            return set(std::move(e), Cos{std::move(a)});
+        },
+        [&](Exp& n) {
+            Ptr a = simplify(std::move(n.a));
+            if (constant(a) == 0) return set(std::move(e), Num{1});
+            return set(std::move(e), Exp{std::move(a)});
+        },
+        [&](auto& leaf) { return set(std::move(e), std::move(leaf)); },
+    }, node);
+}
+
+// Repeated multiplication rather than std::pow, so both languages round identically
+double eval(const Expr& e, double x) {
+    return std::visit(overloaded{
+        [](const Num& n) { return static_cast<double>(n.value); },
+        [&](const X&) { return x; },
+        [&](const Neg& n) { return -eval(*n.a, x); },
+        [&](const Add& n) { return eval(*n.a, x) + eval(*n.b, x); },
+        [&](const Sub& n) { return eval(*n.a, x) - eval(*n.b, x); },
+        [&](const Mul& n) { return eval(*n.a, x) * eval(*n.b, x); },
+        [&](const Pow& n) {
+            double base = eval(*n.a, x);
+            double p = 1.0;
+            for (uint32_t i = 0; i < n.n; i++) p *= base;
+            return p;
+        },
+        [&](const Sin& n) { return std::sin(eval(*n.a, x)); },
+        [&](const Cos& n) { return std::cos(eval(*n.a, x)); },
+        [&](const Exp& n) { return std::exp(eval(*n.a, x)); },
+    }, e.node);
+}
+
+size_t count(const Expr& e) {
+    return 1 + std::visit(overloaded{
+        [](const Num&) -> size_t { return 0; },
+        [](const X&) -> size_t { return 0; },
+        [](const auto& n) {
+            if constexpr (requires { n.b; }) {
+                return count(*n.a) + count(*n.b);
+            } else {
+                return count(*n.a);
+            }
+        },
+    }, e.node);
+}
+
+uint64_t mix(uint64_t z) {
+    z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+    z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+    return z ^ (z >> 31);
+}
+
+uint64_t hash_node(uint64_t tag, uint64_t a, uint64_t b) {
+    return mix(tag * 0x9e3779b97f4a7c15ULL ^ a ^ ((b << 17) | (b >> 47)));
+}
+
+// Structural hash, the same function in both languages, so equal trees give equal checksums
+uint64_t hash(const Expr& e) {
+    return std::visit(overloaded{
+        [](const Num& n) { return hash_node(1, static_cast<uint64_t>(n.value), 0); },
+        [](const X&) { return hash_node(2, 0, 0); },
+        [](const Neg& n) { return hash_node(3, hash(*n.a), 0); },
+        [](const Add& n) { return hash_node(4, hash(*n.a), hash(*n.b)); },
+        [](const Sub& n) { return hash_node(5, hash(*n.a), hash(*n.b)); },
+        [](const Mul& n) { return hash_node(6, hash(*n.a), hash(*n.b)); },
+        [](const Pow& n) { return hash_node(7, hash(*n.a), n.n); },
+        [](const Sin& n) { return hash_node(8, hash(*n.a), 0); },
+        [](const Cos& n) { return hash_node(9, hash(*n.a), 0); },
+        [](const Exp& n) { return hash_node(10, hash(*n.a), 0); },
+    }, e.node);
+}
+
+// A raw derivative that overflows can turn finite once its zero terms are dropped, so only
+// finite values are compared
+bool agrees(double raw, double simplified) {
+    return !std::isfinite(raw) || raw == simplified;
+}
+
+struct Run {
+    Duration build{}, derive{}, simplify{}, teardown{};
+    size_t nodes = 0;
+    std::array<size_t, ORDER> raw_nodes{};
+    std::array<size_t, ORDER> simplified_nodes{};
+    uint64_t checksum = 0;
+    size_t mismatches = 0;
+
+    Duration total() const { return build + derive + simplify + teardown; }
+};
+
+Run run(size_t trees) {
+    Run r;
+    uint64_t seed = 42;
+    auto start = Clock::now();
+    std::vector<Ptr> current;
+    for (size_t i = 0; i < trees; i++) current.push_back(build(seed, MAX_DEPTH));
+    r.build = Clock::now() - start;
+    for (const Ptr& e : current) r.nodes += count(*e);
+
+    for (size_t order = 0; order < ORDER; order++) {
+        start = Clock::now();
+        std::vector<Ptr> raw;
+        raw.reserve(current.size());
+        for (const Ptr& e : current) raw.push_back(derive(*e));
+        r.derive += Clock::now() - start;
+        // Not timed: sizes and values of the unsimplified derivatives, to check simplify against
+        std::vector<double> values;
+        for (const Ptr& e : raw) {
+            r.raw_nodes[order] += count(*e);
+            values.push_back(eval(*e, EVAL_AT));
+        }
+
+        start = Clock::now();
+        current.clear();
+        for (Ptr& e : raw) current.push_back(simplify(std::move(e)));
+        raw.clear();
+        r.simplify += Clock::now() - start;
+        for (size_t i = 0; i < current.size(); i++) {
+            r.simplified_nodes[order] += count(*current[i]);
+            if (!agrees(values[i], eval(*current[i], EVAL_AT))) r.mismatches++;
+        }
+    }
+    for (const Ptr& e : current) r.checksum += hash(*e);
+
+    start = Clock::now();
+    current.clear();
+    current.shrink_to_fit();
+    r.teardown = Clock::now() - start;
+    return r;
+}
+
+size_t parse_trees(int argc, char** argv) {
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--trees") == 0) {
+            long long n = i + 1 < argc ? std::atoll(argv[i + 1]) : 0;
+            if (n <= 0) {
+                std::cerr << "--trees expects a positive integer" << std::endl;
+                std::exit(1);
+            }
+            return static_cast<size_t>(n);
+        }
+    }
+    return DEFAULT_TREES;
+}
+
+int main(int argc, char** argv) {
+    size_t trees = parse_trees(argc, argv);
+
+    // Warm-up
+    run(trees / 100 + 1);
+
+    // Benchmark
+    Run r = run(trees);
+    if (r.mismatches > 0) {
+        fprintf(stderr, "%zu simplified derivatives differ from the raw ones at x = %g\n", r.mismatches, EVAL_AT);
+        return 1;
+    }
+
+    size_t processed = 0;
+    for (size_t n : r.raw_nodes) processed += n;
+    std::cout << std::fixed << std::setprecision(6) << r.total().count() << std::endl;
+    fprintf(stderr, "Trees: %zu random expressions in x, depth up to %u, %zu nodes\n", trees, MAX_DEPTH, r.nodes);
+    for (size_t order = 0; order < ORDER; order++) {
+        fprintf(stderr, "d%zu/dx%zu: %zu nodes raw, %zu after simplifying (%.1f%%)\n", order + 1, order + 1,
+                r.raw_nodes[order], r.simplified_nodes[order],
+                100.0 * static_cast<double>(r.simplified_nodes[order]) / static_cast<double>(r.raw_nodes[order]));
+    }
+    fprintf(stderr, "Build %.3fs, differentiate %.3fs (%.1fM nodes/s), simplify %.3fs (%.1fM nodes/s), teardown %.3fs\n",
+            r.build.count(), r.derive.count(), processed / 1e6 / r.derive.count(), r.simplify.count(),
+            processed / 1e6 / r.simplify.count(), r.teardown.count());
+    fprintf(stderr, "Every simplified derivative matches its raw form at x = %g\n", EVAL_AT);
+    fprintf(stderr, "Checksum: %llu\n", static_cast<unsigned long long>(r.checksum));
+
+    return 0;
+}
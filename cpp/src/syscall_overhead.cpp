@@ -0,0 +1,219 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <ctime>
+#include <fcntl.h>
+#include <unistd.h>
+
+const size_t CLOCK_READS = 20'000'000;
+const size_t SYSCALLS = 2'000'000;
+const size_t ROUND_TRIPS = 200'000;
+const size_t READ_SIZE = 64;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+template <typename T>
+void do_not_optimize(const T& value) {
+    asm volatile("" : : "r"(&value) : "memory");
+}
+
+struct Measured {
+    Duration elapsed;
+    uint64_t checksum = 0;
+};
+
+// Setup stays outside the timing; each call of `op` is one operation and returns what it adds to
+// the checksum
+template <typename F>
+Measured measure(size_t iters, F op) {
+    Measured m;
+    auto start = Clock::now();
+    for (uint64_t i = 0; i < iters; i++) m.checksum += op(i);
+    m.elapsed = Clock::now() - start;
+    return m;
+}
+
+timespec monotonic() {
+    timespec ts{};
+    clock_gettime(CLOCK_MONOTONIC, &ts);
+    return ts;
+}
+
+Measured steady_clock_now(size_t iters) {
+    return measure(iters, [](uint64_t) {
+        auto t = std::chrono::steady_clock::now();
+        do_not_optimize(t);
+        return uint64_t{1};
+    });
+}
+
+Measured system_clock_now(size_t iters) {
+    return measure(iters, [](uint64_t) {
+        auto t = std::chrono::system_clock::now();
+        do_not_optimize(t);
+        return uint64_t{1};
+    });
+}
+
+Measured raw_clock_gettime(size_t iters) {
+    return measure(iters, [](uint64_t) {
+        timespec ts = monotonic();
+        do_not_optimize(ts);
+        return uint64_t{1};
+    });
+}
+
+// getppid rather than getpid, which libc may cache: it always enters the kernel
+Measured raw_getppid(size_t iters) {
+    pid_t parent = getppid();
+    return measure(iters, [parent](uint64_t) { return uint64_t{getppid() == parent}; });
+}
+
+Measured raw_write(size_t iters) {
+    int fd = open("/dev/null", O_WRONLY);
+    Measured m = measure(iters, [fd](uint64_t i) {
+        uint8_t byte = static_cast<uint8_t>(i);
+        return static_cast<uint64_t>(write(fd, &byte, 1));
+    });
+    close(fd);
+    return m;
+}
+
+// Unbuffered, so every call still makes its syscall, through stdio's locking and bookkeeping
+Measured stdio_write(size_t iters) {
+    FILE* null = fopen("/dev/null", "wb");
+    setvbuf(null, nullptr, _IONBF, 0);
+    Measured m = measure(iters, [null](uint64_t i) {
+        uint8_t byte = static_cast<uint8_t>(i);
+        return static_cast<uint64_t>(fwrite(&byte, 1, 1, null));
+    });
+    fclose(null);
+    return m;
+}
+
+uint64_t byte_sum(const uint8_t* buf) {
+    uint64_t sum = 0;
+    for (size_t i = 0; i < READ_SIZE; i++) sum += buf[i];
+    return sum;
+}
+
+Measured raw_read(size_t iters) {
+    int fd = open("/dev/zero", O_RDONLY);
+    uint8_t buf[READ_SIZE];
+    std::fill(buf, buf + READ_SIZE, 1);
+    Measured m = measure(iters, [fd, &buf](uint64_t) {
+        ssize_t n = read(fd, buf, READ_SIZE);
+        return static_cast<uint64_t>(n) + byte_sum(buf);
+    });
+    close(fd);
+    return m;
+}
+
+Measured stdio_read(size_t iters) {
+    FILE* zero = fopen("/dev/zero", "rb");
+    setvbuf(zero, nullptr, _IONBF, 0);
+    uint8_t buf[READ_SIZE];
+    std::fill(buf, buf + READ_SIZE, 1);
+    Measured m = measure(iters, [zero, &buf](uint64_t) {
+        size_t n = fread(buf, 1, READ_SIZE, zero);
+        return static_cast<uint64_t>(n) + byte_sum(buf);
+    });
+    fclose(zero);
+    return m;
+}
+
+// A byte to an echo thread through one pipe and back, plus one, through another: four syscalls
+// and, with the echo thread blocked in read, two wake-ups per round trip
+Measured raw_pipe(size_t iters) {
+    int ping[2], pong[2];
+    if (pipe(ping) != 0 || pipe(pong) != 0) {
+        perror("pipe");
+        std::exit(1);
+    }
+    std::thread echo([&] {
+        uint8_t byte;
+        for (size_t i = 0; i < iters; i++) {
+            if (read(ping[0], &byte, 1) != 1) return;
+            byte++;
+            if (write(pong[1], &byte, 1) != 1) return;
+        }
+    });
+    Measured m = measure(iters, [&](uint64_t i) {
+        uint8_t byte = static_cast<uint8_t>(i % 251);
+        if (write(ping[1], &byte, 1) != 1 || read(pong[0], &byte, 1) != 1) return uint64_t{0};
+        return uint64_t{byte};
+    });
+    echo.join();
+    for (int fd : {ping[0], ping[1], pong[0], pong[1]}) close(fd);
+    return m;
+}
+
+struct Op {
+    const char* name;
+    Measured (*run)(size_t);
+};
+
+// Within a group every op does the same work, the library wrapper first and the raw call after it
+struct Group {
+    const char* name;
+    size_t iters;
+    std::vector<Op> ops;
+};
+
+const Group GROUPS[] = {
+    {"clock read", CLOCK_READS,
+     {{"steady_clock::now", steady_clock_now}, {"system_clock::now", system_clock_now}, {"clock_gettime", raw_clock_gettime}}},
+    {"getppid", SYSCALLS, {{"getppid", raw_getppid}}},
+    {"write 1 B to /dev/null", SYSCALLS, {{"fwrite", stdio_write}, {"write", raw_write}}},
+    {"read 64 B from /dev/zero", SYSCALLS, {{"fread", stdio_read}, {"read", raw_read}}},
+    {"pipe round trip", ROUND_TRIPS, {{"pipe", raw_pipe}}},
+};
+
+int main() {
+    // Warm-up
+    for (const Group& g : GROUPS) {
+        for (const Op& op : g.ops) op.run(g.iters / 100);
+    }
+
+    // Benchmark: runs[group][op]
+    std::vector<std::vector<Measured>> runs;
+    Duration total_duration(0);
+    for (const Group& g : GROUPS) {
+        auto& by_op = runs.emplace_back();
+        for (const Op& op : g.ops) {
+            by_op.push_back(op.run(g.iters));
+            total_duration += by_op.back().elapsed;
+        }
+    }
+    bool all_match = true;
+    uint64_t checksum = 0;
+    for (const auto& by_op : runs) {
+        for (const Measured& r : by_op) all_match &= r.checksum == by_op[0].checksum;
+        checksum += by_op[0].checksum;
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "ns per operation, library wrapper vs the raw call\n");
+    for (size_t g = 0; g < runs.size(); g++) {
+        const Group& group = GROUPS[g];
+        fprintf(stderr, "%s (%zu times):\n", group.name, group.iters);
+        for (size_t o = 0; o < group.ops.size(); o++) {
+            fprintf(stderr, "  %-20s %8.1f\n", group.ops[o].name, runs[g][o].elapsed.count() * 1e9 / group.iters);
+        }
+    }
+    // The clock itself: the smallest step two back-to-back reads can see
+    timespec first = monotonic(), next = monotonic();
+    while (next.tv_sec == first.tv_sec && next.tv_nsec == first.tv_nsec) next = monotonic();
+    fprintf(stderr, "CLOCK_MONOTONIC step: %lldns\n",
+            static_cast<long long>((next.tv_sec - first.tv_sec) * 1'000'000'000LL + next.tv_nsec - first.tv_nsec));
+    std::cerr << "Checksum: " << checksum << " (all variants match: " << (all_match ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
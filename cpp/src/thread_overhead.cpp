@@ -0,0 +1,217 @@
+#include <iostream>
+#include <vector>
+#include <thread>
+#include <atomic>
+#include <mutex>
+#include <condition_variable>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <pthread.h>
+
+const size_t SPAWNS = 100'000;
+const size_t ROUND_TRIPS = 1'000'000;
+const size_t SMALL_STACK = 64 * 1024;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+// The whole of a spawned thread's job, so spawn and join are all there is to time
+uint64_t work(uint64_t i) {
+    return (i * 0x9E3779B97F4A7C15ULL) >> 7;
+}
+
+struct Spawns {
+    Duration elapsed;
+    uint64_t checksum = 0;
+};
+
+// One thread at a time: spawn it, then join it before the next
+template <typename F>
+Spawns spawn_each(size_t spawns, F spawn) {
+    Spawns s;
+    auto start = Clock::now();
+    for (uint64_t i = 0; i < spawns; i++) s.checksum += spawn(i);
+    s.elapsed = Clock::now() - start;
+    return s;
+}
+
+Spawns spawn_thread(size_t spawns) {
+    return spawn_each(spawns, [](uint64_t i) {
+        uint64_t result;
+        std::thread t([&result, i] { result = work(i); });
+        t.join();
+        return result;
+    });
+}
+
+// Joins on destruction
+Spawns spawn_jthread(size_t spawns) {
+    return spawn_each(spawns, [](uint64_t i) {
+        uint64_t result;
+        { std::jthread t([&result, i] { result = work(i); }); }
+        return result;
+    });
+}
+
+struct Job {
+    uint64_t i, result;
+};
+
+void* run_job(void* arg) {
+    Job* job = static_cast<Job*>(arg);
+    job->result = work(job->i);
+    return nullptr;
+}
+
+Spawns spawn_small_stack(size_t spawns) {
+    pthread_attr_t attr;
+    pthread_attr_init(&attr);
+    pthread_attr_setstacksize(&attr, SMALL_STACK);
+    Spawns s = spawn_each(spawns, [&attr](uint64_t i) {
+        Job job{i, 0};
+        pthread_t t;
+        pthread_create(&t, &attr, run_job, &job);
+        pthread_join(t, nullptr);
+        return job.result;
+    });
+    pthread_attr_destroy(&attr);
+    return s;
+}
+
+struct NamedSpawner {
+    const char* name;
+    Spawns (*run)(size_t);
+};
+
+const NamedSpawner SPAWNERS[] = {
+    {"std::thread", spawn_thread},
+    {"std::jthread", spawn_jthread},
+    {"64 KiB stack", spawn_small_stack},
+};
+
+// Each baton is a turn counter two threads hand back and forth with pass and wait_for: odd turns
+// are the echo thread's, even turns the main thread's. Waiting blocks in the kernel rather than
+// spinning
+
+// A mutex-guarded counter and one condition variable both threads wait on
+struct CondvarBaton {
+    std::mutex mutex;
+    std::condition_variable changed;
+    uint32_t turn = 0;
+
+    void pass(uint32_t t) {
+        { std::lock_guard guard(mutex); turn = t; }
+        changed.notify_one();
+    }
+    void wait_for(uint32_t t) {
+        std::unique_lock guard(mutex);
+        changed.wait(guard, [&] { return turn == t; });
+    }
+};
+
+// An atomic counter waited on directly: a futex on Linux, a ulock on macOS
+struct AtomicWaitBaton {
+    std::atomic<uint32_t> turn{0};
+
+    void pass(uint32_t t) {
+        turn.store(t, std::memory_order_release);
+        turn.notify_one();
+    }
+    void wait_for(uint32_t t) {
+        uint32_t seen;
+        while ((seen = turn.load(std::memory_order_acquire)) != t) turn.wait(seen, std::memory_order_acquire);
+    }
+};
+
+struct PingPong {
+    Duration elapsed;
+    std::vector<uint32_t> round_trips_ns; // sorted
+    uint32_t turns = 0; // taken by either thread: twice the round trips
+};
+
+// Every pass wakes a blocked thread, so each round trip is two context switches plus the two
+// clock reads around it
+template <typename Baton>
+PingPong ping_pong(size_t round_trips) {
+    Baton baton;
+    uint32_t rounds = static_cast<uint32_t>(round_trips);
+    uint32_t echoed = 0;
+    PingPong p;
+    p.round_trips_ns.reserve(round_trips);
+    auto start = Clock::now();
+    std::thread echo([&] {
+        for (uint32_t i = 0; i < rounds; i++) {
+            baton.wait_for(2 * i + 1);
+            baton.pass(2 * i + 2);
+            echoed++;
+        }
+    });
+    for (uint32_t i = 0; i < rounds; i++) {
+        auto sent = Clock::now();
+        baton.pass(2 * i + 1);
+        baton.wait_for(2 * i + 2);
+        p.round_trips_ns.push_back(
+            static_cast<uint32_t>(std::chrono::duration_cast<std::chrono::nanoseconds>(Clock::now() - sent).count()));
+    }
+    echo.join();
+    p.elapsed = Clock::now() - start;
+    p.turns = echoed + rounds;
+    std::sort(p.round_trips_ns.begin(), p.round_trips_ns.end());
+    return p;
+}
+
+struct NamedSwitcher {
+    const char* name;
+    PingPong (*run)(size_t);
+};
+
+const NamedSwitcher SWITCHERS[] = {
+    {"condition_variable", ping_pong<CondvarBaton>},
+    {"atomic::wait", ping_pong<AtomicWaitBaton>},
+};
+
+double switch_ns(const std::vector<uint32_t>& sorted_round_trips, size_t per_mille) {
+    return sorted_round_trips[(sorted_round_trips.size() - 1) * per_mille / 1000] / 2.0;
+}
+
+int main() {
+    // Warm-up
+    for (const auto& s : SPAWNERS) s.run(SPAWNS / 100);
+    for (const auto& s : SWITCHERS) s.run(ROUND_TRIPS / 100);
+
+    // Benchmark
+    std::vector<Spawns> spawns;
+    std::vector<PingPong> switches;
+    Duration total_duration(0);
+    for (const auto& s : SPAWNERS) {
+        spawns.push_back(s.run(SPAWNS));
+        total_duration += spawns.back().elapsed;
+    }
+    for (const auto& s : SWITCHERS) {
+        switches.push_back(s.run(ROUND_TRIPS));
+        total_duration += switches.back().elapsed;
+    }
+    bool all_match = true;
+    for (const Spawns& r : spawns) all_match &= r.checksum == spawns[0].checksum;
+    for (const PingPong& r : switches) all_match &= r.turns == switches[0].turns;
+    uint64_t checksum = spawns[0].checksum + switches[0].turns;
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "%zu threads spawned and joined one at a time; %zu round trips between two blocking threads\n", SPAWNS,
+            ROUND_TRIPS);
+    for (size_t i = 0; i < spawns.size(); i++) {
+        double seconds = spawns[i].elapsed.count();
+        fprintf(stderr, "  %-18s %.3fs (%.1f us per spawn + join)\n", SPAWNERS[i].name, seconds, seconds * 1e6 / SPAWNS);
+    }
+    for (size_t i = 0; i < switches.size(); i++) {
+        const auto& trips = switches[i].round_trips_ns;
+        fprintf(stderr, "  %-18s %.3fs (switch p50 %.0fns, p99 %.0fns, p99.9 %.0fns)\n", SWITCHERS[i].name,
+                switches[i].elapsed.count(), switch_ns(trips, 500), switch_ns(trips, 990), switch_ns(trips, 999));
+    }
+    std::cerr << "Checksum: " << checksum << " (all variants match: " << (all_match ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
@@ -8,15 +8,31 @@
 #include <chrono>
 #include <iomanip>
 #include <atomic>
+#include <algorithm>
+#include <cstring>
+#include <coroutine>
+#include <latch>
 
 const size_t NUM_TASKS = 100'000;
 const size_t NUM_WORKERS = 8;
+const size_t AWAIT_POINTS = 2; // yields per coroutine task after its work, each a trip back through the queue
+// --sweep: iterations of spin() per task, roughly 100 ns to 1 ms each, and the total iterations
+// per granularity; task counts are clamped so fine grains stay bounded and coarse ones still
+// give every worker several tasks
+const uint64_t SWEEP_ITERATIONS[] = {50, 500, 5'000, 50'000, 500'000};
+const uint64_t SWEEP_TOTAL_ITERATIONS = 100'000'000;
+const size_t SWEEP_MIN_TASKS = 200;
+const size_t SWEEP_MAX_TASKS = 200'000;
+
+// Index of the worker running on this thread, for the per-worker slots
+thread_local size_t worker_index = 0;
 
 class ThreadPool {
 public:
     ThreadPool(size_t num_threads) : stop(false) {
         for (size_t i = 0; i < num_threads; i++) {
-            workers.emplace_back([this] {
+            workers.emplace_back([this, i] {
+                worker_index = i;
                 while (true) {
                     std::function<void()> task;
                     {
@@ -64,6 +80,79 @@ private:
     bool stop;
 };
 
+// The same queue and lock as ThreadPool, holding suspended coroutines instead of std::function
+class CoroutinePool {
+public:
+    CoroutinePool(size_t num_threads) : stop(false) {
+        for (size_t i = 0; i < num_threads; i++) {
+            workers.emplace_back([this, i] {
+                worker_index = i;
+                while (true) {
+                    std::coroutine_handle<> handle;
+                    {
+                        std::unique_lock<std::mutex> lock(queue_mutex);
+                        condition.wait(lock, [this] { return stop || !ready.empty(); });
+                        
+                        if (stop && ready.empty()) {
+                            return;
+                        }
+                        
+                        handle = ready.front();
+                        ready.pop();
+                    }
+                    handle.resume();
+                }
+            });
+        }
+    }
+    
+    // co_await pool.schedule() suspends the caller and queues it to be resumed on a worker
+    auto schedule() {
+        struct Awaiter {
+            CoroutinePool& pool;
+            bool await_ready() const noexcept { return false; }
+            void await_suspend(std::coroutine_handle<> handle) {
+                {
+                    std::unique_lock<std::mutex> lock(pool.queue_mutex);
+                    pool.ready.push(handle);
+                }
+                pool.condition.notify_one();
+            }
+            void await_resume() const noexcept {}
+        };
+        return Awaiter{*this};
+    }
+    
+    ~CoroutinePool() {
+        {
+            std::unique_lock<std::mutex> lock(queue_mutex);
+            stop = true;
+        }
+        condition.notify_all();
+        for (std::thread& worker : workers) {
+            worker.join();
+        }
+    }
+    
+private:
+    std::vector<std::thread> workers;
+    std::queue<std::coroutine_handle<>> ready;
+    std::mutex queue_mutex;
+    std::condition_variable condition;
+    bool stop;
+};
+
+// Fire-and-forget coroutine: runs eagerly until its first co_await, frees its frame when it finishes
+struct Detached {
+    struct promise_type {
+        Detached get_return_object() { return {}; }
+        std::suspend_never initial_suspend() noexcept { return {}; }
+        std::suspend_never final_suspend() noexcept { return {}; }
+        void return_void() {}
+        void unhandled_exception() { std::terminate(); }
+    };
+};
+
 uint64_t heavy_computation(size_t n) {
     uint64_t result = 0;
     for (size_t i = 0; i < 1000; i++) {
@@ -72,40 +161,252 @@ uint64_t heavy_computation(size_t n) {
     return result;
 }
 
-int main() {
-    std::atomic<uint64_t> counter(0);
+// Work that scales with `iterations`: a chain of dependent multiply-adds the compiler can neither
+// vectorise nor fold into a closed form
+uint64_t spin(size_t n, uint64_t iterations) {
+    uint64_t x = n;
+    for (uint64_t i = 0; i < iterations; i++) {
+        x = (x ^ (x >> 31)) * 6364136223846793005ULL + i;
+    }
+    return x;
+}
+
+enum class Accumulator {
+    Mutex,     // one uint64_t behind a std::mutex, locked by every task
+    Atomic,    // one std::atomic<uint64_t>, fetch_add by every task
+    PerWorker, // one cache-line slot per worker, each written only by its own worker, summed at the end
+};
+
+const Accumulator ACCUMULATORS[] = {Accumulator::Mutex, Accumulator::Atomic, Accumulator::PerWorker};
+
+const char* accumulator_name(Accumulator accumulator) {
+    switch (accumulator) {
+        case Accumulator::Mutex: return "mutex";
+        case Accumulator::Atomic: return "atomic";
+        case Accumulator::PerWorker: return "per-worker";
+    }
+    return "";
+}
+
+struct alignas(64) Slot {
+    std::atomic<uint64_t> value{0};
+};
+
+struct Counter {
+    Accumulator accumulator;
+    std::mutex mutex;
+    uint64_t locked = 0;
+    std::atomic<uint64_t> atomic{0};
+    Slot slots[NUM_WORKERS];
     
-    // Warm-up
-    {
-        ThreadPool pool(NUM_WORKERS);
-        std::atomic<uint64_t> counter_warmup(0);
-        for (size_t i = 0; i < 100; i++) {
-            pool.enqueue([i, &counter_warmup] {
-                uint64_t result = heavy_computation(i);
-                counter_warmup += result;
-            });
+    explicit Counter(Accumulator accumulator) : accumulator(accumulator) {}
+    
+    void add(uint64_t value) {
+        switch (accumulator) {
+            case Accumulator::Mutex: {
+                std::lock_guard<std::mutex> lock(mutex);
+                locked += value;
+                break;
+            }
+            case Accumulator::Atomic:
+                atomic.fetch_add(value, std::memory_order_relaxed);
+                break;
+            case Accumulator::PerWorker: {
+                // A single writer per slot, so a plain load and store instead of a read-modify-write
+                auto& slot = slots[worker_index].value;
+                slot.store(slot.load(std::memory_order_relaxed) + value, std::memory_order_relaxed);
+                break;
+            }
         }
     }
     
-    // Benchmark
+    uint64_t total() {
+        switch (accumulator) {
+            case Accumulator::Mutex: {
+                std::lock_guard<std::mutex> lock(mutex);
+                return locked;
+            }
+            case Accumulator::Atomic:
+                return atomic.load();
+            case Accumulator::PerWorker: {
+                uint64_t sum = 0;
+                for (auto& slot : slots) {
+                    sum += slot.value.load();
+                }
+                return sum;
+            }
+        }
+        return 0;
+    }
+};
+
+// Workers are created inside the timed region for both backends, as the pool has always been
+template<class W>
+double run_pool(size_t num_tasks, Counter& counter, W work) {
     auto start = std::chrono::high_resolution_clock::now();
     {
         ThreadPool pool(NUM_WORKERS);
         
-        for (size_t i = 0; i < NUM_TASKS; i++) {
-            pool.enqueue([i, &counter] {
-                uint64_t result = heavy_computation(i);
-                counter += result;
+        for (size_t i = 0; i < num_tasks; i++) {
+            pool.enqueue([i, &counter, work] {
+                counter.add(work(i));
+            });
+        }
+    }
+    auto end = std::chrono::high_resolution_clock::now();
+    return std::chrono::duration<double>(end - start).count();
+}
+
+// One std::thread per task, NUM_WORKERS in flight at a time
+template<class W>
+double run_spawn(size_t num_tasks, Counter& counter, W work) {
+    auto start = std::chrono::high_resolution_clock::now();
+    for (size_t wave = 0; wave < num_tasks; wave += NUM_WORKERS) {
+        std::vector<std::thread> threads;
+        for (size_t i = wave; i < std::min(num_tasks, wave + NUM_WORKERS); i++) {
+            threads.emplace_back([i, wave, &counter, work] {
+                worker_index = i - wave;
+                counter.add(work(i));
             });
         }
+        for (auto& thread : threads) {
+            thread.join();
+        }
     }
     auto end = std::chrono::high_resolution_clock::now();
+    return std::chrono::duration<double>(end - start).count();
+}
+
+// The same task as a coroutine: hop onto the pool (the spawn), the work, then AWAIT_POINTS yields
+// before the result is added
+template<class W>
+Detached coroutine_task(CoroutinePool& pool, size_t i, Counter& counter, W work, std::latch& done) {
+    co_await pool.schedule();
+    uint64_t value = work(i);
+    for (size_t k = 0; k < AWAIT_POINTS; k++) {
+        co_await pool.schedule();
+    }
+    counter.add(value);
+    done.count_down();
+}
+
+template<class W>
+double run_coroutines(size_t num_tasks, Counter& counter, W work) {
+    auto start = std::chrono::high_resolution_clock::now();
+    {
+        // Declared first so it outlives the pool: a worker can still be inside count_down()
+        std::latch done(static_cast<std::ptrdiff_t>(num_tasks));
+        CoroutinePool pool(NUM_WORKERS);
+        for (size_t i = 0; i < num_tasks; i++) {
+            coroutine_task(pool, i, counter, work, done);
+        }
+        done.wait();
+    }
+    auto end = std::chrono::high_resolution_clock::now();
+    return std::chrono::duration<double>(end - start).count();
+}
+
+enum class Backend {
+    Pool,       // the ThreadPool above
+    Spawn,      // one std::thread per task
+    Coroutines, // every task a C++20 coroutine on CoroutinePool
+};
+
+const Backend BACKENDS[] = {Backend::Pool, Backend::Spawn, Backend::Coroutines};
+
+const char* backend_name(Backend backend) {
+    switch (backend) {
+        case Backend::Pool: return "custom pool";
+        case Backend::Spawn: return "thread per task";
+        case Backend::Coroutines: return "coroutine pool";
+    }
+    return "";
+}
+
+template<class W>
+double run(Backend backend, size_t num_tasks, Counter& counter, W work) {
+    switch (backend) {
+        case Backend::Pool: return run_pool(num_tasks, counter, work);
+        case Backend::Spawn: return run_spawn(num_tasks, counter, work);
+        case Backend::Coroutines: return run_coroutines(num_tasks, counter, work);
+    }
+    return 0.0;
+}
+
+// Throughput of every backend at each granularity, against running the same tasks in a loop on
+// one thread; a speedup below 1x means dispatch costs more than the task. Uses the atomic
+// accumulator so only dispatch is measured
+void report_sweep() {
+    for (uint64_t iterations : SWEEP_ITERATIONS) {
+        size_t num_tasks = std::clamp(static_cast<size_t>(SWEEP_TOTAL_ITERATIONS / iterations), SWEEP_MIN_TASKS, SWEEP_MAX_TASKS);
+        auto work = [iterations](size_t i) { return spin(i, iterations); };
+        
+        auto start = std::chrono::high_resolution_clock::now();
+        uint64_t sequential_count = 0;
+        for (size_t i = 0; i < num_tasks; i++) {
+            sequential_count += work(i);
+        }
+        double sequential = std::chrono::duration<double>(std::chrono::high_resolution_clock::now() - start).count();
+        
+        std::cerr << std::setprecision(0) << "Granularity: " << iterations << " iterations/task, "
+                  << sequential * 1e9 / num_tasks << " ns/task sequential, " << num_tasks << " tasks" << std::endl;
+        for (Backend backend : BACKENDS) {
+            Counter counter(Accumulator::Atomic);
+            double seconds = run(backend, num_tasks, counter, work);
+            std::cerr << std::setprecision(0) << "  " << backend_name(backend) << ": " << num_tasks / seconds << " tasks/s, "
+                      << std::setprecision(2) << sequential / seconds << "x sequential"
+                      << (counter.total() == sequential_count ? "" : " (count mismatch)") << std::endl;
+        }
+    }
+}
+
+int main(int argc, char** argv) {
+    bool sweep = false;
+    for (int i = 1; i < argc; i++) {
+        if (std::strcmp(argv[i], "--sweep") == 0) sweep = true;
+    }
     
-    std::chrono::duration<double> duration = end - start;
+    // Warm-up
+    for (Backend backend : BACKENDS) {
+        for (Accumulator accumulator : ACCUMULATORS) {
+            Counter warmup(accumulator);
+            run(backend, 100, warmup, heavy_computation);
+        }
+    }
     
-    std::cout << std::fixed << std::setprecision(6) << duration.count() << std::endl;
-    std::cerr << "Final count: " << counter.load() << std::endl;
+    // Benchmark: every backend with every accumulator, so lock contention can be told apart
+    // from dispatch cost
+    double durations[3][3];
+    uint64_t counts[3][3];
+    for (size_t b = 0; b < 3; b++) {
+        for (size_t a = 0; a < 3; a++) {
+            Counter counter(ACCUMULATORS[a]);
+            durations[b][a] = run(BACKENDS[b], NUM_TASKS, counter, heavy_computation);
+            counts[b][a] = counter.total();
+        }
+    }
+    
+    // The custom pool with the atomic is the one timed against Rust
+    double duration = durations[0][1];
+    uint64_t final_count = counts[0][1];
+    
+    std::cout << std::fixed << std::setprecision(6) << duration << std::endl;
+    std::cerr << std::fixed;
+    bool agree = true;
+    for (size_t b = 0; b < 3; b++) {
+        std::cerr << backend_name(BACKENDS[b]) << ": ";
+        for (size_t a = 0; a < 3; a++) {
+            std::cerr << (a ? ", " : "") << accumulator_name(ACCUMULATORS[a]) << " " << std::setprecision(6)
+                      << durations[b][a] << "s (" << std::setprecision(2) << durations[b][a] * 1e6 / NUM_TASKS << " us/task)";
+            agree = agree && counts[b][a] == final_count;
+        }
+        std::cerr << std::endl;
+    }
+    std::cerr << "Backends agree: " << (agree ? "true" : "false") << std::endl;
+    if (sweep) {
+        report_sweep();
+    }
+    std::cerr << "Final count: " << final_count << std::endl;
     
     return 0;
 }
-
@@ -0,0 +1,117 @@
+#include <iostream>
+#include <vector>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdio>
+#include <cstddef>
+#include "huge_pages.h"
+#include "numa_placement.h"
+
+const size_t ROWS = 8000; // 320 MB of double per matrix
+const size_t COLS = 5000;
+const size_t BLOCK = 32;  // a 32x32 double tile of source and destination fits in L1
+const size_t LEAF = 32;   // recursion stops once both sides are this small
+const size_t ROUNDS = 3;
+const size_t SAMPLE_STRIDE = 1009;
+
+// Row-major rows x cols in, row-major cols x rows out
+void transpose_naive(const double* src, double* dst, size_t rows, size_t cols) {
+    for (size_t i = 0; i < rows; i++) {
+        for (size_t j = 0; j < cols; j++) {
+            dst[j * rows + i] = src[i * cols + j];
+        }
+    }
+}
+
+void transpose_blocked(const double* src, double* dst, size_t rows, size_t cols) {
+    for (size_t i0 = 0; i0 < rows; i0 += BLOCK) {
+        for (size_t j0 = 0; j0 < cols; j0 += BLOCK) {
+            for (size_t i = i0; i < std::min(i0 + BLOCK, rows); i++) {
+                for (size_t j = j0; j < std::min(j0 + BLOCK, cols); j++) {
+                    dst[j * rows + i] = src[i * cols + j];
+                }
+            }
+        }
+    }
+}
+
+// Cache-oblivious: halve the longer side until the tile fits, with no tuning for any cache level
+void transpose_recursive(const double* src, double* dst, size_t rows, size_t cols, size_t r0, size_t r1, size_t c0,
+                         size_t c1) {
+    if (r1 - r0 <= LEAF && c1 - c0 <= LEAF) {
+        for (size_t i = r0; i < r1; i++) {
+            for (size_t j = c0; j < c1; j++) {
+                dst[j * rows + i] = src[i * cols + j];
+            }
+        }
+    } else if (r1 - r0 >= c1 - c0) {
+        size_t mid = r0 + (r1 - r0) / 2;
+        transpose_recursive(src, dst, rows, cols, r0, mid, c0, c1);
+        transpose_recursive(src, dst, rows, cols, mid, r1, c0, c1);
+    } else {
+        size_t mid = c0 + (c1 - c0) / 2;
+        transpose_recursive(src, dst, rows, cols, r0, r1, c0, mid);
+        transpose_recursive(src, dst, rows, cols, r0, r1, mid, c1);
+    }
+}
+
+using Transpose = void (*)(const double*, double*);
+
+std::chrono::duration<double> run(const std::vector<double>& src, std::vector<double>& dst, Transpose transpose) {
+    auto start = std::chrono::high_resolution_clock::now();
+    for (size_t round = 0; round < ROUNDS; round++) {
+        transpose(src.data(), dst.data());
+    }
+    return std::chrono::high_resolution_clock::now() - start;
+}
+
+int main(int argc, char** argv) {
+    NumaPlacement placement = numa_place(argc, argv);
+    huge_pages_enable(argc, argv);
+
+    // Every element holds its own row-major index, so the output is checkable exactly
+    std::vector<double> src, dst;
+    huge_reserve(src, ROWS * COLS);
+    huge_reserve(dst, ROWS * COLS);
+    src.resize(ROWS * COLS);
+    for (size_t i = 0; i < src.size(); i++) src[i] = static_cast<double>(i);
+    dst.resize(ROWS * COLS, 0.0);
+
+    // Warm-up (also faults in the destination pages)
+    transpose_blocked(src.data(), dst.data(), ROWS, COLS);
+
+    std::pair<const char*, Transpose> variants[] = {
+        {"naive", [](const double* s, double* d) { transpose_naive(s, d, ROWS, COLS); }},
+        {"blocked", [](const double* s, double* d) { transpose_blocked(s, d, ROWS, COLS); }},
+        {"recursive", [](const double* s, double* d) { transpose_recursive(s, d, ROWS, COLS, 0, ROWS, 0, COLS); }},
+    };
+
+    std::chrono::duration<double> total(0);
+    double checksum = 0.0;
+    double gigabytes = 2.0 * ROWS * COLS * 8 * ROUNDS / 1e9; // every element read once and written once
+    fprintf(stderr, "Transposing %zux%zu double (%.0f MB), %zu rounds each\n", ROWS, COLS, ROWS * COLS * 8 / 1e6, ROUNDS);
+    for (auto [name, transpose] : variants) {
+        std::fill(dst.begin(), dst.end(), 0.0);
+        std::chrono::duration<double> elapsed = run(src, dst, transpose);
+        for (size_t j = 0; j < COLS; j++) {
+            for (size_t i = 0; i < ROWS; i++) {
+                if (dst[j * ROWS + i] != src[i * COLS + j]) {
+                    fprintf(stderr, "%s transpose is wrong at (%zu, %zu)\n", name, i, j);
+                    return 1;
+                }
+            }
+        }
+        fprintf(stderr, "  %-10s %8.3fs (%.2f GB/s)\n", name, elapsed.count(), gigabytes / elapsed.count());
+        total += elapsed;
+        checksum = 0.0;
+        for (size_t k = 0; k < dst.size(); k += SAMPLE_STRIDE) checksum += dst[k];
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total.count() << std::endl;
+    std::cerr << numa_report(placement) << std::endl;
+    std::cerr << huge_pages_report() << std::endl;
+    std::cerr << "Checksum: " << std::fixed << std::setprecision(0) << checksum << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,285 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <memory>
+#include <deque>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+const size_t NUM_WORDS = 2'000'000;
+const size_t NUM_LOOKUPS = 2'000'000;
+const size_t NUM_PREFIX_QUERIES = 100'000;
+const uint64_t MAX_SYLLABLES = 5;
+const size_t PREFIX_SYLLABLES = 2;
+const std::string CONSONANTS = "bcdfghklmnprstvz";
+const std::string VOWELS = "aeiou";
+const size_t ALPHABET_SIZE = 26;
+const uint32_t TERMINAL_BIT = 1u << ALPHABET_SIZE;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+// Consonant-vowel syllables give the dictionary realistic shared prefixes
+std::string random_word(uint64_t& seed, size_t syllables) {
+    std::string word;
+    word.reserve(syllables * 2);
+    for (size_t s = 0; s < syllables; s++) {
+        word += CONSONANTS[next_random(seed) % CONSONANTS.size()];
+        word += VOWELS[next_random(seed) % VOWELS.size()];
+    }
+    return word;
+}
+
+void generate_data(std::vector<std::string>& words, std::vector<std::string>& lookups,
+                   std::vector<std::string>& prefixes) {
+    uint64_t seed = 4242;
+    words.reserve(NUM_WORDS);
+    for (size_t i = 0; i < NUM_WORDS; i++) {
+        size_t syllables = 1 + next_random(seed) % MAX_SYLLABLES;
+        words.push_back(random_word(seed, syllables));
+    }
+
+    // Half the lookups hit the dictionary, half are fresh random words
+    lookups.reserve(NUM_LOOKUPS);
+    for (size_t i = 0; i < NUM_LOOKUPS; i++) {
+        if (next_random(seed) % 2 == 0) {
+            lookups.push_back(words[next_random(seed) % NUM_WORDS]);
+        } else {
+            size_t syllables = 1 + next_random(seed) % MAX_SYLLABLES;
+            lookups.push_back(random_word(seed, syllables));
+        }
+    }
+
+    prefixes.reserve(NUM_PREFIX_QUERIES);
+    for (size_t i = 0; i < NUM_PREFIX_QUERIES; i++) {
+        prefixes.push_back(random_word(seed, PREFIX_SYLLABLES));
+    }
+}
+
+// Classic pointer trie: every node owns its children through a sorted vector of unique_ptrs
+struct Node {
+    std::vector<std::pair<char, std::unique_ptr<Node>>> children;
+    bool terminal = false;
+};
+
+struct PointerTrie {
+    Node root;
+    size_t node_count = 1;
+    size_t word_count = 0;
+
+    void insert(const std::string& word) {
+        Node* node = &root;
+        for (char c : word) {
+            auto it = std::lower_bound(node->children.begin(), node->children.end(), c,
+                                       [](const auto& child, char key) { return child.first < key; });
+            if (it == node->children.end() || it->first != c) {
+                it = node->children.emplace(it, c, std::make_unique<Node>());
+                node_count++;
+            }
+            node = it->second.get();
+        }
+        if (!node->terminal) {
+            node->terminal = true;
+            word_count++;
+        }
+    }
+
+    const Node* find(const std::string& prefix) const {
+        const Node* node = &root;
+        for (char c : prefix) {
+            auto it = std::lower_bound(node->children.begin(), node->children.end(), c,
+                                       [](const auto& child, char key) { return child.first < key; });
+            if (it == node->children.end() || it->first != c) {
+                return nullptr;
+            }
+            node = it->second.get();
+        }
+        return node;
+    }
+
+    bool contains(const std::string& word) const {
+        const Node* node = find(word);
+        return node && node->terminal;
+    }
+
+    static size_t count(const Node* node) {
+        size_t total = node->terminal;
+        for (const auto& child : node->children) {
+            total += count(child.second.get());
+        }
+        return total;
+    }
+
+    size_t count_with_prefix(const std::string& prefix) const {
+        const Node* node = find(prefix);
+        return node ? count(node) : 0;
+    }
+};
+
+// Array-mapped trie: a 26-bit child bitmap per node, children stored contiguously
+// in one arena and located with popcount instead of following pointers
+struct CompactNode {
+    uint32_t bitmap; // Bit c = child for letter c, TERMINAL_BIT = end of word
+    uint32_t first_child;
+};
+
+struct CompactTrie {
+    std::vector<CompactNode> nodes;
+
+    // Breadth-first layout so every node's children are adjacent
+    explicit CompactTrie(const PointerTrie& trie) {
+        nodes.reserve(trie.node_count);
+        std::deque<const Node*> queue;
+        nodes.push_back({0, 0});
+        queue.push_back(&trie.root);
+
+        size_t index = 0;
+        while (!queue.empty()) {
+            const Node* node = queue.front();
+            queue.pop_front();
+            uint32_t bitmap = node->terminal ? TERMINAL_BIT : 0;
+            for (const auto& [c, child] : node->children) {
+                bitmap |= 1u << (c - 'a');
+                nodes.push_back({0, 0});
+                queue.push_back(child.get());
+            }
+            nodes[index] = {bitmap, static_cast<uint32_t>(nodes.size() - node->children.size())};
+            index++;
+        }
+    }
+
+    // Returns false if there is no child for letter c
+    inline bool child(uint32_t node, char c, uint32_t& out) const {
+        const CompactNode& n = nodes[node];
+        uint32_t bit = 1u << (c - 'a');
+        if (!(n.bitmap & bit)) {
+            return false;
+        }
+        out = n.first_child + __builtin_popcount(n.bitmap & (bit - 1));
+        return true;
+    }
+
+    bool find(const std::string& prefix, uint32_t& out) const {
+        uint32_t node = 0;
+        for (char c : prefix) {
+            if (!child(node, c, node)) {
+                return false;
+            }
+        }
+        out = node;
+        return true;
+    }
+
+    bool contains(const std::string& word) const {
+        uint32_t node;
+        return find(word, node) && (nodes[node].bitmap & TERMINAL_BIT);
+    }
+
+    size_t count_with_prefix(const std::string& prefix) const {
+        uint32_t start;
+        if (!find(prefix, start)) {
+            return 0;
+        }
+        std::vector<uint32_t> stack = {start};
+        size_t count = 0;
+        while (!stack.empty()) {
+            const CompactNode& n = nodes[stack.back()];
+            stack.pop_back();
+            count += (n.bitmap & TERMINAL_BIT) != 0;
+            uint32_t children = __builtin_popcount(n.bitmap & (TERMINAL_BIT - 1));
+            for (uint32_t c = n.first_child; c < n.first_child + children; c++) {
+                stack.push_back(c);
+            }
+        }
+        return count;
+    }
+};
+
+double rate(size_t ops, double secs) {
+    return ops / secs;
+}
+
+int main() {
+    std::vector<std::string> words, lookups, prefixes;
+    generate_data(words, lookups, prefixes);
+
+    // Warm-up
+    {
+        PointerTrie warmup;
+        for (size_t i = 0; i < NUM_WORDS / 20; i++) {
+            warmup.insert(words[i]);
+        }
+        CompactTrie compact(warmup);
+        volatile size_t found = std::count_if(lookups.begin(), lookups.end(),
+                                              [&](const std::string& w) { return compact.contains(w); });
+        (void)found;
+    }
+
+    // Benchmark pointer trie insertion
+    auto start = std::chrono::high_resolution_clock::now();
+    PointerTrie trie;
+    for (const auto& word : words) {
+        trie.insert(word);
+    }
+    auto insert_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark compact trie construction
+    auto build_start = std::chrono::high_resolution_clock::now();
+    CompactTrie compact(trie);
+    auto build_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark lookups
+    auto lookup_start = std::chrono::high_resolution_clock::now();
+    size_t found = std::count_if(lookups.begin(), lookups.end(),
+                                 [&](const std::string& w) { return trie.contains(w); });
+    auto lookup_end = std::chrono::high_resolution_clock::now();
+
+    auto compact_lookup_start = std::chrono::high_resolution_clock::now();
+    size_t compact_found = std::count_if(lookups.begin(), lookups.end(),
+                                         [&](const std::string& w) { return compact.contains(w); });
+    auto compact_lookup_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark prefix queries
+    auto prefix_start = std::chrono::high_resolution_clock::now();
+    size_t completions = 0;
+    for (const auto& p : prefixes) {
+        completions += trie.count_with_prefix(p);
+    }
+    auto prefix_end = std::chrono::high_resolution_clock::now();
+
+    auto compact_prefix_start = std::chrono::high_resolution_clock::now();
+    size_t compact_completions = 0;
+    for (const auto& p : prefixes) {
+        compact_completions += compact.count_with_prefix(p);
+    }
+    auto compact_prefix_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> insert_duration = insert_end - start;
+    std::chrono::duration<double> build_duration = build_end - build_start;
+    std::chrono::duration<double> lookup_duration = lookup_end - lookup_start;
+    std::chrono::duration<double> compact_lookup_duration = compact_lookup_end - compact_lookup_start;
+    std::chrono::duration<double> prefix_duration = prefix_end - prefix_start;
+    std::chrono::duration<double> compact_prefix_duration = compact_prefix_end - compact_prefix_start;
+    std::chrono::duration<double> total_duration = insert_duration + build_duration + lookup_duration
+                                                 + compact_lookup_duration + prefix_duration
+                                                 + compact_prefix_duration;
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    std::cerr << std::fixed << "Insert: " << insert_duration.count() << "s (" << std::setprecision(0)
+              << rate(NUM_WORDS, insert_duration.count()) << " ops/s), compact build: "
+              << std::setprecision(6) << build_duration.count() << "s" << std::endl;
+    std::cerr << std::setprecision(0) << "Lookup: pointer " << rate(NUM_LOOKUPS, lookup_duration.count())
+              << " ops/s, compact " << rate(NUM_LOOKUPS, compact_lookup_duration.count()) << " ops/s" << std::endl;
+    std::cerr << "Prefix: pointer " << rate(NUM_PREFIX_QUERIES, prefix_duration.count())
+              << " ops/s, compact " << rate(NUM_PREFIX_QUERIES, compact_prefix_duration.count()) << " ops/s"
+              << std::endl;
+    std::cerr << "Nodes: " << trie.node_count << ", unique words: " << trie.word_count << std::endl;
+    std::cerr << "Checksum: " << found << " found, " << completions << " completions (compact: "
+              << compact_found << " found, " << compact_completions << " completions)" << std::endl;
+
+    return 0;
+}
@@ -0,0 +1,232 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <string_view>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <unicode/bytestream.h>
+#include <unicode/locid.h>
+#include <unicode/normalizer2.h>
+#include <unicode/ucasemap.h>
+#include <unicode/unistr.h>
+
+const size_t CORPUS_BYTES = size_t(16) << 20;
+const size_t WORDS_PER_LINE = 12;
+const size_t PASSES = 3;
+
+using Duration = std::chrono::duration<double>;
+using Clock = std::chrono::high_resolution_clock;
+
+// Words from scripts with casing rules worth testing, and some without. The \u escapes are
+// decomposed (NFD) spellings, some with combining marks out of canonical order, for NFC to
+// recompose: Hangul jamo, stacked Vietnamese diacritics, accented Latin
+const char* const WORDS[] = {
+    // English
+    "the", "quick", "Brown", "fox", "DATA", "pipeline", "ingest",
+    // French, German, Turkish: expanding (ß, ﬁ) and locale-sensitive (İ, ı) mappings
+    "café", "cafe\u0301", "Élève", "garçon", "naïve", "Noe\u0308l", "Straße", "größer", "ÄRGER", "Fuß",
+    "ﬁnance", "İstanbul", "ılık", "Iğdır",
+    // Greek, with final sigma and accents
+    "Οδυσσέας", "σοφία", "ΣΟΦΟΣ", "λόγος", "ψυχή", "ΐ",
+    // Cyrillic and Armenian, including the և ligature that uppercases to two letters
+    "Москва", "ёлка", "щука", "ЖИЗНЬ", "Երևան", "և",
+    // Vietnamese, precomposed and decomposed with marks in both orders
+    "Tiếng", "Vie\u0323\u0302t", "Vie\u0302\u0323t", "Nguyễn",
+    // Korean as precomposed syllables and as conjoining jamo
+    "한국어", "\u1112\u1161\u11AB\u1100\u116E\u11A8",
+    // CJK, Devanagari and emoji: no case, and already NFC
+    "東京", "数据", "日本語", "हिन्दी", "नमस्ते", "😀", "👍🏽", "🇯🇵",
+};
+const size_t NUM_WORDS = sizeof(WORDS) / sizeof(WORDS[0]);
+
+uint64_t splitmix64(uint64_t& seed) {
+    seed += 0x9E3779B97F4A7C15ULL;
+    uint64_t z = seed;
+    z = (z ^ (z >> 30)) * 0xBF58476D1CE4E5B9ULL;
+    z = (z ^ (z >> 27)) * 0x94D049BB133111EBULL;
+    return z ^ (z >> 31);
+}
+
+// Lines of WORDS_PER_LINE random words separated by spaces, until the corpus reaches `bytes`
+std::string generate(size_t bytes) {
+    uint64_t seed = 0x1F600;
+    std::string text;
+    text.reserve(bytes + 256);
+    while (text.size() < bytes) {
+        for (size_t w = 0; w < WORDS_PER_LINE; w++) {
+            if (w > 0) text += ' ';
+            text += WORDS[splitmix64(seed) % NUM_WORDS];
+        }
+        text += '\n';
+    }
+    return text;
+}
+
+uint64_t fnv1a(uint64_t hash, std::string_view bytes) {
+    for (unsigned char b : bytes) hash = (hash ^ b) * 0x100000001b3ULL;
+    return hash;
+}
+
+// ICU's case mapping and normalization objects, created once as an application would
+struct Icu {
+    UCaseMap* case_map;
+    const icu::Normalizer2* nfc;
+
+    Icu() {
+        UErrorCode status = U_ZERO_ERROR;
+        case_map = ucasemap_open("", U_FOLD_CASE_DEFAULT, &status);
+        nfc = icu::Normalizer2::getNFCInstance(status);
+        if (U_FAILURE(status)) {
+            fprintf(stderr, "ICU initialization failed: %s\n", u_errorName(status));
+            std::exit(1);
+        }
+    }
+
+    ~Icu() { ucasemap_close(case_map); }
+};
+
+const Icu& icu_instance() {
+    static const Icu icu;
+    return icu;
+}
+
+// The usual route: UTF-8 into a UTF-16 icu::UnicodeString, transform, and back
+void upper_utf16(std::string_view line, std::string& out) {
+    icu::UnicodeString s = icu::UnicodeString::fromUTF8(icu::StringPiece(line.data(), line.size()));
+    s.toUpper(icu::Locale::getRoot()).toUTF8String(out);
+}
+
+void fold_utf16(std::string_view line, std::string& out) {
+    icu::UnicodeString s = icu::UnicodeString::fromUTF8(icu::StringPiece(line.data(), line.size()));
+    s.foldCase(U_FOLD_CASE_DEFAULT).toUTF8String(out);
+}
+
+void nfc_utf16(std::string_view line, std::string& out) {
+    UErrorCode status = U_ZERO_ERROR;
+    icu::UnicodeString s = icu::UnicodeString::fromUTF8(icu::StringPiece(line.data(), line.size()));
+    icu_instance().nfc->normalize(s, status).toUTF8String(out);
+}
+
+// ICU's UTF-8 entry points, with no conversion to UTF-16. The case mappers write into a
+// buffer sized by a first guess and retry once if the mapping grew past it
+using CaseMapFn = int32_t (*)(const UCaseMap*, char*, int32_t, const char*, int32_t, UErrorCode*);
+
+void case_map_utf8(CaseMapFn map, std::string_view line, std::string& out) {
+    UErrorCode status = U_ZERO_ERROR;
+    out.resize(line.size() + line.size() / 4 + 16);
+    int32_t length = map(icu_instance().case_map, out.data(), static_cast<int32_t>(out.size()), line.data(),
+                         static_cast<int32_t>(line.size()), &status);
+    if (status == U_BUFFER_OVERFLOW_ERROR) {
+        status = U_ZERO_ERROR;
+        out.resize(static_cast<size_t>(length));
+        map(icu_instance().case_map, out.data(), length, line.data(), static_cast<int32_t>(line.size()), &status);
+    }
+    out.resize(static_cast<size_t>(length));
+}
+
+void upper_utf8(std::string_view line, std::string& out) {
+    case_map_utf8(ucasemap_utf8ToUpper, line, out);
+}
+
+void fold_utf8(std::string_view line, std::string& out) {
+    case_map_utf8(ucasemap_utf8FoldCase, line, out);
+}
+
+void nfc_utf8(std::string_view line, std::string& out) {
+    UErrorCode status = U_ZERO_ERROR;
+    icu::StringByteSink<std::string> sink(&out, static_cast<int32_t>(line.size()));
+    icu_instance().nfc->normalizeUTF8(0, icu::StringPiece(line.data(), line.size()), sink, nullptr, status);
+}
+
+using TransformFn = void (*)(std::string_view, std::string&);
+
+struct NamedVariant {
+    const char* op;
+    const char* library;
+    TransformFn transform;
+};
+
+// Per operation, the first route is the reference the second has to reproduce
+const NamedVariant VARIANTS[] = {
+    {"uppercase", "icu utf-16", upper_utf16},
+    {"uppercase", "icu utf-8", upper_utf8},
+    {"case fold", "icu utf-16", fold_utf16},
+    {"case fold", "icu utf-8", fold_utf8},
+    {"NFC", "icu utf-16", nfc_utf16},
+    {"NFC", "icu utf-8", nfc_utf8},
+};
+
+struct Output {
+    uint64_t hash = 0xcbf29ce484222325ULL; // FNV-1a of the transformed lines, back to back
+    size_t bytes = 0;
+};
+
+// Line by line, as a pipeline normalizing fields would, each line into a fresh string
+Output transform(TransformFn f, std::string_view text) {
+    Output output;
+    size_t start = 0;
+    for (size_t end; (end = text.find('\n', start)) != std::string_view::npos; start = end + 1) {
+        std::string result;
+        f(text.substr(start, end - start), result);
+        output.hash = fnv1a(output.hash, result);
+        output.bytes += result.size();
+    }
+    return output;
+}
+
+struct Run {
+    Duration duration;
+    Output output;
+};
+
+// The barrier on the input every pass stops the compiler computing one pass and reusing it
+Run run(TransformFn f, const std::string& text, size_t passes) {
+    Run run;
+    run.output = transform(f, text);
+    auto start = Clock::now();
+    for (size_t p = 0; p < passes; p++) {
+        asm volatile("" : : "r"(text.data()) : "memory");
+        run.output = transform(f, text);
+    }
+    run.duration = Clock::now() - start;
+    return run;
+}
+
+int main() {
+    std::string text = generate(CORPUS_BYTES);
+    size_t chars = 0, lines = 0;
+    for (unsigned char c : text) {
+        chars += (c & 0xC0) != 0x80;
+        lines += c == '\n';
+    }
+    icu_instance();
+
+    // Benchmark (each run's first, untimed pass is the warm-up)
+    std::vector<Run> runs;
+    Duration total_duration(0);
+    for (const auto& v : VARIANTS) {
+        runs.push_back(run(v.transform, text, PASSES));
+        total_duration += runs.back().duration;
+    }
+    bool all_match = true;
+    uint64_t checksum = 0;
+    for (size_t i = 0; i < runs.size(); i += 2) {
+        all_match &= runs[i].output.hash == runs[i + 1].output.hash && runs[i].output.bytes == runs[i + 1].output.bytes;
+        checksum += runs[i].output.hash;
+    }
+
+    std::cout << std::fixed << std::setprecision(6) << total_duration.count() << std::endl;
+    fprintf(stderr, "Corpus: %.1f MB, %zu chars in %zu lines x %zu passes per variant (ICU %s)\n", text.size() / 1e6, chars,
+            lines, PASSES, U_ICU_VERSION);
+    for (size_t i = 0; i < runs.size(); i++) {
+        double rate = static_cast<double>(text.size() * PASSES) / runs[i].duration.count();
+        fprintf(stderr, "  %-10s %-13s %.3fs (%.0f MB/s, %zu bytes out)\n", VARIANTS[i].op, VARIANTS[i].library,
+                runs[i].duration.count(), rate / 1e6, runs[i].output.bytes);
+    }
+    std::cerr << "Checksum: " << checksum << " (all variants match: " << (all_match ? "true" : "false") << ")" << std::endl;
+
+    return 0;
+}
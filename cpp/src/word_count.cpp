@@ -0,0 +1,234 @@
+#include <iostream>
+#include <vector>
+#include <string>
+#include <string_view>
+#include <unordered_map>
+#include <queue>
+#include <thread>
+#include <algorithm>
+#include <chrono>
+#include <iomanip>
+#include <cstdint>
+
+#include "bench_measure.h"
+
+const size_t CORPUS_BYTES = 1 << 30; // ~1GB of log lines
+const size_t VOCABULARY_SIZE = 50'000;
+const uint64_t NUM_REQUEST_IDS = 100'000;
+const size_t TOP_K = 20;
+const size_t NUM_THREADS = 8;
+const std::string CONSONANTS = "bcdfghklmnprstvz";
+const std::string VOWELS = "aeiou";
+const char* LEVELS[4] = {"INFO", "DEBUG", "WARN", "ERROR"};
+const char* SERVICES[8] = {"auth", "billing", "gateway", "search", "storage", "queue", "mailer", "cache"};
+
+using Counts = std::unordered_map<std::string_view, uint64_t>;
+using Entry = std::pair<uint64_t, std::string_view>;
+
+uint64_t next_random(uint64_t& seed) {
+    seed = seed * 6364136223846793005ULL + 1442695040888963407ULL;
+    return seed >> 33;
+}
+
+void push_two_digits(std::string& out, uint64_t n) {
+    out += static_cast<char>('0' + n / 10);
+    out += static_cast<char>('0' + n % 10);
+}
+
+// Lines look like "12:34:56 WARN [billing] tokens... req=0x1a2b\n"; message words follow Zipf's law
+std::string generate_corpus() {
+    uint64_t seed = 1234567;
+    std::vector<std::string> vocabulary(VOCABULARY_SIZE);
+    for (auto& word : vocabulary) {
+        uint64_t syllables = 2 + next_random(seed) % 3;
+        for (uint64_t s = 0; s < syllables; s++) {
+            word += CONSONANTS[next_random(seed) % CONSONANTS.size()];
+            word += VOWELS[next_random(seed) % VOWELS.size()];
+        }
+    }
+
+    std::vector<double> cdf;
+    cdf.reserve(VOCABULARY_SIZE);
+    double total = 0.0;
+    for (size_t rank = 0; rank < VOCABULARY_SIZE; rank++) {
+        total += 1.0 / static_cast<double>(rank + 1);
+        cdf.push_back(total);
+    }
+
+    std::string corpus;
+    corpus.reserve(CORPUS_BYTES + 256);
+    while (corpus.size() < CORPUS_BYTES) {
+        push_two_digits(corpus, next_random(seed) % 24);
+        corpus += ':';
+        push_two_digits(corpus, next_random(seed) % 60);
+        corpus += ':';
+        push_two_digits(corpus, next_random(seed) % 60);
+        corpus += ' ';
+
+        uint64_t level = next_random(seed) % 100;
+        corpus += LEVELS[level < 70 ? 0 : level < 85 ? 1 : level < 97 ? 2 : 3];
+        corpus += " [";
+        corpus += SERVICES[next_random(seed) % 8];
+        corpus += "]";
+
+        uint64_t words = 5 + next_random(seed) % 11;
+        for (uint64_t w = 0; w < words; w++) {
+            double u = static_cast<double>(next_random(seed)) / static_cast<double>(1ULL << 31) * total;
+            size_t rank = std::min<size_t>(std::lower_bound(cdf.begin(), cdf.end(), u) - cdf.begin(),
+                                           VOCABULARY_SIZE - 1);
+            corpus += ' ';
+            corpus += vocabulary[rank];
+        }
+
+        corpus += " req=0x";
+        uint64_t id = next_random(seed) % NUM_REQUEST_IDS;
+        for (int shift = 4; shift >= 0; shift--) {
+            corpus += "0123456789abcdef"[(id >> (shift * 4)) & 0xf];
+        }
+        corpus += '\n';
+    }
+
+    return corpus;
+}
+
+inline bool is_word_char(char c) {
+    return (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || (c >= '0' && c <= '9') || c == '_';
+}
+
+// Tokens are maximal runs of ASCII letters, digits, and underscores (case-sensitive)
+void count_words(std::string_view text, Counts& counts) {
+    size_t start = 0;
+    bool in_word = false;
+    for (size_t i = 0; i < text.size(); i++) {
+        bool is_word = is_word_char(text[i]);
+        if (is_word && !in_word) {
+            start = i;
+            in_word = true;
+        } else if (!is_word && in_word) {
+            counts[text.substr(start, i - start)]++;
+            in_word = false;
+        }
+    }
+    if (in_word) {
+        counts[text.substr(start)]++;
+    }
+}
+
+// Ordered by count descending then word ascending
+bool better(const Entry& a, const Entry& b) {
+    return a.first > b.first || (a.first == b.first && a.second < b.second);
+}
+
+// Min-heap of the best K so far: the top of the heap is the worst kept entry
+std::vector<Entry> top_k(const Counts& counts) {
+    std::priority_queue<Entry, std::vector<Entry>, decltype(&better)> heap(&better);
+    for (const auto& [word, count] : counts) {
+        heap.push({count, word});
+        if (heap.size() > TOP_K) {
+            heap.pop();
+        }
+    }
+    std::vector<Entry> top;
+    while (!heap.empty()) {
+        top.push_back(heap.top());
+        heap.pop();
+    }
+    std::sort(top.begin(), top.end(), better);
+    return top;
+}
+
+std::pair<Counts, std::vector<Entry>> run_sequential(std::string_view corpus) {
+    Counts counts;
+    count_words(corpus, counts);
+    auto top = top_k(counts);
+    return {std::move(counts), std::move(top)};
+}
+
+// Map: count each line-aligned chunk independently; reduce: merge the partial maps
+std::pair<Counts, std::vector<Entry>> run_parallel(std::string_view corpus) {
+    std::vector<size_t> bounds = {0};
+    for (size_t i = 1; i < NUM_THREADS; i++) {
+        size_t target = std::max(corpus.size() * i / NUM_THREADS, bounds.back());
+        size_t newline = corpus.find('\n', target);
+        bounds.push_back(newline == std::string_view::npos ? corpus.size() : newline + 1);
+    }
+    bounds.push_back(corpus.size());
+
+    std::vector<Counts> partial(NUM_THREADS);
+    std::vector<std::thread> threads;
+    for (size_t t = 0; t < NUM_THREADS; t++) {
+        threads.emplace_back([&, t] {
+            count_words(corpus.substr(bounds[t], bounds[t + 1] - bounds[t]), partial[t]);
+        });
+    }
+    for (auto& thread : threads) {
+        thread.join();
+    }
+
+    auto largest = std::max_element(partial.begin(), partial.end(),
+                                     [](const Counts& a, const Counts& b) { return a.size() < b.size(); });
+    Counts counts = std::move(*largest);
+    for (auto it = partial.begin(); it != partial.end(); ++it) {
+        if (it == largest) continue;
+        for (const auto& [word, count] : *it) {
+            counts[word] += count;
+        }
+    }
+    auto top = top_k(counts);
+    return {std::move(counts), std::move(top)};
+}
+
+int main() {
+    auto measure = bench_measure_from_env();
+    std::string corpus = generate_corpus();
+    std::string_view text = corpus;
+    double megabytes = corpus.size() / 1e6;
+
+    // Warm-up
+    {
+        auto warmup = run_sequential(text.substr(0, corpus.size() / 100));
+        auto parallel_warmup = run_parallel(text.substr(0, corpus.size() / 100));
+    }
+
+    // Benchmark sequential
+    auto start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    auto [counts, top] = run_sequential(text);
+    measure->stop();
+    auto sequential_end = std::chrono::high_resolution_clock::now();
+
+    // Benchmark parallel map-reduce
+    auto parallel_start = std::chrono::high_resolution_clock::now();
+    measure->start();
+    auto [parallel_counts, parallel_top] = run_parallel(text);
+    measure->stop();
+    auto parallel_end = std::chrono::high_resolution_clock::now();
+
+    std::chrono::duration<double> sequential_duration = sequential_end - start;
+    std::chrono::duration<double> parallel_duration = parallel_end - parallel_start;
+
+    // Checksum
+    uint64_t total_words = 0, parallel_words = 0, top_sum = 0, parallel_top_sum = 0;
+    for (const auto& [word, count] : counts) total_words += count;
+    for (const auto& [word, count] : parallel_counts) parallel_words += count;
+    for (const auto& [count, word] : top) top_sum += count;
+    for (const auto& [count, word] : parallel_top) parallel_top_sum += count;
+    std::string leaders;
+    for (size_t i = 0; i < std::min<size_t>(3, top.size()); i++) {
+        if (i > 0) leaders += ' ';
+        leaders += std::string(top[i].second) + "=" + std::to_string(top[i].first);
+    }
+
+    measure->report();
+    std::cerr << std::fixed << "Sequential: " << sequential_duration.count() << "s (" << std::setprecision(0)
+              << megabytes / sequential_duration.count() << " MB/s), parallel: " << std::setprecision(6)
+              << parallel_duration.count() << "s (" << std::setprecision(0)
+              << megabytes / parallel_duration.count() << " MB/s)" << std::endl;
+    std::cerr << "Corpus: " << megabytes << " MB, words: " << total_words << ", unique: " << counts.size()
+              << ", top: " << leaders << std::endl;
+    std::cerr << "Checksum: " << top_sum << " (parallel: " << parallel_words << " words, "
+              << parallel_counts.size() << " unique, top-" << TOP_K << " sum " << parallel_top_sum << ")"
+              << std::endl;
+
+    return 0;
+}
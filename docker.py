@@ -0,0 +1,95 @@
+#!/usr/bin/env python3
+"""
+Reproducible Linux runs
+Builds the pinned image in docker/Dockerfile (Debian snapshot, exact rustc, gcc or clang) and runs
+build.py inside it with the given arguments, with results/ mounted from the host, so anyone can
+rebuild the compilers a published Linux result came from
+"""
+
+import argparse
+import os
+import platform
+import re
+import shutil
+import subprocess
+import sys
+from pathlib import Path
+from typing import Dict, List
+
+DOCKERFILE = Path("docker/Dockerfile")
+IMAGE = "rust-vs-cpp-bench"
+COMPILERS = {"gcc": ("gcc", "g++"), "clang": ("clang", "clang++")}
+
+
+def dockerfile_pins() -> Dict[str, str]:
+    """The ARG defaults in the Dockerfile, which pin every version in the image"""
+    return dict(re.findall(r"^ARG (\w+)=(\S+)$", DOCKERFILE.read_text(), re.M))
+
+
+def image_tag(compiler: str) -> str:
+    """rust-vs-cpp-bench:rust1.95.0-20250812-gcc, so images from different pins never overwrite each other"""
+    pins = dockerfile_pins()
+    return f"{IMAGE}:rust{pins['RUST_VERSION']}-{pins['DEBIAN_SNAPSHOT'][:8]}-{compiler}"
+
+
+def build_image(tag: str, compiler: str) -> bool:
+    """docker build; the layers are cached, so this only rebuilds what changed in the tree"""
+    cc, cxx = COMPILERS[compiler]
+    print(f"\n🔨 Building {tag}...")
+    command = ["docker", "build", "-f", str(DOCKERFILE), "-t", tag,
+               "--build-arg", f"CC={cc}", "--build-arg", f"CXX={cxx}", "."]
+    if subprocess.run(command).returncode != 0:
+        print("❌ docker build failed")
+        return False
+    print(f"✅ Built {tag}")
+    return True
+
+
+def run_image(tag: str, args: List[str]) -> int:
+    """build.py in the image, as the calling user so results/ stays theirs, and its exit status"""
+    image_id = subprocess.run(["docker", "image", "inspect", "--format", "{{.Id}}", tag],
+                              capture_output=True, text=True).stdout.strip()
+    results = Path("results").resolve()
+    results.mkdir(exist_ok=True)
+    command = ["docker", "run", "--rm", "--init", "--user", f"{os.getuid()}:{os.getgid()}",
+               "-v", f"{results}:/bench/results",
+               # Recorded in the results file as the toolchain's container
+               "-e", f"BENCH_CONTAINER={tag}@{image_id}"]
+    if any(arg.endswith("instructions") for arg in args):
+        # perf_event_open is blocked by the default seccomp profile without it
+        command += ["--cap-add", "PERFMON"]
+    command += [tag, "python3", "build.py", *args]
+    print(f"\nRunning python3 build.py {' '.join(args)} in {tag}...")
+    return subprocess.run(command).returncode
+
+
+def main():
+    """Build the image and run build.py in it; arguments docker.py does not know go to build.py"""
+    parser = argparse.ArgumentParser(description="Run the benchmark suite in a pinned-toolchain Linux image",
+                                     epilog="Any other arguments are passed to build.py in the container")
+    parser.add_argument("--compiler", choices=COMPILERS, default="gcc",
+                        help="the C and C++ compiler in the image (default: gcc)")
+    parser.add_argument("--build-only", action="store_true", help="build the image without running anything")
+    args, build_args = parser.parse_known_args()
+
+    print("="*80)
+    print("Rust vs C++ Benchmark Suite: pinned Linux toolchain")
+    print("="*80)
+
+    if shutil.which("docker") is None:
+        print("\n❌ docker not found: https://docs.docker.com/engine/install/")
+        sys.exit(1)
+    if platform.system() != "Linux":
+        print(f"\n⚠️  On {platform.system()} the container runs in a Linux VM; its times are the VM's, "
+              "not this machine's native numbers")
+
+    tag = image_tag(args.compiler)
+    if not build_image(tag, args.compiler):
+        sys.exit(1)
+    if args.build_only:
+        return
+    sys.exit(run_image(tag, build_args))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,114 @@
+#!/usr/bin/env python3
+"""
+Multi-machine results
+Merges build.py results files from several machines into one dataset keyed by machine id, and
+compares each benchmark's speedup across the machines, grouped by architecture
+"""
+
+import argparse
+import json
+import sys
+from pathlib import Path
+from typing import Dict, List
+
+from build import BENCHMARKS, CV_THRESHOLD, UNRELIABLE_ADVICE
+from summary import geometric_mean, speedups, unreliable_benchmarks
+
+MERGED_FILE = "results/merged_results.json"
+
+
+def machines_in(path: str) -> List[Dict]:
+    """The machines in one file: a build.py results file holds one, a merged file any number"""
+    with open(path, "r") as f:
+        data = json.load(f)
+    if "machines" in data:
+        return list(data["machines"].values())
+
+    machine = data.get("machine")
+    if machine is None:
+        # Results from before build.py recorded the machine: the file name stands in for its id
+        print(f"⚠️  {path} does not say which machine it came from; using the id {Path(path).stem}")
+        machine = {"id": Path(path).stem, "arch": "unknown"}
+    entry = {key: value for key, value in data.items() if key != "machine"}
+    return [{"machine": machine, "source": path, **entry}]
+
+
+def merge(paths: List[str]) -> Dict:
+    """One dataset keyed by machine id; a repeated id, such as two runs on one machine, gets a suffix"""
+    machines = {}
+    for path in paths:
+        for entry in machines_in(path):
+            base = entry["machine"]["id"]
+            key, copy = base, 2
+            while key in machines:
+                key, copy = f"{base} ({copy})", copy + 1
+            machines[key] = entry
+    return {"machines": machines}
+
+
+def print_report(merged: Dict):
+    """Each benchmark's speedup on every machine, machines grouped by architecture, then geometric means"""
+    machines = sorted(merged["machines"].items(), key=lambda item: (item[1]["machine"].get("arch", ""), item[0]))
+    per_machine = {key: speedups(entry) for key, entry in machines}
+    noisy = {key: {u["benchmark"] for u in unreliable_benchmarks(entry, per_machine[key])} for key, entry in machines}
+    headers = [f"{entry['machine'].get('arch', '?')}: {key}{' (translated)' if entry.get('target', {}).get('translated') else ''}"
+               for key, entry in machines]
+    width = max([16] + [len(header) + 2 for header in headers])
+
+    print("\n" + "="*(26 + width * len(headers)))
+    print("SPEEDUP BY MACHINE (C++ time / Rust time, >1 = Rust faster)")
+    print("="*(26 + width * len(headers)))
+    print(f"{'Benchmark':<26}" + "".join(f"{header:>{width}}" for header in headers))
+    print("-"*(26 + width * len(headers)))
+    for benchmark in BENCHMARKS:
+        values = [per_machine[key].get(benchmark) for key, _ in machines]
+        if all(value is None for value in values):
+            continue
+        cells = [f"{value:.2f}{'*' if benchmark in noisy[key] else ' '}" if value else "- "
+                 for value, (key, _) in zip(values, machines)]
+        print(f"{benchmark:<26}" + "".join(f"{cell:>{width}}" for cell in cells))
+
+    # Geometric means over the benchmarks every machine ran, so the machines are compared on one set
+    common = [b for b in BENCHMARKS if all(b in per_machine[key] for key, _ in machines)]
+    print("-"*(26 + width * len(headers)))
+    if not common:
+        print("No benchmark ran on every machine")
+        print("="*(26 + width * len(headers)))
+        return
+    geomeans = {key: geometric_mean([per_machine[key][b] for b in common]) for key, _ in machines}
+    print(f"{f'Geomean ({len(common)} common)':<26}" + "".join(f"{geomeans[key]:>{width - 1}.3f} " for key, _ in machines))
+    if any(noisy.values()):
+        print(f"* unreliable on that machine, run-to-run variation (CV) above {CV_THRESHOLD:.0%}. {UNRELIABLE_ADVICE}")
+
+    architectures = {}
+    for key, entry in machines:
+        architectures.setdefault(entry["machine"].get("arch", "unknown"), []).append(geomeans[key])
+    print("\nBy architecture (geometric mean over its machines):")
+    for arch, values in architectures.items():
+        print(f"  {arch:<12} {geometric_mean(values):.3f} ({len(values)} machine{'s' if len(values) > 1 else ''})")
+    print("="*(26 + width * len(headers)))
+
+
+def main():
+    """Merge the results files given on the command line, save the dataset and print the comparison"""
+    parser = argparse.ArgumentParser(description="Merge build.py results from several machines")
+    parser.add_argument("files", nargs="+", metavar="RESULTS", help="results files, or merged files to add to")
+    parser.add_argument("-o", "--output", default=MERGED_FILE, help=f"merged dataset (default: {MERGED_FILE})")
+    args = parser.parse_args()
+
+    missing = [path for path in args.files if not Path(path).exists()]
+    if missing:
+        print(f"❌ Not found: {', '.join(missing)}")
+        sys.exit(1)
+
+    merged = merge(args.files)
+    Path(args.output).parent.mkdir(parents=True, exist_ok=True)
+    with open(args.output, "w") as f:
+        json.dump(merged, f, indent=2)
+
+    print_report(merged)
+    print(f"\n✅ {len(merged['machines'])} machines merged into {args.output}")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,207 @@
+// Cold caches for the benchmarks that repeat a kernel over data small enough to stay cached.
+// Every repetition after the first finds its inputs where the last one left them, in L1 or L2,
+// which flatters the smallest kernels most:
+//
+//     --cold-cache  flush the measured data out of every cache level before each timed repetition
+//
+// enable() parses the flag. The benchmark then times each repetition on its own and calls flush()
+// on its buffers in between, so the flushing stays out of the measurement. x86_64 flushes with
+// clflush and aarch64 Linux with dc civac, one cache line at a time over just the bytes given.
+// macOS does not let user code clean the caches, so there flush() falls back to evict(): a
+// read-modify-write sweep over twice the last-level cache, which pushes everything else out too
+// and costs the same whatever the buffer's size.
+
+use std::fs;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+const DEFAULT_LLC: usize = 32 << 20; // when the OS does not say
+const SWEEP_STEP: usize = 64; // bytes; every current CPU's lines are 64 or 128
+// A cold repetition is timed alone after its own flush, so a few dozen give a steady mean and
+// keep a run where flush() has to sweep from taking minutes
+const COLD_REPETITIONS: usize = 32;
+
+static COLD: AtomicBool = AtomicBool::new(false);
+static SWEEP: OnceLock<Mutex<Vec<u64>>> = OnceLock::new();
+
+// Parses --cold-cache from `args`
+pub fn enable(args: &[String]) -> bool {
+    let requested = args.iter().any(|a| a == "--cold-cache");
+    if requested {
+        COLD.store(true, Ordering::Relaxed);
+    }
+    requested
+}
+
+pub fn cold() -> bool {
+    COLD.load(Ordering::Relaxed)
+}
+
+// How many times to repeat a kernel the warm measurement would repeat `warm` times
+pub fn repetitions(warm: usize) -> usize {
+    if cold() {
+        warm.min(COLD_REPETITIONS)
+    } else {
+        warm
+    }
+}
+
+// sysfs cache sizes: "48K", "2048K", "32M"
+fn parse_size(size: &str) -> Option<usize> {
+    let size = size.trim();
+    let (digits, shift) = match size.as_bytes().last()? {
+        b'K' => (&size[..size.len() - 1], 10),
+        b'M' => (&size[..size.len() - 1], 20),
+        b'G' => (&size[..size.len() - 1], 30),
+        _ => (size, 0),
+    };
+    digits.parse::<usize>().ok().map(|n| n << shift)
+}
+
+// The largest cache cpu0 sees, which is the last level
+#[cfg(target_os = "linux")]
+fn llc_bytes() -> Option<usize> {
+    fs::read_dir("/sys/devices/system/cpu/cpu0/cache")
+        .ok()?
+        .filter_map(|entry| fs::read_to_string(entry.ok()?.path().join("size")).ok())
+        .filter_map(|size| parse_size(&size))
+        .max()
+}
+
+#[cfg(target_os = "macos")]
+fn llc_bytes() -> Option<usize> {
+    // Apple Silicon has no L3; its performance cores' shared L2 is the last level
+    ["hw.l3cachesize", "hw.perflevel0.l2cachesize", "hw.l2cachesize"].into_iter().find_map(|name| {
+        let name = std::ffi::CString::new(name).ok()?;
+        let mut value = 0u64;
+        let mut len = size_of::<u64>();
+        // SAFETY: value has room for the len bytes sysctlbyname may write, and no new value is set
+        let rc = unsafe {
+            libc::sysctlbyname(name.as_ptr(), (&mut value as *mut u64).cast(), &mut len, std::ptr::null_mut(), 0)
+        };
+        (rc == 0 && value > 0).then_some(value as usize)
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn llc_bytes() -> Option<usize> {
+    None
+}
+
+fn sweep_bytes() -> usize {
+    2 * llc_bytes().unwrap_or(DEFAULT_LLC)
+}
+
+// Pushes everything out of the caches by writing to every line of a buffer twice the size of the
+// last-level cache. A no-op unless --cold-cache was given
+pub fn evict() {
+    if !cold() {
+        return;
+    }
+    let sweep = SWEEP.get_or_init(|| Mutex::new(vec![0u64; sweep_bytes() / size_of::<u64>()]));
+    let mut buf = sweep.lock().unwrap();
+    for x in buf.iter_mut().step_by(SWEEP_STEP / size_of::<u64>()) {
+        *x = x.wrapping_add(1);
+    }
+    black_box(&mut *buf);
+}
+
+#[cfg(target_arch = "x86_64")]
+const FLUSH_INSTRUCTION: Option<&str> = Some("clflush");
+
+#[cfg(target_arch = "x86_64")]
+fn flush_lines(start: usize, end: usize) {
+    use std::arch::x86_64::{_mm_clflush, _mm_mfence};
+    // clflush is part of SSE2, which every x86_64 CPU has
+    for line in (start / 64 * 64..end).step_by(64) {
+        // SAFETY: every line holds at least one byte of the slice, so it is mapped
+        unsafe { _mm_clflush(line as *const u8) };
+    }
+    // Orders the flushes before the next repetition's first access
+    unsafe { _mm_mfence() };
+}
+
+#[cfg(all(target_arch = "aarch64", target_os = "linux"))]
+const FLUSH_INSTRUCTION: Option<&str> = Some("dc civac");
+
+#[cfg(all(target_arch = "aarch64", target_os = "linux"))]
+fn flush_lines(start: usize, end: usize) {
+    use std::arch::asm;
+    let ctr: u64;
+    // SAFETY: Linux lets user code read CTR_EL0 and clean and invalidate by virtual address
+    unsafe { asm!("mrs {}, ctr_el0", out(reg) ctr) };
+    let line = 4 << ((ctr >> 16) & 0xf); // DminLine: log2 of the smallest data line, in words
+    for address in (start / line * line..end).step_by(line) {
+        // SAFETY: every line holds at least one byte of the slice, so it is mapped
+        unsafe { asm!("dc civac, {}", in(reg) address) };
+    }
+    unsafe { asm!("dsb ish") };
+}
+
+#[cfg(not(any(target_arch = "x86_64", all(target_arch = "aarch64", target_os = "linux"))))]
+const FLUSH_INSTRUCTION: Option<&str> = None;
+
+#[cfg(not(any(target_arch = "x86_64", all(target_arch = "aarch64", target_os = "linux"))))]
+fn flush_lines(_: usize, _: usize) {
+    evict();
+}
+
+// Writes `data` back to memory and drops it from every cache level, so the next access to it
+// misses all the way to DRAM. A no-op unless --cold-cache was given
+pub fn flush<T>(data: &[T]) {
+    if cold() && !data.is_empty() {
+        let start = data.as_ptr() as usize;
+        flush_lines(start, start + size_of_val(data));
+    }
+}
+
+fn describe(cold: bool, instruction: Option<&str>, sweep_bytes: usize) -> String {
+    match (cold, instruction) {
+        (false, _) => "Cache: warm, each repetition finds the data the last one left in cache".to_string(),
+        (true, Some(instruction)) => format!("Cache: cold, measured buffers flushed with {} before every timed repetition", instruction),
+        (true, None) => format!("Cache: cold, {} MiB eviction sweep before every timed repetition", sweep_bytes >> 20),
+    }
+}
+
+// The stderr line the cache-aware benchmarks print
+pub fn report() -> String {
+    describe(cold(), FLUSH_INSTRUCTION, sweep_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sysfs_sizes() {
+        assert_eq!(parse_size("48K\n"), Some(48 << 10));
+        assert_eq!(parse_size("32M"), Some(32 << 20));
+        assert_eq!(parse_size("4096"), Some(4096));
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("K"), None);
+    }
+
+    #[test]
+    fn describes_the_mode() {
+        assert_eq!(describe(false, Some("clflush"), 0), "Cache: warm, each repetition finds the data the last one left in cache");
+        assert_eq!(describe(true, Some("dc civac"), 0), "Cache: cold, measured buffers flushed with dc civac before every timed repetition");
+        assert_eq!(describe(true, None, 64 << 20), "Cache: cold, 64 MiB eviction sweep before every timed repetition");
+    }
+
+    // The only test that enables it, since the setting is process-wide
+    #[test]
+    fn flushed_data_keeps_its_values() {
+        assert!(!enable(&["bench".to_string()]));
+        assert_eq!(repetitions(1000), 1000);
+        assert!(enable(&["bench".to_string(), "--cold-cache".to_string()]));
+        assert_eq!(repetitions(1000), COLD_REPETITIONS);
+        assert_eq!(repetitions(3), 3);
+        let data: Vec<u32> = (0..10_000).collect();
+        flush(&data[1..]);
+        flush(&data[..0]);
+        evict();
+        assert!(data.iter().enumerate().all(|(i, &x)| x == i as u32));
+        assert!(report().starts_with("Cache: cold"));
+    }
+}
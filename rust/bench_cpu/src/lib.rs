@@ -0,0 +1,145 @@
+// Which instructions the CPU offers, and which of them each kernel actually ran. The same source
+// hashes with SHA-NI on one machine and with software rounds on the next, and portable SIMD is
+// AVX-512 on one and NEON on another, so a time means little without both:
+//
+//     CPU features: avx2 fma bmi2 sha aes crc32 (x86_64; not available: avx512f)
+//     Code path: compress: hw (SHA-NI)
+//
+// report() lists the features detected at runtime, and path() formats one kernel's path as
+// scalar, simd or hw with what it ran on. build.py copies both lines into the benchmark's entry
+// in the results file. The benchmarks are built with target-cpu=native, so compile-time and
+// runtime features agree unless a binary is copied to another machine; simd_isa() names what the
+// compiler targeted, detect() what the CPU running it has.
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Path {
+    Scalar, // one element at a time in general-purpose registers
+    Simd,   // vector registers, whatever simd_isa() says they are
+    Hw,     // a dedicated instruction for the whole operation (SHA rounds, PEXT, CRC32)
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Path::Scalar => "scalar",
+            Path::Simd => "simd",
+            Path::Hw => "hw",
+        })
+    }
+}
+
+// Every feature this architecture's benchmarks might dispatch on, and whether the CPU has it
+#[cfg(target_arch = "x86_64")]
+pub fn detect() -> Vec<(&'static str, bool)> {
+    vec![
+        ("avx2", is_x86_feature_detected!("avx2")),
+        ("fma", is_x86_feature_detected!("fma")),
+        ("avx512f", is_x86_feature_detected!("avx512f")),
+        ("bmi2", is_x86_feature_detected!("bmi2")),
+        ("sha", is_x86_feature_detected!("sha")),
+        ("aes", is_x86_feature_detected!("aes")),
+        // The crc32 instruction is part of SSE4.2
+        ("crc32", is_x86_feature_detected!("sse4.2")),
+    ]
+}
+
+#[cfg(target_arch = "aarch64")]
+pub fn detect() -> Vec<(&'static str, bool)> {
+    use std::arch::is_aarch64_feature_detected;
+    vec![
+        ("neon", is_aarch64_feature_detected!("neon")),
+        ("sve", is_aarch64_feature_detected!("sve")),
+        ("sve2", is_aarch64_feature_detected!("sve2")),
+        ("sha2", is_aarch64_feature_detected!("sha2")),
+        ("aes", is_aarch64_feature_detected!("aes")),
+        ("crc", is_aarch64_feature_detected!("crc")),
+    ]
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn detect() -> Vec<(&'static str, bool)> {
+    Vec::new()
+}
+
+pub fn has(feature: &str) -> bool {
+    detect().iter().any(|&(name, present)| name == feature && present)
+}
+
+// The vector instructions portable SIMD (wide, GCC vector extensions) was compiled down to
+pub fn simd_isa() -> &'static str {
+    if cfg!(all(target_arch = "x86_64", target_feature = "avx512f")) {
+        "AVX-512"
+    } else if cfg!(all(target_arch = "x86_64", target_feature = "avx2")) {
+        "AVX2"
+    } else if cfg!(target_arch = "x86_64") {
+        "SSE2"
+    } else if cfg!(all(target_arch = "aarch64", target_feature = "neon")) {
+        "NEON"
+    } else if cfg!(all(target_arch = "wasm32", target_feature = "simd128")) {
+        "SIMD128"
+    } else {
+        "no vector unit, emulated"
+    }
+}
+
+fn describe(arch: &str, features: &[(&str, bool)]) -> String {
+    if features.is_empty() {
+        return format!("CPU features: not detected on {}", arch);
+    }
+    let names = |present: bool| -> Vec<&str> {
+        features.iter().filter(|&&(_, p)| p == present).map(|&(name, _)| name).collect()
+    };
+    let (available, missing) = (names(true), names(false));
+    let available = if available.is_empty() { "none".to_string() } else { available.join(" ") };
+    if missing.is_empty() {
+        format!("CPU features: {} ({})", available, arch)
+    } else {
+        format!("CPU features: {} ({}; not available: {})", available, arch, missing.join(" "))
+    }
+}
+
+// The stderr line listing the runtime-detected features
+pub fn report() -> String {
+    describe(std::env::consts::ARCH, &detect())
+}
+
+// The stderr line for one kernel's code path, e.g. path("compress", Path::Hw, "SHA-NI")
+pub fn path(kernel: &str, path: Path, detail: &str) -> String {
+    format!("Code path: {}: {} ({})", kernel, path, detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_the_features() {
+        let features = [("avx2", true), ("avx512f", false), ("sha", true), ("aes", false)];
+        assert_eq!(describe("x86_64", &features), "CPU features: avx2 sha (x86_64; not available: avx512f aes)");
+        assert_eq!(describe("aarch64", &[("neon", true)]), "CPU features: neon (aarch64)");
+        assert_eq!(describe("aarch64", &[("sve", false)]), "CPU features: none (aarch64; not available: sve)");
+        assert_eq!(describe("riscv64", &[]), "CPU features: not detected on riscv64");
+    }
+
+    #[test]
+    fn formats_paths() {
+        assert_eq!(path("compress", Path::Hw, "SHA-NI"), "Code path: compress: hw (SHA-NI)");
+        assert_eq!(path("escape", Path::Simd, "f64x4 on AVX2"), "Code path: escape: simd (f64x4 on AVX2)");
+        assert_eq!(path("pext", Path::Scalar, "software loop"), "Code path: pext: scalar (software loop)");
+    }
+
+    // target-cpu=native compiles in only what the build machine has, which is also what it detects
+    #[test]
+    fn compiled_features_are_detected() {
+        if cfg!(all(target_arch = "x86_64", target_feature = "avx2")) {
+            assert!(has("avx2"));
+        }
+        if cfg!(all(target_arch = "aarch64", target_feature = "neon")) {
+            assert!(has("neon"));
+        }
+        assert!(!has("not-a-feature"));
+        assert!(report().starts_with("CPU features: "));
+    }
+}
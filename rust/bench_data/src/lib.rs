@@ -0,0 +1,151 @@
+// Generated benchmark inputs, cached across runs and shared between the languages. Writing a
+// multi-GB input takes longer than the benchmark takes to read it, so each dataset is generated
+// once per key and every later run, in Rust or C++, opens the same file:
+//
+//     <cache>/<name>/<bytes>-<seed>-v<version>/data
+//
+// The key holds everything the bytes depend on: the dataset's name, its target size, the seed,
+// and a version the benchmark bumps whenever its generator changes. cpp/src/bench_data.h builds
+// the same key, so whichever language runs first writes the file and the other reads it byte
+// for byte. The cache is BENCH_DATA_DIR, or rust-vs-cpp-bench under the user's cache directory.
+//
+// The generator writes to a temporary name that is renamed into place, and only then is the
+// manifest beside it written with the size and FNV-1a digest. A file without a matching
+// manifest, such as one from an interrupted run, is generated again.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+pub struct Dataset {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub digest: u64,
+    pub generated: bool, // false when an earlier run in either language wrote it
+}
+
+impl Dataset {
+    // The stderr line, so runs can be checked to have read the same bytes
+    pub fn describe(&self) -> String {
+        format!("Input: {} {} ({:.2} GB, FNV-1a {:016x})", if self.generated { "generated" } else { "cached" },
+                self.path.display(), self.bytes as f64 / 1e9, self.digest)
+    }
+}
+
+pub fn cache_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("BENCH_DATA_DIR") {
+        return PathBuf::from(dir);
+    }
+    let base = std::env::var_os("XDG_CACHE_HOME").map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("rust-vs-cpp-bench")
+}
+
+pub fn key(name: &str, target_bytes: u64, seed: u64, version: u32) -> String {
+    format!("{}/{}-{:016x}-v{}", name, target_bytes, seed, version)
+}
+
+fn fnv1a(reader: impl Read) -> io::Result<u64> {
+    let mut hash = 0xcbf29ce484222325u64;
+    let mut reader = BufReader::with_capacity(1 << 20, reader);
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            return Ok(hash);
+        }
+        for &byte in &buffer[..n] {
+            hash = (hash ^ byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+// "bytes 2147483700\nfnv1a64 0123456789abcdef\n"
+fn read_manifest(path: &Path) -> Option<(u64, u64)> {
+    let text = fs::read_to_string(path).ok()?;
+    let field = |name: &str| text.lines().find_map(|l| l.strip_prefix(name)?.strip_prefix(' '));
+    Some((field("bytes")?.parse().ok()?, u64::from_str_radix(field("fnv1a64")?, 16).ok()?))
+}
+
+// The dataset under `key` in the cache, calling generate(path) to write it first if no earlier
+// run did
+pub fn cached(name: &str, target_bytes: u64, seed: u64, version: u32,
+              generate: impl FnOnce(&Path) -> io::Result<()>) -> io::Result<Dataset> {
+    cached_in(&cache_dir(), name, target_bytes, seed, version, generate)
+}
+
+fn cached_in(root: &Path, name: &str, target_bytes: u64, seed: u64, version: u32,
+             generate: impl FnOnce(&Path) -> io::Result<()>) -> io::Result<Dataset> {
+    let dir = root.join(key(name, target_bytes, seed, version));
+    let path = dir.join("data");
+    let manifest = dir.join("manifest");
+    if let Some((bytes, digest)) = read_manifest(&manifest) {
+        if fs::metadata(&path).map(|m| m.len()).ok() == Some(bytes) {
+            return Ok(Dataset { path, bytes, digest, generated: false });
+        }
+    }
+
+    fs::create_dir_all(&dir)?;
+    // Per-process names, so two runs generating at once each rename a complete file
+    let partial = dir.join(format!("data.tmp-{}", std::process::id()));
+    generate(&partial)?;
+    let digest = fnv1a(File::open(&partial)?)?;
+    let bytes = fs::metadata(&partial)?.len();
+    fs::rename(&partial, &path)?;
+    let partial_manifest = dir.join(format!("manifest.tmp-{}", std::process::id()));
+    fs::write(&partial_manifest, format!("bytes {}\nfnv1a64 {:016x}\n", bytes, digest))?;
+    fs::rename(&partial_manifest, &manifest)?;
+    Ok(Dataset { path, bytes, digest, generated: true })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bench_data_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn keys_name_every_input_of_the_generator() {
+        assert_eq!(key("jsonl_stream", 2 << 30, 424242, 1), "jsonl_stream/2147483648-0000000000067932-v1");
+        assert_eq!(fnv1a(&b""[..]).unwrap(), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(&b"a"[..]).unwrap(), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn generates_once_then_reuses() {
+        let root = scratch("reuse");
+        let calls = std::cell::Cell::new(0);
+        let write = |path: &Path| {
+            calls.set(calls.get() + 1);
+            fs::write(path, b"hello")
+        };
+        let first = cached_in(&root, "greeting", 5, 7, 1, write).unwrap();
+        let second = cached_in(&root, "greeting", 5, 7, 1, write).unwrap();
+        assert_eq!(calls.get(), 1);
+        assert!(first.generated && !second.generated);
+        assert_eq!((second.path.clone(), second.bytes, second.digest), (first.path, 5, fnv1a(&b"hello"[..]).unwrap()));
+        assert_eq!(fs::read(&second.path).unwrap(), b"hello");
+
+        // Another seed is another dataset
+        let other = cached_in(&root, "greeting", 5, 8, 1, write).unwrap();
+        assert!(other.generated);
+        assert_eq!(calls.get(), 2);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn regenerates_a_file_that_does_not_match_its_manifest() {
+        let root = scratch("truncated");
+        let dataset = cached_in(&root, "numbers", 10, 1, 1, |path| fs::write(path, b"0123456789")).unwrap();
+        fs::write(&dataset.path, b"01234").unwrap();
+        let again = cached_in(&root, "numbers", 10, 1, 1, |path| fs::write(path, b"0123456789")).unwrap();
+        assert!(again.generated);
+        assert_eq!(fs::read(&again.path).unwrap(), b"0123456789");
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
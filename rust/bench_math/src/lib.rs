@@ -0,0 +1,267 @@
+// 3D vectors for the ray tracer, SDF ray marcher and Barnes-Hut simulation. Every operation is
+// written out per component in the order the benchmarks' own copies used, so moving them here
+// left every checksum unchanged.
+
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// The scalars Vec3 is generic over: f64 everywhere, and f32 for barnes_hut --precision f32
+pub trait Real:
+    Copy
+    + Send
+    + Sync
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const MAX: Self;
+    const MIN: Self;
+    fn from_f64(x: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+}
+
+macro_rules! impl_real {
+    ($t:ident) => {
+        impl Real for $t {
+            const MAX: Self = $t::MAX;
+            const MIN: Self = $t::MIN;
+            fn from_f64(x: f64) -> Self {
+                x as $t
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            fn sqrt(self) -> Self {
+                $t::sqrt(self)
+            }
+            fn abs(self) -> Self {
+                $t::abs(self)
+            }
+            fn min(self, other: Self) -> Self {
+                $t::min(self, other)
+            }
+            fn max(self, other: Self) -> Self {
+                $t::max(self, other)
+            }
+        }
+    };
+}
+
+impl_real!(f64);
+impl_real!(f32);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vec3<T = f64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Real> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Vec3 { x, y, z }
+    }
+
+    pub fn splat(v: T) -> Self {
+        Vec3::new(v, v, v)
+    }
+
+    pub fn zero() -> Self {
+        Vec3::splat(T::from_f64(0.0))
+    }
+
+    // Through f64, so f32 -> f64 is exact and f64 -> f32 rounds once
+    pub fn convert<U: Real>(&self) -> Vec3<U> {
+        Vec3::new(U::from_f64(self.x.to_f64()), U::from_f64(self.y.to_f64()), U::from_f64(self.z.to_f64()))
+    }
+
+    pub fn map(&self, f: impl Fn(T) -> T) -> Self {
+        Vec3::new(f(self.x), f(self.y), f(self.z))
+    }
+
+    pub fn dot(&self, other: &Vec3<T>) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length(&self) -> T {
+        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+
+    pub fn normalize(&self) -> Vec3<T> {
+        let len = self.length();
+        Vec3::new(self.x / len, self.y / len, self.z / len)
+    }
+
+    // Mirror image of a direction about the plane with unit normal `normal`
+    pub fn reflect(&self, normal: &Vec3<T>) -> Vec3<T> {
+        *self - *normal * (T::from_f64(2.0) * self.dot(normal))
+    }
+
+    pub fn min(&self, other: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+    }
+
+    pub fn max(&self, other: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+    }
+
+    pub fn axis(&self, axis: usize) -> T {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => self.z,
+        }
+    }
+}
+
+impl<T: Real> Add for Vec3<T> {
+    type Output = Vec3<T>;
+    fn add(self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Real> Sub for Vec3<T> {
+    type Output = Vec3<T>;
+    fn sub(self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Real> Mul<T> for Vec3<T> {
+    type Output = Vec3<T>;
+    fn mul(self, scalar: T) -> Vec3<T> {
+        Vec3::new(self.x * scalar, self.y * scalar, self.z * scalar)
+    }
+}
+
+// Component-wise, for colors
+impl<T: Real> Mul for Vec3<T> {
+    type Output = Vec3<T>;
+    fn mul(self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x * other.x, self.y * other.y, self.z * other.z)
+    }
+}
+
+impl<T: Real> Div<T> for Vec3<T> {
+    type Output = Vec3<T>;
+    fn div(self, scalar: T) -> Vec3<T> {
+        Vec3::new(self.x / scalar, self.y / scalar, self.z / scalar)
+    }
+}
+
+impl<T: Real> Neg for Vec3<T> {
+    type Output = Vec3<T>;
+    fn neg(self) -> Vec3<T> {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Real> AddAssign for Vec3<T> {
+    fn add_assign(&mut self, other: Vec3<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Real> SubAssign for Vec3<T> {
+    fn sub_assign(&mut self, other: Vec3<T>) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Real> MulAssign<T> for Vec3<T> {
+    fn mul_assign(&mut self, scalar: T) {
+        *self = *self * scalar;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64, z: f64) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    #[test]
+    fn arithmetic_is_per_component() {
+        let (a, b) = (v(1.0, 2.0, 3.0), v(4.0, -5.0, 0.5));
+        assert_eq!(a + b, v(5.0, -3.0, 3.5));
+        assert_eq!(a - b, v(-3.0, 7.0, 2.5));
+        assert_eq!(a * 2.0, v(2.0, 4.0, 6.0));
+        assert_eq!(a * b, v(4.0, -10.0, 1.5));
+        assert_eq!(b / 2.0, v(2.0, -2.5, 0.25));
+        assert_eq!(-a, v(-1.0, -2.0, -3.0));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        c *= 2.0;
+        assert_eq!(c, b * 2.0);
+    }
+
+    #[test]
+    fn cross_follows_the_right_hand_rule() {
+        let (x, y, z) = (v(1.0, 0.0, 0.0), v(0.0, 1.0, 0.0), v(0.0, 0.0, 1.0));
+        assert_eq!(x.cross(&y), z);
+        assert_eq!(y.cross(&z), x);
+        assert_eq!(z.cross(&x), y);
+        assert_eq!(y.cross(&x), -z);
+
+        let (a, b) = (v(1.0, 2.0, 3.0), v(-2.0, 0.5, 4.0));
+        let n = a.cross(&b);
+        assert_eq!(n.dot(&a), 0.0);
+        assert_eq!(n.dot(&b), 0.0);
+    }
+
+    #[test]
+    fn normalize_gives_unit_length() {
+        let n = v(3.0, 4.0, 12.0).normalize();
+        assert_eq!(v(3.0, 4.0, 12.0).length(), 13.0);
+        assert!((n.length() - 1.0).abs() < 1e-15);
+        assert!((n.x - 3.0 / 13.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn reflect_flips_the_normal_component() {
+        let normal = v(0.0, 1.0, 0.0);
+        assert_eq!(v(1.0, -1.0, 0.5).reflect(&normal), v(1.0, 1.0, 0.5));
+        assert_eq!(v(2.0, 0.0, -3.0).reflect(&normal), v(2.0, 0.0, -3.0)); // parallel to the plane
+        let d = v(0.3, -0.8, 0.2);
+        assert!((d.reflect(&normal).length() - d.length()).abs() < 1e-15);
+    }
+
+    #[test]
+    fn min_max_axis_and_map() {
+        let (a, b) = (v(1.0, 5.0, -2.0), v(3.0, -1.0, -2.5));
+        assert_eq!(a.min(&b), v(1.0, -1.0, -2.5));
+        assert_eq!(a.max(&b), v(3.0, 5.0, -2.0));
+        assert_eq!([a.axis(0), a.axis(1), a.axis(2)], [1.0, 5.0, -2.0]);
+        assert_eq!(b.map(f64::abs), v(3.0, 1.0, 2.5));
+        assert_eq!(Vec3::splat(2.0), v(2.0, 2.0, 2.0));
+        assert_eq!(Vec3::<f64>::zero(), v(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn convert_round_trips_through_f32() {
+        let a = Vec3::<f32>::new(0.1, -2.5, 1e-3);
+        let wide: Vec3<f64> = a.convert();
+        assert_eq!(wide.x, 0.1f32 as f64);
+        assert_eq!(wide.convert::<f32>(), a);
+        assert_eq!(Vec3::new(1.0f32, 2.0, 3.0).dot(&Vec3::new(4.0, 5.0, 6.0)), 32.0);
+    }
+}
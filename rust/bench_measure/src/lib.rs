@@ -0,0 +1,336 @@
+// What the number on stdout measures. Wall-clock time is the default, but it mixes the work with
+// whatever else the machine did meanwhile; the same timed regions can instead be measured as
+//
+//     BENCH_MEASURE=wall          elapsed time, in seconds
+//     BENCH_MEASURE=cpu           user + system time of every thread in the process, in seconds
+//     BENCH_MEASURE=instructions  retired user-space instructions (Linux perf_event)
+//     BENCH_MEASURE=gpu           time between the GPU's own timestamps around each pass, in seconds
+//
+// A benchmark brackets each timed region with start() and stop(), which add up over any number of
+// regions, and report() prints the total on stdout and the measure's name on stderr:
+//
+//     Measure: cpu (s)
+//
+// build.py checks that line, so a benchmark that still times itself is never mistaken for one
+// that measured what was asked. from_env() must run first thing in main: the instruction counter
+// only follows threads created after it opens, rayon's pool included. GPU time is what the GPU
+// reports, not what the CPU waits for, so only benchmarks that write timestamp queries
+// (from_env_with_gpu) accept it; they read the timestamps back and hand each pass to record_gpu().
+// A new source (energy, another perf counter) is one more Measurer and one more name in select().
+
+use std::time::Instant;
+
+pub trait Measurer {
+    fn name(&self) -> &'static str;
+    fn unit(&self) -> &'static str;
+    fn start(&mut self);
+    fn stop(&mut self); // adds the region since start() to the total
+    fn total(&self) -> f64;
+
+    // Whether the benchmark should write GPU timestamps and pass what they span to record_gpu()
+    fn wants_gpu(&self) -> bool {
+        false
+    }
+
+    fn record_gpu(&mut self, _seconds: f64) {}
+
+    fn report(&self) {
+        println!("{:.6}", self.total());
+        eprintln!("Measure: {} ({})", self.name(), self.unit());
+    }
+}
+
+#[derive(Default)]
+pub struct WallClock {
+    started: Option<Instant>,
+    total: f64,
+}
+
+impl Measurer for WallClock {
+    fn name(&self) -> &'static str {
+        "wall"
+    }
+
+    fn unit(&self) -> &'static str {
+        "s"
+    }
+
+    fn start(&mut self) {
+        self.started = Some(Instant::now());
+    }
+
+    fn stop(&mut self) {
+        if let Some(started) = self.started.take() {
+            self.total += started.elapsed().as_secs_f64();
+        }
+    }
+
+    fn total(&self) -> f64 {
+        self.total
+    }
+}
+
+// User plus system time of the whole process, so a parallel region counts every thread's share
+#[cfg(unix)]
+fn cpu_seconds() -> f64 {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) };
+    let seconds = |t: libc::timeval| t.tv_sec as f64 + t.tv_usec as f64 / 1e6;
+    seconds(usage.ru_utime) + seconds(usage.ru_stime)
+}
+
+#[cfg(unix)]
+#[derive(Default)]
+pub struct CpuTime {
+    started: Option<f64>,
+    total: f64,
+}
+
+#[cfg(unix)]
+impl Measurer for CpuTime {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn unit(&self) -> &'static str {
+        "s"
+    }
+
+    fn start(&mut self) {
+        self.started = Some(cpu_seconds());
+    }
+
+    fn stop(&mut self) {
+        if let Some(started) = self.started.take() {
+            self.total += cpu_seconds() - started;
+        }
+    }
+
+    fn total(&self) -> f64 {
+        self.total
+    }
+}
+
+// The first 64 bytes of struct perf_event_attr (PERF_ATTR_SIZE_VER0), which is all a plain
+// counter needs
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    kind: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    bp_addr: u64,
+}
+
+#[cfg(target_os = "linux")]
+pub struct Instructions {
+    fd: libc::c_int,
+    started: Option<u64>,
+    total: u64,
+}
+
+#[cfg(target_os = "linux")]
+impl Instructions {
+    // Counts from here on for this thread and every thread it creates afterwards (inherit), in
+    // user space only, which perf_event_paranoid up to 2 allows without privileges
+    pub fn open() -> Result<Instructions, String> {
+        const PERF_TYPE_HARDWARE: u32 = 0;
+        const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+        const INHERIT: u64 = 1 << 1;
+        const EXCLUDE_KERNEL: u64 = 1 << 5;
+        const EXCLUDE_HV: u64 = 1 << 6;
+        const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+        let attr = PerfEventAttr {
+            kind: PERF_TYPE_HARDWARE,
+            size: size_of::<PerfEventAttr>() as u32,
+            config: PERF_COUNT_HW_INSTRUCTIONS,
+            flags: INHERIT | EXCLUDE_KERNEL | EXCLUDE_HV,
+            ..Default::default()
+        };
+        let fd = unsafe { libc::syscall(libc::SYS_perf_event_open, &attr, 0, -1, -1, PERF_FLAG_FD_CLOEXEC) };
+        if fd < 0 {
+            return Err(format!("perf_event_open: {} (no hardware counters, as in many VMs, or kernel.perf_event_paranoid above 2)",
+                               std::io::Error::last_os_error()));
+        }
+        Ok(Instructions { fd: fd as libc::c_int, started: None, total: 0 })
+    }
+
+    // The inherited counters of live threads are summed in by the kernel on every read
+    fn read(&self) -> u64 {
+        let mut count = 0u64;
+        let n = unsafe { libc::read(self.fd, (&mut count as *mut u64).cast(), size_of::<u64>()) };
+        assert_eq!(n, size_of::<u64>() as isize, "reading the instruction counter failed");
+        count
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Measurer for Instructions {
+    fn name(&self) -> &'static str {
+        "instructions"
+    }
+
+    fn unit(&self) -> &'static str {
+        "instructions"
+    }
+
+    fn start(&mut self) {
+        self.started = Some(self.read());
+    }
+
+    fn stop(&mut self) {
+        if let Some(started) = self.started.take() {
+            self.total += self.read() - started;
+        }
+    }
+
+    fn total(&self) -> f64 {
+        self.total as f64
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for Instructions {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+#[derive(Default)]
+pub struct GpuTimestamps {
+    total: f64,
+    passes: usize,
+}
+
+impl Measurer for GpuTimestamps {
+    fn name(&self) -> &'static str {
+        "gpu"
+    }
+
+    fn unit(&self) -> &'static str {
+        "s"
+    }
+
+    // The CPU side of the region does not count; only the passes inside it do
+    fn start(&mut self) {}
+
+    fn stop(&mut self) {}
+
+    fn total(&self) -> f64 {
+        self.total
+    }
+
+    fn wants_gpu(&self) -> bool {
+        true
+    }
+
+    fn record_gpu(&mut self, seconds: f64) {
+        self.total += seconds;
+        self.passes += 1;
+    }
+
+    fn report(&self) {
+        assert!(self.passes > 0, "BENCH_MEASURE=gpu, but no GPU pass was timed");
+        println!("{:.6}", self.total);
+        eprintln!("Measure: gpu (s, {} passes)", self.passes);
+    }
+}
+
+pub const MEASURES: [&str; 4] = ["wall", "cpu", "instructions", "gpu"];
+
+// The measurer called `name`; "gpu" only for benchmarks that time their passes with timestamps
+pub fn select(name: &str, gpu: bool) -> Result<Box<dyn Measurer>, String> {
+    match name {
+        "wall" => Ok(Box::<WallClock>::default()),
+        #[cfg(unix)]
+        "cpu" => Ok(Box::<CpuTime>::default()),
+        #[cfg(target_os = "linux")]
+        "instructions" => Ok(Box::new(Instructions::open()?)),
+        "gpu" if gpu => Ok(Box::<GpuTimestamps>::default()),
+        "gpu" => Err("this benchmark runs no GPU passes to time".to_string()),
+        _ if MEASURES.contains(&name) => Err(format!("{} is not available on this platform", name)),
+        _ => Err(format!("unknown measure {} (one of {})", name, MEASURES.join(", "))),
+    }
+}
+
+fn from_env_inner(gpu: bool) -> Box<dyn Measurer> {
+    let name = std::env::var("BENCH_MEASURE").unwrap_or_else(|_| "wall".to_string());
+    select(&name, gpu).unwrap_or_else(|e| panic!("BENCH_MEASURE={}: {}", name, e))
+}
+
+pub fn from_env() -> Box<dyn Measurer> {
+    from_env_inner(false)
+}
+
+pub fn from_env_with_gpu() -> Box<dyn Measurer> {
+    from_env_inner(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spin(iterations: u64) -> u64 {
+        (0..iterations).fold(0u64, |acc, i| std::hint::black_box(acc.wrapping_mul(31).wrapping_add(i)))
+    }
+
+    #[test]
+    fn regions_add_up() {
+        let mut wall = WallClock::default();
+        wall.start();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        wall.stop();
+        let first = wall.total();
+        wall.stop(); // without a start, nothing is added
+        assert_eq!(wall.total(), first);
+        wall.start();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        wall.stop();
+        assert!(first >= 0.02 && wall.total() >= first + 0.02);
+    }
+
+    #[test]
+    fn cpu_time_skips_sleeping() {
+        let mut cpu = CpuTime::default();
+        cpu.start();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        cpu.stop();
+        assert!(cpu.total() < 0.1, "{}", cpu.total());
+        cpu.start();
+        spin(200_000_000);
+        cpu.stop();
+        assert!(cpu.total() > 0.0);
+    }
+
+    #[test]
+    fn gpu_is_only_offered_to_benchmarks_with_passes() {
+        assert!(select("gpu", false).is_err());
+        let mut gpu = select("gpu", true).unwrap();
+        assert!(gpu.wants_gpu());
+        gpu.start();
+        gpu.record_gpu(0.25);
+        gpu.record_gpu(0.5);
+        gpu.stop();
+        assert_eq!(gpu.total(), 0.75);
+        assert!(select("joules", false).err().unwrap().contains("unknown measure"));
+        assert_eq!(select("wall", false).unwrap().name(), "wall");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn instructions_count_the_work_between_start_and_stop() {
+        // Containers and VMs often give no PMU access; there is nothing to check then
+        let Ok(mut counter) = Instructions::open() else { return };
+        counter.start();
+        spin(1_000_000);
+        counter.stop();
+        assert!(counter.total() >= 1_000_000.0, "{}", counter.total());
+    }
+}
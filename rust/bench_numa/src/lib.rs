@@ -0,0 +1,216 @@
+// NUMA placement for the benchmarks whose numbers depend on where their pages land. On a
+// multi-socket machine an unpinned run puts threads and first-touched pages wherever the
+// scheduler happens to, so repeated runs differ by the cost of remote memory:
+//
+//     --numa-node N         run every thread on node N's CPUs and allocate from its memory
+//     --numa-memory-node M  allocate from node M instead (with --numa-node, measures remote access)
+//     --numa-interleave     spread pages round-robin over every node
+//
+// place() must run first thing in main, before any thread or large allocation exists: the CPU
+// mask and memory policy it sets are inherited by every thread created afterwards, rayon's pool
+// included. The placement goes through libnuma, linked with the numa feature; the topology is
+// read from sysfs and reported by every build.
+
+use std::fs;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Memory {
+    #[default]
+    FirstTouch, // the kernel's default: each page on the node of the CPU that first writes it
+    Node(usize),
+    Interleave,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Placement {
+    pub cpu_node: Option<usize>,
+    pub memory: Memory,
+}
+
+fn node_flag(args: &[String], name: &str) -> Option<usize> {
+    args.iter().position(|a| a == name).map(|i| {
+        args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| panic!("{} expects a node number", name))
+    })
+}
+
+impl Placement {
+    pub fn parse(args: &[String]) -> Placement {
+        let cpu_node = node_flag(args, "--numa-node");
+        let memory_node = node_flag(args, "--numa-memory-node");
+        let interleave = args.iter().any(|a| a == "--numa-interleave");
+        let memory = match (memory_node.or(cpu_node), interleave) {
+            (_, true) if memory_node.is_some() => panic!("--numa-memory-node and --numa-interleave are exclusive"),
+            (_, true) => Memory::Interleave,
+            (Some(node), false) => Memory::Node(node),
+            (None, false) => Memory::FirstTouch,
+        };
+        Placement { cpu_node, memory }
+    }
+
+    pub fn describe(&self) -> String {
+        let cpus = match self.cpu_node {
+            Some(node) => format!("CPUs on node {}", node),
+            None => "CPUs unpinned".to_string(),
+        };
+        let memory = match self.memory {
+            Memory::FirstTouch => "memory first-touch".to_string(),
+            Memory::Node(node) => format!("memory on node {}", node),
+            Memory::Interleave => "memory interleaved".to_string(),
+        };
+        format!("{}, {}", cpus, memory)
+    }
+}
+
+pub struct Node {
+    pub id: usize,
+    pub cpus: String, // the kernel's cpulist, e.g. "0-15,32-47"
+    pub memory_bytes: u64,
+}
+
+// Empty where sysfs has no node directory: macOS, WASM, and kernels built without NUMA
+pub fn topology() -> Vec<Node> {
+    let Ok(entries) = fs::read_dir("/sys/devices/system/node") else {
+        return Vec::new();
+    };
+    let mut nodes: Vec<Node> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let id = entry.file_name().to_str()?.strip_prefix("node")?.parse().ok()?;
+            let cpus = fs::read_to_string(entry.path().join("cpulist")).ok()?.trim().to_string();
+            // "Node 0 MemTotal:       65831300 kB"
+            let meminfo = fs::read_to_string(entry.path().join("meminfo")).ok()?;
+            let kilobytes: u64 = meminfo
+                .lines()
+                .find(|l| l.contains("MemTotal:"))
+                .and_then(|l| l.split_whitespace().rev().nth(1))
+                .and_then(|v| v.parse().ok())?;
+            Some(Node { id, cpus, memory_bytes: kilobytes * 1024 })
+        })
+        .collect();
+    nodes.sort_by_key(|node| node.id);
+    nodes
+}
+
+pub fn describe_topology(nodes: &[Node]) -> String {
+    if nodes.is_empty() {
+        return "topology not reported by this OS".to_string();
+    }
+    let list: Vec<String> = nodes
+        .iter()
+        .map(|n| format!("{}: CPUs {}, {:.1} GB", n.id, n.cpus, n.memory_bytes as f64 / 1e9))
+        .collect();
+    format!("{} node{} ({})", nodes.len(), if nodes.len() == 1 { "" } else { "s" }, list.join("; "))
+}
+
+// Parses the NUMA flags from `args` and applies them to the calling thread, which every thread
+// it spawns later inherits
+pub fn place(args: &[String]) -> Placement {
+    let placement = Placement::parse(args);
+    if placement != Placement::default() {
+        bind(&placement);
+    }
+    placement
+}
+
+// The stderr line the NUMA-aware benchmarks print
+pub fn report(placement: &Placement) -> String {
+    format!("NUMA: {}; {}", describe_topology(&topology()), placement.describe())
+}
+
+#[cfg(feature = "numa")]
+mod ffi {
+    use std::ffi::{c_int, c_uint};
+
+    #[repr(C)]
+    pub struct Bitmask {
+        _private: [u8; 0],
+    }
+
+    #[link(name = "numa")]
+    extern "C" {
+        pub static numa_all_nodes_ptr: *mut Bitmask;
+        pub fn numa_available() -> c_int;
+        pub fn numa_max_node() -> c_int;
+        pub fn numa_run_on_node(node: c_int) -> c_int;
+        pub fn numa_allocate_nodemask() -> *mut Bitmask;
+        pub fn numa_bitmask_setbit(mask: *mut Bitmask, bit: c_uint) -> *mut Bitmask;
+        pub fn numa_bitmask_free(mask: *mut Bitmask);
+        pub fn numa_set_membind(mask: *mut Bitmask);
+        pub fn numa_set_interleave_mask(mask: *mut Bitmask);
+    }
+}
+
+#[cfg(feature = "numa")]
+fn bind(placement: &Placement) {
+    use ffi::*;
+    unsafe {
+        assert!(numa_available() >= 0, "libnuma reports no NUMA support on this system");
+        let max_node = numa_max_node() as usize;
+        let memory_node = match placement.memory {
+            Memory::Node(node) => Some(node),
+            _ => None,
+        };
+        for node in placement.cpu_node.into_iter().chain(memory_node) {
+            assert!(node <= max_node, "NUMA node {} does not exist (nodes 0-{})", node, max_node);
+        }
+        if let Some(node) = placement.cpu_node {
+            assert!(numa_run_on_node(node as _) == 0, "numa_run_on_node({}) failed", node);
+        }
+        match placement.memory {
+            Memory::FirstTouch => {}
+            Memory::Node(node) => {
+                // Strict: allocations fail rather than spill onto another node
+                let mask = numa_allocate_nodemask();
+                numa_bitmask_setbit(mask, node as _);
+                numa_set_membind(mask);
+                numa_bitmask_free(mask);
+            }
+            Memory::Interleave => numa_set_interleave_mask(numa_all_nodes_ptr),
+        }
+    }
+}
+
+#[cfg(not(feature = "numa"))]
+fn bind(_: &Placement) {
+    panic!("--numa-node, --numa-memory-node and --numa-interleave need a build with `--features numa` (links libnuma)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_placements() {
+        assert_eq!(Placement::parse(&args("bench")), Placement::default());
+        let node = Placement::parse(&args("bench --numa-node 1"));
+        assert_eq!(node, Placement { cpu_node: Some(1), memory: Memory::Node(1) });
+        let remote = Placement::parse(&args("bench --numa-node 0 --numa-memory-node 1"));
+        assert_eq!(remote, Placement { cpu_node: Some(0), memory: Memory::Node(1) });
+        let interleaved = Placement::parse(&args("bench --numa-node 0 --numa-interleave"));
+        assert_eq!(interleaved, Placement { cpu_node: Some(0), memory: Memory::Interleave });
+        assert_eq!(remote.describe(), "CPUs on node 0, memory on node 1");
+        assert_eq!(Placement::default().describe(), "CPUs unpinned, memory first-touch");
+    }
+
+    #[test]
+    #[should_panic(expected = "exclusive")]
+    fn rejects_a_memory_node_with_interleave() {
+        Placement::parse(&args("bench --numa-memory-node 1 --numa-interleave"));
+    }
+
+    #[test]
+    fn describes_topology() {
+        let nodes = [
+            Node { id: 0, cpus: "0-15".to_string(), memory_bytes: 64_000_000_000 },
+            Node { id: 1, cpus: "16-31".to_string(), memory_bytes: 32_000_000_000 },
+        ];
+        assert_eq!(describe_topology(&nodes), "2 nodes (0: CPUs 0-15, 64.0 GB; 1: CPUs 16-31, 32.0 GB)");
+        assert_eq!(describe_topology(&[]), "topology not reported by this OS");
+    }
+}
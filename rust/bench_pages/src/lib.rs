@@ -0,0 +1,157 @@
+// Huge pages for the benchmarks' largest buffers. A few hundred megabytes of 4 KiB pages needs
+// tens of thousands of TLB entries, far more than any TLB holds, so page walks become part of
+// what is measured; 2 MiB pages cover the same buffer with 512 times fewer:
+//
+//     --huge-pages  back the big buffers with transparent huge pages (madvise(MADV_HUGEPAGE))
+//
+// enable() must run in main before the buffers exist, which come from with_capacity() or
+// filled(). Advising is only a request: whether the kernel found free 2 MiB pages is decided
+// when each one is first touched, so report() counts the huge-page faults /proc/vmstat recorded
+// since enable(). macOS offers no transparent superpages for heap memory (and Apple Silicon
+// none at all), so there the flag is reported as not honored.
+
+use std::fs;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+const HUGE_PAGE: usize = 2 * 1024 * 1024;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+static ADVISED_BYTES: AtomicU64 = AtomicU64::new(0);
+static FAULTS_AT_START: OnceLock<Option<Faults>> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Faults {
+    huge: u64,     // thp_fault_alloc: faults that got a huge page
+    fallback: u64, // thp_fault_fallback: faults in advised memory that fell back to 4 KiB pages
+}
+
+// System-wide counters, so a run next to another process faulting in huge pages over-reports
+fn read_faults() -> Option<Faults> {
+    let vmstat = fs::read_to_string("/proc/vmstat").ok()?;
+    let counter = |name: &str| {
+        vmstat
+            .lines()
+            .find_map(|l| l.strip_prefix(name)?.strip_prefix(' ')?.parse().ok())
+    };
+    Some(Faults { huge: counter("thp_fault_alloc")?, fallback: counter("thp_fault_fallback")? })
+}
+
+// The bracketed choice in "always [madvise] never"; only "never" ignores the advice
+fn thp_mode(setting: &str) -> Option<&str> {
+    setting.split_whitespace().find_map(|s| s.strip_prefix('[')?.strip_suffix(']'))
+}
+
+// Parses --huge-pages from `args` and, if present, starts advising every buffer allocated
+// through this crate from then on
+pub fn enable(args: &[String]) -> bool {
+    let requested = args.iter().any(|a| a == "--huge-pages");
+    if requested {
+        FAULTS_AT_START.get_or_init(read_faults);
+        REQUESTED.store(true, Ordering::Relaxed);
+    }
+    requested
+}
+
+#[cfg(target_os = "linux")]
+fn advise(start: *mut u8, bytes: usize) -> bool {
+    // madvise wants page-aligned bounds; the kernel then uses huge pages for every 2 MiB-aligned
+    // extent inside them, so all but the ragged ends of a large buffer qualify
+    let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let begin = (start as usize).next_multiple_of(page);
+    let end = (start as usize + bytes) / page * page;
+    end > begin && unsafe { libc::madvise(begin as *mut libc::c_void, end - begin, libc::MADV_HUGEPAGE) } == 0
+}
+
+#[cfg(not(target_os = "linux"))]
+fn advise(_: *mut u8, _: usize) -> bool {
+    false
+}
+
+// An empty Vec with room for `len` elements, advised for huge pages when they were requested.
+// Fill it by pushing or extending: the pages are only backed once written
+pub fn with_capacity<T>(len: usize) -> Vec<T> {
+    let mut buffer: Vec<T> = Vec::with_capacity(len);
+    let bytes = buffer.capacity() * size_of::<T>();
+    if REQUESTED.load(Ordering::Relaxed) && bytes >= HUGE_PAGE && advise(buffer.as_mut_ptr().cast(), bytes) {
+        ADVISED_BYTES.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+    buffer
+}
+
+// vec![value; len], advised for huge pages when they were requested. Unrequested, it is exactly
+// vec![value; len], which zero-fills through calloc without touching the pages
+pub fn filled<T: Clone>(value: T, len: usize) -> Vec<T> {
+    if !REQUESTED.load(Ordering::Relaxed) {
+        return vec![value; len];
+    }
+    let mut buffer = with_capacity(len);
+    buffer.resize(len, value);
+    buffer
+}
+
+fn describe(requested: bool, thp: Option<&str>, advised_bytes: u64, faults: Option<Faults>) -> String {
+    let mode = thp.map(|m| format!(" (THP {})", m)).unwrap_or_default();
+    if !requested {
+        return format!("Huge pages: off{}", mode);
+    }
+    let Some(faults) = faults.filter(|_| thp.is_some()) else {
+        return "Huge pages: not honored, this OS has no transparent huge pages for heap memory".to_string();
+    };
+    format!(
+        "Huge pages: {}, {:.1} MB advised, {} huge-page faults, {} fallbacks to 4 KiB pages{}",
+        if faults.huge > 0 { "honored" } else { "not honored" },
+        advised_bytes as f64 / 1e6,
+        faults.huge,
+        faults.fallback,
+        mode
+    )
+}
+
+// The stderr line the huge-page-aware benchmarks print
+pub fn report() -> String {
+    let setting = fs::read_to_string("/sys/kernel/mm/transparent_hugepage/enabled").unwrap_or_default();
+    let faults = match (FAULTS_AT_START.get().copied().flatten(), read_faults()) {
+        (Some(start), Some(now)) => Some(Faults {
+            huge: now.huge.saturating_sub(start.huge),
+            fallback: now.fallback.saturating_sub(start.fallback),
+        }),
+        _ => None,
+    };
+    describe(REQUESTED.load(Ordering::Relaxed), thp_mode(&setting), ADVISED_BYTES.load(Ordering::Relaxed), faults)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_thp_mode() {
+        assert_eq!(thp_mode("always [madvise] never\n"), Some("madvise"));
+        assert_eq!(thp_mode("[always] madvise never"), Some("always"));
+        assert_eq!(thp_mode(""), None);
+    }
+
+    #[test]
+    fn describes_the_outcome() {
+        assert_eq!(describe(false, Some("madvise"), 0, None), "Huge pages: off (THP madvise)");
+        assert_eq!(describe(false, None, 0, None), "Huge pages: off");
+        let honored = describe(true, Some("madvise"), 400_000_000, Some(Faults { huge: 190, fallback: 1 }));
+        assert_eq!(honored, "Huge pages: honored, 400.0 MB advised, 190 huge-page faults, 1 fallbacks to 4 KiB pages (THP madvise)");
+        assert!(describe(true, Some("never"), 0, Some(Faults { huge: 0, fallback: 0 })).starts_with("Huge pages: not honored"));
+        assert!(describe(true, None, 0, None).contains("no transparent huge pages"));
+    }
+
+    // The only test that enables them, since the setting is process-wide
+    #[test]
+    fn advised_buffers_hold_their_values() {
+        assert!(!enable(&["bench".to_string()]));
+        assert!(enable(&["bench".to_string(), "--huge-pages".to_string()]));
+        let buffer = filled(7u64, 3 * HUGE_PAGE / 8);
+        assert!(buffer.iter().all(|&x| x == 7));
+        let mut extended: Vec<u32> = with_capacity(HUGE_PAGE);
+        extended.extend(0..HUGE_PAGE as u32);
+        assert_eq!(extended[12345], 12345);
+        assert!(report().starts_with("Huge pages: "));
+    }
+}
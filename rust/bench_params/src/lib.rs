@@ -0,0 +1,93 @@
+// Run-time values for the constants a scaling curve varies, such as a matrix's size or the
+// number of threads. A benchmark reads each one with its built-in default, and BENCH_PARAMS
+// overrides any of them for one run:
+//
+//     BENCH_PARAMS=size=2048,threads=4
+//
+// so sweep.py can run one binary across a whole grid. Once it has read them all the benchmark
+// calls check(), which rejects a name it never asked for rather than ignore a typo or a
+// parameter it does not have, and prints report() on stderr with the values the run used:
+//
+//     Params: size=2048 threads=4
+//
+// cpp/src/bench_params.h reads the same variable and prints the same line.
+
+pub struct Params {
+    given: Vec<(String, String)>,
+    taken: Vec<(&'static str, String)>,
+}
+
+impl Params {
+    // "name=value,name=value"; an empty string overrides nothing
+    pub fn parse(text: &str) -> Result<Params, String> {
+        let mut given = Vec::new();
+        for item in text.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let (name, value) = item.split_once('=').ok_or(format!("expected name=value, got {}", item))?;
+            given.push((name.trim().to_string(), value.trim().to_string()));
+        }
+        Ok(Params { given, taken: Vec::new() })
+    }
+
+    pub fn from_env() -> Params {
+        let text = std::env::var("BENCH_PARAMS").unwrap_or_default();
+        Params::parse(&text).unwrap_or_else(|e| panic!("BENCH_PARAMS: {}", e))
+    }
+
+    // The value given for `name`, or `default`; the last one given wins
+    pub fn usize(&mut self, name: &'static str, default: usize) -> usize {
+        let value = match self.given.iter().rev().find(|(given, _)| given == name) {
+            Some((_, value)) => value.parse().ok().filter(|&v| v > 0)
+                .unwrap_or_else(|| panic!("BENCH_PARAMS: {}={} is not a positive whole number", name, value)),
+            None => default,
+        };
+        self.taken.push((name, value.to_string()));
+        value
+    }
+
+    pub fn check(&self) {
+        if let Some((name, _)) = self.given.iter().find(|(given, _)| !self.taken.iter().any(|(taken, _)| taken == given)) {
+            let names: Vec<&str> = self.taken.iter().map(|(taken, _)| *taken).collect();
+            if names.is_empty() {
+                panic!("BENCH_PARAMS: this benchmark has no parameters, so not {}", name);
+            }
+            panic!("BENCH_PARAMS: this benchmark has no parameter {} (it takes {})", name, names.join(", "));
+        }
+    }
+
+    pub fn report(&self) -> String {
+        let values: Vec<String> = self.taken.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+        format!("Params: {}", values.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_some_and_defaults_the_rest() {
+        let mut params = Params::parse("size=2048, threads=4,size=512").unwrap();
+        assert_eq!(params.usize("size", 1024), 512);
+        assert_eq!(params.usize("threads", 8), 4);
+        assert_eq!(params.usize("block", 64), 64);
+        params.check();
+        assert_eq!(params.report(), "Params: size=512 threads=4 block=64");
+
+        let mut defaults = Params::parse("").unwrap();
+        assert_eq!(defaults.usize("size", 1024), 1024);
+        defaults.check();
+        assert_eq!(defaults.report(), "Params: size=1024");
+    }
+
+    #[test]
+    fn rejects_what_it_cannot_use() {
+        assert!(Params::parse("size").err().unwrap().contains("expected name=value"));
+        let mut params = Params::parse("size=1024,thread=4").unwrap();
+        params.usize("size", 1024);
+        params.usize("threads", 8);
+        let unknown = std::panic::catch_unwind(|| params.check()).err().unwrap();
+        assert!(unknown.downcast_ref::<String>().unwrap().contains("no parameter thread (it takes size, threads)"));
+        let zero = std::panic::catch_unwind(|| Params::parse("threads=0").unwrap().usize("threads", 8));
+        assert!(zero.is_err());
+    }
+}
@@ -0,0 +1,137 @@
+// Python bindings for the benchmark harness: bench_py.run() launches one of the binaries build.py
+// builds, the same way build.py does, and hands back its timings and stderr report as a dict
+// instead of printed text.
+//
+//     import bench_py, pandas
+//     df = pandas.DataFrame([bench_py.run("prime_sieve", lang=lang, runs=5, mode="wheel")
+//                            for lang in ("rust", "cpp")])
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// The repository root, where build.py leaves rust/target/release and cpp/build
+const DEFAULT_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../..");
+
+struct Output {
+    time: f64,
+    stderr: String,
+}
+
+fn executable(root: &Path, lang: &str, name: &str) -> PyResult<PathBuf> {
+    let path = match lang {
+        "rust" => root.join("rust/target/release").join(name),
+        "cpp" => root.join("cpp/build").join(name),
+        _ => return Err(PyValueError::new_err(format!("lang expects rust or cpp, got {:?}", lang))),
+    };
+    if !path.is_file() {
+        return Err(PyValueError::new_err(format!("no {} build of {} at {}; run build.py first", lang, name, path.display())));
+    }
+    Ok(path)
+}
+
+// Keyword arguments become the benchmark's flags: elements=1024 passes --elements 1024, with
+// underscores turned into dashes. True passes a bare flag (verify=True is --verify), False none
+fn flags(params: Option<&Bound<'_, PyDict>>) -> PyResult<Vec<String>> {
+    let mut args = Vec::new();
+    let Some(params) = params else {
+        return Ok(args);
+    };
+    for (key, value) in params.iter() {
+        let flag = format!("--{}", key.extract::<String>()?.replace('_', "-"));
+        if let Ok(switch) = value.downcast::<PyBool>() {
+            if switch.is_true() {
+                args.push(flag);
+            }
+            continue;
+        }
+        args.push(flag);
+        args.push(value.str()?.to_string());
+    }
+    Ok(args)
+}
+
+// One run, with the contract every benchmark follows: the time alone on stdout, details on stderr
+fn run_once(path: &Path, args: &[String]) -> Result<Output, String> {
+    let output = Command::new(path)
+        .args(args)
+        .output()
+        .map_err(|e| format!("failed to start {}: {}", path.display(), e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    if !output.status.success() {
+        return Err(format!("{} exited with {}: {}", path.display(), output.status, stderr.trim()));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let time = stdout.trim().parse::<f64>()
+        .map_err(|_| format!("{} printed {:?} instead of a time", path.display(), stdout.trim()))?;
+    Ok(Output { time, stderr })
+}
+
+// The unindented "Key: value" lines of a report. Indented lines continue the table above them
+// and stay in the raw text only
+fn details<'py>(py: Python<'py>, stderr: &str) -> PyResult<Bound<'py, PyDict>> {
+    let details = PyDict::new(py);
+    for line in stderr.lines().filter(|l| !l.starts_with(char::is_whitespace)) {
+        if let Some((key, value)) = line.split_once(": ") {
+            details.set_item(key, value.trim())?;
+        }
+    }
+    Ok(details)
+}
+
+/// run(name, lang="rust", runs=1, root=None, **params) -> dict
+///
+/// Runs the `lang` build of benchmark `name` `runs` times, passing `params` as command-line
+/// flags, and returns name, lang, args, times (seconds per run), mean, min, checksum (the value
+/// of the report's last line), details (its "Key: value" lines) and stderr (the last run's full
+/// report). `root` is the repository checkout; it defaults to the one this module was built from.
+#[pyfunction]
+#[pyo3(signature = (name, lang = "rust", runs = 1, root = None, **params))]
+fn run<'py>(
+    py: Python<'py>,
+    name: &str,
+    lang: &str,
+    runs: usize,
+    root: Option<PathBuf>,
+    params: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    if runs == 0 {
+        return Err(PyValueError::new_err("runs expects at least 1"));
+    }
+    let root = root.unwrap_or_else(|| PathBuf::from(DEFAULT_ROOT));
+    let path = executable(&root, lang, name)?;
+    let args = flags(params)?;
+
+    let mut times = Vec::with_capacity(runs);
+    let mut stderr = String::new();
+    for _ in 0..runs {
+        // Other Python threads keep running while the benchmark does
+        let output = py.allow_threads(|| run_once(&path, &args)).map_err(PyRuntimeError::new_err)?;
+        times.push(output.time);
+        stderr = output.stderr;
+    }
+    let checksum = stderr.lines().rev()
+        .find(|l| !l.trim().is_empty())
+        .and_then(|l| l.split_once(": "))
+        .map(|(_, value)| value.trim().to_string());
+
+    let result = PyDict::new(py);
+    result.set_item("name", name)?;
+    result.set_item("lang", lang)?;
+    result.set_item("args", args.as_slice())?;
+    result.set_item("mean", times.iter().sum::<f64>() / runs as f64)?;
+    result.set_item("min", times.iter().copied().fold(f64::INFINITY, f64::min))?;
+    result.set_item("times", times)?;
+    result.set_item("checksum", checksum)?;
+    result.set_item("details", details(py, &stderr)?)?;
+    result.set_item("stderr", stderr)?;
+    Ok(result)
+}
+
+#[pymodule]
+fn bench_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(run, m)?)?;
+    Ok(())
+}
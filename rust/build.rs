@@ -0,0 +1,30 @@
+// Builds the C shim used by the ffi_overhead benchmark and the C++ kernels used by ffi_kernels.
+// Cargo hands the resulting link flags to the bench_kernels library alone, so the kernels reach
+// ffi_kernels through it and ffi_overhead names the shim in its own #[link] attribute.
+fn main() {
+    // Neither FFI benchmark is part of the WASM build, which has no C toolchain to hand
+    if std::env::var("CARGO_CFG_TARGET_FAMILY").as_deref() == Ok("wasm") {
+        return;
+    }
+
+    println!("cargo:rerun-if-changed=src/ffi_shim.c");
+    cc::Build::new().file("src/ffi_shim.c").opt_level(3).compile("ffi_shim");
+
+    // Same flags as cpp/CMakeLists.txt, so these match the C++ harness's own build of them.
+    // rustc never fuses a * b + c into an FMA; with contraction on, the C++ mandelbrot would
+    // round differently and its iteration counts would not match
+    println!("cargo:rerun-if-changed=../cpp/src/kernels.cpp");
+    println!("cargo:rerun-if-changed=../cpp/src/bench_kernels.h");
+    // build.py sets the CPU flag when it builds for another target or CPU, as it does for CMake
+    println!("cargo:rerun-if-env-changed=TARGET_CPU_FLAGS");
+    let cpu = std::env::var("TARGET_CPU_FLAGS").unwrap_or_else(|_| "-march=native".to_string());
+    cc::Build::new()
+        .cpp(true)
+        .file("../cpp/src/kernels.cpp")
+        .opt_level(3)
+        .flag("-std=c++20")
+        .flag(&cpu)
+        .flag("-ffp-contract=off")
+        .define("NDEBUG", None)
+        .compile("cpp_kernels");
+}
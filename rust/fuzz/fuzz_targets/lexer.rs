@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+mod lexer {
+    include!("../../src/lexer.rs");
+
+    // Arbitrary bytes never fail to lex: the tokens come out in order, each non-empty and in
+    // bounds, and only whitespace falls between them
+    pub fn check(src: &[u8]) {
+        let mut tokens = Vec::new();
+        lex(src, &mut tokens);
+        let mut end = 0;
+        for token in &tokens {
+            let (start, len) = (token.start as usize, token.len as usize);
+            assert!(start >= end && len > 0 && start + len <= src.len());
+            assert!(src[end..start].iter().all(|&b| CLASSES[b as usize] == WS));
+            end = start + len;
+        }
+        assert!(src[end..].iter().all(|&b| CLASSES[b as usize] == WS));
+    }
+}
+
+fuzz_target!(|data: &[u8]| lexer::check(data));
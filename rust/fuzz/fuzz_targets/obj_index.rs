@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+mod ray_tracer {
+    include!("../../src/ray_tracer.rs");
+
+    // A face corner after the first two bytes, which give the vertex count so far: any index the
+    // parser accepts names an existing vertex
+    pub fn check(data: &[u8]) {
+        let [a, b, corner @ ..] = data else { return };
+        let Ok(corner) = std::str::from_utf8(corner) else { return };
+        let vertex_count = u16::from_le_bytes([*a, *b]) as usize;
+        if let Some(index) = obj_vertex_index(corner, vertex_count) {
+            assert!(index < vertex_count);
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| ray_tracer::check(data));
@@ -0,0 +1,33 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+mod qoi_codec {
+    include!("../../src/qoi_codec.rs");
+
+    // The first two bytes pick a small image size and the rest is the chunk stream, so every
+    // input gets past the header checks and into the op decoder
+    pub fn check(data: &[u8]) {
+        let [w, h, chunks @ ..] = data else { return };
+        let (width, height) = (1 + *w as u32 % 64, 1 + *h as u32 % 64);
+        let mut image = QOI_MAGIC.to_vec();
+        image.extend_from_slice(&width.to_be_bytes());
+        image.extend_from_slice(&height.to_be_bytes());
+        image.extend_from_slice(&[CHANNELS as u8, 0]);
+        image.extend_from_slice(chunks);
+        image.extend_from_slice(&QOI_PADDING);
+        let (decoded_width, decoded_height, pixels) = decode(&image);
+        assert_eq!((decoded_width, decoded_height), (width, height));
+        assert_eq!(pixels.len(), (width * height) as usize * CHANNELS);
+
+        // The same bytes as one row of RGBA pixels survive an encode and decode unchanged
+        let row = &chunks[..chunks.len() / CHANNELS * CHANNELS];
+        if !row.is_empty() {
+            let count = (row.len() / CHANNELS) as u32;
+            assert_eq!(decode(&encode(row, count, 1)), (count, 1, row.to_vec()));
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| qoi_codec::check(data));
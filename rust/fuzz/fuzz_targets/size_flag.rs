@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+mod sha256 {
+    include!("../../src/sha256.rs");
+
+    // --buffer-size values: anything accepted is a positive byte count that prints back to itself
+    pub fn check(data: &[u8]) {
+        let Ok(value) = std::str::from_utf8(data) else { return };
+        if let Some(size) = parse_size(value) {
+            assert!(size > 0);
+            assert_eq!(parse_size(&size.to_string()), Some(size));
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| sha256::check(data));
@@ -0,0 +1,76 @@
+#[allow(dead_code)]
+mod json_parse {
+    include!("../../src/json_parse.rs");
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        // Versions stay small enough that the digests' per-record sum of them cannot overflow
+        fn metadata(text: BoxedStrategy<String>) -> impl Strategy<Value = Metadata> {
+            let leaf = (text.clone(), text.clone(), 0..1_000_000u32)
+                .prop_map(|(created_at, updated_at, version)| Metadata { created_at, updated_at, version, parent: None });
+            leaf.prop_recursive(6, 12, 1, move |parent| {
+                (text.clone(), text.clone(), 0..1_000_000u32, parent).prop_map(|(created_at, updated_at, version, parent)| {
+                    Metadata { created_at, updated_at, version, parent: Some(Box::new(parent)) }
+                })
+            })
+        }
+
+        fn records(text: BoxedStrategy<String>, real: BoxedStrategy<f64>) -> impl Strategy<Value = Vec<Record>> {
+            let record = (
+                any::<u32>(),
+                text.clone(),
+                text.clone(),
+                any::<u32>(),
+                real.clone(),
+                any::<bool>(),
+                prop::option::of(text.clone()),
+                prop::option::of(text.clone()),
+                prop::option::of(real),
+                prop::collection::vec(text.clone(), 0..6),
+                metadata(text),
+            )
+                .prop_map(|(id, name, email, age, balance, is_active, nickname, phone, score, tags, metadata)| Record {
+                    id, name, email, age, balance, is_active, nickname, phone, score, tags, metadata,
+                });
+            prop::collection::vec(record, 0..20)
+        }
+
+        // JSON has no NaN or infinity, and serde_json's inexact float parsing is only bounded in
+        // ulps for normal numbers
+        fn any_real() -> BoxedStrategy<f64> {
+            (prop::num::f64::NORMAL | prop::num::f64::ZERO).boxed()
+        }
+
+        // Cents, like the generated balances: parsed exactly, so the digests' integer parts agree
+        fn cents() -> BoxedStrategy<f64> {
+            (0..100_000_000u64).prop_map(|c| c as f64 / 100.0).boxed()
+        }
+
+        proptest! {
+            // Any string, so escapes, quotes and non-ASCII text all go through both parsers
+            #[test]
+            fn round_trip_is_identity(records in records(any::<String>().boxed(), any_real())) {
+                let original = serde_json::to_value(&records).unwrap();
+                let json = serde_json::to_string(&records).unwrap();
+                let parsed: Vec<Record> = serde_json::from_str(&json).unwrap();
+                prop_assert!(same(&serde_json::to_value(&parsed).unwrap(), &original));
+                let simd: Vec<Record> = simd_json::serde::from_slice(&mut json.into_bytes()).unwrap();
+                prop_assert!(same(&serde_json::to_value(&simd).unwrap(), &original));
+            }
+
+            // Escape-free strings only: the borrowed records cannot point into an escaped one
+            #[test]
+            fn parse_variants_agree(records in records("[a-zA-Z0-9 @._:-]{0,24}".boxed(), cents())) {
+                let json = serde_json::to_string(&records).unwrap();
+                let expected = owned_digest(&records);
+                prop_assert_eq!(owned_digest(&serde_json::from_str::<Vec<Record>>(&json).unwrap()), expected);
+                prop_assert_eq!(borrowed_digest(&serde_json::from_str::<Vec<BorrowedRecord>>(&json).unwrap()), expected);
+                prop_assert_eq!(value_digest(&serde_json::from_str(&json).unwrap()), expected);
+                let simd: Vec<Record> = simd_json::serde::from_slice(&mut json.into_bytes()).unwrap();
+                prop_assert_eq!(owned_digest(&simd), expected);
+            }
+        }
+    }
+}
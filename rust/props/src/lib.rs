@@ -0,0 +1,6 @@
+// Each module compiles one benchmark's source into itself with include!, so the properties can
+// call its private kernels directly, the way its own unit tests do.
+#![cfg(test)]
+
+mod json;
+mod sort;
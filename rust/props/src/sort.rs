@@ -0,0 +1,112 @@
+use proptest::prelude::*;
+
+// Random, duplicate-heavy, sorted and reversed inputs, long enough to get past the 10k
+// threshold where both quicksorts hand off to sort_unstable; the sorted and reversed ones
+// drive the last-element pivot into the depth limit and the heapsort fallback
+fn inputs() -> impl Strategy<Value = Vec<i32>> {
+    let len = 0..40_000usize;
+    prop_oneof![
+        prop::collection::vec(any::<i32>(), len.clone()),
+        prop::collection::vec(-8..8i32, len.clone()),
+        prop::collection::vec(any::<i32>(), len.clone()).prop_map(|mut v| {
+            v.sort_unstable();
+            v
+        }),
+        prop::collection::vec(any::<i32>(), len).prop_map(|mut v| {
+            v.sort_unstable_by(|a, b| b.cmp(a));
+            v
+        }),
+    ]
+}
+
+fn is_sorted(arr: &[i32]) -> bool {
+    arr.windows(2).all(|w| w[0] <= w[1])
+}
+
+fn is_permutation(a: &[i32], b: &[i32]) -> bool {
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    a.sort_unstable();
+    b.sort_unstable();
+    a == b
+}
+
+// Everything left of the pivot's final slot is <= it, everything right is >, and nothing was
+// lost or duplicated
+fn check_partition(original: &[i32], arr: &[i32], p: usize) -> Result<(), TestCaseError> {
+    let pivot = *original.last().unwrap();
+    prop_assert_eq!(arr[p], pivot);
+    prop_assert!(arr[..p].iter().all(|&x| x <= pivot));
+    prop_assert!(arr[p + 1..].iter().all(|&x| x > pivot));
+    prop_assert!(is_permutation(original, arr));
+    Ok(())
+}
+
+#[allow(dead_code)]
+mod parallel_quicksort {
+    include!("../../src/parallel_quicksort.rs");
+
+    mod properties {
+        use super::super::{check_partition, inputs, is_permutation, is_sorted};
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(64))]
+
+            #[test]
+            fn sort_is_an_ordered_permutation(input in inputs(), depth_limit in 0..48usize) {
+                let mut arr = input.clone();
+                let stats = Stats { depth_limit, max_depth: AtomicUsize::new(0), heapsorted: AtomicUsize::new(0) };
+                parallel_quicksort(&mut arr, 0, &stats);
+                prop_assert!(is_sorted(&arr));
+                prop_assert!(is_permutation(&input, &arr));
+                prop_assert!(stats.max_depth.load(Ordering::Relaxed) <= depth_limit);
+            }
+
+            #[test]
+            fn heapsort_is_an_ordered_permutation(input in prop::collection::vec(any::<i32>(), 0..2_000)) {
+                let mut arr = input.clone();
+                heapsort(&mut arr);
+                prop_assert!(is_sorted(&arr));
+                prop_assert!(is_permutation(&input, &arr));
+            }
+
+            #[test]
+            fn partition_splits_around_the_pivot(input in prop::collection::vec(-50..50i32, 1..2_000)) {
+                let mut arr = input.clone();
+                let p = partition(&mut arr);
+                check_partition(&input, &arr, p)?;
+            }
+        }
+    }
+}
+
+#[allow(dead_code)]
+mod kernels {
+    include!("../../src/kernels.rs");
+
+    mod properties {
+        use super::super::{check_partition, inputs, is_permutation, is_sorted};
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(64))]
+
+            #[test]
+            fn sort_is_an_ordered_permutation(input in inputs(), depth in 0..48usize) {
+                let mut arr = input.clone();
+                quicksort(&mut arr, depth);
+                prop_assert!(is_sorted(&arr));
+                prop_assert!(is_permutation(&input, &arr));
+            }
+
+            #[test]
+            fn partition_splits_around_the_pivot(input in prop::collection::vec(any::<i32>(), 1..2_000)) {
+                let mut arr = input.clone();
+                let p = partition(&mut arr);
+                check_partition(&input, &arr, p)?;
+            }
+        }
+    }
+}
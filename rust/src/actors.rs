@@ -0,0 +1,306 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc as async_mpsc;
+
+const RING_SIZE: usize = 503;
+const HOPS: u32 = 2_000_000; // token passes around the ring
+const MEETINGS: u32 = 200_000; // per chameneos game
+const NUM_WORKERS: usize = 8; // tokio worker threads
+const WARMUP_HOPS: u32 = 10_000;
+const WARMUP_MEETINGS: u32 = 1_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Color {
+    Blue,
+    Red,
+    Yellow,
+}
+
+// The Benchmarks Game's two chameneos games
+const GAMES: [&[Color]; 2] = [
+    &[Color::Blue, Color::Red, Color::Yellow],
+    &[Color::Blue, Color::Red, Color::Yellow, Color::Red, Color::Yellow, Color::Blue, Color::Red, Color::Yellow,
+      Color::Red, Color::Blue],
+];
+
+// Two creatures that meet both take the color neither of them had, or keep theirs if they matched
+fn complement(a: Color, b: Color) -> Color {
+    match (a, b) {
+        _ if a == b => a,
+        (Color::Blue, Color::Red) | (Color::Red, Color::Blue) => Color::Yellow,
+        (Color::Blue, Color::Yellow) | (Color::Yellow, Color::Blue) => Color::Red,
+        _ => Color::Blue,
+    }
+}
+
+// What the meeting place does with a creature's request: wait for a partner, pair it, or turn it
+// away once every meeting has happened. Shared by both runtimes
+struct Mall<R> {
+    meetings_left: u32,
+    waiting: Option<(Color, R)>,
+}
+
+enum Visit<R> {
+    Wait,
+    Meet((Color, R), (Color, R)), // each creature's reply handle with the color of its partner
+    Faded(R),
+}
+
+impl<R> Mall<R> {
+    fn new(meetings: u32) -> Self {
+        Mall { meetings_left: meetings, waiting: None }
+    }
+
+    fn visit(&mut self, color: Color, reply: R) -> Visit<R> {
+        if self.meetings_left == 0 {
+            return Visit::Faded(reply);
+        }
+        match self.waiting.take() {
+            None => {
+                self.waiting = Some((color, reply));
+                Visit::Wait
+            }
+            Some((first_color, first)) => {
+                self.meetings_left -= 1;
+                Visit::Meet((color, first), (first_color, reply))
+            }
+        }
+    }
+}
+
+// Thread ring, one OS thread per actor blocked on its std channel. The actor holding the token when
+// it reaches zero reports its 1-based id; when it returns, dropping its sender ends the next actor,
+// and so on around the ring
+fn ring_threads(hops: u32) -> usize {
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..RING_SIZE).map(|_| mpsc::channel::<u32>()).unzip();
+    let (done_tx, done_rx) = mpsc::channel();
+    thread::scope(|s| {
+        for (i, inbox) in receivers.into_iter().enumerate() {
+            let next = senders[(i + 1) % RING_SIZE].clone();
+            let done = done_tx.clone();
+            s.spawn(move || {
+                for token in inbox {
+                    if token == 0 {
+                        done.send(i + 1).unwrap();
+                        return;
+                    }
+                    let _ = next.send(token - 1);
+                }
+            });
+        }
+        senders[0].send(hops).unwrap();
+        drop(senders);
+        done_rx.recv().unwrap()
+    })
+}
+
+// The same ring as tokio tasks on unbounded channels
+fn ring_tasks(runtime: &tokio::runtime::Runtime, hops: u32) -> usize {
+    runtime.block_on(async {
+        let (senders, receivers): (Vec<_>, Vec<_>) = (0..RING_SIZE).map(|_| async_mpsc::unbounded_channel::<u32>()).unzip();
+        let (done_tx, mut done_rx) = async_mpsc::unbounded_channel();
+        for (i, mut inbox) in receivers.into_iter().enumerate() {
+            let next = senders[(i + 1) % RING_SIZE].clone();
+            let done = done_tx.clone();
+            tokio::spawn(async move {
+                while let Some(token) = inbox.recv().await {
+                    if token == 0 {
+                        done.send(i + 1).unwrap();
+                        return;
+                    }
+                    let _ = next.send(token - 1);
+                }
+            });
+        }
+        senders[0].send(hops).unwrap();
+        drop(senders);
+        done_rx.recv().await.unwrap()
+    })
+}
+
+// Chameneos-redux: every creature asks the mall for a partner until it is turned away, and the
+// meetings each one had add up to twice the total
+fn chameneos_threads(colors: &[Color], meetings: u32) -> u32 {
+    type Reply = mpsc::Sender<Option<Color>>;
+    let (mall_tx, mall_rx) = mpsc::channel::<(Color, Reply)>();
+    thread::scope(|s| {
+        let creatures = colors.len();
+        s.spawn(move || {
+            let mut mall = Mall::new(meetings);
+            let mut faded = 0;
+            for (color, reply) in mall_rx {
+                match mall.visit(color, reply) {
+                    Visit::Wait => {}
+                    Visit::Meet((a_partner, a), (b_partner, b)) => {
+                        let _ = a.send(Some(a_partner));
+                        let _ = b.send(Some(b_partner));
+                    }
+                    Visit::Faded(reply) => {
+                        let _ = reply.send(None);
+                        faded += 1;
+                        if faded == creatures {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+        let handles: Vec<_> = colors
+            .iter()
+            .map(|&start| {
+                let mall = mall_tx.clone();
+                s.spawn(move || {
+                    let (reply, inbox) = mpsc::channel();
+                    let (mut color, mut met) = (start, 0);
+                    loop {
+                        mall.send((color, reply.clone())).unwrap();
+                        match inbox.recv().unwrap() {
+                            Some(partner) => {
+                                color = complement(color, partner);
+                                met += 1;
+                            }
+                            None => return met,
+                        }
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).sum()
+    })
+}
+
+fn chameneos_tasks(runtime: &tokio::runtime::Runtime, colors: &[Color], meetings: u32) -> u32 {
+    type Reply = async_mpsc::UnboundedSender<Option<Color>>;
+    runtime.block_on(async {
+        let (mall_tx, mut mall_rx) = async_mpsc::unbounded_channel::<(Color, Reply)>();
+        let creatures = colors.len();
+        tokio::spawn(async move {
+            let mut mall = Mall::new(meetings);
+            let mut faded = 0;
+            while let Some((color, reply)) = mall_rx.recv().await {
+                match mall.visit(color, reply) {
+                    Visit::Wait => {}
+                    Visit::Meet((a_partner, a), (b_partner, b)) => {
+                        let _ = a.send(Some(a_partner));
+                        let _ = b.send(Some(b_partner));
+                    }
+                    Visit::Faded(reply) => {
+                        let _ = reply.send(None);
+                        faded += 1;
+                        if faded == creatures {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+        let handles: Vec<_> = colors
+            .iter()
+            .map(|&start| {
+                let mall = mall_tx.clone();
+                tokio::spawn(async move {
+                    let (reply, mut inbox) = async_mpsc::unbounded_channel();
+                    let (mut color, mut met) = (start, 0);
+                    loop {
+                        mall.send((color, reply.clone())).unwrap();
+                        match inbox.recv().await.unwrap() {
+                            Some(partner) => {
+                                color = complement(color, partner);
+                                met += 1;
+                            }
+                            None => return met,
+                        }
+                    }
+                })
+            })
+            .collect();
+        let mut total = 0;
+        for handle in handles {
+            total += handle.await.unwrap();
+        }
+        total
+    })
+}
+
+struct Timing {
+    ring: Duration,
+    chameneos: Duration,
+    holder: usize,
+    met: u32,
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let value = f();
+    (start.elapsed(), value)
+}
+
+fn run(ring: impl Fn(u32) -> usize, chameneos: impl Fn(&[Color], u32) -> u32) -> Timing {
+    ring(WARMUP_HOPS);
+    chameneos(GAMES[0], WARMUP_MEETINGS);
+    let (ring_time, holder) = timed(|| ring(HOPS));
+    let (chameneos_time, met) = timed(|| GAMES.iter().map(|game| chameneos(game, MEETINGS)).sum());
+    Timing { ring: ring_time, chameneos: chameneos_time, holder, met }
+}
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_multi_thread().worker_threads(NUM_WORKERS).build().unwrap();
+    let variants = [
+        ("threads + std mpsc", run(ring_threads, chameneos_threads)),
+        ("tokio tasks + mpsc", run(|hops| ring_tasks(&runtime, hops), |game, n| chameneos_tasks(&runtime, game, n))),
+    ];
+
+    // The Benchmarks Game answers: who holds the token at zero, and two meetings per meeting
+    let expected_holder = HOPS as usize % RING_SIZE + 1;
+    let expected_met = 2 * MEETINGS * GAMES.len() as u32;
+    for (name, t) in &variants {
+        if t.holder != expected_holder || t.met != expected_met {
+            eprintln!("{}: token ended at {} (expected {}), {} meetings (expected {})", name, t.holder, expected_holder,
+                      t.met, expected_met);
+            std::process::exit(1);
+        }
+    }
+
+    // The headline is the task runtime, the way most message-passing services are built
+    let headline = &variants[1].1;
+    println!("{:.6}", (headline.ring + headline.chameneos).as_secs_f64());
+    eprintln!("Thread ring: {} actors, {} hops. Chameneos: {} meetings each for {} and {} creatures", RING_SIZE, HOPS,
+              MEETINGS, GAMES[0].len(), GAMES[1].len());
+    for (name, t) in &variants {
+        eprintln!("  {:<22} ring {:7.1} ns/hop   chameneos {:7.1} ns/meeting", name,
+                  t.ring.as_secs_f64() * 1e9 / HOPS as f64,
+                  t.chameneos.as_secs_f64() * 1e9 / (MEETINGS as f64 * GAMES.len() as f64));
+    }
+    eprintln!("Checksum: {}", expected_holder as u64 + expected_met as u64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complement_follows_the_rules() {
+        assert_eq!(complement(Color::Blue, Color::Red), Color::Yellow);
+        assert_eq!(complement(Color::Yellow, Color::Blue), Color::Red);
+        assert_eq!(complement(Color::Red, Color::Yellow), Color::Blue);
+        assert_eq!(complement(Color::Red, Color::Red), Color::Red);
+    }
+
+    #[test]
+    fn mall_pairs_then_turns_creatures_away() {
+        let mut mall = Mall::new(1);
+        assert!(matches!(mall.visit(Color::Blue, 'a'), Visit::Wait));
+        assert!(matches!(mall.visit(Color::Red, 'b'), Visit::Meet((Color::Red, 'a'), (Color::Blue, 'b'))));
+        assert!(matches!(mall.visit(Color::Yellow, 'a'), Visit::Faded('a')));
+    }
+
+    #[test]
+    fn runtimes_agree_on_small_games() {
+        let runtime = tokio::runtime::Builder::new_multi_thread().worker_threads(2).build().unwrap();
+        assert_eq!(ring_threads(1_000), 1_000 % RING_SIZE + 1);
+        assert_eq!(ring_tasks(&runtime, 1_000), 1_000 % RING_SIZE + 1);
+        assert_eq!(chameneos_threads(GAMES[1], 500), 1_000);
+        assert_eq!(chameneos_tasks(&runtime, GAMES[1], 500), 1_000);
+    }
+}
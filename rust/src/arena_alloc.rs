@@ -0,0 +1,305 @@
+use bumpalo::Bump;
+use std::time::{Duration, Instant};
+
+const NODES_PER_ROUND: usize = 4_000_000;
+const NUM_ROUNDS: usize = 5;
+const MAX_DEPTH: u32 = 16;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Shape of the next node; every builder draws the same sequence, so all variants get identical trees
+enum Shape {
+    Num(i64),
+    Neg,
+    Add,
+    Sub,
+    Mul,
+}
+
+fn next_shape(seed: &mut u64, depth: u32) -> Shape {
+    let r = next_random(seed);
+    match r % 8 {
+        _ if depth == 0 => Shape::Num(((r >> 8) % 100) as i64),
+        0..=2 => Shape::Num(((r >> 8) % 100) as i64),
+        3 => Shape::Neg,
+        4 | 5 => Shape::Add,
+        6 => Shape::Sub,
+        _ => Shape::Mul,
+    }
+}
+
+// Variant 1: every node its own heap allocation
+enum BoxExpr {
+    Num(i64),
+    Neg(Box<BoxExpr>),
+    Add(Box<BoxExpr>, Box<BoxExpr>),
+    Sub(Box<BoxExpr>, Box<BoxExpr>),
+    Mul(Box<BoxExpr>, Box<BoxExpr>),
+}
+
+fn build_box(seed: &mut u64, depth: u32, count: &mut usize) -> Box<BoxExpr> {
+    *count += 1;
+    Box::new(match next_shape(seed, depth) {
+        Shape::Num(v) => BoxExpr::Num(v),
+        Shape::Neg => BoxExpr::Neg(build_box(seed, depth - 1, count)),
+        Shape::Add => BoxExpr::Add(build_box(seed, depth - 1, count), build_box(seed, depth - 1, count)),
+        Shape::Sub => BoxExpr::Sub(build_box(seed, depth - 1, count), build_box(seed, depth - 1, count)),
+        Shape::Mul => BoxExpr::Mul(build_box(seed, depth - 1, count), build_box(seed, depth - 1, count)),
+    })
+}
+
+fn eval_box(e: &BoxExpr) -> i64 {
+    match e {
+        BoxExpr::Num(v) => *v,
+        BoxExpr::Neg(a) => eval_box(a).wrapping_neg(),
+        BoxExpr::Add(a, b) => eval_box(a).wrapping_add(eval_box(b)),
+        BoxExpr::Sub(a, b) => eval_box(a).wrapping_sub(eval_box(b)),
+        BoxExpr::Mul(a, b) => eval_box(a).wrapping_mul(eval_box(b)),
+    }
+}
+
+// Variant 2: bumpalo, nodes borrow from the arena and are freed all at once
+enum BumpExpr<'a> {
+    Num(i64),
+    Neg(&'a BumpExpr<'a>),
+    Add(&'a BumpExpr<'a>, &'a BumpExpr<'a>),
+    Sub(&'a BumpExpr<'a>, &'a BumpExpr<'a>),
+    Mul(&'a BumpExpr<'a>, &'a BumpExpr<'a>),
+}
+
+fn build_bump<'a>(bump: &'a Bump, seed: &mut u64, depth: u32, count: &mut usize) -> &'a BumpExpr<'a> {
+    *count += 1;
+    let expr = match next_shape(seed, depth) {
+        Shape::Num(v) => BumpExpr::Num(v),
+        Shape::Neg => BumpExpr::Neg(build_bump(bump, seed, depth - 1, count)),
+        Shape::Add => BumpExpr::Add(build_bump(bump, seed, depth - 1, count), build_bump(bump, seed, depth - 1, count)),
+        Shape::Sub => BumpExpr::Sub(build_bump(bump, seed, depth - 1, count), build_bump(bump, seed, depth - 1, count)),
+        Shape::Mul => BumpExpr::Mul(build_bump(bump, seed, depth - 1, count), build_bump(bump, seed, depth - 1, count)),
+    };
+    bump.alloc(expr)
+}
+
+fn eval_bump(e: &BumpExpr) -> i64 {
+    match e {
+        BumpExpr::Num(v) => *v,
+        BumpExpr::Neg(a) => eval_bump(a).wrapping_neg(),
+        BumpExpr::Add(a, b) => eval_bump(a).wrapping_add(eval_bump(b)),
+        BumpExpr::Sub(a, b) => eval_bump(a).wrapping_sub(eval_bump(b)),
+        BumpExpr::Mul(a, b) => eval_bump(a).wrapping_mul(eval_bump(b)),
+    }
+}
+
+// Variant 3: hand-rolled typed-index arena, nodes live in one Vec and refer to each other by u32
+#[derive(Clone, Copy)]
+struct NodeId(u32);
+
+enum IndexExpr {
+    Num(i64),
+    Neg(NodeId),
+    Add(NodeId, NodeId),
+    Sub(NodeId, NodeId),
+    Mul(NodeId, NodeId),
+}
+
+struct IndexArena {
+    nodes: Vec<IndexExpr>,
+}
+
+impl IndexArena {
+    fn alloc(&mut self, e: IndexExpr) -> NodeId {
+        self.nodes.push(e);
+        NodeId(self.nodes.len() as u32 - 1)
+    }
+
+    fn get(&self, id: NodeId) -> &IndexExpr {
+        &self.nodes[id.0 as usize]
+    }
+}
+
+fn build_index(arena: &mut IndexArena, seed: &mut u64, depth: u32) -> NodeId {
+    let expr = match next_shape(seed, depth) {
+        Shape::Num(v) => IndexExpr::Num(v),
+        Shape::Neg => IndexExpr::Neg(build_index(arena, seed, depth - 1)),
+        Shape::Add => IndexExpr::Add(build_index(arena, seed, depth - 1), build_index(arena, seed, depth - 1)),
+        Shape::Sub => IndexExpr::Sub(build_index(arena, seed, depth - 1), build_index(arena, seed, depth - 1)),
+        Shape::Mul => IndexExpr::Mul(build_index(arena, seed, depth - 1), build_index(arena, seed, depth - 1)),
+    };
+    arena.alloc(expr)
+}
+
+fn eval_index(arena: &IndexArena, id: NodeId) -> i64 {
+    match *arena.get(id) {
+        IndexExpr::Num(v) => v,
+        IndexExpr::Neg(a) => eval_index(arena, a).wrapping_neg(),
+        IndexExpr::Add(a, b) => eval_index(arena, a).wrapping_add(eval_index(arena, b)),
+        IndexExpr::Sub(a, b) => eval_index(arena, a).wrapping_sub(eval_index(arena, b)),
+        IndexExpr::Mul(a, b) => eval_index(arena, a).wrapping_mul(eval_index(arena, b)),
+    }
+}
+
+#[derive(Default)]
+struct Phases {
+    build: Duration,
+    traverse: Duration,
+    teardown: Duration,
+    nodes: usize,
+    checksum: i64,
+}
+
+impl Phases {
+    fn total(&self) -> Duration {
+        self.build + self.traverse + self.teardown
+    }
+}
+
+fn run_box(rounds: usize, nodes_per_round: usize) -> Phases {
+    let mut p = Phases::default();
+    for round in 0..rounds {
+        let mut seed = round as u64;
+        let start = Instant::now();
+        let mut roots = Vec::new();
+        let mut count = 0;
+        while count < nodes_per_round {
+            roots.push(build_box(&mut seed, MAX_DEPTH, &mut count));
+        }
+        p.build += start.elapsed();
+        p.nodes += count;
+
+        let start = Instant::now();
+        p.checksum = roots.iter().fold(p.checksum, |acc, r| acc.wrapping_add(eval_box(r)));
+        p.traverse += start.elapsed();
+
+        let start = Instant::now();
+        drop(roots);
+        p.teardown += start.elapsed();
+    }
+    p
+}
+
+// One arena reused across rounds: reset() keeps its largest chunk, so later rounds allocate nothing from the OS
+fn run_bump(rounds: usize, nodes_per_round: usize) -> Phases {
+    let mut p = Phases::default();
+    let mut bump = Bump::new();
+    for round in 0..rounds {
+        let mut seed = round as u64;
+        let start = Instant::now();
+        let mut roots = Vec::new();
+        let mut count = 0;
+        while count < nodes_per_round {
+            roots.push(build_bump(&bump, &mut seed, MAX_DEPTH, &mut count));
+        }
+        p.build += start.elapsed();
+        p.nodes += count;
+
+        let start = Instant::now();
+        p.checksum = roots.iter().fold(p.checksum, |acc, r| acc.wrapping_add(eval_bump(r)));
+        p.traverse += start.elapsed();
+
+        let start = Instant::now();
+        drop(roots);
+        bump.reset();
+        p.teardown += start.elapsed();
+    }
+    p
+}
+
+fn run_index(rounds: usize, nodes_per_round: usize) -> Phases {
+    let mut p = Phases::default();
+    let mut arena = IndexArena { nodes: Vec::new() };
+    for round in 0..rounds {
+        let mut seed = round as u64;
+        let start = Instant::now();
+        let mut roots = Vec::new();
+        while arena.nodes.len() < nodes_per_round {
+            roots.push(build_index(&mut arena, &mut seed, MAX_DEPTH));
+        }
+        p.build += start.elapsed();
+        p.nodes += arena.nodes.len();
+
+        let start = Instant::now();
+        p.checksum = roots.iter().fold(p.checksum, |acc, &r| acc.wrapping_add(eval_index(&arena, r)));
+        p.traverse += start.elapsed();
+
+        let start = Instant::now();
+        drop(roots);
+        arena.nodes.clear();
+        p.teardown += start.elapsed();
+    }
+    p
+}
+
+fn report(name: &str, p: &Phases) {
+    eprintln!("{:<12} build {:.3}s ({:.1}M nodes/s), traverse {:.3}s, teardown {:.3}s",
+              name, p.build.as_secs_f64(), p.nodes as f64 / 1e6 / p.build.as_secs_f64(),
+              p.traverse.as_secs_f64(), p.teardown.as_secs_f64());
+}
+
+fn main() {
+    // Warm-up
+    let _ = run_box(1, NODES_PER_ROUND / 100);
+    let _ = run_bump(1, NODES_PER_ROUND / 100);
+    let _ = run_index(1, NODES_PER_ROUND / 100);
+
+    // Benchmark
+    let boxed = run_box(NUM_ROUNDS, NODES_PER_ROUND);
+    let bump = run_bump(NUM_ROUNDS, NODES_PER_ROUND);
+    let index = run_index(NUM_ROUNDS, NODES_PER_ROUND);
+
+    let total_duration = boxed.total() + bump.total() + index.total();
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    report("Box", &boxed);
+    report("bumpalo", &bump);
+    report("index arena", &index);
+    eprintln!("Nodes: {} over {} rounds", boxed.nodes, NUM_ROUNDS);
+    eprintln!("Checksum: {} (all variants match: {})", boxed.checksum,
+              boxed.checksum == bump.checksum && boxed.checksum == index.checksum
+                  && boxed.nodes == bump.nodes && boxed.nodes == index.nodes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_known_expressions() {
+        // (3 + -4) * (10 - 2)
+        let boxed = BoxExpr::Mul(
+            Box::new(BoxExpr::Add(Box::new(BoxExpr::Num(3)), Box::new(BoxExpr::Neg(Box::new(BoxExpr::Num(4)))))),
+            Box::new(BoxExpr::Sub(Box::new(BoxExpr::Num(10)), Box::new(BoxExpr::Num(2)))),
+        );
+        assert_eq!(eval_box(&boxed), -8);
+
+        let bump = Bump::new();
+        let sum = bump.alloc(BumpExpr::Add(bump.alloc(BumpExpr::Num(3)), bump.alloc(BumpExpr::Neg(bump.alloc(BumpExpr::Num(4))))));
+        assert_eq!(eval_bump(&BumpExpr::Mul(sum, bump.alloc(BumpExpr::Num(8)))), -8);
+
+        let mut arena = IndexArena { nodes: Vec::new() };
+        let (a, b) = (arena.alloc(IndexExpr::Num(i64::MAX)), arena.alloc(IndexExpr::Num(2)));
+        let product = arena.alloc(IndexExpr::Mul(a, b));
+        assert_eq!(eval_index(&arena, product), -2); // wraps
+    }
+
+    #[test]
+    fn variants_build_identical_trees() {
+        let boxed = run_box(3, 5_000);
+        let bump = run_bump(3, 5_000);
+        let index = run_index(3, 5_000);
+        assert!(boxed.nodes >= 15_000);
+        assert_eq!((bump.nodes, bump.checksum), (boxed.nodes, boxed.checksum));
+        assert_eq!((index.nodes, index.checksum), (boxed.nodes, boxed.checksum));
+    }
+
+    #[test]
+    fn depth_zero_is_a_leaf() {
+        let mut seed = 7;
+        for _ in 0..100 {
+            assert!(matches!(next_shape(&mut seed, 0), Shape::Num(0..=99)));
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use arrow_arith::aggregate;
+use arrow_arith::boolean;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int64Type, UInt32Type};
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, Scalar, UInt32Array};
+use arrow_ord::cmp;
+use arrow_ord::sort::{sort_to_indices, SortOptions};
+use arrow_schema::{DataType, Field, Schema};
+use arrow_select::filter::filter_record_batch;
+use arrow_select::take::take_record_batch;
+use std::sync::Arc;
+use std::time::Instant;
+
+const DEFAULT_ROWS: usize = 100_000_000;
+const WARMUP_ROWS: usize = 1_000_000;
+const MIN_PRICE: f64 = 500.0; // WHERE price >= 500 AND quantity <= 20 keeps about 10% of rows
+const MAX_QUANTITY: i64 = 20;
+const SEED: u64 = 0x6172_726f_7763_6f6d;
+
+// SplitMix64; the top 53 bits become an f64 in [0, 1) exactly, in both languages
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+// An orders table: id, price in [0, 1000) and quantity in 1..=100
+fn make_table(rows: usize) -> RecordBatch {
+    let mut rng = Rng(SEED);
+    let (mut price, mut quantity) = (Vec::with_capacity(rows), Vec::with_capacity(rows));
+    for _ in 0..rows {
+        price.push((rng.next() >> 11) as f64 * (1.0 / (1u64 << 53) as f64) * 1000.0);
+        quantity.push(1 + (rng.next() % 100) as i64);
+    }
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt32, false),
+        Field::new("price", DataType::Float64, false),
+        Field::new("quantity", DataType::Int64, false),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt32Array::from_iter_values(0..rows as u32)),
+        Arc::new(Float64Array::from(price)),
+        Arc::new(Int64Array::from(quantity)),
+    ];
+    RecordBatch::try_new(Arc::new(schema), columns).expect("Failed to build the table")
+}
+
+fn column<'a>(batch: &'a RecordBatch, name: &str) -> &'a ArrayRef {
+    batch.column_by_name(name).expect("Missing column")
+}
+
+// WHERE price >= MIN_PRICE AND quantity <= MAX_QUANTITY: two comparison kernels, a boolean AND,
+// then one filter over every column
+fn filter(table: &RecordBatch) -> RecordBatch {
+    let price = cmp::gt_eq(column(table, "price"), &Scalar::new(Float64Array::from(vec![MIN_PRICE])));
+    let quantity = cmp::lt_eq(column(table, "quantity"), &Scalar::new(Int64Array::from(vec![MAX_QUANTITY])));
+    let mask: BooleanArray = boolean::and(&price.expect("Comparison failed"), &quantity.expect("Comparison failed"))
+        .expect("AND failed");
+    filter_record_batch(table, &mask).expect("Filter failed")
+}
+
+// Floating-point sums depend on each library's summation order, so they are reported but only the
+// exact results (integer sums, min and max) are checked
+struct Aggregates {
+    rows: usize,
+    price_sum: f64,
+    price_min: f64,
+    price_max: f64,
+    quantity_sum: i64,
+}
+
+fn aggregate(batch: &RecordBatch) -> Aggregates {
+    let price = column(batch, "price").as_primitive::<Float64Type>();
+    let quantity = column(batch, "quantity").as_primitive::<Int64Type>();
+    Aggregates {
+        rows: batch.num_rows(),
+        price_sum: aggregate::sum(price).unwrap_or(0.0),
+        price_min: aggregate::min(price).unwrap_or(f64::NAN),
+        price_max: aggregate::max(price).unwrap_or(f64::NAN),
+        quantity_sum: aggregate::sum(quantity).unwrap_or(0),
+    }
+}
+
+// ORDER BY price DESC: sort the key to indices, then gather every column through them
+fn sort(batch: &RecordBatch) -> RecordBatch {
+    let options = SortOptions { descending: true, nulls_first: false };
+    let indices = sort_to_indices(column(batch, "price"), Some(options), None).expect("Sort failed");
+    take_record_batch(batch, &indices).expect("Take failed")
+}
+
+// Position-weighted over the sorted prices, so the order matters but ties between equal prices do not
+fn order_digest(sorted: &RecordBatch) -> u64 {
+    let price = column(sorted, "price").as_primitive::<Float64Type>();
+    price.values().iter().enumerate().fold(0u64, |sum, (i, p)| sum.wrapping_add((i as u64 + 1).wrapping_mul(p.to_bits() >> 32)))
+}
+
+// The same query as plain loops over the columns, to check the kernels against
+fn reference(table: &RecordBatch) -> (usize, i64, f64, f64) {
+    let price = column(table, "price").as_primitive::<Float64Type>().values();
+    let quantity = column(table, "quantity").as_primitive::<Int64Type>().values();
+    let (mut rows, mut quantity_sum, mut min, mut max) = (0, 0, f64::INFINITY, f64::NEG_INFINITY);
+    for (&p, &q) in price.iter().zip(quantity.iter()) {
+        if p >= MIN_PRICE && q <= MAX_QUANTITY {
+            rows += 1;
+            quantity_sum += q;
+            min = min.min(p);
+            max = max.max(p);
+        }
+    }
+    (rows, quantity_sum, min, max)
+}
+
+fn parse_rows() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--rows") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|&n: &usize| n > 0 && n <= u32::MAX as usize)
+            .expect("--rows expects a positive integer that fits in u32"),
+        None => DEFAULT_ROWS,
+    }
+}
+
+fn main() {
+    let rows = parse_rows();
+    let table = make_table(rows);
+
+    // Warm-up
+    let warmup = make_table(WARMUP_ROWS);
+    let _ = sort(&filter(&warmup));
+    let _ = aggregate(&warmup);
+
+    // Benchmark
+    let start = Instant::now();
+    let totals = aggregate(&table);
+    let aggregate_duration = start.elapsed();
+    let start = Instant::now();
+    let selected = filter(&table);
+    let filter_duration = start.elapsed();
+    let start = Instant::now();
+    let sorted = sort(&selected);
+    let sort_duration = start.elapsed();
+    let selected_totals = aggregate(&sorted);
+
+    let (expected_rows, expected_quantity, expected_min, expected_max) = reference(&table);
+    let prices = column(&sorted, "price").as_primitive::<Float64Type>().values();
+    let ordered = prices.windows(2).all(|pair| pair[0] >= pair[1]);
+    if (selected_totals.rows, selected_totals.quantity_sum) != (expected_rows, expected_quantity)
+        || selected_totals.price_min.to_bits() != expected_min.to_bits()
+        || selected_totals.price_max.to_bits() != expected_max.to_bits()
+        || !ordered
+    {
+        eprintln!("Kernel results differ from the reference loop: {} rows, quantity {}, price {}..{}, sorted {}; expected {} rows, quantity {}, price {}..{}",
+                  selected_totals.rows, selected_totals.quantity_sum, selected_totals.price_min,
+                  selected_totals.price_max, ordered, expected_rows, expected_quantity, expected_min, expected_max);
+        std::process::exit(1);
+    }
+
+    let total = (aggregate_duration + filter_duration + sort_duration).as_secs_f64();
+    let rate = |n: usize, seconds: f64| n as f64 / seconds / 1e6;
+    let ids = column(&sorted, "id").as_primitive::<UInt32Type>();
+    let checksum = (totals.quantity_sum as u64)
+        .wrapping_add(selected_totals.rows as u64)
+        .wrapping_add(selected_totals.quantity_sum as u64)
+        .wrapping_add(order_digest(&sorted));
+    println!("{:.6}", total);
+    eprintln!("Table: {} rows (id u32, price f64, quantity i64), {:.0} MB", rows, table.get_array_memory_size() as f64 / 1e6);
+    eprintln!("Aggregate (sum, min, max of 2 columns): {:.6}s, {:.0}M rows/s", aggregate_duration.as_secs_f64(),
+              rate(rows, aggregate_duration.as_secs_f64()));
+    eprintln!("Filter (price >= {}, quantity <= {}): {:.6}s, {:.0}M rows/s, {} rows kept ({:.1}%)", MIN_PRICE, MAX_QUANTITY,
+              filter_duration.as_secs_f64(), rate(rows, filter_duration.as_secs_f64()), selected_totals.rows,
+              100.0 * selected_totals.rows as f64 / rows as f64);
+    eprintln!("Sort (price descending, 3 columns taken): {:.6}s, {:.1}M rows/s", sort_duration.as_secs_f64(),
+              rate(selected_totals.rows, sort_duration.as_secs_f64()));
+    eprintln!("Totals: price sum {:.6e}, min {:.6}, max {:.6}, quantity sum {}", totals.price_sum, totals.price_min,
+              totals.price_max, totals.quantity_sum);
+    if selected_totals.rows > 0 {
+        eprintln!("Top row: id {}, price {:.6}; filtered quantity sum {}, matching the reference loop",
+                  ids.value(0), prices[0], selected_totals.quantity_sum);
+    }
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernels_match_the_reference_loop() {
+        let table = make_table(100_000);
+        let selected = filter(&table);
+        let totals = aggregate(&selected);
+        let (rows, quantity, min, max) = reference(&table);
+        assert_eq!((totals.rows, totals.quantity_sum), (rows, quantity));
+        assert_eq!((totals.price_min, totals.price_max), (min, max));
+        assert!((rows as f64 / 100_000.0 - 0.1).abs() < 0.01);
+    }
+
+    #[test]
+    fn sort_keeps_rows_together() {
+        let table = make_table(20_000);
+        let sorted = sort(&filter(&table));
+        let price = column(&table, "price").as_primitive::<Float64Type>();
+        let quantity = column(&table, "quantity").as_primitive::<Int64Type>();
+        let ids = column(&sorted, "id").as_primitive::<UInt32Type>();
+        let sorted_price = column(&sorted, "price").as_primitive::<Float64Type>();
+        let sorted_quantity = column(&sorted, "quantity").as_primitive::<Int64Type>();
+        for i in 0..sorted.num_rows() {
+            let id = ids.value(i) as usize;
+            assert_eq!((sorted_price.value(i), sorted_quantity.value(i)), (price.value(id), quantity.value(id)));
+            assert!(i == 0 || sorted_price.value(i - 1) >= sorted_price.value(i));
+        }
+    }
+}
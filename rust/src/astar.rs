@@ -0,0 +1,200 @@
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+const GRID_SIZE: usize = 512;
+const NUM_QUERIES: usize = 2000;
+const OBSTACLE_PERCENT: u64 = 20;
+const MAX_WEIGHT: u64 = 9;
+const OBSTACLE: u8 = 0;
+
+struct Grid {
+    cells: Vec<u8>, // 0 = obstacle, otherwise the cost of entering the cell
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn generate_grid() -> Grid {
+    let mut seed = 12345u64;
+    let cells = (0..GRID_SIZE * GRID_SIZE)
+        .map(|_| {
+            if next_random(&mut seed) % 100 < OBSTACLE_PERCENT {
+                OBSTACLE
+            } else {
+                (1 + next_random(&mut seed) % MAX_WEIGHT) as u8
+            }
+        })
+        .collect();
+    Grid { cells }
+}
+
+fn generate_queries(grid: &Grid) -> Vec<(usize, usize)> {
+    let mut seed = 67890u64;
+    let mut random_open_cell = || loop {
+        let cell = (next_random(&mut seed) % (GRID_SIZE * GRID_SIZE) as u64) as usize;
+        if grid.cells[cell] != OBSTACLE {
+            return cell;
+        }
+    };
+    (0..NUM_QUERIES).map(|_| (random_open_cell(), random_open_cell())).collect()
+}
+
+// Manhattan distance; the cheapest cell costs 1, so it never overestimates
+fn heuristic(a: usize, b: usize) -> u32 {
+    let (ax, ay) = (a % GRID_SIZE, a / GRID_SIZE);
+    let (bx, by) = (b % GRID_SIZE, b / GRID_SIZE);
+    (ax.abs_diff(bx) + ay.abs_diff(by)) as u32
+}
+
+// Returns (path cost, path length in steps), or None if the goal is unreachable
+fn astar(grid: &Grid, start: usize, goal: usize) -> Option<(u64, usize)> {
+    let mut open = BinaryHeap::new();
+    let mut g_score: HashMap<usize, u32> = HashMap::new();
+    let mut came_from: HashMap<usize, usize> = HashMap::new();
+
+    g_score.insert(start, 0);
+    open.push(Reverse((heuristic(start, goal), start)));
+
+    while let Some(Reverse((f, current))) = open.pop() {
+        let g = g_score[&current];
+        if f > g + heuristic(current, goal) {
+            continue; // Stale heap entry
+        }
+
+        if current == goal {
+            let mut length = 0;
+            let mut node = goal;
+            while node != start {
+                node = came_from[&node];
+                length += 1;
+            }
+            return Some((g as u64, length));
+        }
+
+        let (x, y) = (current % GRID_SIZE, current / GRID_SIZE);
+        let neighbors = [
+            (x > 0).then(|| current - 1),
+            (x + 1 < GRID_SIZE).then(|| current + 1),
+            (y > 0).then(|| current - GRID_SIZE),
+            (y + 1 < GRID_SIZE).then(|| current + GRID_SIZE),
+        ];
+
+        for next in neighbors.into_iter().flatten() {
+            let weight = grid.cells[next];
+            if weight == OBSTACLE {
+                continue;
+            }
+            let tentative = g + weight as u32;
+            if g_score.get(&next).is_none_or(|&old| tentative < old) {
+                g_score.insert(next, tentative);
+                came_from.insert(next, current);
+                open.push(Reverse((tentative + heuristic(next, goal), next)));
+            }
+        }
+    }
+
+    None
+}
+
+fn solve_all(grid: &Grid, queries: &[(usize, usize)]) -> Vec<Option<(u64, usize)>> {
+    queries.par_iter().map(|&(start, goal)| astar(grid, start, goal)).collect()
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let grid = generate_grid();
+    let queries = generate_queries(&grid);
+
+    // Warm-up
+    let _ = solve_all(&grid, &queries[..NUM_QUERIES / 20]);
+
+    // Benchmark
+    measure.start();
+    let results = solve_all(&grid, &queries);
+    measure.stop();
+
+    // Checksum
+    let total_cost: u64 = results.iter().flatten().map(|&(cost, _)| cost).sum();
+    let total_length: usize = results.iter().flatten().map(|&(_, length)| length).sum();
+    let unreachable = results.iter().filter(|r| r.is_none()).count();
+
+    measure.report();
+    eprintln!("Paths: {}, unreachable: {}, total steps: {}", NUM_QUERIES, unreachable, total_length);
+    eprintln!("Checksum: {}", total_cost);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uniform_grid() -> Grid {
+        Grid { cells: vec![1; GRID_SIZE * GRID_SIZE] }
+    }
+
+    // Plain Dijkstra from `start`, the reference A* has to match
+    fn dijkstra(grid: &Grid, start: usize) -> Vec<u64> {
+        let mut dist = vec![u64::MAX; grid.cells.len()];
+        let mut open = BinaryHeap::from([Reverse((0u64, start))]);
+        dist[start] = 0;
+        while let Some(Reverse((d, cell))) = open.pop() {
+            if d > dist[cell] {
+                continue;
+            }
+            let (x, y) = (cell % GRID_SIZE, cell / GRID_SIZE);
+            let neighbors = [
+                (x > 0).then(|| cell - 1),
+                (x + 1 < GRID_SIZE).then(|| cell + 1),
+                (y > 0).then(|| cell - GRID_SIZE),
+                (y + 1 < GRID_SIZE).then(|| cell + GRID_SIZE),
+            ];
+            for next in neighbors.into_iter().flatten().filter(|&n| grid.cells[n] != OBSTACLE) {
+                let candidate = d + grid.cells[next] as u64;
+                if candidate < dist[next] {
+                    dist[next] = candidate;
+                    open.push(Reverse((candidate, next)));
+                }
+            }
+        }
+        dist
+    }
+
+    #[test]
+    fn open_grid_paths_are_manhattan() {
+        let grid = uniform_grid();
+        let (start, goal) = (3 * GRID_SIZE + 5, 40 * GRID_SIZE + 1);
+        assert_eq!(astar(&grid, start, goal), Some((41, 41)));
+        assert_eq!(astar(&grid, start, start), Some((0, 0)));
+    }
+
+    #[test]
+    fn walled_off_goal_is_unreachable() {
+        let mut grid = uniform_grid();
+        for y in 0..GRID_SIZE {
+            grid.cells[y * GRID_SIZE + 100] = OBSTACLE;
+        }
+        assert_eq!(astar(&grid, 0, 200), None);
+        grid.cells[7 * GRID_SIZE + 100] = 9; // one costly gap
+        assert_eq!(astar(&grid, 0, 200), Some((200 + 2 * 7 - 1 + 9, 200 + 2 * 7)));
+    }
+
+    #[test]
+    fn costs_match_dijkstra() {
+        let grid = generate_grid();
+        let queries = generate_queries(&grid);
+        for &(start, goal) in &queries[..4] {
+            let expected = dijkstra(&grid, start)[goal];
+            match astar(&grid, start, goal) {
+                Some((cost, length)) => {
+                    assert_eq!(cost, expected);
+                    assert!(length >= heuristic(start, goal) as usize);
+                }
+                None => assert_eq!(expected, u64::MAX),
+            }
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use rayon::prelude::*;
+use std::time::Instant;
+
+const DEFAULT_SEQ_LEN: usize = 2048;
+const HEADS: usize = 16;
+const HEAD_DIM: usize = 64;
+const QUERY_BLOCK: usize = 64;  // query rows per parallel task
+const KEY_TILE: usize = 256;    // key columns per tile; a 64x256 K^T tile stays cache-resident
+const ITERATIONS: usize = 3;
+const CHECK_SEQ_LEN: usize = 256;
+const CHECK_HEADS: usize = 2;
+const TOLERANCE: f32 = 1e-4;
+
+fn next_random(seed: &mut u64) -> f32 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*seed >> 40) as f32 / 8_388_608.0 - 1.0
+}
+
+// Q, K and V laid out [head][position][dim]
+struct Inputs {
+    seq_len: usize,
+    heads: usize,
+    q: Vec<f32>,
+    k: Vec<f32>,
+    v: Vec<f32>,
+}
+
+impl Inputs {
+    fn new(seq_len: usize, heads: usize) -> Self {
+        let mut seed = 42u64;
+        let len = heads * seq_len * HEAD_DIM;
+        let mut tensor = || (0..len).map(|_| next_random(&mut seed)).collect::<Vec<f32>>();
+        let q = tensor();
+        let k = tensor();
+        let v = tensor();
+        Inputs { seq_len, heads, q, k, v }
+    }
+}
+
+// Per-head K^T ([dim][position]) so both GEMMs run as contiguous axpys
+fn transpose_keys(inputs: &Inputs) -> Vec<f32> {
+    let l = inputs.seq_len;
+    let mut kt = vec![0.0f32; inputs.k.len()];
+    kt.par_chunks_mut(l * HEAD_DIM).zip(inputs.k.par_chunks(l * HEAD_DIM)).for_each(|(kt, k)| {
+        for (pos, row) in k.chunks_exact(HEAD_DIM).enumerate() {
+            for (d, &x) in row.iter().enumerate() {
+                kt[d * l + pos] = x;
+            }
+        }
+    });
+    kt
+}
+
+// One task: softmax(Q_blk K^T / sqrt(d)) V for QUERY_BLOCK rows of one head
+fn attention_block(q: &[f32], kt: &[f32], v: &[f32], seq_len: usize, out: &mut [f32], scores: &mut [f32]) {
+    let rows = q.len() / HEAD_DIM;
+    let scale = 1.0 / (HEAD_DIM as f32).sqrt();
+
+    // S = Q K^T, tiled over keys so the K^T tile is reused by every query row
+    scores.fill(0.0);
+    for j0 in (0..seq_len).step_by(KEY_TILE) {
+        let j1 = (j0 + KEY_TILE).min(seq_len);
+        for i in 0..rows {
+            let s = &mut scores[i * seq_len + j0..i * seq_len + j1];
+            for d in 0..HEAD_DIM {
+                let qd = q[i * HEAD_DIM + d] * scale;
+                for (sj, &kj) in s.iter_mut().zip(&kt[d * seq_len + j0..d * seq_len + j1]) {
+                    *sj = qd.mul_add(kj, *sj);
+                }
+            }
+        }
+    }
+
+    // Numerically stable row softmax
+    for row in scores.chunks_exact_mut(seq_len) {
+        let max = row.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let mut sum = 0.0f32;
+        for s in row.iter_mut() {
+            *s = (*s - max).exp();
+            sum += *s;
+        }
+        let inv = 1.0 / sum;
+        for s in row.iter_mut() {
+            *s *= inv;
+        }
+    }
+
+    // O = P V, tiled over keys so the V tile is reused by every query row
+    out.fill(0.0);
+    for j0 in (0..seq_len).step_by(KEY_TILE) {
+        let j1 = (j0 + KEY_TILE).min(seq_len);
+        for (i, o) in out.chunks_exact_mut(HEAD_DIM).enumerate() {
+            for j in j0..j1 {
+                let p = scores[i * seq_len + j];
+                for (od, &vd) in o.iter_mut().zip(&v[j * HEAD_DIM..(j + 1) * HEAD_DIM]) {
+                    *od = p.mul_add(vd, *od);
+                }
+            }
+        }
+    }
+}
+
+fn attention(inputs: &Inputs) -> Vec<f32> {
+    let l = inputs.seq_len;
+    let kt = transpose_keys(inputs);
+    let mut out = vec![0.0f32; inputs.q.len()];
+    let blocks_per_head = l / QUERY_BLOCK;
+
+    // Chunks of the head-major output map one-to-one onto (head, query block) tasks
+    out.par_chunks_mut(QUERY_BLOCK * HEAD_DIM).enumerate().for_each_init(
+        || vec![0.0f32; QUERY_BLOCK * l],
+        |scores, (task, o)| {
+            let head = task / blocks_per_head;
+            let row0 = head * l + (task % blocks_per_head) * QUERY_BLOCK;
+            let head_range = head * l * HEAD_DIM..(head + 1) * l * HEAD_DIM;
+            attention_block(
+                &inputs.q[row0 * HEAD_DIM..(row0 + QUERY_BLOCK) * HEAD_DIM],
+                &kt[head_range.clone()],
+                &inputs.v[head_range],
+                l,
+                o,
+                scores,
+            );
+        },
+    );
+    out
+}
+
+// Textbook attention in f64, one query at a time
+fn reference_attention(inputs: &Inputs) -> Vec<f32> {
+    let l = inputs.seq_len;
+    let scale = 1.0 / (HEAD_DIM as f64).sqrt();
+    let mut out = vec![0.0f32; inputs.q.len()];
+    for h in 0..inputs.heads {
+        let base = h * l * HEAD_DIM;
+        for i in 0..l {
+            let q = &inputs.q[base + i * HEAD_DIM..base + (i + 1) * HEAD_DIM];
+            let scores: Vec<f64> = (0..l)
+                .map(|j| {
+                    let k = &inputs.k[base + j * HEAD_DIM..base + (j + 1) * HEAD_DIM];
+                    q.iter().zip(k).map(|(&a, &b)| a as f64 * b as f64).sum::<f64>() * scale
+                })
+                .collect();
+            let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let weights: Vec<f64> = scores.iter().map(|s| (s - max).exp()).collect();
+            let sum: f64 = weights.iter().sum();
+            for d in 0..HEAD_DIM {
+                let o: f64 = (0..l).map(|j| weights[j] * inputs.v[base + j * HEAD_DIM + d] as f64).sum();
+                out[base + i * HEAD_DIM + d] = (o / sum) as f32;
+            }
+        }
+    }
+    out
+}
+
+fn parse_seq_len() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--seq-len") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|n: &usize| *n > 0 && n.is_multiple_of(QUERY_BLOCK))
+            .expect("--seq-len expects a positive multiple of 64"),
+        None => DEFAULT_SEQ_LEN,
+    }
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let seq_len = parse_seq_len();
+
+    // Cross-check against the f64 reference at a small size (doubles as warm-up)
+    let check = Inputs::new(CHECK_SEQ_LEN, CHECK_HEADS);
+    let max_error = attention(&check)
+        .iter()
+        .zip(&reference_attention(&check))
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0f32, f32::max);
+    assert!(max_error < TOLERANCE, "Attention differs from reference by {}", max_error);
+
+    // Benchmark
+    let inputs = Inputs::new(seq_len, HEADS);
+    let start = Instant::now();
+    measure.start();
+    let mut out = Vec::new();
+    for _ in 0..ITERATIONS {
+        out = attention(&inputs);
+    }
+    measure.stop();
+    let duration = start.elapsed();
+
+    let flops = 4.0 * (seq_len * seq_len * HEAD_DIM * HEADS) as f64 * ITERATIONS as f64;
+    let checksum: f64 = out.iter().step_by(97).map(|&x| x as f64).sum();
+
+    measure.report();
+    eprintln!("Sequence {} x {} heads x {} dims, {} iterations: {:.2} GFLOP/s",
+              seq_len, HEADS, HEAD_DIM, ITERATIONS, flops / duration.as_secs_f64() / 1e9);
+    eprintln!("Cross-check ({} x {} heads): max abs error {:.2e}", CHECK_SEQ_LEN, CHECK_HEADS, max_error);
+    eprintln!("Checksum: {:.6}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_error(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn matches_the_reference() {
+        // 320 leaves a partial key tile
+        for (seq_len, heads) in [(64, 1), (320, 2)] {
+            let inputs = Inputs::new(seq_len, heads);
+            assert!(max_error(&attention(&inputs), &reference_attention(&inputs)) < TOLERANCE);
+        }
+    }
+
+    // Equal keys give every position the same weight, so each output row is the mean of V
+    #[test]
+    fn equal_keys_average_the_values() {
+        let mut inputs = Inputs::new(128, 1);
+        let key: Vec<f32> = inputs.k[..HEAD_DIM].to_vec();
+        for row in inputs.k.chunks_exact_mut(HEAD_DIM) {
+            row.copy_from_slice(&key);
+        }
+        let mean: Vec<f32> = (0..HEAD_DIM)
+            .map(|d| inputs.v.iter().skip(d).step_by(HEAD_DIM).sum::<f32>() / 128.0)
+            .collect();
+        for row in attention(&inputs).chunks_exact(HEAD_DIM) {
+            assert!(max_error(row, &mean) < 1e-5);
+        }
+    }
+
+    #[test]
+    fn keys_transpose_per_head() {
+        let inputs = Inputs::new(64, 2);
+        let kt = transpose_keys(&inputs);
+        let l = inputs.seq_len;
+        for (h, pos, d) in [(0, 0, 0), (0, 5, 63), (1, 63, 17)] {
+            assert_eq!(kt[h * l * HEAD_DIM + d * l + pos], inputs.k[(h * l + pos) * HEAD_DIM + d]);
+        }
+    }
+}
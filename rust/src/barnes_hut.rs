@@ -0,0 +1,329 @@
+use bench_math::{Real, Vec3};
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+const NUM_BODIES: usize = 1_000_000;
+const STEPS: usize = 3;
+const THETA: f64 = 0.5; // Opening angle: cells smaller than THETA x distance are treated as one mass
+const SOFTENING2: f64 = 1e-6;
+const DT: f64 = 1e-3;
+const LEAF_SIZE: usize = 16;
+const MORTON_BITS: u32 = 21; // Per axis; 63-bit codes
+const PARALLEL_CUTOFF: usize = 50_000; // Subtrees larger than this are built in parallel
+const ERROR_SAMPLES: usize = 100;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Precision {
+    F64,
+    F32,
+}
+
+#[derive(Clone, Copy)]
+struct Body<T> {
+    position: Vec3<T>,
+    velocity: Vec3<T>,
+}
+
+// Octree cell: a leaf owns a run of the Morton-sorted bodies, an internal node owns up to 8 children
+struct Node<T> {
+    center_of_mass: Vec3<T>,
+    mass: T,
+    size: T,
+    first: usize,
+    count: usize,
+    children: Vec<Node<T>>,
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn next_f64(seed: &mut u64) -> f64 {
+    next_random(seed) as f64 / (1u64 << 31) as f64
+}
+
+// Uniform in the unit ball with a slow rigid rotation about the z axis
+fn generate_bodies() -> Vec<Body<f64>> {
+    let mut seed = 6174u64;
+    let mut bodies = Vec::with_capacity(NUM_BODIES);
+    while bodies.len() < NUM_BODIES {
+        let x = next_f64(&mut seed) * 2.0 - 1.0;
+        let y = next_f64(&mut seed) * 2.0 - 1.0;
+        let z = next_f64(&mut seed) * 2.0 - 1.0;
+        if x * x + y * y + z * z <= 1.0 {
+            bodies.push(Body { position: Vec3::new(x, y, z), velocity: Vec3::new(-0.5 * y, 0.5 * x, 0.0) });
+        }
+    }
+    bodies
+}
+
+// Spreads the low 21 bits of v two positions apart
+fn spread_bits(v: u64) -> u64 {
+    let mut x = v & 0x1F_FFFF;
+    x = (x | (x << 32)) & 0x1F00000000FFFF;
+    x = (x | (x << 16)) & 0x1F0000FF0000FF;
+    x = (x | (x << 8)) & 0x100F00F00F00F00F;
+    x = (x | (x << 4)) & 0x10C30C30C30C30C3;
+    x = (x | (x << 2)) & 0x1249249249249249;
+    x
+}
+
+// Quantized in f64 whatever T is; the codes only order the bodies
+fn morton_code<T: Real>(p: &Vec3<T>, origin: &Vec3<T>, scale: f64) -> u64 {
+    let max_cell = ((1u64 << MORTON_BITS) - 1) as f64;
+    let q = |v: T, o: T| ((v.to_f64() - o.to_f64()) * scale).clamp(0.0, max_cell) as u64;
+    (spread_bits(q(p.x, origin.x)) << 2) | (spread_bits(q(p.y, origin.y)) << 1) | spread_bits(q(p.z, origin.z))
+}
+
+fn build_node<T: Real>(bodies: &[Body<T>], codes: &[u64], first: usize, depth: u32, size: T) -> Node<T> {
+    let count = bodies.len();
+    if count <= LEAF_SIZE || depth == MORTON_BITS {
+        let sum = bodies.iter().fold(Vec3::zero(), |s, b| s + b.position);
+        return Node {
+            center_of_mass: sum * T::from_f64(1.0 / count as f64),
+            mass: T::from_f64(count as f64 / NUM_BODIES as f64),
+            size,
+            first,
+            count,
+            children: Vec::new(),
+        };
+    }
+
+    // Codes are sorted, so each octant at this depth is a contiguous run
+    let shift = 3 * (MORTON_BITS - 1 - depth);
+    let mut bounds = [0usize; 9];
+    for octant in 1..8u64 {
+        bounds[octant as usize] = codes.partition_point(|&c| (c >> shift) & 7 < octant);
+    }
+    bounds[8] = count;
+    let ranges: Vec<(usize, usize)> = (0..8).map(|o| (bounds[o], bounds[o + 1])).filter(|(lo, hi)| hi > lo).collect();
+
+    let build_child = |&(lo, hi): &(usize, usize)| build_node(&bodies[lo..hi], &codes[lo..hi], first + lo, depth + 1, size / T::from_f64(2.0));
+    let children: Vec<Node<T>> = if count > PARALLEL_CUTOFF {
+        ranges.par_iter().map(build_child).collect()
+    } else {
+        ranges.iter().map(build_child).collect()
+    };
+
+    let mass = children.iter().fold(T::from_f64(0.0), |m, c| m + c.mass);
+    let weighted = children.iter().fold(Vec3::zero(), |s, c| s + c.center_of_mass * c.mass);
+    Node { center_of_mass: weighted * (T::from_f64(1.0) / mass), mass, size, first, count, children }
+}
+
+// Sorts the bodies along a Morton curve and builds the octree over them
+fn build_tree<T: Real>(bodies: &mut Vec<Body<T>>) -> Node<T> {
+    let (lo, hi) = bodies
+        .par_iter()
+        .map(|b| (b.position, b.position))
+        .reduce(|| (Vec3::new(T::MAX, T::MAX, T::MAX), Vec3::new(T::MIN, T::MIN, T::MIN)),
+                |(lo1, hi1), (lo2, hi2)| (lo1.min(&lo2), hi1.max(&hi2)));
+    let extent = (hi.x - lo.x).max(hi.y - lo.y).max(hi.z - lo.z) * T::from_f64(1.0 + 1e-9);
+    let scale = (1u64 << MORTON_BITS) as f64 / extent.to_f64();
+
+    let mut keys: Vec<(u64, u32)> = bodies
+        .par_iter()
+        .enumerate()
+        .map(|(i, b)| (morton_code(&b.position, &lo, scale), i as u32))
+        .collect();
+    keys.par_sort_unstable();
+
+    let sorted: Vec<Body<T>> = keys.par_iter().map(|&(_, i)| bodies[i as usize]).collect();
+    let codes: Vec<u64> = keys.par_iter().map(|&(c, _)| c).collect();
+    *bodies = sorted;
+    build_node(bodies, &codes, 0, 0, extent)
+}
+
+fn accumulate<T: Real>(node: &Node<T>, p: &Vec3<T>, bodies: &[Body<T>], acc: &mut Vec3<T>) {
+    let softening2 = T::from_f64(SOFTENING2);
+    let d = node.center_of_mass - *p;
+    let dist2 = d.dot(&d) + softening2;
+    if node.children.is_empty() {
+        // Leaves are summed directly; the body's own term vanishes because d = 0
+        let m = T::from_f64(1.0 / NUM_BODIES as f64);
+        for b in &bodies[node.first..node.first + node.count] {
+            let d = b.position - *p;
+            let r2 = d.dot(&d) + softening2;
+            *acc += d * (m / (r2 * r2.sqrt()));
+        }
+    } else if node.size * node.size < T::from_f64(THETA) * T::from_f64(THETA) * dist2 {
+        *acc += d * (node.mass / (dist2 * dist2.sqrt()));
+    } else {
+        for child in &node.children {
+            accumulate(child, p, bodies, acc);
+        }
+    }
+}
+
+fn acceleration<T: Real>(root: &Node<T>, p: &Vec3<T>, bodies: &[Body<T>]) -> Vec3<T> {
+    let mut acc = Vec3::zero();
+    accumulate(root, p, bodies, &mut acc);
+    acc
+}
+
+// Exact O(n²)-style sum for one body in f64, used to measure the approximation error
+fn direct_acceleration<T: Real>(p: &Vec3<f64>, bodies: &[Body<T>]) -> Vec3<f64> {
+    let m = 1.0 / NUM_BODIES as f64;
+    bodies.iter().fold(Vec3::zero(), |acc, b| {
+        let d = b.position.convert() - *p;
+        let r2 = d.dot(&d) + SOFTENING2;
+        acc + d * (m / (r2 * r2.sqrt()))
+    })
+}
+
+fn step<T: Real>(bodies: &mut Vec<Body<T>>) -> (Duration, Duration) {
+    let start = Instant::now();
+    let root = build_tree(bodies);
+    let build_duration = start.elapsed();
+
+    let start = Instant::now();
+    let accelerations: Vec<Vec3<T>> = bodies.par_iter().map(|b| acceleration(&root, &b.position, bodies)).collect();
+    let dt = T::from_f64(DT);
+    bodies.par_iter_mut().zip(accelerations.par_iter()).for_each(|(b, a)| {
+        b.velocity += *a * dt;
+        b.position += b.velocity * dt;
+    });
+    (build_duration, start.elapsed())
+}
+
+// Mean relative error of the T tree walk against the f64 direct sum (theta and rounding together)
+// and against an f64 tree walk over the same bodies (rounding alone, zero in f64)
+fn relative_error<T: Real>(bodies: &mut Vec<Body<T>>) -> (f64, f64) {
+    let root = build_tree(bodies);
+    let mut wide: Vec<Body<f64>> =
+        bodies.iter().map(|b| Body { position: b.position.convert(), velocity: b.velocity.convert() }).collect();
+    let wide_root = build_tree(&mut wide);
+    let stride = bodies.len() / ERROR_SAMPLES;
+    let relative = |a: Vec3<f64>, b: Vec3<f64>| {
+        let err = a - b;
+        (err.dot(&err) / b.dot(&b)).sqrt()
+    };
+    let (vs_direct, vs_tree) = (0..ERROR_SAMPLES).fold((0.0, 0.0), |(direct, tree), s| {
+        let p = bodies[s * stride].position;
+        let approx = acceleration(&root, &p, bodies).convert();
+        let exact = direct_acceleration(&p.convert(), bodies);
+        let wide_approx = acceleration(&wide_root, &p.convert(), &wide);
+        (direct + relative(approx, exact), tree + relative(approx, wide_approx))
+    });
+    (vs_direct / ERROR_SAMPLES as f64, vs_tree / ERROR_SAMPLES as f64)
+}
+
+struct Run {
+    build: Duration,
+    force: Duration,
+    state: Vec<Body<f64>>,
+    error: (f64, f64),
+}
+
+// Converts the bodies to T outside the timed region; the final state comes back widened to f64
+fn run<T: Real>(bodies: &[Body<f64>]) -> Run {
+    let convert = |b: &Body<f64>| Body { position: b.position.convert::<T>(), velocity: b.velocity.convert::<T>() };
+    let bodies: Vec<Body<T>> = bodies.iter().map(convert).collect();
+
+    // Warm-up
+    let mut warmup = bodies[..NUM_BODIES / 100].to_vec();
+    let _ = step(&mut warmup);
+
+    // Benchmark
+    let mut state = bodies.clone();
+    let mut build = Duration::ZERO;
+    let mut force = Duration::ZERO;
+    for _ in 0..STEPS {
+        let (b, f) = step(&mut state);
+        build += b;
+        force += f;
+    }
+
+    let state = state.iter().map(|b| Body { position: b.position.convert(), velocity: b.velocity.convert() }).collect();
+    Run { build, force, state, error: relative_error(&mut bodies.clone()) }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let precision = match args.iter().position(|a| a == "--precision") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("f64") => Precision::F64,
+            Some("f32") => Precision::F32,
+            _ => panic!("--precision expects f64 or f32"),
+        },
+        None => Precision::F64,
+    };
+
+    let bodies = generate_bodies();
+    let Run { build: build_total, force: force_total, state, error } = match precision {
+        Precision::F64 => run::<f64>(&bodies),
+        Precision::F32 => run::<f32>(&bodies),
+    };
+    let total_duration = build_total + force_total;
+
+    // Checksum
+    let momentum = state.iter().fold(Vec3::zero(), |s, b| s + b.velocity);
+    let spread: f64 = state.iter().map(|b| b.position.dot(&b.position)).sum();
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Precision: {}", if precision == Precision::F64 { "f64" } else { "f32" });
+    eprintln!("Tree build: {:.3}s, forces + integration: {:.3}s over {} steps",
+              build_total.as_secs_f64(), force_total.as_secs_f64(), STEPS);
+    eprintln!("Mean relative force error vs f64 direct sum (theta = {}): {:.2e}", THETA, error.0);
+    if precision != Precision::F64 {
+        eprintln!("Mean relative force error vs f64 tree walk: {:.2e}", error.1);
+    }
+    eprintln!("Checksum: {:.9} (momentum: {:.3e}, {:.3e}, {:.3e})",
+              spread, momentum.x / NUM_BODIES as f64, momentum.y / NUM_BODIES as f64, momentum.z / NUM_BODIES as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cloud(n: usize) -> Vec<Body<f64>> {
+        let mut seed = 99u64;
+        (0..n)
+            .map(|_| {
+                let mut coordinate = || next_f64(&mut seed) * 2.0 - 1.0;
+                Body { position: Vec3::new(coordinate(), coordinate(), coordinate()), velocity: Vec3::zero() }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn morton_codes_interleave_the_axes() {
+        assert_eq!(spread_bits(0b1011), 0b001_000_001_001);
+        assert_eq!(spread_bits(0x1F_FFFF), 0x1249249249249249);
+        let origin = Vec3::zero();
+        assert_eq!(morton_code(&Vec3::new(1.0, 0.0, 0.0), &origin, 1.0), 0b100);
+        assert_eq!(morton_code(&Vec3::new(0.0, 1.0, 1.0), &origin, 1.0), 0b011);
+        assert_eq!(morton_code(&Vec3::new(3.0, 0.0, 2.0), &origin, 1.0), 0b101_100);
+    }
+
+    #[test]
+    fn tree_conserves_mass_and_center() {
+        let mut bodies = cloud(5_000);
+        let root = build_tree(&mut bodies);
+        assert_eq!(root.count, bodies.len());
+        assert!((root.mass - bodies.len() as f64 / NUM_BODIES as f64).abs() < 1e-15);
+        let mean = bodies.iter().fold(Vec3::zero(), |s, b| s + b.position) * (1.0 / bodies.len() as f64);
+        assert!((root.center_of_mass - mean).length() < 1e-12);
+
+        // Children cover their parent's bodies in order
+        let mut first = root.first;
+        for child in &root.children {
+            assert_eq!(child.first, first);
+            first += child.count;
+        }
+        assert_eq!(first, root.count);
+    }
+
+    #[test]
+    fn tree_walk_approximates_the_direct_sum() {
+        let mut bodies = cloud(20_000);
+        let root = build_tree(&mut bodies);
+        for b in bodies.iter().step_by(1_000) {
+            let tree = acceleration(&root, &b.position, &bodies);
+            let direct = direct_acceleration(&b.position, &bodies);
+            assert!((tree - direct).length() < 2e-2 * direct.length());
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const NUM_WORDS: usize = 1 << 22; // 32MB per array
+const PASSES: usize = 10;
+const WARMUP_WORDS: usize = 1 << 16;
+
+fn splitmix64(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Dense words to count and reverse, and sparse ones (about 16 of 64 bits set, a middlegame's
+// worth of pieces) used as PEXT/PDEP masks and as board occupancies
+fn generate(n: usize) -> (Vec<u64>, Vec<u64>) {
+    let mut seed = 2024;
+    let words = (0..n).map(|_| splitmix64(&mut seed)).collect();
+    let sparse = (0..n).map(|_| splitmix64(&mut seed) & splitmix64(&mut seed)).collect();
+    (words, sparse)
+}
+
+// The bit-by-bit loops behind PEXT and PDEP, used where BMI2 is not available. With BMI2 only
+// the tests use them, to check the instructions
+#[cfg_attr(all(target_arch = "x86_64", target_feature = "bmi2"), allow(dead_code))]
+fn pext_soft(x: u64, mut mask: u64) -> u64 {
+    let mut result = 0;
+    let mut bit = 1u64;
+    while mask != 0 {
+        if x & mask & mask.wrapping_neg() != 0 {
+            result |= bit;
+        }
+        mask &= mask - 1;
+        bit <<= 1;
+    }
+    result
+}
+
+#[cfg_attr(all(target_arch = "x86_64", target_feature = "bmi2"), allow(dead_code))]
+fn pdep_soft(x: u64, mut mask: u64) -> u64 {
+    let mut result = 0;
+    let mut bit = 1u64;
+    while mask != 0 {
+        if x & bit != 0 {
+            result |= mask & mask.wrapping_neg();
+        }
+        mask &= mask - 1;
+        bit <<= 1;
+    }
+    result
+}
+
+const HARDWARE_PEXT: bool = cfg!(all(target_arch = "x86_64", target_feature = "bmi2"));
+
+// What count_ones() compiles to: aarch64 has no scalar popcount, so it goes through the vector unit
+fn popcount_path() -> (bench_cpu::Path, &'static str) {
+    if cfg!(all(target_arch = "x86_64", target_feature = "popcnt")) {
+        (bench_cpu::Path::Hw, "popcnt")
+    } else if cfg!(target_arch = "aarch64") {
+        (bench_cpu::Path::Simd, "NEON cnt")
+    } else {
+        (bench_cpu::Path::Scalar, "shift-and-add bit counting")
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+fn pext(x: u64, mask: u64) -> u64 {
+    // Safety: this is only compiled when BMI2 is enabled for the whole build
+    unsafe { std::arch::x86_64::_pext_u64(x, mask) }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+fn pdep(x: u64, mask: u64) -> u64 {
+    // Safety: as for pext
+    unsafe { std::arch::x86_64::_pdep_u64(x, mask) }
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+use {pdep_soft as pdep, pext_soft as pext};
+
+// Every square a knight on `from` (a single bit, a1 = bit 0) attacks, by shifting with the
+// files it would wrap across masked off
+fn knight_attacks(from: u64) -> u64 {
+    let l1 = (from >> 1) & 0x7f7f7f7f7f7f7f7f;
+    let l2 = (from >> 2) & 0x3f3f3f3f3f3f3f3f;
+    let r1 = (from << 1) & 0xfefefefefefefefe;
+    let r2 = (from << 2) & 0xfcfcfcfcfcfcfcfc;
+    let h1 = l1 | r1;
+    let h2 = l2 | r2;
+    (h1 << 16) | (h1 >> 16) | (h2 << 8) | (h2 >> 8)
+}
+
+fn popcount(words: &[u64], _: &[u64]) -> u64 {
+    words.iter().map(|w| w.count_ones() as u64).sum()
+}
+
+fn leading_zeros(words: &[u64], _: &[u64]) -> u64 {
+    // Shifted right by a varying amount so the counts cover 0..=63 instead of nearly always 0
+    words.iter().map(|&w| (w >> (w & 63)).leading_zeros() as u64).sum()
+}
+
+fn trailing_zeros(words: &[u64], _: &[u64]) -> u64 {
+    words.iter().map(|&w| (w << (w >> 58)).trailing_zeros() as u64).sum()
+}
+
+fn reverse_bits(words: &[u64], _: &[u64]) -> u64 {
+    words.iter().fold(0u64, |acc, w| acc.wrapping_add(w.reverse_bits()))
+}
+
+fn extract(words: &[u64], masks: &[u64]) -> u64 {
+    words.iter().zip(masks).fold(0u64, |acc, (&w, &m)| acc.wrapping_add(pext(w, m)))
+}
+
+fn deposit(words: &[u64], masks: &[u64]) -> u64 {
+    words.iter().zip(masks).fold(0u64, |acc, (&w, &m)| acc.wrapping_add(pdep(w, m)))
+}
+
+// One knight on every occupied square: walk the set bits (trailing zeros, clear lowest) and count
+// the empty squares each attacks
+fn knight_moves(_: &[u64], boards: &[u64]) -> u64 {
+    let mut moves = 0u64;
+    for &board in boards {
+        let mut pieces = board;
+        while pieces != 0 {
+            let from = 1u64 << pieces.trailing_zeros();
+            moves += (knight_attacks(from) & !board).count_ones() as u64;
+            pieces &= pieces - 1;
+        }
+    }
+    moves
+}
+
+type KernelFn = fn(&[u64], &[u64]) -> u64;
+
+const KERNELS: [(&str, KernelFn); 7] = [
+    ("popcount", popcount),
+    ("leading zeros", leading_zeros),
+    ("trailing zeros", trailing_zeros),
+    ("bit reverse", reverse_bits),
+    ("pext", extract),
+    ("pdep", deposit),
+    ("knight moves", knight_moves),
+];
+
+struct Run {
+    duration: Duration,
+    result: u64,
+}
+
+// black_box on the inputs every pass stops the compiler computing one pass and multiplying
+fn run(kernel: KernelFn, words: &[u64], sparse: &[u64], passes: usize) -> Run {
+    let mut result = 0u64;
+    let start = Instant::now();
+    for _ in 0..passes {
+        result = result.wrapping_add(kernel(black_box(words), black_box(sparse)));
+    }
+    Run { duration: start.elapsed(), result }
+}
+
+fn main() {
+    let (words, sparse) = generate(NUM_WORDS);
+
+    // Warm-up
+    for (_, kernel) in KERNELS {
+        let _ = run(kernel, &words[..WARMUP_WORDS], &sparse[..WARMUP_WORDS], 1);
+    }
+
+    // Benchmark
+    let runs: Vec<Run> = KERNELS.iter().map(|&(_, kernel)| run(kernel, &words, &sparse, PASSES)).collect();
+    let total_duration: Duration = runs.iter().map(|r| r.duration).sum();
+    let checksum = runs.iter().fold(0u64, |acc, r| acc.wrapping_add(r.result));
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Words: {} u64 x {} passes per kernel", NUM_WORDS, PASSES);
+    for ((name, _), r) in KERNELS.iter().zip(&runs) {
+        let ops = (NUM_WORDS * PASSES) as f64 / r.duration.as_secs_f64();
+        eprintln!("  {:<15} {:.3}s ({:.0} Mops/s)", name, r.duration.as_secs_f64(), ops / 1e6);
+    }
+    eprintln!("{}", bench_cpu::report());
+    let (path, detail) = popcount_path();
+    eprintln!("{}", bench_cpu::path("popcount", path, detail));
+    let (path, detail) = if HARDWARE_PEXT {
+        (bench_cpu::Path::Hw, "BMI2 instructions")
+    } else {
+        (bench_cpu::Path::Scalar, "software loops")
+    };
+    eprintln!("{}", bench_cpu::path("pext/pdep", path, detail));
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pext_and_pdep_invert_each_other() {
+        assert_eq!(pext_soft(0b1011_0110, 0b1111_0000), 0b1011);
+        assert_eq!(pdep_soft(0b1011, 0b1111_0000), 0b1011_0000);
+        let (words, masks) = generate(1_000);
+        for (&w, &m) in words.iter().zip(&masks) {
+            assert_eq!(pext(w, m), pext_soft(w, m));
+            assert_eq!(pdep(w, m), pdep_soft(w, m));
+            assert_eq!(pdep(pext(w, m), m), w & m);
+        }
+    }
+
+    #[test]
+    fn knights_attack_up_to_eight_squares() {
+        assert_eq!(knight_attacks(1 << 0), (1 << 10) | (1 << 17)); // a1: c2, b3
+        assert_eq!(knight_attacks(1 << 27).count_ones(), 8); // d4
+        assert_eq!(knight_attacks(1 << 63), (1 << 53) | (1 << 46)); // h8: f7, g6
+        // Knights on a1 and c2 attack each other: 2 + 6 squares, less the two they stand on
+        assert_eq!(knight_moves(&[], &[(1 << 0) | (1 << 10)]), 1 + 5);
+    }
+}
@@ -0,0 +1,176 @@
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+const NUM_KEYS: u64 = 100_000_000;
+const NUM_QUERIES: u64 = 100_000_000;
+const DEFAULT_FP_RATE: f64 = 0.01;
+const CHUNK_SIZE: u64 = 1 << 16;
+
+// SplitMix64 finalizer: keys are sequential ids, so scramble them first
+fn mix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E3779B97F4A7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+    x ^ (x >> 31)
+}
+
+// Query i alternates between an inserted key and one that was never inserted
+fn query_key(i: u64) -> (u64, bool) {
+    if i.is_multiple_of(2) { (i / 2 % NUM_KEYS, true) } else { (NUM_KEYS + i, false) }
+}
+
+struct BloomFilter {
+    words: Vec<AtomicU64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    // Optimal sizing: m = -n ln p / (ln 2)^2 bits and k = (m / n) ln 2 hashes
+    fn new(expected_keys: u64, fp_rate: f64) -> Self {
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-(expected_keys as f64) * fp_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let num_hashes = ((num_bits as f64 / expected_keys as f64) * ln2).round().max(1.0) as u32;
+        let words = (0..num_bits.div_ceil(64)).map(|_| AtomicU64::new(0)).collect();
+        BloomFilter { words, num_bits, num_hashes }
+    }
+
+    // Kirsch-Mitzenmacher double hashing; multiply-shift maps a hash onto [0, num_bits)
+    #[inline]
+    fn bit_indices(&self, key: u64) -> impl Iterator<Item = u64> + '_ {
+        let h1 = mix64(key);
+        let h2 = mix64(h1) | 1;
+        (0..self.num_hashes as u64).map(move |i| {
+            let h = h1.wrapping_add(i.wrapping_mul(h2));
+            ((h as u128 * self.num_bits as u128) >> 64) as u64
+        })
+    }
+
+    fn insert(&self, key: u64) {
+        for bit in self.bit_indices(key) {
+            self.words[(bit / 64) as usize].fetch_or(1 << (bit % 64), Ordering::Relaxed);
+        }
+    }
+
+    fn contains(&self, key: u64) -> bool {
+        self.bit_indices(key)
+            .all(|bit| self.words[(bit / 64) as usize].load(Ordering::Relaxed) & (1 << (bit % 64)) != 0)
+    }
+}
+
+fn insert_all(filter: &BloomFilter, count: u64) {
+    (0..count.div_ceil(CHUNK_SIZE)).into_par_iter().for_each(|chunk| {
+        let end = ((chunk + 1) * CHUNK_SIZE).min(count);
+        for key in chunk * CHUNK_SIZE..end {
+            filter.insert(key);
+        }
+    });
+}
+
+// Returns (members found, false positives)
+fn query_all(filter: &BloomFilter, count: u64) -> (u64, u64) {
+    (0..count.div_ceil(CHUNK_SIZE))
+        .into_par_iter()
+        .map(|chunk| {
+            let end = ((chunk + 1) * CHUNK_SIZE).min(count);
+            let (mut found, mut false_positives) = (0, 0);
+            for i in chunk * CHUNK_SIZE..end {
+                let (key, member) = query_key(i);
+                if filter.contains(key) {
+                    if member { found += 1 } else { false_positives += 1 }
+                }
+            }
+            (found, false_positives)
+        })
+        .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1))
+}
+
+fn parse_fp_rate() -> f64 {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--fp-rate") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|p| *p > 0.0 && *p < 1.0)
+            .expect("--fp-rate expects a probability between 0 and 1"),
+        None => DEFAULT_FP_RATE,
+    }
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let fp_rate = parse_fp_rate();
+
+    // Warm-up
+    {
+        let warmup = BloomFilter::new(NUM_KEYS / 100, fp_rate);
+        insert_all(&warmup, NUM_KEYS / 100);
+        let _ = query_all(&warmup, NUM_QUERIES / 100);
+    }
+
+    let filter = BloomFilter::new(NUM_KEYS, fp_rate);
+
+    // Benchmark insertion
+    let start = Instant::now();
+    measure.start();
+    insert_all(&filter, NUM_KEYS);
+    measure.stop();
+    let insert_duration = start.elapsed();
+
+    // Benchmark queries
+    let start = Instant::now();
+    measure.start();
+    let (found, false_positives) = query_all(&filter, NUM_QUERIES);
+    measure.stop();
+    let query_duration = start.elapsed();
+
+    let non_members = NUM_QUERIES / 2;
+    let set_bits: u64 = filter.words.iter().map(|w| w.load(Ordering::Relaxed).count_ones() as u64).sum();
+
+    measure.report();
+    eprintln!("Insert: {:.6}s ({:.0} ops/s), query: {:.6}s ({:.0} ops/s)",
+              insert_duration.as_secs_f64(), NUM_KEYS as f64 / insert_duration.as_secs_f64(),
+              query_duration.as_secs_f64(), NUM_QUERIES as f64 / query_duration.as_secs_f64());
+    eprintln!("Bits: {} ({} MB), hashes: {}, target FP rate: {}, measured FP rate: {:.6}",
+              filter.num_bits, filter.num_bits / 8 / 1_000_000, filter.num_hashes,
+              fp_rate, false_positives as f64 / non_members as f64);
+    eprintln!("Checksum: {} found, {} false positives, {} bits set", found, false_positives, set_bits);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizing_follows_the_textbook_formulas() {
+        let filter = BloomFilter::new(1_000, 0.01);
+        assert_eq!(filter.num_bits, 9_586); // ceil(1000 ln 100 / ln² 2)
+        assert_eq!(filter.num_hashes, 7);
+        assert_eq!(filter.words.len(), 150);
+    }
+
+    #[test]
+    fn inserted_keys_are_always_found() {
+        let filter = BloomFilter::new(100_000, 0.01);
+        insert_all(&filter, 100_000);
+        assert!((0..100_000).all(|key| filter.contains(key)));
+        assert!(filter.bit_indices(12_345).all(|bit| bit < filter.num_bits));
+    }
+
+    #[test]
+    fn false_positive_rate_is_near_target() {
+        for fp_rate in [0.1, 0.01] {
+            let filter = BloomFilter::new(200_000, fp_rate);
+            insert_all(&filter, 200_000);
+            let false_positives = (NUM_KEYS..NUM_KEYS + 200_000).filter(|&key| filter.contains(key)).count();
+            let measured = false_positives as f64 / 200_000.0;
+            assert!(measured < 1.5 * fp_rate && measured > 0.5 * fp_rate, "target {}, measured {}", fp_rate, measured);
+        }
+    }
+
+    #[test]
+    fn empty_filter_contains_nothing() {
+        let filter = BloomFilter::new(1_000, 0.01);
+        assert!((0..1_000).all(|key| !filter.contains(key)));
+    }
+}
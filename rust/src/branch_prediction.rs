@@ -0,0 +1,161 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const NUM_ELEMENTS: usize = 1 << 24;
+const THRESHOLD: i32 = 128; // values are uniform in 0..256, so half pass
+const PASSES: usize = 10;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn generate(n: usize) -> Vec<i32> {
+    let mut seed = 42;
+    (0..n).map(|_| (next_random(&mut seed) % 256) as i32).collect()
+}
+
+// The plain `if`: the compiler is free to keep the branch, turn it into a cmov or vectorize it
+fn sum_natural(data: &[i32]) -> i64 {
+    let mut sum = 0i64;
+    for &v in data {
+        if v >= THRESHOLD {
+            sum += v as i64;
+        }
+    }
+    sum
+}
+
+// The same `if` with black_box inside it. An opaque operation cannot be executed speculatively,
+// so the compiler has to keep a real conditional branch
+fn sum_branchy(data: &[i32]) -> i64 {
+    let mut sum = 0i64;
+    for &v in data {
+        if v >= THRESHOLD {
+            sum += black_box(v) as i64;
+        }
+    }
+    sum
+}
+
+// No branch to predict: the comparison becomes an all-ones or all-zeros mask
+fn sum_branchless(data: &[i32]) -> i64 {
+    let mut sum = 0i64;
+    for &v in data {
+        let mask = -((v >= THRESHOLD) as i32);
+        sum += (v & mask) as i64;
+    }
+    sum
+}
+
+#[derive(Clone, Copy)]
+enum Variant {
+    Natural,
+    Branchy,
+    Branchless,
+}
+
+const VARIANTS: [Variant; 3] = [Variant::Natural, Variant::Branchy, Variant::Branchless];
+
+impl Variant {
+    fn name(self) -> &'static str {
+        match self {
+            Variant::Natural => "natural",
+            Variant::Branchy => "branchy",
+            Variant::Branchless => "branchless",
+        }
+    }
+
+    fn kernel(self) -> fn(&[i32]) -> i64 {
+        match self {
+            Variant::Natural => sum_natural,
+            Variant::Branchy => sum_branchy,
+            Variant::Branchless => sum_branchless,
+        }
+    }
+}
+
+struct Run {
+    duration: Duration,
+    sum: i64,
+}
+
+// black_box on the input every pass stops the compiler computing one pass and multiplying
+fn run(variant: Variant, data: &[i32], passes: usize) -> Run {
+    let kernel = variant.kernel();
+    let mut sum = 0i64;
+    let start = Instant::now();
+    for _ in 0..passes {
+        sum += kernel(black_box(data));
+    }
+    Run { duration: start.elapsed(), sum }
+}
+
+fn ns_per_element(run: &Run) -> f64 {
+    run.duration.as_secs_f64() * 1e9 / (NUM_ELEMENTS * PASSES) as f64
+}
+
+fn main() {
+    let shuffled = generate(NUM_ELEMENTS);
+    let mut sorted = shuffled.clone();
+    sorted.sort_unstable();
+
+    // Warm-up
+    for variant in VARIANTS {
+        let _ = run(variant, &sorted, 1);
+        let _ = run(variant, &shuffled, 1);
+    }
+
+    // Benchmark
+    let mut total_duration = Duration::ZERO;
+    let mut lines = Vec::new();
+    let mut sums = Vec::new();
+    let mut ratios = Vec::new();
+    for variant in VARIANTS {
+        let on_sorted = run(variant, &sorted, PASSES);
+        let on_shuffled = run(variant, &shuffled, PASSES);
+        for (data, r) in [("sorted", &on_sorted), ("shuffled", &on_shuffled)] {
+            lines.push(format!("  {:<10} {:<8} {:.3}s ({:.2} ns/element)",
+                               variant.name(), data, r.duration.as_secs_f64(), ns_per_element(r)));
+            total_duration += r.duration;
+            sums.push(r.sum);
+        }
+        ratios.push(format!("{} {:.2}x", variant.name(),
+                            on_shuffled.duration.as_secs_f64() / on_sorted.duration.as_secs_f64()));
+    }
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Elements: {} values in 0..256, summing those >= {}, {} passes", NUM_ELEMENTS, THRESHOLD, PASSES);
+    for line in &lines {
+        eprintln!("{}", line);
+    }
+    eprintln!("Shuffled/sorted time: {}", ratios.join(", "));
+    eprintln!("Checksum: {} (all variants match: {})", sums[0], sums.iter().all(|&s| s == sums[0]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants_agree_around_the_threshold() {
+        let data = [0, THRESHOLD - 1, THRESHOLD, THRESHOLD + 1, 255, 7, THRESHOLD];
+        let expected = 3 * THRESHOLD as i64 + 1 + 255;
+        for variant in VARIANTS {
+            assert_eq!(variant.kernel()(&data), expected, "{}", variant.name());
+        }
+    }
+
+    #[test]
+    fn sorting_does_not_change_the_sum() {
+        let shuffled = generate(10_000);
+        assert!(shuffled.iter().all(|v| (0..256).contains(v)));
+        let mut sorted = shuffled.clone();
+        sorted.sort_unstable();
+        for variant in VARIANTS {
+            assert_eq!(run(variant, &sorted, 2).sum, run(variant, &shuffled, 2).sum, "{}", variant.name());
+        }
+    }
+}
@@ -0,0 +1,448 @@
+use bench_math::Vec3;
+use rayon::prelude::*;
+use std::time::Instant;
+
+const DEFAULT_TRIANGLES: usize = 2_000_000;
+const WARMUP_TRIANGLES: usize = 100_000;
+const BINS: usize = 16;
+const MIN_LEAF: usize = 2; // nodes this small are never split
+const MAX_LEAF: usize = 8; // nodes larger than this are always split, whatever SAH says
+const TRAVERSAL_COST: f32 = 1.0; // relative to one triangle test
+const TASK_SIZE: usize = 32 * 1024; // smaller nodes build their whole subtree on one thread
+const PARALLEL_BIN_SIZE: usize = 256 * 1024; // larger nodes bin their triangles in parallel
+const BIN_CHUNK: usize = 64 * 1024;
+const SEED: u64 = 0x6276_685f_6275_696c;
+
+#[derive(Clone, Copy)]
+struct Triangle {
+    v0: Vec3<f32>,
+    v1: Vec3<f32>,
+    v2: Vec3<f32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Aabb {
+    min: Vec3<f32>,
+    max: Vec3<f32>,
+}
+
+impl Aabb {
+    const EMPTY: Aabb = Aabb {
+        min: Vec3 { x: f32::INFINITY, y: f32::INFINITY, z: f32::INFINITY },
+        max: Vec3 { x: f32::NEG_INFINITY, y: f32::NEG_INFINITY, z: f32::NEG_INFINITY },
+    };
+
+    fn grow(&mut self, other: &Aabb) {
+        self.min = self.min.min(&other.min);
+        self.max = self.max.max(&other.max);
+    }
+
+    fn grow_point(&mut self, p: &Vec3<f32>) {
+        self.min = self.min.min(p);
+        self.max = self.max.max(p);
+    }
+
+    fn area(&self) -> f32 {
+        let d = self.max - self.min;
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+}
+
+// What the builder reads per triangle: its box, and the box's center as the split key
+#[derive(Clone, Copy)]
+struct Primitive {
+    bounds: Aabb,
+    centroid: Vec3<f32>,
+}
+
+// 32 bytes in depth-first order: an inner node's left child is the next node and offset is
+// the distance to its right child; a leaf owns order[offset..offset + count]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Node {
+    bounds: Aabb,
+    offset: u32,
+    count: u32,
+}
+
+struct Bvh {
+    nodes: Vec<Node>,
+    order: Vec<u32>,
+}
+
+#[derive(Clone, Copy)]
+struct Bin {
+    bounds: Aabb,
+    count: u32,
+}
+
+type Bins = [[Bin; BINS]; 3];
+
+const EMPTY_BINS: Bins = [[Bin { bounds: Aabb::EMPTY, count: 0 }; BINS]; 3];
+
+#[derive(Clone, Copy)]
+enum Split {
+    // Triangles whose centroid falls in a bin below `bin` on `axis` go left
+    Binned { axis: usize, bin: usize, lo: f32, scale: f32 },
+    // Every centroid in the same bin (coincident triangles): split the list in half
+    Halves,
+}
+
+// SplitMix64; the top 24 bits become a float in [0, 1) exactly, in both languages
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn range(&mut self, lo: f32, hi: f32) -> f32 {
+        lo + (hi - lo) * ((self.next() >> 40) as f32 * (1.0 / 16_777_216.0))
+    }
+
+    fn point(&mut self, half: f32) -> Vec3<f32> {
+        let x = self.range(-half, half);
+        let y = self.range(-half, half);
+        let z = self.range(-half, half);
+        Vec3::new(x, y, z)
+    }
+}
+
+// Small triangles, each corner within 0.01 of its center, scattered through the [-1, 1] cube
+fn make_triangles(rng: &mut Rng, count: usize) -> Vec<Triangle> {
+    (0..count)
+        .map(|_| {
+            let center = rng.point(1.0);
+            let v0 = center + rng.point(0.01);
+            let v1 = center + rng.point(0.01);
+            let v2 = center + rng.point(0.01);
+            Triangle { v0, v1, v2 }
+        })
+        .collect()
+}
+
+fn primitive(triangle: &Triangle) -> Primitive {
+    let mut bounds = Aabb::EMPTY;
+    bounds.grow_point(&triangle.v0);
+    bounds.grow_point(&triangle.v1);
+    bounds.grow_point(&triangle.v2);
+    Primitive { bounds, centroid: (bounds.min + bounds.max) * 0.5 }
+}
+
+fn bin_index(centroid: f32, lo: f32, scale: f32) -> usize {
+    (((centroid - lo) * scale) as usize).min(BINS - 1)
+}
+
+// The node's box and the box of its triangles' centroids, which the bins divide
+fn node_bounds(prims: &[Primitive], order: &[u32]) -> (Aabb, Aabb) {
+    let mut bounds = Aabb::EMPTY;
+    let mut centroids = Aabb::EMPTY;
+    for &i in order {
+        let p = &prims[i as usize];
+        bounds.grow(&p.bounds);
+        centroids.grow_point(&p.centroid);
+    }
+    (bounds, centroids)
+}
+
+fn bin(prims: &[Primitive], order: &[u32], centroids: &Aabb, scale: &Vec3<f32>) -> Bins {
+    let mut bins = EMPTY_BINS;
+    for &i in order {
+        let p = &prims[i as usize];
+        for (axis, axis_bins) in bins.iter_mut().enumerate() {
+            let b = &mut axis_bins[bin_index(p.centroid.axis(axis), centroids.min.axis(axis), scale.axis(axis))];
+            b.bounds.grow(&p.bounds);
+            b.count += 1;
+        }
+    }
+    bins
+}
+
+// Boxes only grow and counts only add, so merging chunks in any order gives the same bins
+fn merge_bins(mut a: Bins, b: Bins) -> Bins {
+    for (axis_a, axis_b) in a.iter_mut().zip(&b) {
+        for (x, y) in axis_a.iter_mut().zip(axis_b) {
+            x.bounds.grow(&y.bounds);
+            x.count += y.count;
+        }
+    }
+    a
+}
+
+// Bins the node's triangles on all three axes and picks the cheapest of the BINS - 1 planes
+// per axis by the surface area heuristic, or None when a leaf is cheaper
+fn plan(prims: &[Primitive], order: &[u32], parallel: bool) -> (Aabb, Option<Split>) {
+    let (bounds, centroids) = if parallel {
+        order
+            .par_chunks(BIN_CHUNK)
+            .map(|chunk| node_bounds(prims, chunk))
+            .reduce(|| (Aabb::EMPTY, Aabb::EMPTY), |mut a, b| {
+                a.0.grow(&b.0);
+                a.1.grow(&b.1);
+                a
+            })
+    } else {
+        node_bounds(prims, order)
+    };
+    let count = order.len();
+    if count <= MIN_LEAF {
+        return (bounds, None);
+    }
+    let extent = centroids.max - centroids.min;
+    let scale = extent.map(|e| if e > 0.0 { BINS as f32 / e } else { 0.0 });
+    let bins = if parallel {
+        order
+            .par_chunks(BIN_CHUNK)
+            .map(|chunk| bin(prims, chunk, &centroids, &scale))
+            .reduce(|| EMPTY_BINS, merge_bins)
+    } else {
+        bin(prims, order, &centroids, &scale)
+    };
+
+    let mut best: Option<(f32, usize, usize)> = None;
+    for (axis, axis_bins) in bins.iter().enumerate() {
+        if extent.axis(axis) <= 0.0 {
+            continue;
+        }
+        // Right-to-left sweep first, so the left-to-right one can price each plane as it goes
+        let mut right_cost = [0.0f32; BINS];
+        let (mut right, mut right_count) = (Aabb::EMPTY, 0);
+        for b in (1..BINS).rev() {
+            right.grow(&axis_bins[b].bounds);
+            right_count += axis_bins[b].count;
+            right_cost[b] = if right_count > 0 { right.area() * right_count as f32 } else { 0.0 };
+        }
+        let (mut left, mut left_count) = (Aabb::EMPTY, 0);
+        for b in 1..BINS {
+            left.grow(&axis_bins[b - 1].bounds);
+            left_count += axis_bins[b - 1].count;
+            if left_count == 0 || left_count as usize == count {
+                continue;
+            }
+            let cost = left.area() * left_count as f32 + right_cost[b];
+            if best.is_none_or(|(c, _, _)| cost < c) {
+                best = Some((cost, axis, b));
+            }
+        }
+    }
+
+    let area = bounds.area();
+    match best {
+        None if count > MAX_LEAF => (bounds, Some(Split::Halves)),
+        None => (bounds, None),
+        Some((cost, axis, bin)) => {
+            if count <= MAX_LEAF && TRAVERSAL_COST * area + cost >= area * count as f32 {
+                return (bounds, None);
+            }
+            (bounds, Some(Split::Binned { axis, bin, lo: centroids.min.axis(axis), scale: scale.axis(axis) }))
+        }
+    }
+}
+
+// In-place two-way partition; returns how many triangles went left
+fn partition(prims: &[Primitive], order: &mut [u32], split: Split) -> usize {
+    let Split::Binned { axis, bin, lo, scale } = split else {
+        return order.len() / 2;
+    };
+    let (mut i, mut j) = (0, order.len());
+    while i < j {
+        if bin_index(prims[order[i] as usize].centroid.axis(axis), lo, scale) < bin {
+            i += 1;
+        } else {
+            j -= 1;
+            order.swap(i, j);
+        }
+    }
+    i
+}
+
+fn build_sequential(prims: &[Primitive], order: &mut [u32], first: usize, nodes: &mut Vec<Node>) {
+    let (bounds, split) = plan(prims, order, false);
+    let Some(split) = split else {
+        nodes.push(Node { bounds, offset: first as u32, count: order.len() as u32 });
+        return;
+    };
+    let mid = partition(prims, order, split);
+    let (left, right) = order.split_at_mut(mid);
+    let at = nodes.len();
+    nodes.push(Node { bounds, offset: 0, count: 0 });
+    build_sequential(prims, left, first, nodes);
+    nodes[at].offset = (nodes.len() - at) as u32;
+    build_sequential(prims, right, first + mid, nodes);
+}
+
+// Subtrees are built in parallel into separate runs of nodes, returned in depth-first order so
+// one concatenation at the end lays them out exactly as build_sequential would
+fn build_parallel(prims: &[Primitive], order: &mut [u32], first: usize) -> Vec<Vec<Node>> {
+    if order.len() <= TASK_SIZE {
+        let mut nodes = Vec::new();
+        build_sequential(prims, order, first, &mut nodes);
+        return vec![nodes];
+    }
+    let (bounds, split) = plan(prims, order, order.len() > PARALLEL_BIN_SIZE);
+    let Some(split) = split else {
+        return vec![vec![Node { bounds, offset: first as u32, count: order.len() as u32 }]];
+    };
+    let mid = partition(prims, order, split);
+    let (left, right) = order.split_at_mut(mid);
+    let (left, right) = rayon::join(|| build_parallel(prims, left, first), || build_parallel(prims, right, first + mid));
+    let left_nodes: usize = left.iter().map(Vec::len).sum();
+    let mut runs = vec![vec![Node { bounds, offset: (1 + left_nodes) as u32, count: 0 }]];
+    runs.extend(left);
+    runs.extend(right);
+    runs
+}
+
+fn build(triangles: &[Triangle], parallel: bool) -> Bvh {
+    let mut order: Vec<u32> = (0..triangles.len() as u32).collect();
+    let nodes = if parallel {
+        let prims: Vec<Primitive> = triangles.par_iter().map(primitive).collect();
+        build_parallel(&prims, &mut order, 0).concat()
+    } else {
+        let prims: Vec<Primitive> = triangles.iter().map(primitive).collect();
+        let mut nodes = Vec::new();
+        build_sequential(&prims, &mut order, 0, &mut nodes);
+        nodes
+    };
+    Bvh { nodes, order }
+}
+
+struct Quality {
+    leaves: usize,
+    max_leaf: u32,
+    max_depth: usize,
+    depth_sum: u64, // over triangles, of the depth of the leaf holding them
+    sah_cost: f64,  // expected cost of a random ray, in triangle tests
+}
+
+fn quality(bvh: &Bvh) -> Quality {
+    let root_area = bvh.nodes[0].bounds.area() as f64;
+    let mut q = Quality { leaves: 0, max_leaf: 0, max_depth: 0, depth_sum: 0, sah_cost: 0.0 };
+    // Right child pushed first, so nodes are visited in index order
+    let mut stack = vec![(0usize, 0usize)];
+    while let Some((index, depth)) = stack.pop() {
+        let node = &bvh.nodes[index];
+        let weight = node.bounds.area() as f64 / root_area;
+        q.max_depth = q.max_depth.max(depth);
+        if node.count > 0 {
+            q.leaves += 1;
+            q.max_leaf = q.max_leaf.max(node.count);
+            q.depth_sum += node.count as u64 * depth as u64;
+            q.sah_cost += weight * node.count as f64;
+        } else {
+            q.sah_cost += weight * TRAVERSAL_COST as f64;
+            stack.push((index + node.offset as usize, depth + 1));
+            stack.push((index + 1, depth + 1));
+        }
+    }
+    q
+}
+
+fn parse_triangles() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--triangles") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .expect("--triangles expects a positive integer"),
+        None => DEFAULT_TRIANGLES,
+    }
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let count = parse_triangles();
+    let triangles = make_triangles(&mut Rng(SEED), count);
+
+    // Warm-up
+    let warmup = make_triangles(&mut Rng(SEED ^ 1), WARMUP_TRIANGLES);
+    let _ = build(&warmup, false);
+    let _ = build(&warmup, true);
+
+    // Benchmark
+    let start = Instant::now();
+    measure.start();
+    let sequential = build(&triangles, false);
+    measure.stop();
+    let sequential_time = start.elapsed();
+    let start = Instant::now();
+    measure.start();
+    let parallel = build(&triangles, true);
+    measure.stop();
+    let parallel_time = start.elapsed();
+
+    if parallel.nodes != sequential.nodes || parallel.order != sequential.order {
+        eprintln!("Parallel BVH differs from the single-threaded one");
+        std::process::exit(1);
+    }
+    let q = quality(&sequential);
+
+    measure.report();
+    eprintln!("Triangles: {}, {}-bin SAH on 3 axes, leaves of up to {} triangles where SAH prefers them",
+              count, BINS, MAX_LEAF);
+    for (name, time) in [("single-threaded", sequential_time), ("parallel", parallel_time)] {
+        eprintln!("{}: {:.3}s, {:.2}M triangles/s", name, time.as_secs_f64(), count as f64 / time.as_secs_f64() / 1e6);
+    }
+    eprintln!("Parallel speedup: {:.2}x on {} threads", sequential_time.as_secs_f64() / parallel_time.as_secs_f64(),
+              rayon::current_num_threads());
+    eprintln!("Tree: {} nodes, {} leaves (mean {:.2}, max {} triangles), depth max {} mean {:.2}, SAH cost {:.3}",
+              sequential.nodes.len(), q.leaves, count as f64 / q.leaves as f64, q.max_leaf, q.max_depth,
+              q.depth_sum as f64 / count as f64, q.sah_cost);
+    eprintln!("Parallel and single-threaded trees match node for node");
+    eprintln!("Checksum: {}", q.depth_sum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contains(outer: &Aabb, inner: &Aabb) -> bool {
+        outer.min.min(&inner.min) == outer.min && outer.max.max(&inner.max) == outer.max
+    }
+
+    // Every triangle in exactly one leaf, every box enclosing what is under it
+    fn check(bvh: &Bvh, triangles: &[Triangle]) {
+        let mut seen = vec![false; triangles.len()];
+        let mut stack = vec![0usize];
+        while let Some(index) = stack.pop() {
+            let node = &bvh.nodes[index];
+            if node.count > 0 {
+                for &i in &bvh.order[node.offset as usize..(node.offset + node.count) as usize] {
+                    assert!(!seen[i as usize]);
+                    seen[i as usize] = true;
+                    assert!(contains(&node.bounds, &primitive(&triangles[i as usize]).bounds));
+                }
+            } else {
+                for child in [index + 1, index + node.offset as usize] {
+                    assert!(contains(&node.bounds, &bvh.nodes[child].bounds));
+                    stack.push(child);
+                }
+            }
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn parallel_build_matches_sequential() {
+        // Large enough that the root is binned in parallel and split into several tasks
+        let triangles = make_triangles(&mut Rng(SEED), 300_000);
+        let sequential = build(&triangles, false);
+        check(&sequential, &triangles);
+        let parallel = build(&triangles, true);
+        assert!(parallel.nodes == sequential.nodes && parallel.order == sequential.order);
+        let q = quality(&sequential);
+        assert!(q.max_leaf as usize <= MAX_LEAF && q.sah_cost < triangles.len() as f64 / 100.0);
+    }
+
+    #[test]
+    fn coincident_triangles_still_split() {
+        let t = Triangle { v0: Vec3::zero(), v1: Vec3::new(1.0, 0.0, 0.0), v2: Vec3::new(0.0, 1.0, 0.0) };
+        let triangles = vec![t; 100];
+        let bvh = build(&triangles, false);
+        check(&bvh, &triangles);
+        assert!(quality(&bvh).max_leaf as usize <= MAX_LEAF);
+    }
+}
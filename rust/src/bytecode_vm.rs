@@ -0,0 +1,333 @@
+use std::time::{Duration, Instant};
+
+const ITERATIONS: i32 = 50_000_000;
+const SEED: i32 = 12345;
+const STACK_SIZE: usize = 64;
+const NUM_LOCALS: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum Op {
+    Push,
+    Load,
+    Store,
+    Dup,
+    Add,
+    Sub,
+    Mul,
+    And,
+    Xor,
+    Shr,
+    Lt,
+    Jump,
+    JumpIfZero,
+    JumpIfNonZero,
+    Halt,
+}
+
+#[derive(Clone, Copy)]
+struct Instr {
+    op: Op,
+    arg: i32,
+}
+
+fn instr(op: Op, arg: i32) -> Instr {
+    Instr { op, arg }
+}
+
+// i = 0; acc = 0; x = SEED
+// do {
+//     x = (x * 1103515245 + 12345) & 0x7fffffff
+//     acc += (x >> 7) & 1023
+//     if (x & 1) acc ^= i else acc -= x & 15
+//     i += 1
+// } while (i < ITERATIONS)
+// return acc
+fn build_program() -> Vec<Instr> {
+    use Op::*;
+    const I: i32 = 0;
+    const ACC: i32 = 1;
+    const X: i32 = 2;
+    const LOOP: i32 = 6;
+    const ELSE: i32 = 31;
+    const NEXT: i32 = 37;
+    vec![
+        instr(Push, 0), instr(Store, I),
+        instr(Push, 0), instr(Store, ACC),
+        instr(Push, SEED), instr(Store, X),
+        // LOOP
+        instr(Load, X), instr(Push, 1103515245), instr(Mul, 0), instr(Push, 12345), instr(Add, 0),
+        instr(Push, 0x7fffffff), instr(And, 0), instr(Store, X),
+        instr(Load, ACC), instr(Load, X), instr(Push, 7), instr(Shr, 0), instr(Push, 1023), instr(And, 0),
+        instr(Add, 0), instr(Store, ACC),
+        instr(Load, X), instr(Push, 1), instr(And, 0), instr(JumpIfZero, ELSE),
+        instr(Load, ACC), instr(Load, I), instr(Xor, 0), instr(Store, ACC), instr(Jump, NEXT),
+        // ELSE
+        instr(Load, ACC), instr(Load, X), instr(Push, 15), instr(And, 0), instr(Sub, 0), instr(Store, ACC),
+        // NEXT
+        instr(Load, I), instr(Push, 1), instr(Add, 0), instr(Dup, 0), instr(Store, I),
+        instr(Push, ITERATIONS), instr(Lt, 0), instr(JumpIfNonZero, LOOP),
+        instr(Load, ACC), instr(Halt, 0),
+    ]
+}
+
+// The same computation in plain Rust: the result every VM must reproduce, plus the dispatch count
+fn run_native(iterations: i32) -> (i64, u64) {
+    let (mut acc, mut x, mut odd) = (0i64, SEED as i64, 0u64);
+    for i in 0..iterations as i64 {
+        x = x.wrapping_mul(1103515245).wrapping_add(12345) & 0x7fffffff;
+        acc = acc.wrapping_add((x >> 7) & 1023);
+        if x & 1 != 0 {
+            acc ^= i;
+            odd += 1;
+        } else {
+            acc = acc.wrapping_sub(x & 15);
+        }
+    }
+    // 6 setup + 28 per iteration + 5 (then) or 6 (else) + 2 to return
+    let n = iterations as u64;
+    (acc, 6 + 28 * n + 5 * odd + 6 * (n - odd) + 2)
+}
+
+// Variant 1: one `match` over the opcode in a loop
+fn run_match(code: &[Instr]) -> i64 {
+    let mut stack = [0i64; STACK_SIZE];
+    let mut locals = [0i64; NUM_LOCALS];
+    let mut sp = 0usize;
+    let mut pc = 0usize;
+    loop {
+        let Instr { op, arg } = code[pc];
+        pc += 1;
+        match op {
+            Op::Push => {
+                stack[sp] = arg as i64;
+                sp += 1;
+            }
+            Op::Load => {
+                stack[sp] = locals[arg as usize];
+                sp += 1;
+            }
+            Op::Store => {
+                sp -= 1;
+                locals[arg as usize] = stack[sp];
+            }
+            Op::Dup => {
+                stack[sp] = stack[sp - 1];
+                sp += 1;
+            }
+            Op::Add => {
+                sp -= 1;
+                stack[sp - 1] = stack[sp - 1].wrapping_add(stack[sp]);
+            }
+            Op::Sub => {
+                sp -= 1;
+                stack[sp - 1] = stack[sp - 1].wrapping_sub(stack[sp]);
+            }
+            Op::Mul => {
+                sp -= 1;
+                stack[sp - 1] = stack[sp - 1].wrapping_mul(stack[sp]);
+            }
+            Op::And => {
+                sp -= 1;
+                stack[sp - 1] &= stack[sp];
+            }
+            Op::Xor => {
+                sp -= 1;
+                stack[sp - 1] ^= stack[sp];
+            }
+            Op::Shr => {
+                sp -= 1;
+                stack[sp - 1] >>= stack[sp];
+            }
+            Op::Lt => {
+                sp -= 1;
+                stack[sp - 1] = (stack[sp - 1] < stack[sp]) as i64;
+            }
+            Op::Jump => pc = arg as usize,
+            Op::JumpIfZero => {
+                sp -= 1;
+                if stack[sp] == 0 {
+                    pc = arg as usize;
+                }
+            }
+            Op::JumpIfNonZero => {
+                sp -= 1;
+                if stack[sp] != 0 {
+                    pc = arg as usize;
+                }
+            }
+            Op::Halt => return stack[sp - 1],
+        }
+    }
+}
+
+// Variant 2: call threading. The program is pre-decoded into handler pointers, one indirect call per instruction
+struct Vm {
+    stack: [i64; STACK_SIZE],
+    locals: [i64; NUM_LOCALS],
+    sp: usize,
+    pc: usize,
+    running: bool,
+}
+
+type Handler = fn(&mut Vm, i32);
+
+#[derive(Clone, Copy)]
+struct Threaded {
+    handler: Handler,
+    arg: i32,
+}
+
+impl Vm {
+    fn push(&mut self, v: i64) {
+        self.stack[self.sp] = v;
+        self.sp += 1;
+    }
+
+    fn pop(&mut self) -> i64 {
+        self.sp -= 1;
+        self.stack[self.sp]
+    }
+
+    fn binary(&mut self, f: impl Fn(i64, i64) -> i64) {
+        let b = self.pop();
+        let a = self.pop();
+        self.push(f(a, b));
+    }
+}
+
+fn op_push(vm: &mut Vm, arg: i32) { vm.push(arg as i64) }
+fn op_load(vm: &mut Vm, arg: i32) { vm.push(vm.locals[arg as usize]) }
+fn op_store(vm: &mut Vm, arg: i32) { vm.locals[arg as usize] = vm.pop() }
+fn op_dup(vm: &mut Vm, _: i32) { vm.push(vm.stack[vm.sp - 1]) }
+fn op_add(vm: &mut Vm, _: i32) { vm.binary(i64::wrapping_add) }
+fn op_sub(vm: &mut Vm, _: i32) { vm.binary(i64::wrapping_sub) }
+fn op_mul(vm: &mut Vm, _: i32) { vm.binary(i64::wrapping_mul) }
+fn op_and(vm: &mut Vm, _: i32) { vm.binary(|a, b| a & b) }
+fn op_xor(vm: &mut Vm, _: i32) { vm.binary(|a, b| a ^ b) }
+fn op_shr(vm: &mut Vm, _: i32) { vm.binary(|a, b| a >> b) }
+fn op_lt(vm: &mut Vm, _: i32) { vm.binary(|a, b| (a < b) as i64) }
+fn op_jump(vm: &mut Vm, arg: i32) { vm.pc = arg as usize }
+fn op_jump_if_zero(vm: &mut Vm, arg: i32) {
+    if vm.pop() == 0 {
+        vm.pc = arg as usize;
+    }
+}
+fn op_jump_if_nonzero(vm: &mut Vm, arg: i32) {
+    if vm.pop() != 0 {
+        vm.pc = arg as usize;
+    }
+}
+fn op_halt(vm: &mut Vm, _: i32) { vm.running = false }
+
+// Indexed by `Op as usize`
+const HANDLERS: [Handler; 15] = [
+    op_push, op_load, op_store, op_dup, op_add, op_sub, op_mul, op_and, op_xor, op_shr, op_lt,
+    op_jump, op_jump_if_zero, op_jump_if_nonzero, op_halt,
+];
+
+fn thread_code(code: &[Instr]) -> Vec<Threaded> {
+    code.iter().map(|i| Threaded { handler: HANDLERS[i.op as usize], arg: i.arg }).collect()
+}
+
+fn run_threaded(code: &[Threaded]) -> i64 {
+    let mut vm = Vm { stack: [0; STACK_SIZE], locals: [0; NUM_LOCALS], sp: 0, pc: 0, running: true };
+    while vm.running {
+        let Threaded { handler, arg } = code[vm.pc];
+        vm.pc += 1;
+        handler(&mut vm, arg);
+    }
+    vm.stack[vm.sp - 1]
+}
+
+fn timed(f: impl FnOnce() -> i64) -> (Duration, i64) {
+    let start = Instant::now();
+    let result = f();
+    (start.elapsed(), result)
+}
+
+fn main() {
+    let code = build_program();
+    let threaded = thread_code(&code);
+
+    // Warm-up on a short run of the same program
+    let mut warmup = code.clone();
+    for i in warmup.iter_mut().filter(|i| i.op == Op::Push && i.arg == ITERATIONS) {
+        i.arg = ITERATIONS / 100;
+    }
+    let _ = run_match(&warmup);
+    let _ = run_threaded(&thread_code(&warmup));
+
+    // Benchmark
+    let start = Instant::now();
+    let (native_result, dispatches) = run_native(std::hint::black_box(ITERATIONS));
+    let native_duration = start.elapsed();
+    let (match_duration, match_result) = timed(|| run_match(&code));
+    let (threaded_duration, threaded_result) = timed(|| run_threaded(&threaded));
+
+    // Headline: the fastest dispatch strategy this language offers
+    let total_duration = match_duration.min(threaded_duration);
+    let ns_per_dispatch = |d: Duration| d.as_nanos() as f64 / dispatches as f64;
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("{} instructions dispatched per run, ns/dispatch:", dispatches);
+    eprintln!("  match loop        {:.3} ({:.3}s)", ns_per_dispatch(match_duration), match_duration.as_secs_f64());
+    eprintln!("  fn-pointer table  {:.3} ({:.3}s)", ns_per_dispatch(threaded_duration), threaded_duration.as_secs_f64());
+    eprintln!("  native reference  {:.3}s", native_duration.as_secs_f64());
+    eprintln!("Checksum: {} (all variants match: {})", match_result,
+              match_result == native_result && threaded_result == native_result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_both(code: &[Instr]) -> i64 {
+        let result = run_match(code);
+        assert_eq!(run_threaded(&thread_code(code)), result);
+        result
+    }
+
+    #[test]
+    fn evaluates_straight_line_code() {
+        use Op::*;
+        // ((2 + 3) * 4 - 1) ^ 6, then (x >> 2) & 3 with x stored in a local
+        let code = [
+            instr(Push, 2), instr(Push, 3), instr(Add, 0), instr(Push, 4), instr(Mul, 0), instr(Push, 1), instr(Sub, 0),
+            instr(Push, 6), instr(Xor, 0), instr(Dup, 0), instr(Store, 3), instr(Load, 3), instr(Push, 2), instr(Shr, 0),
+            instr(Push, 3), instr(And, 0), instr(Halt, 0),
+        ];
+        assert_eq!(run_both(&code), ((19 ^ 6) >> 2) & 3);
+    }
+
+    #[test]
+    fn branches_and_loops() {
+        use Op::*;
+        // sum = 0; for i in 0..n { sum += i }; if sum < 50 { sum } else { -1 }
+        let program = |n| {
+            vec![
+                instr(Push, 0), instr(Store, 0), instr(Push, 0), instr(Store, 1),
+                instr(Load, 1), instr(Load, 0), instr(Add, 0), instr(Store, 1),
+                instr(Load, 0), instr(Push, 1), instr(Add, 0), instr(Dup, 0), instr(Store, 0),
+                instr(Push, n), instr(Lt, 0), instr(JumpIfNonZero, 4),
+                instr(Load, 1), instr(Dup, 0), instr(Push, 50), instr(Lt, 0), instr(JumpIfZero, 22),
+                instr(Halt, 0),
+                instr(Push, -1), instr(Halt, 0),
+            ]
+        };
+        assert_eq!(run_both(&program(10)), 45);
+        assert_eq!(run_both(&program(20)), -1);
+        assert_eq!(run_both(&[instr(Push, 0), instr(Jump, 3), instr(Push, 7), instr(Halt, 0)]), 0);
+    }
+
+    #[test]
+    fn benchmark_program_matches_native() {
+        for iterations in [1, 2, 1_000, 12_345] {
+            let mut code = build_program();
+            for i in code.iter_mut().filter(|i| i.op == Op::Push && i.arg == ITERATIONS) {
+                i.arg = iterations;
+            }
+            assert_eq!(run_both(&code), run_native(iterations).0, "{} iterations", iterations);
+        }
+    }
+}
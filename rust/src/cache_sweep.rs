@@ -0,0 +1,149 @@
+use std::fmt::Write as _;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const MAX_WORKING_SET: usize = 256 << 20; // 4 KiB .. 256 MiB in powers of two
+const MIN_WORKING_SET: usize = 4 << 10;
+const STRIDES: [usize; 8] = [8, 16, 32, 64, 128, 256, 1024, 4096]; // bytes
+const ACCESSES_PER_POINT: usize = 8 << 20;
+const ELEMENT: usize = std::mem::size_of::<u64>();
+
+// One read-modify-write pass over the first `len` elements, touching every `step`th one
+fn sweep(buf: &mut [u64], len: usize, step: usize) {
+    for x in buf[..len].iter_mut().step_by(step) {
+        *x = x.wrapping_add(1);
+    }
+}
+
+struct Point {
+    working_set: usize,
+    stride: usize,
+    ns_per_access: f64,
+}
+
+fn measure(buf: &mut [u64], working_set: usize, stride: usize) -> (Duration, Point) {
+    let len = working_set / ELEMENT;
+    let step = stride / ELEMENT;
+    let per_pass = len.div_ceil(step);
+    let passes = bench_cache::repetitions((ACCESSES_PER_POINT / per_pass).max(1));
+
+    // One untimed pass pulls the working set into whatever level it fits
+    sweep(buf, len, step);
+    let elapsed = if bench_cache::cold() {
+        // One pass at a time, each starting with the working set flushed out to DRAM
+        (0..passes)
+            .map(|_| {
+                bench_cache::flush(&buf[..len]);
+                let start = Instant::now();
+                sweep(black_box(&mut *buf), len, step);
+                start.elapsed()
+            })
+            .sum()
+    } else {
+        let start = Instant::now();
+        for _ in 0..passes {
+            // black_box stops the compiler from fusing passes (e.g. one +passes when a pass is a single access)
+            sweep(black_box(&mut *buf), len, step);
+        }
+        start.elapsed()
+    };
+    let ns_per_access = elapsed.as_secs_f64() * 1e9 / (passes * per_pass) as f64;
+    (elapsed, Point { working_set, stride, ns_per_access })
+}
+
+fn size_label(bytes: usize) -> String {
+    if bytes >= 1 << 20 {
+        format!("{} MiB", bytes >> 20)
+    } else {
+        format!("{} KiB", bytes >> 10)
+    }
+}
+
+fn parse_csv_path() -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter().position(|a| a == "--csv").map(|i| args.get(i + 1).cloned().expect("--csv expects a file path"))
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let placement = bench_numa::place(&args);
+    bench_pages::enable(&args);
+    bench_cache::enable(&args);
+    let csv_path = parse_csv_path();
+    let mut buf = bench_pages::filled(0u64, MAX_WORKING_SET / ELEMENT);
+
+    let mut total = Duration::ZERO;
+    let mut points = Vec::new();
+    let mut working_set = MIN_WORKING_SET;
+    while working_set <= MAX_WORKING_SET {
+        for &stride in &STRIDES {
+            let (elapsed, point) = measure(&mut buf, working_set, stride);
+            total += elapsed;
+            points.push(point);
+        }
+        working_set *= 2;
+    }
+
+    let mut header = format!("{:>11}", "ns/access");
+    for stride in STRIDES {
+        write!(header, " {:>7}", format!("{}B", stride)).unwrap();
+    }
+    eprintln!("Strided read-modify-write, rows = working set, columns = stride");
+    eprintln!("{}", header);
+    for row in points.chunks(STRIDES.len()) {
+        let mut line = format!("{:>11}", size_label(row[0].working_set));
+        for p in row {
+            write!(line, " {:>7.3}", p.ns_per_access).unwrap();
+        }
+        eprintln!("{}", line);
+    }
+
+    if let Some(path) = csv_path {
+        let mut csv = String::from("working_set_bytes,stride_bytes,ns_per_access\n");
+        for p in &points {
+            writeln!(csv, "{},{},{:.4}", p.working_set, p.stride, p.ns_per_access).unwrap();
+        }
+        std::fs::write(&path, csv).expect("Failed to write CSV");
+        eprintln!("Wrote {} points to {}", points.len(), path);
+    }
+
+    // Every access adds one, so the sum is the exact access count
+    let checksum = buf.iter().fold(0u64, |acc, &x| acc.wrapping_add(x));
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("{}", bench_numa::report(&placement));
+    eprintln!("{}", bench_pages::report());
+    eprintln!("{}", bench_cache::report());
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweep_touches_every_stride() {
+        let mut buf = vec![0u64; 100];
+        sweep(&mut buf, 90, 8);
+        assert_eq!(buf.iter().sum::<u64>(), 12); // 0, 8, ..., 88
+        assert!(buf.iter().enumerate().all(|(i, &x)| x == (i < 90 && i % 8 == 0) as u64));
+    }
+
+    // The checksum main prints relies on every access adding exactly one
+    #[test]
+    fn measure_counts_every_access() {
+        let mut buf = vec![0u64; (64 << 10) / ELEMENT];
+        let (_, point) = measure(&mut buf, 64 << 10, 64);
+        let per_pass = (64 << 10) / 64;
+        let passes = ACCESSES_PER_POINT / per_pass;
+        assert_eq!(buf.iter().sum::<u64>(), ((passes + 1) * per_pass) as u64);
+        assert!(point.ns_per_access > 0.0);
+        assert_eq!((point.working_set, point.stride), (64 << 10, 64));
+    }
+
+    #[test]
+    fn labels_sizes() {
+        assert_eq!(size_label(4 << 10), "4 KiB");
+        assert_eq!(size_label(512 << 10), "512 KiB");
+        assert_eq!(size_label(256 << 20), "256 MiB");
+    }
+}
@@ -0,0 +1,202 @@
+use rayon::prelude::*;
+use std::time::Instant;
+
+const CHANNELS: usize = 64; // Input and output channels
+const HEIGHT: usize = 224;
+const WIDTH: usize = 224;
+const KERNEL: usize = 3;
+const BATCH: usize = 8;
+
+const PADDED_HEIGHT: usize = HEIGHT + KERNEL - 1;
+const PADDED_WIDTH: usize = WIDTH + KERNEL - 1;
+const PLANE: usize = HEIGHT * WIDTH;
+const PADDED_PLANE: usize = PADDED_HEIGHT * PADDED_WIDTH;
+const PATCH: usize = CHANNELS * KERNEL * KERNEL; // Rows of the im2col matrix
+
+fn next_random(seed: &mut u64) -> f32 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*seed >> 40) as f32 / 16_777_216.0 - 0.5
+}
+
+// Inputs are stored pre-padded (zero border) in CHW layout
+fn generate_images() -> Vec<Vec<f32>> {
+    let mut seed = 7u64;
+    (0..BATCH)
+        .map(|_| {
+            let mut image = vec![0.0f32; CHANNELS * PADDED_PLANE];
+            for c in 0..CHANNELS {
+                for y in 0..HEIGHT {
+                    for x in 0..WIDTH {
+                        image[c * PADDED_PLANE + (y + 1) * PADDED_WIDTH + (x + 1)] = next_random(&mut seed);
+                    }
+                }
+            }
+            image
+        })
+        .collect()
+}
+
+// Weights in [out_channel][in_channel][ky][kx] order
+fn generate_weights() -> Vec<f32> {
+    let mut seed = 42u64;
+    (0..CHANNELS * PATCH).map(|_| next_random(&mut seed) * 0.1).collect()
+}
+
+fn conv_direct(image: &[f32], weights: &[f32], output: &mut [f32]) {
+    output.par_chunks_mut(PLANE).enumerate().for_each(|(oc, out)| {
+        out.fill(0.0);
+        for ic in 0..CHANNELS {
+            let plane = &image[ic * PADDED_PLANE..(ic + 1) * PADDED_PLANE];
+            for ky in 0..KERNEL {
+                for kx in 0..KERNEL {
+                    let w = weights[oc * PATCH + (ic * KERNEL + ky) * KERNEL + kx];
+                    for y in 0..HEIGHT {
+                        let src = &plane[(y + ky) * PADDED_WIDTH + kx..][..WIDTH];
+                        let dst = &mut out[y * WIDTH..(y + 1) * WIDTH];
+                        for (d, &s) in dst.iter_mut().zip(src) {
+                            *d += w * s;
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn im2col(image: &[f32], columns: &mut [f32]) {
+    columns.par_chunks_mut(PLANE).enumerate().for_each(|(row, col)| {
+        let ic = row / (KERNEL * KERNEL);
+        let ky = (row / KERNEL) % KERNEL;
+        let kx = row % KERNEL;
+        let plane = &image[ic * PADDED_PLANE..(ic + 1) * PADDED_PLANE];
+        for y in 0..HEIGHT {
+            let src = &plane[(y + ky) * PADDED_WIDTH + kx..][..WIDTH];
+            col[y * WIDTH..(y + 1) * WIDTH].copy_from_slice(src);
+        }
+    });
+}
+
+// (CHANNELS x PATCH) * (PATCH x PLANE), i-k-j order
+fn gemm(weights: &[f32], columns: &[f32], output: &mut [f32]) {
+    output.par_chunks_mut(PLANE).enumerate().for_each(|(oc, out)| {
+        out.fill(0.0);
+        for k in 0..PATCH {
+            let w = weights[oc * PATCH + k];
+            let col = &columns[k * PLANE..(k + 1) * PLANE];
+            for (d, &s) in out.iter_mut().zip(col) {
+                *d += w * s;
+            }
+        }
+    });
+}
+
+fn conv_im2col(image: &[f32], weights: &[f32], columns: &mut [f32], output: &mut [f32]) {
+    im2col(image, columns);
+    gemm(weights, columns, output);
+}
+
+fn checksum(output: &[f32]) -> f64 {
+    output.iter().map(|&v| v as f64).sum()
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let images = generate_images();
+    let weights = generate_weights();
+    let mut output = vec![0.0f32; CHANNELS * PLANE];
+    let mut columns = vec![0.0f32; PATCH * PLANE];
+
+    let gflops = (2 * BATCH * CHANNELS * PATCH * PLANE) as f64 / 1e9;
+
+    // Warm-up
+    conv_direct(&images[0], &weights, &mut output);
+    conv_im2col(&images[0], &weights, &mut columns, &mut output);
+
+    // Benchmark direct convolution
+    let start = Instant::now();
+    measure.start();
+    let mut direct_checksum = 0.0;
+    for image in &images {
+        conv_direct(image, &weights, &mut output);
+        direct_checksum += checksum(&output);
+    }
+    measure.stop();
+    let direct_duration = start.elapsed();
+
+    // Benchmark im2col + GEMM
+    let start = Instant::now();
+    measure.start();
+    let mut im2col_checksum = 0.0;
+    for image in &images {
+        conv_im2col(image, &weights, &mut columns, &mut output);
+        im2col_checksum += checksum(&output);
+    }
+    measure.stop();
+    let im2col_duration = start.elapsed();
+
+    measure.report();
+    eprintln!("Direct: {:.6}s ({:.2} GFLOP/s), im2col+GEMM: {:.6}s ({:.2} GFLOP/s)",
+              direct_duration.as_secs_f64(), gflops / direct_duration.as_secs_f64(),
+              im2col_duration.as_secs_f64(), gflops / im2col_duration.as_secs_f64());
+    eprintln!("Checksum: {:.6} (im2col: {:.6})", direct_checksum, im2col_checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One output pixel straight from the definition, in f64
+    fn reference_pixel(image: &[f32], weights: &[f32], oc: usize, y: usize, x: usize) -> f64 {
+        let mut sum = 0.0;
+        for ic in 0..CHANNELS {
+            for ky in 0..KERNEL {
+                for kx in 0..KERNEL {
+                    let w = weights[oc * PATCH + (ic * KERNEL + ky) * KERNEL + kx] as f64;
+                    sum += w * image[ic * PADDED_PLANE + (y + ky) * PADDED_WIDTH + x + kx] as f64;
+                }
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn direct_and_im2col_match_the_definition() {
+        let image = generate_images().swap_remove(0);
+        let weights = generate_weights();
+        let mut direct = vec![0.0f32; CHANNELS * PLANE];
+        let mut im2col = vec![0.0f32; CHANNELS * PLANE];
+        let mut columns = vec![0.0f32; PATCH * PLANE];
+        conv_direct(&image, &weights, &mut direct);
+        conv_im2col(&image, &weights, &mut columns, &mut im2col);
+
+        // Corners and edges read the zero border
+        for (oc, y, x) in [(0, 0, 0), (5, 0, WIDTH - 1), (17, HEIGHT - 1, 0), (63, HEIGHT - 1, WIDTH - 1), (31, 100, 57)] {
+            let expected = reference_pixel(&image, &weights, oc, y, x);
+            for output in [&direct, &im2col] {
+                assert!((output[oc * PLANE + y * WIDTH + x] as f64 - expected).abs() < 1e-4);
+            }
+        }
+        let max_difference = direct.iter().zip(&im2col).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max);
+        assert!(max_difference < 1e-4);
+    }
+
+    // A centered delta from each channel to itself copies the unpadded image through
+    #[test]
+    fn identity_kernel_copies_the_input() {
+        let image = generate_images().swap_remove(0);
+        let mut weights = vec![0.0f32; CHANNELS * PATCH];
+        for c in 0..CHANNELS {
+            weights[c * PATCH + (c * KERNEL + 1) * KERNEL + 1] = 1.0;
+        }
+        let mut output = vec![0.0f32; CHANNELS * PLANE];
+        conv_direct(&image, &weights, &mut output);
+        for c in [0, 40, 63] {
+            for y in [0, 1, HEIGHT - 1] {
+                let row = &image[c * PADDED_PLANE + (y + 1) * PADDED_WIDTH + 1..][..WIDTH];
+                assert_eq!(&output[c * PLANE + y * WIDTH..][..WIDTH], row);
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::time::Instant;
+
+const NUM_POINTS: usize = 50_000_000;
+const RADIUS: i64 = 1 << 29; // Keeps every cross product exact in i64
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Uniform in a disk (rejection sampled), so the hull has many more vertices than a square's would
+fn generate_points() -> Vec<Point> {
+    let mut seed = 31337u64;
+    let mut points = Vec::with_capacity(NUM_POINTS);
+    while points.len() < NUM_POINTS {
+        let x = (next_random(&mut seed) % (2 * RADIUS as u64 + 1)) as i64 - RADIUS;
+        let y = (next_random(&mut seed) % (2 * RADIUS as u64 + 1)) as i64 - RADIUS;
+        if x * x + y * y <= RADIUS * RADIUS {
+            points.push(Point { x: x as i32, y: y as i32 });
+        }
+    }
+    points
+}
+
+// Positive when o -> a -> b turns counter-clockwise
+#[inline]
+fn cross(o: Point, a: Point, b: Point) -> i64 {
+    (a.x as i64 - o.x as i64) * (b.y as i64 - o.y as i64) - (a.y as i64 - o.y as i64) * (b.x as i64 - o.x as i64)
+}
+
+// Andrew's monotone chain over points already sorted by (x, y); returns the hull counter-clockwise
+fn monotone_chain(points: &[Point]) -> Vec<Point> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut hull: Vec<Point> = Vec::new();
+
+    // Lower hull
+    for &p in points {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+
+    // Upper hull
+    let lower_len = hull.len() + 1;
+    for &p in points.iter().rev().skip(1) {
+        while hull.len() >= lower_len && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+
+    hull.pop(); // The last point repeats the first
+    hull
+}
+
+// Shoelace formula; exact because every vertex is an integer point
+fn twice_area(hull: &[Point]) -> i64 {
+    (0..hull.len())
+        .map(|i| {
+            let a = hull[i];
+            let b = hull[(i + 1) % hull.len()];
+            a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64
+        })
+        .sum()
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let points = generate_points();
+
+    // Warm-up
+    let mut warmup = points[..NUM_POINTS / 100].to_vec();
+    warmup.sort_unstable();
+    let _ = monotone_chain(&warmup);
+
+    // Benchmark: sort, then build the hull
+    let mut sorted = points.clone();
+    let start = Instant::now();
+    measure.start();
+    sorted.sort_unstable();
+    measure.stop();
+    let sort_duration = start.elapsed();
+
+    let hull_start = Instant::now();
+    measure.start();
+    let hull = monotone_chain(&sorted);
+    measure.stop();
+    let hull_duration = hull_start.elapsed();
+
+    // Checksum
+    let area2 = twice_area(&hull);
+    let disk_area = std::f64::consts::PI * (RADIUS * RADIUS) as f64;
+
+    measure.report();
+    eprintln!("Sort: {:.3}s, hull: {:.3}s", sort_duration.as_secs_f64(), hull_duration.as_secs_f64());
+    eprintln!("Hull vertices: {}, area / disk area: {:.9}", hull.len(), area2 as f64 / 2.0 / disk_area);
+    eprintln!("Checksum: {}", area2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    fn hull_of(mut points: Vec<Point>) -> Vec<Point> {
+        points.sort_unstable();
+        monotone_chain(&points)
+    }
+
+    #[test]
+    fn square_hull_drops_interior_and_collinear_points() {
+        let hull = hull_of(vec![p(0, 0), p(2, 2), p(4, 0), p(4, 4), p(1, 3), p(0, 4), p(2, 0), p(4, 2), p(3, 1)]);
+        assert_eq!(hull, [p(0, 0), p(4, 0), p(4, 4), p(0, 4)]);
+        assert_eq!(twice_area(&hull), 32);
+    }
+
+    #[test]
+    fn degenerate_inputs() {
+        assert_eq!(hull_of(vec![p(1, 1)]), [p(1, 1)]);
+        assert_eq!(hull_of(vec![p(0, 0), p(1, 1), p(2, 2), p(3, 3)]), [p(0, 0), p(3, 3)]);
+        assert_eq!(cross(p(0, 0), p(1, 0), p(0, 1)), 1);
+        assert_eq!(cross(p(0, 0), p(0, 1), p(1, 0)), -1);
+        // Extreme coordinates stay exact in i64
+        let r = RADIUS as i32;
+        assert_eq!(twice_area(&hull_of(vec![p(-r, -r), p(r, -r), p(r, r), p(-r, r)])), 8 * RADIUS * RADIUS);
+    }
+
+    #[test]
+    fn every_point_lies_inside_the_hull() {
+        let mut seed = 5u64;
+        let points: Vec<Point> = (0..20_000)
+            .map(|_| p((next_random(&mut seed) % 2001) as i32 - 1000, (next_random(&mut seed) % 2001) as i32 - 1000))
+            .collect();
+        let hull = hull_of(points.clone());
+        assert!(twice_area(&hull) > 0); // counter-clockwise
+        for i in 0..hull.len() {
+            let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+            assert!(points.iter().all(|&q| cross(a, b, q) >= 0));
+        }
+    }
+}
@@ -0,0 +1,674 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+// CoreMark's performance run: a 2000-byte working set split three ways, seeds 0, 0 and 0x66
+const TOTAL_DATA_SIZE: usize = 2000;
+const BLOCK_SIZE: usize = TOTAL_DATA_SIZE / 3;
+const SEEDS: (i16, i16, i16) = (0, 0, 0x66);
+const ITERATIONS: usize = 40_000;
+const WARMUP_ITERATIONS: usize = 2_000;
+
+// The CRCs CoreMark's core_main.c checks the performance run against
+const KNOWN_LIST_CRC: u16 = 0xe714;
+const KNOWN_MATRIX_CRC: u16 = 0x1fd7;
+const KNOWN_STATE_CRC: u16 = 0x8e3a;
+
+// ---- CRC-16 (reflected 0x8005), one bit at a time as in core_util.c ----
+
+fn crcu8(mut data: u8, mut crc: u16) -> u16 {
+    for _ in 0..8 {
+        let x16 = (data & 1) ^ (crc as u8 & 1);
+        data >>= 1;
+        if x16 == 1 {
+            crc ^= 0x4002;
+            crc = (crc >> 1) | 0x8000;
+        } else {
+            crc >>= 1;
+        }
+    }
+    crc
+}
+
+fn crcu16(value: u16, crc: u16) -> u16 {
+    crcu8((value >> 8) as u8, crcu8(value as u8, crc))
+}
+
+fn crc16(value: i16, crc: u16) -> u16 {
+    crcu16(value as u16, crc)
+}
+
+fn crcu32(value: u32, crc: u16) -> u16 {
+    crc16((value >> 16) as i16, crc16(value as i16, crc))
+}
+
+// ---- Matrix: N×N small ints, N chosen so A, B and C fit the block ----
+
+struct Matrix {
+    n: usize,
+    a: Vec<i16>,
+    b: Vec<i16>,
+    c: Vec<i32>,
+}
+
+impl Matrix {
+    fn new(block_size: usize, seed: i32) -> Matrix {
+        let mut i = 0;
+        while i * i * 2 * 4 < block_size {
+            i += 1;
+        }
+        let n = i - 1;
+        let mut seed = if seed == 0 { 1 } else { seed };
+        let (mut a, mut b) = (Vec::with_capacity(n * n), Vec::with_capacity(n * n));
+        for order in 1..=(n * n) as i32 {
+            seed = (order * seed) % 65536;
+            let value = (seed + order) & 0xffff;
+            b.push(value as i16);
+            a.push(((value + order) & 0xff) as i16);
+        }
+        Matrix { n, a, b, c: vec![0; n * n] }
+    }
+
+    fn add_const(&mut self, value: i16) {
+        for a in &mut self.a {
+            *a = a.wrapping_add(value);
+        }
+    }
+
+    fn mul_const(&mut self, value: i16) {
+        for (c, &a) in self.c.iter_mut().zip(&self.a) {
+            *c = a as i32 * value as i32;
+        }
+    }
+
+    fn mul_vect(&mut self) {
+        let n = self.n;
+        for i in 0..n {
+            self.c[i] = (0..n).fold(0i32, |sum, j| sum.wrapping_add(self.a[i * n + j] as i32 * self.b[j] as i32));
+        }
+    }
+
+    fn mul_matrix(&mut self) {
+        let n = self.n;
+        for i in 0..n {
+            for j in 0..n {
+                self.c[i * n + j] = (0..n).fold(0i32, |sum, k| {
+                    sum.wrapping_add(self.a[i * n + k] as i32 * self.b[k * n + j] as i32)
+                });
+            }
+        }
+    }
+
+    // Multiplies, then sums the product of two bit fields of each term instead of the term
+    fn mul_matrix_bitextract(&mut self) {
+        let n = self.n;
+        let extract = |x: i32, from: u32, bits: u32| (x >> from) & !(-1i32 << bits);
+        for i in 0..n {
+            for j in 0..n {
+                self.c[i * n + j] = (0..n).fold(0i32, |sum, k| {
+                    let product = self.a[i * n + k] as i32 * self.b[k * n + j] as i32;
+                    sum.wrapping_add(extract(product, 2, 4) * extract(product, 5, 7))
+                });
+            }
+        }
+    }
+
+    // Scores C by how often a running sum overshoots `clip` and how often values rise
+    fn sum(&self, clip: i16) -> i16 {
+        let (mut total, mut previous, mut score) = (0i32, 0i32, 0i16);
+        for &current in &self.c {
+            total = total.wrapping_add(current);
+            if total > clip as i32 {
+                score = score.wrapping_add(10);
+                total = 0;
+            } else if current > previous {
+                score = score.wrapping_add(1);
+            }
+            previous = current;
+        }
+        score
+    }
+
+    fn test(&mut self, value: i16) -> u16 {
+        let clip = (0xf000u16 as i16) | value;
+        let mut crc = 0;
+        self.add_const(value);
+        self.mul_const(value);
+        crc = crc16(self.sum(clip), crc);
+        self.mul_vect();
+        crc = crc16(self.sum(clip), crc);
+        self.mul_matrix();
+        crc = crc16(self.sum(clip), crc);
+        self.mul_matrix_bitextract();
+        crc = crc16(self.sum(clip), crc);
+        // Puts A back the way it was
+        self.add_const(value.wrapping_neg());
+        crc
+    }
+
+    fn bench(&mut self, seed: i16, crc: u16) -> u16 {
+        crc16(self.test(seed) as i16, crc)
+    }
+}
+
+// ---- State machine: a scanner for comma-separated numbers ----
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum State {
+    Start,
+    Invalid,
+    S1, // after a leading sign
+    S2, // after the e of an exponent
+    Int,
+    Float,
+    Exponent,
+    Scientific,
+}
+
+const NUM_STATES: usize = 8;
+
+const INT_PATTERNS: [&[u8; 4]; 4] = [b"5012", b"1234", b"-874", b"+122"];
+const FLOAT_PATTERNS: [&[u8; 8]; 4] = [b"35.54400", b".1234500", b"-110.700", b"+0.64400"];
+const SCIENTIFIC_PATTERNS: [&[u8; 8]; 4] = [b"5.500e+3", b"-.123e-2", b"-87e+832", b"+0.6e-12"];
+const ERROR_PATTERNS: [&[u8; 8]; 4] = [b"T0.3e-1F", b"-T.T++Tq", b"1T3.4e4z", b"34.0e-T^"];
+
+// Fills the block with comma-separated numbers, valid and not, chosen by the seed, then zeros
+fn init_state(size: usize, mut seed: i16) -> Vec<u8> {
+    let mut block = vec![0u8; size];
+    let mut total = 0;
+    let mut next: &[u8] = &[];
+    while total + next.len() + 1 < size - 1 {
+        if !next.is_empty() {
+            block[total..total + next.len()].copy_from_slice(next);
+            block[total + next.len()] = b',';
+            total += next.len() + 1;
+        }
+        seed = seed.wrapping_add(1);
+        let pattern = ((seed >> 3) & 3) as usize;
+        next = match seed & 7 {
+            0..=2 => INT_PATTERNS[pattern],
+            3 | 4 => FLOAT_PATTERNS[pattern],
+            5 | 6 => SCIENTIFIC_PATTERNS[pattern],
+            _ => ERROR_PATTERNS[pattern],
+        };
+    }
+    block
+}
+
+// Scans one number from `*pos`, leaving `*pos` past its comma (or the character that made it
+// invalid), and counts the transitions out of each state
+fn transition(input: &[u8], pos: &mut usize, counts: &mut [u32; NUM_STATES]) -> State {
+    let mut state = State::Start;
+    let mut i = *pos;
+    while i < input.len() && input[i] != 0 && state != State::Invalid {
+        let symbol = input[i];
+        i += 1;
+        if symbol == b',' {
+            break;
+        }
+        let digit = symbol.is_ascii_digit();
+        match state {
+            State::Start => {
+                if digit {
+                    state = State::Int;
+                } else if symbol == b'+' || symbol == b'-' {
+                    state = State::S1;
+                } else if symbol == b'.' {
+                    state = State::Float;
+                } else {
+                    state = State::Invalid;
+                    counts[State::Invalid as usize] += 1;
+                }
+                counts[State::Start as usize] += 1;
+            }
+            State::S1 => {
+                state = if digit {
+                    State::Int
+                } else if symbol == b'.' {
+                    State::Float
+                } else {
+                    State::Invalid
+                };
+                counts[State::S1 as usize] += 1;
+            }
+            State::Int => {
+                if symbol == b'.' {
+                    state = State::Float;
+                    counts[State::Int as usize] += 1;
+                } else if !digit {
+                    state = State::Invalid;
+                    counts[State::Int as usize] += 1;
+                }
+            }
+            State::Float => {
+                if symbol == b'E' || symbol == b'e' {
+                    state = State::S2;
+                    counts[State::Float as usize] += 1;
+                } else if !digit {
+                    state = State::Invalid;
+                    counts[State::Float as usize] += 1;
+                }
+            }
+            State::S2 => {
+                state = if symbol == b'+' || symbol == b'-' { State::Exponent } else { State::Invalid };
+                counts[State::S2 as usize] += 1;
+            }
+            State::Exponent => {
+                state = if digit { State::Scientific } else { State::Invalid };
+                counts[State::Exponent as usize] += 1;
+            }
+            State::Scientific => {
+                if !digit {
+                    state = State::Invalid;
+                    counts[State::Invalid as usize] += 1;
+                }
+            }
+            State::Invalid => {}
+        }
+    }
+    *pos = i;
+    state
+}
+
+fn scan(block: &[u8], finals: &mut [u32; NUM_STATES], transitions: &mut [u32; NUM_STATES]) {
+    let mut pos = 0;
+    while pos < block.len() && block[pos] != 0 {
+        finals[transition(block, &mut pos, transitions) as usize] += 1;
+    }
+}
+
+// XORs every `step`th byte but the commas with `mask`, as CoreMark corrupts and restores its input
+fn corrupt(block: &mut [u8], step: usize, mask: u8) {
+    for byte in block.iter_mut().step_by(step) {
+        if *byte != b',' {
+            *byte ^= mask;
+        }
+    }
+}
+
+fn bench_state(block: &mut [u8], seed1: i16, seed2: i16, step: i16, mut crc: u16) -> u16 {
+    let (mut finals, mut transitions) = ([0u32; NUM_STATES], [0u32; NUM_STATES]);
+    scan(block, &mut finals, &mut transitions);
+    corrupt(block, step as usize, seed1 as u8);
+    scan(block, &mut finals, &mut transitions);
+    corrupt(block, step as usize, seed2 as u8);
+    for (&f, &t) in finals.iter().zip(&transitions) {
+        crc = crcu32(f, crc);
+        crc = crcu32(t, crc);
+    }
+    crc
+}
+
+// ---- Linked list: nodes in an arena, linked by index ----
+
+const NIL: u32 = u32::MAX;
+
+#[derive(Clone, Copy)]
+struct Node {
+    next: u32,
+    data16: i16,
+    idx: i16,
+}
+
+struct Results {
+    seeds: (i16, i16, i16),
+    nodes: Vec<Node>,
+    list: u32,
+    matrix: Matrix,
+    state: Vec<u8>,
+    crc: u16,
+    crc_list: u16,
+    crc_matrix: u16,
+    crc_state: u16,
+}
+
+// The low 3 bits pick a kernel, bits 3-6 are its input, and bit 7 marks the low 7 bits as a
+// cached result. Running a kernel chains its CRC into the running one
+fn calc_func(data: &mut i16, res: &mut Results) -> i16 {
+    if (*data >> 7) & 1 == 1 {
+        return *data & 0x7f;
+    }
+    let mut dtype = (*data >> 3) & 0xf;
+    dtype |= dtype << 4;
+    let result = match *data & 7 {
+        0 => {
+            let step = dtype.max(0x22);
+            let crc = bench_state(&mut res.state, res.seeds.0, res.seeds.1, step, res.crc) as i16;
+            if res.crc_state == 0 {
+                res.crc_state = crc as u16;
+            }
+            crc
+        }
+        1 => {
+            let crc = res.matrix.bench(dtype, res.crc) as i16;
+            if res.crc_matrix == 0 {
+                res.crc_matrix = crc as u16;
+            }
+            crc
+        }
+        _ => *data,
+    };
+    res.crc = crcu16(result as u16, res.crc);
+    let result = result & 0x7f;
+    *data = (*data & 0xff00u16 as i16) | 0x80 | result;
+    result
+}
+
+#[derive(Clone, Copy)]
+enum Order {
+    Index,   // by idx, first restoring each data16's low byte from its high byte
+    Complex, // by calc_func of data16, which runs the matrix and state kernels
+}
+
+fn compare(nodes: &mut [Node], a: u32, b: u32, order: Order, res: &mut Results) -> i32 {
+    match order {
+        Order::Index => {
+            for n in [a, b] {
+                let d = &mut nodes[n as usize].data16;
+                *d = (*d & 0xff00u16 as i16) | ((*d >> 8) & 0xff);
+            }
+            nodes[a as usize].idx as i32 - nodes[b as usize].idx as i32
+        }
+        Order::Complex => {
+            let x = calc_func(&mut nodes[a as usize].data16, res) as i32;
+            let y = calc_func(&mut nodes[b as usize].data16, res) as i32;
+            x - y
+        }
+    }
+}
+
+// Bottom-up merge sort of a singly linked list, as in core_list_mergesort
+fn mergesort(nodes: &mut [Node], mut list: u32, order: Order, res: &mut Results) -> u32 {
+    let mut insize = 1;
+    loop {
+        let mut p = list;
+        list = NIL;
+        let mut tail = NIL;
+        let mut merges = 0;
+        while p != NIL {
+            merges += 1;
+            let mut q = p;
+            let mut psize = 0;
+            for _ in 0..insize {
+                psize += 1;
+                q = nodes[q as usize].next;
+                if q == NIL {
+                    break;
+                }
+            }
+            let mut qsize = insize;
+            while psize > 0 || (qsize > 0 && q != NIL) {
+                let e;
+                if psize == 0 {
+                    e = q;
+                    q = nodes[q as usize].next;
+                    qsize -= 1;
+                } else if qsize == 0 || q == NIL || compare(nodes, p, q, order, res) <= 0 {
+                    e = p;
+                    p = nodes[p as usize].next;
+                    psize -= 1;
+                } else {
+                    e = q;
+                    q = nodes[q as usize].next;
+                    qsize -= 1;
+                }
+                if tail != NIL {
+                    nodes[tail as usize].next = e;
+                } else {
+                    list = e;
+                }
+                tail = e;
+            }
+            p = q;
+        }
+        nodes[tail as usize].next = NIL;
+        if merges <= 1 {
+            return list;
+        }
+        insize *= 2;
+    }
+}
+
+// By idx when it is non-negative, otherwise by the low byte of data16
+fn find(nodes: &[Node], mut list: u32, idx: i16, data: i16) -> u32 {
+    while list != NIL {
+        let node = &nodes[list as usize];
+        if (idx >= 0 && node.idx == idx) || (idx < 0 && (node.data16 & 0xff) == data) {
+            break;
+        }
+        list = node.next;
+    }
+    list
+}
+
+fn reverse(nodes: &mut [Node], mut list: u32) -> u32 {
+    let mut next = NIL;
+    while list != NIL {
+        let rest = nodes[list as usize].next;
+        nodes[list as usize].next = next;
+        next = list;
+        list = rest;
+    }
+    next
+}
+
+// Unlinks the node after `item`, swapping their contents so `item` keeps its place in the list
+fn remove(nodes: &mut [Node], item: u32) -> u32 {
+    let removed = nodes[item as usize].next;
+    swap_data(nodes, item, removed);
+    nodes[item as usize].next = nodes[removed as usize].next;
+    nodes[removed as usize].next = NIL;
+    removed
+}
+
+fn undo_remove(nodes: &mut [Node], removed: u32, modified: u32) {
+    swap_data(nodes, removed, modified);
+    nodes[removed as usize].next = nodes[modified as usize].next;
+    nodes[modified as usize].next = removed;
+}
+
+fn swap_data(nodes: &mut [Node], a: u32, b: u32) {
+    let (x, y) = (nodes[a as usize], nodes[b as usize]);
+    (nodes[a as usize].data16, nodes[a as usize].idx) = (y.data16, y.idx);
+    (nodes[b as usize].data16, nodes[b as usize].idx) = (x.data16, x.idx);
+}
+
+// A head node, a sentinel with the largest idx, and as many items as fit the block, the first
+// fifth indexed in order and the rest pseudo-randomly; init() sorts them by idx
+fn init_list(block_size: usize, seed: i16) -> Vec<Node> {
+    // A 64-bit CoreMark's node: two pointers plus the 4-byte data it points to
+    let per_item = 16 + 4;
+    let size = block_size / per_item - 2;
+    let mut nodes = vec![Node { next: NIL, data16: 0x8080u16 as i16, idx: 0 }];
+    let insert = |nodes: &mut Vec<Node>, data16: i16, idx: i16| {
+        // core_list_insert_new stops one short of the block's capacity
+        if nodes.len() + 1 < size {
+            nodes.push(Node { next: nodes[0].next, data16, idx });
+            nodes[0].next = (nodes.len() - 1) as u32;
+        }
+    };
+    insert(&mut nodes, 0xffffu16 as i16, 0x7fff);
+    for i in 0..size as u16 {
+        let pattern = (seed as u16 ^ i) & 0xf;
+        let dat = (pattern << 3) | (i & 7);
+        insert(&mut nodes, ((dat << 8) | dat) as i16, 0);
+    }
+    let mut finder = nodes[0].next;
+    let mut i = 1u16;
+    while nodes[finder as usize].next != NIL {
+        nodes[finder as usize].idx = if (i as usize) < size / 5 {
+            i += 1;
+            (i - 1) as i16
+        } else {
+            let pattern = i ^ seed as u16;
+            i += 1;
+            (0x3fff & (((i & 7) << 8) | pattern)) as i16
+        };
+        finder = nodes[finder as usize].next;
+    }
+    nodes
+}
+
+fn bench_list(res: &mut Results, finder_idx: i16) -> u16 {
+    let mut nodes = std::mem::take(&mut res.nodes);
+    let mut list = res.list;
+    let (mut retval, mut found, mut missed) = (0u16, 0u16, 0u16);
+    let (mut idx, mut data) = (finder_idx, 0);
+    // Finds seed3 values, reversing the list after each and moving the node after a hit to the front
+    for i in 0..res.seeds.2 {
+        data = i & 0xff;
+        let this = find(&nodes, list, idx, data);
+        list = reverse(&mut nodes, list);
+        if this == NIL {
+            missed = missed.wrapping_add(1);
+            let second = nodes[list as usize].next;
+            retval = retval.wrapping_add(((nodes[second as usize].data16 >> 8) & 1) as u16);
+        } else {
+            found = found.wrapping_add(1);
+            let data = nodes[this as usize].data16;
+            if data & 1 != 0 {
+                retval = retval.wrapping_add(((data >> 9) & 1) as u16);
+            }
+            let after = nodes[this as usize].next;
+            if after != NIL {
+                nodes[this as usize].next = nodes[after as usize].next;
+                nodes[after as usize].next = nodes[list as usize].next;
+                nodes[list as usize].next = after;
+            }
+        }
+        if idx >= 0 {
+            idx += 1;
+        }
+    }
+    retval = retval.wrapping_add(found.wrapping_mul(4)).wrapping_sub(missed);
+    if finder_idx > 0 {
+        list = mergesort(&mut nodes, list, Order::Complex, res);
+    }
+    let second = nodes[list as usize].next;
+    let removed = remove(&mut nodes, second);
+    // CoreMark CRCs the head's data once per node from here on, not each node's own
+    let mut finder = find(&nodes, list, idx, data);
+    if finder == NIL {
+        finder = nodes[list as usize].next;
+    }
+    while finder != NIL {
+        retval = crc16(nodes[list as usize].data16, retval);
+        finder = nodes[finder as usize].next;
+    }
+    let second = nodes[list as usize].next;
+    undo_remove(&mut nodes, removed, second);
+    list = mergesort(&mut nodes, list, Order::Index, res);
+    let mut finder = nodes[list as usize].next;
+    while finder != NIL {
+        retval = crc16(nodes[list as usize].data16, retval);
+        finder = nodes[finder as usize].next;
+    }
+    res.nodes = nodes;
+    res.list = list;
+    retval
+}
+
+fn init(seeds: (i16, i16, i16)) -> Results {
+    let mut nodes = init_list(BLOCK_SIZE, seeds.0);
+    let matrix = Matrix::new(BLOCK_SIZE, seeds.0 as i32 | ((seeds.1 as i32) << 16));
+    let state = init_state(BLOCK_SIZE, seeds.0);
+    let mut res = Results {
+        seeds,
+        nodes: Vec::new(),
+        list: 0,
+        matrix,
+        state,
+        crc: 0,
+        crc_list: 0,
+        crc_matrix: 0,
+        crc_state: 0,
+    };
+    res.list = mergesort(&mut nodes, 0, Order::Index, &mut res);
+    res.nodes = nodes;
+    res
+}
+
+// Each iteration runs the list benchmark twice, once finding by index and once by value; its
+// sorts run the matrix and state kernels
+fn iterate(res: &mut Results, iterations: usize) -> u16 {
+    res.crc = 0;
+    res.crc_list = 0;
+    res.crc_matrix = 0;
+    res.crc_state = 0;
+    for i in 0..iterations {
+        let crc = bench_list(res, 1);
+        res.crc = crcu16(crc, res.crc);
+        let crc = bench_list(res, -1);
+        res.crc = crcu16(crc, res.crc);
+        if i == 0 {
+            res.crc_list = res.crc;
+        }
+    }
+    res.crc
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    // black_box on the seeds, as CoreMark reads them from volatiles, so nothing is precomputed
+    let seeds = black_box(SEEDS);
+
+    // Warm-up
+    let _ = iterate(&mut init(seeds), WARMUP_ITERATIONS);
+
+    // Benchmark
+    let mut res = init(seeds);
+    let start = Instant::now();
+    measure.start();
+    let crc = iterate(&mut res, ITERATIONS);
+    measure.stop();
+    let duration = start.elapsed();
+
+    let crcs = [("list", res.crc_list, KNOWN_LIST_CRC), ("matrix", res.crc_matrix, KNOWN_MATRIX_CRC),
+                ("state", res.crc_state, KNOWN_STATE_CRC)];
+    for (name, crc, known) in crcs {
+        if crc != known {
+            eprintln!("{} CRC {:#06x} does not match CoreMark's {:#06x}", name, crc, known);
+            std::process::exit(1);
+        }
+    }
+
+    measure.report();
+    eprintln!("Data: {} bytes ({} list nodes, {}x{} matrices, {}-byte state input), seeds {}, {}, {:#x}",
+              TOTAL_DATA_SIZE, res.nodes.len(), res.matrix.n, res.matrix.n, res.state.len(), seeds.0, seeds.1, seeds.2);
+    eprintln!("Iterations: {} in {:.3}s ({:.0} iterations/s)", ITERATIONS, duration.as_secs_f64(),
+              ITERATIONS as f64 / duration.as_secs_f64());
+    eprintln!("CRCs: list {:#06x}, matrix {:#06x}, state {:#06x} (CoreMark's performance-run values)",
+              res.crc_list, res.crc_matrix, res.crc_state);
+    eprintln!("Checksum: {:#06x}", crc);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_iteration_matches_coremark() {
+        let mut res = init(SEEDS);
+        iterate(&mut res, 1);
+        assert_eq!((res.crc_list, res.crc_matrix, res.crc_state), (KNOWN_LIST_CRC, KNOWN_MATRIX_CRC, KNOWN_STATE_CRC));
+    }
+
+    #[test]
+    fn scanner_classifies_numbers() {
+        let input = b"5012,-.123e-2,35.54400,1T3.4e4z,+0.6e-12";
+        let (mut finals, mut transitions) = ([0u32; NUM_STATES], [0u32; NUM_STATES]);
+        scan(input, &mut finals, &mut transitions);
+        assert_eq!(finals[State::Int as usize], 1);
+        assert_eq!(finals[State::Scientific as usize], 2);
+        assert_eq!(finals[State::Float as usize], 1);
+        // An invalid number resumes scanning right after the bad character, not at the next comma:
+        // 1T3.4e4z is read as 1T, 3.4e4 and z, then its comma alone ends a number still at Start
+        assert_eq!(finals[State::Invalid as usize], 3);
+        assert_eq!(finals[State::Start as usize], 1);
+    }
+
+    #[test]
+    fn crc_matches_crc16_arc() {
+        // CRC-16/ARC of "123456789"
+        assert_eq!(b"123456789".iter().fold(0, |crc, &b| crcu8(b, crc)), 0xbb3d);
+    }
+}
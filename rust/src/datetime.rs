@@ -0,0 +1,241 @@
+use chrono::{Datelike, Timelike};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const NUM_TIMESTAMPS: usize = 2_000_000;
+const PASSES: usize = 5; // so 10M timestamps per variant
+const FIRST_SECOND: i64 = 946_684_800; // 2000-01-01T00:00:00Z
+const LAST_SECOND: i64 = 2_208_988_800; // 2040-01-01T00:00:00Z
+// The zone every timestamp is converted to for the report, UTC+09:00. A fixed offset, as the
+// named-zone databases (chrono-tz, the OS tzdata behind C++'s zoned_time) differ by platform
+const REPORT_OFFSET: i64 = 9 * 3600;
+
+fn splitmix64(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date, and back (Howard Hinnant's algorithms)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + (month <= 2) as i64, month, day)
+}
+
+// RFC3339 timestamps between 2000 and 2040 in local time at a random quarter-hour offset from
+// -12:00 to +14:00 (a quarter of them "Z"), half with milliseconds, one per line
+fn generate(n: usize) -> String {
+    let mut seed = 3339;
+    let mut text = String::with_capacity(n * 30);
+    for _ in 0..n {
+        let utc = FIRST_SECOND + (splitmix64(&mut seed) % (LAST_SECOND - FIRST_SECOND) as u64) as i64;
+        let millis = splitmix64(&mut seed).is_multiple_of(2).then(|| splitmix64(&mut seed) % 1000);
+        let offset = match splitmix64(&mut seed) % 4 {
+            0 => None,
+            _ => Some((splitmix64(&mut seed) % 105) as i64 * 15 * 60 - 12 * 3600),
+        };
+        let local = utc + offset.unwrap_or(0);
+        let (year, month, day) = civil_from_days(local.div_euclid(86_400));
+        let second_of_day = local.rem_euclid(86_400);
+        text.push_str(&format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", year, month, day,
+                               second_of_day / 3600, second_of_day / 60 % 60, second_of_day % 60));
+        if let Some(millis) = millis {
+            text.push_str(&format!(".{:03}", millis));
+        }
+        match offset {
+            None => text.push('Z'),
+            Some(offset) => text.push_str(&format!("{}{:02}:{:02}", if offset < 0 { '-' } else { '+' },
+                                                   offset.abs() / 3600, offset.abs() / 60 % 60)),
+        }
+        text.push('\n');
+    }
+    text
+}
+
+// What every variant reports: each timestamp as an instant, the durations between neighbours,
+// and the calendar fields after conversion to REPORT_OFFSET
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+struct Totals {
+    instants: u64, // wrapping sum of Unix milliseconds
+    gaps: u64,     // sum of the absolute gaps to the previous timestamp, in milliseconds
+    local: u64,    // sum of the local times as yyyymmddhh
+    weekdays: u64, // sum of the local weekdays, Monday = 0
+}
+
+impl Totals {
+    fn add(&mut self, unix_millis: i64, gap: Option<u64>, (year, month, day): (i32, u32, u32), hour: u32, weekday: u32) {
+        self.instants = self.instants.wrapping_add(unix_millis as u64);
+        self.gaps = self.gaps.wrapping_add(gap.unwrap_or(0));
+        self.local += ((year as u64 * 100 + month as u64) * 100 + day as u64) * 100 + hour as u64;
+        self.weekdays += weekday as u64;
+    }
+
+    fn checksum(&self) -> u64 {
+        self.instants.wrapping_add(self.gaps).wrapping_add(self.local).wrapping_add(self.weekdays)
+    }
+}
+
+fn run_chrono(text: &str) -> Totals {
+    let zone = chrono::FixedOffset::east_opt(REPORT_OFFSET as i32).unwrap();
+    let mut totals = Totals::default();
+    let mut previous: Option<chrono::DateTime<chrono::FixedOffset>> = None;
+    for line in text.split_terminator('\n') {
+        let t = chrono::DateTime::parse_from_rfc3339(line).unwrap();
+        let gap = previous.map(|p| (t - p).num_milliseconds().unsigned_abs());
+        previous = Some(t);
+        let local = t.with_timezone(&zone);
+        totals.add(t.timestamp_millis(), gap, (local.year(), local.month(), local.day()), local.hour(),
+                   local.weekday().num_days_from_monday());
+    }
+    totals
+}
+
+fn run_time(text: &str) -> Totals {
+    let zone = time::UtcOffset::from_whole_seconds(REPORT_OFFSET as i32).unwrap();
+    let mut totals = Totals::default();
+    let mut previous: Option<time::OffsetDateTime> = None;
+    for line in text.split_terminator('\n') {
+        let t = time::OffsetDateTime::parse(line, &time::format_description::well_known::Rfc3339).unwrap();
+        let gap = previous.map(|p| (t - p).whole_milliseconds().unsigned_abs() as u64);
+        previous = Some(t);
+        let local = t.to_offset(zone);
+        totals.add((t.unix_timestamp_nanos() / 1_000_000) as i64, gap,
+                   (local.year(), u8::from(local.month()) as u32, local.day() as u32), local.hour() as u32,
+                   local.weekday().number_days_from_monday() as u32);
+    }
+    totals
+}
+
+fn digits(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |value, &b| value * 10 + (b - b'0') as u32)
+}
+
+// Fixed field positions and no validation: Unix milliseconds of one timestamp, truncating any
+// fraction beyond them
+fn parse_rfc3339(line: &[u8]) -> i64 {
+    let days = days_from_civil(digits(&line[0..4]) as i64, digits(&line[5..7]), digits(&line[8..10]));
+    let seconds = days * 86_400 + digits(&line[11..13]) as i64 * 3600 + digits(&line[14..16]) as i64 * 60
+        + digits(&line[17..19]) as i64;
+    let mut i = 19;
+    let mut millis = 0;
+    if line[i] == b'.' {
+        i += 1;
+        let mut scale = 100;
+        while line[i].is_ascii_digit() {
+            millis += (line[i] - b'0') as i64 * scale;
+            scale /= 10;
+            i += 1;
+        }
+    }
+    let offset = match line[i] {
+        b'Z' | b'z' => 0,
+        sign => {
+            let offset = digits(&line[i + 1..i + 3]) as i64 * 3600 + digits(&line[i + 4..i + 6]) as i64 * 60;
+            if sign == b'-' { -offset } else { offset }
+        }
+    };
+    (seconds - offset) * 1000 + millis
+}
+
+fn run_hand(text: &str) -> Totals {
+    let mut totals = Totals::default();
+    let mut previous: Option<i64> = None;
+    for line in text.split_terminator('\n') {
+        let t = parse_rfc3339(line.as_bytes());
+        let gap = previous.map(|p| (t - p).unsigned_abs());
+        previous = Some(t);
+        let local = t.div_euclid(1000) + REPORT_OFFSET;
+        let days = local.div_euclid(86_400);
+        let (year, month, day) = civil_from_days(days);
+        totals.add(t, gap, (year as i32, month, day), (local.rem_euclid(86_400) / 3600) as u32,
+                   (days + 3).rem_euclid(7) as u32); // 1970-01-01 was a Thursday
+    }
+    totals
+}
+
+type VariantFn = fn(&str) -> Totals;
+
+const VARIANTS: [(&str, VariantFn); 3] = [("chrono", run_chrono), ("time", run_time), ("hand-rolled", run_hand)];
+
+// black_box on the input every pass stops the compiler computing one pass and reusing it
+fn run(variant: VariantFn, text: &str, passes: usize) -> (Duration, Totals) {
+    let mut totals = Totals::default();
+    let start = Instant::now();
+    for _ in 0..passes {
+        totals = variant(black_box(text));
+    }
+    (start.elapsed(), totals)
+}
+
+fn main() {
+    let text = generate(NUM_TIMESTAMPS);
+    let warmup_end = text.match_indices('\n').nth(NUM_TIMESTAMPS / 100).unwrap().0 + 1;
+
+    // Warm-up
+    for (_, variant) in VARIANTS {
+        let _ = variant(&text[..warmup_end]);
+    }
+
+    // Benchmark; chrono's totals are the reference the others have to reproduce
+    let runs: Vec<(Duration, Totals)> = VARIANTS.iter().map(|&(_, variant)| run(variant, &text, PASSES)).collect();
+    let total_duration: Duration = runs.iter().map(|r| r.0).sum();
+    let all_match = runs.iter().all(|r| r.1 == runs[0].1);
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Timestamps: {} RFC3339 ({:.0} MB) x {} passes per variant, reported at UTC+{:02}:00",
+              NUM_TIMESTAMPS, text.len() as f64 / 1e6, PASSES, REPORT_OFFSET / 3600);
+    for ((name, _), (duration, _)) in VARIANTS.iter().zip(&runs) {
+        let rate = (NUM_TIMESTAMPS * PASSES) as f64 / duration.as_secs_f64();
+        eprintln!("  {:<12} {:.3}s ({:.1} M timestamps/s)", name, duration.as_secs_f64(), rate / 1e6);
+    }
+    eprintln!("Checksum: {} (all variants match: {})", runs[0].1.checksum(), all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hand_rolled_parser_agrees_with_chrono() {
+        for line in ["2000-01-01T00:00:00Z", "2024-02-29T23:59:59.999+14:00", "2039-12-31T12:00:00-12:00",
+                     "2024-03-10T02:30:00.5-05:45", "2024-01-01T00:00:00.123456789+00:00", "1969-12-31T23:59:59z"] {
+            let expected = chrono::DateTime::parse_from_rfc3339(line).unwrap().timestamp_millis();
+            assert_eq!(parse_rfc3339(line.as_bytes()), expected, "{}", line);
+        }
+        for days in [-1, 0, 11_016, 25_567, 50_000] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+
+    #[test]
+    fn variants_agree() {
+        let text = generate(20_000);
+        assert_eq!(text.lines().count(), 20_000);
+        let expected = run_chrono(&text);
+        assert!(expected.gaps > 0 && expected.weekdays > 0);
+        for (name, variant) in VARIANTS {
+            assert_eq!(variant(&text), expected, "{}", name);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::time::{Duration, Instant};
+
+const CORPUS_BYTES: usize = 32 << 20; // 32MB of log lines
+const COMPRESSION_LEVELS: [u8; 2] = [1, 6]; // zlib's fastest and default levels
+const VOCABULARY_SIZE: usize = 50_000;
+const NUM_REQUEST_IDS: u64 = 100_000;
+const CONSONANTS: &[u8] = b"bcdfghklmnprstvz";
+const VOWELS: &[u8] = b"aeiou";
+const LEVELS: [&[u8]; 4] = [b"INFO", b"DEBUG", b"WARN", b"ERROR"];
+const SERVICES: [&[u8]; 8] = [b"auth", b"billing", b"gateway", b"search", b"storage", b"queue", b"mailer", b"cache"];
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn push_two_digits(out: &mut Vec<u8>, n: u64) {
+    out.push(b'0' + (n / 10) as u8);
+    out.push(b'0' + (n % 10) as u8);
+}
+
+// Same log-line generator as word_count: "12:34:56 WARN [billing] tokens... req=0x1a2b\n"
+fn generate_corpus() -> Vec<u8> {
+    let mut seed = 1234567u64;
+    let vocabulary: Vec<Vec<u8>> = (0..VOCABULARY_SIZE)
+        .map(|_| {
+            let syllables = 2 + next_random(&mut seed) % 3;
+            let mut word = Vec::new();
+            for _ in 0..syllables {
+                word.push(CONSONANTS[(next_random(&mut seed) % CONSONANTS.len() as u64) as usize]);
+                word.push(VOWELS[(next_random(&mut seed) % VOWELS.len() as u64) as usize]);
+            }
+            word
+        })
+        .collect();
+
+    let mut cdf = Vec::with_capacity(VOCABULARY_SIZE);
+    let mut total = 0.0f64;
+    for rank in 0..VOCABULARY_SIZE {
+        total += 1.0 / (rank + 1) as f64;
+        cdf.push(total);
+    }
+
+    let mut corpus = Vec::with_capacity(CORPUS_BYTES + 256);
+    while corpus.len() < CORPUS_BYTES {
+        push_two_digits(&mut corpus, next_random(&mut seed) % 24);
+        corpus.push(b':');
+        push_two_digits(&mut corpus, next_random(&mut seed) % 60);
+        corpus.push(b':');
+        push_two_digits(&mut corpus, next_random(&mut seed) % 60);
+        corpus.push(b' ');
+
+        let level = next_random(&mut seed) % 100;
+        corpus.extend_from_slice(LEVELS[if level < 70 { 0 } else if level < 85 { 1 } else if level < 97 { 2 } else { 3 }]);
+        corpus.extend_from_slice(b" [");
+        corpus.extend_from_slice(SERVICES[(next_random(&mut seed) % SERVICES.len() as u64) as usize]);
+        corpus.extend_from_slice(b"]");
+
+        let words = 5 + next_random(&mut seed) % 11;
+        for _ in 0..words {
+            let u = next_random(&mut seed) as f64 / (1u64 << 31) as f64 * total;
+            let rank = cdf.partition_point(|&c| c < u).min(VOCABULARY_SIZE - 1);
+            corpus.push(b' ');
+            corpus.extend_from_slice(&vocabulary[rank]);
+        }
+
+        corpus.extend_from_slice(b" req=0x");
+        let id = next_random(&mut seed) % NUM_REQUEST_IDS;
+        for shift in (0..5).rev() {
+            corpus.push(b"0123456789abcdef"[((id >> (shift * 4)) & 0xf) as usize]);
+        }
+        corpus.push(b'\n');
+    }
+
+    corpus.truncate(CORPUS_BYTES);
+    corpus
+}
+
+// System zlib through its C API, the way flate2's zlib backend would reach it
+fn zlib_compress(data: &[u8], level: u8) -> Vec<u8> {
+    unsafe {
+        let mut len = libz_sys::compressBound(data.len() as libz_sys::uLong);
+        let mut out = vec![0u8; len as usize];
+        let rc = libz_sys::compress2(out.as_mut_ptr(), &mut len, data.as_ptr(), data.len() as libz_sys::uLong, level as i32);
+        assert_eq!(rc, libz_sys::Z_OK, "zlib compress2 failed");
+        out.truncate(len as usize);
+        out
+    }
+}
+
+fn zlib_decompress(data: &[u8], original_len: usize) -> Vec<u8> {
+    unsafe {
+        let mut len = original_len as libz_sys::uLong;
+        let mut out = vec![0u8; original_len];
+        let rc = libz_sys::uncompress(out.as_mut_ptr(), &mut len, data.as_ptr(), data.len() as libz_sys::uLong);
+        assert_eq!(rc, libz_sys::Z_OK, "zlib uncompress failed");
+        out.truncate(len as usize);
+        out
+    }
+}
+
+fn miniz_compress(data: &[u8], level: u8) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(data, level)
+}
+
+fn miniz_decompress(data: &[u8], _original_len: usize) -> Vec<u8> {
+    miniz_oxide::inflate::decompress_to_vec_zlib(data).expect("miniz_oxide inflate failed")
+}
+
+struct LevelResult {
+    compressed_len: usize,
+    compress: Duration,
+    decompress: Duration,
+    round_trip_ok: bool,
+}
+
+fn run_level(corpus: &[u8], level: u8, compress: fn(&[u8], u8) -> Vec<u8>, decompress: fn(&[u8], usize) -> Vec<u8>) -> LevelResult {
+    let start = Instant::now();
+    let compressed = compress(corpus, level);
+    let compress_duration = start.elapsed();
+
+    let start = Instant::now();
+    let restored = decompress(&compressed, corpus.len());
+    let decompress_duration = start.elapsed();
+
+    LevelResult {
+        compressed_len: compressed.len(),
+        compress: compress_duration,
+        decompress: decompress_duration,
+        round_trip_ok: restored == corpus,
+    }
+}
+
+fn report(name: &str, results: &[LevelResult], megabytes: f64) {
+    for (level, r) in COMPRESSION_LEVELS.iter().zip(results) {
+        eprintln!("{:<12} level {}: ratio {:.3}, compress {:.1} MB/s, decompress {:.1} MB/s",
+                  name, level, r.compressed_len as f64 / (megabytes * 1e6),
+                  megabytes / r.compress.as_secs_f64(), megabytes / r.decompress.as_secs_f64());
+    }
+}
+
+fn main() {
+    let corpus = generate_corpus();
+    let megabytes = corpus.len() as f64 / 1e6;
+
+    // Warm-up
+    let _ = run_level(&corpus[..corpus.len() / 100], 6, zlib_compress, zlib_decompress);
+    let _ = run_level(&corpus[..corpus.len() / 100], 6, miniz_compress, miniz_decompress);
+
+    // Benchmark: identical input and levels through the C library and the pure-Rust one
+    let zlib: Vec<LevelResult> = COMPRESSION_LEVELS.iter().map(|&l| run_level(&corpus, l, zlib_compress, zlib_decompress)).collect();
+    let miniz: Vec<LevelResult> = COMPRESSION_LEVELS.iter().map(|&l| run_level(&corpus, l, miniz_compress, miniz_decompress)).collect();
+
+    // The headline time is the pure-Rust implementation; zlib via FFI is the control against C++'s zlib
+    let total_duration: Duration = miniz.iter().map(|r| r.compress + r.decompress).sum();
+    let zlib_duration: Duration = zlib.iter().map(|r| r.compress + r.decompress).sum();
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Corpus: {:.1} MB of log lines", megabytes);
+    report("zlib (FFI)", &zlib, megabytes);
+    report("miniz_oxide", &miniz, megabytes);
+    eprintln!("zlib via FFI total: {:.3}s, miniz_oxide total: {:.3}s", zlib_duration.as_secs_f64(), total_duration.as_secs_f64());
+    eprintln!("Checksum: zlib sizes {} / {} (miniz_oxide: {} / {}, round trips ok: {})",
+              zlib[0].compressed_len, zlib[1].compressed_len, miniz[0].compressed_len, miniz[1].compressed_len,
+              zlib.iter().chain(&miniz).all(|r| r.round_trip_ok));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpus_is_log_lines() {
+        let corpus = generate_corpus();
+        assert_eq!(corpus.len(), CORPUS_BYTES);
+        let line = corpus.split(|&b| b == b'\n').next().unwrap();
+        let text = std::str::from_utf8(line).unwrap();
+        assert_eq!(&text[2..3], ":");
+        assert!(text.contains(" req=0x") && text.contains(" ["));
+    }
+
+    // Both produce zlib streams, so each must inflate the other's output
+    #[test]
+    fn zlib_and_miniz_interoperate() {
+        let corpus = &generate_corpus()[..1 << 20];
+        for level in COMPRESSION_LEVELS {
+            let zlib = zlib_compress(corpus, level);
+            let miniz = miniz_compress(corpus, level);
+            assert!(zlib.len() < corpus.len() / 2 && miniz.len() < corpus.len() / 2);
+            assert_eq!(miniz_decompress(&zlib, corpus.len()), corpus);
+            assert_eq!(zlib_decompress(&miniz, corpus.len()), corpus);
+        }
+    }
+
+    #[test]
+    fn round_trips_edge_cases() {
+        for data in [&b""[..], b"a", &[0u8; 100_000], &(0..=255).collect::<Vec<u8>>()] {
+            for (compress, decompress) in [
+                (zlib_compress as fn(&[u8], u8) -> Vec<u8>, zlib_decompress as fn(&[u8], usize) -> Vec<u8>),
+                (miniz_compress, miniz_decompress),
+            ] {
+                let r = run_level(data, 6, compress, decompress);
+                assert!(r.round_trip_ok);
+            }
+        }
+    }
+}
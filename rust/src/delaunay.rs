@@ -0,0 +1,311 @@
+use std::time::Instant;
+
+const NUM_POINTS: usize = 2_000_000;
+const COORD_BITS: u32 = 20; // Points on a 2^20 x 2^20 integer grid
+const SUPER_EXTENT: i64 = 1 << 26; // Super-triangle corners, far outside the grid
+const NONE: u32 = u32::MAX;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+// Vertices counter-clockwise; neighbor[i] is across the edge opposite vertex i
+#[derive(Clone, Copy)]
+struct Triangle {
+    v: [u32; 3],
+    neighbor: [u32; 3],
+}
+
+// Cavity edge (a, b), counter-clockwise as seen from inside, facing the surviving `outer`
+struct BoundaryEdge {
+    a: u32,
+    b: u32,
+    outer: u32,
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn generate_points() -> Vec<Point> {
+    let mut seed = 8128u64;
+    let mask = (1u64 << COORD_BITS) - 1;
+    (0..NUM_POINTS)
+        .map(|_| {
+            let x = (next_random(&mut seed) & mask) as i64;
+            let y = (next_random(&mut seed) & mask) as i64;
+            Point { x, y }
+        })
+        .collect()
+}
+
+// Position along a Hilbert curve; inserting in this order keeps point-location walks short
+fn hilbert_index(p: Point) -> u64 {
+    let n = 1i64 << COORD_BITS;
+    let (mut x, mut y) = (p.x, p.y);
+    let mut d = 0u64;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = ((x & s) > 0) as i64;
+        let ry = ((y & s) > 0) as i64;
+        d += (s * s * ((3 * rx) ^ ry)) as u64;
+        if ry == 0 {
+            if rx == 1 {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+// Positive when a -> b -> c turns counter-clockwise; exact in i64 for these coordinates
+#[inline]
+fn orient(a: Point, b: Point, c: Point) -> i64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+// Positive when d lies strictly inside the circumcircle of counter-clockwise a, b, c; exact in i128
+#[inline]
+fn in_circle(a: Point, b: Point, c: Point, d: Point) -> bool {
+    let (adx, ady) = (a.x - d.x, a.y - d.y);
+    let (bdx, bdy) = (b.x - d.x, b.y - d.y);
+    let (cdx, cdy) = (c.x - d.x, c.y - d.y);
+    let a_lift = (adx * adx + ady * ady) as i128;
+    let b_lift = (bdx * bdx + bdy * bdy) as i128;
+    let c_lift = (cdx * cdx + cdy * cdy) as i128;
+    let det = a_lift * (bdx * cdy - cdx * bdy) as i128
+        + b_lift * (cdx * ady - adx * cdy) as i128
+        + c_lift * (adx * bdy - bdx * ady) as i128;
+    det > 0
+}
+
+struct Triangulation {
+    points: Vec<Point>,
+    triangles: Vec<Triangle>,
+    // Insertion stamp per triangle marks membership of the current cavity
+    stamp: Vec<u32>,
+    stack: Vec<u32>,
+    cavity: Vec<u32>,
+    boundary: Vec<BoundaryEdge>,
+    last: u32,
+}
+
+impl Triangulation {
+    // Starts from a single super-triangle whose corners are the last three points
+    fn new(mut points: Vec<Point>) -> Self {
+        let n = points.len() as u32;
+        points.push(Point { x: -SUPER_EXTENT, y: -SUPER_EXTENT });
+        points.push(Point { x: SUPER_EXTENT, y: -SUPER_EXTENT });
+        points.push(Point { x: 0, y: SUPER_EXTENT });
+        Triangulation {
+            points,
+            triangles: vec![Triangle { v: [n, n + 1, n + 2], neighbor: [NONE; 3] }],
+            stamp: vec![0],
+            stack: Vec::new(),
+            cavity: Vec::new(),
+            boundary: Vec::new(),
+            last: 0,
+        }
+    }
+
+    fn in_circumcircle(&self, t: u32, p: Point) -> bool {
+        let v = self.triangles[t as usize].v;
+        in_circle(self.points[v[0] as usize], self.points[v[1] as usize], self.points[v[2] as usize], p)
+    }
+
+    // Visibility walk from the most recently created triangle
+    fn locate(&self, p: Point) -> u32 {
+        let mut t = self.last;
+        'walk: loop {
+            let tri = &self.triangles[t as usize];
+            for i in 0..3 {
+                let a = self.points[tri.v[(i + 1) % 3] as usize];
+                let b = self.points[tri.v[(i + 2) % 3] as usize];
+                if orient(a, b, p) < 0 {
+                    t = tri.neighbor[i];
+                    continue 'walk;
+                }
+            }
+            return t;
+        }
+    }
+
+    // Bowyer-Watson step: carve out every triangle whose circumcircle holds the point, then fan it back in
+    fn insert(&mut self, index: u32, stamp: u32) {
+        let p = self.points[index as usize];
+        let start = self.locate(p);
+
+        self.cavity.clear();
+        self.boundary.clear();
+        self.stamp[start as usize] = stamp;
+        self.stack.push(start);
+        while let Some(t) = self.stack.pop() {
+            self.cavity.push(t);
+            let tri = self.triangles[t as usize];
+            for i in 0..3 {
+                let nb = tri.neighbor[i];
+                if nb != NONE && self.stamp[nb as usize] == stamp {
+                    continue;
+                }
+                if nb != NONE && self.in_circumcircle(nb, p) {
+                    self.stamp[nb as usize] = stamp;
+                    self.stack.push(nb);
+                } else {
+                    self.boundary.push(BoundaryEdge { a: tri.v[(i + 1) % 3], b: tri.v[(i + 2) % 3], outer: nb });
+                }
+            }
+        }
+
+        // Reuse the cavity's slots first; a cavity of k triangles always refills with k + 2
+        let first_new = self.cavity.len();
+        for i in 0..self.boundary.len() {
+            let slot = if i < first_new {
+                self.cavity[i]
+            } else {
+                self.triangles.push(Triangle { v: [0; 3], neighbor: [NONE; 3] });
+                self.stamp.push(0);
+                let slot = (self.triangles.len() - 1) as u32;
+                self.cavity.push(slot);
+                slot
+            };
+            let edge = &self.boundary[i];
+            self.triangles[slot as usize] = Triangle { v: [index, edge.a, edge.b], neighbor: [edge.outer, NONE, NONE] };
+            if edge.outer != NONE {
+                // Match by the vertex opposite the shared edge: slot ids may already be reused
+                let outer = &mut self.triangles[edge.outer as usize];
+                let j = outer.v.iter().position(|&v| v != edge.a && v != edge.b).unwrap();
+                outer.neighbor[j] = slot;
+            }
+        }
+
+        // Link the fan: triangle (p, a, b) meets the triangle that starts at b across edge (b, p)
+        for i in 0..self.boundary.len() {
+            let t = self.cavity[i];
+            let b = self.boundary[i].b;
+            let j = self.boundary.iter().position(|e| e.a == b).unwrap();
+            let next = self.cavity[j];
+            self.triangles[t as usize].neighbor[1] = next;
+            self.triangles[next as usize].neighbor[2] = t;
+        }
+        self.last = self.cavity[0];
+    }
+}
+
+fn triangulate(points: &[Point]) -> Triangulation {
+    // Hilbert order, dropping duplicate points (they'd produce degenerate cavities)
+    let mut order: Vec<(u64, Point)> = points.iter().map(|&p| (hilbert_index(p), p)).collect();
+    order.sort_unstable_by_key(|&(key, _)| key);
+    order.dedup_by_key(|&mut (key, _)| key);
+
+    let mut tri = Triangulation::new(order.iter().map(|&(_, p)| p).collect());
+    for i in 0..order.len() as u32 {
+        tri.insert(i, i + 1);
+    }
+    tri
+}
+
+// Triangles not touching the super-triangle, and twice their total area
+fn summarize(tri: &Triangulation) -> (usize, i64) {
+    let first_super = (tri.points.len() - 3) as u32;
+    tri.triangles
+        .iter()
+        .filter(|t| t.v.iter().all(|&v| v < first_super))
+        .fold((0, 0), |(count, area2), t| {
+            let [a, b, c] = t.v.map(|v| tri.points[v as usize]);
+            (count + 1, area2 + orient(a, b, c))
+        })
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let points = generate_points();
+
+    // Warm-up
+    let _ = triangulate(&points[..NUM_POINTS / 100]);
+
+    // Benchmark
+    let start = Instant::now();
+    measure.start();
+    let tri = triangulate(&points);
+    measure.stop();
+    let duration = start.elapsed();
+
+    // Checksum
+    let (count, area2) = summarize(&tri);
+
+    measure.report();
+    eprintln!("Points: {} unique, triangles: {}, {:.0} points/s",
+              tri.points.len() - 3, count, (tri.points.len() - 3) as f64 / duration.as_secs_f64());
+    eprintln!("Checksum: {} (triangles: {})", area2, count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    fn random_points(n: usize) -> Vec<Point> {
+        let mut seed = 17u64;
+        (0..n).map(|_| p((next_random(&mut seed) % 10_000) as i64, (next_random(&mut seed) % 10_000) as i64)).collect()
+    }
+
+    #[test]
+    fn predicates() {
+        assert!(orient(p(0, 0), p(1, 0), p(0, 1)) > 0);
+        assert!(orient(p(0, 0), p(0, 1), p(1, 0)) < 0);
+        assert_eq!(orient(p(0, 0), p(1, 1), p(2, 2)), 0);
+        let (a, b, c) = (p(0, 0), p(4, 0), p(0, 4)); // circumcircle centered at (2, 2)
+        assert!(in_circle(a, b, c, p(2, 2)));
+        assert!(!in_circle(a, b, c, p(4, 4))); // on the circle
+        assert!(!in_circle(a, b, c, p(5, 5)));
+    }
+
+    #[test]
+    fn hilbert_curve_runs_corner_to_corner() {
+        let last = (1i64 << COORD_BITS) - 1;
+        assert_eq!(hilbert_index(p(0, 0)), 0);
+        assert_eq!(hilbert_index(p(last, 0)), (1u64 << (2 * COORD_BITS)) - 1);
+
+        // The 4x4 corner block is the curve's first 16 cells, each a step from the one before
+        let mut block: Vec<(u64, Point)> = (0..16).map(|i| p(i % 4, i / 4)).map(|q| (hilbert_index(q), q)).collect();
+        block.sort_unstable_by_key(|&(d, _)| d);
+        assert!(block.iter().map(|&(d, _)| d).eq(0..16));
+        assert!(block.windows(2).all(|w| (w[0].1.x - w[1].1.x).abs() + (w[0].1.y - w[1].1.y).abs() == 1));
+    }
+
+    #[test]
+    fn square_with_center_gives_four_triangles() {
+        let tri = triangulate(&[p(0, 0), p(10, 0), p(10, 10), p(0, 10), p(5, 5), p(5, 5)]);
+        assert_eq!(tri.points.len() - 3, 5); // the duplicate is dropped
+        assert_eq!(summarize(&tri), (4, 200));
+    }
+
+    #[test]
+    fn random_triangulation_is_delaunay_and_consistent() {
+        let tri = triangulate(&random_points(2_000));
+        let first_super = (tri.points.len() - 3) as u32;
+        for (t, triangle) in tri.triangles.iter().enumerate() {
+            let [a, b, c] = triangle.v.map(|v| tri.points[v as usize]);
+            assert!(orient(a, b, c) > 0);
+            for (i, &nb) in triangle.neighbor.iter().enumerate() {
+                if nb != NONE {
+                    assert!(tri.triangles[nb as usize].neighbor.contains(&(t as u32)), "triangle {} edge {}", t, i);
+                }
+            }
+            if triangle.v.iter().all(|&v| v < first_super) {
+                assert!(tri.points[..first_super as usize].iter().all(|&d| !in_circle(a, b, c, d)));
+            }
+        }
+    }
+}
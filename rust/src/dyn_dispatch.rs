@@ -0,0 +1,250 @@
+use std::f64::consts::PI;
+use std::time::Instant;
+
+const NUM_SHAPES: usize = 10_000_000;
+const NUM_PASSES: usize = 10; // 100M shape evaluations per variant
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn random_dimension(seed: &mut u64) -> f64 {
+    (next_random(seed) % 1000) as f64 / 100.0 + 1.0
+}
+
+trait Shape {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+}
+
+struct Circle {
+    radius: f64,
+}
+
+struct Rectangle {
+    width: f64,
+    height: f64,
+}
+
+struct RightTriangle {
+    a: f64,
+    b: f64,
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+    fn perimeter(&self) -> f64 {
+        2.0 * PI * self.radius
+    }
+}
+
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.width + self.height)
+    }
+}
+
+impl Shape for RightTriangle {
+    fn area(&self) -> f64 {
+        0.5 * self.a * self.b
+    }
+    fn perimeter(&self) -> f64 {
+        self.a + self.b + (self.a * self.a + self.b * self.b).sqrt()
+    }
+}
+
+// Closed set of shapes dispatched with a match instead of a vtable
+enum ShapeEnum {
+    Circle(Circle),
+    Rectangle(Rectangle),
+    RightTriangle(RightTriangle),
+}
+
+impl ShapeEnum {
+    #[inline]
+    fn area(&self) -> f64 {
+        match self {
+            ShapeEnum::Circle(s) => s.area(),
+            ShapeEnum::Rectangle(s) => s.area(),
+            ShapeEnum::RightTriangle(s) => s.area(),
+        }
+    }
+    #[inline]
+    fn perimeter(&self) -> f64 {
+        match self {
+            ShapeEnum::Circle(s) => s.perimeter(),
+            ShapeEnum::Rectangle(s) => s.perimeter(),
+            ShapeEnum::RightTriangle(s) => s.perimeter(),
+        }
+    }
+}
+
+// Shape types are interleaved randomly so the dyn variant can't be devirtualized by position
+fn generate_shapes() -> Vec<ShapeEnum> {
+    let mut seed = 2024u64;
+    (0..NUM_SHAPES)
+        .map(|_| match next_random(&mut seed) % 3 {
+            0 => ShapeEnum::Circle(Circle { radius: random_dimension(&mut seed) }),
+            1 => {
+                let width = random_dimension(&mut seed);
+                ShapeEnum::Rectangle(Rectangle { width, height: random_dimension(&mut seed) })
+            }
+            _ => {
+                let a = random_dimension(&mut seed);
+                ShapeEnum::RightTriangle(RightTriangle { a, b: random_dimension(&mut seed) })
+            }
+        })
+        .collect()
+}
+
+fn boxed(shapes: &[ShapeEnum]) -> Vec<Box<dyn Shape>> {
+    shapes
+        .iter()
+        .map(|s| -> Box<dyn Shape> {
+            match s {
+                ShapeEnum::Circle(c) => Box::new(Circle { radius: c.radius }),
+                ShapeEnum::Rectangle(r) => Box::new(Rectangle { width: r.width, height: r.height }),
+                ShapeEnum::RightTriangle(t) => Box::new(RightTriangle { a: t.a, b: t.b }),
+            }
+        })
+        .collect()
+}
+
+// One homogeneous Vec per concrete type, the layout generics push you towards
+struct ShapeGroups {
+    circles: Vec<Circle>,
+    rectangles: Vec<Rectangle>,
+    triangles: Vec<RightTriangle>,
+}
+
+fn grouped(shapes: &[ShapeEnum]) -> ShapeGroups {
+    let mut groups = ShapeGroups { circles: Vec::new(), rectangles: Vec::new(), triangles: Vec::new() };
+    for s in shapes {
+        match s {
+            ShapeEnum::Circle(c) => groups.circles.push(Circle { radius: c.radius }),
+            ShapeEnum::Rectangle(r) => groups.rectangles.push(Rectangle { width: r.width, height: r.height }),
+            ShapeEnum::RightTriangle(t) => groups.triangles.push(RightTriangle { a: t.a, b: t.b }),
+        }
+    }
+    groups
+}
+
+fn run_dyn(shapes: &[Box<dyn Shape>]) -> f64 {
+    let mut total = 0.0;
+    for _ in 0..NUM_PASSES {
+        for s in shapes {
+            total += s.area() + s.perimeter();
+        }
+    }
+    total
+}
+
+fn run_enum(shapes: &[ShapeEnum]) -> f64 {
+    let mut total = 0.0;
+    for _ in 0..NUM_PASSES {
+        for s in shapes {
+            total += s.area() + s.perimeter();
+        }
+    }
+    total
+}
+
+fn sum_generic<S: Shape>(shapes: &[S]) -> f64 {
+    let mut total = 0.0;
+    for s in shapes {
+        total += s.area() + s.perimeter();
+    }
+    total
+}
+
+fn run_generic(groups: &ShapeGroups) -> f64 {
+    let mut total = 0.0;
+    for _ in 0..NUM_PASSES {
+        total += sum_generic(&groups.circles);
+        total += sum_generic(&groups.rectangles);
+        total += sum_generic(&groups.triangles);
+    }
+    total
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let shapes = generate_shapes();
+    let dyn_shapes = boxed(&shapes);
+    let groups = grouped(&shapes);
+
+    // Warm-up
+    let _ = run_dyn(&dyn_shapes[..NUM_SHAPES / 100]);
+    let _ = run_enum(&shapes[..NUM_SHAPES / 100]);
+    let _ = sum_generic(&groups.circles[..NUM_SHAPES / 300]);
+
+    // Benchmark trait objects
+    let start = Instant::now();
+    measure.start();
+    let dyn_checksum = run_dyn(&dyn_shapes);
+    measure.stop();
+    let dyn_duration = start.elapsed();
+
+    // Benchmark enum dispatch
+    let start = Instant::now();
+    measure.start();
+    let enum_checksum = run_enum(&shapes);
+    measure.stop();
+    let enum_duration = start.elapsed();
+
+    // Benchmark monomorphized generics
+    let start = Instant::now();
+    measure.start();
+    let generic_checksum = run_generic(&groups);
+    measure.stop();
+    let generic_duration = start.elapsed();
+
+    let evaluations = (NUM_SHAPES * NUM_PASSES) as f64;
+
+    measure.report();
+    eprintln!("dyn: {:.6}s ({:.0} shapes/s), enum: {:.6}s ({:.0} shapes/s), generic: {:.6}s ({:.0} shapes/s)",
+              dyn_duration.as_secs_f64(), evaluations / dyn_duration.as_secs_f64(),
+              enum_duration.as_secs_f64(), evaluations / enum_duration.as_secs_f64(),
+              generic_duration.as_secs_f64(), evaluations / generic_duration.as_secs_f64());
+    eprintln!("Checksum: {:.6} (enum: {:.6}, generic: {:.6})", dyn_checksum, enum_checksum, generic_checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shape_formulas() {
+        let triangle = RightTriangle { a: 3.0, b: 4.0 };
+        assert_eq!((triangle.area(), triangle.perimeter()), (6.0, 12.0));
+        let rectangle = Rectangle { width: 2.0, height: 5.0 };
+        assert_eq!((rectangle.area(), rectangle.perimeter()), (10.0, 14.0));
+        let circle = ShapeEnum::Circle(Circle { radius: 2.0 });
+        assert_eq!((circle.area(), circle.perimeter()), (4.0 * PI, 4.0 * PI));
+    }
+
+    // Grouping by type reorders the sum, so the generic total agrees only to rounding
+    #[test]
+    fn dispatch_strategies_agree() {
+        let shapes: Vec<ShapeEnum> = generate_shapes().into_iter().take(3_000).collect();
+        let expected = run_enum(&shapes);
+        assert_eq!(run_dyn(&boxed(&shapes)), expected);
+        let groups = grouped(&shapes);
+        assert_eq!(groups.circles.len() + groups.rectangles.len() + groups.triangles.len(), shapes.len());
+        assert!((run_generic(&groups) - expected).abs() < 1e-9 * expected);
+    }
+
+    #[test]
+    fn dimensions_stay_in_range() {
+        let mut seed = 1;
+        assert!((0..10_000).map(|_| random_dimension(&mut seed)).all(|d| (1.0..11.0).contains(&d)));
+    }
+}
@@ -0,0 +1,135 @@
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier};
+use std::time::Instant;
+use x25519_dalek::{x25519, X25519_BASEPOINT_BYTES};
+
+const NUM_KEYS: usize = 1_000;
+const NUM_MESSAGES: usize = 100_000;
+const MESSAGE_LEN: usize = 64;
+const NUM_EXCHANGES: usize = 100_000;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn random_bytes<const N: usize>(seed: &mut u64) -> [u8; N] {
+    std::array::from_fn(|_| next_random(seed) as u8)
+}
+
+// FNV-1a over every output in order, so any single differing byte changes the checksum
+fn fold_bytes(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+fn sign_all(keys: &[SigningKey], messages: &[[u8; MESSAGE_LEN]]) -> Vec<Signature> {
+    messages.iter().enumerate().map(|(i, m)| keys[i % NUM_KEYS].sign(m)).collect()
+}
+
+fn verify_all(keys: &[SigningKey], messages: &[[u8; MESSAGE_LEN]], signatures: &[Signature]) -> usize {
+    let verifying: Vec<_> = keys.iter().map(|k| k.verifying_key()).collect();
+    messages
+        .iter()
+        .zip(signatures)
+        .enumerate()
+        .filter(|(i, (m, s))| verifying[i % NUM_KEYS].verify(*m, s).is_ok())
+        .count()
+}
+
+// Each exchange combines a fresh secret with one of the long-term peers' public keys
+fn exchange_all(secrets: &[[u8; 32]], peers: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    secrets.iter().enumerate().map(|(i, s)| x25519(*s, peers[i % NUM_KEYS])).collect()
+}
+
+fn main() {
+    let mut seed = 25519u64;
+    let keys: Vec<SigningKey> = (0..NUM_KEYS).map(|_| SigningKey::from_bytes(&random_bytes(&mut seed))).collect();
+    let messages: Vec<[u8; MESSAGE_LEN]> = (0..NUM_MESSAGES).map(|_| random_bytes(&mut seed)).collect();
+    let peers: Vec<[u8; 32]> = (0..NUM_KEYS).map(|_| x25519(random_bytes(&mut seed), X25519_BASEPOINT_BYTES)).collect();
+    let secrets: Vec<[u8; 32]> = (0..NUM_EXCHANGES).map(|_| random_bytes(&mut seed)).collect();
+
+    // Warm-up
+    let warmup = sign_all(&keys, &messages[..NUM_KEYS]);
+    let _ = verify_all(&keys, &messages[..NUM_KEYS], &warmup);
+    let _ = exchange_all(&secrets[..NUM_KEYS], &peers);
+
+    // Benchmark (single-threaded, to measure per-operation cost)
+    let start = Instant::now();
+    let signatures = sign_all(&keys, &messages);
+    let sign_duration = start.elapsed();
+
+    let start = Instant::now();
+    let valid = verify_all(&keys, &messages, &signatures);
+    let verify_duration = start.elapsed();
+
+    let start = Instant::now();
+    let shared = exchange_all(&secrets, &peers);
+    let exchange_duration = start.elapsed();
+
+    let total_duration = sign_duration + verify_duration + exchange_duration;
+    let ops = |n: usize, d: std::time::Duration| n as f64 / d.as_secs_f64();
+
+    // Checksum: Ed25519 signatures and X25519 secrets are deterministic, so both languages must agree
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Ed25519 sign: {:.0} ops/s, verify: {:.0} ops/s ({} of {} valid)",
+              ops(NUM_MESSAGES, sign_duration), ops(NUM_MESSAGES, verify_duration), valid, NUM_MESSAGES);
+    eprintln!("X25519 key exchange: {:.0} ops/s", ops(NUM_EXCHANGES, exchange_duration));
+    eprintln!("Checksum: {:016x} (X25519: {:016x})",
+              fold_bytes(signatures.iter().flat_map(|s| s.to_bytes())),
+              fold_bytes(shared.iter().flatten().copied()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex<const N: usize>(text: &str) -> [u8; N] {
+        std::array::from_fn(|i| u8::from_str_radix(&text[2 * i..2 * i + 2], 16).unwrap())
+    }
+
+    // RFC 8032 section 7.1, test 1 (the empty message)
+    #[test]
+    fn ed25519_matches_rfc_8032() {
+        let key = SigningKey::from_bytes(&hex("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60"));
+        assert_eq!(key.verifying_key().to_bytes(), hex::<32>("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"));
+        assert_eq!(
+            key.sign(b"").to_bytes(),
+            hex::<64>("e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b")
+        );
+    }
+
+    // RFC 7748 section 5.2, first vector
+    #[test]
+    fn x25519_matches_rfc_7748() {
+        let scalar = hex("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4");
+        let u = hex("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c");
+        assert_eq!(x25519(scalar, u), hex::<32>("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552"));
+    }
+
+    #[test]
+    fn signatures_verify_only_their_own_message() {
+        let mut seed = 1u64;
+        let keys: Vec<SigningKey> = (0..NUM_KEYS).map(|_| SigningKey::from_bytes(&random_bytes(&mut seed))).collect();
+        let mut messages: Vec<[u8; MESSAGE_LEN]> = (0..NUM_KEYS + 10).map(|_| random_bytes(&mut seed)).collect();
+        let signatures = sign_all(&keys, &messages);
+        assert_eq!(verify_all(&keys, &messages, &signatures), messages.len());
+        messages[3][0] ^= 1;
+        messages[NUM_KEYS + 5][MESSAGE_LEN - 1] ^= 0x80;
+        assert_eq!(verify_all(&keys, &messages, &signatures), messages.len() - 2);
+    }
+
+    #[test]
+    fn key_exchange_is_symmetric() {
+        let mut seed = 2u64;
+        let secrets: Vec<[u8; 32]> = (0..NUM_KEYS).map(|_| random_bytes(&mut seed)).collect();
+        let publics: Vec<[u8; 32]> = secrets.iter().map(|&s| x25519(s, X25519_BASEPOINT_BYTES)).collect();
+        let peer_secret = random_bytes(&mut seed);
+        let peer_public = x25519(peer_secret, X25519_BASEPOINT_BYTES);
+        let shared = exchange_all(&secrets, &vec![peer_public; NUM_KEYS]);
+        for (s, public) in shared.iter().zip(&publics).step_by(97) {
+            assert_eq!(*s, x25519(peer_secret, *public));
+        }
+        assert_eq!(fold_bytes([].into_iter()), 0xcbf29ce484222325);
+    }
+}
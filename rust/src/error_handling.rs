@@ -0,0 +1,156 @@
+use std::hint::black_box;
+use std::panic;
+use std::time::Instant;
+
+const NUM_CALLS: u64 = 10_000_000;
+const CALL_DEPTH: u32 = 16;
+const ERROR_PERCENT: u64 = 1;
+
+// Error payload carried up the whole chain, like a parse error with context
+#[derive(Debug)]
+struct ParseError {
+    input: u64,
+    code: u32,
+}
+
+fn mix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E3779B97F4A7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+    x ^ (x >> 31)
+}
+
+// Fails for roughly error_percent% of inputs
+fn leaf(x: u64, error_percent: u64) -> Result<u64, ParseError> {
+    let h = mix64(x);
+    if h % 100 < error_percent {
+        Err(ParseError { input: x, code: (h >> 32) as u32 | 1 })
+    } else {
+        Ok(h)
+    }
+}
+
+// Every level does a little work after its callee returns, so the chain can't become a loop
+fn chain_result(depth: u32, x: u64, error_percent: u64) -> Result<u64, ParseError> {
+    if depth == 0 {
+        return leaf(x, error_percent);
+    }
+    let v = chain_result(depth - 1, x, error_percent)?;
+    Ok(v.rotate_left(depth) ^ depth as u64)
+}
+
+fn leaf_panic(x: u64, error_percent: u64) -> u64 {
+    let h = mix64(x);
+    if h % 100 < error_percent {
+        panic::panic_any(ParseError { input: x, code: (h >> 32) as u32 | 1 });
+    }
+    h
+}
+
+fn chain_panic(depth: u32, x: u64, error_percent: u64) -> u64 {
+    if depth == 0 {
+        return leaf_panic(x, error_percent);
+    }
+    chain_panic(depth - 1, x, error_percent).rotate_left(depth) ^ depth as u64
+}
+
+// Returns (checksum, errors seen)
+fn run_result(calls: u64, error_percent: u64) -> (u64, u64) {
+    let depth = black_box(CALL_DEPTH);
+    let (mut checksum, mut errors) = (0u64, 0u64);
+    for i in 0..calls {
+        match chain_result(depth, i, error_percent) {
+            Ok(v) => checksum = checksum.wrapping_add(v),
+            Err(e) => {
+                errors += 1;
+                checksum = checksum.wrapping_add(e.input ^ e.code as u64);
+            }
+        }
+    }
+    (checksum, errors)
+}
+
+fn run_panic(calls: u64, error_percent: u64) -> (u64, u64) {
+    let depth = black_box(CALL_DEPTH);
+    let (mut checksum, mut errors) = (0u64, 0u64);
+    for i in 0..calls {
+        match panic::catch_unwind(|| chain_panic(depth, i, error_percent)) {
+            Ok(v) => checksum = checksum.wrapping_add(v),
+            Err(payload) => {
+                let e = payload.downcast::<ParseError>().expect("unexpected panic payload");
+                errors += 1;
+                checksum = checksum.wrapping_add(e.input ^ e.code as u64);
+            }
+        }
+    }
+    (checksum, errors)
+}
+
+fn main() {
+    // The default hook would print a message for every caught panic
+    panic::set_hook(Box::new(|_| {}));
+    let error_percent = black_box(ERROR_PERCENT);
+
+    // Warm-up
+    let _ = run_result(NUM_CALLS / 100, error_percent);
+    let _ = run_panic(NUM_CALLS / 100, error_percent);
+
+    // Benchmark Result propagation
+    let start = Instant::now();
+    let (result_happy, _) = run_result(NUM_CALLS, black_box(0));
+    let result_happy_duration = start.elapsed();
+
+    let start = Instant::now();
+    let (result_checksum, result_errors) = run_result(NUM_CALLS, error_percent);
+    let result_error_duration = start.elapsed();
+
+    // Benchmark panic + catch_unwind
+    let start = Instant::now();
+    let (panic_happy, _) = run_panic(NUM_CALLS, black_box(0));
+    let panic_happy_duration = start.elapsed();
+
+    let start = Instant::now();
+    let (panic_checksum, panic_errors) = run_panic(NUM_CALLS, error_percent);
+    let panic_error_duration = start.elapsed();
+
+    let _ = panic::take_hook();
+
+    let total_duration = result_happy_duration + result_error_duration + panic_happy_duration + panic_error_duration;
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Result: {:.6}s happy, {:.6}s with {}% errors; panic: {:.6}s happy, {:.6}s with {}% errors",
+              result_happy_duration.as_secs_f64(), result_error_duration.as_secs_f64(), ERROR_PERCENT,
+              panic_happy_duration.as_secs_f64(), panic_error_duration.as_secs_f64(), ERROR_PERCENT);
+    eprintln!("Calls: {} x depth {}, errors: {} (panic: {})", NUM_CALLS, CALL_DEPTH, result_errors, panic_errors);
+    eprintln!("Checksum: {} (panic: {}, happy: {} / {})", result_checksum, panic_checksum, result_happy, panic_happy);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_carry_their_payload_up_the_chain() {
+        assert!((0..1_000).all(|x| chain_result(CALL_DEPTH, x, 0).is_ok()));
+        let e = chain_result(CALL_DEPTH, 42, 100).unwrap_err();
+        assert_eq!(e.input, 42);
+        assert_eq!(e.code, (mix64(42) >> 32) as u32 | 1);
+        assert_eq!(chain_result(0, 42, 0).unwrap(), mix64(42));
+        assert_eq!(chain_result(1, 42, 0).unwrap(), mix64(42).rotate_left(1) ^ 1);
+    }
+
+    #[test]
+    fn result_and_panic_paths_agree() {
+        panic::set_hook(Box::new(|_| {}));
+        for error_percent in [0, 1, 50, 100] {
+            let (checksum, errors) = run_result(20_000, error_percent);
+            assert_eq!(run_panic(20_000, error_percent), (checksum, errors), "{}% errors", error_percent);
+            match error_percent {
+                0 => assert_eq!(errors, 0),
+                100 => assert_eq!(errors, 20_000),
+                _ => assert!(errors.abs_diff(200 * error_percent) < 40 * error_percent),
+            }
+        }
+        let _ = panic::take_hook();
+    }
+}
@@ -0,0 +1,153 @@
+use bench_kernels::{rust_count_primes, rust_mandelbrot, rust_sha256, rust_sort_i32};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const MANDELBROT_SIZE: u32 = 1024;
+const MAX_ITER: u32 = 1000;
+const PRIME_LIMIT: u64 = 100_000_000;
+const SORT_SIZE: usize = 10_000_000;
+const HASH_SIZE: usize = 100_000_000;
+const REPEATS: usize = 3;
+
+// The C++ kernels from cpp/src/kernels.cpp, compiled into this crate by build.rs; rust_* are the
+// same #[no_mangle] functions the bench_kernels cdylib exports to the C++ harness
+extern "C" {
+    fn cpp_mandelbrot(width: u32, height: u32, max_iter: u32) -> u64;
+    fn cpp_count_primes(limit: u64) -> u64;
+    fn cpp_sort_i32(data: *mut i32, len: usize);
+    fn cpp_sha256(data: *const u8, len: usize, digest: *mut u8);
+}
+
+// Fastest of REPEATS runs after an untimed one. `setup` builds each run's input and `check` reduces
+// its output to a comparable u64, both outside the timing
+fn best<I, T>(setup: impl Fn() -> I, kernel: impl Fn(I) -> T, check: impl Fn(T) -> u64) -> (Duration, u64) {
+    let _ = kernel(setup());
+    let mut fastest = Duration::MAX;
+    let mut result = 0;
+    for _ in 0..REPEATS {
+        let input = setup();
+        let start = Instant::now();
+        let output = black_box(kernel(input)); // pins the work between the two clock reads
+        fastest = fastest.min(start.elapsed());
+        result = check(output);
+    }
+    (fastest, result)
+}
+
+// Position-weighted, so any misplaced element changes it
+fn sorted_checksum(v: Vec<i32>) -> u64 {
+    assert!(v.windows(2).all(|w| w[0] <= w[1]), "output is not sorted");
+    v.iter().enumerate().fold(0u64, |acc, (i, &x)| acc.wrapping_add((i as u64) ^ x as u64))
+}
+
+fn digest_prefix(digest: [u8; 32]) -> u64 {
+    u64::from_be_bytes(digest[..8].try_into().unwrap())
+}
+
+fn hex(digest: &[u8; 32]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn main() {
+    let unsorted: Vec<i32> = (0..SORT_SIZE).map(|i| ((i as u64 * 1103515245 + 12345) % 2147483648) as i32).collect();
+    let bytes: Vec<u8> = (0..HASH_SIZE).map(|i| (i % 256) as u8).collect();
+
+    let (size, limit) = (MANDELBROT_SIZE, PRIME_LIMIT);
+    let id = |x: u64| x;
+    // The Rust kernels can be inlined here; black_box keeps the pure ones from being folded at
+    // compile time or hoisted out of the repeat loop
+    let rust_runs = [
+        best(|| (), |_| rust_mandelbrot(black_box(size), size, MAX_ITER), id),
+        best(|| (), |_| rust_count_primes(black_box(limit)), id),
+        best(|| unsorted.clone(), |mut v| {
+            unsafe { rust_sort_i32(v.as_mut_ptr(), v.len()) };
+            v
+        }, sorted_checksum),
+        best(|| (), |_| {
+            let mut digest = [0u8; 32];
+            unsafe { rust_sha256(bytes.as_ptr(), bytes.len(), digest.as_mut_ptr()) };
+            digest
+        }, digest_prefix),
+    ];
+    let cpp_runs = [
+        best(|| (), |_| unsafe { cpp_mandelbrot(size, size, MAX_ITER) }, id),
+        best(|| (), |_| unsafe { cpp_count_primes(limit) }, id),
+        best(|| unsorted.clone(), |mut v| {
+            unsafe { cpp_sort_i32(v.as_mut_ptr(), v.len()) };
+            v
+        }, sorted_checksum),
+        best(|| (), |_| {
+            let mut digest = [0u8; 32];
+            unsafe { cpp_sha256(bytes.as_ptr(), bytes.len(), digest.as_mut_ptr()) };
+            digest
+        }, digest_prefix),
+    ];
+
+    let names = [
+        format!("mandelbrot ({}x{}, {} iterations)", size, size, MAX_ITER),
+        format!("prime sieve (to {})", PRIME_LIMIT),
+        format!("quicksort ({}M i32)", SORT_SIZE / 1_000_000),
+        format!("sha256 ({} MB)", HASH_SIZE / 1_000_000),
+    ];
+    for ((name, rust), cpp) in names.iter().zip(&rust_runs).zip(&cpp_runs) {
+        if rust.1 != cpp.1 {
+            panic!("{}: Rust gave {}, C++ gave {}", name, rust.1, cpp.1);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    unsafe { rust_sha256(bytes.as_ptr(), bytes.len(), digest.as_mut_ptr()) };
+
+    let total: Duration = rust_runs.iter().map(|(t, _)| *t).sum();
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("In-process, single-threaded, best of {} (Rust kernels via Rust, C++ kernels via the C ABI):", REPEATS);
+    for ((name, rust), cpp) in names.iter().zip(&rust_runs).zip(&cpp_runs) {
+        eprintln!("  {:<40} Rust {:.6}s, C++ {:.6}s (C++/Rust {:.2})", name, rust.0.as_secs_f64(), cpp.0.as_secs_f64(),
+                  cpp.0.as_secs_f64() / rust.0.as_secs_f64());
+    }
+    eprintln!("Checksum: mandelbrot {}, primes {}, sort {}, sha256 {}", rust_runs[0].1, rust_runs[1].1,
+              rust_runs[2].1, hex(&digest));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpp_kernels_match_rust_on_small_inputs() {
+        for (size, max_iter) in [(1, 1000), (64, 100), (200, 500)] {
+            assert_eq!(unsafe { cpp_mandelbrot(size, size, max_iter) }, rust_mandelbrot(size, size, max_iter));
+        }
+        for limit in [0, 1, 2, 100, 65_537, 1_000_000] {
+            assert_eq!(unsafe { cpp_count_primes(limit) }, rust_count_primes(limit));
+        }
+
+        let unsorted: Vec<i32> = (0..100_000).map(|i| ((i as u64 * 1103515245 + 12345) % 2147483648) as i32).collect();
+        let sort = |f: unsafe extern "C" fn(*mut i32, usize)| {
+            let mut v = unsorted.clone();
+            unsafe { f(v.as_mut_ptr(), v.len()) };
+            sorted_checksum(v)
+        };
+        assert_eq!(sort(cpp_sort_i32), sort(rust_sort_i32));
+
+        for len in [0, 55, 56, 64, 100_000] {
+            let bytes: Vec<u8> = (0..len).map(|i| (i % 256) as u8).collect();
+            let (mut cpp, mut rust) = ([0u8; 32], [0u8; 32]);
+            unsafe {
+                cpp_sha256(bytes.as_ptr(), bytes.len(), cpp.as_mut_ptr());
+                rust_sha256(bytes.as_ptr(), bytes.len(), rust.as_mut_ptr());
+            }
+            assert_eq!(hex(&cpp), hex(&rust), "{} bytes", len);
+        }
+    }
+
+    #[test]
+    fn checksums_detect_disorder() {
+        assert_eq!(sorted_checksum(vec![1, 2, 3]), 1 + (1 ^ 2) + (2 ^ 3));
+        assert!(std::panic::catch_unwind(|| sorted_checksum(vec![2, 1])).is_err());
+        let mut digest = [0u8; 32];
+        digest[0] = 0xab;
+        digest[7] = 0x01;
+        assert_eq!(digest_prefix(digest), 0xab00_0000_0000_0001);
+    }
+}
@@ -0,0 +1,145 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const NUM_CALLS: u64 = 200_000_000;
+const ADDEND: u64 = 0x9E37_79B9_7F4A_7C15;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Vec2 {
+    x: f64,
+    y: f64,
+}
+
+// Built from src/ffi_shim.c by build.rs
+#[link(name = "ffi_shim", kind = "static")]
+extern "C" {
+    fn ffi_noop();
+    fn ffi_add(a: u64, b: u64) -> u64;
+    fn ffi_dot(a: Vec2, b: Vec2) -> f64;
+}
+
+// Native equivalents kept out of line, so each call is a real call/return like the FFI ones
+#[inline(never)]
+fn native_noop() {
+    // Without a side effect LLVM would delete calls to an empty function
+    black_box(());
+}
+
+#[inline(never)]
+fn native_add(a: u64, b: u64) -> u64 {
+    a.wrapping_add(b)
+}
+
+#[inline(never)]
+fn native_dot(a: Vec2, b: Vec2) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+#[inline(always)]
+fn inlined_add(a: u64, b: u64) -> u64 {
+    a.wrapping_add(b)
+}
+
+#[inline(always)]
+fn inlined_dot(a: Vec2, b: Vec2) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+fn vec2(i: u64) -> Vec2 {
+    Vec2 { x: (i & 1023) as f64, y: 1.0 }
+}
+
+// Arguments go through black_box so the loops can't be vectorized or folded into closed forms
+fn run_noop(calls: u64, f: impl Fn()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..calls {
+        f();
+    }
+    start.elapsed()
+}
+
+fn run_add(calls: u64, f: impl Fn(u64, u64) -> u64) -> (Duration, u64) {
+    let start = Instant::now();
+    let mut sum = 0u64;
+    for i in 0..calls {
+        sum = sum.wrapping_add(f(black_box(i), black_box(ADDEND)));
+    }
+    (start.elapsed(), sum)
+}
+
+fn run_dot(calls: u64, f: impl Fn(Vec2, Vec2) -> f64) -> (Duration, f64) {
+    let start = Instant::now();
+    let mut sum = 0.0;
+    let b = black_box(Vec2 { x: 0.5, y: 0.25 });
+    for i in 0..calls {
+        sum += f(vec2(black_box(i)), b);
+    }
+    (start.elapsed(), sum)
+}
+
+fn ns_per_call(d: Duration) -> f64 {
+    d.as_nanos() as f64 / NUM_CALLS as f64
+}
+
+fn main() {
+    // Warm-up
+    let _ = run_noop(NUM_CALLS / 100, || unsafe { ffi_noop() });
+    let _ = run_add(NUM_CALLS / 100, |a, b| unsafe { ffi_add(a, b) });
+    let _ = run_dot(NUM_CALLS / 100, |a, b| unsafe { ffi_dot(a, b) });
+
+    // Benchmark
+    let ffi_noop_time = run_noop(NUM_CALLS, || unsafe { ffi_noop() });
+    let native_noop_time = run_noop(NUM_CALLS, native_noop);
+
+    let (ffi_add_time, ffi_add_sum) = run_add(NUM_CALLS, |a, b| unsafe { ffi_add(a, b) });
+    let (native_add_time, native_add_sum) = run_add(NUM_CALLS, native_add);
+    let (inlined_add_time, inlined_add_sum) = run_add(NUM_CALLS, inlined_add);
+
+    let (ffi_dot_time, ffi_dot_sum) = run_dot(NUM_CALLS, |a, b| unsafe { ffi_dot(a, b) });
+    let (native_dot_time, native_dot_sum) = run_dot(NUM_CALLS, native_dot);
+    let (inlined_dot_time, inlined_dot_sum) = run_dot(NUM_CALLS, inlined_dot);
+
+    let total_duration = ffi_noop_time + native_noop_time + ffi_add_time + native_add_time + inlined_add_time
+        + ffi_dot_time + native_dot_time + inlined_dot_time;
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("{} calls each, ns/call:", NUM_CALLS);
+    eprintln!("  noop()            FFI {:.3}, native {:.3}", ns_per_call(ffi_noop_time), ns_per_call(native_noop_time));
+    eprintln!("  add(u64, u64)     FFI {:.3}, native {:.3}, inlined {:.3}",
+              ns_per_call(ffi_add_time), ns_per_call(native_add_time), ns_per_call(inlined_add_time));
+    eprintln!("  dot(Vec2, Vec2)   FFI {:.3}, native {:.3}, inlined {:.3}",
+              ns_per_call(ffi_dot_time), ns_per_call(native_dot_time), ns_per_call(inlined_dot_time));
+    eprintln!("Checksum: {} (dot: {:.1}, all paths match: {})", ffi_add_sum, ffi_dot_sum,
+              ffi_add_sum == native_add_sum && ffi_add_sum == inlined_add_sum
+                  && ffi_dot_sum == native_dot_sum && ffi_dot_sum == inlined_dot_sum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ffi_and_native_functions_agree() {
+        for (a, b) in [(0, 0), (1, 2), (u64::MAX, 1), (ADDEND, ADDEND)] {
+            let sum = unsafe { ffi_add(a, b) };
+            assert_eq!(sum, a.wrapping_add(b));
+            assert_eq!(native_add(a, b), sum);
+            assert_eq!(inlined_add(a, b), sum);
+        }
+        let (a, b) = (Vec2 { x: 3.0, y: -2.0 }, Vec2 { x: 0.5, y: 4.0 });
+        assert_eq!(unsafe { ffi_dot(a, b) }, -6.5);
+        assert_eq!(native_dot(a, b), -6.5);
+        unsafe { ffi_noop() };
+    }
+
+    // The sums main compares have closed forms
+    #[test]
+    fn loops_sum_every_call() {
+        let calls = 10_000u64;
+        let (_, sum) = run_add(calls, |a, b| unsafe { ffi_add(a, b) });
+        assert_eq!(sum, calls * (calls - 1) / 2 + calls.wrapping_mul(ADDEND));
+        let (_, dot) = run_dot(2048, native_dot);
+        assert_eq!(dot, 2.0 * 0.5 * (1023.0 * 1024.0 / 2.0) + 2048.0 * 0.25);
+    }
+}
@@ -0,0 +1,18 @@
+// Tiny C functions called across the FFI boundary by ffi_overhead.rs.
+// Compiled separately by build.rs, so LTO cannot inline them into Rust.
+#include <stdint.h>
+
+typedef struct {
+    double x;
+    double y;
+} Vec2;
+
+void ffi_noop(void) {}
+
+uint64_t ffi_add(uint64_t a, uint64_t b) {
+    return a + b;
+}
+
+double ffi_dot(Vec2 a, Vec2 b) {
+    return a.x * b.x + a.y * b.y;
+}
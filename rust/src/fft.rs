@@ -1,35 +1,660 @@
-use rustfft::{FftPlanner, num_complex::Complex};
-use std::time::Instant;
+use rayon::prelude::*;
+use rustfft::{Fft, FftNum, FftPlanner, num_complex::Complex, num_traits::Float};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 const SIZE: usize = 16_777_216; // 2^24
+const BATCH_COUNT: usize = 8192;
+const BATCH_LEN: usize = 4096;
+const BATCH_WARMUP_ROWS: usize = 256;
 
-fn main() {
-    let mut planner = FftPlanner::<f64>::new();
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Single,  // one 2^24-point signal: complex forward, real forward and real inverse
+    Batched, // BATCH_COUNT independent BATCH_LEN-point transforms spread across threads
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Variant {
+    Default, // rustfft, with the real transforms built on it below
+    Vdsp,    // vDSP_DFT_* from Apple's Accelerate, the platform library both languages can call
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Precision {
+    F64,
+    F32,
+}
+
+// Real-to-complex transform of n samples through one n/2-point complex FFT: even samples go in
+// the real parts, odd samples in the imaginary parts, and a twiddle pass separates the two
+// half-length spectra. The inverse runs the same steps backwards
+struct RealFft<T: FftNum> {
+    forward: Arc<dyn Fft<T>>,
+    inverse: Arc<dyn Fft<T>>,
+    twiddles: Vec<Complex<T>>, // exp(-2πik/n) for k in 0..n/2, computed in f64
+}
+
+impl<T: FftNum + Float> RealFft<T> {
+    fn new(planner: &mut FftPlanner<T>, n: usize) -> Self {
+        let twiddles = (0..n / 2)
+            .map(|k| {
+                let angle = -2.0 * std::f64::consts::PI * k as f64 / n as f64;
+                Complex::new(T::from_f64(angle.cos()).unwrap(), T::from_f64(angle.sin()).unwrap())
+            })
+            .collect();
+        RealFft { forward: planner.plan_fft_forward(n / 2), inverse: planner.plan_fft_inverse(n / 2), twiddles }
+    }
+
+    // input: n samples; work: n/2 scratch; output: the n/2 + 1 non-redundant bins
+    fn process_forward(&self, input: &[T], work: &mut [Complex<T>], output: &mut [Complex<T>]) {
+        let m = work.len();
+        for (z, pair) in work.iter_mut().zip(input.chunks_exact(2)) {
+            *z = Complex::new(pair[0], pair[1]);
+        }
+        self.forward.process(work);
+
+        let half = T::from_f64(0.5).unwrap();
+        for (k, out) in output.iter_mut().enumerate() {
+            let z = work[k % m];
+            let z_mirror = work[(m - k) % m].conj();
+            let even = (z + z_mirror) * half;
+            let odd = (z - z_mirror) * Complex::new(T::zero(), -half);
+            let twiddle = if k < m { self.twiddles[k] } else { Complex::new(-T::one(), T::zero()) };
+            *out = even + twiddle * odd;
+        }
+    }
+
+    // input: n/2 + 1 bins; work: n/2 scratch; output: n samples, normalized by 1/n
+    fn process_inverse(&self, input: &[Complex<T>], work: &mut [Complex<T>], output: &mut [T]) {
+        let m = work.len();
+        let half = T::from_f64(0.5).unwrap();
+        for (k, z) in work.iter_mut().enumerate() {
+            let x = input[k];
+            let x_mirror = input[m - k].conj();
+            let even = (x + x_mirror) * half;
+            let odd = (x - x_mirror) * self.twiddles[k].conj() * half;
+            *z = even + odd * Complex::new(T::zero(), T::one());
+        }
+        self.inverse.process(work);
+
+        let scale = T::from_f64(1.0 / m as f64).unwrap();
+        for (pair, z) in output.chunks_exact_mut(2).zip(work.iter()) {
+            pair[0] = z.re * scale;
+            pair[1] = z.im * scale;
+        }
+    }
+}
+
+// Plans for T, converts the f64 signal outside the timed region, then warms up and times one transform
+fn transform<T: FftNum + Float>(signal: &[f64]) -> (Duration, Vec<Complex<T>>) {
+    let mut planner = FftPlanner::<T>::new();
     let fft = planner.plan_fft_forward(SIZE);
-    
+
+    let mut buffer = bench_pages::with_capacity(SIZE);
+    buffer.extend(signal.iter().map(|&s| Complex::new(T::from_f64(s).unwrap(), T::zero())));
+
+    // Warm-up
+    let mut warmup = buffer.clone();
+    fft.process(&mut warmup);
+
+    // Benchmark
+    let start = Instant::now();
+    fft.process(&mut buffer);
+    (start.elapsed(), buffer)
+}
+
+fn widen<T: Float>(c: Complex<T>) -> Complex<f64> {
+    Complex::new(c.re.to_f64().unwrap(), c.im.to_f64().unwrap())
+}
+
+struct Run {
+    complex: Duration,
+    real_forward: Duration,
+    real_inverse: Duration,
+    spectrum: Vec<Complex<f64>>, // complex forward transform, widened to f64
+    real_spectrum_error: f64,    // max |X_real - X_complex| / max |X_complex| over the shared bins
+    round_trip_error: f64,       // max |x' - x| / max |x| after forward and inverse real transforms
+    tolerance: f64,              // 1000 ε of T
+}
+
+fn run<T: FftNum + Float>(signal: &[f64]) -> Run {
+    let (complex, spectrum) = transform::<T>(signal);
+    let spectrum: Vec<Complex<f64>> = spectrum.into_iter().map(widen).collect();
+
+    let mut planner = FftPlanner::<T>::new();
+    let real_fft = RealFft::new(&mut planner, SIZE);
+    let mut input = bench_pages::with_capacity(SIZE);
+    input.extend(signal.iter().map(|&s| T::from_f64(s).unwrap()));
+    let mut work = bench_pages::filled(Complex::new(T::zero(), T::zero()), SIZE / 2);
+    let mut half_spectrum = bench_pages::filled(Complex::new(T::zero(), T::zero()), SIZE / 2 + 1);
+    let mut output = bench_pages::filled(T::zero(), SIZE);
+
+    // Warm-up
+    real_fft.process_forward(&input, &mut work, &mut half_spectrum);
+    real_fft.process_inverse(&half_spectrum, &mut work, &mut output);
+
+    // Benchmark
+    let start = Instant::now();
+    real_fft.process_forward(&input, &mut work, &mut half_spectrum);
+    let real_forward = start.elapsed();
+    let start = Instant::now();
+    real_fft.process_inverse(&half_spectrum, &mut work, &mut output);
+    let real_inverse = start.elapsed();
+
+    let max_spectrum = spectrum.iter().fold(0.0f64, |m, x| m.max(x.norm()));
+    let real_spectrum_error = half_spectrum
+        .iter()
+        .zip(&spectrum)
+        .fold(0.0f64, |e, (&x, c)| e.max((widen(x) - c).norm()))
+        / max_spectrum;
+    let max_signal = signal.iter().fold(0.0f64, |m, x| m.max(x.abs()));
+    let round_trip_error = output
+        .iter()
+        .zip(signal)
+        .fold(0.0f64, |e, (x, s)| e.max((x.to_f64().unwrap() - s).abs()))
+        / max_signal;
+
+    Run {
+        complex,
+        real_forward,
+        real_inverse,
+        spectrum,
+        real_spectrum_error,
+        round_trip_error,
+        tolerance: T::epsilon().to_f64().unwrap() * 1e3,
+    }
+}
+
+// Accelerate's DFT works on split complex data: separate real and imaginary arrays. Data is laid
+// out that way outside the timed region, so only the vDSP_DFT_Execute calls are timed
+#[cfg(target_os = "macos")]
+mod vdsp {
+    use super::*;
+    use std::ffi::c_void;
+    use std::marker::PhantomData;
+
+    const FORWARD: i32 = 1;
+    const INVERSE: i32 = -1;
+
+    #[link(name = "Accelerate", kind = "framework")]
+    extern "C" {
+        fn vDSP_DFT_zop_CreateSetup(previous: *mut c_void, length: usize, direction: i32) -> *mut c_void;
+        fn vDSP_DFT_zrop_CreateSetup(previous: *mut c_void, length: usize, direction: i32) -> *mut c_void;
+        fn vDSP_DFT_Execute(setup: *const c_void, ir: *const f32, ii: *const f32, or: *mut f32, oi: *mut f32);
+        fn vDSP_DFT_DestroySetup(setup: *mut c_void);
+        fn vDSP_DFT_zop_CreateSetupD(previous: *mut c_void, length: usize, direction: i32) -> *mut c_void;
+        fn vDSP_DFT_zrop_CreateSetupD(previous: *mut c_void, length: usize, direction: i32) -> *mut c_void;
+        fn vDSP_DFT_ExecuteD(setup: *const c_void, ir: *const f64, ii: *const f64, or: *mut f64, oi: *mut f64);
+        fn vDSP_DFT_DestroySetupD(setup: *mut c_void);
+    }
+
+    // The f32 and f64 entry points behind one interface
+    pub trait Real: FftNum + Float {
+        unsafe fn create(real: bool, n: usize, direction: i32) -> *mut c_void;
+        unsafe fn execute(setup: *const c_void, ir: *const Self, ii: *const Self, or: *mut Self, oi: *mut Self);
+        unsafe fn destroy(setup: *mut c_void);
+    }
+
+    impl Real for f32 {
+        unsafe fn create(real: bool, n: usize, direction: i32) -> *mut c_void {
+            if real {
+                vDSP_DFT_zrop_CreateSetup(std::ptr::null_mut(), n, direction)
+            } else {
+                vDSP_DFT_zop_CreateSetup(std::ptr::null_mut(), n, direction)
+            }
+        }
+        unsafe fn execute(setup: *const c_void, ir: *const Self, ii: *const Self, or: *mut Self, oi: *mut Self) {
+            vDSP_DFT_Execute(setup, ir, ii, or, oi)
+        }
+        unsafe fn destroy(setup: *mut c_void) {
+            vDSP_DFT_DestroySetup(setup)
+        }
+    }
+
+    impl Real for f64 {
+        unsafe fn create(real: bool, n: usize, direction: i32) -> *mut c_void {
+            if real {
+                vDSP_DFT_zrop_CreateSetupD(std::ptr::null_mut(), n, direction)
+            } else {
+                vDSP_DFT_zop_CreateSetupD(std::ptr::null_mut(), n, direction)
+            }
+        }
+        unsafe fn execute(setup: *const c_void, ir: *const Self, ii: *const Self, or: *mut Self, oi: *mut Self) {
+            vDSP_DFT_ExecuteD(setup, ir, ii, or, oi)
+        }
+        unsafe fn destroy(setup: *mut c_void) {
+            vDSP_DFT_DestroySetupD(setup)
+        }
+    }
+
+    // A DFT setup, destroyed on drop. Executing only reads it, so threads can share one
+    struct Dft<T: Real> {
+        setup: *mut c_void,
+        _marker: PhantomData<T>,
+    }
+
+    unsafe impl<T: Real> Send for Dft<T> {}
+    unsafe impl<T: Real> Sync for Dft<T> {}
+
+    impl<T: Real> Dft<T> {
+        // real: a zrop setup for n real samples, packed as n/2 even/odd pairs
+        fn new(real: bool, n: usize, direction: i32) -> Self {
+            let setup = unsafe { T::create(real, n, direction) };
+            assert!(!setup.is_null(), "vDSP has no {}-point DFT", n);
+            Dft { setup, _marker: PhantomData }
+        }
+
+        fn execute(&self, ir: &[T], ii: &[T], or: &mut [T], oi: &mut [T]) {
+            assert!(ir.len() == ii.len() && or.len() == ir.len() && oi.len() == ir.len());
+            unsafe { T::execute(self.setup, ir.as_ptr(), ii.as_ptr(), or.as_mut_ptr(), oi.as_mut_ptr()) }
+        }
+    }
+
+    impl<T: Real> Drop for Dft<T> {
+        fn drop(&mut self) {
+            unsafe { T::destroy(self.setup) }
+        }
+    }
+
+    fn zeros<T: Real>(n: usize) -> Vec<T> {
+        bench_pages::filled(T::zero(), n)
+    }
+
+    fn timed(f: impl FnOnce()) -> Duration {
+        let start = Instant::now();
+        f();
+        start.elapsed()
+    }
+
+    pub fn run<T: Real>(signal: &[f64]) -> Run {
+        let (n, m) = (SIZE, SIZE / 2);
+
+        // Complex forward, the signal in the real parts
+        let complex = Dft::<T>::new(false, n, FORWARD);
+        let mut input_re: Vec<T> = zeros(n);
+        for (x, &s) in input_re.iter_mut().zip(signal) {
+            *x = T::from_f64(s).unwrap();
+        }
+        let input_im: Vec<T> = zeros(n);
+        let (mut re, mut im) = (zeros(n), zeros(n));
+        complex.execute(&input_re, &input_im, &mut re, &mut im); // warm-up
+        let complex_time = timed(|| complex.execute(&input_re, &input_im, &mut re, &mut im));
+        let spectrum: Vec<Complex<f64>> = re.iter().zip(&im).map(|(&r, &i)| widen(Complex::new(r, i))).collect();
+
+        // Real forward: even samples in, odd samples alongside. The result is packed as
+        // 2 X[k] for 0 < k < n/2, with 2 X[0] and 2 X[n/2], both real, sharing slot 0
+        let real_forward = Dft::<T>::new(true, n, FORWARD);
+        let real_inverse = Dft::<T>::new(true, n, INVERSE);
+        let mut even: Vec<T> = zeros(m);
+        let mut odd: Vec<T> = zeros(m);
+        for (i, pair) in signal.chunks_exact(2).enumerate() {
+            even[i] = T::from_f64(pair[0]).unwrap();
+            odd[i] = T::from_f64(pair[1]).unwrap();
+        }
+        let (mut packed_re, mut packed_im) = (zeros(m), zeros(m));
+        let (mut out_even, mut out_odd) = (zeros(m), zeros(m));
+        real_forward.execute(&even, &odd, &mut packed_re, &mut packed_im); // warm-up
+        real_inverse.execute(&packed_re, &packed_im, &mut out_even, &mut out_odd);
+        let forward_time = timed(|| real_forward.execute(&even, &odd, &mut packed_re, &mut packed_im));
+        // The packed spectrum goes straight back in, and comes out as 2n times the signal
+        let inverse_time = timed(|| real_inverse.execute(&packed_re, &packed_im, &mut out_even, &mut out_odd));
+
+        let half = |k: usize| -> Complex<f64> {
+            let bin = match k {
+                0 => Complex::new(packed_re[0], T::zero()),
+                _ if k == m => Complex::new(packed_im[0], T::zero()),
+                _ => Complex::new(packed_re[k], packed_im[k]),
+            };
+            widen(bin) * 0.5
+        };
+        let max_spectrum = spectrum.iter().fold(0.0f64, |e, x| e.max(x.norm()));
+        let real_spectrum_error = (0..=m).fold(0.0f64, |e, k| e.max((half(k) - spectrum[k]).norm())) / max_spectrum;
+        let scale = 1.0 / (2 * n) as f64;
+        let max_signal = signal.iter().fold(0.0f64, |e, x| e.max(x.abs()));
+        let round_trip_error = signal
+            .chunks_exact(2)
+            .zip(out_even.iter().zip(&out_odd))
+            .fold(0.0f64, |e, (pair, (&x0, &x1))| {
+                e.max((x0.to_f64().unwrap() * scale - pair[0]).abs())
+                    .max((x1.to_f64().unwrap() * scale - pair[1]).abs())
+            })
+            / max_signal;
+
+        Run {
+            complex: complex_time,
+            real_forward: forward_time,
+            real_inverse: inverse_time,
+            spectrum,
+            real_spectrum_error,
+            round_trip_error,
+            tolerance: T::epsilon().to_f64().unwrap() * 1e3,
+        }
+    }
+
+    // Every BATCH_LEN row of the split arrays through one shared setup, rows spread over rayon
+    fn process_rows<T: Real>(dft: &Dft<T>, ir: &[T], ii: &[T], or: &mut [T], oi: &mut [T]) {
+        ir.par_chunks(BATCH_LEN)
+            .zip(ii.par_chunks(BATCH_LEN))
+            .zip(or.par_chunks_mut(BATCH_LEN).zip(oi.par_chunks_mut(BATCH_LEN)))
+            .for_each(|((ir, ii), (or, oi))| dft.execute(ir, ii, or, oi));
+    }
+
+    pub fn run_batched<T: Real>(signals: &[f64]) -> BatchRun {
+        let len = signals.len();
+        let forward = Dft::<T>::new(false, BATCH_LEN, FORWARD);
+        let input_re: Vec<T> = signals.par_iter().map(|&s| T::from_f64(s).unwrap()).collect();
+        let input_im: Vec<T> = vec![T::zero(); len];
+        let (mut re, mut im) = (vec![T::zero(); len], vec![T::zero(); len]);
+
+        // Warm-up
+        let rows = BATCH_WARMUP_ROWS * BATCH_LEN;
+        process_rows(&forward, &input_re[..rows], &input_im[..rows], &mut re[..rows], &mut im[..rows]);
+
+        let start = Instant::now();
+        process_rows(&forward, &input_re, &input_im, &mut re, &mut im);
+        let duration = start.elapsed();
+
+        // Untimed inverse of every row, unnormalized like rustfft's
+        let inverse = Dft::<T>::new(false, BATCH_LEN, INVERSE);
+        let (mut back_re, mut back_im) = (vec![T::zero(); len], vec![T::zero(); len]);
+        process_rows(&inverse, &re, &im, &mut back_re, &mut back_im);
+        let scale = 1.0 / BATCH_LEN as f64;
+        let max_error = signals
+            .par_iter()
+            .zip(back_re.par_iter().zip(&back_im))
+            .map(|(&s, (&r, &i))| (widen(Complex::new(r, i)) * scale - Complex::new(s, 0.0)).norm())
+            .reduce(|| 0.0, f64::max);
+
+        BatchRun {
+            duration,
+            spectra: re.par_iter().zip(&im).map(|(&r, &i)| widen(Complex::new(r, i))).collect(),
+            round_trip_error: max_error / signals.par_iter().map(|s| s.abs()).reduce(|| 0.0, f64::max),
+            tolerance: T::epsilon().to_f64().unwrap() * 1e3,
+        }
+    }
+}
+
+// Same signatures as the macOS module, so main calls either
+#[cfg(not(target_os = "macos"))]
+#[allow(clippy::extra_unused_type_parameters)]
+mod vdsp {
+    use super::*;
+
+    pub fn run<T>(_: &[f64]) -> Run {
+        panic!("--variant vdsp needs macOS, where Accelerate provides vDSP")
+    }
+
+    pub fn run_batched<T>(_: &[f64]) -> BatchRun {
+        panic!("--variant vdsp needs macOS, where Accelerate provides vDSP")
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    bench_pages::enable(&args);
+    let precision = match args.iter().position(|a| a == "--precision") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("f64") => Precision::F64,
+            Some("f32") => Precision::F32,
+            _ => panic!("--precision expects f64 or f32"),
+        },
+        None => Precision::F64,
+    };
+    let mode = match args.iter().position(|a| a == "--mode") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("single") => Mode::Single,
+            Some("batched") => Mode::Batched,
+            _ => panic!("--mode expects single or batched"),
+        },
+        None => Mode::Single,
+    };
+
+    let variant = match args.iter().position(|a| a == "--variant") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("default") => Variant::Default,
+            Some("vdsp") => Variant::Vdsp,
+            _ => panic!("--variant expects default or vdsp"),
+        },
+        None => Variant::Default,
+    };
+
+    match mode {
+        Mode::Single => report_single(precision, variant),
+        Mode::Batched => report_batched(precision, variant),
+    }
+}
+
+fn variant_name(variant: Variant) -> &'static str {
+    match variant {
+        Variant::Default => "rustfft",
+        Variant::Vdsp => "vDSP (Accelerate)",
+    }
+}
+
+fn report_single(precision: Precision, variant: Variant) {
     // Generate input signal
-    let mut buffer: Vec<Complex<f64>> = (0..SIZE)
+    let signal: Vec<f64> = (0..SIZE)
         .map(|i| {
             let t = i as f64 / SIZE as f64;
-            let signal = (2.0 * std::f64::consts::PI * 50.0 * t).sin()
-                       + (2.0 * std::f64::consts::PI * 120.0 * t).sin();
-            Complex::new(signal, 0.0)
+            (2.0 * std::f64::consts::PI * 50.0 * t).sin()
+                + (2.0 * std::f64::consts::PI * 120.0 * t).sin()
         })
         .collect();
-    
+
+    let result = match (variant, precision) {
+        (Variant::Default, Precision::F64) => run::<f64>(&signal),
+        (Variant::Default, Precision::F32) => run::<f32>(&signal),
+        (Variant::Vdsp, Precision::F64) => vdsp::run::<f64>(&signal),
+        (Variant::Vdsp, Precision::F32) => vdsp::run::<f32>(&signal),
+    };
+    let spectrum = &result.spectrum;
+
+    // Normwise against the f64 transform (untimed): max |X - X64| / max |X64|
+    let relative_error = (precision == Precision::F32).then(|| {
+        let (_, reference) = transform::<f64>(&signal);
+        let (max_error, max_reference) = spectrum
+            .iter()
+            .zip(&reference)
+            .fold((0.0f64, 0.0f64), |(e, r), (x, x64)| (e.max((x - x64).norm()), r.max(x64.norm())));
+        max_error / max_reference
+    });
+
+    // Checksum
+    let checksum: f64 = spectrum.iter().take(1000).map(|c| c.norm()).sum();
+    let total_duration = result.complex + result.real_forward + result.real_inverse;
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Variant: {}", variant_name(variant));
+    eprintln!("Precision: {}", if precision == Precision::F64 { "f64" } else { "f32" });
+    eprintln!("Complex forward: {:.6}s, real forward: {:.6}s, real inverse: {:.6}s",
+              result.complex.as_secs_f64(), result.real_forward.as_secs_f64(), result.real_inverse.as_secs_f64());
+    if let Some(error) = relative_error {
+        eprintln!("Relative error vs f64: {:.3e}", error);
+    }
+    eprintln!("Real vs complex spectrum: max error {:.3e} (tolerance {:.1e}: {})",
+              result.real_spectrum_error, result.tolerance, result.real_spectrum_error <= result.tolerance);
+    eprintln!("Round-trip vs original signal: max error {:.3e} (tolerance {:.1e}: {})",
+              result.round_trip_error, result.tolerance, result.round_trip_error <= result.tolerance);
+    eprintln!("{}", bench_pages::report());
+    eprintln!("Checksum: {}", checksum);
+}
+
+// Row r carries tones at 50 + r % 256 and 120 cycles per row, with a per-row phase
+fn batch_signals() -> Vec<f64> {
+    (0..BATCH_COUNT * BATCH_LEN)
+        .map(|i| {
+            let (r, n) = (i / BATCH_LEN, i % BATCH_LEN);
+            let t = n as f64 / BATCH_LEN as f64;
+            let phase = r as f64 / BATCH_COUNT as f64;
+            (2.0 * std::f64::consts::PI * ((50 + r % 256) as f64 * t + phase)).sin()
+                + (2.0 * std::f64::consts::PI * 120.0 * t).sin()
+        })
+        .collect()
+}
+
+// Runs fft over every BATCH_LEN row in parallel, each rayon worker reusing its own scratch buffer
+fn process_rows<T: FftNum>(fft: &dyn Fft<T>, buffer: &mut [Complex<T>]) {
+    let scratch_len = fft.get_inplace_scratch_len();
+    buffer.par_chunks_mut(BATCH_LEN).for_each_init(
+        || vec![Complex::new(T::zero(), T::zero()); scratch_len],
+        |scratch, row| fft.process_with_scratch(row, scratch),
+    );
+}
+
+// One plan shared by all threads; converts outside the timed region, warms up on a few rows
+fn transform_batch<T: FftNum + Float>(signals: &[f64]) -> (Duration, Vec<Complex<T>>) {
+    let mut planner = FftPlanner::<T>::new();
+    let fft = planner.plan_fft_forward(BATCH_LEN);
+
+    let mut buffer: Vec<Complex<T>> = signals
+        .par_iter()
+        .map(|&s| Complex::new(T::from_f64(s).unwrap(), T::zero()))
+        .collect();
+
     // Warm-up
-    let mut warmup = buffer.clone();
-    fft.process(&mut warmup);
-    
+    let mut warmup = buffer[..BATCH_WARMUP_ROWS * BATCH_LEN].to_vec();
+    process_rows(fft.as_ref(), &mut warmup);
+
     // Benchmark
     let start = Instant::now();
-    fft.process(&mut buffer);
-    let duration = start.elapsed();
-    
-    // Checksum
-    let checksum: f64 = buffer.iter().take(1000).map(|c| c.norm()).sum();
-    
-    println!("{:.6}", duration.as_secs_f64());
+    process_rows(fft.as_ref(), &mut buffer);
+    (start.elapsed(), buffer)
+}
+
+// Inverts every row (untimed) and returns max |x' - x| / max |x|
+fn batch_round_trip_error<T: FftNum + Float>(mut spectra: Vec<Complex<T>>, signals: &[f64]) -> f64 {
+    let mut planner = FftPlanner::<T>::new();
+    process_rows(planner.plan_fft_inverse(BATCH_LEN).as_ref(), &mut spectra);
+    let scale = 1.0 / BATCH_LEN as f64;
+    let max_error = spectra
+        .par_iter()
+        .zip(signals)
+        .map(|(&x, &s)| (widen(x) * scale - Complex::new(s, 0.0)).norm())
+        .reduce(|| 0.0, f64::max);
+    max_error / signals.par_iter().map(|s| s.abs()).reduce(|| 0.0, f64::max)
+}
+
+struct BatchRun {
+    duration: Duration,
+    spectra: Vec<Complex<f64>>, // widened to f64
+    round_trip_error: f64,
+    tolerance: f64, // 1000 ε of T
+}
+
+fn run_batched<T: FftNum + Float>(signals: &[f64]) -> BatchRun {
+    let (duration, spectra) = transform_batch::<T>(signals);
+    let wide = spectra.par_iter().map(|&x| widen(x)).collect();
+    BatchRun {
+        duration,
+        spectra: wide,
+        round_trip_error: batch_round_trip_error(spectra, signals),
+        tolerance: T::epsilon().to_f64().unwrap() * 1e3,
+    }
+}
+
+fn report_batched(precision: Precision, variant: Variant) {
+    let signals = batch_signals();
+    let result = match (variant, precision) {
+        (Variant::Default, Precision::F64) => run_batched::<f64>(&signals),
+        (Variant::Default, Precision::F32) => run_batched::<f32>(&signals),
+        (Variant::Vdsp, Precision::F64) => vdsp::run_batched::<f64>(&signals),
+        (Variant::Vdsp, Precision::F32) => vdsp::run_batched::<f32>(&signals),
+    };
+
+    // Normwise against the f64 batch (untimed), as in single mode
+    let relative_error = (precision == Precision::F32).then(|| {
+        let (_, reference) = transform_batch::<f64>(&signals);
+        let (max_error, max_reference) = result
+            .spectra
+            .par_iter()
+            .zip(&reference)
+            .map(|(x, x64)| ((x - x64).norm(), x64.norm()))
+            .reduce(|| (0.0, 0.0), |(e1, r1), (e2, r2)| (e1.max(e2), r1.max(r2)));
+        max_error / max_reference
+    });
+
+    // Checksum: every bin of every row
+    let checksum: f64 = result.spectra.iter().map(|c| c.norm()).sum();
+    let seconds = result.duration.as_secs_f64();
+    let flops = 5.0 * BATCH_LEN as f64 * (BATCH_LEN as f64).log2() * BATCH_COUNT as f64;
+
+    println!("{:.6}", seconds);
+    eprintln!("Variant: {}", variant_name(variant));
+    eprintln!("Precision: {}", if precision == Precision::F64 { "f64" } else { "f32" });
+    eprintln!("Batched: {} x {}-point transforms on {} threads, {:.0} transforms/s, {:.2} GFLOP/s (5 N log2 N)",
+              BATCH_COUNT, BATCH_LEN, rayon::current_num_threads(), BATCH_COUNT as f64 / seconds, flops / seconds / 1e9);
+    if let Some(error) = relative_error {
+        eprintln!("Relative error vs f64: {:.3e}", error);
+    }
+    eprintln!("Round-trip vs original signals: max error {:.3e} (tolerance {:.1e}: {})",
+              result.round_trip_error, result.tolerance, result.round_trip_error <= result.tolerance);
+    eprintln!("{}", bench_pages::report());
     eprintln!("Checksum: {}", checksum);
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(n: usize, cycles: usize) -> Vec<f64> {
+        (0..n).map(|i| (2.0 * std::f64::consts::PI * (cycles * i) as f64 / n as f64).sin()).collect()
+    }
+
+    // sin(2π f t) = (e^{2πi f t} - e^{-2πi f t}) / 2i, so bin f holds -i n/2, bin n - f holds i n/2, others 0
+    fn assert_pure_tone(spectrum: &[Complex<f64>], n: usize, cycles: usize, tolerance: f64) {
+        for (k, x) in spectrum.iter().enumerate() {
+            let expected = match k {
+                _ if k == cycles => Complex::new(0.0, -(n as f64) / 2.0),
+                _ if k == n - cycles => Complex::new(0.0, n as f64 / 2.0),
+                _ => Complex::new(0.0, 0.0),
+            };
+            assert!((x - expected).norm() < tolerance, "bin {}: {} instead of {}", k, x, expected);
+        }
+    }
+
+    #[test]
+    fn real_fft_of_a_pure_sine() {
+        let (n, cycles) = (1024, 50);
+        let signal = sine(n, cycles);
+        let real_fft = RealFft::<f64>::new(&mut FftPlanner::new(), n);
+        let mut work = vec![Complex::new(0.0, 0.0); n / 2];
+        let mut spectrum = vec![Complex::new(0.0, 0.0); n / 2 + 1];
+        real_fft.process_forward(&signal, &mut work, &mut spectrum);
+        assert_pure_tone(&spectrum, n, cycles, 1e-9);
+
+        let mut output = vec![0.0; n];
+        real_fft.process_inverse(&spectrum, &mut work, &mut output);
+        for (x, s) in output.iter().zip(&signal) {
+            assert!((x - s).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn real_fft_matches_complex_fft_in_f32() {
+        let n = 256;
+        let signal: Vec<f64> = (0..n).map(|i| ((i * 37 % 101) as f64 - 50.0) / 50.0).collect();
+        let mut complex: Vec<Complex<f32>> = signal.iter().map(|&s| Complex::new(s as f32, 0.0)).collect();
+        let mut planner = FftPlanner::<f32>::new();
+        planner.plan_fft_forward(n).process(&mut complex);
+
+        let real_fft = RealFft::new(&mut planner, n);
+        let input: Vec<f32> = signal.iter().map(|&s| s as f32).collect();
+        let mut work = vec![Complex::new(0.0, 0.0); n / 2];
+        let mut spectrum = vec![Complex::new(0.0, 0.0); n / 2 + 1];
+        real_fft.process_forward(&input, &mut work, &mut spectrum);
+        for (x, c) in spectrum.iter().zip(&complex) {
+            assert!((x - c).norm() < 1e-3, "{} instead of {}", x, c);
+        }
+    }
+
+    #[test]
+    fn batched_rows_transform_independently() {
+        let signals: Vec<f64> = sine(BATCH_LEN, 3).into_iter().chain(sine(BATCH_LEN, 200)).collect();
+        let mut buffer: Vec<Complex<f64>> = signals.iter().map(|&s| Complex::new(s, 0.0)).collect();
+        process_rows(FftPlanner::new().plan_fft_forward(BATCH_LEN).as_ref(), &mut buffer);
+        assert_pure_tone(&buffer[..BATCH_LEN], BATCH_LEN, 3, 1e-8);
+        assert_pure_tone(&buffer[BATCH_LEN..], BATCH_LEN, 200, 1e-8);
+        assert!(batch_round_trip_error(buffer, &signals) < 1e-12);
+    }
+}
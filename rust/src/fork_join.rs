@@ -0,0 +1,157 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const FIB_N: u32 = 36;
+// Below these the recursion runs sequentially: coarse to fine, so forks grow down the list
+const FIB_CUTOFFS: [u32; 5] = [30, 25, 20, 15, 10];
+const TREE_HEIGHT: u32 = 22; // 4M nodes
+const TREE_CUTOFFS: [u32; 5] = [18, 14, 10, 6, 2];
+const ROUNDS: usize = 3;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn fib(n: u32) -> u64 {
+    if n < 2 { n as u64 } else { fib(n - 1) + fib(n - 2) }
+}
+
+fn fib_parallel(n: u32, cutoff: u32) -> u64 {
+    if n < 2 || n <= cutoff {
+        return fib(n);
+    }
+    let (a, b) = rayon::join(|| fib_parallel(n - 1, cutoff), || fib_parallel(n - 2, cutoff));
+    a + b
+}
+
+// Calls above the cutoff, each of which forks once
+fn fib_forks(n: u32, cutoff: u32) -> u64 {
+    if n < 2 || n <= cutoff { 0 } else { 1 + fib_forks(n - 1, cutoff) + fib_forks(n - 2, cutoff) }
+}
+
+struct Node {
+    value: u64,
+    left: Option<Box<Node>>,
+    right: Option<Box<Node>>,
+}
+
+// A complete tree of the given height, values drawn and nodes allocated in pre-order, the order
+// the sums visit them
+fn build(height: u32, seed: &mut u64) -> Option<Box<Node>> {
+    if height == 0 {
+        return None;
+    }
+    let mut node = Box::new(Node { value: next_random(seed), left: None, right: None });
+    node.left = build(height - 1, seed);
+    node.right = build(height - 1, seed);
+    Some(node)
+}
+
+fn tree_sum(node: &Option<Box<Node>>) -> u64 {
+    match node {
+        None => 0,
+        Some(n) => n.value.wrapping_add(tree_sum(&n.left)).wrapping_add(tree_sum(&n.right)),
+    }
+}
+
+// height is that of the subtree under node, which forks while it stays above the cutoff
+fn tree_sum_parallel(node: &Option<Box<Node>>, height: u32, cutoff: u32) -> u64 {
+    match node {
+        Some(n) if height > cutoff => {
+            let (left, right) = rayon::join(|| tree_sum_parallel(&n.left, height - 1, cutoff),
+                                            || tree_sum_parallel(&n.right, height - 1, cutoff));
+            n.value.wrapping_add(left).wrapping_add(right)
+        }
+        _ => tree_sum(node),
+    }
+}
+
+fn tree_forks(height: u32, cutoff: u32) -> u64 {
+    if height <= cutoff { 0 } else { (1 << (height - cutoff)) - 1 }
+}
+
+// Total time over ROUNDS, checking every round returns the same value
+fn timed(f: impl Fn() -> u64) -> (Duration, u64) {
+    let start = Instant::now();
+    let value = black_box(f());
+    for _ in 1..ROUNDS {
+        assert_eq!(black_box(f()), value, "rounds disagree");
+    }
+    (start.elapsed(), value)
+}
+
+struct Row {
+    cutoff: u32,
+    forks: u64,
+    time: Duration,
+}
+
+fn report(title: &str, sequential: Duration, rows: &[Row]) {
+    eprintln!("{}: sequential {:.3}s over {} rounds", title, sequential.as_secs_f64(), ROUNDS);
+    for r in rows {
+        eprintln!("  cutoff {:>2}  {:>9} forks  rayon::join {:.3}s ({:.2}x)", r.cutoff, r.forks, r.time.as_secs_f64(),
+                  sequential.as_secs_f64() / r.time.as_secs_f64());
+    }
+}
+
+fn main() {
+    let mut seed = 42;
+    let tree = build(TREE_HEIGHT, &mut seed);
+
+    // Warm-up: start the pool's threads and touch the tree
+    fib_parallel(25, 10);
+    tree_sum_parallel(&tree, TREE_HEIGHT, TREE_HEIGHT - 4);
+
+    let (fib_sequential, fib_value) = timed(|| fib(black_box(FIB_N)));
+    let (tree_sequential, tree_value) = timed(|| tree_sum(&tree));
+    let mut fib_rows = Vec::new();
+    for cutoff in FIB_CUTOFFS {
+        let (time, value) = timed(|| fib_parallel(black_box(FIB_N), cutoff));
+        assert_eq!(value, fib_value, "fib with cutoff {} is wrong", cutoff);
+        fib_rows.push(Row { cutoff, forks: fib_forks(FIB_N, cutoff), time });
+    }
+    let mut tree_rows = Vec::new();
+    for cutoff in TREE_CUTOFFS {
+        let (time, value) = timed(|| tree_sum_parallel(&tree, TREE_HEIGHT, cutoff));
+        assert_eq!(value, tree_value, "tree sum with cutoff {} is wrong", cutoff);
+        tree_rows.push(Row { cutoff, forks: tree_forks(TREE_HEIGHT, cutoff), time });
+    }
+
+    let total: Duration = fib_rows.iter().chain(&tree_rows).map(|r| r.time).sum();
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("{} rayon threads", rayon::current_num_threads());
+    report(&format!("fib({}) = {}", FIB_N, fib_value), fib_sequential, &fib_rows);
+    report(&format!("Tree sum over {} nodes", (1u64 << TREE_HEIGHT) - 1), tree_sequential, &tree_rows);
+    eprintln!("Checksum: {}", fib_value.wrapping_add(tree_value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fib_agrees_at_every_cutoff() {
+        assert_eq!(fib(20), 6765);
+        for cutoff in [0, 1, 5, 20, 25] {
+            assert_eq!(fib_parallel(20, cutoff), 6765);
+        }
+    }
+
+    #[test]
+    fn fork_counts() {
+        assert_eq!(fib_forks(5, 3), 2); // 5 and its n - 1
+        assert_eq!(fib_forks(5, 5), 0);
+        assert_eq!(tree_forks(10, 7), 7);
+        assert_eq!(tree_forks(3, 4), 0);
+    }
+
+    #[test]
+    fn tree_sums_agree() {
+        let tree = build(10, &mut 7);
+        let expected = tree_sum(&tree);
+        for cutoff in [0, 3, 9, 10, 12] {
+            assert_eq!(tree_sum_parallel(&tree, 10, cutoff), expected);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use wgpu::util::DeviceExt;
+
+const MATRIX_SIZE: usize = 2048;  // 2048x2048 matrix
+const TOTAL_ELEMENTS: usize = MATRIX_SIZE * MATRIX_SIZE;
+const ITERATIONS: usize = 10;
+const WORKGROUP_SIZE: usize = 16;
+
+// The metal_compute kernel translated to WGSL, so it runs on Vulkan, DX12 and Metal alike
+const SHADER_SOURCE: &str = r#"
+    @group(0) @binding(0) var<storage, read> a: array<f32>;
+    @group(0) @binding(1) var<storage, read> b: array<f32>;
+    @group(0) @binding(2) var<storage, read_write> result: array<f32>;
+    @group(0) @binding(3) var<uniform> matrix_size: u32;
+
+    // Heavy compute shader: matrix element-wise operations with trigonometric functions
+    @compute @workgroup_size(16, 16)
+    fn heavy_compute(@builtin(global_invocation_id) gid: vec3<u32>) {
+        if (gid.x >= matrix_size || gid.y >= matrix_size) {
+            return;
+        }
+        let idx = gid.y * matrix_size + gid.x;
+
+        // Perform multiple heavy computations
+        let val_a = a[idx];
+        let val_b = b[idx];
+
+        var sum = 0.0;
+        for (var i = 0; i < 50; i++) {
+            let t = val_a * f32(i) * 0.01 + val_b;
+            sum += sin(t) * cos(val_b * f32(i) * 0.01) +
+                   tan(val_a * 0.1) * exp(-val_b * 0.001) +
+                   sqrt(abs(t)) * log(abs(t) + 1.0);
+        }
+
+        // Additional matrix-style computation
+        var accumulator = 0.0;
+        let row = gid.y;
+        for (var col = 0u; col < matrix_size; col++) {
+            let idx2 = row * matrix_size + col;
+            accumulator += a[idx2] * b[idx2] * 0.0001;
+        }
+
+        result[idx] = sum + accumulator;
+    }
+"#;
+
+// Timestamps the GPU writes at the start and end of a pass, for BENCH_MEASURE=gpu
+struct PassTimer {
+    queries: wgpu::QuerySet,
+    resolved: wgpu::Buffer,
+    readback: wgpu::Buffer,
+    tick_ns: f64,
+}
+
+impl PassTimer {
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> PassTimer {
+        let buffer = |usage| device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 2 * wgpu::QUERY_SIZE as u64,
+            usage,
+            mapped_at_creation: false,
+        });
+        PassTimer {
+            queries: device.create_query_set(&wgpu::QuerySetDescriptor { label: None, ty: wgpu::QueryType::Timestamp, count: 2 }),
+            resolved: buffer(wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC),
+            readback: buffer(wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST),
+            tick_ns: queue.get_timestamp_period() as f64,
+        }
+    }
+
+    fn seconds(&self, device: &wgpu::Device) -> f64 {
+        let slice = self.readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |status| status.expect("Failed to map timestamps"));
+        device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+        let ticks = {
+            let mapped = slice.get_mapped_range().expect("Failed to read timestamps");
+            let stamps: &[u64] = bytemuck::cast_slice(&mapped);
+            stamps[1].saturating_sub(stamps[0])
+        };
+        self.readback.unmap();
+        ticks as f64 * self.tick_ns / 1e9
+    }
+}
+
+// Runs one pass; with a timer, also returns the GPU time between its timestamps
+fn dispatch(device: &wgpu::Device, queue: &wgpu::Queue, pipeline: &wgpu::ComputePipeline, bind_group: &wgpu::BindGroup,
+            timer: Option<&PassTimer>) -> Option<f64> {
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: None,
+            timestamp_writes: timer.map(|t| wgpu::ComputePassTimestampWrites {
+                query_set: &t.queries,
+                beginning_of_pass_write_index: Some(0),
+                end_of_pass_write_index: Some(1),
+            }),
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        let groups = MATRIX_SIZE.div_ceil(WORKGROUP_SIZE) as u32;
+        pass.dispatch_workgroups(groups, groups, 1);
+    }
+    if let Some(t) = timer {
+        encoder.resolve_query_set(&t.queries, 0..2, &t.resolved, 0);
+        encoder.copy_buffer_to_buffer(&t.resolved, 0, &t.readback, 0, 2 * wgpu::QUERY_SIZE as u64);
+    }
+    queue.submit(Some(encoder.finish()));
+    device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+    timer.map(|t| t.seconds(device))
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env_with_gpu();
+    // Initialize wgpu with whichever backend the platform offers (WGPU_BACKEND overrides)
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        ..Default::default()
+    }))
+    .expect("No GPU adapter found");
+    let timestamps = measure.wants_gpu();
+    assert!(!timestamps || adapter.features().contains(wgpu::Features::TIMESTAMP_QUERY),
+            "BENCH_MEASURE=gpu, but the adapter has no timestamp queries");
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        required_features: if timestamps { wgpu::Features::TIMESTAMP_QUERY } else { wgpu::Features::empty() },
+        ..Default::default()
+    }))
+    .expect("Failed to create device");
+    let timer = timestamps.then(|| PassTimer::new(&device, &queue));
+
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(SHADER_SOURCE.into()),
+    });
+
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: None,
+        layout: None,
+        module: &shader,
+        entry_point: Some("heavy_compute"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+
+    // Create input data - large matrices
+    let input_a: Vec<f32> = (0..TOTAL_ELEMENTS).map(|i| (i as f32 * 0.001).sin()).collect();
+    let input_b: Vec<f32> = (0..TOTAL_ELEMENTS).map(|i| (i as f32 * 0.002).cos()).collect();
+    let result_bytes = (TOTAL_ELEMENTS * std::mem::size_of::<f32>()) as u64;
+
+    let buffer_a = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(&input_a),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+
+    let buffer_b = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(&input_b),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+
+    let buffer_result = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: result_bytes,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+
+    let buffer_size = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::bytes_of(&(MATRIX_SIZE as u32)),
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+
+    // Unlike Metal's shared storage mode, results come back through a mappable staging buffer
+    let buffer_readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: result_bytes,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: buffer_a.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: buffer_b.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 2, resource: buffer_result.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 3, resource: buffer_size.as_entire_binding() },
+        ],
+    });
+
+    // Warm-up
+    dispatch(&device, &queue, &pipeline, &bind_group, None);
+
+    // Benchmark - run multiple iterations
+    measure.start();
+
+    for _ in 0..ITERATIONS {
+        if let Some(seconds) = dispatch(&device, &queue, &pipeline, &bind_group, timer.as_ref()) {
+            measure.record_gpu(seconds);
+        }
+    }
+
+    measure.stop();
+
+    // Get result and checksum
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(&buffer_result, 0, &buffer_readback, 0, result_bytes);
+    queue.submit(Some(encoder.finish()));
+    let slice = buffer_readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |status| status.expect("Failed to map readback buffer"));
+    device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+    let checksum: f32 = {
+        let mapped = slice.get_mapped_range().expect("Failed to read mapped buffer");
+        let result_slice: &[f32] = bytemuck::cast_slice(&mapped);
+        result_slice.iter().step_by(1000).sum()
+    };
+
+    let info = adapter.get_info();
+    measure.report();
+    eprintln!("Adapter: {} ({:?})", info.name, info.backend);
+    eprintln!("Checksum: {}", checksum);
+}
@@ -0,0 +1,306 @@
+use std::num::NonZeroU64;
+use std::time::{Duration, Instant};
+
+const ELEMENTS: usize = 1 << 23;     // 8M u32, 32 MiB
+const WORKGROUP_SIZE: usize = 256;
+const ROUNDS: u32 = 64;              // mixing rounds per element, matched by the shader
+// The same work cut into this many dispatches: one launch of 32768 workgroups down to 32768 launches of one
+const SPLITS: [usize; 5] = [1, 16, 256, 4096, 32768];
+const ROUND_TRIPS: usize = 20;       // single-chunk submit-and-wait launches per split
+
+// Each thread mixes one element in place. The binding covers one chunk, placed by a dynamic offset
+const SHADER_SOURCE: &str = r#"
+    @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+    const ROUNDS: u32 = 64u;
+
+    @compute @workgroup_size(256)
+    fn mix(@builtin(global_invocation_id) id: vec3<u32>) {
+        if (id.x >= arrayLength(&data)) {
+            return;
+        }
+        var x = data[id.x];
+        for (var i = 0u; i < ROUNDS; i++) {
+            x = x * 1664525u + 1013904223u;
+            x ^= x >> 16u;
+        }
+        data[id.x] = x;
+    }
+"#;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// CPU reference for one element
+fn mix(mut x: u32) -> u32 {
+    for _ in 0..ROUNDS {
+        x = x.wrapping_mul(1664525).wrapping_add(1013904223);
+        x ^= x >> 16;
+    }
+    x
+}
+
+struct Row {
+    dispatches: usize,
+    one_pass: Duration,   // every dispatch recorded into one compute pass and submitted once
+    per_submit: Duration, // one command buffer and submit per dispatch, waited on at the end
+    round_trip: Duration, // a single chunk submitted and waited on, averaged over ROUND_TRIPS
+    cpu: Duration,        // one CPU thread doing a chunk's worth of the same mixing
+}
+
+impl Row {
+    fn chunk(&self) -> usize {
+        ELEMENTS / self.dispatches
+    }
+}
+
+// The smallest chunk whose round trip to the GPU beats doing it on one CPU thread
+fn break_even(rows: &[Row]) -> Option<usize> {
+    rows.iter().filter(|r| r.round_trip < r.cpu).map(Row::chunk).min()
+}
+
+struct Gpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    adapter_info: wgpu::AdapterInfo,
+    pipeline: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+    data: wgpu::Buffer,
+    readback: wgpu::Buffer,
+}
+
+impl Gpu {
+    fn new() -> Self {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .expect("No GPU adapter found");
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+            .expect("Failed to create device");
+
+        // An explicit layout, since derived layouts have no dynamic offsets
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: true,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[Some(&layout)],
+            immediate_size: 0,
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER_SOURCE.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("mix"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let bytes = (ELEMENTS * 4) as u64;
+        let data = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: bytes,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: bytes,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Gpu { device, queue, adapter_info: adapter.get_info(), pipeline, layout, data, readback }
+    }
+
+    fn wait(&self) {
+        self.device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+    }
+
+    // A bind group whose binding spans one chunk of the data buffer
+    fn bind_group(&self, chunk: usize) -> wgpu::BindGroup {
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &self.data,
+                    offset: 0,
+                    size: NonZeroU64::new((chunk * 4) as u64),
+                }),
+            }],
+        })
+    }
+
+    // Records dispatches for the given chunks, each at its own offset
+    fn encode(&self, bind_group: &wgpu::BindGroup, chunk: usize, chunks: std::ops::Range<usize>) -> wgpu::CommandBuffer {
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            for i in chunks {
+                pass.set_bind_group(0, bind_group, &[(i * chunk * 4) as u32]);
+                pass.dispatch_workgroups((chunk / WORKGROUP_SIZE) as u32, 1, 1);
+            }
+        }
+        encoder.finish()
+    }
+
+    fn upload(&self, input: &[u32]) {
+        // Queued writes land with the next submission, so flush them here rather than in a timed one
+        self.queue.write_buffer(&self.data, 0, bytemuck::cast_slice(input));
+        self.queue.submit([]);
+        self.wait();
+    }
+
+    fn download(&self) -> Vec<u32> {
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(&self.data, 0, &self.readback, 0, (ELEMENTS * 4) as u64);
+        self.queue.submit(Some(encoder.finish()));
+        self.readback.slice(..).map_async(wgpu::MapMode::Read, |status| status.expect("Failed to map buffer"));
+        self.wait();
+        let out = bytemuck::cast_slice(&self.readback.slice(..).get_mapped_range().expect("Failed to read mapped buffer"))
+            .to_vec();
+        self.readback.unmap();
+        out
+    }
+
+    // Times the GPU ways of running the work in this many dispatches, checking the full runs
+    fn measure(&self, dispatches: usize, input: &[u32], expected: &[u32]) -> (Duration, Duration, Duration) {
+        let chunk = ELEMENTS / dispatches;
+        let bind_group = self.bind_group(chunk);
+        let check = |how: &str| {
+            if self.download() != expected {
+                eprintln!("{} dispatches {}: GPU result differs from the CPU", dispatches, how);
+                std::process::exit(1);
+            }
+        };
+
+        self.upload(input);
+        let start = Instant::now();
+        self.queue.submit(Some(self.encode(&bind_group, chunk, 0..dispatches)));
+        self.wait();
+        let one_pass = start.elapsed();
+        check("in one pass");
+
+        self.upload(input);
+        let start = Instant::now();
+        for i in 0..dispatches {
+            self.queue.submit(Some(self.encode(&bind_group, chunk, i..i + 1)));
+        }
+        self.wait();
+        let per_submit = start.elapsed();
+        check("submitted one by one");
+
+        let start = Instant::now();
+        for _ in 0..ROUND_TRIPS {
+            self.queue.submit(Some(self.encode(&bind_group, chunk, 0..1)));
+            self.wait();
+        }
+        (one_pass, per_submit, start.elapsed() / ROUND_TRIPS as u32)
+    }
+}
+
+fn main() {
+    let gpu = Gpu::new();
+    let mut seed = 42;
+    let input: Vec<u32> = (0..ELEMENTS).map(|_| next_random(&mut seed) as u32).collect();
+
+    let start = Instant::now();
+    let expected: Vec<u32> = input.iter().map(|&x| mix(x)).collect();
+    let cpu = start.elapsed();
+
+    // Warm-up: compile the pipeline and touch the buffers
+    gpu.measure(SPLITS[1], &input, &expected);
+
+    let rows: Vec<Row> = SPLITS
+        .iter()
+        .map(|&dispatches| {
+            let (one_pass, per_submit, round_trip) = gpu.measure(dispatches, &input, &expected);
+            Row { dispatches, one_pass, per_submit, round_trip, cpu: cpu / dispatches as u32 }
+        })
+        .collect();
+
+    let total: Duration = rows.iter().map(|r| r.one_pass + r.per_submit + r.round_trip * ROUND_TRIPS as u32).sum();
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("Adapter: {} ({:?})", gpu.adapter_info.name, gpu.adapter_info.backend);
+    eprintln!("{} elements x {} rounds, workgroups of {}; CPU (1 thread) {:.3}s", ELEMENTS, ROUNDS, WORKGROUP_SIZE,
+              cpu.as_secs_f64());
+    eprintln!("{:>10} {:>9} {:>6}  {:>10} {:>9} {:>10}  {:>10} {:>9}  {:>11} {:>11}", "Dispatches", "Elements",
+              "Groups", "One pass", "Occupancy", "Overhead", "Per submit", "Overhead", "Round trip", "CPU chunk");
+    let base = rows[0].one_pass.as_secs_f64();
+    for r in &rows {
+        // Extra time over the single dispatch, shared out over the extra dispatches
+        let overhead = |d: Duration| match r.dispatches {
+            1 => "-".to_string(),
+            n => format!("{:.2} us", (d.as_secs_f64() - base) * 1e6 / (n - 1) as f64),
+        };
+        eprintln!("{:>10} {:>9} {:>6}  {:>8.3}ms {:>8.1}% {:>10}  {:>8.3}ms {:>9}  {:>8.1} us {:>8.1} us", r.dispatches,
+                  r.chunk(), r.chunk() / WORKGROUP_SIZE, r.one_pass.as_secs_f64() * 1e3,
+                  base / r.one_pass.as_secs_f64() * 100.0, overhead(r.one_pass), r.per_submit.as_secs_f64() * 1e3,
+                  overhead(r.per_submit), r.round_trip.as_secs_f64() * 1e6, r.cpu.as_secs_f64() * 1e6);
+    }
+    match break_even(&rows) {
+        Some(chunk) => eprintln!("A GPU round trip beats one CPU thread from {} elements", chunk),
+        None => eprintln!("A GPU round trip never beats one CPU thread at these sizes"),
+    }
+    eprintln!("Checksum: {}", expected.iter().fold(0u64, |acc, &x| acc.wrapping_add(x as u64)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_tile_the_buffer() {
+        for dispatches in SPLITS {
+            let chunk = ELEMENTS / dispatches;
+            assert_eq!(chunk * dispatches, ELEMENTS);
+            assert_eq!(chunk % WORKGROUP_SIZE, 0);
+            assert_eq!(chunk * 4 % 256, 0, "dynamic offsets must be 256-byte aligned");
+            assert!(chunk / WORKGROUP_SIZE <= 65535, "over the workgroups-per-dimension limit");
+        }
+    }
+
+    #[test]
+    fn mix_matches_the_shader_constants() {
+        assert!(SHADER_SOURCE.contains(&format!("ROUNDS: u32 = {}u", ROUNDS)));
+        assert!(SHADER_SOURCE.contains(&format!("workgroup_size({})", WORKGROUP_SIZE)));
+        assert_eq!(mix(0), 3548675726);
+        assert_eq!(mix(1), 3139396726);
+    }
+
+    #[test]
+    fn break_even_picks_the_smallest_winning_chunk() {
+        let row = |dispatches, round_trip, cpu| Row {
+            dispatches,
+            one_pass: Duration::ZERO,
+            per_submit: Duration::ZERO,
+            round_trip: Duration::from_micros(round_trip),
+            cpu: Duration::from_micros(cpu),
+        };
+        let rows = [row(1, 900, 90000), row(16, 80, 5600), row(256, 40, 350), row(4096, 30, 22)];
+        assert_eq!(break_even(&rows), Some(ELEMENTS / 256));
+        assert_eq!(break_even(&rows[3..]), None);
+    }
+}
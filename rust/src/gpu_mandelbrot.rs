@@ -0,0 +1,363 @@
+use rayon::prelude::*;
+use std::time::Instant;
+use wgpu::util::DeviceExt;
+
+const WIDTH: usize = 4096;  // the CPU mandelbrot's default image and region
+const HEIGHT: usize = 4096;
+const MAX_ITER: u32 = 1000;
+const ITERATIONS: usize = 5;
+const WORKGROUP_SIZE: usize = 16;
+const FRACTION_BITS: u32 = 27; // Q4.27: z stays within 8 of the origin until it escapes
+
+// GPU compilers may fuse and reorder float math (wgpu's Metal shaders compile with fast math), so
+// an f32 kernel could disagree with the CPU on pixels near the boundary. Both sides iterate in
+// Q4.27 fixed point instead, where every step is exact and the counts match pixel for pixel.
+// WGSL has no 64-bit integers, so the shader builds each product from 16-bit halves
+const SHADER_SOURCE: &str = r#"
+    struct View {
+        min_re: i32,
+        min_im: i32,
+        step_re: i32,
+        step_im: i32,
+        width: u32,
+        height: u32,
+        max_iter: u32,
+    }
+
+    @group(0) @binding(0) var<uniform> view: View;
+    @group(0) @binding(1) var<storage, read_write> counts: array<u32>;
+
+    const FRACTION_BITS: u32 = 27u;
+    const TWO: i32 = 1 << 28;
+    const FOUR: i32 = 1 << 29;
+
+    // (a * b) >> 27, rounded toward zero like the CPU's 64-bit division
+    fn mul_fixed(a: i32, b: i32) -> i32 {
+        let ua = u32(abs(a));
+        let ub = u32(abs(b));
+        let al = ua & 0xffffu;
+        let ah = ua >> 16u;
+        let bl = ub & 0xffffu;
+        let bh = ub >> 16u;
+        let mid = ah * bl + al * bh;
+        let low = al * bl;
+        let lo = low + (mid << 16u);
+        let hi = ah * bh + (mid >> 16u) + select(0u, 1u, lo < low);
+        let magnitude = i32((hi << (32u - FRACTION_BITS)) | (lo >> FRACTION_BITS));
+        return select(magnitude, -magnitude, (a < 0) != (b < 0));
+    }
+
+    @compute @workgroup_size(16, 16)
+    fn mandelbrot(@builtin(global_invocation_id) gid: vec3<u32>) {
+        if (gid.x >= view.width || gid.y >= view.height) {
+            return;
+        }
+        let cx = view.min_re + i32(gid.x) * view.step_re;
+        let cy = view.min_im + i32(gid.y) * view.step_im;
+        var x = 0;
+        var y = 0;
+        var iteration = 0u;
+        // A part beyond 2 has escaped already; checking it first keeps the squares in range
+        while (iteration < view.max_iter && abs(x) <= TWO && abs(y) <= TWO) {
+            let xx = mul_fixed(x, x);
+            let yy = mul_fixed(y, y);
+            if (xx + yy > FOUR) {
+                break;
+            }
+            let xy = mul_fixed(x, y);
+            x = xx - yy + cx;
+            y = 2 * xy + cy;
+            iteration++;
+        }
+        counts[gid.y * view.width + gid.x] = iteration;
+    }
+"#;
+
+// The image and the rectangle it samples, in fixed point; as the shader's uniform it is 8 words
+struct View {
+    width: usize,
+    height: usize,
+    min_re: i32,
+    min_im: i32,
+    step_re: i32,
+    step_im: i32,
+}
+
+impl View {
+    // mandelbrot's default -2.5..1.0 x -1.0..1.0 region, whose pixel steps (3.5 / 4096 and
+    // 2 / 4096) are whole numbers in Q4.27, so every pixel samples exactly the same point
+    fn default_region(width: usize, height: usize) -> View {
+        let fixed = |x: f64| (x * (1u64 << FRACTION_BITS) as f64) as i32;
+        View {
+            width,
+            height,
+            min_re: fixed(-2.5),
+            min_im: fixed(-1.0),
+            step_re: fixed(3.5 / width as f64),
+            step_im: fixed(2.0 / height as f64),
+        }
+    }
+
+    fn uniform(&self) -> [u32; 8] {
+        [self.min_re as u32, self.min_im as u32, self.step_re as u32, self.step_im as u32,
+         self.width as u32, self.height as u32, MAX_ITER, 0]
+    }
+}
+
+fn mul_fixed(a: i32, b: i32) -> i32 {
+    ((a as i64 * b as i64) / (1i64 << FRACTION_BITS)) as i32
+}
+
+// The shader's loop, step for step
+fn mandelbrot_point(cx: i32, cy: i32) -> u32 {
+    const TWO: i32 = 1 << 28;
+    const FOUR: i32 = 1 << 29;
+    let (mut x, mut y) = (0i32, 0i32);
+    let mut iteration = 0;
+    while iteration < MAX_ITER && x.abs() <= TWO && y.abs() <= TWO {
+        let (xx, yy) = (mul_fixed(x, x), mul_fixed(y, y));
+        if xx + yy > FOUR {
+            break;
+        }
+        let xy = mul_fixed(x, y);
+        x = xx - yy + cx;
+        y = 2 * xy + cy;
+        iteration += 1;
+    }
+    iteration
+}
+
+// The same kernel on every CPU core, both as the cross-check and as the CPU side of the comparison
+fn cpu_mandelbrot(view: &View) -> Vec<u32> {
+    (0..view.height).into_par_iter().flat_map_iter(|y| {
+        let cy = view.min_im + y as i32 * view.step_im;
+        (0..view.width).map(move |x| mandelbrot_point(view.min_re + x as i32 * view.step_re, cy))
+    }).collect()
+}
+
+// Timestamps the GPU writes at the start and end of a pass, for BENCH_MEASURE=gpu
+struct PassTimer {
+    queries: wgpu::QuerySet,
+    resolved: wgpu::Buffer,
+    readback: wgpu::Buffer,
+    tick_ns: f64,
+}
+
+impl PassTimer {
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> PassTimer {
+        let buffer = |usage| device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 2 * wgpu::QUERY_SIZE as u64,
+            usage,
+            mapped_at_creation: false,
+        });
+        PassTimer {
+            queries: device.create_query_set(&wgpu::QuerySetDescriptor { label: None, ty: wgpu::QueryType::Timestamp, count: 2 }),
+            resolved: buffer(wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC),
+            readback: buffer(wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST),
+            tick_ns: queue.get_timestamp_period() as f64,
+        }
+    }
+
+    fn seconds(&self, device: &wgpu::Device) -> f64 {
+        let slice = self.readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |status| status.expect("Failed to map timestamps"));
+        device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+        let ticks = {
+            let mapped = slice.get_mapped_range().expect("Failed to read timestamps");
+            let stamps: &[u64] = bytemuck::cast_slice(&mapped);
+            stamps[1].saturating_sub(stamps[0])
+        };
+        self.readback.unmap();
+        ticks as f64 * self.tick_ns / 1e9
+    }
+}
+
+// Runs one pass over the image; with a timer, also returns the GPU time between its timestamps
+fn dispatch(device: &wgpu::Device, queue: &wgpu::Queue, pipeline: &wgpu::ComputePipeline, bind_group: &wgpu::BindGroup,
+            view: &View, timer: Option<&PassTimer>) -> Option<f64> {
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: None,
+            timestamp_writes: timer.map(|t| wgpu::ComputePassTimestampWrites {
+                query_set: &t.queries,
+                beginning_of_pass_write_index: Some(0),
+                end_of_pass_write_index: Some(1),
+            }),
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.dispatch_workgroups(view.width.div_ceil(WORKGROUP_SIZE) as u32, view.height.div_ceil(WORKGROUP_SIZE) as u32, 1);
+    }
+    if let Some(t) = timer {
+        encoder.resolve_query_set(&t.queries, 0..2, &t.resolved, 0);
+        encoder.copy_buffer_to_buffer(&t.resolved, 0, &t.readback, 0, 2 * wgpu::QUERY_SIZE as u64);
+    }
+    queue.submit(Some(encoder.finish()));
+    device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+    timer.map(|t| t.seconds(device))
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env_with_gpu();
+    let view = View::default_region(WIDTH, HEIGHT);
+
+    // Initialize wgpu with whichever backend the platform offers (WGPU_BACKEND overrides)
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        ..Default::default()
+    }))
+    .expect("No GPU adapter found");
+    let timestamps = measure.wants_gpu();
+    assert!(!timestamps || adapter.features().contains(wgpu::Features::TIMESTAMP_QUERY),
+            "BENCH_MEASURE=gpu, but the adapter has no timestamp queries");
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        required_features: if timestamps { wgpu::Features::TIMESTAMP_QUERY } else { wgpu::Features::empty() },
+        ..Default::default()
+    }))
+    .expect("Failed to create device");
+    let timer = timestamps.then(|| PassTimer::new(&device, &queue));
+
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(SHADER_SOURCE.into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: None,
+        layout: None,
+        module: &shader,
+        entry_point: Some("mandelbrot"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+
+    let count_bytes = (view.width * view.height * std::mem::size_of::<u32>()) as u64;
+    let buffer_view = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(&view.uniform()),
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+    let buffer_counts = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: count_bytes,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let buffer_readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: count_bytes,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: buffer_view.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: buffer_counts.as_entire_binding() },
+        ],
+    });
+
+    // Warm-up
+    dispatch(&device, &queue, &pipeline, &bind_group, &view, None);
+
+    // Benchmark
+    let start = Instant::now();
+    measure.start();
+    for _ in 0..ITERATIONS {
+        if let Some(seconds) = dispatch(&device, &queue, &pipeline, &bind_group, &view, timer.as_ref()) {
+            measure.record_gpu(seconds);
+        }
+    }
+    measure.stop();
+    let gpu_seconds = start.elapsed().as_secs_f64() / ITERATIONS as f64;
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(&buffer_counts, 0, &buffer_readback, 0, count_bytes);
+    queue.submit(Some(encoder.finish()));
+    let slice = buffer_readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |status| status.expect("Failed to map readback buffer"));
+    device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+    let gpu_counts: Vec<u32> = bytemuck::cast_slice(&slice.get_mapped_range().expect("Failed to read mapped buffer")).to_vec();
+
+    // The CPU renders the same image once, timed for the comparison
+    let start = Instant::now();
+    let cpu_counts = cpu_mandelbrot(&view);
+    let cpu_seconds = start.elapsed().as_secs_f64();
+    let mismatches = gpu_counts.iter().zip(&cpu_counts).filter(|(g, c)| g != c).count();
+    assert_eq!(mismatches, 0, "{} of {} GPU iteration counts differ from the CPU's", mismatches, cpu_counts.len());
+
+    let checksum: u64 = gpu_counts.iter().map(|&c| c as u64).sum();
+    let info = adapter.get_info();
+    measure.report();
+    eprintln!("Adapter: {} ({:?})", info.name, info.backend);
+    eprintln!("Region: {}x{}, re -2.5 .. 1.0, im -1.0 .. 1.0, Q4.27 fixed point, {} iterations max",
+              view.width, view.height, MAX_ITER);
+    eprintln!("GPU: {:.6}s per image; CPU ({} threads): {:.6}s; GPU speedup {:.1}x",
+              gpu_seconds, rayon::current_num_threads(), cpu_seconds, cpu_seconds / gpu_seconds);
+    eprintln!("Cross-check: all {} iteration counts match the CPU", cpu_counts.len());
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The shader's mul_fixed, transcribed, against the 64-bit product
+    fn mul_fixed_halves(a: i32, b: i32) -> i32 {
+        let (ua, ub) = (a.unsigned_abs(), b.unsigned_abs());
+        let (al, ah, bl, bh) = (ua & 0xffff, ua >> 16, ub & 0xffff, ub >> 16);
+        let mid = ah * bl + al * bh;
+        let low = al * bl;
+        let lo = low.wrapping_add(mid << 16);
+        let hi = ah * bh + (mid >> 16) + (lo < low) as u32;
+        let magnitude = ((hi << (32 - FRACTION_BITS)) | (lo >> FRACTION_BITS)) as i32;
+        if (a < 0) != (b < 0) { -magnitude } else { magnitude }
+    }
+
+    #[test]
+    fn shader_products_match_the_cpu() {
+        // Every operand the loop multiplies lies within 2 of zero
+        let mut seed = 0x2545_F491_4F6C_DD1Du64;
+        for _ in 0..100_000 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let a = (seed as i32) >> 3;
+            let b = ((seed >> 32) as i32) >> 3;
+            assert_eq!(mul_fixed_halves(a, b), mul_fixed(a, b), "{} * {}", a, b);
+        }
+        let two = 1 << 28;
+        for (a, b) in [(two, two), (-two, two), (-two, -two), (1, -1), (0, two), (-3, 1 << FRACTION_BITS)] {
+            assert_eq!(mul_fixed_halves(a, b), mul_fixed(a, b), "{} * {}", a, b);
+        }
+    }
+
+    #[test]
+    fn samples_the_cpu_benchmarks_points() {
+        let view = View::default_region(WIDTH, HEIGHT);
+        let scale = (1u64 << FRACTION_BITS) as f64;
+        for x in [0, 1, 1234, WIDTH - 1] {
+            let re = (view.min_re + x as i32 * view.step_re) as f64 / scale;
+            assert_eq!(re, -2.5 + (x as f64 / WIDTH as f64) * 3.5);
+        }
+        for y in [0, 777, HEIGHT - 1] {
+            let im = (view.min_im + y as i32 * view.step_im) as f64 / scale;
+            assert_eq!(im, -1.0 + (y as f64 / HEIGHT as f64) * 2.0);
+        }
+    }
+
+    #[test]
+    fn counts_escape_like_the_float_kernel() {
+        let one = 1 << FRACTION_BITS;
+        assert_eq!(mandelbrot_point(0, 0), MAX_ITER);
+        assert_eq!(mandelbrot_point(-one, 0), MAX_ITER); // the period-2 bulb
+        assert_eq!(mandelbrot_point(one, one), 2); // 1+i, then 1+3i is outside
+        assert_eq!(mandelbrot_point(2 * one + 1, 0), 1);
+        let counts = cpu_mandelbrot(&View::default_region(64, 48));
+        assert_eq!(counts.len(), 64 * 48);
+        assert!(counts.contains(&MAX_ITER) && counts.contains(&1));
+    }
+}
@@ -0,0 +1,250 @@
+use std::time::{Duration, Instant};
+use wgpu::util::DeviceExt;
+
+const DEFAULT_SIZE: usize = 4096;  // 4096x4096 matrices
+const CHECK_SIZE: usize = 256;     // cross-checked against the CPU
+const ITERATIONS: usize = 5;
+const TILE: usize = 64;            // output block computed by one workgroup
+const TOLERANCE: f32 = 1e-3;
+
+// Tiled SGEMM: each 16x16 workgroup stages 64x16 slices of A and 16x64 slices of B in
+// workgroup memory and every thread accumulates a 4x4 block of C in registers
+const SHADER_SOURCE: &str = r#"
+    @group(0) @binding(0) var<storage, read> a: array<f32>;
+    @group(0) @binding(1) var<storage, read> b: array<f32>;
+    @group(0) @binding(2) var<storage, read_write> c: array<f32>;
+    @group(0) @binding(3) var<uniform> n: u32;
+
+    const TILE: u32 = 64u;
+    const DEPTH: u32 = 16u;
+    const THREADS: u32 = 256u;
+
+    var<workgroup> tile_a: array<f32, 1024>; // TILE rows x DEPTH
+    var<workgroup> tile_b: array<f32, 1024>; // DEPTH rows x TILE
+
+    @compute @workgroup_size(16, 16)
+    fn matmul(@builtin(workgroup_id) wg: vec3<u32>, @builtin(local_invocation_id) lid: vec3<u32>) {
+        let row0 = wg.y * TILE;
+        let col0 = wg.x * TILE;
+        let local = lid.y * 16u + lid.x;
+        var acc: array<f32, 16>;
+
+        for (var k0 = 0u; k0 < n; k0 += DEPTH) {
+            for (var i = 0u; i < 4u; i++) {
+                let e = local + i * THREADS;
+                tile_a[e] = a[(row0 + e / DEPTH) * n + k0 + e % DEPTH];
+                tile_b[e] = b[(k0 + e / TILE) * n + col0 + e % TILE];
+            }
+            workgroupBarrier();
+
+            for (var k = 0u; k < DEPTH; k++) {
+                var bv: array<f32, 4>;
+                for (var j = 0u; j < 4u; j++) {
+                    bv[j] = tile_b[k * TILE + lid.x + j * 16u];
+                }
+                for (var i = 0u; i < 4u; i++) {
+                    let av = tile_a[(lid.y + i * 16u) * DEPTH + k];
+                    for (var j = 0u; j < 4u; j++) {
+                        acc[i * 4u + j] += av * bv[j];
+                    }
+                }
+            }
+            workgroupBarrier();
+        }
+
+        for (var i = 0u; i < 4u; i++) {
+            for (var j = 0u; j < 4u; j++) {
+                c[(row0 + lid.y + i * 16u) * n + col0 + lid.x + j * 16u] = acc[i * 4u + j];
+            }
+        }
+    }
+"#;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn random_matrix(n: usize, seed: u64) -> Vec<f32> {
+    let mut seed = seed;
+    (0..n * n).map(|_| (next_random(&mut seed) % 2001) as f32 / 1000.0 - 1.0).collect()
+}
+
+// Reference i-k-j product with f64 accumulation
+fn cpu_matmul(a: &[f32], b: &[f32], n: usize) -> Vec<f32> {
+    let mut c = vec![0.0f64; n * n];
+    for i in 0..n {
+        for k in 0..n {
+            let aik = a[i * n + k] as f64;
+            for j in 0..n {
+                c[i * n + j] += aik * b[k * n + j] as f64;
+            }
+        }
+    }
+    c.into_iter().map(|x| x as f32).collect()
+}
+
+struct Gpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    adapter_info: wgpu::AdapterInfo,
+}
+
+impl Gpu {
+    fn new() -> Self {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .expect("No GPU adapter found");
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+            .expect("Failed to create device");
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER_SOURCE.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: None,
+            module: &shader,
+            entry_point: Some("matmul"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        Gpu { device, queue, pipeline, adapter_info: adapter.get_info() }
+    }
+
+    // Uploads A and B, runs the kernel `iterations` times and reads C back; only the dispatches are timed
+    fn multiply(&self, a: &[f32], b: &[f32], n: usize, iterations: usize) -> (Duration, Vec<f32>) {
+        let bytes = (n * n * std::mem::size_of::<f32>()) as u64;
+        let storage = |data: &[f32]| {
+            self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(data),
+                usage: wgpu::BufferUsages::STORAGE,
+            })
+        };
+        let buffer_a = storage(a);
+        let buffer_b = storage(b);
+        let buffer_c = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: bytes,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let buffer_n = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::bytes_of(&(n as u32)),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let buffer_readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: bytes,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: buffer_a.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: buffer_b.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: buffer_c.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: buffer_n.as_entire_binding() },
+            ],
+        });
+        let groups = (n / TILE) as u32;
+
+        let start = Instant::now();
+        for _ in 0..iterations {
+            let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                pass.set_pipeline(&self.pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(groups, groups, 1);
+            }
+            self.queue.submit(Some(encoder.finish()));
+            self.device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+        }
+        let duration = start.elapsed();
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(&buffer_c, 0, &buffer_readback, 0, bytes);
+        self.queue.submit(Some(encoder.finish()));
+        let slice = buffer_readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |status| status.expect("Failed to map readback buffer"));
+        self.device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+        let c = bytemuck::cast_slice(&slice.get_mapped_range().expect("Failed to read mapped buffer")).to_vec();
+        (duration, c)
+    }
+}
+
+fn parse_size() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--size") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|n: &usize| *n > 0 && n.is_multiple_of(TILE))
+            .expect("--size expects a positive multiple of 64"),
+        None => DEFAULT_SIZE,
+    }
+}
+
+fn main() {
+    let n = parse_size();
+    let gpu = Gpu::new();
+
+    // Cross-check the kernel against the CPU at a small size (doubles as warm-up)
+    let a = random_matrix(CHECK_SIZE, 1);
+    let b = random_matrix(CHECK_SIZE, 2);
+    let (_, gpu_c) = gpu.multiply(&a, &b, CHECK_SIZE, 1);
+    let cpu_c = cpu_matmul(&a, &b, CHECK_SIZE);
+    let max_error = gpu_c.iter().zip(&cpu_c).map(|(g, c)| (g - c).abs()).fold(0.0f32, f32::max);
+    assert!(max_error < TOLERANCE, "GPU result differs from CPU reference by {}", max_error);
+
+    // Benchmark
+    let a = random_matrix(n, 3);
+    let b = random_matrix(n, 4);
+    let (duration, c) = gpu.multiply(&a, &b, n, ITERATIONS);
+
+    let gflops = 2.0 * (n as f64).powi(3) * ITERATIONS as f64 / duration.as_secs_f64() / 1e9;
+    let checksum: f64 = c.iter().step_by(1000).map(|&x| x as f64).sum();
+
+    println!("{:.6}", duration.as_secs_f64());
+    eprintln!("Adapter: {} ({:?})", gpu.adapter_info.name, gpu.adapter_info.backend);
+    eprintln!("{}x{} SGEMM x{}: {:.1} GFLOP/s", n, n, ITERATIONS, gflops);
+    eprintln!("Cross-check ({}x{}): max abs error {:.2e}", CHECK_SIZE, CHECK_SIZE, max_error);
+    eprintln!("Checksum: {:.6}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_reference_multiplies() {
+        let a = [1.0, 2.0, 3.0, 4.0];
+        let b = [5.0, 6.0, 7.0, 8.0];
+        assert_eq!(cpu_matmul(&a, &b, 2), [19.0, 22.0, 43.0, 50.0]);
+
+        // The identity leaves a random matrix unchanged
+        let n = 17;
+        let m = random_matrix(n, 42);
+        let identity: Vec<f32> = (0..n * n).map(|i| if i / n == i % n { 1.0 } else { 0.0 }).collect();
+        assert_eq!(cpu_matmul(&m, &identity, n), m);
+        assert_eq!(cpu_matmul(&identity, &m, n), m);
+    }
+
+    #[test]
+    fn random_matrices_are_reproducible_and_bounded() {
+        let m = random_matrix(CHECK_SIZE, 1);
+        assert_eq!(m, random_matrix(CHECK_SIZE, 1));
+        assert_ne!(m, random_matrix(CHECK_SIZE, 2));
+        assert!(m.iter().all(|x| (-1.0..=1.0).contains(x)));
+    }
+}
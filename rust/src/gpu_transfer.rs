@@ -0,0 +1,242 @@
+use std::time::{Duration, Instant};
+
+const SIZES: [usize; 5] = [4 << 10, 64 << 10, 1 << 20, 16 << 20, 128 << 20];  // 4 KiB .. 128 MiB
+const BYTES_PER_SIZE: usize = 256 << 20;  // repetitions scaled to move ~256 MiB per size
+const MAX_REPS: usize = 256;
+const MIN_REPS: usize = 4;
+const LAUNCHES: usize = 1000;             // submit-and-wait round trips
+const BATCHED_DISPATCHES: usize = 10000;  // dispatches recorded into a single submission
+
+// Minimal kernel: one atomic increment, so launch cost is all that gets measured
+const SHADER_SOURCE: &str = r#"
+    @group(0) @binding(0) var<storage, read_write> counter: atomic<u32>;
+
+    @compute @workgroup_size(1)
+    fn noop() {
+        atomicAdd(&counter, 1u);
+    }
+"#;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+#[derive(Default)]
+struct Timings {
+    upload_private: Duration,
+    upload_shared: Duration,
+    download_private: Duration,
+    download_shared: Duration,
+}
+
+impl Timings {
+    fn total(&self) -> Duration {
+        self.upload_private + self.upload_shared + self.download_private + self.download_shared
+    }
+}
+
+struct Gpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    adapter_info: wgpu::AdapterInfo,
+}
+
+impl Gpu {
+    fn new() -> Self {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .expect("No GPU adapter found");
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+            .expect("Failed to create device");
+        Gpu { device, queue, adapter_info: adapter.get_info() }
+    }
+
+    fn wait(&self) {
+        self.device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+    }
+
+    fn buffer(&self, size: usize, usage: wgpu::BufferUsages) -> wgpu::Buffer {
+        self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: size as u64,
+            usage,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn map(&self, buffer: &wgpu::Buffer, mode: wgpu::MapMode) {
+        buffer.slice(..).map_async(mode, |status| status.expect("Failed to map buffer"));
+        self.wait();
+    }
+
+    fn copy(&self, from: &wgpu::Buffer, to: &wgpu::Buffer, size: usize) {
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(from, 0, to, 0, size as u64);
+        self.queue.submit(Some(encoder.finish()));
+        self.wait();
+    }
+
+    // Shared: the CPU writes straight into host-visible memory
+    fn upload_shared(&self, upload: &wgpu::Buffer, data: &[u8]) {
+        self.map(upload, wgpu::MapMode::Write);
+        upload.slice(..).get_mapped_range_mut().expect("Failed to write mapped buffer").copy_from_slice(data);
+        upload.unmap();
+    }
+
+    // Private: the same write, followed by a GPU copy into device-local memory
+    fn upload_private(&self, upload: &wgpu::Buffer, device_local: &wgpu::Buffer, data: &[u8]) {
+        self.upload_shared(upload, data);
+        self.copy(upload, device_local, data.len());
+    }
+
+    fn download_shared(&self, readback: &wgpu::Buffer, out: &mut [u8]) {
+        self.map(readback, wgpu::MapMode::Read);
+        out.copy_from_slice(&readback.slice(..).get_mapped_range().expect("Failed to read mapped buffer"));
+        readback.unmap();
+    }
+
+    fn download_private(&self, device_local: &wgpu::Buffer, readback: &wgpu::Buffer, out: &mut [u8]) {
+        self.copy(device_local, readback, out.len());
+        self.download_shared(readback, out);
+    }
+
+    fn measure(&self, size: usize, reps: usize, checksum: &mut u64) -> Timings {
+        let mut seed = size as u64;
+        let data: Vec<u8> = (0..size).map(|_| next_random(&mut seed) as u8).collect();
+        let mut out = vec![0u8; size];
+
+        let upload = self.buffer(size, wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC);
+        let device_local = self.buffer(size, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST);
+        let readback = self.buffer(size, wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST);
+
+        let mut timings = Timings::default();
+        let time = |f: &mut dyn FnMut()| {
+            let start = Instant::now();
+            for _ in 0..reps {
+                f();
+            }
+            start.elapsed()
+        };
+        timings.upload_shared = time(&mut || self.upload_shared(&upload, &data));
+        timings.upload_private = time(&mut || self.upload_private(&upload, &device_local, &data));
+        timings.download_private = time(&mut || self.download_private(&device_local, &readback, &mut out));
+        timings.download_shared = time(&mut || self.download_shared(&readback, &mut out));
+
+        assert_eq!(data, out, "Round trip corrupted the {}-byte buffer", size);
+        *checksum = out.iter().step_by(1024).fold(*checksum, |acc, &b| acc.wrapping_add(b as u64));
+        timings
+    }
+
+    // Returns (submit-and-wait round trip, batched dispatch) durations and the final counter value
+    fn launch_latency(&self) -> (Duration, Duration, u32) {
+        let shader = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER_SOURCE.into()),
+        });
+        let pipeline = self.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: None,
+            module: &shader,
+            entry_point: Some("noop"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let counter = self.buffer(4, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST);
+        let readback = self.buffer(4, wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST);
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: counter.as_entire_binding() }],
+        });
+        let submit = |dispatches: usize| {
+            let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                pass.set_pipeline(&pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                for _ in 0..dispatches {
+                    pass.dispatch_workgroups(1, 1, 1);
+                }
+            }
+            self.queue.submit(Some(encoder.finish()));
+            self.wait();
+        };
+
+        // Warm-up, then reset the counter
+        submit(1);
+        self.queue.write_buffer(&counter, 0, bytemuck::bytes_of(&0u32));
+
+        let start = Instant::now();
+        for _ in 0..LAUNCHES {
+            submit(1);
+        }
+        let round_trip = start.elapsed();
+
+        let start = Instant::now();
+        submit(BATCHED_DISPATCHES);
+        let batched = start.elapsed();
+
+        let mut count = [0u8; 4];
+        self.download_private(&counter, &readback, &mut count);
+        (round_trip, batched, u32::from_le_bytes(count))
+    }
+}
+
+fn label(size: usize) -> String {
+    if size >= 1 << 20 {
+        format!("{} MiB", size >> 20)
+    } else {
+        format!("{} KiB", size >> 10)
+    }
+}
+
+fn main() {
+    let gpu = Gpu::new();
+    let mut checksum = 0u64;
+    let mut total = Duration::ZERO;
+
+    eprintln!("Adapter: {} ({:?})", gpu.adapter_info.name, gpu.adapter_info.backend);
+    eprintln!("{:>8} {:>6}  {:>12} {:>12} {:>12} {:>12}", "Size", "Reps", "H2D shared", "H2D private", "D2H shared", "D2H private");
+    for &size in &SIZES {
+        let reps = (BYTES_PER_SIZE / size).clamp(MIN_REPS, MAX_REPS);
+        let timings = gpu.measure(size, reps, &mut checksum);
+        let gbps = |d: Duration| (size * reps) as f64 / d.as_secs_f64() / 1e9;
+        eprintln!(
+            "{:>8} {:>6}  {:>7.2} GB/s {:>7.2} GB/s {:>7.2} GB/s {:>7.2} GB/s",
+            label(size),
+            reps,
+            gbps(timings.upload_shared),
+            gbps(timings.upload_private),
+            gbps(timings.download_shared),
+            gbps(timings.download_private)
+        );
+        total += timings.total();
+    }
+
+    let (round_trip, batched, count) = gpu.launch_latency();
+    assert_eq!(count as usize, LAUNCHES + BATCHED_DISPATCHES, "Kernel launches were lost");
+    total += round_trip + batched;
+    checksum = checksum.wrapping_add(count as u64);
+
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("Launch latency (submit + wait): {:.1} us", round_trip.as_secs_f64() * 1e6 / LAUNCHES as f64);
+    eprintln!("Batched dispatch cost: {:.3} us", batched.as_secs_f64() * 1e6 / BATCHED_DISPATCHES as f64);
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_sizes() {
+        let labels: Vec<String> = SIZES.iter().map(|&s| label(s)).collect();
+        assert_eq!(labels, ["4 KiB", "64 KiB", "1 MiB", "16 MiB", "128 MiB"]);
+    }
+}
@@ -0,0 +1,406 @@
+use half::{bf16, f16, slice::HalfFloatSliceExt};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use wgpu::util::DeviceExt;
+
+const VECTOR_LEN: usize = 16 * 1024 * 1024;  // 16M elements
+const VECTOR_PASSES: usize = 10;
+const GEMM_SIZE: usize = 1024;               // 1024x1024 matrices
+const GPU_ITERATIONS: usize = 10;
+const ALPHA: f32 = 0.1;
+
+// Reduced-precision types are storage formats here: every kernel widens to f32, computes with
+// explicit FMAs and rounds once on store, which is how CPUs without half ALUs run them
+trait Element: Copy + Default {
+    const NAME: &'static str;
+    fn from_f32(x: f32) -> Self;
+    fn to_f32(self) -> f32;
+
+    fn convert_from(dst: &mut [Self], src: &[f32]) {
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d = Self::from_f32(s);
+        }
+    }
+}
+
+impl Element for f32 {
+    const NAME: &'static str = "f32";
+    fn from_f32(x: f32) -> Self {
+        x
+    }
+    fn to_f32(self) -> f32 {
+        self
+    }
+}
+
+impl Element for f16 {
+    const NAME: &'static str = "f16";
+    fn from_f32(x: f32) -> Self {
+        f16::from_f32(x)
+    }
+    fn to_f32(self) -> f32 {
+        f16::to_f32(self)
+    }
+    // The half crate's slice conversion uses F16C / NEON where available
+    fn convert_from(dst: &mut [Self], src: &[f32]) {
+        dst.convert_from_f32_slice(src);
+    }
+}
+
+impl Element for bf16 {
+    const NAME: &'static str = "bf16";
+    fn from_f32(x: f32) -> Self {
+        bf16::from_f32(x)
+    }
+    fn to_f32(self) -> f32 {
+        bf16::to_f32(self)
+    }
+    fn convert_from(dst: &mut [Self], src: &[f32]) {
+        dst.convert_from_f32_slice(src);
+    }
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn random_vector(len: usize, seed: u64) -> Vec<f32> {
+    let mut seed = seed;
+    (0..len).map(|_| (next_random(&mut seed) % 2001) as f32 / 1000.0 - 1.0).collect()
+}
+
+fn axpy<T: Element>(alpha: f32, x: &[T], y: &mut [T]) {
+    for (yi, &xi) in y.iter_mut().zip(x) {
+        *yi = T::from_f32(alpha.mul_add(xi.to_f32(), yi.to_f32()));
+    }
+}
+
+fn dot<T: Element>(x: &[T], y: &[T]) -> f32 {
+    x.iter().zip(y).fold(0.0f32, |acc, (&a, &b)| a.to_f32().mul_add(b.to_f32(), acc))
+}
+
+// i-k-j GEMM with an f32 accumulator row, rounded to T when the row is stored
+fn gemm<T: Element>(a: &[T], b: &[T], c: &mut [T], n: usize) {
+    let mut row = vec![0.0f32; n];
+    for i in 0..n {
+        row.fill(0.0);
+        for k in 0..n {
+            let aik = a[i * n + k].to_f32();
+            let b_row = &b[k * n..(k + 1) * n];
+            for (r, &bkj) in row.iter_mut().zip(b_row) {
+                *r = aik.mul_add(bkj.to_f32(), *r);
+            }
+        }
+        for (cij, &r) in c[i * n..(i + 1) * n].iter_mut().zip(&row) {
+            *cij = T::from_f32(r);
+        }
+    }
+}
+
+struct Inputs {
+    x: Vec<f32>,
+    y: Vec<f32>,
+    a: Vec<f32>,
+    b: Vec<f32>,
+}
+
+#[derive(Default)]
+struct Timings {
+    convert: Duration,
+    axpy: Duration,
+    dot: Duration,
+    gemm: Duration,
+}
+
+impl Timings {
+    fn total(&self) -> Duration {
+        self.convert + self.axpy + self.dot + self.gemm
+    }
+}
+
+struct Outcome {
+    timings: Timings,
+    dot: f32,
+    y: Vec<f32>,
+    c: Vec<f32>,
+}
+
+fn run<T: Element>(inputs: &Inputs) -> Outcome {
+    let mut timings = Timings::default();
+    let mut x = vec![T::default(); VECTOR_LEN];
+    let mut y = vec![T::default(); VECTOR_LEN];
+    let mut dot_result = 0.0f32;
+
+    for _ in 0..VECTOR_PASSES {
+        let start = Instant::now();
+        T::convert_from(&mut x, &inputs.x);
+        T::convert_from(&mut y, &inputs.y);
+        timings.convert += start.elapsed();
+    }
+
+    for _ in 0..VECTOR_PASSES {
+        let start = Instant::now();
+        axpy(ALPHA, black_box(&x), &mut y);
+        timings.axpy += start.elapsed();
+
+        let start = Instant::now();
+        dot_result = black_box(dot(black_box(&x), &y));
+        timings.dot += start.elapsed();
+    }
+
+    let mut a = vec![T::default(); GEMM_SIZE * GEMM_SIZE];
+    let mut b = vec![T::default(); GEMM_SIZE * GEMM_SIZE];
+    let mut c = vec![T::default(); GEMM_SIZE * GEMM_SIZE];
+    T::convert_from(&mut a, &inputs.a);
+    T::convert_from(&mut b, &inputs.b);
+    let start = Instant::now();
+    gemm(black_box(&a), black_box(&b), &mut c, GEMM_SIZE);
+    timings.gemm = start.elapsed();
+
+    Outcome {
+        timings,
+        dot: dot_result,
+        y: y.iter().map(|v| v.to_f32()).collect(),
+        c: c.iter().map(|v| v.to_f32()).collect(),
+    }
+}
+
+fn max_error(values: &[f32], reference: &[f32]) -> f32 {
+    values.iter().zip(reference).map(|(v, r)| (v - r).abs()).fold(0.0f32, f32::max)
+}
+
+// The same naive GEMM in WGSL, instantiated for f32 and f16 storage buffers (f32 accumulation)
+fn gpu_shader(element: &str) -> String {
+    let enable = if element == "f16" { "enable f16;" } else { "" };
+    format!(
+        r#"
+    {enable}
+    @group(0) @binding(0) var<storage, read> a: array<{element}>;
+    @group(0) @binding(1) var<storage, read> b: array<{element}>;
+    @group(0) @binding(2) var<storage, read_write> c: array<{element}>;
+    @group(0) @binding(3) var<uniform> n: u32;
+
+    @compute @workgroup_size(16, 16)
+    fn gemm(@builtin(global_invocation_id) gid: vec3<u32>) {{
+        if (gid.x >= n || gid.y >= n) {{
+            return;
+        }}
+        var acc = 0.0;
+        for (var k = 0u; k < n; k++) {{
+            acc = fma(f32(a[gid.y * n + k]), f32(b[k * n + gid.x]), acc);
+        }}
+        c[gid.y * n + gid.x] = {element}(acc);
+    }}
+"#
+    )
+}
+
+// Runs the GPU GEMM with `element` storage on `bytes`-encoded inputs; returns the time and C widened to f32
+fn gpu_gemm(device: &wgpu::Device, queue: &wgpu::Queue, element: &str, a: &[u8], b: &[u8], widen: fn(&[u8]) -> Vec<f32>) -> (Duration, Vec<f32>) {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(gpu_shader(element).into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: None,
+        layout: None,
+        module: &shader,
+        entry_point: Some("gemm"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+    let storage = |data: &[u8]| {
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: data,
+            usage: wgpu::BufferUsages::STORAGE,
+        })
+    };
+    let bytes = a.len() as u64;
+    let buffer_a = storage(a);
+    let buffer_b = storage(b);
+    let buffer_c = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: bytes,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let buffer_n = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::bytes_of(&(GEMM_SIZE as u32)),
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+    let buffer_readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: bytes,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: buffer_a.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: buffer_b.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 2, resource: buffer_c.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 3, resource: buffer_n.as_entire_binding() },
+        ],
+    });
+    let dispatch = || {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            let groups = GEMM_SIZE.div_ceil(16) as u32;
+            pass.dispatch_workgroups(groups, groups, 1);
+        }
+        queue.submit(Some(encoder.finish()));
+        device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+    };
+
+    // Warm-up
+    dispatch();
+    let start = Instant::now();
+    for _ in 0..GPU_ITERATIONS {
+        dispatch();
+    }
+    let duration = start.elapsed();
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(&buffer_c, 0, &buffer_readback, 0, bytes);
+    queue.submit(Some(encoder.finish()));
+    let slice = buffer_readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |status| status.expect("Failed to map readback buffer"));
+    device.poll(wgpu::PollType::wait_indefinitely()).expect("Failed to wait for GPU");
+    let c = widen(&slice.get_mapped_range().expect("Failed to read mapped buffer"));
+    (duration, c)
+}
+
+// GPU path: f32 vs f16 storage buffers, only on adapters exposing shader-f16
+fn run_gpu(inputs: &Inputs, reference: &[f32]) {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+    let Ok(adapter) = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        ..Default::default()
+    })) else {
+        eprintln!("GPU: no adapter found, skipping");
+        return;
+    };
+    let info = adapter.get_info();
+    if !adapter.features().contains(wgpu::Features::SHADER_F16) {
+        eprintln!("GPU: {} ({:?}) does not support shader-f16, skipping", info.name, info.backend);
+        return;
+    }
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        required_features: wgpu::Features::SHADER_F16,
+        ..Default::default()
+    }))
+    .expect("Failed to create device");
+
+    let a16: Vec<f16> = inputs.a.iter().map(|&v| f16::from_f32(v)).collect();
+    let b16: Vec<f16> = inputs.b.iter().map(|&v| f16::from_f32(v)).collect();
+    let (time32, c32) = gpu_gemm(&device, &queue, "f32", bytemuck::cast_slice(&inputs.a), bytemuck::cast_slice(&inputs.b),
+        |bytes| bytemuck::cast_slice(bytes).to_vec());
+    let (time16, c16) = gpu_gemm(&device, &queue, "f16", bytemuck::cast_slice(&a16), bytemuck::cast_slice(&b16),
+        |bytes| bytemuck::cast_slice::<u8, f16>(bytes).iter().map(|v| v.to_f32()).collect());
+
+    eprintln!("GPU: {} ({:?}), {}x{} GEMM x{}", info.name, info.backend, GEMM_SIZE, GEMM_SIZE, GPU_ITERATIONS);
+    eprintln!("  {:>4}: {:.6} s (max error vs CPU f32 {:.2e})", "f32", time32.as_secs_f64(), max_error(&c32, reference));
+    eprintln!("  {:>4}: {:.6} s (max error vs CPU f32 {:.2e})", "f16", time16.as_secs_f64(), max_error(&c16, reference));
+}
+
+fn report<T: Element>(outcome: &Outcome, reference: &Outcome, checksum: &mut f64) {
+    let t = &outcome.timings;
+    eprintln!(
+        "{:>4}: convert {:.4}s  axpy {:.4}s  dot {:.4}s  gemm {:.4}s  (dot {:.4}, max y error {:.2e}, max C error {:.2e})",
+        T::NAME,
+        t.convert.as_secs_f64(),
+        t.axpy.as_secs_f64(),
+        t.dot.as_secs_f64(),
+        t.gemm.as_secs_f64(),
+        outcome.dot,
+        max_error(&outcome.y, &reference.y),
+        max_error(&outcome.c, &reference.c)
+    );
+    *checksum += outcome.dot as f64;
+    *checksum += outcome.y.iter().step_by(1000).map(|&v| v as f64).sum::<f64>();
+    *checksum += outcome.c.iter().step_by(100).map(|&v| v as f64).sum::<f64>();
+}
+
+fn main() {
+    let inputs = Inputs {
+        x: random_vector(VECTOR_LEN, 1),
+        y: random_vector(VECTOR_LEN, 2),
+        a: random_vector(GEMM_SIZE * GEMM_SIZE, 3),
+        b: random_vector(GEMM_SIZE * GEMM_SIZE, 4),
+    };
+
+    let full = run::<f32>(&inputs);
+    let half = run::<f16>(&inputs);
+    let brain = run::<bf16>(&inputs);
+    let total = full.timings.total() + half.timings.total() + brain.timings.total();
+
+    let mut checksum = 0.0f64;
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("Vectors: {}M elements x{} passes, GEMM: {}x{}", VECTOR_LEN >> 20, VECTOR_PASSES, GEMM_SIZE, GEMM_SIZE);
+    report::<f32>(&full, &full, &mut checksum);
+    report::<f16>(&half, &full, &mut checksum);
+    report::<bf16>(&brain, &full, &mut checksum);
+    run_gpu(&inputs, &full.c);
+    eprintln!("Checksum: {:.6}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to<T: Element>(values: &[f32]) -> Vec<T> {
+        let mut out = vec![T::default(); values.len()];
+        T::convert_from(&mut out, values);
+        out
+    }
+
+    #[test]
+    fn kernels_are_exact_on_representable_values() {
+        // Small integers and halves are exact in all three formats, so every result is too
+        fn check<T: Element>() {
+            let x = to::<T>(&[1.0, 2.0, -3.0, 0.5]);
+            let mut y = to::<T>(&[4.0, 0.0, 1.0, -0.5]);
+            assert_eq!(dot(&x, &y), 4.0 - 3.0 - 0.25, "{}", T::NAME);
+            axpy(2.0, &x, &mut y);
+            assert_eq!(y.iter().map(|v| v.to_f32()).collect::<Vec<_>>(), [6.0, 4.0, -5.0, 0.5], "{}", T::NAME);
+
+            let a = to::<T>(&[1.0, 2.0, 3.0, 4.0]);
+            let b = to::<T>(&[5.0, 6.0, 7.0, 8.0]);
+            let mut c = vec![T::default(); 4];
+            gemm(&a, &b, &mut c, 2);
+            assert_eq!(c.iter().map(|v| v.to_f32()).collect::<Vec<_>>(), [19.0, 22.0, 43.0, 50.0], "{}", T::NAME);
+        }
+        check::<f32>();
+        check::<f16>();
+        check::<bf16>();
+    }
+
+    #[test]
+    fn rounding_error_follows_the_mantissa_width() {
+        let x = random_vector(4096, 7);
+        let f16_err = max_error(&to::<f16>(&x).iter().map(|v| v.to_f32()).collect::<Vec<_>>(), &x);
+        let bf16_err = max_error(&to::<bf16>(&x).iter().map(|v| v.to_f32()).collect::<Vec<_>>(), &x);
+        assert_eq!(max_error(&to::<f32>(&x), &x), 0.0);
+        // Half an ulp at magnitudes below 1: 2^-11 for f16's 10-bit mantissa, 2^-8 for bf16's 7
+        assert!(f16_err > 0.0 && f16_err <= 2f32.powi(-11), "{}", f16_err);
+        assert!(bf16_err > f16_err && bf16_err <= 2f32.powi(-8), "{}", bf16_err);
+    }
+
+    #[test]
+    fn shaders_declare_their_element_type() {
+        let half = gpu_shader("f16");
+        assert!(half.contains("enable f16;") && half.contains("array<f16>") && half.contains("f16(acc)"));
+        let single = gpu_shader("f32");
+        assert!(!single.contains("enable f16;") && single.contains("array<f32>"));
+    }
+}
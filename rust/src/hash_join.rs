@@ -0,0 +1,234 @@
+use rayon::prelude::*;
+use std::time::Instant;
+
+const FACT_ROWS: usize = 100_000_000;
+const DIM_ROWS: usize = 10_000_000;
+const NUM_GROUPS: usize = 100_000;
+const MATCH_PERCENT: u64 = 90; // Share of fact rows whose key exists in the dimension table
+const MAX_AMOUNT: u64 = 10_000;
+const CHUNK_SIZE: usize = 1 << 16;
+const EMPTY: u32 = u32::MAX;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Column-oriented tables, the way analytical engines store them
+struct DimTable {
+    keys: Vec<u32>,
+    groups: Vec<u32>,
+}
+
+struct FactTable {
+    keys: Vec<u32>,
+    amounts: Vec<u32>,
+}
+
+fn generate_tables() -> (DimTable, FactTable) {
+    let mut seed = 8675309u64;
+
+    // Multiplying by an odd constant is a bijection on u32, so dimension keys are unique
+    let dim_keys: Vec<u32> = (0..DIM_ROWS as u32).map(|i| i.wrapping_mul(2654435761)).collect();
+    let dim_groups = (0..DIM_ROWS).map(|_| (next_random(&mut seed) % NUM_GROUPS as u64) as u32).collect();
+
+    let mut fact_keys = Vec::with_capacity(FACT_ROWS);
+    let mut fact_amounts = Vec::with_capacity(FACT_ROWS);
+    for _ in 0..FACT_ROWS {
+        let key = if next_random(&mut seed) % 100 < MATCH_PERCENT {
+            dim_keys[(next_random(&mut seed) % DIM_ROWS as u64) as usize]
+        } else {
+            next_random(&mut seed) as u32
+        };
+        fact_keys.push(key);
+        fact_amounts.push((next_random(&mut seed) % MAX_AMOUNT) as u32);
+    }
+
+    (DimTable { keys: dim_keys, groups: dim_groups }, FactTable { keys: fact_keys, amounts: fact_amounts })
+}
+
+#[derive(Clone, Copy)]
+struct Slot {
+    key: u32,
+    group: u32, // EMPTY marks an unused slot
+}
+
+// Open addressing with linear probing and a power-of-two capacity at most half full
+struct JoinTable {
+    slots: Vec<Slot>,
+    shift: u32,
+}
+
+impl JoinTable {
+    fn build(dim: &DimTable) -> Self {
+        let capacity = (dim.keys.len() * 2).next_power_of_two();
+        let mut table = JoinTable {
+            slots: vec![Slot { key: 0, group: EMPTY }; capacity],
+            shift: 64 - capacity.trailing_zeros(),
+        };
+        let mask = capacity - 1;
+        for (&key, &group) in dim.keys.iter().zip(&dim.groups) {
+            let mut i = table.slot_of(key);
+            while table.slots[i].group != EMPTY {
+                i = (i + 1) & mask;
+            }
+            table.slots[i] = Slot { key, group };
+        }
+        table
+    }
+
+    // Fibonacci hashing: the top bits of key * 2^64/phi
+    #[inline]
+    fn slot_of(&self, key: u32) -> usize {
+        ((key as u64).wrapping_mul(0x9E3779B97F4A7C15) >> self.shift) as usize
+    }
+
+    #[inline]
+    fn probe(&self, key: u32) -> Option<u32> {
+        let mask = self.slots.len() - 1;
+        let mut i = self.slot_of(key);
+        loop {
+            let slot = self.slots[i];
+            if slot.group == EMPTY {
+                return None;
+            }
+            if slot.key == key {
+                return Some(slot.group);
+            }
+            i = (i + 1) & mask;
+        }
+    }
+}
+
+// SELECT group, COUNT(*), SUM(amount) ... GROUP BY group, with dense group ids
+struct Aggregate {
+    counts: Vec<u64>,
+    sums: Vec<u64>,
+}
+
+impl Aggregate {
+    fn new() -> Self {
+        Aggregate { counts: vec![0; NUM_GROUPS], sums: vec![0; NUM_GROUPS] }
+    }
+
+    fn merge(mut self, other: Aggregate) -> Self {
+        for g in 0..NUM_GROUPS {
+            self.counts[g] += other.counts[g];
+            self.sums[g] += other.sums[g];
+        }
+        self
+    }
+}
+
+fn join_aggregate(table: &JoinTable, fact: &FactTable, rows: usize) -> Aggregate {
+    fact.keys[..rows]
+        .par_chunks(CHUNK_SIZE)
+        .zip(fact.amounts[..rows].par_chunks(CHUNK_SIZE))
+        .fold(Aggregate::new, |mut agg, (keys, amounts)| {
+            for (&key, &amount) in keys.iter().zip(amounts) {
+                if let Some(group) = table.probe(key) {
+                    agg.counts[group as usize] += 1;
+                    agg.sums[group as usize] += amount as u64;
+                }
+            }
+            agg
+        })
+        .reduce(Aggregate::new, Aggregate::merge)
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let (dim, fact) = generate_tables();
+
+    // Warm-up
+    {
+        let table = JoinTable::build(&dim);
+        let _ = join_aggregate(&table, &fact, FACT_ROWS / 20);
+    }
+
+    // Benchmark build
+    let start = Instant::now();
+    measure.start();
+    let table = JoinTable::build(&dim);
+    measure.stop();
+    let build_duration = start.elapsed();
+
+    // Benchmark probe + aggregate
+    let start = Instant::now();
+    measure.start();
+    let result = join_aggregate(&table, &fact, FACT_ROWS);
+    measure.stop();
+    let probe_duration = start.elapsed();
+
+    // Checksum
+    let matched: u64 = result.counts.iter().sum();
+    let total_amount: u64 = result.sums.iter().sum();
+    let active_groups = result.counts.iter().filter(|&&c| c > 0).count();
+    let checksum = result.sums.iter().enumerate().fold(0u64, |acc, (g, &s)| {
+        acc.wrapping_add((g as u64 + 1).wrapping_mul(s))
+    });
+
+    measure.report();
+    eprintln!("Build: {:.6}s ({:.0} rows/s), probe + aggregate: {:.6}s ({:.0} rows/s)",
+              build_duration.as_secs_f64(), DIM_ROWS as f64 / build_duration.as_secs_f64(),
+              probe_duration.as_secs_f64(), FACT_ROWS as f64 / probe_duration.as_secs_f64());
+    eprintln!("Matched rows: {}, groups: {}, total amount: {}", matched, active_groups, total_amount);
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probes_find_every_key() {
+        // Colliding keys: every multiple of 2^20 shares the low bits
+        let keys: Vec<u32> = (0..1000).map(|i| i << 20).chain([0xFFFF_FFFE, 1, 2]).collect();
+        let dim = DimTable { groups: (0..keys.len() as u32).map(|i| i % NUM_GROUPS as u32).collect(), keys };
+        let table = JoinTable::build(&dim);
+        assert!(table.slots.len() >= 2 * dim.keys.len() && table.slots.len().is_power_of_two());
+        for (&key, &group) in dim.keys.iter().zip(&dim.groups) {
+            assert_eq!(table.probe(key), Some(group));
+        }
+        assert_eq!(table.probe(3), None);
+        assert_eq!(table.probe(1 << 31), None);
+    }
+
+    #[test]
+    fn join_matches_a_hash_map() {
+        let mut seed = 99u64;
+        let dim_keys: Vec<u32> = (0..5000u32).map(|i| i.wrapping_mul(2654435761)).collect();
+        let dim = DimTable {
+            groups: (0..5000).map(|_| (next_random(&mut seed) % 50) as u32).collect(),
+            keys: dim_keys,
+        };
+        let rows = 3 * CHUNK_SIZE + 123;
+        let mut keys = Vec::with_capacity(rows);
+        let mut amounts = Vec::with_capacity(rows);
+        for _ in 0..rows {
+            keys.push(if next_random(&mut seed) % 100 < 50 { dim.keys[(next_random(&mut seed) % 5000) as usize] } else { next_random(&mut seed) as u32 });
+            amounts.push((next_random(&mut seed) % MAX_AMOUNT) as u32);
+        }
+        let fact = FactTable { keys, amounts };
+
+        let lookup: std::collections::HashMap<u32, u32> = dim.keys.iter().copied().zip(dim.groups.iter().copied()).collect();
+        let mut counts = vec![0u64; NUM_GROUPS];
+        let mut sums = vec![0u64; NUM_GROUPS];
+        for (key, &amount) in fact.keys.iter().zip(&fact.amounts) {
+            if let Some(&g) = lookup.get(key) {
+                counts[g as usize] += 1;
+                sums[g as usize] += amount as u64;
+            }
+        }
+
+        let agg = join_aggregate(&JoinTable::build(&dim), &fact, rows);
+        assert_eq!(agg.counts, counts);
+        assert_eq!(agg.sums, sums);
+        // Only a prefix of the fact table when rows is smaller
+        let head = join_aggregate(&JoinTable::build(&dim), &fact, 10);
+        let matched = fact.keys[..10].iter().filter(|k| lookup.contains_key(k)).count();
+        assert_eq!(head.counts.iter().sum::<u64>(), matched as u64);
+    }
+}
@@ -0,0 +1,307 @@
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+const N_2D: usize = 4096;
+const STEPS_2D: usize = 100;
+const ALPHA_2D: f64 = 0.2; // Explicit scheme is stable below 1/4 in 2D
+const BLOCK_ROWS_2D: usize = 64;
+const BLOCK_COLS_2D: usize = 512;
+
+const N_3D: usize = 256;
+const STEPS_3D: usize = 100;
+const ALPHA_3D: f64 = 0.1; // ... and below 1/6 in 3D
+const BLOCK_PLANES_3D: usize = 16;
+const BLOCK_ROWS_3D: usize = 16;
+const BLOCK_COLS_3D: usize = 64;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Precision {
+    F64,
+    F32,
+    Mixed, // f32 grids, f64 arithmetic: halves the memory traffic without f32 rounding in the update
+}
+
+impl Precision {
+    fn name(self) -> &'static str {
+        match self {
+            Precision::F64 => "f64",
+            Precision::F32 => "f32",
+            Precision::Mixed => "mixed (f32 storage, f64 arithmetic)",
+        }
+    }
+}
+
+trait Real:
+    Copy + Send + Sync + std::ops::Add<Output = Self> + std::ops::Sub<Output = Self> + std::ops::Mul<Output = Self>
+{
+    fn from_f64(x: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl Real for f64 {
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl Real for f32 {
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+// Grid element S read into the arithmetic type A, and back
+#[inline]
+fn convert<F: Real, T: Real>(x: F) -> T {
+    T::from_f64(x.to_f64())
+}
+
+// Zero on the boundary, 1.0 inside a centered hot square/cube a quarter of the domain wide
+fn initial_2d() -> Vec<f64> {
+    let (lo, hi) = (3 * N_2D / 8, 5 * N_2D / 8);
+    (0..N_2D * N_2D)
+        .map(|i| {
+            let (y, x) = (i / N_2D, i % N_2D);
+            if (lo..hi).contains(&y) && (lo..hi).contains(&x) { 1.0 } else { 0.0 }
+        })
+        .collect()
+}
+
+fn initial_3d() -> Vec<f64> {
+    let (lo, hi) = (3 * N_3D / 8, 5 * N_3D / 8);
+    (0..N_3D * N_3D * N_3D)
+        .map(|i| {
+            let (z, y, x) = (i / (N_3D * N_3D), (i / N_3D) % N_3D, i % N_3D);
+            if (lo..hi).contains(&z) && (lo..hi).contains(&y) && (lo..hi).contains(&x) { 1.0 } else { 0.0 }
+        })
+        .collect()
+}
+
+// Updates read S cells and compute in A
+#[inline]
+fn update_2d<S: Real, A: Real>(cur: &[S], i: usize) -> S {
+    let at = |j: usize| -> A { convert(cur[j]) };
+    let c = at(i);
+    let sum = at(i - 1) + at(i + 1) + at(i - N_2D) + at(i + N_2D);
+    convert(c + A::from_f64(ALPHA_2D) * (sum - A::from_f64(4.0) * c))
+}
+
+#[inline]
+fn update_3d<S: Real, A: Real>(cur: &[S], i: usize) -> S {
+    let plane = N_3D * N_3D;
+    let at = |j: usize| -> A { convert(cur[j]) };
+    let c = at(i);
+    let sum = at(i - 1) + at(i + 1) + at(i - N_3D) + at(i + N_3D) + at(i - plane) + at(i + plane);
+    convert(c + A::from_f64(ALPHA_3D) * (sum - A::from_f64(6.0) * c))
+}
+
+// One row per task, full-width sweeps
+fn step_2d_naive<S: Real, A: Real>(cur: &[S], next: &mut [S]) {
+    next.par_chunks_mut(N_2D).enumerate().for_each(|(y, row)| {
+        if y == 0 || y == N_2D - 1 {
+            return;
+        }
+        for (x, out) in (1..N_2D - 1).zip(&mut row[1..N_2D - 1]) {
+            *out = update_2d::<S, A>(cur, y * N_2D + x);
+        }
+    });
+}
+
+// Bands of rows per task, swept in column tiles so the three input rows of a tile stay in L1
+fn step_2d_blocked<S: Real, A: Real>(cur: &[S], next: &mut [S]) {
+    next.par_chunks_mut(BLOCK_ROWS_2D * N_2D).enumerate().for_each(|(band, rows)| {
+        let y0 = band * BLOCK_ROWS_2D;
+        for x0 in (1..N_2D - 1).step_by(BLOCK_COLS_2D) {
+            let x1 = (x0 + BLOCK_COLS_2D).min(N_2D - 1);
+            for (r, row) in rows.chunks_mut(N_2D).enumerate() {
+                let y = y0 + r;
+                if y == 0 || y == N_2D - 1 {
+                    continue;
+                }
+                for (x, out) in (x0..x1).zip(&mut row[x0..x1]) {
+                    *out = update_2d::<S, A>(cur, y * N_2D + x);
+                }
+            }
+        }
+    });
+}
+
+// One plane per task, full sweeps
+fn step_3d_naive<S: Real, A: Real>(cur: &[S], next: &mut [S]) {
+    next.par_chunks_mut(N_3D * N_3D).enumerate().for_each(|(z, plane)| {
+        if z == 0 || z == N_3D - 1 {
+            return;
+        }
+        for y in 1..N_3D - 1 {
+            for x in 1..N_3D - 1 {
+                plane[y * N_3D + x] = update_3d::<S, A>(cur, (z * N_3D + y) * N_3D + x);
+            }
+        }
+    });
+}
+
+// Slabs of planes per task; each (y, x) tile is marched through the slab so neighboring planes are reused
+fn step_3d_blocked<S: Real, A: Real>(cur: &[S], next: &mut [S]) {
+    next.par_chunks_mut(BLOCK_PLANES_3D * N_3D * N_3D).enumerate().for_each(|(slab, planes)| {
+        let z0 = slab * BLOCK_PLANES_3D;
+        for y0 in (1..N_3D - 1).step_by(BLOCK_ROWS_3D) {
+            let y1 = (y0 + BLOCK_ROWS_3D).min(N_3D - 1);
+            for x0 in (1..N_3D - 1).step_by(BLOCK_COLS_3D) {
+                let x1 = (x0 + BLOCK_COLS_3D).min(N_3D - 1);
+                for (p, plane) in planes.chunks_mut(N_3D * N_3D).enumerate() {
+                    let z = z0 + p;
+                    if z == 0 || z == N_3D - 1 {
+                        continue;
+                    }
+                    for y in y0..y1 {
+                        for x in x0..x1 {
+                            plane[y * N_3D + x] = update_3d::<S, A>(cur, (z * N_3D + y) * N_3D + x);
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+// Runs the scheme with double buffering; returns the elapsed time and the final grid
+fn simulate<S: Real>(initial: &[S], steps: usize, stepper: fn(&[S], &mut [S])) -> (Duration, Vec<S>) {
+    let mut cur = initial.to_vec();
+    let mut next = initial.to_vec();
+    let start = Instant::now();
+    for _ in 0..steps {
+        stepper(&cur, &mut next);
+        std::mem::swap(&mut cur, &mut next);
+    }
+    (start.elapsed(), cur)
+}
+
+struct Run {
+    durations: [Duration; 4], // 2D naive, 2D blocked, 3D naive, 3D blocked
+    fields: [Vec<f64>; 2],    // final 2D and 3D grids from the naive runs, widened to f64
+    blocked_matches: bool,
+}
+
+// Stores the grids as S (converted outside the timed region) and runs all four schemes with A arithmetic
+fn run<S: Real, A: Real>(grid_2d: &[f64], grid_3d: &[f64]) -> Run {
+    let grid_2d: Vec<S> = grid_2d.iter().map(|&v| S::from_f64(v)).collect();
+    let grid_3d: Vec<S> = grid_3d.iter().map(|&v| S::from_f64(v)).collect();
+
+    // Warm-up
+    let _ = simulate(&grid_2d, 2, step_2d_naive::<S, A>);
+    let _ = simulate(&grid_3d, 2, step_3d_blocked::<S, A>);
+
+    // Benchmark
+    let (naive_2d, field_2d) = simulate(&grid_2d, STEPS_2D, step_2d_naive::<S, A>);
+    let (blocked_2d, blocked_field_2d) = simulate(&grid_2d, STEPS_2D, step_2d_blocked::<S, A>);
+    let (naive_3d, field_3d) = simulate(&grid_3d, STEPS_3D, step_3d_naive::<S, A>);
+    let (blocked_3d, blocked_field_3d) = simulate(&grid_3d, STEPS_3D, step_3d_blocked::<S, A>);
+
+    let widen = |field: Vec<S>| -> Vec<f64> { field.into_iter().map(S::to_f64).collect() };
+    let heat = |field: &[S]| -> f64 { field.iter().map(|&v| v.to_f64()).sum() };
+    Run {
+        blocked_matches: heat(&field_2d) == heat(&blocked_field_2d) && heat(&field_3d) == heat(&blocked_field_3d),
+        durations: [naive_2d, blocked_2d, naive_3d, blocked_3d],
+        fields: [widen(field_2d), widen(field_3d)],
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let precision = match args.iter().position(|a| a == "--precision") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("f64") => Precision::F64,
+            Some("f32") => Precision::F32,
+            Some("mixed") => Precision::Mixed,
+            _ => panic!("--precision expects f64, f32 or mixed"),
+        },
+        None => Precision::F64,
+    };
+
+    let grid_2d = initial_2d();
+    let grid_3d = initial_3d();
+
+    let Run { durations: [naive_2d, blocked_2d, naive_3d, blocked_3d], fields, blocked_matches } = match precision {
+        Precision::F64 => run::<f64, f64>(&grid_2d, &grid_3d),
+        Precision::F32 => run::<f32, f32>(&grid_2d, &grid_3d),
+        Precision::Mixed => run::<f32, f64>(&grid_2d, &grid_3d),
+    };
+    let heat_2d: f64 = fields[0].iter().sum();
+    let heat_3d: f64 = fields[1].iter().sum();
+
+    let total_duration = naive_2d + blocked_2d + naive_3d + blocked_3d;
+    let mlups = |cells: usize, steps: usize, d: Duration| (cells * steps) as f64 / d.as_secs_f64() / 1e6;
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Precision: {}", precision.name());
+    eprintln!("2D {}² x {} steps: naive {:.0} MLUP/s, blocked {:.0} MLUP/s",
+              N_2D, STEPS_2D, mlups(N_2D * N_2D, STEPS_2D, naive_2d), mlups(N_2D * N_2D, STEPS_2D, blocked_2d));
+    eprintln!("3D {}³ x {} steps: naive {:.0} MLUP/s, blocked {:.0} MLUP/s",
+              N_3D, STEPS_3D, mlups(N_3D * N_3D * N_3D, STEPS_3D, naive_3d), mlups(N_3D * N_3D * N_3D, STEPS_3D, blocked_3d));
+    if precision != Precision::F64 {
+        // Against an untimed f64 run: max pointwise difference (the peak temperature is 1) and total heat drift
+        let reference = run::<f64, f64>(&grid_2d, &grid_3d).fields;
+        for (label, field, reference) in [("2D", &fields[0], &reference[0]), ("3D", &fields[1], &reference[1])] {
+            let max_error = field.iter().zip(reference).fold(0.0f64, |e, (v, r)| e.max((v - r).abs()));
+            let heat: f64 = field.iter().sum();
+            let reference_heat: f64 = reference.iter().sum();
+            eprintln!("{} error vs f64: max pointwise {:.3e}, total heat {:.3e} relative",
+                      label, max_error, ((heat - reference_heat) / reference_heat).abs());
+        }
+    }
+    eprintln!("Checksum: {:.9} (3D: {:.9}, blocked matches: {})", heat_2d, heat_3d, blocked_matches);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_source_spreads_to_its_neighbors() {
+        let center = N_2D / 2 * N_2D + N_2D / 2;
+        let mut grid = vec![0.0f64; N_2D * N_2D];
+        grid[center] = 1.0;
+        let (_, field) = simulate(&grid, 1, step_2d_naive::<f64, f64>);
+        assert_eq!(field[center], 1.0 - 4.0 * ALPHA_2D);
+        for neighbor in [center - 1, center + 1, center - N_2D, center + N_2D] {
+            assert_eq!(field[neighbor], ALPHA_2D);
+        }
+        assert_eq!(field.iter().filter(|&&v| v != 0.0).count(), 5);
+
+        let center = ((N_3D / 2) * N_3D + N_3D / 2) * N_3D + N_3D / 2;
+        let mut grid = vec![0.0f64; N_3D * N_3D * N_3D];
+        grid[center] = 1.0;
+        let (_, field) = simulate(&grid, 1, step_3d_naive::<f64, f64>);
+        assert_eq!(field[center], 1.0 - 6.0 * ALPHA_3D);
+        assert_eq!(field[center + N_3D * N_3D], ALPHA_3D);
+    }
+
+    #[test]
+    fn blocked_sweeps_match_naive() {
+        let (_, naive) = simulate(&initial_2d(), 3, step_2d_naive::<f64, f64>);
+        let (_, blocked) = simulate(&initial_2d(), 3, step_2d_blocked::<f64, f64>);
+        assert!(naive == blocked);
+
+        let grid: Vec<f32> = initial_3d().iter().map(|&v| v as f32).collect();
+        let (_, naive) = simulate(&grid, 3, step_3d_naive::<f32, f64>);
+        let (_, blocked) = simulate(&grid, 3, step_3d_blocked::<f32, f64>);
+        assert!(naive == blocked);
+    }
+
+    #[test]
+    fn heat_is_conserved_away_from_the_boundary() {
+        // The hot square starts a quarter of the domain from the cold edges, far beyond a few steps
+        let initial = initial_2d();
+        let total: f64 = initial.iter().sum();
+        assert_eq!(total, (N_2D / 4 * N_2D / 4) as f64);
+        let (_, field) = simulate(&initial, 5, step_2d_blocked::<f64, f64>);
+        assert!((field.iter().sum::<f64>() - total).abs() < 1e-6 * total);
+        assert!(field.iter().all(|&v| (0.0..=1.0).contains(&v)));
+    }
+}
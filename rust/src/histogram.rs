@@ -0,0 +1,238 @@
+use rayon::prelude::*;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+const NUM_BYTES: usize = 1 << 30;  // 1 GiB of skewed bytes
+const NUM_VALUES: usize = 1 << 27; // 1 GiB of f64
+const PASSES: usize = 3;
+const ATOMIC_PASSES: usize = 1; // one contended fetch_add per byte is an order of magnitude slower
+const CHUNK_SIZE: usize = 1 << 16; // elements per parallel work item
+const BINS: usize = 256;
+
+type Histogram = [u64; BINS];
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Product of two uniform bytes: heavily skewed toward small values, so a few bins
+// are hot (the worst case for shared atomic counters)
+fn generate_bytes() -> Vec<u8> {
+    let mut seed = 42u64;
+    (0..NUM_BYTES)
+        .map(|_| {
+            let r = next_random(&mut seed);
+            (((r & 0xff) * ((r >> 8) & 0xff)) >> 8) as u8
+        })
+        .collect()
+}
+
+// Uniform in [-1000, 1000) from the top 53 bits of the LCG state
+fn generate_values() -> Vec<f64> {
+    let mut seed = 7u64;
+    (0..NUM_VALUES)
+        .map(|_| {
+            next_random(&mut seed);
+            ((seed >> 11) as f64 / (1u64 << 53) as f64).mul_add(2000.0, -1000.0)
+        })
+        .collect()
+}
+
+fn histogram_sequential(data: &[u8]) -> Histogram {
+    let mut bins = [0u64; BINS];
+    for &b in data {
+        bins[b as usize] += 1;
+    }
+    bins
+}
+
+// Every byte is a relaxed fetch_add on one shared table
+fn histogram_atomic(data: &[u8]) -> Histogram {
+    let bins: Vec<AtomicU64> = (0..BINS).map(|_| AtomicU64::new(0)).collect();
+    data.par_chunks(CHUNK_SIZE).for_each(|chunk| {
+        for &b in chunk {
+            bins[b as usize].fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    std::array::from_fn(|i| bins[i].load(Ordering::Relaxed))
+}
+
+// Private table per worker, merged once at the end
+fn histogram_per_thread(data: &[u8]) -> Histogram {
+    data.par_chunks(CHUNK_SIZE)
+        .fold(|| [0u64; BINS], |mut bins, chunk| {
+            for &b in chunk {
+                bins[b as usize] += 1;
+            }
+            bins
+        })
+        .reduce(|| [0u64; BINS], |mut a, b| {
+            for (x, y) in a.iter_mut().zip(&b) {
+                *x += y;
+            }
+            a
+        })
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Stats {
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Stats {
+    const EMPTY: Stats = Stats { sum: 0.0, min: f64::INFINITY, max: f64::NEG_INFINITY };
+
+    fn of(values: &[f64]) -> Stats {
+        values.iter().fold(Stats::EMPTY, |s, &x| Stats { sum: s.sum + x, min: s.min.min(x), max: s.max.max(x) })
+    }
+
+    fn merge(self, other: Stats) -> Stats {
+        Stats { sum: self.sum + other.sum, min: self.min.min(other.min), max: self.max.max(other.max) }
+    }
+}
+
+// f64 has no atomic add, so each field is a compare-exchange loop on its bit pattern
+fn atomic_update(cell: &AtomicU64, f: impl Fn(f64) -> f64) {
+    let mut current = cell.load(Ordering::Relaxed);
+    loop {
+        let next = f(f64::from_bits(current)).to_bits();
+        match cell.compare_exchange_weak(current, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => return,
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+// Each chunk is reduced locally, then folded into shared atomics
+fn reduce_atomic(values: &[f64]) -> Stats {
+    let sum = AtomicU64::new(0.0f64.to_bits());
+    let min = AtomicU64::new(f64::INFINITY.to_bits());
+    let max = AtomicU64::new(f64::NEG_INFINITY.to_bits());
+    values.par_chunks(CHUNK_SIZE).for_each(|chunk| {
+        let s = Stats::of(chunk);
+        atomic_update(&sum, |x| x + s.sum);
+        atomic_update(&min, |x| x.min(s.min));
+        atomic_update(&max, |x| x.max(s.max));
+    });
+    Stats {
+        sum: f64::from_bits(sum.load(Ordering::Relaxed)),
+        min: f64::from_bits(min.load(Ordering::Relaxed)),
+        max: f64::from_bits(max.load(Ordering::Relaxed)),
+    }
+}
+
+fn reduce_per_thread(values: &[f64]) -> Stats {
+    values.par_chunks(CHUNK_SIZE)
+        .fold(|| Stats::EMPTY, |s, chunk| s.merge(Stats::of(chunk)))
+        .reduce(|| Stats::EMPTY, Stats::merge)
+}
+
+// black_box keeps the compiler from collapsing repeated passes over unchanged input
+fn timed<T>(passes: usize, f: impl Fn() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let mut result = black_box(f());
+    for _ in 1..passes {
+        result = black_box(f());
+    }
+    (start.elapsed(), result)
+}
+
+fn main() {
+    let bytes = generate_bytes();
+    let values = generate_values();
+
+    // Warm-up
+    let _ = histogram_per_thread(&bytes[..NUM_BYTES / 64]);
+    let _ = reduce_per_thread(&values[..NUM_VALUES / 64]);
+
+    let hist_runs = [
+        ("sequential", PASSES, timed(PASSES, || histogram_sequential(black_box(&bytes)))),
+        ("atomic", ATOMIC_PASSES, timed(ATOMIC_PASSES, || histogram_atomic(&bytes))),
+        ("per-thread", PASSES, timed(PASSES, || histogram_per_thread(&bytes))),
+    ];
+    let reduce_runs = [
+        ("sequential", PASSES, timed(PASSES, || Stats::of(black_box(&values)))),
+        ("atomic", PASSES, timed(PASSES, || reduce_atomic(&values))),
+        ("per-thread", PASSES, timed(PASSES, || reduce_per_thread(&values))),
+    ];
+
+    let histogram = hist_runs[0].2.1;
+    let stats = reduce_runs[0].2.1;
+    for (name, _, (_, h)) in &hist_runs {
+        assert!(*h == histogram, "{} histogram differs from sequential", name);
+    }
+    for (name, _, (_, s)) in &reduce_runs {
+        assert!(s.min == stats.min && s.max == stats.max, "{} min/max differs from sequential", name);
+        assert!(((s.sum - stats.sum) / stats.sum).abs() < 1e-9, "{} sum differs from sequential", name);
+    }
+
+    let total: Duration = hist_runs.iter().map(|(_, _, (d, _))| *d).sum::<Duration>()
+        + reduce_runs.iter().map(|(_, _, (d, _))| *d).sum::<Duration>();
+
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("Histogram over {:.2} GB of bytes", NUM_BYTES as f64 / 1e9);
+    for (name, passes, (d, _)) in &hist_runs {
+        eprintln!("  {:<10} {}x {:8.3}s ({:.2} GB/s)", name, passes, d.as_secs_f64(),
+                  (NUM_BYTES * passes) as f64 / 1e9 / d.as_secs_f64());
+    }
+    eprintln!("Sum/min/max over {}M f64", NUM_VALUES >> 20);
+    for (name, passes, (d, _)) in &reduce_runs {
+        eprintln!("  {:<10} {}x {:8.3}s ({:.2} GB/s)", name, passes, d.as_secs_f64(),
+                  (NUM_VALUES * 8 * passes) as f64 / 1e9 / d.as_secs_f64());
+    }
+
+    let weighted = histogram.iter().enumerate().fold(0u64, |acc, (i, &c)| acc.wrapping_add((i as u64 + 1) * c));
+    eprintln!("Checksum: histogram {} (bin 0: {}), sum {:.6}, min {:.6}, max {:.6}",
+              weighted, histogram[0], stats.sum, stats.min, stats.max);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histograms_agree() {
+        let mut seed = 1u64;
+        // Not a multiple of CHUNK_SIZE, with one very hot bin
+        let data: Vec<u8> = (0..5 * CHUNK_SIZE + 77)
+            .map(|_| if next_random(&mut seed).is_multiple_of(4) { 0 } else { next_random(&mut seed) as u8 })
+            .collect();
+        let expected = histogram_sequential(&data);
+        assert_eq!(expected.iter().sum::<u64>(), data.len() as u64);
+        assert_eq!(histogram_atomic(&data), expected);
+        assert_eq!(histogram_per_thread(&data), expected);
+
+        let mut known = [0u64; BINS];
+        known[3] = 2;
+        known[255] = 1;
+        assert_eq!(histogram_sequential(&[3, 255, 3]), known);
+        assert_eq!(histogram_per_thread(&[]), [0; BINS]);
+    }
+
+    #[test]
+    fn reductions_agree() {
+        let values: Vec<f64> = (0..3 * CHUNK_SIZE + 5).map(|i| ((i * 37) % 1001) as f64 - 500.0).collect();
+        let expected = Stats::of(&values);
+        assert_eq!((expected.min, expected.max), (-500.0, 500.0));
+        // Integers this small sum exactly in any order
+        for stats in [reduce_atomic(&values), reduce_per_thread(&values)] {
+            assert!(stats == expected);
+        }
+        assert!(Stats::of(&[]) == Stats::EMPTY);
+        assert!(Stats::of(&[2.0]).merge(Stats::of(&[-1.0, 4.0])) == Stats { sum: 5.0, min: -1.0, max: 4.0 });
+    }
+
+    #[test]
+    fn atomic_update_applies_the_function() {
+        let cell = AtomicU64::new(1.5f64.to_bits());
+        atomic_update(&cell, |x| x * 4.0);
+        atomic_update(&cell, |x| x.min(2.0));
+        assert_eq!(f64::from_bits(cell.load(Ordering::Relaxed)), 2.0);
+    }
+}
@@ -0,0 +1,263 @@
+use fast_image_resize as fr;
+use image::imageops::FilterType;
+use std::f64::consts::PI;
+use std::time::{Duration, Instant};
+
+const SRC_WIDTH: usize = 6000;   // 24 MP source
+const SRC_HEIGHT: usize = 4000;
+const TARGETS: [(usize, usize); 3] = [(3000, 2000), (1280, 853), (7200, 4800)];
+const CHANNELS: usize = 3;
+
+#[derive(Clone, Copy)]
+enum Filter {
+    Bilinear,
+    Lanczos3,
+}
+
+impl Filter {
+    const ALL: [Filter; 2] = [Filter::Bilinear, Filter::Lanczos3];
+
+    fn name(self) -> &'static str {
+        match self {
+            Filter::Bilinear => "bilinear",
+            Filter::Lanczos3 => "lanczos3",
+        }
+    }
+
+    fn support(self) -> f64 {
+        match self {
+            Filter::Bilinear => 1.0,
+            Filter::Lanczos3 => 3.0,
+        }
+    }
+
+    fn weight(self, x: f64) -> f64 {
+        let x = x.abs();
+        match self {
+            Filter::Bilinear => (1.0 - x).max(0.0),
+            Filter::Lanczos3 if x < 3.0 => sinc(x) * sinc(x / 3.0),
+            Filter::Lanczos3 => 0.0,
+        }
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Gradients, a checkerboard and per-pixel noise, so every filter tap matters
+fn source_image() -> Vec<u8> {
+    let mut seed = 42u64;
+    let mut pixels = Vec::with_capacity(SRC_WIDTH * SRC_HEIGHT * CHANNELS);
+    for y in 0..SRC_HEIGHT {
+        for x in 0..SRC_WIDTH {
+            let noise = next_random(&mut seed);
+            pixels.push((x * 223 / SRC_WIDTH + (noise & 31) as usize) as u8);
+            pixels.push((y * 223 / SRC_HEIGHT + ((noise >> 5) & 31) as usize) as u8);
+            pixels.push((((x / 48 + y / 48) % 2) * 192 + ((noise >> 10) & 63) as usize) as u8);
+        }
+    }
+    pixels
+}
+
+// Per output coordinate: the first source index and normalized weights (Pillow-style,
+// with the kernel widened by the scale factor when downsampling)
+struct Coefficients {
+    starts: Vec<usize>,
+    weights: Vec<Vec<f32>>,
+}
+
+fn coefficients(filter: Filter, in_size: usize, out_size: usize) -> Coefficients {
+    let scale = in_size as f64 / out_size as f64;
+    let filter_scale = scale.max(1.0);
+    let support = filter.support() * filter_scale;
+    let mut starts = Vec::with_capacity(out_size);
+    let mut weights = Vec::with_capacity(out_size);
+    for i in 0..out_size {
+        let center = (i as f64 + 0.5) * scale;
+        let start = ((center - support + 0.5) as isize).max(0) as usize;
+        let end = ((center + support + 0.5) as usize).min(in_size);
+        let raw: Vec<f64> = (start..end)
+            .map(|x| filter.weight((x as f64 - center + 0.5) / filter_scale))
+            .collect();
+        let total: f64 = raw.iter().sum();
+        starts.push(start);
+        weights.push(raw.iter().map(|w| (w / total) as f32).collect());
+    }
+    Coefficients { starts, weights }
+}
+
+// Separable resample: horizontal pass into an f32 buffer, then vertical pass to u8
+fn resize(src: &[u8], width: usize, height: usize, out_width: usize, out_height: usize, filter: Filter) -> Vec<u8> {
+    let horizontal = coefficients(filter, width, out_width);
+    let vertical = coefficients(filter, height, out_height);
+
+    let row_len = out_width * CHANNELS;
+    let mut tmp = vec![0.0f32; row_len * height];
+    for (src_row, tmp_row) in src.chunks_exact(width * CHANNELS).zip(tmp.chunks_exact_mut(row_len)) {
+        for (x, out) in tmp_row.chunks_exact_mut(CHANNELS).enumerate() {
+            let start = horizontal.starts[x] * CHANNELS;
+            let mut acc = [0.0f32; CHANNELS];
+            for (k, &w) in horizontal.weights[x].iter().enumerate() {
+                let p = &src_row[start + k * CHANNELS..start + (k + 1) * CHANNELS];
+                for c in 0..CHANNELS {
+                    acc[c] = w.mul_add(p[c] as f32, acc[c]);
+                }
+            }
+            out.copy_from_slice(&acc);
+        }
+    }
+
+    let mut dst = vec![0u8; row_len * out_height];
+    let mut acc = vec![0.0f32; row_len];
+    for (y, dst_row) in dst.chunks_exact_mut(row_len).enumerate() {
+        acc.fill(0.0);
+        for (k, &w) in vertical.weights[y].iter().enumerate() {
+            let row = vertical.starts[y] + k;
+            for (a, &t) in acc.iter_mut().zip(&tmp[row * row_len..(row + 1) * row_len]) {
+                *a = w.mul_add(t, *a);
+            }
+        }
+        for (d, &a) in dst_row.iter_mut().zip(&acc) {
+            *d = a.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+    dst
+}
+
+fn resize_image_crate(src: &image::RgbImage, out_width: usize, out_height: usize, filter: Filter) -> Vec<u8> {
+    let filter = match filter {
+        Filter::Bilinear => FilterType::Triangle,
+        Filter::Lanczos3 => FilterType::Lanczos3,
+    };
+    image::imageops::resize(src, out_width as u32, out_height as u32, filter).into_raw()
+}
+
+fn resize_fast(resizer: &mut fr::Resizer, src: &fr::images::Image, out_width: usize, out_height: usize, filter: Filter) -> Vec<u8> {
+    let filter = match filter {
+        Filter::Bilinear => fr::FilterType::Bilinear,
+        Filter::Lanczos3 => fr::FilterType::Lanczos3,
+    };
+    let mut dst = fr::images::Image::new(out_width as u32, out_height as u32, fr::PixelType::U8x3);
+    let options = fr::ResizeOptions::new().resize_alg(fr::ResizeAlg::Convolution(filter));
+    resizer.resize(src, &mut dst, &options).expect("fast_image_resize failed");
+    dst.into_vec()
+}
+
+fn max_diff(a: &[u8], b: &[u8]) -> u8 {
+    a.iter().zip(b).map(|(&x, &y)| x.abs_diff(y)).max().unwrap_or(0)
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let result = f();
+    (start.elapsed(), result)
+}
+
+fn main() {
+    let src = source_image();
+    let src_image = image::RgbImage::from_raw(SRC_WIDTH as u32, SRC_HEIGHT as u32, src.clone()).expect("Bad source buffer");
+    let src_fast = fr::images::Image::from_vec_u8(SRC_WIDTH as u32, SRC_HEIGHT as u32, src.clone(), fr::PixelType::U8x3)
+        .expect("Bad source buffer");
+    let mut resizer = fr::Resizer::new();
+
+    // Warm-up
+    let _ = resize(&src, SRC_WIDTH, SRC_HEIGHT, 640, 427, Filter::Bilinear);
+
+    let mut total = Duration::ZERO;
+    let mut checksum = 0u64;
+    eprintln!("Source: {}x{} RGB, output MPixels/s (max diff vs hand-rolled)", SRC_WIDTH, SRC_HEIGHT);
+    eprintln!("{:>8} {:>10}  {:>12}  {:>18}  {:>18}", "Filter", "Target", "hand-rolled", "image", "fast_image_resize");
+    for filter in Filter::ALL {
+        for &(w, h) in &TARGETS {
+            let (hand_time, hand) = timed(|| resize(&src, SRC_WIDTH, SRC_HEIGHT, w, h, filter));
+            let (image_time, via_image) = timed(|| resize_image_crate(&src_image, w, h, filter));
+            let (fast_time, via_fast) = timed(|| resize_fast(&mut resizer, &src_fast, w, h, filter));
+
+            let mpix = |d: Duration| (w * h) as f64 / d.as_secs_f64() / 1e6;
+            eprintln!(
+                "{:>8} {:>10}  {:>12.1}  {:>12.1} ({:>3})  {:>12.1} ({:>3})",
+                filter.name(),
+                format!("{}x{}", w, h),
+                mpix(hand_time),
+                mpix(image_time),
+                max_diff(&hand, &via_image),
+                mpix(fast_time),
+                max_diff(&hand, &via_fast)
+            );
+            total += hand_time;
+            checksum = hand.iter().fold(checksum, |acc, &b| acc.wrapping_add(b as u64));
+        }
+    }
+
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise(width: usize, height: usize) -> Vec<u8> {
+        let mut seed = 3u64;
+        (0..width * height * CHANNELS).map(|_| next_random(&mut seed) as u8).collect()
+    }
+
+    #[test]
+    fn weights_are_normalized() {
+        for filter in Filter::ALL {
+            assert_eq!(filter.weight(0.0), 1.0);
+            assert!(filter.weight(filter.support()).abs() < 1e-12);
+            for (in_size, out_size) in [(600, 300), (600, 128), (600, 720), (7, 7)] {
+                let c = coefficients(filter, in_size, out_size);
+                for (start, weights) in c.starts.iter().zip(&c.weights) {
+                    assert!(start + weights.len() <= in_size);
+                    assert!((weights.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+                }
+            }
+        }
+        assert!(Filter::Lanczos3.weight(1.0).abs() < 1e-12);
+        assert_eq!(Filter::Bilinear.weight(0.25), 0.75);
+    }
+
+    #[test]
+    fn same_size_and_flat_images_are_unchanged() {
+        let src = noise(37, 23);
+        for filter in Filter::ALL {
+            assert_eq!(resize(&src, 37, 23, 37, 23, filter), src, "{}", filter.name());
+        }
+        // A constant image stays constant at any scale
+        let flat = vec![200u8; 60 * 40 * CHANNELS];
+        for (w, h) in [(30, 20), (13, 9), (72, 48)] {
+            for filter in Filter::ALL {
+                assert!(resize(&flat, 60, 40, w, h, filter).iter().all(|&p| p == 200));
+            }
+        }
+    }
+
+    #[test]
+    fn matches_the_library_resizers() {
+        let (width, height) = (120, 80);
+        let src = noise(width, height);
+        let src_image = image::RgbImage::from_raw(width as u32, height as u32, src.clone()).unwrap();
+        let src_fast = fr::images::Image::from_vec_u8(width as u32, height as u32, src.clone(), fr::PixelType::U8x3).unwrap();
+        let mut resizer = fr::Resizer::new();
+        // Rounding differences only
+        for (w, h) in [(60, 40), (144, 96)] {
+            let hand = resize(&src, width, height, w, h, Filter::Bilinear);
+            assert!(max_diff(&hand, &resize_fast(&mut resizer, &src_fast, w, h, Filter::Bilinear)) <= 2);
+            assert!(max_diff(&hand, &resize_image_crate(&src_image, w, h, Filter::Bilinear)) <= 2);
+        }
+    }
+}
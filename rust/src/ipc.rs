@@ -0,0 +1,350 @@
+use memmap2::MmapMut;
+use std::fs::OpenOptions;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+const STREAM_BYTES: usize = 1 << 30;
+const CHUNK_BYTES: usize = 64 << 10; // per send in the stream
+const PING_BYTES: usize = 64;
+const WARMUP_ROUND_TRIPS: usize = 1_000;
+const ROUND_TRIPS: usize = 50_000;
+const RING_BYTES: usize = 1 << 20; // data area of each shared-memory ring
+const RING_HEADER: usize = 256; // head and tail on cache lines of their own
+const SPINS_BEFORE_YIELD: u32 = 100;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// A byte stream each way between the two processes
+trait Channel {
+    fn send(&mut self, data: &[u8]);
+    fn recv(&mut self, buf: &mut [u8]);
+}
+
+// A pipe or a socket: write and read until every byte has gone through the kernel
+struct FdChannel<R, W> {
+    reader: R,
+    writer: W,
+}
+
+impl<R: Read, W: Write> Channel for FdChannel<R, W> {
+    fn send(&mut self, data: &[u8]) {
+        self.writer.write_all(data).expect("write failed");
+    }
+
+    fn recv(&mut self, buf: &mut [u8]) {
+        self.reader.read_exact(buf).expect("read failed");
+    }
+}
+
+// Back off from a busy-wait: spin briefly, then give the CPU to the other process
+fn wait_for_peer(spins: &mut u32) {
+    if *spins < SPINS_BEFORE_YIELD {
+        *spins += 1;
+        std::hint::spin_loop();
+    } else {
+        std::thread::yield_now();
+    }
+}
+
+// Single-producer single-consumer byte ring in memory shared by both processes. head counts bytes
+// written and tail bytes read; each side only stores its own and loads the other's
+struct Ring {
+    _map: Rc<MmapMut>,
+    base: *mut u8,
+}
+
+impl Ring {
+    // base must point at RING_HEADER + RING_BYTES zeroed bytes of map that no other ring uses
+    fn new(map: &Rc<MmapMut>, base: *mut u8) -> Self {
+        Ring { _map: Rc::clone(map), base }
+    }
+
+    fn head(&self) -> &AtomicU64 {
+        // SAFETY: the mapping is page aligned and offsets are multiples of 8, and it lives as long as self
+        unsafe { &*(self.base as *const AtomicU64) }
+    }
+
+    fn tail(&self) -> &AtomicU64 {
+        // SAFETY: as for head
+        unsafe { &*(self.base.add(RING_HEADER / 2) as *const AtomicU64) }
+    }
+
+    fn write(&self, mut src: &[u8]) {
+        let mut head = self.head().load(Ordering::Relaxed);
+        let mut spins = 0;
+        while !src.is_empty() {
+            let free = RING_BYTES - (head - self.tail().load(Ordering::Acquire)) as usize;
+            if free == 0 {
+                wait_for_peer(&mut spins);
+                continue;
+            }
+            let at = head as usize % RING_BYTES;
+            let n = src.len().min(free).min(RING_BYTES - at);
+            // SAFETY: at + n stays inside the data area, and the reader has released those bytes
+            unsafe { std::ptr::copy_nonoverlapping(src.as_ptr(), self.base.add(RING_HEADER + at), n) };
+            head += n as u64;
+            src = &src[n..];
+            self.head().store(head, Ordering::Release);
+            spins = 0;
+        }
+    }
+
+    fn read(&self, mut dst: &mut [u8]) {
+        let mut tail = self.tail().load(Ordering::Relaxed);
+        let mut spins = 0;
+        while !dst.is_empty() {
+            let available = (self.head().load(Ordering::Acquire) - tail) as usize;
+            if available == 0 {
+                wait_for_peer(&mut spins);
+                continue;
+            }
+            let at = tail as usize % RING_BYTES;
+            let n = dst.len().min(available).min(RING_BYTES - at);
+            // SAFETY: at + n stays inside the data area, and the writer has published those bytes
+            unsafe { std::ptr::copy_nonoverlapping(self.base.add(RING_HEADER + at), dst.as_mut_ptr(), n) };
+            tail += n as u64;
+            dst = &mut dst[n..];
+            self.tail().store(tail, Ordering::Release);
+            spins = 0;
+        }
+    }
+}
+
+struct ShmChannel {
+    tx: Ring,
+    rx: Ring,
+}
+
+impl Channel for ShmChannel {
+    fn send(&mut self, data: &[u8]) {
+        self.tx.write(data);
+    }
+
+    fn recv(&mut self, buf: &mut [u8]) {
+        self.rx.read(buf);
+    }
+}
+
+struct Endpoints {
+    parent: Box<dyn Channel>,
+    child: Box<dyn Channel>,
+}
+
+fn make_pipes() -> Endpoints {
+    let (down_rx, down_tx) = std::io::pipe().unwrap();
+    let (up_rx, up_tx) = std::io::pipe().unwrap();
+    Endpoints {
+        parent: Box::new(FdChannel { reader: up_rx, writer: down_tx }),
+        child: Box::new(FdChannel { reader: down_rx, writer: up_tx }),
+    }
+}
+
+fn make_socket() -> Endpoints {
+    let (parent, child) = UnixStream::pair().unwrap();
+    Endpoints {
+        parent: Box::new(FdChannel { reader: parent.try_clone().unwrap(), writer: parent }),
+        child: Box::new(FdChannel { reader: child.try_clone().unwrap(), writer: child }),
+    }
+}
+
+// Both rings in one shared mapping of an unlinked file, on tmpfs where there is one. fork hands the
+// mapping to the child at the same address (memmap2's anonymous maps are private, which fork would copy)
+fn make_shared_ring() -> Endpoints {
+    let ring = RING_HEADER + RING_BYTES;
+    let dir = if std::fs::metadata("/dev/shm").is_ok() { PathBuf::from("/dev/shm") } else { std::env::temp_dir() };
+    let path = dir.join(format!("ipc_ring_{}", std::process::id()));
+    let file = OpenOptions::new().read(true).write(true).create_new(true).open(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    file.set_len(2 * ring as u64).unwrap();
+    // SAFETY: the file was unlinked before anyone else could open it, so only this process and its
+    // children see the mapping
+    let mut map = unsafe { MmapMut::map_mut(&file) }.unwrap();
+    let down = map.as_mut_ptr();
+    let up = down.wrapping_add(ring);
+    let map = Rc::new(map);
+    Endpoints {
+        parent: Box::new(ShmChannel { tx: Ring::new(&map, down), rx: Ring::new(&map, up) }),
+        child: Box::new(ShmChannel { tx: Ring::new(&map, up), rx: Ring::new(&map, down) }),
+    }
+}
+
+// The stream's message: random words, with the message index stamped over the first
+fn stream_chunk() -> Vec<u8> {
+    let mut seed = 42;
+    (0..CHUNK_BYTES / 8).flat_map(|_| next_random(&mut seed).to_le_bytes()).collect()
+}
+
+fn word_sum(data: &[u8]) -> u64 {
+    data.chunks_exact(8).fold(0u64, |sum, w| sum.wrapping_add(u64::from_le_bytes(w.try_into().unwrap())))
+}
+
+// What the parent expects back: every message's words, indices included, summed with wraparound
+fn expected_stream_sum(messages: u64) -> u64 {
+    let rest = word_sum(&stream_chunk()[8..]);
+    messages.wrapping_mul(rest).wrapping_add(messages * messages.saturating_sub(1) / 2)
+}
+
+// The child: sum the stream and report it, then echo every ping
+fn serve(channel: &mut dyn Channel) {
+    let mut buf = vec![0u8; CHUNK_BYTES];
+    let mut sum = 0u64;
+    for _ in 0..STREAM_BYTES / CHUNK_BYTES {
+        channel.recv(&mut buf);
+        sum = sum.wrapping_add(word_sum(&buf));
+    }
+    channel.send(&sum.to_le_bytes());
+
+    for _ in 0..WARMUP_ROUND_TRIPS + ROUND_TRIPS {
+        channel.recv(&mut buf[..PING_BYTES]);
+        channel.send(&buf[..PING_BYTES]);
+    }
+}
+
+struct Result {
+    name: &'static str,
+    stream: Duration,
+    round_trips: Duration,
+    sum: u64,
+}
+
+fn ping(channel: &mut dyn Channel, round: usize, message: &mut [u8]) {
+    message[..8].copy_from_slice(&(round as u64).to_le_bytes());
+    channel.send(message);
+    channel.recv(message);
+    let stamp = u64::from_le_bytes(message[..8].try_into().unwrap());
+    if stamp != round as u64 {
+        eprintln!("Ping {} came back as {}", round, stamp);
+        std::process::exit(1);
+    }
+}
+
+fn run(name: &'static str, endpoints: Endpoints) -> Result {
+    let Endpoints { parent, mut child } = endpoints;
+    // SAFETY: this process is single-threaded, and the child never returns from the branch below
+    let pid = unsafe { libc::fork() };
+    assert!(pid != -1, "fork failed");
+    if pid == 0 {
+        drop(parent);
+        // A panic must not unwind into the parent's code, so it becomes the exit status
+        let served = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| serve(child.as_mut())));
+        // SAFETY: _exit ends the child without running the parent's atexit handlers
+        unsafe { libc::_exit(if served.is_ok() { 0 } else { 1 }) };
+    }
+    drop(child);
+    let mut channel = parent;
+
+    // Stream: time until the child has summed the last byte and said so
+    let mut chunk = stream_chunk();
+    let start = Instant::now();
+    for message in 0..(STREAM_BYTES / CHUNK_BYTES) as u64 {
+        chunk[..8].copy_from_slice(&message.to_le_bytes());
+        channel.send(&chunk);
+    }
+    let mut sum = [0u8; 8];
+    channel.recv(&mut sum);
+    let stream = start.elapsed();
+
+    // Round trips, after a warm-up
+    let mut message = [0xA5u8; PING_BYTES];
+    for i in 0..WARMUP_ROUND_TRIPS {
+        ping(channel.as_mut(), i, &mut message);
+    }
+    let start = Instant::now();
+    for i in 0..ROUND_TRIPS {
+        ping(channel.as_mut(), WARMUP_ROUND_TRIPS + i, &mut message);
+    }
+    let round_trips = start.elapsed();
+
+    let mut status = 0;
+    // SAFETY: pid is a child of this process that has not been reaped yet
+    let reaped = unsafe { libc::waitpid(pid, &mut status, 0) };
+    if reaped != pid || !libc::WIFEXITED(status) || libc::WEXITSTATUS(status) != 0 {
+        eprintln!("{}: child did not exit cleanly", name);
+        std::process::exit(1);
+    }
+    Result { name, stream, round_trips, sum: u64::from_le_bytes(sum) }
+}
+
+fn main() {
+    let expected = expected_stream_sum((STREAM_BYTES / CHUNK_BYTES) as u64);
+
+    let results = [
+        run("pipe", make_pipes()),
+        run("unix socket", make_socket()),
+        run("shared-memory ring", make_shared_ring()),
+    ];
+
+    let mut total = Duration::ZERO;
+    for r in &results {
+        if r.sum != expected {
+            eprintln!("{} streamed a sum of {}, expected {}", r.name, r.sum, expected);
+            std::process::exit(1);
+        }
+        total += r.stream + r.round_trips;
+    }
+
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("{} MiB streamed in {} KiB messages, {} round trips of {} bytes", STREAM_BYTES >> 20, CHUNK_BYTES >> 10,
+              ROUND_TRIPS, PING_BYTES);
+    for r in &results {
+        eprintln!("  {:<20} {:7.2} GB/s   {:7.2} us per round trip", r.name,
+                  STREAM_BYTES as f64 / r.stream.as_secs_f64() / 1e9,
+                  r.round_trips.as_secs_f64() * 1e6 / ROUND_TRIPS as f64);
+    }
+    eprintln!("Checksum: {}", expected);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Both ends in one process, with uneven sizes so copies straddle the end of the data area
+    fn round_trip(endpoints: Endpoints) {
+        let Endpoints { mut parent, mut child } = endpoints;
+        let data: Vec<u8> = (0..RING_BYTES / 3).map(|i| (i % 251) as u8).collect();
+        let mut back = vec![0u8; data.len()];
+        for _ in 0..4 {
+            parent.send(&data);
+            child.recv(&mut back[..1000]);
+            child.recv(&mut back[1000..]);
+            assert_eq!(back, data);
+            child.send(&back[..PING_BYTES]);
+            parent.recv(&mut back[..PING_BYTES]);
+            assert_eq!(back[..PING_BYTES], data[..PING_BYTES]);
+        }
+    }
+
+    #[test]
+    fn shared_ring_wraps_around() {
+        round_trip(make_shared_ring());
+    }
+
+    #[test]
+    fn socket_carries_bytes_both_ways() {
+        let Endpoints { mut parent, mut child } = make_socket();
+        parent.send(b"ping");
+        let mut buf = [0u8; 4];
+        child.recv(&mut buf);
+        child.send(&buf);
+        parent.recv(&mut buf);
+        assert_eq!(&buf, b"ping");
+    }
+
+    #[test]
+    fn expected_sum_matches_the_messages() {
+        let mut chunk = stream_chunk();
+        let mut sum = 0u64;
+        for message in 0..5u64 {
+            chunk[..8].copy_from_slice(&message.to_le_bytes());
+            sum = sum.wrapping_add(word_sum(&chunk));
+        }
+        assert_eq!(expected_stream_sum(5), sum);
+    }
+}
@@ -0,0 +1,126 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+const NUM_ELEMENTS: usize = 50_000_000;
+const NUM_PASSES: usize = 10; // 500M elements through each variant
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn generate(seed: u64) -> Vec<u32> {
+    let mut seed = seed;
+    (0..NUM_ELEMENTS).map(|_| next_random(&mut seed) as u32).collect()
+}
+
+// The pipeline stages, shared by both variants so only the control flow differs
+#[inline]
+fn keep(x: u32) -> bool {
+    !x.is_multiple_of(3)
+}
+
+#[inline]
+fn transform(x: u32) -> u64 {
+    (x.wrapping_mul(0x9E37_79B1) >> 7) as u64
+}
+
+#[inline]
+fn combine(acc: u64, m: u64, y: u32) -> u64 {
+    acc.wrapping_add(m * y as u64) ^ (m >> 3)
+}
+
+// filter -> map -> zip -> fold, written as one iterator chain
+fn run_iterators(a: &[u32], b: &[u32]) -> u64 {
+    a.iter()
+        .filter(|&&x| keep(x))
+        .map(|&x| transform(x))
+        .zip(b.iter())
+        .fold(0u64, |acc, (m, &y)| combine(acc, m, y))
+}
+
+// The same pipeline as the loop you would write by hand; `j` is the zip cursor into `b`
+fn run_loop(a: &[u32], b: &[u32]) -> u64 {
+    let mut acc = 0u64;
+    let mut j = 0;
+    for &x in a {
+        if !keep(x) {
+            continue;
+        }
+        if j == b.len() {
+            break;
+        }
+        acc = combine(acc, transform(x), b[j]);
+        j += 1;
+    }
+    acc
+}
+
+fn run_passes(a: &[u32], b: &[u32], passes: usize, f: fn(&[u32], &[u32]) -> u64) -> u64 {
+    let mut checksum = 0u64;
+    for _ in 0..passes {
+        // black_box keeps the compiler from hoisting the pass out of the loop
+        checksum = checksum.wrapping_add(f(black_box(a), black_box(b)));
+    }
+    checksum
+}
+
+fn main() {
+    let a = generate(2024);
+    let b = generate(4048);
+
+    // Warm-up
+    let _ = run_passes(&a[..NUM_ELEMENTS / 100], &b, 1, run_iterators);
+    let _ = run_passes(&a[..NUM_ELEMENTS / 100], &b, 1, run_loop);
+
+    // Benchmark iterator chain
+    let start = Instant::now();
+    let iter_checksum = run_passes(&a, &b, NUM_PASSES, run_iterators);
+    let iter_duration = start.elapsed();
+
+    // Benchmark hand-written loop
+    let start = Instant::now();
+    let loop_checksum = run_passes(&a, &b, NUM_PASSES, run_loop);
+    let loop_duration = start.elapsed();
+
+    let total_duration = iter_duration + loop_duration;
+    let elements = (NUM_ELEMENTS * NUM_PASSES) as f64;
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Iterator chain: {:.3}s ({:.0}M elements/s)", iter_duration.as_secs_f64(),
+              elements / 1e6 / iter_duration.as_secs_f64());
+    eprintln!("Manual loop:    {:.3}s ({:.0}M elements/s)", loop_duration.as_secs_f64(),
+              elements / 1e6 / loop_duration.as_secs_f64());
+    eprintln!("Abstraction cost: {:.3}x", iter_duration.as_secs_f64() / loop_duration.as_secs_f64());
+    eprintln!("Checksum: {} (variants match: {})", iter_checksum, iter_checksum == loop_checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipeline_on_known_input() {
+        // 3 and 6 are filtered out; 1, 2, 4 pair with 10, 20, 30
+        let (a, b) = ([1, 3, 2, 6, 4], [10, 20, 30, 40]);
+        let expected = [(1, 10), (2, 20), (4, 30)]
+            .iter()
+            .fold(0u64, |acc, &(x, y)| combine(acc, transform(x), y));
+        assert_eq!(run_iterators(&a, &b), expected);
+        assert_eq!(run_loop(&a, &b), expected);
+        assert_eq!(run_loop(&[], &b), 0);
+    }
+
+    #[test]
+    fn variants_agree_whichever_side_runs_out() {
+        let mut seed = 5u64;
+        let a: Vec<u32> = (0..10_000).map(|_| next_random(&mut seed) as u32).collect();
+        let b: Vec<u32> = (0..10_000).map(|_| next_random(&mut seed) as u32).collect();
+        for b_len in [0, 10, 6000, 10_000] {
+            assert_eq!(run_iterators(&a, &b[..b_len]), run_loop(&a, &b[..b_len]), "b_len {}", b_len);
+        }
+        assert_eq!(run_passes(&a, &b, 3, run_iterators), run_loop(&a, &b).wrapping_mul(3));
+    }
+}
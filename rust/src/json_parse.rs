@@ -1,8 +1,13 @@
 use serde::{Deserialize, Serialize};
-use serde_json;
+use std::str::FromStr;
 use std::time::Instant;
 
-#[derive(Serialize, Deserialize, Clone)]
+const RECORDS: usize = 10_000;
+// --corpus floats: embeddings the size of a small sentence-embedding model's
+const VECTORS: usize = 2_000;
+const DIMENSIONS: usize = 384;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 struct Record {
     id: u32,
     name: String,
@@ -10,63 +15,505 @@ struct Record {
     age: u32,
     balance: f64,
     is_active: bool,
+    // Optional fields, present in --optional-density of the records and omitted otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nickname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<f64>,
     tags: Vec<String>,
     metadata: Metadata,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 struct Metadata {
     created_at: String,
     updated_at: String,
     version: u32,
+    // Further levels of nesting for --depth above 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<Box<Metadata>>,
+}
+
+// Zero-copy view of the same document: strings borrow from the input buffer
+#[derive(Deserialize)]
+struct BorrowedRecord<'a> {
+    id: u32,
+    name: &'a str,
+    email: &'a str,
+    age: u32,
+    balance: f64,
+    is_active: bool,
+    #[serde(borrow)]
+    nickname: Option<&'a str>,
+    #[serde(borrow)]
+    phone: Option<&'a str>,
+    score: Option<f64>,
+    #[serde(borrow)]
+    tags: Vec<&'a str>,
+    #[serde(borrow)]
+    metadata: BorrowedMetadata<'a>,
+}
+
+#[derive(Deserialize)]
+struct BorrowedMetadata<'a> {
+    created_at: &'a str,
+    updated_at: &'a str,
+    version: u32,
+    #[serde(borrow)]
+    parent: Option<Box<BorrowedMetadata<'a>>>,
+}
+
+// A row of the float-heavy corpus: almost every byte of the document is a number, so its parse
+// time is number parsing, where the records above mostly copy strings
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Embedding {
+    id: u32,
+    vector: Vec<f32>,
+}
+
+// The document shape; the defaults reproduce the original 10k flat records
+struct Shape {
+    records: usize,
+    depth: usize,                 // levels of metadata objects, 1 = a single nested object
+    string_length: Option<usize>, // pad or cut name and nickname to this many bytes
+    optional_density: f64,        // fraction of records carrying each optional field
+}
+
+impl Shape {
+    // Deterministic per record and field, so both languages produce the same document
+    fn has_optional(&self, i: usize, field: usize) -> bool {
+        (i * 37 + field * 11) % 100 < (self.optional_density * 100.0).round() as usize
+    }
+
+    fn sized(&self, base: String) -> String {
+        match self.string_length {
+            Some(length) => base.chars().chain(std::iter::repeat('x')).take(length).collect(),
+            None => base,
+        }
+    }
+}
+
+// Folds every scalar and string length so the variants can be checked against each other
+fn digest(id: u32, strings: &[&str], age: u32, reals: [f64; 2], is_active: bool, tags: &[impl AsRef<str>], version: u32) -> u64 {
+    let lengths: usize = strings.iter().map(|s| s.len()).sum::<usize>() + tags.iter().map(|t| t.as_ref().len()).sum::<usize>();
+    let reals = reals.iter().fold(0u64, |acc, &x| acc.wrapping_add(x as u64));
+    (id as u64 + age as u64 + version as u64 + is_active as u64 + lengths as u64).wrapping_add(reals)
+}
+
+fn owned_digest(records: &[Record]) -> u64 {
+    records.iter().fold(0u64, |acc, r| {
+        let mut strings = vec![r.name.as_str(), r.email.as_str()];
+        strings.extend(r.nickname.as_deref());
+        strings.extend(r.phone.as_deref());
+        let mut version = 0u32;
+        for m in std::iter::successors(Some(&r.metadata), |m| m.parent.as_deref()) {
+            strings.extend([m.created_at.as_str(), m.updated_at.as_str()]);
+            version += m.version;
+        }
+        acc.wrapping_add(digest(r.id, &strings, r.age, [r.balance, r.score.unwrap_or(0.0)], r.is_active, &r.tags, version))
+    })
 }
 
-fn generate_records(count: usize) -> Vec<Record> {
-    (0..count)
+fn borrowed_digest(records: &[BorrowedRecord]) -> u64 {
+    records.iter().fold(0u64, |acc, r| {
+        let mut strings = vec![r.name, r.email];
+        strings.extend(r.nickname);
+        strings.extend(r.phone);
+        let mut version = 0u32;
+        for m in std::iter::successors(Some(&r.metadata), |m| m.parent.as_deref()) {
+            strings.extend([m.created_at, m.updated_at]);
+            version += m.version;
+        }
+        acc.wrapping_add(digest(r.id, &strings, r.age, [r.balance, r.score.unwrap_or(0.0)], r.is_active, &r.tags, version))
+    })
+}
+
+// The same fold over an untyped serde_json::Value tree, looking every field up by key
+fn value_digest(document: &serde_json::Value) -> u64 {
+    let records = document.as_array().expect("Expected an array of records");
+    records.iter().fold(0u64, |acc, r| {
+        let text = |key: &str| r[key].as_str().unwrap_or_default();
+        let number = |v: &serde_json::Value| v.as_u64().unwrap_or_default() as u32;
+        let mut strings = vec![text("name"), text("email")];
+        strings.extend(["nickname", "phone"].iter().filter_map(|key| r.get(*key).and_then(|v| v.as_str())));
+        let mut version = 0u32;
+        for m in std::iter::successors(r.get("metadata"), |m| m.get("parent")) {
+            strings.extend([m["created_at"].as_str().unwrap_or_default(), m["updated_at"].as_str().unwrap_or_default()]);
+            version += number(&m["version"]);
+        }
+        let tags: Vec<&str> = r["tags"].as_array().map_or(Vec::new(), |t| t.iter().filter_map(|t| t.as_str()).collect());
+        let reals = [r["balance"].as_f64().unwrap_or_default(), r.get("score").and_then(|s| s.as_f64()).unwrap_or(0.0)];
+        acc.wrapping_add(digest(number(&r["id"]), &strings, number(&r["age"]), reals, r["is_active"].as_bool() == Some(true),
+                                &tags, version))
+    })
+}
+
+// Folds every float's bits, so a parse that lands one ulp off shows up
+fn embedding_digest(embeddings: &[Embedding]) -> u64 {
+    embeddings.iter().fold(0u64, |acc, e| {
+        e.vector.iter().fold(acc.wrapping_add(e.id as u64), |acc, x| acc.wrapping_add(x.to_bits() as u64))
+    })
+}
+
+fn embedding_value_digest(document: &serde_json::Value) -> u64 {
+    let embeddings = document.as_array().expect("Expected an array of embeddings");
+    embeddings.iter().fold(0u64, |acc, e| {
+        let vector = e["vector"].as_array().map_or(&[][..], |v| v.as_slice());
+        vector.iter().fold(acc.wrapping_add(e["id"].as_u64().unwrap_or_default()), |acc, x| {
+            acc.wrapping_add((x.as_f64().unwrap_or_default() as f32).to_bits() as u64)
+        })
+    })
+}
+
+// Uniform in [-1, 1) and 24 bits deep, like a normalized feature, so most values print with
+// eight or nine significant digits; the same bits in both languages
+fn embedding_value(index: u64) -> f32 {
+    let h = index.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    let h = (h ^ (h >> 31)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    (h >> 40) as f32 / (1u32 << 23) as f32 - 1.0
+}
+
+fn generate_embeddings(vectors: usize, dimensions: usize) -> Vec<Embedding> {
+    (0..vectors)
+        .map(|i| Embedding {
+            id: i as u32,
+            vector: (0..dimensions).map(|j| embedding_value((i * dimensions + j) as u64)).collect(),
+        })
+        .collect()
+}
+
+fn generate_metadata(i: usize, level: usize, depth: usize) -> Metadata {
+    Metadata {
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        updated_at: "2024-01-02T00:00:00Z".to_string(),
+        version: ((i + level) % 100) as u32,
+        parent: (level + 1 < depth).then(|| Box::new(generate_metadata(i, level + 1, depth))),
+    }
+}
+
+fn generate_records(shape: &Shape) -> Vec<Record> {
+    (0..shape.records)
         .map(|i| Record {
             id: i as u32,
-            name: format!("User {}", i),
+            name: shape.sized(format!("User {}", i)),
             email: format!("user{}@example.com", i),
             age: 20 + (i % 50) as u32,
             balance: (i as f64) * 123.456,
             is_active: i % 2 == 0,
+            nickname: shape.has_optional(i, 0).then(|| shape.sized(format!("nick{}", i))),
+            phone: shape.has_optional(i, 1).then(|| format!("+1-555-{:04}", i % 10_000)),
+            score: shape.has_optional(i, 2).then(|| (i % 1000) as f64 / 8.0),
             tags: vec![
                 format!("tag{}", i % 10),
                 format!("category{}", i % 5),
                 "important".to_string(),
             ],
-            metadata: Metadata {
-                created_at: "2024-01-01T00:00:00Z".to_string(),
-                updated_at: "2024-01-02T00:00:00Z".to_string(),
-                version: (i % 100) as u32,
-            },
+            metadata: generate_metadata(i, 0, shape.depth),
         })
         .collect()
 }
 
+fn parse_flag<T: FromStr>(args: &[String], name: &str, expected: &str) -> Option<T> {
+    args.iter().position(|a| a == name).map(|i| {
+        args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| panic!("{} expects {}", name, expected))
+    })
+}
+
+fn parse_shape(args: &[String]) -> Shape {
+    let shape = Shape {
+        records: parse_flag(args, "--records", "a record count").unwrap_or(RECORDS),
+        depth: parse_flag(args, "--depth", "a nesting depth from 1 to 100").unwrap_or(1),
+        string_length: parse_flag(args, "--string-length", "a length in bytes"),
+        optional_density: parse_flag(args, "--optional-density", "a fraction from 0 to 1").unwrap_or(0.0),
+    };
+    // serde_json stops at 128 levels of nesting
+    if !(1..=100).contains(&shape.depth) {
+        panic!("--depth expects a nesting depth from 1 to 100");
+    }
+    if !(0.0..=1.0).contains(&shape.optional_density) {
+        panic!("--optional-density expects a fraction from 0 to 1");
+    }
+    shape
+}
+
+enum Corpus {
+    Records(Shape),
+    Floats { vectors: usize, dimensions: usize },
+}
+
+fn parse_corpus(args: &[String]) -> Corpus {
+    match parse_flag::<String>(args, "--corpus", "records or floats").as_deref() {
+        None | Some("records") => Corpus::Records(parse_shape(args)),
+        Some("floats") => {
+            if let Some(flag) = ["--depth", "--string-length", "--optional-density"].iter().find(|f| args.iter().any(|a| a == *f)) {
+                panic!("{} only shapes --corpus records", flag);
+            }
+            Corpus::Floats {
+                vectors: parse_flag(args, "--records", "a record count").unwrap_or(VECTORS),
+                dimensions: parse_flag(args, "--dimensions", "a vector length").unwrap_or(DIMENSIONS),
+            }
+        }
+        Some(_) => panic!("--corpus expects records or floats"),
+    }
+}
+
+// The same parse and serialize round trip over embeddings, with the parsers that apply to a
+// document without strings: serde_json into structs and into a Value tree, and simd-json
+fn run_floats(vectors: usize, dimensions: usize, json_output: bool, mut measure: Box<dyn bench_measure::Measurer>) {
+    let embeddings = generate_embeddings(vectors, dimensions);
+    let json_string = serde_json::to_string(&embeddings).expect("Failed to serialize");
+
+    // Warm-up
+    let _ = serde_json::from_str::<Vec<Embedding>>(&json_string).expect("Failed to parse");
+    let _ = serde_json::from_str::<serde_json::Value>(&json_string).expect("Failed to parse");
+    let _ = simd_json::serde::from_slice::<Vec<Embedding>>(&mut json_string.clone().into_bytes()).expect("Failed to parse");
+
+    let start = Instant::now();
+    measure.start();
+    let parsed: Vec<Embedding> = serde_json::from_str(&json_string).expect("Failed to parse");
+    measure.stop();
+    let parse_duration = start.elapsed();
+
+    let start = Instant::now();
+    measure.start();
+    let serialized = serde_json::to_string(&parsed).expect("Failed to serialize");
+    measure.stop();
+    let serialize_duration = start.elapsed();
+
+    let start = Instant::now();
+    let dom: serde_json::Value = serde_json::from_str(&json_string).expect("Failed to parse");
+    let dom_duration = start.elapsed();
+
+    let mut simd_input = json_string.clone().into_bytes();
+    let start = Instant::now();
+    let simd_parsed: Vec<Embedding> = simd_json::serde::from_slice(&mut simd_input).expect("Failed to parse");
+    let simd_duration = start.elapsed();
+
+    let megabytes = json_string.len() as f64 / 1e6;
+    let numbers = vectors * dimensions;
+    let expected = embedding_digest(&embeddings);
+    let checksum = embedding_digest(&parsed);
+    let matches = checksum == expected && checksum == embedding_value_digest(&dom) && checksum == embedding_digest(&simd_parsed);
+
+    measure.report();
+    if json_output {
+        let report = serde_json::json!({
+            "corpus": "floats",
+            "shape": { "records": vectors, "dimensions": dimensions },
+            "bytes": serialized.len(),
+            "parse": { "seconds": parse_duration.as_secs_f64(), "mb_per_s": megabytes / parse_duration.as_secs_f64() },
+            "serialize": {
+                "seconds": serialize_duration.as_secs_f64(),
+                "mb_per_s": megabytes / serialize_duration.as_secs_f64(),
+            },
+            "parse_variants_mb_per_s": {
+                "serde_json_owned": megabytes / parse_duration.as_secs_f64(),
+                "serde_json_value": megabytes / dom_duration.as_secs_f64(),
+                "simd_json_owned": megabytes / simd_duration.as_secs_f64(),
+            },
+            "checksum": checksum,
+            "variants_match": matches,
+        });
+        eprintln!("{}", report);
+        return;
+    }
+    eprintln!("Corpus: floats, {} vectors of {} dimensions", vectors, dimensions);
+    eprintln!("Parse: {:.6}s, Serialize: {:.6}s", parse_duration.as_secs_f64(), serialize_duration.as_secs_f64());
+    eprintln!("Parse: {:.1} MB/s, Serialize: {:.1} MB/s", megabytes / parse_duration.as_secs_f64(),
+              megabytes / serialize_duration.as_secs_f64());
+    eprintln!("Numbers: {} ({:.1} M/s parsed), JSON size: {} bytes", numbers,
+              numbers as f64 / 1e6 / parse_duration.as_secs_f64(), serialized.len());
+    eprintln!("Parse variants (MB/s): serde_json owned {:.1}, serde_json Value {:.1}, simd-json owned {:.1}",
+              megabytes / parse_duration.as_secs_f64(), megabytes / dom_duration.as_secs_f64(),
+              megabytes / simd_duration.as_secs_f64());
+    eprintln!("Checksum: {} (all variants match: {})", checksum, matches);
+}
+
 fn main() {
-    let records = generate_records(10_000);
+    let measure = bench_measure::from_env();
+    let args: Vec<String> = std::env::args().collect();
+    let json_output = args.iter().any(|a| a == "--json");
+    let shape = match parse_corpus(&args) {
+        Corpus::Records(shape) => shape,
+        Corpus::Floats { vectors, dimensions } => return run_floats(vectors, dimensions, json_output, measure),
+    };
+    let mut measure = measure;
+    let records = generate_records(&shape);
     
     // Serialize
     let json_string = serde_json::to_string(&records).expect("Failed to serialize");
     
     // Warm-up
     let _ = serde_json::from_str::<Vec<Record>>(&json_string).expect("Failed to parse");
+    let _ = serde_json::from_str::<Vec<BorrowedRecord>>(&json_string).expect("Failed to parse");
+    let _ = serde_json::from_str::<serde_json::Value>(&json_string).expect("Failed to parse");
+    let _ = simd_json::serde::from_slice::<Vec<Record>>(&mut json_string.clone().into_bytes()).expect("Failed to parse");
     
     // Benchmark parse
     let start = Instant::now();
+    measure.start();
     let parsed: Vec<Record> = serde_json::from_str(&json_string).expect("Failed to parse");
+    measure.stop();
     let parse_duration = start.elapsed();
     
     // Benchmark serialize
     let start = Instant::now();
+    measure.start();
     let serialized = serde_json::to_string(&parsed).expect("Failed to serialize");
+    measure.stop();
     let serialize_duration = start.elapsed();
     
-    let total_duration = parse_duration + serialize_duration;
+    // Parse variants, reported alongside the owned serde_json round-trip
+    let start = Instant::now();
+    let borrowed: Vec<BorrowedRecord> = serde_json::from_str(&json_string).expect("Failed to parse");
+    let borrowed_duration = start.elapsed();
+
+    // Untyped DOM: build a serde_json::Value tree, then walk it for the checksum
+    let start = Instant::now();
+    let dom: serde_json::Value = serde_json::from_str(&json_string).expect("Failed to parse");
+    let dom_duration = start.elapsed();
+    let start = Instant::now();
+    let dom_digest = value_digest(&dom);
+    let dom_walk_duration = start.elapsed();
+
+    // simd-json parses in place, so it gets its own copy of the input (not timed)
+    let mut simd_input = json_string.clone().into_bytes();
+    let start = Instant::now();
+    let simd_parsed: Vec<Record> = simd_json::serde::from_slice(&mut simd_input).expect("Failed to parse");
+    let simd_duration = start.elapsed();
+
+    let megabytes = json_string.len() as f64 / 1e6;
+    let owned = owned_digest(&parsed);
+    let matches = owned == borrowed_digest(&borrowed) && owned == owned_digest(&simd_parsed) && owned == dom_digest;
     
-    println!("{:.6}", total_duration.as_secs_f64());
+    measure.report();
+    if json_output {
+        // One machine-readable line instead of the text report
+        let report = serde_json::json!({
+            "corpus": "records",
+            "shape": {
+                "records": shape.records,
+                "depth": shape.depth,
+                "string_length": shape.string_length,
+                "optional_density": shape.optional_density,
+            },
+            "bytes": serialized.len(),
+            "parse": { "seconds": parse_duration.as_secs_f64(), "mb_per_s": megabytes / parse_duration.as_secs_f64() },
+            "serialize": {
+                "seconds": serialize_duration.as_secs_f64(),
+                "mb_per_s": megabytes / serialize_duration.as_secs_f64(),
+            },
+            "parse_variants_mb_per_s": {
+                "serde_json_owned": megabytes / parse_duration.as_secs_f64(),
+                "serde_json_borrowed": megabytes / borrowed_duration.as_secs_f64(),
+                "serde_json_value": megabytes / dom_duration.as_secs_f64(),
+                "simd_json_owned": megabytes / simd_duration.as_secs_f64(),
+            },
+            "dom_walk_seconds": dom_walk_duration.as_secs_f64(),
+            "checksum": owned,
+            "variants_match": matches,
+        });
+        eprintln!("{}", report);
+        return;
+    }
+    eprintln!("Shape: {} records, depth {}, string length {}, optional density {}", shape.records, shape.depth,
+              shape.string_length.map_or("default".to_string(), |l| l.to_string()), shape.optional_density);
     eprintln!("Parse: {:.6}s, Serialize: {:.6}s", parse_duration.as_secs_f64(), serialize_duration.as_secs_f64());
+    eprintln!("Parse: {:.1} MB/s, Serialize: {:.1} MB/s", megabytes / parse_duration.as_secs_f64(),
+              megabytes / serialize_duration.as_secs_f64());
     eprintln!("Records: {}, JSON size: {} bytes", parsed.len(), serialized.len());
+    eprintln!("Parse variants (MB/s): serde_json owned {:.1}, serde_json borrowed {:.1}, serde_json Value {:.1}, simd-json owned {:.1}",
+              megabytes / parse_duration.as_secs_f64(), megabytes / borrowed_duration.as_secs_f64(),
+              megabytes / dom_duration.as_secs_f64(), megabytes / simd_duration.as_secs_f64());
+    eprintln!("DOM walk: {:.6}s over {} records", dom_walk_duration.as_secs_f64(), dom.as_array().map_or(0, |a| a.len()));
+    eprintln!("Checksum: {} (all variants match: {})", owned, matches);
+}
+
+// Without its float_roundtrip feature serde_json may parse a float one ulp off (1851.84 comes back
+// as 1851.8400000000001), so reals are compared to within a few ulps and everything else exactly.
+// Shared by the tests below and the round-trip property in props/
+#[cfg(test)]
+fn same(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    use serde_json::Value;
+    match (a, b) {
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(a, b)),
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| same(a, b)))
+        }
+        (Value::Number(a), Value::Number(b)) if a.is_f64() && b.is_f64() => {
+            let (a, b) = (a.as_f64().unwrap(), b.as_f64().unwrap());
+            (a - b).abs() <= 4.0 * f64::EPSILON * a.abs()
+        }
+        _ => a == b,
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(records: usize, depth: usize, string_length: Option<usize>, optional_density: f64) -> Shape {
+        Shape { records, depth, string_length, optional_density }
+    }
+
+    #[test]
+    fn serializes_a_known_record() {
+        let records = generate_records(&shape(1, 1, None, 0.0));
+        assert_eq!(
+            serde_json::to_string(&records).unwrap(),
+            r#"[{"id":0,"name":"User 0","email":"user0@example.com","age":20,"balance":0.0,"is_active":true,"#.to_string()
+                + r#""tags":["tag0","category0","important"],"#
+                + r#""metadata":{"created_at":"2024-01-01T00:00:00Z","updated_at":"2024-01-02T00:00:00Z","version":0}}]"#
+        );
+    }
+
+    #[test]
+    fn round_trip_is_identity() {
+        for shape in [shape(200, 1, None, 0.0), shape(200, 5, Some(40), 0.5), shape(50, 100, Some(2), 1.0)] {
+            let records = generate_records(&shape);
+            let original = serde_json::to_value(&records).unwrap();
+            let json = serde_json::to_string(&records).unwrap();
+            let parsed: Vec<Record> = serde_json::from_str(&json).unwrap();
+            assert!(same(&serde_json::to_value(&parsed).unwrap(), &original));
+            let simd: Vec<Record> = simd_json::serde::from_slice(&mut json.clone().into_bytes()).unwrap();
+            assert!(same(&serde_json::to_value(&simd).unwrap(), &original));
+            assert_eq!(owned_digest(&parsed), owned_digest(&records));
+        }
+    }
+
+    #[test]
+    fn parse_variants_agree() {
+        let shape = shape(300, 3, Some(12), 0.3);
+        let records = generate_records(&shape);
+        let json = serde_json::to_string(&records).unwrap();
+        let expected = owned_digest(&records);
+        assert_eq!(borrowed_digest(&serde_json::from_str::<Vec<BorrowedRecord>>(&json).unwrap()), expected);
+        assert_eq!(value_digest(&serde_json::from_str(&json).unwrap()), expected);
+    }
+
+    #[test]
+    fn shape_controls_optional_fields_and_lengths() {
+        let records = generate_records(&shape(100, 4, Some(3), 0.25));
+        assert_eq!(records.iter().filter(|r| r.nickname.is_some()).count(), 25);
+        assert!(records.iter().all(|r| r.name.len() == 3));
+        assert!(records.iter().flat_map(|r| &r.nickname).all(|n| n.len() == 3));
+        let levels = std::iter::successors(Some(&records[0].metadata), |m| m.parent.as_deref()).count();
+        assert_eq!(levels, 4);
+    }
+
+    #[test]
+    fn float_corpus_parses_to_the_same_bits() {
+        let embeddings = generate_embeddings(200, 96);
+        assert!(embeddings.iter().flat_map(|e| &e.vector).all(|x| (-1.0..1.0).contains(x)));
+        let json = serde_json::to_string(&embeddings).unwrap();
+        let expected = embedding_digest(&embeddings);
+        assert_eq!(embedding_digest(&serde_json::from_str::<Vec<Embedding>>(&json).unwrap()), expected);
+        assert_eq!(embedding_value_digest(&serde_json::from_str(&json).unwrap()), expected);
+        let simd: Vec<Embedding> = simd_json::serde::from_slice(&mut json.into_bytes()).unwrap();
+        assert_eq!(embedding_digest(&simd), expected);
+    }
+}
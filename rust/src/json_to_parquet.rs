@@ -0,0 +1,305 @@
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, UInt32Type};
+use arrow_array::RecordBatch;
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef};
+use bytes::Bytes;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Instant;
+
+const DEFAULT_RECORDS: usize = 1_000_000;
+const WARMUP_RECORDS: usize = 50_000;
+const BATCH_ROWS: usize = 65_536; // rows per decoded RecordBatch
+const ROW_GROUP_ROWS: usize = 131_072;
+const OPTIONAL_PERCENT: usize = 50; // share of records carrying each optional field
+
+// json_parse's record at depth 1, with half of the optional fields present
+#[derive(Serialize)]
+struct Record {
+    id: u32,
+    name: String,
+    email: String,
+    age: u32,
+    balance: f64,
+    is_active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nickname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<f64>,
+    tags: Vec<String>,
+    metadata: Metadata,
+}
+
+#[derive(Serialize)]
+struct Metadata {
+    created_at: String,
+    updated_at: String,
+    version: u32,
+}
+
+#[derive(Clone, Copy)]
+enum Codec {
+    Uncompressed,
+    Snappy,
+    Zstd,
+}
+
+impl Codec {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Codec::Uncompressed),
+            "snappy" => Some(Codec::Snappy),
+            "zstd" => Some(Codec::Zstd),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Codec::Uncompressed => "none",
+            Codec::Snappy => "snappy",
+            Codec::Zstd => "zstd",
+        }
+    }
+
+    fn compression(self) -> Compression {
+        match self {
+            Codec::Uncompressed => Compression::UNCOMPRESSED,
+            Codec::Snappy => Compression::SNAPPY,
+            Codec::Zstd => Compression::ZSTD(ZstdLevel::try_new(3).unwrap()),
+        }
+    }
+}
+
+// Deterministic per record and field, so both languages produce the same document
+fn has_optional(i: usize, field: usize) -> bool {
+    (i * 37 + field * 11) % 100 < OPTIONAL_PERCENT
+}
+
+fn generate_records(count: usize) -> Vec<Record> {
+    (0..count)
+        .map(|i| Record {
+            id: i as u32,
+            name: format!("User {}", i),
+            email: format!("user{}@example.com", i),
+            age: 20 + (i % 50) as u32,
+            balance: (i as f64) * 123.456,
+            is_active: i % 2 == 0,
+            nickname: has_optional(i, 0).then(|| format!("nick{}", i)),
+            phone: has_optional(i, 1).then(|| format!("+1-555-{:04}", i % 10_000)),
+            score: has_optional(i, 2).then(|| (i % 1000) as f64 / 8.0),
+            tags: vec![format!("tag{}", i % 10), format!("category{}", i % 5), "important".to_string()],
+            metadata: Metadata {
+                created_at: "2024-01-01T00:00:00Z".to_string(),
+                updated_at: "2024-01-02T00:00:00Z".to_string(),
+                version: (i % 100) as u32,
+            },
+        })
+        .collect()
+}
+
+// One record per line, the form both Arrow JSON readers take
+fn to_ndjson(records: &[Record]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut out, record).expect("Failed to serialize");
+        out.push(b'\n');
+    }
+    out
+}
+
+fn schema() -> SchemaRef {
+    let metadata = Fields::from(vec![
+        Field::new("created_at", DataType::Utf8, false),
+        Field::new("updated_at", DataType::Utf8, false),
+        Field::new("version", DataType::UInt32, false),
+    ]);
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::UInt32, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("email", DataType::Utf8, false),
+        Field::new("age", DataType::UInt32, false),
+        Field::new("balance", DataType::Float64, false),
+        Field::new("is_active", DataType::Boolean, false),
+        Field::new("nickname", DataType::Utf8, true),
+        Field::new("phone", DataType::Utf8, true),
+        Field::new("score", DataType::Float64, true),
+        Field::new("tags", DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))), false),
+        Field::new("metadata", DataType::Struct(metadata), false),
+    ]))
+}
+
+// JSON text to Arrow columns, against the explicit schema rather than an inferred one
+fn decode(ndjson: &[u8], schema: SchemaRef) -> Vec<RecordBatch> {
+    arrow_json::ReaderBuilder::new(schema)
+        .with_batch_size(BATCH_ROWS)
+        .build(ndjson)
+        .expect("Failed to build the JSON reader")
+        .collect::<Result<_, _>>()
+        .expect("Failed to decode JSON")
+}
+
+// Arrow columns to a Parquet file in memory, with dictionary encoding on (the default)
+fn encode(batches: &[RecordBatch], schema: SchemaRef, codec: Codec) -> Vec<u8> {
+    let properties = WriterProperties::builder()
+        .set_compression(codec.compression())
+        .set_max_row_group_row_count(Some(ROW_GROUP_ROWS))
+        .build();
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema, Some(properties)).expect("Failed to create the writer");
+    for batch in batches {
+        writer.write(batch).expect("Failed to write a batch");
+    }
+    writer.into_inner().expect("Failed to finish the file")
+}
+
+fn read_back(parquet: Vec<u8>) -> Vec<RecordBatch> {
+    ParquetRecordBatchReaderBuilder::try_new(Bytes::from(parquet))
+        .expect("Failed to open the Parquet file")
+        .with_batch_size(BATCH_ROWS)
+        .build()
+        .expect("Failed to build the Parquet reader")
+        .collect::<Result<_, _>>()
+        .expect("Failed to read the Parquet file")
+}
+
+// Folds every integer, flag, truncated real and string length, so the round trip can be checked
+// against the records it started from, and one language against the other
+#[derive(Default, PartialEq, Debug)]
+struct Digest {
+    rows: u64,
+    sum: u64,
+}
+
+impl Digest {
+    fn add(&mut self, value: u64) {
+        self.sum = self.sum.wrapping_add(value);
+    }
+
+    fn of_records(records: &[Record]) -> Self {
+        let mut digest = Digest::default();
+        for r in records {
+            digest.rows += 1;
+            digest.add(r.id as u64 + r.age as u64 + r.metadata.version as u64 + r.is_active as u64);
+            digest.add(r.balance as u64 + r.score.map_or(0, |s| s as u64));
+            let strings = [&r.name, &r.email, &r.metadata.created_at, &r.metadata.updated_at].into_iter()
+                .chain(r.nickname.iter())
+                .chain(r.phone.iter())
+                .chain(r.tags.iter());
+            digest.add(strings.map(|s| s.len() as u64).sum());
+        }
+        digest
+    }
+
+    fn of_batches(batches: &[RecordBatch]) -> Self {
+        let mut digest = Digest::default();
+        for batch in batches {
+            let column = |name: &str| batch.column_by_name(name).expect("Missing column");
+            let metadata = column("metadata").as_struct();
+            let field = |name: &str| metadata.column_by_name(name).expect("Missing field");
+            digest.rows += batch.num_rows() as u64;
+            for array in [column("id"), column("age"), field("version")] {
+                digest.add(array.as_primitive::<UInt32Type>().values().iter().map(|&v| v as u64).sum());
+            }
+            digest.add(column("is_active").as_boolean().true_count() as u64);
+            for array in [column("balance"), column("score")] {
+                digest.add(array.as_primitive::<Float64Type>().iter().flatten().map(|v| v as u64).sum());
+            }
+            for array in [column("name"), column("email"), column("nickname"), column("phone"), field("created_at"),
+                          field("updated_at"), column("tags").as_list::<i32>().values()] {
+                digest.add(array.as_string::<i32>().iter().flatten().map(|s| s.len() as u64).sum());
+            }
+        }
+        digest
+    }
+}
+
+fn parse_flag(args: &[String], name: &str) -> Option<String> {
+    args.iter().position(|a| a == name).map(|i| args.get(i + 1).cloned().unwrap_or_default())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let count = parse_flag(&args, "--records").map_or(DEFAULT_RECORDS, |v| {
+        v.parse().ok().filter(|&n: &usize| n > 0).expect("--records expects a positive integer")
+    });
+    let codec = parse_flag(&args, "--compression").map_or(Codec::Snappy, |v| {
+        Codec::parse(&v).expect("--compression expects none, snappy or zstd")
+    });
+    let records = generate_records(count);
+    let ndjson = to_ndjson(&records);
+    let schema = schema();
+
+    // Warm-up
+    let warmup = to_ndjson(&generate_records(WARMUP_RECORDS));
+    let _ = encode(&decode(&warmup, schema.clone()), schema.clone(), codec);
+
+    // Benchmark
+    let start = Instant::now();
+    let batches = decode(&ndjson, schema.clone());
+    let decode_duration = start.elapsed();
+    let start = Instant::now();
+    let parquet = encode(&batches, schema, codec);
+    let encode_duration = start.elapsed();
+
+    // Read the file back and check it holds what was generated (not timed in the headline)
+    let parquet_bytes = parquet.len();
+    let start = Instant::now();
+    let round_trip = read_back(parquet);
+    let read_duration = start.elapsed();
+    let expected = Digest::of_records(&records);
+    let actual = Digest::of_batches(&round_trip);
+    if actual != expected {
+        eprintln!("Parquet round trip differs from the records: {:?}, expected {:?}", actual, expected);
+        std::process::exit(1);
+    }
+
+    let total = (decode_duration + encode_duration).as_secs_f64();
+    let megabytes = ndjson.len() as f64 / 1e6;
+    let rate = |seconds: f64| count as f64 / seconds / 1e6;
+    println!("{:.6}", total);
+    eprintln!("Input: {} records, {:.1} MB NDJSON", count, megabytes);
+    eprintln!("JSON decode (arrow-json): {:.6}s, {:.2}M rows/s, {:.1} MB/s", decode_duration.as_secs_f64(),
+              rate(decode_duration.as_secs_f64()), megabytes / decode_duration.as_secs_f64());
+    eprintln!("Parquet encode (parquet, {}): {:.6}s, {:.2}M rows/s", codec.name(), encode_duration.as_secs_f64(),
+              rate(encode_duration.as_secs_f64()));
+    eprintln!("Conversion: {:.2}M rows/s end to end", rate(total));
+    eprintln!("Output: {} bytes Parquet, {:.1}% of the JSON, {} rows per row group", parquet_bytes,
+              100.0 * parquet_bytes as f64 / ndjson.len() as f64, ROW_GROUP_ROWS);
+    eprintln!("Read back: {:.6}s, {:.2}M rows/s, {} rows match the input", read_duration.as_secs_f64(),
+              rate(read_duration.as_secs_f64()), actual.rows);
+    eprintln!("Checksum: {}", actual.sum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+
+    #[test]
+    fn round_trip_preserves_every_column() {
+        let records = generate_records(3000);
+        let batches = decode(&to_ndjson(&records), schema());
+        assert_eq!(Digest::of_batches(&batches), Digest::of_records(&records));
+        for codec in [Codec::Uncompressed, Codec::Snappy, Codec::Zstd] {
+            let round_trip = read_back(encode(&batches, schema(), codec));
+            assert_eq!(Digest::of_batches(&round_trip), Digest::of_records(&records));
+        }
+    }
+
+    #[test]
+    fn optional_fields_become_nulls() {
+        let records = generate_records(200);
+        let batches = decode(&to_ndjson(&records), schema());
+        let nickname = batches[0].column_by_name("nickname").unwrap();
+        assert_eq!(nickname.null_count(), records.iter().filter(|r| r.nickname.is_none()).count());
+        assert_eq!(nickname.as_string::<i32>().value(1), "nick1");
+        let tags = batches[0].column_by_name("tags").unwrap().as_list::<i32>();
+        assert_eq!(tags.value_length(7), 3);
+    }
+}
@@ -0,0 +1,203 @@
+use serde::Deserialize;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+const DEFAULT_MEGABYTES: u64 = 2048; // ~2GB stream
+const IO_BUFFER_BYTES: usize = 1 << 20;
+const WARMUP_RECORDS: usize = 100_000;
+const SEED: u64 = 424242;
+// Bump with any change to generate_file's output, so cached streams are written again
+const DATA_VERSION: u32 = 1;
+const BASE_TIMESTAMP: u64 = 1_700_000_000_000;
+const NUM_USERS: u64 = 1_000_000;
+const NUM_PAGES: u64 = 10_000;
+const EVENTS: [&str; 5] = ["view", "click", "purchase", "signup", "logout"];
+const COUNTRIES: [&str; 16] = [
+    "US", "DE", "FR", "GB", "JP", "BR", "IN", "CA", "AU", "ES", "IT", "NL", "SE", "KR", "MX", "PL",
+];
+
+// Only the fields the query needs; `user` and `page` are skipped by the parser
+#[derive(Deserialize)]
+struct Event<'a> {
+    ts: u64,
+    event: &'a str,
+    amount: f64,
+    country: &'a str,
+    ok: bool,
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn parse_megabytes() -> u64 {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--megabytes") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .expect("--megabytes expects a positive integer"),
+        None => DEFAULT_MEGABYTES,
+    }
+}
+
+// Event log lines: {"ts":...,"user":"u123","event":"view","page":"/products/42","amount":12.34,"country":"DE","ok":true}
+fn generate_file(path: &Path, target_bytes: u64) -> std::io::Result<u64> {
+    let mut seed = SEED;
+    let mut out = BufWriter::with_capacity(IO_BUFFER_BYTES, File::create(path)?);
+    let mut written = 0u64;
+    let mut line = String::with_capacity(160);
+    let mut i = 0u64;
+    while written < target_bytes {
+        line.clear();
+        let ts = BASE_TIMESTAMP + i * 3 + next_random(&mut seed) % 3;
+        let user = next_random(&mut seed) % NUM_USERS;
+        let kind = next_random(&mut seed) % 100;
+        let event = EVENTS[if kind < 50 { 0 } else if kind < 80 { 1 } else if kind < 90 { 2 } else if kind < 95 { 3 } else { 4 }];
+        let page = next_random(&mut seed) % NUM_PAGES;
+        let cents = next_random(&mut seed) % 50_000;
+        let country = COUNTRIES[(next_random(&mut seed) % COUNTRIES.len() as u64) as usize];
+        let ok = !next_random(&mut seed).is_multiple_of(20);
+        writeln!(line, "{{\"ts\":{},\"user\":\"u{}\",\"event\":\"{}\",\"page\":\"/products/{}\",\"amount\":{}.{:02},\"country\":\"{}\",\"ok\":{}}}",
+                 ts, user, event, page, cents / 100, cents % 100, country, ok).unwrap();
+        out.write_all(line.as_bytes())?;
+        written += line.len() as u64;
+        i += 1;
+    }
+    out.flush()?;
+    Ok(written)
+}
+
+struct Aggregate {
+    records: u64,
+    bytes: u64,
+    last_ts: u64,
+    count_by_country: [u64; COUNTRIES.len()],
+    cents_by_country: [u64; COUNTRIES.len()],
+}
+
+// SELECT country, COUNT(*), SUM(amount) WHERE event = 'purchase' AND ok GROUP BY country,
+// one line at a time through a reused buffer
+fn process(path: &Path, limit: usize) -> std::io::Result<Aggregate> {
+    let mut reader = BufReader::with_capacity(IO_BUFFER_BYTES, File::open(path)?);
+    let mut agg = Aggregate {
+        records: 0,
+        bytes: 0,
+        last_ts: 0,
+        count_by_country: [0; COUNTRIES.len()],
+        cents_by_country: [0; COUNTRIES.len()],
+    };
+    let mut line = String::new();
+    while agg.records < limit as u64 {
+        line.clear();
+        let n = reader.read_line(&mut line)?;
+        if n == 0 {
+            break;
+        }
+        agg.bytes += n as u64;
+        agg.records += 1;
+        let event: Event = serde_json::from_str(&line).expect("malformed record");
+        agg.last_ts = agg.last_ts.max(event.ts);
+        if event.event == "purchase" && event.ok {
+            if let Some(c) = COUNTRIES.iter().position(|&c| c == event.country) {
+                agg.count_by_country[c] += 1;
+                agg.cents_by_country[c] += (event.amount * 100.0).round() as u64;
+            }
+        }
+    }
+    Ok(agg)
+}
+
+fn main() -> std::io::Result<()> {
+    let mut measure = bench_measure::from_env();
+    let megabytes = parse_megabytes();
+    // Generated by the first run in either language, then read by every run after it
+    let dataset = bench_data::cached("jsonl_stream", megabytes << 20, SEED, DATA_VERSION,
+                                     |path| generate_file(path, megabytes << 20).map(|_| ()))?;
+    let path = &dataset.path;
+
+    // Warm-up
+    let _ = process(path, WARMUP_RECORDS)?;
+
+    // Benchmark
+    let start = Instant::now();
+    measure.start();
+    let agg = process(path, usize::MAX)?;
+    measure.stop();
+    let total_duration = start.elapsed();
+
+    let purchases: u64 = agg.count_by_country.iter().sum();
+    let revenue: u64 = agg.cents_by_country.iter().sum();
+    let (top, _) = agg.cents_by_country.iter().enumerate().max_by_key(|&(i, &c)| (c, std::cmp::Reverse(i))).unwrap();
+    let checksum = agg.count_by_country.iter().zip(&agg.cents_by_country)
+        .fold(agg.last_ts, |acc, (&n, &c)| acc.wrapping_mul(31).wrapping_add(n * 1_000_003 + c));
+
+    measure.report();
+    eprintln!("Stream: {:.2} GB, {} records, {:.1} MB/s sustained ({:.2}M records/s)",
+              dataset.bytes as f64 / 1e9, agg.records, agg.bytes as f64 / 1e6 / total_duration.as_secs_f64(),
+              agg.records as f64 / 1e6 / total_duration.as_secs_f64());
+    eprintln!("Purchases: {}, revenue {}.{:02}, top country {}", purchases, revenue / 100, revenue % 100, COUNTRIES[top]);
+    eprintln!("{}", dataset.describe());
+    eprintln!("Checksum: {}", checksum);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("jsonl_stream_{}_{}.jsonl", name, std::process::id()))
+    }
+
+    #[test]
+    fn aggregates_purchases_by_country() {
+        let path = temp_path("known");
+        let lines = [
+            r#"{"ts":5,"user":"u1","event":"purchase","page":"/products/1","amount":12.34,"country":"DE","ok":true}"#,
+            r#"{"ts":9,"user":"u2","event":"purchase","page":"/products/2","amount":0.66,"country":"DE","ok":true}"#,
+            r#"{"ts":7,"user":"u3","event":"purchase","page":"/products/3","amount":99.00,"country":"US","ok":false}"#,
+            r#"{"ts":8,"user":"u4","event":"view","page":"/products/4","amount":5.00,"country":"US","ok":true}"#,
+            r#"{"ts":3,"user":"u5","event":"purchase","page":"/products/5","amount":1.50,"country":"ZZ","ok":true}"#,
+        ];
+        fs::write(&path, lines.join("\n") + "\n").unwrap();
+        let agg = process(&path, usize::MAX).unwrap();
+        let de = COUNTRIES.iter().position(|&c| c == "DE").unwrap();
+        assert_eq!((agg.records, agg.last_ts), (5, 9));
+        assert_eq!(agg.bytes, fs::metadata(&path).unwrap().len());
+        assert_eq!((agg.count_by_country[de], agg.cents_by_country[de]), (2, 1300));
+        assert_eq!(agg.count_by_country.iter().sum::<u64>(), 2);
+
+        let head = process(&path, 1).unwrap();
+        assert_eq!((head.records, head.cents_by_country[de]), (1, 1234));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn generated_stream_matches_a_full_parse() {
+        let path = temp_path("generated");
+        let written = generate_file(&path, 200_000).unwrap();
+        assert_eq!(written, fs::metadata(&path).unwrap().len());
+        assert!(written >= 200_000);
+
+        let agg = process(&path, usize::MAX).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let mut cents = 0u64;
+        for line in text.lines() {
+            let v: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert!(v["user"].as_str().unwrap().starts_with('u'));
+            if v["event"] == "purchase" && v["ok"] == true {
+                cents += (v["amount"].as_f64().unwrap() * 100.0).round() as u64;
+            }
+        }
+        assert_eq!(agg.records, text.lines().count() as u64);
+        assert_eq!(agg.cents_by_country.iter().sum::<u64>(), cents);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,229 @@
+// Single-threaded copies of the core benchmark kernels behind a C ABI, built as the
+// `bench_kernels` cdylib. The C++ harness (`cpp/src/ffi_kernels.cpp`) loads it and times these
+// next to its own `cpp_*` kernels in one process, and the `ffi_kernels` binary does the reverse,
+// so neither comparison includes process startup, page-faulting the input, or output.
+// The declarations both sides share are in `cpp/src/bench_kernels.h`.
+
+use sha2::{Digest, Sha256};
+
+pub mod plugin;
+
+const BASE_RE_SPAN: f64 = 3.5; // -2.5..1.0 on the real axis, as in mandelbrot at zoom 1
+const CENTER_RE: f64 = -0.75;
+const SEGMENT_SIZE: usize = 32 * 1024; // odd numbers per sieve window, as in prime_sieve
+const SORT_THRESHOLD: usize = 10_000; // subarrays this short go to the standard library sort
+
+fn mandelbrot_point(cx: f64, cy: f64, max_iter: u32) -> u32 {
+    let mut x = 0.0;
+    let mut y = 0.0;
+    let mut iteration = 0;
+
+    while x * x + y * y <= 4.0 && iteration < max_iter {
+        let xtemp = x * x - y * y + cx;
+        y = 2.0 * x * y + cy;
+        x = xtemp;
+        iteration += 1;
+    }
+
+    iteration
+}
+
+/// Escape iterations summed over a `width` x `height` grid of the zoom-1 mandelbrot view.
+#[no_mangle]
+pub extern "C" fn rust_mandelbrot(width: u32, height: u32, max_iter: u32) -> u64 {
+    let scale = BASE_RE_SPAN / width as f64;
+    let im_top = scale * height as f64 / 2.0;
+    let mut total = 0u64;
+    for py in 0..height {
+        let cy = im_top - py as f64 * scale;
+        for px in 0..width {
+            let cx = CENTER_RE - BASE_RE_SPAN / 2.0 + px as f64 * scale;
+            total += mandelbrot_point(cx, cy, max_iter) as u64;
+        }
+    }
+    total
+}
+
+fn small_odd_primes(limit: usize) -> Vec<usize> {
+    let mut is_prime = vec![true; limit + 1];
+    let mut primes = Vec::new();
+    for i in (3..=limit).step_by(2) {
+        if is_prime[i] {
+            primes.push(i);
+            for j in (i * i..=limit).step_by(2 * i) {
+                is_prime[j] = false;
+            }
+        }
+    }
+    primes
+}
+
+/// π(limit), by prime_sieve's segmented odd-only sieve.
+#[no_mangle]
+pub extern "C" fn rust_count_primes(limit: u64) -> u64 {
+    let limit = limit as usize;
+    if limit < 2 {
+        return 0;
+    }
+    let base_primes = small_odd_primes(limit.isqrt());
+    let mut next: Vec<usize> = base_primes.iter().map(|&p| p * p).collect();
+    let mut segment = vec![0u8; SEGMENT_SIZE];
+
+    let mut count = 1; // 2
+    let mut seg_low = 3;
+    while seg_low <= limit {
+        let seg_high = (seg_low + 2 * SEGMENT_SIZE).min(limit + 1);
+        let window = &mut segment[..(seg_high - seg_low).div_ceil(2)];
+        window.fill(0);
+        for (&p, n) in base_primes.iter().zip(next.iter_mut()) {
+            let mut j = *n;
+            while j < seg_high {
+                window[(j - seg_low) / 2] = 1;
+                j += 2 * p;
+            }
+            *n = j;
+        }
+        count += window.iter().filter(|&&c| c == 0).count();
+        seg_low = seg_high;
+    }
+    count as u64
+}
+
+fn heapsort(arr: &mut [i32]) {
+    fn sift_down(arr: &mut [i32], mut root: usize, end: usize) {
+        loop {
+            let mut child = 2 * root + 1;
+            if child >= end {
+                break;
+            }
+            if child + 1 < end && arr[child] < arr[child + 1] {
+                child += 1;
+            }
+            if arr[root] >= arr[child] {
+                break;
+            }
+            arr.swap(root, child);
+            root = child;
+        }
+    }
+    for i in (0..arr.len() / 2).rev() {
+        sift_down(arr, i, arr.len());
+    }
+    for end in (1..arr.len()).rev() {
+        arr.swap(0, end);
+        sift_down(arr, 0, end);
+    }
+}
+
+// Lomuto, last-element pivot, as in parallel_quicksort
+fn partition(arr: &mut [i32]) -> usize {
+    let high = arr.len() - 1;
+    let pivot = arr[high];
+    let mut i = 0;
+    for j in 0..high {
+        if arr[j] <= pivot {
+            arr.swap(i, j);
+            i += 1;
+        }
+    }
+    arr.swap(i, high);
+    i
+}
+
+fn quicksort(arr: &mut [i32], depth: usize) {
+    if arr.len() <= SORT_THRESHOLD {
+        arr.sort_unstable();
+        return;
+    }
+    if depth == 0 {
+        heapsort(arr);
+        return;
+    }
+    let pi = partition(arr);
+    let (left, right) = arr.split_at_mut(pi);
+    quicksort(left, depth - 1);
+    quicksort(&mut right[1..], depth - 1);
+}
+
+/// Sorts `len` i32s in place: parallel_quicksort's partitioning on one thread.
+///
+/// # Safety
+/// `data` must point to `len` initialized, writable i32s (or be anything when `len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn rust_sort_i32(data: *mut i32, len: usize) {
+    if len == 0 {
+        return;
+    }
+    let arr = std::slice::from_raw_parts_mut(data, len);
+    quicksort(arr, 2 * (usize::BITS - len.leading_zeros()) as usize);
+}
+
+/// SHA-256 of `len` bytes into the 32 bytes at `digest`, through the sha2 crate as in sha256.
+///
+/// # Safety
+/// `data` must point to `len` readable bytes (or be anything when `len` is 0), and `digest` to
+/// 32 writable ones.
+#[no_mangle]
+pub unsafe extern "C" fn rust_sha256(data: *const u8, len: usize, digest: *mut u8) {
+    let bytes = if len == 0 { &[][..] } else { std::slice::from_raw_parts(data, len) };
+    let mut hasher = Sha256::new();
+    // 64 KiB updates, for the reason given in sha256's leaf_hash
+    for chunk in bytes.chunks(64 * 1024) {
+        hasher.update(chunk);
+    }
+    std::ptr::copy_nonoverlapping(hasher.finalize().as_ptr(), digest, 32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sha256(bytes: &[u8]) -> String {
+        let mut digest = [0u8; 32];
+        unsafe { rust_sha256(bytes.as_ptr(), bytes.len(), digest.as_mut_ptr()) };
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn counts_primes() {
+        for (limit, expected) in [(0, 0), (1, 0), (2, 1), (3, 2), (10, 4), (100, 25), (1_000_000, 78_498), (10_000_000, 664_579)] {
+            assert_eq!(rust_count_primes(limit), expected, "limit {}", limit);
+        }
+    }
+
+    #[test]
+    fn mandelbrot_sums_escape_counts() {
+        // One pixel at the view's top-left corner, -2.5 + 1.75i, escapes on the first step
+        assert_eq!(rust_mandelbrot(1, 1, 1000), 1);
+        // The 4x2 grid's second row lies on the real axis, at -2.5, -1.625, -0.75 and 0.125, of which
+        // all but -2.5 (one step) stay bounded; the first row is 0.875 above it
+        let bounded_row = 1 + 3 * 50;
+        let top_row: u64 = (0..4).map(|x| mandelbrot_point(-2.5 + x as f64 * 0.875, 0.875, 50) as u64).sum();
+        assert_eq!(rust_mandelbrot(4, 2, 50), top_row + bounded_row);
+    }
+
+    #[test]
+    fn sorts_in_place() {
+        let mut small = [5, -3, 9, 0, -3, i32::MIN, i32::MAX];
+        unsafe { rust_sort_i32(small.as_mut_ptr(), small.len()) };
+        assert_eq!(small, [i32::MIN, -3, -3, 0, 5, 9, i32::MAX]);
+        unsafe { rust_sort_i32(std::ptr::null_mut(), 0) };
+
+        // Past the threshold, and sorted input that exhausts the depth limit into heapsort
+        for mut data in [(0..100_000).map(|i: i32| (i * 7919) % 100_003).collect::<Vec<_>>(), (0..50_000).collect()] {
+            let mut expected = data.clone();
+            expected.sort();
+            unsafe { rust_sort_i32(data.as_mut_ptr(), data.len()) };
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    fn sha256_matches_known_digests() {
+        assert_eq!(sha256(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(sha256(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        // Several 64 KiB updates must hash like one
+        let long = vec![b'a'; 1_000_000];
+        assert_eq!(sha256(&long), "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0");
+    }
+}
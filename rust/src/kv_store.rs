@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::RwLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const RECORD_COUNT: u64 = 1 << 20;
+const VALUE_WORDS: usize = 16; // 128-byte records
+const SHARD_BITS: u32 = 6;
+const NUM_SHARDS: usize = 1 << SHARD_BITS;
+const NUM_THREADS: usize = 8;
+const ZIPF_THETA: f64 = 0.99; // YCSB's default request skew
+const MAX_SCAN_LENGTH: u64 = 100;
+
+type Value = [u64; VALUE_WORDS];
+
+struct Workload {
+    name: &'static str,
+    read_percent: u64,
+    update_percent: u64, // the remainder are scans
+    ops_per_thread: usize,
+}
+
+const WORKLOADS: [Workload; 3] = [
+    Workload { name: "A (update-heavy)", read_percent: 50, update_percent: 50, ops_per_thread: 250_000 },
+    Workload { name: "B (read-heavy)", read_percent: 95, update_percent: 5, ops_per_thread: 250_000 },
+    Workload { name: "E (scan)", read_percent: 0, update_percent: 5, ops_per_thread: 25_000 },
+];
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Record contents depend only on the key and the phase that last wrote it, so the
+// final store is the same no matter how the client threads interleave
+fn make_value(key: u64, version: u64) -> Value {
+    let mut x = key.wrapping_mul(0x9E3779B97F4A7C15) ^ version;
+    std::array::from_fn(|_| {
+        x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        x
+    })
+}
+
+fn fold_value(value: &Value) -> u64 {
+    value.iter().fold(0u64, |acc, &w| acc.wrapping_mul(31).wrapping_add(w))
+}
+
+// Padded so neighbouring shard locks don't share a cache line
+#[repr(align(64))]
+struct Shard(RwLock<HashMap<u64, Value>>);
+
+struct Store {
+    shards: Vec<Shard>,
+}
+
+impl Store {
+    fn new() -> Self {
+        let per_shard = RECORD_COUNT as usize / NUM_SHARDS;
+        Store { shards: (0..NUM_SHARDS).map(|_| Shard(RwLock::new(HashMap::with_capacity(per_shard)))).collect() }
+    }
+
+    fn shard(&self, key: u64) -> &RwLock<HashMap<u64, Value>> {
+        &self.shards[(key.wrapping_mul(0x9E3779B97F4A7C15) >> (64 - SHARD_BITS)) as usize].0
+    }
+
+    fn read(&self, key: u64) -> Option<Value> {
+        self.shard(key).read().unwrap().get(&key).copied()
+    }
+
+    fn write(&self, key: u64, value: Value) {
+        self.shard(key).write().unwrap().insert(key, value);
+    }
+
+    // Order-independent fold over every record
+    fn checksum(&self) -> u64 {
+        self.shards.iter().fold(0u64, |acc, shard| {
+            shard.0.read().unwrap().iter().fold(acc, |acc, (&key, value)| acc.wrapping_add(key ^ fold_value(value)))
+        })
+    }
+}
+
+// YCSB's ZipfianGenerator (Gray et al.), scrambled so the hottest keys land on different shards
+struct Zipfian {
+    zeta_n: f64,
+    alpha: f64,
+    eta: f64,
+    half_pow_theta: f64,
+}
+
+impl Zipfian {
+    fn new(n: u64, theta: f64) -> Self {
+        let zeta_n: f64 = (1..=n).map(|i| 1.0 / (i as f64).powf(theta)).sum();
+        let zeta_2 = 1.0 + 0.5f64.powf(theta);
+        Zipfian {
+            zeta_n,
+            alpha: 1.0 / (1.0 - theta),
+            eta: (1.0 - (2.0 / n as f64).powf(1.0 - theta)) / (1.0 - zeta_2 / zeta_n),
+            half_pow_theta: 0.5f64.powf(theta),
+        }
+    }
+
+    fn next_key(&self, seed: &mut u64) -> u64 {
+        let u = next_random(seed) as f64 / (1u64 << 31) as f64;
+        let uz = u * self.zeta_n;
+        let rank = if uz < 1.0 {
+            0
+        } else if uz < 1.0 + self.half_pow_theta {
+            1
+        } else {
+            ((RECORD_COUNT as f64 * (self.eta * u - self.eta + 1.0).powf(self.alpha)) as u64).min(RECORD_COUNT - 1)
+        };
+        rank.wrapping_mul(0xBF58476D1CE4E5B9) & (RECORD_COUNT - 1)
+    }
+}
+
+fn load(store: &Store) -> Duration {
+    let start = Instant::now();
+    let per_thread = RECORD_COUNT / NUM_THREADS as u64;
+    thread::scope(|s| {
+        for t in 0..NUM_THREADS as u64 {
+            let end = if t == NUM_THREADS as u64 - 1 { RECORD_COUNT } else { (t + 1) * per_thread };
+            s.spawn(move || {
+                for key in t * per_thread..end {
+                    store.write(key, make_value(key, 0));
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+struct WorkloadResult {
+    elapsed: Duration,
+    ops: usize,
+    latencies_ns: Vec<u32>, // sorted
+}
+
+// Every client thread times each operation individually for the latency percentiles
+fn run_workload(store: &Store, zipf: &Zipfian, workload: &Workload, version: u64, ops_per_thread: usize) -> WorkloadResult {
+    let start = Instant::now();
+    let per_thread: Vec<Vec<u32>> = thread::scope(|s| {
+        let handles: Vec<_> = (0..NUM_THREADS as u64)
+            .map(|t| {
+                s.spawn(move || {
+                    let mut seed = version * 1000 + t;
+                    let mut latencies = Vec::with_capacity(ops_per_thread);
+                    let mut read_sum = 0u64;
+                    for _ in 0..ops_per_thread {
+                        let op = next_random(&mut seed) % 100;
+                        let key = zipf.next_key(&mut seed);
+                        let op_start = Instant::now();
+                        if op < workload.read_percent {
+                            read_sum = read_sum.wrapping_add(store.read(key).map_or(0, |v| fold_value(&v)));
+                        } else if op < workload.read_percent + workload.update_percent {
+                            store.write(key, make_value(key, version));
+                        } else {
+                            // Hash-partitioned, so a scan is a multi-get over the next run of keys
+                            let length = 1 + next_random(&mut seed) % MAX_SCAN_LENGTH;
+                            for k in key..key + length {
+                                let k = k & (RECORD_COUNT - 1);
+                                read_sum = read_sum.wrapping_add(store.read(k).map_or(0, |v| fold_value(&v)));
+                            }
+                        }
+                        latencies.push(op_start.elapsed().as_nanos().min(u32::MAX as u128) as u32);
+                    }
+                    black_box(read_sum);
+                    latencies
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    let elapsed = start.elapsed();
+
+    let mut latencies_ns: Vec<u32> = per_thread.into_iter().flatten().collect();
+    latencies_ns.sort_unstable();
+    WorkloadResult { elapsed, ops: latencies_ns.len(), latencies_ns }
+}
+
+fn percentile_us(sorted: &[u32], per_mille: usize) -> f64 {
+    sorted[(sorted.len() - 1) * per_mille / 1000] as f64 / 1000.0
+}
+
+fn main() {
+    let zipf = Zipfian::new(RECORD_COUNT, ZIPF_THETA);
+    let store = Store::new();
+    let load_duration = load(&store);
+
+    // Warm-up: rewrites load-phase values, so the store is unchanged
+    let _ = run_workload(&store, &zipf, &WORKLOADS[1], 0, WORKLOADS[1].ops_per_thread / 100);
+
+    // Benchmark
+    let results: Vec<WorkloadResult> = WORKLOADS
+        .iter()
+        .enumerate()
+        .map(|(i, w)| run_workload(&store, &zipf, w, i as u64 + 1, w.ops_per_thread))
+        .collect();
+
+    let total_duration = load_duration + results.iter().map(|r| r.elapsed).sum::<Duration>();
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Load: {} records at {:.0} inserts/s ({} shards, {} threads)",
+              RECORD_COUNT, RECORD_COUNT as f64 / load_duration.as_secs_f64(), NUM_SHARDS, NUM_THREADS);
+    for (w, r) in WORKLOADS.iter().zip(&results) {
+        eprintln!("{:<18} {:.0} ops/s, p50 {:.2}us, p99 {:.2}us, p99.9 {:.2}us",
+                  w.name, r.ops as f64 / r.elapsed.as_secs_f64(),
+                  percentile_us(&r.latencies_ns, 500), percentile_us(&r.latencies_ns, 990), percentile_us(&r.latencies_ns, 999));
+    }
+    eprintln!("Checksum: {}", store.checksum());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_reads_back_writes() {
+        let store = Store::new();
+        assert_eq!(store.read(7), None);
+        store.write(7, make_value(7, 1));
+        store.write(7, make_value(7, 2));
+        assert_eq!(store.read(7), Some(make_value(7, 2)));
+        assert_ne!(make_value(7, 1), make_value(7, 2));
+        assert_eq!(store.checksum(), 7 ^ fold_value(&make_value(7, 2)));
+    }
+
+    #[test]
+    fn final_store_is_independent_of_interleaving() {
+        let zipf = Zipfian::new(RECORD_COUNT, ZIPF_THETA);
+        let run = || {
+            let store = Store::new();
+            load(&store);
+            let loaded = store.checksum();
+            for (i, workload) in WORKLOADS.iter().enumerate() {
+                let result = run_workload(&store, &zipf, workload, i as u64 + 1, 2000);
+                assert_eq!(result.ops, 2000 * NUM_THREADS);
+                assert!(result.latencies_ns.windows(2).all(|w| w[0] <= w[1]));
+            }
+            (loaded, store.checksum())
+        };
+        let (loaded, first) = run();
+        let expected = (0..RECORD_COUNT).fold(0u64, |acc, key| acc.wrapping_add(key ^ fold_value(&make_value(key, 0))));
+        assert_eq!(loaded, expected);
+        assert_ne!(first, loaded);
+        assert_eq!(run().1, first);
+    }
+
+    #[test]
+    fn zipfian_keys_are_skewed_and_in_range() {
+        let zipf = Zipfian::new(RECORD_COUNT, ZIPF_THETA);
+        let mut seed = 11u64;
+        let keys: Vec<u64> = (0..100_000).map(|_| zipf.next_key(&mut seed)).collect();
+        assert!(keys.iter().all(|&k| k < RECORD_COUNT));
+        // Rank 0 scrambles to key 0 and draws about 1/zeta_n of the requests, ~7% at this size
+        let hottest = keys.iter().filter(|&&k| k == 0).count() as f64 / keys.len() as f64;
+        assert!((hottest - 1.0 / zipf.zeta_n).abs() < 0.01, "{}", hottest);
+    }
+
+    #[test]
+    fn percentiles_index_the_sorted_latencies() {
+        let sorted: Vec<u32> = (1..=1001).map(|i| i * 1000).collect();
+        assert_eq!(percentile_us(&sorted, 0), 1.0);
+        assert_eq!(percentile_us(&sorted, 500), 501.0);
+        assert_eq!(percentile_us(&sorted, 1000), 1001.0);
+    }
+}
@@ -0,0 +1,182 @@
+use rayon::prelude::*;
+use std::time::Instant;
+
+const N: usize = 2048;
+const DEFAULT_STEPS: usize = 2000;
+const TAU: f64 = 0.8; // Relaxation time; kinematic viscosity is (TAU - 0.5) / 3
+const VORTEX_VELOCITY: f64 = 0.05;
+const WAVES: f64 = 8.0; // Taylor-Green periods across the domain
+const DRIFT: (f64, f64) = (0.02, 0.01); // Uniform background flow so total momentum is nonzero
+
+// D2Q9 lattice: rest, 4 axis, 4 diagonal directions
+const CX: [f64; 9] = [0.0, 1.0, 0.0, -1.0, 0.0, 1.0, -1.0, -1.0, 1.0];
+const CY: [f64; 9] = [0.0, 0.0, 1.0, 0.0, -1.0, 1.0, 1.0, -1.0, -1.0];
+const W: [f64; 9] = [
+    4.0 / 9.0,
+    1.0 / 9.0, 1.0 / 9.0, 1.0 / 9.0, 1.0 / 9.0,
+    1.0 / 36.0, 1.0 / 36.0, 1.0 / 36.0, 1.0 / 36.0,
+];
+
+type Cell = [f64; 9];
+
+fn equilibrium(rho: f64, ux: f64, uy: f64) -> Cell {
+    let u2 = ux * ux + uy * uy;
+    std::array::from_fn(|q| {
+        let cu = CX[q] * ux + CY[q] * uy;
+        W[q] * rho * (1.0 + 3.0 * cu + 4.5 * cu * cu - 1.5 * u2)
+    })
+}
+
+// Taylor-Green vortex array on top of the drift, with the matching pressure field
+fn initial_state() -> Vec<Cell> {
+    let k = 2.0 * std::f64::consts::PI * WAVES / N as f64;
+    (0..N * N)
+        .map(|i| {
+            let (x, y) = ((i % N) as f64, (i / N) as f64);
+            let ux = DRIFT.0 - VORTEX_VELOCITY * (k * x).cos() * (k * y).sin();
+            let uy = DRIFT.1 + VORTEX_VELOCITY * (k * x).sin() * (k * y).cos();
+            let rho = 1.0 - 0.75 * VORTEX_VELOCITY * VORTEX_VELOCITY * ((2.0 * k * x).cos() + (2.0 * k * y).cos());
+            equilibrium(rho, ux, uy)
+        })
+        .collect()
+}
+
+// Fused pull-stream and BGK collision on a periodic grid
+fn step(src: &[Cell], dst: &mut [Cell]) {
+    let omega = 1.0 / TAU;
+    dst.par_chunks_mut(N).enumerate().for_each(|(y, row)| {
+        let ym = if y == 0 { N - 1 } else { y - 1 };
+        let yp = if y == N - 1 { 0 } else { y + 1 };
+        let (here, below, above) = (&src[y * N..(y + 1) * N], &src[ym * N..(ym + 1) * N], &src[yp * N..(yp + 1) * N]);
+        for (x, out) in row.iter_mut().enumerate() {
+            let xm = if x == 0 { N - 1 } else { x - 1 };
+            let xp = if x == N - 1 { 0 } else { x + 1 };
+            // Each population arrives from the neighbor opposite its direction
+            let f = [
+                here[x][0],
+                here[xm][1],
+                below[x][2],
+                here[xp][3],
+                above[x][4],
+                below[xm][5],
+                below[xp][6],
+                above[xp][7],
+                above[xm][8],
+            ];
+            let rho = f[0] + f[1] + f[2] + f[3] + f[4] + f[5] + f[6] + f[7] + f[8];
+            let ux = (f[1] + f[5] + f[8] - f[3] - f[6] - f[7]) / rho;
+            let uy = (f[2] + f[5] + f[6] - f[4] - f[7] - f[8]) / rho;
+            let feq = equilibrium(rho, ux, uy);
+            *out = std::array::from_fn(|q| f[q] + omega * (feq[q] - f[q]));
+        }
+    });
+}
+
+// Total mass, momentum, and kinetic energy of the flow relative to the mean velocity
+fn moments(state: &[Cell]) -> (f64, f64, f64, f64) {
+    let (mut mass, mut px, mut py) = (0.0, 0.0, 0.0);
+    for f in state {
+        mass += f.iter().sum::<f64>();
+        px += f[1] + f[5] + f[8] - f[3] - f[6] - f[7];
+        py += f[2] + f[5] + f[6] - f[4] - f[7] - f[8];
+    }
+    let (mean_ux, mean_uy) = (px / mass, py / mass);
+    let mut energy = 0.0;
+    for f in state {
+        let rho: f64 = f.iter().sum();
+        let ux = (f[1] + f[5] + f[8] - f[3] - f[6] - f[7]) / rho - mean_ux;
+        let uy = (f[2] + f[5] + f[6] - f[4] - f[7] - f[8]) / rho - mean_uy;
+        energy += 0.5 * rho * (ux * ux + uy * uy);
+    }
+    (mass, px, py, energy)
+}
+
+fn parse_steps() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--steps") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .expect("--steps expects a positive integer"),
+        None => DEFAULT_STEPS,
+    }
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let steps = parse_steps();
+    let initial = initial_state();
+    let (_, _, _, initial_energy) = moments(&initial);
+
+    // Warm-up
+    let mut scratch = initial.clone();
+    step(&initial, &mut scratch);
+
+    // Benchmark
+    let mut cur = initial;
+    let mut next = scratch;
+    let start = Instant::now();
+    measure.start();
+    for _ in 0..steps {
+        step(&cur, &mut next);
+        std::mem::swap(&mut cur, &mut next);
+    }
+    measure.stop();
+    let duration = start.elapsed();
+
+    // Checksum: momentum is conserved up to roundoff; vortex energy should decay as exp(-4 nu k^2 t)
+    let (mass, px, py, energy) = moments(&cur);
+    let nu = (TAU - 0.5) / 3.0;
+    let k = 2.0 * std::f64::consts::PI * WAVES / N as f64;
+    let expected_ratio = (-4.0 * nu * k * k * steps as f64).exp();
+
+    measure.report();
+    eprintln!("Grid: {}² x {} steps, {:.0} MLUP/s", N, steps, (N * N * steps) as f64 / duration.as_secs_f64() / 1e6);
+    eprintln!("Kinetic energy ratio: {:.6} (analytic: {:.6}), mass: {:.6}", energy / initial_energy, expected_ratio, mass);
+    eprintln!("Checksum: {:.9} (momentum y: {:.9}, kinetic energy: {:.9})", px, py, energy);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equilibrium_has_the_requested_moments() {
+        let (rho, ux, uy) = (1.2, 0.03, -0.05);
+        let f = equilibrium(rho, ux, uy);
+        let density: f64 = f.iter().sum();
+        let px: f64 = (0..9).map(|q| CX[q] * f[q]).sum();
+        let py: f64 = (0..9).map(|q| CY[q] * f[q]).sum();
+        assert!((density - rho).abs() < 1e-15);
+        assert!((px - rho * ux).abs() < 1e-15);
+        assert!((py - rho * uy).abs() < 1e-15);
+        assert!((W.iter().sum::<f64>() - 1.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn uniform_flow_is_a_fixed_point() {
+        let uniform = vec![equilibrium(1.0, DRIFT.0, DRIFT.1); N * N];
+        let mut next = uniform.clone();
+        step(&uniform, &mut next);
+        for q in 0..9 {
+            assert!((next[12345][q] - uniform[0][q]).abs() < 1e-16);
+        }
+    }
+
+    #[test]
+    fn steps_conserve_mass_and_momentum() {
+        let mut state = initial_state();
+        let mut next = state.clone();
+        let (mass, px, py, energy) = moments(&state);
+        assert!((mass / (N * N) as f64 - 1.0).abs() < 1e-12);
+        assert!((px / mass - DRIFT.0).abs() < 1e-12 && (py / mass - DRIFT.1).abs() < 1e-12);
+        for _ in 0..3 {
+            step(&state, &mut next);
+            std::mem::swap(&mut state, &mut next);
+        }
+        let (mass_after, px_after, py_after, energy_after) = moments(&state);
+        assert!(((mass_after - mass) / mass).abs() < 1e-12);
+        assert!(((px_after - px) / px).abs() < 1e-10 && ((py_after - py) / py).abs() < 1e-10);
+        // Viscosity only dissipates the vortices
+        assert!(energy_after < energy);
+    }
+}
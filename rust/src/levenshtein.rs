@@ -0,0 +1,190 @@
+use std::time::Instant;
+
+const NUM_PAIRS: usize = 50_000;
+const MIN_LEN: usize = 16;
+const MAX_LEN: usize = 64; // Myers' algorithm keeps the whole pattern in one u64
+const ALPHABET: &[u8] = b"abcdefgh";
+const MAX_EDITS: u64 = 16;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Pairs are a random string and a copy mutated by a handful of random edits
+fn generate_pairs() -> Vec<(Vec<u8>, Vec<u8>)> {
+    let mut seed = 31337u64;
+    (0..NUM_PAIRS)
+        .map(|_| {
+            let len = MIN_LEN + (next_random(&mut seed) % (MAX_LEN - MIN_LEN + 1) as u64) as usize;
+            let a: Vec<u8> = (0..len)
+                .map(|_| ALPHABET[(next_random(&mut seed) % ALPHABET.len() as u64) as usize])
+                .collect();
+
+            let mut b = a.clone();
+            let edits = next_random(&mut seed) % (MAX_EDITS + 1);
+            for _ in 0..edits {
+                let pos = (next_random(&mut seed) % b.len() as u64) as usize;
+                let c = ALPHABET[(next_random(&mut seed) % ALPHABET.len() as u64) as usize];
+                match next_random(&mut seed) % 3 {
+                    0 => b[pos] = c,
+                    1 if b.len() < MAX_LEN => b.insert(pos, c),
+                    2 if b.len() > 1 => {
+                        b.remove(pos);
+                    }
+                    _ => b[pos] = c,
+                }
+            }
+            (a, b)
+        })
+        .collect()
+}
+
+// Classic Wagner-Fischer dynamic programming with two rolling rows
+fn levenshtein_dp(a: &[u8], b: &[u8], prev: &mut Vec<u32>, curr: &mut Vec<u32>) -> u32 {
+    prev.clear();
+    prev.extend(0..=b.len() as u32);
+    curr.clear();
+    curr.resize(b.len() + 1, 0);
+
+    for (i, &ca) in a.iter().enumerate() {
+        curr[0] = i as u32 + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = prev[j] + (ca != cb) as u32;
+            let deletion = prev[j + 1] + 1;
+            let insertion = curr[j] + 1;
+            curr[j + 1] = substitution.min(deletion).min(insertion);
+        }
+        std::mem::swap(prev, curr);
+    }
+
+    prev[b.len()]
+}
+
+// Myers/Hyyrö bit-parallel edit distance, pattern length <= 64
+fn levenshtein_myers(a: &[u8], b: &[u8]) -> u32 {
+    if a.is_empty() {
+        return b.len() as u32;
+    }
+
+    let mut peq = [0u64; 256];
+    for (i, &c) in a.iter().enumerate() {
+        peq[c as usize] |= 1 << i;
+    }
+
+    let last = 1u64 << (a.len() - 1);
+    let mut pv = u64::MAX;
+    let mut mv = 0u64;
+    let mut score = a.len() as u32;
+
+    for &c in b {
+        let eq = peq[c as usize];
+        let xv = eq | mv;
+        let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+        let mut ph = mv | !(xh | pv);
+        let mut mh = pv & xh;
+        if ph & last != 0 {
+            score += 1;
+        }
+        if mh & last != 0 {
+            score -= 1;
+        }
+        ph = (ph << 1) | 1;
+        mh <<= 1;
+        pv = mh | !(xv | ph);
+        mv = ph & xv;
+    }
+
+    score
+}
+
+fn run_dp(pairs: &[(Vec<u8>, Vec<u8>)]) -> u64 {
+    let mut prev = Vec::with_capacity(MAX_LEN + 1);
+    let mut curr = Vec::with_capacity(MAX_LEN + 1);
+    pairs.iter().map(|(a, b)| levenshtein_dp(a, b, &mut prev, &mut curr) as u64).sum()
+}
+
+fn run_myers(pairs: &[(Vec<u8>, Vec<u8>)]) -> u64 {
+    pairs.iter().map(|(a, b)| levenshtein_myers(a, b) as u64).sum()
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let pairs = generate_pairs();
+
+    // Warm-up
+    let _ = run_dp(&pairs[..1000]);
+    let _ = run_myers(&pairs[..1000]);
+
+    // Benchmark dynamic programming
+    let start = Instant::now();
+    measure.start();
+    let dp_checksum = run_dp(&pairs);
+    measure.stop();
+    let dp_duration = start.elapsed();
+
+    // Benchmark bit-parallel
+    let start = Instant::now();
+    measure.start();
+    let myers_checksum = run_myers(&pairs);
+    measure.stop();
+    let myers_duration = start.elapsed();
+
+    measure.report();
+    eprintln!("DP: {:.6}s ({:.0} comparisons/s), Myers: {:.6}s ({:.0} comparisons/s)",
+              dp_duration.as_secs_f64(), NUM_PAIRS as f64 / dp_duration.as_secs_f64(),
+              myers_duration.as_secs_f64(), NUM_PAIRS as f64 / myers_duration.as_secs_f64());
+    eprintln!("Checksum: {} (Myers: {})", dp_checksum, myers_checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dp(a: &[u8], b: &[u8]) -> u32 {
+        levenshtein_dp(a, b, &mut Vec::new(), &mut Vec::new())
+    }
+
+    #[test]
+    fn known_distances() {
+        for (a, b, d) in [
+            (&b"kitten"[..], &b"sitting"[..], 3),
+            (b"flaw", b"lawn", 2),
+            (b"", b"abc", 3),
+            (b"abc", b"", 3),
+            (b"", b"", 0),
+            (b"abcdefgh", b"abcdefgh", 0),
+            (b"abcdefgh", b"hgfedcba", 8),
+        ] {
+            assert_eq!(dp(a, b), d, "{:?} {:?}", a, b);
+            assert_eq!(levenshtein_myers(a, b), d, "{:?} {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn myers_matches_dp_up_to_64_characters() {
+        let mut seed = 8u64;
+        let mut random = |len: usize| -> Vec<u8> {
+            (0..len).map(|_| ALPHABET[(next_random(&mut seed) % 3) as usize]).collect()
+        };
+        for len in [1, 2, 31, 32, 33, 63, MAX_LEN] {
+            let a = random(len);
+            for other in [0, 1, len / 2, len, 80] {
+                let b = random(other);
+                assert_eq!(levenshtein_myers(&a, &b), dp(&a, &b), "{} vs {}", len, other);
+            }
+        }
+    }
+
+    #[test]
+    fn generated_pairs_stay_within_bounds() {
+        let pairs = generate_pairs();
+        assert_eq!(pairs.len(), NUM_PAIRS);
+        assert!(pairs.iter().all(|(a, b)| (MIN_LEN..=MAX_LEN).contains(&a.len()) && b.len() <= MAX_LEN));
+        let head = &pairs[..2000];
+        assert_eq!(run_myers(head), run_dp(head));
+        assert!(head.iter().all(|(a, b)| levenshtein_myers(a, b) as u64 <= MAX_EDITS));
+    }
+}
@@ -0,0 +1,406 @@
+use std::time::Instant;
+
+const CORPUS_BYTES: usize = 64 << 20; // 64MB of C-like source
+const NUM_PASSES: usize = 5;
+const STATEMENTS_PER_FUNCTION: u64 = 12;
+const NAMES: [&str; 10] = ["count", "buf", "node", "index", "value", "tmp", "ptr", "len", "key", "state"];
+const BINARY_OPS: [&str; 10] = ["+", "-", "*", "/", "%", "<<", ">>", "&", "|", "^"];
+const COMPARISONS: [&str; 6] = ["==", "!=", "<", "<=", ">", ">="];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+enum TokenKind {
+    Identifier,
+    Keyword,
+    Integer,
+    Float,
+    String,
+    Char,
+    Punct,
+    Comment,
+}
+
+const KIND_NAMES: [&str; 8] = ["identifier", "keyword", "integer", "float", "string", "char", "punct", "comment"];
+
+#[derive(Clone, Copy)]
+struct Token {
+    kind: TokenKind,
+    start: u32,
+    len: u32,
+}
+
+// Byte classes driving the lexer's start state
+const WS: u8 = 0;
+const IDENT: u8 = 1;
+const DIGIT: u8 = 2;
+const QUOTE: u8 = 3;
+const APOSTROPHE: u8 = 4;
+const SLASH: u8 = 5;
+const PUNCT: u8 = 6;
+const OTHER: u8 = 7;
+
+const fn build_classes() -> [u8; 256] {
+    let mut classes = [OTHER; 256];
+    let mut b = 0;
+    while b < 256 {
+        let c = b as u8;
+        classes[b] = match c {
+            b' ' | b'\t' | b'\n' | b'\r' => WS,
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => IDENT,
+            b'0'..=b'9' => DIGIT,
+            b'"' => QUOTE,
+            b'\'' => APOSTROPHE,
+            b'/' => SLASH,
+            b'+' | b'-' | b'*' | b'%' | b'=' | b'!' | b'<' | b'>' | b'&' | b'|' | b'^' | b'~' | b'?' | b':' | b';'
+            | b',' | b'.' | b'(' | b')' | b'[' | b']' | b'{' | b'}' | b'#' => PUNCT,
+            _ => OTHER,
+        };
+        b += 1;
+    }
+    classes
+}
+
+const CLASSES: [u8; 256] = build_classes();
+
+fn is_ident_continue(b: u8) -> bool {
+    CLASSES[b as usize] == IDENT || CLASSES[b as usize] == DIGIT
+}
+
+fn is_keyword(s: &[u8]) -> bool {
+    matches!(
+        s,
+        b"if" | b"int" | b"for" | b"char" | b"else" | b"long" | b"void" | b"case" | b"float" | b"while" | b"break"
+            | b"const" | b"double" | b"return" | b"struct" | b"static" | b"sizeof" | b"switch" | b"default"
+            | b"typedef" | b"unsigned" | b"continue"
+    )
+}
+
+// Longest punctuator starting at `i` (maximal munch)
+fn punct_len(src: &[u8], i: usize) -> usize {
+    let rest = &src[i..];
+    if rest.len() >= 3 && matches!(&rest[..3], b"<<=" | b">>=" | b"...") {
+        return 3;
+    }
+    if rest.len() >= 2
+        && matches!(
+            &rest[..2],
+            b"==" | b"!=" | b"<=" | b">=" | b"&&" | b"||" | b"++" | b"--" | b"+=" | b"-=" | b"*=" | b"/="
+                | b"%=" | b"&=" | b"|=" | b"^=" | b"<<" | b">>" | b"->" | b"::"
+        )
+    {
+        return 2;
+    }
+    1
+}
+
+fn skip_quoted(src: &[u8], mut i: usize, quote: u8) -> usize {
+    i += 1;
+    while i < src.len() && src[i] != quote {
+        i += if src[i] == b'\\' { 2 } else { 1 };
+    }
+    (i + 1).min(src.len())
+}
+
+fn lex(src: &[u8], tokens: &mut Vec<Token>) {
+    tokens.clear();
+    let n = src.len();
+    let mut i = 0;
+    while i < n {
+        let start = i;
+        let kind = match CLASSES[src[i] as usize] {
+            WS => {
+                i += 1;
+                while i < n && CLASSES[src[i] as usize] == WS {
+                    i += 1;
+                }
+                continue;
+            }
+            IDENT => {
+                i += 1;
+                while i < n && is_ident_continue(src[i]) {
+                    i += 1;
+                }
+                if is_keyword(&src[start..i]) { TokenKind::Keyword } else { TokenKind::Identifier }
+            }
+            DIGIT => {
+                if src[i] == b'0' && i + 1 < n && (src[i + 1] | 0x20) == b'x' {
+                    i += 2;
+                    while i < n && src[i].is_ascii_hexdigit() {
+                        i += 1;
+                    }
+                    TokenKind::Integer
+                } else {
+                    let mut kind = TokenKind::Integer;
+                    while i < n && src[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                    if i + 1 < n && src[i] == b'.' && src[i + 1].is_ascii_digit() {
+                        kind = TokenKind::Float;
+                        i += 1;
+                        while i < n && src[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                    if i < n && (src[i] | 0x20) == b'e' {
+                        let mut j = i + 1;
+                        if j < n && (src[j] == b'+' || src[j] == b'-') {
+                            j += 1;
+                        }
+                        if j < n && src[j].is_ascii_digit() {
+                            kind = TokenKind::Float;
+                            i = j;
+                            while i < n && src[i].is_ascii_digit() {
+                                i += 1;
+                            }
+                        }
+                    }
+                    kind
+                }
+            }
+            QUOTE => {
+                i = skip_quoted(src, i, b'"');
+                TokenKind::String
+            }
+            APOSTROPHE => {
+                i = skip_quoted(src, i, b'\'');
+                TokenKind::Char
+            }
+            SLASH if i + 1 < n && src[i + 1] == b'/' => {
+                while i < n && src[i] != b'\n' {
+                    i += 1;
+                }
+                TokenKind::Comment
+            }
+            SLASH if i + 1 < n && src[i + 1] == b'*' => {
+                i += 2;
+                while i + 1 < n && !(src[i] == b'*' && src[i + 1] == b'/') {
+                    i += 1;
+                }
+                i = (i + 2).min(n);
+                TokenKind::Comment
+            }
+            SLASH | PUNCT => {
+                i += punct_len(src, i);
+                TokenKind::Punct
+            }
+            _ => {
+                // Stray bytes become single-byte punctuators rather than errors
+                i += 1;
+                TokenKind::Punct
+            }
+        };
+        tokens.push(Token { kind, start: start as u32, len: (i - start) as u32 });
+    }
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn push_name(out: &mut String, seed: &mut u64) {
+    out.push_str(NAMES[(next_random(seed) % NAMES.len() as u64) as usize]);
+    out.push('_');
+    out.push_str(&(next_random(seed) % 1000).to_string());
+}
+
+fn push_number(out: &mut String, seed: &mut u64) {
+    let value = next_random(seed) % 100_000;
+    if next_random(seed).is_multiple_of(4) {
+        out.push_str(&format!("0x{:X}", value));
+    } else {
+        out.push_str(&value.to_string());
+    }
+}
+
+fn pick<'a>(items: &[&'a str], seed: &mut u64) -> &'a str {
+    items[(next_random(seed) % items.len() as u64) as usize]
+}
+
+// Functions full of declarations, arithmetic, branches, loops, calls, literals and comments
+fn generate_corpus() -> Vec<u8> {
+    let mut seed = 0xC0DEu64;
+    let mut out = String::with_capacity(CORPUS_BYTES + 256);
+    let mut statement = 0u64;
+    while out.len() < CORPUS_BYTES {
+        if statement.is_multiple_of(STATEMENTS_PER_FUNCTION) {
+            if statement > 0 {
+                out.push_str("    return 0;\n}\n\n");
+            }
+            out.push_str("static int ");
+            push_name(&mut out, &mut seed);
+            out.push_str("(int ");
+            push_name(&mut out, &mut seed);
+            out.push_str(", const char *");
+            push_name(&mut out, &mut seed);
+            out.push_str(") {\n");
+        }
+        statement += 1;
+        out.push_str("    ");
+        match next_random(&mut seed) % 8 {
+            0 => {
+                out.push_str("int ");
+                push_name(&mut out, &mut seed);
+                out.push_str(" = ");
+                push_number(&mut out, &mut seed);
+                out.push_str(";\n");
+            }
+            1 => {
+                push_name(&mut out, &mut seed);
+                out.push_str(" = ");
+                push_name(&mut out, &mut seed);
+                out.push(' ');
+                out.push_str(pick(&BINARY_OPS, &mut seed));
+                out.push(' ');
+                push_name(&mut out, &mut seed);
+                out.push_str(";\n");
+            }
+            2 => {
+                out.push_str("if (");
+                push_name(&mut out, &mut seed);
+                out.push(' ');
+                out.push_str(pick(&COMPARISONS, &mut seed));
+                out.push(' ');
+                push_number(&mut out, &mut seed);
+                out.push_str(" && !");
+                push_name(&mut out, &mut seed);
+                out.push_str(") { ");
+                push_name(&mut out, &mut seed);
+                out.push_str(" += 1; } else { ");
+                push_name(&mut out, &mut seed);
+                out.push_str("--; }\n");
+            }
+            3 => {
+                out.push_str("// TODO: handle ");
+                push_name(&mut out, &mut seed);
+                out.push_str(" overflow when the buffer wraps\n");
+            }
+            4 => {
+                out.push_str("/* invariant: ");
+                push_name(&mut out, &mut seed);
+                out.push_str(" <= len * 2 */\n");
+            }
+            5 => {
+                out.push_str("printf(\"%s: \\\"%d\\\"\\n\", ");
+                push_name(&mut out, &mut seed);
+                out.push_str(", ");
+                push_name(&mut out, &mut seed);
+                out.push_str("->");
+                push_name(&mut out, &mut seed);
+                out.push_str(");\n");
+            }
+            6 => {
+                out.push_str("double ");
+                push_name(&mut out, &mut seed);
+                out.push_str(" = ");
+                out.push_str(&(next_random(&mut seed) % 1000).to_string());
+                out.push('.');
+                out.push_str(&(next_random(&mut seed) % 1000).to_string());
+                out.push_str("e-");
+                out.push_str(&(next_random(&mut seed) % 10).to_string());
+                out.push_str(";\n");
+            }
+            _ => {
+                out.push_str("for (int i = 0; i < ");
+                push_number(&mut out, &mut seed);
+                out.push_str("; i++) { ");
+                push_name(&mut out, &mut seed);
+                out.push_str("[i] <<= '\\t'; }\n");
+            }
+        }
+    }
+    out.truncate(CORPUS_BYTES);
+    out.into_bytes()
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let corpus = generate_corpus();
+    let mut tokens = Vec::with_capacity(corpus.len() / 3);
+
+    // Warm-up
+    lex(&corpus[..corpus.len() / 100], &mut tokens);
+
+    // Benchmark
+    let start = Instant::now();
+    measure.start();
+    for _ in 0..NUM_PASSES {
+        lex(&corpus, &mut tokens);
+    }
+    measure.stop();
+    let total_duration = start.elapsed();
+
+    let mut counts = [0usize; KIND_NAMES.len()];
+    let mut checksum = 0u64;
+    for t in &tokens {
+        counts[t.kind as usize] += 1;
+        checksum = checksum.wrapping_mul(31).wrapping_add(((t.kind as u64) << 32) | (t.start as u64 ^ t.len as u64));
+    }
+    let megabytes = (corpus.len() * NUM_PASSES) as f64 / 1e6;
+
+    measure.report();
+    eprintln!("Lexed {:.1} MB in {} passes: {:.1} MB/s, {:.1}M tokens/s",
+              megabytes, NUM_PASSES, megabytes / total_duration.as_secs_f64(),
+              (tokens.len() * NUM_PASSES) as f64 / 1e6 / total_duration.as_secs_f64());
+    let breakdown: Vec<String> = KIND_NAMES.iter().zip(&counts).map(|(name, n)| format!("{} {}", name, n)).collect();
+    eprintln!("Tokens per pass: {} ({})", tokens.len(), breakdown.join(", "));
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TokenKind::*;
+
+    fn tokens(src: &str) -> Vec<(TokenKind, &str)> {
+        let mut out = Vec::new();
+        lex(src.as_bytes(), &mut out);
+        out.iter().map(|t| (t.kind, &src[t.start as usize..(t.start + t.len) as usize])).collect()
+    }
+
+    #[test]
+    fn lexes_a_statement() {
+        assert_eq!(
+            tokens("for (int i_2 = 0x1F; i_2 <<= 3.5e-2; i_2++) { s = \"a\\\"b\"; c = '\\''; } // done"),
+            [
+                (Keyword, "for"), (Punct, "("), (Keyword, "int"), (Identifier, "i_2"), (Punct, "="),
+                (Integer, "0x1F"), (Punct, ";"), (Identifier, "i_2"), (Punct, "<<="), (Float, "3.5e-2"),
+                (Punct, ";"), (Identifier, "i_2"), (Punct, "++"), (Punct, ")"), (Punct, "{"),
+                (Identifier, "s"), (Punct, "="), (String, "\"a\\\"b\""), (Punct, ";"), (Identifier, "c"),
+                (Punct, "="), (Char, "'\\''"), (Punct, ";"), (Punct, "}"), (Comment, "// done"),
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers_comments_and_edges() {
+        assert_eq!(tokens("1.x 2e 7e+1 08"), [(Integer, "1"), (Punct, "."), (Identifier, "x"), (Integer, "2"),
+                                             (Identifier, "e"), (Float, "7e+1"), (Integer, "08")]);
+        assert_eq!(tokens("a /* x * / */ b / c"), [(Identifier, "a"), (Comment, "/* x * / */"), (Identifier, "b"),
+                                                   (Punct, "/"), (Identifier, "c")]);
+        // Unterminated literals and comments run to the end of the input
+        assert_eq!(tokens("\"open"), [(String, "\"open")]);
+        assert_eq!(tokens("/* open"), [(Comment, "/* open")]);
+        assert_eq!(tokens("a->b...c @"), [(Identifier, "a"), (Punct, "->"), (Identifier, "b"), (Punct, "..."),
+                                          (Identifier, "c"), (Punct, "@")]);
+        assert!(tokens(" \n\t ").is_empty());
+        assert_eq!(tokens("returned return"), [(Identifier, "returned"), (Keyword, "return")]);
+    }
+
+    #[test]
+    fn tokens_tile_the_corpus_between_whitespace() {
+        let corpus = generate_corpus();
+        assert_eq!(corpus.len(), CORPUS_BYTES);
+        let mut out = Vec::new();
+        lex(&corpus[..1 << 20], &mut out);
+        let mut end = 0;
+        for t in &out {
+            assert!(corpus[end..t.start as usize].iter().all(|&b| CLASSES[b as usize] == WS));
+            assert!(t.len > 0);
+            end = (t.start + t.len) as usize;
+        }
+        assert!(out.iter().any(|t| t.kind == Float) && out.iter().any(|t| t.kind == Comment));
+    }
+}
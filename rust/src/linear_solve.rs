@@ -0,0 +1,316 @@
+use rayon::prelude::*;
+use std::time::Instant;
+
+const DEFAULT_SIZE: usize = 2048; // BENCH_PARAMS size
+const STRIP: usize = 64; // columns of the inverse per task: a 64-wide row of it is one 512-byte stream
+const RESIDUAL_LIMIT: f64 = 16.0; // HPL's pass threshold for the scaled residual
+
+// Matrices are n x n, flat row-major: element (i, j) lives at i * n + j. Every multiply-add is an
+// explicit FMA and every sum runs in index order, so both languages pivot the same way and reach
+// the same solutions bit for bit
+
+// xorshift64, identical in the C++ version
+struct Rng(u64);
+
+impl Rng {
+    // Uniform in [-1, 1) with 53 random bits
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    }
+}
+
+// Uniform random entries; such matrices are nonsingular with overwhelming probability but need pivoting
+fn random_matrix(n: usize, seed: u64) -> Vec<f64> {
+    let mut rng = Rng(seed);
+    (0..n * n).map(|_| rng.next()).collect()
+}
+
+// Symmetric random off-diagonal entries with n on the diagonal: strictly diagonally dominant, so
+// positive definite
+fn spd_matrix(n: usize, seed: u64) -> Vec<f64> {
+    let mut rng = Rng(seed);
+    let mut a = vec![0.0; n * n];
+    for i in 0..n {
+        a[i * n + i] = n as f64;
+        for j in i + 1..n {
+            let value = rng.next();
+            a[i * n + j] = value;
+            a[j * n + i] = value;
+        }
+    }
+    a
+}
+
+// The solution every system is built to have
+fn known_solution(n: usize) -> Vec<f64> {
+    (0..n).map(|i| 1.0 + (i % 7) as f64).collect()
+}
+
+fn mat_vec(a: &[f64], n: usize, x: &[f64]) -> Vec<f64> {
+    a.chunks(n).map(|row| row.iter().zip(x).fold(0.0, |s, (&a, &x)| a.mul_add(x, s))).collect()
+}
+
+// HPL's ||Ax - b|| / (eps * (||A|| * ||x|| + ||b||) * n) in the infinity norm; a backward-stable
+// solve keeps it of order one whatever the matrix's condition
+fn scaled_residual(a: &[f64], n: usize, x: &[f64], b: &[f64]) -> f64 {
+    let max_abs = |v: &[f64]| v.iter().fold(0.0, |m: f64, x| m.max(x.abs()));
+    let residual: Vec<f64> = mat_vec(a, n, x).iter().zip(b).map(|(ax, b)| ax - b).collect();
+    let norm_a = a.chunks(n).map(|row| row.iter().map(|x| x.abs()).sum::<f64>()).fold(0.0, f64::max);
+    max_abs(&residual) / (f64::EPSILON * (norm_a * max_abs(x) + max_abs(b)) * n as f64)
+}
+
+// PA = LU in place, right-looking: L's unit diagonal is implied below U. Returns the row each step
+// swapped in; the trailing rows update in parallel once the pivot row is fixed
+fn lu_factor(a: &mut [f64], n: usize) -> Vec<usize> {
+    let mut pivots = Vec::with_capacity(n);
+    for k in 0..n {
+        // Partial pivoting: the first largest magnitude on or below the diagonal
+        let p = (k + 1..n).fold(k, |p, i| if a[i * n + k].abs() > a[p * n + k].abs() { i } else { p });
+        pivots.push(p);
+        if p != k {
+            let (top, bottom) = a.split_at_mut(p * n);
+            top[k * n..(k + 1) * n].swap_with_slice(&mut bottom[..n]);
+        }
+        let (done, trailing) = a.split_at_mut((k + 1) * n);
+        let row_k = &done[k * n..];
+        let pivot = row_k[k];
+        assert!(pivot != 0.0, "the matrix is singular");
+        trailing.par_chunks_mut(n).for_each(|row| {
+            let l = row[k] / pivot;
+            row[k] = l;
+            for (x, &u) in row[k + 1..].iter_mut().zip(&row_k[k + 1..]) {
+                *x = (-l).mul_add(u, *x);
+            }
+        });
+    }
+    pivots
+}
+
+// x from U x = y, with U the upper triangle of u
+fn back_substitute(u: &[f64], n: usize, mut y: Vec<f64>) -> Vec<f64> {
+    for i in (0..n).rev() {
+        let row = &u[i * n..(i + 1) * n];
+        let s = row[i + 1..].iter().zip(&y[i + 1..]).fold(y[i], |s, (&u, &x)| (-u).mul_add(x, s));
+        y[i] = s / row[i];
+    }
+    y
+}
+
+fn lu_solve(lu: &[f64], n: usize, pivots: &[usize], b: &[f64]) -> Vec<f64> {
+    let mut y = b.to_vec();
+    for (k, &p) in pivots.iter().enumerate() {
+        y.swap(k, p);
+    }
+    for i in 0..n {
+        let row = &lu[i * n..i * n + i];
+        y[i] = row.iter().zip(&y[..i]).fold(y[i], |s, (&l, &x)| (-l).mul_add(x, s));
+    }
+    back_substitute(lu, n, y)
+}
+
+// A = U^T U in place in the upper triangle (the lower one is left stale): each step finishes row k
+// of U, then subtracts its outer product from the trailing upper triangle, rows in parallel
+fn cholesky_factor(a: &mut [f64], n: usize) {
+    for k in 0..n {
+        let (done, trailing) = a.split_at_mut((k + 1) * n);
+        let row_k = &mut done[k * n..];
+        assert!(row_k[k] > 0.0, "the matrix is not positive definite");
+        let d = row_k[k].sqrt();
+        row_k[k] = d;
+        for x in &mut row_k[k + 1..] {
+            *x /= d;
+        }
+        let row_k = &*row_k;
+        trailing.par_chunks_mut(n).enumerate().for_each(|(r, row)| {
+            let i = k + 1 + r;
+            let u_ki = row_k[i];
+            for (x, &u) in row[i..].iter_mut().zip(&row_k[i..]) {
+                *x = (-u_ki).mul_add(u, *x);
+            }
+        });
+    }
+}
+
+fn cholesky_solve(u: &[f64], n: usize, b: &[f64]) -> Vec<f64> {
+    // U^T y = b by columns of U^T, which are rows of U
+    let mut y = b.to_vec();
+    for k in 0..n {
+        let row = &u[k * n..(k + 1) * n];
+        y[k] /= row[k];
+        let y_k = y[k];
+        for (y, &u) in y[k + 1..].iter_mut().zip(&row[k + 1..]) {
+            *y = (-u).mul_add(y_k, *y);
+        }
+    }
+    back_substitute(u, n, y)
+}
+
+// A^-1 = U^-1 L^-1 P, solved for the columns of P in strips of STRIP in parallel: 2n^3 flops, as
+// the triangular solves do not skip the identity's zeros
+fn lu_inverse(lu: &[f64], n: usize, pivots: &[usize]) -> Vec<f64> {
+    // Row i of P is the unit row e_perm[i], where perm replays the factorization's swaps on 0..n
+    let mut perm: Vec<usize> = (0..n).collect();
+    for (k, &p) in pivots.iter().enumerate() {
+        perm.swap(k, p);
+    }
+
+    let strips: Vec<Vec<f64>> = (0..n.div_ceil(STRIP))
+        .into_par_iter()
+        .map(|s| {
+            let (j0, w) = (s * STRIP, STRIP.min(n - s * STRIP));
+            // The strip's columns, stored n x w row-major
+            let mut x = vec![0.0; n * w];
+            for (i, &j) in perm.iter().enumerate() {
+                if (j0..j0 + w).contains(&j) {
+                    x[i * w + j - j0] = 1.0;
+                }
+            }
+            // L Y = P, L unit lower triangular
+            for i in 0..n {
+                let (solved, rest) = x.split_at_mut(i * w);
+                let row = &mut rest[..w];
+                for (k, &l) in lu[i * n..i * n + i].iter().enumerate() {
+                    for (x, &y) in row.iter_mut().zip(&solved[k * w..(k + 1) * w]) {
+                        *x = (-l).mul_add(y, *x);
+                    }
+                }
+            }
+            // U X = Y
+            for i in (0..n).rev() {
+                let (head, solved) = x.split_at_mut((i + 1) * w);
+                let row = &mut head[i * w..];
+                for (k, &u) in lu[i * n + i + 1..(i + 1) * n].iter().enumerate() {
+                    for (x, &y) in row.iter_mut().zip(&solved[k * w..(k + 1) * w]) {
+                        *x = (-u).mul_add(y, *x);
+                    }
+                }
+                let d = lu[i * n + i];
+                for x in row {
+                    *x /= d;
+                }
+            }
+            x
+        })
+        .collect();
+
+    let mut inverse = vec![0.0; n * n];
+    for (s, strip) in strips.iter().enumerate() {
+        let (j0, w) = (s * STRIP, STRIP.min(n - s * STRIP));
+        for (i, row) in strip.chunks(w).enumerate() {
+            inverse[i * n + j0..i * n + j0 + w].copy_from_slice(row);
+        }
+    }
+    inverse
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let mut params = bench_params::Params::from_env();
+    let n = params.usize("size", DEFAULT_SIZE);
+    let cores = std::thread::available_parallelism().map_or(1, |c| c.get());
+    let threads = params.usize("threads", cores);
+    params.check();
+    rayon::ThreadPoolBuilder::new().num_threads(threads).build_global().expect("rayon pool already started");
+
+    let a = random_matrix(n, 0x9E37_79B9_7F4A_7C15);
+    let spd = spd_matrix(n, 0xD1B5_4A32_D192_ED03);
+    let x_true = known_solution(n);
+    let b = mat_vec(&a, n, &x_true);
+    let b_spd = mat_vec(&spd, n, &x_true);
+    let nf = n as f64;
+
+    // Benchmark: each phase factors a fresh copy made outside the timed region
+    let mut lu = a.clone();
+    let start = Instant::now();
+    measure.start();
+    let pivots = lu_factor(&mut lu, n);
+    let x_lu = lu_solve(&lu, n, &pivots, &b);
+    measure.stop();
+    let lu_time = start.elapsed().as_secs_f64();
+
+    let mut u = spd.clone();
+    let start = Instant::now();
+    measure.start();
+    cholesky_factor(&mut u, n);
+    let x_cholesky = cholesky_solve(&u, n, &b_spd);
+    measure.stop();
+    let cholesky_time = start.elapsed().as_secs_f64();
+
+    let start = Instant::now();
+    measure.start();
+    let inverse = lu_inverse(&lu, n, &pivots);
+    measure.stop();
+    let inverse_time = start.elapsed().as_secs_f64();
+    let x_inverse = mat_vec(&inverse, n, &b);
+
+    let max_error = |x: &[f64]| x.iter().zip(&x_true).fold(0.0, |m: f64, (x, t)| m.max((x - t).abs()));
+    let phases = [
+        ("LU (partial pivoting)", lu_time, 2.0 / 3.0 * nf * nf * nf, scaled_residual(&a, n, &x_lu, &b), max_error(&x_lu)),
+        ("Cholesky", cholesky_time, nf * nf * nf / 3.0, scaled_residual(&spd, n, &x_cholesky, &b_spd), max_error(&x_cholesky)),
+        ("Inverse from LU, x = A^-1 b", inverse_time, 2.0 * nf * nf * nf, scaled_residual(&a, n, &x_inverse, &b), max_error(&x_inverse)),
+    ];
+
+    measure.report();
+    for (name, seconds, flops, residual, error) in phases {
+        eprintln!(
+            "{}: {:.3}s, {:.2} GFLOP/s, scaled residual {:.4}, max error {:.3e}",
+            name, seconds, flops / seconds / 1e9, residual, error
+        );
+        assert!(residual < RESIDUAL_LIMIT, "{}: scaled residual {} is over {}", name, residual, RESIDUAL_LIMIT);
+    }
+    eprintln!("{}", params.report());
+    let checksum: f64 = x_lu.iter().chain(&x_cholesky).chain(&x_inverse).sum();
+    eprintln!("Checksum: {:.6}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lu_pivots_and_solves_a_small_system_exactly() {
+        // A zero in the corner forces a swap; every step's arithmetic is exact
+        let a = [0.0, 2.0, 1.0, 4.0, 1.0, 0.0, 2.0, 0.0, 4.0];
+        let mut lu = a.to_vec();
+        let pivots = lu_factor(&mut lu, 3);
+        assert_eq!(pivots, [1, 1, 2]);
+        let x = lu_solve(&lu, 3, &pivots, &mat_vec(&a, 3, &[1.0, 2.0, 3.0]));
+        assert_eq!(x, [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn cholesky_factor_multiplies_back() {
+        let n = 50;
+        let spd = spd_matrix(n, 7);
+        let mut u = spd.clone();
+        cholesky_factor(&mut u, n);
+        for i in 0..n {
+            for j in i..n {
+                let product: f64 = (0..=i).map(|k| u[k * n + i] * u[k * n + j]).sum();
+                assert!((product - spd[i * n + j]).abs() < 1e-12, "({}, {})", i, j);
+            }
+        }
+        let b = mat_vec(&spd, n, &known_solution(n));
+        assert!(scaled_residual(&spd, n, &cholesky_solve(&u, n, &b), &b) < RESIDUAL_LIMIT);
+    }
+
+    // A size that leaves a narrow last strip
+    #[test]
+    fn inverse_times_matrix_is_the_identity() {
+        let n = STRIP + 36;
+        let a = random_matrix(n, 11);
+        let mut lu = a.clone();
+        let pivots = lu_factor(&mut lu, n);
+        let inverse = lu_inverse(&lu, n, &pivots);
+        for i in 0..n {
+            for j in 0..n {
+                let product: f64 = (0..n).map(|k| a[i * n + k] * inverse[k * n + j]).sum();
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((product - expected).abs() < 1e-9, "({}, {}): {}", i, j, product);
+            }
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use std::cell::UnsafeCell;
+use std::hint::{black_box, spin_loop};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Barrier, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const THREAD_COUNTS: [usize; 4] = [1, 2, 4, 8];
+const OPS: usize = 5_000_000; // per configuration, split between the threads
+const SLOTS: usize = 16;
+const SPINS_BEFORE_YIELD: u32 = 100;
+
+struct Mix {
+    name: &'static str,
+    read_percent: u64,
+}
+
+const MIXES: [Mix; 2] = [
+    Mix { name: "read-heavy", read_percent: 95 },
+    Mix { name: "write-heavy", read_percent: 25 },
+];
+
+fn splitmix64(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Two cache lines of counters. A read sums them all; a write adds to two. Additions commute,
+// so the final state is the same under any interleaving
+#[derive(Default)]
+struct State {
+    slots: [u64; SLOTS],
+}
+
+impl State {
+    fn sum(&self) -> u64 {
+        self.slots.iter().fold(0u64, |acc, &s| acc.wrapping_add(s))
+    }
+
+    fn update(&mut self, r: u64) {
+        let k = (r >> 8) as usize % SLOTS;
+        self.slots[k] = self.slots[k].wrapping_add(r >> 32);
+        self.slots[(k + 5) % SLOTS] += 1;
+    }
+
+    fn digest(&self) -> u64 {
+        self.slots.iter().fold(0u64, |acc, &s| acc.wrapping_mul(31).wrapping_add(s))
+    }
+}
+
+// Exclusive locks take reads exclusively too
+trait Lock: Sync {
+    fn new(state: State) -> Self;
+    fn read<R>(&self, f: impl FnOnce(&State) -> R) -> R;
+    fn write(&self, f: impl FnOnce(&mut State));
+    fn into_inner(self) -> State;
+}
+
+impl Lock for Mutex<State> {
+    fn new(state: State) -> Self {
+        Mutex::new(state)
+    }
+
+    fn read<R>(&self, f: impl FnOnce(&State) -> R) -> R {
+        f(&self.lock().unwrap())
+    }
+
+    fn write(&self, f: impl FnOnce(&mut State)) {
+        f(&mut self.lock().unwrap())
+    }
+
+    fn into_inner(self) -> State {
+        Mutex::into_inner(self).unwrap()
+    }
+}
+
+impl Lock for parking_lot::Mutex<State> {
+    fn new(state: State) -> Self {
+        parking_lot::Mutex::new(state)
+    }
+
+    fn read<R>(&self, f: impl FnOnce(&State) -> R) -> R {
+        f(&self.lock())
+    }
+
+    fn write(&self, f: impl FnOnce(&mut State)) {
+        f(&mut self.lock())
+    }
+
+    fn into_inner(self) -> State {
+        parking_lot::Mutex::into_inner(self)
+    }
+}
+
+impl Lock for RwLock<State> {
+    fn new(state: State) -> Self {
+        RwLock::new(state)
+    }
+
+    fn read<R>(&self, f: impl FnOnce(&State) -> R) -> R {
+        f(&RwLock::read(self).unwrap())
+    }
+
+    fn write(&self, f: impl FnOnce(&mut State)) {
+        f(&mut RwLock::write(self).unwrap())
+    }
+
+    fn into_inner(self) -> State {
+        RwLock::into_inner(self).unwrap()
+    }
+}
+
+impl Lock for parking_lot::RwLock<State> {
+    fn new(state: State) -> Self {
+        parking_lot::RwLock::new(state)
+    }
+
+    fn read<R>(&self, f: impl FnOnce(&State) -> R) -> R {
+        f(&parking_lot::RwLock::read(self))
+    }
+
+    fn write(&self, f: impl FnOnce(&mut State)) {
+        f(&mut parking_lot::RwLock::write(self))
+    }
+
+    fn into_inner(self) -> State {
+        parking_lot::RwLock::into_inner(self)
+    }
+}
+
+// Test-and-test-and-set: waiters spin on a plain load so the line stays shared until the holder
+// releases it, then yield so a preempted holder can run
+struct SpinLock {
+    locked: AtomicBool,
+    state: UnsafeCell<State>,
+}
+
+// SAFETY: the state is only reached while holding `locked`
+unsafe impl Sync for SpinLock {}
+
+impl SpinLock {
+    fn with<R>(&self, f: impl FnOnce(&mut State) -> R) -> R {
+        let mut spins = 0;
+        while self.locked.swap(true, Ordering::Acquire) {
+            while self.locked.load(Ordering::Relaxed) {
+                if spins < SPINS_BEFORE_YIELD {
+                    spins += 1;
+                    spin_loop();
+                } else {
+                    thread::yield_now();
+                }
+            }
+        }
+        // SAFETY: holding the lock, so no other reference to the state exists
+        let result = f(unsafe { &mut *self.state.get() });
+        self.locked.store(false, Ordering::Release);
+        result
+    }
+}
+
+impl Lock for SpinLock {
+    fn new(state: State) -> Self {
+        SpinLock { locked: AtomicBool::new(false), state: UnsafeCell::new(state) }
+    }
+
+    fn read<R>(&self, f: impl FnOnce(&State) -> R) -> R {
+        self.with(|s| f(s))
+    }
+
+    fn write(&self, f: impl FnOnce(&mut State)) {
+        self.with(f)
+    }
+
+    fn into_inner(self) -> State {
+        self.state.into_inner()
+    }
+}
+
+struct Run {
+    elapsed: Duration,
+    fairness: f64, // first thread to finish over last: 1.0 when every thread finishes together
+    digest: u64,
+}
+
+// Every thread does its share of the operations from its own seed; finish times after a common
+// start show whether the lock let some threads through ahead of others
+fn run<L: Lock>(threads: usize, ops: usize, read_percent: u64) -> Run {
+    let lock = L::new(State::default());
+    let barrier = Barrier::new(threads);
+    let start = Instant::now();
+    let finishes: Vec<Duration> = thread::scope(|s| {
+        let handles: Vec<_> = (0..threads as u64)
+            .map(|t| {
+                let (lock, barrier) = (&lock, &barrier);
+                s.spawn(move || {
+                    let mut seed = 0x10CC + t;
+                    let mut reads = 0u64;
+                    barrier.wait();
+                    let begin = Instant::now();
+                    for _ in 0..ops / threads {
+                        let r = splitmix64(&mut seed);
+                        if r % 100 < read_percent {
+                            reads = reads.wrapping_add(lock.read(State::sum));
+                        } else {
+                            lock.write(|state| state.update(r));
+                        }
+                    }
+                    black_box(reads);
+                    begin.elapsed()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    let elapsed = start.elapsed();
+    let first = finishes.iter().min().unwrap().as_secs_f64();
+    let last = finishes.iter().max().unwrap().as_secs_f64();
+    Run { elapsed, fairness: first / last, digest: lock.into_inner().digest() }
+}
+
+type Variant = (&'static str, fn(usize, usize, u64) -> Run);
+
+const VARIANTS: [Variant; 5] = [
+    ("std Mutex", run::<Mutex<State>>),
+    ("parking_lot Mutex", run::<parking_lot::Mutex<State>>),
+    ("spinlock", run::<SpinLock>),
+    ("std RwLock", run::<RwLock<State>>),
+    ("parking_lot RwLock", run::<parking_lot::RwLock<State>>),
+];
+
+fn main() {
+    // Warm-up
+    for (_, f) in VARIANTS {
+        let _ = f(THREAD_COUNTS[THREAD_COUNTS.len() - 1], OPS / 100, MIXES[0].read_percent);
+    }
+
+    // Benchmark: runs[mix][variant][thread count]
+    let runs: Vec<Vec<Vec<Run>>> = MIXES
+        .iter()
+        .map(|mix| {
+            VARIANTS.iter().map(|(_, f)| THREAD_COUNTS.iter().map(|&t| f(t, OPS, mix.read_percent)).collect()).collect()
+        })
+        .collect();
+    let total_duration: Duration = runs.iter().flatten().flatten().map(|r| r.elapsed).sum();
+    let all_match = runs.iter().all(|by_variant| {
+        by_variant.iter().all(|by_threads| by_threads.iter().zip(&by_variant[0]).all(|(r, first)| r.digest == first.digest))
+    });
+    let checksum = runs.iter().flat_map(|by_variant| &by_variant[0]).fold(0u64, |acc, r| acc.wrapping_add(r.digest));
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("{} operations per configuration over {} counters; Mops/s and fairness (first/last thread to finish)",
+              OPS, SLOTS);
+    for (mix, by_variant) in MIXES.iter().zip(&runs) {
+        eprintln!("{} ({}% reads):", mix.name, mix.read_percent);
+        for ((name, _), by_threads) in VARIANTS.iter().zip(by_variant) {
+            let cells: Vec<String> = THREAD_COUNTS
+                .iter()
+                .zip(by_threads)
+                .map(|(t, r)| format!("{}t {:6.1} {:.2}", t, OPS as f64 / r.elapsed.as_secs_f64() / 1e6, r.fairness))
+                .collect();
+            eprintln!("  {:<18} {}", name, cells.join("  "));
+        }
+    }
+    eprintln!("Checksum: {} (all locks match: {})", checksum, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updates_commute() {
+        let (mut a, mut b) = (State::default(), State::default());
+        let rs = [3u64 << 32 | 7 << 8, 1 << 40 | 2 << 8, 9 << 33 | 15 << 8];
+        for &r in &rs {
+            a.update(r);
+        }
+        for &r in rs.iter().rev() {
+            b.update(r);
+        }
+        assert_eq!(a.slots, b.slots);
+        assert_eq!(a.sum(), 3 + (1 << 8) + 18 + 3);
+    }
+
+    #[test]
+    fn locks_agree() {
+        for mix in &MIXES {
+            let expected = run::<Mutex<State>>(3, 30_000, mix.read_percent).digest;
+            for (name, f) in VARIANTS {
+                let r = f(3, 30_000, mix.read_percent);
+                assert_eq!(r.digest, expected, "{} {}", name, mix.name);
+                assert!(r.fairness > 0.0 && r.fairness <= 1.0);
+            }
+        }
+    }
+}
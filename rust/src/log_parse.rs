@@ -0,0 +1,602 @@
+use nom::branch::alt;
+use nom::bytes::complete::{escaped, is_not, tag, take_while1, take_while_m_n};
+use nom::character::complete::{char, hex_digit1, one_of, u16, u32, u64};
+use nom::combinator::{all_consuming, map_res, opt, value};
+use nom::multi::fold_many1;
+use nom::sequence::{delimited, preceded};
+use nom::{IResult, Parser};
+use std::time::Instant;
+
+const DEFAULT_MEGABYTES: usize = 256;
+const WARMUP_BYTES: usize = 4 << 20;
+const BASE_MILLIS: u64 = 1_700_000_000_000; // 2023-11-14T22:13:20Z
+const MALFORMED_EVERY: u64 = 1000; // about one line in a thousand is broken in one of three ways
+const SERVICES: [&str; 8] = ["auth", "billing", "gateway", "search", "storage", "queue", "mailer", "cache"];
+const RESOURCES: [&str; 6] = ["users", "orders", "items", "carts", "sessions", "invoices"];
+const STATUSES: [u16; 8] = [200, 200, 200, 201, 204, 404, 500, 503];
+const WORDS: [&str; 16] = [
+    "request", "completed", "upstream", "timeout", "cache", "miss", "retry", "after", "token", "expired", "user",
+    "session", "queued", "payload", "rejected", "ok",
+];
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Days since 1970-01-01 to (year, month, day) and back, from Howard Hinnant's algorithms
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + (month <= 2) as i64, month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 } as i64;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    era * 146_097 + yoe * 365 + yoe / 4 - yoe / 100 + doy - 719_468
+}
+
+// Lines look like
+//   2023-11-14T22:13:20.042Z WARN [billing] GET /api/v2/orders/1234 latency_us=18234 status=503 bytes=5120 req=1a2b3c4d5e6f7081 user=42 msg="upstream \"db\" timeout"
+// with the key=value fields rotated into a different order per line and user= on most lines
+fn generate_log(target_bytes: usize) -> String {
+    let mut seed = 20240601u64;
+    let mut millis = BASE_MILLIS;
+    let mut out = String::with_capacity(target_bytes + 512);
+    let mut line = 0u64;
+    while out.len() < target_bytes {
+        line += 1;
+        millis += next_random(&mut seed) % 50;
+        let (date, time) = (millis.div_euclid(86_400_000) as i64, millis % 86_400_000);
+        let (year, month, day) = civil_from_days(date);
+        let broken = if line.is_multiple_of(MALFORMED_EVERY) { 1 + next_random(&mut seed) % 3 } else { 0 };
+        let month = if broken == 1 { 13 } else { month };
+        out.push_str(&format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z ", year, month, day, time / 3_600_000,
+                              time / 60_000 % 60, time / 1000 % 60, time % 1000));
+
+        let level = next_random(&mut seed) % 100;
+        out.push_str(if level < 10 { "DEBUG" } else if level < 80 { "INFO" } else if level < 95 { "WARN" } else { "ERROR" });
+        out.push_str(" [");
+        out.push_str(SERVICES[(next_random(&mut seed) % SERVICES.len() as u64) as usize]);
+        out.push_str("] ");
+        let method = next_random(&mut seed) % 20;
+        out.push_str(if method < 12 { "GET" } else if method < 17 { "POST" } else if method < 19 { "PUT" } else { "DELETE" });
+        out.push_str(&format!(" /api/v{}/{}/{}", 1 + next_random(&mut seed) % 3,
+                              RESOURCES[(next_random(&mut seed) % RESOURCES.len() as u64) as usize],
+                              next_random(&mut seed) % 100_000));
+
+        let status = STATUSES[(next_random(&mut seed) % STATUSES.len() as u64) as usize];
+        let mut fields = [
+            if broken == 2 { "status=5x3".to_string() } else { format!("status={}", status) },
+            format!("latency_us={}", next_random(&mut seed) % 200_000),
+            format!("bytes={}", next_random(&mut seed) % 65_536),
+            format!("req={:016x}", (next_random(&mut seed) << 33) ^ next_random(&mut seed)),
+        ];
+        fields.rotate_left((next_random(&mut seed) % 4) as usize);
+        for field in &fields {
+            out.push(' ');
+            out.push_str(field);
+        }
+        if next_random(&mut seed) % 10 < 7 {
+            out.push_str(&format!(" user={}", next_random(&mut seed) % 1_000_000));
+        }
+
+        out.push_str(" msg=\"");
+        let words = 2 + next_random(&mut seed) % 8;
+        for w in 0..words {
+            if w > 0 {
+                out.push(' ');
+            }
+            let word = WORDS[(next_random(&mut seed) % WORDS.len() as u64) as usize];
+            match next_random(&mut seed) % 20 {
+                0 => out.push_str(&format!("\\\"{}\\\"", word)),
+                1 => out.push_str(&format!("C:\\\\{}", word)),
+                _ => out.push_str(word),
+            }
+        }
+        if broken != 3 {
+            out.push('"');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+// One parsed line; strings borrow from the log, and the message keeps its escapes
+#[derive(PartialEq, Debug)]
+struct Record<'a> {
+    millis: u64,
+    level: Level,
+    service: &'a str,
+    method: Method,
+    path: &'a str,
+    status: u16,
+    latency_us: u32,
+    bytes: u64,
+    req: u64,
+    user: Option<u32>,
+    message: &'a str,
+}
+
+// The key=value fields in any order: all but user= are required, and a repeated key keeps its last value
+#[derive(Default)]
+struct Fields {
+    status: Option<u16>,
+    latency_us: Option<u32>,
+    bytes: Option<u64>,
+    req: Option<u64>,
+    user: Option<u32>,
+}
+
+enum Field {
+    Status(u16),
+    Latency(u32),
+    Bytes(u64),
+    Req(u64),
+    User(u32),
+}
+
+impl Fields {
+    fn set(mut self, field: Field) -> Self {
+        match field {
+            Field::Status(v) => self.status = Some(v),
+            Field::Latency(v) => self.latency_us = Some(v),
+            Field::Bytes(v) => self.bytes = Some(v),
+            Field::Req(v) => self.req = Some(v),
+            Field::User(v) => self.user = Some(v),
+        }
+        self
+    }
+}
+
+fn timestamp(millis: u64, year: u64, month: u64, day: u64, hour: u64, minute: u64, second: u64) -> Option<u64> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let days = days_from_civil(year as i64, month as u32, day as u32);
+    Some(days as u64 * 86_400_000 + ((hour * 60 + minute) * 60 + second) * 1000 + millis)
+}
+
+// Variant 1: nom combinators over &str, one small parser per piece of the line
+mod combinators {
+    use super::*;
+
+    fn digits(n: usize) -> impl FnMut(&str) -> IResult<&str, u64> {
+        move |i| map_res(take_while_m_n(n, n, |c: char| c.is_ascii_digit()), |s: &str| s.parse::<u64>()).parse(i)
+    }
+
+    fn timestamp(i: &str) -> IResult<&str, u64> {
+        let (i, (year, _, month, _, day, _, hour, _, minute, _, second, _, millis, _)) = (
+            digits(4), char('-'), digits(2), char('-'), digits(2), char('T'),
+            digits(2), char(':'), digits(2), char(':'), digits(2), char('.'), digits(3), char('Z'),
+        ).parse(i)?;
+        match super::timestamp(millis, year, month, day, hour, minute, second) {
+            Some(t) => Ok((i, t)),
+            None => Err(nom::Err::Error(nom::error::Error::new(i, nom::error::ErrorKind::Verify))),
+        }
+    }
+
+    fn level(i: &str) -> IResult<&str, Level> {
+        alt((
+            value(Level::Debug, tag("DEBUG")),
+            value(Level::Info, tag("INFO")),
+            value(Level::Warn, tag("WARN")),
+            value(Level::Error, tag("ERROR")),
+        )).parse(i)
+    }
+
+    fn method(i: &str) -> IResult<&str, Method> {
+        alt((
+            value(Method::Get, tag("GET")),
+            value(Method::Post, tag("POST")),
+            value(Method::Put, tag("PUT")),
+            value(Method::Delete, tag("DELETE")),
+        )).parse(i)
+    }
+
+    fn field(i: &str) -> IResult<&str, Field> {
+        alt((
+            preceded(tag("status="), u16).map(Field::Status),
+            preceded(tag("latency_us="), u32).map(Field::Latency),
+            preceded(tag("bytes="), u64).map(Field::Bytes),
+            preceded(tag("req="), map_res(hex_digit1, |h| u64::from_str_radix(h, 16))).map(Field::Req),
+            preceded(tag("user="), u32).map(Field::User),
+        )).parse(i)
+    }
+
+    pub fn record(line: &str) -> IResult<&str, Record<'_>> {
+        let (i, millis) = timestamp(line)?;
+        let (i, level) = preceded(char(' '), level).parse(i)?;
+        let (i, service) = preceded(tag(" ["), take_while1(|c: char| c.is_ascii_lowercase())).parse(i)?;
+        let (i, method) = preceded(tag("] "), method).parse(i)?;
+        let (i, path) = preceded(char(' '), is_not(" ")).parse(i)?;
+        let (i, fields) = fold_many1(preceded(char(' '), field), Fields::default, Fields::set).parse(i)?;
+        let message = delimited(tag(" msg=\""), opt(escaped(is_not("\\\""), '\\', one_of("\"\\"))), char('"'));
+        let (i, message) = all_consuming(message).parse(i)?;
+        let (Some(status), Some(latency_us), Some(bytes), Some(req)) = (fields.status, fields.latency_us, fields.bytes, fields.req) else {
+            return Err(nom::Err::Error(nom::error::Error::new(i, nom::error::ErrorKind::Verify)));
+        };
+        let record = Record {
+            millis, level, service, method, path, status, latency_us, bytes, req, user: fields.user,
+            message: message.unwrap_or(""),
+        };
+        Ok((i, record))
+    }
+
+    pub fn parse(line: &str) -> Option<Record<'_>> {
+        record(line).ok().map(|(_, r)| r)
+    }
+}
+
+// Variant 2: a hand-written cursor over the bytes, the same code as the C++ side
+mod handwritten {
+    use super::*;
+
+    struct Cursor<'a> {
+        line: &'a str,
+        s: &'a [u8],
+        pos: usize,
+    }
+
+    impl<'a> Cursor<'a> {
+        fn peek(&self) -> Option<u8> {
+            self.s.get(self.pos).copied()
+        }
+
+        fn byte(&mut self, b: u8) -> Option<()> {
+            if self.peek()? != b {
+                return None;
+            }
+            self.pos += 1;
+            Some(())
+        }
+
+        fn literal(&mut self, lit: &[u8]) -> bool {
+            let found = self.s[self.pos..].starts_with(lit);
+            if found {
+                self.pos += lit.len();
+            }
+            found
+        }
+
+        fn digits(&mut self, n: usize) -> Option<u64> {
+            let end = self.pos + n;
+            let mut v = 0;
+            for &c in self.s.get(self.pos..end)? {
+                if !c.is_ascii_digit() {
+                    return None;
+                }
+                v = v * 10 + (c - b'0') as u64;
+            }
+            self.pos = end;
+            Some(v)
+        }
+
+        // One or more decimal digits, rejecting values above max
+        fn number(&mut self, max: u64) -> Option<u64> {
+            let start = self.pos;
+            let mut v: u64 = 0;
+            while let Some(c) = self.peek().filter(u8::is_ascii_digit) {
+                v = v.checked_mul(10)?.checked_add((c - b'0') as u64)?;
+                self.pos += 1;
+            }
+            (self.pos > start && v <= max).then_some(v)
+        }
+
+        fn hex(&mut self) -> Option<u64> {
+            let start = self.pos;
+            let mut v: u64 = 0;
+            while let Some(d) = self.peek().and_then(|c| (c as char).to_digit(16)) {
+                v = v.checked_mul(16)? | d as u64;
+                self.pos += 1;
+            }
+            (self.pos > start).then_some(v)
+        }
+
+        fn take_while(&mut self, keep: impl Fn(u8) -> bool) -> &'a str {
+            let start = self.pos;
+            while self.peek().is_some_and(&keep) {
+                self.pos += 1;
+            }
+            // Stopping at an ASCII byte or the end keeps the slice on char boundaries
+            &self.line[start..self.pos]
+        }
+    }
+
+    pub fn parse(line: &str) -> Option<Record<'_>> {
+        let mut c = Cursor { line, s: line.as_bytes(), pos: 0 };
+        let year = c.digits(4)?;
+        c.byte(b'-')?;
+        let month = c.digits(2)?;
+        c.byte(b'-')?;
+        let day = c.digits(2)?;
+        c.byte(b'T')?;
+        let hour = c.digits(2)?;
+        c.byte(b':')?;
+        let minute = c.digits(2)?;
+        c.byte(b':')?;
+        let second = c.digits(2)?;
+        c.byte(b'.')?;
+        let millis = c.digits(3)?;
+        c.byte(b'Z')?;
+        let millis = timestamp(millis, year, month, day, hour, minute, second)?;
+
+        c.byte(b' ')?;
+        let level = if c.literal(b"DEBUG") {
+            Level::Debug
+        } else if c.literal(b"INFO") {
+            Level::Info
+        } else if c.literal(b"WARN") {
+            Level::Warn
+        } else if c.literal(b"ERROR") {
+            Level::Error
+        } else {
+            return None;
+        };
+        if !c.literal(b" [") {
+            return None;
+        }
+        let service = c.take_while(|b| b.is_ascii_lowercase());
+        if service.is_empty() || !c.literal(b"] ") {
+            return None;
+        }
+        let method = if c.literal(b"GET") {
+            Method::Get
+        } else if c.literal(b"POST") {
+            Method::Post
+        } else if c.literal(b"PUT") {
+            Method::Put
+        } else if c.literal(b"DELETE") {
+            Method::Delete
+        } else {
+            return None;
+        };
+        c.byte(b' ')?;
+        let path = c.take_while(|b| b != b' ');
+        if path.is_empty() {
+            return None;
+        }
+
+        let mut fields = Fields::default();
+        let mut count = 0;
+        loop {
+            let at = c.pos;
+            if c.byte(b' ').is_none() {
+                break;
+            }
+            let field = if c.literal(b"status=") {
+                c.number(u16::MAX as u64).map(|v| Field::Status(v as u16))
+            } else if c.literal(b"latency_us=") {
+                c.number(u32::MAX as u64).map(|v| Field::Latency(v as u32))
+            } else if c.literal(b"bytes=") {
+                c.number(u64::MAX).map(Field::Bytes)
+            } else if c.literal(b"req=") {
+                c.hex().map(Field::Req)
+            } else if c.literal(b"user=") {
+                c.number(u32::MAX as u64).map(|v| Field::User(v as u32))
+            } else {
+                None
+            };
+            match field {
+                Some(f) => {
+                    fields = fields.set(f);
+                    count += 1;
+                }
+                None => {
+                    c.pos = at;
+                    break;
+                }
+            }
+        }
+        if count == 0 || !c.literal(b" msg=\"") {
+            return None;
+        }
+        let start = c.pos;
+        loop {
+            match c.peek()? {
+                b'"' => break,
+                b'\\' => {
+                    c.pos += 1;
+                    if !matches!(c.peek()?, b'"' | b'\\') {
+                        return None;
+                    }
+                    c.pos += 1;
+                }
+                _ => c.pos += 1,
+            }
+        }
+        let message = &line[start..c.pos];
+        if c.pos + 1 != line.len() {
+            return None;
+        }
+        Some(Record {
+            millis, level, service, method, path,
+            status: fields.status?, latency_us: fields.latency_us?, bytes: fields.bytes?, req: fields.req?,
+            user: fields.user, message,
+        })
+    }
+}
+
+// What the benchmark keeps from each line, folded so both parsers can be compared
+#[derive(Default, PartialEq, Debug)]
+struct Stats {
+    lines: u64,
+    rejected: u64,
+    levels: [u64; 4],
+    methods: [u64; 4],
+    server_errors: u64,
+    latency_us: u64,
+    bytes: u64,
+    millis: u64,
+    req: u64,
+    users: u64,
+    text: u64, // service, path and unescaped message characters
+}
+
+// Length of a message once each \" and \\ escape collapses to one character
+fn unescaped_len(message: &str) -> usize {
+    let mut bytes = message.bytes();
+    let mut len = 0;
+    while let Some(b) = bytes.next() {
+        if b == b'\\' {
+            bytes.next();
+        }
+        len += 1;
+    }
+    len
+}
+
+impl Stats {
+    fn add(&mut self, record: Option<Record>) {
+        self.lines += 1;
+        let Some(r) = record else {
+            self.rejected += 1;
+            return;
+        };
+        self.levels[r.level as usize] += 1;
+        self.methods[r.method as usize] += 1;
+        self.server_errors += (r.status >= 500) as u64;
+        self.latency_us += r.latency_us as u64;
+        self.bytes += r.bytes;
+        self.millis = self.millis.wrapping_add(r.millis);
+        self.req ^= r.req;
+        self.users += r.user.map_or(0, |u| u as u64 + 1);
+        self.text += (r.service.len() + r.path.len() + unescaped_len(r.message)) as u64;
+    }
+
+    fn checksum(&self) -> u64 {
+        let fields = [self.lines, self.rejected, self.server_errors, self.latency_us, self.bytes, self.millis, self.req,
+                      self.users, self.text];
+        fields.iter().chain(&self.levels).chain(&self.methods).fold(0u64, |h, &v| h.wrapping_mul(31).wrapping_add(v))
+    }
+}
+
+fn run(log: &str, parse: impl Fn(&str) -> Option<Record<'_>>) -> Stats {
+    let mut stats = Stats::default();
+    for line in log.lines() {
+        stats.add(parse(line));
+    }
+    stats
+}
+
+fn parse_megabytes() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--megabytes") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .expect("--megabytes expects a positive integer"),
+        None => DEFAULT_MEGABYTES,
+    }
+}
+
+fn main() {
+    let megabytes = parse_megabytes();
+    let log = generate_log(megabytes << 20);
+
+    // Warm-up
+    let warmup = &log[..log[..WARMUP_BYTES.min(log.len())].rfind('\n').map_or(0, |n| n + 1)];
+    let _ = run(warmup, combinators::parse);
+    let _ = run(warmup, handwritten::parse);
+
+    // Benchmark
+    let start = Instant::now();
+    let nom_stats = run(&log, combinators::parse);
+    let nom_duration = start.elapsed();
+    let start = Instant::now();
+    let hand_stats = run(&log, handwritten::parse);
+    let hand_duration = start.elapsed();
+
+    if nom_stats != hand_stats {
+        eprintln!("nom and hand-written parsers disagree: {:?} vs {:?}", nom_stats, hand_stats);
+        std::process::exit(1);
+    }
+
+    let mb = log.len() as f64 / 1e6;
+    let rate = |seconds: f64| (mb / seconds, nom_stats.lines as f64 / seconds / 1e6);
+    let (nom_mbs, nom_lines) = rate(nom_duration.as_secs_f64());
+    let (hand_mbs, hand_lines) = rate(hand_duration.as_secs_f64());
+    println!("{:.6}", nom_duration.as_secs_f64());
+    eprintln!("Log: {:.1} MB, {} lines, {} malformed and rejected", mb, nom_stats.lines, nom_stats.rejected);
+    eprintln!("nom combinators: {:.6}s, {:.0} MB/s, {:.2}M lines/s", nom_duration.as_secs_f64(), nom_mbs, nom_lines);
+    eprintln!("Hand-written:    {:.6}s, {:.0} MB/s, {:.2}M lines/s ({:.2}x nom)", hand_duration.as_secs_f64(), hand_mbs,
+              hand_lines, nom_duration.as_secs_f64() / hand_duration.as_secs_f64());
+    eprintln!("Levels: DEBUG {}, INFO {}, WARN {}, ERROR {}; {} server errors, {} bytes served",
+              nom_stats.levels[0], nom_stats.levels[1], nom_stats.levels[2], nom_stats.levels[3],
+              nom_stats.server_errors, nom_stats.bytes);
+    eprintln!("Checksum: {}", nom_stats.checksum());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = "2023-11-14T22:13:20.042Z WARN [billing] GET /api/v2/orders/1234 latency_us=18234 status=503 \
+                        bytes=5120 req=00000000001a2b3c user=42 msg=\"upstream \\\"db\\\" C:\\\\tmp timeout\"";
+
+    #[test]
+    fn both_parsers_read_a_known_line() {
+        let expected = Record {
+            millis: BASE_MILLIS + 42, level: Level::Warn, service: "billing", method: Method::Get,
+            path: "/api/v2/orders/1234", status: 503, latency_us: 18234, bytes: 5120, req: 0x1a2b3c, user: Some(42),
+            message: "upstream \\\"db\\\" C:\\\\tmp timeout",
+        };
+        assert_eq!(combinators::parse(LINE), Some(expected));
+        assert_eq!(handwritten::parse(LINE), combinators::parse(LINE));
+    }
+
+    #[test]
+    fn both_parsers_reject_broken_lines() {
+        let broken = [
+            LINE.replace("-11-", "-13-"),
+            LINE.replace("status=503", "status=5x3"),
+            LINE.replace("status=503", "status=99999"),
+            LINE[..LINE.len() - 1].to_string(),
+            LINE.replace(" req=00000000001a2b3c", ""),
+            LINE.replace("WARN", "NOTICE"),
+            format!("{} ", LINE),
+        ];
+        for line in &broken {
+            assert_eq!(combinators::parse(line), None, "{}", line);
+            assert_eq!(handwritten::parse(line), None, "{}", line);
+        }
+    }
+
+    #[test]
+    fn parsers_agree_on_a_generated_log() {
+        let log = generate_log(1 << 20);
+        let stats = run(&log, combinators::parse);
+        assert_eq!(run(&log, handwritten::parse), stats);
+        assert!(stats.rejected > 0 && stats.rejected * 500 < stats.lines);
+        assert_eq!(days_from_civil(2023, 11, 14), 19_675);
+        assert_eq!(civil_from_days(19_675), (2023, 11, 14));
+    }
+}
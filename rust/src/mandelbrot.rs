@@ -1,9 +1,51 @@
 use rayon::prelude::*;
+use std::fs::File;
+use std::io::BufWriter;
+use std::str::FromStr;
 use std::time::Instant;
+use wide::f64x4;
 
 const WIDTH: usize = 4096;
 const HEIGHT: usize = 4096;
 const MAX_ITER: u32 = 1000;
+const LANES: usize = 4;
+const DEFAULT_CENTER: (f64, f64) = (-0.75, 0.0);
+const BASE_RE_SPAN: f64 = 3.5; // zoom 1 spans -2.5..1.0 on the real axis
+const BANDS: usize = 8; // contiguous row bands for the load-imbalance estimate
+// Deep zoom: a point near the seahorse-valley spiral, deep enough that neighbouring pixels
+// are closer together than f64 can resolve around the center
+const DEEP_CENTER: (&str, &str) = ("-0.743643887037158704752191506114774", "0.131825904205311970493132056385139");
+const DEEP_ZOOM: f64 = 1e20;
+const DEEP_SIZE: usize = 512;
+const DEEP_MAX_ITER: u32 = 20_000;
+const SPOT_CHECK_GRID: usize = 8; // 8x8 pixels re-iterated directly in double-double
+
+#[derive(Clone, Copy, PartialEq)]
+enum Variant {
+    Scalar,
+    Simd,
+    Deep,
+}
+
+// Image size and the rectangle of the complex plane it samples
+struct View {
+    width: usize,
+    height: usize,
+    min_re: f64,
+    max_re: f64,
+    min_im: f64,
+    max_im: f64,
+}
+
+impl View {
+    fn re(&self, x: usize) -> f64 {
+        self.min_re + (x as f64 / self.width as f64) * (self.max_re - self.min_re)
+    }
+
+    fn im(&self, y: usize) -> f64 {
+        self.min_im + (y as f64 / self.height as f64) * (self.max_im - self.min_im)
+    }
+}
 
 fn mandelbrot_point(cx: f64, cy: f64) -> u32 {
     let mut x = 0.0;
@@ -20,34 +62,490 @@ fn mandelbrot_point(cx: f64, cy: f64) -> u32 {
     iteration
 }
 
-fn compute_mandelbrot() -> Vec<u32> {
-    let min_re = -2.5;
-    let max_re = 1.0;
-    let min_im = -1.0;
-    let max_im = 1.0;
-    
-    (0..HEIGHT).into_par_iter().flat_map(|y| {
-        (0..WIDTH).into_par_iter().map(move |x| {
-            let cx = min_re + (x as f64 / WIDTH as f64) * (max_re - min_re);
-            let cy = min_im + (y as f64 / HEIGHT as f64) * (max_im - min_im);
-            mandelbrot_point(cx, cy)
-        }).collect::<Vec<_>>()
-    }).collect()
+// LANES adjacent pixels at once; lanes that escape are masked out of the count
+// while the rest keep iterating, and the loop ends once every lane has escaped
+fn mandelbrot_lanes(cx: f64x4, cy: f64) -> [u32; LANES] {
+    let cy = f64x4::splat(cy);
+    let four = f64x4::splat(4.0);
+    let one = f64x4::splat(1.0);
+    let mut x = f64x4::splat(0.0);
+    let mut y = f64x4::splat(0.0);
+    let mut counts = f64x4::splat(0.0);
+    let mut active = x.simd_eq(x);
+
+    for _ in 0..MAX_ITER {
+        active &= (x * x + y * y).simd_le(four);
+        if !active.any() {
+            break;
+        }
+        counts += active & one;
+        let xtemp = x * x - y * y + cx;
+        y = f64x4::splat(2.0) * x * y + cy;
+        x = xtemp;
+    }
+
+    counts.to_array().map(|c| c as u32)
+}
+
+fn compute_mandelbrot(variant: Variant, view: &View) -> Vec<u32> {
+    match variant {
+        Variant::Scalar => (0..view.height).into_par_iter().flat_map(|y| {
+            (0..view.width).into_par_iter().map(move |x| {
+                mandelbrot_point(view.re(x), view.im(y))
+            }).collect::<Vec<_>>()
+        }).collect(),
+        Variant::Simd => (0..view.height).into_par_iter().flat_map(|y| {
+            (0..view.width / LANES).into_par_iter().flat_map_iter(move |chunk| {
+                let cx: [f64; LANES] = std::array::from_fn(|lane| view.re(chunk * LANES + lane));
+                mandelbrot_lanes(f64x4::new(cx), view.im(y))
+            }).collect::<Vec<_>>()
+        }).collect(),
+        Variant::Deep => unreachable!("deep zoom renders through run_deep"),
+    }
+}
+
+// Unevaluated sum hi + lo with |lo| <= ulp(hi) / 2, about 106 bits of mantissa. Every
+// product goes through an explicit fused multiply-add so both languages round identically
+#[derive(Clone, Copy)]
+struct DoubleDouble {
+    hi: f64,
+    lo: f64,
+}
+
+impl DoubleDouble {
+    const ZERO: DoubleDouble = DoubleDouble { hi: 0.0, lo: 0.0 };
+
+    fn from_f64(x: f64) -> Self {
+        DoubleDouble { hi: x, lo: 0.0 }
+    }
+
+    // Digits are accumulated exactly enough in double-double, then scaled by the fraction length
+    fn parse(text: &str) -> Option<Self> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        let mut value = DoubleDouble::ZERO;
+        let mut fraction_digits = 0;
+        let mut seen_point = false;
+        for c in digits.chars() {
+            match c {
+                '.' if !seen_point => seen_point = true,
+                '0'..='9' => {
+                    value = value.mul_f64(10.0).add(DoubleDouble::from_f64((c as u8 - b'0') as f64));
+                    if seen_point {
+                        fraction_digits += 1;
+                    }
+                }
+                _ => return None,
+            }
+        }
+        for _ in 0..fraction_digits {
+            value = value.div_f64(10.0);
+        }
+        Some(if negative { value.neg() } else { value })
+    }
+
+    fn two_sum(a: f64, b: f64) -> Self {
+        let s = a + b;
+        let bb = s - a;
+        DoubleDouble { hi: s, lo: (a - (s - bb)) + (b - bb) }
+    }
+
+    fn quick_two_sum(a: f64, b: f64) -> Self {
+        let s = a + b;
+        DoubleDouble { hi: s, lo: b - (s - a) }
+    }
+
+    fn two_prod(a: f64, b: f64) -> Self {
+        let p = a * b;
+        DoubleDouble { hi: p, lo: a.mul_add(b, -p) }
+    }
+
+    fn neg(self) -> Self {
+        DoubleDouble { hi: -self.hi, lo: -self.lo }
+    }
+
+    fn add(self, other: Self) -> Self {
+        let s = Self::two_sum(self.hi, other.hi);
+        let t = Self::two_sum(self.lo, other.lo);
+        let hi = Self::quick_two_sum(s.hi, s.lo + t.hi);
+        Self::quick_two_sum(hi.hi, hi.lo + t.lo)
+    }
+
+    fn sub(self, other: Self) -> Self {
+        self.add(other.neg())
+    }
+
+    fn mul(self, other: Self) -> Self {
+        let p = Self::two_prod(self.hi, other.hi);
+        let lo = self.hi.mul_add(other.lo, self.lo.mul_add(other.hi, p.lo));
+        Self::quick_two_sum(p.hi, lo)
+    }
+
+    fn mul_f64(self, b: f64) -> Self {
+        let p = Self::two_prod(self.hi, b);
+        Self::quick_two_sum(p.hi, self.lo.mul_add(b, p.lo))
+    }
+
+    fn div_f64(self, b: f64) -> Self {
+        let q1 = self.hi / b;
+        let r = self.sub(Self::two_prod(q1, b));
+        let q2 = r.hi / b;
+        let q = Self::quick_two_sum(q1, q2);
+        let r = r.sub(Self::two_prod(q2, b));
+        Self::quick_two_sum(q.hi, q.lo + r.hi / b)
+    }
+}
+
+// Deep-zoom view: the center needs double-double, but offsets from it fit in f64
+struct DeepView {
+    width: usize,
+    height: usize,
+    center_re: DoubleDouble,
+    center_im: DoubleDouble,
+    zoom: f64,
+    re_span: f64,
+    im_span: f64,
+}
+
+impl DeepView {
+    fn delta(&self, x: usize, y: usize) -> (f64, f64) {
+        ((x as f64 / self.width as f64 - 0.5) * self.re_span, (y as f64 / self.height as f64 - 0.5) * self.im_span)
+    }
+}
+
+// The center's orbit Z_n in double-double, rounded to f64 for the perturbation loop.
+// Stops at the first escaped value, which the per-pixel loop never steps from
+fn reference_orbit(view: &DeepView) -> Vec<(f64, f64)> {
+    let (cr, ci) = (view.center_re, view.center_im);
+    let (mut zr, mut zi) = (DoubleDouble::ZERO, DoubleDouble::ZERO);
+    let mut orbit = Vec::with_capacity(DEEP_MAX_ITER as usize + 1);
+    loop {
+        orbit.push((zr.hi, zi.hi));
+        if zr.hi.mul_add(zr.hi, zi.hi * zi.hi) > 4.0 || orbit.len() > DEEP_MAX_ITER as usize {
+            return orbit;
+        }
+        let zr_next = zr.mul(zr).sub(zi.mul(zi)).add(cr);
+        zi = zr.mul(zi).mul_f64(2.0).add(ci);
+        zr = zr_next;
+    }
+}
+
+// Perturbation: with z = Z + d and c = C + dc, d_{n+1} = (2 Z_n + d_n) d_n + dc, all in f64.
+// When |z| drops below |d|, or the reference orbit runs out, the pixel rebases onto the
+// start of the orbit with d = z (Zhuoran's glitch avoidance), so one reference serves every pixel
+fn perturbed_point(orbit: &[(f64, f64)], dcr: f64, dci: f64) -> (u32, u32) {
+    let (mut dr, mut di) = (0.0f64, 0.0f64);
+    let mut reference = 0;
+    let mut rebases = 0;
+    let mut iteration = 0;
+    while iteration < DEEP_MAX_ITER {
+        let (zr, zi) = orbit[reference];
+        let (fr, fi) = (zr + dr, zi + di);
+        let magnitude = fr.mul_add(fr, fi * fi);
+        if magnitude > 4.0 {
+            break;
+        }
+        let (zr, zi) = if magnitude < dr.mul_add(dr, di * di) || reference == orbit.len() - 1 {
+            dr = fr;
+            di = fi;
+            reference = 0;
+            rebases += 1;
+            orbit[0]
+        } else {
+            (zr, zi)
+        };
+        let (ar, ai) = (zr + zr + dr, zi + zi + di);
+        let dr_next = ar.mul_add(dr, (-ai).mul_add(di, dcr));
+        di = ar.mul_add(di, ai.mul_add(dr, dci));
+        dr = dr_next;
+        reference += 1;
+        iteration += 1;
+    }
+    (iteration, rebases)
+}
+
+fn compute_deep(view: &DeepView, orbit: &[(f64, f64)]) -> (Vec<u32>, u64) {
+    let mut result = vec![0u32; view.width * view.height];
+    let rebases = result
+        .par_chunks_mut(view.width)
+        .enumerate()
+        .map(|(y, row)| {
+            let mut rebases = 0u64;
+            for (x, out) in row.iter_mut().enumerate() {
+                let (dcr, dci) = view.delta(x, y);
+                let (n, r) = perturbed_point(orbit, dcr, dci);
+                *out = n;
+                rebases += r as u64;
+            }
+            rebases
+        })
+        .sum();
+    (result, rebases)
+}
+
+// Direct double-double iteration of one pixel, the slow reference the perturbation must match
+fn direct_point(view: &DeepView, x: usize, y: usize) -> u32 {
+    let (dcr, dci) = view.delta(x, y);
+    let cr = view.center_re.add(DoubleDouble::from_f64(dcr));
+    let ci = view.center_im.add(DoubleDouble::from_f64(dci));
+    let (mut zr, mut zi) = (DoubleDouble::ZERO, DoubleDouble::ZERO);
+    let mut iteration = 0;
+    while iteration < DEEP_MAX_ITER && zr.hi.mul_add(zr.hi, zi.hi * zi.hi) <= 4.0 {
+        let zr_next = zr.mul(zr).sub(zi.mul(zi)).add(cr);
+        zi = zr.mul(zi).mul_f64(2.0).add(ci);
+        zr = zr_next;
+        iteration += 1;
+    }
+    iteration
+}
+
+// Black inside the set; outside, a smooth polynomial palette over sqrt-scaled iteration counts
+fn colorize(iterations: &[u32], max_iter: u32) -> Vec<u8> {
+    let mut rgb = Vec::with_capacity(iterations.len() * 3);
+    for &n in iterations {
+        if n >= max_iter {
+            rgb.extend_from_slice(&[0, 0, 0]);
+            continue;
+        }
+        let t = (n as f64 / max_iter as f64).sqrt();
+        let u = 1.0 - t;
+        rgb.push((9.0 * u * t * t * t * 255.0) as u8);
+        rgb.push((15.0 * u * u * t * t * 255.0) as u8);
+        rgb.push((8.5 * u * u * u * t * 255.0) as u8);
+    }
+    rgb
+}
+
+fn write_png(path: &str, width: usize, height: usize, iterations: &[u32], max_iter: u32) {
+    let file = File::create(path).expect("Failed to create PNG file");
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().expect("PNG header failed");
+    writer.write_image_data(&colorize(iterations, max_iter)).expect("PNG encode failed");
+}
+
+fn parse_variant() -> Variant {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--variant") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("scalar") => Variant::Scalar,
+            Some("simd") => Variant::Simd,
+            Some("deep") => Variant::Deep,
+            _ => panic!("--variant expects scalar, simd or deep"),
+        },
+        None => Variant::Scalar,
+    }
+}
+
+fn parse_flag<T: FromStr>(args: &[String], name: &str, expected: &str) -> Option<T> {
+    args.iter().position(|a| a == name).map(|i| {
+        args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| panic!("{} expects {}", name, expected))
+    })
+}
+
+fn parse_pair<T: FromStr>(args: &[String], name: &str, separator: char, expected: &str) -> Option<(T, T)> {
+    let value: String = parse_flag(args, name, expected)?;
+    let pair = value.split_once(separator).and_then(|(a, b)| Some((a.parse().ok()?, b.parse().ok()?)));
+    Some(pair.unwrap_or_else(|| panic!("{} expects {}", name, expected)))
+}
+
+// Without --center or --zoom the original stretched -2.5..1.0 x -1.0..1.0 region is kept,
+// so the default run is unchanged; either flag switches to square pixels around the center
+fn parse_view(args: &[String]) -> View {
+    let (width, height) = parse_pair(args, "--size", 'x', "WIDTHxHEIGHT").unwrap_or((WIDTH, HEIGHT));
+    assert!(width > 0 && height > 0 && width % LANES == 0, "--size width must be a positive multiple of {}", LANES);
+    let center: Option<(f64, f64)> = parse_pair(args, "--center", ',', "RE,IM");
+    let zoom: Option<f64> = parse_flag(args, "--zoom", "a positive number");
+    if center.is_none() && zoom.is_none() {
+        return View { width, height, min_re: -2.5, max_re: 1.0, min_im: -1.0, max_im: 1.0 };
+    }
+    let (center_re, center_im) = center.unwrap_or(DEFAULT_CENTER);
+    let zoom = zoom.unwrap_or(1.0);
+    assert!(zoom > 0.0, "--zoom expects a positive number");
+    let re_span = BASE_RE_SPAN / zoom;
+    let im_span = re_span * height as f64 / width as f64;
+    View {
+        width,
+        height,
+        min_re: center_re - re_span / 2.0,
+        max_re: center_re + re_span / 2.0,
+        min_im: center_im - im_span / 2.0,
+        max_im: center_im + im_span / 2.0,
+    }
+}
+
+// The center stays a string until it is parsed into double-double, since f64 would round
+// it to a point many pixels away at deep zoom
+fn parse_deep_view(args: &[String]) -> DeepView {
+    let (width, height) = parse_pair(args, "--size", 'x', "WIDTHxHEIGHT").unwrap_or((DEEP_SIZE, DEEP_SIZE));
+    assert!(width > 0 && height > 0, "--size expects WIDTHxHEIGHT");
+    let (re, im) = match parse_pair::<String>(args, "--center", ',', "RE,IM") {
+        Some((re, im)) => (re, im),
+        None => (DEEP_CENTER.0.to_string(), DEEP_CENTER.1.to_string()),
+    };
+    let center_re = DoubleDouble::parse(&re).expect("--center expects decimal RE,IM");
+    let center_im = DoubleDouble::parse(&im).expect("--center expects decimal RE,IM");
+    let zoom: f64 = parse_flag(args, "--zoom", "a positive number").unwrap_or(DEEP_ZOOM);
+    assert!(zoom > 0.0, "--zoom expects a positive number");
+    let re_span = BASE_RE_SPAN / zoom;
+    DeepView { width, height, center_re, center_im, zoom, re_span, im_span: re_span * height as f64 / width as f64 }
+}
+
+// How unevenly the work is spread: the busiest of BANDS equal row bands against the mean,
+// which is what a static row split across threads would see
+fn band_imbalance(iterations: &[u32], width: usize, height: usize) -> f64 {
+    let rows_per_band = height.div_ceil(BANDS);
+    let bands: Vec<u64> = iterations
+        .chunks(rows_per_band * width)
+        .map(|band| band.iter().map(|&n| n as u64).sum())
+        .collect();
+    let mean = bands.iter().sum::<u64>() as f64 / bands.len() as f64;
+    *bands.iter().max().unwrap() as f64 / mean
+}
+
+fn print_iteration_stats(result: &[u32], width: usize, height: usize, max_iter: u32) {
+    let total_iterations: u64 = result.iter().map(|&x| x as u64).sum();
+    let in_set = result.iter().filter(|&&x| x >= max_iter).count();
+    eprintln!("Iterations: mean {:.2}, {:.2}% in set, {}-band imbalance {:.2}x",
+              total_iterations as f64 / result.len() as f64, 100.0 * in_set as f64 / result.len() as f64,
+              BANDS, band_imbalance(result, width, height));
+}
+
+fn run_deep(args: &[String], png_path: Option<String>) {
+    let view = parse_deep_view(args);
+    let (_, _) = compute_deep(&view, &reference_orbit(&view)); // warm-up
+
+    // Benchmark: the reference orbit is part of the work, computed once per frame
+    let start = Instant::now();
+    let orbit = reference_orbit(&view);
+    let orbit_time = start.elapsed();
+    let (result, rebases) = compute_deep(&view, &orbit);
+    let duration = start.elapsed();
+
+    let checksum: u64 = result.iter().take(1000).map(|&x| x as u64).sum();
+    let mut spot_mismatches = 0;
+    for gy in 0..SPOT_CHECK_GRID {
+        for gx in 0..SPOT_CHECK_GRID {
+            let (x, y) = ((2 * gx + 1) * view.width / (2 * SPOT_CHECK_GRID), (2 * gy + 1) * view.height / (2 * SPOT_CHECK_GRID));
+            if direct_point(&view, x, y) != result[y * view.width + x] {
+                spot_mismatches += 1;
+            }
+        }
+    }
+
+    println!("{:.6}", duration.as_secs_f64());
+    eprintln!("{}", bench_cpu::report());
+    eprintln!("Variant: deep (double-double reference orbit, f64 perturbation)");
+    eprintln!("{}", bench_cpu::path("escape", bench_cpu::Path::Scalar, "f64 perturbation"));
+    eprintln!("Region: {}x{}, zoom {:e}, pixel spacing {:e}", view.width, view.height, view.zoom, view.re_span / view.width as f64);
+    eprintln!("Reference orbit: {} iterations in {:.3}ms, {} rebases",
+              orbit.len() - 1, orbit_time.as_secs_f64() * 1e3, rebases);
+    print_iteration_stats(&result, view.width, view.height, DEEP_MAX_ITER);
+    eprintln!("Spot check: {}/{} pixels match direct double-double iteration",
+              SPOT_CHECK_GRID * SPOT_CHECK_GRID - spot_mismatches, SPOT_CHECK_GRID * SPOT_CHECK_GRID);
+    if let Some(path) = png_path {
+        write_png(&path, view.width, view.height, &result, DEEP_MAX_ITER);
+        eprintln!("Wrote {}", path);
+    }
+    eprintln!("Checksum: {}", checksum);
 }
 
 fn main() {
+    let mut measure = bench_measure::from_env();
+    let args: Vec<String> = std::env::args().collect();
+    let variant = parse_variant();
+    let view = parse_view(&args);
+    let png_path: Option<String> = parse_flag(&args, "--png", "a file path");
+    if variant == Variant::Deep {
+        run_deep(&args, png_path);
+        return;
+    }
+
     // Warm-up
-    let _ = compute_mandelbrot();
+    let _ = compute_mandelbrot(variant, &view);
     
     // Benchmark
-    let start = Instant::now();
-    let result = compute_mandelbrot();
-    let duration = start.elapsed();
+    measure.start();
+    let result = compute_mandelbrot(variant, &view);
+    measure.stop();
     
     // Checksum
     let checksum: u64 = result.iter().take(1000).map(|&x| x as u64).sum();
     
-    println!("{:.6}", duration.as_secs_f64());
+    measure.report();
+    eprintln!("{}", bench_cpu::report());
+    if variant == Variant::Simd {
+        eprintln!("Variant: simd (f64x4, {} pixels per vector)", LANES);
+        eprintln!("{}", bench_cpu::path("escape", bench_cpu::Path::Simd, &format!("f64x4 on {}", bench_cpu::simd_isa())));
+    } else {
+        eprintln!("Variant: scalar");
+        eprintln!("{}", bench_cpu::path("escape", bench_cpu::Path::Scalar, "f64"));
+    }
+    eprintln!("Region: {}x{}, re {:.12} .. {:.12}, im {:.12} .. {:.12}",
+              view.width, view.height, view.min_re, view.max_re, view.min_im, view.max_im);
+    print_iteration_stats(&result, view.width, view.height, MAX_ITER);
+    if let Some(path) = png_path {
+        write_png(&path, view.width, view.height, &result, MAX_ITER);
+        eprintln!("Wrote {}", path);
+    }
     eprintln!("Checksum: {}", checksum);
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_of_known_orbits() {
+        // Bounded: the fixed point 0, the 2-cycle 0, -1, the tip -2 -> 2 -> 2, and i -> -1 + i -> -i
+        for (cx, cy) in [(0.0, 0.0), (-1.0, 0.0), (-2.0, 0.0), (0.0, 1.0), (-0.75, 0.0)] {
+            assert_eq!(mandelbrot_point(cx, cy), MAX_ITER, "c = {} + {}i", cx, cy);
+        }
+        // Escaping: 0 -> 3; 0 -> 2 -> 6; 0 -> 1 -> 2 -> 5, each step counted while |z|² <= 4
+        assert_eq!(mandelbrot_point(3.0, 0.0), 1);
+        assert_eq!(mandelbrot_point(2.0, 0.0), 2);
+        assert_eq!(mandelbrot_point(1.0, 0.0), 3);
+    }
+
+    #[test]
+    fn simd_matches_scalar() {
+        let view = View { width: 64, height: 48, min_re: -2.5, max_re: 1.0, min_im: -1.25, max_im: 1.25 };
+        let scalar = compute_mandelbrot(Variant::Scalar, &view);
+        assert_eq!(scalar.len(), 64 * 48);
+        assert_eq!(compute_mandelbrot(Variant::Simd, &view), scalar);
+        assert!(scalar.contains(&MAX_ITER) && scalar.contains(&1));
+    }
+
+    #[test]
+    fn double_double_parses_beyond_f64() {
+        // The f64 nearest 0.1 is 0.1000000000000000055511151231257827...
+        let tenth = DoubleDouble::parse("0.1").unwrap();
+        assert_eq!(tenth.hi, 0.1);
+        assert!((tenth.sub(DoubleDouble::from_f64(0.1)).hi + 5.551115123125783e-18).abs() < 1e-30);
+        assert_eq!(DoubleDouble::parse("-1.5").unwrap().hi, -1.5);
+        assert!(DoubleDouble::parse("1.2.3").is_none());
+    }
+
+    // The perturbed orbit around a shallow reference agrees with direct iteration
+    #[test]
+    fn perturbation_matches_direct_iteration() {
+        let view = DeepView {
+            width: 16,
+            height: 16,
+            center_re: DoubleDouble::from_f64(-0.75),
+            center_im: DoubleDouble::from_f64(0.1),
+            zoom: 100.0,
+            re_span: BASE_RE_SPAN / 100.0,
+            im_span: BASE_RE_SPAN / 100.0,
+        };
+        let (result, _) = compute_deep(&view, &reference_orbit(&view));
+        for (x, y) in [(0, 0), (3, 12), (8, 8), (15, 1)] {
+            assert_eq!(result[y * view.width + x], direct_point(&view, x, y), "pixel ({}, {})", x, y);
+        }
+    }
+}
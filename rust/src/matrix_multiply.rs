@@ -1,46 +1,404 @@
 use rayon::prelude::*;
-use std::time::Instant;
-
-const SIZE: usize = 1024;
-
-fn matrix_multiply_parallel(a: &Vec<Vec<f64>>, b: &Vec<Vec<f64>>) -> Vec<Vec<f64>> {
-    let mut result = vec![vec![0.0; SIZE]; SIZE];
-    
-    result.par_iter_mut().enumerate().for_each(|(i, row)| {
-        for j in 0..SIZE {
-            let mut sum = 0.0;
-            for k in 0..SIZE {
-                sum += a[i][k] * b[k][j];
+use std::time::{Duration, Instant};
+
+const DEFAULT_SIZE: usize = 1024; // BENCH_PARAMS size, a multiple of BLOCK
+const BLOCK: usize = 64; // a 64x64 f64 tile is 32 KiB, so a tile of B stays cache-resident while reused
+
+// Matrices are n x n, flat row-major: element (i, j) lives at i * n + j. Hand-written kernels come
+// first, then library GEMMs, so results separate "hand-written Rust vs hand-written C++" from
+// "ecosystem vs ecosystem"
+#[derive(Clone, Copy)]
+enum Variant {
+    Naive,             // i-k-j: the inner loop streams a row of B into a row of C
+    Blocked,           // i-k-j over BLOCK x BLOCK tiles
+    BlockedTransposed, // B transposed first, then blocked row-by-row dot products
+    MatrixMultiply,    // the pure-Rust matrixmultiply crate's gemm, one call per band of rows
+    Blas,              // cblas_?gemm from the system BLAS (Accelerate or OpenBLAS), which threads itself
+}
+
+impl Variant {
+    fn name(self) -> &'static str {
+        match self {
+            Variant::Naive => "naive",
+            Variant::Blocked => "blocked",
+            Variant::BlockedTransposed => "blocked-transposed",
+            Variant::MatrixMultiply => "matrixmultiply",
+            Variant::Blas => "blas",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Precision {
+    F64,
+    F32,
+    Mixed, // f32 inputs, f64 accumulation and result
+}
+
+impl Precision {
+    fn name(self) -> &'static str {
+        match self {
+            Precision::F64 => "f64",
+            Precision::F32 => "f32",
+            Precision::Mixed => "mixed (f32 inputs, f64 accumulation)",
+        }
+    }
+}
+
+// Every multiply-add is an explicit FMA so both languages round identically in f32
+trait Real: Copy + Send + Sync + Default + std::ops::Add<Output = Self> {
+    fn from_f64(x: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn mul_add(self, a: Self, b: Self) -> Self;
+
+    // C[m x n] = A[m x n] * B[n x n], all row-major with row stride n
+    unsafe fn crate_gemm(m: usize, n: usize, a: *const Self, b: *const Self, c: *mut Self);
+
+    #[cfg(feature = "blas")]
+    unsafe fn cblas_gemm(n: usize, a: *const Self, b: *const Self, c: *mut Self);
+}
+
+impl Real for f64 {
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+    fn to_f64(self) -> f64 {
+        self
+    }
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        f64::mul_add(self, a, b)
+    }
+    unsafe fn crate_gemm(m: usize, n: usize, a: *const Self, b: *const Self, c: *mut Self) {
+        let s = n as isize;
+        matrixmultiply::dgemm(m, n, n, 1.0, a, s, 1, b, s, 1, 0.0, c, s, 1);
+    }
+    #[cfg(feature = "blas")]
+    unsafe fn cblas_gemm(n: usize, a: *const Self, b: *const Self, c: *mut Self) {
+        let n = n as i32;
+        cblas::cblas_dgemm(cblas::ROW_MAJOR, cblas::NO_TRANS, cblas::NO_TRANS, n, n, n, 1.0, a, n, b, n, 0.0, c, n);
+    }
+}
+
+impl Real for f32 {
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        f32::mul_add(self, a, b)
+    }
+    unsafe fn crate_gemm(m: usize, n: usize, a: *const Self, b: *const Self, c: *mut Self) {
+        let s = n as isize;
+        matrixmultiply::sgemm(m, n, n, 1.0, a, s, 1, b, s, 1, 0.0, c, s, 1);
+    }
+    #[cfg(feature = "blas")]
+    unsafe fn cblas_gemm(n: usize, a: *const Self, b: *const Self, c: *mut Self) {
+        let n = n as i32;
+        cblas::cblas_sgemm(cblas::ROW_MAJOR, cblas::NO_TRANS, cblas::NO_TRANS, n, n, n, 1.0, a, n, b, n, 0.0, c, n);
+    }
+}
+
+// Input element S widened (or passed through) to the accumulator type A
+#[inline]
+fn widen<S: Real, A: Real>(x: S) -> A {
+    A::from_f64(x.to_f64())
+}
+
+fn matrix_multiply_naive<S: Real, A: Real>(n: usize, a: &[S], b: &[S]) -> Vec<A> {
+    let mut result = vec![A::default(); n * n];
+
+    result.par_chunks_mut(n).enumerate().for_each(|(i, row)| {
+        for k in 0..n {
+            let a_ik: A = widen(a[i * n + k]);
+            let b_row = &b[k * n..(k + 1) * n];
+            for (c, &b_kj) in row.iter_mut().zip(b_row) {
+                *c = a_ik.mul_add(widen(b_kj), *c);
             }
-            row[j] = sum;
         }
     });
-    
+
     result
 }
 
-fn main() {
-    // Initialize matrices
-    let a: Vec<Vec<f64>> = (0..SIZE)
-        .map(|i| (0..SIZE).map(|j| (i + j) as f64).collect())
-        .collect();
-    
-    let b: Vec<Vec<f64>> = (0..SIZE)
-        .map(|i| (0..SIZE).map(|j| (i * j) as f64).collect())
-        .collect();
-    
+// Each task owns a band of BLOCK rows of C and walks the k and j tiles across it
+fn matrix_multiply_blocked<S: Real, A: Real>(n: usize, a: &[S], b: &[S]) -> Vec<A> {
+    let mut result = vec![A::default(); n * n];
+
+    result.par_chunks_mut(BLOCK * n).enumerate().for_each(|(band, rows)| {
+        let i0 = band * BLOCK;
+        for kk in (0..n).step_by(BLOCK) {
+            for jj in (0..n).step_by(BLOCK) {
+                for (di, row) in rows.chunks_mut(n).enumerate() {
+                    let c = &mut row[jj..jj + BLOCK];
+                    for k in kk..kk + BLOCK {
+                        let a_ik: A = widen(a[(i0 + di) * n + k]);
+                        let b_row = &b[k * n + jj..k * n + jj + BLOCK];
+                        for (c, &b_kj) in c.iter_mut().zip(b_row) {
+                            *c = a_ik.mul_add(widen(b_kj), *c);
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    result
+}
+
+fn transpose<S: Real>(n: usize, m: &[S]) -> Vec<S> {
+    let mut t = vec![S::default(); n * n];
+    for i in 0..n {
+        for j in 0..n {
+            t[j * n + i] = m[i * n + j];
+        }
+    }
+    t
+}
+
+// Four independent partial sums so the reduction can vectorize (strict addition order
+// would otherwise serialize it); len is a multiple of 4
+fn dot<S: Real, A: Real>(x: &[S], y: &[S]) -> A {
+    let mut lanes = [A::default(); 4];
+    for (xs, ys) in x.chunks_exact(4).zip(y.chunks_exact(4)) {
+        for l in 0..4 {
+            lanes[l] = widen::<S, A>(xs[l]).mul_add(widen(ys[l]), lanes[l]);
+        }
+    }
+    (lanes[0] + lanes[1]) + (lanes[2] + lanes[3])
+}
+
+// With B transposed both operands of each dot product are contiguous rows
+fn matrix_multiply_blocked_transposed<S: Real, A: Real>(n: usize, a: &[S], b: &[S]) -> Vec<A> {
+    let bt = transpose(n, b);
+    let mut result = vec![A::default(); n * n];
+
+    result.par_chunks_mut(BLOCK * n).enumerate().for_each(|(band, rows)| {
+        let i0 = band * BLOCK;
+        for kk in (0..n).step_by(BLOCK) {
+            for jj in (0..n).step_by(BLOCK) {
+                for (di, row) in rows.chunks_mut(n).enumerate() {
+                    let a_row = &a[(i0 + di) * n + kk..(i0 + di) * n + kk + BLOCK];
+                    for j in jj..jj + BLOCK {
+                        let bt_row = &bt[j * n + kk..j * n + kk + BLOCK];
+                        row[j] = row[j] + dot::<S, A>(a_row, bt_row);
+                    }
+                }
+            }
+        }
+    });
+
+    result
+}
+
+// matrixmultiply runs single-threaded without its `threading` feature, so rayon spreads
+// BLOCK-row bands of C over the cores the same way the hand-written kernels do
+fn matrix_multiply_crate<T: Real>(n: usize, a: &[T], b: &[T]) -> Vec<T> {
+    let mut result = vec![T::default(); n * n];
+
+    result.par_chunks_mut(BLOCK * n).enumerate().for_each(|(band, rows)| {
+        let a_band = &a[band * BLOCK * n..(band + 1) * BLOCK * n];
+        unsafe {
+            T::crate_gemm(BLOCK, n, a_band.as_ptr(), b.as_ptr(), rows.as_mut_ptr());
+        }
+    });
+
+    result
+}
+
+#[cfg(feature = "blas")]
+mod cblas {
+    pub const ROW_MAJOR: i32 = 101;
+    pub const NO_TRANS: i32 = 111;
+
+    #[cfg_attr(target_os = "macos", link(name = "Accelerate", kind = "framework"))]
+    #[cfg_attr(not(target_os = "macos"), link(name = "openblas"))]
+    extern "C" {
+        #[allow(clippy::too_many_arguments)]
+        pub fn cblas_dgemm(
+            layout: i32, trans_a: i32, trans_b: i32, m: i32, n: i32, k: i32,
+            alpha: f64, a: *const f64, lda: i32, b: *const f64, ldb: i32,
+            beta: f64, c: *mut f64, ldc: i32,
+        );
+        #[allow(clippy::too_many_arguments)]
+        pub fn cblas_sgemm(
+            layout: i32, trans_a: i32, trans_b: i32, m: i32, n: i32, k: i32,
+            alpha: f32, a: *const f32, lda: i32, b: *const f32, ldb: i32,
+            beta: f32, c: *mut f32, ldc: i32,
+        );
+    }
+}
+
+#[cfg(feature = "blas")]
+fn matrix_multiply_blas<T: Real>(n: usize, a: &[T], b: &[T]) -> Vec<T> {
+    let mut result = vec![T::default(); n * n];
+    unsafe {
+        T::cblas_gemm(n, a.as_ptr(), b.as_ptr(), result.as_mut_ptr());
+    }
+    result
+}
+
+#[cfg(not(feature = "blas"))]
+fn matrix_multiply_blas<T: Real>(_: usize, _: &[T], _: &[T]) -> Vec<T> {
+    panic!("--variant blas needs a build with `--features blas` (links Accelerate on macOS, OpenBLAS elsewhere)")
+}
+
+// The hand-written kernels, generic over input and accumulator precision
+fn matrix_multiply<S: Real, A: Real>(variant: Variant, n: usize, a: &[S], b: &[S]) -> Vec<A> {
+    match variant {
+        Variant::Naive => matrix_multiply_naive(n, a, b),
+        Variant::Blocked => matrix_multiply_blocked(n, a, b),
+        Variant::BlockedTransposed => matrix_multiply_blocked_transposed(n, a, b),
+        Variant::MatrixMultiply | Variant::Blas => unreachable!("library GEMMs have one precision throughout"),
+    }
+}
+
+fn matrix_multiply_uniform<T: Real>(variant: Variant, n: usize, a: &[T], b: &[T]) -> Vec<T> {
+    match variant {
+        Variant::MatrixMultiply => matrix_multiply_crate(n, a, b),
+        Variant::Blas => matrix_multiply_blas(n, a, b),
+        _ => matrix_multiply::<T, T>(variant, n, a, b),
+    }
+}
+
+// Converts the f64 inputs to S outside the timed region, then warms up and times one multiply.
+// The result comes back widened to f64 for the checksum and accuracy check
+fn benchmark<S: Real, A: Real>(a: &[f64], b: &[f64], multiply: impl Fn(&[S], &[S]) -> Vec<A>) -> (Duration, Vec<f64>) {
+    let a: Vec<S> = a.iter().map(|&x| S::from_f64(x)).collect();
+    let b: Vec<S> = b.iter().map(|&x| S::from_f64(x)).collect();
+
     // Warm-up
-    let _ = matrix_multiply_parallel(&a, &b);
-    
+    let _ = multiply(&a, &b);
+
     // Benchmark
     let start = Instant::now();
-    let result = matrix_multiply_parallel(&a, &b);
+    let result = multiply(&a, &b);
     let duration = start.elapsed();
-    
-    // Prevent optimization
-    let checksum: f64 = result[0].iter().sum();
-    
+
+    (duration, result.iter().map(|x| x.to_f64()).collect())
+}
+
+fn parse_variant() -> Variant {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--variant") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("naive") => Variant::Naive,
+            Some("blocked") => Variant::Blocked,
+            Some("blocked-transposed") => Variant::BlockedTransposed,
+            Some("matrixmultiply") => Variant::MatrixMultiply,
+            Some("blas") => Variant::Blas,
+            _ => panic!("--variant expects naive, blocked, blocked-transposed, matrixmultiply or blas"),
+        },
+        None => Variant::Blocked,
+    }
+}
+
+fn parse_precision() -> Precision {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--precision") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("f64") => Precision::F64,
+            Some("f32") => Precision::F32,
+            Some("mixed") => Precision::Mixed,
+            _ => panic!("--precision expects f64, f32 or mixed"),
+        },
+        None => Precision::F64,
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let placement = bench_numa::place(&args);
+    let variant = parse_variant();
+    let precision = parse_precision();
+    assert!(
+        precision != Precision::Mixed || !matches!(variant, Variant::MatrixMultiply | Variant::Blas),
+        "--precision mixed runs the hand-written kernels only"
+    );
+    let mut params = bench_params::Params::from_env();
+    let n = params.usize("size", DEFAULT_SIZE);
+    let cores = std::thread::available_parallelism().map_or(1, |c| c.get());
+    let threads = params.usize("threads", cores);
+    params.check();
+    assert!(n.is_multiple_of(BLOCK), "BENCH_PARAMS: size={} is not a multiple of the {}-row block", n, BLOCK);
+    // After the NUMA placement, so the pool's threads inherit it
+    rayon::ThreadPoolBuilder::new().num_threads(threads).build_global().expect("rayon pool already started");
+
+    // Initialize matrices
+    let a: Vec<f64> = (0..n * n).map(|e| (e / n + e % n) as f64).collect();
+    let b: Vec<f64> = (0..n * n).map(|e| (e / n * (e % n)) as f64).collect();
+
+    let (duration, result) = match precision {
+        Precision::F64 => benchmark::<f64, f64>(&a, &b, |a, b| matrix_multiply_uniform(variant, n, a, b)),
+        Precision::F32 => benchmark::<f32, f32>(&a, &b, |a, b| matrix_multiply_uniform(variant, n, a, b)),
+        Precision::Mixed => benchmark::<f32, f64>(&a, &b, |a, b| matrix_multiply(variant, n, a, b)),
+    };
+
+    // In f64 every product and partial sum is an integer below 2^53, so all variants agree
+    // exactly; the entries outgrow f32's 24-bit significand, so f32 results round
+    let checksum: f64 = result[..n].iter().sum();
+    let gflops = 2.0 * (n * n * n) as f64 / duration.as_secs_f64() / 1e9;
+
     println!("{:.6}", duration.as_secs_f64());
+    match variant {
+        Variant::Blocked | Variant::BlockedTransposed => eprintln!("Variant: {} ({}x{} blocks)", variant.name(), BLOCK, BLOCK),
+        _ => eprintln!("Variant: {}", variant.name()),
+    }
+    eprintln!("Precision: {}", precision.name());
+    eprintln!("{}", params.report());
+    eprintln!("Throughput: {:.2} GFLOP/s", gflops);
+    if precision != Precision::F64 {
+        // Normwise against the exact f64 product (untimed): max |C - C64| / max |C64|
+        let reference: Vec<f64> = matrix_multiply::<f64, f64>(Variant::Blocked, n, &a, &b);
+        let max_error = result.iter().zip(&reference).map(|(x, r)| (x - r).abs()).fold(0.0, f64::max);
+        let max_reference = reference.iter().map(|r| r.abs()).fold(0.0, f64::max);
+        eprintln!("Relative error vs f64: {:.3e}", max_error / max_reference);
+    }
+    eprintln!("{}", bench_numa::report(&placement));
     eprintln!("Checksum: {}", checksum);
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Small integers, so every variant's sums are exact in f32 as well as f64
+    fn small_integers(n: usize, seed: usize) -> Vec<f64> {
+        (0..n * n).map(|e| ((e * 7 + seed) % 9) as f64 - 4.0).collect()
+    }
+
+    // The default size and one a sweep might pick that is a multiple of the block but no power of two
+    #[test]
+    fn variants_agree_exactly_on_small_integers() {
+        for n in [DEFAULT_SIZE, 192] {
+            let (a, b) = (small_integers(n, 1), small_integers(n, 5));
+            let expected: Vec<f64> = matrix_multiply::<f64, f64>(Variant::Naive, n, &a, &b);
+            for (i, j) in [(0, 0), (3, n - 24), (n - 1, n - 1)] {
+                let direct: f64 = (0..n).map(|k| a[i * n + k] * b[k * n + j]).sum();
+                assert_eq!(expected[i * n + j], direct);
+            }
+
+            for variant in [Variant::Blocked, Variant::BlockedTransposed, Variant::MatrixMultiply] {
+                assert!(matrix_multiply_uniform::<f64>(variant, n, &a, &b) == expected, "{} at {}", variant.name(), n);
+            }
+            let (a32, b32): (Vec<f32>, Vec<f32>) = (a.iter().map(|&x| x as f32).collect(), b.iter().map(|&x| x as f32).collect());
+            for variant in [Variant::Naive, Variant::BlockedTransposed, Variant::MatrixMultiply] {
+                let widened: Vec<f64> = matrix_multiply_uniform::<f32>(variant, n, &a32, &b32).iter().map(|&x| x as f64).collect();
+                assert!(widened == expected, "f32 {} at {}", variant.name(), n);
+            }
+            assert!(matrix_multiply::<f32, f64>(Variant::Blocked, n, &a32, &b32) == expected);
+        }
+    }
+
+    #[test]
+    fn transpose_and_dot() {
+        let n = DEFAULT_SIZE;
+        let m: Vec<f64> = (0..n * n).map(|e| e as f64).collect();
+        let t = transpose(n, &m);
+        assert_eq!(t[1], n as f64);
+        assert!(transpose(n, &t) == m);
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        assert_eq!(dot::<f64, f64>(&x, &x), 204.0);
+    }
+}
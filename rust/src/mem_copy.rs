@@ -0,0 +1,209 @@
+use std::fmt::Write as _;
+use std::hint::black_box;
+use std::ptr;
+use std::time::{Duration, Instant};
+
+const MIN_SIZE: usize = 64; // 64 B .. 1 GiB in powers of four
+const MAX_SIZE: usize = 1 << 30;
+const BYTES_PER_POINT: usize = 512 << 20;
+const ALIGN: usize = 64; // the cache line; "+1" destinations sit one byte past it
+const FILL_BYTE: u8 = 0xA5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Op {
+    CopyFromSlice,
+    CloneFromSlice, // specialized to the same memcpy for Copy types
+    CopyNonoverlapping,
+    Fill,
+}
+
+const OPS: [Op; 4] = [Op::CopyFromSlice, Op::CloneFromSlice, Op::CopyNonoverlapping, Op::Fill];
+
+impl Op {
+    // Column labels, shared with the C++ grid (std::copy, element loop, std::memcpy, std::memset)
+    fn label(self) -> &'static str {
+        match self {
+            Op::CopyFromSlice => "copy",
+            Op::CloneFromSlice => "clone",
+            Op::CopyNonoverlapping => "memcpy",
+            Op::Fill => "fill",
+        }
+    }
+}
+
+// Runs `op` `reps` times over equal-length slices. black_box on both sides every time keeps the
+// compiler from hoisting a repeated copy or fill out of the loop
+fn run(op: Op, src: &[u8], dst: &mut [u8], reps: usize) {
+    assert_eq!(src.len(), dst.len());
+    match op {
+        Op::CopyFromSlice => {
+            for _ in 0..reps {
+                black_box(&mut *dst).copy_from_slice(black_box(src));
+            }
+        }
+        Op::CloneFromSlice => {
+            for _ in 0..reps {
+                black_box(&mut *dst).clone_from_slice(black_box(src));
+            }
+        }
+        Op::CopyNonoverlapping => {
+            for _ in 0..reps {
+                let dst = black_box(&mut *dst);
+                // Safety: the slices have equal lengths, and a shared and a mutable borrow cannot overlap
+                unsafe { ptr::copy_nonoverlapping(black_box(src).as_ptr(), dst.as_mut_ptr(), dst.len()) };
+            }
+        }
+        Op::Fill => {
+            for _ in 0..reps {
+                black_box(&mut *dst).fill(FILL_BYTE);
+            }
+        }
+    }
+}
+
+struct Point {
+    size: usize,
+    gb_per_s: f64, // bytes written per second: one copy moves `size` bytes
+}
+
+// Times `op` on `size` bytes, with the destination `offset` bytes past its ALIGN boundary. Also
+// returns the first and last bytes written, for the checksum
+fn measure(op: Op, src: &[u8], dst: &mut [u8], size: usize, offset: usize) -> (Duration, Point, u64) {
+    let (src, dst) = (&src[..size], &mut dst[offset..offset + size]);
+    let reps = bench_cache::repetitions((BYTES_PER_POINT / size).max(1));
+
+    // One untimed run pulls both buffers into whatever level they fit (and faults them in)
+    run(op, src, dst, 1);
+    let elapsed = if bench_cache::cold() {
+        // One repetition at a time, each starting with both buffers flushed out to DRAM
+        (0..reps)
+            .map(|_| {
+                bench_cache::flush(src);
+                bench_cache::flush(dst);
+                let start = Instant::now();
+                run(op, src, dst, 1);
+                start.elapsed()
+            })
+            .sum()
+    } else {
+        let start = Instant::now();
+        run(op, src, dst, reps);
+        start.elapsed()
+    };
+    let gb_per_s = (size * reps) as f64 / elapsed.as_secs_f64() / 1e9;
+    (elapsed, Point { size, gb_per_s }, dst[0] as u64 + dst[size - 1] as u64)
+}
+
+// The ALIGN-aligned part of `buf`
+fn aligned(buf: &mut [u8]) -> &mut [u8] {
+    let start = buf.as_ptr().align_offset(ALIGN);
+    &mut buf[start..]
+}
+
+fn size_label(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1 << 30 => format!("{} GiB", b >> 30),
+        b if b >= 1 << 20 => format!("{} MiB", b >> 20),
+        b if b >= 1 << 10 => format!("{} KiB", b >> 10),
+        b => format!("{} B", b),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let placement = bench_numa::place(&args);
+    bench_pages::enable(&args);
+    bench_cache::enable(&args);
+
+    let mut src_buf = bench_pages::filled(0u8, MAX_SIZE + ALIGN);
+    let mut dst_buf = bench_pages::filled(0u8, MAX_SIZE + 2 * ALIGN);
+    let src = aligned(&mut src_buf);
+    // Relative to the aligned start, so the bytes copied (and the checksum) do not depend on where
+    // the allocator put the buffer
+    for (i, b) in src.iter_mut().enumerate() {
+        *b = (i % 251) as u8;
+    }
+    let src = &*src;
+    let dst = aligned(&mut dst_buf);
+
+    let mut total = Duration::ZERO;
+    let mut checksum = 0u64;
+    let mut rows = Vec::new();
+    let mut size = MIN_SIZE;
+    while size <= MAX_SIZE {
+        let mut row = Vec::new();
+        for op in OPS {
+            for offset in [0, 1] {
+                let (elapsed, point, ends) = measure(op, src, dst, size, offset);
+                total += elapsed;
+                checksum += ends;
+                row.push(point);
+            }
+        }
+        rows.push(row);
+        size *= 4;
+    }
+
+    let mut header = format!("{:>8}", "GB/s");
+    for op in OPS {
+        write!(header, " {:>8} {:>8}", op.label(), format!("{}+1", op.label())).unwrap();
+    }
+    eprintln!("Bytes written per second, rows = size, columns = operation (+1: destination one byte past 64-byte alignment)");
+    eprintln!("copy = copy_from_slice, clone = clone_from_slice, memcpy = ptr::copy_nonoverlapping, fill = fill");
+    eprintln!("{}", header);
+    for row in &rows {
+        let mut line = format!("{:>8}", size_label(row[0].size));
+        for p in row {
+            write!(line, " {:>8.2}", p.gb_per_s).unwrap();
+        }
+        eprintln!("{}", line);
+    }
+
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("{}", bench_numa::report(&placement));
+    eprintln!("{}", bench_pages::report());
+    eprintln!("{}", bench_cache::report());
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_op_writes_the_whole_destination() {
+        let src: Vec<u8> = (0..300).map(|i| (i % 251) as u8).collect();
+        for op in OPS {
+            for offset in [0, 1] {
+                let mut dst = vec![0u8; 302];
+                run(op, &src[..257], &mut dst[offset..offset + 257], 2);
+                let expected: Vec<u8> = match op {
+                    Op::Fill => vec![FILL_BYTE; 257],
+                    _ => src[..257].to_vec(),
+                };
+                assert_eq!(&dst[offset..offset + 257], &expected[..], "{:?} +{}", op, offset);
+                assert!(dst[..offset].iter().chain(&dst[offset + 257..]).all(|&b| b == 0), "{:?} +{}", op, offset);
+            }
+        }
+    }
+
+    // The checksum main prints adds these: the pattern's first and last byte, or two fill bytes
+    #[test]
+    fn measure_returns_the_ends_written() {
+        let src: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+        let mut dst = vec![0u8; 4096 + ALIGN];
+        let (_, point, ends) = measure(Op::CopyFromSlice, &src, &mut dst, 1024, 1);
+        assert_eq!(ends, 1023 % 251);
+        assert!(point.gb_per_s > 0.0);
+        let (_, _, ends) = measure(Op::Fill, &src, &mut dst, 64, 0);
+        assert_eq!(ends, 2 * FILL_BYTE as u64);
+    }
+
+    #[test]
+    fn labels_sizes() {
+        assert_eq!(size_label(64), "64 B");
+        assert_eq!(size_label(16 << 10), "16 KiB");
+        assert_eq!(size_label(256 << 20), "256 MiB");
+        assert_eq!(size_label(1 << 30), "1 GiB");
+    }
+}
@@ -1,106 +1,253 @@
+#[cfg(target_os = "macos")]
+#[macro_use]
+extern crate objc;
+
+#[cfg(target_os = "macos")]
 use metal::*;
-use std::time::Instant;
+#[cfg(target_os = "macos")]
+use rayon::prelude::*;
+#[cfg(target_os = "macos")]
+use std::str::FromStr;
+#[cfg(target_os = "macos")]
+use std::time::{Duration, Instant};
 
+#[cfg(target_os = "macos")]
 const MATRIX_SIZE: usize = 2048;  // 2048x2048 matrix
-const TOTAL_ELEMENTS: usize = MATRIX_SIZE * MATRIX_SIZE;
+#[cfg(target_os = "macos")]
 const ITERATIONS: usize = 10;
+#[cfg(target_os = "macos")]
+const TUNE_ITERATIONS: usize = 3; // timed dispatches per tuning candidate
+#[cfg(target_os = "macos")]
+const MIN_GROUP_SIDE: u64 = 8;    // tuning sweeps power-of-two sides from 8x8 up to the pipeline limit
+#[cfg(target_os = "macos")]
+const CHECK_SIZE: usize = 256; // validated element-wise against the CPU
+#[cfg(target_os = "macos")]
+const TOLERANCE: f32 = 1e-3;   // relative; Metal compiles with fast math by default
+
+// The default kernel, also usable as a starting point for --kernel-file
+#[cfg(target_os = "macos")]
+const SHADER_SOURCE: &str = include_str!("../../shaders/heavy_compute.metal");
+
+// dispatchThreads lets Metal trim the edge threadgroups; dispatchThreadgroups launches whole
+// groups only, so it is used only where the groups tile the grid (heavy_compute has no bounds check)
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy, PartialEq)]
+enum Strategy {
+    Threads,
+    Threadgroups,
+}
+
+#[cfg(target_os = "macos")]
+impl Strategy {
+    fn name(self) -> &'static str {
+        match self {
+            Strategy::Threads => "dispatchThreads",
+            Strategy::Threadgroups => "dispatchThreadgroups",
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy)]
+struct Launch {
+    threadgroup: (u64, u64),
+    strategy: Strategy,
+}
 
+#[cfg(target_os = "macos")]
+impl Launch {
+    fn tiles(&self, n: usize) -> bool {
+        let (w, h) = self.threadgroup;
+        self.strategy == Strategy::Threads || (n as u64 % w == 0 && n as u64 % h == 0)
+    }
+}
+
+// Everything a GPU experiment might change without recompiling. A replacement kernel must take
+// the same arguments as heavy_compute: a, b and result at buffers 0-2, the matrix size at 3
+#[cfg(target_os = "macos")]
+struct Config {
+    kernel_file: Option<String>, // .metallib is loaded precompiled, anything else compiled as MSL
+    kernel_name: String,
+    matrix_size: usize,
+    iterations: usize,
+    threadgroup: Option<(u64, u64)>, // None tunes the shape
+    strategy: Option<Strategy>,      // None tunes the dispatch call
+}
+
+#[cfg(target_os = "macos")]
+fn parse_flag<T: FromStr>(args: &[String], name: &str, expected: &str) -> Option<T> {
+    args.iter().position(|a| a == name).map(|i| {
+        args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| panic!("{} expects {}", name, expected))
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn parse_config(args: &[String]) -> Config {
+    let threadgroup = parse_flag::<String>(args, "--threadgroup", "WIDTHxHEIGHT").map(|value| {
+        value
+            .split_once('x')
+            .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+            .filter(|&(w, h): &(u64, u64)| w > 0 && h > 0)
+            .unwrap_or_else(|| panic!("--threadgroup expects WIDTHxHEIGHT"))
+    });
+    Config {
+        kernel_file: parse_flag(args, "--kernel-file", "a .metal or .metallib path"),
+        kernel_name: parse_flag(args, "--kernel-name", "a kernel function name").unwrap_or_else(|| "heavy_compute".to_string()),
+        matrix_size: parse_flag(args, "--size", "a matrix size").unwrap_or(MATRIX_SIZE),
+        iterations: parse_flag(args, "--iterations", "an iteration count").unwrap_or(ITERATIONS),
+        threadgroup,
+        strategy: parse_flag::<String>(args, "--dispatch", "threads or threadgroups").map(|value| match value.as_str() {
+            "threads" => Strategy::Threads,
+            "threadgroups" => Strategy::Threadgroups,
+            _ => panic!("--dispatch expects threads or threadgroups"),
+        }),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn load_library(device: &Device, config: &Config) -> (Library, &'static str) {
+    match &config.kernel_file {
+        Some(path) if path.ends_with(".metallib") => {
+            let library = device.new_library_with_file(path)
+                .unwrap_or_else(|e| panic!("Failed to load {}: {}", path, e));
+            (library, "precompiled metallib")
+        }
+        Some(path) => {
+            let source = std::fs::read_to_string(path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e));
+            let library = device.new_library_with_source(&source, &CompileOptions::new())
+                .unwrap_or_else(|e| panic!("Failed to compile {}: {}", path, e));
+            (library, "MSL source compiled at startup")
+        }
+        None => {
+            let library = device.new_library_with_source(SHADER_SOURCE, &CompileOptions::new())
+                .expect("Failed to compile shader");
+            (library, "embedded MSL source")
+        }
+    }
+}
+
+// Metal only exists on Apple platforms; gpu_compute runs the same kernel everywhere through wgpu
+#[cfg(not(target_os = "macos"))]
 fn main() {
-    // Initialize Metal
-    let device = Device::system_default().expect("No Metal device found");
-    
-    let shader_source = r#"
-        #include <metal_stdlib>
-        using namespace metal;
-        
-        // Heavy compute shader: matrix element-wise operations with trigonometric functions
-        kernel void heavy_compute(device const float* a [[buffer(0)]],
-                                 device const float* b [[buffer(1)]],
-                                 device float* result [[buffer(2)]],
-                                 constant uint& matrix_size [[buffer(3)]],
-                                 uint2 gid [[thread_position_in_grid]])
-        {
-            uint idx = gid.y * matrix_size + gid.x;
-            
-            // Perform multiple heavy computations
-            float val_a = a[idx];
-            float val_b = b[idx];
-            
-            float sum = 0.0;
-            for (int i = 0; i < 50; i++) {
-                float t = val_a * float(i) * 0.01 + val_b;
-                sum += sin(t) * cos(val_b * float(i) * 0.01) + 
-                       tan(val_a * 0.1) * exp(-val_b * 0.001) +
-                       sqrt(abs(t)) * log(abs(t) + 1.0);
-            }
-            
-            // Additional matrix-style computation
-            float accumulator = 0.0;
-            uint row = gid.y;
-            for (uint col = 0; col < matrix_size; col++) {
-                uint idx2 = row * matrix_size + col;
-                accumulator += a[idx2] * b[idx2] * 0.0001;
+    eprintln!("metal_compute requires macOS; use gpu_compute for the portable wgpu version");
+    std::process::exit(1);
+}
+
+#[cfg(target_os = "macos")]
+fn inputs(n: usize) -> (Vec<f32>, Vec<f32>) {
+    let a = (0..n * n).map(|i| (i as f32 * 0.001).sin()).collect();
+    let b = (0..n * n).map(|i| (i as f32 * 0.002).cos()).collect();
+    (a, b)
+}
+
+// heavy_compute on the CPU in f32, one row per rayon task. The row reduction is the same for
+// every element of a row, so it is done once per row rather than once per element
+#[cfg(target_os = "macos")]
+fn cpu_heavy_compute(a: &[f32], b: &[f32], n: usize) -> Vec<f32> {
+    let mut result = vec![0.0f32; n * n];
+    result.par_chunks_mut(n).enumerate().for_each(|(row, out)| {
+        let (a, b) = (&a[row * n..(row + 1) * n], &b[row * n..(row + 1) * n]);
+        let accumulator = a.iter().zip(b).fold(0.0f32, |acc, (&x, &y)| acc + x * y * 0.0001);
+        for (col, out) in out.iter_mut().enumerate() {
+            let (val_a, val_b) = (a[col], b[col]);
+            let mut sum = 0.0f32;
+            for i in 0..50 {
+                let t = val_a * i as f32 * 0.01 + val_b;
+                sum += t.sin() * (val_b * i as f32 * 0.01).cos()
+                    + (val_a * 0.1).tan() * (-val_b * 0.001).exp()
+                    + t.abs().sqrt() * (t.abs() + 1.0).ln();
             }
-            
-            result[idx] = sum + accumulator;
+            *out = sum + accumulator;
         }
-    "#;
-    
-    let compile_options = CompileOptions::new();
-    let library = device.new_library_with_source(shader_source, &compile_options)
-        .expect("Failed to compile shader");
-    
-    let kernel = library.get_function("heavy_compute", None)
-        .expect("Failed to get kernel function");
-    
-    let pipeline = device.new_compute_pipeline_state_with_function(&kernel)
-        .expect("Failed to create pipeline");
-    
-    // Create input data - large matrices
-    let input_a: Vec<f32> = (0..TOTAL_ELEMENTS).map(|i| (i as f32 * 0.001).sin()).collect();
-    let input_b: Vec<f32> = (0..TOTAL_ELEMENTS).map(|i| (i as f32 * 0.002).cos()).collect();
+    });
+    result
+}
+
+// Launches the tuning pass tries: every shape and strategy not fixed on the command line
+#[cfg(target_os = "macos")]
+fn candidates(config: &Config, max_threads: u64) -> Vec<Launch> {
+    let shapes = match config.threadgroup {
+        Some(shape) => vec![shape],
+        None => {
+            let sides: Vec<u64> = (0..).map(|k| MIN_GROUP_SIDE << k).take_while(|&side| side * MIN_GROUP_SIDE <= max_threads).collect();
+            sides.iter().flat_map(|&w| sides.iter().map(move |&h| (w, h))).filter(|&(w, h)| w * h <= max_threads).collect()
+        }
+    };
+    let strategies = match config.strategy {
+        Some(strategy) => vec![strategy],
+        None => vec![Strategy::Threads, Strategy::Threadgroups],
+    };
+    shapes.iter()
+        .flat_map(|&threadgroup| strategies.iter().map(move |&strategy| Launch { threadgroup, strategy }))
+        .filter(|launch| config.strategy.is_some() || launch.tiles(config.matrix_size))
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+struct Run {
+    result: Vec<f32>,
+    wall: Duration,
+    encode: Duration,
+    gpu: f64,
+}
+
+// One untimed warm-up dispatch, then `iterations` timed ones over an n x n grid
+#[cfg(target_os = "macos")]
+fn dispatch(device: &Device, pipeline: &ComputePipelineState, n: usize, (input_a, input_b): (&[f32], &[f32]),
+            launch: Launch, iterations: usize) -> Run {
+    let total_elements = n * n;
     
     let buffer_a = device.new_buffer_with_data(
         input_a.as_ptr() as *const _,
-        (TOTAL_ELEMENTS * std::mem::size_of::<f32>()) as u64,
+        (total_elements * std::mem::size_of::<f32>()) as u64,
         MTLResourceOptions::StorageModeShared,
     );
     
     let buffer_b = device.new_buffer_with_data(
         input_b.as_ptr() as *const _,
-        (TOTAL_ELEMENTS * std::mem::size_of::<f32>()) as u64,
+        (total_elements * std::mem::size_of::<f32>()) as u64,
         MTLResourceOptions::StorageModeShared,
     );
     
     let buffer_result = device.new_buffer(
-        (TOTAL_ELEMENTS * std::mem::size_of::<f32>()) as u64,
+        (total_elements * std::mem::size_of::<f32>()) as u64,
         MTLResourceOptions::StorageModeShared,
     );
     
-    let matrix_size_u32 = MATRIX_SIZE as u32;
+    let matrix_size_u32 = n as u32;
     let buffer_size = device.new_buffer_with_data(
         &matrix_size_u32 as *const u32 as *const _,
         std::mem::size_of::<u32>() as u64,
         MTLResourceOptions::StorageModeShared,
     );
     
-    // Warm-up
-    {
-        let command_queue = device.new_command_queue();
-        let command_buffer = command_queue.new_command_buffer();
+    let encode = |command_buffer: &CommandBufferRef| {
         let encoder = command_buffer.new_compute_command_encoder();
         
-        encoder.set_compute_pipeline_state(&pipeline);
+        encoder.set_compute_pipeline_state(pipeline);
         encoder.set_buffer(0, Some(&buffer_a), 0);
         encoder.set_buffer(1, Some(&buffer_b), 0);
         encoder.set_buffer(2, Some(&buffer_result), 0);
         encoder.set_buffer(3, Some(&buffer_size), 0);
         
-        let grid_size = MTLSize::new(MATRIX_SIZE as u64, MATRIX_SIZE as u64, 1);
-        let threadgroup_size = MTLSize::new(16, 16, 1);
-        encoder.dispatch_threads(grid_size, threadgroup_size);
+        let (w, h) = launch.threadgroup;
+        let threadgroup_size = MTLSize::new(w, h, 1);
+        match launch.strategy {
+            Strategy::Threads => encoder.dispatch_threads(MTLSize::new(n as u64, n as u64, 1), threadgroup_size),
+            Strategy::Threadgroups => {
+                encoder.dispatch_thread_groups(MTLSize::new(n as u64 / w, n as u64 / h, 1), threadgroup_size)
+            }
+        }
         encoder.end_encoding();
-        
+    };
+    
+    // Warm-up
+    {
+        let command_queue = device.new_command_queue();
+        let command_buffer = command_queue.new_command_buffer();
+        encode(command_buffer);
         command_buffer.commit();
         command_buffer.wait_until_completed();
     }
@@ -108,34 +255,169 @@ fn main() {
     // Benchmark - run multiple iterations
     let start = Instant::now();
     let command_queue = device.new_command_queue();
+    let mut encode_time = Duration::ZERO;
+    let mut gpu_time = 0.0f64;
     
-    for _ in 0..ITERATIONS {
+    for _ in 0..iterations {
+        let encode_start = Instant::now();
         let command_buffer = command_queue.new_command_buffer();
-        let encoder = command_buffer.new_compute_command_encoder();
-        
-        encoder.set_compute_pipeline_state(&pipeline);
-        encoder.set_buffer(0, Some(&buffer_a), 0);
-        encoder.set_buffer(1, Some(&buffer_b), 0);
-        encoder.set_buffer(2, Some(&buffer_result), 0);
-        encoder.set_buffer(3, Some(&buffer_size), 0);
-        
-        let grid_size = MTLSize::new(MATRIX_SIZE as u64, MATRIX_SIZE as u64, 1);
-        let threadgroup_size = MTLSize::new(16, 16, 1);
-        encoder.dispatch_threads(grid_size, threadgroup_size);
-        encoder.end_encoding();
-        
+        encode(command_buffer);
         command_buffer.commit();
+        encode_time += encode_start.elapsed();
         command_buffer.wait_until_completed();
+        
+        // GPUStartTime/GPUEndTime bracket the kernel on the GPU timeline (not wrapped by metal-rs)
+        let (gpu_start, gpu_end): (f64, f64) = unsafe {
+            (msg_send![command_buffer, GPUStartTime], msg_send![command_buffer, GPUEndTime])
+        };
+        gpu_time += gpu_end - gpu_start;
     }
     
-    let duration = start.elapsed();
+    let wall = start.elapsed();
     
-    // Get result and checksum
     let result_ptr = buffer_result.contents() as *const f32;
-    let result_slice = unsafe { std::slice::from_raw_parts(result_ptr, TOTAL_ELEMENTS) };
-    let checksum: f32 = result_slice.iter().step_by(1000).sum();
+    let result = unsafe { std::slice::from_raw_parts(result_ptr, total_elements) }.to_vec();
+    Run { result, wall, encode: encode_time, gpu: gpu_time }
+}
+
+#[cfg(target_os = "macos")]
+fn main() {
+    // Initialize Metal
+    let device = Device::system_default().expect("No Metal device found");
+    
+    let args: Vec<String> = std::env::args().collect();
+    let config = parse_config(&args);
+    let matrix_size = config.matrix_size;
     
-    println!("{:.6}", duration.as_secs_f64());
+    let (library, origin) = load_library(&device, &config);
+    
+    let kernel = library.get_function(&config.kernel_name, None)
+        .unwrap_or_else(|e| panic!("Failed to get kernel function {}: {}", config.kernel_name, e));
+    
+    let pipeline = device.new_compute_pipeline_state_with_function(&kernel)
+        .expect("Failed to create pipeline");
+    
+    let max_threads = pipeline.max_total_threads_per_threadgroup();
+    if let Some((w, h)) = config.threadgroup {
+        if w * h > max_threads {
+            panic!("--threadgroup {}x{} exceeds this pipeline's limit of {} threads", w, h, max_threads);
+        }
+    }
+    let launches = candidates(&config, max_threads);
+    if let Some(launch) = launches.iter().find(|launch| !launch.tiles(matrix_size)) {
+        panic!("--dispatch threadgroups needs --size to be a multiple of the {}x{} threadgroup",
+               launch.threadgroup.0, launch.threadgroup.1);
+    }
+    let (input_a, input_b) = inputs(matrix_size);
+    
+    // Tuning: neither binding's default shape is assumed, each candidate is timed on the GPU
+    // timeline and the fastest is used for the measurement below
+    let tuning: Vec<(Launch, f64)> = if launches.len() > 1 {
+        launches.iter()
+            .map(|&launch| {
+                let run = dispatch(&device, &pipeline, matrix_size, (&input_a, &input_b), launch, TUNE_ITERATIONS);
+                (launch, run.gpu / TUNE_ITERATIONS as f64)
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let launch = tuning.iter()
+        .min_by(|x, y| x.1.total_cmp(&y.1))
+        .map_or(launches[0], |&(launch, _)| launch);
+    let (group_width, group_height) = launch.threadgroup;
+    
+    // Validate element-wise against the CPU at a reduced size. Only the embedded kernel must
+    // pass; a replacement kernel's mismatch is reported, since it may compute something else
+    let (check_a, check_b) = inputs(CHECK_SIZE);
+    let check_launch = if launch.tiles(CHECK_SIZE) { launch } else { Launch { strategy: Strategy::Threads, ..launch } };
+    let check = dispatch(&device, &pipeline, CHECK_SIZE, (&check_a, &check_b), check_launch, 1);
+    let reference = cpu_heavy_compute(&check_a, &check_b, CHECK_SIZE);
+    let max_error = check.result.iter().zip(&reference)
+        .map(|(g, c)| (g - c).abs() / c.abs().max(1.0))
+        .fold(0.0f32, f32::max);
+    if config.kernel_file.is_none() {
+        assert!(max_error < TOLERANCE, "GPU result differs from CPU reference by {} (relative)", max_error);
+    }
+    
+    // Benchmark
+    let run = dispatch(&device, &pipeline, matrix_size, (&input_a, &input_b), launch, config.iterations);
+    
+    // The same work on the CPU once, as a same-binary comparison point (not part of the GPU time)
+    let start = Instant::now();
+    let cpu_result = cpu_heavy_compute(&input_a, &input_b, matrix_size);
+    let cpu_time = start.elapsed().as_secs_f64();
+    
+    // Get result and checksum
+    let checksum: f32 = run.result.iter().step_by(1000).sum();
+    let cpu_checksum: f32 = cpu_result.iter().step_by(1000).sum();
+    
+    let wall_time = run.wall.as_secs_f64();
+    let gpu_time = run.gpu;
+    println!("{:.6}", wall_time);
+    eprintln!("Kernel: {} ({}), {}x{} matrix, {} iterations, {}x{} threadgroups via {}", config.kernel_name,
+              config.kernel_file.as_deref().map_or(origin.to_string(), |path| format!("{}, {}", path, origin)),
+              matrix_size, matrix_size, config.iterations, group_width, group_height, launch.strategy.name());
+    if !tuning.is_empty() {
+        eprintln!("Threadgroup tuning ({} candidates, {} dispatches each, limit {} threads, SIMD width {}):",
+                  tuning.len(), TUNE_ITERATIONS, max_threads, pipeline.thread_execution_width());
+        for (candidate, seconds) in &tuning {
+            let (w, h) = candidate.threadgroup;
+            let selected = w == group_width && h == group_height && candidate.strategy == launch.strategy;
+            eprintln!("  {:>7} {:<20} {:.6} s per dispatch{}", format!("{}x{}", w, h), candidate.strategy.name(), seconds,
+                      if selected { "  <- selected" } else { "" });
+        }
+    }
+    eprintln!("GPU execution time: {:.6} s", gpu_time);
+    eprintln!("Encode + commit time: {:.6} s", run.encode.as_secs_f64());
+    eprintln!("Driver + sync overhead: {:.6} s ({:.1}% of wall time)", wall_time - gpu_time, (wall_time - gpu_time) / wall_time * 100.0);
+    eprintln!("Validation ({}x{} vs CPU): max relative error {:.2e} (tolerance {:.0e}: {})", CHECK_SIZE, CHECK_SIZE,
+              max_error, TOLERANCE, if max_error < TOLERANCE { "pass" } else { "FAIL" });
+    eprintln!("CPU heavy_compute (rayon, {} threads): {:.6} s per pass vs GPU {:.6} s per dispatch ({:.1}x), CPU checksum {}",
+              rayon::current_num_threads(), cpu_time, gpu_time / config.iterations as f64,
+              cpu_time / (gpu_time / config.iterations as f64), cpu_checksum);
     eprintln!("Checksum: {}", checksum);
 }
 
+#[cfg(all(test, target_os = "macos"))]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        std::iter::once("metal_compute").chain(line.split_whitespace()).map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_flags_with_defaults() {
+        let config = parse_config(&args(""));
+        assert_eq!((config.kernel_name.as_str(), config.matrix_size, config.iterations), ("heavy_compute", MATRIX_SIZE, ITERATIONS));
+        assert!(config.kernel_file.is_none() && config.threadgroup.is_none() && config.strategy.is_none());
+
+        let config = parse_config(&args("--size 1000 --threadgroup 32x8 --dispatch threadgroups --kernel-name k"));
+        assert_eq!((config.kernel_name.as_str(), config.matrix_size, config.threadgroup), ("k", 1000, Some((32, 8))));
+        assert!(config.strategy == Some(Strategy::Threadgroups));
+    }
+
+    #[test]
+    fn candidates_cover_the_shapes_that_fit() {
+        // Power-of-two sides from 8 with at most 1024 threads: 15 shapes, all tiling 2048
+        assert_eq!(candidates(&parse_config(&args("")), 1024).len(), 30);
+        // Only 8x8 tiles 1000, so the other shapes launch with dispatchThreads alone
+        let launches = candidates(&parse_config(&args("--size 1000")), 1024);
+        assert_eq!(launches.len(), 16);
+        assert!(launches.iter().all(|l| l.tiles(1000)));
+        assert_eq!(candidates(&parse_config(&args("--threadgroup 16x16 --dispatch threads")), 1024).len(), 1);
+    }
+
+    #[test]
+    fn cpu_reference_adds_the_row_reduction() {
+        let n = 4;
+        let (a, b) = inputs(n);
+        let result = cpu_heavy_compute(&a, &b, n);
+        let mut other_row = a.clone();
+        other_row[..n].iter_mut().for_each(|x| *x += 1.0);
+        // Changing row 0 leaves the other rows alone
+        assert_eq!(cpu_heavy_compute(&other_row, &b, n)[n..], result[n..]);
+        assert!(result.iter().all(|x| x.is_finite()));
+    }
+}
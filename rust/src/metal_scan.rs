@@ -0,0 +1,233 @@
+#[cfg(target_os = "macos")]
+#[macro_use]
+extern crate objc;
+
+#[cfg(target_os = "macos")]
+use metal::*;
+#[cfg(target_os = "macos")]
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "macos")]
+const DEFAULT_ELEMENTS: usize = 1 << 26; // 64M u32, 256 MB
+#[cfg(target_os = "macos")]
+const ITERATIONS: usize = 10;
+#[cfg(target_os = "macos")]
+const GROUP_SIZE: u64 = 256;   // threads per threadgroup, as in the shader
+#[cfg(target_os = "macos")]
+const BLOCK: usize = 256 * 4;  // elements per scanned block: GROUP_SIZE x ELEMENTS_PER_THREAD
+#[cfg(target_os = "macos")]
+const REDUCE_GROUPS: u64 = 1024; // the reduction's grid-stride loop covers the input with this many groups
+#[cfg(target_os = "macos")]
+const MIN_SIMD_WIDTH: u64 = 16; // the _simd kernels scan the per-group totals within one SIMD group
+#[cfg(target_os = "macos")]
+const SAMPLE_STRIDE: usize = 4099;
+
+#[cfg(target_os = "macos")]
+const SHADER_SOURCE: &str = include_str!("../../shaders/scan.metal");
+
+// Metal only exists on Apple platforms
+#[cfg(not(target_os = "macos"))]
+fn main() {
+    eprintln!("metal_scan requires macOS");
+    std::process::exit(1);
+}
+
+// The prefix_sum input, truncated to u32: stateless per index, small values
+#[cfg(target_os = "macos")]
+fn input_value(i: usize) -> u32 {
+    let mut x = (i as u64).wrapping_mul(0x9E3779B97F4A7C15);
+    x ^= x >> 29;
+    (x & 1023) as u32
+}
+
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy, PartialEq)]
+enum Combine {
+    Tree, // threadgroup memory and barriers only
+    Simd, // simd_sum / simd_prefix_*_sum within SIMD groups, threadgroup memory across them
+}
+
+#[cfg(target_os = "macos")]
+impl Combine {
+    fn name(self) -> &'static str {
+        match self {
+            Combine::Tree => "threadgroup tree",
+            Combine::Simd => "simdgroup",
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            Combine::Tree => "tree",
+            Combine::Simd => "simd",
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+struct Timing {
+    wall: Duration,
+    gpu: f64, // summed GPUEndTime - GPUStartTime over the timed command buffers
+}
+
+#[cfg(target_os = "macos")]
+fn pipeline(device: &Device, library: &Library, name: &str) -> ComputePipelineState {
+    let function = library.get_function(name, None)
+        .unwrap_or_else(|e| panic!("Failed to get kernel function {}: {}", name, e));
+    let pipeline = device.new_compute_pipeline_state_with_function(&function)
+        .unwrap_or_else(|e| panic!("Failed to create pipeline {}: {}", name, e));
+    if pipeline.max_total_threads_per_threadgroup() < GROUP_SIZE {
+        panic!("{} allows only {} threads per threadgroup, the shader needs {}", name,
+               pipeline.max_total_threads_per_threadgroup(), GROUP_SIZE);
+    }
+    pipeline
+}
+
+// One untimed warm-up command buffer, then ITERATIONS timed ones. `reset` runs on the CPU
+// before each commit; `encode` records the dispatches
+#[cfg(target_os = "macos")]
+fn timed(queue: &CommandQueue, reset: impl Fn(), encode: impl Fn(&ComputeCommandEncoderRef)) -> Timing {
+    let run = || {
+        reset();
+        let command_buffer = queue.new_command_buffer();
+        let encoder = command_buffer.new_compute_command_encoder();
+        encode(encoder);
+        encoder.end_encoding();
+        command_buffer.commit();
+        command_buffer.wait_until_completed();
+        let (gpu_start, gpu_end): (f64, f64) = unsafe {
+            (msg_send![command_buffer, GPUStartTime], msg_send![command_buffer, GPUEndTime])
+        };
+        gpu_end - gpu_start
+    };
+    run();
+    let start = Instant::now();
+    let gpu = (0..ITERATIONS).map(|_| run()).sum();
+    Timing { wall: start.elapsed(), gpu }
+}
+
+#[cfg(target_os = "macos")]
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let n = match args.iter().position(|a| a == "--elements") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&n| n > 0 && n <= 1 << 31)
+            .unwrap_or_else(|| panic!("--elements expects a count from 1 to 2^31")),
+        None => DEFAULT_ELEMENTS,
+    };
+    let blocks = n.div_ceil(BLOCK);
+
+    let device = Device::system_default().expect("No Metal device found");
+    let library = device.new_library_with_source(SHADER_SOURCE, &CompileOptions::new())
+        .expect("Failed to compile shader");
+    let queue = device.new_command_queue();
+
+    let input: Vec<u32> = (0..n).map(input_value).collect();
+    let shared = MTLResourceOptions::StorageModeShared;
+    let buffer_input = device.new_buffer_with_data(input.as_ptr() as *const _, (n * 4) as u64, shared);
+    let buffer_output = device.new_buffer((n * 4) as u64, shared);
+    let buffer_totals = device.new_buffer((blocks * 4) as u64, shared);
+    let buffer_sum = device.new_buffer(4, shared);
+    let (count, block_count) = (n as u32, blocks as u32);
+
+    // CPU reference: wrapping, like the GPU's uint arithmetic
+    let start = Instant::now();
+    let expected_scan: Vec<u32> = input.iter()
+        .scan(0u32, |acc, &x| {
+            *acc = acc.wrapping_add(x);
+            Some(*acc)
+        })
+        .collect();
+    let cpu_time = start.elapsed();
+    let expected_sum = expected_scan[n - 1];
+
+    let sum_ptr = buffer_sum.contents() as *mut u32;
+    // Only read between command buffers, once the GPU is done writing
+    let output = || unsafe { std::slice::from_raw_parts(buffer_output.contents() as *const u32, n) };
+    let group = MTLSize::new(GROUP_SIZE, 1, 1);
+
+    let mut simd_width = u64::MAX;
+    let mut reduce_runs = Vec::new();
+    let mut scan_runs = Vec::new();
+    for combine in [Combine::Tree, Combine::Simd] {
+        let reduce = pipeline(&device, &library, &format!("reduce_{}", combine.suffix()));
+        let scan_blocks = pipeline(&device, &library, &format!("scan_blocks_{}", combine.suffix()));
+        let scan_totals = pipeline(&device, &library, &format!("scan_totals_{}", combine.suffix()));
+        let add_offsets = pipeline(&device, &library, "scan_add_offsets");
+        simd_width = simd_width.min(reduce.thread_execution_width()).min(scan_blocks.thread_execution_width());
+        if combine == Combine::Simd && simd_width < MIN_SIMD_WIDTH {
+            panic!("The simdgroup kernels need a SIMD width of at least {}, this GPU has {}", MIN_SIMD_WIDTH, simd_width);
+        }
+
+        let reduction = timed(&queue, || unsafe { *sum_ptr = 0 }, |encoder| {
+            encoder.set_compute_pipeline_state(&reduce);
+            encoder.set_buffer(0, Some(&buffer_input), 0);
+            encoder.set_buffer(1, Some(&buffer_sum), 0);
+            encoder.set_bytes(2, 4, &count as *const u32 as *const _);
+            encoder.dispatch_thread_groups(MTLSize::new(REDUCE_GROUPS, 1, 1), group);
+        });
+        let sum = unsafe { *sum_ptr };
+        assert!(sum == expected_sum, "{} reduction gave {}, expected {}", combine.name(), sum, expected_sum);
+        reduce_runs.push((combine, reduction));
+
+        // The three dispatches share one encoder, whose serial dispatch order makes each see the
+        // previous one's writes
+        let scan = timed(&queue, || {}, |encoder| {
+            encoder.set_buffer(0, Some(&buffer_input), 0);
+            encoder.set_buffer(1, Some(&buffer_output), 0);
+            encoder.set_buffer(2, Some(&buffer_totals), 0);
+            encoder.set_bytes(3, 4, &count as *const u32 as *const _);
+            encoder.set_bytes(4, 4, &block_count as *const u32 as *const _);
+            encoder.set_compute_pipeline_state(&scan_blocks);
+            encoder.dispatch_thread_groups(MTLSize::new(blocks as u64, 1, 1), group);
+            encoder.set_compute_pipeline_state(&scan_totals);
+            encoder.dispatch_thread_groups(MTLSize::new(1, 1, 1), group);
+            encoder.set_compute_pipeline_state(&add_offsets);
+            encoder.dispatch_thread_groups(MTLSize::new(blocks as u64, 1, 1), group);
+        });
+        let result = output();
+        if let Some(i) = (0..n).find(|&i| result[i] != expected_scan[i]) {
+            panic!("{} scan differs from the CPU at element {}: {} vs {}", combine.name(), i, result[i], expected_scan[i]);
+        }
+        scan_runs.push((combine, scan));
+    }
+
+    let total: Duration = reduce_runs.iter().chain(&scan_runs).map(|(_, t)| t.wall).sum();
+    let result = output();
+    let checksum = result.iter().step_by(SAMPLE_STRIDE).fold(0u32, |acc, &x| acc.wrapping_add(x))
+        .wrapping_add(result[n - 1]);
+
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("Device: {} (SIMD width {})", device.name(), simd_width);
+    let rate = |seconds: f64| n as f64 / seconds / 1e9;
+    eprintln!("Reduction over {}M u32 ({} groups of {}, grid-stride), per dispatch:", n >> 20, REDUCE_GROUPS, GROUP_SIZE);
+    for (combine, t) in &reduce_runs {
+        let per = t.gpu / ITERATIONS as f64;
+        eprintln!("  {:<16} {:.6}s GPU ({:.2} G elements/s, {:.1} GB/s read)", combine.name(), per, rate(per),
+                  rate(per) * 4.0);
+    }
+    eprintln!("Inclusive scan over {}M u32 ({} blocks of {}, reduce-then-scan in 3 dispatches), per dispatch:",
+              n >> 20, blocks, BLOCK);
+    for (combine, t) in &scan_runs {
+        let per = t.gpu / ITERATIONS as f64;
+        eprintln!("  {:<16} {:.6}s GPU ({:.2} G elements/s)", combine.name(), per, rate(per));
+    }
+    eprintln!("CPU sequential scan: {:.6}s ({:.2} G elements/s)", cpu_time.as_secs_f64(), rate(cpu_time.as_secs_f64()));
+    eprintln!("Checksum: sum {}, scan {}", expected_sum, checksum);
+}
+
+#[cfg(all(test, target_os = "macos"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inputs_fit_ten_bits() {
+        // Scrambled, but the same value for the same index on every run
+        let values: Vec<u32> = (0..1 << 16).map(input_value).collect();
+        assert!(values.iter().all(|&v| v < 1024));
+        assert!(values.iter().any(|&v| v > 1000) && values.contains(&0));
+        assert_eq!(input_value(12345), input_value(12345));
+        assert_eq!((Combine::Tree.suffix(), Combine::Simd.suffix()), ("tree", "simd"));
+    }
+}
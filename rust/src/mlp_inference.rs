@@ -0,0 +1,174 @@
+use rayon::prelude::*;
+use std::time::Instant;
+
+const LAYER_SIZES: [usize; 4] = [784, 512, 512, 10];
+const BATCH_SIZE: usize = 256;
+const NUM_BATCHES: usize = 1000;
+const INPUT_POOL: usize = 64; // Distinct input batches, reused round-robin
+
+struct Layer {
+    inputs: usize,
+    outputs: usize,
+    weights: Vec<f32>, // inputs x outputs, row-major
+    bias: Vec<f32>,
+}
+
+#[derive(Clone, Copy)]
+enum Kernel {
+    PlainLoops,
+    MatrixMultiply,
+}
+
+fn next_random(seed: &mut u64) -> f32 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*seed >> 40) as f32 / 16_777_216.0 - 0.5
+}
+
+fn build_network() -> Vec<Layer> {
+    let mut seed = 42u64;
+    LAYER_SIZES
+        .windows(2)
+        .map(|w| {
+            let (inputs, outputs) = (w[0], w[1]);
+            let scale = 2.0 / (inputs as f32).sqrt();
+            let weights = (0..inputs * outputs).map(|_| next_random(&mut seed) * scale).collect();
+            let bias = (0..outputs).map(|_| next_random(&mut seed) * 0.1).collect();
+            Layer { inputs, outputs, weights, bias }
+        })
+        .collect()
+}
+
+fn build_inputs() -> Vec<Vec<f32>> {
+    let mut seed = 7u64;
+    (0..INPUT_POOL)
+        .map(|_| {
+            (0..BATCH_SIZE * LAYER_SIZES[0])
+                .map(|_| next_random(&mut seed) + 0.5)
+                .collect()
+        })
+        .collect()
+}
+
+// i-k-j order so the innermost loop is a contiguous axpy both compilers vectorize
+fn dense_plain(layer: &Layer, input: &[f32], output: &mut [f32]) {
+    for (x, y) in input.chunks_exact(layer.inputs).zip(output.chunks_exact_mut(layer.outputs)) {
+        y.copy_from_slice(&layer.bias);
+        for (k, &xk) in x.iter().enumerate() {
+            let w = &layer.weights[k * layer.outputs..(k + 1) * layer.outputs];
+            for (yj, &wj) in y.iter_mut().zip(w) {
+                *yj += xk * wj;
+            }
+        }
+    }
+}
+
+fn dense_matrixmultiply(layer: &Layer, input: &[f32], output: &mut [f32]) {
+    for y in output.chunks_exact_mut(layer.outputs) {
+        y.copy_from_slice(&layer.bias);
+    }
+    unsafe {
+        matrixmultiply::sgemm(
+            BATCH_SIZE, layer.inputs, layer.outputs,
+            1.0,
+            input.as_ptr(), layer.inputs as isize, 1,
+            layer.weights.as_ptr(), layer.outputs as isize, 1,
+            1.0,
+            output.as_mut_ptr(), layer.outputs as isize, 1,
+        );
+    }
+}
+
+fn infer_batch(network: &[Layer], input: &[f32], kernel: Kernel) -> f64 {
+    let mut activations = input.to_vec();
+
+    for (i, layer) in network.iter().enumerate() {
+        let mut output = vec![0.0f32; BATCH_SIZE * layer.outputs];
+        match kernel {
+            Kernel::PlainLoops => dense_plain(layer, &activations, &mut output),
+            Kernel::MatrixMultiply => dense_matrixmultiply(layer, &activations, &mut output),
+        }
+
+        // ReLU on every hidden layer, raw logits out of the last one
+        if i + 1 < network.len() {
+            for v in output.iter_mut() {
+                *v = v.max(0.0);
+            }
+        }
+        activations = output;
+    }
+
+    activations.iter().map(|&v| v as f64).sum()
+}
+
+fn run_inference(network: &[Layer], inputs: &[Vec<f32>], kernel: Kernel) -> f64 {
+    let per_batch: Vec<f64> = (0..NUM_BATCHES)
+        .into_par_iter()
+        .map(|b| infer_batch(network, &inputs[b % INPUT_POOL], kernel))
+        .collect();
+
+    // Summed in batch order so the checksum doesn't depend on scheduling
+    per_batch.iter().sum()
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let network = build_network();
+    let inputs = build_inputs();
+
+    let flops_per_batch: usize = network.iter().map(|l| 2 * BATCH_SIZE * l.inputs * l.outputs).sum();
+    let total_gflops = (flops_per_batch * NUM_BATCHES) as f64 / 1e9;
+
+    // Warm-up
+    let _ = infer_batch(&network, &inputs[0], Kernel::PlainLoops);
+    let _ = infer_batch(&network, &inputs[0], Kernel::MatrixMultiply);
+
+    // Benchmark plain loops (mirrors the C++ implementation)
+    let start = Instant::now();
+    measure.start();
+    let checksum = run_inference(&network, &inputs, Kernel::PlainLoops);
+    measure.stop();
+    let plain_duration = start.elapsed();
+
+    // Benchmark matrixmultiply-backed GEMM
+    let start = Instant::now();
+    let gemm_checksum = run_inference(&network, &inputs, Kernel::MatrixMultiply);
+    let gemm_duration = start.elapsed();
+
+    measure.report();
+    eprintln!("Plain loops: {:.6}s ({:.2} GFLOP/s), matrixmultiply: {:.6}s ({:.2} GFLOP/s)",
+              plain_duration.as_secs_f64(), total_gflops / plain_duration.as_secs_f64(),
+              gemm_duration.as_secs_f64(), total_gflops / gemm_duration.as_secs_f64());
+    eprintln!("Checksum: {:.6} (matrixmultiply: {:.6})", checksum, gemm_checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_layer_on_known_weights() {
+        // y = x W + b with W = [[1, 2], [3, 4], [5, 6]]
+        let layer = Layer { inputs: 3, outputs: 2, weights: vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], bias: vec![0.5, -1.0] };
+        let input: Vec<f32> = (0..BATCH_SIZE).flat_map(|r| [r as f32, 1.0, -1.0]).collect();
+        let (mut plain, mut gemm) = (vec![0.0; BATCH_SIZE * 2], vec![0.0; BATCH_SIZE * 2]);
+        dense_plain(&layer, &input, &mut plain);
+        dense_matrixmultiply(&layer, &input, &mut gemm);
+        assert_eq!(plain[..4], [0.5 - 2.0, -1.0 - 2.0, 0.5 + 1.0 - 2.0, -1.0 + 2.0 - 2.0]);
+        assert!(plain == gemm);
+    }
+
+    #[test]
+    fn kernels_agree_on_the_network() {
+        let network = build_network();
+        assert_eq!(network.iter().map(|l| (l.inputs, l.outputs)).collect::<Vec<_>>(), [(784, 512), (512, 512), (512, 10)]);
+        let inputs = build_inputs();
+        for input in &inputs[..4] {
+            let plain = infer_batch(&network, input, Kernel::PlainLoops);
+            let gemm = infer_batch(&network, input, Kernel::MatrixMultiply);
+            // Summation order differs, so only to f32 rounding
+            assert!(((plain - gemm) / plain).abs() < 1e-4, "{} vs {}", plain, gemm);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use rayon::prelude::*;
+use std::time::Instant;
+
+const SIZE: usize = 8192;
+const OCTAVES: u32 = 6;
+const BASE_FREQUENCY: f32 = 1.0 / 512.0;
+const LACUNARITY: f32 = 2.0;
+const GAIN: f32 = 0.5;
+
+// The 8 gradient directions of 2D improved Perlin noise
+const GRADIENTS: [(f32, f32); 8] = [
+    (1.0, 1.0), (-1.0, 1.0), (1.0, -1.0), (-1.0, -1.0),
+    (1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0),
+];
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Shuffled 0..256, duplicated so lookups never need a wrap
+fn build_permutation() -> [u8; 512] {
+    let mut seed = 2024u64;
+    let mut p: [u8; 256] = std::array::from_fn(|i| i as u8);
+    for i in (1..256).rev() {
+        let j = (next_random(&mut seed) % (i as u64 + 1)) as usize;
+        p.swap(i, j);
+    }
+    std::array::from_fn(|i| p[i & 255])
+}
+
+#[inline]
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + t * (b - a)
+}
+
+#[inline]
+fn grad(hash: u8, x: f32, y: f32) -> f32 {
+    let (gx, gy) = GRADIENTS[(hash & 7) as usize];
+    gx * x + gy * y
+}
+
+fn perlin(perm: &[u8; 512], x: f32, y: f32) -> f32 {
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let xi = (x0 as i32 & 255) as usize;
+    let yi = (y0 as i32 & 255) as usize;
+    let xf = x - x0;
+    let yf = y - y0;
+    let u = fade(xf);
+    let v = fade(yf);
+
+    let a = perm[xi] as usize + yi;
+    let b = perm[xi + 1] as usize + yi;
+    let n00 = grad(perm[a], xf, yf);
+    let n01 = grad(perm[a + 1], xf, yf - 1.0);
+    let n10 = grad(perm[b], xf - 1.0, yf);
+    let n11 = grad(perm[b + 1], xf - 1.0, yf - 1.0);
+
+    lerp(lerp(n00, n10, u), lerp(n01, n11, u), v)
+}
+
+// Fractal Brownian motion: each octave doubles the frequency and halves the amplitude
+fn fbm(perm: &[u8; 512], x: f32, y: f32) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = BASE_FREQUENCY;
+    for _ in 0..OCTAVES {
+        sum += amplitude * perlin(perm, x * frequency, y * frequency);
+        frequency *= LACUNARITY;
+        amplitude *= GAIN;
+    }
+    sum
+}
+
+fn fill_rows(perm: &[u8; 512], heightmap: &mut [f32], first_row: usize) {
+    for (r, row) in heightmap.chunks_mut(SIZE).enumerate() {
+        let y = (first_row + r) as f32;
+        for (x, h) in row.iter_mut().enumerate() {
+            *h = fbm(perm, x as f32, y);
+        }
+    }
+}
+
+fn generate_heightmap(perm: &[u8; 512], heightmap: &mut [f32]) {
+    heightmap
+        .par_chunks_mut(SIZE)
+        .enumerate()
+        .for_each(|(y, row)| fill_rows(perm, row, y));
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let perm = build_permutation();
+    let mut heightmap = vec![0.0f32; SIZE * SIZE];
+
+    // Warm-up
+    fill_rows(&perm, &mut heightmap[..10 * SIZE], 0);
+
+    // Benchmark
+    let start = Instant::now();
+    measure.start();
+    generate_heightmap(&perm, &mut heightmap);
+    measure.stop();
+    let duration = start.elapsed();
+
+    // Checksum
+    let sum: f64 = heightmap.iter().map(|&h| h as f64).sum();
+    let (min, max) = heightmap
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(lo, hi), &h| (lo.min(h), hi.max(h)));
+
+    measure.report();
+    eprintln!("Samples: {:.0} M/s ({} octaves), height range [{:.4}, {:.4}]",
+              (SIZE * SIZE) as f64 / duration.as_secs_f64() / 1e6, OCTAVES, min, max);
+    eprintln!("Checksum: {:.6}", sum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permutation_is_a_shuffle_of_every_byte() {
+        let perm = build_permutation();
+        let mut seen = [false; 256];
+        for &p in &perm[..256] {
+            seen[p as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+        assert_eq!(perm[..256], perm[256..]);
+        assert_ne!(perm[..8], [0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn perlin_vanishes_on_the_lattice_and_stays_bounded() {
+        let perm = build_permutation();
+        assert_eq!((fade(0.0), fade(0.5), fade(1.0)), (0.0, 0.5, 1.0));
+        for (x, y) in [(0.0, 0.0), (3.0, 7.0), (255.0, 1.0), (300.0, -2.0)] {
+            assert_eq!(perlin(&perm, x, y), 0.0);
+        }
+        let mut seed = 9u64;
+        for _ in 0..10_000 {
+            let (x, y) = (next_random(&mut seed) as f32 / 1e6, next_random(&mut seed) as f32 / 1e6);
+            assert!(perlin(&perm, x, y).abs() <= 1.0);
+            // The lattice repeats every 256 cells
+            assert!((perlin(&perm, x % 256.0, y % 256.0) - perlin(&perm, x % 256.0 + 256.0, y % 256.0)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn heightmap_rows_match_direct_fbm() {
+        let perm = build_permutation();
+        let mut map = vec![0.0f32; SIZE * 4];
+        generate_heightmap(&perm, &mut map);
+        // The parallel split passes each row its own index: row 3 here is y = 3
+        assert_eq!(map[3 * SIZE + 100], fbm(&perm, 100.0, 3.0));
+        assert_eq!(map[0], 0.0);
+        assert!(map.iter().all(|h| h.abs() < 2.0));
+    }
+}
@@ -0,0 +1,293 @@
+use std::hint::black_box;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+const NUM_VALUES: usize = 5_000_000; // of each type
+const PASSES: usize = 10;            // so 50M integers and 50M floats per variant and direction
+const MAX_DECIMALS: usize = 6;
+const POW10: [f64; MAX_DECIMALS + 1] = [1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6];
+
+fn splitmix64(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Integers of 1 to 20 digits. Floats are m / 10^k with 1 <= k <= 6, m odd and under 10^10 with
+// at least one integer digit: the shortest round-trip text is then exactly that decimal in
+// fixed notation, in both languages, and the hand-rolled conversions below are exact
+fn generate(n: usize) -> (Vec<u64>, Vec<f64>) {
+    let mut seed = 7;
+    let ints = (0..n).map(|_| splitmix64(&mut seed) >> (splitmix64(&mut seed) % 64)).collect();
+    let floats = (0..n)
+        .map(|_| {
+            let k = 1 + (splitmix64(&mut seed) % MAX_DECIMALS as u64) as usize;
+            let low = 10u64.pow(k as u32);
+            let m = (low + splitmix64(&mut seed) % (10_000_000_000 - low)) | 1;
+            m as f64 / POW10[k]
+        })
+        .collect();
+    (ints, floats)
+}
+
+// Parsers take newline-terminated text and return the wrapping sum of the values (of the bit
+// patterns, for floats)
+
+fn parse_ints_std(text: &str) -> u64 {
+    text.split_terminator('\n').fold(0u64, |sum, line| sum.wrapping_add(line.parse::<u64>().unwrap()))
+}
+
+fn parse_floats_std(text: &str) -> u64 {
+    text.split_terminator('\n').fold(0u64, |sum, line| sum.wrapping_add(line.parse::<f64>().unwrap().to_bits()))
+}
+
+// No validation, no splitting: one pass over the bytes
+fn parse_ints_hand(text: &str) -> u64 {
+    let mut sum = 0u64;
+    let mut value = 0u64;
+    for &b in text.as_bytes() {
+        if b == b'\n' {
+            sum = sum.wrapping_add(value);
+            value = 0;
+        } else {
+            value = value * 10 + (b - b'0') as u64;
+        }
+    }
+    sum
+}
+
+// The mantissa stays under 2^53 and the divisor is an exact power of ten, so one division
+// rounds correctly (the fast path of every real decimal-to-float parser)
+fn parse_floats_hand(text: &str) -> u64 {
+    let mut sum = 0u64;
+    let mut mantissa = 0u64;
+    let mut decimals = 0;
+    let mut seen_point = false;
+    for &b in text.as_bytes() {
+        match b {
+            b'\n' => {
+                sum = sum.wrapping_add((mantissa as f64 / POW10[decimals]).to_bits());
+                (mantissa, decimals, seen_point) = (0, 0, false);
+            }
+            b'.' => seen_point = true,
+            _ => {
+                mantissa = mantissa * 10 + (b - b'0') as u64;
+                decimals += seen_point as usize;
+            }
+        }
+    }
+    sum
+}
+
+// Formatters append each value and a newline to `out`
+
+fn format_ints_fmt(values: &[u64], out: &mut Vec<u8>) {
+    for v in values {
+        writeln!(out, "{}", v).unwrap();
+    }
+}
+
+fn format_floats_fmt(values: &[f64], out: &mut Vec<u8>) {
+    for v in values {
+        writeln!(out, "{}", v).unwrap();
+    }
+}
+
+fn format_ints_itoa(values: &[u64], out: &mut Vec<u8>) {
+    let mut buf = itoa::Buffer::new();
+    for &v in values {
+        out.extend_from_slice(buf.format(v).as_bytes());
+        out.push(b'\n');
+    }
+}
+
+fn format_floats_ryu(values: &[f64], out: &mut Vec<u8>) {
+    let mut buf = ryu::Buffer::new();
+    for &v in values {
+        out.extend_from_slice(buf.format_finite(v).as_bytes());
+        out.push(b'\n');
+    }
+}
+
+fn push_digits(mut v: u64, out: &mut Vec<u8>) {
+    let mut digits = [0u8; 20];
+    let mut i = digits.len();
+    loop {
+        i -= 1;
+        digits[i] = b'0' + (v % 10) as u8;
+        v /= 10;
+        if v == 0 {
+            break;
+        }
+    }
+    out.extend_from_slice(&digits[i..]);
+}
+
+fn format_ints_hand(values: &[u64], out: &mut Vec<u8>) {
+    for &v in values {
+        push_digits(v, out);
+        out.push(b'\n');
+    }
+}
+
+// Fixed point at MAX_DECIMALS with trailing zeros dropped: only the shortest form for values
+// like these, the shortcut a pipeline takes when it knows its data
+fn format_floats_hand(values: &[f64], out: &mut Vec<u8>) {
+    let scale = POW10[MAX_DECIMALS] as u64;
+    for &v in values {
+        let fixed = (v * POW10[MAX_DECIMALS]).round() as u64;
+        push_digits(fixed / scale, out);
+        let mut fraction = fixed % scale;
+        if fraction != 0 {
+            let mut width = MAX_DECIMALS;
+            while fraction.is_multiple_of(10) {
+                fraction /= 10;
+                width -= 1;
+            }
+            let mut digits = [0u8; MAX_DECIMALS];
+            for d in digits[..width].iter_mut().rev() {
+                *d = b'0' + (fraction % 10) as u8;
+                fraction /= 10;
+            }
+            out.push(b'.');
+            out.extend_from_slice(&digits[..width]);
+        }
+        out.push(b'\n');
+    }
+}
+
+type ParseFn = fn(&str) -> u64;
+type FormatFn<T> = fn(&[T], &mut Vec<u8>);
+
+const INT_PARSERS: [(&str, ParseFn); 2] = [("str::parse", parse_ints_std), ("hand-rolled", parse_ints_hand)];
+const FLOAT_PARSERS: [(&str, ParseFn); 2] = [("str::parse", parse_floats_std), ("hand-rolled", parse_floats_hand)];
+const INT_FORMATTERS: [(&str, FormatFn<u64>); 3] =
+    [("write!", format_ints_fmt), ("itoa", format_ints_itoa), ("hand-rolled", format_ints_hand)];
+const FLOAT_FORMATTERS: [(&str, FormatFn<f64>); 3] =
+    [("write!", format_floats_fmt), ("ryu", format_floats_ryu), ("hand-rolled", format_floats_hand)];
+
+// black_box on the input every pass stops the compiler computing one pass and multiplying
+fn run_parse(parse: ParseFn, text: &str, passes: usize) -> (Duration, u64) {
+    let mut sum = 0u64;
+    let start = Instant::now();
+    for _ in 0..passes {
+        sum = sum.wrapping_add(parse(black_box(text)));
+    }
+    (start.elapsed(), sum)
+}
+
+// The output buffer is reused, so only the first pass grows it
+fn run_format<T>(format: FormatFn<T>, values: &[T], out: &mut Vec<u8>, passes: usize) -> Duration {
+    let start = Instant::now();
+    for _ in 0..passes {
+        out.clear();
+        format(black_box(values), out);
+    }
+    start.elapsed()
+}
+
+fn report(what: &str, variant: &str, duration: Duration) {
+    let rate = (NUM_VALUES * PASSES) as f64 / duration.as_secs_f64();
+    eprintln!("  {:<14} {:<12} {:.3}s ({:.1} M/s)", what, variant, duration.as_secs_f64(), rate / 1e6);
+}
+
+fn main() {
+    let (ints, floats) = generate(NUM_VALUES);
+    let mut out = Vec::with_capacity(NUM_VALUES * 21);
+    format_ints_fmt(&ints, &mut out);
+    let int_text = String::from_utf8(out.clone()).unwrap();
+    out.clear();
+    format_floats_fmt(&floats, &mut out);
+    let float_text = String::from_utf8(out.clone()).unwrap();
+
+    let mut total_duration = Duration::ZERO;
+    let mut lines = Vec::new();
+    let mut all_match = true;
+    let mut checksum = 0u64;
+
+    // Benchmark parsing (each variant gets one untimed warm-up pass)
+    for (what, parsers, text) in [("parse u64", INT_PARSERS, &int_text), ("parse f64", FLOAT_PARSERS, &float_text)] {
+        let expected = parsers[0].1(text);
+        checksum = checksum.wrapping_add(expected);
+        for (variant, parse) in parsers {
+            let _ = parse(text);
+            let (duration, sum) = run_parse(parse, text, PASSES);
+            all_match &= sum == expected.wrapping_mul(PASSES as u64);
+            total_duration += duration;
+            lines.push((what, variant, duration));
+        }
+    }
+
+    // Benchmark formatting; every variant has to reproduce the parsed text byte for byte
+    for (variant, format) in INT_FORMATTERS {
+        format(&ints[..NUM_VALUES / 100], &mut out);
+        let duration = run_format(format, &ints, &mut out, PASSES);
+        all_match &= out == int_text.as_bytes();
+        total_duration += duration;
+        lines.push(("format u64", variant, duration));
+    }
+    for (variant, format) in FLOAT_FORMATTERS {
+        format(&floats[..NUM_VALUES / 100], &mut out);
+        let duration = run_format(format, &floats, &mut out, PASSES);
+        all_match &= out == float_text.as_bytes();
+        total_duration += duration;
+        lines.push(("format f64", variant, duration));
+    }
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Values: {} u64 (1-20 digits) and {} f64 (1-{} decimals) x {} passes per variant ({:.0} MB of text)",
+              NUM_VALUES, NUM_VALUES, MAX_DECIMALS, PASSES, (int_text.len() + float_text.len()) as f64 / 1e6);
+    for (what, variant, duration) in lines {
+        report(what, variant, duration);
+    }
+    eprintln!("Checksum: {} (all variants match: {})", checksum, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsers_agree_with_str_parse() {
+        let ints = "0\n7\n18446744073709551615\n1234567890\n";
+        assert_eq!(parse_ints_hand(ints), parse_ints_std(ints));
+        let floats = "0.5\n1.000001\n9999999999.9\n3.25\n";
+        assert_eq!(parse_floats_hand(floats), parse_floats_std(floats));
+
+        let (ints, floats) = generate(20_000);
+        let mut out = Vec::new();
+        format_ints_fmt(&ints, &mut out);
+        let text = String::from_utf8(out.clone()).unwrap();
+        assert_eq!(parse_ints_hand(&text), parse_ints_std(&text));
+        out.clear();
+        format_floats_fmt(&floats, &mut out);
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(parse_floats_hand(&text), parse_floats_std(&text));
+    }
+
+    #[test]
+    fn formatters_produce_identical_text() {
+        let mut expected = Vec::new();
+        let ints = [0, 9, 10, 4_000_000, u64::MAX];
+        format_ints_fmt(&ints, &mut expected);
+        assert_eq!(expected, b"0\n9\n10\n4000000\n18446744073709551615\n");
+        for (_, format) in INT_FORMATTERS {
+            let mut out = Vec::new();
+            format(&ints, &mut out);
+            assert_eq!(out, expected);
+        }
+
+        let (_, floats) = generate(20_000);
+        let floats = [&[1.5, 2.05, 10.000001][..], &floats].concat();
+        expected.clear();
+        format_floats_fmt(&floats, &mut expected);
+        assert!(expected.starts_with(b"1.5\n2.05\n10.000001\n"));
+        for (_, format) in FLOAT_FORMATTERS {
+            let mut out = Vec::new();
+            format(&floats, &mut out);
+            assert_eq!(out, expected);
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use slab::Slab;
+use std::hint::black_box;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const NUM_THREADS: usize = 8;
+const LIVE: usize = 10_000; // long-lived entities per thread
+const FRAMES: usize = 2_000;
+const CHURN: usize = 500;   // long-lived entities replaced per frame
+const BURST: usize = 2_000; // short-lived entities spawned and released per frame
+
+fn splitmix64(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// One cache line, like a game server's entity or a connection's session state
+struct Entity {
+    id: u64,
+    pos: [u32; 4],
+    vel: [u32; 4],
+    payload: [u64; 3],
+}
+
+fn spawn(r: u64) -> Entity {
+    let (lo, hi) = (r as u32, (r >> 32) as u32);
+    Entity {
+        id: r,
+        pos: [lo, hi, lo ^ hi, lo.wrapping_add(hi)],
+        vel: [hi >> 24, lo >> 24, 1, 2],
+        payload: [r, !r, r.rotate_left(17)],
+    }
+}
+
+fn step(e: &mut Entity) {
+    for (p, v) in e.pos.iter_mut().zip(e.vel) {
+        *p = p.wrapping_add(v);
+    }
+}
+
+fn digest(e: &Entity) -> u64 {
+    e.pos.iter().fold(e.id ^ e.payload[2], |acc, &p| acc.wrapping_mul(31).wrapping_add(p as u64))
+}
+
+// Each thread owns its pool, as a game server's per-worker pools do; only Box shares one
+// allocator between the threads
+trait Pool: Default {
+    type Handle;
+    fn acquire(&mut self, e: Entity) -> Self::Handle;
+    fn release(&mut self, h: Self::Handle);
+    fn get<'a>(&'a mut self, h: &'a mut Self::Handle) -> &'a mut Entity;
+}
+
+// A heap allocation per entity, through the global allocator
+#[derive(Default)]
+struct Heap;
+
+impl Pool for Heap {
+    type Handle = Box<Entity>;
+
+    fn acquire(&mut self, e: Entity) -> Box<Entity> {
+        Box::new(e)
+    }
+
+    fn release(&mut self, h: Box<Entity>) {
+        drop(h);
+    }
+
+    fn get<'a>(&'a mut self, h: &'a mut Box<Entity>) -> &'a mut Entity {
+        h
+    }
+}
+
+// The slab crate: a vector of occupied-or-vacant slots threading its free list through the
+// vacant ones, with usize keys
+#[derive(Default)]
+struct SlabPool(Slab<Entity>);
+
+impl Pool for SlabPool {
+    type Handle = usize;
+
+    fn acquire(&mut self, e: Entity) -> usize {
+        self.0.insert(e)
+    }
+
+    fn release(&mut self, h: usize) {
+        self.0.remove(h);
+    }
+
+    fn get<'a>(&'a mut self, h: &'a mut usize) -> &'a mut Entity {
+        &mut self.0[*h]
+    }
+}
+
+// Hand-rolled: the entities in one vector and a stack of free indices beside it. A released
+// slot keeps its stale entity until the next acquire overwrites it
+#[derive(Default)]
+struct FreeList {
+    objects: Vec<Entity>,
+    free: Vec<u32>,
+}
+
+impl Pool for FreeList {
+    type Handle = u32;
+
+    fn acquire(&mut self, e: Entity) -> u32 {
+        match self.free.pop() {
+            Some(i) => {
+                self.objects[i as usize] = e;
+                i
+            }
+            None => {
+                self.objects.push(e);
+                (self.objects.len() - 1) as u32
+            }
+        }
+    }
+
+    fn release(&mut self, h: u32) {
+        self.free.push(h);
+    }
+
+    fn get<'a>(&'a mut self, h: &'a mut u32) -> &'a mut Entity {
+        &mut self.objects[*h as usize]
+    }
+}
+
+// One thread's frames: replace CHURN random long-lived entities, spawn a burst of short-lived
+// ones and release them in spawn order, then step every long-lived entity. Released entities
+// are digested on the way out
+fn churn<P: Pool>(thread: u64, frames: usize) -> u64 {
+    let mut seed = black_box(0x600D + thread);
+    let mut pool = P::default();
+    let mut live: Vec<P::Handle> = (0..LIVE).map(|_| pool.acquire(spawn(splitmix64(&mut seed)))).collect();
+    let mut burst = Vec::with_capacity(BURST);
+    let mut checksum = 0u64;
+    for _ in 0..frames {
+        for _ in 0..CHURN {
+            let r = splitmix64(&mut seed);
+            let fresh = pool.acquire(spawn(r));
+            let mut old = std::mem::replace(&mut live[(r % LIVE as u64) as usize], fresh);
+            checksum = checksum.wrapping_add(digest(pool.get(&mut old)));
+            pool.release(old);
+        }
+        for _ in 0..BURST {
+            let mut h = pool.acquire(spawn(splitmix64(&mut seed)));
+            step(pool.get(&mut h));
+            burst.push(h);
+        }
+        for mut h in burst.drain(..) {
+            checksum = checksum.wrapping_add(digest(pool.get(&mut h)));
+            pool.release(h);
+        }
+        for h in &mut live {
+            step(pool.get(h));
+        }
+    }
+    for mut h in live {
+        checksum = checksum.wrapping_add(digest(pool.get(&mut h)));
+        pool.release(h);
+    }
+    checksum
+}
+
+fn run<P: Pool>(frames: usize) -> (Duration, u64) {
+    let start = Instant::now();
+    let checksum = thread::scope(|s| {
+        let handles: Vec<_> = (0..NUM_THREADS as u64).map(|t| s.spawn(move || churn::<P>(t, frames))).collect();
+        handles.into_iter().fold(0u64, |acc, h| acc.wrapping_add(h.join().unwrap()))
+    });
+    (start.elapsed(), checksum)
+}
+
+type Variant = (&'static str, fn(usize) -> (Duration, u64));
+
+const VARIANTS: [Variant; 3] = [
+    ("Box", run::<Heap>),
+    ("slab", run::<SlabPool>),
+    ("free list", run::<FreeList>),
+];
+
+fn main() {
+    // Warm-up
+    for (_, f) in VARIANTS {
+        let _ = f(FRAMES / 100);
+    }
+
+    // Benchmark
+    let runs: Vec<(Duration, u64)> = VARIANTS.iter().map(|(_, f)| f(FRAMES)).collect();
+    let total_duration: Duration = runs.iter().map(|r| r.0).sum();
+    let all_match = runs.iter().all(|r| r.1 == runs[0].1);
+    let pairs = NUM_THREADS * (LIVE + FRAMES * (CHURN + BURST));
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Threads: {}, each {} live entities of {} bytes; per frame {} replaced and {} short-lived, {} frames",
+              NUM_THREADS, LIVE, size_of::<Entity>(), CHURN, BURST, FRAMES);
+    for ((name, _), (duration, _)) in VARIANTS.iter().zip(&runs) {
+        eprintln!("  {:<10} {:.3}s ({:.1}M acquire/release pairs/s)", name, duration.as_secs_f64(),
+                  pairs as f64 / duration.as_secs_f64() / 1e6);
+    }
+    eprintln!("Checksum: {} (all variants match: {})", runs[0].1, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn free_list_reuses_the_last_released_slot() {
+        let mut pool = FreeList::default();
+        let (a, b) = (pool.acquire(spawn(1)), pool.acquire(spawn(2)));
+        pool.release(a);
+        pool.release(b);
+        let mut c = pool.acquire(spawn(3));
+        assert_eq!(c, b);
+        assert_eq!(pool.get(&mut c).id, 3);
+        assert_eq!(pool.objects.len(), 2);
+    }
+
+    #[test]
+    fn variants_agree() {
+        let expected = churn::<Heap>(3, 4);
+        assert_eq!(churn::<SlabPool>(3, 4), expected);
+        assert_eq!(churn::<FreeList>(3, 4), expected);
+        assert_ne!(churn::<FreeList>(4, 4), expected);
+    }
+}
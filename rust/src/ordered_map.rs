@@ -0,0 +1,186 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ENTRIES: usize = 5_000_000; // inserts, so slightly fewer distinct keys
+const KEY_SPACE: u64 = 4 * ENTRIES as u64; // about a fifth of it ends up present
+const LOOKUPS: usize = 2_000_000;
+const SCANS: usize = 100_000;
+const SCAN_WIDTH: u64 = 100; // keys per range, about 20 of them present
+
+fn splitmix64(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Order-sensitive, so iteration out of key order fails the checksum
+fn mix(acc: u64, key: u64, value: u64) -> u64 {
+    acc.wrapping_mul(0x100000001b3).wrapping_add(key ^ value)
+}
+
+struct Workload {
+    inserts: Vec<u64>, // keys in insertion order; a repeated key overwrites its value
+    lookups: Vec<u64>,
+    scans: Vec<u64>, // first key of each range
+}
+
+fn generate(entries: usize, key_space: u64, lookups: usize, scans: usize) -> Workload {
+    let mut seed = 1972; // Bayer and McCreight's B-tree paper
+    Workload {
+        inserts: (0..entries).map(|_| splitmix64(&mut seed) % key_space).collect(),
+        lookups: (0..lookups).map(|_| splitmix64(&mut seed) % key_space).collect(),
+        scans: (0..scans).map(|_| splitmix64(&mut seed) % (key_space - SCAN_WIDTH)).collect(),
+    }
+}
+
+// The operations of an ordered workload. A hash map has no order to use: its range scans probe
+// every key in the range, and its ordered iteration sorts a copy of the entries
+trait OrderedWorkload: Default {
+    fn insert(&mut self, key: u64, value: u64);
+    fn get(&self, key: u64) -> Option<u64>;
+    fn len(&self) -> usize;
+    fn scan(&self, first: u64) -> u64;
+    fn iterate_in_order(&self) -> u64;
+}
+
+impl OrderedWorkload for BTreeMap<u64, u64> {
+    fn insert(&mut self, key: u64, value: u64) {
+        BTreeMap::insert(self, key, value);
+    }
+
+    fn get(&self, key: u64) -> Option<u64> {
+        BTreeMap::get(self, &key).copied()
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn scan(&self, first: u64) -> u64 {
+        self.range(first..first + SCAN_WIDTH).fold(0, |acc, (&k, &v)| mix(acc, k, v))
+    }
+
+    fn iterate_in_order(&self) -> u64 {
+        self.iter().fold(0, |acc, (&k, &v)| mix(acc, k, v))
+    }
+}
+
+impl OrderedWorkload for HashMap<u64, u64> {
+    fn insert(&mut self, key: u64, value: u64) {
+        HashMap::insert(self, key, value);
+    }
+
+    fn get(&self, key: u64) -> Option<u64> {
+        HashMap::get(self, &key).copied()
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn scan(&self, first: u64) -> u64 {
+        (first..first + SCAN_WIDTH).fold(0, |acc, k| match HashMap::get(self, &k) {
+            Some(&v) => mix(acc, k, v),
+            None => acc,
+        })
+    }
+
+    fn iterate_in_order(&self) -> u64 {
+        let mut entries: Vec<(u64, u64)> = self.iter().map(|(&k, &v)| (k, v)).collect();
+        entries.sort_unstable_by_key(|&(k, _)| k);
+        entries.iter().fold(0, |acc, &(k, v)| mix(acc, k, v))
+    }
+}
+
+const PHASES: [&str; 4] = ["insert", "lookup", "range scan", "in order"];
+
+struct Run {
+    durations: [Duration; 4],
+    results: [u64; 4],
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let result = f();
+    (start.elapsed(), result)
+}
+
+fn run<M: OrderedWorkload>(w: &Workload) -> Run {
+    let (insert_time, map) = timed(|| {
+        let mut map = M::default();
+        for (i, &key) in black_box(&w.inserts).iter().enumerate() {
+            map.insert(key, i as u64);
+        }
+        map
+    });
+    let (lookup_time, found) = timed(|| {
+        black_box(&w.lookups).iter().fold(0u64, |acc, &key| match map.get(key) {
+            Some(v) => acc.wrapping_add(v + 1),
+            None => acc,
+        })
+    });
+    let (scan_time, scanned) =
+        timed(|| black_box(&w.scans).iter().fold(0u64, |acc, &first| acc.wrapping_add(map.scan(first))));
+    let (iterate_time, iterated) = timed(|| map.iterate_in_order());
+    Run {
+        durations: [insert_time, lookup_time, scan_time, iterate_time],
+        results: [map.len() as u64, found, scanned, iterated],
+    }
+}
+
+const MAPS: [&str; 2] = ["BTreeMap", "HashMap"];
+
+fn run_all(w: &Workload) -> [Run; 2] {
+    [run::<BTreeMap<u64, u64>>(w), run::<HashMap<u64, u64>>(w)]
+}
+
+fn main() {
+    let workload = generate(ENTRIES, KEY_SPACE, LOOKUPS, SCANS);
+
+    // Warm-up
+    let _ = run_all(&generate(ENTRIES / 100, KEY_SPACE / 100, LOOKUPS / 100, SCANS / 100));
+
+    // Benchmark
+    let runs = run_all(&workload);
+    let total_duration: Duration = runs.iter().flat_map(|r| r.durations).sum();
+    let all_match = runs.iter().all(|r| r.results == runs[0].results);
+    let checksum = runs[0].results.iter().fold(0u64, |acc, &r| acc.wrapping_add(r));
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Inserts: {} over {} keys; {} lookups; {} range scans of {} keys", ENTRIES, KEY_SPACE, LOOKUPS, SCANS,
+              SCAN_WIDTH);
+    for (name, r) in MAPS.iter().zip(&runs) {
+        let phases: Vec<String> =
+            PHASES.iter().zip(r.durations).map(|(phase, d)| format!("{} {:.3}s", phase, d.as_secs_f64())).collect();
+        eprintln!("  {:<9} {}", name, phases.join("  "));
+    }
+    eprintln!("Checksum: {} (all maps match: {})", checksum, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_map_scans_match_btree_ranges() {
+        let mut btree = BTreeMap::new();
+        let mut hash = HashMap::new();
+        for (k, v) in [(5, 50), (105, 1), (3, 30), (104, 2), (5, 51)] {
+            OrderedWorkload::insert(&mut btree, k, v);
+            OrderedWorkload::insert(&mut hash, k, v);
+        }
+        assert_eq!(btree.scan(5), mix(mix(0, 5, 51), 104, 2));
+        assert_eq!(hash.scan(5), btree.scan(5));
+        assert_eq!(hash.iterate_in_order(), btree.iterate_in_order());
+    }
+
+    #[test]
+    fn maps_agree() {
+        let runs = run_all(&generate(20_000, 80_000, 20_000, 2_000));
+        assert_eq!(runs[0].results, runs[1].results);
+        assert!(runs[0].results[1] > 0 && runs[0].results[2] > 0);
+    }
+}
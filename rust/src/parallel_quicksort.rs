@@ -1,10 +1,104 @@
-use rayon;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Instant;
 
 const ARRAY_SIZE: usize = 10_000_000;
 const THRESHOLD: usize = 10_000;
 
-fn parallel_quicksort(arr: &mut [i32]) {
+#[derive(Clone, Copy)]
+enum Distribution {
+    Lcg,        // the original (i * 1103515245 + 12345) mod 2^31 sequence
+    Uniform,    // splitmix64 draws in 0..2^31
+    Sorted,     // 0, 1, 2, ...
+    Reverse,    // n - 1, n - 2, ..., 0
+    Duplicates, // splitmix64 draws in 0..100
+    OrganPipe,  // ascending to the middle, then descending
+    Killer,     // Musser's median-of-3 killer sequence
+}
+
+impl Distribution {
+    fn name(self) -> &'static str {
+        match self {
+            Distribution::Lcg => "lcg",
+            Distribution::Uniform => "uniform",
+            Distribution::Sorted => "sorted",
+            Distribution::Reverse => "reverse",
+            Distribution::Duplicates => "duplicates",
+            Distribution::OrganPipe => "organ-pipe",
+            Distribution::Killer => "killer",
+        }
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+fn generate(distribution: Distribution, n: usize) -> Vec<i32> {
+    let mut state = 42u64;
+    let mut data = bench_pages::with_capacity(n);
+    match distribution {
+        Distribution::Lcg => data.extend((0..n).map(|i| ((i * 1103515245 + 12345) % 2147483648) as i32)),
+        Distribution::Uniform => data.extend((0..n).map(|_| (splitmix64(&mut state) >> 33) as i32)),
+        Distribution::Sorted => data.extend((0..n).map(|i| i as i32)),
+        Distribution::Reverse => data.extend((0..n).map(|i| (n - 1 - i) as i32)),
+        Distribution::Duplicates => data.extend((0..n).map(|_| (splitmix64(&mut state) % 100) as i32)),
+        Distribution::OrganPipe => data.extend((0..n).map(|i| i.min(n - 1 - i) as i32)),
+        Distribution::Killer => {
+            // 1-based in Musser's paper: a[i] = i for odd i, k + i - 1 for even i, a[k + i] = 2i
+            let k = n / 2;
+            data.resize(n, n as i32);
+            for i in 1..=k {
+                data[i - 1] = if i % 2 == 1 { i } else { k + i - 1 } as i32;
+                data[k + i - 1] = (2 * i) as i32;
+            }
+        }
+    }
+    data
+}
+
+// A last-element pivot goes quadratic on sorted, reversed, organ-pipe, killer and duplicate-heavy
+// input, and the recursion would overflow the stack long before finishing. Past the depth limit
+// a subarray is heapsorted instead, as introsort does, and counted
+struct Stats {
+    depth_limit: usize,
+    max_depth: AtomicUsize,
+    heapsorted: AtomicUsize,
+}
+
+fn heapsort(arr: &mut [i32]) {
+    fn sift_down(arr: &mut [i32], mut root: usize, end: usize) {
+        loop {
+            let mut child = 2 * root + 1;
+            if child >= end {
+                break;
+            }
+            if child + 1 < end && arr[child] < arr[child + 1] {
+                child += 1;
+            }
+            if arr[root] >= arr[child] {
+                break;
+            }
+            arr.swap(root, child);
+            root = child;
+        }
+    }
+
+    let len = arr.len();
+    for root in (0..len / 2).rev() {
+        sift_down(arr, root, len);
+    }
+    for end in (1..len).rev() {
+        arr.swap(0, end);
+        sift_down(arr, 0, end);
+    }
+}
+
+fn parallel_quicksort(arr: &mut [i32], depth: usize, stats: &Stats) {
     if arr.len() <= THRESHOLD {
         arr.sort_unstable();
         return;
@@ -14,12 +108,19 @@ fn parallel_quicksort(arr: &mut [i32]) {
         return;
     }
     
+    stats.max_depth.fetch_max(depth, Ordering::Relaxed);
+    if depth >= stats.depth_limit {
+        stats.heapsorted.fetch_add(arr.len(), Ordering::Relaxed);
+        heapsort(arr);
+        return;
+    }
+    
     let pivot_idx = partition(arr);
     let (left, right) = arr.split_at_mut(pivot_idx);
     
     rayon::join(
-        || parallel_quicksort(left),
-        || parallel_quicksort(&mut right[1..])
+        || parallel_quicksort(left, depth + 1, stats),
+        || parallel_quicksort(&mut right[1..], depth + 1, stats)
     );
 }
 
@@ -40,24 +141,132 @@ fn partition(arr: &mut [i32]) -> usize {
 }
 
 fn main() {
-    // Generate random data
-    let mut data: Vec<i32> = (0..ARRAY_SIZE)
-        .map(|i| ((i * 1103515245 + 12345) % 2147483648) as i32)
-        .collect();
+    let mut measure = bench_measure::from_env();
+    let args: Vec<String> = std::env::args().collect();
+    let placement = bench_numa::place(&args);
+    bench_pages::enable(&args);
+    let distribution = match args.iter().position(|a| a == "--distribution") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("lcg") => Distribution::Lcg,
+            Some("uniform") => Distribution::Uniform,
+            Some("sorted") => Distribution::Sorted,
+            Some("reverse") => Distribution::Reverse,
+            Some("duplicates") => Distribution::Duplicates,
+            Some("organ-pipe") => Distribution::OrganPipe,
+            Some("killer") => Distribution::Killer,
+            _ => panic!("--distribution expects lcg, uniform, sorted, reverse, duplicates, organ-pipe or killer"),
+        },
+        None => Distribution::Lcg,
+    };
+    
+    // Generate data
+    let mut data = generate(distribution, ARRAY_SIZE);
+    // Twice the depth of a perfectly balanced recursion, as introsort uses
+    let depth_limit = 2 * ARRAY_SIZE.ilog2() as usize;
+    let new_stats = || Stats { depth_limit, max_depth: AtomicUsize::new(0), heapsorted: AtomicUsize::new(0) };
     
     // Warm-up
     let mut warmup = data.clone();
-    parallel_quicksort(&mut warmup);
+    parallel_quicksort(&mut warmup, 0, &new_stats());
+    
+    // Production sorts on the same input, for comparison rows
+    let mut baselines = Vec::new();
+    for (name, sort) in [
+        ("std sort_unstable (pdqsort)", (|d| d.sort_unstable()) as fn(&mut [i32])),
+        ("rayon par_sort_unstable", |d| d.par_sort_unstable()),
+    ] {
+        let mut copy = data.clone();
+        let start = Instant::now();
+        sort(&mut copy);
+        baselines.push((name, start.elapsed(), copy.windows(2).all(|w| w[0] <= w[1])));
+    }
     
     // Benchmark
-    let start = Instant::now();
-    parallel_quicksort(&mut data);
-    let duration = start.elapsed();
+    let stats = new_stats();
+    measure.start();
+    parallel_quicksort(&mut data, 0, &stats);
+    measure.stop();
     
     // Verify sort
     let is_sorted = data.windows(2).all(|w| w[0] <= w[1]);
     
-    println!("{:.6}", duration.as_secs_f64());
+    measure.report();
+    eprintln!("Distribution: {}", distribution.name());
+    eprintln!("Max recursion depth: {} (limit {})", stats.max_depth.load(Ordering::Relaxed), depth_limit);
+    let heapsorted = stats.heapsorted.load(Ordering::Relaxed);
+    if heapsorted > 0 {
+        eprintln!("Introsort fallback: {} elements heapsorted at the depth limit (quadratic partitioning)", heapsorted);
+    }
+    for (name, duration, sorted) in &baselines {
+        eprintln!("{}: {:.6}s (sorted: {})", name, duration.as_secs_f64(), sorted);
+    }
+    eprintln!("{}", bench_numa::report(&placement));
+    eprintln!("{}", bench_pages::report());
     eprintln!("Sorted: {}", is_sorted);
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISTRIBUTIONS: [Distribution; 7] = [
+        Distribution::Lcg,
+        Distribution::Uniform,
+        Distribution::Sorted,
+        Distribution::Reverse,
+        Distribution::Duplicates,
+        Distribution::OrganPipe,
+        Distribution::Killer,
+    ];
+
+    fn stats(n: usize) -> Stats {
+        Stats { depth_limit: 2 * n.ilog2() as usize, max_depth: AtomicUsize::new(0), heapsorted: AtomicUsize::new(0) }
+    }
+
+    #[test]
+    fn heapsort_sorts_known_arrays() {
+        for (input, expected) in [
+            (vec![], vec![]),
+            (vec![7], vec![7]),
+            (vec![3, -1, 2, -1, 0], vec![-1, -1, 0, 2, 3]),
+            (vec![5, 4, 3, 2, 1, 0], vec![0, 1, 2, 3, 4, 5]),
+            (vec![i32::MAX, i32::MIN, 0], vec![i32::MIN, 0, i32::MAX]),
+        ] {
+            let mut data = input;
+            heapsort(&mut data);
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    fn partition_splits_around_the_last_element() {
+        let mut data = vec![9, 1, 8, 2, 7, 3, 5];
+        let p = partition(&mut data);
+        assert_eq!(p, 3);
+        assert_eq!(data[p], 5);
+        assert!(data[..p].iter().all(|&x| x <= 5) && data[p + 1..].iter().all(|&x| x > 5));
+    }
+
+    #[test]
+    fn sorts_every_distribution() {
+        let n = 4 * THRESHOLD + 17;
+        for distribution in DISTRIBUTIONS {
+            let mut data = generate(distribution, n);
+            let mut expected = data.clone();
+            expected.sort();
+            parallel_quicksort(&mut data, 0, &stats(n));
+            assert_eq!(data, expected, "{} input", distribution.name());
+        }
+    }
+
+    #[test]
+    fn quadratic_input_falls_back_to_heapsort() {
+        let n = 4 * THRESHOLD;
+        let stats = stats(n);
+        let mut data = generate(Distribution::Sorted, n);
+        parallel_quicksort(&mut data, 0, &stats);
+        assert!(data.windows(2).all(|w| w[0] <= w[1]));
+        assert!(stats.heapsorted.load(Ordering::Relaxed) > 0);
+        assert_eq!(stats.max_depth.load(Ordering::Relaxed), stats.depth_limit);
+    }
+}
@@ -0,0 +1,138 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use rayon::prelude::*;
+use std::time::Instant;
+
+// OWASP minimums for interactive logins
+const ARGON2_MEMORY_KIB: u32 = 19 * 1024;
+const ARGON2_ITERATIONS: u32 = 2;
+const ARGON2_LANES: u32 = 1;
+const ARGON2_PASSWORDS: usize = 256;
+const BCRYPT_COST: u32 = 10;
+const BCRYPT_PASSWORDS: usize = 128;
+
+const PASSWORD_LEN: usize = 12;
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%&*";
+
+struct Credential {
+    password: Vec<u8>,
+    salt: [u8; 16],
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn generate_credentials(count: usize, seed: &mut u64) -> Vec<Credential> {
+    (0..count)
+        .map(|_| {
+            let password = (0..PASSWORD_LEN).map(|_| ALPHABET[next_random(seed) as usize % ALPHABET.len()]).collect();
+            let salt = std::array::from_fn(|_| next_random(seed) as u8);
+            Credential { password, salt }
+        })
+        .collect()
+}
+
+// FNV-1a over every output in order, so any single differing hash changes the checksum
+fn fold_hashes<'a>(outputs: impl Iterator<Item = &'a [u8]>) -> u64 {
+    let mut h = 0xcbf29ce484222325u64;
+    for byte in outputs.flatten() {
+        h = (h ^ *byte as u64).wrapping_mul(0x100000001b3);
+    }
+    h
+}
+
+fn argon2_hash(argon2: &Argon2, c: &Credential) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    argon2.hash_password_into(&c.password, &c.salt, &mut out).expect("argon2 hashing failed");
+    out
+}
+
+fn bcrypt_hash(c: &Credential) -> String {
+    bcrypt::hash_with_salt(&c.password, BCRYPT_COST, c.salt)
+        .expect("bcrypt hashing failed")
+        .format_for_version(bcrypt::Version::TwoB)
+}
+
+fn main() {
+    let mut seed = 0x5A17u64;
+    let argon2_credentials = generate_credentials(ARGON2_PASSWORDS, &mut seed);
+    let bcrypt_credentials = generate_credentials(BCRYPT_PASSWORDS, &mut seed);
+    let params = Params::new(ARGON2_MEMORY_KIB, ARGON2_ITERATIONS, ARGON2_LANES, Some(32)).unwrap();
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+
+    // Warm-up
+    let _ = argon2_hash(&argon2, &argon2_credentials[0]);
+    let _ = bcrypt_hash(&bcrypt_credentials[0]);
+
+    // Benchmark: one password per task, as a login server would spread requests over cores
+    let start = Instant::now();
+    let argon2_outputs: Vec<[u8; 32]> = argon2_credentials.par_iter().map(|c| argon2_hash(&argon2, c)).collect();
+    let argon2_duration = start.elapsed();
+
+    let start = Instant::now();
+    let bcrypt_outputs: Vec<String> = bcrypt_credentials.par_iter().map(bcrypt_hash).collect();
+    let bcrypt_duration = start.elapsed();
+
+    let total_duration = argon2_duration + bcrypt_duration;
+    let in_flight = rayon::current_num_threads().min(ARGON2_PASSWORDS);
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Argon2id (m = {} MiB, t = {}, p = {}): {:.1} hashes/s, {} MiB working memory ({} hashes in flight)",
+              ARGON2_MEMORY_KIB / 1024, ARGON2_ITERATIONS, ARGON2_LANES,
+              ARGON2_PASSWORDS as f64 / argon2_duration.as_secs_f64(),
+              in_flight as u32 * ARGON2_MEMORY_KIB / 1024, in_flight);
+    eprintln!("bcrypt (cost = {}): {:.1} hashes/s, 4 KiB state per hash",
+              BCRYPT_COST, BCRYPT_PASSWORDS as f64 / bcrypt_duration.as_secs_f64());
+    eprintln!("First bcrypt hash: {}", bcrypt_outputs[0]);
+    eprintln!("Checksum: {:016x} (bcrypt: {:016x})",
+              fold_hashes(argon2_outputs.iter().map(|h| &h[..])),
+              fold_hashes(bcrypt_outputs.iter().map(|h| h.as_bytes())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn argon2_matches_the_reference_implementation() {
+        // The reference CLI: echo -n password | argon2 somesalt -id -t 2 -m 16 -p 1 -l 32
+        let params = Params::new(1 << 16, 2, 1, Some(32)).unwrap();
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+        let mut out = [0u8; 32];
+        argon2.hash_password_into(b"password", b"somesalt", &mut out).unwrap();
+        assert_eq!(hex(&out), "09316115d5cf24ed5a15a31a3ba326e5cf32edc24702987c02b6566f61913cf7");
+    }
+
+    #[test]
+    fn hashes_depend_on_password_and_salt() {
+        let mut seed = 1u64;
+        let credentials = generate_credentials(2, &mut seed);
+        assert!(credentials.iter().all(|c| c.password.len() == PASSWORD_LEN && c.password.iter().all(|b| ALPHABET.contains(b))));
+        let params = Params::new(ARGON2_MEMORY_KIB, ARGON2_ITERATIONS, ARGON2_LANES, Some(32)).unwrap();
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+
+        let first = argon2_hash(&argon2, &credentials[0]);
+        assert_eq!(first, argon2_hash(&argon2, &credentials[0]));
+        let other_salt = Credential { password: credentials[0].password.clone(), salt: credentials[1].salt };
+        assert_ne!(first, argon2_hash(&argon2, &other_salt));
+
+        let hash = bcrypt_hash(&credentials[0]);
+        assert!(hash.starts_with("$2b$10$"));
+        assert!(bcrypt::verify(&credentials[0].password, &hash).unwrap());
+        assert!(!bcrypt::verify(&credentials[1].password, &hash).unwrap());
+    }
+
+    #[test]
+    fn fold_hashes_is_fnv1a_over_the_concatenation() {
+        assert_eq!(fold_hashes(std::iter::empty()), 0xcbf29ce484222325);
+        assert_eq!(fold_hashes([&b"a"[..]].into_iter()), 0xaf63dc4c8601ec8c);
+        assert_eq!(fold_hashes([&b"ab"[..], b"c"].into_iter()), fold_hashes([&b"a"[..], b"bc"].into_iter()));
+    }
+}
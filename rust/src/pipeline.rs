@@ -0,0 +1,244 @@
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const BLOCK_BYTES: usize = 64 << 10;
+const BLOCKS: u64 = 1_024; // 64 MiB through the pipeline per run
+const QUEUE_DEPTHS: [usize; 4] = [1, 4, 16, 64]; // blocks each channel holds before its sender blocks
+const COMPRESSION_LEVEL: i32 = 1;
+const VOCABULARY_SIZE: usize = 512;
+const STAGES: [&str; 4] = ["generate", "transform", "compress", "hash"];
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn vocabulary() -> Vec<Vec<u8>> {
+    let mut seed = 7;
+    (0..VOCABULARY_SIZE)
+        .map(|_| {
+            let len = 3 + next_random(&mut seed) % 7;
+            (0..len).map(|_| b'a' + (next_random(&mut seed) % 26) as u8).collect()
+        })
+        .collect()
+}
+
+fn push_decimal(out: &mut Vec<u8>, mut n: u64) {
+    let mut digits = [0u8; 20];
+    let mut i = digits.len();
+    loop {
+        i -= 1;
+        digits[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    out.extend_from_slice(&digits[i..]);
+}
+
+// Stage 1: a block of log-like text, words with the odd number, seeded by its index
+fn generate(vocabulary: &[Vec<u8>], index: u64) -> Vec<u8> {
+    let mut seed = index.wrapping_mul(0x9E3779B97F4A7C15) ^ 0xDEADBEEF;
+    let mut out = Vec::with_capacity(BLOCK_BYTES + 32);
+    while out.len() < BLOCK_BYTES {
+        let r = next_random(&mut seed);
+        if r.is_multiple_of(8) {
+            push_decimal(&mut out, next_random(&mut seed) % 1_000_000);
+        } else {
+            out.extend_from_slice(&vocabulary[(r >> 3) as usize % VOCABULARY_SIZE]);
+        }
+        out.push(if r.is_multiple_of(13) { b'\n' } else { b' ' });
+    }
+    out.truncate(BLOCK_BYTES);
+    out
+}
+
+// Stage 2: normalize in place, upper-casing letters and masking digits as a redaction pass would
+fn transform(data: &mut [u8]) {
+    for b in data.iter_mut() {
+        *b = if b.is_ascii_lowercase() { *b - 32 } else if b.is_ascii_digit() { b'#' } else { *b };
+    }
+}
+
+// Stage 3: system zlib through its C API, as in deflate
+fn compress(data: &[u8]) -> Vec<u8> {
+    // SAFETY: out has compressBound(len) bytes and len tells zlib so; compress2 writes back the used length
+    unsafe {
+        let mut len = libz_sys::compressBound(data.len() as libz_sys::uLong);
+        let mut out = vec![0u8; len as usize];
+        let rc = libz_sys::compress2(out.as_mut_ptr(), &mut len, data.as_ptr(), data.len() as libz_sys::uLong,
+                                     COMPRESSION_LEVEL);
+        assert_eq!(rc, libz_sys::Z_OK, "zlib compress2 failed");
+        out.truncate(len as usize);
+        out
+    }
+}
+
+// Stage 4: FNV-1a 64 of the compressed block
+fn hash(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |h, &b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+// What the last stage folds every block into, in order
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Digest {
+    checksum: u64,
+    compressed_bytes: u64,
+}
+
+impl Digest {
+    fn new() -> Self {
+        Digest { checksum: 0, compressed_bytes: 0 }
+    }
+
+    fn add(&mut self, block: &[u8]) {
+        self.checksum = self.checksum.wrapping_mul(31).wrapping_add(hash(block));
+        self.compressed_bytes += block.len() as u64;
+    }
+}
+
+// All four stages one block at a time on this thread
+fn run_sequential(vocabulary: &[Vec<u8>], blocks: u64) -> Digest {
+    let mut digest = Digest::new();
+    for index in 0..blocks {
+        let mut block = generate(vocabulary, index);
+        transform(&mut block);
+        digest.add(&compress(&block));
+    }
+    digest
+}
+
+// Time spent inside a stage's work, as opposed to waiting on its channels
+fn busy<T>(total: &mut Duration, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let value = f();
+    *total += start.elapsed();
+    value
+}
+
+type Block = (u64, Vec<u8>);
+
+// A middle stage: receive, work, send on, until the stage before hangs up
+fn stage(inbox: Receiver<Block>, outbox: SyncSender<Block>, work: impl Fn(Vec<u8>) -> Vec<u8>) -> Duration {
+    let mut total = Duration::ZERO;
+    for (index, block) in inbox {
+        let block = busy(&mut total, || work(block));
+        outbox.send((index, block)).unwrap();
+    }
+    total
+}
+
+// One thread per stage, each pair joined by a channel holding up to depth blocks. Returns the digest
+// and each stage's busy time
+fn run_pipeline(vocabulary: &[Vec<u8>], blocks: u64, depth: usize) -> (Digest, [Duration; 4]) {
+    let (generated_tx, generated_rx) = sync_channel::<Block>(depth);
+    let (transformed_tx, transformed_rx) = sync_channel::<Block>(depth);
+    let (compressed_tx, compressed_rx) = sync_channel::<Block>(depth);
+    thread::scope(|s| {
+        let generator = s.spawn(move || {
+            let mut total = Duration::ZERO;
+            for index in 0..blocks {
+                let block = busy(&mut total, || generate(vocabulary, index));
+                generated_tx.send((index, block)).unwrap();
+            }
+            total
+        });
+        let transformer = s.spawn(move || {
+            stage(generated_rx, transformed_tx, |mut block| {
+                transform(&mut block);
+                block
+            })
+        });
+        let compressor = s.spawn(move || stage(transformed_rx, compressed_tx, |block| compress(&block)));
+        let hasher = s.spawn(move || {
+            let (mut total, mut digest) = (Duration::ZERO, Digest::new());
+            for (expected, (index, block)) in compressed_rx.into_iter().enumerate() {
+                assert_eq!(index, expected as u64, "blocks arrived out of order");
+                busy(&mut total, || digest.add(&block));
+            }
+            (digest, total)
+        });
+        let busy_times = [generator, transformer, compressor].map(|h| h.join().unwrap());
+        let (digest, hash_time) = hasher.join().unwrap();
+        (digest, [busy_times[0], busy_times[1], busy_times[2], hash_time])
+    })
+}
+
+fn main() {
+    let vocabulary = vocabulary();
+
+    // Warm-up
+    run_pipeline(&vocabulary, 32, 4);
+
+    let start = Instant::now();
+    let expected = run_sequential(&vocabulary, BLOCKS);
+    let sequential = start.elapsed();
+
+    let mut runs = Vec::new();
+    for depth in QUEUE_DEPTHS {
+        let start = Instant::now();
+        let (digest, busy_times) = run_pipeline(&vocabulary, BLOCKS, depth);
+        let wall = start.elapsed();
+        if digest != expected {
+            eprintln!("Depth {}: pipeline digest {:?} differs from sequential {:?}", depth, digest, expected);
+            std::process::exit(1);
+        }
+        runs.push((depth, wall, busy_times));
+    }
+
+    let megabytes = (BLOCKS as usize * BLOCK_BYTES) as f64 / 1e6;
+    let total: Duration = runs.iter().map(|r| r.1).sum();
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("{} blocks of {} KiB, zlib level {} to {:.1} MB ({:.3} ratio)", BLOCKS, BLOCK_BYTES >> 10,
+              COMPRESSION_LEVEL, expected.compressed_bytes as f64 / 1e6, expected.compressed_bytes as f64 / 1e6 / megabytes);
+    eprintln!("  sequential  {:.3}s {:7.1} MB/s", sequential.as_secs_f64(), megabytes / sequential.as_secs_f64());
+    for (depth, wall, busy_times) in &runs {
+        let shares: Vec<String> = STAGES
+            .iter()
+            .zip(busy_times)
+            .map(|(name, b)| format!("{} {:.0}%", name, b.as_secs_f64() / wall.as_secs_f64() * 100.0))
+            .collect();
+        eprintln!("  depth {:>3}   {:.3}s {:7.1} MB/s ({:.2}x)  busy: {}", depth, wall.as_secs_f64(),
+                  megabytes / wall.as_secs_f64(), sequential.as_secs_f64() / wall.as_secs_f64(), shares.join(", "));
+    }
+    eprintln!("Checksum: {}", expected.checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_are_text_of_the_right_size() {
+        let vocabulary = vocabulary();
+        let block = generate(&vocabulary, 3);
+        assert_eq!(block.len(), BLOCK_BYTES);
+        assert!(block.iter().all(|&b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b' ' || b == b'\n'));
+        assert_eq!(block, generate(&vocabulary, 3));
+        assert_ne!(block, generate(&vocabulary, 4));
+    }
+
+    #[test]
+    fn transform_masks_and_upper_cases() {
+        let mut text = *b"req 42 ok\n";
+        transform(&mut text);
+        assert_eq!(&text, b"REQ ## OK\n");
+        let mut decimal = Vec::new();
+        push_decimal(&mut decimal, 0);
+        push_decimal(&mut decimal, 907);
+        assert_eq!(decimal, b"0907");
+    }
+
+    #[test]
+    fn pipeline_matches_sequential() {
+        let vocabulary = vocabulary();
+        let expected = run_sequential(&vocabulary, 20);
+        for depth in [1, 3] {
+            assert_eq!(run_pipeline(&vocabulary, 20, depth).0, expected);
+        }
+        assert_eq!(hash(b""), 0xcbf29ce484222325);
+        assert_eq!(hash(b"a"), 0xaf63dc4c8601ec8c);
+    }
+}
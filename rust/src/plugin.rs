@@ -0,0 +1,64 @@
+// The Rust side of the plugin ABI in `cpp/src/bench_plugin.h`, exported from the `bench_kernels`
+// cdylib. The `plugin_call` binary loads that library at runtime with libloading, and also calls
+// these functions through the rlib, linked in like any other Rust dependency.
+
+pub const ABI_VERSION: u32 = 1;
+
+/// What `plugin_entry` hands a host: the ABI version to check, then the operations.
+#[repr(C)]
+pub struct BenchPlugin {
+    pub abi_version: u32,
+    pub mix: extern "C" fn(u64) -> u64,
+    pub mix_sum: unsafe extern "C" fn(*const u64, usize) -> u64,
+}
+
+/// SplitMix64's output function: a few cycles of work, so the cost of reaching it shows.
+#[no_mangle]
+pub extern "C" fn plugin_mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// `plugin_mix` summed over `len` values, wrapping: one call for a whole batch.
+///
+/// # Safety
+///
+/// `values` must point to `len` readable `u64`s.
+#[no_mangle]
+pub unsafe extern "C" fn plugin_mix_sum(values: *const u64, len: usize) -> u64 {
+    // SAFETY: the caller guarantees values points to len u64s
+    let values = unsafe { std::slice::from_raw_parts(values, len) };
+    values.iter().fold(0u64, |sum, &v| sum.wrapping_add(plugin_mix(v)))
+}
+
+static PLUGIN: BenchPlugin = BenchPlugin { abi_version: ABI_VERSION, mix: plugin_mix, mix_sum: plugin_mix_sum };
+
+#[no_mangle]
+pub extern "C" fn plugin_entry() -> *const BenchPlugin {
+    &PLUGIN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mix_matches_splitmix64() {
+        // The first outputs of SplitMix64 seeded with 0, whose state after n steps is n * golden gamma
+        assert_eq!(plugin_mix(0), 0xE220A8397B1DCDAF);
+        assert_eq!(plugin_mix(0x9E3779B97F4A7C15), 0x6E789E6AA1B965F4);
+    }
+
+    #[test]
+    fn entry_and_batch_agree_with_mix() {
+        let values = [1, 2, 3, u64::MAX];
+        // SAFETY: plugin_entry returns a pointer to a static
+        let plugin = unsafe { &*plugin_entry() };
+        assert_eq!(plugin.abi_version, ABI_VERSION);
+        let expected = values.iter().fold(0u64, |sum, &v| sum.wrapping_add((plugin.mix)(v)));
+        // SAFETY: values holds len u64s
+        assert_eq!(unsafe { (plugin.mix_sum)(values.as_ptr(), values.len()) }, expected);
+    }
+}
@@ -0,0 +1,141 @@
+use bench_kernels::plugin::{plugin_mix, BenchPlugin, ABI_VERSION};
+use libloading::{Library, Symbol};
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const CALLS: u64 = 200_000_000; // per variant
+const BATCH: usize = 1024; // values per plugin_mix_sum call
+const LOAD_CYCLES: usize = 1_000;
+const WARMUP_CALLS: u64 = 1_000_000;
+
+type MixFn = extern "C" fn(u64) -> u64;
+
+// One call per value: the sum of mix(i) for i in 0..calls
+fn per_call(calls: u64, mix: impl Fn(u64) -> u64) -> u64 {
+    (0..calls).fold(0u64, |sum, i| sum.wrapping_add(mix(i)))
+}
+
+// The same sum, handing the plugin BATCH values per call
+fn batched(calls: u64, plugin: &BenchPlugin) -> u64 {
+    let mut values = vec![0u64; BATCH];
+    let mut sum = 0u64;
+    for first in (0..calls).step_by(BATCH) {
+        let len = BATCH.min((calls - first) as usize);
+        for (j, v) in values[..len].iter_mut().enumerate() {
+            *v = first + j as u64;
+        }
+        // SAFETY: values holds at least len u64s
+        sum = sum.wrapping_add(unsafe { (plugin.mix_sum)(values.as_ptr(), len) });
+    }
+    sum
+}
+
+fn open_plugin(path: &Path) -> Library {
+    // SAFETY: the library is this crate's own cdylib, whose initializers are Rust's and sound
+    unsafe { Library::new(path) }.unwrap_or_else(|e| {
+        eprintln!("Failed to load {}: {}", path.display(), e);
+        std::process::exit(1);
+    })
+}
+
+fn checked_entry(library: &Library) -> &BenchPlugin {
+    // SAFETY: plugin_entry has this signature in bench_plugin.h, and returns a pointer to a static
+    // that lives as long as the library stays loaded
+    let plugin = unsafe {
+        let entry: Symbol<extern "C" fn() -> *const BenchPlugin> =
+            library.get(b"plugin_entry").expect("plugin has no plugin_entry");
+        &*entry()
+    };
+    if plugin.abi_version != ABI_VERSION {
+        eprintln!("Plugin ABI version {}, expected {}", plugin.abi_version, ABI_VERSION);
+        std::process::exit(1);
+    }
+    plugin
+}
+
+struct Variant {
+    name: &'static str,
+    time: Duration,
+    sum: u64,
+}
+
+fn timed(name: &'static str, run: impl FnOnce() -> u64) -> Variant {
+    let start = Instant::now();
+    let sum = run();
+    Variant { name, time: start.elapsed(), sum }
+}
+
+fn main() {
+    let file = libloading::library_filename("bench_kernels");
+    let path = std::env::current_exe().unwrap().with_file_name(&file);
+
+    // Load, resolve and unload before anything else holds the library open, so each cycle maps it afresh
+    let start = Instant::now();
+    for _ in 0..LOAD_CYCLES {
+        let library = open_plugin(&path);
+        checked_entry(&library);
+    }
+    let load_time = start.elapsed();
+
+    let library = open_plugin(&path);
+    // SAFETY: plugin_mix has this signature in bench_plugin.h
+    let dynamic_mix: MixFn = unsafe { *library.get::<MixFn>(b"plugin_mix").expect("plugin has no plugin_mix") };
+    let plugin = checked_entry(&library);
+    // Opaque to the optimizer, so the static pointer variant cannot become a direct call
+    let static_mix: MixFn = black_box(plugin_mix);
+
+    // Warm-up
+    let _ = per_call(WARMUP_CALLS, |x| plugin_mix(x));
+    let _ = per_call(WARMUP_CALLS, |x| dynamic_mix(x));
+    let _ = batched(WARMUP_CALLS, plugin);
+
+    // Benchmark
+    let variants = [
+        timed("static, direct call", || per_call(CALLS, |x| plugin_mix(x))),
+        timed("static, fn pointer", || per_call(CALLS, |x| static_mix(x))),
+        timed("dlopen, symbol", || per_call(CALLS, |x| dynamic_mix(x))),
+        timed("dlopen, vtable", || per_call(CALLS, |x| (plugin.mix)(x))),
+        timed("dlopen, batched", || batched(CALLS, plugin)),
+    ];
+
+    for v in &variants {
+        if v.sum != variants[0].sum {
+            eprintln!("{} summed to {}, expected {}", v.name, v.sum, variants[0].sum);
+            std::process::exit(1);
+        }
+    }
+
+    // The headline is the loaded symbol, the usual way a host calls into a plugin
+    println!("{:.6}", variants[2].time.as_secs_f64());
+    eprintln!("{} calls per variant into {}, ns per value", CALLS, file.to_string_lossy());
+    for v in &variants {
+        eprintln!("  {:<24} {:7.3}", v.name, v.time.as_secs_f64() * 1e9 / CALLS as f64);
+    }
+    eprintln!("Load + resolve + unload: {:.1} us per cycle over {} cycles",
+              load_time.as_secs_f64() * 1e6 / LOAD_CYCLES as f64, LOAD_CYCLES);
+    eprintln!("Checksum: {}", variants[0].sum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bench_kernels::plugin::plugin_entry;
+
+    #[test]
+    fn every_call_path_sums_the_same() {
+        // SAFETY: plugin_entry returns a pointer to a static
+        let plugin = unsafe { &*plugin_entry() };
+        let expected = per_call(5_000, |x| plugin_mix(x));
+        assert_eq!(per_call(5_000, |x| (plugin.mix)(x)), expected);
+        assert_eq!(batched(5_000, plugin), expected);
+    }
+
+    #[test]
+    fn a_partial_last_batch_is_summed() {
+        // SAFETY: plugin_entry returns a pointer to a static
+        let plugin = unsafe { &*plugin_entry() };
+        assert_eq!(batched(BATCH as u64 + 3, plugin), per_call(BATCH as u64 + 3, |x| plugin_mix(x)));
+        assert_eq!(batched(0, plugin), 0);
+    }
+}
@@ -0,0 +1,112 @@
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+const WIDTH: u32 = 4096;  // 32 MB of RGBA8
+const HEIGHT: u32 = 2048;
+const CHANNELS: usize = 4;
+const ROUNDS: usize = 2;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Smooth gradients and soft circles with low-amplitude noise, so filters and
+// deflate both have real work to do (pure noise would be incompressible)
+fn generate_image() -> Vec<u8> {
+    let (width, height) = (WIDTH as usize, HEIGHT as usize);
+    let mut seed = 42u64;
+    let mut pixels = Vec::with_capacity(width * height * CHANNELS);
+    for y in 0..height {
+        for x in 0..width {
+            let noise = next_random(&mut seed);
+            let dx = (x % 512) as i64 - 256;
+            let dy = (y % 512) as i64 - 256;
+            let ring = if dx * dx + dy * dy < 160 * 160 { 64 } else { 0 };
+            pixels.push((x * 191 / width + (noise & 7) as usize) as u8);
+            pixels.push((y * 191 / height + ring + ((noise >> 3) & 7) as usize) as u8);
+            pixels.push(((x + y) * 127 / (width + height) + ring * 2) as u8);
+            pixels.push(if (x / 64 + y / 64) % 7 == 0 { 128 } else { 255 });
+        }
+    }
+    pixels
+}
+
+// Deflate level 6 with adaptive per-row filtering, matching libpng's defaults
+fn encode(pixels: &[u8], height: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, WIDTH, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(png::Compression::Balanced);
+    let mut writer = encoder.write_header().expect("PNG header failed");
+    writer.write_image_data(pixels).expect("PNG encode failed");
+    writer.finish().expect("PNG finish failed");
+    out
+}
+
+fn decode(bytes: &[u8]) -> Vec<u8> {
+    let mut reader = png::Decoder::new(Cursor::new(bytes)).read_info().expect("PNG header invalid");
+    let mut pixels = vec![0u8; reader.output_buffer_size().expect("PNG too large")];
+    let info = reader.next_frame(&mut pixels).expect("PNG decode failed");
+    assert_eq!((info.width, info.color_type), (WIDTH, png::ColorType::Rgba));
+    pixels.truncate(info.buffer_size());
+    pixels
+}
+
+fn main() {
+    let pixels = generate_image();
+    let megabytes = pixels.len() as f64 / 1e6;
+
+    // Warm-up on the top sixteenth of the image
+    let _ = decode(&encode(&pixels[..pixels.len() / 16], HEIGHT / 16));
+
+    let mut encode_time = Duration::ZERO;
+    let mut decode_time = Duration::ZERO;
+    let mut encoded = Vec::new();
+    let mut decoded = Vec::new();
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        encoded = encode(&pixels, HEIGHT);
+        encode_time += start.elapsed();
+
+        let start = Instant::now();
+        decoded = decode(&encoded);
+        decode_time += start.elapsed();
+    }
+    assert!(decoded == pixels, "Decoded pixels differ from the original");
+
+    let checksum = decoded.iter().fold(0u64, |acc, &b| acc.wrapping_add(b as u64));
+    let rate = |d: Duration| megabytes * ROUNDS as f64 / d.as_secs_f64();
+
+    println!("{:.6}", (encode_time + decode_time).as_secs_f64());
+    eprintln!("Image: {}x{} RGBA8 ({:.1} MB raw), {} rounds", WIDTH, HEIGHT, megabytes, ROUNDS);
+    eprintln!("Encoded size: {:.2} MB (ratio {:.3})", encoded.len() as f64 / 1e6, encoded.len() as f64 / pixels.len() as f64);
+    eprintln!("Encode: {:.1} MB/s, decode: {:.1} MB/s", rate(encode_time), rate(decode_time));
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_the_generated_image() {
+        let rows = 64;
+        let pixels = &generate_image()[..WIDTH as usize * rows * CHANNELS];
+        let encoded = encode(pixels, rows as u32);
+        assert_eq!(&encoded[..8], b"\x89PNG\r\n\x1a\n");
+        // Gradients and flat alpha compress well below raw size
+        assert!(encoded.len() < pixels.len() / 2);
+        assert_eq!(decode(&encoded), pixels);
+    }
+
+    #[test]
+    fn round_trips_noise() {
+        let mut seed = 5u64;
+        let pixels: Vec<u8> = (0..WIDTH as usize * 3 * CHANNELS).map(|_| next_random(&mut seed) as u8).collect();
+        assert_eq!(decode(&encode(&pixels, 3)), pixels);
+    }
+}
@@ -0,0 +1,426 @@
+use geo::{Contains, Coord, LineString, Point, Polygon};
+use rayon::prelude::*;
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::RTree;
+use std::time::Instant;
+
+const WORLD: f64 = 1000.0; // points and polygons lie in [0, WORLD)^2
+const POLYGONS: usize = 1000;
+const MIN_VERTICES: usize = 16;
+const MAX_VERTICES: usize = 128;
+const MIN_RADIUS: f64 = 3.0;
+const MAX_RADIUS: f64 = 15.0;
+const NODE_CAPACITY: usize = 16; // children per R-tree node
+const DEFAULT_POINTS: u64 = 100_000_000;
+const WARMUP_POINTS: u64 = 1_000_000;
+const VERIFY_POINTS: u64 = 100_000; // checked against a scan of every polygon, without the index
+const CHUNK: u64 = 1 << 20;
+const SEED: u64 = 0x706f_6c79_676f_6e73;
+const GOLDEN: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// The SplitMix64 output function: a stream from a counter, or a point from its index
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(GOLDEN);
+        mix(self.0)
+    }
+
+    fn range(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (hi - lo) * ((self.next() >> 11) as f64 * (1.0 / (1u64 << 53) as f64))
+    }
+}
+
+// Point i of the stream, 32 bits per coordinate, so no point set needs to be stored
+fn point(i: u64) -> (f64, f64) {
+    let bits = mix(SEED.wrapping_add((i + 1).wrapping_mul(GOLDEN)));
+    let scale = WORLD / 4_294_967_296.0;
+    ((bits >> 32) as f64 * scale, (bits & 0xffff_ffff) as f64 * scale)
+}
+
+#[derive(Clone, Copy)]
+struct Rect {
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+}
+
+impl Rect {
+    const EMPTY: Rect = Rect { min_x: f64::INFINITY, min_y: f64::INFINITY, max_x: f64::NEG_INFINITY, max_y: f64::NEG_INFINITY };
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+
+    fn union(&self, o: &Rect) -> Rect {
+        Rect { min_x: self.min_x.min(o.min_x), min_y: self.min_y.min(o.min_y), max_x: self.max_x.max(o.max_x), max_y: self.max_y.max(o.max_y) }
+    }
+
+    fn center(&self) -> (f64, f64) {
+        ((self.min_x + self.max_x) * 0.5, (self.min_y + self.max_y) * 0.5)
+    }
+}
+
+// Every polygon's ring in one pair of coordinate arrays; ring p is offsets[p]..offsets[p + 1]
+struct Polygons {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    offsets: Vec<usize>,
+    bounds: Vec<Rect>,
+}
+
+impl Polygons {
+    fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    // Even-odd ray casting: count the edges a ray from (x, y) toward +x crosses. Each edge is
+    // half-open in y, so a ray through a vertex counts it once
+    fn contains(&self, p: usize, x: f64, y: f64) -> bool {
+        let (xs, ys) = (&self.xs[self.offsets[p]..self.offsets[p + 1]], &self.ys[self.offsets[p]..self.offsets[p + 1]]);
+        let mut inside = false;
+        let mut j = xs.len() - 1;
+        for i in 0..xs.len() {
+            if (ys[i] > y) != (ys[j] > y) && x < (xs[j] - xs[i]) * (y - ys[i]) / (ys[j] - ys[i]) + xs[i] {
+                inside = !inside;
+            }
+            j = i;
+        }
+        inside
+    }
+}
+
+// Star-shaped, mostly concave rings: vertices at increasing angles around a center, each at a
+// random fraction of the radius, so no ring crosses itself. Polygons overlap one another freely
+fn make_polygons(rng: &mut Rng) -> Polygons {
+    let mut polygons = Polygons { xs: Vec::new(), ys: Vec::new(), offsets: vec![0], bounds: Vec::new() };
+    for _ in 0..POLYGONS {
+        let radius = rng.range(MIN_RADIUS, MAX_RADIUS);
+        let (cx, cy) = (rng.range(radius, WORLD - radius), rng.range(radius, WORLD - radius));
+        let vertices = MIN_VERTICES + (rng.next() % (MAX_VERTICES - MIN_VERTICES + 1) as u64) as usize;
+        let mut bounds = Rect::EMPTY;
+        for k in 0..vertices {
+            let angle = (k as f64 + rng.range(0.0, 0.9)) * std::f64::consts::TAU / vertices as f64;
+            let r = radius * rng.range(0.4, 1.0);
+            let (x, y) = (cx + r * angle.cos(), cy + r * angle.sin());
+            bounds = bounds.union(&Rect { min_x: x, min_y: y, max_x: x, max_y: y });
+            polygons.xs.push(x);
+            polygons.ys.push(y);
+        }
+        polygons.offsets.push(polygons.xs.len());
+        polygons.bounds.push(bounds);
+    }
+    polygons
+}
+
+#[derive(Clone, Copy)]
+struct Node {
+    bounds: Rect,
+    first: u32, // first child node, or first entry of items for a leaf
+    count: u32,
+    leaf: bool,
+}
+
+// A static R-tree over the polygons' bounding boxes, bulk-loaded with Sort-Tile-Recursive:
+// sort by center x, cut into vertical slices, sort each slice by center y and pack runs of
+// NODE_CAPACITY into nodes, then repeat over the nodes until one root remains
+struct RTreeIndex {
+    nodes: Vec<Node>,
+    items: Vec<u32>, // polygon indices in leaf order
+    root: usize,
+}
+
+// Reorders entries (index, bounds) into STR order and returns the groups of NODE_CAPACITY
+fn str_groups(entries: &mut [(u32, Rect)]) -> Vec<std::ops::Range<usize>> {
+    let by = |axis: usize| move |a: &(u32, Rect), b: &(u32, Rect)| {
+        let (ca, cb) = (a.1.center(), b.1.center());
+        let (ka, kb) = if axis == 0 { (ca.0, cb.0) } else { (ca.1, cb.1) };
+        ka.total_cmp(&kb).then(a.0.cmp(&b.0))
+    };
+    let leaves = entries.len().div_ceil(NODE_CAPACITY);
+    let slices = (leaves as f64).sqrt().ceil() as usize;
+    let slice_len = slices * NODE_CAPACITY;
+    entries.sort_unstable_by(by(0));
+    let mut groups = Vec::with_capacity(leaves);
+    for start in (0..entries.len()).step_by(slice_len) {
+        let end = (start + slice_len).min(entries.len());
+        entries[start..end].sort_unstable_by(by(1));
+        for group in (start..end).step_by(NODE_CAPACITY) {
+            groups.push(group..(group + NODE_CAPACITY).min(end));
+        }
+    }
+    groups
+}
+
+impl RTreeIndex {
+    fn build(polygons: &Polygons) -> Self {
+        let mut entries: Vec<(u32, Rect)> = polygons.bounds.iter().enumerate().map(|(i, b)| (i as u32, *b)).collect();
+        let bounds = |entries: &[(u32, Rect)]| entries.iter().fold(Rect::EMPTY, |b, e| b.union(&e.1));
+        let groups = str_groups(&mut entries);
+        let mut pending: Vec<Node> = groups.into_iter().map(|group| {
+            Node { bounds: bounds(&entries[group.clone()]), first: group.start as u32, count: group.len() as u32, leaf: true }
+        }).collect();
+        let items = entries.iter().map(|e| e.0).collect();
+        // Each pass packs the pending level into parents; a parent's children must be contiguous,
+        // so the level is stored in its packed order
+        let mut nodes = Vec::new();
+        while pending.len() > 1 {
+            let mut level: Vec<(u32, Rect)> = pending.iter().enumerate().map(|(i, n)| (i as u32, n.bounds)).collect();
+            let groups = str_groups(&mut level);
+            let base = nodes.len();
+            nodes.extend(level.iter().map(|&(i, _)| pending[i as usize]));
+            pending = groups.into_iter().map(|group| {
+                let first = (base + group.start) as u32;
+                Node { bounds: bounds(&level[group.clone()]), first, count: group.len() as u32, leaf: false }
+            }).collect();
+        }
+        let root = nodes.len();
+        nodes.push(pending[0]);
+        RTreeIndex { nodes, items, root }
+    }
+
+    // Calls visit with every polygon whose bounding box holds the point
+    fn query(&self, x: f64, y: f64, mut visit: impl FnMut(usize)) {
+        let mut stack = [0u32; 64];
+        stack[0] = self.root as u32;
+        let mut depth = 1;
+        while depth > 0 {
+            depth -= 1;
+            let node = &self.nodes[stack[depth] as usize];
+            if !node.bounds.contains(x, y) {
+                continue;
+            }
+            let children = node.first as usize..(node.first + node.count) as usize;
+            if node.leaf {
+                for &item in &self.items[children] {
+                    visit(item as usize);
+                }
+            } else {
+                for child in children {
+                    stack[depth] = child as u32;
+                    depth += 1;
+                }
+            }
+        }
+    }
+
+    fn height(&self) -> usize {
+        let mut node = &self.nodes[self.root];
+        let mut height = 1;
+        while !node.leaf {
+            node = &self.nodes[node.first as usize];
+            height += 1;
+        }
+        height
+    }
+}
+
+// Containment counts per polygon, and how many points fell in at least one
+#[derive(Clone, PartialEq, Debug)]
+struct Hits {
+    per_polygon: Vec<u64>,
+    covered: u64,
+}
+
+impl Hits {
+    fn new(polygons: usize) -> Self {
+        Hits { per_polygon: vec![0; polygons], covered: 0 }
+    }
+
+    fn merge(mut self, other: Hits) -> Hits {
+        for (a, b) in self.per_polygon.iter_mut().zip(other.per_polygon) {
+            *a += b;
+        }
+        self.covered += other.covered;
+        self
+    }
+
+    fn total(&self) -> u64 {
+        self.per_polygon.iter().sum()
+    }
+
+    fn checksum(&self) -> u64 {
+        self.per_polygon.iter().enumerate().fold(self.covered, |sum, (p, &h)| sum.wrapping_add(h.wrapping_mul(p as u64 + 1)))
+    }
+}
+
+// Runs count(hits, start, end) over CHUNK-sized ranges of the point stream in parallel
+fn parallel_hits(polygons: usize, points: u64, count: impl Fn(&mut Hits, u64, u64) + Sync) -> Hits {
+    (0..points.div_ceil(CHUNK))
+        .into_par_iter()
+        .map(|c| {
+            let mut hits = Hits::new(polygons);
+            count(&mut hits, c * CHUNK, ((c + 1) * CHUNK).min(points));
+            hits
+        })
+        .reduce(|| Hits::new(polygons), Hits::merge)
+}
+
+fn count_indexed(polygons: &Polygons, index: &RTreeIndex, points: u64) -> Hits {
+    parallel_hits(polygons.len(), points, |hits, start, end| {
+        for i in start..end {
+            let (x, y) = point(i);
+            let mut inside = false;
+            index.query(x, y, |p| {
+                if polygons.bounds[p].contains(x, y) && polygons.contains(p, x, y) {
+                    hits.per_polygon[p] += 1;
+                    inside = true;
+                }
+            });
+            hits.covered += inside as u64;
+        }
+    })
+}
+
+// Every polygon's box for every point: the cost the index saves, on a small prefix of the stream
+fn count_scan(polygons: &Polygons, points: u64) -> Hits {
+    let mut hits = Hits::new(polygons.len());
+    for i in 0..points {
+        let (x, y) = point(i);
+        let mut inside = false;
+        for p in 0..polygons.len() {
+            if polygons.bounds[p].contains(x, y) && polygons.contains(p, x, y) {
+                hits.per_polygon[p] += 1;
+                inside = true;
+            }
+        }
+        hits.covered += inside as u64;
+    }
+    hits
+}
+
+// The geo crate's Polygon and Contains, indexed by an rstar R*-tree of bounding rectangles
+struct GeoIndex {
+    polygons: Vec<Polygon<f64>>,
+    tree: RTree<GeomWithData<Rectangle<[f64; 2]>, usize>>,
+}
+
+impl GeoIndex {
+    fn build(polygons: &Polygons) -> Self {
+        let rings = (0..polygons.len()).map(|p| {
+            let range = polygons.offsets[p]..polygons.offsets[p + 1];
+            let coords = polygons.xs[range.clone()].iter().zip(&polygons.ys[range]).map(|(&x, &y)| Coord { x, y });
+            Polygon::new(LineString::from_iter(coords), Vec::new())
+        });
+        let boxes = polygons.bounds.iter().enumerate().map(|(p, b)| {
+            GeomWithData::new(Rectangle::from_corners([b.min_x, b.min_y], [b.max_x, b.max_y]), p)
+        });
+        GeoIndex { polygons: rings.collect(), tree: RTree::bulk_load(boxes.collect()) }
+    }
+
+    fn count(&self, points: u64) -> Hits {
+        parallel_hits(self.polygons.len(), points, |hits, start, end| {
+            for i in start..end {
+                let (x, y) = point(i);
+                let mut inside = false;
+                for candidate in self.tree.locate_all_at_point(&[x, y]) {
+                    if self.polygons[candidate.data].contains(&Point::new(x, y)) {
+                        hits.per_polygon[candidate.data] += 1;
+                        inside = true;
+                    }
+                }
+                hits.covered += inside as u64;
+            }
+        })
+    }
+}
+
+fn parse_points() -> u64 {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--points") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|&n: &u64| n > 0)
+            .expect("--points expects a positive integer"),
+        None => DEFAULT_POINTS,
+    }
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let points = parse_points();
+    let polygons = make_polygons(&mut Rng(SEED));
+    let index = RTreeIndex::build(&polygons);
+    let geo_index = GeoIndex::build(&polygons);
+
+    // The index must find exactly what a scan of every polygon finds
+    let verify = VERIFY_POINTS.min(points);
+    if count_indexed(&polygons, &index, verify) != count_scan(&polygons, verify) {
+        eprintln!("R-tree results differ from a full scan over the first {} points", verify);
+        std::process::exit(1);
+    }
+
+    // Warm-up
+    let _ = count_indexed(&polygons, &index, WARMUP_POINTS.min(points));
+    let _ = geo_index.count(WARMUP_POINTS.min(points));
+
+    // Benchmark
+    let start = Instant::now();
+    measure.start();
+    let hits = count_indexed(&polygons, &index, points);
+    measure.stop();
+    let indexed_duration = start.elapsed();
+    let start = Instant::now();
+    let geo_hits = geo_index.count(points);
+    let geo_duration = start.elapsed();
+
+    if geo_hits != hits {
+        eprintln!("geo results differ from ray casting: {} hits, expected {}", geo_hits.total(), hits.total());
+        std::process::exit(1);
+    }
+
+    let rate = |seconds: f64| points as f64 / seconds / 1e6;
+    measure.report();
+    eprintln!("Polygons: {} star-shaped rings, {} vertices, R-tree of {} nodes, height {}", polygons.len(),
+              polygons.xs.len(), index.nodes.len(), index.height());
+    eprintln!("Points: {} in a {}x{} square, {} checked against a full scan", points, WORLD, WORLD, verify);
+    eprintln!("R-tree + ray casting: {:.6}s, {:.1}M points/s", indexed_duration.as_secs_f64(),
+              rate(indexed_duration.as_secs_f64()));
+    eprintln!("geo + rstar: {:.6}s, {:.1}M points/s ({:.2}x)", geo_duration.as_secs_f64(), rate(geo_duration.as_secs_f64()),
+              geo_duration.as_secs_f64() / indexed_duration.as_secs_f64());
+    eprintln!("Hits: {} point-polygon containments, {} points ({:.2}%) inside at least one polygon; both variants agree",
+              hits.total(), hits.covered, 100.0 * hits.covered as f64 / points as f64);
+    eprintln!("Checksum: {}", hits.checksum());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_casting_handles_concave_rings() {
+        // An L shape: the notch at the top right is outside
+        let (xs, ys) = (vec![0.0, 2.0, 2.0, 1.0, 1.0, 0.0], vec![0.0, 0.0, 1.0, 1.0, 2.0, 2.0]);
+        let n = xs.len();
+        let polygons = Polygons { xs, ys, offsets: vec![0, n], bounds: vec![Rect { min_x: 0.0, min_y: 0.0, max_x: 2.0, max_y: 2.0 }] };
+        assert!(polygons.contains(0, 0.5, 0.5));
+        assert!(polygons.contains(0, 1.5, 0.5));
+        assert!(polygons.contains(0, 0.5, 1.5));
+        assert!(!polygons.contains(0, 1.5, 1.5));
+        // A ray through the vertex at (1, 1) is counted once
+        assert!(polygons.contains(0, 0.5, 1.0));
+        assert!(!polygons.contains(0, 2.5, 0.5));
+    }
+
+    #[test]
+    fn index_and_geo_match_a_full_scan() {
+        let polygons = make_polygons(&mut Rng(SEED));
+        let index = RTreeIndex::build(&polygons);
+        let scan = count_scan(&polygons, 20_000);
+        assert_eq!(count_indexed(&polygons, &index, 20_000), scan);
+        assert_eq!(GeoIndex::build(&polygons).count(20_000), scan);
+        assert!(scan.total() > 1000);
+        // Every polygon is reachable through the tree exactly once
+        let mut items = index.items.clone();
+        items.sort_unstable();
+        assert!(items.iter().enumerate().all(|(i, &p)| p as usize == i));
+    }
+}
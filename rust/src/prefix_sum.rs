@@ -0,0 +1,152 @@
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+const DEFAULT_ELEMENTS: usize = 1_000_000_000; // 8 GB of u64, scanned in place
+const NUM_BLOCKS: usize = 64; // parallel blocks per pass
+const ROUNDS: usize = 3;
+const SAMPLE_STRIDE: usize = 4099;
+
+// Stateless per-index mix, so the input can be regenerated in parallel between rounds
+fn input_value(i: usize) -> u64 {
+    let mut x = (i as u64).wrapping_mul(0x9E3779B97F4A7C15);
+    x ^= x >> 29;
+    x & 1023
+}
+
+fn fill(data: &mut [u64]) {
+    let block = data.len().div_ceil(NUM_BLOCKS);
+    data.par_chunks_mut(block).enumerate().for_each(|(b, chunk)| {
+        for (i, x) in chunk.iter_mut().enumerate() {
+            *x = input_value(b * block + i);
+        }
+    });
+}
+
+fn scan_sequential(data: &mut [u64]) {
+    let mut acc = 0u64;
+    for x in data.iter_mut() {
+        acc = acc.wrapping_add(*x);
+        *x = acc;
+    }
+}
+
+// Reduce-then-scan: block totals in parallel, a tiny sequential exclusive scan over
+// them, then every block rescans itself from its offset in parallel
+fn scan_parallel(data: &mut [u64]) {
+    let block = data.len().div_ceil(NUM_BLOCKS);
+    let totals: Vec<u64> = data
+        .par_chunks(block)
+        .map(|chunk| chunk.iter().fold(0u64, |acc, &x| acc.wrapping_add(x)))
+        .collect();
+
+    let mut offsets = Vec::with_capacity(totals.len());
+    let mut running = 0u64;
+    for total in totals {
+        offsets.push(running);
+        running = running.wrapping_add(total);
+    }
+
+    data.par_chunks_mut(block).zip(offsets.par_iter()).for_each(|(chunk, &offset)| {
+        let mut acc = offset;
+        for x in chunk.iter_mut() {
+            acc = acc.wrapping_add(*x);
+            *x = acc;
+        }
+    });
+}
+
+// Sampled elements plus the final one (which must equal the input total)
+fn checksum(data: &[u64]) -> u64 {
+    data.iter().step_by(SAMPLE_STRIDE).fold(0u64, |acc, &x| acc.wrapping_add(x)).wrapping_add(data[data.len() - 1])
+}
+
+fn run(data: &mut [u64], scan: fn(&mut [u64])) -> (Duration, u64) {
+    let mut elapsed = Duration::ZERO;
+    let mut sum = 0;
+    for _ in 0..ROUNDS {
+        fill(data);
+        let start = Instant::now();
+        scan(data);
+        elapsed += start.elapsed();
+        sum = checksum(data);
+    }
+    (elapsed, sum)
+}
+
+fn parse_elements() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--elements") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .expect("--elements expects a positive integer"),
+        None => DEFAULT_ELEMENTS,
+    }
+}
+
+fn main() {
+    let elements = parse_elements();
+    let mut data = vec![0u64; elements];
+
+    // Warm-up
+    let warmup = &mut data[..elements.div_ceil(100)];
+    fill(warmup);
+    scan_parallel(warmup);
+
+    let expected_total = (0..elements).into_par_iter().map(input_value).sum::<u64>();
+    let (sequential_time, sequential_sum) = run(&mut data, scan_sequential);
+    let sequential_last = data[elements - 1];
+    let (parallel_time, parallel_sum) = run(&mut data, scan_parallel);
+    assert_eq!(sequential_last, expected_total, "Sequential scan total is wrong");
+    assert_eq!(data[elements - 1], expected_total, "Parallel scan total is wrong");
+    assert_eq!(parallel_sum, sequential_sum, "Parallel scan differs from sequential");
+
+    let rate = |d: Duration| (elements * ROUNDS) as f64 / d.as_secs_f64() / 1e9;
+
+    println!("{:.6}", (sequential_time + parallel_time).as_secs_f64());
+    eprintln!("Inclusive scan of {}M u64 ({:.1} GB), {} rounds", elements / 1_000_000, (elements * 8) as f64 / 1e9, ROUNDS);
+    eprintln!("Sequential: {:.3}s ({:.2} G elements/s)", sequential_time.as_secs_f64(), rate(sequential_time));
+    eprintln!("Parallel ({} blocks, two-pass): {:.3}s ({:.2} G elements/s, {:.2}x)",
+              NUM_BLOCKS, parallel_time.as_secs_f64(), rate(parallel_time),
+              sequential_time.as_secs_f64() / parallel_time.as_secs_f64());
+    eprintln!("Checksum: {} (total {})", sequential_sum, expected_total);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_known_input() {
+        let mut data = [3, 1, 4, 1, 5, 9, 2, 6];
+        scan_parallel(&mut data);
+        assert_eq!(data, [3, 4, 8, 9, 14, 23, 25, 31]);
+        let mut wrapping = [u64::MAX, 2];
+        scan_sequential(&mut wrapping);
+        assert_eq!(wrapping, [u64::MAX, 1]);
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        // Fewer elements than blocks, uneven last blocks and an exact multiple
+        for len in [1, 5, NUM_BLOCKS, 1000, NUM_BLOCKS * 4099, 1 << 20] {
+            let mut expected = vec![0u64; len];
+            fill(&mut expected);
+            assert!(expected.iter().enumerate().all(|(i, &x)| x == input_value(i) && x < 1024));
+            let mut parallel = expected.clone();
+            scan_sequential(&mut expected);
+            scan_parallel(&mut parallel);
+            assert!(parallel == expected, "len {}", len);
+            assert_eq!(checksum(&parallel), checksum(&expected));
+        }
+    }
+
+    #[test]
+    fn checksum_adds_the_total() {
+        let data: Vec<u64> = (1..=10_000).collect();
+        assert_eq!(checksum(&data), 1 + 4100 + 8199 + 10_000);
+        let mut data = vec![0u64; 10_000];
+        let (_, sum) = run(&mut data, scan_parallel);
+        assert_eq!(sum, checksum(&data));
+    }
+}
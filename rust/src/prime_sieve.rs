@@ -1,14 +1,57 @@
-use std::time::Instant;
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
 
 const LIMIT: usize = 100_000_000;
+const SEGMENT_SIZE: usize = 32 * 1024; // odd numbers per segment, one byte each: an L1-sized window
+const MIN_CHUNK_SEGMENTS: usize = 16;   // segments per parallel task, grown so there are at most MAX_CHUNKS tasks
+const MAX_CHUNKS: usize = 1024;
+const WHEEL: usize = 2 * 3 * 5 * 7;
+const SPOKES: usize = 48; // residues mod 210 coprime to it, the only candidates the wheel keeps
+const WARMUP_LIMIT: usize = 1_000_000;
+
+// π(n), the number of primes up to n, for checking runs whose limit is in the table
+const PI_REFERENCE: [(usize, usize); 11] = [
+    (10, 4),
+    (100, 25),
+    (1_000, 168),
+    (10_000, 1_229),
+    (100_000, 9_592),
+    (1_000_000, 78_498),
+    (10_000_000, 664_579),
+    (100_000_000, 5_761_455),
+    (1_000_000_000, 50_847_534),
+    (4_294_967_296, 203_280_221),
+    (10_000_000_000, 455_052_511),
+];
+
+#[derive(Clone, Copy)]
+enum Mode {
+    Bool,      // Vec<bool>, one byte per number (the original benchmark)
+    Bits,      // one bit per odd number
+    Segmented, // one byte per odd number, sieved a cache-sized window at a time
+    Parallel,  // segmented, with contiguous runs of segments spread over rayon workers
+    Wheel,     // one byte per number coprime to 2, 3, 5 and 7
+}
+
+impl Mode {
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Bool => "bool",
+            Mode::Bits => "bits",
+            Mode::Segmented => "segmented",
+            Mode::Parallel => "parallel",
+            Mode::Wheel => "wheel",
+        }
+    }
+}
 
 fn sieve_of_eratosthenes(limit: usize) -> Vec<usize> {
     let mut is_prime = vec![true; limit + 1];
     is_prime[0] = false;
     is_prime[1] = false;
-    
+
     let sqrt_limit = (limit as f64).sqrt() as usize;
-    
+
     for i in 2..=sqrt_limit {
         if is_prime[i] {
             let mut j = i * i;
@@ -18,7 +61,7 @@ fn sieve_of_eratosthenes(limit: usize) -> Vec<usize> {
             }
         }
     }
-    
+
     is_prime.iter()
         .enumerate()
         .filter(|(_, &is_p)| is_p)
@@ -26,16 +69,262 @@ fn sieve_of_eratosthenes(limit: usize) -> Vec<usize> {
         .collect()
 }
 
+// Bit k stands for 2k + 1 and is set once that number is known composite
+fn count_primes_bits(limit: usize) -> usize {
+    if limit < 2 {
+        return 0;
+    }
+    let odd_count = (limit - 1) / 2 + 1;
+    let mut composite = bench_pages::filled(0u64, odd_count.div_ceil(64));
+    composite[0] = 1; // 1 is not prime
+
+    let mut i = 3;
+    while i * i <= limit {
+        if composite[i / 2 / 64] >> (i / 2 % 64) & 1 == 0 {
+            let mut j = i * i;
+            while j <= limit {
+                composite[j / 2 / 64] |= 1 << (j / 2 % 64);
+                j += 2 * i;
+            }
+        }
+        i += 2;
+    }
+
+    let composites: usize = composite.iter().map(|w| w.count_ones() as usize).sum();
+    1 + odd_count - composites // 2, plus every odd number left unmarked
+}
+
+// Odd primes up to `limit`, for seeding the segmented sieve
+fn small_odd_primes(limit: usize) -> Vec<usize> {
+    let mut is_prime = vec![true; limit + 1];
+    let mut primes = Vec::new();
+    for i in (3..=limit).step_by(2) {
+        if is_prime[i] {
+            primes.push(i);
+            for j in (i * i..=limit).step_by(2 * i) {
+                is_prime[j] = false;
+            }
+        }
+    }
+    primes
+}
+
+// Counts primes among the odd numbers in [low, high), low odd and > 1. Each base prime
+// carries its next odd multiple from one segment to the next
+fn count_primes_range(low: usize, high: usize, base_primes: &[usize], segment: &mut [u8]) -> usize {
+    let mut next: Vec<usize> = base_primes
+        .iter()
+        .map(|&p| {
+            let start = (p * p).max(low.div_ceil(p) * p);
+            if start % 2 == 0 { start + p } else { start }
+        })
+        .collect();
+
+    let mut count = 0;
+    let mut seg_low = low;
+    while seg_low < high {
+        let seg_high = (seg_low + 2 * segment.len()).min(high);
+        let len = (seg_high - seg_low).div_ceil(2);
+        let window = &mut segment[..len];
+        window.fill(0);
+        for (&p, n) in base_primes.iter().zip(next.iter_mut()) {
+            let mut j = *n;
+            while j < seg_high {
+                window[(j - seg_low) / 2] = 1;
+                j += 2 * p;
+            }
+            *n = j;
+        }
+        count += window.iter().filter(|&&c| c == 0).count();
+        seg_low = seg_high; // an odd start plus an even width stays odd
+    }
+    count
+}
+
+fn count_primes_segmented(limit: usize) -> usize {
+    if limit < 2 {
+        return 0;
+    }
+    let base_primes = small_odd_primes(limit.isqrt());
+    let mut segment = vec![0u8; SEGMENT_SIZE];
+    1 + count_primes_range(3, limit + 1, &base_primes, &mut segment)
+}
+
+// Each task sieves its own chunk with a private window and fresh starting multiples,
+// so the only shared state is the read-only base primes
+fn count_primes_parallel(limit: usize) -> usize {
+    if limit < 2 {
+        return 0;
+    }
+    let base_primes = small_odd_primes(limit.isqrt());
+    let segment_span = 2 * SEGMENT_SIZE;
+    let chunk_segments = (limit / segment_span).div_ceil(MAX_CHUNKS).max(MIN_CHUNK_SEGMENTS);
+    let chunk_span = chunk_segments * segment_span; // even, so every chunk starts on an odd number
+    let chunks = (limit + 1 - 3).div_ceil(chunk_span);
+
+    let odd_primes: usize = (0..chunks)
+        .into_par_iter()
+        .map_init(
+            || vec![0u8; SEGMENT_SIZE],
+            |segment, c| {
+                let low = 3 + c * chunk_span;
+                let high = (low + chunk_span).min(limit + 1);
+                count_primes_range(low, high, &base_primes, segment)
+            },
+        )
+        .sum();
+    1 + odd_primes
+}
+
+// 2-3-5-7 wheel: candidate k stands for WHEEL * (k / SPOKES) + residues[k % SPOKES], 22.9% of
+// the numbers. A prime p strikes p * q for every candidate q >= p; the q of one residue class are
+// WHEEL apart, so their multiples all share a residue and sit exactly SPOKES * p candidates apart
+fn count_primes_wheel(limit: usize) -> usize {
+    if limit < 11 {
+        return [2, 3, 5, 7].iter().filter(|&&p| p <= limit).count();
+    }
+    let residues: Vec<usize> = (1..WHEEL).filter(|r| r % 2 != 0 && r % 3 != 0 && r % 5 != 0 && r % 7 != 0).collect();
+    let mut index = [usize::MAX; WHEEL];
+    for (k, &r) in residues.iter().enumerate() {
+        index[r] = k;
+    }
+    let value = |k: usize| WHEEL * (k / SPOKES) + residues[k % SPOKES];
+    let position = |n: usize| SPOKES * (n / WHEEL) + index[n % WHEEL];
+
+    let candidates = SPOKES * (limit / WHEEL) + residues.iter().filter(|&&r| r <= limit % WHEEL).count();
+    let mut composite = bench_pages::filled(0u8, candidates);
+    composite[0] = 1; // 1 is not prime
+
+    let mut k = 1;
+    while value(k) * value(k) <= limit {
+        if composite[k] == 0 {
+            let p = value(k);
+            for q in (k..k + SPOKES).map(value) {
+                if p * q > limit {
+                    break;
+                }
+                let mut j = position(p * q);
+                while j < candidates {
+                    composite[j] = 1;
+                    j += SPOKES * p;
+                }
+            }
+        }
+        k += 1;
+    }
+
+    4 + composite.iter().filter(|&&c| c == 0).count() // 2, 3, 5 and 7, plus every candidate left unmarked
+}
+
+fn count_primes(mode: Mode, limit: usize) -> usize {
+    match mode {
+        Mode::Bool => sieve_of_eratosthenes(limit).len(),
+        Mode::Bits => count_primes_bits(limit),
+        Mode::Segmented => count_primes_segmented(limit),
+        Mode::Parallel => count_primes_parallel(limit),
+        Mode::Wheel => count_primes_wheel(limit),
+    }
+}
+
+fn parse_mode() -> Mode {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--mode") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("bool") => Mode::Bool,
+            Some("bits") => Mode::Bits,
+            Some("segmented") => Mode::Segmented,
+            Some("parallel") => Mode::Parallel,
+            Some("wheel") => Mode::Wheel,
+            _ => panic!("--mode expects bool, bits, segmented, parallel or wheel"),
+        },
+        None => Mode::Bool,
+    }
+}
+
+fn parse_limit() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--limit") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .expect("--limit expects a positive integer"),
+        None => LIMIT,
+    }
+}
+
+fn reference_count(limit: usize) -> Option<usize> {
+    PI_REFERENCE.iter().find(|&&(n, _)| n == limit).map(|&(_, count)| count)
+}
+
+fn time_count(mode: Mode, limit: usize) -> (Duration, usize) {
+    let start = Instant::now();
+    let count = count_primes(mode, limit);
+    (start.elapsed(), count)
+}
+
 fn main() {
-    // Warm-up with smaller limit
-    let _ = sieve_of_eratosthenes(1_000_000);
-    
+    let args: Vec<String> = std::env::args().collect();
+    bench_pages::enable(&args);
+    let mode = parse_mode();
+    let limit = parse_limit();
+
+    // Warm-up with smaller limit, which doubles as a check of the mode against π(1e6)
+    let warmup = count_primes(mode, WARMUP_LIMIT);
+    assert_eq!(warmup, reference_count(WARMUP_LIMIT).unwrap(), "{} sieve miscounts primes up to {}", mode.name(), WARMUP_LIMIT);
+
     // Benchmark
-    let start = Instant::now();
-    let primes = sieve_of_eratosthenes(LIMIT);
-    let duration = start.elapsed();
-    
+    let (duration, count) = time_count(mode, limit);
+
     println!("{:.6}", duration.as_secs_f64());
-    eprintln!("Number of primes: {}", primes.len());
+    eprintln!("Sieve: {}, limit: {}", mode.name(), limit);
+    if let Mode::Parallel = mode {
+        // The serial segmented sieve does identical work on one thread, so it is the baseline
+        let (serial, serial_count) = time_count(Mode::Segmented, limit);
+        assert_eq!(serial_count, count, "Parallel sieve differs from serial");
+        eprintln!("Serial (segmented): {:.3}s, parallel ({} threads): {:.3}s ({:.2}x)",
+                  serial.as_secs_f64(), rayon::current_num_threads(), duration.as_secs_f64(),
+                  serial.as_secs_f64() / duration.as_secs_f64());
+    }
+    match reference_count(limit) {
+        Some(expected) => {
+            assert_eq!(count, expected, "{} sieve counts {} primes up to {}, π({}) = {}", mode.name(), count, limit, limit, expected);
+            eprintln!("π({}) check: pass", limit);
+        }
+        None => eprintln!("π({}) check: no reference value (limits 10^1 to 10^10 and 2^32 have one)", limit),
+    }
+    eprintln!("{}", bench_pages::report());
+    eprintln!("Number of primes: {}", count);
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [Mode; 5] = [Mode::Bool, Mode::Bits, Mode::Segmented, Mode::Parallel, Mode::Wheel];
+
+    #[test]
+    fn lists_the_primes_below_thirty() {
+        assert_eq!(sieve_of_eratosthenes(30), [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(sieve_of_eratosthenes(1), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn every_mode_matches_pi_up_to_ten_million() {
+        for &(limit, expected) in PI_REFERENCE.iter().take_while(|&&(n, _)| n <= 10_000_000) {
+            for mode in MODES {
+                assert_eq!(count_primes(mode, limit), expected, "{} sieve, limit {}", mode.name(), limit);
+            }
+        }
+    }
+
+    // Small limits land next to the wheel's, segments' and words' edges
+    #[test]
+    fn every_mode_agrees_on_small_limits() {
+        for limit in (1..500).chain([WHEEL - 1, WHEEL, WHEEL + 1, 2 * SEGMENT_SIZE + 1, 2 * SEGMENT_SIZE + 3]) {
+            let expected = sieve_of_eratosthenes(limit).len();
+            for mode in MODES {
+                assert_eq!(count_primes(mode, limit), expected, "{} sieve, limit {}", mode.name(), limit);
+            }
+        }
+    }
+}
@@ -0,0 +1,442 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const BULK_KEYS: usize = 2_000_000;
+const HOLD_SIZE: usize = 100_000;
+const HOLD_OPS: usize = 10_000_000;
+const MAX_INCREMENT: u64 = 1 << 20;
+const GRAPH_NODES: usize = 1_000_000;
+const GRAPH_DEGREE: usize = 8;
+const MAX_WEIGHT: u64 = 1000;
+const NONE: u32 = u32::MAX;
+
+fn splitmix64(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Order-sensitive, so a heap popping out of order fails the checksum
+fn mix(acc: u64, key: u64) -> u64 {
+    acc.wrapping_mul(0x100000001b3).wrapping_add(key)
+}
+
+// Min-heaps over u64 keys. `u32` items ride along for Dijkstra, which also needs a way to lower
+// a queued item's key
+trait MinQueue {
+    fn push(&mut self, key: u64, item: u32);
+    fn pop(&mut self) -> Option<(u64, u32)>;
+    // Queues `item` at `key`, lowering its key if it is already queued
+    fn decrease_key(&mut self, item: u32, key: u64);
+}
+
+// std's binary max-heap behind Reverse. It has no decrease-key: a lowered key is pushed again
+// and the stale entry skipped when it surfaces (lazy deletion)
+#[derive(Default)]
+struct StdHeap(BinaryHeap<Reverse<(u64, u32)>>);
+
+impl MinQueue for StdHeap {
+    fn push(&mut self, key: u64, item: u32) {
+        self.0.push(Reverse((key, item)));
+    }
+
+    fn pop(&mut self) -> Option<(u64, u32)> {
+        self.0.pop().map(|Reverse(entry)| entry)
+    }
+
+    fn decrease_key(&mut self, item: u32, key: u64) {
+        self.push(key, item);
+    }
+}
+
+// Implicit 4-ary heap: half the depth of a binary heap, and the four children share a cache
+// line. `position` maps items to their slots for decrease-key; it is only kept up to date for
+// items below its length, so the bulk and hold phases (items 0) leave it empty
+#[derive(Default)]
+struct QuadHeap {
+    entries: Vec<(u64, u32)>,
+    position: Vec<u32>,
+}
+
+impl QuadHeap {
+    fn with_items(items: usize) -> Self {
+        QuadHeap { entries: Vec::new(), position: vec![NONE; items] }
+    }
+
+    fn place(&mut self, slot: usize, entry: (u64, u32)) {
+        self.entries[slot] = entry;
+        if let Some(p) = self.position.get_mut(entry.1 as usize) {
+            *p = slot as u32;
+        }
+    }
+
+    fn sift_up(&mut self, mut slot: usize, entry: (u64, u32)) {
+        while slot > 0 {
+            let parent = (slot - 1) / 4;
+            if self.entries[parent].0 <= entry.0 {
+                break;
+            }
+            self.place(slot, self.entries[parent]);
+            slot = parent;
+        }
+        self.place(slot, entry);
+    }
+
+    fn sift_down(&mut self, mut slot: usize, entry: (u64, u32)) {
+        let len = self.entries.len();
+        loop {
+            let first = slot * 4 + 1;
+            if first >= len {
+                break;
+            }
+            let mut best = first;
+            for child in first + 1..(first + 4).min(len) {
+                if self.entries[child].0 < self.entries[best].0 {
+                    best = child;
+                }
+            }
+            if self.entries[best].0 >= entry.0 {
+                break;
+            }
+            self.place(slot, self.entries[best]);
+            slot = best;
+        }
+        self.place(slot, entry);
+    }
+}
+
+impl MinQueue for QuadHeap {
+    fn push(&mut self, key: u64, item: u32) {
+        self.entries.push((key, item));
+        self.sift_up(self.entries.len() - 1, (key, item));
+    }
+
+    fn pop(&mut self) -> Option<(u64, u32)> {
+        let last = self.entries.pop()?;
+        if self.entries.is_empty() {
+            return Some(last);
+        }
+        let top = self.entries[0];
+        self.sift_down(0, last);
+        if let Some(p) = self.position.get_mut(top.1 as usize) {
+            *p = NONE;
+        }
+        Some(top)
+    }
+
+    fn decrease_key(&mut self, item: u32, key: u64) {
+        match self.position[item as usize] {
+            NONE => self.push(key, item),
+            slot => self.sift_up(slot as usize, (key, item)),
+        }
+    }
+}
+
+// Pairing heap with its nodes in one arena, linked by index, and freed nodes recycled. Each
+// node keeps its first child, next sibling and previous node (parent for a first child), so
+// decrease-key can cut a subtree out in O(1)
+struct PairingNode {
+    key: u64,
+    item: u32,
+    child: u32,
+    sibling: u32,
+    prev: u32,
+}
+
+#[derive(Default)]
+struct PairingHeap {
+    nodes: Vec<PairingNode>,
+    free: Vec<u32>,
+    root: u32,
+    handle: Vec<u32>, // node of each queued item, for decrease-key, as QuadHeap::position
+    pairs: Vec<u32>,  // scratch for the two-pass merge
+}
+
+impl PairingHeap {
+    fn with_items(items: usize) -> Self {
+        PairingHeap { root: NONE, handle: vec![NONE; items], ..Default::default() }
+    }
+
+    // Melds two roots, the larger becoming the first child of the smaller
+    fn meld(&mut self, a: u32, b: u32) -> u32 {
+        if a == NONE {
+            return b;
+        }
+        if b == NONE {
+            return a;
+        }
+        let (parent, child) = if self.nodes[b as usize].key < self.nodes[a as usize].key { (b, a) } else { (a, b) };
+        let first = self.nodes[parent as usize].child;
+        self.nodes[child as usize].sibling = first;
+        self.nodes[child as usize].prev = parent;
+        if first != NONE {
+            self.nodes[first as usize].prev = child;
+        }
+        self.nodes[parent as usize].child = child;
+        parent
+    }
+
+    // The standard two-pass merge: meld siblings pairwise left to right, then the pairs right
+    // to left
+    fn merge_children(&mut self, first: u32) -> u32 {
+        let mut pairs = std::mem::take(&mut self.pairs);
+        let mut next = first;
+        while next != NONE {
+            let a = next;
+            let b = self.nodes[a as usize].sibling;
+            next = if b == NONE { NONE } else { self.nodes[b as usize].sibling };
+            for n in [a, b] {
+                if n != NONE {
+                    self.nodes[n as usize].sibling = NONE;
+                    self.nodes[n as usize].prev = NONE;
+                }
+            }
+            pairs.push(self.meld(a, b));
+        }
+        let mut root = NONE;
+        while let Some(pair) = pairs.pop() {
+            root = self.meld(pair, root);
+        }
+        self.pairs = pairs;
+        root
+    }
+}
+
+impl MinQueue for PairingHeap {
+    fn push(&mut self, key: u64, item: u32) {
+        let node = PairingNode { key, item, child: NONE, sibling: NONE, prev: NONE };
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.nodes[index as usize] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                (self.nodes.len() - 1) as u32
+            }
+        };
+        if let Some(h) = self.handle.get_mut(item as usize) {
+            *h = index;
+        }
+        self.root = self.meld(self.root, index);
+    }
+
+    fn pop(&mut self) -> Option<(u64, u32)> {
+        if self.root == NONE {
+            return None;
+        }
+        let top = self.root;
+        let (key, item, child) = {
+            let node = &self.nodes[top as usize];
+            (node.key, node.item, node.child)
+        };
+        self.root = self.merge_children(child);
+        self.free.push(top);
+        if let Some(h) = self.handle.get_mut(item as usize) {
+            *h = NONE;
+        }
+        Some((key, item))
+    }
+
+    fn decrease_key(&mut self, item: u32, key: u64) {
+        let node = self.handle[item as usize];
+        if node == NONE {
+            return self.push(key, item);
+        }
+        self.nodes[node as usize].key = key;
+        if node == self.root {
+            return;
+        }
+        // Cut the subtree out of its sibling list and meld it back in at the root
+        let (prev, sibling) = (self.nodes[node as usize].prev, self.nodes[node as usize].sibling);
+        if self.nodes[prev as usize].child == node {
+            self.nodes[prev as usize].child = sibling;
+        } else {
+            self.nodes[prev as usize].sibling = sibling;
+        }
+        if sibling != NONE {
+            self.nodes[sibling as usize].prev = prev;
+        }
+        self.nodes[node as usize].sibling = NONE;
+        self.nodes[node as usize].prev = NONE;
+        self.root = self.meld(self.root, node);
+    }
+}
+
+// Push every key, then pop them all: a heap sort
+fn bulk<Q: MinQueue>(queue: &mut Q, keys: &[u64]) -> u64 {
+    for &key in keys {
+        queue.push(key, 0);
+    }
+    let mut acc = 0;
+    while let Some((key, _)) = queue.pop() {
+        acc = mix(acc, key);
+    }
+    acc
+}
+
+// The classic hold model of discrete-event simulation: the queue stays at its size while the
+// earliest event is popped and a later one pushed in its place
+fn hold<Q: MinQueue>(queue: &mut Q, keys: &[u64], increments: &[u64]) -> u64 {
+    for &key in keys {
+        queue.push(key, 0);
+    }
+    let mut acc = 0;
+    for &increment in increments {
+        let (key, _) = queue.pop().unwrap();
+        acc = mix(acc, key);
+        queue.push(key + increment, 0);
+    }
+    while queue.pop().is_some() {}
+    acc
+}
+
+// Random directed graph in CSR form: GRAPH_DEGREE edges out of each node
+struct Graph {
+    offsets: Vec<u32>,
+    targets: Vec<u32>,
+    weights: Vec<u32>,
+}
+
+fn generate_graph(nodes: usize, degree: usize) -> Graph {
+    let mut seed = 1959; // Dijkstra's paper
+    let offsets = (0..=nodes).map(|n| (n * degree) as u32).collect();
+    let targets = (0..nodes * degree).map(|_| (splitmix64(&mut seed) % nodes as u64) as u32).collect();
+    let weights = (0..nodes * degree).map(|_| (1 + splitmix64(&mut seed) % MAX_WEIGHT) as u32).collect();
+    Graph { offsets, targets, weights }
+}
+
+// Single-source shortest paths from node 0: the sum of the distances to the reachable nodes
+fn dijkstra<Q: MinQueue>(queue: &mut Q, graph: &Graph) -> u64 {
+    let mut dist = vec![u64::MAX; graph.offsets.len() - 1];
+    dist[0] = 0;
+    queue.decrease_key(0, 0);
+    while let Some((d, u)) = queue.pop() {
+        if d > dist[u as usize] {
+            continue; // a stale duplicate left by lazy deletion
+        }
+        let edges = graph.offsets[u as usize] as usize..graph.offsets[u as usize + 1] as usize;
+        for (&v, &w) in graph.targets[edges.clone()].iter().zip(&graph.weights[edges]) {
+            let candidate = d + w as u64;
+            if candidate < dist[v as usize] {
+                dist[v as usize] = candidate;
+                queue.decrease_key(v, candidate);
+            }
+        }
+    }
+    dist.iter().filter(|&&d| d != u64::MAX).fold(0u64, |acc, &d| acc.wrapping_add(d))
+}
+
+struct Workloads {
+    bulk_keys: Vec<u64>,
+    hold_keys: Vec<u64>,
+    increments: Vec<u64>,
+    graph: Graph,
+}
+
+fn generate(bulk_keys: usize, hold_size: usize, hold_ops: usize, nodes: usize) -> Workloads {
+    let mut seed = 42;
+    Workloads {
+        bulk_keys: (0..bulk_keys).map(|_| splitmix64(&mut seed) >> 1).collect(),
+        hold_keys: (0..hold_size).map(|_| splitmix64(&mut seed) % (MAX_INCREMENT * 4)).collect(),
+        increments: (0..hold_ops).map(|_| splitmix64(&mut seed) % MAX_INCREMENT).collect(),
+        graph: generate_graph(nodes, GRAPH_DEGREE),
+    }
+}
+
+struct Run {
+    durations: [Duration; 3], // bulk, hold, dijkstra
+    results: [u64; 3],
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let result = f();
+    (start.elapsed(), result)
+}
+
+// A fresh queue per phase, built by `new` for the given number of Dijkstra items
+fn run<Q: MinQueue>(new: impl Fn(usize) -> Q, w: &Workloads) -> Run {
+    let nodes = w.graph.offsets.len() - 1;
+    let (bulk_time, bulk_result) = timed(|| bulk(&mut new(0), black_box(&w.bulk_keys)));
+    let (hold_time, hold_result) = timed(|| hold(&mut new(0), black_box(&w.hold_keys), &w.increments));
+    let (dijkstra_time, dijkstra_result) = timed(|| dijkstra(&mut new(nodes), black_box(&w.graph)));
+    Run { durations: [bulk_time, hold_time, dijkstra_time], results: [bulk_result, hold_result, dijkstra_result] }
+}
+
+const HEAPS: [&str; 3] = ["BinaryHeap", "4-ary", "pairing"];
+
+fn run_all(w: &Workloads) -> [Run; 3] {
+    [
+        run(|_| StdHeap::default(), w),
+        run(QuadHeap::with_items, w),
+        run(PairingHeap::with_items, w),
+    ]
+}
+
+fn main() {
+    let workloads = generate(BULK_KEYS, HOLD_SIZE, HOLD_OPS, GRAPH_NODES);
+
+    // Warm-up
+    let _ = run_all(&generate(BULK_KEYS / 100, HOLD_SIZE / 100, HOLD_OPS / 100, GRAPH_NODES / 100));
+
+    // Benchmark
+    let runs = run_all(&workloads);
+    let total_duration: Duration = runs.iter().flat_map(|r| r.durations).sum();
+    let all_match = runs.iter().all(|r| r.results == runs[0].results);
+    let checksum = runs[0].results.iter().fold(0u64, |acc, &r| acc.wrapping_add(r));
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Bulk: {} keys; hold: {} ops at size {}; Dijkstra: {} nodes, {} edges", BULK_KEYS, HOLD_OPS, HOLD_SIZE,
+              GRAPH_NODES, GRAPH_NODES * GRAPH_DEGREE);
+    for (name, r) in HEAPS.iter().zip(&runs) {
+        let [bulk, hold, dijkstra] = r.durations.map(|d| d.as_secs_f64());
+        eprintln!("  {:<11} bulk {:.3}s ({:.1} M/s)  hold {:.3}s ({:.1} M/s)  dijkstra {:.3}s", name, bulk,
+                  BULK_KEYS as f64 / bulk / 1e6, hold, HOLD_OPS as f64 / hold / 1e6, dijkstra);
+    }
+    eprintln!("Checksum: {} (all heaps match: {})", checksum, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain<Q: MinQueue>(mut queue: Q) -> Vec<(u64, u32)> {
+        std::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    // Items 0-7 at keys 100-107, then three decrease-keys. The pop and re-push first leaves the
+    // pairing heap's nodes below the root's children, so the cuts are not all at the top
+    fn decrease_keys<Q: MinQueue>(mut queue: Q) -> Vec<u32> {
+        (0..8).for_each(|i| queue.push(100 + i as u64, i));
+        let _ = queue.pop();
+        queue.push(100, 0);
+        queue.decrease_key(5, 1);
+        queue.decrease_key(7, 50);
+        queue.decrease_key(3, 99);
+        drain(queue).into_iter().map(|(_, item)| item).collect()
+    }
+
+    #[test]
+    fn decrease_key_reorders() {
+        assert_eq!(decrease_keys(QuadHeap::with_items(8)), [5, 7, 3, 0, 1, 2, 4, 6]);
+        assert_eq!(decrease_keys(PairingHeap::with_items(8)), [5, 7, 3, 0, 1, 2, 4, 6]);
+    }
+
+    #[test]
+    fn heaps_agree() {
+        let w = generate(10_000, 1_000, 20_000, 5_000);
+        let runs = run_all(&w);
+        let mut sorted = w.bulk_keys.clone();
+        sorted.sort_unstable();
+        assert_eq!(runs[0].results[0], sorted.iter().fold(0, |acc, &k| mix(acc, k)));
+        for r in &runs {
+            assert_eq!(r.results, runs[0].results);
+        }
+        assert!(runs[0].results[2] > 0);
+    }
+}
@@ -0,0 +1,212 @@
+use std::ffi::{c_char, CString};
+use std::hint::black_box;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command};
+use std::ptr;
+use std::time::{Duration, Instant};
+
+const SPAWNS: usize = 1_000; // per spawner and wave size
+const WAVES: [usize; 2] = [1, 16]; // children started before the first is reaped
+const PROGRAM: &str = "/usr/bin/true";
+// A build tool's heap: fork has to copy its page tables, posix_spawn does not
+const BALLAST_BYTES: usize = 64 << 20;
+
+extern "C" {
+    static environ: *const *mut c_char;
+}
+
+trait Spawner {
+    type Child;
+    fn new() -> Self;
+    fn spawn(&self) -> Self::Child;
+    fn reap(&self, child: Self::Child) -> bool; // exited with status 0
+}
+
+// std picks posix_spawn when nothing it was asked for needs code to run in the child
+struct StdCommand;
+
+impl Spawner for StdCommand {
+    type Child = Child;
+
+    fn new() -> Self {
+        StdCommand
+    }
+
+    fn spawn(&self) -> Child {
+        Command::new(PROGRAM).spawn().unwrap()
+    }
+
+    fn reap(&self, mut child: Child) -> bool {
+        child.wait().unwrap().success()
+    }
+}
+
+// A pre_exec hook, even an empty one, forces std's fork + exec path
+struct StdForkExec;
+
+impl Spawner for StdForkExec {
+    type Child = Child;
+
+    fn new() -> Self {
+        StdForkExec
+    }
+
+    fn spawn(&self) -> Child {
+        let mut command = Command::new(PROGRAM);
+        // SAFETY: the hook does nothing, so it cannot break anything between fork and exec
+        unsafe { command.pre_exec(|| Ok(())) };
+        command.spawn().unwrap()
+    }
+
+    fn reap(&self, mut child: Child) -> bool {
+        child.wait().unwrap().success()
+    }
+}
+
+// The raw calls, with the path and argv built once and the parent's environment passed on
+struct Argv {
+    path: CString,
+    argv: [*mut c_char; 2],
+}
+
+impl Argv {
+    fn new() -> Self {
+        let path = CString::new(PROGRAM).unwrap();
+        let argv = [path.as_ptr() as *mut c_char, ptr::null_mut()];
+        Argv { path, argv }
+    }
+}
+
+fn wait_pid(pid: libc::pid_t) -> bool {
+    let mut status = 0;
+    // SAFETY: pid is a child of this process that has not been reaped yet
+    let reaped = unsafe { libc::waitpid(pid, &mut status, 0) };
+    reaped == pid && libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0
+}
+
+struct PosixSpawn(Argv);
+
+impl Spawner for PosixSpawn {
+    type Child = libc::pid_t;
+
+    fn new() -> Self {
+        PosixSpawn(Argv::new())
+    }
+
+    fn spawn(&self) -> libc::pid_t {
+        let mut pid = 0;
+        // SAFETY: path and argv are NUL-terminated and outlive the call; null file actions and
+        // attributes mean the defaults
+        let rc = unsafe {
+            libc::posix_spawn(&mut pid, self.0.path.as_ptr(), ptr::null(), ptr::null(), self.0.argv.as_ptr(), environ)
+        };
+        assert_eq!(rc, 0, "posix_spawn failed");
+        pid
+    }
+
+    fn reap(&self, pid: libc::pid_t) -> bool {
+        wait_pid(pid)
+    }
+}
+
+struct ForkExec(Argv);
+
+impl Spawner for ForkExec {
+    type Child = libc::pid_t;
+
+    fn new() -> Self {
+        ForkExec(Argv::new())
+    }
+
+    fn spawn(&self) -> libc::pid_t {
+        // SAFETY: the child only calls execve and _exit, both async-signal-safe, so other threads
+        // holding locks at the fork cannot deadlock it
+        match unsafe { libc::fork() } {
+            0 => unsafe {
+                libc::execve(self.0.path.as_ptr(), self.0.argv.as_ptr().cast(), environ.cast());
+                libc::_exit(127)
+            },
+            -1 => panic!("fork failed"),
+            pid => pid,
+        }
+    }
+
+    fn reap(&self, pid: libc::pid_t) -> bool {
+        wait_pid(pid)
+    }
+}
+
+struct Run {
+    elapsed: Duration,
+    succeeded: usize,
+}
+
+// Start a wave of children, reap them all, repeat
+fn run<S: Spawner>(spawns: usize, wave: usize) -> Run {
+    let spawner = S::new();
+    let mut children = Vec::with_capacity(wave);
+    let mut succeeded = 0;
+    let start = Instant::now();
+    for first in (0..spawns).step_by(wave) {
+        children.extend((first..spawns.min(first + wave)).map(|_| spawner.spawn()));
+        succeeded += children.drain(..).map(|c| spawner.reap(c)).filter(|&ok| ok).count();
+    }
+    Run { elapsed: start.elapsed(), succeeded }
+}
+
+type Variant = (&'static str, fn(usize, usize) -> Run);
+
+const VARIANTS: [Variant; 4] = [
+    ("Command", run::<StdCommand>),
+    ("Command + pre_exec", run::<StdForkExec>),
+    ("posix_spawn", run::<PosixSpawn>),
+    ("fork + execve", run::<ForkExec>),
+];
+
+fn main() {
+    let ballast = vec![1u8; BALLAST_BYTES];
+    black_box(&ballast);
+
+    // Warm-up
+    for (_, f) in VARIANTS {
+        let _ = f(SPAWNS / 100, WAVES[0]);
+    }
+
+    // Benchmark: runs[variant][wave]
+    let runs: Vec<Vec<Run>> =
+        VARIANTS.iter().map(|(_, f)| WAVES.iter().map(|&wave| f(SPAWNS, wave)).collect()).collect();
+    let total_duration: Duration = runs.iter().flatten().map(|r| r.elapsed).sum();
+    let all_match = runs.iter().flatten().all(|r| r.succeeded == SPAWNS);
+    let checksum = runs[0].iter().map(|r| r.succeeded as u64).sum::<u64>();
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("{} runs of {} per spawner and wave size, with a {} MiB parent heap; us per process", SPAWNS, PROGRAM,
+              BALLAST_BYTES >> 20);
+    for ((name, _), by_wave) in VARIANTS.iter().zip(&runs) {
+        let cells: Vec<String> = WAVES
+            .iter()
+            .zip(by_wave)
+            .map(|(wave, r)| format!("wave {:>2} {:7.1}", wave, r.elapsed.as_secs_f64() * 1e6 / SPAWNS as f64))
+            .collect();
+        eprintln!("  {:<20} {}", name, cells.join("  "));
+    }
+    eprintln!("Checksum: {} (all children exited cleanly: {})", checksum, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_spawner_reaps_its_children() {
+        for (name, f) in VARIANTS {
+            assert_eq!(f(6, 1).succeeded, 6, "{}", name);
+        }
+    }
+
+    #[test]
+    fn a_partial_last_wave_is_spawned() {
+        assert_eq!(run::<PosixSpawn>(7, 3).succeeded, 7);
+        assert_eq!(run::<StdCommand>(5, 16).succeeded, 5);
+    }
+}
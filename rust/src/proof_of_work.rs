@@ -0,0 +1,123 @@
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+
+const NUM_NONCES: u32 = 1 << 27;
+const CHUNK_NONCES: u32 = 1 << 16;
+const ZERO_BITS: u32 = 20; // Difficulty: a hash meets the target if its top 20 bits are zero
+
+// Bitcoin-style 80-byte header: version, previous block hash, merkle root, time, bits, nonce
+fn build_header() -> [u8; 80] {
+    let mut seed = 21_000_000u64;
+    let mut header = [0u8; 80];
+    header[0..4].copy_from_slice(&0x2000_0000u32.to_le_bytes());
+    for byte in &mut header[4..68] {
+        *byte = next_random(&mut seed) as u8;
+    }
+    header[68..72].copy_from_slice(&1_700_000_000u32.to_le_bytes());
+    header[72..76].copy_from_slice(&0x1d00_ffffu32.to_le_bytes());
+    header
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Top 64 bits of the hash read as a 256-bit little-endian number, as Bitcoin compares against the target
+fn hash_key(hash: &[u8]) -> u64 {
+    u64::from_le_bytes(hash[24..32].try_into().unwrap())
+}
+
+// Hashes every nonce in [start, end); the first 64 header bytes are absorbed once as a midstate
+fn search(header: &[u8; 80], start: u32, end: u32) -> (u64, (u64, u32)) {
+    let mut midstate = Sha256::new();
+    midstate.update(&header[..64]);
+    let mut tail = [0u8; 16];
+    tail[..12].copy_from_slice(&header[64..76]);
+
+    let mut found = 0u64;
+    let mut best = (u64::MAX, 0u32);
+    for nonce in start..end {
+        tail[12..].copy_from_slice(&nonce.to_le_bytes());
+        let mut hasher = midstate.clone();
+        hasher.update(tail);
+        let first = hasher.finalize();
+        let key = hash_key(&Sha256::digest(first));
+        if key >> (64 - ZERO_BITS) == 0 {
+            found += 1;
+        }
+        best = best.min((key, nonce));
+    }
+    (found, best)
+}
+
+fn parallel_search(header: &[u8; 80], nonces: u32) -> (u64, (u64, u32)) {
+    (0..nonces.div_ceil(CHUNK_NONCES))
+        .into_par_iter()
+        .map(|c| search(header, c * CHUNK_NONCES, ((c + 1) * CHUNK_NONCES).min(nonces)))
+        .reduce(|| (0, (u64::MAX, 0)), |a, b| (a.0 + b.0, a.1.min(b.1)))
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let header = build_header();
+
+    // Warm-up
+    let _ = parallel_search(&header, NUM_NONCES / 64);
+
+    // Benchmark
+    let start = Instant::now();
+    measure.start();
+    let (found, (_, best_nonce)) = parallel_search(&header, NUM_NONCES);
+    measure.stop();
+    let duration = start.elapsed();
+
+    // Checksum: rehash the best nonce and print it in Bitcoin's byte-reversed display order
+    let mut block = header;
+    block[76..].copy_from_slice(&best_nonce.to_le_bytes());
+    let best_hash = Sha256::digest(Sha256::digest(block));
+    let display: String = best_hash.iter().rev().map(|b| format!("{:02x}", b)).collect();
+
+    measure.report();
+    eprintln!("{:.2} MH/s (double SHA-256), {} of {} nonces below the {}-bit target",
+              NUM_NONCES as f64 / duration.as_secs_f64() / 1e6, found, NUM_NONCES, ZERO_BITS);
+    eprintln!("Checksum: nonce {} -> {}", best_nonce, display);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Double SHA-256 of the whole 80-byte header, without the midstate
+    fn key_of(header: &[u8; 80], nonce: u32) -> u64 {
+        let mut full = *header;
+        full[76..].copy_from_slice(&nonce.to_le_bytes());
+        hash_key(&Sha256::digest(Sha256::digest(full)))
+    }
+
+    #[test]
+    fn midstate_search_matches_full_hashes() {
+        let header = build_header();
+        assert_eq!(&header[72..76], &0x1d00_ffffu32.to_le_bytes());
+        assert_eq!(&header[76..], &[0; 4]);
+        let best = (1000..1100).map(|n| (key_of(&header, n), n)).min().unwrap();
+        assert_eq!(search(&header, 1000, 1100), (0, best));
+        assert_eq!(search(&header, 5, 5), (0, (u64::MAX, 0)));
+    }
+
+    #[test]
+    fn parallel_search_matches_serial() {
+        let header = build_header();
+        let nonces = 3 * CHUNK_NONCES + 1234;
+        let serial = search(&header, 0, nonces);
+        assert_eq!(parallel_search(&header, nonces), serial);
+        // A 20-bit target is hit about once per million nonces
+        let (found, (key, nonce)) = parallel_search(&header, 1 << 22);
+        assert!((1..=12).contains(&found), "{}", found);
+        assert_eq!(key, key_of(&header, nonce));
+        assert_eq!(key >> (64 - ZERO_BITS), 0);
+    }
+}
@@ -0,0 +1,260 @@
+use std::time::{Duration, Instant};
+
+const WIDTH: u32 = 4096;  // 64 MB of RGBA8
+const HEIGHT: u32 = 4096;
+const CHANNELS: usize = 4;
+const ROUNDS: usize = 5;
+
+const QOI_OP_INDEX: u8 = 0x00; // 00xxxxxx
+const QOI_OP_DIFF: u8 = 0x40;  // 01xxxxxx
+const QOI_OP_LUMA: u8 = 0x80;  // 10xxxxxx
+const QOI_OP_RUN: u8 = 0xc0;   // 11xxxxxx
+const QOI_OP_RGB: u8 = 0xfe;
+const QOI_OP_RGBA: u8 = 0xff;
+const QOI_MASK_2: u8 = 0xc0;
+const QOI_MAGIC: &[u8; 4] = b"qoif";
+const QOI_HEADER_SIZE: usize = 14;
+const QOI_PADDING: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+type Pixel = [u8; 4];
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// 256x256 tiles cycling through flat fills, gradients, a small palette and noise,
+// so every QOI op shows up in realistic proportions
+fn generate_image() -> Vec<u8> {
+    let (width, height) = (WIDTH as usize, HEIGHT as usize);
+    let mut seed = 42u64;
+    let palette: Vec<Pixel> = (0..16)
+        .map(|_| {
+            let c = next_random(&mut seed);
+            [c as u8, (c >> 8) as u8, (c >> 16) as u8, 255]
+        })
+        .collect();
+    let mut pixels = Vec::with_capacity(width * height * CHANNELS);
+    for y in 0..height {
+        for x in 0..width {
+            let noise = next_random(&mut seed);
+            let tile = x / 256 + y / 256;
+            let pixel: Pixel = match tile % 4 {
+                0 => [(tile * 37) as u8, (tile * 91) as u8, (tile * 53) as u8, 255],
+                1 => [(x / 2) as u8, (y / 3) as u8, ((x + y) / 4) as u8, 255],
+                2 => palette[(noise % 16) as usize],
+                _ => [(x as u64 + (noise & 63)) as u8, (y as u64 + ((noise >> 6) & 15)) as u8, (noise >> 10) as u8, 255],
+            };
+            let alpha = if (x / 128) % 9 == 4 { (y * 255 / height) as u8 } else { pixel[3] };
+            pixels.extend_from_slice(&[pixel[0], pixel[1], pixel[2], alpha]);
+        }
+    }
+    pixels
+}
+
+fn color_hash(p: Pixel) -> usize {
+    (p[0] as usize * 3 + p[1] as usize * 5 + p[2] as usize * 7 + p[3] as usize * 11) % 64
+}
+
+// Straight from the QOI specification, one op per pixel or run
+fn encode(pixels: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let pixel_count = width as usize * height as usize;
+    let mut out = Vec::with_capacity(QOI_HEADER_SIZE + pixel_count * (CHANNELS + 1) + QOI_PADDING.len());
+    out.extend_from_slice(QOI_MAGIC);
+    out.extend_from_slice(&width.to_be_bytes());
+    out.extend_from_slice(&height.to_be_bytes());
+    out.push(CHANNELS as u8);
+    out.push(0); // sRGB with linear alpha
+
+    let mut index = [[0u8; 4]; 64];
+    let mut prev: Pixel = [0, 0, 0, 255];
+    let mut run = 0u8;
+    for (i, chunk) in pixels.chunks_exact(CHANNELS).enumerate() {
+        let px: Pixel = [chunk[0], chunk[1], chunk[2], chunk[3]];
+        if px == prev {
+            run += 1;
+            if run == 62 || i == pixel_count - 1 {
+                out.push(QOI_OP_RUN | (run - 1));
+                run = 0;
+            }
+            continue;
+        }
+        if run > 0 {
+            out.push(QOI_OP_RUN | (run - 1));
+            run = 0;
+        }
+
+        let hash = color_hash(px);
+        if index[hash] == px {
+            out.push(QOI_OP_INDEX | hash as u8);
+        } else {
+            index[hash] = px;
+            if px[3] == prev[3] {
+                let vr = px[0].wrapping_sub(prev[0]) as i8;
+                let vg = px[1].wrapping_sub(prev[1]) as i8;
+                let vb = px[2].wrapping_sub(prev[2]) as i8;
+                let vg_r = vr.wrapping_sub(vg);
+                let vg_b = vb.wrapping_sub(vg);
+                if (-2..=1).contains(&vr) && (-2..=1).contains(&vg) && (-2..=1).contains(&vb) {
+                    out.push(QOI_OP_DIFF | ((vr + 2) as u8) << 4 | ((vg + 2) as u8) << 2 | (vb + 2) as u8);
+                } else if (-8..=7).contains(&vg_r) && (-32..=31).contains(&vg) && (-8..=7).contains(&vg_b) {
+                    out.push(QOI_OP_LUMA | (vg + 32) as u8);
+                    out.push(((vg_r + 8) as u8) << 4 | (vg_b + 8) as u8);
+                } else {
+                    out.extend_from_slice(&[QOI_OP_RGB, px[0], px[1], px[2]]);
+                }
+            } else {
+                out.extend_from_slice(&[QOI_OP_RGBA, px[0], px[1], px[2], px[3]]);
+            }
+        }
+        prev = px;
+    }
+    out.extend_from_slice(&QOI_PADDING);
+    out
+}
+
+fn decode(data: &[u8]) -> (u32, u32, Vec<u8>) {
+    assert!(data.len() >= QOI_HEADER_SIZE + QOI_PADDING.len() && &data[..4] == QOI_MAGIC, "Not a QOI image");
+    let width = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+    let height = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
+    assert_eq!(data[12] as usize, CHANNELS, "Only RGBA QOI images are supported");
+
+    let mut pixels = vec![0u8; width as usize * height as usize * CHANNELS];
+    let mut index = [[0u8; 4]; 64];
+    let mut px: Pixel = [0, 0, 0, 255];
+    let mut run = 0u8;
+    let mut p = QOI_HEADER_SIZE;
+    let chunks_end = data.len() - QOI_PADDING.len();
+    for out in pixels.chunks_exact_mut(CHANNELS) {
+        if run > 0 {
+            run -= 1;
+        } else if p < chunks_end {
+            let b1 = data[p];
+            p += 1;
+            if b1 == QOI_OP_RGB {
+                px[..3].copy_from_slice(&data[p..p + 3]);
+                p += 3;
+            } else if b1 == QOI_OP_RGBA {
+                px.copy_from_slice(&data[p..p + 4]);
+                p += 4;
+            } else {
+                match b1 & QOI_MASK_2 {
+                    QOI_OP_INDEX => px = index[b1 as usize],
+                    QOI_OP_DIFF => {
+                        px[0] = px[0].wrapping_add((b1 >> 4) & 0x03).wrapping_sub(2);
+                        px[1] = px[1].wrapping_add((b1 >> 2) & 0x03).wrapping_sub(2);
+                        px[2] = px[2].wrapping_add(b1 & 0x03).wrapping_sub(2);
+                    }
+                    QOI_OP_LUMA => {
+                        let b2 = data[p];
+                        p += 1;
+                        let vg = (b1 & 0x3f).wrapping_sub(32);
+                        px[0] = px[0].wrapping_add(vg.wrapping_sub(8).wrapping_add((b2 >> 4) & 0x0f));
+                        px[1] = px[1].wrapping_add(vg);
+                        px[2] = px[2].wrapping_add(vg.wrapping_sub(8).wrapping_add(b2 & 0x0f));
+                    }
+                    _ => run = b1 & 0x3f,
+                }
+            }
+            index[color_hash(px)] = px;
+        }
+        out.copy_from_slice(&px);
+    }
+    (width, height, pixels)
+}
+
+fn main() {
+    let pixels = generate_image();
+    let megabytes = pixels.len() as f64 / 1e6;
+
+    // Warm-up on the top sixteenth of the image
+    let _ = decode(&encode(&pixels[..pixels.len() / 16], WIDTH, HEIGHT / 16));
+
+    let mut encode_time = Duration::ZERO;
+    let mut decode_time = Duration::ZERO;
+    let mut encoded = Vec::new();
+    let mut decoded = Vec::new();
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        encoded = encode(&pixels, WIDTH, HEIGHT);
+        encode_time += start.elapsed();
+
+        let start = Instant::now();
+        let (width, height, image) = decode(&encoded);
+        decode_time += start.elapsed();
+        assert_eq!((width, height), (WIDTH, HEIGHT));
+        decoded = image;
+    }
+    assert!(decoded == pixels, "Decoded pixels differ from the original");
+
+    // The format is fully specified, so the encoded bytes must match C++ exactly
+    let byte_sum = encoded.iter().fold(0u64, |acc, &b| acc.wrapping_add(b as u64));
+    let rate = |d: Duration| megabytes * ROUNDS as f64 / d.as_secs_f64();
+
+    println!("{:.6}", (encode_time + decode_time).as_secs_f64());
+    eprintln!("Image: {}x{} RGBA8 ({:.1} MB raw), {} rounds", WIDTH, HEIGHT, megabytes, ROUNDS);
+    eprintln!("Encoded size: {:.2} MB (ratio {:.3})", encoded.len() as f64 / 1e6, encoded.len() as f64 / pixels.len() as f64);
+    eprintln!("Encode: {:.1} MB/s, decode: {:.1} MB/s", rate(encode_time), rate(decode_time));
+    eprintln!("Checksum: {} encoded bytes, byte sum {}", encoded.len(), byte_sum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(pixels: &[u8], width: u32, height: u32) -> Vec<u8> {
+        let encoded = encode(pixels, width, height);
+        let (w, h, decoded) = decode(&encoded);
+        assert_eq!((w, h), (width, height));
+        assert_eq!(decoded, pixels);
+        encoded
+    }
+
+    #[test]
+    fn encodes_each_op_as_the_spec_does() {
+        let pixels = [
+            0, 0, 0, 255, 0, 0, 0, 255, // run of 2 matching the initial previous pixel
+            1, 0, 0, 255,               // diff (+1, 0, 0)
+            21, 20, 19, 255,            // luma: vg = 20, vg_r = 0, vg_b = -1
+            200, 0, 100, 255,           // rgb
+            200, 0, 100, 7,             // rgba
+            1, 0, 0, 255,               // index
+        ];
+        let encoded = round_trip(&pixels, 7, 1);
+        assert_eq!(&encoded[..4], QOI_MAGIC);
+        assert_eq!(&encoded[4..QOI_HEADER_SIZE], &[0, 0, 0, 7, 0, 0, 0, 1, 4, 0]);
+        let ops = &encoded[QOI_HEADER_SIZE..encoded.len() - QOI_PADDING.len()];
+        assert_eq!(ops, &[
+            QOI_OP_RUN | 1,
+            QOI_OP_DIFF | 3 << 4 | 2 << 2 | 2,
+            QOI_OP_LUMA | 52, 8 << 4 | 7,
+            QOI_OP_RGB, 200, 0, 100,
+            QOI_OP_RGBA, 200, 0, 100, 7,
+            QOI_OP_INDEX | color_hash([1, 0, 0, 255]) as u8,
+        ]);
+        assert_eq!(&encoded[encoded.len() - 8..], &QOI_PADDING);
+    }
+
+    #[test]
+    fn long_runs_split_at_62() {
+        let pixels: Vec<u8> = [200, 9, 9, 255].repeat(130);
+        let encoded = round_trip(&pixels, 130, 1);
+        let ops = &encoded[QOI_HEADER_SIZE..encoded.len() - QOI_PADDING.len()];
+        // One rgb op, then the 129 repeats as 62 + 62 + 5
+        assert_eq!(ops, &[QOI_OP_RGB, 200, 9, 9, QOI_OP_RUN | 61, QOI_OP_RUN | 61, QOI_OP_RUN | 4]);
+    }
+
+    #[test]
+    fn round_trips_generated_and_random_images() {
+        let image = generate_image();
+        let rows = &image[..WIDTH as usize * 300 * CHANNELS];
+        let encoded = round_trip(rows, WIDTH, 300);
+        assert!(encoded.len() < rows.len());
+
+        let mut seed = 77u64;
+        let noise: Vec<u8> = (0..97 * 31 * CHANNELS).map(|_| (next_random(&mut seed) % 4) as u8 * 60).collect();
+        round_trip(&noise, 97, 31);
+    }
+}
@@ -0,0 +1,402 @@
+use std::time::Instant;
+
+const WIDTH: usize = 1920;
+const HEIGHT: usize = 1080;
+const DEFAULT_LAYERS: usize = 32; // 32 x 64,800 = 2.07M triangles
+const WARMUP_LAYERS: usize = 2;
+const CELL: usize = 8; // mesh cell size in pixels, two triangles per cell
+const JITTER: f32 = 1.5; // interior vertices move up to this far each way; under CELL / 4 keeps cells convex
+const NEAR: f32 = 2.0;
+const FAR: f32 = 40.0;
+const SUBPIXEL_BITS: i32 = 4; // vertices snap to 1/16 pixel
+const SUBPIXEL: i32 = 1 << SUBPIXEL_BITS;
+const TEXTURE_BITS: u32 = 8;
+const TEXTURE_SIZE: usize = 1 << TEXTURE_BITS; // 256x256 RGBA8, wrapped
+const SEED: u64 = 0x7261_7374_6572_697a;
+
+// Screen-space corner: position in pixels, w (view depth) and texture coordinates in texels/size
+#[derive(Clone, Copy)]
+struct Vertex {
+    x: f32,
+    y: f32,
+    w: f32,
+    u: f32,
+    v: f32,
+}
+
+struct Texture {
+    texels: Vec<u32>, // RGBA8, red in the low byte
+}
+
+struct Framebuffer {
+    color: Vec<u32>,
+    depth: Vec<f32>, // 1/w of the nearest surface so far, 0 for none
+}
+
+#[derive(Default)]
+struct Stats {
+    triangles: u64, // set up and scanned, after dropping zero-area ones
+    tested: u64,    // pixels inside bounding boxes
+    covered: u64,   // pixels inside triangles
+    shaded: u64,    // pixels that passed the depth test and were textured
+}
+
+// SplitMix64; the top 24 bits become a float in [0, 1) exactly, in both languages
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn range(&mut self, lo: f32, hi: f32) -> f32 {
+        lo + (hi - lo) * ((self.next() >> 40) as f32 * (1.0 / 16_777_216.0))
+    }
+}
+
+// Full-screen meshes of jittered cells, one per layer, from FAR to NEAR in painter's order.
+// Border vertices stay on the screen edge, so every layer covers each pixel exactly once.
+// Each vertex's w varies up to 50% past its layer's depth, so perspective-correct and affine
+// interpolation differ and neighbouring layers interpenetrate
+fn make_scene(rng: &mut Rng, layers: usize) -> Vec<[Vertex; 3]> {
+    let (columns, rows) = (WIDTH / CELL, HEIGHT / CELL);
+    let mut triangles = Vec::with_capacity(layers * columns * rows * 2);
+    for layer in 0..layers {
+        let t = layer as f32 / layers.max(2).saturating_sub(1) as f32;
+        let depth = FAR + (NEAR - FAR) * t;
+        let offset = layer as f32 * 0.37; // so layers show different parts of the texture
+        let mut grid = Vec::with_capacity((columns + 1) * (rows + 1));
+        for gy in 0..=rows {
+            for gx in 0..=columns {
+                let jitter_x = if gx == 0 || gx == columns { 0.0 } else { rng.range(-JITTER, JITTER) };
+                let jitter_y = if gy == 0 || gy == rows { 0.0 } else { rng.range(-JITTER, JITTER) };
+                let x = (gx * CELL) as f32 + jitter_x;
+                let y = (gy * CELL) as f32 + jitter_y;
+                let w = depth * rng.range(1.0, 1.5);
+                grid.push(Vertex { x, y, w, u: x / 64.0 + offset, v: y / 64.0 + offset });
+            }
+        }
+        for gy in 0..rows {
+            for gx in 0..columns {
+                let corner = |dx: usize, dy: usize| grid[(gy + dy) * (columns + 1) + gx + dx];
+                let (a, b, c, d) = (corner(0, 0), corner(1, 0), corner(1, 1), corner(0, 1));
+                // Alternate the diagonal, and the winding, from cell to cell
+                if (gx + gy) % 2 == 0 {
+                    triangles.push([a, b, c]);
+                    triangles.push([a, d, c]);
+                } else {
+                    triangles.push([a, b, d]);
+                    triangles.push([b, c, d]);
+                }
+            }
+        }
+    }
+    triangles
+}
+
+// An XOR pattern over an 8x8 checkerboard, so both minification and blending show up in the checksum
+fn make_texture() -> Texture {
+    let mut texels = Vec::with_capacity(TEXTURE_SIZE * TEXTURE_SIZE);
+    for y in 0..TEXTURE_SIZE as u32 {
+        for x in 0..TEXTURE_SIZE as u32 {
+            let checker = if ((x >> 5) ^ (y >> 5)) & 1 == 0 { 0xe0 } else { 0x20 };
+            let r = (x ^ y) & 0xff;
+            let g = checker;
+            let b = (x.wrapping_mul(y) >> 4) & 0xff;
+            texels.push(r | (g << 8) | (b << 16) | 0xff00_0000);
+        }
+    }
+    Texture { texels }
+}
+
+// Bilinear filtering in 8.8 fixed point: u and v are in texels scaled by 256, the four weights
+// are 8-bit products summing to 65536, and each channel is blended in integers
+fn sample_bilinear(texture: &Texture, u: i32, v: i32) -> u32 {
+    let mask = TEXTURE_SIZE as i32 - 1;
+    let (u, v) = (u - 128, v - 128); // texel centers sit at +0.5
+    let (x0, y0) = ((u >> 8) & mask, (v >> 8) & mask);
+    let (x1, y1) = ((x0 + 1) & mask, (y0 + 1) & mask);
+    let (fx, fy) = ((u & 0xff) as u32, (v & 0xff) as u32);
+    let texel = |x: i32, y: i32| texture.texels[(y as usize) * TEXTURE_SIZE + x as usize];
+    let (t00, t10, t01, t11) = (texel(x0, y0), texel(x1, y0), texel(x0, y1), texel(x1, y1));
+    let (w00, w10) = ((256 - fx) * (256 - fy), fx * (256 - fy));
+    let (w01, w11) = ((256 - fx) * fy, fx * fy);
+    let mut out = 0u32;
+    for shift in [0, 8, 16, 24] {
+        let channel = |t: u32| (t >> shift) & 0xff;
+        let blended = channel(t00) * w00 + channel(t10) * w10 + channel(t01) * w01 + channel(t11) * w11;
+        out |= (blended >> 16) << shift;
+    }
+    out
+}
+
+// One edge in fixed point: value at the first pixel center of the bounding box and its steps.
+// The fill test is value + bias >= 0, where bias is -1 off top and left edges, so a pixel center
+// exactly on an edge shared by two triangles is drawn by exactly one of them
+#[derive(Clone, Copy)]
+struct Edge {
+    step_x: i32,
+    step_y: i32,
+    bias: i32,
+}
+
+impl Edge {
+    // From a to b in 28.4 fixed point, for a triangle wound so its area is positive
+    fn new(ax: i32, ay: i32, bx: i32, by: i32) -> Self {
+        let (dx, dy) = (bx - ax, by - ay);
+        let top_left = (dy == 0 && dx > 0) || dy < 0;
+        Edge { step_x: -dy * SUBPIXEL, step_y: dx * SUBPIXEL, bias: if top_left { 0 } else { -1 } }
+    }
+}
+
+fn edge_function(ax: i32, ay: i32, bx: i32, by: i32, px: i32, py: i32) -> i32 {
+    (bx - ax) * (py - ay) - (by - ay) * (px - ax)
+}
+
+// Scans the triangle's bounding box with incremental integer edge functions and calls
+// pixel(x, y, b0, b1, b2) with barycentric weights at each covered pixel center. Vertices must
+// lie on screen, which keeps every edge value within i32
+fn rasterize(tri: &[Vertex; 3], stats: &mut Stats, mut pixel: impl FnMut(usize, usize, f32, f32, f32)) {
+    let snap = |c: f32| (c * SUBPIXEL as f32).round() as i32;
+    let (mut x, mut y) = ([snap(tri[0].x), snap(tri[1].x), snap(tri[2].x)], [snap(tri[0].y), snap(tri[1].y), snap(tri[2].y)]);
+    let mut order = [0, 1, 2];
+    let mut area = edge_function(x[0], y[0], x[1], y[1], x[2], y[2]);
+    if area == 0 {
+        return;
+    }
+    if area < 0 {
+        x.swap(1, 2);
+        y.swap(1, 2);
+        order.swap(1, 2);
+        area = -area;
+    }
+    stats.triangles += 1;
+
+    // Pixel centers whose box overlaps the triangle's, clamped to the screen
+    let min_x = ((x.iter().min().unwrap() + SUBPIXEL / 2 - 1) >> SUBPIXEL_BITS).max(0);
+    let min_y = ((y.iter().min().unwrap() + SUBPIXEL / 2 - 1) >> SUBPIXEL_BITS).max(0);
+    let max_x = ((x.iter().max().unwrap() - SUBPIXEL / 2) >> SUBPIXEL_BITS).min(WIDTH as i32 - 1);
+    let max_y = ((y.iter().max().unwrap() - SUBPIXEL / 2) >> SUBPIXEL_BITS).min(HEIGHT as i32 - 1);
+    if min_x > max_x || min_y > max_y {
+        return;
+    }
+
+    // Edge i is opposite vertex i, so its value is vertex i's barycentric weight times the area
+    let edges = [Edge::new(x[1], y[1], x[2], y[2]), Edge::new(x[2], y[2], x[0], y[0]), Edge::new(x[0], y[0], x[1], y[1])];
+    let (px, py) = (min_x * SUBPIXEL + SUBPIXEL / 2, min_y * SUBPIXEL + SUBPIXEL / 2);
+    let mut row = [
+        edge_function(x[1], y[1], x[2], y[2], px, py),
+        edge_function(x[2], y[2], x[0], y[0], px, py),
+        edge_function(x[0], y[0], x[1], y[1], px, py),
+    ];
+    let inv_area = 1.0 / area as f32;
+    stats.tested += ((max_x - min_x + 1) * (max_y - min_y + 1)) as u64;
+
+    for py in min_y..=max_y {
+        let mut e = row;
+        for px in min_x..=max_x {
+            if (e[0] + edges[0].bias) | (e[1] + edges[1].bias) | (e[2] + edges[2].bias) >= 0 {
+                stats.covered += 1;
+                let mut b = [0.0f32; 3];
+                for (i, &o) in order.iter().enumerate() {
+                    b[o] = e[i] as f32 * inv_area;
+                }
+                pixel(px as usize, py as usize, b[0], b[1], b[2]);
+            }
+            for i in 0..3 {
+                e[i] += edges[i].step_x;
+            }
+        }
+        for i in 0..3 {
+            row[i] += edges[i].step_y;
+        }
+    }
+}
+
+// 1/w, u/w and v/w are linear in screen space, so they are interpolated with the barycentric
+// weights and u and v recovered with one divide per pixel: perspective-correct texture mapping
+struct Interpolants {
+    iw: [f32; 3],
+    uw: [f32; 3],
+    vw: [f32; 3],
+}
+
+impl Interpolants {
+    fn new(tri: &[Vertex; 3]) -> Self {
+        let iw = [1.0 / tri[0].w, 1.0 / tri[1].w, 1.0 / tri[2].w];
+        let uw = [tri[0].u * iw[0], tri[1].u * iw[1], tri[2].u * iw[2]];
+        let vw = [tri[0].v * iw[0], tri[1].v * iw[1], tri[2].v * iw[2]];
+        Interpolants { iw, uw, vw }
+    }
+
+    fn depth(&self, b0: f32, b1: f32, b2: f32) -> f32 {
+        b0 * self.iw[0] + b1 * self.iw[1] + b2 * self.iw[2]
+    }
+
+    fn uv(&self, b0: f32, b1: f32, b2: f32, depth: f32) -> (f32, f32) {
+        let w = 1.0 / depth;
+        let u = (b0 * self.uw[0] + b1 * self.uw[1] + b2 * self.uw[2]) * w;
+        let v = (b0 * self.vw[0] + b1 * self.vw[1] + b2 * self.vw[2]) * w;
+        (u, v)
+    }
+}
+
+// Depth-tests on 1/w (larger is nearer), then samples the texture and writes the pixel
+fn draw(fb: &mut Framebuffer, texture: &Texture, tri: &[Vertex; 3], stats: &mut Stats) {
+    let interpolants = Interpolants::new(tri);
+    let scale = (TEXTURE_SIZE * 256) as f32;
+    let mut shaded = 0;
+    rasterize(tri, stats, |x, y, b0, b1, b2| {
+        let index = y * WIDTH + x;
+        let z = interpolants.depth(b0, b1, b2);
+        if z <= fb.depth[index] {
+            return;
+        }
+        fb.depth[index] = z;
+        let (u, v) = interpolants.uv(b0, b1, b2, z);
+        fb.color[index] = sample_bilinear(texture, (u * scale) as i32, (v * scale) as i32);
+        shaded += 1;
+    });
+    stats.shaded += shaded;
+}
+
+fn render(fb: &mut Framebuffer, texture: &Texture, triangles: &[[Vertex; 3]]) -> Stats {
+    fb.color.fill(0);
+    fb.depth.fill(0.0);
+    let mut stats = Stats::default();
+    for tri in triangles {
+        draw(fb, texture, tri, &mut stats);
+    }
+    stats
+}
+
+fn parse_layers() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--layers") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .expect("--layers expects a positive integer"),
+        None => DEFAULT_LAYERS,
+    }
+}
+
+fn main() {
+    let layers = parse_layers();
+    let triangles = make_scene(&mut Rng(SEED), layers);
+    let texture = make_texture();
+    let mut fb = Framebuffer { color: vec![0; WIDTH * HEIGHT], depth: vec![0.0; WIDTH * HEIGHT] };
+
+    // Warm-up
+    let _ = render(&mut fb, &texture, &make_scene(&mut Rng(SEED ^ 1), WARMUP_LAYERS));
+
+    // Benchmark
+    let start = Instant::now();
+    let stats = render(&mut fb, &texture, &triangles);
+    let duration = start.elapsed();
+
+    // The meshes are watertight, so each layer covers every pixel once: no cracks, no double hits
+    let expected = (layers * WIDTH * HEIGHT) as u64;
+    if stats.covered != expected {
+        eprintln!("Covered {} pixels, expected {} for {} watertight layers", stats.covered, expected, layers);
+        std::process::exit(1);
+    }
+
+    let checksum = fb.color.iter().fold(0u64, |sum, &c| sum.wrapping_add(c as u64));
+    let seconds = duration.as_secs_f64();
+
+    println!("{:.6}", seconds);
+    eprintln!("Framebuffer: {}x{}, {}x{} RGBA8 texture, bilinear, perspective-correct", WIDTH, HEIGHT, TEXTURE_SIZE, TEXTURE_SIZE);
+    eprintln!("Triangles: {} drawn, {:.2}M triangles/s", stats.triangles, stats.triangles as f64 / seconds / 1e6);
+    eprintln!("Pixels: {} tested, {} covered ({:.1} per triangle), {} shaded, {:.1}M shaded pixels/s",
+              stats.tested, stats.covered, stats.covered as f64 / stats.triangles.max(1) as f64, stats.shaded,
+              stats.shaded as f64 / seconds / 1e6);
+    eprintln!("Depth complexity: {:.2} covered per screen pixel, {:.1}% of covered pixels passed the depth test",
+              stats.covered as f64 / (WIDTH * HEIGHT) as f64, 100.0 * stats.shaded as f64 / stats.covered.max(1) as f64);
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertex(x: f32, y: f32) -> Vertex {
+        Vertex { x, y, w: 1.0, u: 0.0, v: 0.0 }
+    }
+
+    #[test]
+    fn shared_edges_are_filled_once() {
+        // A quad split along its diagonal, with pixel centers on the top, left and diagonal edges;
+        // the winding of the second triangle is flipped to exercise the swap
+        let (a, b, c, d) = (vertex(10.5, 10.5), vertex(30.5, 10.5), vertex(30.5, 26.5), vertex(10.5, 26.5));
+        let mut coverage = vec![0u32; WIDTH * HEIGHT];
+        let mut stats = Stats::default();
+        for tri in [[a, b, c], [a, d, c]] {
+            rasterize(&tri, &mut stats, |x, y, b0, b1, b2| {
+                assert!((b0 + b1 + b2 - 1.0).abs() < 1e-5);
+                coverage[y * WIDTH + x] += 1;
+            });
+        }
+        for y in 0..40 {
+            for x in 0..40 {
+                let inside = (10..30).contains(&x) && (10..26).contains(&y);
+                assert_eq!(coverage[y * WIDTH + x], inside as u32, "pixel {}, {}", x, y);
+            }
+        }
+        assert_eq!(stats.covered, 20 * 16);
+    }
+
+    #[test]
+    fn mesh_layers_cover_every_pixel_once() {
+        let mut coverage = vec![0u8; WIDTH * HEIGHT];
+        let mut stats = Stats::default();
+        for tri in make_scene(&mut Rng(SEED), 1) {
+            rasterize(&tri, &mut stats, |x, y, _, _, _| coverage[y * WIDTH + x] += 1);
+        }
+        assert_eq!(stats.triangles as usize, (WIDTH / CELL) * (HEIGHT / CELL) * 2);
+        assert!(coverage.iter().all(|&c| c == 1));
+    }
+
+    #[test]
+    fn texture_coordinates_are_perspective_correct() {
+        // u runs 0 to 1 along an edge receding from w = 1 to w = 3. With b1 the screen-space
+        // weight of the far corner, u = (b1 / 3) / (1 - 2 b1 / 3), well short of the affine b1
+        let tri = [
+            Vertex { x: 0.0, y: 0.0, w: 1.0, u: 0.0, v: 0.0 },
+            Vertex { x: 64.0, y: 0.0, w: 3.0, u: 1.0, v: 0.0 },
+            Vertex { x: 0.0, y: 64.0, w: 1.0, u: 0.0, v: 1.0 },
+        ];
+        let interpolants = Interpolants::new(&tri);
+        let mut checked = 0;
+        rasterize(&tri, &mut Stats::default(), |x, y, b0, b1, b2| {
+            if (x, y) == (31, 1) {
+                let b1_expected = 31.5 / 64.0;
+                assert!((b1 - b1_expected).abs() < 1e-6);
+                let (u, _) = interpolants.uv(b0, b1, b2, interpolants.depth(b0, b1, b2));
+                let expected = (b1_expected / 3.0) / (1.0 - 2.0 * b1_expected / 3.0);
+                assert!((u - expected).abs() < 1e-5 && u < 0.5 * b1_expected, "u = {}", u);
+                checked += 1;
+            }
+        });
+        assert_eq!(checked, 1);
+    }
+
+    #[test]
+    fn bilinear_blends_neighbours() {
+        let texture = make_texture();
+        let texel = |x: usize, y: usize| texture.texels[y * TEXTURE_SIZE + x];
+        // At a texel center the sample is that texel; halfway between two it is their average
+        assert_eq!(sample_bilinear(&texture, 3 * 256 + 128, 5 * 256 + 128), texel(3, 5));
+        let half = sample_bilinear(&texture, 4 * 256, 5 * 256 + 128);
+        for shift in [0, 8, 16, 24] {
+            let (a, b) = ((texel(3, 5) >> shift) & 0xff, (texel(4, 5) >> shift) & 0xff);
+            assert_eq!((half >> shift) & 0xff, (a + b) / 2);
+        }
+        // Coordinates wrap at the texture edge
+        assert_eq!(sample_bilinear(&texture, 128, 128), sample_bilinear(&texture, 256 * 256 + 128, 128));
+    }
+}
@@ -1,142 +1,1003 @@
+use bench_math::Vec3;
 use rayon::prelude::*;
-use std::sync::Arc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
 use std::time::Instant;
 
 const WIDTH: usize = 1920;
 const HEIGHT: usize = 1080;
 const SAMPLES: usize = 4;
+const DEFAULT_MAX_DEPTH: usize = 8;
+const LEAF_SIZE: usize = 4;
+const TILE_SIZE: usize = 32; // square tiles; the last column and row are cut to the frame
+const T_MIN: f64 = 1e-4;
+const AO_RAYS: usize = 4; // occlusion rays per camera sample in --mode ao
+const AO_DISTANCE: f64 = 0.5; // how far an occluder may be and still shade the hit
+const GOLDEN_PATH: &str = "golden/ray_tracer.png"; // relative to the repository root, where build.py runs
+const GOLDEN_AO_PATH: &str = "golden/ray_tracer_ao.png";
+const PIXEL_TOLERANCE: u8 = 8;          // per-channel difference, out of 255
+const MAX_MISMATCH_FRACTION: f64 = 0.001; // pixels beyond tolerance before --verify fails
 
-#[derive(Clone, Copy)]
-struct Vec3 {
-    x: f64,
-    y: f64,
-    z: f64,
+// Small PCG-style generator, seeded per pixel so renders are deterministic
+struct Rng {
+    state: u64,
 }
 
-impl Vec3 {
-    fn new(x: f64, y: f64, z: f64) -> Self {
-        Vec3 { x, y, z }
-    }
-    
-    fn dot(&self, other: &Vec3) -> f64 {
-        self.x * other.x + self.y * other.y + self.z * other.z
+impl Rng {
+    fn new(seed: u64) -> Self {
+        let mut rng = Rng { state: seed.wrapping_mul(0x9E3779B97F4A7C15).wrapping_add(1) };
+        rng.next_u64();
+        rng
     }
-    
-    fn length(&self) -> f64 {
-        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
-    }
-    
-    fn normalize(&self) -> Vec3 {
-        let len = self.length();
-        Vec3::new(self.x / len, self.y / len, self.z / len)
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let x = self.state;
+        (x ^ (x >> 33)).wrapping_mul(0xff51afd7ed558ccd) ^ (x >> 29)
     }
-    
-    fn sub(&self, other: &Vec3) -> Vec3 {
-        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
     }
-    
-    fn add(&self, other: &Vec3) -> Vec3 {
-        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+
+    fn unit_vector(&mut self) -> Vec3 {
+        let z = 1.0 - 2.0 * self.next_f64();
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let phi = 2.0 * std::f64::consts::PI * self.next_f64();
+        Vec3::new(r * phi.cos(), r * phi.sin(), z)
     }
-    
-    fn mul(&self, scalar: f64) -> Vec3 {
-        Vec3::new(self.x * scalar, self.y * scalar, self.z * scalar)
+
+    // Cosine-weighted over the hemisphere around unit `normal`: uniform on the unit disk, lifted
+    // onto the hemisphere
+    fn cosine_direction(&mut self, normal: &Vec3) -> Vec3 {
+        let r2 = self.next_f64();
+        let r = r2.sqrt();
+        let phi = 2.0 * std::f64::consts::PI * self.next_f64();
+        let (tangent, bitangent) = orthonormal_basis(normal);
+        tangent * (r * phi.cos()) + bitangent * (r * phi.sin()) + *normal * (1.0 - r2).max(0.0).sqrt()
     }
 }
 
+// Two unit vectors completing unit `n` to an orthonormal basis, without a branch on its direction
+// (Duff et al., "Building an Orthonormal Basis, Revisited")
+fn orthonormal_basis(n: &Vec3) -> (Vec3, Vec3) {
+    let sign = 1.0f64.copysign(n.z);
+    let a = -1.0 / (sign + n.z);
+    let b = n.x * n.y * a;
+    (Vec3::new(1.0 + sign * n.x * n.x * a, sign * b, -sign * n.x), Vec3::new(b, sign + n.y * n.y * a, -n.y))
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Material {
+    Diffuse,
+    #[serde(rename = "mirror")]
+    Reflective,
+    Emissive, // a light: ends the path, contributing its color
+}
+
 struct Sphere {
     center: Vec3,
     radius: f64,
     color: Vec3,
+    material: Material,
 }
 
 impl Sphere {
-    fn intersect(&self, origin: &Vec3, direction: &Vec3) -> Option<f64> {
-        let oc = origin.sub(&self.center);
+    fn intersect(&self, origin: &Vec3, direction: &Vec3, t_max: f64) -> Option<f64> {
+        let oc = *origin - self.center;
         let a = direction.dot(direction);
-        let b = 2.0 * oc.dot(direction);
+        let half_b = oc.dot(direction);
         let c = oc.dot(&oc) - self.radius * self.radius;
-        let discriminant = b * b - 4.0 * a * c;
-        
+        let discriminant = half_b * half_b - a * c;
+
         if discriminant < 0.0 {
+            return None;
+        }
+
+        let sqrt_d = discriminant.sqrt();
+        let near = (-half_b - sqrt_d) / a;
+        if near > T_MIN && near < t_max {
+            return Some(near);
+        }
+        let far = (-half_b + sqrt_d) / a;
+        if far > T_MIN && far < t_max {
+            Some(far)
+        } else {
             None
+        }
+    }
+
+    fn bounds(&self) -> PrimitiveBounds {
+        let r = Vec3::splat(self.radius);
+        PrimitiveBounds { min: self.center - r, max: self.center + r, centroid: self.center }
+    }
+}
+
+// Stored as one corner plus two edges, the form the Moller-Trumbore test wants
+struct Triangle {
+    v0: Vec3,
+    edge1: Vec3,
+    edge2: Vec3,
+    normal: Vec3,
+    color: Vec3,
+    material: Material,
+}
+
+impl Triangle {
+    // None for a degenerate (zero-area) triangle, which no ray can hit
+    fn new(v0: Vec3, v1: Vec3, v2: Vec3, color: Vec3, material: Material) -> Option<Self> {
+        let edge1 = v1 - v0;
+        let edge2 = v2 - v0;
+        let normal = edge1.cross(&edge2);
+        if normal.dot(&normal) == 0.0 {
+            return None;
+        }
+        Some(Triangle { v0, edge1, edge2, normal: normal.normalize(), color, material })
+    }
+
+    fn intersect(&self, origin: &Vec3, direction: &Vec3, t_max: f64) -> Option<f64> {
+        let p = direction.cross(&self.edge2);
+        let det = self.edge1.dot(&p);
+        if det == 0.0 {
+            return None; // ray parallel to the plane
+        }
+        let inv_det = 1.0 / det;
+        let s = *origin - self.v0;
+        let u = s.dot(&p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(&self.edge1);
+        let v = direction.dot(&q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = self.edge2.dot(&q) * inv_det;
+        if t > T_MIN && t < t_max { Some(t) } else { None }
+    }
+
+    fn bounds(&self) -> PrimitiveBounds {
+        let v1 = self.v0 + self.edge1;
+        let v2 = self.v0 + self.edge2;
+        PrimitiveBounds {
+            min: self.v0.min(&v1).min(&v2),
+            max: self.v0.max(&v1).max(&v2),
+            centroid: (self.v0 + v1 + v2) * (1.0 / 3.0),
+        }
+    }
+}
+
+// What the BVH builder needs to know about one primitive
+struct PrimitiveBounds {
+    min: Vec3,
+    max: Vec3,
+    centroid: Vec3,
+}
+
+// Flattened BVH node: leaves (count > 0) store a range into `indices`,
+// interior nodes store their right child in `first` (left child is next)
+struct BvhNode {
+    min: Vec3,
+    max: Vec3,
+    first: usize,
+    count: usize,
+}
+
+impl BvhNode {
+    fn hit(&self, origin: &Vec3, inv_dir: &Vec3, t_max: f64) -> bool {
+        let mut t_near = T_MIN;
+        let mut t_far = t_max;
+        for axis in 0..3 {
+            let inv = inv_dir.axis(axis);
+            let o = origin.axis(axis);
+            let mut t0 = (self.min.axis(axis) - o) * inv;
+            let mut t1 = (self.max.axis(axis) - o) * inv;
+            if inv < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_near = t_near.max(t0);
+            t_far = t_far.min(t1);
+            if t_far < t_near {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// BVH over one kind of primitive; `indices` orders the primitives so every leaf is a range
+struct Bvh {
+    indices: Vec<usize>,
+    nodes: Vec<BvhNode>,
+}
+
+impl Bvh {
+    fn new(bounds: &[PrimitiveBounds]) -> Self {
+        let mut bvh = Bvh { indices: (0..bounds.len()).collect(), nodes: Vec::new() };
+        if !bounds.is_empty() {
+            bvh.build(bounds, 0, bounds.len());
+        }
+        bvh
+    }
+
+    // Median split along the longest axis of the centroid bounds
+    fn build(&mut self, bounds: &[PrimitiveBounds], first: usize, count: usize) -> usize {
+        let node_index = self.nodes.len();
+        let mut min = Vec3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut max = Vec3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+        let mut centroid_min = min;
+        let mut centroid_max = max;
+        for &i in &self.indices[first..first + count] {
+            let b = &bounds[i];
+            min = min.min(&b.min);
+            max = max.max(&b.max);
+            centroid_min = centroid_min.min(&b.centroid);
+            centroid_max = centroid_max.max(&b.centroid);
+        }
+        self.nodes.push(BvhNode { min, max, first, count });
+
+        if count <= LEAF_SIZE {
+            return node_index;
+        }
+
+        let extent = centroid_max - centroid_min;
+        let axis = if extent.x > extent.y && extent.x > extent.z {
+            0
+        } else if extent.y > extent.z {
+            1
         } else {
-            let t = (-b - discriminant.sqrt()) / (2.0 * a);
-            if t > 0.0 {
-                Some(t)
+            2
+        };
+
+        self.indices[first..first + count].sort_by(|&a, &b| {
+            bounds[a].centroid.axis(axis).partial_cmp(&bounds[b].centroid.axis(axis)).unwrap()
+        });
+
+        let half = count / 2;
+        self.build(bounds, first, half);
+        let right = self.build(bounds, first + half, count - half);
+
+        self.nodes[node_index].first = right;
+        self.nodes[node_index].count = 0;
+        node_index
+    }
+
+    // Index of the nearest primitive closer than `closest_t`, which shrinks to its distance.
+    // `hit(i, t_max)` tests primitive i against the current bound
+    fn traverse(&self, origin: &Vec3, inv_dir: &Vec3, closest_t: &mut f64,
+                mut hit: impl FnMut(usize, f64) -> Option<f64>) -> Option<usize> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let mut nearest = None;
+        let mut stack = [0usize; 64];
+        let mut stack_len = 1;
+
+        while stack_len > 0 {
+            stack_len -= 1;
+            let node_index = stack[stack_len];
+            let node = &self.nodes[node_index];
+            if !node.hit(origin, inv_dir, *closest_t) {
+                continue;
+            }
+
+            if node.count > 0 {
+                for &i in &self.indices[node.first..node.first + node.count] {
+                    if let Some(t) = hit(i, *closest_t) {
+                        *closest_t = t;
+                        nearest = Some(i);
+                    }
+                }
             } else {
-                None
+                stack[stack_len] = node.first;
+                stack[stack_len + 1] = node_index + 1;
+                stack_len += 2;
             }
         }
+
+        nearest
     }
+
+    // Whether any primitive is closer than `t_max`, stopping at the first one found: an
+    // occlusion ray needs no nearest hit
+    fn any_hit(&self, origin: &Vec3, inv_dir: &Vec3, t_max: f64, mut hit: impl FnMut(usize, f64) -> Option<f64>) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let mut stack = [0usize; 64];
+        let mut stack_len = 1;
+
+        while stack_len > 0 {
+            stack_len -= 1;
+            let node_index = stack[stack_len];
+            let node = &self.nodes[node_index];
+            if !node.hit(origin, inv_dir, t_max) {
+                continue;
+            }
+
+            if node.count > 0 {
+                if self.indices[node.first..node.first + node.count].iter().any(|&i| hit(i, t_max).is_some()) {
+                    return true;
+                }
+            } else {
+                stack[stack_len] = node.first;
+                stack[stack_len + 1] = node_index + 1;
+                stack_len += 2;
+            }
+        }
+
+        false
+    }
+}
+
+// Pinhole camera whose image plane is kept vertical: `right` is horizontal and `up` is
+// world up, so the frame spans 2 * aspect by 2 units at the tip of `direction`
+struct Camera {
+    origin: Vec3,
+    direction: Vec3,
+    right: Vec3,
+    up: Vec3,
+}
+
+impl Camera {
+    fn new(origin: Vec3, direction: Vec3) -> Self {
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let right = direction.cross(&up);
+        assert!(right.dot(&right) > 0.0, "Camera direction must not be vertical");
+        Camera { origin, direction, right: right.normalize(), up }
+    }
+}
+
+// Background gradient from the horizon color straight ahead to the zenith color overhead
+struct Sky {
+    horizon: Vec3,
+    zenith: Vec3,
+}
+
+enum Surface<'a> {
+    Sphere(&'a Sphere),
+    Triangle(&'a Triangle),
+}
+
+impl Surface<'_> {
+    fn normal(&self, hit_point: &Vec3, direction: &Vec3) -> Vec3 {
+        match self {
+            Surface::Sphere(s) => (*hit_point - s.center) * (1.0 / s.radius),
+            // Triangles are two-sided: shade the face the ray arrived at
+            Surface::Triangle(tri) => if tri.normal.dot(direction) > 0.0 { -tri.normal } else { tri.normal },
+        }
+    }
+}
+
+// Spheres and triangles keep separate BVHs: a ray walks the sphere tree, then the triangle
+// tree clipped to the nearest sphere hit
+struct Scene {
+    spheres: Vec<Sphere>,
+    sphere_bvh: Bvh,
+    triangles: Vec<Triangle>,
+    triangle_bvh: Bvh,
+    camera: Camera,
+    sky: Sky,
+}
+
+impl Scene {
+    fn new(spheres: Vec<Sphere>, triangles: Vec<Triangle>, camera: Camera, sky: Sky) -> Self {
+        let sphere_bounds: Vec<_> = spheres.iter().map(Sphere::bounds).collect();
+        let triangle_bounds: Vec<_> = triangles.iter().map(Triangle::bounds).collect();
+        Scene {
+            sphere_bvh: Bvh::new(&sphere_bounds),
+            triangle_bvh: Bvh::new(&triangle_bounds),
+            spheres,
+            triangles,
+            camera,
+            sky,
+        }
+    }
+
+    fn bvh_nodes(&self) -> usize {
+        self.sphere_bvh.nodes.len() + self.triangle_bvh.nodes.len()
+    }
+
+    fn intersect(&self, origin: &Vec3, direction: &Vec3) -> Option<(f64, Surface<'_>)> {
+        let inv_dir = Vec3::new(1.0 / direction.x, 1.0 / direction.y, 1.0 / direction.z);
+        let mut closest_t = f64::INFINITY;
+        let sphere = self.sphere_bvh.traverse(origin, &inv_dir, &mut closest_t, |i, t_max| {
+            self.spheres[i].intersect(origin, direction, t_max)
+        });
+        let triangle = self.triangle_bvh.traverse(origin, &inv_dir, &mut closest_t, |i, t_max| {
+            self.triangles[i].intersect(origin, direction, t_max)
+        });
+        match (triangle, sphere) {
+            (Some(i), _) => Some((closest_t, Surface::Triangle(&self.triangles[i]))),
+            (None, Some(i)) => Some((closest_t, Surface::Sphere(&self.spheres[i]))),
+            (None, None) => None,
+        }
+    }
+
+    fn occluded(&self, origin: &Vec3, direction: &Vec3, t_max: f64) -> bool {
+        let inv_dir = Vec3::new(1.0 / direction.x, 1.0 / direction.y, 1.0 / direction.z);
+        self.sphere_bvh.any_hit(origin, &inv_dir, t_max, |i, t_max| self.spheres[i].intersect(origin, direction, t_max))
+            || self.triangle_bvh.any_hit(origin, &inv_dir, t_max, |i, t_max| self.triangles[i].intersect(origin, direction, t_max))
+    }
+}
+
+// Scene file layout, shared with the C++ version (see scenes/default.json)
+#[derive(Deserialize)]
+struct SceneFile {
+    camera: CameraFile,
+    sky: SkyFile,
+    materials: Vec<MaterialFile>,
+    #[serde(default)]
+    spheres: Vec<SphereFile>,
+    sphere_grid: Option<GridFile>,
+    #[serde(default)]
+    meshes: Vec<MeshFile>,
+}
+
+#[derive(Deserialize)]
+struct CameraFile {
+    origin: [f64; 3],
+    direction: [f64; 3],
+}
+
+#[derive(Deserialize)]
+struct SkyFile {
+    horizon: [f64; 3],
+    zenith: [f64; 3],
+}
+
+#[derive(Deserialize)]
+struct MaterialFile {
+    name: String,
+    #[serde(rename = "type")]
+    kind: Material,
+    color: [f64; 3],
+}
+
+#[derive(Deserialize)]
+struct SphereFile {
+    center: [f64; 3],
+    radius: f64,
+    material: String,
+}
+
+// Rows of small randomly sized, colored and jittered spheres resting on y = origin[1],
+// starting at origin and marching +x by column and -z by row
+#[derive(Deserialize)]
+struct GridFile {
+    columns: usize,
+    rows: usize,
+    seed: u64,
+    origin: [f64; 3],
+    spacing: f64,
+    jitter: f64,
+    radius_min: f64,
+    radius_jitter: f64,
+    mirror_fraction: f64,
 }
 
-fn trace_ray(origin: &Vec3, direction: &Vec3, spheres: &[Sphere]) -> Vec3 {
-    let mut closest_t = f64::INFINITY;
-    let mut hit_sphere: Option<&Sphere> = None;
-    
-    for sphere in spheres {
-        if let Some(t) = sphere.intersect(origin, direction) {
-            if t < closest_t {
-                closest_t = t;
-                hit_sphere = Some(sphere);
+// Triangles from a Wavefront OBJ file, scaled about the model origin and then translated.
+// `path` is relative to the scene file
+#[derive(Deserialize)]
+struct MeshFile {
+    path: String,
+    material: String,
+    #[serde(default = "unit_scale")]
+    scale: f64,
+    #[serde(default)]
+    translate: [f64; 3],
+}
+
+fn unit_scale() -> f64 {
+    1.0
+}
+
+fn vec3(v: [f64; 3]) -> Vec3 {
+    Vec3::new(v[0], v[1], v[2])
+}
+
+// The benchmark's own scene, identical to scenes/default.json so no file is needed to run it
+const DEFAULT_SCENE: &str = r#"{
+  "camera": { "origin": [0, 0.5, 1], "direction": [0, -0.3, -1.5] },
+  "sky": { "horizon": [1, 1, 1], "zenith": [0.5, 0.7, 1] },
+  "materials": [
+    { "name": "ground", "type": "diffuse", "color": [0.8, 0.8, 0.8] },
+    { "name": "chrome", "type": "mirror", "color": [0.9, 0.9, 0.9] },
+    { "name": "green", "type": "diffuse", "color": [0.1, 0.8, 0.1] },
+    { "name": "blue", "type": "diffuse", "color": [0.1, 0.1, 0.8] }
+  ],
+  "spheres": [
+    { "center": [0, -1001, -5], "radius": 1000, "material": "ground" },
+    { "center": [0, 0, -5], "radius": 1, "material": "chrome" },
+    { "center": [2, 0, -6], "radius": 1, "material": "green" },
+    { "center": [-2, 0, -6], "radius": 1, "material": "blue" }
+  ],
+  "sphere_grid": {
+    "columns": 50, "rows": 40, "seed": 2024, "origin": [-12.5, -1, -2], "spacing": 0.5,
+    "jitter": 0.3, "radius_min": 0.1, "radius_jitter": 0.1, "mirror_fraction": 0.3
+  }
+}"#;
+
+fn load_scene_file(path: Option<&str>) -> SceneFile {
+    let text = match path {
+        Some(path) => std::fs::read_to_string(path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e)),
+        None => DEFAULT_SCENE.to_string(),
+    };
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("Invalid scene file {}: {}", path.unwrap_or("(built-in)"), e))
+}
+
+// 1-based vertex index of one face corner ("7", "7/3", "7//2" or "7/3/2"); negative
+// indices count back from the most recent vertex
+fn obj_vertex_index(corner: &str, vertex_count: usize) -> Option<usize> {
+    let index: i64 = corner.split('/').next()?.parse().ok()?;
+    let resolved = if index < 0 { vertex_count as i64 + index } else { index - 1 };
+    (0..vertex_count as i64).contains(&resolved).then_some(resolved as usize)
+}
+
+// Positions and faces only: polygons are fanned into triangles, and normals, texture
+// coordinates, groups and materials are ignored
+fn load_obj(path: &Path) -> (Vec<Vec3>, Vec<[usize; 3]>) {
+    let text = std::fs::read_to_string(path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+    let mut vertices = Vec::new();
+    let mut faces = Vec::new();
+    for (line_number, line) in text.lines().enumerate() {
+        let fail = |what: &str| -> ! { panic!("{}:{}: {}", path.display(), line_number + 1, what) };
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("v") => {
+                let c: Vec<f64> = fields.take(3).map(|f| f.parse().unwrap_or_else(|_| fail("bad vertex"))).collect();
+                if c.len() != 3 {
+                    fail("vertex needs three coordinates");
+                }
+                vertices.push(Vec3::new(c[0], c[1], c[2]));
+            }
+            Some("f") => {
+                let corners: Vec<usize> = fields
+                    .map(|f| obj_vertex_index(f, vertices.len()).unwrap_or_else(|| fail("bad face index")))
+                    .collect();
+                if corners.len() < 3 {
+                    fail("face needs at least three vertices");
+                }
+                for k in 1..corners.len() - 1 {
+                    faces.push([corners[0], corners[k], corners[k + 1]]);
+                }
             }
+            _ => {}
+        }
+    }
+    (vertices, faces)
+}
+
+fn build_scene(file: &SceneFile, base_dir: &Path) -> Scene {
+    let materials: HashMap<&str, &MaterialFile> = file.materials.iter().map(|m| (m.name.as_str(), m)).collect();
+    let lookup = |name: &str| *materials.get(name).unwrap_or_else(|| panic!("Unknown material {}", name));
+    let mut spheres: Vec<Sphere> = file.spheres.iter().map(|s| {
+        let material = lookup(&s.material);
+        Sphere { center: vec3(s.center), radius: s.radius, color: vec3(material.color), material: material.kind }
+    }).collect();
+
+    if let Some(grid) = &file.sphere_grid {
+        let mut rng = Rng::new(grid.seed);
+        for gz in 0..grid.rows {
+            for gx in 0..grid.columns {
+                let radius = grid.radius_min + grid.radius_jitter * rng.next_f64();
+                let x = grid.origin[0] + gx as f64 * grid.spacing + grid.jitter * rng.next_f64();
+                let z = grid.origin[2] - gz as f64 * grid.spacing - grid.jitter * rng.next_f64();
+                let color = Vec3::new(rng.next_f64(), rng.next_f64(), rng.next_f64());
+                let material = if rng.next_f64() < grid.mirror_fraction { Material::Reflective } else { Material::Diffuse };
+                spheres.push(Sphere { center: Vec3::new(x, grid.origin[1] + radius, z), radius, color, material });
+            }
+        }
+    }
+
+    let mut triangles = Vec::new();
+    for mesh in &file.meshes {
+        let material = lookup(&mesh.material);
+        let (vertices, faces) = load_obj(&base_dir.join(&mesh.path));
+        let placed: Vec<Vec3> = vertices.iter().map(|&v| v * mesh.scale + vec3(mesh.translate)).collect();
+        triangles.extend(faces.iter().filter_map(|&[a, b, c]| {
+            Triangle::new(placed[a], placed[b], placed[c], vec3(material.color), material.kind)
+        }));
+    }
+    assert!(!spheres.is_empty() || !triangles.is_empty(), "Scene is empty");
+
+    let camera = Camera::new(vec3(file.camera.origin), vec3(file.camera.direction));
+    let sky = Sky { horizon: vec3(file.sky.horizon), zenith: vec3(file.sky.zenith) };
+    Scene::new(spheres, triangles, camera, sky)
+}
+
+fn sky(direction: &Vec3, sky: &Sky) -> Vec3 {
+    let t = 0.5 * (direction.normalize().y + 1.0);
+    sky.horizon * (1.0 - t) + sky.zenith * t
+}
+
+fn trace_path(origin: &Vec3, direction: &Vec3, scene: &Scene, rng: &mut Rng, max_depth: usize) -> Vec3 {
+    let mut origin = *origin;
+    let mut direction = *direction;
+    let mut throughput = Vec3::new(1.0, 1.0, 1.0);
+
+    for _ in 0..max_depth {
+        let (t, surface) = match scene.intersect(&origin, &direction) {
+            Some(hit) => hit,
+            None => return throughput * sky(&direction, &scene.sky),
+        };
+
+        let hit_point = origin + direction * t;
+        let normal = surface.normal(&hit_point, &direction);
+        let (color, material) = match surface {
+            Surface::Sphere(s) => (s.color, s.material),
+            Surface::Triangle(tri) => (tri.color, tri.material),
+        };
+        direction = match material {
+            Material::Diffuse => {
+                let scattered = normal + rng.unit_vector();
+                if scattered.dot(&scattered) < 1e-12 { normal } else { scattered }
+            }
+            Material::Reflective => direction.reflect(&normal),
+            Material::Emissive => return throughput * color,
+        };
+        throughput = throughput * color;
+        origin = hit_point;
+    }
+
+    Vec3::new(0.0, 0.0, 0.0) // Path exceeded the bounce budget
+}
+
+// The fraction of AO_RAYS cosine-weighted rays from the first hit that travel AO_DISTANCE
+// unblocked, in gray; a miss is fully open. Materials and the sky play no part, so the cost is
+// the generator and the intersection tests
+fn ambient_occlusion(origin: &Vec3, direction: &Vec3, scene: &Scene, rng: &mut Rng) -> Vec3 {
+    let (t, surface) = match scene.intersect(origin, direction) {
+        Some(hit) => hit,
+        None => return Vec3::splat(1.0),
+    };
+    let hit_point = *origin + *direction * t;
+    let normal = surface.normal(&hit_point, direction);
+    let open = (0..AO_RAYS).filter(|_| !scene.occluded(&hit_point, &rng.cosine_direction(&normal), AO_DISTANCE)).count();
+    Vec3::splat(open as f64 / AO_RAYS as f64)
+}
+
+// What each camera sample computes
+#[derive(Clone, Copy)]
+enum Mode {
+    Path { max_depth: usize },
+    AmbientOcclusion,
+}
+
+fn render_pixel(x: usize, y: usize, scene: &Scene, mode: Mode) -> Vec3 {
+    let mut rng = Rng::new((y * WIDTH + x) as u64);
+    let aspect = WIDTH as f64 / HEIGHT as f64;
+    let camera = &scene.camera;
+    let mut color = Vec3::new(0.0, 0.0, 0.0);
+    for _ in 0..SAMPLES {
+        let u = (x as f64 + rng.next_f64()) / (WIDTH as f64) - 0.5;
+        let v = 0.5 - (y as f64 + rng.next_f64()) / (HEIGHT as f64);
+        let direction = (camera.direction + camera.right * (u * 2.0 * aspect) + camera.up * (v * 2.0)).normalize();
+        let sample_color = match mode {
+            Mode::Path { max_depth } => trace_path(&camera.origin, &direction, scene, &mut rng, max_depth),
+            Mode::AmbientOcclusion => ambient_occlusion(&camera.origin, &direction, scene, &mut rng),
+        };
+        color += sample_color;
+    }
+    color * (1.0 / SAMPLES as f64)
+}
+
+struct Tile {
+    x0: usize,
+    y0: usize,
+    width: usize,
+    pixels: Vec<Vec3>, // row-major within the tile
+    seconds: f64,
+}
+
+fn render_tile(index: usize, scene: &Scene, mode: Mode) -> Tile {
+    let start = Instant::now();
+    let tiles_x = WIDTH.div_ceil(TILE_SIZE);
+    let x0 = index % tiles_x * TILE_SIZE;
+    let y0 = index / tiles_x * TILE_SIZE;
+    let width = TILE_SIZE.min(WIDTH - x0);
+    let height = TILE_SIZE.min(HEIGHT - y0);
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in y0..y0 + height {
+        for x in x0..x0 + width {
+            pixels.push(render_pixel(x, y, scene, mode));
+        }
+    }
+    Tile { x0, y0, width, pixels, seconds: start.elapsed().as_secs_f64() }
+}
+
+// Each tile is one rayon task, so idle workers steal whole tiles rather than single pixels.
+// Returns the frame and each tile's render time in seconds
+fn render(scene: &Scene, mode: Mode) -> (Vec<Vec3>, Vec<f64>) {
+    let tile_count = WIDTH.div_ceil(TILE_SIZE) * HEIGHT.div_ceil(TILE_SIZE);
+    let tiles: Vec<Tile> = (0..tile_count).into_par_iter().map(|i| render_tile(i, scene, mode)).collect();
+
+    let mut image = vec![Vec3::new(0.0, 0.0, 0.0); WIDTH * HEIGHT];
+    for tile in &tiles {
+        for (row, pixels) in tile.pixels.chunks(tile.width).enumerate() {
+            let offset = (tile.y0 + row) * WIDTH + tile.x0;
+            image[offset..offset + tile.width].copy_from_slice(pixels);
+        }
+    }
+    (image, tiles.iter().map(|t| t.seconds).collect())
+}
+
+// Spread of tile costs: a max far above the mean means a few tiles (mirrors, dense geometry)
+// dominate and finer tiles would balance better
+fn print_tile_stats(tile_seconds: &[f64]) {
+    let mut sorted = tile_seconds.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mean = sorted.iter().sum::<f64>() / sorted.len() as f64;
+    let max = sorted[sorted.len() - 1];
+    eprintln!("Tiles: {} of {}x{}, ms per tile: min {:.3}, median {:.3}, mean {:.3}, max {:.3} (max/mean {:.2})",
+              sorted.len(), TILE_SIZE, TILE_SIZE, 1e3 * sorted[0], 1e3 * sorted[sorted.len() / 2], 1e3 * mean,
+              1e3 * max, max / mean);
+}
+
+// Gamma 2, clamped and rounded to 8 bits per channel
+fn to_rgb8(image: &[Vec3]) -> Vec<u8> {
+    let mut rgb = Vec::with_capacity(image.len() * 3);
+    for c in image {
+        for v in [c.x, c.y, c.z] {
+            rgb.push((v.max(0.0).sqrt().min(1.0) * 255.0 + 0.5) as u8);
         }
     }
-    
-    if let Some(sphere) = hit_sphere {
-        let hit_point = origin.add(&direction.mul(closest_t));
-        let normal = hit_point.sub(&sphere.center).normalize();
-        let light_dir = Vec3::new(1.0, 1.0, 1.0).normalize();
-        let diffuse = normal.dot(&light_dir).max(0.0);
-        sphere.color.mul(diffuse)
-    } else {
-        Vec3::new(0.2, 0.3, 0.4) // Background color
+    rgb
+}
+
+fn write_image(path: &str, rgb: &[u8]) {
+    let file = File::create(path).expect("Failed to create image file");
+    let mut out = BufWriter::new(file);
+    if path.ends_with(".ppm") {
+        write!(out, "P6\n{} {}\n255\n", WIDTH, HEIGHT).expect("Failed to write PPM");
+        out.write_all(rgb).expect("Failed to write PPM");
+        return;
+    }
+    let mut encoder = png::Encoder::new(out, WIDTH as u32, HEIGHT as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().expect("PNG header failed");
+    writer.write_image_data(rgb).expect("PNG encode failed");
+}
+
+fn read_png(path: &str) -> Vec<u8> {
+    let file = File::open(path).unwrap_or_else(|e| panic!("Failed to open {}: {}", path, e));
+    let mut reader = png::Decoder::new(BufReader::new(file)).read_info().expect("PNG header invalid");
+    let mut pixels = vec![0u8; reader.output_buffer_size().expect("PNG too large")];
+    let info = reader.next_frame(&mut pixels).expect("PNG decode failed");
+    assert!(
+        (info.width as usize, info.height as usize, info.color_type, info.bit_depth)
+            == (WIDTH, HEIGHT, png::ColorType::Rgb, png::BitDepth::Eight),
+        "{} is not a {}x{} 8-bit RGB image", path, WIDTH, HEIGHT
+    );
+    pixels.truncate(info.buffer_size());
+    pixels
+}
+
+// Path tracing is chaotic: a last-bit difference can send one bounce elsewhere, so a few
+// pixels may legitimately differ a lot. The frame passes when nearly all pixels are close
+fn verify(rgb: &[u8], path: &str) -> bool {
+    let golden = read_png(path);
+    let mut mismatches = 0;
+    let mut max_difference = 0;
+    for (a, b) in rgb.chunks(3).zip(golden.chunks(3)) {
+        let difference = a.iter().zip(b).map(|(&x, &y)| x.abs_diff(y)).max().unwrap();
+        max_difference = max_difference.max(difference);
+        if difference > PIXEL_TOLERANCE {
+            mismatches += 1;
+        }
+    }
+    let fraction = mismatches as f64 / (WIDTH * HEIGHT) as f64;
+    let passed = fraction <= MAX_MISMATCH_FRACTION;
+    eprintln!("Verify: {} pixels ({:.4}%) differ from {} by more than {}/255 (max {}), {}",
+              mismatches, 100.0 * fraction, path, PIXEL_TOLERANCE, max_difference,
+              if passed { "PASS" } else { "FAIL" });
+    passed
+}
+
+// A value is taken only when the next argument is not another flag
+fn parse_optional_path(args: &[String], name: &str, default: &str) -> Option<String> {
+    args.iter().position(|a| a == name).map(|i| match args.get(i + 1) {
+        Some(v) if !v.starts_with("--") => v.clone(),
+        _ => default.to_string(),
+    })
+}
+
+fn parse_max_depth() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--depth") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .expect("--depth expects a positive integer"),
+        None => DEFAULT_MAX_DEPTH,
+    }
+}
+
+fn parse_mode() -> Mode {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--mode") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("path") => Mode::Path { max_depth: parse_max_depth() },
+            Some("ao") => {
+                assert!(!args.iter().any(|a| a == "--depth"), "--depth applies to --mode path only");
+                Mode::AmbientOcclusion
+            }
+            _ => panic!("--mode expects path or ao"),
+        },
+        None => Mode::Path { max_depth: parse_max_depth() },
     }
 }
 
 fn main() {
-    let spheres = Arc::new(vec![
-        Sphere { center: Vec3::new(0.0, 0.0, -5.0), radius: 1.0, color: Vec3::new(1.0, 0.0, 0.0) },
-        Sphere { center: Vec3::new(2.0, 0.0, -6.0), radius: 1.0, color: Vec3::new(0.0, 1.0, 0.0) },
-        Sphere { center: Vec3::new(-2.0, 0.0, -6.0), radius: 1.0, color: Vec3::new(0.0, 0.0, 1.0) },
-        Sphere { center: Vec3::new(0.0, -1001.0, -5.0), radius: 1000.0, color: Vec3::new(0.8, 0.8, 0.8) },
-    ]);
-    
+    let mut measure = bench_measure::from_env();
+    let args: Vec<String> = std::env::args().collect();
+    let mode = parse_mode();
+    let output_path = args.iter().position(|a| a == "--output")
+        .map(|i| args.get(i + 1).cloned().expect("--output expects a file path"));
+    let golden_path = match mode {
+        Mode::Path { .. } => GOLDEN_PATH,
+        Mode::AmbientOcclusion => GOLDEN_AO_PATH,
+    };
+    let verify_path = parse_optional_path(&args, "--verify", golden_path);
+    let scene_path = args.iter().position(|a| a == "--scene")
+        .map(|i| args.get(i + 1).cloned().expect("--scene expects a file path"));
+    let base_dir = scene_path.as_deref().and_then(|p| Path::new(p).parent()).unwrap_or(Path::new("."));
+    let scene = build_scene(&load_scene_file(scene_path.as_deref()), base_dir);
+
     // Warm-up
-    let spheres_warmup = Arc::clone(&spheres);
-    let _: Vec<_> = (0..100).into_par_iter().map(move |_| {
-        let origin = Vec3::new(0.0, 0.0, 0.0);
-        let direction = Vec3::new(0.0, 0.0, -1.0).normalize();
-        trace_ray(&origin, &direction, &spheres_warmup)
-    }).collect();
-    
+    let _: Vec<_> = (0..WIDTH).into_par_iter().map(|x| render_pixel(x, HEIGHT / 2, &scene, mode)).collect();
+
     // Benchmark
-    let start = Instant::now();
-    
-    let spheres_render = Arc::clone(&spheres);
-    let image: Vec<Vec3> = (0..HEIGHT).into_par_iter().flat_map(|y| {
-        let spheres_row = Arc::clone(&spheres_render);
-        (0..WIDTH).into_par_iter().map(move |x| {
-            let mut color = Vec3::new(0.0, 0.0, 0.0);
-            for _ in 0..SAMPLES {
-                let u = (x as f64) / (WIDTH as f64) - 0.5;
-                let v = 0.5 - (y as f64) / (HEIGHT as f64);
-                let origin = Vec3::new(0.0, 0.0, 0.0);
-                let direction = Vec3::new(u * 2.0, v * 2.0, -1.0).normalize();
-                let sample_color = trace_ray(&origin, &direction, &spheres_row);
-                color = color.add(&sample_color);
-            }
-            color.mul(1.0 / SAMPLES as f64)
-        }).collect::<Vec<_>>()
-    }).collect();
-    
-    let duration = start.elapsed();
-    
+    measure.start();
+    let (image, tile_seconds) = render(&scene, mode);
+    measure.stop();
+
     // Checksum
-    let checksum: f64 = image.iter().take(100).map(|c| c.x + c.y + c.z).sum();
-    
-    println!("{:.6}", duration.as_secs_f64());
+    let checksum: f64 = image.iter().map(|c| c.x + c.y + c.z).sum();
+
+    measure.report();
+    eprintln!("Scene: {}", scene_path.as_deref().unwrap_or("built-in"));
+    eprintln!("Spheres: {}, triangles: {}, BVH nodes: {}", scene.spheres.len(), scene.triangles.len(), scene.bvh_nodes());
+    match mode {
+        Mode::Path { max_depth } => eprintln!("Mode: path tracing, max depth: {}", max_depth),
+        Mode::AmbientOcclusion => eprintln!("Mode: ambient occlusion, {} rays per sample within {}", AO_RAYS, AO_DISTANCE),
+    }
+    print_tile_stats(&tile_seconds);
     eprintln!("Checksum: {}", checksum);
+
+    if output_path.is_none() && verify_path.is_none() {
+        return;
+    }
+    let rgb = to_rgb8(&image);
+    if let Some(path) = output_path {
+        write_image(&path, &rgb);
+        eprintln!("Wrote {}", path);
+    }
+    if let Some(path) = verify_path {
+        if !verify(&rgb, &path) {
+            std::process::exit(1);
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64, z: f64) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+
+    fn sphere(center: Vec3, radius: f64) -> Sphere {
+        Sphere { center, radius, color: v(1.0, 1.0, 1.0), material: Material::Diffuse }
+    }
+
+    #[test]
+    fn sphere_hits_near_then_far() {
+        let s = sphere(v(0.0, 0.0, -5.0), 1.0);
+        let forward = v(0.0, 0.0, -1.0);
+        assert_eq!(s.intersect(&Vec3::zero(), &forward, f64::INFINITY), Some(4.0));
+        // From inside only the far side is ahead
+        assert_eq!(s.intersect(&v(0.0, 0.0, -5.0), &forward, f64::INFINITY), Some(1.0));
+        assert_eq!(s.intersect(&Vec3::zero(), &forward, 3.0), None);
+        assert_eq!(s.intersect(&v(2.0, 0.0, 0.0), &forward, f64::INFINITY), None);
+        assert_eq!(s.intersect(&Vec3::zero(), &-forward, f64::INFINITY), None);
+    }
+
+    #[test]
+    fn triangle_hits_inside_its_edges() {
+        let white = v(1.0, 1.0, 1.0);
+        let t = Triangle::new(v(0.0, 0.0, -2.0), v(1.0, 0.0, -2.0), v(0.0, 1.0, -2.0), white, Material::Diffuse).unwrap();
+        assert_eq!(t.normal, v(0.0, 0.0, 1.0));
+        let forward = v(0.0, 0.0, -1.0);
+        assert_eq!(t.intersect(&v(0.25, 0.25, 0.0), &forward, f64::INFINITY), Some(2.0));
+        assert_eq!(t.intersect(&v(0.75, 0.75, 0.0), &forward, f64::INFINITY), None);
+        assert_eq!(t.intersect(&v(0.25, 0.25, 0.0), &v(1.0, 0.0, 0.0), f64::INFINITY), None);
+        assert!(Triangle::new(v(0.0, 0.0, 0.0), v(1.0, 1.0, 1.0), v(2.0, 2.0, 2.0), white, Material::Diffuse).is_none());
+    }
+
+    #[test]
+    fn bvh_finds_the_nearest_sphere() {
+        let scene = build_scene(&load_scene_file(None), Path::new("."));
+        assert_eq!(scene.spheres.len(), 4 + 50 * 40);
+        assert!(scene.triangles.is_empty());
+
+        let mut rng = Rng::new(1);
+        for _ in 0..2000 {
+            let origin = v(rng.next_f64() * 4.0 - 2.0, rng.next_f64(), 1.0);
+            let direction = rng.unit_vector();
+            let brute = scene.spheres.iter().enumerate()
+                .filter_map(|(i, s)| s.intersect(&origin, &direction, f64::INFINITY).map(|t| (t, i)))
+                .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            match (scene.intersect(&origin, &direction), brute) {
+                (Some((t, Surface::Sphere(s))), Some((brute_t, i))) => {
+                    assert_eq!(t, brute_t);
+                    assert!(std::ptr::eq(s, &scene.spheres[i]));
+                }
+                (None, None) => {}
+                _ => panic!("BVH and brute force disagree for {:?} {:?}", origin, direction),
+            }
+        }
+    }
+
+    #[test]
+    fn cosine_directions_stay_in_the_hemisphere() {
+        let mut rng = Rng::new(3);
+        for normal in [v(0.0, 0.0, 1.0), v(0.0, 0.0, -1.0), v(0.0, 1.0, 0.0), rng.unit_vector(), rng.unit_vector()] {
+            let (tangent, bitangent) = orthonormal_basis(&normal);
+            for (a, b) in [(tangent, bitangent), (tangent, normal), (bitangent, normal)] {
+                assert!(a.dot(&b).abs() < 1e-12);
+            }
+            assert!((tangent.length() - 1.0).abs() < 1e-12 && (bitangent.length() - 1.0).abs() < 1e-12);
+            // The mean cosine of a cosine-weighted hemisphere is 2/3
+            let mean_cosine = (0..20000).map(|_| {
+                let direction = rng.cosine_direction(&normal);
+                assert!((direction.length() - 1.0).abs() < 1e-9);
+                let cosine = direction.dot(&normal);
+                assert!(cosine >= 0.0);
+                cosine
+            }).sum::<f64>() / 20000.0;
+            assert!((mean_cosine - 2.0 / 3.0).abs() < 0.01, "{}", mean_cosine);
+        }
+    }
+
+    #[test]
+    fn occlusion_agrees_with_the_nearest_hit() {
+        let scene = build_scene(&load_scene_file(None), Path::new("."));
+        let mut rng = Rng::new(2);
+        for _ in 0..2000 {
+            let origin = v(rng.next_f64() * 4.0 - 2.0, rng.next_f64() - 0.9, rng.next_f64() * -4.0 - 2.0);
+            let direction = rng.unit_vector();
+            let nearest = scene.intersect(&origin, &direction).map(|(t, _)| t);
+            assert_eq!(scene.occluded(&origin, &direction, AO_DISTANCE), nearest.is_some_and(|t| t < AO_DISTANCE));
+        }
+    }
+
+    #[test]
+    fn obj_indices_resolve_forms_and_negatives() {
+        assert_eq!(obj_vertex_index("1", 3), Some(0));
+        assert_eq!(obj_vertex_index("3/1/2", 3), Some(2));
+        assert_eq!(obj_vertex_index("2//5", 3), Some(1));
+        assert_eq!(obj_vertex_index("-1", 3), Some(2));
+        assert_eq!(obj_vertex_index("-3", 3), Some(0));
+        for bad in ["0", "4", "-4", "x", ""] {
+            assert_eq!(obj_vertex_index(bad, 3), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn obj_polygons_fan_into_triangles() {
+        let path = std::env::temp_dir().join(format!("ray_tracer_quad_{}.obj", std::process::id()));
+        std::fs::write(&path, "# quad\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvn 0 0 1\nf 1 2 3 4\nf -1 -2 -3\n").unwrap();
+        let (vertices, faces) = load_obj(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(vertices.len(), 4);
+        assert_eq!(faces, [[0, 1, 2], [0, 2, 3], [3, 2, 1]]);
+    }
+}
@@ -0,0 +1,277 @@
+use bench_math::Vec3;
+use std::time::Instant;
+use wide::f32x8;
+
+const TRIANGLES: usize = 256;
+const DEFAULT_RAYS: usize = 1 << 20; // 1M rays x 256 triangles = 268M tests per variant
+const WARMUP_RAYS: usize = 1 << 16;
+const LANES: usize = 8;
+const T_MIN: f32 = 1e-4;
+const NO_HIT: u32 = u32::MAX;
+const SEED: u64 = 0x7269_6e74_6572_7365;
+
+// Stored as one corner plus two edges, the form the Moller-Trumbore test wants
+#[derive(Clone, Copy)]
+struct Triangle {
+    v0: Vec3<f32>,
+    edge1: Vec3<f32>,
+    edge2: Vec3<f32>,
+}
+
+// Structure-of-arrays, so a packet of LANES rays loads as six vectors
+struct Rays {
+    ox: Vec<f32>,
+    oy: Vec<f32>,
+    oz: Vec<f32>,
+    dx: Vec<f32>,
+    dy: Vec<f32>,
+    dz: Vec<f32>,
+}
+
+impl Rays {
+    fn origin(&self, i: usize) -> Vec3<f32> {
+        Vec3::new(self.ox[i], self.oy[i], self.oz[i])
+    }
+
+    fn direction(&self, i: usize) -> Vec3<f32> {
+        Vec3::new(self.dx[i], self.dy[i], self.dz[i])
+    }
+}
+
+// The nearest triangle a ray hit, or NO_HIT with t = infinity
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Hit {
+    t: f32,
+    triangle: u32,
+}
+
+// SplitMix64; the top 24 bits become a float in [0, 1) exactly, in both languages
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn range(&mut self, lo: f32, hi: f32) -> f32 {
+        lo + (hi - lo) * ((self.next() >> 40) as f32 * (1.0 / 16_777_216.0))
+    }
+
+    fn point(&mut self, half: f32) -> Vec3<f32> {
+        let x = self.range(-half, half);
+        let y = self.range(-half, half);
+        let z = self.range(-half, half);
+        Vec3::new(x, y, z)
+    }
+}
+
+// Small triangles scattered through the [-1, 1] cube, each corner within 0.3 of its center
+fn make_triangles(rng: &mut Rng, count: usize) -> Vec<Triangle> {
+    (0..count)
+        .map(|_| {
+            let center = rng.point(1.0);
+            let v0 = center + rng.point(0.3);
+            let v1 = center + rng.point(0.3);
+            let v2 = center + rng.point(0.3);
+            Triangle { v0, edge1: v1 - v0, edge2: v2 - v0 }
+        })
+        .collect()
+}
+
+// Origins on the z = -3 plane aimed at points in the cube. Directions are left unnormalized,
+// so t is a fraction of the way to the target and no square root enters the results
+fn make_rays(rng: &mut Rng, count: usize) -> Rays {
+    let mut rays = Rays { ox: vec![], oy: vec![], oz: vec![], dx: vec![], dy: vec![], dz: vec![] };
+    for _ in 0..count {
+        let ox = rng.range(-2.0, 2.0);
+        let oy = rng.range(-2.0, 2.0);
+        let direction = rng.point(1.0) - Vec3::new(ox, oy, -3.0);
+        rays.ox.push(ox);
+        rays.oy.push(oy);
+        rays.oz.push(-3.0);
+        rays.dx.push(direction.x);
+        rays.dy.push(direction.y);
+        rays.dz.push(direction.z);
+    }
+    rays
+}
+
+// Moller-Trumbore with an early exit at each failed test; triangles are two-sided
+fn intersect(triangle: &Triangle, origin: &Vec3<f32>, direction: &Vec3<f32>, t_max: f32) -> Option<f32> {
+    let p = direction.cross(&triangle.edge2);
+    let det = triangle.edge1.dot(&p);
+    if det == 0.0 {
+        return None; // ray parallel to the plane
+    }
+    let inv_det = 1.0 / det;
+    let s = *origin - triangle.v0;
+    let u = s.dot(&p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(&triangle.edge1);
+    let v = direction.dot(&q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = triangle.edge2.dot(&q) * inv_det;
+    if t > T_MIN && t < t_max { Some(t) } else { None }
+}
+
+fn nearest_scalar(triangles: &[Triangle], rays: &Rays, hits: &mut [Hit]) {
+    for (i, hit) in hits.iter_mut().enumerate() {
+        let (origin, direction) = (rays.origin(i), rays.direction(i));
+        *hit = Hit { t: f32::INFINITY, triangle: NO_HIT };
+        for (index, triangle) in triangles.iter().enumerate() {
+            if let Some(t) = intersect(triangle, &origin, &direction, hit.t) {
+                *hit = Hit { t, triangle: index as u32 };
+            }
+        }
+    }
+}
+
+// The same arithmetic in the same order for LANES rays against one triangle, with no branches:
+// every lane computes u, v and t and a mask keeps the hits. A zero determinant makes u infinite
+// or NaN, which fails the range test, so parallel rays miss here just as they do in the scalar test
+fn nearest_packet(triangles: &[Triangle], rays: &Rays, hits: &mut [Hit]) {
+    let load = |v: &[f32], base: usize| f32x8::new(v[base..base + LANES].try_into().unwrap());
+    let (zero, one, t_min) = (f32x8::splat(0.0), f32x8::splat(1.0), f32x8::splat(T_MIN));
+    for (packet, out) in hits.chunks_exact_mut(LANES).enumerate() {
+        let base = packet * LANES;
+        let (ox, oy, oz) = (load(&rays.ox, base), load(&rays.oy, base), load(&rays.oz, base));
+        let (dx, dy, dz) = (load(&rays.dx, base), load(&rays.dy, base), load(&rays.dz, base));
+        let mut best_t = f32x8::splat(f32::INFINITY);
+        let mut best_id = f32x8::splat(-1.0); // triangle indices are exact in f32
+        for (index, triangle) in triangles.iter().enumerate() {
+            let (e1x, e1y, e1z) = (f32x8::splat(triangle.edge1.x), f32x8::splat(triangle.edge1.y), f32x8::splat(triangle.edge1.z));
+            let (e2x, e2y, e2z) = (f32x8::splat(triangle.edge2.x), f32x8::splat(triangle.edge2.y), f32x8::splat(triangle.edge2.z));
+            let px = dy * e2z - dz * e2y;
+            let py = dz * e2x - dx * e2z;
+            let pz = dx * e2y - dy * e2x;
+            let inv_det = one / (e1x * px + e1y * py + e1z * pz);
+            let sx = ox - f32x8::splat(triangle.v0.x);
+            let sy = oy - f32x8::splat(triangle.v0.y);
+            let sz = oz - f32x8::splat(triangle.v0.z);
+            let u = (sx * px + sy * py + sz * pz) * inv_det;
+            let qx = sy * e1z - sz * e1y;
+            let qy = sz * e1x - sx * e1z;
+            let qz = sx * e1y - sy * e1x;
+            let v = (dx * qx + dy * qy + dz * qz) * inv_det;
+            let t = (e2x * qx + e2y * qy + e2z * qz) * inv_det;
+            let mask = u.simd_ge(zero) & u.simd_le(one) & v.simd_ge(zero) & (u + v).simd_le(one)
+                & t.simd_gt(t_min) & t.simd_lt(best_t);
+            best_t = mask.bitselect(t, best_t);
+            best_id = mask.bitselect(f32x8::splat(index as f32), best_id);
+        }
+        for ((hit, t), id) in out.iter_mut().zip(best_t.to_array()).zip(best_id.to_array()) {
+            *hit = Hit { t, triangle: if id < 0.0 { NO_HIT } else { id as u32 } };
+        }
+    }
+}
+
+fn parse_rays() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--rays") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|&n: &usize| n > 0 && n % LANES == 0)
+            .unwrap_or_else(|| panic!("--rays expects a positive multiple of {}", LANES)),
+        None => DEFAULT_RAYS,
+    }
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let count = parse_rays();
+    let mut rng = Rng(SEED);
+    let triangles = make_triangles(&mut rng, TRIANGLES);
+    let rays = make_rays(&mut rng, count);
+    let mut scalar = vec![Hit { t: 0.0, triangle: NO_HIT }; count];
+    let mut packet = scalar.clone();
+
+    // Warm-up
+    let warmup = make_rays(&mut Rng(SEED ^ 1), WARMUP_RAYS);
+    nearest_scalar(&triangles, &warmup, &mut scalar[..WARMUP_RAYS.min(count)]);
+    nearest_packet(&triangles, &warmup, &mut packet[..WARMUP_RAYS.min(count)]);
+
+    // Benchmark
+    let start = Instant::now();
+    measure.start();
+    nearest_scalar(&triangles, &rays, &mut scalar);
+    measure.stop();
+    let scalar_time = start.elapsed();
+    let start = Instant::now();
+    measure.start();
+    nearest_packet(&triangles, &rays, &mut packet);
+    measure.stop();
+    let packet_time = start.elapsed();
+
+    let mismatches = scalar.iter().zip(&packet).filter(|(a, b)| a.triangle != b.triangle || a.t.to_bits() != b.t.to_bits()).count();
+    if mismatches > 0 {
+        eprintln!("Packet results differ from scalar on {} of {} rays", mismatches, count);
+        std::process::exit(1);
+    }
+
+    let hit_count = scalar.iter().filter(|h| h.triangle != NO_HIT).count();
+    let t_sum: f64 = scalar.iter().filter(|h| h.triangle != NO_HIT).map(|h| h.t as f64).sum();
+    let checksum: u64 = scalar.iter().filter(|h| h.triangle != NO_HIT).map(|h| h.triangle as u64 + 1).sum();
+    let tests = (count * TRIANGLES) as f64;
+
+    measure.report();
+    eprintln!("{}", bench_cpu::report());
+    eprintln!("{}", bench_cpu::path("intersect", bench_cpu::Path::Scalar, "f32, one ray at a time"));
+    eprintln!("{}", bench_cpu::path("intersect_packet", bench_cpu::Path::Simd, &format!("f32x8 packets on {}", bench_cpu::simd_isa())));
+    eprintln!("Scene: {} triangles, {} rays, {:.2}G ray-triangle tests per variant", TRIANGLES, count, tests / 1e9);
+    for (name, time) in [("scalar", scalar_time), ("packet", packet_time)] {
+        let seconds = time.as_secs_f64();
+        eprintln!("{}: {:.3}s, {:.2}M rays/s, {:.3}G tests/s", name, seconds, count as f64 / seconds / 1e6, tests / seconds / 1e9);
+    }
+    eprintln!("Packet speedup: {:.2}x", scalar_time.as_secs_f64() / packet_time.as_secs_f64());
+    eprintln!("Hits: {} of {} rays ({:.2}%), mean nearest t {:.6}; packet and scalar agree on every ray",
+              hit_count, count, 100.0 * hit_count as f64 / count as f64, t_sum / hit_count.max(1) as f64);
+    eprintln!("Checksum: {}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_triangle() -> Triangle {
+        Triangle { v0: Vec3::zero(), edge1: Vec3::new(1.0, 0.0, 0.0), edge2: Vec3::new(0.0, 1.0, 0.0) }
+    }
+
+    #[test]
+    fn intersects_the_unit_triangle() {
+        let triangle = unit_triangle();
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        assert_eq!(intersect(&triangle, &Vec3::new(0.25, 0.25, 2.0), &down, f32::INFINITY), Some(2.0));
+        // Two-sided: from below, with an unnormalized direction
+        assert_eq!(intersect(&triangle, &Vec3::new(0.25, 0.25, -1.0), &Vec3::new(0.0, 0.0, 4.0), f32::INFINITY), Some(0.25));
+        // Outside the u + v <= 1 edge, behind the origin, past t_max, and parallel to the plane
+        assert_eq!(intersect(&triangle, &Vec3::new(0.75, 0.75, 2.0), &down, f32::INFINITY), None);
+        assert_eq!(intersect(&triangle, &Vec3::new(0.25, 0.25, -2.0), &down, f32::INFINITY), None);
+        assert_eq!(intersect(&triangle, &Vec3::new(0.25, 0.25, 2.0), &down, 1.5), None);
+        assert_eq!(intersect(&triangle, &Vec3::new(0.25, 0.25, 2.0), &Vec3::new(1.0, 0.0, 0.0), f32::INFINITY), None);
+    }
+
+    #[test]
+    fn packets_match_scalar() {
+        let mut rng = Rng(SEED);
+        let mut triangles = make_triangles(&mut rng, 64);
+        // A degenerate triangle, whose zero determinant every ray must miss in both variants
+        triangles.push(Triangle { v0: Vec3::zero(), edge1: Vec3::new(1.0, 1.0, 0.0), edge2: Vec3::new(2.0, 2.0, 0.0) });
+        let rays = make_rays(&mut rng, 4096);
+        let mut scalar = vec![Hit { t: 0.0, triangle: NO_HIT }; rays.ox.len()];
+        let mut packet = scalar.clone();
+        nearest_scalar(&triangles, &rays, &mut scalar);
+        nearest_packet(&triangles, &rays, &mut packet);
+        assert_eq!(scalar, packet);
+        let hits = scalar.iter().filter(|h| h.triangle != NO_HIT).count();
+        assert!(hits > 0 && hits < rays.ox.len(), "{} hits", hits);
+        assert!(scalar.iter().all(|h| h.triangle != 64));
+    }
+}
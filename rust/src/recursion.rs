@@ -0,0 +1,71 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+const FIB_N: u64 = 42;
+const ACKERMANN_M: u64 = 3;
+const ACKERMANN_N: u64 = 11;
+
+// Naive doubly-recursive Fibonacci: ~866M calls for n = 42
+fn fib(n: u64) -> u64 {
+    if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+
+// Ackermann's function is not primitive recursive, so it can't be turned into loops;
+// A(3, n) recurses 2^(n+3) frames deep
+fn ackermann(m: u64, n: u64) -> u64 {
+    match (m, n) {
+        (0, n) => n + 1,
+        (m, 0) => ackermann(m - 1, 1),
+        (m, n) => ackermann(m - 1, ackermann(m, n - 1)),
+    }
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    // Arguments pass through black_box so neither call can be evaluated at compile time
+
+    // Warm-up
+    let _ = fib(black_box(30));
+    let _ = ackermann(black_box(2), black_box(1000));
+
+    // Benchmark Fibonacci
+    let start = Instant::now();
+    measure.start();
+    let fib_result = fib(black_box(FIB_N));
+    measure.stop();
+    let fib_duration = start.elapsed();
+
+    // Benchmark Ackermann
+    let start = Instant::now();
+    measure.start();
+    let ackermann_result = ackermann(black_box(ACKERMANN_M), black_box(ACKERMANN_N));
+    measure.stop();
+    let ackermann_duration = start.elapsed();
+
+    measure.report();
+    eprintln!("fib({}): {:.6}s, ackermann({}, {}): {:.6}s",
+              FIB_N, fib_duration.as_secs_f64(), ACKERMANN_M, ACKERMANN_N, ackermann_duration.as_secs_f64());
+    eprintln!("Checksum: {} (fib: {}, ackermann: {})", fib_result + ackermann_result, fib_result, ackermann_result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fibonacci_values() {
+        let first: Vec<u64> = (0..10).map(fib).collect();
+        assert_eq!(first, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+        assert_eq!(fib(30), 832_040);
+    }
+
+    #[test]
+    fn ackermann_closed_forms() {
+        for n in 0..6 {
+            assert_eq!(ackermann(0, n), n + 1);
+            assert_eq!(ackermann(1, n), n + 2);
+            assert_eq!(ackermann(2, n), 2 * n + 3);
+            assert_eq!(ackermann(3, n), (1 << (n + 3)) - 3);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::cell::{Cell, RefCell};
+use std::ops::Deref;
+use std::rc::{self, Rc};
+use std::sync::{self, Arc};
+use std::time::{Duration, Instant};
+
+const NUM_NODES: usize = 1_000_000;
+const NUM_OPS: usize = 2_000_000;
+const INSERT_PERCENT: u64 = 50;
+const RELINK_PERCENT: u64 = 35; // the remainder drop a whole subtree
+const INSERT_FANOUT: usize = 3; // each insert adds a node with this many leaf children
+
+// Strong/weak pointer pair the graph is generic over, so Rc and Arc run identical code
+trait Pointer: Sized {
+    type Strong: Clone + Deref<Target = Node<Self>>;
+    type Weak: Clone;
+
+    fn new(node: Node<Self>) -> Self::Strong;
+    fn downgrade(strong: &Self::Strong) -> Self::Weak;
+    fn upgrade(weak: &Self::Weak) -> Option<Self::Strong>;
+    fn dangling() -> Self::Weak;
+    fn ptr_eq(a: &Self::Strong, b: &Self::Strong) -> bool;
+}
+
+struct Node<P: Pointer> {
+    value: Cell<u64>,
+    parent: RefCell<P::Weak>,
+    children: RefCell<Vec<P::Strong>>,
+}
+
+struct RcPointer;
+
+impl Pointer for RcPointer {
+    type Strong = Rc<Node<Self>>;
+    type Weak = rc::Weak<Node<Self>>;
+
+    fn new(node: Node<Self>) -> Self::Strong { Rc::new(node) }
+    fn downgrade(strong: &Self::Strong) -> Self::Weak { Rc::downgrade(strong) }
+    fn upgrade(weak: &Self::Weak) -> Option<Self::Strong> { weak.upgrade() }
+    fn dangling() -> Self::Weak { rc::Weak::new() }
+    fn ptr_eq(a: &Self::Strong, b: &Self::Strong) -> bool { Rc::ptr_eq(a, b) }
+}
+
+struct ArcPointer;
+
+impl Pointer for ArcPointer {
+    type Strong = Arc<Node<Self>>;
+    type Weak = sync::Weak<Node<Self>>;
+
+    // Atomic counts on a single thread, exactly what shared_ptr pays; the graph itself is never shared
+    #[allow(clippy::arc_with_non_send_sync)]
+    fn new(node: Node<Self>) -> Self::Strong { Arc::new(node) }
+    fn downgrade(strong: &Self::Strong) -> Self::Weak { Arc::downgrade(strong) }
+    fn upgrade(weak: &Self::Weak) -> Option<Self::Strong> { weak.upgrade() }
+    fn dangling() -> Self::Weak { sync::Weak::new() }
+    fn ptr_eq(a: &Self::Strong, b: &Self::Strong) -> bool { Arc::ptr_eq(a, b) }
+}
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+struct Graph<P: Pointer> {
+    root: Option<P::Strong>,
+    // Weak handles to random nodes; slots go dead when their subtree is dropped
+    handles: Vec<P::Weak>,
+    seed: u64,
+}
+
+fn attach<P: Pointer>(parent: &P::Strong, value: u64) -> P::Strong {
+    let child = P::new(Node {
+        value: Cell::new(value),
+        parent: RefCell::new(P::downgrade(parent)),
+        children: RefCell::new(Vec::new()),
+    });
+    parent.children.borrow_mut().push(child.clone());
+    child
+}
+
+// Removes `node` from its parent's child list, returning that strong reference
+fn detach<P: Pointer>(node: &P::Strong) -> Option<P::Strong> {
+    let parent = P::upgrade(&node.parent.borrow())?;
+    let mut siblings = parent.children.borrow_mut();
+    let pos = siblings.iter().position(|c| P::ptr_eq(c, node))?;
+    Some(siblings.swap_remove(pos))
+}
+
+impl<P: Pointer> Graph<P> {
+    fn build(seed: u64) -> Self {
+        let root = P::new(Node { value: Cell::new(0), parent: RefCell::new(P::dangling()), children: RefCell::new(Vec::new()) });
+        let mut graph = Graph { handles: vec![P::downgrade(&root)], root: Some(root), seed };
+        for i in 1..NUM_NODES {
+            let slot = (next_random(&mut graph.seed) % i as u64) as usize;
+            let parent = P::upgrade(&graph.handles[slot]).unwrap();
+            let value = next_random(&mut graph.seed);
+            let child = attach::<P>(&parent, value);
+            graph.handles.push(P::downgrade(&child));
+        }
+        graph
+    }
+
+    // Slot 0 is the root and is never overwritten, so this always terminates
+    fn pick_live(&mut self) -> P::Strong {
+        loop {
+            let slot = (next_random(&mut self.seed) % NUM_NODES as u64) as usize;
+            if let Some(node) = P::upgrade(&self.handles[slot]) {
+                return node;
+            }
+        }
+    }
+
+    fn pick_non_root(&mut self) -> P::Strong {
+        loop {
+            let node = self.pick_live();
+            if P::upgrade(&node.parent.borrow()).is_some() {
+                return node;
+            }
+        }
+    }
+
+    fn is_ancestor_or_self(ancestor: &P::Strong, node: &P::Strong) -> bool {
+        let mut current = node.clone();
+        loop {
+            if P::ptr_eq(&current, ancestor) {
+                return true;
+            }
+            let parent = P::upgrade(&current.parent.borrow());
+            match parent {
+                Some(p) => current = p,
+                None => return false,
+            }
+        }
+    }
+
+    fn insert(&mut self) {
+        let parent = self.pick_live();
+        let value = next_random(&mut self.seed);
+        let node = attach::<P>(&parent, value);
+        let slot = 1 + (next_random(&mut self.seed) % (NUM_NODES as u64 - 1)) as usize;
+        self.handles[slot] = P::downgrade(&node);
+        for _ in 0..INSERT_FANOUT {
+            let leaf = attach::<P>(&node, value.rotate_left(7));
+            let slot = 1 + (next_random(&mut self.seed) % (NUM_NODES as u64 - 1)) as usize;
+            self.handles[slot] = P::downgrade(&leaf);
+        }
+    }
+
+    // Moves a subtree under a new parent, unless that would create a cycle
+    fn relink(&mut self) -> bool {
+        let node = self.pick_non_root();
+        let new_parent = self.pick_live();
+        if Self::is_ancestor_or_self(&node, &new_parent) {
+            return false;
+        }
+        if let Some(strong) = detach::<P>(&node) {
+            *strong.parent.borrow_mut() = P::downgrade(&new_parent);
+            new_parent.children.borrow_mut().push(strong);
+        }
+        true
+    }
+
+    // The last strong reference goes away here, freeing the whole subtree
+    fn drop_subtree(&mut self) {
+        let node = self.pick_non_root();
+        drop(detach::<P>(&node));
+    }
+
+    // Iterative walk holding strong references, the way a visitor would
+    fn traverse(&self) -> (usize, u64) {
+        let mut count = 0usize;
+        let mut sum = 0u64;
+        let mut stack: Vec<P::Strong> = self.root.iter().cloned().collect();
+        while let Some(node) = stack.pop() {
+            count += 1;
+            sum = sum.wrapping_add(node.value.get());
+            stack.extend(node.children.borrow().iter().cloned());
+        }
+        (count, sum)
+    }
+}
+
+struct Stats {
+    build: Duration,
+    churn: Duration,
+    traverse: Duration,
+    teardown: Duration,
+    live_nodes: usize,
+    value_sum: u64,
+    rejected_relinks: usize,
+}
+
+impl Stats {
+    fn total(&self) -> Duration {
+        self.build + self.churn + self.traverse + self.teardown
+    }
+}
+
+fn run<P: Pointer>(ops: usize) -> Stats {
+    let start = Instant::now();
+    let mut graph = Graph::<P>::build(2024);
+    let build = start.elapsed();
+
+    let start = Instant::now();
+    let mut rejected_relinks = 0;
+    for _ in 0..ops {
+        let op = next_random(&mut graph.seed) % 100;
+        if op < INSERT_PERCENT {
+            graph.insert();
+        } else if op < INSERT_PERCENT + RELINK_PERCENT {
+            rejected_relinks += !graph.relink() as usize;
+        } else {
+            graph.drop_subtree();
+        }
+    }
+    let churn = start.elapsed();
+
+    let start = Instant::now();
+    let (live_nodes, value_sum) = graph.traverse();
+    let traverse = start.elapsed();
+
+    let start = Instant::now();
+    drop(graph);
+    let teardown = start.elapsed();
+
+    Stats { build, churn, traverse, teardown, live_nodes, value_sum, rejected_relinks }
+}
+
+fn report(name: &str, s: &Stats) {
+    eprintln!("{:<4} build {:.3}s, churn {:.3}s ({:.0} ops/s), traverse {:.3}s, teardown {:.3}s",
+              name, s.build.as_secs_f64(), s.churn.as_secs_f64(), NUM_OPS as f64 / s.churn.as_secs_f64(),
+              s.traverse.as_secs_f64(), s.teardown.as_secs_f64());
+}
+
+fn main() {
+    // Warm-up
+    let _ = run::<ArcPointer>(NUM_OPS / 100);
+
+    // Benchmark: Arc is the headline since shared_ptr's counts are atomic too
+    let arc = run::<ArcPointer>(NUM_OPS);
+    let rc = run::<RcPointer>(NUM_OPS);
+
+    println!("{:.6}", arc.total().as_secs_f64());
+    report("Arc", &arc);
+    report("Rc", &rc);
+    eprintln!("Live nodes: {}, rejected relinks: {}", arc.live_nodes, arc.rejected_relinks);
+    eprintln!("Checksum: {} (Rc matches: {})", arc.value_sum,
+              rc.value_sum == arc.value_sum && rc.live_nodes == arc.live_nodes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root<P: Pointer>() -> P::Strong {
+        P::new(Node { value: Cell::new(1), parent: RefCell::new(P::dangling()), children: RefCell::new(Vec::new()) })
+    }
+
+    #[test]
+    fn detaching_frees_the_subtree() {
+        let top = root::<RcPointer>();
+        let child = attach::<RcPointer>(&top, 2);
+        let grandchild = attach::<RcPointer>(&child, 3);
+        let (weak_child, weak_grandchild) = (Rc::downgrade(&child), Rc::downgrade(&grandchild));
+        assert!(Graph::<RcPointer>::is_ancestor_or_self(&top, &grandchild));
+        assert!(!Graph::<RcPointer>::is_ancestor_or_self(&grandchild, &top));
+        assert!(Rc::ptr_eq(&weak_grandchild.upgrade().unwrap().parent.borrow().upgrade().unwrap(), &child));
+
+        drop(grandchild);
+        let detached = detach::<RcPointer>(&child).unwrap();
+        assert!(top.children.borrow().is_empty());
+        drop((child, detached));
+        assert!(weak_child.upgrade().is_none() && weak_grandchild.upgrade().is_none());
+        // The root has no parent to detach from
+        assert!(detach::<RcPointer>(&top).is_none());
+    }
+
+    #[test]
+    fn build_links_every_node_under_the_root() {
+        let graph = Graph::<ArcPointer>::build(7);
+        let (count, _) = graph.traverse();
+        assert_eq!(count, NUM_NODES);
+        assert!(graph.handles.iter().all(|h| h.upgrade().is_some()));
+    }
+
+    #[test]
+    fn rc_and_arc_churn_identically() {
+        let (rc, arc) = (run::<RcPointer>(20_000), run::<ArcPointer>(20_000));
+        assert_eq!((rc.live_nodes, rc.value_sum, rc.rejected_relinks), (arc.live_nodes, arc.value_sum, arc.rejected_relinks));
+        assert!(rc.live_nodes > 0 && rc.live_nodes != NUM_NODES);
+    }
+}
@@ -0,0 +1,310 @@
+use std::hint::spin_loop;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const CAPACITY: usize = 1024; // slots per ring, a power of two
+const ROUND_TRIPS: usize = 500_000;
+const MESSAGES: u64 = 100_000_000;
+const SPINS_BEFORE_YIELD: u32 = 100; // longer than a handoff between cores takes
+
+// Apple Silicon and recent x86 parts move lines in pairs, so 128 bytes apart is what keeps two
+// indices from sharing
+#[repr(align(128))]
+#[derive(Default)]
+struct CacheLine(AtomicUsize);
+
+// Where the producer's tail and the consumer's head live
+trait Indices: Default + Send + Sync + 'static {
+    fn head(&self) -> &AtomicUsize;
+    fn tail(&self) -> &AtomicUsize;
+}
+
+// Each index on its own line, so each side writes only its own
+#[derive(Default)]
+struct Padded {
+    head: CacheLine,
+    tail: CacheLine,
+}
+
+impl Indices for Padded {
+    fn head(&self) -> &AtomicUsize {
+        &self.head.0
+    }
+
+    fn tail(&self) -> &AtomicUsize {
+        &self.tail.0
+    }
+}
+
+// Both indices on one line: every push and pop invalidates the other core's copy
+#[derive(Default)]
+struct Adjacent {
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+impl Indices for Adjacent {
+    fn head(&self) -> &AtomicUsize {
+        &self.head
+    }
+
+    fn tail(&self) -> &AtomicUsize {
+        &self.tail
+    }
+}
+
+// Indices count up forever and are masked into the slots. Relaxed atomic slots compile to plain
+// loads and stores; the release store of an index publishes them
+struct Ring<I> {
+    indices: I,
+    slots: Box<[AtomicU64]>,
+}
+
+// Each side keeps its own index and a cached copy of the other's, and rereads the shared one
+// only when the cache says the ring is full or empty
+struct Producer<I> {
+    ring: Arc<Ring<I>>,
+    tail: usize,
+    cached_head: usize,
+}
+
+struct Consumer<I> {
+    ring: Arc<Ring<I>>,
+    head: usize,
+    cached_tail: usize,
+}
+
+trait Sender: Send + 'static {
+    fn try_send(&mut self, value: u64) -> bool;
+}
+
+trait Receiver: Send + 'static {
+    fn try_recv(&mut self) -> Option<u64>;
+}
+
+impl<I: Indices> Sender for Producer<I> {
+    fn try_send(&mut self, value: u64) -> bool {
+        if self.tail - self.cached_head == CAPACITY {
+            self.cached_head = self.ring.indices.head().load(Ordering::Acquire);
+            if self.tail - self.cached_head == CAPACITY {
+                return false;
+            }
+        }
+        self.ring.slots[self.tail & (CAPACITY - 1)].store(value, Ordering::Relaxed);
+        self.tail += 1;
+        self.ring.indices.tail().store(self.tail, Ordering::Release);
+        true
+    }
+}
+
+impl<I: Indices> Receiver for Consumer<I> {
+    fn try_recv(&mut self) -> Option<u64> {
+        if self.head == self.cached_tail {
+            self.cached_tail = self.ring.indices.tail().load(Ordering::Acquire);
+            if self.head == self.cached_tail {
+                return None;
+            }
+        }
+        let value = self.ring.slots[self.head & (CAPACITY - 1)].load(Ordering::Relaxed);
+        self.head += 1;
+        self.ring.indices.head().store(self.head, Ordering::Release);
+        Some(value)
+    }
+}
+
+impl Sender for rtrb::Producer<u64> {
+    fn try_send(&mut self, value: u64) -> bool {
+        self.push(value).is_ok()
+    }
+}
+
+impl Receiver for rtrb::Consumer<u64> {
+    fn try_recv(&mut self) -> Option<u64> {
+        self.pop().ok()
+    }
+}
+
+trait Channel {
+    type Tx: Sender;
+    type Rx: Receiver;
+    fn channel() -> (Self::Tx, Self::Rx);
+}
+
+struct HandRolled<I>(PhantomData<I>);
+
+impl<I: Indices> Channel for HandRolled<I> {
+    type Tx = Producer<I>;
+    type Rx = Consumer<I>;
+
+    fn channel() -> (Producer<I>, Consumer<I>) {
+        let ring = Arc::new(Ring { indices: I::default(), slots: (0..CAPACITY).map(|_| AtomicU64::new(0)).collect() });
+        (Producer { ring: ring.clone(), tail: 0, cached_head: 0 }, Consumer { ring, head: 0, cached_tail: 0 })
+    }
+}
+
+// The rtrb crate: a wait-free SPSC ring with padded, cached indices
+struct Rtrb;
+
+impl Channel for Rtrb {
+    type Tx = rtrb::Producer<u64>;
+    type Rx = rtrb::Consumer<u64>;
+
+    fn channel() -> (Self::Tx, Self::Rx) {
+        rtrb::RingBuffer::new(CAPACITY)
+    }
+}
+
+// Spin, then give the core away, so the benchmark still finishes when both threads share one
+fn backoff(spins: &mut u32) {
+    if *spins < SPINS_BEFORE_YIELD {
+        *spins += 1;
+        spin_loop();
+    } else {
+        thread::yield_now();
+    }
+}
+
+fn send(tx: &mut impl Sender, value: u64) {
+    let mut spins = 0;
+    while !tx.try_send(value) {
+        backoff(&mut spins);
+    }
+}
+
+fn recv(rx: &mut impl Receiver) -> u64 {
+    let mut spins = 0;
+    loop {
+        if let Some(value) = rx.try_recv() {
+            return value;
+        }
+        backoff(&mut spins);
+    }
+}
+
+struct Latency {
+    elapsed: Duration,
+    round_trips_ns: Vec<u32>, // sorted
+    checksum: u64,
+}
+
+// A message to an echo thread and back through a second ring, one in flight at a time. Each
+// round trip is two handoffs plus the two clock reads around it
+fn ping_pong<C: Channel>(round_trips: usize) -> Latency {
+    let (mut ping_tx, mut ping_rx) = C::channel();
+    let (mut pong_tx, mut pong_rx) = C::channel();
+    let start = Instant::now();
+    let (mut round_trips_ns, checksum) = thread::scope(|s| {
+        s.spawn(move || {
+            for _ in 0..round_trips {
+                let value = recv(&mut ping_rx);
+                send(&mut pong_tx, value + 1);
+            }
+        });
+        let mut samples = Vec::with_capacity(round_trips);
+        let mut checksum = 0u64;
+        for i in 0..round_trips as u64 {
+            let sent = Instant::now();
+            send(&mut ping_tx, i);
+            checksum = checksum.wrapping_add(recv(&mut pong_rx));
+            samples.push(sent.elapsed().as_nanos() as u32);
+        }
+        (samples, checksum)
+    });
+    let elapsed = start.elapsed();
+    round_trips_ns.sort_unstable();
+    Latency { elapsed, round_trips_ns, checksum }
+}
+
+// The producer sends 0..messages flat out; the consumer folds them in arrival order
+fn stream<C: Channel>(messages: u64) -> (Duration, u64) {
+    let (mut tx, mut rx) = C::channel();
+    let start = Instant::now();
+    let checksum = thread::scope(|s| {
+        s.spawn(move || {
+            for i in 0..messages {
+                send(&mut tx, i);
+            }
+        });
+        (0..messages).fold(0u64, |acc, _| acc.wrapping_mul(31).wrapping_add(recv(&mut rx)))
+    });
+    (start.elapsed(), checksum)
+}
+
+struct Run {
+    latency: Latency,
+    stream: (Duration, u64),
+}
+
+fn run<C: Channel>(round_trips: usize, messages: u64) -> Run {
+    Run { latency: ping_pong::<C>(round_trips), stream: stream::<C>(messages) }
+}
+
+fn handoff_ns(sorted_round_trips: &[u32], per_mille: usize) -> f64 {
+    sorted_round_trips[(sorted_round_trips.len() - 1) * per_mille / 1000] as f64 / 2.0
+}
+
+type Variant = (&'static str, fn(usize, u64) -> Run);
+
+const VARIANTS: [Variant; 3] = [
+    ("padded", run::<HandRolled<Padded>>),
+    ("adjacent", run::<HandRolled<Adjacent>>),
+    ("rtrb", run::<Rtrb>),
+];
+
+fn main() {
+    // Warm-up
+    for (_, f) in VARIANTS {
+        let _ = f(ROUND_TRIPS / 100, MESSAGES / 100);
+    }
+
+    // Benchmark
+    let runs: Vec<Run> = VARIANTS.iter().map(|(_, f)| f(ROUND_TRIPS, MESSAGES)).collect();
+    let total_duration: Duration = runs.iter().map(|r| r.latency.elapsed + r.stream.0).sum();
+    let all_match = runs.iter().all(|r| {
+        (r.latency.checksum, r.stream.1) == (runs[0].latency.checksum, runs[0].stream.1)
+    });
+    let checksum = runs[0].latency.checksum.wrapping_add(runs[0].stream.1);
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Rings of {} u64 slots: {} round trips, then {} messages streamed", CAPACITY, ROUND_TRIPS, MESSAGES);
+    for ((name, _), r) in VARIANTS.iter().zip(&runs) {
+        let trips = &r.latency.round_trips_ns;
+        eprintln!("  {:<8} handoff p50 {:.0}ns, p99 {:.0}ns, p99.9 {:.0}ns; stream {:.3}s ({:.1}M msgs/s)", name,
+                  handoff_ns(trips, 500), handoff_ns(trips, 990), handoff_ns(trips, 999), r.stream.0.as_secs_f64(),
+                  MESSAGES as f64 / r.stream.0.as_secs_f64() / 1e6);
+    }
+    eprintln!("Checksum: {} (all variants match: {})", checksum, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_fills_and_wraps() {
+        let (mut tx, mut rx) = HandRolled::<Padded>::channel();
+        for round in 0..3 {
+            for i in 0..CAPACITY as u64 {
+                assert!(tx.try_send(round * 10_000 + i));
+            }
+            assert!(!tx.try_send(0));
+            for i in 0..CAPACITY as u64 {
+                assert_eq!(rx.try_recv(), Some(round * 10_000 + i));
+            }
+            assert_eq!(rx.try_recv(), None);
+        }
+    }
+
+    #[test]
+    fn variants_agree() {
+        let runs: Vec<Run> = VARIANTS.iter().map(|(_, f)| f(1_000, 100_000)).collect();
+        for r in &runs {
+            assert_eq!(r.latency.checksum, 1_000 * 1_001 / 2);
+            assert_eq!(r.latency.round_trips_ns.len(), 1_000);
+            assert_eq!(r.stream.1, runs[0].stream.1);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_pcg::Pcg64;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+const NUM_VALUES: usize = 200_000_000; // Per generator, per output type
+const NUM_STREAMS: usize = 8; // Independent generators in the multi-threaded run
+const BASE_SEED: u64 = 0x5EED;
+
+// SplitMix64 expands one u64 into the 32-byte seeds every generator here takes
+fn seed_bytes(stream: u64) -> [u8; 32] {
+    let mut x = BASE_SEED.wrapping_add(stream.wrapping_mul(0x9E3779B97F4A7C15));
+    let mut seed = [0u8; 32];
+    for chunk in seed.chunks_exact_mut(8) {
+        x = x.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = x;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        chunk.copy_from_slice(&(z ^ (z >> 31)).to_le_bytes());
+    }
+    seed
+}
+
+fn sum_u64<R: RngCore>(rng: &mut R, count: usize) -> u64 {
+    let mut sum = 0u64;
+    for _ in 0..count {
+        sum = sum.wrapping_add(rng.next_u64());
+    }
+    sum
+}
+
+// rand's Standard f64: the top 53 bits scaled into [0, 1)
+fn sum_f64<R: RngCore>(rng: &mut R, count: usize) -> f64 {
+    let mut sum = 0.0;
+    for _ in 0..count {
+        sum += rng.gen::<f64>();
+    }
+    sum
+}
+
+struct Run {
+    u64_duration: Duration,
+    f64_duration: Duration,
+    u64_sum: u64,
+    f64_sum: f64,
+}
+
+fn run_single<R: RngCore + SeedableRng<Seed = [u8; 32]>>(count: usize) -> Run {
+    let mut rng = R::from_seed(seed_bytes(0));
+    let start = Instant::now();
+    let u64_sum = sum_u64(&mut rng, count);
+    let u64_duration = start.elapsed();
+
+    let mut rng = R::from_seed(seed_bytes(0));
+    let start = Instant::now();
+    let f64_sum = sum_f64(&mut rng, count);
+    let f64_duration = start.elapsed();
+
+    Run { u64_duration, f64_duration, u64_sum, f64_sum }
+}
+
+// Each stream gets its own generator and seed, so results don't depend on the thread count
+fn run_multi<R: RngCore + SeedableRng<Seed = [u8; 32]>>(count: usize) -> Run {
+    let per_stream = count / NUM_STREAMS;
+
+    let start = Instant::now();
+    let u64_sum = (0..NUM_STREAMS)
+        .into_par_iter()
+        .map(|s| sum_u64(&mut R::from_seed(seed_bytes(s as u64)), per_stream))
+        .reduce(|| 0, u64::wrapping_add);
+    let u64_duration = start.elapsed();
+
+    let start = Instant::now();
+    let partial: Vec<f64> = (0..NUM_STREAMS)
+        .into_par_iter()
+        .map(|s| sum_f64(&mut R::from_seed(seed_bytes(s as u64)), per_stream))
+        .collect();
+    let f64_sum = partial.iter().sum();
+    let f64_duration = start.elapsed();
+
+    Run { u64_duration, f64_duration, u64_sum, f64_sum }
+}
+
+fn report(name: &str, single: &Run, multi: &Run) {
+    let rate = |d: Duration| NUM_VALUES as f64 / d.as_secs_f64() / 1e6;
+    eprintln!("{:<12} single: {:>7.0} M u64/s, {:>7.0} M f64/s | multi: {:>7.0} M u64/s, {:>7.0} M f64/s",
+              name, rate(single.u64_duration), rate(single.f64_duration),
+              rate(multi.u64_duration), rate(multi.f64_duration));
+}
+
+fn elapsed(run: &Run) -> Duration {
+    run.u64_duration + run.f64_duration
+}
+
+fn main() {
+    // Warm-up
+    let _ = run_single::<Xoshiro256PlusPlus>(NUM_VALUES / 100);
+    let _ = run_multi::<ChaCha8Rng>(NUM_VALUES / 100);
+
+    // Benchmark each generator single- and multi-threaded
+    let runs = [
+        ("Xoshiro256++", run_single::<Xoshiro256PlusPlus>(NUM_VALUES), run_multi::<Xoshiro256PlusPlus>(NUM_VALUES)),
+        ("PCG64", run_single::<Pcg64>(NUM_VALUES), run_multi::<Pcg64>(NUM_VALUES)),
+        ("ChaCha8", run_single::<ChaCha8Rng>(NUM_VALUES), run_multi::<ChaCha8Rng>(NUM_VALUES)),
+        ("StdRng", run_single::<StdRng>(NUM_VALUES), run_multi::<StdRng>(NUM_VALUES)),
+    ];
+
+    let total_duration: Duration = runs.iter().map(|(_, single, multi)| elapsed(single) + elapsed(multi)).sum();
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    for (name, single, multi) in &runs {
+        report(name, single, multi);
+    }
+    // StdRng is ChaCha12 and has no bit-exact C++ counterpart, so it stays out of the checksum
+    let checksum = runs[..3].iter().fold(0u64, |acc, (_, single, multi)| {
+        acc.wrapping_add(single.u64_sum).wrapping_add(multi.u64_sum)
+    });
+    let float_checksum: f64 = runs[..3].iter().map(|(_, single, multi)| single.f64_sum + multi.f64_sum).sum();
+    eprintln!("Checksum: {} (f64 sum: {:.6})", checksum, float_checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mean_f64<R: RngCore + SeedableRng<Seed = [u8; 32]>>() -> f64 {
+        let count = 1 << 20;
+        sum_f64(&mut R::from_seed(seed_bytes(3)), count) / count as f64
+    }
+
+    #[test]
+    fn streams_get_distinct_reproducible_seeds() {
+        let seeds: Vec<[u8; 32]> = (0..NUM_STREAMS as u64).map(seed_bytes).collect();
+        for (i, a) in seeds.iter().enumerate() {
+            assert!(seeds[i + 1..].iter().all(|b| a != b));
+        }
+        assert_eq!(seed_bytes(5), seeds[5]);
+
+        let mut rng = Xoshiro256PlusPlus::from_seed(seeds[0]);
+        let first: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        assert_eq!(sum_u64(&mut Xoshiro256PlusPlus::from_seed(seeds[0]), 4),
+                   first.iter().fold(0u64, |a, &b| a.wrapping_add(b)));
+    }
+
+    #[test]
+    fn uniform_f64_means_are_near_one_half() {
+        // Standard error of the mean of 2^20 uniforms is about 2.8e-4
+        for mean in [mean_f64::<StdRng>(), mean_f64::<ChaCha8Rng>(), mean_f64::<Pcg64>(), mean_f64::<Xoshiro256PlusPlus>()] {
+            assert!((mean - 0.5).abs() < 2e-3, "{}", mean);
+        }
+    }
+
+    #[test]
+    fn multi_stream_runs_are_deterministic() {
+        let count = NUM_STREAMS * 1000;
+        let (a, b) = (run_multi::<Pcg64>(count), run_multi::<Pcg64>(count));
+        assert_eq!((a.u64_sum, a.f64_sum), (b.u64_sum, b.f64_sum));
+        let stream_zero = run_single::<Pcg64>(1000);
+        assert_ne!(a.u64_sum, stream_zero.u64_sum);
+        assert_eq!(stream_zero.u64_sum, sum_u64(&mut Pcg64::from_seed(seed_bytes(0)), 1000));
+    }
+}
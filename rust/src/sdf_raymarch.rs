@@ -0,0 +1,235 @@
+use bench_math::Vec3;
+use rayon::prelude::*;
+
+const WIDTH: usize = 1920;
+const HEIGHT: usize = 1080;
+const MAX_STEPS: u32 = 256;
+const MAX_DISTANCE: f64 = 60.0;
+const HIT_EPSILON: f64 = 1e-4;
+const SHADOW_STEPS: u32 = 64;
+const SHADOW_SOFTNESS: f64 = 16.0;
+const NORMAL_EPSILON: f64 = 1e-4;
+
+fn sd_sphere(p: &Vec3, radius: f64) -> f64 {
+    p.length() - radius
+}
+
+fn sd_box(p: &Vec3, half_extent: &Vec3) -> f64 {
+    let q = p.map(f64::abs) - *half_extent;
+    q.map(|c| c.max(0.0)).length() + q.x.max(q.y.max(q.z)).min(0.0)
+}
+
+fn sd_torus(p: &Vec3, major: f64, minor: f64) -> f64 {
+    let qx = (p.x * p.x + p.z * p.z).sqrt() - major;
+    (qx * qx + p.y * p.y).sqrt() - minor
+}
+
+// Polynomial smooth minimum: blends surfaces within `k` of each other
+fn smooth_min(a: f64, b: f64, k: f64) -> f64 {
+    let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+    b + (a - b) * h - k * h * (1.0 - h)
+}
+
+// Everything above the ground plane
+fn sd_objects(p: &Vec3) -> f64 {
+    // Sphere melted into a rounded box
+    let blob = smooth_min(
+        sd_sphere(&(*p - Vec3::new(-1.6, 0.7, -4.0)), 0.6),
+        sd_box(&(*p - Vec3::new(-0.9, 0.45, -4.0)), &Vec3::splat(0.4)) - 0.05,
+        0.35,
+    );
+
+    // Sphere with a sinusoidal displacement
+    let c = *p - Vec3::new(1.5, 0.8, -4.5);
+    let wobble = sd_sphere(&c, 0.75) - 0.04 * (8.0 * c.x).sin() * (8.0 * c.y).sin() * (8.0 * c.z).sin();
+
+    let ring = sd_torus(&(*p - Vec3::new(0.2, 0.2, -2.8)), 0.5, 0.15);
+
+    // 9×5 grid of small spheres via limited domain repetition
+    let spacing = 0.7;
+    let cell_x = (p.x / spacing).round().clamp(-4.0, 4.0);
+    let cell_z = ((p.z + 7.0) / spacing).round().clamp(-2.0, 2.0);
+    let local = Vec3::new(p.x - spacing * cell_x, p.y - 0.2, p.z + 7.0 - spacing * cell_z);
+    let grid = sd_sphere(&local, 0.2);
+
+    blob.min(wobble).min(ring).min(grid)
+}
+
+fn sd_scene(p: &Vec3) -> f64 {
+    p.y.min(sd_objects(p))
+}
+
+// Returns the hit distance (if any) and the number of steps taken
+fn march(origin: &Vec3, direction: &Vec3) -> (Option<f64>, u32) {
+    let mut t = 0.0;
+    for step in 0..MAX_STEPS {
+        let d = sd_scene(&(*origin + *direction * t));
+        if d < HIT_EPSILON * t.max(1.0) {
+            return (Some(t), step + 1);
+        }
+        t += d;
+        if t > MAX_DISTANCE {
+            return (None, step + 1);
+        }
+    }
+    (None, MAX_STEPS)
+}
+
+// Tetrahedral gradient estimate: 4 scene evaluations
+fn normal(p: &Vec3) -> Vec3 {
+    let k = [
+        Vec3::new(1.0, -1.0, -1.0),
+        Vec3::new(-1.0, -1.0, 1.0),
+        Vec3::new(-1.0, 1.0, -1.0),
+        Vec3::new(1.0, 1.0, 1.0),
+    ];
+    k.iter()
+        .fold(Vec3::zero(), |n, &k| n + k * sd_scene(&(*p + k * NORMAL_EPSILON)))
+        .normalize()
+}
+
+// Penumbra from how closely the shadow ray grazes other surfaces
+fn soft_shadow(origin: &Vec3, direction: &Vec3, min_t: f64, max_t: f64) -> f64 {
+    let mut result: f64 = 1.0;
+    let mut t = min_t;
+    for _ in 0..SHADOW_STEPS {
+        let h = sd_scene(&(*origin + *direction * t));
+        if h < HIT_EPSILON {
+            return 0.0;
+        }
+        result = result.min(SHADOW_SOFTNESS * h / t);
+        t += h.clamp(0.01, 0.5);
+        if t > max_t {
+            break;
+        }
+    }
+    result.clamp(0.0, 1.0)
+}
+
+fn sky(direction: &Vec3) -> Vec3 {
+    let t = 0.5 * (direction.y + 1.0);
+    Vec3::new(0.9, 0.9, 0.95) * (1.0 - t) + Vec3::new(0.4, 0.6, 1.0) * t
+}
+
+fn albedo(p: &Vec3) -> Vec3 {
+    if p.y < sd_objects(p) {
+        // Checkerboard ground
+        if (p.x.floor() + p.z.floor()) as i64 % 2 == 0 {
+            Vec3::new(0.8, 0.8, 0.8)
+        } else {
+            Vec3::new(0.3, 0.3, 0.3)
+        }
+    } else {
+        Vec3::new(0.9, 0.55, 0.3)
+    }
+}
+
+fn shade(p: &Vec3, direction: &Vec3, t: f64, light: &Vec3) -> Vec3 {
+    let n = normal(p);
+    let diffuse = n.dot(light).max(0.0);
+    let shadow = if diffuse > 0.0 { soft_shadow(&(*p + n * 0.002), light, 0.01, 20.0) } else { 0.0 };
+    let half = (*light - *direction).normalize();
+    let specular = n.dot(&half).max(0.0).powf(32.0) * shadow;
+    let ambient = 0.5 + 0.5 * n.y;
+
+    let color = albedo(p) * (diffuse * shadow + 0.15 * ambient) + Vec3::splat(specular) * 0.3;
+    let fog = (-0.002 * t * t).exp();
+    color * fog + sky(direction) * (1.0 - fog)
+}
+
+struct Camera {
+    origin: Vec3,
+    forward: Vec3,
+    right: Vec3,
+    up: Vec3,
+}
+
+fn camera() -> Camera {
+    let origin = Vec3::new(0.0, 1.4, 1.0);
+    let forward = (Vec3::new(0.0, 0.5, -4.0) - origin).normalize();
+    let right = forward.cross(&Vec3::new(0.0, 1.0, 0.0)).normalize();
+    let up = right.cross(&forward);
+    Camera { origin, forward, right, up }
+}
+
+fn render_pixel(x: usize, y: usize, cam: &Camera, light: &Vec3) -> (Vec3, u32) {
+    let aspect = WIDTH as f64 / HEIGHT as f64;
+    let u = ((x as f64 + 0.5) / WIDTH as f64 * 2.0 - 1.0) * aspect;
+    let v = 1.0 - (y as f64 + 0.5) / HEIGHT as f64 * 2.0;
+    let direction = (cam.forward * 1.5 + cam.right * u + cam.up * v).normalize();
+    let (hit, steps) = march(&cam.origin, &direction);
+    let color = match hit {
+        Some(t) => shade(&(cam.origin + direction * t), &direction, t, light),
+        None => sky(&direction),
+    };
+    // Gamma correction
+    (color.map(|c| c.powf(1.0 / 2.2)), steps)
+}
+
+fn render(cam: &Camera, light: &Vec3) -> Vec<(Vec3, u32)> {
+    (0..HEIGHT).into_par_iter().flat_map(|y| {
+        (0..WIDTH).into_par_iter().map(move |x| render_pixel(x, y, cam, light)).collect::<Vec<_>>()
+    }).collect()
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let cam = camera();
+    let light = Vec3::new(0.6, 0.7, 0.3).normalize();
+
+    // Warm-up
+    let _: Vec<_> = (0..WIDTH).into_par_iter().map(|x| render_pixel(x, HEIGHT / 2, &cam, &light)).collect();
+
+    // Benchmark
+    measure.start();
+    let image = render(&cam, &light);
+    measure.stop();
+
+    // Checksum
+    let checksum: f64 = image.iter().map(|(c, _)| c.x + c.y + c.z).sum();
+    let total_steps: u64 = image.iter().map(|&(_, s)| s as u64).sum();
+
+    measure.report();
+    eprintln!("Primary march steps: {} ({:.1} per pixel)", total_steps, total_steps as f64 / (WIDTH * HEIGHT) as f64);
+    eprintln!("Checksum: {:.6}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitive_distances() {
+        let p = Vec3::new(3.0, 0.0, 4.0);
+        assert_eq!(sd_sphere(&p, 1.0), 4.0);
+        assert_eq!(sd_box(&Vec3::new(2.0, 0.0, 0.0), &Vec3::splat(1.0)), 1.0);
+        assert_eq!(sd_box(&Vec3::new(2.0, 2.0, 1.0), &Vec3::splat(1.0)), 2f64.sqrt());
+        assert_eq!(sd_box(&Vec3::zero(), &Vec3::new(1.0, 0.5, 2.0)), -0.5);
+        assert_eq!(sd_torus(&Vec3::new(2.0, 0.0, 0.0), 2.0, 0.5), -0.5);
+        assert_eq!(sd_torus(&Vec3::new(0.0, 0.0, 0.0), 2.0, 0.5), 1.5);
+    }
+
+    #[test]
+    fn smooth_min_blends_only_nearby_surfaces() {
+        assert_eq!(smooth_min(1.0, 5.0, 0.35), 1.0);
+        assert_eq!(smooth_min(5.0, 1.0, 0.35), 1.0);
+        // Equal distances dip k/4 below both
+        assert!((smooth_min(1.0, 1.0, 0.4) - 0.9).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rays_hit_the_ground_and_miss_into_the_sky() {
+        let origin = Vec3::new(5.0, 1.0, 10.0);
+        let (hit, _) = march(&origin, &Vec3::new(0.0, -1.0, 0.0));
+        assert!((hit.unwrap() - 1.0).abs() < 1e-3);
+        assert_eq!(march(&origin, &Vec3::new(0.0, 1.0, 0.0)).0, None);
+        let n = normal(&Vec3::new(5.0, 0.0, 10.0));
+        assert!((n.y - 1.0).abs() < 1e-9);
+
+        // The centre pixel converges within the step budget
+        let cam = camera();
+        let light = Vec3::new(0.6, 0.8, 0.4).normalize();
+        let (color, steps) = render_pixel(WIDTH / 2, HEIGHT / 2, &cam, &light);
+        assert!(steps < MAX_STEPS && color.x >= 0.0 && color.x <= 1.0);
+    }
+}
@@ -1,32 +1,309 @@
+use rayon::prelude::*;
 use sha2::{Sha256, Digest};
-use std::time::Instant;
+use std::fs::File;
+use std::io::Read;
+use std::time::{Duration, Instant};
 
 const DATA_SIZE: usize = 100_000_000;
 const CHUNK_SIZE: usize = 1024;
+const LEAF_SIZE: usize = 1 << 20; // Merkle leaves are 1 MiB
+const DEFAULT_READ_BUFFER: usize = 64 * 1024;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Stream, // one hasher fed CHUNK_SIZE slices in order
+    Merkle, // LEAF_SIZE leaves hashed in parallel, then combined pairwise into a root
+}
+
+// Where the bytes come from; the hashing work is the same for all three
+enum Source {
+    Generated,                  // DATA_SIZE bytes built in memory
+    Read { buffer_size: usize }, // read(2) into one reused buffer, each fill hashed as one update
+    Mmap,                        // the whole file mapped read-only
+}
+
+type Hash = sha2::digest::Output<Sha256>;
+
+// Domain-separated as in RFC 6962: 0x00 prefixes leaves, 0x01 prefixes interior nodes, so a
+// leaf can never be mistaken for a parent
+fn leaf_hash(chunk: &[u8]) -> Hash {
+    // Fed in 64 KiB slices: built with target-cpu=native, sha2 0.10 runs a single large update
+    // that starts mid-block (here, after the prefix byte) around 100x slower
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    for slice in chunk.chunks(64 * 1024) {
+        hasher.update(slice);
+    }
+    hasher.finalize()
+}
+
+fn parent_hash(left: &Hash, right: &Hash) -> Hash {
+    Sha256::new().chain_update([0x01]).chain_update(left).chain_update(right).finalize()
+}
+
+// Leaves are the parallel part; the ~100 interior hashes are folded level by level on one
+// thread, carrying an odd last node up unchanged
+fn merkle_root(data: &[u8]) -> Hash {
+    let mut level: Vec<Hash> = data.par_chunks(LEAF_SIZE).map(leaf_hash).collect();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| if pair.len() == 2 { parent_hash(&pair[0], &pair[1]) } else { pair[0] })
+            .collect();
+    }
+    level[0]
+}
+
+// sha2 picks its backend at runtime; on x86 that is SHA-NI whenever the CPU has it
+fn compress_path() -> (bench_cpu::Path, &'static str) {
+    if cfg!(any(target_arch = "x86", target_arch = "x86_64")) && bench_cpu::has("sha") {
+        (bench_cpu::Path::Hw, "SHA-NI")
+    } else if cfg!(target_arch = "aarch64") && bench_cpu::has("sha2") {
+        (bench_cpu::Path::Scalar, "software rounds; the CPU has SHA2 instructions, but sha2 only uses them with its asm feature")
+    } else {
+        (bench_cpu::Path::Scalar, "software rounds")
+    }
+}
+
+// Read-only mapping of a whole file, unmapped on drop. Page faults land inside the hashing,
+// so mmap runs measure I/O and hashing together
+#[cfg(unix)]
+struct Mapping {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+#[cfg(unix)]
+impl Mapping {
+    fn new(file: &File) -> std::io::Result<Mapping> {
+        use std::os::unix::io::AsRawFd;
+        let len = file.metadata()?.len() as usize;
+        if len == 0 {
+            // mmap rejects empty lengths
+            return Ok(Mapping { ptr: std::ptr::null_mut(), len });
+        }
+        let ptr = unsafe { libc::mmap(std::ptr::null_mut(), len, libc::PROT_READ, libc::MAP_PRIVATE, file.as_raw_fd(), 0) };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Mapping { ptr, len })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            unsafe { libc::munmap(self.ptr, self.len) };
+        }
+    }
+}
+
+// Returns the hash and the time spent inside read() versus inside the hasher
+fn hash_reader(file: &mut File, buffer_size: usize) -> std::io::Result<(Hash, Duration, Duration)> {
+    let mut buffer = vec![0u8; buffer_size];
+    let mut hasher = Sha256::new();
+    let (mut read_time, mut hash_time) = (Duration::ZERO, Duration::ZERO);
+    loop {
+        let start = Instant::now();
+        let n = file.read(&mut buffer)?;
+        read_time += start.elapsed();
+        if n == 0 {
+            break;
+        }
+        let start = Instant::now();
+        hasher.update(&buffer[..n]);
+        hash_time += start.elapsed();
+    }
+    Ok((hasher.finalize(), read_time, hash_time))
+}
+
+fn hash_stream(data: &[u8]) -> Hash {
+    let mut hasher = Sha256::new();
+    for chunk in data.chunks(CHUNK_SIZE) {
+        hasher.update(chunk);
+    }
+    hasher.finalize()
+}
+
+// Bytes with an optional binary K, M or G suffix: 4096, 64K, 1M
+fn parse_size(value: &str) -> Option<usize> {
+    let (digits, scale) = match value.chars().last()? {
+        'K' | 'k' => (&value[..value.len() - 1], 1 << 10),
+        'M' | 'm' => (&value[..value.len() - 1], 1 << 20),
+        'G' | 'g' => (&value[..value.len() - 1], 1 << 30),
+        _ => (value, 1),
+    };
+    digits.parse::<usize>().ok().filter(|&n| n > 0).and_then(|n| n.checked_mul(scale))
+}
 
 fn main() {
+    let mut measure = bench_measure::from_env();
+    let args: Vec<String> = std::env::args().collect();
+    let mode = match args.iter().position(|a| a == "--mode") {
+        Some(i) => match args.get(i + 1).map(String::as_str) {
+            Some("stream") => Mode::Stream,
+            Some("merkle") => Mode::Merkle,
+            _ => panic!("--mode expects stream or merkle"),
+        },
+        None => Mode::Stream,
+    };
+    let path = args.iter().position(|a| a == "--file").map(|i| match args.get(i + 1) {
+        Some(path) => path.clone(),
+        None => panic!("--file expects a path"),
+    });
+    let buffer_size = args.iter().position(|a| a == "--buffer-size").map(|i| {
+        args.get(i + 1)
+            .and_then(|v| parse_size(v))
+            .unwrap_or_else(|| panic!("--buffer-size expects a byte count such as 4096, 64K or 1M"))
+    });
+    let use_mmap = args.iter().any(|a| a == "--mmap");
+
+    let source = match (&path, use_mmap) {
+        (None, false) if buffer_size.is_none() => Source::Generated,
+        (None, _) => panic!("--buffer-size and --mmap need --file"),
+        (Some(_), true) if buffer_size.is_some() => panic!("--buffer-size applies to read() input, not --mmap"),
+        (Some(_), true) => Source::Mmap,
+        (Some(_), false) if mode == Mode::Merkle => panic!("--mode merkle hashes files through --mmap"),
+        (Some(_), false) => Source::Read { buffer_size: buffer_size.unwrap_or(DEFAULT_READ_BUFFER) },
+    };
+    if cfg!(not(unix)) && matches!(source, Source::Mmap) {
+        panic!("--mmap needs a Unix mmap");
+    }
+
     // Generate data
-    let data: Vec<u8> = (0..DATA_SIZE).map(|i| (i % 256) as u8).collect();
-    
+    let data: Vec<u8> = match source {
+        Source::Generated => (0..DATA_SIZE).map(|i| (i % 256) as u8).collect(),
+        _ => (0..LEAF_SIZE * 8).map(|i| (i % 256) as u8).collect(), // warm-up only
+    };
+    let mut file = path.as_ref().map(|p| File::open(p).unwrap_or_else(|e| panic!("Failed to open {}: {}", p, e)));
+
     // Warm-up
     {
         let mut hasher = Sha256::new();
         hasher.update(&data[..1_000_000]);
         let _ = hasher.finalize();
     }
-    
+    if mode == Mode::Merkle {
+        let _ = merkle_root(&data[..LEAF_SIZE * 8]);
+    }
+
     // Benchmark
     let start = Instant::now();
-    
-    let mut hasher = Sha256::new();
-    for chunk in data.chunks(CHUNK_SIZE) {
-        hasher.update(chunk);
-    }
-    let result = hasher.finalize();
-    
+    measure.start();
+
+    let mut split = None;
+    let (result, len) = match (&source, file.as_mut()) {
+        (Source::Generated, _) => {
+            let hash = if mode == Mode::Merkle { merkle_root(&data) } else { hash_stream(&data) };
+            (hash, data.len())
+        }
+        (Source::Read { buffer_size }, Some(file)) => {
+            let (hash, read_time, hash_time) = hash_reader(file, *buffer_size)
+                .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.as_deref().unwrap_or_default(), e));
+            split = Some((read_time, hash_time));
+            (hash, file.metadata().map(|m| m.len() as usize).unwrap_or(0))
+        }
+        #[cfg(unix)]
+        (Source::Mmap, Some(file)) => {
+            let mapping = Mapping::new(file)
+                .unwrap_or_else(|e| panic!("Failed to map {}: {}", path.as_deref().unwrap_or_default(), e));
+            let bytes = mapping.bytes();
+            let hash = if mode == Mode::Merkle { merkle_root(bytes) } else { hash_stream(bytes) };
+            (hash, bytes.len())
+        }
+        _ => unreachable!(),
+    };
+
+    measure.stop();
     let duration = start.elapsed();
-    
-    println!("{:.6}", duration.as_secs_f64());
+
+    measure.report();
+    match (&source, &path) {
+        (Source::Read { buffer_size }, Some(path)) => {
+            eprintln!("Input: {} ({} bytes), read() into a {} B buffer", path, len, buffer_size)
+        }
+        (Source::Mmap, Some(path)) => eprintln!("Input: {} ({} bytes), mmap", path, len),
+        _ => eprintln!("Input: generated ({} bytes in memory)", len),
+    }
+    match (mode, &source) {
+        (Mode::Stream, Source::Read { buffer_size }) => eprintln!("Mode: stream ({} B updates, 1 thread)", buffer_size),
+        (Mode::Stream, _) => eprintln!("Mode: stream ({} B updates, 1 thread)", CHUNK_SIZE),
+        (Mode::Merkle, _) => eprintln!("Mode: merkle ({} leaves of {} KiB on {} threads)",
+                                       len.div_ceil(LEAF_SIZE), LEAF_SIZE / 1024, rayon::current_num_threads()),
+    }
+    let gbps = |seconds: f64| len as f64 / seconds / 1e9;
+    eprintln!("Throughput: {:.3} GB/s", gbps(duration.as_secs_f64()));
+    if let Some((read_time, hash_time)) = split {
+        // I/O-bound vs CPU-bound: a warm page cache makes read() a memcpy
+        eprintln!("Read: {:.3}s ({:.3} GB/s), hash: {:.3}s ({:.3} GB/s)", read_time.as_secs_f64(),
+                  gbps(read_time.as_secs_f64()), hash_time.as_secs_f64(), gbps(hash_time.as_secs_f64()));
+    }
+    let (path, detail) = compress_path();
+    eprintln!("{}", bench_cpu::report());
+    eprintln!("{}", bench_cpu::path("compress", path, detail));
     eprintln!("Hash: {:x}", result);
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(hash: &Hash) -> String {
+        hash.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn stream_matches_known_digests() {
+        assert_eq!(hex(&hash_stream(b"")), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(hex(&hash_stream(b"abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let million = vec![b'a'; 1_000_000];
+        assert_eq!(hex(&hash_stream(&million)), "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0");
+    }
+
+    #[test]
+    fn merkle_root_folds_leaves_pairwise() {
+        let data: Vec<u8> = (0..5 * LEAF_SIZE / 2).map(|i| (i % 251) as u8).collect();
+        let leaves: Vec<Hash> = data.chunks(LEAF_SIZE).map(leaf_hash).collect();
+        assert_eq!(leaves.len(), 3);
+        // Three leaves: the odd one is carried up unchanged
+        assert_eq!(merkle_root(&data), parent_hash(&parent_hash(&leaves[0], &leaves[1]), &leaves[2]));
+        // A single leaf is its own root, and differs from the plain digest by the prefix byte
+        assert_eq!(merkle_root(b"abc"), leaf_hash(b"abc"));
+        assert_ne!(leaf_hash(b"abc"), hash_stream(b"abc"));
+        assert_eq!(leaf_hash(b"abc"), Sha256::digest(b"\x00abc"));
+    }
+
+    #[test]
+    fn reader_and_mapping_match_the_stream() {
+        let data: Vec<u8> = (0..300_001u32).map(|i| (i * 7 % 256) as u8).collect();
+        let path = std::env::temp_dir().join(format!("sha256_test_{}", std::process::id()));
+        std::fs::write(&path, &data).unwrap();
+        for buffer_size in [1, 4096, DEFAULT_READ_BUFFER, 1 << 20] {
+            let (hash, _, _) = hash_reader(&mut File::open(&path).unwrap(), buffer_size).unwrap();
+            assert_eq!(hash, hash_stream(&data), "buffer {}", buffer_size);
+        }
+        #[cfg(unix)]
+        assert_eq!(Mapping::new(&File::open(&path).unwrap()).unwrap().bytes(), &data[..]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn parses_sizes_with_suffixes() {
+        assert_eq!(parse_size("4096"), Some(4096));
+        assert_eq!(parse_size("64K"), Some(64 << 10));
+        assert_eq!(parse_size("1m"), Some(1 << 20));
+        assert_eq!(parse_size("2G"), Some(2 << 30));
+        // 2^34 G is 2^64 bytes, one past usize on 64-bit targets
+        for bad in ["", "0", "K", "-1", "1.5M", "12X", "17179869184G"] {
+            assert_eq!(parse_size(bad), None, "{:?}", bad);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use smallvec::SmallVec;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const NUM_BUFFERS: usize = 20_000_000;
+const MAX_LEN: usize = 24;      // lengths drawn uniformly from 1..=MAX_LEN, so a third outgrow INLINE
+const INLINE: usize = 16;       // SmallVec's inline slots
+const MAX_CAPACITY: usize = 64; // the fixed array's size, and the largest --max-len
+const WARMUP_BUFFERS: usize = 200_000;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn next_len(seed: &mut u64, max_len: usize) -> usize {
+    1 + next_random(seed) as usize % max_len
+}
+
+// The work done on every buffer: one position-weighted pass, light enough that allocation is
+// a visible share of the loop. black_box makes each buffer exist in memory, so no variant's
+// storage can be optimized into registers
+fn digest(buf: &[u32]) -> u64 {
+    black_box(buf).iter().fold(0u64, |acc, &v| acc.wrapping_mul(31).wrapping_add(v as u64))
+}
+
+// Every variant starts from the same seed, so they fill identical buffers in the same order
+struct Run {
+    duration: Duration,
+    checksum: u64,
+    heap_allocations: usize,
+}
+
+// A fixed-size array, always on the stack. Safe Rust has no uninitialized arrays, so it is
+// zeroed each time, where C++ leaves it uninitialized
+fn run_array(buffers: usize, max_len: usize) -> Run {
+    let mut seed = 1;
+    let mut checksum = 0u64;
+    let start = Instant::now();
+    for _ in 0..buffers {
+        let len = next_len(&mut seed, max_len);
+        let mut buf = [0u32; MAX_CAPACITY];
+        for slot in &mut buf[..len] {
+            *slot = next_random(&mut seed) as u32;
+        }
+        checksum = checksum.wrapping_add(digest(&buf[..len]));
+    }
+    Run { duration: start.elapsed(), checksum, heap_allocations: 0 }
+}
+
+// Inline up to INLINE elements, one heap allocation past that
+fn run_smallvec(buffers: usize, max_len: usize) -> Run {
+    let mut seed = 1;
+    let mut checksum = 0u64;
+    let mut spills = 0;
+    let start = Instant::now();
+    for _ in 0..buffers {
+        let len = next_len(&mut seed, max_len);
+        let mut buf: SmallVec<[u32; INLINE]> = SmallVec::with_capacity(len);
+        for _ in 0..len {
+            buf.push(next_random(&mut seed) as u32);
+        }
+        spills += buf.spilled() as usize;
+        checksum = checksum.wrapping_add(digest(&buf));
+    }
+    Run { duration: start.elapsed(), checksum, heap_allocations: spills }
+}
+
+// One heap allocation per buffer
+fn run_vec(buffers: usize, max_len: usize) -> Run {
+    let mut seed = 1;
+    let mut checksum = 0u64;
+    let start = Instant::now();
+    for _ in 0..buffers {
+        let len = next_len(&mut seed, max_len);
+        let mut buf = Vec::with_capacity(len);
+        for _ in 0..len {
+            buf.push(next_random(&mut seed) as u32);
+        }
+        checksum = checksum.wrapping_add(digest(&buf));
+    }
+    Run { duration: start.elapsed(), checksum, heap_allocations: buffers }
+}
+
+fn parse_max_len() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--max-len") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|n| (1..=MAX_CAPACITY).contains(n))
+            .unwrap_or_else(|| panic!("--max-len expects a length from 1 to {}", MAX_CAPACITY)),
+        None => MAX_LEN,
+    }
+}
+
+fn report(name: &str, run: &Run) {
+    eprintln!("  {:<24} {:.3}s ({:.1} ns/buffer, {} heap allocations)",
+              name, run.duration.as_secs_f64(), run.duration.as_secs_f64() * 1e9 / NUM_BUFFERS as f64,
+              run.heap_allocations);
+}
+
+fn main() {
+    let max_len = parse_max_len();
+
+    // Warm-up
+    let _ = run_array(WARMUP_BUFFERS, max_len);
+    let _ = run_smallvec(WARMUP_BUFFERS, max_len);
+    let _ = run_vec(WARMUP_BUFFERS, max_len);
+
+    // Benchmark
+    let array = run_array(NUM_BUFFERS, max_len);
+    let small = run_smallvec(NUM_BUFFERS, max_len);
+    let heap = run_vec(NUM_BUFFERS, max_len);
+
+    let total_duration = array.duration + small.duration + heap.duration;
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Buffers: {} of 1-{} u32 ({:.1}% longer than the {} inline slots)",
+              NUM_BUFFERS, max_len, small.heap_allocations as f64 * 100.0 / NUM_BUFFERS as f64, INLINE);
+    report(&format!("[u32; {}]", MAX_CAPACITY), &array);
+    report(&format!("SmallVec<[u32; {}]>", INLINE), &small);
+    report("Vec<u32>", &heap);
+    eprintln!("Checksum: {} (all variants match: {})", array.checksum,
+              array.checksum == small.checksum && array.checksum == heap.checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_weights_by_position() {
+        assert_eq!(digest(&[3, 1, 2]), (3 * 31 + 1) * 31 + 2);
+        assert_ne!(digest(&[1, 2, 3]), digest(&[3, 2, 1]));
+        assert_eq!(digest(&[]), 0);
+    }
+
+    #[test]
+    fn variants_fill_identical_buffers() {
+        for max_len in [1, INLINE, INLINE + 1, MAX_CAPACITY] {
+            let (array, small, heap) = (run_array(5_000, max_len), run_smallvec(5_000, max_len), run_vec(5_000, max_len));
+            assert_eq!(small.checksum, array.checksum, "max_len {}", max_len);
+            assert_eq!(heap.checksum, array.checksum, "max_len {}", max_len);
+            assert_eq!(small.heap_allocations == 0, max_len <= INLINE, "max_len {}", max_len);
+        }
+    }
+}
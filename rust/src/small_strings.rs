@@ -0,0 +1,228 @@
+use compact_str::CompactString;
+use smartstring::{LazyCompact, SmartString};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const NUM_STRINGS: usize = 2_000_000;
+const MAX_LEN: usize = 22; // lengths uniform over 1..=MAX_LEN, all within libc++'s 22-byte SSO
+const ROUNDS: usize = 4;
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+fn splitmix64(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// The strings back to back in one buffer, with their lengths. Short lengths repeat often, so
+// the sorted set has runs of equal strings
+fn generate(count: usize) -> (String, Vec<usize>) {
+    let mut seed = 23;
+    let mut text = String::new();
+    let mut lengths = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len = 1 + (splitmix64(&mut seed) % MAX_LEN as u64) as usize;
+        for _ in 0..len {
+            text.push(ALPHABET[(splitmix64(&mut seed) % ALPHABET.len() as u64) as usize] as char);
+        }
+        lengths.push(len);
+    }
+    (text, lengths)
+}
+
+fn slices<'a>(text: &'a str, lengths: &[usize]) -> Vec<&'a str> {
+    let mut start = 0;
+    lengths.iter().map(|&len| {
+        start += len;
+        &text[start - len..start]
+    }).collect()
+}
+
+// FNV-1a, the same in both languages, so the hash phase measures reaching the bytes rather than
+// each standard library's hasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+// What each string type has to offer the benchmark
+trait ShortString: Clone + Ord {
+    fn create(s: &str) -> Self;
+    fn text(&self) -> &str;
+    fn on_heap(&self) -> bool;
+}
+
+// Always on the heap, with a capacity word: 24 bytes
+impl ShortString for String {
+    fn create(s: &str) -> Self {
+        String::from(s)
+    }
+
+    fn text(&self) -> &str {
+        self
+    }
+
+    fn on_heap(&self) -> bool {
+        true
+    }
+}
+
+// Always on the heap, pointer and length only: 16 bytes
+impl ShortString for Box<str> {
+    fn create(s: &str) -> Self {
+        s.into()
+    }
+
+    fn text(&self) -> &str {
+        self
+    }
+
+    fn on_heap(&self) -> bool {
+        true
+    }
+}
+
+// 24 bytes, inline up to 24
+impl ShortString for CompactString {
+    fn create(s: &str) -> Self {
+        CompactString::new(s)
+    }
+
+    fn text(&self) -> &str {
+        self.as_str()
+    }
+
+    fn on_heap(&self) -> bool {
+        self.is_heap_allocated()
+    }
+}
+
+// 24 bytes, inline up to 23
+impl ShortString for SmartString<LazyCompact> {
+    fn create(s: &str) -> Self {
+        SmartString::from(s)
+    }
+
+    fn text(&self) -> &str {
+        self.as_str()
+    }
+
+    fn on_heap(&self) -> bool {
+        !self.is_inline()
+    }
+}
+
+const PHASES: [&str; 5] = ["create", "clone", "hash", "compare", "drop"];
+
+struct Run {
+    durations: [Duration; 5],
+    hashed: u64,   // sum of the strings' hashes
+    distinct: u64, // strings unequal to their predecessor once sorted
+    ordered: u64,  // order-sensitive fold of the sorted hashes
+    on_heap: usize,
+    handle_bytes: usize,
+}
+
+// Per round: build the strings from slices, clone the set, hash every clone, sort the clones and
+// count distinct neighbours, then drop both sets
+fn run<S: ShortString>(sources: &[&str], rounds: usize) -> Run {
+    let mut run = Run { durations: [Duration::ZERO; 5], hashed: 0, distinct: 0, ordered: 0, on_heap: 0,
+                       handle_bytes: size_of::<S>() };
+    for _ in 0..rounds {
+        let start = Instant::now();
+        let strings: Vec<S> = black_box(sources).iter().map(|s| S::create(s)).collect();
+        let created = Instant::now();
+        let mut copies = strings.clone();
+        let cloned = Instant::now();
+        run.hashed = copies.iter().fold(0u64, |acc, s| acc.wrapping_add(fnv1a(s.text().as_bytes())));
+        let hashed = Instant::now();
+        copies.sort_unstable();
+        run.distinct = 1 + copies.windows(2).filter(|w| w[0] != w[1]).count() as u64;
+        let compared = Instant::now();
+
+        run.ordered = copies.iter().fold(0u64, |acc, s| acc.wrapping_mul(31).wrapping_add(fnv1a(s.text().as_bytes())));
+        run.on_heap = strings.iter().filter(|s| s.on_heap()).count();
+
+        let drop_start = Instant::now();
+        drop(black_box(copies));
+        drop(black_box(strings));
+        let dropped = Instant::now();
+
+        for (total, d) in run.durations.iter_mut().zip([
+            created - start,
+            cloned - created,
+            hashed - cloned,
+            compared - hashed,
+            dropped - drop_start,
+        ]) {
+            *total += d;
+        }
+    }
+    run
+}
+
+type Variant = (&'static str, fn(&[&str], usize) -> Run);
+
+const VARIANTS: [Variant; 4] = [
+    ("String", run::<String>),
+    ("Box<str>", run::<Box<str>>),
+    ("CompactString", run::<CompactString>),
+    ("SmartString", run::<SmartString<LazyCompact>>),
+];
+
+fn main() {
+    let (text, lengths) = generate(NUM_STRINGS);
+    let sources = slices(&text, &lengths);
+
+    // Warm-up
+    for (_, f) in VARIANTS {
+        let _ = f(&sources[..NUM_STRINGS / 100], 1);
+    }
+
+    // Benchmark
+    let runs: Vec<Run> = VARIANTS.iter().map(|(_, f)| f(&sources, ROUNDS)).collect();
+    let total_duration: Duration = runs.iter().flat_map(|r| r.durations).sum();
+    let all_match = runs.iter().all(|r| {
+        (r.hashed, r.distinct, r.ordered) == (runs[0].hashed, runs[0].distinct, runs[0].ordered)
+    });
+    let checksum = runs[0].hashed.wrapping_add(runs[0].distinct).wrapping_add(runs[0].ordered);
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Strings: {} of 1-{} bytes ({} distinct) x {} rounds", NUM_STRINGS, MAX_LEN, runs[0].distinct, ROUNDS);
+    for ((name, _), r) in VARIANTS.iter().zip(&runs) {
+        let phases: Vec<String> =
+            PHASES.iter().zip(r.durations).map(|(phase, d)| format!("{} {:.3}s", phase, d.as_secs_f64())).collect();
+        eprintln!("  {:<14} {}  ({}-byte handle, {:.1}% on the heap)", name, phases.join("  "),
+                  r.handle_bytes, r.on_heap as f64 * 100.0 / NUM_STRINGS as f64);
+    }
+    eprintln!("Checksum: {} (all variants match: {})", checksum, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_capacities() {
+        let fits = "x".repeat(MAX_LEN);
+        assert!(!CompactString::create(&fits).on_heap());
+        assert!(!SmartString::<LazyCompact>::create(&fits).on_heap());
+        assert!(SmartString::<LazyCompact>::create(&"x".repeat(24)).on_heap());
+        assert!(CompactString::create(&"x".repeat(25)).on_heap());
+        assert_eq!(Box::<str>::create(&fits).text(), fits);
+    }
+
+    #[test]
+    fn variants_agree() {
+        let (text, lengths) = generate(20_000);
+        let sources = slices(&text, &lengths);
+        assert_eq!(sources.iter().map(|s| s.len()).collect::<Vec<_>>(), lengths);
+        let runs: Vec<Run> = VARIANTS.iter().map(|(_, f)| f(&sources, 1)).collect();
+        for r in &runs[1..] {
+            assert_eq!((r.hashed, r.distinct, r.ordered), (runs[0].hashed, runs[0].distinct, runs[0].ordered));
+        }
+        assert!(runs[0].distinct > 1_000 && runs[0].distinct < 20_000);
+        assert_eq!(runs[2].on_heap, 0);
+    }
+}
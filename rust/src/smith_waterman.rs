@@ -0,0 +1,309 @@
+use rayon::prelude::*;
+use std::time::Instant;
+
+const QUERY_LEN: usize = 256;
+const NUM_SEQUENCES: usize = 5000;
+const MIN_SEQ_LEN: usize = 256;
+const MAX_SEQ_LEN: usize = 768;
+const MATCH: i16 = 2;
+const MISMATCH: i16 = -3;
+const GAP_OPEN: i16 = 5; // Cost of the first gap position
+const GAP_EXTEND: i16 = 2; // Cost of each further gap position
+const HIT_THRESHOLD: i32 = 50;
+
+const LANES: usize = 8; // 8 x i16 = one 128-bit vector
+const NEG: i16 = -16384;
+
+type Lanes = [i16; LANES];
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn score(a: u8, b: u8) -> i16 {
+    if a == b { MATCH } else { MISMATCH }
+}
+
+// DNA encoded as 0..4; half of the database sequences embed a mutated slice of the query
+fn generate_data() -> (Vec<u8>, Vec<Vec<u8>>) {
+    let mut seed = 2718u64;
+    let query: Vec<u8> = (0..QUERY_LEN).map(|_| (next_random(&mut seed) % 4) as u8).collect();
+
+    let database = (0..NUM_SEQUENCES)
+        .map(|_| {
+            let len = MIN_SEQ_LEN + (next_random(&mut seed) % (MAX_SEQ_LEN - MIN_SEQ_LEN + 1) as u64) as usize;
+            let mut seq: Vec<u8> = (0..len).map(|_| (next_random(&mut seed) % 4) as u8).collect();
+
+            if next_random(&mut seed).is_multiple_of(2) {
+                let slice_len = 64 + (next_random(&mut seed) % 128) as usize;
+                let from = (next_random(&mut seed) % (QUERY_LEN - slice_len + 1) as u64) as usize;
+                let to = (next_random(&mut seed) % (len - slice_len + 1) as u64) as usize;
+                for k in 0..slice_len {
+                    seq[to + k] = if next_random(&mut seed).is_multiple_of(10) {
+                        (next_random(&mut seed) % 4) as u8
+                    } else {
+                        query[from + k]
+                    };
+                }
+            }
+            seq
+        })
+        .collect();
+
+    (query, database)
+}
+
+// Gotoh's affine-gap recurrence, one database column at a time
+fn sw_scalar(query: &[u8], db: &[u8], h: &mut [i32], e: &mut [i32]) -> i32 {
+    let (open, extend) = (GAP_OPEN as i32, GAP_EXTEND as i32);
+    h.fill(0);
+    e.fill(NEG as i32);
+    let mut best = 0;
+
+    for &d in db {
+        let mut h_diag = 0;
+        let mut h_up = 0;
+        let mut f = NEG as i32;
+        for (i, &q) in query.iter().enumerate() {
+            let h_left = h[i];
+            let e_i = (e[i] - extend).max(h_left - open);
+            f = (f - extend).max(h_up - open);
+            let h_new = (h_diag + score(q, d) as i32).max(e_i).max(f).max(0);
+            e[i] = e_i;
+            h_diag = h_left;
+            h[i] = h_new;
+            h_up = h_new;
+            best = best.max(h_new);
+        }
+    }
+
+    best
+}
+
+#[inline(always)]
+fn add(mut a: Lanes, b: &Lanes) -> Lanes {
+    for (x, &y) in a.iter_mut().zip(b) {
+        *x += y;
+    }
+    a
+}
+
+#[inline(always)]
+fn sub(mut a: Lanes, b: i16) -> Lanes {
+    for x in a.iter_mut() {
+        *x -= b;
+    }
+    a
+}
+
+#[inline(always)]
+fn max(mut a: Lanes, b: &Lanes) -> Lanes {
+    for (x, &y) in a.iter_mut().zip(b) {
+        *x = (*x).max(y);
+    }
+    a
+}
+
+#[inline(always)]
+fn shift_in(a: &Lanes, fill: i16) -> Lanes {
+    let mut r = [fill; LANES];
+    r[1..].copy_from_slice(&a[..LANES - 1]);
+    r
+}
+
+#[inline(always)]
+fn any_greater(a: &Lanes, b: &Lanes) -> bool {
+    a.iter().zip(b).fold(false, |acc, (x, y)| acc | (x > y))
+}
+
+// Farrar's striped layout: lane k of segment j holds query position k * seg_len + j
+struct StripedProfile {
+    seg_len: usize,
+    profile: Vec<Lanes>, // [residue][segment]
+}
+
+impl StripedProfile {
+    fn new(query: &[u8]) -> Self {
+        let seg_len = query.len().div_ceil(LANES);
+        let mut profile = vec![[0i16; LANES]; 4 * seg_len];
+        for residue in 0..4u8 {
+            for j in 0..seg_len {
+                for (k, slot) in profile[residue as usize * seg_len + j].iter_mut().enumerate() {
+                    let pos = k * seg_len + j;
+                    // Padding positions always mismatch, so they can never raise the best score
+                    *slot = if pos < query.len() { score(query[pos], residue) } else { MISMATCH };
+                }
+            }
+        }
+        StripedProfile { seg_len, profile }
+    }
+}
+
+struct StripedBuffers {
+    h_load: Vec<Lanes>,
+    h_store: Vec<Lanes>,
+    e: Vec<Lanes>,
+}
+
+fn sw_striped(profile: &StripedProfile, db: &[u8], buffers: &mut StripedBuffers) -> i32 {
+    let seg_len = profile.seg_len;
+    let StripedBuffers { h_load, h_store, e } = buffers;
+    h_store.clear();
+    h_store.resize(seg_len, [0; LANES]);
+    h_load.clear();
+    h_load.resize(seg_len, [0; LANES]);
+    e.clear();
+    e.resize(seg_len, [NEG; LANES]);
+    let mut v_max = [0i16; LANES];
+
+    for &d in db {
+        let scores = &profile.profile[d as usize * seg_len..(d as usize + 1) * seg_len];
+        let mut v_f = [NEG; LANES];
+        let mut v_h = shift_in(&h_store[seg_len - 1], 0);
+        std::mem::swap(h_load, h_store);
+
+        for j in 0..seg_len {
+            v_h = add(v_h, &scores[j]);
+            v_h = max(v_h, &e[j]);
+            v_h = max(v_h, &v_f);
+            v_h = max(v_h, &[0; LANES]);
+            h_store[j] = v_h;
+            v_max = max(v_max, &v_h);
+
+            v_h = sub(v_h, GAP_OPEN);
+            e[j] = max(sub(e[j], GAP_EXTEND), &v_h);
+            v_f = max(sub(v_f, GAP_EXTEND), &v_h);
+            v_h = h_load[j];
+        }
+
+        // Lazy-F: propagate vertical gaps that crossed a segment boundary
+        'lazy_f: for _ in 0..LANES {
+            v_f = shift_in(&v_f, NEG);
+            for j in 0..seg_len {
+                let h = max(h_store[j], &v_f);
+                h_store[j] = h;
+                v_max = max(v_max, &h);
+                let h_open = sub(h, GAP_OPEN);
+                e[j] = max(e[j], &h_open);
+                v_f = sub(v_f, GAP_EXTEND);
+                if !any_greater(&v_f, &h_open) {
+                    break 'lazy_f;
+                }
+            }
+        }
+    }
+
+    v_max.iter().copied().max().unwrap() as i32
+}
+
+fn run_scalar(query: &[u8], database: &[Vec<u8>]) -> Vec<i32> {
+    database
+        .par_iter()
+        .map_init(
+            || (vec![0; query.len()], vec![0; query.len()]),
+            |(h, e), db| sw_scalar(query, db, h, e),
+        )
+        .collect()
+}
+
+fn run_striped(profile: &StripedProfile, database: &[Vec<u8>]) -> Vec<i32> {
+    database
+        .par_iter()
+        .map_init(
+            || StripedBuffers { h_load: Vec::new(), h_store: Vec::new(), e: Vec::new() },
+            |buffers, db| sw_striped(profile, db, buffers),
+        )
+        .collect()
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let (query, database) = generate_data();
+    let profile = StripedProfile::new(&query);
+    let cells: usize = database.iter().map(|s| s.len() * QUERY_LEN).sum();
+
+    // Warm-up
+    let _ = run_scalar(&query, &database[..100]);
+    let _ = run_striped(&profile, &database[..100]);
+
+    // Benchmark scalar
+    let start = Instant::now();
+    measure.start();
+    let scalar_scores = run_scalar(&query, &database);
+    measure.stop();
+    let scalar_duration = start.elapsed();
+
+    // Benchmark striped
+    let start = Instant::now();
+    measure.start();
+    let striped_scores = run_striped(&profile, &database);
+    measure.stop();
+    let striped_duration = start.elapsed();
+
+    // Checksum
+    let checksum: i64 = scalar_scores.iter().map(|&s| s as i64).sum();
+    let striped_checksum: i64 = striped_scores.iter().map(|&s| s as i64).sum();
+    let hits = scalar_scores.iter().filter(|&&s| s >= HIT_THRESHOLD).count();
+
+    measure.report();
+    eprintln!("Scalar: {:.6}s ({:.2} GCUPS), striped: {:.6}s ({:.2} GCUPS)",
+              scalar_duration.as_secs_f64(), cells as f64 / scalar_duration.as_secs_f64() / 1e9,
+              striped_duration.as_secs_f64(), cells as f64 / striped_duration.as_secs_f64() / 1e9);
+    eprintln!("Sequences: {}, hits (score >= {}): {}", NUM_SEQUENCES, HIT_THRESHOLD, hits);
+    eprintln!("Checksum: {} (striped: {})", checksum, striped_checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn both(query: &[u8], db: &[u8]) -> (i32, i32) {
+        let (mut h, mut e) = (vec![0; query.len()], vec![0; query.len()]);
+        let mut buffers = StripedBuffers { h_load: Vec::new(), h_store: Vec::new(), e: Vec::new() };
+        (sw_scalar(query, db, &mut h, &mut e), sw_striped(&StripedProfile::new(query), db, &mut buffers))
+    }
+
+    #[test]
+    fn scores_known_alignments() {
+        let query = [0, 0, 0, 0, 1, 1, 1, 1];
+        assert_eq!(both(&query, &query), (16, 16));
+        // One inserted base costs a gap open but keeps both halves of the match
+        assert_eq!(both(&query, &[0, 0, 0, 0, 2, 1, 1, 1, 1]), (11, 11));
+        // Two inserted bases cost more than the shorter half is worth
+        assert_eq!(both(&query, &[3, 0, 0, 0, 0, 2, 2, 2, 1, 1, 1, 1, 3]), (8, 8));
+        assert_eq!(both(&query, &[2, 3, 2, 3]), (0, 0));
+        assert_eq!(both(&query, &[]), (0, 0));
+    }
+
+    #[test]
+    fn striped_matches_scalar() {
+        let mut seed = 4u64;
+        // Query lengths that leave padding lanes, and long enough runs for lazy-F to matter
+        for query_len in [1, 7, 13, 64, 100] {
+            let query: Vec<u8> = (0..query_len).map(|_| (next_random(&mut seed) % 4) as u8).collect();
+            for _ in 0..20 {
+                let len = 1 + (next_random(&mut seed) % 300) as usize;
+                let mut db: Vec<u8> = (0..len).map(|_| (next_random(&mut seed) % 4) as u8).collect();
+                if len > query_len {
+                    db[..query_len].copy_from_slice(&query);
+                    db.insert(query_len / 2, 3);
+                }
+                let (scalar, striped) = both(&query, &db);
+                assert_eq!(scalar, striped, "query {} db {}", query_len, len);
+            }
+        }
+    }
+
+    #[test]
+    fn generated_database_agrees_across_kernels() {
+        let (query, database) = generate_data();
+        let head = &database[..200];
+        let scalar = run_scalar(&query, head);
+        assert_eq!(run_striped(&StripedProfile::new(&query), head), scalar);
+        // The half that embed a query slice score far above chance
+        assert!(scalar.iter().filter(|&&s| s >= HIT_THRESHOLD).count() > 50);
+    }
+}
@@ -0,0 +1,222 @@
+use rusqlite::{params, Connection, Result};
+use std::time::{Duration, Instant};
+
+const NUM_ROWS: i64 = 1_000_000;
+const NUM_CUSTOMERS: u64 = 100_000;
+const NUM_PRODUCTS: u64 = 10_000;
+const POINT_READS: usize = 200_000;
+const CUSTOMER_LOOKUPS: usize = 50_000;
+const POINT_UPDATES: usize = 100_000;
+const ANALYTICAL_ROUNDS: usize = 5;
+const REGIONS: [&str; 8] = ["north", "south", "east", "west", "central", "coastal", "mountain", "island"];
+
+const ANALYTICAL_QUERIES: [&str; 3] = [
+    "SELECT region, COUNT(*), SUM(quantity * price_cents) FROM orders GROUP BY region ORDER BY region",
+    "SELECT product, SUM(quantity) AS units FROM orders GROUP BY product ORDER BY units DESC, product LIMIT 10",
+    "SELECT customer, COUNT(*) AS n FROM orders WHERE region = 'island' GROUP BY customer HAVING n > 2 ORDER BY n DESC, customer LIMIT 10",
+];
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn create_schema(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE orders (
+             id INTEGER PRIMARY KEY,
+             customer INTEGER NOT NULL,
+             product INTEGER NOT NULL,
+             quantity INTEGER NOT NULL,
+             price_cents INTEGER NOT NULL,
+             region TEXT NOT NULL
+         );",
+    )
+}
+
+// One transaction, one prepared statement, a bind + step + reset per row
+fn bulk_insert(conn: &mut Connection, rows: i64) -> Result<()> {
+    let mut seed = 8086u64;
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare("INSERT INTO orders VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+        for id in 0..rows {
+            let customer = next_random(&mut seed) % NUM_CUSTOMERS;
+            let product = next_random(&mut seed) % NUM_PRODUCTS;
+            let quantity = 1 + next_random(&mut seed) % 10;
+            let price_cents = 100 + next_random(&mut seed) % 100_000;
+            let region = REGIONS[(next_random(&mut seed) % REGIONS.len() as u64) as usize];
+            stmt.execute(params![id, customer as i64, product as i64, quantity as i64, price_cents as i64, region])?;
+        }
+    }
+    tx.commit()
+}
+
+fn build_indexes(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE INDEX idx_orders_customer ON orders(customer);
+         CREATE INDEX idx_orders_region_product ON orders(region, product);",
+    )
+}
+
+// Primary-key lookups and secondary-index aggregates; returns a checksum of everything read
+fn point_reads(conn: &Connection, rows: i64, reads: usize, lookups: usize) -> Result<i64> {
+    let mut seed = 4004u64;
+    let mut checksum = 0i64;
+    let mut by_id = conn.prepare("SELECT quantity, price_cents FROM orders WHERE id = ?1")?;
+    for _ in 0..reads {
+        let id = (next_random(&mut seed) % rows as u64) as i64;
+        let (quantity, price): (i64, i64) = by_id.query_row(params![id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        checksum = checksum.wrapping_add(quantity * price);
+    }
+    let mut by_customer = conn.prepare("SELECT COUNT(*), COALESCE(SUM(quantity), 0) FROM orders WHERE customer = ?1")?;
+    for _ in 0..lookups {
+        let customer = (next_random(&mut seed) % NUM_CUSTOMERS) as i64;
+        let (count, units): (i64, i64) = by_customer.query_row(params![customer], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        checksum = checksum.wrapping_add(count * 1_000 + units);
+    }
+    Ok(checksum)
+}
+
+fn point_updates(conn: &mut Connection, rows: i64, updates: usize) -> Result<()> {
+    let mut seed = 6502u64;
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare("UPDATE orders SET quantity = quantity + 1 WHERE id = ?1")?;
+        for _ in 0..updates {
+            let id = (next_random(&mut seed) % rows as u64) as i64;
+            stmt.execute(params![id])?;
+        }
+    }
+    tx.commit()
+}
+
+// Full-scan group-bys; every column after the group key is folded into the checksum
+fn analytics(conn: &Connection) -> Result<i64> {
+    let mut checksum = 0i64;
+    for query in ANALYTICAL_QUERIES {
+        let mut stmt = conn.prepare_cached(query)?;
+        let columns = stmt.column_count();
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            for col in 1..columns {
+                checksum = checksum.wrapping_mul(31).wrapping_add(row.get::<_, i64>(col)?);
+            }
+        }
+    }
+    Ok(checksum)
+}
+
+struct Timings {
+    insert: Duration,
+    index: Duration,
+    reads: Duration,
+    updates: Duration,
+    analytics: Duration,
+}
+
+// Every phase is divided by `scale`, so the warm-up is a miniature of the full run
+fn run(scale: usize) -> Result<(Timings, i64, i64)> {
+    let rows = NUM_ROWS / scale as i64;
+    let mut conn = Connection::open_in_memory()?;
+    create_schema(&conn)?;
+
+    let start = Instant::now();
+    bulk_insert(&mut conn, rows)?;
+    let insert = start.elapsed();
+
+    let start = Instant::now();
+    build_indexes(&conn)?;
+    let index = start.elapsed();
+
+    let start = Instant::now();
+    let read_checksum = point_reads(&conn, rows, POINT_READS / scale, CUSTOMER_LOOKUPS / scale)?;
+    let reads = start.elapsed();
+
+    let start = Instant::now();
+    point_updates(&mut conn, rows, POINT_UPDATES / scale)?;
+    let updates = start.elapsed();
+
+    let start = Instant::now();
+    let mut analytics_checksum = 0;
+    for _ in 0..ANALYTICAL_ROUNDS.div_ceil(scale) {
+        analytics_checksum = analytics(&conn)?;
+    }
+    let analytics = start.elapsed();
+
+    Ok((Timings { insert, index, reads, updates, analytics }, read_checksum, analytics_checksum))
+}
+
+fn main() -> Result<()> {
+    // Warm-up
+    let _ = run(100)?;
+
+    // Benchmark
+    let (t, read_checksum, analytics_checksum) = run(1)?;
+    let total_duration = t.insert + t.index + t.reads + t.updates + t.analytics;
+    let rate = |n: usize, d: Duration| n as f64 / d.as_secs_f64();
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Bulk insert: {:.0} rows/s, index build: {:.3}s",
+              rate(NUM_ROWS as usize, t.insert), t.index.as_secs_f64());
+    eprintln!("Point reads: {:.0} TPS, point updates: {:.0} TPS",
+              rate(POINT_READS + CUSTOMER_LOOKUPS, t.reads), rate(POINT_UPDATES, t.updates));
+    eprintln!("Analytical queries: {:.2} queries/s", rate(ANALYTICAL_ROUNDS * ANALYTICAL_QUERIES.len(), t.analytics));
+    eprintln!("SQLite {}", rusqlite::version());
+    eprintln!("Checksum: {} (analytics: {})", read_checksum, analytics_checksum);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loaded(rows: i64) -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        bulk_insert(&mut conn, rows).unwrap();
+        conn
+    }
+
+    fn total_quantity(conn: &Connection) -> i64 {
+        conn.query_row("SELECT SUM(quantity) FROM orders", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn inserts_every_row_in_range() {
+        let conn = loaded(5000);
+        let (count, min_id, max_id, bad): (i64, i64, i64, i64) = conn.query_row(
+            "SELECT COUNT(*), MIN(id), MAX(id),
+                    SUM(quantity NOT BETWEEN 1 AND 10 OR customer >= 100000 OR product >= 10000)
+             FROM orders", [], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))).unwrap();
+        assert_eq!((count, min_id, max_id, bad), (5000, 0, 4999, 0));
+        let regions: i64 = conn.query_row("SELECT COUNT(DISTINCT region) FROM orders", [], |row| row.get(0)).unwrap();
+        assert_eq!(regions, REGIONS.len() as i64);
+    }
+
+    #[test]
+    fn indexes_leave_results_unchanged() {
+        let conn = loaded(5000);
+        let (reads, analytical) = (point_reads(&conn, 5000, 500, 100).unwrap(), analytics(&conn).unwrap());
+        build_indexes(&conn).unwrap();
+        assert_eq!(point_reads(&conn, 5000, 500, 100).unwrap(), reads);
+        assert_eq!(analytics(&conn).unwrap(), analytical);
+    }
+
+    #[test]
+    fn updates_add_one_unit_each() {
+        let mut conn = loaded(5000);
+        let before = total_quantity(&conn);
+        point_updates(&mut conn, 5000, 777).unwrap();
+        assert_eq!(total_quantity(&conn), before + 777);
+    }
+
+    #[test]
+    fn scaled_runs_are_reproducible() {
+        let (_, reads, analytical) = run(200).unwrap();
+        assert_eq!(run(200).unwrap().1, reads);
+        assert_eq!(run(200).unwrap().2, analytical);
+    }
+}
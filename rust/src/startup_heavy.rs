@@ -0,0 +1,25 @@
+// Spawned by startup_time: reports when main was reached, then does what a small async HTTP tool
+// does before its first request: a tokio runtime with worker threads, a reqwest client, one request
+// built and handed to a task. Nothing is sent
+
+const WORKERS: usize = 2;
+const URL: &str = "http://127.0.0.1:8080/health";
+
+fn main() {
+    let mut now = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: now is a valid timespec to write into
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+    let entered = now.tv_sec as u64 * 1_000_000_000 + now.tv_nsec as u64;
+    println!("{}", entered);
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(WORKERS)
+        .enable_all()
+        .build()
+        .unwrap();
+    let client = reqwest::Client::builder().user_agent("startup_heavy").build().unwrap();
+    let request = client.get(URL).header("accept", "application/json").build().unwrap();
+    // The request's target, echoed from a worker so the parent can check the setup really ran
+    let target = runtime.block_on(async move { tokio::spawn(async move { request.url().path().len() }).await.unwrap() });
+    println!("{}", target);
+}
@@ -0,0 +1,169 @@
+use std::ffi::{c_char, CStr, CString};
+use std::fs::File;
+use std::io::Read;
+use std::os::fd::FromRawFd;
+use std::path::Path;
+use std::ptr;
+
+const RUNS: usize = 500; // per helper
+const WARMUP_RUNS: usize = 20;
+// The helpers sit next to this binary. The trivial one links only std; the heavy one links tokio
+// and reqwest, and after reporting main it prints the request path length from a runtime worker
+const HELPERS: [(&str, Option<&str>); 2] = [("startup_trivial", None), ("startup_heavy", Some("7"))];
+
+extern "C" {
+    static environ: *const *mut c_char;
+}
+
+// CLOCK_MONOTONIC is shared by every process, so a child's reading can be compared with ours
+fn monotonic_ns() -> u64 {
+    let mut now = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: now is a valid timespec to write into
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+    now.tv_sec as u64 * 1_000_000_000 + now.tv_nsec as u64
+}
+
+struct Spawned {
+    start: u64,
+    output: String,
+    exited: u64,
+    status_ok: bool,
+}
+
+// posix_spawn a child with its stdout on a pipe, read everything it prints and reap it
+fn spawn_once(argv: &[&CStr]) -> Spawned {
+    let mut fds = [0; 2];
+    // SAFETY: fds has room for the two descriptors
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0, "pipe failed");
+    let [read_end, write_end] = fds;
+    let mut args: Vec<*mut c_char> = argv.iter().map(|a| a.as_ptr() as *mut c_char).collect();
+    args.push(ptr::null_mut());
+
+    let mut pid = 0;
+    let start;
+    // SAFETY: the file actions are initialized before use and destroyed after; argv is
+    // NUL-terminated and outlives the call
+    unsafe {
+        let mut actions = std::mem::zeroed();
+        libc::posix_spawn_file_actions_init(&mut actions);
+        libc::posix_spawn_file_actions_adddup2(&mut actions, write_end, 1);
+        libc::posix_spawn_file_actions_addclose(&mut actions, read_end);
+        libc::posix_spawn_file_actions_addclose(&mut actions, write_end);
+        start = monotonic_ns();
+        let rc = libc::posix_spawn(&mut pid, args[0], &actions, ptr::null(), args.as_ptr(), environ);
+        libc::posix_spawn_file_actions_destroy(&mut actions);
+        assert_eq!(rc, 0, "posix_spawn of {:?} failed", argv[0]);
+        libc::close(write_end);
+    }
+
+    let mut output = String::new();
+    // SAFETY: read_end is an open descriptor this File now owns and closes
+    let _ = unsafe { File::from_raw_fd(read_end) }.read_to_string(&mut output);
+    let mut status = 0;
+    // SAFETY: pid is a child of this process that has not been reaped yet
+    let reaped = unsafe { libc::waitpid(pid, &mut status, 0) };
+    let exited = monotonic_ns();
+    let status_ok = reaped == pid && libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0;
+    Spawned { start, output, exited, status_ok }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Sample {
+    to_main: u64, // spawn call to the first line of main, ns
+    to_exit: u64, // spawn call to the child reaped, ns
+}
+
+// The child's report: its main timestamp, then the line it was expected to print if any
+fn sample(spawned: &Spawned, expect: Option<&str>) -> Option<Sample> {
+    let mut lines = spawned.output.lines();
+    let entered: u64 = lines.next()?.parse().ok()?;
+    if !spawned.status_ok || lines.next() != expect || lines.next().is_some() {
+        return None;
+    }
+    Some(Sample { to_main: entered.checked_sub(spawned.start)?, to_exit: spawned.exited - spawned.start })
+}
+
+// (median, mean, min) in microseconds
+fn summarize(values: &mut [u64]) -> (f64, f64, f64) {
+    values.sort_unstable();
+    let us = |ns: u64| ns as f64 / 1e3;
+    let mean = values.iter().sum::<u64>() as f64 / values.len() as f64 / 1e3;
+    (us(values[values.len() / 2]), mean, us(values[0]))
+}
+
+fn run(path: &Path, expect: Option<&str>, runs: usize) -> Result<Vec<Sample>, String> {
+    let program = CString::new(path.as_os_str().as_encoded_bytes()).unwrap();
+    (0..runs)
+        .map(|_| {
+            let spawned = spawn_once(&[&program]);
+            sample(&spawned, expect).ok_or_else(|| format!("{} did not report cleanly: {:?}", path.display(), spawned.output))
+        })
+        .collect()
+}
+
+fn main() {
+    let dir = std::env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let mut results = Vec::new();
+    for (name, expect) in HELPERS {
+        let path = dir.join(name);
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or_else(|_| {
+            eprintln!("{} not found next to startup_time; build every binary", path.display());
+            std::process::exit(1);
+        });
+
+        // Warm-up: the first spawns fault the binary into the page cache
+        let measured = run(&path, expect, WARMUP_RUNS).and_then(|_| run(&path, expect, RUNS));
+        match measured {
+            Ok(samples) => results.push((name, size, samples)),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // The headline is the trivial helper, the floor every program in the language pays
+    let total_ns: u64 = results[0].2.iter().map(|s| s.to_exit).sum();
+    println!("{:.6}", total_ns as f64 / 1e9);
+    eprintln!("{} spawns per helper, us as median / mean / min", RUNS);
+    let mut medians = Vec::new();
+    for (name, size, samples) in &results {
+        let (main_med, main_mean, main_min) = summarize(&mut samples.iter().map(|s| s.to_main).collect::<Vec<_>>());
+        let (exit_med, exit_mean, exit_min) = summarize(&mut samples.iter().map(|s| s.to_exit).collect::<Vec<_>>());
+        eprintln!("  {:<16} {:>6.2} MB   to main {:7.1} / {:7.1} / {:7.1}   to exit {:7.1} / {:7.1} / {:7.1}", name,
+                  *size as f64 / 1e6, main_med, main_mean, main_min, exit_med, exit_mean, exit_min);
+        medians.push((main_med, exit_med));
+    }
+    eprintln!("Heavy over trivial: +{:.1} us to main, +{:.1} us to exit (medians)", medians[1].0 - medians[0].0,
+              medians[1].1 - medians[0].1);
+    eprintln!("Checksum: {}", results[0].2.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spawned_output_is_captured() {
+        let spawned = spawn_once(&[c"/bin/echo", c"hello"]);
+        assert!(spawned.status_ok);
+        assert_eq!(spawned.output, "hello\n");
+        assert!(spawned.exited >= spawned.start);
+    }
+
+    #[test]
+    fn samples_need_a_clean_report() {
+        let spawned = |output: &str, status_ok| Spawned { start: 1_000, output: output.to_string(), exited: 5_000, status_ok };
+        assert_eq!(sample(&spawned("3000\n", true), None), Some(Sample { to_main: 2_000, to_exit: 4_000 }));
+        assert_eq!(sample(&spawned("3000\n7\n", true), Some("7")), Some(Sample { to_main: 2_000, to_exit: 4_000 }));
+        assert_eq!(sample(&spawned("3000\n", true), Some("7")), None);
+        assert_eq!(sample(&spawned("3000\n", false), None), None);
+        assert_eq!(sample(&spawned("500\n", true), None), None);
+        assert_eq!(sample(&spawned("", true), None), None);
+    }
+
+    #[test]
+    fn summary_is_median_mean_and_min() {
+        assert_eq!(summarize(&mut [5_000, 1_000, 3_000]), (3.0, 3.0, 1.0));
+    }
+}
@@ -0,0 +1,9 @@
+// Spawned by startup_time: reports when main was reached and exits, linking nothing but std
+
+fn main() {
+    let mut now = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: now is a valid timespec to write into
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+    let entered = now.tv_sec as u64 * 1_000_000_000 + now.tv_nsec as u64;
+    println!("{}", entered);
+}
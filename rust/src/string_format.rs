@@ -0,0 +1,173 @@
+use std::fmt::Write;
+use std::time::Instant;
+
+const NUM_RECORDS: usize = 5_000_000;
+const NAMES: [&str; 8] = ["alice", "bob", "carol", "dave", "erin", "frank", "grace", "heidi"];
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+struct Record {
+    id: u64,
+    name: &'static str,
+    score: f64,
+    count: u32,
+    active: bool,
+}
+
+// Scores are odd multiples of 0.001, so the shortest round-trip form always has exactly
+// three decimals and every formatting strategy produces byte-identical strings
+fn generate_records() -> Vec<Record> {
+    let mut seed = 1618u64;
+    (0..NUM_RECORDS)
+        .map(|_| Record {
+            id: next_random(&mut seed) * 1000 + next_random(&mut seed) % 1000,
+            name: NAMES[(next_random(&mut seed) % NAMES.len() as u64) as usize],
+            score: ((next_random(&mut seed) % 1_000_000) | 1) as f64 / 1000.0,
+            count: (next_random(&mut seed) % 100_000) as u32,
+            active: next_random(&mut seed).is_multiple_of(2),
+        })
+        .collect()
+}
+
+#[inline]
+fn fingerprint(s: &str) -> u64 {
+    s.len() as u64 + s.as_bytes()[s.len() / 2] as u64
+}
+
+// format! allocates a fresh String per record
+fn run_format(records: &[Record]) -> u64 {
+    records
+        .iter()
+        .map(|r| {
+            let s = format!("id={} name={} score={} count={} active={}", r.id, r.name, r.score, r.count, r.active);
+            fingerprint(&s)
+        })
+        .sum()
+}
+
+// Piecewise push_str with to_string() for each number
+fn run_push_str(records: &[Record]) -> u64 {
+    records
+        .iter()
+        .map(|r| {
+            let mut s = String::new();
+            s.push_str("id=");
+            s.push_str(&r.id.to_string());
+            s.push_str(" name=");
+            s.push_str(r.name);
+            s.push_str(" score=");
+            s.push_str(&r.score.to_string());
+            s.push_str(" count=");
+            s.push_str(&r.count.to_string());
+            s.push_str(" active=");
+            s.push_str(if r.active { "true" } else { "false" });
+            fingerprint(&s)
+        })
+        .sum()
+}
+
+// write! into one preallocated buffer that is cleared between records
+fn run_write(records: &[Record]) -> u64 {
+    let mut buf = String::with_capacity(128);
+    records
+        .iter()
+        .map(|r| {
+            buf.clear();
+            write!(buf, "id={} name={} score={} count={} active={}", r.id, r.name, r.score, r.count, r.active).unwrap();
+            fingerprint(&buf)
+        })
+        .sum()
+}
+
+// Dedicated integer/float formatters writing into a reused buffer
+fn run_itoa_ryu(records: &[Record]) -> u64 {
+    let mut buf = String::with_capacity(128);
+    let mut ints = itoa::Buffer::new();
+    let mut floats = ryu::Buffer::new();
+    records
+        .iter()
+        .map(|r| {
+            buf.clear();
+            buf.push_str("id=");
+            buf.push_str(ints.format(r.id));
+            buf.push_str(" name=");
+            buf.push_str(r.name);
+            buf.push_str(" score=");
+            buf.push_str(floats.format_finite(r.score));
+            buf.push_str(" count=");
+            buf.push_str(ints.format(r.count));
+            buf.push_str(" active=");
+            buf.push_str(if r.active { "true" } else { "false" });
+            fingerprint(&buf)
+        })
+        .sum()
+}
+
+fn main() {
+    let records = generate_records();
+    let warmup = &records[..NUM_RECORDS / 100];
+
+    // Warm-up
+    let _ = run_format(warmup) + run_push_str(warmup) + run_write(warmup) + run_itoa_ryu(warmup);
+
+    // Benchmark each strategy
+    let start = Instant::now();
+    let format_checksum = run_format(&records);
+    let format_duration = start.elapsed();
+
+    let start = Instant::now();
+    let push_checksum = run_push_str(&records);
+    let push_duration = start.elapsed();
+
+    let start = Instant::now();
+    let write_checksum = run_write(&records);
+    let write_duration = start.elapsed();
+
+    let start = Instant::now();
+    let itoa_checksum = run_itoa_ryu(&records);
+    let itoa_duration = start.elapsed();
+
+    let total_duration = format_duration + push_duration + write_duration + itoa_duration;
+    let rate = |d: std::time::Duration| NUM_RECORDS as f64 / d.as_secs_f64();
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("format!: {:.0} strings/s, push_str: {:.0} strings/s, write!: {:.0} strings/s, itoa/ryu: {:.0} strings/s",
+              rate(format_duration), rate(push_duration), rate(write_duration), rate(itoa_duration));
+    eprintln!("Checksum: {} (push_str: {}, write!: {}, itoa/ryu: {})",
+              format_checksum, push_checksum, write_checksum, itoa_checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategies_agree_on_a_known_record() {
+        let records = [Record { id: 42, name: "carol", score: 12.345, count: 7, active: false }];
+        let line = "id=42 name=carol score=12.345 count=7 active=false";
+        // 50 bytes, and the middle byte is the decimal point of the score
+        assert_eq!(fingerprint(line), 50 + b'.' as u64);
+        for run in [run_format, run_push_str, run_write, run_itoa_ryu] {
+            assert_eq!(run(&records), fingerprint(line));
+        }
+    }
+
+    #[test]
+    fn scores_always_print_three_decimals() {
+        let records = generate_records();
+        let mut floats = ryu::Buffer::new();
+        for r in &records {
+            let shortest = r.score.to_string();
+            assert_eq!(shortest.len() - shortest.find('.').unwrap(), 4, "{}", shortest);
+            assert_eq!(floats.format_finite(r.score), shortest);
+        }
+        let head = &records[..10_000];
+        let expected = run_format(head);
+        assert_eq!((run_push_str(head), run_write(head), run_itoa_ryu(head)), (expected, expected, expected));
+    }
+}
@@ -0,0 +1,355 @@
+use std::time::{Duration, Instant};
+
+const DEFAULT_TREES: usize = 5_000;
+const MAX_DEPTH: u32 = 10;
+const ORDER: usize = 3; // d/dx taken three times, simplifying after each
+const EVAL_AT: f64 = 0.3;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Expressions in one variable x, with integer constants
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Num(i64),
+    X,
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Pow(Box<Expr>, u32),
+    Sin(Box<Expr>),
+    Cos(Box<Expr>),
+    Exp(Box<Expr>),
+}
+
+use Expr::*;
+
+fn num(v: i64) -> Box<Expr> {
+    Box::new(Num(v))
+}
+
+// A random tree: leaves are x or a small constant, inner nodes mostly arithmetic
+fn build(seed: &mut u64, depth: u32) -> Box<Expr> {
+    let r = next_random(seed);
+    Box::new(match r % 16 {
+        _ if depth == 0 => leaf(r),
+        0..=3 => leaf(r),
+        4..=6 => Add(build(seed, depth - 1), build(seed, depth - 1)),
+        7 => Sub(build(seed, depth - 1), build(seed, depth - 1)),
+        8..=10 => Mul(build(seed, depth - 1), build(seed, depth - 1)),
+        11 => Neg(build(seed, depth - 1)),
+        12 => Pow(build(seed, depth - 1), 2 + ((r >> 8) % 3) as u32),
+        13 => Sin(build(seed, depth - 1)),
+        14 => Cos(build(seed, depth - 1)),
+        _ => Exp(build(seed, depth - 1)),
+    })
+}
+
+fn leaf(r: u64) -> Expr {
+    if (r >> 8).is_multiple_of(3) {
+        Num(1 + ((r >> 10) % 9) as i64)
+    } else {
+        X
+    }
+}
+
+// d/dx by the textbook rules; every subtree a rule reuses is cloned, as a naive CAS would
+fn derive(e: &Expr) -> Box<Expr> {
+    Box::new(match e {
+        Num(_) => Num(0),
+        X => Num(1),
+        Neg(a) => Neg(derive(a)),
+        Add(a, b) => Add(derive(a), derive(b)),
+        Sub(a, b) => Sub(derive(a), derive(b)),
+        Mul(a, b) => Add(Box::new(Mul(derive(a), b.clone())), Box::new(Mul(a.clone(), derive(b)))),
+        Pow(a, n) => Mul(Box::new(Mul(num(*n as i64), Box::new(Pow(a.clone(), n - 1)))), derive(a)),
+        Sin(a) => Mul(Box::new(Cos(a.clone())), derive(a)),
+        Cos(a) => Neg(Box::new(Mul(Box::new(Sin(a.clone())), derive(a)))),
+        Exp(a) => Mul(Box::new(Exp(a.clone())), derive(a)),
+    })
+}
+
+fn constant(e: &Expr) -> Option<i64> {
+    match e {
+        Num(v) => Some(*v),
+        _ => None,
+    }
+}
+
+// Constants fold only while the result is exact as an f64, so folding never changes a value
+fn exact(v: Option<i64>) -> bool {
+    v.is_some_and(|v| v.unsigned_abs() <= 1 << 53)
+}
+
+fn set(mut e: Box<Expr>, node: Expr) -> Box<Expr> {
+    *e = node;
+    e
+}
+
+// -a of an already simplified a, written into e's node when a stays
+fn negate(e: Box<Expr>, mut a: Box<Expr>) -> Box<Expr> {
+    match *a {
+        Num(v) => {
+            *a = Num(-v);
+            a
+        }
+        Neg(inner) => inner,
+        _ => set(e, Neg(a)),
+    }
+}
+
+// Bottom-up rewriting: fold constants, drop identities and zeros, cancel double negation and
+// move constant factors to the left. No rule reassociates, so values stay the same to the bit.
+// Takes the tree by value and rewrites nodes in place, so only discarded subtrees are freed
+fn simplify(mut e: Box<Expr>) -> Box<Expr> {
+    match std::mem::replace(&mut *e, X) {
+        Neg(a) => negate(e, simplify(a)),
+        Add(a, b) => {
+            let (a, b) = (simplify(a), simplify(b));
+            match (constant(&a), constant(&b)) {
+                (Some(x), Some(y)) if exact(x.checked_add(y)) => set(e, Num(x + y)),
+                (Some(0), _) => b,
+                (_, Some(0)) => a,
+                _ => set(e, Add(a, b)),
+            }
+        }
+        Sub(a, b) => {
+            let (a, b) = (simplify(a), simplify(b));
+            match (constant(&a), constant(&b)) {
+                (Some(x), Some(y)) if exact(x.checked_sub(y)) => set(e, Num(x - y)),
+                (_, Some(0)) => a,
+                (Some(0), _) => negate(e, b),
+                _ => set(e, Sub(a, b)),
+            }
+        }
+        Mul(a, b) => {
+            let (a, b) = (simplify(a), simplify(b));
+            match (constant(&a), constant(&b)) {
+                (Some(x), Some(y)) if exact(x.checked_mul(y)) => set(e, Num(x * y)),
+                (Some(0), _) | (_, Some(0)) => set(e, Num(0)),
+                (Some(1), _) => b,
+                (_, Some(1)) => a,
+                (None, Some(_)) => set(e, Mul(b, a)),
+                _ => set(e, Mul(a, b)),
+            }
+        }
+        Pow(a, n) => {
+            let a = simplify(a);
+            match (constant(&a), n) {
+                (_, 0) => set(e, Num(1)),
+                (_, 1) => a,
+                (Some(x), n) if exact(x.checked_pow(n)) => set(e, Num(x.pow(n))),
+                (_, n) => set(e, Pow(a, n)),
+            }
+        }
+        Sin(a) => {
+            let a = simplify(a);
+            if constant(&a) == Some(0) { a } else { set(e, Sin(a)) }
+        }
+        Cos(a) => {
+            let a = simplify(a);
+            if constant(&a) == Some(0) { set(e, Num(1)) } else { set(e, Cos(a)) }
+        }
+        Exp(a) => {
+            let a = simplify(a);
+            if constant(&a) == Some(0) { set(e, Num(1)) } else { set(e, Exp(a)) }
+        }
+        leaf => set(e, leaf),
+    }
+}
+
+// Repeated multiplication rather than powi, so both languages round identically
+fn eval(e: &Expr, x: f64) -> f64 {
+    match e {
+        Num(v) => *v as f64,
+        X => x,
+        Neg(a) => -eval(a, x),
+        Add(a, b) => eval(a, x) + eval(b, x),
+        Sub(a, b) => eval(a, x) - eval(b, x),
+        Mul(a, b) => eval(a, x) * eval(b, x),
+        Pow(a, n) => {
+            let base = eval(a, x);
+            (0..*n).fold(1.0, |p, _| p * base)
+        }
+        Sin(a) => eval(a, x).sin(),
+        Cos(a) => eval(a, x).cos(),
+        Exp(a) => eval(a, x).exp(),
+    }
+}
+
+fn count(e: &Expr) -> usize {
+    1 + match e {
+        Num(_) | X => 0,
+        Neg(a) | Pow(a, _) | Sin(a) | Cos(a) | Exp(a) => count(a),
+        Add(a, b) | Sub(a, b) | Mul(a, b) => count(a) + count(b),
+    }
+}
+
+// Structural hash, the same function in both languages, so equal trees give equal checksums
+fn hash(e: &Expr) -> u64 {
+    fn mix(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    let node = |tag: u64, a: u64, b: u64| mix(tag.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ a ^ b.rotate_left(17));
+    match e {
+        Num(v) => node(1, *v as u64, 0),
+        X => node(2, 0, 0),
+        Neg(a) => node(3, hash(a), 0),
+        Add(a, b) => node(4, hash(a), hash(b)),
+        Sub(a, b) => node(5, hash(a), hash(b)),
+        Mul(a, b) => node(6, hash(a), hash(b)),
+        Pow(a, n) => node(7, hash(a), *n as u64),
+        Sin(a) => node(8, hash(a), 0),
+        Cos(a) => node(9, hash(a), 0),
+        Exp(a) => node(10, hash(a), 0),
+    }
+}
+
+// A raw derivative that overflows can turn finite once its zero terms are dropped, so only
+// finite values are compared
+fn agrees(raw: f64, simplified: f64) -> bool {
+    !raw.is_finite() || raw == simplified
+}
+
+#[derive(Default)]
+struct Run {
+    build: Duration,
+    derive: Duration,
+    simplify: Duration,
+    teardown: Duration,
+    nodes: usize,
+    raw_nodes: [usize; ORDER],
+    simplified_nodes: [usize; ORDER],
+    checksum: u64,
+    mismatches: usize,
+}
+
+impl Run {
+    fn total(&self) -> Duration {
+        self.build + self.derive + self.simplify + self.teardown
+    }
+}
+
+fn run(trees: usize) -> Run {
+    let mut r = Run::default();
+    let mut seed = 42;
+    let start = Instant::now();
+    let mut current: Vec<Box<Expr>> = (0..trees).map(|_| build(&mut seed, MAX_DEPTH)).collect();
+    r.build = start.elapsed();
+    r.nodes = current.iter().map(|e| count(e)).sum();
+
+    for order in 0..ORDER {
+        let start = Instant::now();
+        let raw: Vec<Box<Expr>> = current.iter().map(|e| derive(e)).collect();
+        r.derive += start.elapsed();
+        // Not timed: sizes and values of the unsimplified derivatives, to check simplify against
+        r.raw_nodes[order] = raw.iter().map(|e| count(e)).sum();
+        let values: Vec<f64> = raw.iter().map(|e| eval(e, EVAL_AT)).collect();
+
+        let start = Instant::now();
+        drop(current);
+        current = raw.into_iter().map(simplify).collect();
+        r.simplify += start.elapsed();
+        r.simplified_nodes[order] = current.iter().map(|e| count(e)).sum();
+        r.mismatches += current.iter().zip(&values).filter(|(e, &v)| !agrees(v, eval(e, EVAL_AT))).count();
+    }
+    r.checksum = current.iter().fold(0u64, |sum, e| sum.wrapping_add(hash(e)));
+
+    let start = Instant::now();
+    drop(current);
+    r.teardown = start.elapsed();
+    r
+}
+
+fn parse_trees() -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|a| a == "--trees") {
+        Some(i) => args.get(i + 1)
+            .and_then(|v| v.parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .expect("--trees expects a positive integer"),
+        None => DEFAULT_TREES,
+    }
+}
+
+fn main() {
+    let trees = parse_trees();
+
+    // Warm-up
+    let _ = run(trees / 100 + 1);
+
+    // Benchmark
+    let r = run(trees);
+    if r.mismatches > 0 {
+        eprintln!("{} simplified derivatives differ from the raw ones at x = {}", r.mismatches, EVAL_AT);
+        std::process::exit(1);
+    }
+
+    let processed: usize = r.raw_nodes.iter().sum();
+    println!("{:.6}", r.total().as_secs_f64());
+    eprintln!("Trees: {} random expressions in x, depth up to {}, {} nodes", trees, MAX_DEPTH, r.nodes);
+    for order in 0..ORDER {
+        eprintln!("d{}/dx{}: {} nodes raw, {} after simplifying ({:.1}%)", order + 1, order + 1, r.raw_nodes[order],
+                  r.simplified_nodes[order], 100.0 * r.simplified_nodes[order] as f64 / r.raw_nodes[order] as f64);
+    }
+    eprintln!("Build {:.3}s, differentiate {:.3}s ({:.1}M nodes/s), simplify {:.3}s ({:.1}M nodes/s), teardown {:.3}s",
+              r.build.as_secs_f64(), r.derive.as_secs_f64(), processed as f64 / 1e6 / r.derive.as_secs_f64(),
+              r.simplify.as_secs_f64(), processed as f64 / 1e6 / r.simplify.as_secs_f64(), r.teardown.as_secs_f64());
+    eprintln!("Every simplified derivative matches its raw form at x = {}", EVAL_AT);
+    eprintln!("Checksum: {}", r.checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x() -> Box<Expr> {
+        Box::new(X)
+    }
+
+    #[test]
+    fn derives_and_simplifies_known_expressions() {
+        // d/dx (3 * x^2) = 0 * x^2 + 3 * ((2 * x^1) * 1); constants are not reassociated
+        let f = Mul(num(3), Box::new(Pow(x(), 2)));
+        assert_eq!(*simplify(derive(&f)), Mul(num(3), Box::new(Mul(num(2), x()))));
+        // d/dx (sin(x) * x) = (cos(x) * 1) * x + sin(x) * 1, which drops both ones
+        let g = Mul(Box::new(Sin(x())), x());
+        assert_eq!(*simplify(derive(&g)), Add(Box::new(Mul(Box::new(Cos(x())), x())), Box::new(Sin(x()))));
+        // d/dx (x - x) and d/dx of a constant fold away
+        assert_eq!(*simplify(derive(&Sub(x(), x()))), Num(0));
+        assert_eq!(*simplify(derive(&Exp(num(5)))), Num(0));
+    }
+
+    #[test]
+    fn derivative_matches_a_finite_difference() {
+        let mut seed = 7;
+        let h = 1e-6;
+        let mut checked = 0;
+        for _ in 0..200 {
+            let f = build(&mut seed, 6);
+            let df = simplify(derive(&f));
+            let numeric = (eval(&f, EVAL_AT + h) - eval(&f, EVAL_AT - h)) / (2.0 * h);
+            let exact = eval(&df, EVAL_AT);
+            // Large values (exp(4^4) is about 1e111) swamp the difference quotient
+            if eval(&f, EVAL_AT).abs() < 1e6 && exact.abs() < 1e6 {
+                assert!((numeric - exact).abs() <= 1e-4 * exact.abs().max(1.0), "{:?}: {} vs {}", f, numeric, exact);
+                checked += 1;
+            }
+        }
+        assert!(checked > 150);
+    }
+
+    #[test]
+    fn simplify_keeps_values() {
+        let r = run(300);
+        assert_eq!(r.mismatches, 0);
+        assert!(r.simplified_nodes.iter().zip(&r.raw_nodes).all(|(s, raw)| s < raw));
+    }
+}
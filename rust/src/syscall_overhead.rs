@@ -0,0 +1,239 @@
+use std::fs::File;
+use std::hint::black_box;
+use std::io::{Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::process::parent_id;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+const CLOCK_READS: usize = 20_000_000;
+const SYSCALLS: usize = 2_000_000;
+const ROUND_TRIPS: usize = 200_000;
+const READ_SIZE: usize = 64;
+
+struct Measured {
+    elapsed: Duration,
+    checksum: u64,
+}
+
+// Setup stays outside the timing; each call of `op` is one operation and returns what it adds to
+// the checksum
+fn measure(iters: usize, mut op: impl FnMut(u64) -> u64) -> Measured {
+    let start = Instant::now();
+    let checksum = (0..iters as u64).fold(0u64, |acc, i| acc.wrapping_add(op(i)));
+    Measured { elapsed: start.elapsed(), checksum }
+}
+
+fn monotonic() -> libc::timespec {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: ts is a valid timespec to write into
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+    ts
+}
+
+fn instant_now(iters: usize) -> Measured {
+    measure(iters, |_| {
+        black_box(Instant::now());
+        1
+    })
+}
+
+fn system_time_now(iters: usize) -> Measured {
+    measure(iters, |_| {
+        black_box(SystemTime::now());
+        1
+    })
+}
+
+fn clock_gettime(iters: usize) -> Measured {
+    measure(iters, |_| {
+        black_box(monotonic());
+        1
+    })
+}
+
+// getppid rather than getpid, which libc may cache: it always enters the kernel
+fn std_parent_id(iters: usize) -> Measured {
+    let parent = parent_id();
+    measure(iters, |_| (parent_id() == parent) as u64)
+}
+
+fn libc_getppid(iters: usize) -> Measured {
+    // SAFETY: getppid has no preconditions
+    let parent = unsafe { libc::getppid() };
+    measure(iters, |_| (unsafe { libc::getppid() } == parent) as u64)
+}
+
+fn std_write(iters: usize) -> Measured {
+    let mut null = File::create("/dev/null").unwrap();
+    measure(iters, |i| null.write(&[i as u8]).unwrap() as u64)
+}
+
+fn libc_write(iters: usize) -> Measured {
+    let null = File::create("/dev/null").unwrap();
+    let fd = null.as_raw_fd();
+    measure(iters, |i| {
+        let byte = i as u8;
+        // SAFETY: fd stays open for the loop and the buffer is one valid byte
+        unsafe { libc::write(fd, (&byte as *const u8).cast(), 1) as u64 }
+    })
+}
+
+fn std_read(iters: usize) -> Measured {
+    let mut zero = File::open("/dev/zero").unwrap();
+    let mut buf = [1u8; READ_SIZE];
+    measure(iters, |_| {
+        let n = zero.read(&mut buf).unwrap();
+        n as u64 + buf.iter().map(|&b| b as u64).sum::<u64>()
+    })
+}
+
+fn libc_read(iters: usize) -> Measured {
+    let zero = File::open("/dev/zero").unwrap();
+    let fd = zero.as_raw_fd();
+    let mut buf = [1u8; READ_SIZE];
+    measure(iters, |_| {
+        // SAFETY: fd stays open for the loop and buf has room for READ_SIZE bytes
+        let n = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), READ_SIZE) };
+        n as u64 + buf.iter().map(|&b| b as u64).sum::<u64>()
+    })
+}
+
+// A byte to an echo thread through one pipe and back, plus one, through another: four syscalls
+// and, with the echo thread blocked in read, two wake-ups per round trip
+fn std_pipe(iters: usize) -> Measured {
+    let (mut ping_rx, mut ping_tx) = std::io::pipe().unwrap();
+    let (mut pong_rx, mut pong_tx) = std::io::pipe().unwrap();
+    thread::scope(|s| {
+        s.spawn(move || {
+            let mut byte = [0u8];
+            for _ in 0..iters {
+                ping_rx.read_exact(&mut byte).unwrap();
+                pong_tx.write_all(&[byte[0].wrapping_add(1)]).unwrap();
+            }
+        });
+        let mut byte = [0u8];
+        measure(iters, |i| {
+            ping_tx.write_all(&[(i % 251) as u8]).unwrap();
+            pong_rx.read_exact(&mut byte).unwrap();
+            byte[0] as u64
+        })
+    })
+}
+
+fn libc_pipe(iters: usize) -> Measured {
+    let (mut ping, mut pong) = ([0; 2], [0; 2]);
+    // SAFETY: each array has room for the two descriptors pipe writes
+    unsafe {
+        assert_eq!(libc::pipe(ping.as_mut_ptr()), 0);
+        assert_eq!(libc::pipe(pong.as_mut_ptr()), 0);
+    }
+    let measured = thread::scope(|s| {
+        s.spawn(|| {
+            let mut byte = 0u8;
+            for _ in 0..iters {
+                // SAFETY: the descriptors stay open until both threads are done and byte is valid
+                unsafe {
+                    libc::read(ping[0], (&mut byte as *mut u8).cast(), 1);
+                    byte = byte.wrapping_add(1);
+                    libc::write(pong[1], (&byte as *const u8).cast(), 1);
+                }
+            }
+        });
+        measure(iters, |i| {
+            let mut byte = (i % 251) as u8;
+            // SAFETY: as above
+            unsafe {
+                libc::write(ping[1], (&byte as *const u8).cast(), 1);
+                libc::read(pong[0], (&mut byte as *mut u8).cast(), 1);
+            }
+            byte as u64
+        })
+    });
+    for fd in ping.into_iter().chain(pong) {
+        // SAFETY: both threads are done with the descriptors
+        unsafe { libc::close(fd) };
+    }
+    measured
+}
+
+type Op = (&'static str, fn(usize) -> Measured);
+
+// Within a group every op does the same work, std first and the raw libc call after it
+struct Group {
+    name: &'static str,
+    iters: usize,
+    ops: &'static [Op],
+}
+
+const GROUPS: [Group; 5] = [
+    Group {
+        name: "clock read",
+        iters: CLOCK_READS,
+        ops: &[("Instant::now", instant_now), ("SystemTime::now", system_time_now), ("clock_gettime", clock_gettime)],
+    },
+    Group {
+        name: "getppid",
+        iters: SYSCALLS,
+        ops: &[("process::parent_id", std_parent_id), ("libc::getppid", libc_getppid)],
+    },
+    Group { name: "write 1 B to /dev/null", iters: SYSCALLS, ops: &[("File::write", std_write), ("libc::write", libc_write)] },
+    Group { name: "read 64 B from /dev/zero", iters: SYSCALLS, ops: &[("File::read", std_read), ("libc::read", libc_read)] },
+    Group { name: "pipe round trip", iters: ROUND_TRIPS, ops: &[("io::pipe", std_pipe), ("libc::pipe", libc_pipe)] },
+];
+
+fn main() {
+    // Warm-up
+    for group in &GROUPS {
+        for (_, f) in group.ops {
+            let _ = f(group.iters / 100);
+        }
+    }
+
+    // Benchmark: runs[group][op]
+    let runs: Vec<Vec<Measured>> = GROUPS.iter().map(|g| g.ops.iter().map(|(_, f)| f(g.iters)).collect()).collect();
+    let total_duration: Duration = runs.iter().flatten().map(|r| r.elapsed).sum();
+    let all_match = runs.iter().all(|by_op| by_op.iter().all(|r| r.checksum == by_op[0].checksum));
+    let checksum = runs.iter().fold(0u64, |acc, by_op| acc.wrapping_add(by_op[0].checksum));
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("ns per operation, std wrapper vs the raw libc call");
+    for (group, by_op) in GROUPS.iter().zip(&runs) {
+        eprintln!("{} ({} times):", group.name, group.iters);
+        for ((name, _), r) in group.ops.iter().zip(by_op) {
+            eprintln!("  {:<20} {:8.1}", name, r.elapsed.as_secs_f64() * 1e9 / group.iters as f64);
+        }
+    }
+    // The clock itself: the smallest step two back-to-back reads can see
+    let first = monotonic();
+    let mut next = monotonic();
+    while (next.tv_sec, next.tv_nsec) == (first.tv_sec, first.tv_nsec) {
+        next = monotonic();
+    }
+    eprintln!("CLOCK_MONOTONIC step: {}ns", (next.tv_sec - first.tv_sec) * 1_000_000_000 + next.tv_nsec - first.tv_nsec);
+    eprintln!("Checksum: {} (all variants match: {})", checksum, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ops_in_a_group_agree() {
+        for group in &GROUPS {
+            let expected = (group.ops[0].1)(300).checksum;
+            for (name, f) in group.ops {
+                assert_eq!(f(300).checksum, expected, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn checksums_count_the_work() {
+        assert_eq!(instant_now(10).checksum, 10);
+        assert_eq!(libc_getppid(10).checksum, 10);
+        assert_eq!(std_write(10).checksum, 10);
+        assert_eq!(std_read(10).checksum, 10 * READ_SIZE as u64);
+        assert_eq!(libc_pipe(300).checksum, (0..300).map(|i| i % 251 + 1).sum::<u64>());
+    }
+}
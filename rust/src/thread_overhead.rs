@@ -0,0 +1,208 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+const SPAWNS: usize = 100_000;
+const ROUND_TRIPS: usize = 1_000_000;
+const SMALL_STACK: usize = 64 * 1024;
+
+// The whole of a spawned thread's job, so spawn and join are all there is to time
+fn work(i: u64) -> u64 {
+    i.wrapping_mul(0x9E3779B97F4A7C15) >> 7
+}
+
+struct Spawns {
+    elapsed: Duration,
+    checksum: u64,
+}
+
+// One thread at a time: spawn it, then join it before the next
+fn spawn_each(spawns: usize, spawn: impl Fn(u64) -> u64) -> Spawns {
+    let start = Instant::now();
+    let checksum = (0..spawns as u64).fold(0u64, |acc, i| acc.wrapping_add(spawn(i)));
+    Spawns { elapsed: start.elapsed(), checksum }
+}
+
+fn spawn_std(spawns: usize) -> Spawns {
+    spawn_each(spawns, |i| thread::spawn(move || work(i)).join().unwrap())
+}
+
+fn spawn_scoped(spawns: usize) -> Spawns {
+    spawn_each(spawns, |i| thread::scope(|s| s.spawn(move || work(i)).join().unwrap()))
+}
+
+fn spawn_small_stack(spawns: usize) -> Spawns {
+    spawn_each(spawns, |i| {
+        thread::Builder::new().stack_size(SMALL_STACK).spawn(move || work(i)).unwrap().join().unwrap()
+    })
+}
+
+type Spawner = (&'static str, fn(usize) -> Spawns);
+
+const SPAWNERS: [Spawner; 3] = [
+    ("thread::spawn", spawn_std),
+    ("thread::scope", spawn_scoped),
+    ("64 KiB stack", spawn_small_stack),
+];
+
+// A turn counter two threads hand back and forth: odd turns are the echo thread's, even turns
+// the main thread's. Waiting blocks in the kernel rather than spinning
+trait Baton: Sync {
+    fn new() -> Self;
+    fn register_echo(&self, _echo: &Thread) {}
+    fn pass(&self, turn: u32);
+    fn wait_for(&self, turn: u32);
+}
+
+// A mutex-guarded counter and one condition variable both threads wait on
+struct CondvarBaton {
+    turn: Mutex<u32>,
+    changed: Condvar,
+}
+
+impl Baton for CondvarBaton {
+    fn new() -> Self {
+        CondvarBaton { turn: Mutex::new(0), changed: Condvar::new() }
+    }
+
+    fn pass(&self, turn: u32) {
+        *self.turn.lock().unwrap() = turn;
+        self.changed.notify_one();
+    }
+
+    fn wait_for(&self, turn: u32) {
+        drop(self.changed.wait_while(self.turn.lock().unwrap(), |t| *t != turn).unwrap());
+    }
+}
+
+// An atomic counter, each thread parking until the other unparks it: a futex on Linux, a ulock
+// or semaphore on macOS
+struct ParkBaton {
+    turn: AtomicU32,
+    main: Thread,
+    echo: OnceLock<Thread>,
+}
+
+impl Baton for ParkBaton {
+    fn new() -> Self {
+        ParkBaton { turn: AtomicU32::new(0), main: thread::current(), echo: OnceLock::new() }
+    }
+
+    fn register_echo(&self, echo: &Thread) {
+        self.echo.set(echo.clone()).unwrap();
+    }
+
+    fn pass(&self, turn: u32) {
+        self.turn.store(turn, Ordering::Release);
+        if turn % 2 == 1 { self.echo.get().unwrap() } else { &self.main }.unpark();
+    }
+
+    fn wait_for(&self, turn: u32) {
+        while self.turn.load(Ordering::Acquire) != turn {
+            thread::park();
+        }
+    }
+}
+
+struct PingPong {
+    elapsed: Duration,
+    round_trips_ns: Vec<u32>, // sorted
+    turns: u32, // taken by either thread: twice the round trips
+}
+
+// Every pass wakes a blocked thread, so each round trip is two context switches plus the two
+// clock reads around it
+fn ping_pong<B: Baton>(round_trips: usize) -> PingPong {
+    let baton = B::new();
+    let rounds = round_trips as u32;
+    let start = Instant::now();
+    let (mut round_trips_ns, turns) = thread::scope(|s| {
+        let echo = s.spawn(|| {
+            (0..rounds).fold(0u32, |taken, i| {
+                baton.wait_for(2 * i + 1);
+                baton.pass(2 * i + 2);
+                taken + 1
+            })
+        });
+        baton.register_echo(echo.thread());
+        let mut samples = Vec::with_capacity(round_trips);
+        for i in 0..rounds {
+            let sent = Instant::now();
+            baton.pass(2 * i + 1);
+            baton.wait_for(2 * i + 2);
+            samples.push(sent.elapsed().as_nanos() as u32);
+        }
+        let echoed = echo.join().unwrap();
+        (samples, echoed + rounds)
+    });
+    let elapsed = start.elapsed();
+    round_trips_ns.sort_unstable();
+    PingPong { elapsed, round_trips_ns, turns }
+}
+
+type Switcher = (&'static str, fn(usize) -> PingPong);
+
+const SWITCHERS: [Switcher; 2] = [
+    ("Mutex + Condvar", ping_pong::<CondvarBaton>),
+    ("park / unpark", ping_pong::<ParkBaton>),
+];
+
+fn switch_ns(sorted_round_trips: &[u32], per_mille: usize) -> f64 {
+    sorted_round_trips[(sorted_round_trips.len() - 1) * per_mille / 1000] as f64 / 2.0
+}
+
+fn main() {
+    // Warm-up
+    for (_, f) in SPAWNERS {
+        let _ = f(SPAWNS / 100);
+    }
+    for (_, f) in SWITCHERS {
+        let _ = f(ROUND_TRIPS / 100);
+    }
+
+    // Benchmark
+    let spawns: Vec<Spawns> = SPAWNERS.iter().map(|(_, f)| f(SPAWNS)).collect();
+    let switches: Vec<PingPong> = SWITCHERS.iter().map(|(_, f)| f(ROUND_TRIPS)).collect();
+    let total_duration: Duration =
+        spawns.iter().map(|r| r.elapsed).chain(switches.iter().map(|r| r.elapsed)).sum();
+    let all_match = spawns.iter().all(|r| r.checksum == spawns[0].checksum)
+        && switches.iter().all(|r| r.turns == switches[0].turns);
+    let checksum = spawns[0].checksum.wrapping_add(switches[0].turns as u64);
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("{} threads spawned and joined one at a time; {} round trips between two blocking threads", SPAWNS,
+              ROUND_TRIPS);
+    for ((name, _), r) in SPAWNERS.iter().zip(&spawns) {
+        eprintln!("  {:<16} {:.3}s ({:.1} us per spawn + join)", name, r.elapsed.as_secs_f64(),
+                  r.elapsed.as_secs_f64() * 1e6 / SPAWNS as f64);
+    }
+    for ((name, _), r) in SWITCHERS.iter().zip(&switches) {
+        let trips = &r.round_trips_ns;
+        eprintln!("  {:<16} {:.3}s (switch p50 {:.0}ns, p99 {:.0}ns, p99.9 {:.0}ns)", name, r.elapsed.as_secs_f64(),
+                  switch_ns(trips, 500), switch_ns(trips, 990), switch_ns(trips, 999));
+    }
+    eprintln!("Checksum: {} (all variants match: {})", checksum, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spawners_agree() {
+        let expected = (0..50).fold(0u64, |acc, i| acc.wrapping_add(work(i)));
+        for (name, f) in SPAWNERS {
+            assert_eq!(f(50).checksum, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn ping_pongs_take_every_turn() {
+        for (name, f) in SWITCHERS {
+            let r = f(500);
+            assert_eq!(r.turns, 1_000, "{}", name);
+            assert_eq!(r.round_trips_ns.len(), 500, "{}", name);
+        }
+    }
+}
@@ -1,9 +1,18 @@
-use std::sync::{Arc, Mutex, mpsc};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier, Mutex, mpsc};
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 const NUM_TASKS: usize = 100_000;
 const NUM_WORKERS: usize = 8;
+const AWAIT_POINTS: usize = 2; // yields per async task after its work, each a trip back through the scheduler
+// --sweep: iterations of spin() per task, roughly 100 ns to 1 ms each, and the total iterations
+// per granularity; task counts are clamped so fine grains stay bounded and coarse ones still
+// give every worker several tasks
+const SWEEP_ITERATIONS: [u64; 5] = [50, 500, 5_000, 50_000, 500_000];
+const SWEEP_TOTAL_ITERATIONS: u64 = 100_000_000;
+const SWEEP_TASKS: (usize, usize) = (200, 200_000);
 
 struct ThreadPool {
     workers: Vec<thread::JoinHandle<()>>,
@@ -18,9 +27,10 @@ impl ThreadPool {
         let receiver = Arc::new(Mutex::new(receiver));
         let mut workers = Vec::with_capacity(size);
         
-        for _ in 0..size {
+        for index in 0..size {
             let receiver = Arc::clone(&receiver);
             let handle = thread::spawn(move || {
+                WORKER.set(index);
                 loop {
                     let task = receiver.lock().unwrap().recv();
                     match task {
@@ -64,42 +74,297 @@ fn heavy_computation(n: usize) -> u64 {
     result
 }
 
-fn main() {
-    let counter = Arc::new(Mutex::new(0u64));
-    
-    // Warm-up
-    {
-        let pool = ThreadPool::new(NUM_WORKERS);
-        let counter_warmup = Arc::new(Mutex::new(0u64));
-        for i in 0..100 {
-            let counter_clone = Arc::clone(&counter_warmup);
-            pool.execute(move || {
-                let result = heavy_computation(i);
-                let mut count = counter_clone.lock().unwrap();
-                *count = count.wrapping_add(result);
-            });
+// Work that scales with `iterations`: a chain of dependent multiply-adds the compiler can neither
+// vectorise nor fold into a closed form
+fn spin(n: usize, iterations: u64) -> u64 {
+    let mut x = n as u64;
+    for i in 0..iterations {
+        x = (x ^ (x >> 31)).wrapping_mul(6364136223846793005).wrapping_add(i);
+    }
+    x
+}
+
+#[derive(Clone, Copy)]
+enum Backend {
+    Custom, // the ThreadPool above: one channel behind a mutex, like the C++ pool
+    Rayon,  // rayon::ThreadPool::spawn inside a scope, work-stealing deques
+    Crate,  // the threadpool crate: one mpsc channel behind a mutex, with a condvar for join
+    Spawn,  // std::thread::spawn per task, NUM_WORKERS in flight at a time
+    Tokio,  // every task an async fn spawned on a multi-threaded tokio runtime
+}
+
+impl Backend {
+    fn name(self) -> &'static str {
+        match self {
+            Backend::Custom => "custom pool",
+            Backend::Rayon => "rayon pool",
+            Backend::Crate => "threadpool crate",
+            Backend::Spawn => "thread per task",
+            Backend::Tokio => "tokio async",
         }
     }
-    
-    // Benchmark
+}
+
+#[derive(Clone, Copy)]
+enum Accumulator {
+    Mutex,     // one Mutex<u64>, locked by every task
+    Atomic,    // one AtomicU64, fetch_add by every task
+    PerWorker, // one cache-line slot per worker, each written only by its own worker, summed at the end
+}
+
+impl Accumulator {
+    fn name(self) -> &'static str {
+        match self {
+            Accumulator::Mutex => "mutex",
+            Accumulator::Atomic => "atomic",
+            Accumulator::PerWorker => "per-worker",
+        }
+    }
+}
+
+thread_local! {
+    // Index of the worker running on this thread, for the per-worker slots
+    static WORKER: Cell<usize> = const { Cell::new(0) };
+}
+
+#[repr(align(64))]
+struct Slot(AtomicU64);
+
+struct Counter {
+    accumulator: Accumulator,
+    mutex: Mutex<u64>,
+    atomic: AtomicU64,
+    slots: Vec<Slot>,
+}
+
+impl Counter {
+    fn new(accumulator: Accumulator) -> Counter {
+        let slots = (0..NUM_WORKERS).map(|_| Slot(AtomicU64::new(0))).collect();
+        Counter { accumulator, mutex: Mutex::new(0), atomic: AtomicU64::new(0), slots }
+    }
+
+    fn add(&self, value: u64) {
+        match self.accumulator {
+            Accumulator::Mutex => {
+                let mut count = self.mutex.lock().unwrap();
+                *count = count.wrapping_add(value);
+            }
+            Accumulator::Atomic => {
+                self.atomic.fetch_add(value, Ordering::Relaxed);
+            }
+            Accumulator::PerWorker => {
+                // A single writer per slot, so a plain load and store instead of a read-modify-write
+                let slot = &self.slots[WORKER.get()].0;
+                slot.store(slot.load(Ordering::Relaxed).wrapping_add(value), Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn total(&self) -> u64 {
+        match self.accumulator {
+            Accumulator::Mutex => *self.mutex.lock().unwrap(),
+            Accumulator::Atomic => self.atomic.load(Ordering::Relaxed),
+            Accumulator::PerWorker => {
+                self.slots.iter().fold(0u64, |total, slot| total.wrapping_add(slot.0.load(Ordering::Relaxed)))
+            }
+        }
+    }
+}
+
+fn task<W: Fn(usize) -> u64>(i: usize, counter: &Counter, work: W) {
+    counter.add(work(i));
+}
+
+// The same task as an async fn: the work, then AWAIT_POINTS yields before the result is added
+async fn async_task<W: Fn(usize) -> u64>(i: usize, counter: Arc<Counter>, work: W) {
+    let value = work(i);
+    for _ in 0..AWAIT_POINTS {
+        tokio::task::yield_now().await;
+    }
+    counter.add(value);
+}
+
+// Workers are created inside the timed region for every backend, as the pool has always been.
+// Returns the elapsed time and the final count
+fn run<W>(backend: Backend, accumulator: Accumulator, num_tasks: usize, work: W) -> (Duration, u64)
+where
+    W: Fn(usize) -> u64 + Copy + Send + Sync + 'static,
+{
+    let counter = Arc::new(Counter::new(accumulator));
     let start = Instant::now();
-    {
-        let pool = ThreadPool::new(NUM_WORKERS);
-        
-        for i in 0..NUM_TASKS {
-            let counter_clone = Arc::clone(&counter);
-            pool.execute(move || {
-                let result = heavy_computation(i);
-                let mut count = counter_clone.lock().unwrap();
-                *count = count.wrapping_add(result);
+    match backend {
+        Backend::Custom => {
+            let pool = ThreadPool::new(NUM_WORKERS);
+            for i in 0..num_tasks {
+                let counter_clone = Arc::clone(&counter);
+                pool.execute(move || task(i, &counter_clone, work));
+            }
+        }
+        Backend::Rayon => {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(NUM_WORKERS)
+                .start_handler(|index| WORKER.set(index))
+                .build()
+                .unwrap();
+            pool.scope(|s| {
+                for i in 0..num_tasks {
+                    let counter = &counter;
+                    s.spawn(move |_| task(i, counter, work));
+                }
+            });
+        }
+        Backend::Crate => {
+            let pool = threadpool::ThreadPool::new(NUM_WORKERS);
+            // The crate has no start hook, so one task per worker numbers them: each holds its
+            // worker at the barrier until all are numbered, so no worker takes two
+            let barrier = Arc::new(Barrier::new(NUM_WORKERS));
+            let next_worker = Arc::new(AtomicUsize::new(0));
+            for _ in 0..NUM_WORKERS {
+                let (barrier, next_worker) = (Arc::clone(&barrier), Arc::clone(&next_worker));
+                pool.execute(move || {
+                    WORKER.set(next_worker.fetch_add(1, Ordering::Relaxed));
+                    barrier.wait();
+                });
+            }
+            for i in 0..num_tasks {
+                let counter_clone = Arc::clone(&counter);
+                pool.execute(move || task(i, &counter_clone, work));
+            }
+            pool.join();
+        }
+        Backend::Spawn => {
+            for wave in (0..num_tasks).step_by(NUM_WORKERS) {
+                let handles: Vec<_> = (wave..num_tasks.min(wave + NUM_WORKERS))
+                    .map(|i| {
+                        let counter_clone = Arc::clone(&counter);
+                        thread::spawn(move || {
+                            WORKER.set(i - wave);
+                            task(i, &counter_clone, work)
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    handle.join().unwrap();
+                }
+            }
+        }
+        Backend::Tokio => {
+            let next_worker = AtomicUsize::new(0);
+            let runtime = tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(NUM_WORKERS)
+                .on_thread_start(move || WORKER.set(next_worker.fetch_add(1, Ordering::Relaxed)))
+                .build()
+                .unwrap();
+            runtime.block_on(async {
+                let handles: Vec<_> = (0..num_tasks)
+                    .map(|i| tokio::spawn(async_task(i, Arc::clone(&counter), work)))
+                    .collect();
+                for handle in handles {
+                    handle.await.unwrap();
+                }
             });
         }
     }
     let duration = start.elapsed();
-    
-    let final_count = *counter.lock().unwrap();
-    
+    let final_count = counter.total();
+    (duration, final_count)
+}
+
+// Throughput of every backend at each granularity, against running the same tasks in a loop on
+// one thread; a speedup below 1x means dispatch costs more than the task. Uses the atomic
+// accumulator so only dispatch is measured
+fn report_sweep(backends: &[Backend]) {
+    for &iterations in &SWEEP_ITERATIONS {
+        let num_tasks = ((SWEEP_TOTAL_ITERATIONS / iterations) as usize).clamp(SWEEP_TASKS.0, SWEEP_TASKS.1);
+        let work = move |i| spin(i, iterations);
+
+        let start = Instant::now();
+        let sequential_count = (0..num_tasks).fold(0u64, |count, i| count.wrapping_add(work(i)));
+        let sequential = start.elapsed().as_secs_f64();
+
+        eprintln!("Granularity: {} iterations/task, {:.0} ns/task sequential, {} tasks", iterations,
+                  sequential * 1e9 / num_tasks as f64, num_tasks);
+        for &backend in backends {
+            let (duration, count) = run(backend, Accumulator::Atomic, num_tasks, work);
+            let seconds = duration.as_secs_f64();
+            eprintln!("  {}: {:.0} tasks/s, {:.2}x sequential{}", backend.name(), num_tasks as f64 / seconds,
+                      sequential / seconds, if count == sequential_count { "" } else { " (count mismatch)" });
+        }
+    }
+}
+
+fn main() {
+    let sweep = std::env::args().any(|a| a == "--sweep");
+    let backends = [Backend::Custom, Backend::Rayon, Backend::Crate, Backend::Spawn, Backend::Tokio];
+    let accumulators = [Accumulator::Mutex, Accumulator::Atomic, Accumulator::PerWorker];
+
+    // Warm-up
+    for &backend in &backends {
+        for &accumulator in &accumulators {
+            run(backend, accumulator, 100, heavy_computation);
+        }
+    }
+
+    // Benchmark: every backend with every accumulator, so lock contention can be told apart
+    // from dispatch cost
+    let results: Vec<Vec<(Duration, u64)>> = backends
+        .iter()
+        .map(|&backend| accumulators.iter().map(|&acc| run(backend, acc, NUM_TASKS, heavy_computation)).collect())
+        .collect();
+
+    // The custom pool with the mutex is the one timed against C++
+    let (duration, final_count) = results[0][0];
+
     println!("{:.6}", duration.as_secs_f64());
+    for (backend, row) in backends.iter().zip(&results) {
+        let cells: Vec<String> = accumulators
+            .iter()
+            .zip(row)
+            .map(|(acc, (duration, _))| format!("{} {:.6}s ({:.2} us/task)", acc.name(), duration.as_secs_f64(),
+                                                duration.as_secs_f64() * 1e6 / NUM_TASKS as f64))
+            .collect();
+        eprintln!("{}: {}", backend.name(), cells.join(", "));
+    }
+    eprintln!("Backends agree: {}", results.iter().flatten().all(|&(_, count)| count == final_count));
+    if sweep {
+        report_sweep(&backends);
+    }
     eprintln!("Final count: {}", final_count);
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulators_sum_the_same() {
+        for accumulator in [Accumulator::Mutex, Accumulator::Atomic, Accumulator::PerWorker] {
+            let counter = Counter::new(accumulator);
+            for i in 1..=100 {
+                counter.add(i);
+            }
+            assert_eq!(counter.total(), 5050, "{}", accumulator.name());
+        }
+    }
+
+    #[test]
+    fn every_backend_runs_every_task_once() {
+        // Task i adds i + 1, so a lost or repeated task changes the total
+        let expected = (1..=1000u64).sum::<u64>();
+        for backend in [Backend::Custom, Backend::Rayon, Backend::Crate, Backend::Spawn, Backend::Tokio] {
+            for accumulator in [Accumulator::Mutex, Accumulator::Atomic, Accumulator::PerWorker] {
+                let (_, count) = run(backend, accumulator, 1000, |i| i as u64 + 1);
+                assert_eq!(count, expected, "{} with {}", backend.name(), accumulator.name());
+            }
+        }
+    }
+
+    #[test]
+    fn backends_match_the_sequential_sum() {
+        let work = |i| spin(i, 100);
+        let sequential = (0..500).fold(0u64, |count, i| count.wrapping_add(work(i)));
+        assert_eq!(run(Backend::Rayon, Accumulator::PerWorker, 500, work).1, sequential);
+        assert_eq!(run(Backend::Custom, Accumulator::Atomic, 500, heavy_computation).1,
+                   (0..500).fold(0u64, |count, i| count.wrapping_add(heavy_computation(i))));
+    }
+}
@@ -0,0 +1,135 @@
+use std::time::{Duration, Instant};
+
+const ROWS: usize = 8000; // 320 MB of f64 per matrix
+const COLS: usize = 5000;
+const BLOCK: usize = 32;  // a 32x32 f64 tile of source and destination fits in L1
+const LEAF: usize = 32;   // recursion stops once both sides are this small
+const ROUNDS: usize = 3;
+const SAMPLE_STRIDE: usize = 1009;
+
+type Transpose = fn(&[f64], &mut [f64]);
+
+// Row-major rows x cols in, row-major cols x rows out
+fn transpose_naive(src: &[f64], dst: &mut [f64], rows: usize, cols: usize) {
+    for i in 0..rows {
+        for j in 0..cols {
+            dst[j * rows + i] = src[i * cols + j];
+        }
+    }
+}
+
+fn transpose_blocked(src: &[f64], dst: &mut [f64], rows: usize, cols: usize) {
+    for i0 in (0..rows).step_by(BLOCK) {
+        for j0 in (0..cols).step_by(BLOCK) {
+            for i in i0..(i0 + BLOCK).min(rows) {
+                for j in j0..(j0 + BLOCK).min(cols) {
+                    dst[j * rows + i] = src[i * cols + j];
+                }
+            }
+        }
+    }
+}
+
+// Cache-oblivious: halve the longer side until the tile fits, with no tuning for any cache level
+fn transpose_recursive(src: &[f64], dst: &mut [f64], rows: usize, cols: usize, r: (usize, usize), c: (usize, usize)) {
+    let (r0, r1) = r;
+    let (c0, c1) = c;
+    if r1 - r0 <= LEAF && c1 - c0 <= LEAF {
+        for i in r0..r1 {
+            for j in c0..c1 {
+                dst[j * rows + i] = src[i * cols + j];
+            }
+        }
+    } else if r1 - r0 >= c1 - c0 {
+        let mid = r0 + (r1 - r0) / 2;
+        transpose_recursive(src, dst, rows, cols, (r0, mid), c);
+        transpose_recursive(src, dst, rows, cols, (mid, r1), c);
+    } else {
+        let mid = c0 + (c1 - c0) / 2;
+        transpose_recursive(src, dst, rows, cols, r, (c0, mid));
+        transpose_recursive(src, dst, rows, cols, r, (mid, c1));
+    }
+}
+
+fn run(src: &[f64], dst: &mut [f64], transpose: Transpose) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        transpose(src, dst);
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let placement = bench_numa::place(&args);
+    bench_pages::enable(&args);
+
+    // Every element holds its own row-major index, so the output is checkable exactly
+    let mut src = bench_pages::with_capacity(ROWS * COLS);
+    src.extend((0..ROWS * COLS).map(|i| i as f64));
+    let mut dst = bench_pages::filled(0.0f64, ROWS * COLS);
+
+    // Warm-up (also faults in the destination pages)
+    transpose_blocked(&src, &mut dst, ROWS, COLS);
+
+    let variants: [(&str, Transpose); 3] = [
+        ("naive", |s, d| transpose_naive(s, d, ROWS, COLS)),
+        ("blocked", |s, d| transpose_blocked(s, d, ROWS, COLS)),
+        ("recursive", |s, d| transpose_recursive(s, d, ROWS, COLS, (0, ROWS), (0, COLS))),
+    ];
+
+    let mut total = Duration::ZERO;
+    let mut checksum = 0.0f64;
+    let gigabytes = (2 * ROWS * COLS * 8 * ROUNDS) as f64 / 1e9; // every element read once and written once
+    eprintln!("Transposing {}x{} f64 ({:.0} MB), {} rounds each", ROWS, COLS, (ROWS * COLS * 8) as f64 / 1e6, ROUNDS);
+    for &(name, transpose) in &variants {
+        dst.fill(0.0);
+        let elapsed = run(&src, &mut dst, transpose);
+        for j in 0..COLS {
+            for i in 0..ROWS {
+                assert!(dst[j * ROWS + i] == src[i * COLS + j], "{} transpose is wrong at ({}, {})", name, i, j);
+            }
+        }
+        eprintln!("  {:<10} {:8.3}s ({:.2} GB/s)", name, elapsed.as_secs_f64(), gigabytes / elapsed.as_secs_f64());
+        total += elapsed;
+        checksum = dst.iter().step_by(SAMPLE_STRIDE).sum();
+    }
+
+    println!("{:.6}", total.as_secs_f64());
+    eprintln!("{}", bench_numa::report(&placement));
+    eprintln!("{}", bench_pages::report());
+    eprintln!("Checksum: {:.0}", checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transposes_a_known_matrix() {
+        let src = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 2x3
+        let mut dst = [0.0; 6];
+        transpose_naive(&src, &mut dst, 2, 3);
+        assert_eq!(dst, [1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+    }
+
+    #[test]
+    fn variants_agree_on_ragged_shapes() {
+        // Sides that are not multiples of BLOCK or LEAF, and shapes taller and wider than square
+        for (rows, cols) in [(1, 1), (1, 77), (77, 1), (33, 65), (100, 40), (129, 257)] {
+            let src: Vec<f64> = (0..rows * cols).map(|i| i as f64).collect();
+            let mut expected = vec![0.0; rows * cols];
+            transpose_naive(&src, &mut expected, rows, cols);
+            let mut blocked = vec![0.0; rows * cols];
+            transpose_blocked(&src, &mut blocked, rows, cols);
+            let mut recursive = vec![0.0; rows * cols];
+            transpose_recursive(&src, &mut recursive, rows, cols, (0, rows), (0, cols));
+            assert_eq!(blocked, expected, "blocked {}x{}", rows, cols);
+            assert_eq!(recursive, expected, "recursive {}x{}", rows, cols);
+
+            let mut back = vec![0.0; rows * cols];
+            transpose_blocked(&expected, &mut back, cols, rows);
+            assert_eq!(back, src);
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::time::Instant;
+
+const NUM_WORDS: usize = 2_000_000;
+const NUM_LOOKUPS: usize = 2_000_000;
+const NUM_PREFIX_QUERIES: usize = 100_000;
+const MAX_SYLLABLES: u64 = 5;
+const PREFIX_SYLLABLES: usize = 2;
+const CONSONANTS: &[u8] = b"bcdfghklmnprstvz";
+const VOWELS: &[u8] = b"aeiou";
+const ALPHABET_SIZE: usize = 26;
+const TERMINAL_BIT: u32 = 1 << ALPHABET_SIZE;
+
+type Words = Vec<Vec<u8>>;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+// Consonant-vowel syllables give the dictionary realistic shared prefixes
+fn random_word(seed: &mut u64, syllables: usize) -> Vec<u8> {
+    let mut word = Vec::with_capacity(syllables * 2);
+    for _ in 0..syllables {
+        word.push(CONSONANTS[(next_random(seed) % CONSONANTS.len() as u64) as usize]);
+        word.push(VOWELS[(next_random(seed) % VOWELS.len() as u64) as usize]);
+    }
+    word
+}
+
+fn generate_data() -> (Words, Words, Words) {
+    let mut seed = 4242u64;
+    let words: Words = (0..NUM_WORDS)
+        .map(|_| {
+            let syllables = 1 + (next_random(&mut seed) % MAX_SYLLABLES) as usize;
+            random_word(&mut seed, syllables)
+        })
+        .collect();
+
+    // Half the lookups hit the dictionary, half are fresh random words
+    let lookups = (0..NUM_LOOKUPS)
+        .map(|_| {
+            if next_random(&mut seed).is_multiple_of(2) {
+                words[(next_random(&mut seed) % NUM_WORDS as u64) as usize].clone()
+            } else {
+                let syllables = 1 + (next_random(&mut seed) % MAX_SYLLABLES) as usize;
+                random_word(&mut seed, syllables)
+            }
+        })
+        .collect();
+
+    let prefixes = (0..NUM_PREFIX_QUERIES)
+        .map(|_| random_word(&mut seed, PREFIX_SYLLABLES))
+        .collect();
+
+    (words, lookups, prefixes)
+}
+
+// Classic pointer trie: every node owns its children through a sorted Vec of boxes
+#[derive(Default)]
+struct Node {
+    children: Vec<(u8, Box<Node>)>,
+    terminal: bool,
+}
+
+struct PointerTrie {
+    root: Node,
+    node_count: usize,
+    word_count: usize,
+}
+
+impl PointerTrie {
+    fn new() -> Self {
+        PointerTrie { root: Node::default(), node_count: 1, word_count: 0 }
+    }
+
+    fn insert(&mut self, word: &[u8]) {
+        let mut node = &mut self.root;
+        for &c in word {
+            let index = match node.children.binary_search_by_key(&c, |(k, _)| *k) {
+                Ok(index) => index,
+                Err(index) => {
+                    node.children.insert(index, (c, Box::default()));
+                    self.node_count += 1;
+                    index
+                }
+            };
+            node = &mut node.children[index].1;
+        }
+        if !node.terminal {
+            node.terminal = true;
+            self.word_count += 1;
+        }
+    }
+
+    fn find(&self, prefix: &[u8]) -> Option<&Node> {
+        let mut node = &self.root;
+        for &c in prefix {
+            let index = node.children.binary_search_by_key(&c, |(k, _)| *k).ok()?;
+            node = &node.children[index].1;
+        }
+        Some(node)
+    }
+
+    fn contains(&self, word: &[u8]) -> bool {
+        self.find(word).is_some_and(|node| node.terminal)
+    }
+
+    fn count_with_prefix(&self, prefix: &[u8]) -> usize {
+        fn count(node: &Node) -> usize {
+            node.terminal as usize + node.children.iter().map(|(_, child)| count(child)).sum::<usize>()
+        }
+        self.find(prefix).map_or(0, count)
+    }
+}
+
+// Array-mapped trie: a 26-bit child bitmap per node, children stored contiguously
+// in one arena and located with popcount instead of following pointers
+#[derive(Clone, Copy)]
+struct CompactNode {
+    bitmap: u32, // Bit c = child for letter c, TERMINAL_BIT = end of word
+    first_child: u32,
+}
+
+struct CompactTrie {
+    nodes: Vec<CompactNode>,
+}
+
+impl CompactTrie {
+    // Breadth-first layout so every node's children are adjacent
+    fn from_pointer_trie(trie: &PointerTrie) -> Self {
+        let mut nodes = Vec::with_capacity(trie.node_count);
+        let mut queue = std::collections::VecDeque::with_capacity(trie.node_count);
+        nodes.push(CompactNode { bitmap: 0, first_child: 0 });
+        queue.push_back(&trie.root);
+
+        let mut index = 0;
+        while let Some(node) = queue.pop_front() {
+            let mut bitmap = if node.terminal { TERMINAL_BIT } else { 0 };
+            for (c, child) in &node.children {
+                bitmap |= 1 << (c - b'a');
+                nodes.push(CompactNode { bitmap: 0, first_child: 0 });
+                queue.push_back(child);
+            }
+            nodes[index] = CompactNode { bitmap, first_child: (nodes.len() - node.children.len()) as u32 };
+            index += 1;
+        }
+
+        CompactTrie { nodes }
+    }
+
+    #[inline]
+    fn child(&self, node: u32, c: u8) -> Option<u32> {
+        let CompactNode { bitmap, first_child } = self.nodes[node as usize];
+        let bit = 1u32 << (c - b'a');
+        if bitmap & bit == 0 {
+            return None;
+        }
+        Some(first_child + (bitmap & (bit - 1)).count_ones())
+    }
+
+    fn find(&self, prefix: &[u8]) -> Option<u32> {
+        prefix.iter().try_fold(0, |node, &c| self.child(node, c))
+    }
+
+    fn contains(&self, word: &[u8]) -> bool {
+        self.find(word).is_some_and(|node| self.nodes[node as usize].bitmap & TERMINAL_BIT != 0)
+    }
+
+    fn count_with_prefix(&self, prefix: &[u8]) -> usize {
+        let Some(start) = self.find(prefix) else { return 0 };
+        let mut stack = vec![start];
+        let mut count = 0;
+        while let Some(node) = stack.pop() {
+            let CompactNode { bitmap, first_child } = self.nodes[node as usize];
+            count += (bitmap & TERMINAL_BIT != 0) as usize;
+            let children = (bitmap & (TERMINAL_BIT - 1)).count_ones();
+            stack.extend(first_child..first_child + children);
+        }
+        count
+    }
+}
+
+fn rate(ops: usize, secs: f64) -> f64 {
+    ops as f64 / secs
+}
+
+fn main() {
+    let (words, lookups, prefixes) = generate_data();
+
+    // Warm-up
+    {
+        let mut warmup = PointerTrie::new();
+        for word in &words[..NUM_WORDS / 20] {
+            warmup.insert(word);
+        }
+        let compact = CompactTrie::from_pointer_trie(&warmup);
+        let _ = lookups.iter().filter(|w| compact.contains(w)).count();
+    }
+
+    // Benchmark pointer trie insertion
+    let start = Instant::now();
+    let mut trie = PointerTrie::new();
+    for word in &words {
+        trie.insert(word);
+    }
+    let insert_duration = start.elapsed();
+
+    // Benchmark compact trie construction
+    let start = Instant::now();
+    let compact = CompactTrie::from_pointer_trie(&trie);
+    let build_duration = start.elapsed();
+
+    // Benchmark lookups
+    let start = Instant::now();
+    let found = lookups.iter().filter(|w| trie.contains(w)).count();
+    let lookup_duration = start.elapsed();
+
+    let start = Instant::now();
+    let compact_found = lookups.iter().filter(|w| compact.contains(w)).count();
+    let compact_lookup_duration = start.elapsed();
+
+    // Benchmark prefix queries
+    let start = Instant::now();
+    let completions: usize = prefixes.iter().map(|p| trie.count_with_prefix(p)).sum();
+    let prefix_duration = start.elapsed();
+
+    let start = Instant::now();
+    let compact_completions: usize = prefixes.iter().map(|p| compact.count_with_prefix(p)).sum();
+    let compact_prefix_duration = start.elapsed();
+
+    let total_duration = insert_duration
+        + build_duration
+        + lookup_duration
+        + compact_lookup_duration
+        + prefix_duration
+        + compact_prefix_duration;
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Insert: {:.6}s ({:.0} ops/s), compact build: {:.6}s",
+              insert_duration.as_secs_f64(), rate(NUM_WORDS, insert_duration.as_secs_f64()),
+              build_duration.as_secs_f64());
+    eprintln!("Lookup: pointer {:.0} ops/s, compact {:.0} ops/s",
+              rate(NUM_LOOKUPS, lookup_duration.as_secs_f64()),
+              rate(NUM_LOOKUPS, compact_lookup_duration.as_secs_f64()));
+    eprintln!("Prefix: pointer {:.0} ops/s, compact {:.0} ops/s",
+              rate(NUM_PREFIX_QUERIES, prefix_duration.as_secs_f64()),
+              rate(NUM_PREFIX_QUERIES, compact_prefix_duration.as_secs_f64()));
+    eprintln!("Nodes: {}, unique words: {}", trie.node_count, trie.word_count);
+    eprintln!("Checksum: {} found, {} completions (compact: {} found, {} completions)",
+              found, completions, compact_found, compact_completions);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn build(words: &[&[u8]]) -> PointerTrie {
+        let mut trie = PointerTrie::new();
+        for word in words {
+            trie.insert(word);
+        }
+        trie
+    }
+
+    #[test]
+    fn pointer_trie_counts_nodes_words_and_prefixes() {
+        let trie = build(&[b"ba", b"bad", b"bat", b"ba", b"ko"]);
+        assert_eq!((trie.node_count, trie.word_count), (7, 4)); // root, b, a, d, t, k, o
+        assert!(trie.contains(b"ba") && trie.contains(b"bat") && !trie.contains(b"b") && !trie.contains(b"bab"));
+        assert_eq!(trie.count_with_prefix(b"b"), 3);
+        assert_eq!(trie.count_with_prefix(b""), 4);
+        assert_eq!(trie.count_with_prefix(b"z"), 0);
+    }
+
+    #[test]
+    fn compact_trie_answers_like_the_pointer_trie() {
+        let mut seed = 7u64;
+        let words: Words = (0..5000).map(|i| random_word(&mut seed, 1 + i % 4)).collect();
+        let mut trie = PointerTrie::new();
+        for word in &words {
+            trie.insert(word);
+        }
+        let compact = CompactTrie::from_pointer_trie(&trie);
+        assert_eq!(compact.nodes.len(), trie.node_count);
+        assert_eq!(trie.word_count, words.iter().collect::<HashSet<_>>().len());
+
+        for _ in 0..2000 {
+            let syllables = 1 + (next_random(&mut seed) % 4) as usize;
+            let probe = random_word(&mut seed, syllables);
+            assert_eq!(compact.contains(&probe), trie.contains(&probe));
+            assert_eq!(compact.contains(&probe), words.contains(&probe));
+            let prefix = &probe[..2];
+            assert_eq!(compact.count_with_prefix(prefix), trie.count_with_prefix(prefix));
+        }
+        assert_eq!(compact.count_with_prefix(b""), trie.word_count);
+    }
+}
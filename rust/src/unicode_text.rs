@@ -0,0 +1,180 @@
+use icu_locale_core::LanguageIdentifier;
+use std::borrow::Cow;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use unicode_normalization::UnicodeNormalization;
+
+const CORPUS_BYTES: usize = 16 << 20;
+const WORDS_PER_LINE: usize = 12;
+const PASSES: usize = 3;
+
+// Words from scripts with casing rules worth testing, and some without. The \u{...} escapes are
+// decomposed (NFD) spellings, some with combining marks out of canonical order, for NFC to
+// recompose: Hangul jamo, stacked Vietnamese diacritics, accented Latin
+const WORDS: [&str; 47] = [
+    // English
+    "the", "quick", "Brown", "fox", "DATA", "pipeline", "ingest",
+    // French, German, Turkish: expanding (ß, ﬁ) and locale-sensitive (İ, ı) mappings
+    "café", "cafe\u{301}", "Élève", "garçon", "naïve", "Noe\u{308}l", "Straße", "größer", "ÄRGER", "Fuß",
+    "ﬁnance", "İstanbul", "ılık", "Iğdır",
+    // Greek, with final sigma and accents
+    "Οδυσσέας", "σοφία", "ΣΟΦΟΣ", "λόγος", "ψυχή", "ΐ",
+    // Cyrillic and Armenian, including the և ligature that uppercases to two letters
+    "Москва", "ёлка", "щука", "ЖИЗНЬ", "Երևան", "և",
+    // Vietnamese, precomposed and decomposed with marks in both orders
+    "Tiếng", "Vie\u{323}\u{302}t", "Vie\u{302}\u{323}t", "Nguyễn",
+    // Korean as precomposed syllables and as conjoining jamo
+    "한국어", "\u{1112}\u{1161}\u{11AB}\u{1100}\u{116E}\u{11A8}",
+    // CJK, Devanagari and emoji: no case, and already NFC
+    "東京", "数据", "日本語", "हिन्दी", "नमस्ते", "😀", "👍🏽", "🇯🇵",
+];
+
+fn splitmix64(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Lines of WORDS_PER_LINE random words separated by spaces, until the corpus reaches `bytes`
+fn generate(bytes: usize) -> String {
+    let mut seed = 0x1F600;
+    let mut text = String::with_capacity(bytes + 256);
+    while text.len() < bytes {
+        for w in 0..WORDS_PER_LINE {
+            if w > 0 {
+                text.push(' ');
+            }
+            text.push_str(WORDS[(splitmix64(&mut seed) % WORDS.len() as u64) as usize]);
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash = (hash ^ b as u64).wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn upper_std(line: &str) -> Cow<'_, str> {
+    Cow::Owned(line.to_uppercase())
+}
+
+fn upper_icu4x(line: &str) -> Cow<'_, str> {
+    icu_casemap::CaseMapper::new().uppercase_to_string(line, &LanguageIdentifier::UNKNOWN)
+}
+
+fn fold_caseless(line: &str) -> Cow<'_, str> {
+    Cow::Owned(caseless::default_case_fold_str(line))
+}
+
+fn fold_icu4x(line: &str) -> Cow<'_, str> {
+    icu_casemap::CaseMapper::new().fold_string(line)
+}
+
+fn nfc_unicode_normalization(line: &str) -> Cow<'_, str> {
+    Cow::Owned(line.nfc().collect())
+}
+
+fn nfc_icu4x(line: &str) -> Cow<'_, str> {
+    icu_normalizer::ComposingNormalizer::new_nfc().normalize(line)
+}
+
+type TransformFn = fn(&str) -> Cow<'_, str>;
+
+// Per operation, the first library is the reference the second has to reproduce
+const VARIANTS: [(&str, &str, TransformFn); 6] = [
+    ("uppercase", "std", upper_std),
+    ("uppercase", "icu4x", upper_icu4x),
+    ("case fold", "caseless", fold_caseless),
+    ("case fold", "icu4x", fold_icu4x),
+    ("NFC", "unicode-norm", nfc_unicode_normalization),
+    ("NFC", "icu4x", nfc_icu4x),
+];
+
+struct Output {
+    hash: u64, // FNV-1a of the transformed lines, back to back
+    bytes: usize,
+}
+
+// Line by line, as a pipeline normalizing fields would
+fn transform(f: TransformFn, text: &str) -> Output {
+    let mut output = Output { hash: 0xcbf29ce484222325, bytes: 0 };
+    for line in text.lines() {
+        let result = f(line);
+        output.hash = fnv1a(output.hash, result.as_bytes());
+        output.bytes += result.len();
+    }
+    output
+}
+
+// black_box on the input every pass stops the compiler computing one pass and reusing it
+fn run(f: TransformFn, text: &str, passes: usize) -> (Duration, Output) {
+    let mut output = transform(f, text);
+    let start = Instant::now();
+    for _ in 0..passes {
+        output = transform(f, black_box(text));
+    }
+    (start.elapsed(), output)
+}
+
+fn main() {
+    let text = generate(CORPUS_BYTES);
+    let chars = text.chars().count();
+
+    // Benchmark (each run's first, untimed pass is the warm-up)
+    let runs: Vec<(Duration, Output)> = VARIANTS.iter().map(|&(_, _, f)| run(f, &text, PASSES)).collect();
+    let total_duration: Duration = runs.iter().map(|r| r.0).sum();
+    let all_match = runs.chunks(2).all(|pair| pair[0].1.hash == pair[1].1.hash && pair[0].1.bytes == pair[1].1.bytes);
+    let checksum = runs.chunks(2).fold(0u64, |acc, pair| acc.wrapping_add(pair[0].1.hash));
+
+    println!("{:.6}", total_duration.as_secs_f64());
+    eprintln!("Corpus: {:.1} MB, {} chars in {} lines x {} passes per variant", text.len() as f64 / 1e6, chars,
+              text.lines().count(), PASSES);
+    for ((op, library, _), (duration, output)) in VARIANTS.iter().zip(&runs) {
+        let rate = (text.len() * PASSES) as f64 / duration.as_secs_f64();
+        eprintln!("  {:<10} {:<13} {:.3}s ({:.0} MB/s, {} bytes out)", op, library, duration.as_secs_f64(), rate / 1e6,
+                  output.bytes);
+    }
+    eprintln!("Checksum: {} (all variants match: {})", checksum, all_match);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn each(op: &str) -> impl Iterator<Item = TransformFn> + '_ {
+        VARIANTS.iter().filter(move |v| v.0 == op).map(|v| v.2)
+    }
+
+    #[test]
+    fn special_mappings() {
+        for upper in each("uppercase") {
+            assert_eq!(upper("Straße ﬁnance և"), "STRASSE FINANCE ԵՒ");
+            assert_eq!(upper("ılık"), "ILIK");
+        }
+        for fold in each("case fold") {
+            assert_eq!(fold("Straße ΣΟΦΟΣ σοφός"), "strasse σοφοσ σοφόσ");
+            assert_eq!(fold("İ"), "i\u{307}");
+        }
+        for nfc in each("NFC") {
+            assert_eq!(nfc("cafe\u{301}"), "café");
+            assert_eq!(nfc("Vie\u{302}\u{323}t"), "Việt");
+            assert_eq!(nfc("\u{1112}\u{1161}\u{11AB}"), "한");
+            assert_eq!(nfc("東京 😀"), "東京 😀");
+        }
+    }
+
+    #[test]
+    fn libraries_agree_on_the_corpus() {
+        let text = generate(64 << 10);
+        for pair in VARIANTS.chunks(2) {
+            let (a, b) = (transform(pair[0].2, &text), transform(pair[1].2, &text));
+            assert_eq!((a.hash, a.bytes), (b.hash, b.bytes), "{}", pair[0].0);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::time::Instant;
+
+const CORPUS_BYTES: usize = 1 << 30; // ~1GB of log lines
+const VOCABULARY_SIZE: usize = 50_000;
+const NUM_REQUEST_IDS: u64 = 100_000;
+const TOP_K: usize = 20;
+const NUM_CHUNKS: usize = 64;
+const CONSONANTS: &[u8] = b"bcdfghklmnprstvz";
+const VOWELS: &[u8] = b"aeiou";
+const LEVELS: [&[u8]; 4] = [b"INFO", b"DEBUG", b"WARN", b"ERROR"];
+const SERVICES: [&[u8]; 8] = [b"auth", b"billing", b"gateway", b"search", b"storage", b"queue", b"mailer", b"cache"];
+
+type Counts<'a> = HashMap<&'a [u8], u64>;
+type TopK<'a> = Vec<(u64, &'a [u8])>;
+
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+fn push_two_digits(out: &mut Vec<u8>, n: u64) {
+    out.push(b'0' + (n / 10) as u8);
+    out.push(b'0' + (n % 10) as u8);
+}
+
+// Lines look like "12:34:56 WARN [billing] tokens... req=0x1a2b\n"; message words follow Zipf's law
+fn generate_corpus() -> Vec<u8> {
+    let mut seed = 1234567u64;
+    let vocabulary: Vec<Vec<u8>> = (0..VOCABULARY_SIZE)
+        .map(|_| {
+            let syllables = 2 + next_random(&mut seed) % 3;
+            let mut word = Vec::new();
+            for _ in 0..syllables {
+                word.push(CONSONANTS[(next_random(&mut seed) % CONSONANTS.len() as u64) as usize]);
+                word.push(VOWELS[(next_random(&mut seed) % VOWELS.len() as u64) as usize]);
+            }
+            word
+        })
+        .collect();
+
+    let mut cdf = Vec::with_capacity(VOCABULARY_SIZE);
+    let mut total = 0.0f64;
+    for rank in 0..VOCABULARY_SIZE {
+        total += 1.0 / (rank + 1) as f64;
+        cdf.push(total);
+    }
+
+    let mut corpus = Vec::with_capacity(CORPUS_BYTES + 256);
+    while corpus.len() < CORPUS_BYTES {
+        push_two_digits(&mut corpus, next_random(&mut seed) % 24);
+        corpus.push(b':');
+        push_two_digits(&mut corpus, next_random(&mut seed) % 60);
+        corpus.push(b':');
+        push_two_digits(&mut corpus, next_random(&mut seed) % 60);
+        corpus.push(b' ');
+
+        let level = next_random(&mut seed) % 100;
+        corpus.extend_from_slice(LEVELS[if level < 70 { 0 } else if level < 85 { 1 } else if level < 97 { 2 } else { 3 }]);
+        corpus.extend_from_slice(b" [");
+        corpus.extend_from_slice(SERVICES[(next_random(&mut seed) % SERVICES.len() as u64) as usize]);
+        corpus.extend_from_slice(b"]");
+
+        let words = 5 + next_random(&mut seed) % 11;
+        for _ in 0..words {
+            let u = next_random(&mut seed) as f64 / (1u64 << 31) as f64 * total;
+            let rank = cdf.partition_point(|&c| c < u).min(VOCABULARY_SIZE - 1);
+            corpus.push(b' ');
+            corpus.extend_from_slice(&vocabulary[rank]);
+        }
+
+        corpus.extend_from_slice(b" req=0x");
+        let id = next_random(&mut seed) % NUM_REQUEST_IDS;
+        for shift in (0..5).rev() {
+            corpus.push(b"0123456789abcdef"[((id >> (shift * 4)) & 0xf) as usize]);
+        }
+        corpus.push(b'\n');
+    }
+
+    corpus
+}
+
+// Tokens are maximal runs of ASCII letters, digits, and underscores (case-sensitive)
+fn count_words<'a>(text: &'a [u8], counts: &mut Counts<'a>) {
+    let mut start = None;
+    for (i, &c) in text.iter().enumerate() {
+        let is_word = c.is_ascii_alphanumeric() || c == b'_';
+        match (is_word, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                *counts.entry(&text[s..i]).or_insert(0) += 1;
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        *counts.entry(&text[s..]).or_insert(0) += 1;
+    }
+}
+
+// Min-heap of the best K so far, ordered by count descending then word ascending
+fn top_k<'a>(counts: &Counts<'a>) -> TopK<'a> {
+    let mut heap = BinaryHeap::with_capacity(TOP_K + 1);
+    for (&word, &count) in counts {
+        heap.push(Reverse((count, Reverse(word))));
+        if heap.len() > TOP_K {
+            heap.pop();
+        }
+    }
+    let mut top: Vec<_> = heap.into_iter().map(|Reverse((count, Reverse(word)))| (count, word)).collect();
+    top.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+    top
+}
+
+fn run_sequential(corpus: &[u8]) -> (Counts<'_>, TopK<'_>) {
+    let mut counts = HashMap::new();
+    count_words(corpus, &mut counts);
+    let top = top_k(&counts);
+    (counts, top)
+}
+
+// Map: count each line-aligned chunk independently; reduce: merge the partial maps
+fn run_parallel(corpus: &[u8]) -> (Counts<'_>, TopK<'_>) {
+    let mut bounds = vec![0];
+    for i in 1..NUM_CHUNKS {
+        let target = (corpus.len() * i / NUM_CHUNKS).max(*bounds.last().unwrap());
+        let end = corpus[target..].iter().position(|&c| c == b'\n').map_or(corpus.len(), |p| target + p + 1);
+        bounds.push(end);
+    }
+    bounds.push(corpus.len());
+
+    let counts = bounds
+        .par_windows(2)
+        .map(|w| {
+            let mut counts = HashMap::new();
+            count_words(&corpus[w[0]..w[1]], &mut counts);
+            counts
+        })
+        .reduce(HashMap::new, |a, b| {
+            let (mut large, small) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+            for (word, count) in small {
+                *large.entry(word).or_insert(0) += count;
+            }
+            large
+        });
+    let top = top_k(&counts);
+    (counts, top)
+}
+
+fn main() {
+    let mut measure = bench_measure::from_env();
+    let corpus = generate_corpus();
+    let megabytes = corpus.len() as f64 / 1e6;
+
+    // Warm-up
+    let _ = run_sequential(&corpus[..corpus.len() / 100]);
+    let _ = run_parallel(&corpus[..corpus.len() / 100]);
+
+    // Benchmark sequential
+    let start = Instant::now();
+    measure.start();
+    let (counts, top) = run_sequential(&corpus);
+    measure.stop();
+    let sequential_duration = start.elapsed();
+
+    // Benchmark parallel map-reduce
+    let start = Instant::now();
+    measure.start();
+    let (parallel_counts, parallel_top) = run_parallel(&corpus);
+    measure.stop();
+    let parallel_duration = start.elapsed();
+
+    // Checksum
+    let total_words: u64 = counts.values().sum();
+    let parallel_words: u64 = parallel_counts.values().sum();
+    let top_sum: u64 = top.iter().map(|&(count, _)| count).sum();
+    let parallel_top_sum: u64 = parallel_top.iter().map(|&(count, _)| count).sum();
+    let leaders: Vec<String> = top
+        .iter()
+        .take(3)
+        .map(|&(count, word)| format!("{}={}", String::from_utf8_lossy(word), count))
+        .collect();
+
+    measure.report();
+    eprintln!("Sequential: {:.6}s ({:.0} MB/s), parallel: {:.6}s ({:.0} MB/s)",
+              sequential_duration.as_secs_f64(), megabytes / sequential_duration.as_secs_f64(),
+              parallel_duration.as_secs_f64(), megabytes / parallel_duration.as_secs_f64());
+    eprintln!("Corpus: {:.0} MB, words: {}, unique: {}, top: {}",
+              megabytes, total_words, counts.len(), leaders.join(" "));
+    eprintln!("Checksum: {} (parallel: {} words, {} unique, top-{} sum {})",
+              top_sum, parallel_words, parallel_counts.len(), TOP_K, parallel_top_sum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_runs_of_word_characters() {
+        let mut counts = HashMap::new();
+        count_words(b"12:34:56 WARN [auth] foo_bar foo, Foo req=0x1a2b", &mut counts);
+        let expected: Counts = [
+            (&b"12"[..], 1), (b"34", 1), (b"56", 1), (b"WARN", 1), (b"auth", 1), (b"foo_bar", 1),
+            (b"foo", 1), (b"Foo", 1), (b"req", 1), (b"0x1a2b", 1),
+        ].into_iter().collect();
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn top_k_breaks_ties_by_word() {
+        let words: Vec<Vec<u8>> = (0..TOP_K as u8 + 5).map(|i| vec![b'a' + i]).collect();
+        let mut counts: Counts = words.iter().map(|w| (w.as_slice(), 1)).collect();
+        counts.insert(b"zz", 9);
+        let top = top_k(&counts);
+        assert_eq!(top.len(), TOP_K);
+        assert_eq!(top[0], (9, &b"zz"[..]));
+        assert_eq!(top[1..].iter().map(|&(_, w)| w[0]).collect::<Vec<_>>(), (b'a'..).take(TOP_K - 1).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        // Chunk boundaries land mid-line and are moved to the next newline
+        let text = b"aa bb\ncc aa\n\nbb aa dd\nee".repeat(50);
+        let (counts, top) = run_sequential(&text);
+        let (parallel_counts, parallel_top) = run_parallel(&text);
+        assert_eq!(counts, parallel_counts);
+        assert_eq!(top, parallel_top);
+        // repeat() glues each copy's last word to the next copy's first
+        assert_eq!((counts[&b"aa"[..]], counts[&b"eeaa"[..]], counts[&b"ee"[..]]), (101, 49, 1));
+    }
+}
@@ -0,0 +1,36 @@
+#include <metal_stdlib>
+using namespace metal;
+
+// Heavy compute shader: matrix element-wise operations with trigonometric functions.
+// metal_compute binds a, b and result at buffers 0-2, the matrix size at buffer 3, and
+// dispatches one thread per element of the size x size grid
+kernel void heavy_compute(device const float* a [[buffer(0)]],
+                          device const float* b [[buffer(1)]],
+                          device float* result [[buffer(2)]],
+                          constant uint& matrix_size [[buffer(3)]],
+                          uint2 gid [[thread_position_in_grid]])
+{
+    uint idx = gid.y * matrix_size + gid.x;
+    
+    // Perform multiple heavy computations
+    float val_a = a[idx];
+    float val_b = b[idx];
+    
+    float sum = 0.0;
+    for (int i = 0; i < 50; i++) {
+        float t = val_a * float(i) * 0.01 + val_b;
+        sum += sin(t) * cos(val_b * float(i) * 0.01) + 
+               tan(val_a * 0.1) * exp(-val_b * 0.001) +
+               sqrt(abs(t)) * log(abs(t) + 1.0);
+    }
+    
+    // Additional matrix-style computation
+    float accumulator = 0.0;
+    uint row = gid.y;
+    for (uint col = 0; col < matrix_size; col++) {
+        uint idx2 = row * matrix_size + col;
+        accumulator += a[idx2] * b[idx2] * 0.0001;
+    }
+    
+    result[idx] = sum + accumulator;
+}
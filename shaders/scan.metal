@@ -0,0 +1,226 @@
+#include <metal_stdlib>
+using namespace metal;
+
+// Reduction and inclusive prefix scan over uint, wrapping on overflow like the CPU reference.
+// metal_scan dispatches every kernel in threadgroups of GROUP_SIZE threads. The _tree kernels
+// combine values through threadgroup memory and barriers alone; the _simd kernels combine
+// within each SIMD group with simdgroup intrinsics and use threadgroup memory only across groups
+
+constant uint GROUP_SIZE = 256;
+constant uint ELEMENTS_PER_THREAD = 4;
+constant uint BLOCK = GROUP_SIZE * ELEMENTS_PER_THREAD; // elements scanned per threadgroup
+// The SIMD kernels scan the per-group totals within one SIMD group, so they need at least as many
+// lanes as there are groups: a SIMD width of 16 or more, which metal_scan checks before dispatching
+constant uint MAX_SIMDGROUPS = GROUP_SIZE / 16;
+
+struct Lanes {
+    uint lid;         // thread in the threadgroup
+    uint lane;        // thread in its SIMD group
+    uint simd_id;     // SIMD group in the threadgroup
+    uint simd_count;  // SIMD groups in the threadgroup
+    uint simd_width;  // threads per SIMD group
+};
+
+// Exclusive scan of one value per thread across the threadgroup, also giving the group total.
+// `partial` holds GROUP_SIZE values for the tree scan and MAX_SIMDGROUPS + 1 for the SIMD one
+template <bool SIMD>
+uint exclusive_scan(uint value, Lanes t, threadgroup uint* partial, thread uint& total)
+{
+    if (SIMD) {
+        // Scan within each SIMD group, then scan the per-group totals in the first group
+        uint inclusive = simd_prefix_inclusive_sum(value);
+        if (t.lane == t.simd_width - 1) {
+            partial[t.simd_id] = inclusive;
+        }
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+        if (t.simd_id == 0) {
+            uint group = t.lane < t.simd_count ? partial[t.lane] : 0;
+            uint prefix = simd_prefix_exclusive_sum(group);
+            if (t.lane < t.simd_count) {
+                partial[t.lane] = prefix;
+            }
+            if (t.lane == t.simd_count - 1) {
+                partial[MAX_SIMDGROUPS] = prefix + group;
+            }
+        }
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+        total = partial[MAX_SIMDGROUPS];
+        return partial[t.simd_id] + inclusive - value;
+    }
+
+    // Hillis-Steele: log2(GROUP_SIZE) rounds, each read separated from its write by a barrier
+    partial[t.lid] = value;
+    threadgroup_barrier(mem_flags::mem_threadgroup);
+    for (uint offset = 1; offset < GROUP_SIZE; offset <<= 1) {
+        uint left = t.lid >= offset ? partial[t.lid - offset] : 0;
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+        partial[t.lid] += left;
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+    }
+    total = partial[GROUP_SIZE - 1];
+    return partial[t.lid] - value;
+}
+
+// Inclusive scan of data[base, base + BLOCK) plus `carry` into out, returning the block total.
+// Each thread scans ELEMENTS_PER_THREAD consecutive elements serially, so only one value per
+// thread crosses lanes; elements past `count` read as zero and are not written
+template <bool SIMD>
+uint scan_block(device const uint* data, device uint* out, uint base, uint count, uint carry,
+                Lanes t, threadgroup uint* partial)
+{
+    uint first = base + t.lid * ELEMENTS_PER_THREAD;
+    uint values[ELEMENTS_PER_THREAD];
+    uint sum = 0;
+    for (uint k = 0; k < ELEMENTS_PER_THREAD; k++) {
+        sum += first + k < count ? data[first + k] : 0;
+        values[k] = sum;
+    }
+    uint total;
+    uint prefix = carry + exclusive_scan<SIMD>(sum, t, partial, total);
+    for (uint k = 0; k < ELEMENTS_PER_THREAD; k++) {
+        if (first + k < count) {
+            out[first + k] = prefix + values[k];
+        }
+    }
+    return total;
+}
+
+#define LANE_ARGUMENTS                                              \
+    uint lid [[thread_position_in_threadgroup]],                    \
+    uint lane [[thread_index_in_simdgroup]],                        \
+    uint simd_id [[simdgroup_index_in_threadgroup]],                \
+    uint simd_count [[simdgroups_per_threadgroup]],                 \
+    uint simd_width [[threads_per_simdgroup]]
+
+// ---- Reduction: grid-stride partial sums, combined per threadgroup, one atomic per group ----
+
+kernel void reduce_tree(device const uint* input [[buffer(0)]],
+                        device atomic_uint* total [[buffer(1)]],
+                        constant uint& count [[buffer(2)]],
+                        uint gid [[thread_position_in_grid]],
+                        uint grid_size [[threads_per_grid]],
+                        uint lid [[thread_position_in_threadgroup]])
+{
+    threadgroup uint partial[GROUP_SIZE];
+    uint sum = 0;
+    for (uint i = gid; i < count; i += grid_size) {
+        sum += input[i];
+    }
+    partial[lid] = sum;
+    threadgroup_barrier(mem_flags::mem_threadgroup);
+    for (uint stride = GROUP_SIZE / 2; stride > 0; stride >>= 1) {
+        if (lid < stride) {
+            partial[lid] += partial[lid + stride];
+        }
+        threadgroup_barrier(mem_flags::mem_threadgroup);
+    }
+    if (lid == 0) {
+        atomic_fetch_add_explicit(total, partial[0], memory_order_relaxed);
+    }
+}
+
+kernel void reduce_simd(device const uint* input [[buffer(0)]],
+                        device atomic_uint* total [[buffer(1)]],
+                        constant uint& count [[buffer(2)]],
+                        uint gid [[thread_position_in_grid]],
+                        uint grid_size [[threads_per_grid]],
+                        LANE_ARGUMENTS)
+{
+    threadgroup uint partial[MAX_SIMDGROUPS];
+    uint sum = 0;
+    for (uint i = gid; i < count; i += grid_size) {
+        sum += input[i];
+    }
+    sum = simd_sum(sum);
+    if (lane == 0) {
+        partial[simd_id] = sum;
+    }
+    threadgroup_barrier(mem_flags::mem_threadgroup);
+    if (simd_id == 0) {
+        sum = simd_sum(lane < simd_count ? partial[lane] : 0);
+        if (lane == 0) {
+            atomic_fetch_add_explicit(total, sum, memory_order_relaxed);
+        }
+    }
+}
+
+// ---- Scan: reduce-then-scan in three dispatches ----
+// 1. scan_blocks_*: every threadgroup scans one BLOCK and records its total
+// 2. scan_totals_*: one threadgroup scans the block totals in place, BLOCK at a time
+// 3. scan_add_offsets: every block after the first adds the inclusive total of the ones before it
+
+template <bool SIMD>
+void scan_blocks(device const uint* input, device uint* output, device uint* totals, uint count,
+                 uint group, Lanes t, threadgroup uint* partial)
+{
+    uint total = scan_block<SIMD>(input, output, group * BLOCK, count, 0, t, partial);
+    if (t.lid == 0) {
+        totals[group] = total;
+    }
+}
+
+template <bool SIMD>
+void scan_totals(device uint* totals, uint blocks, Lanes t, threadgroup uint* partial)
+{
+    uint carry = 0;
+    for (uint base = 0; base < blocks; base += BLOCK) {
+        carry += scan_block<SIMD>(totals, totals, base, blocks, carry, t, partial);
+        threadgroup_barrier(mem_flags::mem_threadgroup); // partial is reused by the next chunk
+    }
+}
+
+kernel void scan_blocks_tree(device const uint* input [[buffer(0)]],
+                             device uint* output [[buffer(1)]],
+                             device uint* totals [[buffer(2)]],
+                             constant uint& count [[buffer(3)]],
+                             uint group [[threadgroup_position_in_grid]],
+                             LANE_ARGUMENTS)
+{
+    threadgroup uint partial[GROUP_SIZE];
+    scan_blocks<false>(input, output, totals, count, group, {lid, lane, simd_id, simd_count, simd_width}, partial);
+}
+
+kernel void scan_blocks_simd(device const uint* input [[buffer(0)]],
+                             device uint* output [[buffer(1)]],
+                             device uint* totals [[buffer(2)]],
+                             constant uint& count [[buffer(3)]],
+                             uint group [[threadgroup_position_in_grid]],
+                             LANE_ARGUMENTS)
+{
+    threadgroup uint partial[MAX_SIMDGROUPS + 1];
+    scan_blocks<true>(input, output, totals, count, group, {lid, lane, simd_id, simd_count, simd_width}, partial);
+}
+
+kernel void scan_totals_tree(device uint* totals [[buffer(2)]],
+                             constant uint& blocks [[buffer(4)]],
+                             LANE_ARGUMENTS)
+{
+    threadgroup uint partial[GROUP_SIZE];
+    scan_totals<false>(totals, blocks, {lid, lane, simd_id, simd_count, simd_width}, partial);
+}
+
+kernel void scan_totals_simd(device uint* totals [[buffer(2)]],
+                             constant uint& blocks [[buffer(4)]],
+                             LANE_ARGUMENTS)
+{
+    threadgroup uint partial[MAX_SIMDGROUPS + 1];
+    scan_totals<true>(totals, blocks, {lid, lane, simd_id, simd_count, simd_width}, partial);
+}
+
+kernel void scan_add_offsets(device uint* output [[buffer(1)]],
+                             device const uint* totals [[buffer(2)]],
+                             constant uint& count [[buffer(3)]],
+                             uint group [[threadgroup_position_in_grid]],
+                             uint lid [[thread_position_in_threadgroup]])
+{
+    if (group == 0) {
+        return;
+    }
+    uint offset = totals[group - 1];
+    for (uint k = 0; k < ELEMENTS_PER_THREAD; k++) {
+        uint i = group * BLOCK + k * GROUP_SIZE + lid; // strided by GROUP_SIZE so loads coalesce
+        if (i < count) {
+            output[i] += offset;
+        }
+    }
+}
@@ -0,0 +1,371 @@
+#!/usr/bin/env python3
+"""
+Rust vs C++ Speedup Summary
+Headline numbers from a build.py run: the geometric-mean speedup across the suite,
+the winner of each category, the benchmarks furthest from parity, and what the
+binaries cost to build and ship
+"""
+
+import json
+import math
+import sys
+from pathlib import Path
+from typing import Dict, List, Optional
+
+from build import (BENCHMARKS, CATEGORIES, CONFIDENCE, CV_THRESHOLD, UNRELIABLE_ADVICE, calculate_statistics,
+                   speedup_interval)
+
+RESULTS_FILE = "results/benchmark_results.json"
+CONFIG_FILE = "bench.toml"
+NUM_OUTLIERS = 5
+
+
+def speedups(data: Dict) -> Dict[str, float]:
+    """C++ mean time / Rust mean time for every benchmark that ran in both languages (>1 = Rust faster)"""
+    result = {}
+    for benchmark in BENCHMARKS:
+        rust_mean = data["rust"].get(benchmark, {}).get("stats", {}).get("mean", 0)
+        cpp_mean = data["cpp"].get(benchmark, {}).get("stats", {}).get("mean", 0)
+        # A mean of 0 is a benchmark with no successful runs
+        if rust_mean > 0 and cpp_mean > 0:
+            result[benchmark] = cpp_mean / rust_mean
+    return result
+
+
+def speedup_intervals(data: Dict, per_benchmark: Dict[str, float]) -> Dict[str, List[float]]:
+    """Bootstrap interval of each speedup, from the runs behind the means"""
+    result = {}
+    for benchmark in per_benchmark:
+        rust_times = data["rust"][benchmark].get("times", [])
+        cpp_times = data["cpp"][benchmark].get("times", [])
+        interval = speedup_interval(rust_times, cpp_times)
+        if interval:
+            result[benchmark] = list(interval)
+    return result
+
+
+def variation(data: Dict, lang: str, benchmark: str) -> Optional[float]:
+    """A benchmark's run-to-run coefficient of variation in one language, from its stats or, in older files, its times"""
+    entry = data[lang].get(benchmark, {})
+    if "cv" in entry.get("stats", {}):
+        return entry["stats"]["cv"]
+    return calculate_statistics(entry.get("times", [])).get("cv")
+
+
+def unreliable_benchmarks(data: Dict, per_benchmark: Dict[str, float]) -> List[Dict]:
+    """Benchmarks whose runs varied by more than CV_THRESHOLD in either language, with both CVs"""
+    result = []
+    for benchmark in per_benchmark:
+        rust, cpp = variation(data, "rust", benchmark), variation(data, "cpp", benchmark)
+        if any(cv is not None and cv > CV_THRESHOLD for cv in (rust, cpp)):
+            result.append({"benchmark": benchmark, "rust_cv": rust, "cpp_cv": cpp})
+    return result
+
+
+def tally(benchmarks: List[str], per_benchmark: Dict[str, float], intervals: Dict[str, List[float]]) -> Dict[str, int]:
+    """Wins in each language, where a win needs its interval clear of 1, and the ties within noise
+
+    A benchmark without an interval (a single run) falls back to its point speedup
+    """
+    counts = {"rust_wins": 0, "cpp_wins": 0, "ties": 0}
+    for benchmark in benchmarks:
+        low, high = intervals.get(benchmark, [per_benchmark[benchmark]] * 2)
+        if low > 1:
+            counts["rust_wins"] += 1
+        elif high < 1:
+            counts["cpp_wins"] += 1
+        else:
+            counts["ties"] += 1
+    return counts
+
+
+def geometric_mean(values: List[float]) -> float:
+    """The mean of ratios that treats 2x faster and 2x slower symmetrically"""
+    return math.exp(sum(math.log(v) for v in values) / len(values))
+
+
+def winner(speedup: float) -> str:
+    if speedup > 1:
+        return "Rust"
+    if speedup < 1:
+        return "C++"
+    return "Tie"
+
+
+def describe(speedup: float) -> str:
+    """A speedup as how many times faster the winner is"""
+    if speedup == 1:
+        return "even"
+    return f"{max(speedup, 1 / speedup):.2f}x faster in {winner(speedup)}"
+
+
+def summarize(data: Dict) -> Dict:
+    """Suite-wide and per-category geometric means, win counts and the largest outliers each way"""
+    per_benchmark = speedups(data)
+    if not per_benchmark:
+        return {}
+    intervals = speedup_intervals(data, per_benchmark)
+    unreliable = unreliable_benchmarks(data, per_benchmark)
+    noisy = {entry["benchmark"] for entry in unreliable}
+
+    categories = {}
+    for category, benches in CATEGORIES.items():
+        present = [b for b in benches if b in per_benchmark]
+        if not present:
+            continue
+        speedup = geometric_mean([per_benchmark[b] for b in present])
+        categories[category] = {
+            "benchmarks": len(present),
+            "geomean_speedup": speedup,
+            "winner": winner(speedup),
+            **tally(present, per_benchmark, intervals),
+        }
+
+    ranked = sorted(per_benchmark.items(), key=lambda item: item[1], reverse=True)
+    def outlier(benchmark: str, speedup: float) -> Dict:
+        entry = {"benchmark": benchmark, "speedup": speedup}
+        if benchmark in intervals:
+            entry["ci"] = intervals[benchmark]
+        if benchmark in noisy:
+            entry["unreliable"] = True
+        return entry
+
+    return {
+        "benchmarks": len(per_benchmark),
+        "geomean_speedup": geometric_mean(list(per_benchmark.values())),
+        **tally(list(per_benchmark), per_benchmark, intervals),
+        "categories": categories,
+        "rust_outliers": [outlier(b, s) for b, s in ranked[:NUM_OUTLIERS] if s > 1],
+        "cpp_outliers": [outlier(b, s) for b, s in reversed(ranked[-NUM_OUTLIERS:]) if s < 1],
+        "speedups": per_benchmark,
+        "confidence": CONFIDENCE,
+        "speedup_ci": intervals,
+        "cv_threshold": CV_THRESHOLD,
+        "unreliable": unreliable,
+    }
+
+
+def score_settings(config_file: str = CONFIG_FILE) -> Dict:
+    """The [score] table of bench.toml: reference results file and category weights, all 1.0 by default"""
+    settings = {"reference": "", "weights": {category: 1.0 for category in CATEGORIES}}
+    if not Path(config_file).exists():
+        return settings
+    try:
+        import tomllib
+    except ModuleNotFoundError:
+        print(f"⚠️  {config_file} needs Python 3.11+ (tomllib); scoring with equal weights")
+        return settings
+    with open(config_file, "rb") as f:
+        score = tomllib.load(f).get("score", {})
+    settings["reference"] = score.get("reference", "")
+    settings["weights"].update(score.get("weights", {}))
+    return settings
+
+
+def machine_label(data: Dict) -> str:
+    """The machine a results file says it came from, not the one reading it, as the file may have been copied"""
+    machine = data.get("machine")
+    if not machine:
+        return "unknown"
+    return f"{machine.get('id', 'unknown')} ({machine.get('system', '?')} {machine.get('arch', '?')})"
+
+
+def composite_score(data: Dict, settings: Dict) -> Dict:
+    """SPEC-style score per language: reference time / mean time, geometric mean within each category, then
+    the weighted geometric mean over categories, x100
+
+    Only benchmarks with times in both languages and the reference count, so both languages are
+    scored on the same set
+    """
+    reference = None
+    if settings["reference"]:
+        with open(settings["reference"], "r") as f:
+            reference = json.load(f)
+
+    def mean_time(results: Dict, lang: str, benchmark: str) -> float:
+        return results[lang].get(benchmark, {}).get("stats", {}).get("mean", 0)
+
+    categories = {}
+    for category, benches in CATEGORIES.items():
+        weight = settings["weights"].get(category, 1.0)
+        ratios = {"rust": [], "cpp": []}
+        for benchmark in benches:
+            rust, cpp = mean_time(data, "rust", benchmark), mean_time(data, "cpp", benchmark)
+            if reference is None:
+                base = math.sqrt(rust * cpp)
+            else:
+                # The reference machine's own geometric mean of both languages, as without a reference
+                base = math.sqrt(mean_time(reference, "rust", benchmark) * mean_time(reference, "cpp", benchmark))
+            if rust > 0 and cpp > 0 and base > 0:
+                ratios["rust"].append(base / rust)
+                ratios["cpp"].append(base / cpp)
+        if weight > 0 and ratios["rust"]:
+            categories[category] = {
+                "weight": weight,
+                "benchmarks": len(ratios["rust"]),
+                "rust": 100 * geometric_mean(ratios["rust"]),
+                "cpp": 100 * geometric_mean(ratios["cpp"]),
+            }
+    if not categories:
+        return {}
+
+    total_weight = sum(entry["weight"] for entry in categories.values())
+    def weighted(lang: str) -> float:
+        return math.exp(sum(entry["weight"] * math.log(entry[lang]) for entry in categories.values()) / total_weight)
+
+    return {
+        "machine": machine_label(data),
+        "reference": settings["reference"] or "geometric mean of both languages in this run",
+        "rust": weighted("rust"),
+        "cpp": weighted("cpp"),
+        "categories": categories,
+    }
+
+
+def build_costs(data: Dict) -> Dict:
+    """Rebuild times and binary sizes wherever both languages recorded them, with Rust / C++ geometric means"""
+    per_benchmark = {}
+    for benchmark in BENCHMARKS:
+        rust = data["rust"].get(benchmark, {}).get("build", {})
+        cpp = data["cpp"].get(benchmark, {}).get("build", {})
+        entry = {}
+        for key in ("build_seconds", "binary_bytes"):
+            if rust.get(key) and cpp.get(key):
+                entry[f"rust_{key}"] = rust[key]
+                entry[f"cpp_{key}"] = cpp[key]
+        if entry:
+            per_benchmark[benchmark] = entry
+    if not per_benchmark:
+        return {}
+
+    costs = {}
+    for key in ("build_seconds", "binary_bytes"):
+        pairs = [(e[f"rust_{key}"], e[f"cpp_{key}"]) for e in per_benchmark.values() if f"rust_{key}" in e]
+        if pairs:
+            costs[key] = {
+                "benchmarks": len(pairs),
+                "rust_total": sum(rust for rust, _ in pairs),
+                "cpp_total": sum(cpp for _, cpp in pairs),
+                # Unlike the speedups, >1 = Rust costs more
+                "geomean_ratio": geometric_mean([rust / cpp for rust, cpp in pairs]),
+            }
+    costs["per_benchmark"] = per_benchmark
+    return costs
+
+
+def print_report(summary: Dict):
+    """Print the headline numbers"""
+    print("\n" + "="*80)
+    print("SPEEDUP SUMMARY (C++ time / Rust time, >1 = Rust faster)")
+    print("="*80)
+    print(f"Geometric-mean speedup over {summary['benchmarks']} benchmarks: "
+          f"{summary['geomean_speedup']:.3f} ({describe(summary['geomean_speedup'])})")
+    print(f"Wins: Rust {summary['rust_wins']}, C++ {summary['cpp_wins']}, within noise {summary['ties']} "
+          f"(a win needs the {summary['confidence']:.0%} bootstrap interval clear of 1)")
+
+    print(f"\n{'Category':<26} {'Benchmarks':<12} {'Geomean':<10} {'Rust/C++/tie':<15} {'Winner':<10}")
+    print("-"*80)
+    for category, stats in summary["categories"].items():
+        wins = f"{stats['rust_wins']}/{stats['cpp_wins']}/{stats['ties']}"
+        print(f"{category:<26} {stats['benchmarks']:<12} {stats['geomean_speedup']:>7.3f}    {wins:<15} {stats['winner']:<10}")
+
+    for title, outliers in (("Largest Rust leads", summary["rust_outliers"]),
+                            ("Largest C++ leads", summary["cpp_outliers"])):
+        print(f"\n{title}:")
+        if not outliers:
+            print("  (none)")
+        for outlier in outliers:
+            ci = ""
+            if "ci" in outlier:
+                # In the winner's terms, like describe()
+                low, high = outlier["ci"] if outlier["speedup"] > 1 else (1 / outlier["ci"][1], 1 / outlier["ci"][0])
+                ci = f" (CI {low:.2f}-{high:.2f}x)"
+            flag = " [unreliable]" if outlier.get("unreliable") else ""
+            print(f"  {outlier['benchmark']:<25} {describe(outlier['speedup'])}{ci}{flag}")
+
+    if summary.get("unreliable"):
+        print(f"\nUnreliable, run-to-run variation (CV) above {summary['cv_threshold']:.0%}:")
+        for entry in summary["unreliable"]:
+            cvs = "".join(f"{lang} CV {entry[key]:>6.1%}    " if entry[key] is not None else f"{lang} CV {'-':>6}    "
+                          for lang, key in (("Rust", "rust_cv"), ("C++", "cpp_cv")))
+            print(f"  {entry['benchmark']:<25} {cvs}".rstrip())
+        print(f"  {UNRELIABLE_ADVICE}")
+
+    score = summary.get("score")
+    if score:
+        print(f"\nComposite score on {score['machine']} (SPEC-style, 100 = reference, higher is faster):")
+        print(f"  Rust {score['rust']:.1f}, C++ {score['cpp']:.1f}    reference: {score['reference']}")
+        print(f"  {'Category':<26} {'Weight':<8} {'Rust':>7} {'C++':>7}")
+        for category, entry in score["categories"].items():
+            print(f"  {category:<26} {entry['weight']:<8g} {entry['rust']:>7.1f} {entry['cpp']:>7.1f}")
+
+    build = summary.get("build", {})
+    if "build_seconds" in build or "binary_bytes" in build:
+        print("\nBuild cost (Rust / C++, >1 = Rust costs more):")
+    if "build_seconds" in build:
+        times = build["build_seconds"]
+        print(f"  Rebuild time over {times['benchmarks']} benchmarks: Rust {times['rust_total']:.1f}s, "
+              f"C++ {times['cpp_total']:.1f}s, geometric-mean ratio {times['geomean_ratio']:.2f}")
+    if "binary_bytes" in build:
+        sizes = build["binary_bytes"]
+        print(f"  Binary size over {sizes['benchmarks']} benchmarks: Rust {sizes['rust_total'] / 1e6:.1f} MB, "
+              f"C++ {sizes['cpp_total'] / 1e6:.1f} MB, geometric-mean ratio {sizes['geomean_ratio']:.2f}")
+    print("="*80)
+
+
+def report(results_file: str = RESULTS_FILE) -> bool:
+    """Summarize a results file, save the summary next to it as summary.json and print it"""
+    if not Path(results_file).exists():
+        print(f"❌ {results_file} not found; run build.py first")
+        return False
+
+    with open(results_file, "r") as f:
+        data = json.load(f)
+    summary = summarize(data)
+
+    if not summary:
+        print(f"❌ No benchmark in {results_file} has times for both Rust and C++")
+        return False
+    score = composite_score(data, score_settings())
+    if score:
+        summary["score"] = score
+    build = build_costs(data)
+    if build:
+        summary["build"] = build
+    if "interrupted" in data:
+        summary["interrupted"] = data["interrupted"]
+    if "category" in data:
+        summary["category"] = data["category"]
+    if data.get("measure", {}).get("name", "wall") != "wall":
+        summary["measure"] = data["measure"]
+    if data.get("target", {}).get("translated"):
+        summary["translated"] = data["target"]
+
+    summary_file = Path(results_file).with_name("summary.json")
+    with open(summary_file, "w") as f:
+        json.dump(summary, f, indent=2)
+
+    print_report(summary)
+    if "category" in summary:
+        print(f"\nℹ️  A run of the {summary['category']} category only")
+    if "measure" in summary:
+        print(f"\nℹ️  Speedups compare {summary['measure']['name']} ({summary['measure']['unit']}), not wall-clock time")
+    if "translated" in summary:
+        print(f"\n⚠️  Translated: the {summary['translated']['triple']} binaries ran under Rosetta or an emulator, "
+              "so these are not native numbers")
+    if "interrupted" in summary:
+        interrupted = summary["interrupted"]
+        print(f"\n⚠️  Partial results: the run stopped on {interrupted['signal']} during {interrupted['during']}")
+    print(f"\n✅ Summary saved to {summary_file}")
+    return True
+
+
+def main():
+    """Summarize the results file given on the command line, or build.py's"""
+    results_file = sys.argv[1] if len(sys.argv) > 1 else RESULTS_FILE
+    if not report(results_file):
+        sys.exit(1)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,195 @@
+#!/usr/bin/env python3
+"""
+Parameter sweeps
+Runs one benchmark across a grid of BENCH_PARAMS values, such as matrix sizes against thread
+counts, in both languages, and saves every run as one row of a long-format CSV for plotting
+scaling curves
+"""
+
+import argparse
+import csv
+import itertools
+import os
+import re
+import shutil
+import signal
+import statistics
+import sys
+import tempfile
+from pathlib import Path
+from typing import Dict, List, Optional, Tuple
+
+from build import (BENCHMARKS, NUM_RUNS, Interrupted, build_cpp, build_rust, check_native, executable_path,
+                   machine_info, raise_interrupted, run_command)
+
+LANGUAGES = ["rust", "cpp"]
+
+
+def parse_values(spec: str) -> List[int]:
+    """"256,512,1024", or "256..4096" for every doubling from 256 to 4096; the two mix"""
+    values = set()
+    for item in spec.split(","):
+        low, dots, high = item.partition("..")
+        if not dots:
+            values.add(int(item))
+            continue
+        value, high = int(low), int(high)
+        if value <= 0 or high < value:
+            raise ValueError(f"{item} is not a range from a positive number up")
+        while value <= high:
+            values.add(value)
+            value *= 2
+    return sorted(values)
+
+
+def parse_grid(specs: List[str]) -> Tuple[List[str], List[Tuple[int, ...]]]:
+    """The parameter names and every combination of their values, from NAME=VALUES arguments"""
+    names, axes = [], []
+    for spec in specs:
+        name, equals, values = spec.partition("=")
+        if not equals or not name or name in names:
+            raise ValueError(f"--param {spec}: expected NAME=VALUES, once per name")
+        names.append(name)
+        try:
+            axes.append(parse_values(values))
+        except ValueError as e:
+            raise ValueError(f"--param {spec}: {e}")
+    return names, list(itertools.product(*axes))
+
+
+def parse_params(stderr: str) -> Optional[Dict[str, str]]:
+    """The "Params: size=2048 threads=4" line, the values the run actually used"""
+    match = re.search(r"^Params:(.*)$", stderr, re.M)
+    return dict(item.split("=", 1) for item in match.group(1).split()) if match else None
+
+
+def sweep_run(lang: str, benchmark: str, params: Dict[str, int], env: Dict[str, str]) -> float:
+    """One run at one point of the grid; raises ValueError with the reason if it does not give a time"""
+    given = ",".join(f"{name}={value}" for name, value in params.items())
+    success, stdout, stderr = run_command([executable_path(lang, benchmark)], env={**env, "BENCH_PARAMS": given})
+    refused = re.search(r"BENCH_PARAMS: (.*)", stderr)
+    if refused:
+        raise ValueError(refused.group(1))
+    if not success:
+        raise ValueError(f"failed: {stderr.strip()}")
+    used = parse_params(stderr)
+    if used is None:
+        raise ValueError("takes no parameters, so every run would be the same")
+    if any(used.get(name) != str(value) for name, value in params.items()):
+        raise ValueError(f"ran with {used}, not {params}")
+    try:
+        return float(stdout.strip())
+    except ValueError:
+        raise ValueError(f"returned invalid output: {stdout}")
+
+
+def print_report(rows: List[Dict], names: List[str], languages: List[str]):
+    """The median time at each point of the grid, and the speedup where both languages ran"""
+    points: Dict[Tuple, Dict[str, List[float]]] = {}
+    for row in rows:
+        point = tuple(row[name] for name in names)
+        points.setdefault(point, {}).setdefault(row["language"], []).append(row["seconds"])
+
+    headers = names + [f"{'Rust' if lang == 'rust' else 'C++'} median" for lang in languages]
+    headers += ["Speedup"] if len(languages) == 2 else []
+    print("\n" + "="*(14 * len(headers)))
+    print("SWEEP (median seconds; speedup is C++ time / Rust time, >1 = Rust faster)")
+    print("="*(14 * len(headers)))
+    print("".join(f"{header:>14}" for header in headers))
+    print("-"*(14 * len(headers)))
+    for point, times in points.items():
+        medians = [statistics.median(times[lang]) if lang in times else None for lang in languages]
+        cells = [str(value) for value in point] + [f"{m:.4f}s" if m is not None else "-" for m in medians]
+        if len(languages) == 2:
+            cells.append(f"{medians[1] / medians[0]:.2f}" if None not in medians and medians[0] > 0 else "-")
+        print("".join(f"{cell:>14}" for cell in cells))
+    print("="*(14 * len(headers)))
+
+
+def sweep(benchmark: str, specs: List[str], runs: int, languages: List[str], output: str, scratch_dir: str):
+    """Build, then run every point of the grid runs times per language, saving each run as a CSV row"""
+    try:
+        names, grid = parse_grid(specs)
+    except ValueError as e:
+        print(f"❌ {e}")
+        sys.exit(1)
+
+    if ("rust" in languages and not build_rust()) or ("cpp" in languages and not build_cpp()):
+        print("\n❌ Failed to build benchmarks")
+        sys.exit(1)
+    check_native([benchmark])
+
+    env = {**os.environ, "TMPDIR": scratch_dir}
+    machine = machine_info()["id"]
+    rows, failed = [], set()
+    total = len(grid) * runs * len(languages)
+    done = 0
+    print(f"\nSweeping {benchmark} over {len(grid)} points ({', '.join(names)}), {runs} runs each...")
+    interrupted = None
+    try:
+        for point in grid:
+            params = dict(zip(names, point))
+            label = " ".join(f"{name}={value}" for name, value in params.items())
+            # Languages alternate run by run, so both see the same heat and background load
+            for run in range(1, runs + 1):
+                for lang in languages:
+                    done += 1
+                    if (lang, point) in failed:
+                        continue
+                    try:
+                        seconds = sweep_run(lang, benchmark, params, env)
+                    except ValueError as e:
+                        print(f"[{done}/{total}] {label} {lang}: ⚠️  {e}")
+                        failed.add((lang, point))
+                        continue
+                    print(f"[{done}/{total}] {label} {lang} run {run}: {seconds:.4f}s")
+                    rows.append({"benchmark": benchmark, "machine": machine, "language": lang, **params,
+                                 "run": run, "seconds": seconds})
+    except Interrupted as e:
+        print(f"\n\n⚠️  {e} received, saving the runs finished so far")
+        interrupted = e
+
+    if not rows:
+        print("\n❌ No point of the grid ran")
+        sys.exit(1)
+    Path(output).parent.mkdir(parents=True, exist_ok=True)
+    with open(output, "w", newline="") as f:
+        writer = csv.DictWriter(f, fieldnames=["benchmark", "machine", "language", *names, "run", "seconds"])
+        writer.writeheader()
+        writer.writerows(rows)
+
+    print_report(rows, names, languages)
+    print(f"\n✅ {len(rows)} runs saved to {output}")
+    if interrupted:
+        sys.exit(128 + interrupted.signum)
+
+
+def main():
+    """Sweep the benchmark and parameters given on the command line"""
+    parser = argparse.ArgumentParser(description="Run one benchmark across a grid of parameters")
+    parser.add_argument("benchmark", choices=BENCHMARKS, metavar="BENCHMARK")
+    parser.add_argument("--param", action="append", required=True, metavar="NAME=VALUES",
+                        help="a parameter and its values, e.g. size=256..4096 (doubling) or threads=1,2,4,8,16; "
+                             "repeat for a grid over several")
+    parser.add_argument("--runs", type=int, default=NUM_RUNS, help=f"runs per point and language (default: {NUM_RUNS})")
+    parser.add_argument("--languages", nargs="+", choices=LANGUAGES, default=LANGUAGES)
+    parser.add_argument("-o", "--output", help="CSV file (default: results/sweep_BENCHMARK.csv)")
+    args = parser.parse_args()
+
+    signal.signal(signal.SIGINT, raise_interrupted)
+    signal.signal(signal.SIGTERM, raise_interrupted)
+    scratch_dir = tempfile.mkdtemp(prefix="rust_vs_cpp_")
+    try:
+        # In LANGUAGES order whatever the command line's, so the speedup is always C++ over Rust
+        languages = [lang for lang in LANGUAGES if lang in args.languages]
+        sweep(args.benchmark, args.param, args.runs, languages,
+              args.output or f"results/sweep_{args.benchmark}.csv", scratch_dir)
+    except Interrupted as e:
+        print(f"\n\n⚠️  {e} received, stopping")
+        sys.exit(128 + e.signum)
+    finally:
+        shutil.rmtree(scratch_dir, ignore_errors=True)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,379 @@
+#!/usr/bin/env python3
+"""
+Vectorization report
+Disassembles the release builds from build.py and reports, for each benchmark's own functions,
+how many of their innermost loops run on SIMD registers, so a gap between the Rust and C++ times
+can be traced to a loop one compiler vectorized and the other left scalar
+"""
+
+import argparse
+import hashlib
+import json
+import platform
+import re
+import subprocess
+import sys
+from pathlib import Path
+from typing import Dict, Iterator, List, Optional, Tuple
+
+from build import BENCHMARKS, build_cpp, build_rust, executable_path, run_command, source_path
+
+# LLVM's loop-vectorize remarks would be the direct answer, but rustc places a Rust loop at its
+# iterator's source in core, which no kernel can be matched to. The machine code is what both
+# compilers agree on, so a loop counts as vectorized when its body uses vector registers
+
+INSTRUCTION = re.compile(r"^\s*([0-9a-f]+):\s+(\S+)\s*(.*)$")
+SYMBOL = re.compile(r"^([0-9a-f]+) <(.+)>:$")
+BRANCH_TARGET = re.compile(r"(?:0x)?([0-9a-f]+)\s+<")
+
+# xmm also carries scalar float math (addsd, cvtsi2ss), 64-bit moves and element inserts and extracts
+X86_SCALAR = re.compile(r"(?:s[sdh]|2s[sdh][lq]?|2u?si[lq]?)$|^(?:movd|movq|pinsr|pextr)")
+# Register copies and zeroing idioms, which compilers also use around scalar float code
+X86_REGISTER_ONLY = {"movaps", "movapd", "movups", "movupd", "movdqa", "movdqu", "xorps", "xorpd", "pxor"}
+X86_REGISTERS = [("%zmm", 512), ("%ymm", 256), ("%xmm", 128)]
+
+NEON_ARRANGEMENT = re.compile(r"\bv\d+\.(\d+)([bhsd])\b")
+NEON_ELEMENT_BITS = {"b": 8, "h": 16, "s": 32, "d": 64}
+NEON_MOVES = {"mov", "movi", "fmov"}
+SVE = 2048  # Ranks above any fixed width; printed as SVE
+
+ASM_DIR = Path("artifacts/asm")
+
+
+def x86_vector_width(mnemonic: str, operands: str) -> int:
+    """Bits of the widest vector register an AT&T-syntax instruction works on, 0 for scalar"""
+    width = next((bits for register, bits in X86_REGISTERS if register in operands), 0)
+    if width != 128:
+        return width
+    base = mnemonic[1:] if mnemonic.startswith("v") else mnemonic
+    if X86_SCALAR.search(base):
+        return 0
+    if base in X86_REGISTER_ONLY and all(op.strip().startswith("%") for op in operands.split(",")):
+        return 0
+    return width
+
+
+def aarch64_vector_width(mnemonic: str, operands: str) -> int:
+    """Bits of the widest NEON or SVE register an instruction works on, 0 for scalar"""
+    if re.search(r"\bz\d+\.[bhsdq]\b", operands):
+        return SVE
+    if mnemonic in NEON_MOVES:
+        return 0
+    widths = [int(count) * NEON_ELEMENT_BITS[element]
+              for count, element in NEON_ARRANGEMENT.findall(operands) if int(count) > 1]
+    if re.search(r"\bq\d+\b", operands):
+        widths.append(128)
+    return max(widths, default=0)
+
+
+def is_branch(mnemonic: str) -> bool:
+    return (mnemonic.startswith("j") or mnemonic.startswith("b.")
+            or mnemonic in ("b", "cbz", "cbnz", "tbz", "tbnz"))
+
+
+def disassemble(executable: str) -> Iterator[Tuple[str, List[Tuple[int, str, str]]]]:
+    """Each function symbol in the binary with its (address, mnemonic, operands) instructions"""
+    process = subprocess.Popen(["objdump", "-d", "--no-show-raw-insn", "-C", executable],
+                               stdout=subprocess.PIPE, stderr=subprocess.DEVNULL, text=True)
+    symbol, instructions = None, []
+    for line in process.stdout:
+        line = line.rstrip()
+        header = SYMBOL.match(line)
+        if header:
+            if symbol is not None:
+                yield symbol, instructions
+            symbol, instructions = header.group(2), []
+            continue
+        instruction = INSTRUCTION.match(line)
+        if instruction and symbol is not None:
+            instructions.append((int(instruction.group(1), 16), instruction.group(2), instruction.group(3)))
+    if symbol is not None:
+        yield symbol, instructions
+    process.wait()
+
+
+def innermost_loops(instructions: List[Tuple[int, str, str]]) -> List[Tuple[int, int]]:
+    """Address ranges closed by a backward branch inside the function that hold no other loop"""
+    if not instructions:
+        return []
+    start = instructions[0][0]
+    loops = []
+    for address, mnemonic, operands in instructions:
+        target = BRANCH_TARGET.search(operands)
+        if is_branch(mnemonic) and target:
+            destination = int(target.group(1), 16)
+            if start <= destination <= address:
+                loops.append((destination, address))
+    return [loop for loop in set(loops)
+            if not any(other != loop and loop[0] <= other[0] and other[1] <= loop[1] for other in loops)]
+
+
+def loop_widths(instructions: List[Tuple[int, str, str]], vector_width) -> List[int]:
+    """The widest vector register used inside each innermost loop, 0 for a scalar loop"""
+    return [max((vector_width(mnemonic, operands) for address, mnemonic, operands in instructions
+                 if first <= address <= last), default=0)
+            for first, last in innermost_loops(instructions)]
+
+
+def strip_brackets(name: str, open_bracket: str, close_bracket: str) -> str:
+    """Remove every balanced bracketed group, generic arguments or parameter lists"""
+    out, depth = [], 0
+    for char in name:
+        if char == open_bracket:
+            depth += 1
+        elif char == close_bracket and depth:
+            depth -= 1
+        elif not depth:
+            out.append(char)
+    return "".join(out)
+
+
+def rust_function(symbol: str, benchmark: str) -> Optional[str]:
+    """The benchmark function a Rust symbol's code belongs to, or None for library code
+
+    Closures count towards the function that wrote them, including when they were inlined into a
+    library function such as a rayon or std::thread entry point, which names them in its generics
+    """
+    if symbol.startswith("core::ptr::drop_in_place"):
+        return None
+    name = re.sub(r"::h[0-9a-f]{16}$", "", symbol)
+    name = re.sub(r"^<(.+?) as .+>::(\w+)$", r"\1::\2", name)
+    prefix = f"{benchmark}::"
+    if not name.startswith(prefix):
+        closure = re.search(rf"(?<![\w:]){re.escape(prefix)}[\w:]*?::\{{\{{closure\}}\}}", name)
+        if not closure:
+            return None
+        name = closure.group(0)
+    name = strip_brackets(name, "<", ">").replace("::{{closure}}", "")
+    return name[len(prefix):]
+
+
+def cpp_owner(symbol: str, end: int) -> str:
+    """The qualified name that ends at end, skipping back over template arguments and parameters"""
+    pairs = {")": "(", ">": "<"}
+    i, depth_stack = end, []
+    while i > 0:
+        char = symbol[i - 1]
+        if depth_stack:
+            if char == depth_stack[-1][1]:
+                depth_stack.pop()
+            elif char in pairs:
+                depth_stack.append((char, pairs[char]))
+        elif char in pairs:
+            depth_stack.append((char, pairs[char]))
+        elif not (char.isalnum() or char in "_:~"):
+            break
+        i -= 1
+    return symbol[i:end]
+
+
+def cpp_function(symbol: str, source: str) -> Optional[str]:
+    """The benchmark function a C++ symbol's code belongs to, or None for library code
+
+    Lambdas count towards the function that wrote them, as Rust closures do. A function is the
+    benchmark's own when it is not in std and its name is called or defined in the source
+    """
+    lambda_at = symbol.find("::{lambda")
+    name = cpp_owner(symbol, lambda_at) if lambda_at >= 0 else symbol
+    name = strip_brackets(strip_brackets(name, "<", ">"), "(", ")").split()[-1:] or [""]
+    name = name[0].split("::{")[0]
+    if not name or name.startswith(("std::", "__")):
+        return None
+    identifier = name.split("::")[-1]
+    if not re.search(rf"\b{re.escape(identifier)}\s*[(<]", source):
+        return None
+    return name
+
+
+def benchmark_symbols(lang: str, benchmark: str) -> Optional[List[Tuple[str, str, List]]]:
+    """(function, symbol, instructions) for every symbol of one language's binary that is the benchmark's own"""
+    executable = executable_path(lang, benchmark)
+    source = source_path(lang, benchmark)
+    if not Path(executable).exists() or source is None:
+        return None
+
+    source_text = source.read_text()
+    symbols = []
+    for symbol, instructions in disassemble(executable):
+        if lang == "rust":
+            function = rust_function(symbol, benchmark)
+        else:
+            function = cpp_function(symbol, source_text)
+        if function is not None:
+            symbols.append((function, symbol, instructions))
+    return symbols
+
+
+def host_vector_width():
+    return aarch64_vector_width if platform.machine() in ("arm64", "aarch64") else x86_vector_width
+
+
+def analyze(symbols: List[Tuple[str, str, List]]) -> Dict[str, Dict]:
+    """Loops and vectorized loops per benchmark function"""
+    vector_width = host_vector_width()
+    functions = {}
+    for function, symbol, instructions in symbols:
+        widths = loop_widths(instructions, vector_width)
+        entry = functions.setdefault(function, {"loops": 0, "vectorized": 0, "widest": 0})
+        entry["loops"] += len(widths)
+        entry["vectorized"] += sum(1 for width in widths if width)
+        entry["widest"] = max([entry["widest"]] + widths)
+    return {name: entry for name, entry in functions.items() if entry["loops"]}
+
+
+def toml_section(text: str, header: str) -> str:
+    """The lines of one [header] table in a TOML file, up to the next table"""
+    match = re.search(rf"^\[{re.escape(header)}\]\n(.*?)(?=^\[|\Z)", text, re.M | re.S)
+    return match.group(1).strip() if match else ""
+
+
+def rust_toolchain() -> Tuple[str, str]:
+    """A key naming rustc's version and the release flags, and the description it was made from"""
+    success, version, stderr = run_command(["rustc", "-vV"])
+    host = re.search(r"^host: (\S+)", version, re.M).group(1)
+    config = Path("rust/.cargo/config.toml").read_text()
+    # A target's rustflags replace the [build] ones
+    rustflags = (re.search(r"rustflags\s*=\s*(\[.*?\])", toml_section(config, f"target.{host}"), re.S)
+                 or re.search(r"rustflags\s*=\s*(\[.*?\])", toml_section(config, "build"), re.S))
+    flags = " ".join(json.loads(rustflags.group(1))) if rustflags else ""
+    profile = toml_section(Path("rust/Cargo.toml").read_text(), "profile.release")
+    description = f"{version.strip()}\n\nRUSTFLAGS: {flags}\n\n[profile.release]\n{profile}\n"
+    number = re.search(r"^rustc (\S+)", version).group(1)
+    return f"rustc-{number}-{hashlib.sha1(description.encode()).hexdigest()[:8]}", description
+
+
+def cpp_toolchain() -> Tuple[str, str]:
+    """A key naming the C++ compiler's version and the Release flags, and the description it was made from"""
+    compiler = "c++"
+    cache = Path("cpp/build/CMakeCache.txt")
+    if cache.exists():
+        cached = re.search(r"^CMAKE_CXX_COMPILER:\w+=(.+)$", cache.read_text(), re.M)
+        compiler = cached.group(1) if cached else compiler
+    success, version, stderr = run_command([compiler, "--version"])
+    first_line = version.splitlines()[0] if version else compiler
+    cmake = Path("cpp/CMakeLists.txt").read_text()
+    flags = " ".join(re.findall(r'^set\(CMAKE_CXX_FLAGS(?:_RELEASE)? "(?:\$\{CMAKE_CXX_FLAGS\} )?(.*)"\)', cmake, re.M))
+    description = f"{compiler}: {first_line}\n\nCXXFLAGS (Release): {flags}\n"
+    family = "clang" if "clang" in first_line else "gcc"
+    number = (re.findall(r"\d+\.\d+(?:\.\d+)?", first_line) or ["unknown"])[-1]
+    return f"{family}-{number}-{hashlib.sha1(description.encode()).hexdigest()[:8]}", description
+
+
+def annotate(symbol: str, instructions: List[Tuple[int, str, str]], vector_width) -> List[str]:
+    """A symbol's instructions at offsets from its start, with its innermost loops marked
+
+    Addresses are dropped from branch and call targets, which keep their symbol+offset, so the
+    same code diffs clean between builds that laid it out at different addresses
+    """
+    start = instructions[0][0] if instructions else 0
+    loops = sorted(innermost_loops(instructions))
+    lines = [f"# {symbol}"]
+    for address, mnemonic, operands in instructions:
+        for number, (first, last) in enumerate(loops, 1):
+            if address == first:
+                width = max((vector_width(m, o) for a, m, o in instructions if first <= a <= last), default=0)
+                kind = "scalar" if not width else ("vectorized, SVE" if width == SVE else f"vectorized, {width}-bit")
+                lines.append(f"    # loop {number}: {kind}")
+        operands = re.sub(r"(?:0x)?[0-9a-f]+ (?=<)", "", operands)
+        lines.append(f"  +{address - start:#06x}:  {mnemonic:<10} {operands}".rstrip())
+        for number, (first, last) in enumerate(loops, 1):
+            if address == last:
+                lines.append(f"    # end of loop {number}")
+    return lines
+
+
+def dump_asm(symbols: List[Tuple[str, str, List]], directory: Path):
+    """One .s file per benchmark function holding each of its symbols, annotated"""
+    vector_width = host_vector_width()
+    listings = {}
+    for function, symbol, instructions in symbols:
+        listings.setdefault(function, []).append("\n".join(annotate(symbol, instructions, vector_width)))
+    directory.mkdir(parents=True, exist_ok=True)
+    for function, blocks in listings.items():
+        (directory / f"{function.replace('::', '.')}.s").write_text("\n\n".join(sorted(blocks)) + "\n")
+
+
+def format_cell(entry: Optional[Dict]) -> str:
+    if entry is None:
+        return "-"
+    if not entry["vectorized"]:
+        return f"0/{entry['loops']} scalar"
+    width = "SVE" if entry["widest"] == SVE else f"{entry['widest']}-bit"
+    return f"{entry['vectorized']}/{entry['loops']} {width}"
+
+
+def print_report(report: Dict):
+    """Per benchmark, each function's vectorized/innermost loops and widest register in both languages"""
+    print("\n" + "="*80)
+    print("VECTORIZED LOOPS (vectorized/innermost, widest register)")
+    print("="*80)
+
+    for benchmark, languages in report.items():
+        rust, cpp = languages["rust"] or {}, languages["cpp"] or {}
+        print(f"\n{benchmark}")
+        print(f"  {'Function':<40} {'Rust':<18} {'C++':<18}")
+        for function in sorted(set(rust) | set(cpp)):
+            print(f"  {function[:40]:<40} {format_cell(rust.get(function)):<18} {format_cell(cpp.get(function)):<18}")
+
+    print("\n" + "="*80)
+
+
+def save_report(report: Dict, filename: str = "results/vectorization.json"):
+    Path("results").mkdir(exist_ok=True)
+    with open(filename, "w") as f:
+        json.dump({"machine": platform.machine(), "benchmarks": report}, f, indent=2)
+    print(f"\n✅ Report saved to {filename}")
+
+
+def main():
+    """Main execution"""
+    parser = argparse.ArgumentParser(description="Vectorized loops per benchmark function in the Rust and C++ builds")
+    parser.add_argument("benchmarks", nargs="*", metavar="BENCHMARK", help="benchmarks to report (default: all)")
+    parser.add_argument("--asm", action="store_true",
+                        help=f"also write each function's annotated assembly to {ASM_DIR}/<toolchain>/<benchmark>")
+    args = parser.parse_args()
+
+    print("="*80)
+    print("Rust vs C++ Benchmark Suite: vectorization report")
+    print("="*80)
+
+    benchmarks = args.benchmarks or BENCHMARKS
+    unknown = [name for name in benchmarks if name not in BENCHMARKS]
+    if unknown:
+        print(f"\n❌ Unknown benchmarks: {', '.join(unknown)}")
+        sys.exit(1)
+
+    if not build_rust() or not build_cpp():
+        print("\n❌ Failed to build benchmarks")
+        sys.exit(1)
+
+    toolchains = {}
+    if args.asm:
+        for lang, toolchain in (("rust", rust_toolchain), ("cpp", cpp_toolchain)):
+            key, description = toolchain()
+            toolchains[lang] = ASM_DIR / key
+            toolchains[lang].mkdir(parents=True, exist_ok=True)
+            (toolchains[lang] / "toolchain.txt").write_text(description)
+
+    report = {}
+    for benchmark in benchmarks:
+        print(f"Disassembling {benchmark}...")
+        report[benchmark] = {}
+        for lang in ("rust", "cpp"):
+            symbols = benchmark_symbols(lang, benchmark)
+            if symbols is None:
+                print(f"  ⚠️  no {lang} binary for {benchmark}")
+                report[benchmark][lang] = None
+                continue
+            report[benchmark][lang] = analyze(symbols)
+            if args.asm:
+                dump_asm(symbols, toolchains[lang] / benchmark)
+
+    print_report(report)
+    save_report(report)
+    for lang, directory in toolchains.items():
+        print(f"✅ {lang} assembly saved to {directory}")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,215 @@
+#!/usr/bin/env python3
+"""
+Rust vs C++ on WebAssembly
+Builds the portable CPU benchmarks for wasm32-wasip1-threads, runs them under wasmtime,
+and reports them next to the native builds from build.py
+"""
+
+import json
+import os
+import shutil
+import sys
+from pathlib import Path
+from typing import Dict, List
+
+from build import (CV_THRESHOLD, NUM_RUNS, UNRELIABLE_ADVICE, build_cpp, build_rust, calculate_statistics, check_native,
+                   run_command)
+
+# Pure CPU work with no GPU, system library or OS-specific I/O in the default run
+WASM_BENCHMARKS = [
+    "mandelbrot",
+    "prime_sieve",
+    "parallel_quicksort",
+    "json_parse",
+    "sha256",
+]
+
+TARGET = "wasm32-wasip1-threads"  # wasi-threads, so std::thread and rayon still run in parallel
+WASI_SDK = Path(os.environ.get("WASI_SDK_PATH", "/opt/wasi-sdk"))
+CPP_WASM_DIR = Path("cpp/build-wasm")
+CONFIGS = ["rust", "rust-wasm", "cpp", "cpp-wasm"]
+
+# Mirrors the native flags: -O3 and the widest SIMD available (SIMD128 here), an 8 MiB stack like
+# the native main thread, and a 4 GiB shared memory as rust/.cargo/config.toml sets for Rust.
+# wasi-libc only emulates mmap, which sha256's --mmap path uses
+CPP_FLAGS = [
+    "--target=" + TARGET, "-pthread", "-O3", "-msimd128", "-std=c++20", "-DNDEBUG", "-D_WASI_EMULATED_MMAN",
+    "-Wl,--import-memory,--export-memory,--max-memory=4294967296", "-Wl,-z,stack-size=8388608",
+    "-lwasi-emulated-mman",
+]
+
+# Threads for the wasm module (-W) and the wasi-threads API (-S). available_parallelism() is
+# unsupported inside the module, so rayon is told the core count the native run would see
+WASMTIME = ["wasmtime", "run", "-W", "threads=y", "-S", "threads=y",
+            "--env", f"RAYON_NUM_THREADS={os.cpu_count() or 1}"]
+
+
+def build_rust_wasm() -> bool:
+    """Build the Rust benchmarks for the WASI target"""
+    print(f"Building Rust benchmarks for {TARGET}...")
+    bins = [arg for name in WASM_BENCHMARKS for arg in ("--bin", name)]
+    success, stdout, stderr = run_command(
+        ["cargo", "build", "--release", "--target", TARGET] + bins,
+        cwd="rust"
+    )
+
+    if not success:
+        print(f"❌ Rust WASM build failed (rustup target add {TARGET}):")
+        print(stderr)
+        return False
+
+    print("✅ Rust WASM build successful")
+    return True
+
+
+def build_cpp_wasm() -> bool:
+    """Build the C++ benchmarks with wasi-sdk's clang++"""
+    print(f"Building C++ benchmarks for {TARGET}...")
+    compiler = WASI_SDK / "bin" / "clang++"
+    if not compiler.exists():
+        print(f"❌ wasi-sdk not found at {WASI_SDK}; set WASI_SDK_PATH")
+        return False
+
+    CPP_WASM_DIR.mkdir(parents=True, exist_ok=True)
+    for benchmark in WASM_BENCHMARKS:
+        success, stdout, stderr = run_command(
+            [str(compiler), f"cpp/src/{benchmark}.cpp", "-o", str(CPP_WASM_DIR / f"{benchmark}.wasm")] + CPP_FLAGS
+        )
+        if not success:
+            print(f"❌ C++ WASM build of {benchmark} failed:")
+            print(stderr)
+            return False
+
+    print("✅ C++ WASM build successful")
+    return True
+
+
+def command(config: str, benchmark: str) -> List[str]:
+    if config == "rust":
+        return [f"rust/target/release/{benchmark}"]
+    if config == "cpp":
+        return [f"cpp/build/{benchmark}"]
+    if config == "rust-wasm":
+        return WASMTIME + [f"rust/target/{TARGET}/release/{benchmark}.wasm"]
+    return WASMTIME + [str(CPP_WASM_DIR / f"{benchmark}.wasm")]
+
+
+def run_benchmark(config: str, benchmark: str) -> float:
+    """Run one build of a benchmark and return its reported time"""
+    success, stdout, stderr = run_command(command(config, benchmark))
+
+    if not success:
+        print(f"  ⚠️  {config}/{benchmark} failed: {stderr}")
+        return -1.0
+
+    try:
+        return float(stdout.strip())
+    except ValueError:
+        print(f"  ⚠️  {config}/{benchmark} returned invalid output: {stdout}")
+        return -1.0
+
+
+def run_all_benchmarks() -> Dict:
+    """Run every benchmark NUM_RUNS times in each of the four builds"""
+    results = {config: {} for config in CONFIGS}
+
+    for benchmark in WASM_BENCHMARKS:
+        print(f"\n{'='*60}")
+        print(f"Running: {benchmark}")
+        print(f"{'='*60}")
+
+        for config in CONFIGS:
+            results[config][benchmark] = []
+            for run in range(NUM_RUNS):
+                print(f"{config:<10} {benchmark} (run {run+1}/{NUM_RUNS})...", end=" ", flush=True)
+                seconds = run_benchmark(config, benchmark)
+                if seconds >= 0:
+                    results[config][benchmark].append(seconds)
+                    print(f"✓ {seconds:.4f}s")
+                else:
+                    print("✗ Failed")
+
+    return results
+
+
+def save_results(results: Dict, filename: str = "results/wasm_results.json"):
+    """Save times and statistics for all four builds"""
+    Path("results").mkdir(exist_ok=True)
+
+    processed = {
+        config: {
+            benchmark: {"times": times, "stats": calculate_statistics(times)}
+            for benchmark, times in benchmarks.items()
+        }
+        for config, benchmarks in results.items()
+    }
+
+    with open(filename, "w") as f:
+        json.dump(processed, f, indent=2)
+
+    print(f"\n✅ Results saved to {filename}")
+
+
+def print_summary(results: Dict):
+    """Average times per build, and what WASM costs each language"""
+    print("\n" + "="*96)
+    print("NATIVE VS WASM SUMMARY")
+    print("="*96)
+    print(f"{'Benchmark':<22} {'Rust native':<14} {'Rust WASM':<14} {'C++ native':<14} {'C++ WASM':<14} "
+          f"{'Rust WASM cost':<15} {'C++ WASM cost':<15}")
+    print("-"*96)
+    unreliable = 0
+
+    for benchmark in WASM_BENCHMARKS:
+        averages = {}
+        for config in CONFIGS:
+            times = results[config].get(benchmark, [])
+            averages[config] = sum(times) / len(times) if times else None
+
+        noisy = {c for c in CONFIGS if calculate_statistics(results[c].get(benchmark, [])).get("unreliable")}
+        unreliable += bool(noisy)
+        cells = [f"{averages[c]:>10.4f}s{'*' if c in noisy else ' '}  " if averages[c] is not None else f"{'-':>11}   "
+                 for c in CONFIGS]
+        costs = []
+        for native, wasm in (("rust", "rust-wasm"), ("cpp", "cpp-wasm")):
+            if averages[native] and averages[wasm] is not None:
+                costs.append(f"{averages[wasm] / averages[native]:>9.2f}x     ")
+            else:
+                costs.append(f"{'-':>10}     ")
+        print(f"{benchmark:<22} " + " ".join(cells) + " " + " ".join(costs))
+
+    if unreliable:
+        print(f"* unreliable, run-to-run variation (CV) above {CV_THRESHOLD:.0%}. {UNRELIABLE_ADVICE}")
+    print("="*96)
+
+
+def main():
+    """Main execution"""
+    print("="*80)
+    print("Rust vs C++ Benchmark Suite: native vs WebAssembly")
+    print("="*80)
+
+    if shutil.which("wasmtime") is None:
+        print("\n❌ wasmtime not found: curl https://wasmtime.dev/install.sh -sSf | bash")
+        sys.exit(1)
+
+    if not build_rust() or not build_rust_wasm():
+        print("\n❌ Failed to build Rust benchmarks")
+        sys.exit(1)
+
+    if not build_cpp() or not build_cpp_wasm():
+        print("\n❌ Failed to build C++ benchmarks")
+        sys.exit(1)
+
+    print("\n✅ All builds successful!")
+    # The native half of the comparison has to be native
+    check_native(WASM_BENCHMARKS)
+
+    print(f"\nRunning benchmarks ({NUM_RUNS} runs each)...")
+    results = run_all_benchmarks()
+    save_results(results)
+    print_summary(results)
+
+
+if __name__ == "__main__":
+    main()